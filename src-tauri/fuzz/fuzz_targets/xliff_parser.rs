@@ -0,0 +1,26 @@
+//! Feeds arbitrary bytes to the XLIFF → JLIFF converter; any panic is a bug.
+//!
+//! Run from `src-tauri/` with `cargo +nightly fuzz run xliff_parser`. Seed the corpus with
+//! client XLIFFs (`fuzz/corpus/xliff_parser/`) to reach deeper parser states faster.
+
+#![no_main]
+
+use std::fs;
+
+use libfuzzer_sys::fuzz_target;
+use weg_translator_lib::{ConversionOptions, convert_xliff};
+
+fuzz_target!(|data: &[u8]| {
+    let dir = tempfile::tempdir().expect("create temp dir");
+    let input = dir.path().join("input.xlf");
+    fs::write(&input, data).expect("write fuzz input");
+    let opts = ConversionOptions::new(
+        input,
+        dir.path().join("out"),
+        "Fuzz".to_string(),
+        "fuzz".to_string(),
+        "fuzz@example.com".to_string(),
+    );
+    // Errors are expected for malformed input; only panics are failures.
+    let _ = convert_xliff(&opts);
+});
@@ -0,0 +1,5 @@
+-- Rollback: drop QA findings storage.
+
+DROP INDEX IF EXISTS idx_qa_findings_check;
+DROP INDEX IF EXISTS idx_qa_findings_document;
+DROP TABLE IF EXISTS qa_findings;
@@ -0,0 +1,22 @@
+-- QA findings raised by automated checks (spellcheck, terminology, tags, ...)
+-- against individual JLIFF transunits.
+
+CREATE TABLE IF NOT EXISTS qa_findings (
+    finding_uuid TEXT PRIMARY KEY,
+    project_uuid TEXT NOT NULL,
+    jliff_rel_path TEXT NOT NULL,
+    transunit_id TEXT NOT NULL,
+    check_type TEXT NOT NULL,
+    severity TEXT NOT NULL CHECK (severity IN ('info','warning','critical')),
+    message TEXT NOT NULL,
+    details TEXT,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    resolved_at TEXT,
+    FOREIGN KEY (project_uuid) REFERENCES projects(project_uuid) ON UPDATE CASCADE ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_qa_findings_document
+    ON qa_findings(project_uuid, jliff_rel_path, transunit_id);
+
+CREATE INDEX IF NOT EXISTS idx_qa_findings_check
+    ON qa_findings(project_uuid, check_type);
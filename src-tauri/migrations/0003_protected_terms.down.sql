@@ -0,0 +1,4 @@
+-- Rollback: drop project protected terms.
+
+DROP INDEX IF EXISTS idx_project_protected_terms_project;
+DROP TABLE IF EXISTS project_protected_terms;
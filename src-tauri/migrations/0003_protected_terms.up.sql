@@ -0,0 +1,16 @@
+-- Project-level "do not translate" lists (product names, code identifiers, ...).
+-- Matches are protected as placeholders during conversion and checked by QA.
+
+CREATE TABLE IF NOT EXISTS project_protected_terms (
+    term_uuid TEXT PRIMARY KEY,
+    project_uuid TEXT NOT NULL,
+    term TEXT NOT NULL,
+    case_sensitive INTEGER NOT NULL DEFAULT 1 CHECK (case_sensitive IN (0, 1)),
+    note TEXT,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (project_uuid, term),
+    FOREIGN KEY (project_uuid) REFERENCES projects(project_uuid) ON UPDATE CASCADE ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_project_protected_terms_project
+    ON project_protected_terms(project_uuid);
@@ -0,0 +1,4 @@
+-- Rollback: drop project regex protection rules.
+
+DROP INDEX IF EXISTS idx_project_protection_rules_project;
+DROP TABLE IF EXISTS project_protection_rules;
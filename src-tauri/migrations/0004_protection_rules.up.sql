@@ -0,0 +1,16 @@
+-- Project-level regex protection rules (URLs, e-mails, version strings, ...).
+-- Matches are protected as placeholders while segments are built.
+
+CREATE TABLE IF NOT EXISTS project_protection_rules (
+    rule_uuid TEXT PRIMARY KEY,
+    project_uuid TEXT NOT NULL,
+    name TEXT NOT NULL,
+    pattern TEXT NOT NULL,
+    is_enabled INTEGER NOT NULL DEFAULT 1 CHECK (is_enabled IN (0, 1)),
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (project_uuid) REFERENCES projects(project_uuid) ON UPDATE CASCADE ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_project_protection_rules_project
+    ON project_protection_rules(project_uuid);
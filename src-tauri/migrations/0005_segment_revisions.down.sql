@@ -0,0 +1,4 @@
+-- Rollback: drop segment revision history.
+
+DROP INDEX IF EXISTS idx_segment_revisions_document;
+DROP TABLE IF EXISTS segment_revisions;
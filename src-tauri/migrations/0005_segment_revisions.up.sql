@@ -0,0 +1,18 @@
+-- Target revisions of individual JLIFF transunits (external review imports, ...).
+-- Each row keeps the target before and after the change so edits can be audited.
+
+CREATE TABLE IF NOT EXISTS segment_revisions (
+    revision_uuid TEXT PRIMARY KEY,
+    project_uuid TEXT NOT NULL,
+    jliff_rel_path TEXT NOT NULL,
+    transunit_id TEXT NOT NULL,
+    previous_target TEXT NOT NULL,
+    new_target TEXT NOT NULL,
+    origin TEXT NOT NULL,
+    author TEXT,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (project_uuid) REFERENCES projects(project_uuid) ON UPDATE CASCADE ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_segment_revisions_document
+    ON segment_revisions(project_uuid, jliff_rel_path, transunit_id);
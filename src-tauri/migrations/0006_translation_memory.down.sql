@@ -0,0 +1,4 @@
+-- Rollback: drop the translation memory.
+
+DROP INDEX IF EXISTS idx_translation_memory_pair;
+DROP TABLE IF EXISTS translation_memory_entries;
@@ -0,0 +1,19 @@
+-- Translation memory: confirmed source/target pairs reused for pretranslation.
+-- Language codes are stored lowercased; the same source may have several targets.
+
+CREATE TABLE IF NOT EXISTS translation_memory_entries (
+    entry_uuid TEXT PRIMARY KEY,
+    source_lang TEXT NOT NULL,
+    target_lang TEXT NOT NULL,
+    source_text TEXT NOT NULL,
+    target_text TEXT NOT NULL,
+    project_uuid TEXT,
+    origin TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (source_lang, target_lang, source_text, target_text),
+    FOREIGN KEY (project_uuid) REFERENCES projects(project_uuid) ON UPDATE CASCADE ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_translation_memory_pair
+    ON translation_memory_entries(source_lang, target_lang);
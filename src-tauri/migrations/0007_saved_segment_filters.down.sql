@@ -0,0 +1,3 @@
+-- Rollback: drop saved segment filters.
+
+DROP TABLE IF EXISTS saved_segment_filters;
@@ -0,0 +1,13 @@
+-- Named segment filters ("saved views") of the editor, per user.
+-- The filter definition is stored as the JSON sent by the UI.
+
+CREATE TABLE IF NOT EXISTS saved_segment_filters (
+    filter_uuid TEXT PRIMARY KEY,
+    user_uuid TEXT NOT NULL,
+    name TEXT NOT NULL,
+    filter_json TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (user_uuid, name),
+    FOREIGN KEY (user_uuid) REFERENCES users(user_uuid) ON UPDATE CASCADE ON DELETE CASCADE
+);
@@ -0,0 +1,4 @@
+-- Rollback: drop segment confirmation history.
+
+DROP INDEX IF EXISTS idx_segment_confirmations_user;
+DROP TABLE IF EXISTS segment_confirmations;
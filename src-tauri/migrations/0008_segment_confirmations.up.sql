@@ -0,0 +1,17 @@
+-- One row per segment confirmation, with the confirming user and the source word count
+-- at that moment. Feeds the per-linguist productivity report.
+
+CREATE TABLE IF NOT EXISTS segment_confirmations (
+    confirmation_uuid TEXT PRIMARY KEY,
+    project_uuid TEXT NOT NULL,
+    jliff_rel_path TEXT NOT NULL,
+    transunit_id TEXT NOT NULL,
+    user_uuid TEXT,
+    word_count INTEGER NOT NULL,
+    confirmed_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (project_uuid) REFERENCES projects(project_uuid) ON UPDATE CASCADE ON DELETE CASCADE,
+    FOREIGN KEY (user_uuid) REFERENCES users(user_uuid) ON UPDATE CASCADE ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_segment_confirmations_user
+    ON segment_confirmations(user_uuid, confirmed_at);
@@ -0,0 +1,4 @@
+-- Rollback: drop QA profiles and their assignments.
+
+DROP TABLE IF EXISTS qa_profile_assignments;
+DROP TABLE IF EXISTS qa_profiles;
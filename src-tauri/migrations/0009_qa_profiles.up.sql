@@ -0,0 +1,25 @@
+-- Named QA profiles (enabled checks, severities, per-language length limits) and their
+-- assignment to projects or clients. The definition is stored as the profile JSON.
+
+CREATE TABLE IF NOT EXISTS qa_profiles (
+    profile_uuid TEXT PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    description TEXT,
+    definition_json TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- A project or client has at most one profile; a project assignment overrides the
+-- assignment of the project's client.
+CREATE TABLE IF NOT EXISTS qa_profile_assignments (
+    assignment_uuid TEXT PRIMARY KEY,
+    profile_uuid TEXT NOT NULL,
+    project_uuid TEXT UNIQUE,
+    client_uuid TEXT UNIQUE,
+    assigned_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CHECK ((project_uuid IS NULL) <> (client_uuid IS NULL)),
+    FOREIGN KEY (profile_uuid) REFERENCES qa_profiles(profile_uuid) ON UPDATE CASCADE ON DELETE CASCADE,
+    FOREIGN KEY (project_uuid) REFERENCES projects(project_uuid) ON UPDATE CASCADE ON DELETE CASCADE,
+    FOREIGN KEY (client_uuid) REFERENCES clients(client_uuid) ON UPDATE CASCADE ON DELETE CASCADE
+);
@@ -0,0 +1,3 @@
+-- Rollback: drop the delivery gate override audit trail.
+
+DROP TABLE IF EXISTS delivery_gate_overrides;
@@ -0,0 +1,17 @@
+-- Audit trail of project deliveries forced past the QA delivery gate, with what was still
+-- open at that moment.
+
+CREATE TABLE IF NOT EXISTS delivery_gate_overrides (
+    override_uuid TEXT PRIMARY KEY,
+    project_uuid TEXT NOT NULL,
+    user_uuid TEXT,
+    reason TEXT NOT NULL,
+    critical_findings INTEGER NOT NULL,
+    empty_targets INTEGER NOT NULL,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (project_uuid) REFERENCES projects(project_uuid) ON UPDATE CASCADE ON DELETE CASCADE,
+    FOREIGN KEY (user_uuid) REFERENCES users(user_uuid) ON UPDATE CASCADE ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_delivery_gate_overrides_project
+    ON delivery_gate_overrides(project_uuid, created_at);
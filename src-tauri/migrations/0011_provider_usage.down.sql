@@ -0,0 +1,4 @@
+-- Rollback: drop provider usage accounting and budgets.
+
+DROP TABLE IF EXISTS provider_budgets;
+DROP TABLE IF EXISTS provider_usage;
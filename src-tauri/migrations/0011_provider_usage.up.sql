@@ -0,0 +1,24 @@
+-- Characters and tokens sent to each MT/LLM provider, aggregated per project and month, and
+-- the monthly caps that pause machine translation once reached.
+
+CREATE TABLE IF NOT EXISTS provider_usage (
+    provider TEXT NOT NULL,
+    project_uuid TEXT NOT NULL,
+    usage_month TEXT NOT NULL,
+    characters INTEGER NOT NULL DEFAULT 0,
+    tokens INTEGER NOT NULL DEFAULT 0,
+    requests INTEGER NOT NULL DEFAULT 0,
+    updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (provider, project_uuid, usage_month),
+    FOREIGN KEY (project_uuid) REFERENCES projects(project_uuid) ON UPDATE CASCADE ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_provider_usage_month
+    ON provider_usage(usage_month, provider);
+
+CREATE TABLE IF NOT EXISTS provider_budgets (
+    provider TEXT PRIMARY KEY,
+    monthly_character_cap INTEGER CHECK (monthly_character_cap IS NULL OR monthly_character_cap > 0),
+    monthly_token_cap INTEGER CHECK (monthly_token_cap IS NULL OR monthly_token_cap > 0),
+    updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
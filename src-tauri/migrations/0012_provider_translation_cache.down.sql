@@ -0,0 +1,3 @@
+-- Rollback: drop the provider translation cache.
+
+DROP TABLE IF EXISTS provider_translation_cache;
@@ -0,0 +1,19 @@
+-- Translations returned by MT/LLM providers, keyed by provider, model, language pair and the
+-- hashes of the source text and its context, so re-running a batch is not billed twice.
+
+CREATE TABLE IF NOT EXISTS provider_translation_cache (
+    provider TEXT NOT NULL,
+    model TEXT NOT NULL,
+    source_lang TEXT NOT NULL,
+    target_lang TEXT NOT NULL,
+    source_hash TEXT NOT NULL,
+    context_hash TEXT NOT NULL,
+    target_text TEXT NOT NULL,
+    hits INTEGER NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    expires_at TEXT NOT NULL,
+    PRIMARY KEY (provider, model, source_lang, target_lang, source_hash, context_hash)
+);
+
+CREATE INDEX IF NOT EXISTS idx_provider_translation_cache_expiry
+    ON provider_translation_cache(expires_at);
@@ -0,0 +1,3 @@
+-- Rollback: drop provider rate limits.
+
+DROP TABLE IF EXISTS provider_rate_limits;
@@ -0,0 +1,8 @@
+-- Per-provider request and token limits applied to outbound MT/LLM calls.
+
+CREATE TABLE IF NOT EXISTS provider_rate_limits (
+    provider TEXT PRIMARY KEY,
+    requests_per_minute INTEGER CHECK (requests_per_minute IS NULL OR requests_per_minute > 0),
+    tokens_per_minute INTEGER CHECK (tokens_per_minute IS NULL OR tokens_per_minute > 0),
+    updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
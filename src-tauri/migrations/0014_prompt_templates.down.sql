@@ -0,0 +1,4 @@
+-- Rollback: drop prompt templates and their assignments.
+
+DROP TABLE IF EXISTS prompt_template_assignments;
+DROP TABLE IF EXISTS prompt_templates;
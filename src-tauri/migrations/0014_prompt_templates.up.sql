@@ -0,0 +1,22 @@
+-- Editable LLM prompt templates and their assignment to projects. An assignment applies to
+-- one language pair, or to every pair when the language columns are empty.
+
+CREATE TABLE IF NOT EXISTS prompt_templates (
+    template_uuid TEXT PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    description TEXT,
+    definition_json TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS prompt_template_assignments (
+    project_uuid TEXT NOT NULL,
+    source_lang TEXT NOT NULL DEFAULT '',
+    target_lang TEXT NOT NULL DEFAULT '',
+    template_uuid TEXT NOT NULL,
+    assigned_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (project_uuid, source_lang, target_lang),
+    FOREIGN KEY (project_uuid) REFERENCES projects(project_uuid) ON UPDATE CASCADE ON DELETE CASCADE,
+    FOREIGN KEY (template_uuid) REFERENCES prompt_templates(template_uuid) ON UPDATE CASCADE ON DELETE CASCADE
+);
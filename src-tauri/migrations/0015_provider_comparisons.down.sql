@@ -0,0 +1,4 @@
+-- Rollback: drop provider comparison candidates and preferred providers.
+
+DROP TABLE IF EXISTS preferred_providers;
+DROP TABLE IF EXISTS provider_candidates;
@@ -0,0 +1,26 @@
+-- Candidate translations produced by comparing MT/LLM providers on the same segments. They
+-- are kept apart from the JLIFF targets until a translator picks one.
+
+CREATE TABLE IF NOT EXISTS provider_candidates (
+    project_uuid TEXT NOT NULL,
+    jliff_rel_path TEXT NOT NULL,
+    transunit_id TEXT NOT NULL,
+    provider TEXT NOT NULL,
+    source_text TEXT NOT NULL,
+    target_text TEXT NOT NULL,
+    qe_score REAL NOT NULL,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (project_uuid, jliff_rel_path, transunit_id, provider),
+    FOREIGN KEY (project_uuid) REFERENCES projects(project_uuid) ON UPDATE CASCADE ON DELETE CASCADE
+);
+
+-- Provider a PM picked as the default for a language pair of a project.
+CREATE TABLE IF NOT EXISTS preferred_providers (
+    project_uuid TEXT NOT NULL,
+    source_lang TEXT NOT NULL,
+    target_lang TEXT NOT NULL,
+    provider TEXT NOT NULL,
+    updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (project_uuid, source_lang, target_lang),
+    FOREIGN KEY (project_uuid) REFERENCES projects(project_uuid) ON UPDATE CASCADE ON DELETE CASCADE
+);
@@ -0,0 +1,5 @@
+-- Rollback: drop provider routing rules.
+
+DROP INDEX IF EXISTS idx_provider_routing_rules_client;
+DROP INDEX IF EXISTS idx_provider_routing_rules_project;
+DROP TABLE IF EXISTS provider_routing_rules;
@@ -0,0 +1,23 @@
+-- Ordered rules routing segments to MT/LLM providers, owned by a project or a client. A
+-- project's rules are evaluated before those of its client; unset conditions match anything.
+
+CREATE TABLE IF NOT EXISTS provider_routing_rules (
+    rule_uuid TEXT PRIMARY KEY,
+    project_uuid TEXT,
+    client_uuid TEXT,
+    position INTEGER NOT NULL,
+    source_lang TEXT,
+    target_lang TEXT,
+    subject TEXT,
+    provider TEXT NOT NULL,
+    note TEXT,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CHECK ((project_uuid IS NULL) <> (client_uuid IS NULL)),
+    FOREIGN KEY (project_uuid) REFERENCES projects(project_uuid) ON UPDATE CASCADE ON DELETE CASCADE,
+    FOREIGN KEY (client_uuid) REFERENCES clients(client_uuid) ON UPDATE CASCADE ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_provider_routing_rules_project
+    ON provider_routing_rules(project_uuid, position);
+CREATE INDEX IF NOT EXISTS idx_provider_routing_rules_client
+    ON provider_routing_rules(client_uuid, position);
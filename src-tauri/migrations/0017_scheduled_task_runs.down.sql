@@ -0,0 +1,3 @@
+-- Rollback: drop the background task run history.
+
+DROP TABLE IF EXISTS scheduled_task_runs;
@@ -0,0 +1,11 @@
+-- Last run of each background task, so schedules survive restarts and missed runs are caught
+-- up at the next start.
+
+CREATE TABLE IF NOT EXISTS scheduled_task_runs (
+    task_id TEXT PRIMARY KEY,
+    last_started_at TEXT NOT NULL,
+    last_finished_at TEXT,
+    last_status TEXT NOT NULL CHECK (last_status IN ('running', 'succeeded', 'failed')),
+    last_error TEXT,
+    run_count INTEGER NOT NULL DEFAULT 0
+);
@@ -0,0 +1,4 @@
+-- Rollback: drop job prerequisites.
+
+DROP INDEX IF EXISTS idx_job_dependencies_prerequisite;
+DROP TABLE IF EXISTS job_dependencies;
@@ -0,0 +1,14 @@
+-- Prerequisites of jobs: a job starts only once every job it depends on has succeeded.
+
+CREATE TABLE IF NOT EXISTS job_dependencies (
+    artifact_uuid TEXT NOT NULL,
+    job_type TEXT NOT NULL,
+    depends_on_artifact_uuid TEXT NOT NULL,
+    depends_on_job_type TEXT NOT NULL,
+    PRIMARY KEY (artifact_uuid, job_type, depends_on_artifact_uuid, depends_on_job_type),
+    FOREIGN KEY (artifact_uuid, job_type) REFERENCES jobs(artifact_uuid, job_type) ON UPDATE CASCADE ON DELETE CASCADE,
+    FOREIGN KEY (depends_on_artifact_uuid, depends_on_job_type) REFERENCES jobs(artifact_uuid, job_type) ON UPDATE CASCADE ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_job_dependencies_prerequisite
+    ON job_dependencies(depends_on_artifact_uuid, depends_on_job_type);
@@ -0,0 +1,5 @@
+-- Rollback: drop pipeline presets and their project assignments.
+
+DROP TABLE IF EXISTS project_pipeline_presets;
+DROP INDEX IF EXISTS idx_pipeline_presets_project_type;
+DROP TABLE IF EXISTS pipeline_presets;
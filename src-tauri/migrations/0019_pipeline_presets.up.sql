@@ -0,0 +1,56 @@
+-- Pipeline presets: named, ordered stages with their options, optionally the default of a
+-- project type, and the preset chosen for a project. The definition is stored as JSON.
+
+CREATE TABLE IF NOT EXISTS pipeline_presets (
+    preset_uuid TEXT PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    description TEXT,
+    project_type TEXT,
+    definition_json TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- A project type has at most one default preset.
+CREATE UNIQUE INDEX IF NOT EXISTS idx_pipeline_presets_project_type
+    ON pipeline_presets(project_type) WHERE project_type IS NOT NULL;
+
+CREATE TABLE IF NOT EXISTS project_pipeline_presets (
+    project_uuid TEXT PRIMARY KEY,
+    preset_uuid TEXT NOT NULL,
+    assigned_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (project_uuid) REFERENCES projects(project_uuid) ON UPDATE CASCADE ON DELETE CASCADE,
+    FOREIGN KEY (preset_uuid) REFERENCES pipeline_presets(preset_uuid) ON UPDATE CASCADE ON DELETE CASCADE
+);
+
+-- Built-in presets; ids are stored as blobs like the ones bound by the application.
+INSERT OR IGNORE INTO pipeline_presets (preset_uuid, name, description, project_type, definition_json)
+VALUES
+    (
+        X'5f0c8a4e2d6b4c1e9a3f000000000001',
+        'Full translation',
+        'Convert, fill exact TM matches, run the QA checks and write the results back into the XLIFF.',
+        'translation',
+        '{"stages":[{"stage":"convert"},{"stage":"translate","minMatch":100},{"stage":"qa"},{"stage":"merge"}]}'
+    ),
+    (
+        X'5f0c8a4e2d6b4c1e9a3f000000000002',
+        'TM fuzzy + light QA',
+        'Convert, fill TM matches from 75% and check invisible characters only.',
+        NULL,
+        '{"stages":[{"stage":"convert"},{"stage":"translate","minMatch":75},{"stage":"qa","checks":["unicode_text"]}]}'
+    ),
+    (
+        X'5f0c8a4e2d6b4c1e9a3f000000000003',
+        'Human only',
+        'Convert the files and leave translation to the translators.',
+        NULL,
+        '{"stages":[{"stage":"convert"}]}'
+    ),
+    (
+        X'5f0c8a4e2d6b4c1e9a3f000000000004',
+        'RAG ingestion',
+        'Convert the files so their segments can be indexed.',
+        'rag',
+        '{"stages":[{"stage":"convert"}]}'
+    );
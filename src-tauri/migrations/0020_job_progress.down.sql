@@ -0,0 +1,7 @@
+-- Rollback: drop job progress columns.
+
+ALTER TABLE jobs DROP COLUMN progress_updated_at;
+ALTER TABLE jobs DROP COLUMN started_at;
+ALTER TABLE jobs DROP COLUMN units_per_second;
+ALTER TABLE jobs DROP COLUMN total_units;
+ALTER TABLE jobs DROP COLUMN processed_units;
@@ -0,0 +1,8 @@
+-- Progress of long jobs, kept with the job so a restarted frontend can show progress bars
+-- and estimates: processed/total units, rolling throughput and when the job started.
+
+ALTER TABLE jobs ADD COLUMN processed_units INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE jobs ADD COLUMN total_units INTEGER;
+ALTER TABLE jobs ADD COLUMN units_per_second REAL;
+ALTER TABLE jobs ADD COLUMN started_at TEXT;
+ALTER TABLE jobs ADD COLUMN progress_updated_at TEXT;
@@ -0,0 +1,17 @@
+-- Transactional outbox: domain events written with the state change they describe, then
+-- published by the dispatcher and marked delivered, so no event is lost when no window
+-- listens or a publish fails.
+
+CREATE TABLE IF NOT EXISTS event_outbox (
+    event_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    topic TEXT NOT NULL,
+    payload_json TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    next_attempt_at TEXT,
+    last_error TEXT,
+    delivered_at TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_event_outbox_pending
+    ON event_outbox(event_id) WHERE delivered_at IS NULL;
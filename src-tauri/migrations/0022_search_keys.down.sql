@@ -0,0 +1,5 @@
+-- Rollback: drop name search keys.
+
+ALTER TABLE project_files DROP COLUMN filename_search;
+ALTER TABLE clients DROP COLUMN name_search;
+ALTER TABLE projects DROP COLUMN project_name_search;
@@ -0,0 +1,7 @@
+-- Lowercased, unaccented copies of project names, client names and filenames, rewritten by
+-- the DB layer with every name so filters can match "Müller" when searching "muller".
+-- Existing rows are keyed when the schema is next initialised.
+
+ALTER TABLE projects ADD COLUMN project_name_search TEXT;
+ALTER TABLE clients ADD COLUMN name_search TEXT;
+ALTER TABLE project_files ADD COLUMN filename_search TEXT;
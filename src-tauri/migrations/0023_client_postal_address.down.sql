@@ -0,0 +1,10 @@
+-- Rollback: drop structured client address columns.
+
+ALTER TABLE clients DROP COLUMN address_place_id;
+ALTER TABLE clients DROP COLUMN address_country;
+ALTER TABLE clients DROP COLUMN address_country_code;
+ALTER TABLE clients DROP COLUMN address_region;
+ALTER TABLE clients DROP COLUMN address_locality;
+ALTER TABLE clients DROP COLUMN address_postal_code;
+ALTER TABLE clients DROP COLUMN address_street_number;
+ALTER TABLE clients DROP COLUMN address_street;
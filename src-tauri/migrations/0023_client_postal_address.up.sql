@@ -0,0 +1,11 @@
+-- Structured postal address of clients, filled from Google Places or validated manual entry,
+-- so reports and invoices can print it in the layout of the client's country.
+
+ALTER TABLE clients ADD COLUMN address_street TEXT;
+ALTER TABLE clients ADD COLUMN address_street_number TEXT;
+ALTER TABLE clients ADD COLUMN address_postal_code TEXT;
+ALTER TABLE clients ADD COLUMN address_locality TEXT;
+ALTER TABLE clients ADD COLUMN address_region TEXT;
+ALTER TABLE clients ADD COLUMN address_country_code TEXT;
+ALTER TABLE clients ADD COLUMN address_country TEXT;
+ALTER TABLE clients ADD COLUMN address_place_id TEXT;
@@ -0,0 +1,4 @@
+-- Rollback: drop client VAT validation columns.
+
+ALTER TABLE clients DROP COLUMN vat_checked_at;
+ALTER TABLE clients DROP COLUMN vat_status;
@@ -0,0 +1,4 @@
+-- How far each client's VAT number was verified, and when, for invoicing records.
+
+ALTER TABLE clients ADD COLUMN vat_status TEXT;
+ALTER TABLE clients ADD COLUMN vat_checked_at TEXT;
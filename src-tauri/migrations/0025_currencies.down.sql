@@ -0,0 +1,5 @@
+-- Rollback: drop exchange-rate snapshots and the client currency.
+
+DROP TABLE IF EXISTS exchange_rates;
+
+ALTER TABLE clients DROP COLUMN currency;
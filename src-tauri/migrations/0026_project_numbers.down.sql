@@ -0,0 +1,6 @@
+-- Rollback: drop project numbers and their sequences.
+
+DROP TABLE IF EXISTS project_number_sequences;
+DROP INDEX IF EXISTS idx_projects_project_number;
+
+ALTER TABLE projects DROP COLUMN project_number;
@@ -0,0 +1,12 @@
+-- Readable project numbers generated from a template at creation, with one counter per
+-- numbering scope (e.g. per client and year).
+
+ALTER TABLE projects ADD COLUMN project_number TEXT;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_projects_project_number
+    ON projects(project_number);
+
+CREATE TABLE IF NOT EXISTS project_number_sequences (
+    scope TEXT PRIMARY KEY,
+    last_value INTEGER NOT NULL
+);
@@ -0,0 +1,4 @@
+-- Rollback: drop delivery package records.
+
+DROP INDEX IF EXISTS idx_delivery_packages_project;
+DROP TABLE IF EXISTS delivery_packages;
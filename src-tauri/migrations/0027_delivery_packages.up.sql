@@ -0,0 +1,21 @@
+-- Delivery packages built for a project language pair. The ZIP itself is a project file with
+-- a `delivery_package` artifact; `delivered_at` is set on the package a transition to
+-- `delivered` referenced.
+
+CREATE TABLE IF NOT EXISTS delivery_packages (
+    artifact_uuid TEXT PRIMARY KEY,
+    project_uuid TEXT NOT NULL,
+    source_lang TEXT NOT NULL,
+    target_lang TEXT NOT NULL,
+    rel_path TEXT NOT NULL,
+    document_count INTEGER NOT NULL,
+    bilingual_count INTEGER NOT NULL,
+    open_findings INTEGER NOT NULL,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    delivered_at TEXT,
+    FOREIGN KEY (artifact_uuid) REFERENCES artifacts(artifact_uuid) ON UPDATE CASCADE ON DELETE CASCADE,
+    FOREIGN KEY (project_uuid) REFERENCES projects(project_uuid) ON UPDATE CASCADE ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_delivery_packages_project
+    ON delivery_packages(project_uuid, created_at);
@@ -0,0 +1,6 @@
+-- Rollback: drop delivery targets and upload receipts.
+
+DROP INDEX IF EXISTS idx_delivery_receipts_project;
+DROP TABLE IF EXISTS delivery_receipts;
+DROP INDEX IF EXISTS idx_delivery_targets_client;
+DROP TABLE IF EXISTS delivery_targets;
@@ -0,0 +1,38 @@
+-- Upload targets a client receives delivery packages on, and receipts of uploads. Target
+-- settings are the JSON of `delivery::transports::TargetSettings`; passwords and secret keys
+-- are kept in the secrets vault, never here.
+
+CREATE TABLE IF NOT EXISTS delivery_targets (
+    target_uuid TEXT PRIMARY KEY,
+    client_uuid TEXT NOT NULL,
+    name TEXT NOT NULL,
+    transport TEXT NOT NULL CHECK (transport IN ('sftp', 's3')),
+    settings_json TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (client_uuid) REFERENCES clients(client_uuid) ON UPDATE CASCADE ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_delivery_targets_client
+    ON delivery_targets(client_uuid, name);
+
+CREATE TABLE IF NOT EXISTS delivery_receipts (
+    receipt_uuid TEXT PRIMARY KEY,
+    artifact_uuid TEXT NOT NULL,
+    project_uuid TEXT NOT NULL,
+    target_uuid TEXT,
+    transport TEXT NOT NULL,
+    status TEXT NOT NULL CHECK (status IN ('uploaded', 'failed')),
+    remote_location TEXT,
+    size_bytes INTEGER,
+    sha256 TEXT,
+    error TEXT,
+    started_at TEXT NOT NULL,
+    completed_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (artifact_uuid) REFERENCES delivery_packages(artifact_uuid) ON UPDATE CASCADE ON DELETE CASCADE,
+    FOREIGN KEY (project_uuid) REFERENCES projects(project_uuid) ON UPDATE CASCADE ON DELETE CASCADE,
+    FOREIGN KEY (target_uuid) REFERENCES delivery_targets(target_uuid) ON UPDATE CASCADE ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_delivery_receipts_project
+    ON delivery_receipts(project_uuid, completed_at);
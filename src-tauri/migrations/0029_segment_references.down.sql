@@ -0,0 +1,5 @@
+-- Rollback: drop links between reference assets and segments.
+
+DROP INDEX IF EXISTS idx_segment_references_file;
+DROP INDEX IF EXISTS idx_segment_references_document;
+DROP TABLE IF EXISTS segment_references;
@@ -0,0 +1,22 @@
+-- Reference assets (screenshots, spec sections, ...) linked to individual JLIFF transunits
+-- so the editor can show contextual material next to a segment. `anchor` locates the
+-- relevant part of the asset, such as a page or a section heading.
+
+CREATE TABLE IF NOT EXISTS segment_references (
+    reference_uuid TEXT PRIMARY KEY,
+    project_uuid TEXT NOT NULL,
+    jliff_rel_path TEXT NOT NULL,
+    transunit_id TEXT NOT NULL,
+    file_uuid TEXT NOT NULL,
+    anchor TEXT,
+    note TEXT,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (project_uuid) REFERENCES projects(project_uuid) ON UPDATE CASCADE ON DELETE CASCADE,
+    FOREIGN KEY (project_uuid, file_uuid) REFERENCES project_files(project_uuid, file_uuid) ON UPDATE CASCADE ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_segment_references_document
+    ON segment_references(project_uuid, jliff_rel_path, transunit_id);
+CREATE INDEX IF NOT EXISTS idx_segment_references_file
+    ON segment_references(project_uuid, file_uuid);
@@ -0,0 +1,4 @@
+-- Rollback: drop project health evaluations and due dates.
+
+DROP TABLE IF EXISTS project_health;
+ALTER TABLE projects DROP COLUMN due_date;
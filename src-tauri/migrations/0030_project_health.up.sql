@@ -0,0 +1,14 @@
+-- Due dates of projects and the last health evaluation of each one. The stored level is what
+-- a new evaluation is compared with to notice that a project's health got worse; `flags` is
+-- a JSON array of the risks behind the score.
+
+ALTER TABLE projects ADD COLUMN due_date TEXT;
+
+CREATE TABLE IF NOT EXISTS project_health (
+    project_uuid TEXT PRIMARY KEY,
+    level TEXT NOT NULL CHECK (level IN ('healthy','at_risk','critical')),
+    score INTEGER NOT NULL,
+    flags TEXT NOT NULL DEFAULT '[]',
+    evaluated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (project_uuid) REFERENCES projects(project_uuid) ON UPDATE CASCADE ON DELETE CASCADE
+);
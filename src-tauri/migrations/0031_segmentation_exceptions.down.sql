@@ -0,0 +1,4 @@
+-- Rollback: drop segmentation exceptions.
+
+DROP INDEX IF EXISTS idx_segmentation_exceptions_project;
+DROP TABLE IF EXISTS segmentation_exceptions;
@@ -0,0 +1,18 @@
+-- Segmentation exceptions tuning sentence breaks for a source language: abbreviations that do
+-- not end a sentence, and break/no-break rules as before/after regular expressions. Rows
+-- without a project apply everywhere; a project's rows are evaluated before the global ones.
+-- `language` is a language code or a bare language ("de" also covers "de-AT").
+
+CREATE TABLE IF NOT EXISTS segmentation_exceptions (
+    exception_uuid TEXT PRIMARY KEY,
+    project_uuid TEXT,
+    language TEXT NOT NULL,
+    kind TEXT NOT NULL CHECK (kind IN ('abbreviation','no_break','break')),
+    before_text TEXT NOT NULL,
+    after_text TEXT NOT NULL DEFAULT '',
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (project_uuid) REFERENCES projects(project_uuid) ON UPDATE CASCADE ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_segmentation_exceptions_project
+    ON segmentation_exceptions(project_uuid, language);
@@ -0,0 +1,3 @@
+-- Rollback: drop project translation memory assignments.
+
+DROP TABLE IF EXISTS project_tm_assignments;
@@ -0,0 +1,14 @@
+-- Translation memories a project draws on. A memory is the set of entries sharing an
+-- `origin` (`editor`, `reversed`, imported packages...). Lower priorities are consulted
+-- first and win ties; the penalty, in percentage points, is taken off every match score.
+-- Projects without rows use every entry of the language pair without penalty.
+
+CREATE TABLE IF NOT EXISTS project_tm_assignments (
+    project_uuid TEXT NOT NULL,
+    tm_origin TEXT NOT NULL,
+    priority INTEGER NOT NULL DEFAULT 0,
+    penalty INTEGER NOT NULL DEFAULT 0 CHECK (penalty BETWEEN 0 AND 100),
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (project_uuid, tm_origin),
+    FOREIGN KEY (project_uuid) REFERENCES projects(project_uuid) ON UPDATE CASCADE ON DELETE CASCADE
+);
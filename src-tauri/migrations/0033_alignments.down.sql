@@ -0,0 +1,4 @@
+-- Rollback: drop document alignments.
+
+DROP TABLE IF EXISTS alignment_pairs;
+DROP TABLE IF EXISTS alignments;
@@ -0,0 +1,24 @@
+-- Sentence alignments of legacy bilingual documents, reviewed before they become translation
+-- memory entries. Pairs start `pending`; committing an alignment adds its `accepted` pairs to
+-- the memory with the `alignment` origin and stamps `committed_at`.
+
+CREATE TABLE IF NOT EXISTS alignments (
+    alignment_uuid TEXT PRIMARY KEY,
+    source_lang TEXT NOT NULL,
+    target_lang TEXT NOT NULL,
+    source_name TEXT NOT NULL,
+    target_name TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    committed_at TEXT
+);
+
+CREATE TABLE IF NOT EXISTS alignment_pairs (
+    alignment_uuid TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    source_text TEXT NOT NULL,
+    target_text TEXT NOT NULL,
+    score REAL NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending' CHECK (status IN ('pending','accepted','rejected')),
+    PRIMARY KEY (alignment_uuid, position),
+    FOREIGN KEY (alignment_uuid) REFERENCES alignments(alignment_uuid) ON UPDATE CASCADE ON DELETE CASCADE
+);
@@ -0,0 +1,5 @@
+-- Rollback: drop term candidates and termbase entries.
+
+DROP TABLE IF EXISTS term_candidates;
+DROP INDEX IF EXISTS idx_termbase_entries_pair;
+DROP TABLE IF EXISTS termbase_entries;
@@ -0,0 +1,36 @@
+-- Termbase entries and the term candidates extracted from project content. Candidates start
+-- `pending`; accepting one adds it to a termbase (a named set of entries, like the `origin` of
+-- translation memory entries). Re-running an extraction replaces only pending candidates, so
+-- earlier decisions are kept.
+
+CREATE TABLE IF NOT EXISTS termbase_entries (
+    entry_uuid TEXT PRIMARY KEY,
+    termbase TEXT NOT NULL,
+    source_lang TEXT NOT NULL,
+    target_lang TEXT NOT NULL,
+    source_term TEXT NOT NULL,
+    target_term TEXT NOT NULL DEFAULT '',
+    project_uuid TEXT,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (termbase, source_lang, target_lang, source_term, target_term),
+    FOREIGN KEY (project_uuid) REFERENCES projects(project_uuid) ON UPDATE CASCADE ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_termbase_entries_pair
+    ON termbase_entries(source_lang, target_lang);
+
+CREATE TABLE IF NOT EXISTS term_candidates (
+    candidate_uuid TEXT PRIMARY KEY,
+    project_uuid TEXT NOT NULL,
+    source_lang TEXT NOT NULL,
+    target_lang TEXT NOT NULL,
+    term TEXT NOT NULL,
+    term_key TEXT NOT NULL,
+    target_term TEXT,
+    frequency INTEGER NOT NULL,
+    score REAL NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending' CHECK (status IN ('pending','accepted','rejected')),
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (project_uuid, source_lang, target_lang, term_key),
+    FOREIGN KEY (project_uuid) REFERENCES projects(project_uuid) ON UPDATE CASCADE ON DELETE CASCADE
+);
@@ -0,0 +1,6 @@
+-- Rollback: drop project notes and their mentions.
+
+DROP INDEX IF EXISTS idx_note_mentions_user;
+DROP TABLE IF EXISTS note_mentions;
+DROP INDEX IF EXISTS idx_notes_project;
+DROP TABLE IF EXISTS notes;
@@ -0,0 +1,30 @@
+-- Project notes: collaboration notes on a project, optionally pinned to the top of the list
+-- and written in Markdown. `note_mentions` keeps the `@handles` found in a note body, with the
+-- user they resolved to when a user of that name exists.
+
+CREATE TABLE IF NOT EXISTS notes (
+    note_id TEXT PRIMARY KEY,
+    project_id TEXT NOT NULL,
+    author_user_id TEXT NOT NULL,
+    body TEXT NOT NULL,
+    is_markdown INTEGER NOT NULL DEFAULT 1 CHECK (is_markdown IN (0, 1)),
+    pinned INTEGER NOT NULL DEFAULT 0 CHECK (pinned IN (0, 1)),
+    created_at TEXT NOT NULL,
+    updated_at TEXT,
+    FOREIGN KEY (project_id) REFERENCES projects(project_uuid) ON UPDATE CASCADE ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_notes_project
+    ON notes(project_id, pinned DESC, created_at DESC);
+
+CREATE TABLE IF NOT EXISTS note_mentions (
+    note_id TEXT NOT NULL,
+    mention TEXT NOT NULL,
+    user_uuid TEXT,
+    PRIMARY KEY (note_id, mention),
+    FOREIGN KEY (note_id) REFERENCES notes(note_id) ON UPDATE CASCADE ON DELETE CASCADE,
+    FOREIGN KEY (user_uuid) REFERENCES users(user_uuid) ON UPDATE CASCADE ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_note_mentions_user
+    ON note_mentions(user_uuid);
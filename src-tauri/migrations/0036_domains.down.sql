@@ -0,0 +1,6 @@
+-- Rollback: drop domains and their project and termbase links.
+
+DROP TABLE IF EXISTS termbase_domains;
+DROP INDEX IF EXISTS idx_project_domains_domain;
+DROP TABLE IF EXISTS project_domains;
+DROP TABLE IF EXISTS domains;
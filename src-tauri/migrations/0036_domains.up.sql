@@ -0,0 +1,28 @@
+-- Subject-matter domains. Projects and termbases (the `termbase` names of
+-- `termbase_entries`) are linked to domains so terminology lookups and MT routing key off
+-- the same list rather than free-text project subjects.
+
+CREATE TABLE IF NOT EXISTS domains (
+    domain_id TEXT PRIMARY KEY,
+    name TEXT NOT NULL COLLATE NOCASE UNIQUE,
+    description TEXT,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS project_domains (
+    project_uuid TEXT NOT NULL,
+    domain_id TEXT NOT NULL,
+    PRIMARY KEY (project_uuid, domain_id),
+    FOREIGN KEY (project_uuid) REFERENCES projects(project_uuid) ON UPDATE CASCADE ON DELETE CASCADE,
+    FOREIGN KEY (domain_id) REFERENCES domains(domain_id) ON UPDATE CASCADE ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_project_domains_domain
+    ON project_domains(domain_id);
+
+CREATE TABLE IF NOT EXISTS termbase_domains (
+    termbase TEXT NOT NULL,
+    domain_id TEXT NOT NULL,
+    PRIMARY KEY (termbase, domain_id),
+    FOREIGN KEY (domain_id) REFERENCES domains(domain_id) ON UPDATE CASCADE ON DELETE CASCADE
+);
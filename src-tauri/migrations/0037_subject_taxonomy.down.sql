@@ -0,0 +1,5 @@
+-- Rollback: drop the subject taxonomy and its aliases.
+
+DROP INDEX IF EXISTS idx_subject_aliases_subject;
+DROP TABLE IF EXISTS subject_aliases;
+DROP TABLE IF EXISTS subject_taxonomy;
@@ -0,0 +1,19 @@
+-- Managed project subjects. Each subject and alias has a match key (lowercased, punctuation
+-- dropped) so "IT", "I.T." and "it" resolve to the same entry. Project subjects are
+-- rewritten to the canonical subject when they match one of its keys.
+
+CREATE TABLE IF NOT EXISTS subject_taxonomy (
+    subject TEXT PRIMARY KEY,
+    subject_key TEXT NOT NULL UNIQUE,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS subject_aliases (
+    alias_key TEXT PRIMARY KEY,
+    alias TEXT NOT NULL,
+    subject TEXT NOT NULL,
+    FOREIGN KEY (subject) REFERENCES subject_taxonomy(subject) ON UPDATE CASCADE ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_subject_aliases_subject
+    ON subject_aliases(subject);
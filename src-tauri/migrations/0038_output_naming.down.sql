@@ -0,0 +1,5 @@
+-- Rollback: drop project naming overrides and recorded output names.
+
+DROP INDEX IF EXISTS idx_conversion_output_names_xliff;
+DROP TABLE IF EXISTS conversion_output_names;
+DROP TABLE IF EXISTS project_output_naming;
@@ -0,0 +1,29 @@
+-- Output naming. `project_output_naming` overrides the global XLIFF and JLIFF name templates
+-- for a project; `conversion_output_names` records the names chosen for each file and
+-- language pair, with the templates they came from, so later plans and pipeline runs reuse
+-- them until the templates change.
+
+CREATE TABLE IF NOT EXISTS project_output_naming (
+    project_uuid TEXT PRIMARY KEY,
+    xliff_template TEXT,
+    jliff_template TEXT,
+    updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (project_uuid) REFERENCES projects(project_uuid) ON UPDATE CASCADE ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS conversion_output_names (
+    project_uuid TEXT NOT NULL,
+    file_uuid TEXT NOT NULL,
+    source_lang TEXT NOT NULL,
+    target_lang TEXT NOT NULL,
+    xliff_template TEXT NOT NULL,
+    xliff_rel_path TEXT NOT NULL,
+    jliff_template TEXT NOT NULL,
+    jliff_prefix TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (project_uuid, file_uuid, source_lang, target_lang),
+    FOREIGN KEY (project_uuid) REFERENCES projects(project_uuid) ON UPDATE CASCADE ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_conversion_output_names_xliff
+    ON conversion_output_names(project_uuid, xliff_rel_path);
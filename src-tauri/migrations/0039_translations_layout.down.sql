@@ -0,0 +1,3 @@
+-- Rollback: drop the per-project Translations folder layout.
+
+ALTER TABLE project_output_naming DROP COLUMN translations_layout;
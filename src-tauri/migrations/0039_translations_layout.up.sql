@@ -0,0 +1,5 @@
+-- Layout of the Translations folder per project: `language_first` puts the language pair
+-- folder above the folders the source files are stored in, `source_first` below them.
+-- Projects without a value keep the language-first layout.
+
+ALTER TABLE project_output_naming ADD COLUMN translations_layout TEXT;
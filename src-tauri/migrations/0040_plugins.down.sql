@@ -0,0 +1,4 @@
+-- Rollback: drop registered plugins and their settings.
+
+DROP TABLE IF EXISTS plugin_settings;
+DROP TABLE IF EXISTS plugins;
@@ -0,0 +1,19 @@
+-- Plugins registered from their manifest, and the setting values stored for each of them.
+-- The manifest is kept as registered so a plugin folder that changes or disappears is
+-- noticed before the plugin runs.
+
+CREATE TABLE IF NOT EXISTS plugins (
+    plugin_id TEXT PRIMARY KEY,
+    plugin_dir TEXT NOT NULL,
+    manifest_json TEXT NOT NULL,
+    registered_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS plugin_settings (
+    plugin_id TEXT NOT NULL,
+    setting_key TEXT NOT NULL,
+    value_json TEXT NOT NULL,
+    PRIMARY KEY (plugin_id, setting_key),
+    FOREIGN KEY (plugin_id) REFERENCES plugins(plugin_id) ON UPDATE CASCADE ON DELETE CASCADE
+);
@@ -0,0 +1,4 @@
+-- Rollback: drop automation scripts.
+
+DROP INDEX IF EXISTS idx_automation_scripts_hook;
+DROP TABLE IF EXISTS automation_scripts;
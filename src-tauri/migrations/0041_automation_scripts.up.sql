@@ -0,0 +1,17 @@
+-- Automation scripts: small user scripts attached to a hook point, each of which can be
+-- switched off without deleting it. The last run's time and error are kept for the editor.
+
+CREATE TABLE IF NOT EXISTS automation_scripts (
+    script_uuid TEXT PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    hook TEXT NOT NULL CHECK (hook IN ('on_segment_confirm', 'on_project_create', 'on_qa_finding')),
+    source TEXT NOT NULL,
+    enabled INTEGER NOT NULL DEFAULT 1,
+    last_run_at TEXT,
+    last_error TEXT,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_automation_scripts_hook
+    ON automation_scripts(hook, enabled);
@@ -0,0 +1,4 @@
+-- Rollback: drop offline operations.
+
+DROP INDEX IF EXISTS idx_offline_operations_created;
+DROP TABLE IF EXISTS offline_operations;
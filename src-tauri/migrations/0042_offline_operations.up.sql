@@ -0,0 +1,13 @@
+-- Offline operations: outbound work requested while the app was offline, such as delivery
+-- uploads, kept so it can be replayed or discarded once connectivity returns.
+
+CREATE TABLE IF NOT EXISTS offline_operations (
+    operation_uuid TEXT PRIMARY KEY,
+    operation TEXT NOT NULL,
+    payload_json TEXT NOT NULL,
+    project_uuid TEXT REFERENCES projects(project_uuid) ON DELETE CASCADE,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_offline_operations_created
+    ON offline_operations(created_at);
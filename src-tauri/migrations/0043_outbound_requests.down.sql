@@ -0,0 +1,4 @@
+-- Rollback: drop the outbound request audit and the per-project external MT flag.
+
+DROP TABLE IF EXISTS outbound_requests;
+ALTER TABLE projects DROP COLUMN no_external_mt;
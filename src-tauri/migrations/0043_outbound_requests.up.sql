@@ -0,0 +1,21 @@
+-- Outbound request audit: every request that sent document text to a translation provider,
+-- for clients who must know where their content went. Rows outlive their project, so
+-- `project_uuid` is not a foreign key. Projects flagged `no_external_mt` are only translated
+-- by providers that keep the text on this machine.
+
+ALTER TABLE projects ADD COLUMN no_external_mt INTEGER NOT NULL DEFAULT 0;
+
+CREATE TABLE IF NOT EXISTS outbound_requests (
+    request_uuid TEXT PRIMARY KEY,
+    provider TEXT NOT NULL,
+    project_uuid TEXT,
+    segment_count INTEGER NOT NULL,
+    char_count INTEGER NOT NULL,
+    destination_host TEXT,
+    sent_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_outbound_requests_sent
+    ON outbound_requests(sent_at);
+CREATE INDEX IF NOT EXISTS idx_outbound_requests_project
+    ON outbound_requests(project_uuid, sent_at);
@@ -0,0 +1,287 @@
+//! Validation and country-specific formatting of client postal addresses.
+//!
+//! Addresses resolved through Google Places are trusted as they come; manually entered ones
+//! must name a known country and, where the country has one, carry a postal code in its
+//! national format. Formatting follows the layout of the address's country, because that is
+//! what its postal service reads: "Hauptstraße 5 / 10115 Berlin" in Germany, "5 Main St /
+//! Springfield, IL 62701" in the United States.
+
+use anyhow::{Result, bail};
+use regex::Regex;
+
+use crate::db::types::PostalAddress;
+
+/// ISO 3166-1 alpha-2 codes.
+const COUNTRY_CODES: &str = "AD AE AF AG AI AL AM AO AQ AR AS AT AU AW AX AZ BA BB BD BE BF BG BH \
+BI BJ BL BM BN BO BQ BR BS BT BV BW BY BZ CA CC CD CF CG CH CI CK CL CM CN CO CR CU CV CW CX CY \
+CZ DE DJ DK DM DO DZ EC EE EG EH ER ES ET FI FJ FK FM FO FR GA GB GD GE GF GG GH GI GL GM GN GP \
+GQ GR GS GT GU GW GY HK HM HN HR HT HU ID IE IL IM IN IO IQ IR IS IT JE JM JO JP KE KG KH KI KM \
+KN KP KR KW KY KZ LA LB LC LI LK LR LS LT LU LV LY MA MC MD ME MF MG MH MK ML MM MN MO MP MQ MR \
+MS MT MU MV MW MX MY MZ NA NC NE NF NG NI NL NO NP NR NU NZ OM PA PE PF PG PH PK PL PM PN PR PS \
+PT PW PY QA RE RO RS RU RW SA SB SC SD SE SG SH SI SJ SK SL SM SN SO SR SS ST SV SX SY SZ TC TD \
+TF TG TH TJ TK TL TM TN TO TR TT TV TW TZ UA UG UM US UY UZ VA VC VE VG VI VN VU WF WS YE YT ZA \
+ZM ZW";
+
+/// Postal code formats, matched against the code uppercased with runs of spaces collapsed.
+/// Countries not listed accept any postal code.
+const POSTAL_FORMATS: &[(&str, &str)] = &[
+    ("AT", r"^\d{4}$"),
+    ("AU", r"^\d{4}$"),
+    ("BE", r"^\d{4}$"),
+    ("BG", r"^\d{4}$"),
+    ("BR", r"^\d{5}-?\d{3}$"),
+    ("CA", r"^[A-Z]\d[A-Z] ?\d[A-Z]\d$"),
+    ("CH", r"^\d{4}$"),
+    ("CN", r"^\d{6}$"),
+    ("CZ", r"^\d{3} ?\d{2}$"),
+    ("DE", r"^\d{5}$"),
+    ("DK", r"^\d{4}$"),
+    ("EE", r"^\d{5}$"),
+    ("ES", r"^\d{5}$"),
+    ("FI", r"^\d{5}$"),
+    ("FR", r"^\d{5}$"),
+    ("GB", r"^[A-Z]{1,2}\d[A-Z\d]? ?\d[A-Z]{2}$"),
+    ("GR", r"^\d{3} ?\d{2}$"),
+    ("HR", r"^\d{5}$"),
+    ("HU", r"^\d{4}$"),
+    ("IE", r"^[A-Z]\d[\dW] ?[A-Z\d]{4}$"),
+    ("IN", r"^\d{6}$"),
+    ("IT", r"^\d{5}$"),
+    ("JP", r"^\d{3}-?\d{4}$"),
+    ("KR", r"^\d{5}$"),
+    ("LT", r"^(LT-)?\d{5}$"),
+    ("LU", r"^(L-)?\d{4}$"),
+    ("LV", r"^(LV-)?\d{4}$"),
+    ("MX", r"^\d{5}$"),
+    ("NL", r"^\d{4} ?[A-Z]{2}$"),
+    ("NO", r"^\d{4}$"),
+    ("NZ", r"^\d{4}$"),
+    ("PL", r"^\d{2}-\d{3}$"),
+    ("PT", r"^\d{4}-\d{3}$"),
+    ("RO", r"^\d{6}$"),
+    ("RU", r"^\d{6}$"),
+    ("SE", r"^\d{3} ?\d{2}$"),
+    ("SI", r"^\d{4}$"),
+    ("SK", r"^\d{3} ?\d{2}$"),
+    ("TR", r"^\d{5}$"),
+    ("US", r"^\d{5}(-\d{4})?$"),
+];
+
+/// How a country lays out the street and city lines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Layout {
+    /// "Hauptstraße 5" / "10115 Berlin".
+    Continental,
+    /// "5 rue de Rivoli" / "75001 Paris".
+    French,
+    /// "10 Downing Street" / "London" / "SW1A 2AA".
+    British,
+    /// "1600 Main St" / "Springfield, IL 62701".
+    American,
+    /// "1 George St" / "Sydney NSW 2000".
+    Australian,
+}
+
+impl Layout {
+    fn for_country(code: &str) -> Self {
+        match code {
+            "FR" | "BE" | "LU" | "MC" => Layout::French,
+            "GB" | "IE" | "IM" | "JE" | "GG" => Layout::British,
+            "US" | "CA" | "PR" | "PH" => Layout::American,
+            "AU" | "NZ" => Layout::Australian,
+            _ => Layout::Continental,
+        }
+    }
+}
+
+/// Trims every field, drops empty ones and uppercases the country code. Manual entries
+/// (without a Places id) must then name a known country and a locality, and a postal code
+/// must match the country's format.
+pub fn normalize_postal_address(address: PostalAddress) -> Result<PostalAddress> {
+    let clean = |value: Option<String>| {
+        value
+            .map(|value| value.split_whitespace().collect::<Vec<_>>().join(" "))
+            .filter(|value| !value.is_empty())
+    };
+    let mut address = PostalAddress {
+        street: clean(address.street),
+        street_number: clean(address.street_number),
+        postal_code: clean(address.postal_code),
+        locality: clean(address.locality),
+        region: clean(address.region),
+        country_code: clean(address.country_code).map(|code| code.to_uppercase()),
+        country: clean(address.country),
+        place_id: clean(address.place_id),
+    };
+    if address.is_empty() || address.place_id.is_some() {
+        return Ok(address);
+    }
+
+    let Some(country_code) = address.country_code.clone() else {
+        bail!("The address needs a country code.");
+    };
+    if !is_country_code(&country_code) {
+        bail!("'{country_code}' is not an ISO 3166-1 country code.");
+    }
+    if address.locality.is_none() {
+        bail!("The address needs a city or locality.");
+    }
+    if let Some(postal_code) = address.postal_code.as_mut() {
+        *postal_code = postal_code.to_uppercase();
+        if let Some(pattern) = postal_format(&country_code)
+            && !pattern.is_match(postal_code)
+        {
+            bail!("'{postal_code}' is not a valid postal code for {country_code}.");
+        }
+    }
+    Ok(address)
+}
+
+/// Lines of `address` in the layout of its country, with the recipient `name` first when
+/// given. The country line is added when the address is abroad from `home_country_code`
+/// (or always, without one).
+pub fn format_postal_address(
+    name: Option<&str>,
+    address: &PostalAddress,
+    home_country_code: Option<&str>,
+) -> Vec<String> {
+    let country_code = address.country_code.as_deref().unwrap_or_default();
+    let layout = Layout::for_country(country_code);
+    let street = address.street.as_deref();
+    let number = address.street_number.as_deref();
+    let postal = address.postal_code.as_deref();
+    let locality = address.locality.as_deref();
+    let region = address.region.as_deref();
+
+    let mut lines: Vec<String> = name
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+        .into_iter()
+        .collect();
+    let street_line = match layout {
+        Layout::Continental => join(&[street, number], " "),
+        _ => join(&[number, street], " "),
+    };
+    lines.extend(street_line);
+    match layout {
+        Layout::Continental | Layout::French => {
+            // Italian addresses end the city line with the province.
+            let province = region.filter(|_| country_code == "IT");
+            lines.extend(join(&[postal, locality, province], " "));
+        }
+        Layout::British => {
+            lines.extend(locality.map(str::to_string));
+            lines.extend(postal.map(str::to_string));
+        }
+        Layout::American => {
+            let region_postal = join(&[region, postal], " ");
+            lines.extend(join(&[locality, region_postal.as_deref()], ", "));
+        }
+        Layout::Australian => lines.extend(join(&[locality, region, postal], " ")),
+    }
+
+    let abroad =
+        home_country_code.is_none_or(|home| !home.trim().eq_ignore_ascii_case(country_code));
+    if abroad {
+        let country = address
+            .country
+            .as_deref()
+            .or(address.country_code.as_deref());
+        lines.extend(country.map(str::to_uppercase));
+    }
+    lines
+}
+
+fn join(parts: &[Option<&str>], separator: &str) -> Option<String> {
+    let parts: Vec<&str> = parts.iter().flatten().copied().collect();
+    (!parts.is_empty()).then(|| parts.join(separator))
+}
+
+fn is_country_code(code: &str) -> bool {
+    code.len() == 2 && COUNTRY_CODES.split_whitespace().any(|known| known == code)
+}
+
+fn postal_format(country_code: &str) -> Option<Regex> {
+    POSTAL_FORMATS
+        .iter()
+        .find(|(code, _)| *code == country_code)
+        .and_then(|(_, pattern)| Regex::new(pattern).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn address(
+        street: &str,
+        number: &str,
+        postal: &str,
+        locality: &str,
+        region: Option<&str>,
+        country_code: &str,
+    ) -> PostalAddress {
+        PostalAddress {
+            street: Some(street.into()),
+            street_number: Some(number.into()),
+            postal_code: Some(postal.into()),
+            locality: Some(locality.into()),
+            region: region.map(Into::into),
+            country_code: Some(country_code.into()),
+            country: None,
+            place_id: None,
+        }
+    }
+
+    #[test]
+    fn validates_manual_entries_but_trusts_places() {
+        let normalized = normalize_postal_address(address(
+            " Keizersgracht ",
+            "12",
+            "1015 cs",
+            "Amsterdam",
+            None,
+            "nl",
+        ))
+        .unwrap();
+        assert_eq!(normalized.country_code.as_deref(), Some("NL"));
+        assert_eq!(normalized.postal_code.as_deref(), Some("1015 CS"));
+        assert_eq!(normalized.street.as_deref(), Some("Keizersgracht"));
+
+        let wrong_postal = address("Hauptstraße", "5", "1011", "Berlin", None, "DE");
+        assert!(normalize_postal_address(wrong_postal.clone()).is_err());
+        assert!(normalize_postal_address(address("Main", "1", "1", "X", None, "XX")).is_err());
+        let from_places = PostalAddress {
+            place_id: Some("ChIJ123".into()),
+            ..wrong_postal
+        };
+        assert!(normalize_postal_address(from_places).is_ok());
+        assert!(
+            normalize_postal_address(PostalAddress::default())
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn lays_out_addresses_the_way_their_country_does() {
+        let berlin = address("Hauptstraße", "5", "10115", "Berlin", None, "DE");
+        assert_eq!(
+            format_postal_address(Some("Müller GmbH"), &berlin, Some("de")),
+            ["Müller GmbH", "Hauptstraße 5", "10115 Berlin"]
+        );
+        let springfield = PostalAddress {
+            country: Some("United States".into()),
+            ..address("Main St", "1600", "62701", "Springfield", Some("IL"), "US")
+        };
+        assert_eq!(
+            format_postal_address(None, &springfield, Some("DE")),
+            ["1600 Main St", "Springfield, IL 62701", "UNITED STATES"]
+        );
+        let london = address("Downing Street", "10", "SW1A 2AA", "London", None, "GB");
+        assert_eq!(
+            format_postal_address(None, &london, Some("GB")),
+            ["10 Downing Street", "London", "SW1A 2AA"]
+        );
+    }
+}
@@ -0,0 +1,275 @@
+//! Crash reports for problems that happen on users' machines.
+//!
+//! Two kinds of reports are written as JSON files under `<app_data>/crashes/`:
+//!
+//! * a panic hook records the message, location, thread and backtrace of every panic;
+//! * a sentinel file is created when a session starts and removed on a clean exit, so a
+//!   sentinel still present at the next start means the previous session died without
+//!   running its shutdown (native crash, killed process, power loss). That session gets an
+//!   `unclean_shutdown` report unless a panic report already explains it.
+//!
+//! Reports stay local; uploading them is opt-in and handled by the IPC layer.
+
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::panic::{self, PanicHookInfo};
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Directory (relative to the app data directory) holding crash reports.
+pub const CRASHES_DIR: &str = "crashes";
+/// Present while a session runs.
+const SENTINEL_FILE: &str = "session.lock";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CrashKind {
+    Panic,
+    UncleanShutdown,
+}
+
+impl CrashKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CrashKind::Panic => "panic",
+            CrashKind::UncleanShutdown => "unclean_shutdown",
+        }
+    }
+}
+
+/// One crash report, stored as `<id>.json`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashReport {
+    pub id: Uuid,
+    pub kind: CrashKind,
+    pub occurred_at: DateTime<Utc>,
+    /// Start of the session the report belongs to, when known.
+    pub session_started_at: Option<DateTime<Utc>>,
+    pub app_version: String,
+    pub os: String,
+    pub arch: String,
+    pub thread: Option<String>,
+    pub message: String,
+    /// `file:line:column` of the panic.
+    pub location: Option<String>,
+    pub backtrace: Option<String>,
+    #[serde(default)]
+    pub uploaded_at: Option<DateTime<Utc>>,
+}
+
+impl CrashReport {
+    fn new(kind: CrashKind, app_version: &str, message: String) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            kind,
+            occurred_at: Utc::now(),
+            session_started_at: None,
+            app_version: app_version.to_string(),
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            thread: None,
+            message,
+            location: None,
+            backtrace: None,
+            uploaded_at: None,
+        }
+    }
+}
+
+/// Crash reporting for the running session, registered as Tauri state.
+pub struct CrashReporter {
+    dir: PathBuf,
+    app_version: String,
+    session_started_at: DateTime<Utc>,
+    previous_session_report: Option<Uuid>,
+}
+
+impl CrashReporter {
+    /// Starts a session: reports an unclean end of the previous one, then writes the
+    /// sentinel for this one.
+    pub fn start(dir: PathBuf, app_version: &str) -> io::Result<Self> {
+        fs::create_dir_all(&dir)?;
+        let sentinel = dir.join(SENTINEL_FILE);
+        let previous_session_report = match fs::read_to_string(&sentinel) {
+            Ok(contents) => Some(report_unclean_shutdown(
+                &dir,
+                app_version,
+                DateTime::parse_from_rfc3339(contents.trim())
+                    .ok()
+                    .map(|started_at| started_at.with_timezone(&Utc)),
+            )?),
+            Err(error) if error.kind() == ErrorKind::NotFound => None,
+            Err(error) => return Err(error),
+        };
+        let session_started_at = Utc::now();
+        fs::write(&sentinel, session_started_at.to_rfc3339())?;
+        Ok(Self {
+            dir,
+            app_version: app_version.to_string(),
+            session_started_at,
+            previous_session_report,
+        })
+    }
+
+    /// Records every panic as a report before running the previously installed hook.
+    pub fn install_panic_hook(&self) {
+        let dir = self.dir.clone();
+        let app_version = self.app_version.clone();
+        let session_started_at = self.session_started_at;
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            let mut report = panic_report(info, &app_version);
+            report.session_started_at = Some(session_started_at);
+            // Nothing sensible can be done when the report cannot be written mid-panic.
+            let _ = write_report(&dir, &report);
+            previous(info);
+        }));
+    }
+
+    /// Marks the session as cleanly ended.
+    pub fn end_session(&self) {
+        if let Err(error) = fs::remove_file(self.dir.join(SENTINEL_FILE))
+            && error.kind() != ErrorKind::NotFound
+        {
+            log::warn!(target: "crash", "failed to remove the session sentinel: {error}");
+        }
+    }
+
+    /// Report explaining why the previous session did not end cleanly, if it did not.
+    pub fn previous_session_report(&self) -> Option<Uuid> {
+        self.previous_session_report
+    }
+
+    /// All reports, newest first.
+    pub fn list_reports(&self) -> io::Result<Vec<CrashReport>> {
+        list_reports(&self.dir)
+    }
+
+    pub fn mark_uploaded(&self, id: Uuid, uploaded_at: DateTime<Utc>) -> io::Result<()> {
+        let path = report_path(&self.dir, id);
+        let mut report: CrashReport =
+            serde_json::from_slice(&fs::read(&path)?).map_err(io::Error::other)?;
+        report.uploaded_at = Some(uploaded_at);
+        write_report(&self.dir, &report)
+    }
+}
+
+/// Returns the report for a session that ended without removing its sentinel: the panic
+/// recorded during that session when there is one, a new `unclean_shutdown` report otherwise.
+fn report_unclean_shutdown(
+    dir: &Path,
+    app_version: &str,
+    session_started_at: Option<DateTime<Utc>>,
+) -> io::Result<Uuid> {
+    let panicked = list_reports(dir)?.into_iter().find(|report| {
+        report.kind == CrashKind::Panic
+            && session_started_at.is_some()
+            && report.session_started_at == session_started_at
+    });
+    if let Some(report) = panicked {
+        return Ok(report.id);
+    }
+    let mut report = CrashReport::new(
+        CrashKind::UncleanShutdown,
+        app_version,
+        "The previous session ended without shutting down cleanly.".into(),
+    );
+    report.session_started_at = session_started_at;
+    write_report(dir, &report)?;
+    Ok(report.id)
+}
+
+fn panic_report(info: &PanicHookInfo<'_>, app_version: &str) -> CrashReport {
+    let payload = info.payload();
+    let message = payload
+        .downcast_ref::<&str>()
+        .map(|text| text.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "panic with a non-string payload".into());
+    let mut report = CrashReport::new(CrashKind::Panic, app_version, message);
+    report.thread = std::thread::current().name().map(str::to_string);
+    report.location = info.location().map(|location| {
+        format!(
+            "{}:{}:{}",
+            location.file(),
+            location.line(),
+            location.column()
+        )
+    });
+    report.backtrace = Some(std::backtrace::Backtrace::force_capture().to_string());
+    report
+}
+
+fn report_path(dir: &Path, id: Uuid) -> PathBuf {
+    dir.join(format!("{id}.json"))
+}
+
+fn write_report(dir: &Path, report: &CrashReport) -> io::Result<()> {
+    let json = serde_json::to_vec_pretty(report).map_err(io::Error::other)?;
+    fs::write(report_path(dir, report.id), json)
+}
+
+fn list_reports(dir: &Path) -> io::Result<Vec<CrashReport>> {
+    let mut reports = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().is_none_or(|ext| ext != "json") {
+            continue;
+        }
+        // A report cut short by the crash itself is skipped rather than failing the list.
+        match fs::read(&path).map(|bytes| serde_json::from_slice::<CrashReport>(&bytes)) {
+            Ok(Ok(report)) => reports.push(report),
+            _ => log::warn!(target: "crash", "skipping unreadable report {}", path.display()),
+        }
+    }
+    reports.sort_by(|a, b| b.occurred_at.cmp(&a.occurred_at));
+    Ok(reports)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_sessions_that_did_not_end_cleanly() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(CRASHES_DIR);
+
+        let clean = CrashReporter::start(path.clone(), "1.0.0").unwrap();
+        assert_eq!(clean.previous_session_report(), None);
+        clean.end_session();
+
+        let killed = CrashReporter::start(path.clone(), "1.0.0").unwrap();
+        assert_eq!(killed.previous_session_report(), None);
+
+        let after_kill = CrashReporter::start(path.clone(), "1.0.0").unwrap();
+        let reports = after_kill.list_reports().unwrap();
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].kind, CrashKind::UncleanShutdown);
+        assert_eq!(
+            reports[0].session_started_at,
+            Some(killed.session_started_at)
+        );
+        assert_eq!(after_kill.previous_session_report(), Some(reports[0].id));
+
+        // A panic recorded during the session explains the unclean end on its own.
+        let mut panic = CrashReport::new(CrashKind::Panic, "1.0.0", "boom".into());
+        panic.session_started_at = Some(after_kill.session_started_at);
+        write_report(&path, &panic).unwrap();
+        let after_panic = CrashReporter::start(path.clone(), "1.0.0").unwrap();
+        assert_eq!(after_panic.previous_session_report(), Some(panic.id));
+        assert_eq!(after_panic.list_reports().unwrap().len(), 2);
+
+        after_panic.mark_uploaded(panic.id, Utc::now()).unwrap();
+        let uploaded = after_panic.list_reports().unwrap();
+        assert!(
+            uploaded
+                .iter()
+                .any(|report| report.id == panic.id && report.uploaded_at.is_some())
+        );
+    }
+}
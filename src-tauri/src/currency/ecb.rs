@@ -0,0 +1,84 @@
+//! Euro foreign exchange reference rates published daily by the European Central Bank.
+
+use std::time::Duration;
+
+use anyhow::{Context, Result, bail};
+use regex::Regex;
+
+use crate::http;
+
+pub const ECB_DAILY_URL: &str = "https://www.ecb.europa.eu/stats/eurofxref/eurofxref-daily.xml";
+
+/// One ECB reference rate: one euro is worth `rate` units of `currency` on `rate_date`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReferenceRate {
+    pub currency: String,
+    pub rate: f64,
+    pub rate_date: String,
+}
+
+/// Downloads the latest reference rates.
+pub async fn fetch_reference_rates() -> Result<Vec<ReferenceRate>> {
+    let client = http::client_builder()
+        .timeout(Duration::from_secs(15))
+        .build()?;
+    let body = client
+        .get(ECB_DAILY_URL)
+        .send()
+        .await
+        .context("the ECB could not be reached")?
+        .error_for_status()
+        .context("the ECB rejected the request")?
+        .text()
+        .await
+        .context("the ECB response could not be read")?;
+    parse_reference_rates(&body)
+}
+
+/// Reads the `Cube` elements of an ECB rates document.
+pub fn parse_reference_rates(xml: &str) -> Result<Vec<ReferenceRate>> {
+    let date = Regex::new(r#"time=["'](\d{4}-\d{2}-\d{2})["']"#).expect("valid pattern");
+    let rate = Regex::new(r#"currency=["']([A-Z]{3})["']\s+rate=["']([0-9.]+)["']"#)
+        .expect("valid pattern");
+    let Some(rate_date) = date.captures(xml).map(|captures| captures[1].to_string()) else {
+        bail!("the ECB rates have no date");
+    };
+    let rates: Vec<ReferenceRate> = rate
+        .captures_iter(xml)
+        .filter_map(|captures| {
+            Some(ReferenceRate {
+                currency: captures[1].to_string(),
+                rate: captures[2].parse().ok()?,
+                rate_date: rate_date.clone(),
+            })
+        })
+        .collect();
+    if rates.is_empty() {
+        bail!("the ECB document holds no rates");
+    }
+    Ok(rates)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_the_daily_reference_rates() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<gesmes:Envelope xmlns:gesmes="http://www.gesmes.org/xml/2002-08-01">
+  <Cube>
+    <Cube time='2026-03-02'>
+      <Cube currency='USD' rate='1.0812'/>
+      <Cube currency='JPY' rate='162.45'/>
+    </Cube>
+  </Cube>
+</gesmes:Envelope>"#;
+        let rates = parse_reference_rates(xml).unwrap();
+        assert_eq!(rates.len(), 2);
+        assert_eq!(rates[0].currency, "USD");
+        assert_eq!(rates[0].rate, 1.0812);
+        assert_eq!(rates[1].rate_date, "2026-03-02");
+        assert!(parse_reference_rates("<Cube/>").is_err());
+    }
+}
@@ -0,0 +1,217 @@
+//! Currencies and conversion between them with dated exchange-rate snapshots.
+//!
+//! A snapshot says that on `rate_date` one unit of the base currency was worth `rate` units
+//! of the quote currency. Rates are entered by hand or fetched from the European Central
+//! Bank ([`ecb`]), which quotes everything against the euro; a [`RateTable`] therefore
+//! converts directly, through the inverse of a pair, or across a currency both sides are
+//! quoted against, so USD→CHF works from EUR→USD and EUR→CHF.
+
+pub mod ecb;
+
+use anyhow::{Result, bail};
+
+use crate::db::types::ExchangeRateRecord;
+
+/// ISO 4217 codes in use, with the number of digits of their minor unit.
+const CURRENCIES: &[(&str, u32)] = &[
+    ("AED", 2),
+    ("ARS", 2),
+    ("AUD", 2),
+    ("BGN", 2),
+    ("BHD", 3),
+    ("BRL", 2),
+    ("CAD", 2),
+    ("CHF", 2),
+    ("CLP", 0),
+    ("CNY", 2),
+    ("COP", 2),
+    ("CZK", 2),
+    ("DKK", 2),
+    ("EGP", 2),
+    ("EUR", 2),
+    ("GBP", 2),
+    ("HKD", 2),
+    ("HUF", 2),
+    ("IDR", 2),
+    ("ILS", 2),
+    ("INR", 2),
+    ("ISK", 0),
+    ("JOD", 3),
+    ("JPY", 0),
+    ("KRW", 0),
+    ("KWD", 3),
+    ("MAD", 2),
+    ("MXN", 2),
+    ("MYR", 2),
+    ("NOK", 2),
+    ("NZD", 2),
+    ("OMR", 3),
+    ("PHP", 2),
+    ("PLN", 2),
+    ("QAR", 2),
+    ("RON", 2),
+    ("RSD", 2),
+    ("SAR", 2),
+    ("SEK", 2),
+    ("SGD", 2),
+    ("THB", 2),
+    ("TND", 3),
+    ("TRY", 2),
+    ("TWD", 2),
+    ("UAH", 2),
+    ("USD", 2),
+    ("VND", 0),
+    ("ZAR", 2),
+];
+
+/// Uppercases `code` and checks it is a known ISO 4217 currency.
+pub fn normalize_currency(code: &str) -> Result<String> {
+    let code = code.trim().to_uppercase();
+    if minor_unit_digits(&code).is_none() {
+        bail!("'{code}' is not a supported ISO 4217 currency code.");
+    }
+    Ok(code)
+}
+
+fn minor_unit_digits(code: &str) -> Option<u32> {
+    CURRENCIES
+        .iter()
+        .find(|(known, _)| *known == code)
+        .map(|(_, digits)| *digits)
+}
+
+/// Rounds `amount` to the minor unit of `currency`: cents for EUR, whole yen for JPY.
+pub fn round_amount(amount: f64, currency: &str) -> f64 {
+    let scale = 10f64.powi(minor_unit_digits(currency).unwrap_or(2) as i32);
+    (amount * scale).round() / scale
+}
+
+/// Rate of one conversion and the snapshot date it rests on.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conversion {
+    pub rate: f64,
+    /// Date of the oldest snapshot used; `None` when both currencies are the same.
+    pub rate_date: Option<String>,
+}
+
+/// The exchange rates in effect on one day, one snapshot per currency pair.
+#[derive(Debug, Clone, Default)]
+pub struct RateTable {
+    rates: Vec<ExchangeRateRecord>,
+}
+
+impl RateTable {
+    pub fn new(rates: Vec<ExchangeRateRecord>) -> Self {
+        Self { rates }
+    }
+
+    /// Rate turning amounts in `from` into `to`, if the snapshots connect the two.
+    pub fn conversion(&self, from: &str, to: &str) -> Option<Conversion> {
+        if from == to {
+            return Some(Conversion {
+                rate: 1.0,
+                rate_date: None,
+            });
+        }
+        if let Some(direct) = self.pair(from, to) {
+            return Some(direct);
+        }
+        // Cross over a currency both sides are quoted against, preferring the most recent.
+        self.currencies()
+            .filter(|pivot| *pivot != from && *pivot != to)
+            .filter_map(|pivot| {
+                let first = self.pair(from, pivot)?;
+                let second = self.pair(pivot, to)?;
+                Some(Conversion {
+                    rate: first.rate * second.rate,
+                    rate_date: first.rate_date.min(second.rate_date),
+                })
+            })
+            .max_by(|a, b| a.rate_date.cmp(&b.rate_date))
+    }
+
+    /// Converts `amount` from `from` into `to`, rounded to the minor unit of `to`.
+    pub fn convert(&self, amount: f64, from: &str, to: &str) -> Option<(f64, Conversion)> {
+        let conversion = self.conversion(from, to)?;
+        Some((round_amount(amount * conversion.rate, to), conversion))
+    }
+
+    fn pair(&self, from: &str, to: &str) -> Option<Conversion> {
+        self.rates.iter().find_map(|record| {
+            let rate = if record.base_currency == from && record.quote_currency == to {
+                record.rate
+            } else if record.base_currency == to && record.quote_currency == from {
+                1.0 / record.rate
+            } else {
+                return None;
+            };
+            Some(Conversion {
+                rate,
+                rate_date: Some(record.rate_date.clone()),
+            })
+        })
+    }
+
+    fn currencies(&self) -> impl Iterator<Item = &str> {
+        let mut codes: Vec<&str> = self
+            .rates
+            .iter()
+            .flat_map(|record| {
+                [
+                    record.base_currency.as_str(),
+                    record.quote_currency.as_str(),
+                ]
+            })
+            .collect();
+        codes.sort_unstable();
+        codes.dedup();
+        codes.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(base: &str, quote: &str, rate: f64, date: &str) -> ExchangeRateRecord {
+        ExchangeRateRecord {
+            base_currency: base.into(),
+            quote_currency: quote.into(),
+            rate_date: date.into(),
+            rate,
+            source: "manual".into(),
+            recorded_at: "2026-01-01T00:00:00Z".into(),
+        }
+    }
+
+    #[test]
+    fn converts_directly_inversely_and_across_a_shared_currency() {
+        let table = RateTable::new(vec![
+            snapshot("EUR", "USD", 1.25, "2026-03-02"),
+            snapshot("EUR", "CHF", 0.95, "2026-02-27"),
+            snapshot("EUR", "JPY", 160.0, "2026-03-02"),
+        ]);
+
+        let (usd, conversion) = table.convert(100.0, "EUR", "USD").unwrap();
+        assert_eq!(usd, 125.0);
+        assert_eq!(conversion.rate_date.as_deref(), Some("2026-03-02"));
+        assert_eq!(table.convert(125.0, "USD", "EUR").unwrap().0, 100.0);
+
+        let (chf, conversion) = table.convert(125.0, "USD", "CHF").unwrap();
+        assert_eq!(chf, 95.0);
+        assert_eq!(conversion.rate_date.as_deref(), Some("2026-02-27"));
+        assert_eq!(table.convert(10.0, "USD", "JPY").unwrap().0, 1280.0);
+
+        assert!(table.conversion("USD", "GBP").is_none());
+        assert_eq!(table.conversion("GBP", "GBP").unwrap().rate, 1.0);
+    }
+
+    #[test]
+    fn knows_currency_codes_and_minor_units() {
+        assert_eq!(normalize_currency(" usd ").unwrap(), "USD");
+        assert!(normalize_currency("EURO").is_err());
+        assert_eq!(round_amount(10.005_1, "EUR"), 10.01);
+        assert_eq!(round_amount(1234.5, "JPY"), 1235.0);
+        assert_eq!(round_amount(1.234_56, "KWD"), 1.235);
+    }
+}
@@ -38,11 +38,11 @@ pub fn build_client(row: &SqliteRow) -> DbResult<Client> {
 }
 
 /// Maps a domain reference row into [`Domain`].
-#[allow(dead_code)]
 pub fn build_domain(row: &SqliteRow) -> DbResult<Domain> {
     Ok(Domain {
         domain_id: row.try_get("domain_id")?,
         name: row.try_get("name")?,
+        description: row.try_get("description")?,
     })
 }
 
@@ -156,8 +156,7 @@ pub fn build_validation(row: &SqliteRow) -> DbResult<Validation> {
     })
 }
 
-/// Hydrates a project note row.
-#[allow(dead_code)]
+/// Hydrates a project note row; its mentions are loaded separately.
 pub fn build_note(row: &SqliteRow) -> DbResult<Note> {
     let note_id_raw: String = row.try_get("note_id")?;
     let project_id_raw: String = row.try_get("project_id")?;
@@ -172,7 +171,11 @@ pub fn build_note(row: &SqliteRow) -> DbResult<Note> {
         project_id,
         author_user_id: row.try_get("author_user_id")?,
         body: row.try_get("body")?,
+        is_markdown: row.try_get::<i64, _>("is_markdown")? != 0,
+        pinned: row.try_get::<i64, _>("pinned")? != 0,
         created_at: row.try_get("created_at")?,
+        updated_at: row.try_get("updated_at")?,
+        mentions: Vec::new(),
     })
 }
 
@@ -0,0 +1,242 @@
+//! Locale-aware ordering of names in list queries.
+//!
+//! SQLite compares text byte by byte, which puts "Zoë" before "Émile" and every accented
+//! name after the unaccented ones. List operations therefore fetch their rows and order them
+//! with a [`Collator`] built from the UI language. Like ICU, comparison is multi-level: base
+//! letters first, then accents, then case, so "resume" < "résumé" < "Résumé" < "resumes".
+//! Languages that treat some accented letters as letters of their own ("ñ" in Spanish, "å"
+//! in Swedish) get those letters placed after the one they follow in their alphabet.
+
+use std::cmp::Ordering;
+
+use unicode_normalization::char::{decompose_canonical, is_combining_mark};
+
+/// Gap between the weights of consecutive code points, leaving room for tailored letters.
+const WEIGHT_STEP: u32 = 8;
+
+/// Secondary weight marking the second letter of an expansion such as "ß" → "ss".
+const EXPANSION_MARK: u32 = 1;
+
+/// Alphabet differences from the root order of a language.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum Tailoring {
+    #[default]
+    Root,
+    Spanish,
+    Swedish,
+    Danish,
+    Turkish,
+    Polish,
+    Czech,
+}
+
+impl Tailoring {
+    /// Letters sorted as letters of their own, each with the letter it follows. Letters
+    /// following the same one keep the order of this list.
+    fn letters(self) -> &'static [(char, char)] {
+        match self {
+            Tailoring::Root => &[],
+            Tailoring::Spanish => &[('ñ', 'n')],
+            Tailoring::Swedish => &[('å', 'z'), ('ä', 'z'), ('ö', 'z')],
+            Tailoring::Danish => &[('æ', 'z'), ('ø', 'z'), ('å', 'z')],
+            Tailoring::Turkish => &[
+                ('ç', 'c'),
+                ('ğ', 'g'),
+                ('ı', 'h'),
+                ('ö', 'o'),
+                ('ş', 's'),
+                ('ü', 'u'),
+            ],
+            Tailoring::Polish => &[
+                ('ą', 'a'),
+                ('ć', 'c'),
+                ('ę', 'e'),
+                ('ł', 'l'),
+                ('ń', 'n'),
+                ('ó', 'o'),
+                ('ś', 's'),
+                ('ź', 'z'),
+                ('ż', 'z'),
+            ],
+            Tailoring::Czech => &[('č', 'c'), ('ř', 'r'), ('š', 's'), ('ž', 'z')],
+        }
+    }
+}
+
+/// Orders strings for a language.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Collator {
+    tailoring: Tailoring,
+}
+
+/// Comparable key of a string under a [`Collator`]; compute it once per row when sorting.
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SortKey {
+    primary: Vec<u32>,
+    secondary: Vec<u32>,
+    tertiary: Vec<bool>,
+    /// Byte order as the last resort, so distinct strings never compare equal.
+    text: String,
+}
+
+impl Collator {
+    /// Collator for a BCP 47 tag such as `sv` or `es-MX`; unknown languages use the root order.
+    pub fn for_language(tag: &str) -> Self {
+        let language = tag
+            .split(['-', '_'])
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_lowercase();
+        let tailoring = match language.as_str() {
+            "es" | "gl" => Tailoring::Spanish,
+            "sv" | "fi" => Tailoring::Swedish,
+            "da" | "nb" | "nn" | "no" => Tailoring::Danish,
+            "tr" | "az" => Tailoring::Turkish,
+            "pl" => Tailoring::Polish,
+            "cs" => Tailoring::Czech,
+            _ => Tailoring::Root,
+        };
+        Self { tailoring }
+    }
+
+    pub fn compare(&self, left: &str, right: &str) -> Ordering {
+        self.sort_key(left).cmp(&self.sort_key(right))
+    }
+
+    /// Sorts `items` by the string `key` returns.
+    pub fn sort_by_text<T>(&self, items: &mut [T], key: impl Fn(&T) -> &str) {
+        items.sort_by_cached_key(|item| self.sort_key(key(item)));
+    }
+
+    pub fn sort_key(&self, text: &str) -> SortKey {
+        let mut key = SortKey {
+            text: text.to_string(),
+            ..SortKey::default()
+        };
+        for ch in text.chars() {
+            let upper = ch.is_uppercase();
+            for lower in self.lowercase(ch) {
+                if let Some(weight) = self.tailored_weight(lower) {
+                    key.push_letter(weight, upper);
+                    continue;
+                }
+                match lower {
+                    'ß' => key.push_expansion(&['s', 's'], upper),
+                    'æ' => key.push_expansion(&['a', 'e'], upper),
+                    'œ' => key.push_expansion(&['o', 'e'], upper),
+                    // Letters with a stroke do not decompose; treat the stroke as an accent.
+                    'ø' => key.push_accented('o', '\u{0338}', upper),
+                    'ł' => key.push_accented('l', '\u{0337}', upper),
+                    'đ' => key.push_accented('d', '\u{0335}', upper),
+                    _ => decompose_canonical(lower, |part| {
+                        if is_combining_mark(part) {
+                            key.secondary.push(part as u32);
+                        } else {
+                            key.push_letter(weight(part), upper);
+                        }
+                    }),
+                }
+            }
+        }
+        key
+    }
+
+    fn lowercase(&self, ch: char) -> Vec<char> {
+        match (self.tailoring, ch) {
+            (Tailoring::Turkish, 'I') => vec!['ı'],
+            (Tailoring::Turkish, 'İ') => vec!['i'],
+            _ => ch.to_lowercase().collect(),
+        }
+    }
+
+    fn tailored_weight(&self, lower: char) -> Option<u32> {
+        let letters = self.tailoring.letters();
+        let index = letters.iter().position(|(letter, _)| *letter == lower)?;
+        let anchor = letters[index].1;
+        let rank = letters[..index]
+            .iter()
+            .filter(|(_, follows)| *follows == anchor)
+            .count() as u32;
+        Some(weight(anchor) + rank + 1)
+    }
+}
+
+impl SortKey {
+    fn push_letter(&mut self, primary: u32, upper: bool) {
+        self.primary.push(primary);
+        self.secondary.push(0);
+        self.tertiary.push(upper);
+    }
+
+    fn push_accented(&mut self, base: char, accent: char, upper: bool) {
+        self.push_letter(weight(base), upper);
+        self.secondary.push(accent as u32);
+    }
+
+    fn push_expansion(&mut self, letters: &[char], upper: bool) {
+        for (index, letter) in letters.iter().enumerate() {
+            self.push_letter(weight(*letter), upper);
+            if index > 0 {
+                self.secondary.push(EXPANSION_MARK);
+            }
+        }
+    }
+}
+
+fn weight(ch: char) -> u32 {
+    ch as u32 * WEIGHT_STEP
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sorted(language: &str, names: &[&str]) -> Vec<String> {
+        let mut names: Vec<String> = names.iter().map(|name| name.to_string()).collect();
+        Collator::for_language(language).sort_by_text(&mut names, String::as_str);
+        names
+    }
+
+    #[test]
+    fn orders_by_letters_then_accents_then_case() {
+        assert_eq!(
+            sorted(
+                "en",
+                &["Zoë", "Émile", "eagle", "Apple", "apple", "Oslo", "Ölsen"]
+            ),
+            ["apple", "Apple", "eagle", "Émile", "Ölsen", "Oslo", "Zoë"]
+        );
+        assert_eq!(
+            sorted("de-DE", &["resumes", "Résumé", "résumé", "resume"]),
+            ["resume", "résumé", "Résumé", "resumes"]
+        );
+        assert_eq!(
+            sorted("de", &["Strasse", "Straße", "Strast"]),
+            ["Strasse", "Straße", "Strast"]
+        );
+    }
+
+    #[test]
+    fn places_language_specific_letters_in_their_alphabet() {
+        assert_eq!(
+            sorted(
+                "sv-SE",
+                &["Öberg", "Zetterlund", "Åberg", "Anders", "Ärlig"]
+            ),
+            ["Anders", "Zetterlund", "Åberg", "Ärlig", "Öberg"]
+        );
+        assert_eq!(
+            sorted("es", &["oca", "ñandú", "nylon"]),
+            ["nylon", "ñandú", "oca"]
+        );
+        assert_eq!(
+            sorted("en", &["oca", "ñandú", "nylon"]),
+            ["ñandú", "nylon", "oca"]
+        );
+        assert_eq!(
+            sorted("tr", &["ikinci", "Isparta", "hız"]),
+            ["hız", "Isparta", "ikinci"]
+        );
+    }
+}
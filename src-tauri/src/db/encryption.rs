@@ -0,0 +1,94 @@
+//! Optional encryption of the database file at rest.
+//!
+//! Builds with the `sqlcipher` feature link SQLCipher instead of plain SQLite. An encrypted
+//! database is opened with a raw 256-bit key that lives in the OS keychain under
+//! [`DATABASE_KEY_SECRET`] and never in the settings file. A plaintext database is encrypted
+//! by exporting it into a new file with `sqlcipher_export` and swapping the two files; see
+//! [`crate::db::DbManager::encrypt_database`].
+
+use std::path::{Path, PathBuf};
+
+use sha2::{Digest, Sha256};
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+use crate::db::error::DbResult;
+
+/// Name of the database key in the secrets vault.
+pub const DATABASE_KEY_SECRET: &str = "database-key";
+
+/// A new random key: 64 hex digits, hashed from four random UUIDs so every bit is uniform.
+pub fn generate_key() -> String {
+    let mut hasher = Sha256::new();
+    for _ in 0..4 {
+        hasher.update(Uuid::new_v4().as_bytes());
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+/// Whether `key` has the shape of a key made by [`generate_key`]. Only such keys are put into
+/// SQL, which keeps them from breaking out of the quoted literal.
+pub fn is_valid_key(key: &str) -> bool {
+    key.len() == 64 && key.chars().all(|ch| ch.is_ascii_hexdigit())
+}
+
+/// Value of `PRAGMA key` for a raw key, so SQLCipher skips its key derivation.
+pub(crate) fn key_pragma(key: &str) -> String {
+    format!("\"x'{key}'\"")
+}
+
+/// Whether the linked SQLite library is SQLCipher.
+pub(crate) async fn cipher_available(pool: &SqlitePool) -> DbResult<bool> {
+    let version: Option<(String,)> = sqlx::query_as("PRAGMA cipher_version")
+        .fetch_optional(pool)
+        .await?;
+    Ok(version.is_some_and(|(version,)| !version.is_empty()))
+}
+
+/// Writes an encrypted copy of the database behind `pool` to `destination`.
+pub(crate) async fn export_encrypted(
+    pool: &SqlitePool,
+    destination: &Path,
+    key: &str,
+) -> DbResult<()> {
+    let mut conn = pool.acquire().await?;
+    sqlx::query("PRAGMA wal_checkpoint(TRUNCATE)")
+        .execute(&mut *conn)
+        .await?;
+    sqlx::query("ATTACH DATABASE ?1 AS encrypted KEY ?2")
+        .bind(destination.to_string_lossy().into_owned())
+        .bind(format!("x'{key}'"))
+        .execute(&mut *conn)
+        .await?;
+    let exported = sqlx::query("SELECT sqlcipher_export('encrypted')")
+        .execute(&mut *conn)
+        .await;
+    sqlx::query("DETACH DATABASE encrypted")
+        .execute(&mut *conn)
+        .await?;
+    exported?;
+    Ok(())
+}
+
+/// The write-ahead log and shared-memory files SQLite keeps next to `db_path`.
+pub(crate) fn sidecar_files(db_path: &Path) -> [PathBuf; 2] {
+    let name = db_path.as_os_str().to_string_lossy();
+    [
+        PathBuf::from(format!("{name}-wal")),
+        PathBuf::from(format!("{name}-shm")),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generated_keys_are_raw_hex_keys() {
+        let key = generate_key();
+        assert!(is_valid_key(&key));
+        assert_ne!(key, generate_key());
+        assert_eq!(key_pragma("ab12"), "\"x'ab12'\"");
+        assert!(!is_valid_key("x'; DROP TABLE projects; --"));
+    }
+}
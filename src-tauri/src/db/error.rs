@@ -54,6 +54,12 @@ pub enum DbError {
     InvalidSubdirectory(String),
     #[error("constraint violation: {0}")]
     ConstraintViolation(String),
+    #[error("this build cannot encrypt the database: SQLCipher is not available")]
+    EncryptionUnavailable,
+    #[error("client details are encrypted but their key is not available")]
+    PiiKeyMissing,
+    #[error("failed to decrypt client details: {0}")]
+    PiiDecryption(String),
 }
 
 impl From<sqlx::Error> for DbError {
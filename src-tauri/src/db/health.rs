@@ -0,0 +1,186 @@
+//! Project health: a 0–100 score and the risk flags behind it.
+//!
+//! Every open project starts at 100 and loses points for each risk: failed jobs, no activity
+//! for two weeks, untranslated segments with the due date three days away or past, and open
+//! critical QA findings. Delivered and archived projects are always healthy. The level is
+//! derived from the score, except that an overdue project is always critical.
+
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime};
+
+/// Days without activity after which an open project is flagged as stale.
+const STALE_AFTER_DAYS: i64 = 14;
+/// Hours before the due date from which untranslated segments put a project at risk.
+const DEADLINE_WINDOW_HOURS: i64 = 72;
+/// Statuses of projects whose work is finished.
+const CLOSED_STATUSES: [&str; 3] = ["delivered", "archived", "completed"];
+
+pub const FLAG_FAILED_JOBS: &str = "failed_jobs";
+pub const FLAG_STALE: &str = "stale";
+pub const FLAG_DEADLINE_AT_RISK: &str = "deadline_at_risk";
+pub const FLAG_OVERDUE: &str = "overdue";
+pub const FLAG_QA_CRITICALS: &str = "qa_criticals";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum HealthLevel {
+    Healthy,
+    AtRisk,
+    Critical,
+}
+
+impl HealthLevel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HealthLevel::Healthy => "healthy",
+            HealthLevel::AtRisk => "at_risk",
+            HealthLevel::Critical => "critical",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "healthy" => Some(Self::Healthy),
+            "at_risk" => Some(Self::AtRisk),
+            "critical" => Some(Self::Critical),
+            _ => None,
+        }
+    }
+}
+
+/// What the evaluation of a project looks at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HealthSignals {
+    pub project_status: String,
+    pub last_activity: Option<NaiveDateTime>,
+    pub due_date: Option<NaiveDateTime>,
+    pub failed_jobs: i64,
+    pub open_critical_findings: i64,
+    /// Segments of the converted documents.
+    pub total_segments: i64,
+    /// Distinct segments confirmed at least once.
+    pub confirmed_segments: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HealthAssessment {
+    pub score: i64,
+    pub level: HealthLevel,
+    pub flags: Vec<&'static str>,
+}
+
+pub fn evaluate(signals: &HealthSignals, now: NaiveDateTime) -> HealthAssessment {
+    let status = signals.project_status.trim().to_ascii_lowercase();
+    if CLOSED_STATUSES.contains(&status.as_str()) {
+        return HealthAssessment {
+            score: 100,
+            level: HealthLevel::Healthy,
+            flags: Vec::new(),
+        };
+    }
+
+    let mut score = 100;
+    let mut flags = Vec::new();
+    if signals.failed_jobs > 0 {
+        score -= (20 + 5 * (signals.failed_jobs - 1)).min(35);
+        flags.push(FLAG_FAILED_JOBS);
+    }
+    if signals
+        .last_activity
+        .is_some_and(|last| now - last > Duration::days(STALE_AFTER_DAYS))
+    {
+        score -= 15;
+        flags.push(FLAG_STALE);
+    }
+    let untranslated =
+        signals.total_segments == 0 || signals.confirmed_segments < signals.total_segments;
+    let mut overdue = false;
+    if let Some(due) = signals.due_date
+        && untranslated
+    {
+        if due <= now {
+            score -= 40;
+            flags.push(FLAG_OVERDUE);
+            overdue = true;
+        } else if due - now <= Duration::hours(DEADLINE_WINDOW_HOURS) {
+            score -= 25;
+            flags.push(FLAG_DEADLINE_AT_RISK);
+        }
+    }
+    if signals.open_critical_findings > 0 {
+        score -= (25 + 5 * (signals.open_critical_findings - 1)).min(40);
+        flags.push(FLAG_QA_CRITICALS);
+    }
+
+    let score = score.max(0);
+    let level = if overdue || score < 50 {
+        HealthLevel::Critical
+    } else if score < 80 {
+        HealthLevel::AtRisk
+    } else {
+        HealthLevel::Healthy
+    };
+    HealthAssessment {
+        score,
+        level,
+        flags,
+    }
+}
+
+/// Parses a stored timestamp: RFC 3339, SQLite's `YYYY-MM-DD HH:MM:SS`, or a bare date,
+/// which stands for the end of that day.
+pub fn parse_timestamp(value: &str) -> Option<NaiveDateTime> {
+    let value = value.trim();
+    if let Ok(parsed) = DateTime::parse_from_rfc3339(value) {
+        return Some(parsed.naive_utc());
+    }
+    if let Ok(parsed) = NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S") {
+        return Some(parsed);
+    }
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(23, 59, 59))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(value: &str) -> NaiveDateTime {
+        parse_timestamp(value).expect("valid timestamp")
+    }
+
+    #[test]
+    fn risks_lower_the_score_and_overdue_projects_are_critical() {
+        let now = at("2026-05-10 12:00:00");
+        let mut signals = HealthSignals {
+            project_status: "active".into(),
+            last_activity: Some(at("2026-05-09 08:00:00")),
+            due_date: Some(at("2026-05-12")),
+            failed_jobs: 0,
+            open_critical_findings: 0,
+            total_segments: 40,
+            confirmed_segments: 40,
+        };
+        assert_eq!(evaluate(&signals, now).level, HealthLevel::Healthy);
+
+        signals.confirmed_segments = 10;
+        let near_deadline = evaluate(&signals, now);
+        assert_eq!(near_deadline.flags, vec![FLAG_DEADLINE_AT_RISK]);
+        assert_eq!(near_deadline.level, HealthLevel::AtRisk);
+
+        signals.failed_jobs = 2;
+        signals.last_activity = Some(at("2026-04-01T09:00:00Z"));
+        let failing = evaluate(&signals, now);
+        assert_eq!(failing.score, 35);
+        assert_eq!(failing.level, HealthLevel::Critical);
+
+        signals.failed_jobs = 0;
+        signals.last_activity = Some(at("2026-05-09 08:00:00"));
+        signals.due_date = Some(at("2026-05-09"));
+        let overdue = evaluate(&signals, now);
+        assert_eq!(overdue.flags, vec![FLAG_OVERDUE]);
+        assert_eq!(overdue.level, HealthLevel::Critical);
+
+        signals.project_status = "Delivered".into();
+        assert_eq!(evaluate(&signals, now).score, 100);
+    }
+}
@@ -1,8 +1,10 @@
 //! Core database manager responsible for owning the SQLite pool.
+use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex as StdMutex};
 
+use serde::Serialize;
 use sqlx::{
     SqlitePool,
     sqlite::{SqliteConnectOptions, SqlitePoolOptions},
@@ -10,16 +12,53 @@ use sqlx::{
 use tokio::sync::{Mutex, RwLock};
 use uuid::Uuid;
 
+use super::collation::Collator;
 use super::config::DatabasePerformanceConfig;
 use super::constants::SQLITE_DB_FILE;
-use super::error::DbResult;
-use super::operations::{artifacts_v2, clients, jobs_v2, projects_v2, users};
-use super::schema::initialise_schema;
+use super::encryption;
+use super::error::{DbError, DbResult};
+use super::numbering::ProjectNumberTemplate;
+use super::operations::{
+    alignments, artifacts_v2, automation_scripts, clients, delivery_gate_overrides,
+    delivery_packages, delivery_transports, exchange_rates, jobs_v2, language_pair_activity,
+    offline_operations, outbound_requests, outbox, output_naming, pipeline_presets, plugins,
+    project_health, projects_v2, prompt_templates, protected_terms, protection_rules,
+    provider_cache, provider_comparisons, provider_rate_limits, provider_routing_rules,
+    provider_usage, qa_findings, qa_profiles, saved_segment_filters, scheduled_tasks,
+    segment_confirmations, segment_references, segment_revisions, segmentation_exceptions,
+    subject_taxonomy, terminology, tm_assignments, translation_memory, users,
+};
+use super::pii::FieldCipher;
+use super::schema::{initialise_schema, schema_version};
 use super::types::{
-    ArtifactRecord, ClientRecord, JobRecord, NewArtifactArgs, NewClientArgs, NewFileInfoArgs,
-    NewJobArgs, NewProjectArgs, NewProjectFileArgs, NewUserArgs, ProjectBundle, ProjectFileBundle,
-    ProjectListRecord, ProjectRecord, ProjectStatistics, UpdateArtifactStatusArgs,
-    UpdateClientArgs, UpdateJobStatusArgs, UpdateProjectArgs, UpdateUserArgs, UserProfile,
+    AlignmentPairRecord, AlignmentRecord, ArtifactRecord, AutomationScriptRecord, ClientRecord,
+    ConversionOutputNameRecord, DailyProductivityRecord, DeliveryGateOverrideRecord,
+    DeliveryPackageRecord, DeliveryReceiptRecord, DeliveryTargetRecord, ExchangeRateRecord,
+    JobDependencyRecord, JobProgressArgs, JobRecord, LanguagePairActivityRecord, NewAlignmentArgs,
+    NewArtifactArgs, NewAutomationScriptArgs, NewClientArgs, NewDeliveryGateOverrideArgs,
+    NewDeliveryPackageArgs, NewDeliveryReceiptArgs, NewDeliveryTargetArgs, NewExchangeRateArgs,
+    NewFileInfoArgs, NewJobArgs, NewOfflineOperationArgs, NewOutboundRequestArgs,
+    NewPipelinePresetArgs, NewProjectArgs, NewProjectFileArgs, NewPromptTemplateArgs,
+    NewProtectedTermArgs, NewProtectionRuleArgs, NewProviderCacheEntryArgs,
+    NewProviderCandidateArgs, NewProviderRoutingRuleArgs, NewQaProfileArgs,
+    NewSavedSegmentFilterArgs, NewSegmentConfirmationArgs, NewSegmentReferenceArgs,
+    NewSegmentationExceptionArgs, NewTermCandidateArgs, NewTermbaseEntryArgs,
+    NewTranslationMemoryEntryArgs, NewUserArgs, OfflineOperationRecord, OutboundRequestRecord,
+    OutboxEventRecord, PipelinePresetRecord, PluginRecord, PluginSettingRecord,
+    PreferredProviderRecord, ProjectBundle, ProjectFileBundle, ProjectHealthRecord,
+    ProjectListRecord, ProjectOutputNamingRecord, ProjectRecord, ProjectStatistics,
+    ProjectTmAssignmentArgs, ProjectTmAssignmentRecord, PromptTemplateAssignmentRecord,
+    PromptTemplateRecord, ProtectedTermRecord, ProtectionRuleRecord, ProviderBudgetArgs,
+    ProviderBudgetRecord, ProviderCacheKey, ProviderCacheStatsRecord, ProviderCandidateRecord,
+    ProviderMonthlyUsageRecord, ProviderRateLimitRecord, ProviderRoutingRuleRecord,
+    ProviderUsageRecord, QaFindingRecord, QaProfileRecord, QaProfileTarget,
+    RecordProviderUsageArgs, RecordSegmentRevisionsArgs, ReplaceQaFindingsArgs, RoutingRuleOwner,
+    SavedSegmentFilterRecord, ScheduledTaskRunRecord, SegmentReferenceRecord,
+    SegmentRevisionRecord, SegmentationExceptionRecord, SubjectSuggestionRecord,
+    SubjectTaxonomyRecord, TermCandidateDecisionArgs, TermCandidateRecord, TermbaseEntryRecord,
+    TranslationMemoryEntryRecord, UpdateAlignmentPairArgs, UpdateArtifactStatusArgs,
+    UpdateClientArgs, UpdateJobStatusArgs, UpdateProjectArgs, UpdatePromptTemplateArgs,
+    UpdateProtectionRuleArgs, UpdateTranslationMemoryEntryArgs, UpdateUserArgs, UserProfile,
 };
 
 /// Central entry-point for all database interactions. Wraps the SQLite pool and synchronises writes.
@@ -28,6 +67,11 @@ pub struct DbManager {
     pub(crate) pool: Arc<RwLock<SqlitePool>>,
     pub(crate) write_lock: Arc<Mutex<()>>,
     performance: DatabasePerformanceConfig,
+    collator: Arc<StdMutex<Collator>>,
+    project_numbering: Arc<StdMutex<ProjectNumberTemplate>>,
+    /// SQLCipher key of the database, when it is encrypted.
+    encryption_key: Arc<StdMutex<Option<String>>>,
+    pii_cipher: Arc<StdMutex<FieldCipher>>,
 }
 
 impl DbManager {
@@ -41,13 +85,27 @@ impl DbManager {
     pub async fn new_with_base_dir_and_performance(
         base_dir: &Path,
         performance: DatabasePerformanceConfig,
+    ) -> DbResult<Self> {
+        Self::new_with_encryption_key(base_dir, performance, None).await
+    }
+
+    /// Creates a manager for a database encrypted with `encryption_key`, or a plaintext one
+    /// when it is `None`.
+    pub async fn new_with_encryption_key(
+        base_dir: &Path,
+        performance: DatabasePerformanceConfig,
+        encryption_key: Option<String>,
     ) -> DbResult<Self> {
         fs::create_dir_all(base_dir)?;
-        let pool = Self::connect_pool(base_dir, performance).await?;
+        let pool = Self::connect_pool(base_dir, performance, encryption_key.as_deref()).await?;
         Ok(Self {
             pool: Arc::new(RwLock::new(pool)),
             write_lock: Arc::new(Mutex::new(())),
             performance,
+            collator: Arc::default(),
+            project_numbering: Arc::default(),
+            encryption_key: Arc::new(StdMutex::new(encryption_key)),
+            pii_cipher: Arc::default(),
         })
     }
 
@@ -57,22 +115,93 @@ impl DbManager {
             pool: Arc::new(RwLock::new(pool)),
             write_lock: Arc::new(Mutex::new(())),
             performance: DatabasePerformanceConfig::default(),
+            collator: Arc::default(),
+            project_numbering: Arc::default(),
+            encryption_key: Arc::default(),
+            pii_cipher: Arc::default(),
         }
     }
 
+    /// Orders name listings for `language`, normally the UI language.
+    pub fn set_collation_language(&self, language: &str) {
+        *self
+            .collator
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = Collator::for_language(language);
+    }
+
+    /// Collator of the current UI language.
+    pub(crate) fn collator(&self) -> Collator {
+        *self
+            .collator
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Seals and opens client personal data with `cipher`.
+    pub fn set_pii_cipher(&self, cipher: FieldCipher) {
+        *self
+            .pii_cipher
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = cipher;
+    }
+
+    /// Cipher of client personal data.
+    pub(crate) fn pii_cipher(&self) -> FieldCipher {
+        *self
+            .pii_cipher
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Numbers projects created from now on with `template`.
+    pub fn set_project_number_template(&self, template: ProjectNumberTemplate) {
+        *self
+            .project_numbering
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = template;
+    }
+
+    fn project_number_template(&self) -> ProjectNumberTemplate {
+        self.project_numbering
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+
     /// Returns a cloned handle to the current pool.
     pub(crate) async fn pool(&self) -> SqlitePool {
         self.pool.read().await.clone()
     }
 
+    /// Whether the database is encrypted.
+    pub fn is_encrypted(&self) -> bool {
+        self.encryption_key
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .is_some()
+    }
+
+    /// SQLCipher key of the open database, when it is encrypted.
+    pub(crate) fn encryption_key(&self) -> Option<String> {
+        self.encryption_key
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+
     async fn connect_pool(
         base_dir: &Path,
         performance: DatabasePerformanceConfig,
+        encryption_key: Option<&str>,
     ) -> Result<SqlitePool, sqlx::Error> {
         let db_path = base_dir.join(SQLITE_DB_FILE);
         let mut connect_options = SqliteConnectOptions::new()
             .filename(&db_path)
             .create_if_missing(true);
+        if let Some(key) = encryption_key {
+            connect_options = connect_options.pragma("key", encryption::key_pragma(key));
+        }
         connect_options = connect_options.foreign_keys(true);
 
         let journal_mode_stmt = Arc::new(format!(
@@ -125,17 +254,90 @@ impl DbManager {
 
     /// Reopens the database using the provided base directory, swapping the pool atomically.
     pub async fn reopen_with_base_dir(&self, base_dir: &Path) -> DbResult<()> {
+        self.reopen_with_key(base_dir, self.encryption_key()).await
+    }
+
+    /// Reopens the database in `base_dir`, which is encrypted with `encryption_key` when one
+    /// is given, such as the database of another workspace.
+    pub async fn reopen_with_key(
+        &self,
+        base_dir: &Path,
+        encryption_key: Option<String>,
+    ) -> DbResult<()> {
         fs::create_dir_all(base_dir)?;
         let performance = self.performance;
-        let new_pool = Self::connect_pool(base_dir, performance).await?;
+        let new_pool = Self::connect_pool(base_dir, performance, encryption_key.as_deref()).await?;
         let _guard = self.write_lock.lock().await;
         let mut writer = self.pool.write().await;
         let old_pool = std::mem::replace(&mut *writer, new_pool);
+        *self
+            .encryption_key
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = encryption_key;
         drop(writer);
         old_pool.close().await;
         Ok(())
     }
 
+    /// Encrypts the plaintext database in `base_dir` with `key`. The data is exported into an
+    /// encrypted copy, which replaces the original once it opens with the key; the plaintext
+    /// file is then deleted. On failure the plaintext database stays in use.
+    pub async fn encrypt_database(&self, base_dir: &Path, key: &str) -> DbResult<()> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        if !encryption::cipher_available(&pool).await? {
+            return Err(DbError::EncryptionUnavailable);
+        }
+        let db_path = base_dir.join(SQLITE_DB_FILE);
+        let encrypted_path = db_path.with_extension("db.encrypting");
+        let plaintext_path = db_path.with_extension("db.plaintext");
+        if encrypted_path.exists() {
+            fs::remove_file(&encrypted_path)?;
+        }
+        encryption::export_encrypted(&pool, &encrypted_path, key).await?;
+
+        let mut writer = self.pool.write().await;
+        writer.close().await;
+        fs::rename(&db_path, &plaintext_path)?;
+        for sidecar in encryption::sidecar_files(&db_path) {
+            if sidecar.exists() {
+                fs::remove_file(sidecar)?;
+            }
+        }
+        fs::rename(&encrypted_path, &db_path)?;
+
+        match Self::connect_pool(base_dir, self.performance, Some(key)).await {
+            Ok(encrypted_pool) => {
+                *writer = encrypted_pool;
+                *self
+                    .encryption_key
+                    .lock()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(key.to_string());
+                drop(writer);
+                if let Err(error) = fs::remove_file(&plaintext_path) {
+                    log::warn!(
+                        target: "db::encryption",
+                        "unable to delete the plaintext database {}: {error}",
+                        plaintext_path.display()
+                    );
+                }
+                Ok(())
+            }
+            Err(error) => {
+                log::error!(target: "db::encryption", "encrypted database failed to open: {error}");
+                fs::remove_file(&db_path)?;
+                for sidecar in encryption::sidecar_files(&db_path) {
+                    if sidecar.exists() {
+                        fs::remove_file(sidecar)?;
+                    }
+                }
+                fs::rename(&plaintext_path, &db_path)?;
+                *writer = Self::connect_pool(base_dir, self.performance, None).await?;
+                Err(error.into())
+            }
+        }
+    }
+
     /// Creates a new user profile.
     pub async fn create_user_profile(&self, args: NewUserArgs) -> DbResult<UserProfile> {
         let _guard = self.write_lock.lock().await;
@@ -163,17 +365,24 @@ impl DbManager {
         users::get_user(&pool, user_uuid).await
     }
 
+    /// Creates the users of an import together.
+    pub async fn import_user_profiles(&self, users: &[NewUserArgs]) -> DbResult<()> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        users::create_users(&pool, users).await
+    }
+
     /// Lists all user profiles.
     pub async fn list_user_profiles(&self) -> DbResult<Vec<UserProfile>> {
         let pool = self.pool().await;
-        users::list_users(&pool).await
+        users::list_users(&pool, &self.collator()).await
     }
 
     /// Creates a client record.
     pub async fn create_client_record(&self, args: NewClientArgs) -> DbResult<ClientRecord> {
         let _guard = self.write_lock.lock().await;
         let pool = self.pool().await;
-        clients::create_client(&pool, args).await
+        clients::create_client(&pool, &self.pii_cipher(), args).await
     }
 
     /// Updates a client record.
@@ -183,7 +392,7 @@ impl DbManager {
     ) -> DbResult<Option<ClientRecord>> {
         let _guard = self.write_lock.lock().await;
         let pool = self.pool().await;
-        clients::update_client(&pool, args).await
+        clients::update_client(&pool, &self.pii_cipher(), args).await
     }
 
     /// Deletes a client record.
@@ -196,20 +405,37 @@ impl DbManager {
     /// Retrieves a client record.
     pub async fn get_client_record(&self, client_uuid: Uuid) -> DbResult<Option<ClientRecord>> {
         let pool = self.pool().await;
-        clients::get_client(&pool, client_uuid).await
+        clients::get_client(&pool, &self.pii_cipher(), client_uuid).await
+    }
+
+    /// Creates the clients of an import together.
+    pub async fn import_client_records(&self, clients: &[NewClientArgs]) -> DbResult<()> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        clients::create_clients(&pool, &self.pii_cipher(), clients).await
+    }
+
+    /// Lists clients ordered by name, optionally only those matching `query`.
+    pub async fn list_client_records(&self, query: Option<&str>) -> DbResult<Vec<ClientRecord>> {
+        let pool = self.pool().await;
+        clients::list_clients(&pool, &self.pii_cipher(), &self.collator(), query).await
     }
 
-    /// Lists clients ordered by name.
-    pub async fn list_client_records(&self) -> DbResult<Vec<ClientRecord>> {
+    /// Re-seals the personal data of every client with `cipher` and uses it from then on.
+    /// Returns how many clients were rewritten.
+    pub async fn rotate_client_pii_key(&self, cipher: FieldCipher) -> DbResult<u64> {
+        let _guard = self.write_lock.lock().await;
         let pool = self.pool().await;
-        clients::list_clients(&pool).await
+        let rewritten = clients::rotate_pii_key(&pool, &self.pii_cipher(), &cipher).await?;
+        self.set_pii_cipher(cipher);
+        Ok(rewritten)
     }
 
     /// Creates a new project bundle with subjects and language pairs.
     pub async fn create_project_bundle(&self, args: NewProjectArgs) -> DbResult<ProjectBundle> {
         let _guard = self.write_lock.lock().await;
         let pool = self.pool().await;
-        projects_v2::create_project(&pool, args).await
+        projects_v2::create_project(&pool, args, &self.project_number_template()).await
     }
 
     /// Updates an existing project bundle.
@@ -222,6 +448,18 @@ impl DbManager {
         projects_v2::update_project(&pool, args).await
     }
 
+    /// Reverses a project language pair and the file language pairs using it.
+    pub async fn swap_project_language_pair(
+        &self,
+        project_uuid: Uuid,
+        source_lang: &str,
+        target_lang: &str,
+    ) -> DbResult<Option<ProjectBundle>> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        projects_v2::swap_project_language_pair(&pool, project_uuid, source_lang, target_lang).await
+    }
+
     /// Deletes a project.
     pub async fn delete_project_bundle(&self, project_uuid: Uuid) -> DbResult<()> {
         let _guard = self.write_lock.lock().await;
@@ -232,7 +470,12 @@ impl DbManager {
     /// Retrieves a project bundle by identifier.
     pub async fn get_project_bundle(&self, project_uuid: Uuid) -> DbResult<Option<ProjectBundle>> {
         let pool = self.pool().await;
-        projects_v2::get_project(&pool, project_uuid).await
+        let mut bundle = projects_v2::get_project(&pool, project_uuid).await?;
+        if let Some(bundle) = bundle.as_mut() {
+            self.collator()
+                .sort_by_text(&mut bundle.files, |file| &file.link.filename);
+        }
+        Ok(bundle)
     }
 
     /// Retrieves aggregate statistics for a project.
@@ -244,10 +487,26 @@ impl DbManager {
         projects_v2::get_project_statistics(&pool, project_uuid).await
     }
 
-    /// Lists project records.
-    pub async fn list_project_records(&self) -> DbResult<Vec<ProjectListRecord>> {
+    /// Whether the project forbids sending its text to external MT providers.
+    pub async fn project_forbids_external_mt(&self, project_uuid: Uuid) -> DbResult<bool> {
+        let pool = self.pool().await;
+        projects_v2::external_mt_forbidden(&pool, project_uuid).await
+    }
+
+    /// Lists project records, optionally only those matching `query`.
+    pub async fn list_project_records(
+        &self,
+        query: Option<&str>,
+    ) -> DbResult<Vec<ProjectListRecord>> {
+        let pool = self.pool().await;
+        projects_v2::list_projects(&pool, &self.collator(), query).await
+    }
+
+    /// Re-evaluates the health of every project, recording an event for each that got worse.
+    pub async fn refresh_project_health(&self) -> DbResult<Vec<ProjectHealthRecord>> {
+        let _guard = self.write_lock.lock().await;
         let pool = self.pool().await;
-        projects_v2::list_projects(&pool).await
+        project_health::refresh_health(&pool).await
     }
 
     /// Attaches file metadata and link to a project.
@@ -331,6 +590,13 @@ impl DbManager {
         jobs_v2::update_job_status(&pool, args).await
     }
 
+    /// Records the progress and rolling throughput of a job.
+    pub async fn record_job_progress(&self, args: JobProgressArgs) -> DbResult<Option<JobRecord>> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        jobs_v2::record_job_progress(&pool, args).await
+    }
+
     /// Deletes a job entry.
     pub async fn delete_job_record(&self, artifact_uuid: Uuid, job_type: &str) -> DbResult<()> {
         let _guard = self.write_lock.lock().await;
@@ -343,4 +609,1316 @@ impl DbManager {
         let pool = self.pool().await;
         jobs_v2::list_jobs_for_project(&pool, project_uuid).await
     }
+
+    /// Replaces the jobs a job waits for.
+    pub async fn replace_job_dependencies(
+        &self,
+        artifact_uuid: Uuid,
+        job_type: &str,
+        depends_on: &[(Uuid, String)],
+    ) -> DbResult<()> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        jobs_v2::replace_job_dependencies(&pool, artifact_uuid, job_type, depends_on).await
+    }
+
+    /// Lists the prerequisites of the jobs of a project.
+    pub async fn list_job_dependencies_for_project(
+        &self,
+        project_uuid: Uuid,
+    ) -> DbResult<Vec<JobDependencyRecord>> {
+        let pool = self.pool().await;
+        jobs_v2::list_job_dependencies_for_project(&pool, project_uuid).await
+    }
+
+    /// Adds an event to the outbox that no database change carries, such as a staged update.
+    pub async fn publish_outbox_event(
+        &self,
+        topic: &str,
+        payload: &impl Serialize,
+    ) -> DbResult<()> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        outbox::publish_event(&pool, topic, payload).await
+    }
+
+    /// Lists undelivered outbox events that are due for a publish attempt.
+    pub async fn list_due_outbox_events(&self, limit: i64) -> DbResult<Vec<OutboxEventRecord>> {
+        let pool = self.pool().await;
+        outbox::list_due_events(&pool, limit).await
+    }
+
+    /// Lists outbox events after `after_event_id`, delivered or not.
+    pub async fn list_outbox_events_after(
+        &self,
+        after_event_id: i64,
+        limit: i64,
+    ) -> DbResult<Vec<OutboxEventRecord>> {
+        let pool = self.pool().await;
+        outbox::list_events_after(&pool, after_event_id, limit).await
+    }
+
+    /// Marks an outbox event as published.
+    pub async fn mark_outbox_event_delivered(&self, event_id: i64) -> DbResult<()> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        outbox::mark_event_delivered(&pool, event_id).await
+    }
+
+    /// Records a failed publish of an outbox event and delays its next attempt.
+    pub async fn record_outbox_event_failure(
+        &self,
+        event_id: i64,
+        error: &str,
+        retry_in_secs: i64,
+    ) -> DbResult<()> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        outbox::record_event_failure(&pool, event_id, error, retry_in_secs).await
+    }
+
+    /// Deletes outbox events delivered more than `older_than_days` ago.
+    pub async fn purge_delivered_outbox_events(&self, older_than_days: i64) -> DbResult<u64> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        outbox::purge_delivered_events(&pool, older_than_days).await
+    }
+
+    /// Lists failed, paused or erroring jobs across projects, most recent first.
+    pub async fn list_failing_jobs(&self, limit: i64) -> DbResult<Vec<JobRecord>> {
+        let pool = self.pool().await;
+        jobs_v2::list_failing_jobs(&pool, limit).await
+    }
+
+    /// Counts the jobs currently running, across projects.
+    pub async fn count_running_jobs(&self) -> DbResult<i64> {
+        let pool = self.pool().await;
+        jobs_v2::count_running_jobs(&pool).await
+    }
+
+    /// Version of the latest applied migration.
+    pub async fn schema_version(&self) -> DbResult<Option<i64>> {
+        let pool = self.pool().await;
+        Ok(schema_version(&pool).await?)
+    }
+
+    /// Replaces the stored findings of one QA check for a JLIFF document.
+    pub async fn replace_qa_findings(
+        &self,
+        args: ReplaceQaFindingsArgs,
+    ) -> DbResult<Vec<QaFindingRecord>> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        qa_findings::replace_findings(&pool, args).await
+    }
+
+    /// Lists QA findings for a project, optionally scoped to one JLIFF document.
+    pub async fn list_qa_findings(
+        &self,
+        project_uuid: Uuid,
+        jliff_rel_path: Option<&str>,
+    ) -> DbResult<Vec<QaFindingRecord>> {
+        let pool = self.pool().await;
+        qa_findings::list_findings(&pool, project_uuid, jliff_rel_path).await
+    }
+
+    /// Resolves or reopens a QA finding.
+    pub async fn set_qa_finding_resolved(
+        &self,
+        finding_uuid: Uuid,
+        resolved: bool,
+    ) -> DbResult<Option<QaFindingRecord>> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        qa_findings::set_finding_resolved(&pool, finding_uuid, resolved).await
+    }
+
+    /// Records target changes applied to a JLIFF document.
+    pub async fn record_segment_revisions(
+        &self,
+        args: RecordSegmentRevisionsArgs,
+    ) -> DbResult<Vec<SegmentRevisionRecord>> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        segment_revisions::record_revisions(&pool, args).await
+    }
+
+    /// Lists the revisions of a JLIFF document, optionally for a single transunit.
+    pub async fn list_segment_revisions(
+        &self,
+        project_uuid: Uuid,
+        jliff_rel_path: &str,
+        transunit_id: Option<&str>,
+    ) -> DbResult<Vec<SegmentRevisionRecord>> {
+        let pool = self.pool().await;
+        segment_revisions::list_revisions(&pool, project_uuid, jliff_rel_path, transunit_id).await
+    }
+
+    /// Lists the transunits of a JLIFF document revised at or after `since`.
+    pub async fn list_revised_transunits_since(
+        &self,
+        project_uuid: Uuid,
+        jliff_rel_path: &str,
+        since: &str,
+    ) -> DbResult<Vec<String>> {
+        let pool = self.pool().await;
+        segment_revisions::list_revised_transunits_since(&pool, project_uuid, jliff_rel_path, since)
+            .await
+    }
+
+    /// Records that a segment was confirmed, for productivity reporting.
+    pub async fn record_segment_confirmation(
+        &self,
+        args: NewSegmentConfirmationArgs,
+    ) -> DbResult<()> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        segment_confirmations::record_confirmation(&pool, args).await
+    }
+
+    /// Aggregates a user's confirmations per day between two SQLite datetimes.
+    pub async fn daily_productivity(
+        &self,
+        user_uuid: Uuid,
+        from: &str,
+        until: &str,
+    ) -> DbResult<Vec<DailyProductivityRecord>> {
+        let pool = self.pool().await;
+        segment_confirmations::daily_productivity(&pool, user_uuid, from, until).await
+    }
+
+    /// Aggregates projects, deliveries and TM entries per language pair.
+    pub async fn list_language_pair_activity(&self) -> DbResult<Vec<LanguagePairActivityRecord>> {
+        let pool = self.pool().await;
+        language_pair_activity::list_activity(&pool).await
+    }
+
+    /// Links a reference asset to a transunit.
+    pub async fn create_segment_reference(
+        &self,
+        args: NewSegmentReferenceArgs,
+    ) -> DbResult<SegmentReferenceRecord> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        segment_references::insert_reference(&pool, args).await
+    }
+
+    /// Fetches a segment reference.
+    pub async fn get_segment_reference(
+        &self,
+        reference_uuid: Uuid,
+    ) -> DbResult<Option<SegmentReferenceRecord>> {
+        let pool = self.pool().await;
+        segment_references::get_reference(&pool, reference_uuid).await
+    }
+
+    /// Lists the references of a JLIFF document, optionally for a single transunit.
+    pub async fn list_segment_references(
+        &self,
+        project_uuid: Uuid,
+        jliff_rel_path: &str,
+        transunit_id: Option<&str>,
+    ) -> DbResult<Vec<SegmentReferenceRecord>> {
+        let pool = self.pool().await;
+        segment_references::list_references(&pool, project_uuid, jliff_rel_path, transunit_id).await
+    }
+
+    /// Replaces the anchor and note of a segment reference.
+    pub async fn update_segment_reference(
+        &self,
+        reference_uuid: Uuid,
+        anchor: Option<&str>,
+        note: Option<&str>,
+    ) -> DbResult<Option<SegmentReferenceRecord>> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        segment_references::update_reference(&pool, reference_uuid, anchor, note).await
+    }
+
+    /// Removes a segment reference; the asset itself is kept.
+    pub async fn delete_segment_reference(&self, reference_uuid: Uuid) -> DbResult<bool> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        segment_references::delete_reference(&pool, reference_uuid).await
+    }
+
+    /// Counts the references of each transunit of a JLIFF document.
+    pub async fn count_segment_references(
+        &self,
+        project_uuid: Uuid,
+        jliff_rel_path: &str,
+    ) -> DbResult<HashMap<String, i64>> {
+        let pool = self.pool().await;
+        segment_references::count_references(&pool, project_uuid, jliff_rel_path).await
+    }
+
+    /// Saves a named segment filter for a user, replacing one with the same name.
+    pub async fn save_segment_filter(
+        &self,
+        args: NewSavedSegmentFilterArgs,
+    ) -> DbResult<SavedSegmentFilterRecord> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        saved_segment_filters::upsert_saved_filter(&pool, args).await
+    }
+
+    /// Lists the saved segment filters of a user.
+    pub async fn list_saved_segment_filters(
+        &self,
+        user_uuid: Uuid,
+    ) -> DbResult<Vec<SavedSegmentFilterRecord>> {
+        let pool = self.pool().await;
+        saved_segment_filters::list_saved_filters(&pool, user_uuid, &self.collator()).await
+    }
+
+    /// Deletes a saved segment filter.
+    pub async fn delete_saved_segment_filter(&self, filter_uuid: Uuid) -> DbResult<()> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        saved_segment_filters::delete_saved_filter(&pool, filter_uuid).await
+    }
+
+    /// Adds entries to the translation memory, refreshing pairs that already exist.
+    pub async fn upsert_translation_memory_entries(
+        &self,
+        entries: &[NewTranslationMemoryEntryArgs],
+    ) -> DbResult<Vec<TranslationMemoryEntryRecord>> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        translation_memory::upsert_entries(&pool, entries).await
+    }
+
+    /// Lists the translation memory entries of a language pair.
+    pub async fn list_translation_memory_entries(
+        &self,
+        source_lang: &str,
+        target_lang: &str,
+    ) -> DbResult<Vec<TranslationMemoryEntryRecord>> {
+        let pool = self.pool().await;
+        translation_memory::list_entries_for_pair(&pool, source_lang, target_lang).await
+    }
+
+    /// Lists the whole translation memory.
+    pub async fn list_all_translation_memory_entries(
+        &self,
+    ) -> DbResult<Vec<TranslationMemoryEntryRecord>> {
+        let pool = self.pool().await;
+        translation_memory::list_all_entries(&pool).await
+    }
+
+    /// Rewrites translation memory entries, merging those that become duplicates.
+    pub async fn update_translation_memory_entries(
+        &self,
+        edits: &[UpdateTranslationMemoryEntryArgs],
+    ) -> DbResult<u64> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        translation_memory::update_entries(&pool, edits).await
+    }
+
+    /// Deletes translation memory entries.
+    pub async fn delete_translation_memory_entries(&self, entry_uuids: &[Uuid]) -> DbResult<u64> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        translation_memory::delete_entries(&pool, entry_uuids).await
+    }
+
+    /// Stores a document alignment awaiting review.
+    pub async fn create_alignment(&self, args: &NewAlignmentArgs) -> DbResult<AlignmentRecord> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        alignments::create_alignment(&pool, args).await
+    }
+
+    pub async fn get_alignment(&self, alignment_uuid: Uuid) -> DbResult<Option<AlignmentRecord>> {
+        let pool = self.pool().await;
+        alignments::get_alignment(&pool, alignment_uuid).await
+    }
+
+    /// Lists document alignments, most recent first.
+    pub async fn list_alignments(&self) -> DbResult<Vec<AlignmentRecord>> {
+        let pool = self.pool().await;
+        alignments::list_alignments(&pool).await
+    }
+
+    /// Lists the pairs of a document alignment in document order.
+    pub async fn list_alignment_pairs(
+        &self,
+        alignment_uuid: Uuid,
+    ) -> DbResult<Vec<AlignmentPairRecord>> {
+        let pool = self.pool().await;
+        alignments::list_alignment_pairs(&pool, alignment_uuid).await
+    }
+
+    /// Applies review changes to the pairs of a document alignment.
+    pub async fn update_alignment_pairs(
+        &self,
+        alignment_uuid: Uuid,
+        updates: &[UpdateAlignmentPairArgs],
+    ) -> DbResult<u64> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        alignments::update_alignment_pairs(&pool, alignment_uuid, updates).await
+    }
+
+    /// Stamps a document alignment as committed to the translation memory.
+    pub async fn mark_alignment_committed(
+        &self,
+        alignment_uuid: Uuid,
+    ) -> DbResult<Option<AlignmentRecord>> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        alignments::mark_alignment_committed(&pool, alignment_uuid).await
+    }
+
+    /// Deletes a document alignment and its pairs.
+    pub async fn delete_alignment(&self, alignment_uuid: Uuid) -> DbResult<bool> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        alignments::delete_alignment(&pool, alignment_uuid).await
+    }
+
+    /// Replaces the pending term candidates of a project language pair.
+    pub async fn replace_pending_term_candidates(
+        &self,
+        project_uuid: Uuid,
+        source_lang: &str,
+        target_lang: &str,
+        candidates: &[NewTermCandidateArgs],
+    ) -> DbResult<u64> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        terminology::replace_pending_candidates(
+            &pool,
+            project_uuid,
+            source_lang,
+            target_lang,
+            candidates,
+        )
+        .await
+    }
+
+    /// Lists the term candidates of a project, optionally with one status only.
+    pub async fn list_term_candidates(
+        &self,
+        project_uuid: Uuid,
+        status: Option<&str>,
+    ) -> DbResult<Vec<TermCandidateRecord>> {
+        let pool = self.pool().await;
+        terminology::list_candidates(&pool, project_uuid, status).await
+    }
+
+    /// Records review decisions on term candidates, adding accepted ones to a termbase.
+    pub async fn decide_term_candidates(
+        &self,
+        project_uuid: Uuid,
+        termbase: &str,
+        decisions: &[TermCandidateDecisionArgs],
+    ) -> DbResult<Vec<TermbaseEntryRecord>> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        terminology::decide_candidates(&pool, project_uuid, termbase, decisions).await
+    }
+
+    /// Lists the termbase entries of a language pair.
+    pub async fn list_termbase_entries(
+        &self,
+        source_lang: &str,
+        target_lang: &str,
+    ) -> DbResult<Vec<TermbaseEntryRecord>> {
+        let pool = self.pool().await;
+        terminology::list_termbase_entries(&pool, source_lang, target_lang).await
+    }
+
+    /// Lists the entries of every termbase.
+    pub async fn list_all_termbase_entries(&self) -> DbResult<Vec<TermbaseEntryRecord>> {
+        let pool = self.pool().await;
+        terminology::list_all_termbase_entries(&pool).await
+    }
+
+    /// Adds terms to their termbases, skipping those already there.
+    pub async fn add_termbase_entries(
+        &self,
+        entries: &[NewTermbaseEntryArgs],
+    ) -> DbResult<Vec<TermbaseEntryRecord>> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        terminology::add_termbase_entries(&pool, entries).await
+    }
+
+    /// Fetches a project's output naming overrides.
+    pub async fn get_project_output_naming(
+        &self,
+        project_uuid: Uuid,
+    ) -> DbResult<Option<ProjectOutputNamingRecord>> {
+        let pool = self.pool().await;
+        output_naming::get_project_naming(&pool, project_uuid).await
+    }
+
+    /// Replaces a project's output naming overrides; clearing all of them removes them.
+    pub async fn set_project_output_naming(
+        &self,
+        project_uuid: Uuid,
+        xliff_template: Option<&str>,
+        jliff_template: Option<&str>,
+        translations_layout: Option<&str>,
+    ) -> DbResult<Option<ProjectOutputNamingRecord>> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        output_naming::set_project_naming(
+            &pool,
+            project_uuid,
+            xliff_template,
+            jliff_template,
+            translations_layout,
+        )
+        .await
+    }
+
+    /// Lists the output names recorded for a project's conversions.
+    pub async fn list_conversion_output_names(
+        &self,
+        project_uuid: Uuid,
+    ) -> DbResult<Vec<ConversionOutputNameRecord>> {
+        let pool = self.pool().await;
+        output_naming::list_output_names(&pool, project_uuid).await
+    }
+
+    /// Records the output names of a file and language pair.
+    pub async fn record_conversion_output_name(
+        &self,
+        args: NewConversionOutputNameArgs,
+    ) -> DbResult<ConversionOutputNameRecord> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        output_naming::upsert_output_name(&pool, &args).await
+    }
+
+    /// Adds or renames a managed subject and replaces its aliases.
+    pub async fn upsert_subject(
+        &self,
+        subject: &str,
+        aliases: &[String],
+    ) -> DbResult<SubjectTaxonomyRecord> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        subject_taxonomy::upsert_subject(&pool, subject, aliases).await
+    }
+
+    /// Removes a managed subject. Returns whether it existed.
+    pub async fn delete_subject(&self, subject: &str) -> DbResult<bool> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        subject_taxonomy::delete_subject(&pool, subject).await
+    }
+
+    /// Lists the managed subjects with their aliases.
+    pub async fn list_subject_taxonomy(&self) -> DbResult<Vec<SubjectTaxonomyRecord>> {
+        let pool = self.pool().await;
+        subject_taxonomy::list_subjects(&pool).await
+    }
+
+    /// Suggests subjects with a word starting with `prefix`.
+    pub async fn suggest_subjects(
+        &self,
+        prefix: &str,
+        limit: usize,
+    ) -> DbResult<Vec<SubjectSuggestionRecord>> {
+        let pool = self.pool().await;
+        subject_taxonomy::suggest_subjects(&pool, prefix, limit).await
+    }
+
+    /// Replaces the translation memories assigned to a project.
+    pub async fn replace_project_tm_assignments(
+        &self,
+        project_uuid: Uuid,
+        assignments: &[ProjectTmAssignmentArgs],
+    ) -> DbResult<Vec<ProjectTmAssignmentRecord>> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        tm_assignments::replace_assignments(&pool, project_uuid, assignments).await
+    }
+
+    /// Lists the translation memories assigned to a project, by priority.
+    pub async fn list_project_tm_assignments(
+        &self,
+        project_uuid: Uuid,
+    ) -> DbResult<Vec<ProjectTmAssignmentRecord>> {
+        let pool = self.pool().await;
+        tm_assignments::list_assignments(&pool, project_uuid).await
+    }
+
+    /// Replaces the protected ("do not translate") term list of a project.
+    pub async fn replace_protected_terms(
+        &self,
+        project_uuid: Uuid,
+        terms: &[NewProtectedTermArgs],
+    ) -> DbResult<Vec<ProtectedTermRecord>> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        protected_terms::replace_protected_terms(&pool, project_uuid, terms).await
+    }
+
+    /// Lists the protected terms configured for a project.
+    pub async fn list_protected_terms(
+        &self,
+        project_uuid: Uuid,
+    ) -> DbResult<Vec<ProtectedTermRecord>> {
+        let pool = self.pool().await;
+        protected_terms::list_protected_terms(&pool, project_uuid).await
+    }
+
+    /// Creates a regex protection rule for a project.
+    pub async fn create_protection_rule(
+        &self,
+        args: NewProtectionRuleArgs,
+    ) -> DbResult<ProtectionRuleRecord> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        protection_rules::create_protection_rule(&pool, args).await
+    }
+
+    /// Updates a regex protection rule.
+    pub async fn update_protection_rule(
+        &self,
+        args: UpdateProtectionRuleArgs,
+    ) -> DbResult<Option<ProtectionRuleRecord>> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        protection_rules::update_protection_rule(&pool, args).await
+    }
+
+    /// Deletes a regex protection rule.
+    pub async fn delete_protection_rule(&self, rule_uuid: Uuid) -> DbResult<()> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        protection_rules::delete_protection_rule(&pool, rule_uuid).await
+    }
+
+    /// Lists the regex protection rules of a project.
+    pub async fn list_protection_rules(
+        &self,
+        project_uuid: Uuid,
+    ) -> DbResult<Vec<ProtectionRuleRecord>> {
+        let pool = self.pool().await;
+        protection_rules::list_protection_rules(&pool, project_uuid).await
+    }
+
+    /// Creates a segmentation exception for a project, or a global one.
+    pub async fn create_segmentation_exception(
+        &self,
+        args: NewSegmentationExceptionArgs,
+    ) -> DbResult<SegmentationExceptionRecord> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        segmentation_exceptions::create_exception(&pool, args).await
+    }
+
+    /// Adds segmentation exceptions, skipping those that already exist.
+    pub async fn add_missing_segmentation_exceptions(
+        &self,
+        exceptions: &[NewSegmentationExceptionArgs],
+    ) -> DbResult<Vec<SegmentationExceptionRecord>> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        segmentation_exceptions::add_missing_exceptions(&pool, exceptions).await
+    }
+
+    /// Deletes a segmentation exception.
+    pub async fn delete_segmentation_exception(&self, exception_uuid: Uuid) -> DbResult<bool> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        segmentation_exceptions::delete_exception(&pool, exception_uuid).await
+    }
+
+    /// Lists the segmentation exceptions of a project, or the global ones.
+    pub async fn list_segmentation_exceptions(
+        &self,
+        project_uuid: Option<Uuid>,
+    ) -> DbResult<Vec<SegmentationExceptionRecord>> {
+        let pool = self.pool().await;
+        segmentation_exceptions::list_exceptions(&pool, project_uuid).await
+    }
+
+    /// Lists the segmentation exceptions applying to a source language in a project.
+    pub async fn segmentation_exceptions_for_language(
+        &self,
+        project_uuid: Option<Uuid>,
+        language: &str,
+    ) -> DbResult<Vec<SegmentationExceptionRecord>> {
+        let pool = self.pool().await;
+        segmentation_exceptions::exceptions_for_language(&pool, project_uuid, language).await
+    }
+
+    /// Stores a QA profile, replacing an existing profile with the same name.
+    pub async fn upsert_qa_profile(&self, args: NewQaProfileArgs) -> DbResult<QaProfileRecord> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        qa_profiles::upsert_profile(&pool, args).await
+    }
+
+    /// Fetches a QA profile.
+    pub async fn get_qa_profile(&self, profile_uuid: Uuid) -> DbResult<Option<QaProfileRecord>> {
+        let pool = self.pool().await;
+        qa_profiles::get_profile(&pool, profile_uuid).await
+    }
+
+    /// Lists all QA profiles.
+    pub async fn list_qa_profiles(&self) -> DbResult<Vec<QaProfileRecord>> {
+        let pool = self.pool().await;
+        qa_profiles::list_profiles(&pool).await
+    }
+
+    /// Assigns (or, with `None`, unassigns) the QA profile of a project or client.
+    pub async fn assign_qa_profile(
+        &self,
+        target: QaProfileTarget,
+        profile_uuid: Option<Uuid>,
+    ) -> DbResult<()> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        qa_profiles::assign_profile(&pool, target, profile_uuid).await
+    }
+
+    /// Resolves the QA profile of a project, falling back to its client's profile.
+    pub async fn qa_profile_for_project(
+        &self,
+        project_uuid: Uuid,
+    ) -> DbResult<Option<QaProfileRecord>> {
+        let pool = self.pool().await;
+        qa_profiles::profile_for_project(&pool, project_uuid).await
+    }
+
+    /// Stores a pipeline preset, replacing an existing preset with the same name.
+    pub async fn upsert_pipeline_preset(
+        &self,
+        args: NewPipelinePresetArgs,
+    ) -> DbResult<PipelinePresetRecord> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        pipeline_presets::upsert_preset(&pool, args).await
+    }
+
+    /// Fetches a pipeline preset.
+    pub async fn get_pipeline_preset(
+        &self,
+        preset_uuid: Uuid,
+    ) -> DbResult<Option<PipelinePresetRecord>> {
+        let pool = self.pool().await;
+        pipeline_presets::get_preset(&pool, preset_uuid).await
+    }
+
+    /// Lists all pipeline presets.
+    pub async fn list_pipeline_presets(&self) -> DbResult<Vec<PipelinePresetRecord>> {
+        let pool = self.pool().await;
+        pipeline_presets::list_presets(&pool).await
+    }
+
+    /// Deletes a pipeline preset.
+    pub async fn delete_pipeline_preset(&self, preset_uuid: Uuid) -> DbResult<bool> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        pipeline_presets::delete_preset(&pool, preset_uuid).await
+    }
+
+    /// Assigns (or, with `None`, unassigns) the pipeline preset of a project.
+    pub async fn assign_pipeline_preset(
+        &self,
+        project_uuid: Uuid,
+        preset_uuid: Option<Uuid>,
+    ) -> DbResult<()> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        pipeline_presets::assign_preset(&pool, project_uuid, preset_uuid).await
+    }
+
+    /// Resolves the pipeline preset of a project, falling back to its project type's default.
+    pub async fn pipeline_preset_for_project(
+        &self,
+        project_uuid: Uuid,
+    ) -> DbResult<Option<PipelinePresetRecord>> {
+        let pool = self.pool().await;
+        pipeline_presets::preset_for_project(&pool, project_uuid).await
+    }
+
+    /// Registers a plugin, or updates the folder and manifest of an already registered one.
+    pub async fn register_plugin(
+        &self,
+        plugin_id: &str,
+        plugin_dir: &str,
+        manifest_json: &str,
+    ) -> DbResult<PluginRecord> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        plugins::upsert_plugin(&pool, plugin_id, plugin_dir, manifest_json).await
+    }
+
+    /// Fetches a registered plugin.
+    pub async fn get_plugin(&self, plugin_id: &str) -> DbResult<Option<PluginRecord>> {
+        let pool = self.pool().await;
+        plugins::get_plugin(&pool, plugin_id).await
+    }
+
+    /// Lists the registered plugins.
+    pub async fn list_plugins(&self) -> DbResult<Vec<PluginRecord>> {
+        let pool = self.pool().await;
+        plugins::list_plugins(&pool).await
+    }
+
+    /// Unregisters a plugin, dropping its stored settings.
+    pub async fn unregister_plugin(&self, plugin_id: &str) -> DbResult<bool> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        plugins::delete_plugin(&pool, plugin_id).await
+    }
+
+    /// Lists the stored settings of a plugin.
+    pub async fn list_plugin_settings(
+        &self,
+        plugin_id: &str,
+    ) -> DbResult<Vec<PluginSettingRecord>> {
+        let pool = self.pool().await;
+        plugins::list_plugin_settings(&pool, plugin_id).await
+    }
+
+    /// Replaces the stored settings of a plugin; values are JSON.
+    pub async fn replace_plugin_settings(
+        &self,
+        plugin_id: &str,
+        values: &[(String, String)],
+    ) -> DbResult<Vec<PluginSettingRecord>> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        plugins::replace_plugin_settings(&pool, plugin_id, values).await
+    }
+
+    /// Stores an automation script, replacing the script with the same name.
+    pub async fn upsert_automation_script(
+        &self,
+        args: NewAutomationScriptArgs,
+    ) -> DbResult<AutomationScriptRecord> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        automation_scripts::upsert_script(&pool, args).await
+    }
+
+    /// Replaces an automation script, including its name.
+    pub async fn update_automation_script(
+        &self,
+        script_uuid: Uuid,
+        args: NewAutomationScriptArgs,
+    ) -> DbResult<Option<AutomationScriptRecord>> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        automation_scripts::update_script(&pool, script_uuid, args).await
+    }
+
+    /// Lists all automation scripts.
+    pub async fn list_automation_scripts(&self) -> DbResult<Vec<AutomationScriptRecord>> {
+        let pool = self.pool().await;
+        automation_scripts::list_scripts(&pool).await
+    }
+
+    /// Lists the enabled automation scripts of a hook, in the order they run.
+    pub async fn list_enabled_automation_scripts(
+        &self,
+        hook: &str,
+    ) -> DbResult<Vec<AutomationScriptRecord>> {
+        let pool = self.pool().await;
+        automation_scripts::list_enabled_scripts(&pool, hook).await
+    }
+
+    /// Enables or disables an automation script.
+    pub async fn set_automation_script_enabled(
+        &self,
+        script_uuid: Uuid,
+        enabled: bool,
+    ) -> DbResult<Option<AutomationScriptRecord>> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        automation_scripts::set_script_enabled(&pool, script_uuid, enabled).await
+    }
+
+    /// Deletes an automation script.
+    pub async fn delete_automation_script(&self, script_uuid: Uuid) -> DbResult<bool> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        automation_scripts::delete_script(&pool, script_uuid).await
+    }
+
+    /// Records the outcome of an automation script run.
+    pub async fn record_automation_script_run(
+        &self,
+        script_uuid: Uuid,
+        error: Option<&str>,
+    ) -> DbResult<()> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        automation_scripts::record_script_run(&pool, script_uuid, error).await
+    }
+
+    /// Queues an outbound operation requested while offline.
+    pub async fn queue_offline_operation(
+        &self,
+        args: NewOfflineOperationArgs,
+    ) -> DbResult<OfflineOperationRecord> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        offline_operations::queue_operation(&pool, args).await
+    }
+
+    /// Lists the operations queued while offline, oldest first.
+    pub async fn list_offline_operations(&self) -> DbResult<Vec<OfflineOperationRecord>> {
+        let pool = self.pool().await;
+        offline_operations::list_operations(&pool).await
+    }
+
+    /// Deletes an operation queued while offline.
+    pub async fn delete_offline_operation(&self, operation_uuid: Uuid) -> DbResult<bool> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        offline_operations::delete_operation(&pool, operation_uuid).await
+    }
+
+    /// Records a request that sent document text to a provider.
+    pub async fn record_outbound_request(
+        &self,
+        args: NewOutboundRequestArgs,
+    ) -> DbResult<OutboundRequestRecord> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        outbound_requests::record_request(&pool, args).await
+    }
+
+    /// Lists the newest outbound requests, of one project or of all of them.
+    pub async fn list_outbound_requests(
+        &self,
+        project_uuid: Option<Uuid>,
+        limit: i64,
+    ) -> DbResult<Vec<OutboundRequestRecord>> {
+        let pool = self.pool().await;
+        outbound_requests::list_requests(&pool, project_uuid, limit).await
+    }
+
+    /// Records a delivery forced past the QA delivery gate.
+    pub async fn record_delivery_gate_override(
+        &self,
+        args: NewDeliveryGateOverrideArgs,
+    ) -> DbResult<DeliveryGateOverrideRecord> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        delivery_gate_overrides::record_override(&pool, args).await
+    }
+
+    /// Records a delivery package built for a project language pair.
+    pub async fn record_delivery_package(
+        &self,
+        args: NewDeliveryPackageArgs,
+    ) -> DbResult<DeliveryPackageRecord> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        delivery_packages::record_package(&pool, args).await
+    }
+
+    /// Lists the delivery packages of a project, newest first.
+    pub async fn list_delivery_packages(
+        &self,
+        project_uuid: Uuid,
+    ) -> DbResult<Vec<DeliveryPackageRecord>> {
+        let pool = self.pool().await;
+        delivery_packages::list_packages(&pool, project_uuid).await
+    }
+
+    /// Marks the package sent with a project delivery.
+    pub async fn mark_delivery_package_delivered(
+        &self,
+        artifact_uuid: Uuid,
+    ) -> DbResult<Option<DeliveryPackageRecord>> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        delivery_packages::mark_package_delivered(&pool, artifact_uuid).await
+    }
+
+    /// Lists the delivery targets of a client by name.
+    pub async fn list_delivery_targets(
+        &self,
+        client_uuid: Uuid,
+    ) -> DbResult<Vec<DeliveryTargetRecord>> {
+        let pool = self.pool().await;
+        delivery_transports::list_targets(&pool, client_uuid).await
+    }
+
+    /// Fetches a delivery target.
+    pub async fn get_delivery_target(
+        &self,
+        target_uuid: Uuid,
+    ) -> DbResult<Option<DeliveryTargetRecord>> {
+        let pool = self.pool().await;
+        delivery_transports::get_target(&pool, target_uuid).await
+    }
+
+    /// Creates a delivery target or replaces its name and settings.
+    pub async fn upsert_delivery_target(
+        &self,
+        args: NewDeliveryTargetArgs,
+    ) -> DbResult<DeliveryTargetRecord> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        delivery_transports::upsert_target(&pool, args).await
+    }
+
+    /// Deletes a delivery target; its receipts are kept without the target.
+    pub async fn delete_delivery_target(&self, target_uuid: Uuid) -> DbResult<bool> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        delivery_transports::delete_target(&pool, target_uuid).await
+    }
+
+    /// Records the outcome of a package upload.
+    pub async fn record_delivery_receipt(
+        &self,
+        args: NewDeliveryReceiptArgs,
+    ) -> DbResult<DeliveryReceiptRecord> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        delivery_transports::record_receipt(&pool, args).await
+    }
+
+    /// Lists the upload receipts of a project, newest first.
+    pub async fn list_delivery_receipts(
+        &self,
+        project_uuid: Uuid,
+    ) -> DbResult<Vec<DeliveryReceiptRecord>> {
+        let pool = self.pool().await;
+        delivery_transports::list_receipts(&pool, project_uuid).await
+    }
+
+    /// Adds one provider request to the monthly usage of a project.
+    pub async fn record_provider_usage(
+        &self,
+        args: RecordProviderUsageArgs,
+    ) -> DbResult<ProviderUsageRecord> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        provider_usage::record_usage(&pool, args).await
+    }
+
+    /// Lists provider usage, optionally scoped to a project and/or a `YYYY-MM` month.
+    pub async fn list_provider_usage(
+        &self,
+        project_uuid: Option<Uuid>,
+        usage_month: Option<&str>,
+    ) -> DbResult<Vec<ProviderUsageRecord>> {
+        let pool = self.pool().await;
+        provider_usage::list_usage(&pool, project_uuid, usage_month).await
+    }
+
+    /// Returns the usage of every provider in a month across all projects.
+    pub async fn provider_monthly_totals(
+        &self,
+        usage_month: &str,
+    ) -> DbResult<Vec<ProviderMonthlyUsageRecord>> {
+        let pool = self.pool().await;
+        provider_usage::monthly_totals(&pool, usage_month).await
+    }
+
+    /// Sets (or, with both caps unset, removes) the monthly budget of a provider.
+    pub async fn set_provider_budget(
+        &self,
+        args: ProviderBudgetArgs,
+    ) -> DbResult<Option<ProviderBudgetRecord>> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        provider_usage::set_budget(&pool, args).await
+    }
+
+    /// Lists the configured provider budgets.
+    pub async fn list_provider_budgets(&self) -> DbResult<Vec<ProviderBudgetRecord>> {
+        let pool = self.pool().await;
+        provider_usage::list_budgets(&pool).await
+    }
+
+    /// Looks up cached provider translations, one result per key.
+    pub async fn lookup_provider_cache(
+        &self,
+        keys: &[ProviderCacheKey],
+    ) -> DbResult<Vec<Option<String>>> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        provider_cache::lookup_entries(&pool, keys).await
+    }
+
+    /// Caches provider translations for `ttl_hours`.
+    pub async fn store_provider_cache(
+        &self,
+        entries: Vec<NewProviderCacheEntryArgs>,
+        ttl_hours: u32,
+    ) -> DbResult<u64> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        provider_cache::store_entries(&pool, entries, ttl_hours).await
+    }
+
+    /// Returns cache statistics per provider model.
+    pub async fn provider_cache_stats(&self) -> DbResult<Vec<ProviderCacheStatsRecord>> {
+        let pool = self.pool().await;
+        provider_cache::cache_stats(&pool).await
+    }
+
+    /// Purges cached provider translations.
+    pub async fn purge_provider_cache(
+        &self,
+        provider: Option<&str>,
+        expired_only: bool,
+    ) -> DbResult<u64> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        provider_cache::purge_entries(&pool, provider, expired_only).await
+    }
+
+    /// Sets (or, with both limits unset, removes) the rate limits of a provider.
+    pub async fn set_provider_rate_limit(
+        &self,
+        provider: &str,
+        requests_per_minute: Option<i64>,
+        tokens_per_minute: Option<i64>,
+    ) -> DbResult<Option<ProviderRateLimitRecord>> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        provider_rate_limits::set_limit(&pool, provider, requests_per_minute, tokens_per_minute)
+            .await
+    }
+
+    /// Lists the configured provider rate limits.
+    pub async fn list_provider_rate_limits(&self) -> DbResult<Vec<ProviderRateLimitRecord>> {
+        let pool = self.pool().await;
+        provider_rate_limits::list_limits(&pool).await
+    }
+
+    /// Stores exchange-rate snapshots, replacing those of the same pair and date.
+    pub async fn record_exchange_rates(&self, rates: &[NewExchangeRateArgs]) -> DbResult<()> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        exchange_rates::record_rates(&pool, rates).await
+    }
+
+    /// Deletes an exchange-rate snapshot; returns whether it existed.
+    pub async fn delete_exchange_rate(
+        &self,
+        base_currency: &str,
+        quote_currency: &str,
+        rate_date: &str,
+    ) -> DbResult<bool> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        exchange_rates::delete_rate(&pool, base_currency, quote_currency, rate_date).await
+    }
+
+    /// Returns the latest snapshot of every currency pair on or before `on_date`.
+    pub async fn exchange_rates_on(&self, on_date: &str) -> DbResult<Vec<ExchangeRateRecord>> {
+        let pool = self.pool().await;
+        exchange_rates::rates_on(&pool, on_date).await
+    }
+
+    /// Lists every snapshot of a currency pair, newest first.
+    pub async fn exchange_rate_history(
+        &self,
+        base_currency: &str,
+        quote_currency: &str,
+    ) -> DbResult<Vec<ExchangeRateRecord>> {
+        let pool = self.pool().await;
+        exchange_rates::rate_history(&pool, base_currency, quote_currency).await
+    }
+
+    /// Creates a prompt template.
+    pub async fn create_prompt_template(
+        &self,
+        args: NewPromptTemplateArgs,
+    ) -> DbResult<PromptTemplateRecord> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        prompt_templates::create_template(&pool, args).await
+    }
+
+    /// Updates a prompt template.
+    pub async fn update_prompt_template(
+        &self,
+        args: UpdatePromptTemplateArgs,
+    ) -> DbResult<Option<PromptTemplateRecord>> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        prompt_templates::update_template(&pool, args).await
+    }
+
+    /// Deletes a prompt template and its assignments.
+    pub async fn delete_prompt_template(&self, template_uuid: Uuid) -> DbResult<bool> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        prompt_templates::delete_template(&pool, template_uuid).await
+    }
+
+    /// Fetches a prompt template by id.
+    pub async fn get_prompt_template(
+        &self,
+        template_uuid: Uuid,
+    ) -> DbResult<Option<PromptTemplateRecord>> {
+        let pool = self.pool().await;
+        prompt_templates::get_template(&pool, template_uuid).await
+    }
+
+    /// Lists all prompt templates.
+    pub async fn list_prompt_templates(&self) -> DbResult<Vec<PromptTemplateRecord>> {
+        let pool = self.pool().await;
+        prompt_templates::list_templates(&pool).await
+    }
+
+    /// Assigns (or, with `None`, unassigns) the prompt template of a project language pair.
+    pub async fn assign_prompt_template(
+        &self,
+        project_uuid: Uuid,
+        source_lang: &str,
+        target_lang: &str,
+        template_uuid: Option<Uuid>,
+    ) -> DbResult<()> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        prompt_templates::assign_template(
+            &pool,
+            project_uuid,
+            source_lang,
+            target_lang,
+            template_uuid,
+        )
+        .await
+    }
+
+    /// Lists the prompt template assignments of a project.
+    pub async fn list_prompt_template_assignments(
+        &self,
+        project_uuid: Uuid,
+    ) -> DbResult<Vec<PromptTemplateAssignmentRecord>> {
+        let pool = self.pool().await;
+        prompt_templates::list_assignments(&pool, project_uuid).await
+    }
+
+    /// Resolves the prompt template of a project language pair.
+    pub async fn prompt_template_for_pair(
+        &self,
+        project_uuid: Uuid,
+        source_lang: &str,
+        target_lang: &str,
+    ) -> DbResult<Option<PromptTemplateRecord>> {
+        let pool = self.pool().await;
+        prompt_templates::template_for_pair(&pool, project_uuid, source_lang, target_lang).await
+    }
+
+    /// Stores provider comparison candidates for a document.
+    pub async fn store_provider_candidates(
+        &self,
+        project_uuid: Uuid,
+        jliff_rel_path: &str,
+        candidates: &[NewProviderCandidateArgs],
+    ) -> DbResult<()> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        provider_comparisons::store_candidates(&pool, project_uuid, jliff_rel_path, candidates)
+            .await
+    }
+
+    /// Lists the provider comparison candidates of a document.
+    pub async fn list_provider_candidates(
+        &self,
+        project_uuid: Uuid,
+        jliff_rel_path: &str,
+    ) -> DbResult<Vec<ProviderCandidateRecord>> {
+        let pool = self.pool().await;
+        provider_comparisons::list_candidates(&pool, project_uuid, jliff_rel_path).await
+    }
+
+    /// Sets (or, with `None`, clears) the preferred provider of a project language pair.
+    pub async fn set_preferred_provider(
+        &self,
+        project_uuid: Uuid,
+        source_lang: &str,
+        target_lang: &str,
+        provider: Option<&str>,
+    ) -> DbResult<()> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        provider_comparisons::set_preferred_provider(
+            &pool,
+            project_uuid,
+            source_lang,
+            target_lang,
+            provider,
+        )
+        .await
+    }
+
+    /// Lists the preferred providers of a project.
+    pub async fn list_preferred_providers(
+        &self,
+        project_uuid: Uuid,
+    ) -> DbResult<Vec<PreferredProviderRecord>> {
+        let pool = self.pool().await;
+        provider_comparisons::list_preferred_providers(&pool, project_uuid).await
+    }
+
+    /// Replaces the provider routing rules of a project or client.
+    pub async fn replace_provider_routing_rules(
+        &self,
+        owner: RoutingRuleOwner,
+        rules: &[NewProviderRoutingRuleArgs],
+    ) -> DbResult<()> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        provider_routing_rules::replace_rules(&pool, owner, rules).await
+    }
+
+    /// Lists the provider routing rules of a project or client.
+    pub async fn list_provider_routing_rules(
+        &self,
+        owner: RoutingRuleOwner,
+    ) -> DbResult<Vec<ProviderRoutingRuleRecord>> {
+        let pool = self.pool().await;
+        provider_routing_rules::list_rules(&pool, owner).await
+    }
+
+    /// Lists the routing rules applying to a project, its own before its client's.
+    pub async fn provider_routing_rules_for_project(
+        &self,
+        project_uuid: Uuid,
+    ) -> DbResult<Vec<ProviderRoutingRuleRecord>> {
+        let pool = self.pool().await;
+        provider_routing_rules::rules_for_project(&pool, project_uuid).await
+    }
+
+    /// Records that a background task started.
+    pub async fn record_scheduled_task_started(
+        &self,
+        task_id: &str,
+        started_at: &str,
+    ) -> DbResult<()> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        scheduled_tasks::record_started(&pool, task_id, started_at).await
+    }
+
+    /// Records how the current run of a background task ended.
+    pub async fn record_scheduled_task_finished(
+        &self,
+        task_id: &str,
+        finished_at: &str,
+        error: Option<&str>,
+    ) -> DbResult<()> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        scheduled_tasks::record_finished(&pool, task_id, finished_at, error).await
+    }
+
+    /// Lists the run history of background tasks.
+    pub async fn list_scheduled_task_runs(&self) -> DbResult<Vec<ScheduledTaskRunRecord>> {
+        let pool = self.pool().await;
+        scheduled_tasks::list_runs(&pool).await
+    }
 }
@@ -1,12 +1,18 @@
 //! Database module exposing the manager, domain types, and grouped operations.
 
 pub(crate) mod builders;
+pub mod collation;
 pub mod config;
 pub mod constants;
+pub mod encryption;
 pub mod error;
+pub mod health;
 pub mod manager;
+pub mod numbering;
 mod operations;
+pub mod pii;
 mod schema;
+pub mod search;
 pub mod types;
 pub mod utils;
 
@@ -19,10 +25,11 @@ pub use schema::initialise_schema;
 #[allow(unused_imports)]
 pub use types::{
     Artifact, ArtifactKind, ArtifactStatus, Client, Domain, FileTarget, FileTargetStatus, Job,
-    JobState, JobType, LanguagePair, NewProject, NewProjectFile, NewProjectFileConversion,
-    NewTranslationRecord, Note, PersistedTranslationOutput, ProjectDetails,
-    ProjectFileConversionRequest, ProjectFileConversionRow, ProjectFileConversionStatus,
-    ProjectFileDetails, ProjectFileImportStatus, ProjectFileRole, ProjectFileStorageState,
-    ProjectFileWithConversions, ProjectLifecycleStatus, ProjectListItem, ProjectStatus,
-    ProjectType, User, Validation,
+    JobState, JobType, LanguagePair, NewNoteArgs, NewProject, NewProjectFile,
+    NewProjectFileConversion, NewTranslationRecord, Note, NoteMention, NotePage,
+    PersistedTranslationOutput, ProjectDetails, ProjectFileConversionRequest,
+    ProjectFileConversionRow, ProjectFileConversionStatus, ProjectFileDetails,
+    ProjectFileImportStatus, ProjectFileRole, ProjectFileStorageState, ProjectFileWithConversions,
+    ProjectLifecycleStatus, ProjectListItem, ProjectStatus, ProjectType, UpdateNoteArgs, User,
+    Validation,
 };
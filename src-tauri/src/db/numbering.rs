@@ -0,0 +1,210 @@
+//! Project numbers: readable references such as `ACME-2026-0042`, assigned at creation.
+//!
+//! A template mixes literal text with placeholders: `{client_code}`, `{yyyy}`, `{yy}`, `{mm}`
+//! and exactly one `{seq}` (or `{seq:N}`, zero-padded to N digits). The sequence counts per
+//! scope, the number with the sequence left out, so `{client_code}-{yyyy}-{seq}` restarts at
+//! 1 for every client each year. Counters live in `project_number_sequences` and advance in
+//! the transaction that creates the project, and numbers already taken are skipped, so two
+//! projects never share a number.
+
+use anyhow::{Result, bail};
+use sqlx::{Sqlite, Transaction};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::db::error::DbResult;
+use crate::db::search::search_key;
+
+pub const DEFAULT_PROJECT_NUMBER_TEMPLATE: &str = "{client_code}-{yyyy}-{seq:4}";
+
+/// Client code of projects without a client.
+const INTERNAL_CLIENT_CODE: &str = "INT";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Part {
+    Text(String),
+    ClientCode,
+    Year,
+    ShortYear,
+    Month,
+    Sequence { width: usize },
+}
+
+/// A parsed project number template.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProjectNumberTemplate {
+    parts: Vec<Part>,
+}
+
+impl Default for ProjectNumberTemplate {
+    fn default() -> Self {
+        Self::parse(DEFAULT_PROJECT_NUMBER_TEMPLATE).expect("the default template is valid")
+    }
+}
+
+impl ProjectNumberTemplate {
+    pub fn parse(template: &str) -> Result<Self> {
+        let mut parts = Vec::new();
+        let mut text = String::new();
+        let mut chars = template.trim().chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '{' => {
+                    let mut closed = false;
+                    let name: String = chars
+                        .by_ref()
+                        .take_while(|c| {
+                            closed = *c == '}';
+                            !closed
+                        })
+                        .collect();
+                    if !closed {
+                        bail!("The project number template has an unclosed '{{'.");
+                    }
+                    if !text.is_empty() {
+                        parts.push(Part::Text(std::mem::take(&mut text)));
+                    }
+                    parts.push(match name.as_str() {
+                        "client_code" => Part::ClientCode,
+                        "yyyy" => Part::Year,
+                        "yy" => Part::ShortYear,
+                        "mm" => Part::Month,
+                        "seq" => Part::Sequence { width: 1 },
+                        _ => match name.strip_prefix("seq:").map(str::parse::<usize>) {
+                            Some(Ok(width @ 1..=10)) => Part::Sequence { width },
+                            _ => bail!("'{{{name}}}' is not a project number placeholder."),
+                        },
+                    });
+                }
+                '}' => bail!("The project number template has an unmatched '}}'."),
+                _ => text.push(c),
+            }
+        }
+        if !text.is_empty() {
+            parts.push(Part::Text(text));
+        }
+        let sequences = parts
+            .iter()
+            .filter(|part| matches!(part, Part::Sequence { .. }))
+            .count();
+        if sequences != 1 {
+            bail!("The project number template needs exactly one {{seq}} placeholder.");
+        }
+        Ok(Self { parts })
+    }
+
+    /// The number for `sequence`, or the scope the sequence counts in when `None`.
+    fn render(&self, context: &NumberContext, sequence: Option<i64>) -> String {
+        let mut number = String::new();
+        for part in &self.parts {
+            match part {
+                Part::Text(text) => number.push_str(text),
+                Part::ClientCode => number.push_str(&context.client_code),
+                Part::Year => number.push_str(&format!("{:04}", context.year)),
+                Part::ShortYear => number.push_str(&format!("{:02}", context.year % 100)),
+                Part::Month => number.push_str(&format!("{:02}", context.month)),
+                Part::Sequence { width } => match sequence {
+                    Some(value) => number.push_str(&format!("{value:0width$}")),
+                    None => number.push('#'),
+                },
+            }
+        }
+        number
+    }
+}
+
+struct NumberContext {
+    client_code: String,
+    year: i32,
+    month: u8,
+}
+
+/// Code of a client for project numbers: the first four letters or digits of its name,
+/// uppercased and without accents ("Müller GmbH" is `MULL`).
+pub fn client_code(client_name: Option<&str>) -> String {
+    let code: String = client_name
+        .map(search_key)
+        .unwrap_or_default()
+        .chars()
+        .filter(char::is_ascii_alphanumeric)
+        .take(4)
+        .collect();
+    if code.is_empty() {
+        INTERNAL_CLIENT_CODE.to_string()
+    } else {
+        code.to_uppercase()
+    }
+}
+
+/// Draws the next free number of `template` for a project of `client_uuid` created now.
+pub(crate) async fn assign_project_number(
+    tx: &mut Transaction<'_, Sqlite>,
+    template: &ProjectNumberTemplate,
+    client_uuid: Option<Uuid>,
+) -> DbResult<String> {
+    let client_name: Option<String> = match client_uuid {
+        Some(client_uuid) => {
+            sqlx::query_scalar("SELECT name FROM clients WHERE client_uuid = ?1")
+                .bind(client_uuid)
+                .fetch_optional(&mut **tx)
+                .await?
+        }
+        None => None,
+    };
+    let now = OffsetDateTime::now_utc();
+    let context = NumberContext {
+        client_code: client_code(client_name.as_deref()),
+        year: now.year(),
+        month: u8::from(now.month()),
+    };
+    let scope = template.render(&context, None);
+    loop {
+        let value: i64 = sqlx::query_scalar(
+            r#"
+            INSERT INTO project_number_sequences (scope, last_value)
+            VALUES (?1, 1)
+            ON CONFLICT (scope) DO UPDATE SET last_value = last_value + 1
+            RETURNING last_value
+            "#,
+        )
+        .bind(&scope)
+        .fetch_one(&mut **tx)
+        .await?;
+        let number = template.render(&context, Some(value));
+        let taken: bool =
+            sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM projects WHERE project_number = ?1)")
+                .bind(&number)
+                .fetch_one(&mut **tx)
+                .await?;
+        if !taken {
+            return Ok(number);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_templates_and_rejects_malformed_ones() {
+        let context = NumberContext {
+            client_code: client_code(Some("Müller GmbH")),
+            year: 2026,
+            month: 3,
+        };
+        let template = ProjectNumberTemplate::default();
+        assert_eq!(template.render(&context, Some(42)), "MULL-2026-0042");
+        assert_eq!(template.render(&context, None), "MULL-2026-#");
+
+        let monthly = ProjectNumberTemplate::parse("P{yy}{mm}/{seq}").unwrap();
+        assert_eq!(monthly.render(&context, Some(7)), "P2603/7");
+        assert_eq!(client_code(None), "INT");
+
+        assert!(ProjectNumberTemplate::parse("{yyyy}").is_err());
+        assert!(ProjectNumberTemplate::parse("{seq}-{seq}").is_err());
+        assert!(ProjectNumberTemplate::parse("{client}-{seq}").is_err());
+        assert!(ProjectNumberTemplate::parse("{seq:0}").is_err());
+        assert!(ProjectNumberTemplate::parse("{yyyy}-{seq").is_err());
+    }
+}
@@ -0,0 +1,153 @@
+//! Document alignment operations.
+
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+use crate::db::error::DbResult;
+use crate::db::types::{
+    AlignmentPairRecord, AlignmentRecord, NewAlignmentArgs, UpdateAlignmentPairArgs,
+};
+
+/// Stores an alignment and its pairs in a single transaction.
+pub async fn create_alignment(
+    pool: &SqlitePool,
+    args: &NewAlignmentArgs,
+) -> DbResult<AlignmentRecord> {
+    let mut tx = pool.begin().await?;
+
+    let record = sqlx::query_as::<_, AlignmentRecord>(
+        r#"
+        INSERT INTO alignments (
+            alignment_uuid,
+            source_lang,
+            target_lang,
+            source_name,
+            target_name
+        )
+        VALUES (?1, ?2, ?3, ?4, ?5)
+        RETURNING *
+        "#,
+    )
+    .bind(args.alignment_uuid)
+    .bind(args.source_lang.trim().to_lowercase())
+    .bind(args.target_lang.trim().to_lowercase())
+    .bind(&args.source_name)
+    .bind(&args.target_name)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    for (position, pair) in args.pairs.iter().enumerate() {
+        sqlx::query(
+            r#"
+            INSERT INTO alignment_pairs (alignment_uuid, position, source_text, target_text, score)
+            VALUES (?1, ?2, ?3, ?4, ?5)
+            "#,
+        )
+        .bind(args.alignment_uuid)
+        .bind(position as i64)
+        .bind(&pair.source_text)
+        .bind(&pair.target_text)
+        .bind(pair.score)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+    Ok(record)
+}
+
+pub async fn get_alignment(
+    pool: &SqlitePool,
+    alignment_uuid: Uuid,
+) -> DbResult<Option<AlignmentRecord>> {
+    let record =
+        sqlx::query_as::<_, AlignmentRecord>("SELECT * FROM alignments WHERE alignment_uuid = ?1")
+            .bind(alignment_uuid)
+            .fetch_optional(pool)
+            .await?;
+    Ok(record)
+}
+
+/// Lists alignments, most recent first.
+pub async fn list_alignments(pool: &SqlitePool) -> DbResult<Vec<AlignmentRecord>> {
+    let records = sqlx::query_as::<_, AlignmentRecord>(
+        "SELECT * FROM alignments ORDER BY created_at DESC, alignment_uuid ASC",
+    )
+    .fetch_all(pool)
+    .await?;
+    Ok(records)
+}
+
+/// Lists the pairs of an alignment in document order.
+pub async fn list_alignment_pairs(
+    pool: &SqlitePool,
+    alignment_uuid: Uuid,
+) -> DbResult<Vec<AlignmentPairRecord>> {
+    let records = sqlx::query_as::<_, AlignmentPairRecord>(
+        "SELECT * FROM alignment_pairs WHERE alignment_uuid = ?1 ORDER BY position ASC",
+    )
+    .bind(alignment_uuid)
+    .fetch_all(pool)
+    .await?;
+    Ok(records)
+}
+
+/// Applies review changes to pairs in a single transaction, returning how many matched.
+pub async fn update_alignment_pairs(
+    pool: &SqlitePool,
+    alignment_uuid: Uuid,
+    updates: &[UpdateAlignmentPairArgs],
+) -> DbResult<u64> {
+    let mut tx = pool.begin().await?;
+
+    let mut updated = 0;
+    for update in updates {
+        updated += sqlx::query(
+            r#"
+            UPDATE alignment_pairs
+            SET status = COALESCE(?3, status),
+                source_text = COALESCE(?4, source_text),
+                target_text = COALESCE(?5, target_text)
+            WHERE alignment_uuid = ?1 AND position = ?2
+            "#,
+        )
+        .bind(alignment_uuid)
+        .bind(update.position)
+        .bind(&update.status)
+        .bind(&update.source_text)
+        .bind(&update.target_text)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+    }
+
+    tx.commit().await?;
+    Ok(updated)
+}
+
+/// Stamps an alignment as committed to the translation memory.
+pub async fn mark_alignment_committed(
+    pool: &SqlitePool,
+    alignment_uuid: Uuid,
+) -> DbResult<Option<AlignmentRecord>> {
+    let record = sqlx::query_as::<_, AlignmentRecord>(
+        r#"
+        UPDATE alignments SET committed_at = CURRENT_TIMESTAMP
+        WHERE alignment_uuid = ?1
+        RETURNING *
+        "#,
+    )
+    .bind(alignment_uuid)
+    .fetch_optional(pool)
+    .await?;
+    Ok(record)
+}
+
+/// Deletes an alignment and its pairs, returning whether it existed.
+pub async fn delete_alignment(pool: &SqlitePool, alignment_uuid: Uuid) -> DbResult<bool> {
+    let result = sqlx::query("DELETE FROM alignments WHERE alignment_uuid = ?1")
+        .bind(alignment_uuid)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
@@ -0,0 +1,134 @@
+//! Automation scripts attached to hook points, and the outcome of their last run.
+
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+use crate::db::error::DbResult;
+use crate::db::types::{AutomationScriptRecord, NewAutomationScriptArgs};
+
+/// Stores a script under its name, replacing the source, hook and flag of an existing one.
+pub async fn upsert_script(
+    pool: &SqlitePool,
+    args: NewAutomationScriptArgs,
+) -> DbResult<AutomationScriptRecord> {
+    let record = sqlx::query_as::<_, AutomationScriptRecord>(
+        r#"
+        INSERT INTO automation_scripts (script_uuid, name, hook, source, enabled)
+        VALUES (?1, ?2, ?3, ?4, ?5)
+        ON CONFLICT (name) DO UPDATE SET
+            hook = excluded.hook,
+            source = excluded.source,
+            enabled = excluded.enabled,
+            last_error = NULL,
+            updated_at = CURRENT_TIMESTAMP
+        RETURNING *
+        "#,
+    )
+    .bind(Uuid::new_v4())
+    .bind(&args.name)
+    .bind(&args.hook)
+    .bind(&args.source)
+    .bind(args.enabled)
+    .fetch_one(pool)
+    .await?;
+    Ok(record)
+}
+
+/// Replaces a script, including its name. Returns `None` when it does not exist.
+pub async fn update_script(
+    pool: &SqlitePool,
+    script_uuid: Uuid,
+    args: NewAutomationScriptArgs,
+) -> DbResult<Option<AutomationScriptRecord>> {
+    let record = sqlx::query_as::<_, AutomationScriptRecord>(
+        r#"
+        UPDATE automation_scripts
+        SET name = ?2, hook = ?3, source = ?4, enabled = ?5, last_error = NULL,
+            updated_at = CURRENT_TIMESTAMP
+        WHERE script_uuid = ?1
+        RETURNING *
+        "#,
+    )
+    .bind(script_uuid)
+    .bind(&args.name)
+    .bind(&args.hook)
+    .bind(&args.source)
+    .bind(args.enabled)
+    .fetch_optional(pool)
+    .await?;
+    Ok(record)
+}
+
+/// Lists all scripts by name.
+pub async fn list_scripts(pool: &SqlitePool) -> DbResult<Vec<AutomationScriptRecord>> {
+    let records = sqlx::query_as::<_, AutomationScriptRecord>(
+        "SELECT * FROM automation_scripts ORDER BY name ASC",
+    )
+    .fetch_all(pool)
+    .await?;
+    Ok(records)
+}
+
+/// Lists the enabled scripts of a hook by name, the order they run in.
+pub async fn list_enabled_scripts(
+    pool: &SqlitePool,
+    hook: &str,
+) -> DbResult<Vec<AutomationScriptRecord>> {
+    let records = sqlx::query_as::<_, AutomationScriptRecord>(
+        "SELECT * FROM automation_scripts WHERE hook = ?1 AND enabled = 1 ORDER BY name ASC",
+    )
+    .bind(hook)
+    .fetch_all(pool)
+    .await?;
+    Ok(records)
+}
+
+/// Enables or disables a script. Returns `None` when it does not exist.
+pub async fn set_script_enabled(
+    pool: &SqlitePool,
+    script_uuid: Uuid,
+    enabled: bool,
+) -> DbResult<Option<AutomationScriptRecord>> {
+    let record = sqlx::query_as::<_, AutomationScriptRecord>(
+        r#"
+        UPDATE automation_scripts
+        SET enabled = ?2, updated_at = CURRENT_TIMESTAMP
+        WHERE script_uuid = ?1
+        RETURNING *
+        "#,
+    )
+    .bind(script_uuid)
+    .bind(enabled)
+    .fetch_optional(pool)
+    .await?;
+    Ok(record)
+}
+
+/// Deletes a script. Returns whether a script was removed.
+pub async fn delete_script(pool: &SqlitePool, script_uuid: Uuid) -> DbResult<bool> {
+    let result = sqlx::query("DELETE FROM automation_scripts WHERE script_uuid = ?1")
+        .bind(script_uuid)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Records that a script ran, with its error when it failed.
+pub async fn record_script_run(
+    pool: &SqlitePool,
+    script_uuid: Uuid,
+    error: Option<&str>,
+) -> DbResult<()> {
+    sqlx::query(
+        r#"
+        UPDATE automation_scripts
+        SET last_run_at = CURRENT_TIMESTAMP, last_error = ?2
+        WHERE script_uuid = ?1
+        "#,
+    )
+    .bind(script_uuid)
+    .bind(error)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
@@ -1,40 +1,127 @@
 //! Standalone client operations for the refactored schema.
+//!
+//! Contact details, notes and the street-level postal address are sealed with the
+//! [`FieldCipher`] on the way in and opened on the way out, so callers only see plaintext.
 
 use sqlx::{QueryBuilder, Sqlite, SqlitePool, Transaction};
 use uuid::Uuid;
 
+use crate::db::collation::Collator;
 use crate::db::error::DbResult;
-use crate::db::types::{ClientRecord, NewClientArgs, UpdateClientArgs};
+use crate::db::pii::FieldCipher;
+use crate::db::search::{contains_pattern, search_key};
+use crate::db::types::{
+    ClientRecord, NewClientArgs, PostalAddress, UpdateClientArgs, VatValidation,
+};
 
 /// Inserts a new client record.
-pub async fn create_client(pool: &SqlitePool, args: NewClientArgs) -> DbResult<ClientRecord> {
+pub async fn create_client(
+    pool: &SqlitePool,
+    cipher: &FieldCipher,
+    args: NewClientArgs,
+) -> DbResult<ClientRecord> {
+    let mut tx = pool.begin().await?;
+    insert_client(&mut tx, cipher, &args).await?;
+    let record = fetch_client(&mut tx, cipher, args.client_uuid).await?;
+    tx.commit().await?;
+
+    record.ok_or_else(|| sqlx::Error::RowNotFound.into())
+}
+
+/// Inserts several clients in one transaction, so either all of them are stored or none.
+pub async fn create_clients(
+    pool: &SqlitePool,
+    cipher: &FieldCipher,
+    clients: &[NewClientArgs],
+) -> DbResult<()> {
     let mut tx = pool.begin().await?;
+    for args in clients {
+        insert_client(&mut tx, cipher, args).await?;
+    }
+    tx.commit().await?;
+    Ok(())
+}
 
+async fn insert_client(
+    tx: &mut Transaction<'_, Sqlite>,
+    cipher: &FieldCipher,
+    args: &NewClientArgs,
+) -> DbResult<()> {
     sqlx::query(
         r#"
-        INSERT INTO clients (client_uuid, name, email, phone, address, vat_number, note)
-        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+        INSERT INTO clients (
+            client_uuid, name, email, phone, address, vat_number, note, currency, name_search
+        )
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
         "#,
     )
     .bind(args.client_uuid)
     .bind(&args.name)
-    .bind(&args.email)
-    .bind(&args.phone)
-    .bind(&args.address)
+    .bind(cipher.seal(args.email.as_deref())?)
+    .bind(cipher.seal(args.phone.as_deref())?)
+    .bind(cipher.seal(args.address.as_deref())?)
     .bind(&args.vat_number)
-    .bind(&args.note)
-    .execute(&mut *tx)
+    .bind(cipher.seal(args.note.as_deref())?)
+    .bind(&args.currency)
+    .bind(search_key(&args.name))
+    .execute(&mut **tx)
     .await?;
+    write_postal_address(tx, cipher, args.client_uuid, &args.postal_address).await?;
+    write_vat_validation(tx, args.client_uuid, &args.vat_validation).await
+}
 
-    let record = fetch_client(&mut tx, args.client_uuid).await?;
-    tx.commit().await?;
+async fn write_vat_validation(
+    tx: &mut Transaction<'_, Sqlite>,
+    client_uuid: Uuid,
+    validation: &VatValidation,
+) -> DbResult<()> {
+    sqlx::query("UPDATE clients SET vat_status = ?2, vat_checked_at = ?3 WHERE client_uuid = ?1")
+        .bind(client_uuid)
+        .bind(&validation.status)
+        .bind(&validation.checked_at)
+        .execute(&mut **tx)
+        .await?;
+    Ok(())
+}
 
-    record.ok_or_else(|| sqlx::Error::RowNotFound.into())
+async fn write_postal_address(
+    tx: &mut Transaction<'_, Sqlite>,
+    cipher: &FieldCipher,
+    client_uuid: Uuid,
+    address: &PostalAddress,
+) -> DbResult<()> {
+    sqlx::query(
+        r#"
+        UPDATE clients
+        SET address_street = ?2,
+            address_street_number = ?3,
+            address_postal_code = ?4,
+            address_locality = ?5,
+            address_region = ?6,
+            address_country_code = ?7,
+            address_country = ?8,
+            address_place_id = ?9
+        WHERE client_uuid = ?1
+        "#,
+    )
+    .bind(client_uuid)
+    .bind(cipher.seal(address.street.as_deref())?)
+    .bind(cipher.seal(address.street_number.as_deref())?)
+    .bind(cipher.seal(address.postal_code.as_deref())?)
+    .bind(cipher.seal(address.locality.as_deref())?)
+    .bind(&address.region)
+    .bind(&address.country_code)
+    .bind(&address.country)
+    .bind(&address.place_id)
+    .execute(&mut **tx)
+    .await?;
+    Ok(())
 }
 
 /// Updates mutable fields for a client.
 pub async fn update_client(
     pool: &SqlitePool,
+    cipher: &FieldCipher,
     args: UpdateClientArgs,
 ) -> DbResult<Option<ClientRecord>> {
     let mut tx = pool.begin().await?;
@@ -45,6 +132,7 @@ pub async fn update_client(
         || args.address.is_some()
         || args.vat_number.is_some()
         || args.note.is_some()
+        || args.currency.is_some()
     {
         let mut builder = QueryBuilder::<Sqlite>::new("UPDATE clients SET ");
         let mut first = true;
@@ -55,6 +143,8 @@ pub async fn update_client(
             }
             builder.push("name = ");
             builder.push_bind(name);
+            builder.push(", name_search = ");
+            builder.push_bind(search_key(name));
             first = false;
         }
 
@@ -63,7 +153,7 @@ pub async fn update_client(
                 builder.push(", ");
             }
             builder.push("email = ");
-            builder.push_bind(email.clone());
+            builder.push_bind(cipher.seal(email.as_deref())?);
             first = false;
         }
 
@@ -72,7 +162,7 @@ pub async fn update_client(
                 builder.push(", ");
             }
             builder.push("phone = ");
-            builder.push_bind(phone.clone());
+            builder.push_bind(cipher.seal(phone.as_deref())?);
             first = false;
         }
 
@@ -81,7 +171,7 @@ pub async fn update_client(
                 builder.push(", ");
             }
             builder.push("address = ");
-            builder.push_bind(address.clone());
+            builder.push_bind(cipher.seal(address.as_deref())?);
             first = false;
         }
 
@@ -99,7 +189,16 @@ pub async fn update_client(
                 builder.push(", ");
             }
             builder.push("note = ");
-            builder.push_bind(note.clone());
+            builder.push_bind(cipher.seal(note.as_deref())?);
+            first = false;
+        }
+
+        if let Some(currency) = args.currency.as_ref() {
+            if !first {
+                builder.push(", ");
+            }
+            builder.push("currency = ");
+            builder.push_bind(currency.clone());
         }
 
         builder.push(" WHERE client_uuid = ");
@@ -107,7 +206,14 @@ pub async fn update_client(
         builder.build().execute(&mut *tx).await?;
     }
 
-    let record = fetch_client(&mut tx, args.client_uuid).await?;
+    if let Some(address) = &args.postal_address {
+        write_postal_address(&mut tx, cipher, args.client_uuid, address).await?;
+    }
+    if let Some(validation) = &args.vat_validation {
+        write_vat_validation(&mut tx, args.client_uuid, validation).await?;
+    }
+
+    let record = fetch_client(&mut tx, cipher, args.client_uuid).await?;
     tx.commit().await?;
 
     Ok(record)
@@ -123,24 +229,75 @@ pub async fn delete_client(pool: &SqlitePool, client_uuid: Uuid) -> DbResult<()>
 }
 
 /// Retrieves a client by identifier.
-pub async fn get_client(pool: &SqlitePool, client_uuid: Uuid) -> DbResult<Option<ClientRecord>> {
+pub async fn get_client(
+    pool: &SqlitePool,
+    cipher: &FieldCipher,
+    client_uuid: Uuid,
+) -> DbResult<Option<ClientRecord>> {
     let mut tx = pool.begin().await?;
-    let record = fetch_client(&mut tx, client_uuid).await?;
+    let record = fetch_client(&mut tx, cipher, client_uuid).await?;
     tx.commit().await?;
     Ok(record)
 }
 
-/// Lists clients ordered by name.
-pub async fn list_clients(pool: &SqlitePool) -> DbResult<Vec<ClientRecord>> {
-    let records: Vec<ClientRecord> =
-        sqlx::query_as("SELECT * FROM clients ORDER BY name COLLATE NOCASE ASC")
-            .fetch_all(pool)
-            .await?;
+/// Lists clients ordered by name, keeping those whose name contains `query` when given,
+/// ignoring case and accents.
+pub async fn list_clients(
+    pool: &SqlitePool,
+    cipher: &FieldCipher,
+    collator: &Collator,
+    query: Option<&str>,
+) -> DbResult<Vec<ClientRecord>> {
+    let mut records = sqlx::query_as::<_, ClientRecord>(
+        r"SELECT * FROM clients WHERE ?1 IS NULL OR name_search LIKE ?1 ESCAPE '\'",
+    )
+    .bind(query.map(contains_pattern))
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .map(|record| open_client(cipher, record))
+    .collect::<DbResult<Vec<_>>>()?;
+    collator.sort_by_text(&mut records, |client| &client.name);
     Ok(records)
 }
 
+/// Re-seals the personal data of every client, opened with `current` and sealed with `next`.
+/// Plaintext left from before the encryption is sealed too. Returns how many clients were
+/// rewritten.
+pub async fn rotate_pii_key(
+    pool: &SqlitePool,
+    current: &FieldCipher,
+    next: &FieldCipher,
+) -> DbResult<u64> {
+    let mut tx = pool.begin().await?;
+    let records = sqlx::query_as::<_, ClientRecord>("SELECT * FROM clients")
+        .fetch_all(&mut *tx)
+        .await?;
+    let count = records.len() as u64;
+    for record in records {
+        let record = open_client(current, record)?;
+        sqlx::query(
+            r#"
+            UPDATE clients SET email = ?2, phone = ?3, address = ?4, note = ?5
+            WHERE client_uuid = ?1
+            "#,
+        )
+        .bind(record.client_uuid)
+        .bind(next.seal(record.email.as_deref())?)
+        .bind(next.seal(record.phone.as_deref())?)
+        .bind(next.seal(record.address.as_deref())?)
+        .bind(next.seal(record.note.as_deref())?)
+        .execute(&mut *tx)
+        .await?;
+        write_postal_address(&mut tx, next, record.client_uuid, &record.postal_address).await?;
+    }
+    tx.commit().await?;
+    Ok(count)
+}
+
 async fn fetch_client(
     tx: &mut Transaction<'_, Sqlite>,
+    cipher: &FieldCipher,
     client_uuid: Uuid,
 ) -> DbResult<Option<ClientRecord>> {
     let record =
@@ -148,5 +305,18 @@ async fn fetch_client(
             .bind(client_uuid)
             .fetch_optional(&mut **tx)
             .await?;
+    record.map(|record| open_client(cipher, record)).transpose()
+}
+
+fn open_client(cipher: &FieldCipher, mut record: ClientRecord) -> DbResult<ClientRecord> {
+    record.email = cipher.open(record.email)?;
+    record.phone = cipher.open(record.phone)?;
+    record.address = cipher.open(record.address)?;
+    record.note = cipher.open(record.note)?;
+    let address = &mut record.postal_address;
+    address.street = cipher.open(address.street.take())?;
+    address.street_number = cipher.open(address.street_number.take())?;
+    address.postal_code = cipher.open(address.postal_code.take())?;
+    address.locality = cipher.open(address.locality.take())?;
     Ok(record)
 }
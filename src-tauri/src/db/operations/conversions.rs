@@ -5,7 +5,6 @@ use sqlx::{Executor, Row, Sqlite, Transaction};
 use uuid::Uuid;
 
 use crate::db::builders::{build_project_file_conversion, conversion_projection};
-use crate::db::constants::{CONVERTIBLE_EXTENSIONS, SKIP_CONVERSION_EXTENSIONS};
 use crate::db::error::{DbError, DbResult};
 use crate::db::manager::DbManager;
 use crate::db::types::{
@@ -13,6 +12,7 @@ use crate::db::types::{
     ProjectFileConversionStatus, ProjectFileImportStatus,
 };
 use crate::db::utils::now_iso8601;
+use crate::formats;
 
 impl DbManager {
     /// Inserts one or more conversion rows within an existing transaction.
@@ -344,11 +344,9 @@ impl DbManager {
             let file_id = Uuid::parse_str(&file_id_raw)
                 .map_err(|_| DbError::InvalidProjectId(file_id_raw.clone()))?;
 
+            // The database has no app folder, so the embedded formats manifest applies.
             let ext: String = row.try_get("ext")?;
-            if SKIP_CONVERSION_EXTENSIONS.contains(&ext.as_str()) {
-                continue;
-            }
-            if !CONVERTIBLE_EXTENSIONS.contains(&ext.as_str()) {
+            if !formats::embedded().is_convertible(&ext) {
                 continue;
             }
 
@@ -0,0 +1,32 @@
+//! Audit trail of deliveries that bypassed the QA delivery gate.
+
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+use crate::db::error::DbResult;
+use crate::db::types::{DeliveryGateOverrideRecord, NewDeliveryGateOverrideArgs};
+
+/// Stores an override entry.
+pub async fn record_override(
+    pool: &SqlitePool,
+    args: NewDeliveryGateOverrideArgs,
+) -> DbResult<DeliveryGateOverrideRecord> {
+    let record = sqlx::query_as::<_, DeliveryGateOverrideRecord>(
+        r#"
+        INSERT INTO delivery_gate_overrides (
+            override_uuid, project_uuid, user_uuid, reason, critical_findings, empty_targets
+        )
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+        RETURNING *
+        "#,
+    )
+    .bind(Uuid::new_v4())
+    .bind(args.project_uuid)
+    .bind(args.user_uuid)
+    .bind(&args.reason)
+    .bind(args.critical_findings)
+    .bind(args.empty_targets)
+    .fetch_one(pool)
+    .await?;
+    Ok(record)
+}
@@ -0,0 +1,69 @@
+//! Delivery packages built for a project language pair.
+
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+use crate::db::error::DbResult;
+use crate::db::types::{DeliveryPackageRecord, NewDeliveryPackageArgs};
+
+/// Stores a package entry for an artifact that was just registered.
+pub async fn record_package(
+    pool: &SqlitePool,
+    args: NewDeliveryPackageArgs,
+) -> DbResult<DeliveryPackageRecord> {
+    let record = sqlx::query_as::<_, DeliveryPackageRecord>(
+        r#"
+        INSERT INTO delivery_packages (
+            artifact_uuid, project_uuid, source_lang, target_lang, rel_path,
+            document_count, bilingual_count, open_findings
+        )
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+        RETURNING *
+        "#,
+    )
+    .bind(args.artifact_uuid)
+    .bind(args.project_uuid)
+    .bind(&args.source_lang)
+    .bind(&args.target_lang)
+    .bind(&args.rel_path)
+    .bind(args.document_count)
+    .bind(args.bilingual_count)
+    .bind(args.open_findings)
+    .fetch_one(pool)
+    .await?;
+    Ok(record)
+}
+
+/// Lists the packages of a project, newest first.
+pub async fn list_packages(
+    pool: &SqlitePool,
+    project_uuid: Uuid,
+) -> DbResult<Vec<DeliveryPackageRecord>> {
+    let records = sqlx::query_as::<_, DeliveryPackageRecord>(
+        "SELECT * FROM delivery_packages WHERE project_uuid = ?1 ORDER BY created_at DESC, rowid DESC",
+    )
+    .bind(project_uuid)
+    .fetch_all(pool)
+    .await?;
+    Ok(records)
+}
+
+/// Marks a package as the one sent with the delivery; a package delivered before keeps its
+/// first delivery date.
+pub async fn mark_package_delivered(
+    pool: &SqlitePool,
+    artifact_uuid: Uuid,
+) -> DbResult<Option<DeliveryPackageRecord>> {
+    let record = sqlx::query_as::<_, DeliveryPackageRecord>(
+        r#"
+        UPDATE delivery_packages
+        SET delivered_at = COALESCE(delivered_at, CURRENT_TIMESTAMP)
+        WHERE artifact_uuid = ?1
+        RETURNING *
+        "#,
+    )
+    .bind(artifact_uuid)
+    .fetch_optional(pool)
+    .await?;
+    Ok(record)
+}
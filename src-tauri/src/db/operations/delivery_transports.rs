@@ -0,0 +1,116 @@
+//! Delivery targets of clients and receipts of package uploads.
+
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+use crate::db::error::DbResult;
+use crate::db::types::{
+    DeliveryReceiptRecord, DeliveryTargetRecord, NewDeliveryReceiptArgs, NewDeliveryTargetArgs,
+};
+
+/// Lists the targets of a client ordered by name.
+pub async fn list_targets(
+    pool: &SqlitePool,
+    client_uuid: Uuid,
+) -> DbResult<Vec<DeliveryTargetRecord>> {
+    let records = sqlx::query_as::<_, DeliveryTargetRecord>(
+        "SELECT * FROM delivery_targets WHERE client_uuid = ?1 ORDER BY name COLLATE NOCASE, rowid",
+    )
+    .bind(client_uuid)
+    .fetch_all(pool)
+    .await?;
+    Ok(records)
+}
+
+pub async fn get_target(
+    pool: &SqlitePool,
+    target_uuid: Uuid,
+) -> DbResult<Option<DeliveryTargetRecord>> {
+    let record = sqlx::query_as::<_, DeliveryTargetRecord>(
+        "SELECT * FROM delivery_targets WHERE target_uuid = ?1",
+    )
+    .bind(target_uuid)
+    .fetch_optional(pool)
+    .await?;
+    Ok(record)
+}
+
+/// Inserts a target or replaces the name and settings of an existing one. The client of an
+/// existing target is never changed.
+pub async fn upsert_target(
+    pool: &SqlitePool,
+    args: NewDeliveryTargetArgs,
+) -> DbResult<DeliveryTargetRecord> {
+    let record = sqlx::query_as::<_, DeliveryTargetRecord>(
+        r#"
+        INSERT INTO delivery_targets (target_uuid, client_uuid, name, transport, settings_json)
+        VALUES (?1, ?2, ?3, ?4, ?5)
+        ON CONFLICT(target_uuid) DO UPDATE SET
+            name = excluded.name,
+            transport = excluded.transport,
+            settings_json = excluded.settings_json,
+            updated_at = CURRENT_TIMESTAMP
+        RETURNING *
+        "#,
+    )
+    .bind(args.target_uuid)
+    .bind(args.client_uuid)
+    .bind(&args.name)
+    .bind(&args.transport)
+    .bind(&args.settings_json)
+    .fetch_one(pool)
+    .await?;
+    Ok(record)
+}
+
+pub async fn delete_target(pool: &SqlitePool, target_uuid: Uuid) -> DbResult<bool> {
+    let result = sqlx::query("DELETE FROM delivery_targets WHERE target_uuid = ?1")
+        .bind(target_uuid)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn record_receipt(
+    pool: &SqlitePool,
+    args: NewDeliveryReceiptArgs,
+) -> DbResult<DeliveryReceiptRecord> {
+    let record = sqlx::query_as::<_, DeliveryReceiptRecord>(
+        r#"
+        INSERT INTO delivery_receipts (
+            receipt_uuid, artifact_uuid, project_uuid, target_uuid, transport, status,
+            remote_location, size_bytes, sha256, error, started_at
+        )
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
+        RETURNING *
+        "#,
+    )
+    .bind(args.receipt_uuid)
+    .bind(args.artifact_uuid)
+    .bind(args.project_uuid)
+    .bind(args.target_uuid)
+    .bind(&args.transport)
+    .bind(&args.status)
+    .bind(&args.remote_location)
+    .bind(args.size_bytes)
+    .bind(&args.sha256)
+    .bind(&args.error)
+    .bind(&args.started_at)
+    .fetch_one(pool)
+    .await?;
+    Ok(record)
+}
+
+/// Lists the receipts of a project, newest first.
+pub async fn list_receipts(
+    pool: &SqlitePool,
+    project_uuid: Uuid,
+) -> DbResult<Vec<DeliveryReceiptRecord>> {
+    let records = sqlx::query_as::<_, DeliveryReceiptRecord>(
+        "SELECT * FROM delivery_receipts WHERE project_uuid = ?1 ORDER BY completed_at DESC, rowid DESC",
+    )
+    .bind(project_uuid)
+    .fetch_all(pool)
+    .await?;
+    Ok(records)
+}
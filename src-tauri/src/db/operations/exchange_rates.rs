@@ -0,0 +1,95 @@
+//! Dated exchange-rate snapshots.
+
+use sqlx::SqlitePool;
+
+use crate::db::error::DbResult;
+use crate::db::types::{ExchangeRateRecord, NewExchangeRateArgs};
+
+/// Stores snapshots, replacing those of the same pair and date.
+pub async fn record_rates(pool: &SqlitePool, rates: &[NewExchangeRateArgs]) -> DbResult<()> {
+    let mut tx = pool.begin().await?;
+    for args in rates {
+        sqlx::query(
+            r#"
+            INSERT INTO exchange_rates (base_currency, quote_currency, rate_date, rate, source)
+            VALUES (?1, ?2, ?3, ?4, ?5)
+            ON CONFLICT (base_currency, quote_currency, rate_date) DO UPDATE SET
+                rate = excluded.rate,
+                source = excluded.source,
+                recorded_at = CURRENT_TIMESTAMP
+            "#,
+        )
+        .bind(&args.base_currency)
+        .bind(&args.quote_currency)
+        .bind(&args.rate_date)
+        .bind(args.rate)
+        .bind(&args.source)
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Deletes one snapshot; returns whether it existed.
+pub async fn delete_rate(
+    pool: &SqlitePool,
+    base_currency: &str,
+    quote_currency: &str,
+    rate_date: &str,
+) -> DbResult<bool> {
+    let result = sqlx::query(
+        r#"
+        DELETE FROM exchange_rates
+        WHERE base_currency = ?1 AND quote_currency = ?2 AND rate_date = ?3
+        "#,
+    )
+    .bind(base_currency)
+    .bind(quote_currency)
+    .bind(rate_date)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// The latest snapshot of every pair taken on or before `on_date` (`YYYY-MM-DD`).
+pub async fn rates_on(pool: &SqlitePool, on_date: &str) -> DbResult<Vec<ExchangeRateRecord>> {
+    let records = sqlx::query_as::<_, ExchangeRateRecord>(
+        r#"
+        SELECT r.*
+        FROM exchange_rates r
+        WHERE r.rate_date = (
+            SELECT MAX(x.rate_date)
+            FROM exchange_rates x
+            WHERE x.base_currency = r.base_currency
+              AND x.quote_currency = r.quote_currency
+              AND x.rate_date <= ?1
+        )
+        ORDER BY r.base_currency ASC, r.quote_currency ASC
+        "#,
+    )
+    .bind(on_date)
+    .fetch_all(pool)
+    .await?;
+    Ok(records)
+}
+
+/// Every snapshot of one pair, newest first.
+pub async fn rate_history(
+    pool: &SqlitePool,
+    base_currency: &str,
+    quote_currency: &str,
+) -> DbResult<Vec<ExchangeRateRecord>> {
+    let records = sqlx::query_as::<_, ExchangeRateRecord>(
+        r#"
+        SELECT * FROM exchange_rates
+        WHERE base_currency = ?1 AND quote_currency = ?2
+        ORDER BY rate_date DESC
+        "#,
+    )
+    .bind(base_currency)
+    .bind(quote_currency)
+    .fetch_all(pool)
+    .await?;
+    Ok(records)
+}
@@ -1,12 +1,19 @@
 //! Job operations for the refactored schema.
 
+use chrono::DateTime;
+use serde_json::json;
 use sqlx::SqlitePool;
 use uuid::Uuid;
 
+use super::outbox::enqueue_event;
 use crate::db::error::DbResult;
-use crate::db::types::{JobRecord, NewJobArgs, UpdateJobStatusArgs};
+use crate::db::types::{
+    JobDependencyRecord, JobProgressArgs, JobRecord, NewJobArgs, UpdateJobStatusArgs,
+};
+use crate::ipc::events::JOB_STATUS_CHANGED;
+use crate::jobs::progress::rolling_throughput;
 
-/// Inserts or replaces a job row.
+/// Inserts or replaces a job row. Re-queuing a job as `PENDING` clears its progress.
 pub async fn upsert_job(pool: &SqlitePool, args: NewJobArgs) -> DbResult<JobRecord> {
     let mut tx = pool.begin().await?;
 
@@ -23,7 +30,12 @@ pub async fn upsert_job(pool: &SqlitePool, args: NewJobArgs) -> DbResult<JobReco
         ON CONFLICT(artifact_uuid, job_type) DO UPDATE SET
             project_uuid = excluded.project_uuid,
             job_status = excluded.job_status,
-            error_log = excluded.error_log
+            error_log = excluded.error_log,
+            processed_units = CASE WHEN excluded.job_status = 'PENDING' THEN 0 ELSE processed_units END,
+            total_units = CASE WHEN excluded.job_status = 'PENDING' THEN NULL ELSE total_units END,
+            units_per_second = CASE WHEN excluded.job_status = 'PENDING' THEN NULL ELSE units_per_second END,
+            started_at = CASE WHEN excluded.job_status = 'PENDING' THEN NULL ELSE started_at END,
+            progress_updated_at = CASE WHEN excluded.job_status = 'PENDING' THEN NULL ELSE progress_updated_at END
         "#,
     )
     .bind(args.artifact_uuid)
@@ -34,12 +46,15 @@ pub async fn upsert_job(pool: &SqlitePool, args: NewJobArgs) -> DbResult<JobReco
     .execute(&mut *tx)
     .await?;
 
-    let record = fetch_job(&mut tx, args.artifact_uuid, &args.job_type).await?;
+    let record = fetch_job(&mut tx, args.artifact_uuid, &args.job_type)
+        .await?
+        .ok_or(sqlx::Error::RowNotFound)?;
+    enqueue_status_event(&mut tx, &record).await?;
     tx.commit().await?;
-    record.ok_or_else(|| sqlx::Error::RowNotFound.into())
+    Ok(record)
 }
 
-/// Updates job status and optional error log.
+/// Updates job status and optional error log. A job entering `RUNNING` records its start.
 pub async fn update_job_status(
     pool: &SqlitePool,
     args: UpdateJobStatusArgs,
@@ -50,7 +65,12 @@ pub async fn update_job_status(
         r#"
         UPDATE jobs
         SET job_status = ?3,
-            error_log = ?4
+            error_log = ?4,
+            started_at = CASE
+                WHEN ?3 = 'RUNNING' AND job_status <> 'RUNNING'
+                    THEN strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+                ELSE started_at
+            END
         WHERE artifact_uuid = ?1
           AND job_type = ?2
         "#,
@@ -62,6 +82,64 @@ pub async fn update_job_status(
     .execute(&mut *tx)
     .await?;
 
+    let record = fetch_job(&mut tx, args.artifact_uuid, &args.job_type).await?;
+    if let Some(record) = &record {
+        enqueue_status_event(&mut tx, record).await?;
+    }
+    tx.commit().await?;
+    Ok(record)
+}
+
+/// Stores the processed/total counters of a job and blends the rate since the previous
+/// report into its rolling throughput. Returns `None` when the job does not exist.
+pub async fn record_job_progress(
+    pool: &SqlitePool,
+    args: JobProgressArgs,
+) -> DbResult<Option<JobRecord>> {
+    let mut tx = pool.begin().await?;
+
+    let Some(current) = fetch_job(&mut tx, args.artifact_uuid, &args.job_type).await? else {
+        return Ok(None);
+    };
+    let recorded_at = args.recorded_at.to_rfc3339();
+    let last_report = current
+        .progress_updated_at
+        .as_deref()
+        .or(current.started_at.as_deref())
+        .and_then(|value| DateTime::parse_from_rfc3339(value).ok());
+    let units_per_second = match last_report {
+        Some(last_report) => {
+            let elapsed = args.recorded_at.signed_duration_since(last_report);
+            rolling_throughput(
+                current.units_per_second,
+                args.processed_units - current.processed_units,
+                elapsed.num_milliseconds() as f64 / 1000.0,
+            )
+        }
+        None => current.units_per_second,
+    };
+
+    sqlx::query(
+        r#"
+        UPDATE jobs
+        SET processed_units = ?3,
+            total_units = COALESCE(?4, total_units),
+            units_per_second = ?5,
+            started_at = COALESCE(started_at, ?6),
+            progress_updated_at = ?6
+        WHERE artifact_uuid = ?1
+          AND job_type = ?2
+        "#,
+    )
+    .bind(args.artifact_uuid)
+    .bind(&args.job_type)
+    .bind(args.processed_units)
+    .bind(args.total_units)
+    .bind(units_per_second)
+    .bind(&recorded_at)
+    .execute(&mut *tx)
+    .await?;
+
     let record = fetch_job(&mut tx, args.artifact_uuid, &args.job_type).await?;
     tx.commit().await?;
     Ok(record)
@@ -89,6 +167,89 @@ pub async fn list_jobs_for_project(
     Ok(jobs)
 }
 
+/// Lists jobs that failed, were paused or logged an error, at most `limit`.
+pub async fn list_failing_jobs(pool: &SqlitePool, limit: i64) -> DbResult<Vec<JobRecord>> {
+    let jobs: Vec<JobRecord> = sqlx::query_as(
+        r#"
+        SELECT * FROM jobs
+        WHERE job_status IN ('FAILED', 'PAUSED') OR error_log IS NOT NULL
+        ORDER BY rowid DESC
+        LIMIT ?1
+        "#,
+    )
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+    Ok(jobs)
+}
+
+/// Counts the jobs currently running, across projects.
+pub async fn count_running_jobs(pool: &SqlitePool) -> DbResult<i64> {
+    let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM jobs WHERE job_status = 'RUNNING'")
+        .fetch_one(pool)
+        .await?;
+    Ok(count)
+}
+
+/// Replaces the prerequisites of a job with `depends_on`, given as `(artifact_uuid, job_type)`.
+pub async fn replace_job_dependencies(
+    pool: &SqlitePool,
+    artifact_uuid: Uuid,
+    job_type: &str,
+    depends_on: &[(Uuid, String)],
+) -> DbResult<()> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query("DELETE FROM job_dependencies WHERE artifact_uuid = ?1 AND job_type = ?2")
+        .bind(artifact_uuid)
+        .bind(job_type)
+        .execute(&mut *tx)
+        .await?;
+
+    for (depends_on_artifact_uuid, depends_on_job_type) in depends_on {
+        sqlx::query(
+            r#"
+            INSERT OR IGNORE INTO job_dependencies (
+                artifact_uuid,
+                job_type,
+                depends_on_artifact_uuid,
+                depends_on_job_type
+            )
+            VALUES (?1, ?2, ?3, ?4)
+            "#,
+        )
+        .bind(artifact_uuid)
+        .bind(job_type)
+        .bind(depends_on_artifact_uuid)
+        .bind(depends_on_job_type)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Lists the prerequisites of every job of a project.
+pub async fn list_job_dependencies_for_project(
+    pool: &SqlitePool,
+    project_uuid: Uuid,
+) -> DbResult<Vec<JobDependencyRecord>> {
+    let dependencies: Vec<JobDependencyRecord> = sqlx::query_as(
+        r#"
+        SELECT d.*
+        FROM job_dependencies d
+        JOIN jobs j ON j.artifact_uuid = d.artifact_uuid AND j.job_type = d.job_type
+        WHERE j.project_uuid = ?1
+        ORDER BY d.artifact_uuid, d.job_type
+        "#,
+    )
+    .bind(project_uuid)
+    .fetch_all(pool)
+    .await?;
+    Ok(dependencies)
+}
+
 async fn fetch_job(
     tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
     artifact_uuid: Uuid,
@@ -103,3 +264,17 @@ async fn fetch_job(
     .await?;
     Ok(record)
 }
+
+async fn enqueue_status_event(
+    tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+    record: &JobRecord,
+) -> DbResult<()> {
+    let payload = json!({
+        "artifactUuid": record.artifact_uuid.to_string(),
+        "jobType": record.job_type,
+        "projectUuid": record.project_uuid.to_string(),
+        "jobStatus": record.job_status,
+        "errorLog": record.error_log,
+    });
+    enqueue_event(tx, JOB_STATUS_CHANGED, &payload).await
+}
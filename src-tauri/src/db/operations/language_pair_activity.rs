@@ -0,0 +1,61 @@
+//! Per language pair aggregates across all projects.
+
+use sqlx::SqlitePool;
+
+use crate::db::error::DbResult;
+use crate::db::types::LanguagePairActivityRecord;
+
+/// Lists every language pair used by a project or present in the translation memory, with
+/// its project count, delivery turnaround and TM size. Language codes are compared and
+/// returned lowercased, as the translation memory stores them.
+pub async fn list_activity(pool: &SqlitePool) -> DbResult<Vec<LanguagePairActivityRecord>> {
+    let records = sqlx::query_as::<_, LanguagePairActivityRecord>(
+        r#"
+        WITH pairs AS (
+            SELECT lower(source_lang) AS source_lang, lower(target_lang) AS target_lang,
+                   project_uuid
+            FROM project_language_pairs
+        ),
+        deliveries AS (
+            SELECT lower(d.source_lang) AS source_lang, lower(d.target_lang) AS target_lang,
+                   COUNT(*) AS delivered_packages,
+                   AVG((julianday(d.delivered_at) - julianday(p.creation_date)) * 24.0)
+                       AS average_turnaround_hours
+            FROM delivery_packages d
+            JOIN projects p ON p.project_uuid = d.project_uuid
+            WHERE d.delivered_at IS NOT NULL
+            GROUP BY 1, 2
+        ),
+        memory AS (
+            SELECT lower(source_lang) AS source_lang, lower(target_lang) AS target_lang,
+                   COUNT(*) AS tm_entries
+            FROM translation_memory_entries
+            GROUP BY 1, 2
+        ),
+        keys AS (
+            SELECT source_lang, target_lang FROM pairs
+            UNION
+            SELECT source_lang, target_lang FROM memory
+        )
+        SELECT
+            k.source_lang,
+            k.target_lang,
+            (
+                SELECT COUNT(DISTINCT pairs.project_uuid) FROM pairs
+                WHERE pairs.source_lang = k.source_lang AND pairs.target_lang = k.target_lang
+            ) AS project_count,
+            COALESCE(d.delivered_packages, 0) AS delivered_packages,
+            d.average_turnaround_hours,
+            COALESCE(m.tm_entries, 0) AS tm_entries
+        FROM keys k
+        LEFT JOIN deliveries d
+            ON d.source_lang = k.source_lang AND d.target_lang = k.target_lang
+        LEFT JOIN memory m
+            ON m.source_lang = k.source_lang AND m.target_lang = k.target_lang
+        ORDER BY k.source_lang, k.target_lang
+        "#,
+    )
+    .fetch_all(pool)
+    .await?;
+    Ok(records)
+}
@@ -1,18 +1,52 @@
 //! Database operations grouped by their primary entity or workflow.
 
+pub mod alignments;
 pub mod artifacts;
 pub mod artifacts_v2;
+pub mod automation_scripts;
 pub mod clients;
 pub mod conversions;
+pub mod delivery_gate_overrides;
+pub mod delivery_packages;
+pub mod delivery_transports;
+pub mod exchange_rates;
 pub mod file_targets;
 pub mod jobs;
 pub mod jobs_v2;
+pub mod language_pair_activity;
 pub mod language_pairs;
 pub mod notes;
+pub mod offline_operations;
+pub mod outbound_requests;
+pub mod outbox;
+pub mod output_naming;
+pub mod pipeline_presets;
+pub mod plugins;
 pub mod project_files;
+pub mod project_health;
 pub mod projects;
 pub mod projects_v2;
+pub mod prompt_templates;
+pub mod protected_terms;
+pub mod protection_rules;
+pub mod provider_cache;
+pub mod provider_comparisons;
+pub mod provider_rate_limits;
+pub mod provider_routing_rules;
+pub mod provider_usage;
+pub mod qa_findings;
+pub mod qa_profiles;
 pub mod reference;
+pub mod saved_segment_filters;
+pub mod scheduled_tasks;
+pub mod segment_confirmations;
+pub mod segment_references;
+pub mod segment_revisions;
+pub mod segmentation_exceptions;
+pub mod subject_taxonomy;
+pub mod terminology;
+pub mod tm_assignments;
 pub mod translation_jobs;
+pub mod translation_memory;
 pub mod users;
 pub mod validations;
@@ -1,67 +1,166 @@
 //! Operations for managing project notes.
+//!
+//! Notes are listed pinned first, then newest first. Every time a body is saved the
+//! `@handles` in it are extracted into `note_mentions` and resolved against usernames, so
+//! a user can be shown the notes that mention them. In Markdown notes, handles inside code
+//! spans and fenced code blocks are not mentions.
 
+use sqlx::{Sqlite, SqlitePool, Transaction};
 use uuid::Uuid;
 
 use crate::db::builders::build_note;
 use crate::db::error::{DbError, DbResult};
 use crate::db::manager::DbManager;
-use crate::db::types::Note;
+use crate::db::types::{NewNoteArgs, Note, NoteMention, NotePage, UpdateNoteArgs};
 use crate::db::utils::now_iso8601;
 
+const NOTE_COLUMNS: &str =
+    "note_id, project_id, author_user_id, body, is_markdown, pinned, created_at, updated_at";
+
 impl DbManager {
     /// Inserts a note for the specified project.
-    pub async fn insert_note(
-        &self,
-        project_id: Uuid,
-        author_user_id: &str,
-        body: &str,
-    ) -> DbResult<Note> {
+    pub async fn insert_note(&self, args: NewNoteArgs) -> DbResult<Note> {
         let _guard = self.write_lock.lock().await;
         let pool = self.pool().await;
         let note_id = Uuid::new_v4();
         let now = now_iso8601();
 
-        let row = sqlx::query(
+        let mut tx = pool.begin().await?;
+        let row = sqlx::query(&format!(
             "INSERT INTO notes (
                  note_id,
                  project_id,
                  author_user_id,
                  body,
+                 is_markdown,
+                 pinned,
                  created_at
              )
-             VALUES (?1, ?2, ?3, ?4, ?5)
-             RETURNING note_id, project_id, author_user_id, body, created_at",
-        )
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+             RETURNING {NOTE_COLUMNS}"
+        ))
         .bind(&note_id.to_string())
-        .bind(&project_id.to_string())
-        .bind(author_user_id)
-        .bind(body)
+        .bind(&args.project_id.to_string())
+        .bind(&args.author_user_id)
+        .bind(&args.body)
+        .bind(args.is_markdown)
+        .bind(args.pinned)
+        .bind(&now)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let mut note = build_note(&row)?;
+        note.mentions = replace_mentions(&mut tx, &note).await?;
+        tx.commit().await?;
+        Ok(note)
+    }
+
+    /// Applies the given changes to a note. Returns `None` when the note does not exist.
+    pub async fn update_note(&self, args: UpdateNoteArgs) -> DbResult<Option<Note>> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        let now = now_iso8601();
+
+        let mut tx = pool.begin().await?;
+        let row = sqlx::query(&format!(
+            "UPDATE notes
+             SET body = COALESCE(?2, body),
+                 is_markdown = COALESCE(?3, is_markdown),
+                 pinned = COALESCE(?4, pinned),
+                 updated_at = ?5
+             WHERE note_id = ?1
+             RETURNING {NOTE_COLUMNS}"
+        ))
+        .bind(&args.note_id.to_string())
+        .bind(args.body.as_deref())
+        .bind(args.is_markdown)
+        .bind(args.pinned)
         .bind(&now)
-        .fetch_one(&pool)
+        .fetch_optional(&mut *tx)
         .await?;
+        let Some(row) = row else {
+            return Ok(None);
+        };
 
-        build_note(&row)
+        let mut note = build_note(&row)?;
+        note.mentions = if args.body.is_some() || args.is_markdown.is_some() {
+            replace_mentions(&mut tx, &note).await?
+        } else {
+            fetch_mentions(&mut tx, note.note_id).await?
+        };
+        tx.commit().await?;
+        Ok(Some(note))
     }
 
-    /// Lists notes for a project ordered from newest to oldest.
-    pub async fn list_notes_for_project(&self, project_id: Uuid) -> DbResult<Vec<Note>> {
+    /// Retrieves a note by identifier.
+    pub async fn get_note(&self, note_id: Uuid) -> DbResult<Option<Note>> {
         let pool = self.pool().await;
-        let rows = sqlx::query(
-            "SELECT note_id, project_id, author_user_id, body, created_at
+        let mut tx = pool.begin().await?;
+        let row = sqlx::query(&format!(
+            "SELECT {NOTE_COLUMNS} FROM notes WHERE note_id = ?1"
+        ))
+        .bind(&note_id.to_string())
+        .fetch_optional(&mut *tx)
+        .await?;
+        let note = match row {
+            Some(row) => {
+                let mut note = build_note(&row)?;
+                note.mentions = fetch_mentions(&mut tx, note_id).await?;
+                Some(note)
+            }
+            None => None,
+        };
+        tx.commit().await?;
+        Ok(note)
+    }
+
+    /// Lists a page of a project's notes, pinned notes first, then from newest to oldest.
+    pub async fn list_notes_for_project(
+        &self,
+        project_id: Uuid,
+        limit: i64,
+        offset: i64,
+    ) -> DbResult<NotePage> {
+        let pool = self.pool().await;
+        let total: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM notes WHERE project_id = ?1")
+            .bind(&project_id.to_string())
+            .fetch_one(&pool)
+            .await?;
+        let rows = sqlx::query(&format!(
+            "SELECT {NOTE_COLUMNS}
              FROM notes
              WHERE project_id = ?1
-             ORDER BY created_at DESC",
-        )
+             ORDER BY pinned DESC, created_at DESC, note_id ASC
+             LIMIT ?2 OFFSET ?3"
+        ))
         .bind(&project_id.to_string())
+        .bind(limit)
+        .bind(offset)
         .fetch_all(&pool)
         .await?;
 
-        let mut notes = Vec::with_capacity(rows.len());
-        for row in rows {
-            notes.push(build_note(&row)?);
-        }
+        Ok(NotePage {
+            total,
+            notes: hydrate_notes(&pool, rows).await?,
+        })
+    }
+
+    /// Lists the notes mentioning a user, from newest to oldest, across projects.
+    pub async fn list_notes_mentioning(&self, user_uuid: Uuid, limit: i64) -> DbResult<Vec<Note>> {
+        let pool = self.pool().await;
+        let rows = sqlx::query(&format!(
+            "SELECT {NOTE_COLUMNS}
+             FROM notes
+             WHERE note_id IN (SELECT note_id FROM note_mentions WHERE user_uuid = ?1)
+             ORDER BY created_at DESC, note_id ASC
+             LIMIT ?2"
+        ))
+        .bind(&user_uuid.to_string())
+        .bind(limit)
+        .fetch_all(&pool)
+        .await?;
 
-        Ok(notes)
+        hydrate_notes(&pool, rows).await
     }
 
     /// Deletes a note by identifier.
@@ -81,3 +180,135 @@ impl DbManager {
         Ok(())
     }
 }
+
+/// The `@handles` of a note body, lowercased, without `@` and in order of first use. An
+/// `@` inside a word (as in an email address) does not start a mention; trailing `.` and
+/// `-` belong to the sentence, not the handle.
+fn extract_mentions(body: &str, is_markdown: bool) -> Vec<String> {
+    let mut mentions: Vec<String> = Vec::new();
+    let mut in_fence = false;
+    for line in body.lines() {
+        if is_markdown && line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+
+        let mut in_code = false;
+        let mut previous: Option<char> = None;
+        let mut chars = line.char_indices().peekable();
+        while let Some((offset, ch)) = chars.next() {
+            if is_markdown && ch == '`' {
+                in_code = !in_code;
+            } else if ch == '@'
+                && !in_code
+                && !previous.is_some_and(|previous| previous.is_alphanumeric() || previous == '_')
+            {
+                let rest = &line[offset + 1..];
+                let length = rest
+                    .find(|ch: char| !(ch.is_alphanumeric() || matches!(ch, '.' | '_' | '-')))
+                    .unwrap_or(rest.len());
+                let handle = rest[..length].trim_end_matches(['.', '-']);
+                if !handle.is_empty() {
+                    let handle = handle.to_lowercase();
+                    if !mentions.contains(&handle) {
+                        mentions.push(handle);
+                    }
+                }
+                while chars
+                    .peek()
+                    .is_some_and(|(next, _)| *next <= offset + length)
+                {
+                    chars.next();
+                }
+            }
+            previous = Some(ch);
+        }
+    }
+    mentions
+}
+
+/// Replaces the stored mentions of a note with those in its body.
+async fn replace_mentions(
+    tx: &mut Transaction<'_, Sqlite>,
+    note: &Note,
+) -> DbResult<Vec<NoteMention>> {
+    let note_id = note.note_id.to_string();
+    sqlx::query("DELETE FROM note_mentions WHERE note_id = ?1")
+        .bind(&note_id)
+        .execute(&mut **tx)
+        .await?;
+    for mention in extract_mentions(&note.body, note.is_markdown) {
+        sqlx::query(
+            "INSERT INTO note_mentions (note_id, mention, user_uuid)
+             VALUES (
+                 ?1,
+                 ?2,
+                 (SELECT user_uuid FROM users WHERE username = ?2 COLLATE NOCASE
+                  ORDER BY user_uuid LIMIT 1)
+             )",
+        )
+        .bind(&note_id)
+        .bind(&mention)
+        .execute(&mut **tx)
+        .await?;
+    }
+    fetch_mentions(tx, note.note_id).await
+}
+
+async fn fetch_mentions(
+    tx: &mut Transaction<'_, Sqlite>,
+    note_id: Uuid,
+) -> DbResult<Vec<NoteMention>> {
+    let rows: Vec<(String, Option<String>)> = sqlx::query_as(
+        "SELECT mention, user_uuid FROM note_mentions WHERE note_id = ?1 ORDER BY rowid",
+    )
+    .bind(&note_id.to_string())
+    .fetch_all(&mut **tx)
+    .await?;
+    rows.into_iter()
+        .map(|(mention, user_uuid)| {
+            let user_uuid = user_uuid
+                .map(|raw| Uuid::parse_str(&raw).map_err(|_| DbError::InvalidUuid(raw)))
+                .transpose()?;
+            Ok(NoteMention { mention, user_uuid })
+        })
+        .collect()
+}
+
+async fn hydrate_notes(
+    pool: &SqlitePool,
+    rows: Vec<sqlx::sqlite::SqliteRow>,
+) -> DbResult<Vec<Note>> {
+    let mut tx = pool.begin().await?;
+    let mut notes = Vec::with_capacity(rows.len());
+    for row in rows {
+        let mut note = build_note(&row)?;
+        note.mentions = fetch_mentions(&mut tx, note.note_id).await?;
+        notes.push(note);
+    }
+    tx.commit().await?;
+    Ok(notes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::extract_mentions;
+
+    #[test]
+    fn mentions_skip_email_addresses_and_markdown_code() {
+        let body = "Thanks @Anna.Meier and @bob-, see mail@example.com.\n\
+                    `@not_this` but @anna.meier again.\n\
+                    ```\n@nor_this\n```\n@carla_r: done.";
+        assert_eq!(
+            extract_mentions(body, true),
+            vec!["anna.meier", "bob", "carla_r"]
+        );
+        assert_eq!(
+            extract_mentions(body, false),
+            vec!["anna.meier", "bob", "not_this", "nor_this", "carla_r"]
+        );
+    }
+}
@@ -0,0 +1,47 @@
+//! Outbound operations requested while offline, kept until they are replayed or discarded.
+
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+use crate::db::error::DbResult;
+use crate::db::types::{NewOfflineOperationArgs, OfflineOperationRecord};
+
+/// Records an operation to run once the app is back online.
+pub async fn queue_operation(
+    pool: &SqlitePool,
+    args: NewOfflineOperationArgs,
+) -> DbResult<OfflineOperationRecord> {
+    let record = sqlx::query_as::<_, OfflineOperationRecord>(
+        r#"
+        INSERT INTO offline_operations (operation_uuid, operation, payload_json, project_uuid)
+        VALUES (?1, ?2, ?3, ?4)
+        RETURNING *
+        "#,
+    )
+    .bind(Uuid::new_v4())
+    .bind(&args.operation)
+    .bind(&args.payload_json)
+    .bind(args.project_uuid)
+    .fetch_one(pool)
+    .await?;
+    Ok(record)
+}
+
+/// Lists the queued operations, oldest first.
+pub async fn list_operations(pool: &SqlitePool) -> DbResult<Vec<OfflineOperationRecord>> {
+    let records = sqlx::query_as::<_, OfflineOperationRecord>(
+        "SELECT * FROM offline_operations ORDER BY created_at ASC, rowid ASC",
+    )
+    .fetch_all(pool)
+    .await?;
+    Ok(records)
+}
+
+/// Deletes a queued operation. Returns whether one was removed.
+pub async fn delete_operation(pool: &SqlitePool, operation_uuid: Uuid) -> DbResult<bool> {
+    let result = sqlx::query("DELETE FROM offline_operations WHERE operation_uuid = ?1")
+        .bind(operation_uuid)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
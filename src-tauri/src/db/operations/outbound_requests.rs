@@ -0,0 +1,58 @@
+//! Audit of the requests that sent document text to translation providers.
+
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+use crate::db::error::DbResult;
+use crate::db::types::{NewOutboundRequestArgs, OutboundRequestRecord};
+
+/// Records a request that sent document text to a provider.
+pub async fn record_request(
+    pool: &SqlitePool,
+    args: NewOutboundRequestArgs,
+) -> DbResult<OutboundRequestRecord> {
+    let record = sqlx::query_as::<_, OutboundRequestRecord>(
+        r#"
+        INSERT INTO outbound_requests (
+            request_uuid,
+            provider,
+            project_uuid,
+            segment_count,
+            char_count,
+            destination_host
+        )
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+        RETURNING *
+        "#,
+    )
+    .bind(Uuid::new_v4())
+    .bind(&args.provider)
+    .bind(args.project_uuid)
+    .bind(args.segment_count)
+    .bind(args.char_count)
+    .bind(&args.destination_host)
+    .fetch_one(pool)
+    .await?;
+    Ok(record)
+}
+
+/// Lists up to `limit` requests, newest first, of `project_uuid` or of every project.
+pub async fn list_requests(
+    pool: &SqlitePool,
+    project_uuid: Option<Uuid>,
+    limit: i64,
+) -> DbResult<Vec<OutboundRequestRecord>> {
+    let records = sqlx::query_as::<_, OutboundRequestRecord>(
+        r#"
+        SELECT * FROM outbound_requests
+        WHERE ?1 IS NULL OR project_uuid = ?1
+        ORDER BY sent_at DESC, rowid DESC
+        LIMIT ?2
+        "#,
+    )
+    .bind(project_uuid)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+    Ok(records)
+}
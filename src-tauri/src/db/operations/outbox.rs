@@ -0,0 +1,189 @@
+//! Transactional outbox of domain events.
+//!
+//! State changes call [`enqueue_event`] inside their own transaction, so an event exists
+//! exactly when its change was committed. The dispatcher reads due events, publishes them
+//! and marks them delivered; a failed publish is retried later, which makes delivery
+//! at-least-once.
+
+use serde::Serialize;
+use sqlx::{Sqlite, SqlitePool, Transaction};
+
+use crate::db::error::DbResult;
+use crate::db::types::OutboxEventRecord;
+
+/// Adds an event to the outbox as part of `tx`.
+pub async fn enqueue_event(
+    tx: &mut Transaction<'_, Sqlite>,
+    topic: &str,
+    payload: &impl Serialize,
+) -> DbResult<()> {
+    let payload_json = serde_json::to_string(payload)?;
+    sqlx::query("INSERT INTO event_outbox (topic, payload_json) VALUES (?1, ?2)")
+        .bind(topic)
+        .bind(payload_json)
+        .execute(&mut **tx)
+        .await?;
+    Ok(())
+}
+
+/// Adds an event that is not tied to a change of the database, in its own transaction.
+pub async fn publish_event(
+    pool: &SqlitePool,
+    topic: &str,
+    payload: &impl Serialize,
+) -> DbResult<()> {
+    let mut tx = pool.begin().await?;
+    enqueue_event(&mut tx, topic, payload).await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Lists undelivered events whose retry delay has passed, oldest first, at most `limit`.
+pub async fn list_due_events(pool: &SqlitePool, limit: i64) -> DbResult<Vec<OutboxEventRecord>> {
+    let events = sqlx::query_as::<_, OutboxEventRecord>(
+        r#"
+        SELECT * FROM event_outbox
+        WHERE delivered_at IS NULL
+          AND (next_attempt_at IS NULL OR next_attempt_at <= datetime('now'))
+        ORDER BY event_id ASC
+        LIMIT ?1
+        "#,
+    )
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+    Ok(events)
+}
+
+/// Lists events after `after_event_id`, delivered or not, oldest first, at most `limit`.
+pub async fn list_events_after(
+    pool: &SqlitePool,
+    after_event_id: i64,
+    limit: i64,
+) -> DbResult<Vec<OutboxEventRecord>> {
+    let events = sqlx::query_as::<_, OutboxEventRecord>(
+        "SELECT * FROM event_outbox WHERE event_id > ?1 ORDER BY event_id ASC LIMIT ?2",
+    )
+    .bind(after_event_id)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+    Ok(events)
+}
+
+/// Marks an event as published.
+pub async fn mark_event_delivered(pool: &SqlitePool, event_id: i64) -> DbResult<()> {
+    sqlx::query(
+        r#"
+        UPDATE event_outbox
+        SET delivered_at = CURRENT_TIMESTAMP,
+            attempts = attempts + 1,
+            last_error = NULL
+        WHERE event_id = ?1
+        "#,
+    )
+    .bind(event_id)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Records a failed publish and holds the event back for `retry_in_secs`.
+pub async fn record_event_failure(
+    pool: &SqlitePool,
+    event_id: i64,
+    error: &str,
+    retry_in_secs: i64,
+) -> DbResult<()> {
+    sqlx::query(
+        r#"
+        UPDATE event_outbox
+        SET attempts = attempts + 1,
+            last_error = ?2,
+            next_attempt_at = datetime('now', '+' || ?3 || ' seconds')
+        WHERE event_id = ?1
+        "#,
+    )
+    .bind(event_id)
+    .bind(error)
+    .bind(retry_in_secs)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Deletes events delivered more than `older_than_days` ago. Returns how many were removed.
+pub async fn purge_delivered_events(pool: &SqlitePool, older_than_days: i64) -> DbResult<u64> {
+    let result = sqlx::query(
+        r#"
+        DELETE FROM event_outbox
+        WHERE delivered_at IS NOT NULL
+          AND delivered_at < datetime('now', '-' || ?1 || ' days')
+        "#,
+    )
+    .bind(older_than_days)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    use super::*;
+    use crate::db::schema::initialise_schema;
+
+    #[tokio::test]
+    async fn enqueued_events_stay_due_until_delivered() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect(":memory:")
+            .await
+            .expect("expected in-memory database");
+        initialise_schema(&pool)
+            .await
+            .expect("expected schema bootstrap to succeed");
+
+        let mut tx = pool.begin().await.unwrap();
+        enqueue_event(
+            &mut tx,
+            "jobs://status_changed",
+            &serde_json::json!({"n": 1}),
+        )
+        .await
+        .unwrap();
+        tx.rollback().await.unwrap();
+        assert!(list_due_events(&pool, 10).await.unwrap().is_empty());
+
+        let mut tx = pool.begin().await.unwrap();
+        for n in [1, 2] {
+            enqueue_event(
+                &mut tx,
+                "jobs://status_changed",
+                &serde_json::json!({"n": n}),
+            )
+            .await
+            .unwrap();
+        }
+        tx.commit().await.unwrap();
+
+        let due = list_due_events(&pool, 10).await.unwrap();
+        assert_eq!(due.len(), 2);
+        assert_eq!(due[0].payload_json, r#"{"n":1}"#);
+
+        record_event_failure(&pool, due[0].event_id, "no listener", 60)
+            .await
+            .unwrap();
+        mark_event_delivered(&pool, due[1].event_id).await.unwrap();
+        assert!(list_due_events(&pool, 10).await.unwrap().is_empty());
+
+        record_event_failure(&pool, due[0].event_id, "no listener", 0)
+            .await
+            .unwrap();
+        let retried = list_due_events(&pool, 10).await.unwrap();
+        assert_eq!(retried.len(), 1);
+        assert_eq!(retried[0].attempts, 2);
+        assert_eq!(retried[0].last_error.as_deref(), Some("no listener"));
+    }
+}
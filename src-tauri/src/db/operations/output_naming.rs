@@ -0,0 +1,118 @@
+//! Project output naming overrides, including the layout of the Translations folder, and
+//! the output names recorded for conversions.
+
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+use crate::db::error::DbResult;
+use crate::db::types::{
+    ConversionOutputNameRecord, NewConversionOutputNameArgs, ProjectOutputNamingRecord,
+};
+use crate::db::utils::now_iso8601;
+
+/// Fetches a project's naming overrides, if it has any.
+pub async fn get_project_naming(
+    pool: &SqlitePool,
+    project_uuid: Uuid,
+) -> DbResult<Option<ProjectOutputNamingRecord>> {
+    let record = sqlx::query_as::<_, ProjectOutputNamingRecord>(
+        "SELECT * FROM project_output_naming WHERE project_uuid = ?1",
+    )
+    .bind(project_uuid)
+    .fetch_optional(pool)
+    .await?;
+    Ok(record)
+}
+
+/// Replaces a project's naming overrides; clearing all of them removes the row.
+pub async fn set_project_naming(
+    pool: &SqlitePool,
+    project_uuid: Uuid,
+    xliff_template: Option<&str>,
+    jliff_template: Option<&str>,
+    translations_layout: Option<&str>,
+) -> DbResult<Option<ProjectOutputNamingRecord>> {
+    if xliff_template.is_none() && jliff_template.is_none() && translations_layout.is_none() {
+        sqlx::query("DELETE FROM project_output_naming WHERE project_uuid = ?1")
+            .bind(project_uuid)
+            .execute(pool)
+            .await?;
+        return Ok(None);
+    }
+
+    let record = sqlx::query_as::<_, ProjectOutputNamingRecord>(
+        r#"
+        INSERT INTO project_output_naming (
+            project_uuid, xliff_template, jliff_template, translations_layout, updated_at
+        )
+        VALUES (?1, ?2, ?3, ?4, ?5)
+        ON CONFLICT (project_uuid) DO UPDATE SET
+            xliff_template = excluded.xliff_template,
+            jliff_template = excluded.jliff_template,
+            translations_layout = excluded.translations_layout,
+            updated_at = excluded.updated_at
+        RETURNING *
+        "#,
+    )
+    .bind(project_uuid)
+    .bind(xliff_template)
+    .bind(jliff_template)
+    .bind(translations_layout)
+    .bind(now_iso8601())
+    .fetch_one(pool)
+    .await?;
+    Ok(Some(record))
+}
+
+/// Lists the output names recorded for a project's conversions.
+pub async fn list_output_names(
+    pool: &SqlitePool,
+    project_uuid: Uuid,
+) -> DbResult<Vec<ConversionOutputNameRecord>> {
+    let records = sqlx::query_as::<_, ConversionOutputNameRecord>(
+        r#"
+        SELECT * FROM conversion_output_names
+        WHERE project_uuid = ?1
+        ORDER BY xliff_rel_path ASC
+        "#,
+    )
+    .bind(project_uuid)
+    .fetch_all(pool)
+    .await?;
+    Ok(records)
+}
+
+/// Records the output names of a file and language pair, replacing earlier ones.
+pub async fn upsert_output_name(
+    pool: &SqlitePool,
+    args: &NewConversionOutputNameArgs,
+) -> DbResult<ConversionOutputNameRecord> {
+    let record = sqlx::query_as::<_, ConversionOutputNameRecord>(
+        r#"
+        INSERT INTO conversion_output_names (
+            project_uuid, file_uuid, source_lang, target_lang,
+            xliff_template, xliff_rel_path, jliff_template, jliff_prefix, created_at
+        )
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+        ON CONFLICT (project_uuid, file_uuid, source_lang, target_lang) DO UPDATE SET
+            xliff_template = excluded.xliff_template,
+            xliff_rel_path = excluded.xliff_rel_path,
+            jliff_template = excluded.jliff_template,
+            jliff_prefix = excluded.jliff_prefix,
+            created_at = excluded.created_at
+        RETURNING *
+        "#,
+    )
+    .bind(args.project_uuid)
+    .bind(args.file_uuid)
+    .bind(&args.source_lang)
+    .bind(&args.target_lang)
+    .bind(&args.xliff_template)
+    .bind(&args.xliff_rel_path)
+    .bind(&args.jliff_template)
+    .bind(&args.jliff_prefix)
+    .bind(now_iso8601())
+    .fetch_one(pool)
+    .await?;
+    Ok(record)
+}
@@ -0,0 +1,132 @@
+//! Pipeline preset storage, project type defaults and project assignments.
+
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+use crate::db::error::DbResult;
+use crate::db::types::{NewPipelinePresetArgs, PipelinePresetRecord};
+
+/// Stores a preset under its name, replacing an existing one. A preset made the default of a
+/// project type takes that role over from the previous default.
+pub async fn upsert_preset(
+    pool: &SqlitePool,
+    args: NewPipelinePresetArgs,
+) -> DbResult<PipelinePresetRecord> {
+    let mut tx = pool.begin().await?;
+    if let Some(project_type) = &args.project_type {
+        sqlx::query(
+            r#"
+            UPDATE pipeline_presets
+            SET project_type = NULL, updated_at = CURRENT_TIMESTAMP
+            WHERE project_type = ?1 AND name <> ?2
+            "#,
+        )
+        .bind(project_type)
+        .bind(&args.name)
+        .execute(&mut *tx)
+        .await?;
+    }
+    let record = sqlx::query_as::<_, PipelinePresetRecord>(
+        r#"
+        INSERT INTO pipeline_presets (preset_uuid, name, description, project_type, definition_json)
+        VALUES (?1, ?2, ?3, ?4, ?5)
+        ON CONFLICT (name) DO UPDATE SET
+            description = excluded.description,
+            project_type = excluded.project_type,
+            definition_json = excluded.definition_json,
+            updated_at = CURRENT_TIMESTAMP
+        RETURNING *
+        "#,
+    )
+    .bind(Uuid::new_v4())
+    .bind(&args.name)
+    .bind(&args.description)
+    .bind(&args.project_type)
+    .bind(&args.definition_json)
+    .fetch_one(&mut *tx)
+    .await?;
+    tx.commit().await?;
+    Ok(record)
+}
+
+/// Fetches a preset by id.
+pub async fn get_preset(
+    pool: &SqlitePool,
+    preset_uuid: Uuid,
+) -> DbResult<Option<PipelinePresetRecord>> {
+    let record = sqlx::query_as::<_, PipelinePresetRecord>(
+        "SELECT * FROM pipeline_presets WHERE preset_uuid = ?1 LIMIT 1",
+    )
+    .bind(preset_uuid)
+    .fetch_optional(pool)
+    .await?;
+    Ok(record)
+}
+
+/// Lists all presets by name.
+pub async fn list_presets(pool: &SqlitePool) -> DbResult<Vec<PipelinePresetRecord>> {
+    let records = sqlx::query_as::<_, PipelinePresetRecord>(
+        "SELECT * FROM pipeline_presets ORDER BY name ASC",
+    )
+    .fetch_all(pool)
+    .await?;
+    Ok(records)
+}
+
+/// Deletes a preset; projects it was assigned to fall back to their type's default.
+/// Returns whether a preset was removed.
+pub async fn delete_preset(pool: &SqlitePool, preset_uuid: Uuid) -> DbResult<bool> {
+    let result = sqlx::query("DELETE FROM pipeline_presets WHERE preset_uuid = ?1")
+        .bind(preset_uuid)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Assigns a preset to a project, replacing its previous one. `None` removes the assignment.
+pub async fn assign_preset(
+    pool: &SqlitePool,
+    project_uuid: Uuid,
+    preset_uuid: Option<Uuid>,
+) -> DbResult<()> {
+    let mut tx = pool.begin().await?;
+    sqlx::query("DELETE FROM project_pipeline_presets WHERE project_uuid = ?1")
+        .bind(project_uuid)
+        .execute(&mut *tx)
+        .await?;
+    if let Some(preset_uuid) = preset_uuid {
+        sqlx::query(
+            "INSERT INTO project_pipeline_presets (project_uuid, preset_uuid) VALUES (?1, ?2)",
+        )
+        .bind(project_uuid)
+        .bind(preset_uuid)
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Preset governing a project: its own assignment, otherwise the default of its type.
+pub async fn preset_for_project(
+    pool: &SqlitePool,
+    project_uuid: Uuid,
+) -> DbResult<Option<PipelinePresetRecord>> {
+    let record = sqlx::query_as::<_, PipelinePresetRecord>(
+        r#"
+        SELECT presets.*
+        FROM pipeline_presets AS presets
+        LEFT JOIN project_pipeline_presets AS assignments
+            ON assignments.preset_uuid = presets.preset_uuid AND assignments.project_uuid = ?1
+        LEFT JOIN projects
+            ON projects.project_uuid = ?1 AND projects.type = presets.project_type
+        WHERE assignments.project_uuid IS NOT NULL OR projects.project_uuid IS NOT NULL
+        ORDER BY assignments.project_uuid IS NULL
+        LIMIT 1
+        "#,
+    )
+    .bind(project_uuid)
+    .fetch_optional(pool)
+    .await?;
+    Ok(record)
+}
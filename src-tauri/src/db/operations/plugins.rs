@@ -0,0 +1,97 @@
+//! Registered plugins and their stored settings.
+
+use sqlx::SqlitePool;
+
+use crate::db::error::DbResult;
+use crate::db::types::{PluginRecord, PluginSettingRecord};
+use crate::db::utils::now_iso8601;
+
+/// Registers a plugin, or updates the folder and manifest of the one with the same id.
+pub async fn upsert_plugin(
+    pool: &SqlitePool,
+    plugin_id: &str,
+    plugin_dir: &str,
+    manifest_json: &str,
+) -> DbResult<PluginRecord> {
+    let now = now_iso8601();
+    let record = sqlx::query_as::<_, PluginRecord>(
+        r#"
+        INSERT INTO plugins (plugin_id, plugin_dir, manifest_json, registered_at, updated_at)
+        VALUES (?1, ?2, ?3, ?4, ?4)
+        ON CONFLICT (plugin_id) DO UPDATE SET
+            plugin_dir = excluded.plugin_dir,
+            manifest_json = excluded.manifest_json,
+            updated_at = excluded.updated_at
+        RETURNING *
+        "#,
+    )
+    .bind(plugin_id)
+    .bind(plugin_dir)
+    .bind(manifest_json)
+    .bind(now)
+    .fetch_one(pool)
+    .await?;
+    Ok(record)
+}
+
+pub async fn get_plugin(pool: &SqlitePool, plugin_id: &str) -> DbResult<Option<PluginRecord>> {
+    let record = sqlx::query_as::<_, PluginRecord>("SELECT * FROM plugins WHERE plugin_id = ?1")
+        .bind(plugin_id)
+        .fetch_optional(pool)
+        .await?;
+    Ok(record)
+}
+
+pub async fn list_plugins(pool: &SqlitePool) -> DbResult<Vec<PluginRecord>> {
+    let records = sqlx::query_as::<_, PluginRecord>("SELECT * FROM plugins ORDER BY plugin_id ASC")
+        .fetch_all(pool)
+        .await?;
+    Ok(records)
+}
+
+/// Removes a plugin and its settings.
+pub async fn delete_plugin(pool: &SqlitePool, plugin_id: &str) -> DbResult<bool> {
+    let result = sqlx::query("DELETE FROM plugins WHERE plugin_id = ?1")
+        .bind(plugin_id)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn list_plugin_settings(
+    pool: &SqlitePool,
+    plugin_id: &str,
+) -> DbResult<Vec<PluginSettingRecord>> {
+    let records = sqlx::query_as::<_, PluginSettingRecord>(
+        "SELECT * FROM plugin_settings WHERE plugin_id = ?1 ORDER BY setting_key ASC",
+    )
+    .bind(plugin_id)
+    .fetch_all(pool)
+    .await?;
+    Ok(records)
+}
+
+/// Replaces the stored settings of a plugin with `values`, given as `(key, JSON value)`.
+pub async fn replace_plugin_settings(
+    pool: &SqlitePool,
+    plugin_id: &str,
+    values: &[(String, String)],
+) -> DbResult<Vec<PluginSettingRecord>> {
+    let mut tx = pool.begin().await?;
+    sqlx::query("DELETE FROM plugin_settings WHERE plugin_id = ?1")
+        .bind(plugin_id)
+        .execute(&mut *tx)
+        .await?;
+    for (key, value_json) in values {
+        sqlx::query(
+            "INSERT INTO plugin_settings (plugin_id, setting_key, value_json) VALUES (?1, ?2, ?3)",
+        )
+        .bind(plugin_id)
+        .bind(key)
+        .bind(value_json)
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await?;
+    list_plugin_settings(pool, plugin_id).await
+}
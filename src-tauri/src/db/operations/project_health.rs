@@ -0,0 +1,147 @@
+//! Stored project health evaluations and the events raised when a project gets worse.
+
+use std::collections::HashMap;
+
+use serde_json::json;
+use sqlx::SqlitePool;
+use sqlx::types::Json;
+use uuid::Uuid;
+
+use super::outbox::enqueue_event;
+use crate::db::error::DbResult;
+use crate::db::health::{HealthLevel, HealthSignals, evaluate, parse_timestamp};
+use crate::db::types::ProjectHealthRecord;
+use crate::ipc::events::PROJECT_HEALTH_DEGRADED;
+
+/// Project uuid, status, due date, failed jobs, open critical findings, total segments,
+/// confirmed segments and last activity.
+type SignalRow = (
+    Uuid,
+    String,
+    Option<String>,
+    i64,
+    i64,
+    i64,
+    i64,
+    Option<String>,
+);
+
+/// Evaluates every project and stores the results. A `projects://health_degraded` event is
+/// recorded for each project whose level is worse than at its previous evaluation; a project
+/// evaluated for the first time is compared with `healthy`.
+pub async fn refresh_health(pool: &SqlitePool) -> DbResult<Vec<ProjectHealthRecord>> {
+    let mut tx = pool.begin().await?;
+    let rows = sqlx::query_as::<_, SignalRow>(
+        r#"
+        SELECT
+            p.project_uuid,
+            p.project_status,
+            p.due_date,
+            (
+                SELECT COUNT(*) FROM jobs j
+                WHERE j.project_uuid = p.project_uuid AND lower(j.job_status) = 'failed'
+            ),
+            (
+                SELECT COUNT(*) FROM qa_findings f
+                WHERE f.project_uuid = p.project_uuid
+                  AND f.severity = 'critical'
+                  AND f.resolved_at IS NULL
+            ),
+            (
+                SELECT COALESCE(SUM(a.segment_count), 0) FROM artifacts a
+                WHERE a.project_uuid = p.project_uuid AND a.artifact_type = 'jliff'
+            ),
+            (
+                SELECT COUNT(DISTINCT c.jliff_rel_path || char(0) || c.transunit_id)
+                FROM segment_confirmations c
+                WHERE c.project_uuid = p.project_uuid
+            ),
+            MAX(
+                p.update_date,
+                COALESCE(
+                    (
+                        SELECT MAX(c.confirmed_at) FROM segment_confirmations c
+                        WHERE c.project_uuid = p.project_uuid
+                    ),
+                    p.update_date
+                ),
+                COALESCE(
+                    (
+                        SELECT MAX(r.created_at) FROM segment_revisions r
+                        WHERE r.project_uuid = p.project_uuid
+                    ),
+                    p.update_date
+                )
+            )
+        FROM projects p
+        "#,
+    )
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let previous: HashMap<Uuid, String> =
+        sqlx::query_as::<_, (Uuid, String)>("SELECT project_uuid, level FROM project_health")
+            .fetch_all(&mut *tx)
+            .await?
+            .into_iter()
+            .collect();
+
+    let now = chrono::Utc::now().naive_utc();
+    let mut records = Vec::with_capacity(rows.len());
+    for (project_uuid, status, due_date, failed, criticals, total, confirmed, last_activity) in rows
+    {
+        let assessment = evaluate(
+            &HealthSignals {
+                project_status: status,
+                last_activity: last_activity.as_deref().and_then(parse_timestamp),
+                due_date: due_date.as_deref().and_then(parse_timestamp),
+                failed_jobs: failed,
+                open_critical_findings: criticals,
+                total_segments: total,
+                confirmed_segments: confirmed,
+            },
+            now,
+        );
+        let flags: Vec<String> = assessment
+            .flags
+            .iter()
+            .map(|flag| flag.to_string())
+            .collect();
+        let record = sqlx::query_as::<_, ProjectHealthRecord>(
+            r#"
+            INSERT INTO project_health (project_uuid, level, score, flags, evaluated_at)
+            VALUES (?1, ?2, ?3, ?4, CURRENT_TIMESTAMP)
+            ON CONFLICT(project_uuid) DO UPDATE SET
+                level = excluded.level,
+                score = excluded.score,
+                flags = excluded.flags,
+                evaluated_at = excluded.evaluated_at
+            RETURNING *
+            "#,
+        )
+        .bind(project_uuid)
+        .bind(assessment.level.as_str())
+        .bind(assessment.score)
+        .bind(Json(&flags))
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let previous_level = previous
+            .get(&project_uuid)
+            .and_then(|level| HealthLevel::parse(level))
+            .unwrap_or(HealthLevel::Healthy);
+        if assessment.level > previous_level {
+            let payload = json!({
+                "projectUuid": project_uuid.to_string(),
+                "previousLevel": previous_level.as_str(),
+                "level": assessment.level.as_str(),
+                "score": assessment.score,
+                "flags": flags,
+            });
+            enqueue_event(&mut tx, PROJECT_HEALTH_DEGRADED, &payload).await?;
+        }
+        records.push(record);
+    }
+    tx.commit().await?;
+    Ok(records)
+}
@@ -5,7 +5,12 @@ use std::collections::HashSet;
 use sqlx::{QueryBuilder, Sqlite, SqlitePool, Transaction};
 use uuid::Uuid;
 
+use super::outbox::enqueue_event;
+use super::subject_taxonomy::resolve_subject;
+use crate::db::collation::Collator;
 use crate::db::error::{DbError, DbResult};
+use crate::db::numbering::{ProjectNumberTemplate, assign_project_number};
+use crate::db::search::{contains_pattern, search_key};
 use crate::db::types::{
     FileInfoRecord, FileLanguagePairInput, FileLanguagePairRecord, NewFileInfoArgs, NewProjectArgs,
     NewProjectFileArgs, ProjectBundle, ProjectConversionStats, ProjectFileBundle,
@@ -14,6 +19,8 @@ use crate::db::types::{
     ProjectStatistics, ProjectSubjectInput, ProjectSubjectRecord, ProjectWarningStats,
     UpdateProjectArgs,
 };
+use crate::ipc::dto::{ProjectsChangedKind, ProjectsChangedPayload};
+use crate::ipc::events::PROJECTS_UPDATED;
 
 fn ensure_project_language_pairs_unique(pairs: &[ProjectLanguagePairInput]) -> DbResult<()> {
     let mut seen: HashSet<(String, String)> = HashSet::with_capacity(pairs.len());
@@ -30,7 +37,11 @@ fn ensure_project_language_pairs_unique(pairs: &[ProjectLanguagePairInput]) -> D
 }
 
 /// Creates a project with associated subjects and language pairs.
-pub async fn create_project(pool: &SqlitePool, args: NewProjectArgs) -> DbResult<ProjectBundle> {
+pub async fn create_project(
+    pool: &SqlitePool,
+    args: NewProjectArgs,
+    numbering: &ProjectNumberTemplate,
+) -> DbResult<ProjectBundle> {
     if args.language_pairs.is_empty() {
         return Err(DbError::ConstraintViolation(
             "project requires at least one language pair".into(),
@@ -39,6 +50,7 @@ pub async fn create_project(pool: &SqlitePool, args: NewProjectArgs) -> DbResult
     ensure_project_language_pairs_unique(&args.language_pairs)?;
 
     let mut tx = pool.begin().await?;
+    let project_number = assign_project_number(&mut tx, numbering, args.client_uuid).await?;
 
     sqlx::query(
         r#"
@@ -49,9 +61,12 @@ pub async fn create_project(pool: &SqlitePool, args: NewProjectArgs) -> DbResult
             user_uuid,
             client_uuid,
             type,
-            notes
+            notes,
+            project_name_search,
+            project_number,
+            due_date
         )
-        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
         "#,
     )
     .bind(args.project_uuid)
@@ -61,6 +76,9 @@ pub async fn create_project(pool: &SqlitePool, args: NewProjectArgs) -> DbResult
     .bind(args.client_uuid)
     .bind(&args.r#type)
     .bind(&args.notes)
+    .bind(search_key(&args.project_name))
+    .bind(&project_number)
+    .bind(&args.due_date)
     .execute(&mut *tx)
     .await?;
 
@@ -68,6 +86,7 @@ pub async fn create_project(pool: &SqlitePool, args: NewProjectArgs) -> DbResult
     insert_project_language_pairs(&mut tx, args.project_uuid, &args.language_pairs).await?;
 
     let bundle = fetch_project_bundle(&mut tx, args.project_uuid).await?;
+    enqueue_projects_changed(&mut tx, ProjectsChangedKind::Created, args.project_uuid).await?;
     tx.commit().await?;
 
     bundle.ok_or_else(|| sqlx::Error::RowNotFound.into())
@@ -86,6 +105,8 @@ pub async fn update_project(
         || args.client_uuid.is_some()
         || args.r#type.is_some()
         || args.notes.is_some()
+        || args.due_date.is_some()
+        || args.no_external_mt.is_some()
     {
         let mut builder = QueryBuilder::<Sqlite>::new("UPDATE projects SET ");
         let mut first = true;
@@ -96,6 +117,8 @@ pub async fn update_project(
             }
             builder.push("project_name = ");
             builder.push_bind(name);
+            builder.push(", project_name_search = ");
+            builder.push_bind(search_key(name));
             first = false;
         }
 
@@ -141,6 +164,24 @@ pub async fn update_project(
             }
             builder.push("notes = ");
             builder.push_bind(notes.clone());
+            first = false;
+        }
+
+        if let Some(due_date) = args.due_date.as_ref() {
+            if !first {
+                builder.push(", ");
+            }
+            builder.push("due_date = ");
+            builder.push_bind(due_date.clone());
+            first = false;
+        }
+
+        if let Some(no_external_mt) = args.no_external_mt {
+            if !first {
+                builder.push(", ");
+            }
+            builder.push("no_external_mt = ");
+            builder.push_bind(no_external_mt);
         }
 
         builder.push(" WHERE project_uuid = ");
@@ -163,6 +204,9 @@ pub async fn update_project(
     }
 
     let bundle = fetch_project_bundle(&mut tx, args.project_uuid).await?;
+    if bundle.is_some() {
+        enqueue_projects_changed(&mut tx, ProjectsChangedKind::Updated, args.project_uuid).await?;
+    }
     tx.commit().await?;
 
     Ok(bundle)
@@ -170,13 +214,102 @@ pub async fn update_project(
 
 /// Deletes a project and cascaded rows.
 pub async fn delete_project(pool: &SqlitePool, project_uuid: Uuid) -> DbResult<()> {
-    sqlx::query("DELETE FROM projects WHERE project_uuid = ?1")
+    let mut tx = pool.begin().await?;
+    let result = sqlx::query("DELETE FROM projects WHERE project_uuid = ?1")
         .bind(project_uuid)
-        .execute(pool)
+        .execute(&mut *tx)
         .await?;
+    if result.rows_affected() > 0 {
+        enqueue_projects_changed(&mut tx, ProjectsChangedKind::Deleted, project_uuid).await?;
+    }
+    tx.commit().await?;
     Ok(())
 }
 
+/// Reverses a project language pair together with the file language pairs that use it.
+/// Returns `None` when the project does not exist.
+pub async fn swap_project_language_pair(
+    pool: &SqlitePool,
+    project_uuid: Uuid,
+    source_lang: &str,
+    target_lang: &str,
+) -> DbResult<Option<ProjectBundle>> {
+    let mut tx = pool.begin().await?;
+    let pairs: Vec<(String, String)> = sqlx::query_as(
+        "SELECT source_lang, target_lang FROM project_language_pairs WHERE project_uuid = ?1",
+    )
+    .bind(project_uuid)
+    .fetch_all(&mut *tx)
+    .await?;
+    let Some((source_lang, target_lang)) = pairs.iter().find(|(source, target)| {
+        source.eq_ignore_ascii_case(source_lang) && target.eq_ignore_ascii_case(target_lang)
+    }) else {
+        let exists = fetch_project_bundle(&mut tx, project_uuid).await?.is_some();
+        return if exists {
+            Err(DbError::ConstraintViolation(format!(
+                "project has no language pair {source_lang} → {target_lang}"
+            )))
+        } else {
+            Ok(None)
+        };
+    };
+    if pairs.iter().any(|(source, target)| {
+        source.eq_ignore_ascii_case(target_lang) && target.eq_ignore_ascii_case(source_lang)
+    }) {
+        return Err(DbError::ConstraintViolation(format!(
+            "project already has the language pair {target_lang} → {source_lang}"
+        )));
+    }
+
+    // The reversed pair must exist before file pairs move to it (see the
+    // `flp_must_be_subset_of_plp_update` trigger).
+    sqlx::query(
+        "INSERT INTO project_language_pairs (project_uuid, source_lang, target_lang)
+         VALUES (?1, ?2, ?3)",
+    )
+    .bind(project_uuid)
+    .bind(target_lang)
+    .bind(source_lang)
+    .execute(&mut *tx)
+    .await?;
+    sqlx::query(
+        "UPDATE file_language_pairs SET source_lang = ?3, target_lang = ?2
+         WHERE project_uuid = ?1 AND source_lang = ?2 AND target_lang = ?3",
+    )
+    .bind(project_uuid)
+    .bind(source_lang)
+    .bind(target_lang)
+    .execute(&mut *tx)
+    .await?;
+    sqlx::query(
+        "DELETE FROM project_language_pairs
+         WHERE project_uuid = ?1 AND source_lang = ?2 AND target_lang = ?3",
+    )
+    .bind(project_uuid)
+    .bind(source_lang)
+    .bind(target_lang)
+    .execute(&mut *tx)
+    .await?;
+
+    let bundle = fetch_project_bundle(&mut tx, project_uuid).await?;
+    enqueue_projects_changed(&mut tx, ProjectsChangedKind::Updated, project_uuid).await?;
+    tx.commit().await?;
+    Ok(bundle)
+}
+
+/// Records a `projects://updated` event in the outbox as part of `tx`.
+async fn enqueue_projects_changed(
+    tx: &mut Transaction<'_, Sqlite>,
+    kind: ProjectsChangedKind,
+    project_uuid: Uuid,
+) -> DbResult<()> {
+    let payload = ProjectsChangedPayload {
+        kind,
+        project_id: Some(project_uuid.to_string()),
+    };
+    enqueue_event(tx, PROJECTS_UPDATED, &payload).await
+}
+
 /// Retrieves a bundled project view.
 pub async fn get_project(pool: &SqlitePool, project_uuid: Uuid) -> DbResult<Option<ProjectBundle>> {
     let mut tx = pool.begin().await?;
@@ -199,9 +332,28 @@ pub async fn get_project_statistics(
     })
 }
 
+/// Whether the project forbids sending its text to external MT providers. Unknown projects
+/// do not.
+pub async fn external_mt_forbidden(pool: &SqlitePool, project_uuid: Uuid) -> DbResult<bool> {
+    let forbidden = sqlx::query_scalar::<_, bool>(
+        "SELECT no_external_mt FROM projects WHERE project_uuid = ?1",
+    )
+    .bind(project_uuid)
+    .fetch_optional(pool)
+    .await?;
+    Ok(forbidden.unwrap_or(false))
+}
+
 /// Lists project records without eager loading relations while including derived aggregates.
-pub async fn list_projects(pool: &SqlitePool) -> DbResult<Vec<ProjectListRecord>> {
-    let rows: Vec<ProjectListRecord> = sqlx::query_as(
+/// Projects created on the same date are ordered by name for the UI language. A `query`
+/// keeps the projects whose name, number, client name or a filename contains it, ignoring
+/// case and accents.
+pub async fn list_projects(
+    pool: &SqlitePool,
+    collator: &Collator,
+    query: Option<&str>,
+) -> DbResult<Vec<ProjectListRecord>> {
+    let mut rows: Vec<ProjectListRecord> = sqlx::query_as(
         r#"
         SELECT
             p.project_uuid,
@@ -214,6 +366,9 @@ pub async fn list_projects(pool: &SqlitePool) -> DbResult<Vec<ProjectListRecord>
             c.name AS client_name,
             p.type,
             p.notes,
+            p.project_number,
+            p.due_date,
+            p.no_external_mt,
             COALESCE(
                 (
                     SELECT json_group_array(subject)
@@ -229,11 +384,28 @@ pub async fn list_projects(pool: &SqlitePool) -> DbResult<Vec<ProjectListRecord>
             ) AS file_count
         FROM projects p
         LEFT JOIN clients c ON c.client_uuid = p.client_uuid
-        ORDER BY p.creation_date DESC, p.project_name COLLATE NOCASE ASC
+        WHERE ?1 IS NULL
+           OR p.project_name_search LIKE ?1 ESCAPE '\'
+           OR c.name_search LIKE ?1 ESCAPE '\'
+           OR p.project_number LIKE ?1 ESCAPE '\'
+           OR EXISTS (
+                SELECT 1
+                FROM project_files pf
+                WHERE pf.project_uuid = p.project_uuid
+                  AND pf.filename_search LIKE ?1 ESCAPE '\'
+           )
+        ORDER BY p.creation_date DESC
         "#,
     )
+    .bind(query.map(contains_pattern))
     .fetch_all(pool)
     .await?;
+    rows.sort_by(|left, right| {
+        right
+            .creation_date
+            .cmp(&left.creation_date)
+            .then_with(|| collator.compare(&left.project_name, &right.project_name))
+    });
     Ok(rows)
 }
 
@@ -270,12 +442,13 @@ pub async fn attach_project_file(
 
     sqlx::query(
         r#"
-        INSERT INTO project_files (project_uuid, file_uuid, filename, stored_at, type)
-        VALUES (?1, ?2, ?3, ?4, ?5)
+        INSERT INTO project_files (project_uuid, file_uuid, filename, stored_at, type, filename_search)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6)
         ON CONFLICT(project_uuid, file_uuid) DO UPDATE SET
             filename = excluded.filename,
             stored_at = excluded.stored_at,
-            type = excluded.type
+            type = excluded.type,
+            filename_search = excluded.filename_search
         "#,
     )
     .bind(link.project_uuid)
@@ -283,6 +456,7 @@ pub async fn attach_project_file(
     .bind(&link.filename)
     .bind(&link.stored_at)
     .bind(&link.r#type)
+    .bind(search_key(&link.filename))
     .execute(&mut *tx)
     .await?;
 
@@ -424,12 +598,13 @@ async fn insert_subjects(
     subjects: &[ProjectSubjectInput],
 ) -> DbResult<()> {
     for subject in subjects {
+        let subject = resolve_subject(tx, &subject.subject).await?;
         sqlx::query(
             "INSERT INTO project_subjects (project_uuid, subject)
              VALUES (?1, ?2)",
         )
         .bind(project_uuid)
-        .bind(&subject.subject)
+        .bind(&subject)
         .execute(&mut **tx)
         .await?;
     }
@@ -794,11 +969,12 @@ mod tests {
             client_uuid: None,
             r#type: "standard".into(),
             notes: None,
+            due_date: None,
             subjects: vec![],
             language_pairs: vec![pair.clone(), pair],
         };
 
-        let result = create_project(&pool, args).await;
+        let result = create_project(&pool, args, &ProjectNumberTemplate::default()).await;
         match result {
             Err(DbError::ConstraintViolation(message)) => {
                 assert!(
@@ -845,12 +1021,14 @@ mod tests {
                 client_uuid: None,
                 r#type: "standard".into(),
                 notes: None,
+                due_date: None,
                 subjects: vec![],
                 language_pairs: vec![ProjectLanguagePairInput {
                     source_lang: "en".into(),
                     target_lang: "fr".into(),
                 }],
             },
+            &ProjectNumberTemplate::default(),
         )
         .await
         .expect("expected project creation to succeed");
@@ -932,12 +1110,14 @@ mod tests {
                     client_uuid: None,
                     r#type: "translation".into(),
                     notes: None,
+                    due_date: None,
                     subjects: vec![],
                     language_pairs: vec![ProjectLanguagePairInput {
                         source_lang: "en".into(),
                         target_lang: "fr".into(),
                     }],
                 },
+                &ProjectNumberTemplate::default(),
             )
             .await
             .expect("expected project creation to succeed");
@@ -1029,6 +1209,7 @@ mod tests {
                 client_uuid: None,
                 r#type: "standard".into(),
                 notes: None,
+                due_date: None,
                 subjects: vec![ProjectSubjectInput {
                     subject: "initial".into(),
                 }],
@@ -1037,6 +1218,7 @@ mod tests {
                     target_lang: "fr".into(),
                 }],
             },
+            &ProjectNumberTemplate::default(),
         )
         .await
         .expect("expected project creation to succeed");
@@ -1051,6 +1233,8 @@ mod tests {
                 client_uuid: None,
                 r#type: None,
                 notes: None,
+                due_date: None,
+                no_external_mt: None,
                 subjects: Some(vec![
                     ProjectSubjectInput {
                         subject: "duplicate".into(),
@@ -1100,12 +1284,14 @@ mod tests {
                 client_uuid: None,
                 r#type: "standard".into(),
                 notes: None,
+                due_date: None,
                 subjects: vec![],
                 language_pairs: vec![ProjectLanguagePairInput {
                     source_lang: "en".into(),
                     target_lang: "fr".into(),
                 }],
             },
+            &ProjectNumberTemplate::default(),
         )
         .await
         .expect("expected project creation to succeed");
@@ -1157,12 +1343,14 @@ mod tests {
                 client_uuid: None,
                 r#type: "standard".into(),
                 notes: None,
+                due_date: None,
                 subjects: vec![],
                 language_pairs: vec![ProjectLanguagePairInput {
                     source_lang: "en".into(),
                     target_lang: "fr".into(),
                 }],
             },
+            &ProjectNumberTemplate::default(),
         )
         .await
         .expect("expected project creation to succeed");
@@ -0,0 +1,183 @@
+//! LLM prompt templates and their per-project, per-language-pair assignments.
+
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+use crate::db::error::DbResult;
+use crate::db::types::{
+    NewPromptTemplateArgs, PromptTemplateAssignmentRecord, PromptTemplateRecord,
+    UpdatePromptTemplateArgs,
+};
+
+/// Inserts a template.
+pub async fn create_template(
+    pool: &SqlitePool,
+    args: NewPromptTemplateArgs,
+) -> DbResult<PromptTemplateRecord> {
+    let record = sqlx::query_as::<_, PromptTemplateRecord>(
+        r#"
+        INSERT INTO prompt_templates (template_uuid, name, description, definition_json)
+        VALUES (?1, ?2, ?3, ?4)
+        RETURNING *
+        "#,
+    )
+    .bind(Uuid::new_v4())
+    .bind(&args.name)
+    .bind(&args.description)
+    .bind(&args.definition_json)
+    .fetch_one(pool)
+    .await?;
+    Ok(record)
+}
+
+/// Updates the given fields of a template. Returns `None` when it does not exist.
+pub async fn update_template(
+    pool: &SqlitePool,
+    args: UpdatePromptTemplateArgs,
+) -> DbResult<Option<PromptTemplateRecord>> {
+    let (set_description, description) = match args.description {
+        Some(description) => (true, description),
+        None => (false, None),
+    };
+    let record = sqlx::query_as::<_, PromptTemplateRecord>(
+        r#"
+        UPDATE prompt_templates
+        SET name = COALESCE(?2, name),
+            description = CASE WHEN ?3 THEN ?4 ELSE description END,
+            definition_json = COALESCE(?5, definition_json),
+            updated_at = CURRENT_TIMESTAMP
+        WHERE template_uuid = ?1
+        RETURNING *
+        "#,
+    )
+    .bind(args.template_uuid)
+    .bind(&args.name)
+    .bind(set_description)
+    .bind(description)
+    .bind(&args.definition_json)
+    .fetch_optional(pool)
+    .await?;
+    Ok(record)
+}
+
+/// Deletes a template together with its assignments.
+pub async fn delete_template(pool: &SqlitePool, template_uuid: Uuid) -> DbResult<bool> {
+    let result = sqlx::query("DELETE FROM prompt_templates WHERE template_uuid = ?1")
+        .bind(template_uuid)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Fetches a template by id.
+pub async fn get_template(
+    pool: &SqlitePool,
+    template_uuid: Uuid,
+) -> DbResult<Option<PromptTemplateRecord>> {
+    let record = sqlx::query_as::<_, PromptTemplateRecord>(
+        "SELECT * FROM prompt_templates WHERE template_uuid = ?1 LIMIT 1",
+    )
+    .bind(template_uuid)
+    .fetch_optional(pool)
+    .await?;
+    Ok(record)
+}
+
+/// Lists all templates by name.
+pub async fn list_templates(pool: &SqlitePool) -> DbResult<Vec<PromptTemplateRecord>> {
+    let records = sqlx::query_as::<_, PromptTemplateRecord>(
+        "SELECT * FROM prompt_templates ORDER BY name ASC",
+    )
+    .fetch_all(pool)
+    .await?;
+    Ok(records)
+}
+
+/// Assigns a template to a project language pair (empty languages match any). `None` removes
+/// the assignment.
+pub async fn assign_template(
+    pool: &SqlitePool,
+    project_uuid: Uuid,
+    source_lang: &str,
+    target_lang: &str,
+    template_uuid: Option<Uuid>,
+) -> DbResult<()> {
+    match template_uuid {
+        Some(template_uuid) => {
+            sqlx::query(
+                r#"
+                INSERT INTO prompt_template_assignments (project_uuid, source_lang, target_lang, template_uuid)
+                VALUES (?1, ?2, ?3, ?4)
+                ON CONFLICT (project_uuid, source_lang, target_lang) DO UPDATE SET
+                    template_uuid = excluded.template_uuid,
+                    assigned_at = CURRENT_TIMESTAMP
+                "#,
+            )
+            .bind(project_uuid)
+            .bind(source_lang)
+            .bind(target_lang)
+            .bind(template_uuid)
+            .execute(pool)
+            .await?;
+        }
+        None => {
+            sqlx::query(
+                r#"
+                DELETE FROM prompt_template_assignments
+                WHERE project_uuid = ?1 AND source_lang = ?2 AND target_lang = ?3
+                "#,
+            )
+            .bind(project_uuid)
+            .bind(source_lang)
+            .bind(target_lang)
+            .execute(pool)
+            .await?;
+        }
+    }
+    Ok(())
+}
+
+/// Lists the template assignments of a project.
+pub async fn list_assignments(
+    pool: &SqlitePool,
+    project_uuid: Uuid,
+) -> DbResult<Vec<PromptTemplateAssignmentRecord>> {
+    let records = sqlx::query_as::<_, PromptTemplateAssignmentRecord>(
+        r#"
+        SELECT * FROM prompt_template_assignments
+        WHERE project_uuid = ?1
+        ORDER BY source_lang ASC, target_lang ASC
+        "#,
+    )
+    .bind(project_uuid)
+    .fetch_all(pool)
+    .await?;
+    Ok(records)
+}
+
+/// Template governing a language pair of a project: the most specific matching assignment.
+pub async fn template_for_pair(
+    pool: &SqlitePool,
+    project_uuid: Uuid,
+    source_lang: &str,
+    target_lang: &str,
+) -> DbResult<Option<PromptTemplateRecord>> {
+    let record = sqlx::query_as::<_, PromptTemplateRecord>(
+        r#"
+        SELECT templates.*
+        FROM prompt_template_assignments AS assignments
+        JOIN prompt_templates AS templates ON templates.template_uuid = assignments.template_uuid
+        WHERE assignments.project_uuid = ?1
+          AND assignments.source_lang IN ('', ?2)
+          AND assignments.target_lang IN ('', ?3)
+        ORDER BY (assignments.target_lang <> '') DESC, (assignments.source_lang <> '') DESC
+        LIMIT 1
+        "#,
+    )
+    .bind(project_uuid)
+    .bind(source_lang)
+    .bind(target_lang)
+    .fetch_optional(pool)
+    .await?;
+    Ok(record)
+}
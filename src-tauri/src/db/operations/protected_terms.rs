@@ -0,0 +1,72 @@
+//! Project protected-term ("do not translate") operations.
+
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+use crate::db::error::DbResult;
+use crate::db::types::{NewProtectedTermArgs, ProtectedTermRecord};
+
+/// Replaces the full protected-term list of a project.
+pub async fn replace_protected_terms(
+    pool: &SqlitePool,
+    project_uuid: Uuid,
+    terms: &[NewProtectedTermArgs],
+) -> DbResult<Vec<ProtectedTermRecord>> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query("DELETE FROM project_protected_terms WHERE project_uuid = ?1")
+        .bind(project_uuid)
+        .execute(&mut *tx)
+        .await?;
+
+    let mut inserted = Vec::with_capacity(terms.len());
+    for term in terms {
+        let record = sqlx::query_as::<_, ProtectedTermRecord>(
+            r#"
+            INSERT INTO project_protected_terms (
+                term_uuid,
+                project_uuid,
+                term,
+                case_sensitive,
+                note
+            )
+            VALUES (?1, ?2, ?3, ?4, ?5)
+            ON CONFLICT(project_uuid, term) DO UPDATE SET
+                case_sensitive = excluded.case_sensitive,
+                note = excluded.note
+            RETURNING *
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(project_uuid)
+        .bind(&term.term)
+        .bind(term.case_sensitive)
+        .bind(&term.note)
+        .fetch_one(&mut *tx)
+        .await?;
+        inserted.push(record);
+    }
+
+    tx.commit().await?;
+    Ok(inserted)
+}
+
+/// Lists the protected terms configured for a project, longest first so callers can match
+/// greedily without re-sorting.
+pub async fn list_protected_terms(
+    pool: &SqlitePool,
+    project_uuid: Uuid,
+) -> DbResult<Vec<ProtectedTermRecord>> {
+    let records = sqlx::query_as::<_, ProtectedTermRecord>(
+        r#"
+        SELECT *
+        FROM project_protected_terms
+        WHERE project_uuid = ?1
+        ORDER BY length(term) DESC, term ASC
+        "#,
+    )
+    .bind(project_uuid)
+    .fetch_all(pool)
+    .await?;
+    Ok(records)
+}
@@ -0,0 +1,94 @@
+//! Project regex protection rule operations.
+
+use sqlx::{QueryBuilder, Sqlite, SqlitePool};
+use uuid::Uuid;
+
+use crate::db::error::DbResult;
+use crate::db::types::{NewProtectionRuleArgs, ProtectionRuleRecord, UpdateProtectionRuleArgs};
+
+/// Inserts a new protection rule.
+pub async fn create_protection_rule(
+    pool: &SqlitePool,
+    args: NewProtectionRuleArgs,
+) -> DbResult<ProtectionRuleRecord> {
+    let record = sqlx::query_as::<_, ProtectionRuleRecord>(
+        r#"
+        INSERT INTO project_protection_rules (
+            rule_uuid,
+            project_uuid,
+            name,
+            pattern,
+            is_enabled
+        )
+        VALUES (?1, ?2, ?3, ?4, ?5)
+        RETURNING *
+        "#,
+    )
+    .bind(args.rule_uuid)
+    .bind(args.project_uuid)
+    .bind(&args.name)
+    .bind(&args.pattern)
+    .bind(args.is_enabled)
+    .fetch_one(pool)
+    .await?;
+    Ok(record)
+}
+
+/// Updates the provided fields of a protection rule.
+pub async fn update_protection_rule(
+    pool: &SqlitePool,
+    args: UpdateProtectionRuleArgs,
+) -> DbResult<Option<ProtectionRuleRecord>> {
+    let mut builder = QueryBuilder::<Sqlite>::new(
+        "UPDATE project_protection_rules SET updated_at = CURRENT_TIMESTAMP",
+    );
+    if let Some(name) = args.name.as_ref() {
+        builder.push(", name = ");
+        builder.push_bind(name);
+    }
+    if let Some(pattern) = args.pattern.as_ref() {
+        builder.push(", pattern = ");
+        builder.push_bind(pattern);
+    }
+    if let Some(is_enabled) = args.is_enabled {
+        builder.push(", is_enabled = ");
+        builder.push_bind(is_enabled);
+    }
+    builder.push(" WHERE rule_uuid = ");
+    builder.push_bind(args.rule_uuid);
+    builder.push(" RETURNING *");
+
+    let record = builder
+        .build_query_as::<ProtectionRuleRecord>()
+        .fetch_optional(pool)
+        .await?;
+    Ok(record)
+}
+
+/// Deletes a protection rule.
+pub async fn delete_protection_rule(pool: &SqlitePool, rule_uuid: Uuid) -> DbResult<()> {
+    sqlx::query("DELETE FROM project_protection_rules WHERE rule_uuid = ?1")
+        .bind(rule_uuid)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Lists the protection rules of a project in creation order.
+pub async fn list_protection_rules(
+    pool: &SqlitePool,
+    project_uuid: Uuid,
+) -> DbResult<Vec<ProtectionRuleRecord>> {
+    let records = sqlx::query_as::<_, ProtectionRuleRecord>(
+        r#"
+        SELECT *
+        FROM project_protection_rules
+        WHERE project_uuid = ?1
+        ORDER BY created_at ASC, name ASC
+        "#,
+    )
+    .bind(project_uuid)
+    .fetch_all(pool)
+    .await?;
+    Ok(records)
+}
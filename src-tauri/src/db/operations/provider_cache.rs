@@ -0,0 +1,124 @@
+//! Cache of provider translations, so identical requests are not sent (and billed) twice.
+
+use sqlx::SqlitePool;
+
+use crate::db::error::DbResult;
+use crate::db::types::{NewProviderCacheEntryArgs, ProviderCacheKey, ProviderCacheStatsRecord};
+
+/// Returns the unexpired cached translation of every key (`None` on a miss) and counts a hit
+/// on each entry found.
+pub async fn lookup_entries(
+    pool: &SqlitePool,
+    keys: &[ProviderCacheKey],
+) -> DbResult<Vec<Option<String>>> {
+    let mut tx = pool.begin().await?;
+    let mut targets = Vec::with_capacity(keys.len());
+    for key in keys {
+        let target = sqlx::query_scalar::<_, String>(
+            r#"
+            UPDATE provider_translation_cache
+            SET hits = hits + 1
+            WHERE provider = ?1
+              AND model = ?2
+              AND source_lang = ?3
+              AND target_lang = ?4
+              AND source_hash = ?5
+              AND context_hash = ?6
+              AND expires_at > CURRENT_TIMESTAMP
+            RETURNING target_text
+            "#,
+        )
+        .bind(&key.provider)
+        .bind(&key.model)
+        .bind(&key.source_lang)
+        .bind(&key.target_lang)
+        .bind(&key.source_hash)
+        .bind(&key.context_hash)
+        .fetch_optional(&mut *tx)
+        .await?;
+        targets.push(target);
+    }
+    tx.commit().await?;
+    Ok(targets)
+}
+
+/// Stores translations valid for `ttl_hours`, replacing existing entries for the same keys.
+pub async fn store_entries(
+    pool: &SqlitePool,
+    entries: Vec<NewProviderCacheEntryArgs>,
+    ttl_hours: u32,
+) -> DbResult<u64> {
+    let mut tx = pool.begin().await?;
+    let mut stored = 0;
+    for entry in entries {
+        stored += sqlx::query(
+            r#"
+            INSERT INTO provider_translation_cache (
+                provider, model, source_lang, target_lang, source_hash, context_hash,
+                target_text, expires_at
+            )
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, datetime('now', '+' || ?8 || ' hours'))
+            ON CONFLICT (provider, model, source_lang, target_lang, source_hash, context_hash)
+            DO UPDATE SET
+                target_text = excluded.target_text,
+                hits = 0,
+                created_at = CURRENT_TIMESTAMP,
+                expires_at = excluded.expires_at
+            "#,
+        )
+        .bind(&entry.key.provider)
+        .bind(&entry.key.model)
+        .bind(&entry.key.source_lang)
+        .bind(&entry.key.target_lang)
+        .bind(&entry.key.source_hash)
+        .bind(&entry.key.context_hash)
+        .bind(&entry.target_text)
+        .bind(i64::from(ttl_hours))
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+    }
+    tx.commit().await?;
+    Ok(stored)
+}
+
+/// Entry counts and hits per provider model.
+pub async fn cache_stats(pool: &SqlitePool) -> DbResult<Vec<ProviderCacheStatsRecord>> {
+    let records = sqlx::query_as::<_, ProviderCacheStatsRecord>(
+        r#"
+        SELECT
+            provider,
+            model,
+            COUNT(*) AS entries,
+            SUM(CASE WHEN expires_at <= CURRENT_TIMESTAMP THEN 1 ELSE 0 END) AS expired_entries,
+            SUM(hits) AS hits
+        FROM provider_translation_cache
+        GROUP BY provider, model
+        ORDER BY provider ASC, model ASC
+        "#,
+    )
+    .fetch_all(pool)
+    .await?;
+    Ok(records)
+}
+
+/// Deletes cached translations, optionally only those of one provider and/or only expired
+/// ones. Returns the number of removed entries.
+pub async fn purge_entries(
+    pool: &SqlitePool,
+    provider: Option<&str>,
+    expired_only: bool,
+) -> DbResult<u64> {
+    let result = sqlx::query(
+        r#"
+        DELETE FROM provider_translation_cache
+        WHERE (?1 IS NULL OR provider = ?1)
+          AND (?2 = 0 OR expires_at <= CURRENT_TIMESTAMP)
+        "#,
+    )
+    .bind(provider)
+    .bind(expired_only)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected())
+}
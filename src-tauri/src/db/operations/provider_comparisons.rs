@@ -0,0 +1,127 @@
+//! Candidates from provider comparisons and the preferred provider per language pair.
+
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+use crate::db::error::DbResult;
+use crate::db::types::{
+    NewProviderCandidateArgs, PreferredProviderRecord, ProviderCandidateRecord,
+};
+
+/// Stores candidates of one document, replacing earlier ones from the same providers.
+pub async fn store_candidates(
+    pool: &SqlitePool,
+    project_uuid: Uuid,
+    jliff_rel_path: &str,
+    candidates: &[NewProviderCandidateArgs],
+) -> DbResult<()> {
+    let mut tx = pool.begin().await?;
+    for candidate in candidates {
+        sqlx::query(
+            r#"
+            INSERT INTO provider_candidates (
+                project_uuid, jliff_rel_path, transunit_id, provider, source_text, target_text,
+                qe_score
+            )
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+            ON CONFLICT (project_uuid, jliff_rel_path, transunit_id, provider) DO UPDATE SET
+                source_text = excluded.source_text,
+                target_text = excluded.target_text,
+                qe_score = excluded.qe_score,
+                created_at = CURRENT_TIMESTAMP
+            "#,
+        )
+        .bind(project_uuid)
+        .bind(jliff_rel_path)
+        .bind(&candidate.transunit_id)
+        .bind(&candidate.provider)
+        .bind(&candidate.source_text)
+        .bind(&candidate.target_text)
+        .bind(candidate.qe_score)
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Lists the candidates stored for a document.
+pub async fn list_candidates(
+    pool: &SqlitePool,
+    project_uuid: Uuid,
+    jliff_rel_path: &str,
+) -> DbResult<Vec<ProviderCandidateRecord>> {
+    let records = sqlx::query_as::<_, ProviderCandidateRecord>(
+        r#"
+        SELECT * FROM provider_candidates
+        WHERE project_uuid = ?1 AND jliff_rel_path = ?2
+        ORDER BY transunit_id ASC, provider ASC
+        "#,
+    )
+    .bind(project_uuid)
+    .bind(jliff_rel_path)
+    .fetch_all(pool)
+    .await?;
+    Ok(records)
+}
+
+/// Sets the preferred provider of a project language pair; `None` clears it.
+pub async fn set_preferred_provider(
+    pool: &SqlitePool,
+    project_uuid: Uuid,
+    source_lang: &str,
+    target_lang: &str,
+    provider: Option<&str>,
+) -> DbResult<()> {
+    match provider {
+        Some(provider) => {
+            sqlx::query(
+                r#"
+                INSERT INTO preferred_providers (project_uuid, source_lang, target_lang, provider)
+                VALUES (?1, ?2, ?3, ?4)
+                ON CONFLICT (project_uuid, source_lang, target_lang) DO UPDATE SET
+                    provider = excluded.provider,
+                    updated_at = CURRENT_TIMESTAMP
+                "#,
+            )
+            .bind(project_uuid)
+            .bind(source_lang)
+            .bind(target_lang)
+            .bind(provider)
+            .execute(pool)
+            .await?;
+        }
+        None => {
+            sqlx::query(
+                r#"
+                DELETE FROM preferred_providers
+                WHERE project_uuid = ?1 AND source_lang = ?2 AND target_lang = ?3
+                "#,
+            )
+            .bind(project_uuid)
+            .bind(source_lang)
+            .bind(target_lang)
+            .execute(pool)
+            .await?;
+        }
+    }
+    Ok(())
+}
+
+/// Lists the preferred providers of a project.
+pub async fn list_preferred_providers(
+    pool: &SqlitePool,
+    project_uuid: Uuid,
+) -> DbResult<Vec<PreferredProviderRecord>> {
+    let records = sqlx::query_as::<_, PreferredProviderRecord>(
+        r#"
+        SELECT * FROM preferred_providers
+        WHERE project_uuid = ?1
+        ORDER BY source_lang ASC, target_lang ASC
+        "#,
+    )
+    .bind(project_uuid)
+    .fetch_all(pool)
+    .await?;
+    Ok(records)
+}
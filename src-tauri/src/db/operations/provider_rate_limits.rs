@@ -0,0 +1,50 @@
+//! Configured request and token limits of MT/LLM providers.
+
+use sqlx::SqlitePool;
+
+use crate::db::error::DbResult;
+use crate::db::types::ProviderRateLimitRecord;
+
+/// Sets the limits of a provider; clearing both limits removes the entry.
+pub async fn set_limit(
+    pool: &SqlitePool,
+    provider: &str,
+    requests_per_minute: Option<i64>,
+    tokens_per_minute: Option<i64>,
+) -> DbResult<Option<ProviderRateLimitRecord>> {
+    if requests_per_minute.is_none() && tokens_per_minute.is_none() {
+        sqlx::query("DELETE FROM provider_rate_limits WHERE provider = ?1")
+            .bind(provider)
+            .execute(pool)
+            .await?;
+        return Ok(None);
+    }
+
+    let record = sqlx::query_as::<_, ProviderRateLimitRecord>(
+        r#"
+        INSERT INTO provider_rate_limits (provider, requests_per_minute, tokens_per_minute)
+        VALUES (?1, ?2, ?3)
+        ON CONFLICT (provider) DO UPDATE SET
+            requests_per_minute = excluded.requests_per_minute,
+            tokens_per_minute = excluded.tokens_per_minute,
+            updated_at = CURRENT_TIMESTAMP
+        RETURNING *
+        "#,
+    )
+    .bind(provider)
+    .bind(requests_per_minute)
+    .bind(tokens_per_minute)
+    .fetch_one(pool)
+    .await?;
+    Ok(Some(record))
+}
+
+/// Lists all configured limits by provider.
+pub async fn list_limits(pool: &SqlitePool) -> DbResult<Vec<ProviderRateLimitRecord>> {
+    let records = sqlx::query_as::<_, ProviderRateLimitRecord>(
+        "SELECT * FROM provider_rate_limits ORDER BY provider ASC",
+    )
+    .fetch_all(pool)
+    .await?;
+    Ok(records)
+}
@@ -0,0 +1,95 @@
+//! Ordered provider routing rules of projects and clients.
+
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+use crate::db::error::DbResult;
+use crate::db::types::{NewProviderRoutingRuleArgs, ProviderRoutingRuleRecord, RoutingRuleOwner};
+
+fn owner_columns(owner: RoutingRuleOwner) -> (Option<Uuid>, Option<Uuid>) {
+    match owner {
+        RoutingRuleOwner::Project(uuid) => (Some(uuid), None),
+        RoutingRuleOwner::Client(uuid) => (None, Some(uuid)),
+    }
+}
+
+/// Replaces the rules of a project or client, keeping the given order.
+pub async fn replace_rules(
+    pool: &SqlitePool,
+    owner: RoutingRuleOwner,
+    rules: &[NewProviderRoutingRuleArgs],
+) -> DbResult<()> {
+    let (project_uuid, client_uuid) = owner_columns(owner);
+    let mut tx = pool.begin().await?;
+    sqlx::query(
+        "DELETE FROM provider_routing_rules WHERE project_uuid IS ?1 AND client_uuid IS ?2",
+    )
+    .bind(project_uuid)
+    .bind(client_uuid)
+    .execute(&mut *tx)
+    .await?;
+    for (position, rule) in rules.iter().enumerate() {
+        sqlx::query(
+            r#"
+            INSERT INTO provider_routing_rules (
+                rule_uuid, project_uuid, client_uuid, position, source_lang, target_lang,
+                subject, provider, note
+            )
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(project_uuid)
+        .bind(client_uuid)
+        .bind(position as i64)
+        .bind(&rule.source_lang)
+        .bind(&rule.target_lang)
+        .bind(&rule.subject)
+        .bind(&rule.provider)
+        .bind(&rule.note)
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Lists the rules of a project or client in evaluation order.
+pub async fn list_rules(
+    pool: &SqlitePool,
+    owner: RoutingRuleOwner,
+) -> DbResult<Vec<ProviderRoutingRuleRecord>> {
+    let (project_uuid, client_uuid) = owner_columns(owner);
+    let records = sqlx::query_as::<_, ProviderRoutingRuleRecord>(
+        r#"
+        SELECT * FROM provider_routing_rules
+        WHERE project_uuid IS ?1 AND client_uuid IS ?2
+        ORDER BY position ASC
+        "#,
+    )
+    .bind(project_uuid)
+    .bind(client_uuid)
+    .fetch_all(pool)
+    .await?;
+    Ok(records)
+}
+
+/// Rules applying to a project in evaluation order: its own, then those of its client.
+pub async fn rules_for_project(
+    pool: &SqlitePool,
+    project_uuid: Uuid,
+) -> DbResult<Vec<ProviderRoutingRuleRecord>> {
+    let records = sqlx::query_as::<_, ProviderRoutingRuleRecord>(
+        r#"
+        SELECT rules.*
+        FROM provider_routing_rules AS rules
+        LEFT JOIN projects ON projects.client_uuid = rules.client_uuid
+        WHERE rules.project_uuid = ?1 OR projects.project_uuid = ?1
+        ORDER BY rules.project_uuid IS NULL, rules.position ASC
+        "#,
+    )
+    .bind(project_uuid)
+    .fetch_all(pool)
+    .await?;
+    Ok(records)
+}
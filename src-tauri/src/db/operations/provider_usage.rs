@@ -0,0 +1,125 @@
+//! MT/LLM provider usage accounting and monthly budget caps.
+
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+use crate::db::error::DbResult;
+use crate::db::types::{
+    ProviderBudgetArgs, ProviderBudgetRecord, ProviderMonthlyUsageRecord, ProviderUsageRecord,
+    RecordProviderUsageArgs,
+};
+
+/// Adds one request to the usage of a project with a provider in a month.
+pub async fn record_usage(
+    pool: &SqlitePool,
+    args: RecordProviderUsageArgs,
+) -> DbResult<ProviderUsageRecord> {
+    let record = sqlx::query_as::<_, ProviderUsageRecord>(
+        r#"
+        INSERT INTO provider_usage (provider, project_uuid, usage_month, characters, tokens, requests)
+        VALUES (?1, ?2, ?3, ?4, ?5, 1)
+        ON CONFLICT (provider, project_uuid, usage_month) DO UPDATE SET
+            characters = characters + excluded.characters,
+            tokens = tokens + excluded.tokens,
+            requests = requests + 1,
+            updated_at = CURRENT_TIMESTAMP
+        RETURNING *
+        "#,
+    )
+    .bind(&args.provider)
+    .bind(args.project_uuid)
+    .bind(&args.usage_month)
+    .bind(args.characters)
+    .bind(args.tokens)
+    .fetch_one(pool)
+    .await?;
+    Ok(record)
+}
+
+/// Lists usage rows, optionally restricted to a project and/or a month, newest month first.
+pub async fn list_usage(
+    pool: &SqlitePool,
+    project_uuid: Option<Uuid>,
+    usage_month: Option<&str>,
+) -> DbResult<Vec<ProviderUsageRecord>> {
+    let records = sqlx::query_as::<_, ProviderUsageRecord>(
+        r#"
+        SELECT * FROM provider_usage
+        WHERE (?1 IS NULL OR project_uuid = ?1)
+          AND (?2 IS NULL OR usage_month = ?2)
+        ORDER BY usage_month DESC, provider ASC, project_uuid ASC
+        "#,
+    )
+    .bind(project_uuid)
+    .bind(usage_month)
+    .fetch_all(pool)
+    .await?;
+    Ok(records)
+}
+
+/// Usage of every provider in `usage_month` across all projects.
+pub async fn monthly_totals(
+    pool: &SqlitePool,
+    usage_month: &str,
+) -> DbResult<Vec<ProviderMonthlyUsageRecord>> {
+    let records = sqlx::query_as::<_, ProviderMonthlyUsageRecord>(
+        r#"
+        SELECT
+            provider,
+            usage_month,
+            SUM(characters) AS characters,
+            SUM(tokens) AS tokens,
+            SUM(requests) AS requests
+        FROM provider_usage
+        WHERE usage_month = ?1
+        GROUP BY provider, usage_month
+        ORDER BY provider ASC
+        "#,
+    )
+    .bind(usage_month)
+    .fetch_all(pool)
+    .await?;
+    Ok(records)
+}
+
+/// Sets the monthly caps of a provider; clearing both caps removes its budget.
+pub async fn set_budget(
+    pool: &SqlitePool,
+    args: ProviderBudgetArgs,
+) -> DbResult<Option<ProviderBudgetRecord>> {
+    if args.monthly_character_cap.is_none() && args.monthly_token_cap.is_none() {
+        sqlx::query("DELETE FROM provider_budgets WHERE provider = ?1")
+            .bind(&args.provider)
+            .execute(pool)
+            .await?;
+        return Ok(None);
+    }
+
+    let record = sqlx::query_as::<_, ProviderBudgetRecord>(
+        r#"
+        INSERT INTO provider_budgets (provider, monthly_character_cap, monthly_token_cap)
+        VALUES (?1, ?2, ?3)
+        ON CONFLICT (provider) DO UPDATE SET
+            monthly_character_cap = excluded.monthly_character_cap,
+            monthly_token_cap = excluded.monthly_token_cap,
+            updated_at = CURRENT_TIMESTAMP
+        RETURNING *
+        "#,
+    )
+    .bind(&args.provider)
+    .bind(args.monthly_character_cap)
+    .bind(args.monthly_token_cap)
+    .fetch_one(pool)
+    .await?;
+    Ok(Some(record))
+}
+
+/// Lists all configured budgets by provider.
+pub async fn list_budgets(pool: &SqlitePool) -> DbResult<Vec<ProviderBudgetRecord>> {
+    let records = sqlx::query_as::<_, ProviderBudgetRecord>(
+        "SELECT * FROM provider_budgets ORDER BY provider ASC",
+    )
+    .fetch_all(pool)
+    .await?;
+    Ok(records)
+}
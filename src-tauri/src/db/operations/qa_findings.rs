@@ -0,0 +1,155 @@
+//! QA finding operations shared by every automated check.
+
+use std::collections::HashSet;
+
+use serde_json::json;
+use sqlx::{QueryBuilder, Sqlite, SqlitePool};
+use uuid::Uuid;
+
+use super::outbox::enqueue_event;
+use crate::db::error::DbResult;
+use crate::db::types::{QaFindingRecord, ReplaceQaFindingsArgs};
+use crate::ipc::events::QA_FINDINGS_RAISED;
+
+/// Replaces the findings of a single check type for a document (or a subset of its units).
+/// Findings that were not there before, by segment and message, are published as a
+/// `qa://findings_raised` event.
+pub async fn replace_findings(
+    pool: &SqlitePool,
+    args: ReplaceQaFindingsArgs,
+) -> DbResult<Vec<QaFindingRecord>> {
+    if args.transunit_ids.as_ref().is_some_and(Vec::is_empty) {
+        return Ok(Vec::new());
+    }
+    let mut tx = pool.begin().await?;
+
+    let mut select = QueryBuilder::<Sqlite>::new("SELECT transunit_id, message FROM qa_findings");
+    push_scope(&mut select, &args);
+    let previous: HashSet<(String, String)> = select
+        .build_query_as::<(String, String)>()
+        .fetch_all(&mut *tx)
+        .await?
+        .into_iter()
+        .collect();
+
+    let mut delete = QueryBuilder::<Sqlite>::new("DELETE FROM qa_findings");
+    push_scope(&mut delete, &args);
+    delete.build().execute(&mut *tx).await?;
+
+    let mut inserted = Vec::with_capacity(args.findings.len());
+    for finding in &args.findings {
+        let record = sqlx::query_as::<_, QaFindingRecord>(
+            r#"
+            INSERT INTO qa_findings (
+                finding_uuid,
+                project_uuid,
+                jliff_rel_path,
+                transunit_id,
+                check_type,
+                severity,
+                message,
+                details
+            )
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+            RETURNING *
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(args.project_uuid)
+        .bind(&args.jliff_rel_path)
+        .bind(&finding.transunit_id)
+        .bind(&args.check_type)
+        .bind(&finding.severity)
+        .bind(&finding.message)
+        .bind(&finding.details)
+        .fetch_one(&mut *tx)
+        .await?;
+        inserted.push(record);
+    }
+
+    let raised: Vec<_> = inserted
+        .iter()
+        .filter(|record| !previous.contains(&(record.transunit_id.clone(), record.message.clone())))
+        .map(|record| {
+            json!({
+                "findingUuid": record.finding_uuid.to_string(),
+                "transunitId": record.transunit_id,
+                "severity": record.severity,
+                "message": record.message,
+            })
+        })
+        .collect();
+    if !raised.is_empty() {
+        let payload = json!({
+            "projectUuid": args.project_uuid.to_string(),
+            "jliffRelPath": args.jliff_rel_path,
+            "checkType": args.check_type,
+            "findings": raised,
+        });
+        enqueue_event(&mut tx, QA_FINDINGS_RAISED, &payload).await?;
+    }
+
+    tx.commit().await?;
+    Ok(inserted)
+}
+
+/// Narrows a query on `qa_findings` to the document, check type and units of `args`.
+fn push_scope(query: &mut QueryBuilder<'_, Sqlite>, args: &ReplaceQaFindingsArgs) {
+    query.push(" WHERE project_uuid = ");
+    query.push_bind(args.project_uuid);
+    query.push(" AND jliff_rel_path = ");
+    query.push_bind(args.jliff_rel_path.clone());
+    query.push(" AND check_type = ");
+    query.push_bind(args.check_type.clone());
+    if let Some(ids) = args.transunit_ids.as_ref() {
+        query.push(" AND transunit_id IN (");
+        let mut separated = query.separated(", ");
+        for id in ids {
+            separated.push_bind(id.clone());
+        }
+        separated.push_unseparated(")");
+    }
+}
+
+/// Lists findings for a project, optionally narrowed to one JLIFF document.
+pub async fn list_findings(
+    pool: &SqlitePool,
+    project_uuid: Uuid,
+    jliff_rel_path: Option<&str>,
+) -> DbResult<Vec<QaFindingRecord>> {
+    let mut builder =
+        QueryBuilder::<Sqlite>::new("SELECT * FROM qa_findings WHERE project_uuid = ");
+    builder.push_bind(project_uuid);
+    if let Some(path) = jliff_rel_path {
+        builder.push(" AND jliff_rel_path = ");
+        builder.push_bind(path);
+    }
+    builder.push(" ORDER BY jliff_rel_path ASC, transunit_id ASC, created_at ASC");
+
+    let records = builder
+        .build_query_as::<QaFindingRecord>()
+        .fetch_all(pool)
+        .await?;
+    Ok(records)
+}
+
+/// Marks a finding as resolved (or reopens it when `resolved` is false).
+pub async fn set_finding_resolved(
+    pool: &SqlitePool,
+    finding_uuid: Uuid,
+    resolved: bool,
+) -> DbResult<Option<QaFindingRecord>> {
+    let record = sqlx::query_as::<_, QaFindingRecord>(
+        r#"
+        UPDATE qa_findings
+        SET resolved_at = CASE WHEN ?2 THEN CURRENT_TIMESTAMP ELSE NULL END
+        WHERE finding_uuid = ?1
+        RETURNING *
+        "#,
+    )
+    .bind(finding_uuid)
+    .bind(resolved)
+    .fetch_optional(pool)
+    .await?;
+    Ok(record)
+}
@@ -0,0 +1,115 @@
+//! QA profile storage and project/client assignments.
+
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+use crate::db::error::DbResult;
+use crate::db::types::{NewQaProfileArgs, QaProfileRecord, QaProfileTarget};
+
+/// Stores a profile under its name, replacing the definition of an existing one.
+pub async fn upsert_profile(
+    pool: &SqlitePool,
+    args: NewQaProfileArgs,
+) -> DbResult<QaProfileRecord> {
+    let record = sqlx::query_as::<_, QaProfileRecord>(
+        r#"
+        INSERT INTO qa_profiles (profile_uuid, name, description, definition_json)
+        VALUES (?1, ?2, ?3, ?4)
+        ON CONFLICT (name) DO UPDATE SET
+            description = excluded.description,
+            definition_json = excluded.definition_json,
+            updated_at = CURRENT_TIMESTAMP
+        RETURNING *
+        "#,
+    )
+    .bind(Uuid::new_v4())
+    .bind(&args.name)
+    .bind(&args.description)
+    .bind(&args.definition_json)
+    .fetch_one(pool)
+    .await?;
+    Ok(record)
+}
+
+/// Fetches a profile by id.
+pub async fn get_profile(
+    pool: &SqlitePool,
+    profile_uuid: Uuid,
+) -> DbResult<Option<QaProfileRecord>> {
+    let record = sqlx::query_as::<_, QaProfileRecord>(
+        "SELECT * FROM qa_profiles WHERE profile_uuid = ?1 LIMIT 1",
+    )
+    .bind(profile_uuid)
+    .fetch_optional(pool)
+    .await?;
+    Ok(record)
+}
+
+/// Lists all profiles by name.
+pub async fn list_profiles(pool: &SqlitePool) -> DbResult<Vec<QaProfileRecord>> {
+    let records =
+        sqlx::query_as::<_, QaProfileRecord>("SELECT * FROM qa_profiles ORDER BY name ASC")
+            .fetch_all(pool)
+            .await?;
+    Ok(records)
+}
+
+/// Assigns a profile to a project or client, replacing its previous profile. `None` removes
+/// the assignment.
+pub async fn assign_profile(
+    pool: &SqlitePool,
+    target: QaProfileTarget,
+    profile_uuid: Option<Uuid>,
+) -> DbResult<()> {
+    let (project_uuid, client_uuid) = match target {
+        QaProfileTarget::Project(uuid) => (Some(uuid), None),
+        QaProfileTarget::Client(uuid) => (None, Some(uuid)),
+    };
+
+    let mut tx = pool.begin().await?;
+    sqlx::query(
+        "DELETE FROM qa_profile_assignments WHERE project_uuid IS ?1 AND client_uuid IS ?2",
+    )
+    .bind(project_uuid)
+    .bind(client_uuid)
+    .execute(&mut *tx)
+    .await?;
+    if let Some(profile_uuid) = profile_uuid {
+        sqlx::query(
+            r#"
+            INSERT INTO qa_profile_assignments (assignment_uuid, profile_uuid, project_uuid, client_uuid)
+            VALUES (?1, ?2, ?3, ?4)
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(profile_uuid)
+        .bind(project_uuid)
+        .bind(client_uuid)
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Profile governing a project: its own assignment, otherwise the one of its client.
+pub async fn profile_for_project(
+    pool: &SqlitePool,
+    project_uuid: Uuid,
+) -> DbResult<Option<QaProfileRecord>> {
+    let record = sqlx::query_as::<_, QaProfileRecord>(
+        r#"
+        SELECT profiles.*
+        FROM qa_profile_assignments AS assignments
+        JOIN qa_profiles AS profiles ON profiles.profile_uuid = assignments.profile_uuid
+        LEFT JOIN projects ON projects.client_uuid = assignments.client_uuid
+        WHERE assignments.project_uuid = ?1 OR projects.project_uuid = ?1
+        ORDER BY assignments.project_uuid IS NULL
+        LIMIT 1
+        "#,
+    )
+    .bind(project_uuid)
+    .fetch_optional(pool)
+    .await?;
+    Ok(record)
+}
@@ -1,4 +1,10 @@
 //! Operations for reference tables (users, clients, domains).
+//!
+//! Domains are linked to projects (`project_domains`) and to termbases by name
+//! (`termbase_domains`).
+
+use sqlx::Row;
+use uuid::Uuid;
 
 use crate::db::builders::{build_client, build_domain, build_user};
 use crate::db::error::DbResult;
@@ -85,7 +91,7 @@ impl DbManager {
             "INSERT INTO domains (domain_id, name)
              VALUES (?1, ?2)
              ON CONFLICT(domain_id) DO UPDATE SET name = excluded.name
-             RETURNING domain_id, name",
+             RETURNING domain_id, name, description",
         )
         .bind(domain_id)
         .bind(name)
@@ -113,9 +119,10 @@ impl DbManager {
     /// Lists registered domains.
     pub async fn list_domains(&self) -> DbResult<Vec<Domain>> {
         let pool = self.pool().await;
-        let rows = sqlx::query("SELECT domain_id, name FROM domains ORDER BY name ASC")
-            .fetch_all(&pool)
-            .await?;
+        let rows =
+            sqlx::query("SELECT domain_id, name, description FROM domains ORDER BY name ASC")
+                .fetch_all(&pool)
+                .await?;
 
         let mut domains = Vec::with_capacity(rows.len());
         for row in rows {
@@ -124,4 +131,165 @@ impl DbManager {
 
         Ok(domains)
     }
+
+    /// Creates a domain with a new identifier.
+    pub async fn create_domain(&self, name: &str, description: Option<&str>) -> DbResult<Domain> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+
+        let row = sqlx::query(
+            "INSERT INTO domains (domain_id, name, description, created_at)
+             VALUES (?1, ?2, ?3, ?4)
+             RETURNING domain_id, name, description",
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(name)
+        .bind(description)
+        .bind(now_iso8601())
+        .fetch_one(&pool)
+        .await?;
+
+        build_domain(&row)
+    }
+
+    /// Renames a domain and replaces its description. Returns `None` for an unknown domain.
+    pub async fn update_domain(
+        &self,
+        domain_id: &str,
+        name: &str,
+        description: Option<&str>,
+    ) -> DbResult<Option<Domain>> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+
+        let row = sqlx::query(
+            "UPDATE domains SET name = ?2, description = ?3
+             WHERE domain_id = ?1
+             RETURNING domain_id, name, description",
+        )
+        .bind(domain_id)
+        .bind(name)
+        .bind(description)
+        .fetch_optional(&pool)
+        .await?;
+
+        row.as_ref().map(build_domain).transpose()
+    }
+
+    /// Deletes a domain and its project and termbase links. Returns whether it existed.
+    pub async fn delete_domain(&self, domain_id: &str) -> DbResult<bool> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+
+        let result = sqlx::query("DELETE FROM domains WHERE domain_id = ?1")
+            .bind(domain_id)
+            .execute(&pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Domains a project is linked to, by name.
+    pub async fn list_project_domains(&self, project_uuid: Uuid) -> DbResult<Vec<Domain>> {
+        let pool = self.pool().await;
+        let rows = sqlx::query(
+            "SELECT d.domain_id, d.name, d.description
+             FROM project_domains pd
+             INNER JOIN domains d ON d.domain_id = pd.domain_id
+             WHERE pd.project_uuid = ?1
+             ORDER BY d.name ASC",
+        )
+        .bind(project_uuid)
+        .fetch_all(&pool)
+        .await?;
+
+        rows.iter().map(build_domain).collect()
+    }
+
+    /// Replaces the domains a project is linked to.
+    pub async fn set_project_domains(
+        &self,
+        project_uuid: Uuid,
+        domain_ids: &[String],
+    ) -> DbResult<Vec<Domain>> {
+        {
+            let _guard = self.write_lock.lock().await;
+            let pool = self.pool().await;
+            let mut tx = pool.begin().await?;
+            sqlx::query("DELETE FROM project_domains WHERE project_uuid = ?1")
+                .bind(project_uuid)
+                .execute(&mut *tx)
+                .await?;
+            for domain_id in domain_ids {
+                sqlx::query(
+                    "INSERT OR IGNORE INTO project_domains (project_uuid, domain_id)
+                     VALUES (?1, ?2)",
+                )
+                .bind(project_uuid)
+                .bind(domain_id)
+                .execute(&mut *tx)
+                .await?;
+            }
+            tx.commit().await?;
+        }
+        self.list_project_domains(project_uuid).await
+    }
+
+    /// Names of the termbases that have entries or domain links.
+    pub async fn list_termbase_names(&self) -> DbResult<Vec<String>> {
+        let pool = self.pool().await;
+        let names = sqlx::query_scalar(
+            "SELECT termbase FROM termbase_entries
+             UNION
+             SELECT termbase FROM termbase_domains
+             ORDER BY termbase ASC",
+        )
+        .fetch_all(&pool)
+        .await?;
+
+        Ok(names)
+    }
+
+    /// Every termbase linked to a domain, with the domain, ordered by termbase then name.
+    pub async fn list_termbase_domains(&self) -> DbResult<Vec<(String, Domain)>> {
+        let pool = self.pool().await;
+        let rows = sqlx::query(
+            "SELECT td.termbase, d.domain_id, d.name, d.description
+             FROM termbase_domains td
+             INNER JOIN domains d ON d.domain_id = td.domain_id
+             ORDER BY td.termbase ASC, d.name ASC",
+        )
+        .fetch_all(&pool)
+        .await?;
+
+        rows.iter()
+            .map(|row| Ok((row.try_get("termbase")?, build_domain(row)?)))
+            .collect()
+    }
+
+    /// Replaces the domains a termbase is linked to.
+    pub async fn set_termbase_domains(
+        &self,
+        termbase: &str,
+        domain_ids: &[String],
+    ) -> DbResult<()> {
+        let _guard = self.write_lock.lock().await;
+        let pool = self.pool().await;
+        let mut tx = pool.begin().await?;
+        sqlx::query("DELETE FROM termbase_domains WHERE termbase = ?1")
+            .bind(termbase)
+            .execute(&mut *tx)
+            .await?;
+        for domain_id in domain_ids {
+            sqlx::query(
+                "INSERT OR IGNORE INTO termbase_domains (termbase, domain_id) VALUES (?1, ?2)",
+            )
+            .bind(termbase)
+            .bind(domain_id)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
 }
@@ -0,0 +1,57 @@
+//! Saved segment filter ("saved view") operations.
+
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+use crate::db::collation::Collator;
+use crate::db::error::DbResult;
+use crate::db::types::{NewSavedSegmentFilterArgs, SavedSegmentFilterRecord};
+
+/// Stores a filter under its name, replacing the definition of an existing one.
+pub async fn upsert_saved_filter(
+    pool: &SqlitePool,
+    args: NewSavedSegmentFilterArgs,
+) -> DbResult<SavedSegmentFilterRecord> {
+    let record = sqlx::query_as::<_, SavedSegmentFilterRecord>(
+        r#"
+        INSERT INTO saved_segment_filters (filter_uuid, user_uuid, name, filter_json)
+        VALUES (?1, ?2, ?3, ?4)
+        ON CONFLICT (user_uuid, name) DO UPDATE SET
+            filter_json = excluded.filter_json,
+            updated_at = CURRENT_TIMESTAMP
+        RETURNING *
+        "#,
+    )
+    .bind(Uuid::new_v4())
+    .bind(args.user_uuid)
+    .bind(&args.name)
+    .bind(&args.filter_json)
+    .fetch_one(pool)
+    .await?;
+    Ok(record)
+}
+
+/// Lists a user's saved filters by name.
+pub async fn list_saved_filters(
+    pool: &SqlitePool,
+    user_uuid: Uuid,
+    collator: &Collator,
+) -> DbResult<Vec<SavedSegmentFilterRecord>> {
+    let mut records = sqlx::query_as::<_, SavedSegmentFilterRecord>(
+        "SELECT * FROM saved_segment_filters WHERE user_uuid = ?1",
+    )
+    .bind(user_uuid)
+    .fetch_all(pool)
+    .await?;
+    collator.sort_by_text(&mut records, |filter| &filter.name);
+    Ok(records)
+}
+
+/// Removes a saved filter.
+pub async fn delete_saved_filter(pool: &SqlitePool, filter_uuid: Uuid) -> DbResult<()> {
+    sqlx::query("DELETE FROM saved_segment_filters WHERE filter_uuid = ?1")
+        .bind(filter_uuid)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
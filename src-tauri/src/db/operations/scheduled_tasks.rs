@@ -0,0 +1,63 @@
+//! Run history of background tasks driven by the scheduler.
+
+use sqlx::SqlitePool;
+
+use crate::db::error::DbResult;
+use crate::db::types::ScheduledTaskRunRecord;
+
+/// Records that a task started, counting the run.
+pub async fn record_started(pool: &SqlitePool, task_id: &str, started_at: &str) -> DbResult<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO scheduled_task_runs (
+            task_id, last_started_at, last_finished_at, last_status, last_error, run_count
+        )
+        VALUES (?1, ?2, NULL, 'running', NULL, 1)
+        ON CONFLICT (task_id) DO UPDATE SET
+            last_started_at = excluded.last_started_at,
+            last_finished_at = NULL,
+            last_status = 'running',
+            last_error = NULL,
+            run_count = run_count + 1
+        "#,
+    )
+    .bind(task_id)
+    .bind(started_at)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Records the outcome of the current run of a task; `error` is `None` on success.
+pub async fn record_finished(
+    pool: &SqlitePool,
+    task_id: &str,
+    finished_at: &str,
+    error: Option<&str>,
+) -> DbResult<()> {
+    sqlx::query(
+        r#"
+        UPDATE scheduled_task_runs
+        SET last_finished_at = ?2,
+            last_status = CASE WHEN ?3 IS NULL THEN 'succeeded' ELSE 'failed' END,
+            last_error = ?3
+        WHERE task_id = ?1
+        "#,
+    )
+    .bind(task_id)
+    .bind(finished_at)
+    .bind(error)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Lists the run history of every task that ran at least once.
+pub async fn list_runs(pool: &SqlitePool) -> DbResult<Vec<ScheduledTaskRunRecord>> {
+    let records = sqlx::query_as::<_, ScheduledTaskRunRecord>(
+        "SELECT * FROM scheduled_task_runs ORDER BY task_id ASC",
+    )
+    .fetch_all(pool)
+    .await?;
+    Ok(records)
+}
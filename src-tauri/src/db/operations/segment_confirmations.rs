@@ -0,0 +1,77 @@
+//! Segment confirmation history and productivity aggregation.
+
+use serde_json::json;
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+use super::outbox::enqueue_event;
+use crate::db::error::DbResult;
+use crate::db::types::{DailyProductivityRecord, NewSegmentConfirmationArgs};
+use crate::ipc::events::SEGMENT_CONFIRMED;
+
+/// Records that a segment was confirmed, with a `segments://confirmed` event.
+pub async fn record_confirmation(
+    pool: &SqlitePool,
+    args: NewSegmentConfirmationArgs,
+) -> DbResult<()> {
+    let mut tx = pool.begin().await?;
+    sqlx::query(
+        r#"
+        INSERT INTO segment_confirmations (
+            confirmation_uuid,
+            project_uuid,
+            jliff_rel_path,
+            transunit_id,
+            user_uuid,
+            word_count
+        )
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+        "#,
+    )
+    .bind(Uuid::new_v4())
+    .bind(args.project_uuid)
+    .bind(&args.jliff_rel_path)
+    .bind(&args.transunit_id)
+    .bind(args.user_uuid)
+    .bind(args.word_count)
+    .execute(&mut *tx)
+    .await?;
+    let payload = json!({
+        "projectUuid": args.project_uuid.to_string(),
+        "jliffRelPath": args.jliff_rel_path,
+        "transunitId": args.transunit_id,
+        "userUuid": args.user_uuid.map(|user_uuid| user_uuid.to_string()),
+        "wordCount": args.word_count,
+    });
+    enqueue_event(&mut tx, SEGMENT_CONFIRMED, &payload).await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Sums a user's confirmations per UTC day for `from <= confirmed_at < until`, where both
+/// bounds are SQLite datetimes. Days without confirmations are omitted.
+pub async fn daily_productivity(
+    pool: &SqlitePool,
+    user_uuid: Uuid,
+    from: &str,
+    until: &str,
+) -> DbResult<Vec<DailyProductivityRecord>> {
+    let records = sqlx::query_as::<_, DailyProductivityRecord>(
+        r#"
+        SELECT
+            date(confirmed_at) AS day,
+            COUNT(*) AS segments,
+            COALESCE(SUM(word_count), 0) AS words
+        FROM segment_confirmations
+        WHERE user_uuid = ?1 AND confirmed_at >= ?2 AND confirmed_at < ?3
+        GROUP BY day
+        ORDER BY day ASC
+        "#,
+    )
+    .bind(user_uuid)
+    .bind(from)
+    .bind(until)
+    .fetch_all(pool)
+    .await?;
+    Ok(records)
+}
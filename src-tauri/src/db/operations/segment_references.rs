@@ -0,0 +1,124 @@
+//! Links between reference assets and the transunits they illustrate.
+
+use std::collections::HashMap;
+
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+use crate::db::error::DbResult;
+use crate::db::types::{NewSegmentReferenceArgs, SegmentReferenceRecord};
+
+pub async fn insert_reference(
+    pool: &SqlitePool,
+    args: NewSegmentReferenceArgs,
+) -> DbResult<SegmentReferenceRecord> {
+    let record = sqlx::query_as::<_, SegmentReferenceRecord>(
+        r#"
+        INSERT INTO segment_references (
+            reference_uuid, project_uuid, jliff_rel_path, transunit_id, file_uuid, anchor, note
+        )
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+        RETURNING *
+        "#,
+    )
+    .bind(args.reference_uuid)
+    .bind(args.project_uuid)
+    .bind(&args.jliff_rel_path)
+    .bind(&args.transunit_id)
+    .bind(args.file_uuid)
+    .bind(&args.anchor)
+    .bind(&args.note)
+    .fetch_one(pool)
+    .await?;
+    Ok(record)
+}
+
+pub async fn get_reference(
+    pool: &SqlitePool,
+    reference_uuid: Uuid,
+) -> DbResult<Option<SegmentReferenceRecord>> {
+    let record = sqlx::query_as::<_, SegmentReferenceRecord>(
+        "SELECT * FROM segment_references WHERE reference_uuid = ?1",
+    )
+    .bind(reference_uuid)
+    .fetch_optional(pool)
+    .await?;
+    Ok(record)
+}
+
+/// Lists the references of a document, or of one of its transunits, in the order they were
+/// linked.
+pub async fn list_references(
+    pool: &SqlitePool,
+    project_uuid: Uuid,
+    jliff_rel_path: &str,
+    transunit_id: Option<&str>,
+) -> DbResult<Vec<SegmentReferenceRecord>> {
+    let records = sqlx::query_as::<_, SegmentReferenceRecord>(
+        r#"
+        SELECT * FROM segment_references
+        WHERE project_uuid = ?1
+          AND jliff_rel_path = ?2
+          AND (?3 IS NULL OR transunit_id = ?3)
+        ORDER BY created_at, rowid
+        "#,
+    )
+    .bind(project_uuid)
+    .bind(jliff_rel_path)
+    .bind(transunit_id)
+    .fetch_all(pool)
+    .await?;
+    Ok(records)
+}
+
+/// Replaces the anchor and note of a reference.
+pub async fn update_reference(
+    pool: &SqlitePool,
+    reference_uuid: Uuid,
+    anchor: Option<&str>,
+    note: Option<&str>,
+) -> DbResult<Option<SegmentReferenceRecord>> {
+    let record = sqlx::query_as::<_, SegmentReferenceRecord>(
+        r#"
+        UPDATE segment_references
+        SET anchor = ?2, note = ?3, updated_at = CURRENT_TIMESTAMP
+        WHERE reference_uuid = ?1
+        RETURNING *
+        "#,
+    )
+    .bind(reference_uuid)
+    .bind(anchor)
+    .bind(note)
+    .fetch_optional(pool)
+    .await?;
+    Ok(record)
+}
+
+pub async fn delete_reference(pool: &SqlitePool, reference_uuid: Uuid) -> DbResult<bool> {
+    let result = sqlx::query("DELETE FROM segment_references WHERE reference_uuid = ?1")
+        .bind(reference_uuid)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Counts the references of each transunit of a document; transunits without any are left
+/// out.
+pub async fn count_references(
+    pool: &SqlitePool,
+    project_uuid: Uuid,
+    jliff_rel_path: &str,
+) -> DbResult<HashMap<String, i64>> {
+    let rows = sqlx::query_as::<_, (String, i64)>(
+        r#"
+        SELECT transunit_id, COUNT(*) FROM segment_references
+        WHERE project_uuid = ?1 AND jliff_rel_path = ?2
+        GROUP BY transunit_id
+        "#,
+    )
+    .bind(project_uuid)
+    .bind(jliff_rel_path)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows.into_iter().collect())
+}
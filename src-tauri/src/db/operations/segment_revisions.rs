@@ -0,0 +1,95 @@
+//! Segment revision history operations.
+
+use sqlx::{QueryBuilder, Sqlite, SqlitePool};
+use uuid::Uuid;
+
+use crate::db::error::DbResult;
+use crate::db::types::{RecordSegmentRevisionsArgs, SegmentRevisionRecord};
+
+/// Inserts one revision per changed transunit in a single transaction.
+pub async fn record_revisions(
+    pool: &SqlitePool,
+    args: RecordSegmentRevisionsArgs,
+) -> DbResult<Vec<SegmentRevisionRecord>> {
+    let mut tx = pool.begin().await?;
+
+    let mut inserted = Vec::with_capacity(args.revisions.len());
+    for revision in &args.revisions {
+        let record = sqlx::query_as::<_, SegmentRevisionRecord>(
+            r#"
+            INSERT INTO segment_revisions (
+                revision_uuid,
+                project_uuid,
+                jliff_rel_path,
+                transunit_id,
+                previous_target,
+                new_target,
+                origin,
+                author
+            )
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+            RETURNING *
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(args.project_uuid)
+        .bind(&args.jliff_rel_path)
+        .bind(&revision.transunit_id)
+        .bind(&revision.previous_target)
+        .bind(&revision.new_target)
+        .bind(&args.origin)
+        .bind(&args.author)
+        .fetch_one(&mut *tx)
+        .await?;
+        inserted.push(record);
+    }
+
+    tx.commit().await?;
+    Ok(inserted)
+}
+
+/// Lists revisions of a document (or one of its transunits), oldest first.
+pub async fn list_revisions(
+    pool: &SqlitePool,
+    project_uuid: Uuid,
+    jliff_rel_path: &str,
+    transunit_id: Option<&str>,
+) -> DbResult<Vec<SegmentRevisionRecord>> {
+    let mut builder =
+        QueryBuilder::<Sqlite>::new("SELECT * FROM segment_revisions WHERE project_uuid = ");
+    builder.push_bind(project_uuid);
+    builder.push(" AND jliff_rel_path = ");
+    builder.push_bind(jliff_rel_path);
+    if let Some(transunit_id) = transunit_id {
+        builder.push(" AND transunit_id = ");
+        builder.push_bind(transunit_id);
+    }
+    builder.push(" ORDER BY transunit_id ASC, created_at ASC");
+
+    let records = builder
+        .build_query_as::<SegmentRevisionRecord>()
+        .fetch_all(pool)
+        .await?;
+    Ok(records)
+}
+
+/// Lists the transunits of a document revised at or after `since` (an SQLite datetime).
+pub async fn list_revised_transunits_since(
+    pool: &SqlitePool,
+    project_uuid: Uuid,
+    jliff_rel_path: &str,
+    since: &str,
+) -> DbResult<Vec<String>> {
+    let ids = sqlx::query_scalar::<_, String>(
+        r#"
+        SELECT DISTINCT transunit_id FROM segment_revisions
+        WHERE project_uuid = ?1 AND jliff_rel_path = ?2 AND created_at >= ?3
+        "#,
+    )
+    .bind(project_uuid)
+    .bind(jliff_rel_path)
+    .bind(since)
+    .fetch_all(pool)
+    .await?;
+    Ok(ids)
+}
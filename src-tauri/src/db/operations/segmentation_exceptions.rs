@@ -0,0 +1,147 @@
+//! Segmentation exceptions of projects and of the whole workspace.
+
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+use crate::db::error::DbResult;
+use crate::db::types::{NewSegmentationExceptionArgs, SegmentationExceptionRecord};
+
+/// Inserts a segmentation exception.
+pub async fn create_exception(
+    pool: &SqlitePool,
+    args: NewSegmentationExceptionArgs,
+) -> DbResult<SegmentationExceptionRecord> {
+    let record = sqlx::query_as::<_, SegmentationExceptionRecord>(
+        r#"
+        INSERT INTO segmentation_exceptions (
+            exception_uuid,
+            project_uuid,
+            language,
+            kind,
+            before_text,
+            after_text
+        )
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+        RETURNING *
+        "#,
+    )
+    .bind(args.exception_uuid)
+    .bind(args.project_uuid)
+    .bind(&args.language)
+    .bind(&args.kind)
+    .bind(&args.before_text)
+    .bind(&args.after_text)
+    .fetch_one(pool)
+    .await?;
+    Ok(record)
+}
+
+/// Adds exceptions in a single transaction, skipping those that already exist with the same
+/// scope, language, kind and texts; returns the exceptions created.
+pub async fn add_missing_exceptions(
+    pool: &SqlitePool,
+    exceptions: &[NewSegmentationExceptionArgs],
+) -> DbResult<Vec<SegmentationExceptionRecord>> {
+    let mut tx = pool.begin().await?;
+
+    let mut created = Vec::new();
+    for args in exceptions {
+        let record = sqlx::query_as::<_, SegmentationExceptionRecord>(
+            r#"
+            INSERT INTO segmentation_exceptions (
+                exception_uuid,
+                project_uuid,
+                language,
+                kind,
+                before_text,
+                after_text
+            )
+            SELECT ?1, ?2, ?3, ?4, ?5, ?6
+            WHERE NOT EXISTS (
+                SELECT 1 FROM segmentation_exceptions
+                WHERE project_uuid IS ?2
+                  AND language = ?3
+                  AND kind = ?4
+                  AND before_text = ?5
+                  AND after_text = ?6
+            )
+            RETURNING *
+            "#,
+        )
+        .bind(args.exception_uuid)
+        .bind(args.project_uuid)
+        .bind(&args.language)
+        .bind(&args.kind)
+        .bind(&args.before_text)
+        .bind(&args.after_text)
+        .fetch_optional(&mut *tx)
+        .await?;
+        created.extend(record);
+    }
+
+    tx.commit().await?;
+    Ok(created)
+}
+
+/// Deletes a segmentation exception, returning whether it existed.
+pub async fn delete_exception(pool: &SqlitePool, exception_uuid: Uuid) -> DbResult<bool> {
+    let result = sqlx::query("DELETE FROM segmentation_exceptions WHERE exception_uuid = ?1")
+        .bind(exception_uuid)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Lists the exceptions of a project, or the global ones when `project_uuid` is `None`.
+pub async fn list_exceptions(
+    pool: &SqlitePool,
+    project_uuid: Option<Uuid>,
+) -> DbResult<Vec<SegmentationExceptionRecord>> {
+    let records = sqlx::query_as::<_, SegmentationExceptionRecord>(
+        r#"
+        SELECT *
+        FROM segmentation_exceptions
+        WHERE project_uuid IS ?1
+        ORDER BY language ASC, created_at ASC
+        "#,
+    )
+    .bind(project_uuid)
+    .fetch_all(pool)
+    .await?;
+    Ok(records)
+}
+
+/// Exceptions applying to `language` in a project: the project's own first, then the global
+/// ones. An exception for a bare language such as `de` also applies to `de-AT`.
+pub async fn exceptions_for_language(
+    pool: &SqlitePool,
+    project_uuid: Option<Uuid>,
+    language: &str,
+) -> DbResult<Vec<SegmentationExceptionRecord>> {
+    let records = sqlx::query_as::<_, SegmentationExceptionRecord>(
+        r#"
+        SELECT *
+        FROM segmentation_exceptions
+        WHERE project_uuid IS NULL OR project_uuid = ?1
+        ORDER BY project_uuid IS NULL, created_at ASC
+        "#,
+    )
+    .bind(project_uuid)
+    .fetch_all(pool)
+    .await?;
+    let language = language.trim().replace('_', "-");
+    Ok(records
+        .into_iter()
+        .filter(|record| language_applies(&record.language, &language))
+        .collect())
+}
+
+fn language_applies(exception_language: &str, language: &str) -> bool {
+    language.eq_ignore_ascii_case(exception_language)
+        || language
+            .get(..exception_language.len() + 1)
+            .is_some_and(|prefix| {
+                prefix.ends_with('-')
+                    && prefix[..exception_language.len()].eq_ignore_ascii_case(exception_language)
+            })
+}
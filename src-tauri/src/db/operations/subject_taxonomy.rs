@@ -0,0 +1,317 @@
+//! Managed project subjects and their aliases.
+//!
+//! Subjects and aliases are matched by [`subject_key`], so spelling variants such as "I.T."
+//! and "it" resolve to the same managed subject. Project subjects are resolved when they are
+//! written and rewritten when a subject or alias is saved; subjects that match nothing are
+//! kept as typed, with whitespace tidied.
+
+use std::collections::{HashMap, HashSet};
+
+use sqlx::{Sqlite, SqlitePool, Transaction};
+
+use crate::db::error::{DbError, DbResult};
+use crate::db::search::search_key;
+use crate::db::types::{SubjectSuggestionRecord, SubjectTaxonomyRecord};
+
+/// Match key of a subject or alias: folded like a search key, with dots and apostrophes
+/// dropped and any other punctuation treated as a word break.
+pub fn subject_key(text: &str) -> String {
+    let mut key = String::with_capacity(text.len());
+    for ch in search_key(text).chars() {
+        if ch.is_alphanumeric() {
+            key.push(ch);
+        } else if !matches!(ch, '.' | '\'' | '’') {
+            key.push(' ');
+        }
+    }
+    key.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// `text` with surrounding whitespace removed and inner runs collapsed to one space.
+pub fn tidy_subject(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// The managed subject `subject` resolves to, or `subject` tidied when it matches none.
+pub async fn resolve_subject(tx: &mut Transaction<'_, Sqlite>, subject: &str) -> DbResult<String> {
+    let key = subject_key(subject);
+    let managed: Option<String> = sqlx::query_scalar(
+        "SELECT subject FROM subject_taxonomy WHERE subject_key = ?1
+         UNION ALL
+         SELECT subject FROM subject_aliases WHERE alias_key = ?1
+         LIMIT 1",
+    )
+    .bind(&key)
+    .fetch_optional(&mut **tx)
+    .await?;
+
+    Ok(managed.unwrap_or_else(|| tidy_subject(subject)))
+}
+
+/// Adds a managed subject, or renames the one with the same key, and replaces its aliases.
+/// Project subjects matching the subject or one of its aliases are rewritten to it.
+pub async fn upsert_subject(
+    pool: &SqlitePool,
+    subject: &str,
+    aliases: &[String],
+) -> DbResult<SubjectTaxonomyRecord> {
+    let subject = tidy_subject(subject);
+    let key = subject_key(&subject);
+    let mut tx = pool.begin().await?;
+
+    let owner: Option<String> =
+        sqlx::query_scalar("SELECT subject FROM subject_aliases WHERE alias_key = ?1")
+            .bind(&key)
+            .fetch_optional(&mut *tx)
+            .await?;
+    if let Some(owner) = owner {
+        return Err(DbError::ConstraintViolation(format!(
+            "subject_aliases: '{subject}' is already an alias of '{owner}'"
+        )));
+    }
+
+    let existing: Option<String> =
+        sqlx::query_scalar("SELECT subject FROM subject_taxonomy WHERE subject_key = ?1")
+            .bind(&key)
+            .fetch_optional(&mut *tx)
+            .await?;
+    match existing {
+        Some(existing) if existing != subject => {
+            sqlx::query("UPDATE subject_taxonomy SET subject = ?2 WHERE subject = ?1")
+                .bind(&existing)
+                .bind(&subject)
+                .execute(&mut *tx)
+                .await?;
+        }
+        Some(_) => {}
+        None => {
+            sqlx::query("INSERT INTO subject_taxonomy (subject, subject_key) VALUES (?1, ?2)")
+                .bind(&subject)
+                .bind(&key)
+                .execute(&mut *tx)
+                .await?;
+        }
+    }
+
+    sqlx::query("DELETE FROM subject_aliases WHERE subject = ?1")
+        .bind(&subject)
+        .execute(&mut *tx)
+        .await?;
+    let mut keys: HashSet<String> = HashSet::from([key]);
+    for alias in aliases {
+        let alias = tidy_subject(alias);
+        let alias_key = subject_key(&alias);
+        if alias_key.is_empty() || !keys.insert(alias_key.clone()) {
+            continue;
+        }
+        let other: Option<String> =
+            sqlx::query_scalar("SELECT subject FROM subject_taxonomy WHERE subject_key = ?1")
+                .bind(&alias_key)
+                .fetch_optional(&mut *tx)
+                .await?;
+        if let Some(other) = other {
+            return Err(DbError::ConstraintViolation(format!(
+                "subject_aliases: '{alias}' is already the subject '{other}'"
+            )));
+        }
+        sqlx::query("INSERT INTO subject_aliases (alias_key, alias, subject) VALUES (?1, ?2, ?3)")
+            .bind(&alias_key)
+            .bind(&alias)
+            .bind(&subject)
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    let used: Vec<String> = sqlx::query_scalar("SELECT DISTINCT subject FROM project_subjects")
+        .fetch_all(&mut *tx)
+        .await?;
+    for variant in used
+        .into_iter()
+        .filter(|used| *used != subject && keys.contains(&subject_key(used)))
+    {
+        // A project tagged with both spellings keeps a single row.
+        sqlx::query("UPDATE OR IGNORE project_subjects SET subject = ?2 WHERE subject = ?1")
+            .bind(&variant)
+            .bind(&subject)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("DELETE FROM project_subjects WHERE subject = ?1")
+            .bind(&variant)
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    tx.commit().await?;
+
+    let record = list_subjects(pool)
+        .await?
+        .into_iter()
+        .find(|record| record.subject == subject);
+    record.ok_or_else(|| sqlx::Error::RowNotFound.into())
+}
+
+/// Removes a managed subject and its aliases. Projects keep the subject as free text.
+pub async fn delete_subject(pool: &SqlitePool, subject: &str) -> DbResult<bool> {
+    let result = sqlx::query("DELETE FROM subject_taxonomy WHERE subject = ?1")
+        .bind(subject)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Lists the managed subjects alphabetically, with their aliases.
+pub async fn list_subjects(pool: &SqlitePool) -> DbResult<Vec<SubjectTaxonomyRecord>> {
+    let subjects: Vec<(String, String)> =
+        sqlx::query_as("SELECT subject, created_at FROM subject_taxonomy ORDER BY subject_key ASC")
+            .fetch_all(pool)
+            .await?;
+    let aliases: Vec<(String, String)> =
+        sqlx::query_as("SELECT subject, alias FROM subject_aliases ORDER BY alias_key ASC")
+            .fetch_all(pool)
+            .await?;
+
+    let mut by_subject: HashMap<String, Vec<String>> = HashMap::new();
+    for (subject, alias) in aliases {
+        by_subject.entry(subject).or_default().push(alias);
+    }
+    Ok(subjects
+        .into_iter()
+        .map(|(subject, created_at)| SubjectTaxonomyRecord {
+            aliases: by_subject.remove(&subject).unwrap_or_default(),
+            subject,
+            created_at,
+        })
+        .collect())
+}
+
+/// Subjects with a word starting with `prefix`: managed subjects (also found through their
+/// aliases) first, then subjects only used by projects, most used first.
+pub async fn suggest_subjects(
+    pool: &SqlitePool,
+    prefix: &str,
+    limit: usize,
+) -> DbResult<Vec<SubjectSuggestionRecord>> {
+    let taxonomy = list_subjects(pool).await?;
+    let usage: Vec<(String, i64)> =
+        sqlx::query_as("SELECT subject, COUNT(*) FROM project_subjects GROUP BY subject")
+            .fetch_all(pool)
+            .await?;
+    Ok(rank_suggestions(
+        &subject_key(prefix),
+        &taxonomy,
+        &usage,
+        limit,
+    ))
+}
+
+fn rank_suggestions(
+    prefix: &str,
+    taxonomy: &[SubjectTaxonomyRecord],
+    usage: &[(String, i64)],
+    limit: usize,
+) -> Vec<SubjectSuggestionRecord> {
+    let matches = |key: &str| {
+        key.starts_with(prefix)
+            || key
+                .match_indices(' ')
+                .any(|(at, _)| key[at + 1..].starts_with(prefix))
+    };
+
+    let mut managed_keys: HashMap<String, usize> = HashMap::new();
+    for (index, record) in taxonomy.iter().enumerate() {
+        managed_keys.insert(subject_key(&record.subject), index);
+        for alias in &record.aliases {
+            managed_keys.insert(subject_key(alias), index);
+        }
+    }
+    let mut managed_counts = vec![0; taxonomy.len()];
+    let mut suggestions = Vec::new();
+    for (subject, count) in usage {
+        match managed_keys.get(&subject_key(subject)) {
+            Some(&index) => managed_counts[index] += count,
+            None if matches(&subject_key(subject)) => suggestions.push(SubjectSuggestionRecord {
+                subject: subject.clone(),
+                matched_alias: None,
+                managed: false,
+                project_count: *count,
+            }),
+            None => {}
+        }
+    }
+    for (record, project_count) in taxonomy.iter().zip(managed_counts) {
+        let matched_alias = if matches(&subject_key(&record.subject)) {
+            None
+        } else if let Some(alias) = record
+            .aliases
+            .iter()
+            .find(|alias| matches(&subject_key(alias)))
+        {
+            Some(alias.clone())
+        } else {
+            continue;
+        };
+        suggestions.push(SubjectSuggestionRecord {
+            subject: record.subject.clone(),
+            matched_alias,
+            managed: true,
+            project_count,
+        });
+    }
+
+    suggestions.sort_by(|left, right| {
+        right
+            .managed
+            .cmp(&left.managed)
+            .then(right.project_count.cmp(&left.project_count))
+            .then_with(|| subject_key(&left.subject).cmp(&subject_key(&right.subject)))
+    });
+    suggestions.truncate(limit);
+    suggestions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_fold_spelling_variants_and_suggestions_rank_managed_subjects_first() {
+        assert_eq!(subject_key(" I.T. "), "it");
+        assert_eq!(subject_key("E-Commerce"), subject_key("e commerce"));
+        assert_eq!(subject_key("Ärzte’s  Guide"), "arztes guide");
+
+        let taxonomy = vec![SubjectTaxonomyRecord {
+            subject: "Information Technology".into(),
+            aliases: vec!["IT".into(), "Software".into()],
+            created_at: String::new(),
+        }];
+        let usage = vec![
+            ("I.T.".to_string(), 2),
+            ("Software Localization".to_string(), 3),
+            ("Legal".to_string(), 5),
+        ];
+
+        let suggestions = rank_suggestions(&subject_key("soft"), &taxonomy, &usage, 10);
+        let summary: Vec<(&str, Option<&str>, bool, i64)> = suggestions
+            .iter()
+            .map(|suggestion| {
+                (
+                    suggestion.subject.as_str(),
+                    suggestion.matched_alias.as_deref(),
+                    suggestion.managed,
+                    suggestion.project_count,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("Information Technology", Some("Software"), true, 2),
+                ("Software Localization", None, false, 3),
+            ]
+        );
+
+        let by_word = rank_suggestions(&subject_key("tech"), &taxonomy, &usage, 10);
+        assert_eq!(by_word[0].matched_alias, None);
+        assert_eq!(rank_suggestions("", &taxonomy, &usage, 2).len(), 2);
+    }
+}
@@ -0,0 +1,233 @@
+//! Termbase and term candidate operations.
+
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+use crate::db::error::DbResult;
+use crate::db::types::{
+    NewTermCandidateArgs, NewTermbaseEntryArgs, TermCandidateDecisionArgs, TermCandidateRecord,
+    TermbaseEntryRecord,
+};
+
+/// Replaces the pending candidates of a project language pair with a new extraction run.
+/// Candidates already accepted or rejected are kept and not proposed again.
+pub async fn replace_pending_candidates(
+    pool: &SqlitePool,
+    project_uuid: Uuid,
+    source_lang: &str,
+    target_lang: &str,
+    candidates: &[NewTermCandidateArgs],
+) -> DbResult<u64> {
+    let source_lang = source_lang.trim().to_lowercase();
+    let target_lang = target_lang.trim().to_lowercase();
+    let mut tx = pool.begin().await?;
+
+    sqlx::query(
+        r#"
+        DELETE FROM term_candidates
+        WHERE project_uuid = ?1 AND source_lang = ?2 AND target_lang = ?3 AND status = 'pending'
+        "#,
+    )
+    .bind(project_uuid)
+    .bind(&source_lang)
+    .bind(&target_lang)
+    .execute(&mut *tx)
+    .await?;
+
+    let mut inserted = 0;
+    for candidate in candidates {
+        inserted += sqlx::query(
+            r#"
+            INSERT INTO term_candidates (
+                candidate_uuid,
+                project_uuid,
+                source_lang,
+                target_lang,
+                term,
+                term_key,
+                target_term,
+                frequency,
+                score
+            )
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+            ON CONFLICT(project_uuid, source_lang, target_lang, term_key) DO NOTHING
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(project_uuid)
+        .bind(&source_lang)
+        .bind(&target_lang)
+        .bind(&candidate.term)
+        .bind(&candidate.term_key)
+        .bind(&candidate.target_term)
+        .bind(candidate.frequency)
+        .bind(candidate.score)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+    }
+
+    tx.commit().await?;
+    Ok(inserted)
+}
+
+/// Lists the candidates of a project, best first, optionally restricted to one status.
+pub async fn list_candidates(
+    pool: &SqlitePool,
+    project_uuid: Uuid,
+    status: Option<&str>,
+) -> DbResult<Vec<TermCandidateRecord>> {
+    let records = sqlx::query_as::<_, TermCandidateRecord>(
+        r#"
+        SELECT * FROM term_candidates
+        WHERE project_uuid = ?1 AND (?2 IS NULL OR status = ?2)
+        ORDER BY source_lang ASC, target_lang ASC, score DESC, term_key ASC
+        "#,
+    )
+    .bind(project_uuid)
+    .bind(status)
+    .fetch_all(pool)
+    .await?;
+    Ok(records)
+}
+
+/// Records review decisions in a single transaction. Accepted candidates are added to
+/// `termbase`; returns the termbase entries created.
+pub async fn decide_candidates(
+    pool: &SqlitePool,
+    project_uuid: Uuid,
+    termbase: &str,
+    decisions: &[TermCandidateDecisionArgs],
+) -> DbResult<Vec<TermbaseEntryRecord>> {
+    let mut tx = pool.begin().await?;
+
+    let mut entries = Vec::new();
+    for decision in decisions {
+        let Some(candidate) = sqlx::query_as::<_, TermCandidateRecord>(
+            r#"
+            UPDATE term_candidates
+            SET status = ?3,
+                target_term = COALESCE(?4, target_term)
+            WHERE project_uuid = ?1 AND candidate_uuid = ?2
+            RETURNING *
+            "#,
+        )
+        .bind(project_uuid)
+        .bind(decision.candidate_uuid)
+        .bind(&decision.status)
+        .bind(&decision.target_term)
+        .fetch_optional(&mut *tx)
+        .await?
+        else {
+            continue;
+        };
+        if candidate.status != "accepted" {
+            continue;
+        }
+
+        let entry = sqlx::query_as::<_, TermbaseEntryRecord>(
+            r#"
+            INSERT INTO termbase_entries (
+                entry_uuid,
+                termbase,
+                source_lang,
+                target_lang,
+                source_term,
+                target_term,
+                project_uuid
+            )
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+            ON CONFLICT(termbase, source_lang, target_lang, source_term, target_term) DO NOTHING
+            RETURNING *
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(termbase)
+        .bind(&candidate.source_lang)
+        .bind(&candidate.target_lang)
+        .bind(&candidate.term)
+        .bind(candidate.target_term.as_deref().unwrap_or_default())
+        .bind(project_uuid)
+        .fetch_optional(&mut *tx)
+        .await?;
+        entries.extend(entry);
+    }
+
+    tx.commit().await?;
+    Ok(entries)
+}
+
+/// Lists the termbase entries of a language pair across termbases.
+pub async fn list_termbase_entries(
+    pool: &SqlitePool,
+    source_lang: &str,
+    target_lang: &str,
+) -> DbResult<Vec<TermbaseEntryRecord>> {
+    let records = sqlx::query_as::<_, TermbaseEntryRecord>(
+        r#"
+        SELECT * FROM termbase_entries
+        WHERE source_lang = ?1 AND target_lang = ?2
+        ORDER BY termbase ASC, source_term ASC, target_term ASC
+        "#,
+    )
+    .bind(source_lang.trim().to_lowercase())
+    .bind(target_lang.trim().to_lowercase())
+    .fetch_all(pool)
+    .await?;
+    Ok(records)
+}
+
+/// Lists every termbase entry.
+pub async fn list_all_termbase_entries(pool: &SqlitePool) -> DbResult<Vec<TermbaseEntryRecord>> {
+    let records = sqlx::query_as::<_, TermbaseEntryRecord>(
+        r#"
+        SELECT * FROM termbase_entries
+        ORDER BY termbase ASC, source_lang ASC, target_lang ASC, source_term ASC, target_term ASC
+        "#,
+    )
+    .fetch_all(pool)
+    .await?;
+    Ok(records)
+}
+
+/// Adds entries in a single transaction, skipping those already in their termbase; returns
+/// the entries created.
+pub async fn add_termbase_entries(
+    pool: &SqlitePool,
+    entries: &[NewTermbaseEntryArgs],
+) -> DbResult<Vec<TermbaseEntryRecord>> {
+    let mut tx = pool.begin().await?;
+
+    let mut created = Vec::new();
+    for entry in entries {
+        let record = sqlx::query_as::<_, TermbaseEntryRecord>(
+            r#"
+            INSERT INTO termbase_entries (
+                entry_uuid,
+                termbase,
+                source_lang,
+                target_lang,
+                source_term,
+                target_term,
+                project_uuid
+            )
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+            ON CONFLICT(termbase, source_lang, target_lang, source_term, target_term) DO NOTHING
+            RETURNING *
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(&entry.termbase)
+        .bind(entry.source_lang.trim().to_lowercase())
+        .bind(entry.target_lang.trim().to_lowercase())
+        .bind(&entry.source_term)
+        .bind(&entry.target_term)
+        .bind(entry.project_uuid)
+        .fetch_optional(&mut *tx)
+        .await?;
+        created.extend(record);
+    }
+
+    tx.commit().await?;
+    Ok(created)
+}
@@ -0,0 +1,60 @@
+//! Project translation memory assignment operations.
+
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+use crate::db::error::DbResult;
+use crate::db::types::{ProjectTmAssignmentArgs, ProjectTmAssignmentRecord};
+
+/// Replaces the translation memories assigned to a project in a single transaction.
+pub async fn replace_assignments(
+    pool: &SqlitePool,
+    project_uuid: Uuid,
+    assignments: &[ProjectTmAssignmentArgs],
+) -> DbResult<Vec<ProjectTmAssignmentRecord>> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query("DELETE FROM project_tm_assignments WHERE project_uuid = ?1")
+        .bind(project_uuid)
+        .execute(&mut *tx)
+        .await?;
+
+    for assignment in assignments {
+        sqlx::query(
+            r#"
+            INSERT INTO project_tm_assignments (project_uuid, tm_origin, priority, penalty)
+            VALUES (?1, ?2, ?3, ?4)
+            ON CONFLICT (project_uuid, tm_origin) DO UPDATE SET
+                priority = excluded.priority,
+                penalty = excluded.penalty
+            "#,
+        )
+        .bind(project_uuid)
+        .bind(&assignment.tm_origin)
+        .bind(assignment.priority)
+        .bind(assignment.penalty)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+    list_assignments(pool, project_uuid).await
+}
+
+/// Lists the translation memories assigned to a project in the order they are consulted.
+pub async fn list_assignments(
+    pool: &SqlitePool,
+    project_uuid: Uuid,
+) -> DbResult<Vec<ProjectTmAssignmentRecord>> {
+    let records = sqlx::query_as::<_, ProjectTmAssignmentRecord>(
+        r#"
+        SELECT * FROM project_tm_assignments
+        WHERE project_uuid = ?1
+        ORDER BY priority ASC, tm_origin ASC
+        "#,
+    )
+    .bind(project_uuid)
+    .fetch_all(pool)
+    .await?;
+    Ok(records)
+}
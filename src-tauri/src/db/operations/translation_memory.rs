@@ -0,0 +1,138 @@
+//! Translation memory operations.
+
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+use crate::db::error::DbResult;
+use crate::db::types::{
+    NewTranslationMemoryEntryArgs, TranslationMemoryEntryRecord, UpdateTranslationMemoryEntryArgs,
+};
+
+/// Inserts entries in a single transaction, refreshing pairs that already exist.
+pub async fn upsert_entries(
+    pool: &SqlitePool,
+    entries: &[NewTranslationMemoryEntryArgs],
+) -> DbResult<Vec<TranslationMemoryEntryRecord>> {
+    let mut tx = pool.begin().await?;
+
+    let mut stored = Vec::with_capacity(entries.len());
+    for entry in entries {
+        let record = sqlx::query_as::<_, TranslationMemoryEntryRecord>(
+            r#"
+            INSERT INTO translation_memory_entries (
+                entry_uuid,
+                source_lang,
+                target_lang,
+                source_text,
+                target_text,
+                project_uuid,
+                origin
+            )
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+            ON CONFLICT (source_lang, target_lang, source_text, target_text) DO UPDATE SET
+                project_uuid = excluded.project_uuid,
+                origin = excluded.origin,
+                updated_at = CURRENT_TIMESTAMP
+            RETURNING *
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(entry.source_lang.trim().to_lowercase())
+        .bind(entry.target_lang.trim().to_lowercase())
+        .bind(&entry.source_text)
+        .bind(&entry.target_text)
+        .bind(entry.project_uuid)
+        .bind(&entry.origin)
+        .fetch_one(&mut *tx)
+        .await?;
+        stored.push(record);
+    }
+
+    tx.commit().await?;
+    Ok(stored)
+}
+
+/// Lists the entries of a language pair, most recently updated first.
+pub async fn list_entries_for_pair(
+    pool: &SqlitePool,
+    source_lang: &str,
+    target_lang: &str,
+) -> DbResult<Vec<TranslationMemoryEntryRecord>> {
+    let records = sqlx::query_as::<_, TranslationMemoryEntryRecord>(
+        r#"
+        SELECT * FROM translation_memory_entries
+        WHERE source_lang = ?1 AND target_lang = ?2
+        ORDER BY updated_at DESC, entry_uuid ASC
+        "#,
+    )
+    .bind(source_lang.trim().to_lowercase())
+    .bind(target_lang.trim().to_lowercase())
+    .fetch_all(pool)
+    .await?;
+    Ok(records)
+}
+
+/// Lists every entry, grouped by language pair.
+pub async fn list_all_entries(pool: &SqlitePool) -> DbResult<Vec<TranslationMemoryEntryRecord>> {
+    let records = sqlx::query_as::<_, TranslationMemoryEntryRecord>(
+        r#"
+        SELECT * FROM translation_memory_entries
+        ORDER BY source_lang ASC, target_lang ASC, source_text ASC, target_text ASC
+        "#,
+    )
+    .fetch_all(pool)
+    .await?;
+    Ok(records)
+}
+
+/// Rewrites the texts of entries in a single transaction. An entry whose new texts match
+/// another entry of the pair is merged into it (deleted); returns how many were merged.
+pub async fn update_entries(
+    pool: &SqlitePool,
+    edits: &[UpdateTranslationMemoryEntryArgs],
+) -> DbResult<u64> {
+    let mut tx = pool.begin().await?;
+
+    let mut merged = 0;
+    for edit in edits {
+        let updated = sqlx::query(
+            r#"
+            UPDATE OR IGNORE translation_memory_entries
+            SET source_text = ?2, target_text = ?3, updated_at = CURRENT_TIMESTAMP
+            WHERE entry_uuid = ?1
+            "#,
+        )
+        .bind(edit.entry_uuid)
+        .bind(&edit.source_text)
+        .bind(&edit.target_text)
+        .execute(&mut *tx)
+        .await?;
+        if updated.rows_affected() == 0 {
+            merged += sqlx::query("DELETE FROM translation_memory_entries WHERE entry_uuid = ?1")
+                .bind(edit.entry_uuid)
+                .execute(&mut *tx)
+                .await?
+                .rows_affected();
+        }
+    }
+
+    tx.commit().await?;
+    Ok(merged)
+}
+
+/// Deletes entries in a single transaction, returning how many existed.
+pub async fn delete_entries(pool: &SqlitePool, entry_uuids: &[Uuid]) -> DbResult<u64> {
+    let mut tx = pool.begin().await?;
+
+    let mut deleted = 0;
+    for entry_uuid in entry_uuids {
+        deleted += sqlx::query("DELETE FROM translation_memory_entries WHERE entry_uuid = ?1")
+            .bind(entry_uuid)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+    }
+
+    tx.commit().await?;
+    Ok(deleted)
+}
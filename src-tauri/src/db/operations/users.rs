@@ -3,6 +3,7 @@
 use sqlx::{QueryBuilder, Sqlite, SqlitePool, Transaction};
 use uuid::Uuid;
 
+use crate::db::collation::Collator;
 use crate::db::error::DbResult;
 use crate::db::types::{
     NewUserArgs, PermissionOverrideInput, UpdateUserArgs, UserPermissionOverrideRecord,
@@ -12,7 +13,25 @@ use crate::db::types::{
 /// Creates a user with roles and permission overrides.
 pub async fn create_user(pool: &SqlitePool, args: NewUserArgs) -> DbResult<UserProfile> {
     let mut tx = pool.begin().await?;
+    insert_user(&mut tx, &args).await?;
+    let profile = fetch_user_profile(&mut tx, args.user_uuid).await?;
+    tx.commit().await?;
+
+    profile.ok_or_else(|| sqlx::Error::RowNotFound.into())
+}
 
+/// Inserts several users with their roles in one transaction, so either all of them are
+/// stored or none.
+pub async fn create_users(pool: &SqlitePool, users: &[NewUserArgs]) -> DbResult<()> {
+    let mut tx = pool.begin().await?;
+    for args in users {
+        insert_user(&mut tx, args).await?;
+    }
+    tx.commit().await?;
+    Ok(())
+}
+
+async fn insert_user(tx: &mut Transaction<'_, Sqlite>, args: &NewUserArgs) -> DbResult<()> {
     sqlx::query(
         r#"
         INSERT INTO users (user_uuid, username, email, phone, address)
@@ -24,16 +43,11 @@ pub async fn create_user(pool: &SqlitePool, args: NewUserArgs) -> DbResult<UserP
     .bind(&args.email)
     .bind(&args.phone)
     .bind(&args.address)
-    .execute(&mut *tx)
+    .execute(&mut **tx)
     .await?;
 
-    replace_roles(&mut tx, args.user_uuid, &args.roles).await?;
-    replace_permission_overrides(&mut tx, args.user_uuid, &args.permission_overrides).await?;
-
-    let profile = fetch_user_profile(&mut tx, args.user_uuid).await?;
-    tx.commit().await?;
-
-    profile.ok_or_else(|| sqlx::Error::RowNotFound.into())
+    replace_roles(tx, args.user_uuid, &args.roles).await?;
+    replace_permission_overrides(tx, args.user_uuid, &args.permission_overrides).await
 }
 
 /// Updates a user and optionally replaces list relationships.
@@ -119,13 +133,13 @@ pub async fn get_user(pool: &SqlitePool, user_uuid: Uuid) -> DbResult<Option<Use
     Ok(profile)
 }
 
-/// Lists all user profiles.
-pub async fn list_users(pool: &SqlitePool) -> DbResult<Vec<UserProfile>> {
+/// Lists all user profiles by username.
+pub async fn list_users(pool: &SqlitePool, collator: &Collator) -> DbResult<Vec<UserProfile>> {
     let mut tx = pool.begin().await?;
-    let rows: Vec<UserRecord> =
-        sqlx::query_as("SELECT * FROM users ORDER BY username COLLATE NOCASE ASC")
-            .fetch_all(&mut *tx)
-            .await?;
+    let mut rows: Vec<UserRecord> = sqlx::query_as("SELECT * FROM users")
+        .fetch_all(&mut *tx)
+        .await?;
+    collator.sort_by_text(&mut rows, |user| &user.username);
     let mut profiles = Vec::with_capacity(rows.len());
     for user in rows {
         let roles = fetch_roles(&mut tx, user.user_uuid).await?;
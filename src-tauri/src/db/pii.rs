@@ -0,0 +1,160 @@
+//! Field-level encryption of client personal data.
+//!
+//! The email, phone, free-text address, note and street-level postal address of a client are
+//! stored sealed with ChaCha20-Poly1305 under a key kept in the secrets vault as
+//! [`PII_KEY_SECRET`]. This works whether or not the whole database is encrypted. Sealed values
+//! are text of the form `pii1:<hex nonce and ciphertext>`; values without that prefix predate
+//! the encryption and are read as they are, until the next key rotation seals them.
+
+use std::fmt::Write as _;
+
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+
+use crate::db::error::{DbError, DbResult};
+
+/// Name of the client data key in the secrets vault.
+pub const PII_KEY_SECRET: &str = "client-pii-key";
+
+const SEALED_PREFIX: &str = "pii1:";
+const NONCE_LEN: usize = 12;
+
+/// Seals and opens client fields. The default cipher has no key: it stores new values as
+/// plaintext and fails on sealed ones. A [locked](FieldCipher::locked) cipher has no key either
+/// but fails on both.
+#[derive(Clone, Copy, Default)]
+pub struct FieldCipher {
+    key: Option<[u8; 32]>,
+    locked: bool,
+}
+
+impl FieldCipher {
+    /// A cipher for a key of 64 hex digits, as made by
+    /// [`crate::db::encryption::generate_key`].
+    pub fn from_hex(key: &str) -> Option<Self> {
+        let bytes = decode_hex(key.trim())?;
+        Some(Self {
+            key: Some(bytes.try_into().ok()?),
+            locked: false,
+        })
+    }
+
+    /// The cipher of a locked workspace: client data is encrypted but its key has been put
+    /// away, so nothing can be sealed or opened until it is loaded again.
+    pub fn locked() -> Self {
+        Self {
+            key: None,
+            locked: true,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.key.is_some()
+    }
+
+    /// Seals `value`, or returns it unchanged when encryption is off. Fails when the cipher is
+    /// locked rather than store plaintext.
+    pub fn seal(&self, value: Option<&str>) -> DbResult<Option<String>> {
+        let Some(value) = value else {
+            return Ok(None);
+        };
+        let Some(key) = self.key.as_ref() else {
+            if self.locked {
+                return Err(DbError::PiiKeyMissing);
+            }
+            return Ok(Some(value.to_string()));
+        };
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = ChaCha20Poly1305::new(Key::from_slice(key))
+            .encrypt(&nonce, value.as_bytes())
+            .map_err(|error| DbError::PiiDecryption(error.to_string()))?;
+        let mut sealed = String::from(SEALED_PREFIX);
+        for byte in nonce.iter().chain(&ciphertext) {
+            let _ = write!(sealed, "{byte:02x}");
+        }
+        Ok(Some(sealed))
+    }
+
+    /// Opens a stored value, passing plaintext through.
+    pub fn open(&self, value: Option<String>) -> DbResult<Option<String>> {
+        let Some(sealed) = value
+            .as_deref()
+            .and_then(|value| value.strip_prefix(SEALED_PREFIX))
+        else {
+            return Ok(value);
+        };
+        let key = self.key.as_ref().ok_or(DbError::PiiKeyMissing)?;
+        let bytes = decode_hex(sealed)
+            .filter(|bytes| bytes.len() > NONCE_LEN)
+            .ok_or_else(|| DbError::PiiDecryption("malformed sealed value".into()))?;
+        let (nonce, ciphertext) = bytes.split_at(NONCE_LEN);
+        let plaintext = ChaCha20Poly1305::new(Key::from_slice(key))
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| DbError::PiiDecryption("wrong key or tampered value".into()))?;
+        String::from_utf8(plaintext)
+            .map(Some)
+            .map_err(|error| DbError::PiiDecryption(error.to_string()))
+    }
+}
+
+fn decode_hex(value: &str) -> Option<Vec<u8>> {
+    if !value.len().is_multiple_of(2) || !value.is_ascii() {
+        return None;
+    }
+    (0..value.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(&value[index..index + 2], 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::encryption::generate_key;
+
+    #[test]
+    fn sealed_values_open_only_with_their_key() {
+        let cipher = FieldCipher::from_hex(&generate_key()).expect("valid key");
+        let sealed = cipher.seal(Some("ada@example.com")).unwrap().unwrap();
+        assert!(sealed.starts_with(SEALED_PREFIX));
+        assert_ne!(
+            cipher.seal(Some("ada@example.com")).unwrap().unwrap(),
+            sealed
+        );
+        assert_eq!(
+            cipher.open(Some(sealed.clone())).unwrap().as_deref(),
+            Some("ada@example.com")
+        );
+        assert_eq!(
+            cipher.open(Some("legacy".into())).unwrap().as_deref(),
+            Some("legacy")
+        );
+
+        let other = FieldCipher::from_hex(&generate_key()).unwrap();
+        assert!(matches!(
+            other.open(Some(sealed.clone())),
+            Err(DbError::PiiDecryption(_))
+        ));
+        assert!(matches!(
+            FieldCipher::default().open(Some(sealed)),
+            Err(DbError::PiiKeyMissing)
+        ));
+        assert!(FieldCipher::from_hex("abc").is_none());
+    }
+
+    #[test]
+    fn a_locked_cipher_refuses_to_store_plaintext() {
+        assert_eq!(
+            FieldCipher::default()
+                .seal(Some("ada@example.com"))
+                .unwrap()
+                .as_deref(),
+            Some("ada@example.com")
+        );
+        assert!(matches!(
+            FieldCipher::locked().seal(Some("ada@example.com")),
+            Err(DbError::PiiKeyMissing)
+        ));
+        assert_eq!(FieldCipher::locked().seal(None).unwrap(), None);
+    }
+}
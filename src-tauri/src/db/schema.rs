@@ -2,13 +2,24 @@
 //!
 //! The historic hand-written schema initialiser has been replaced with versioned
 //! migrations in `src-tauri/migrations`. Tests and consumers should continue
-//! calling `initialise_schema`, which now simply runs the embedded migrator.
+//! calling `initialise_schema`, which runs the embedded migrator and then fills the
+//! derived columns SQL cannot compute, such as name search keys.
 
 use sqlx::{SqlitePool, migrate::Migrator};
 
+use super::search::backfill_search_keys;
+
 pub static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
 
 /// Applies any pending migrations against the provided pool.
 pub async fn initialise_schema(pool: &SqlitePool) -> Result<(), sqlx::Error> {
-    MIGRATOR.run(pool).await.map_err(Into::into)
+    MIGRATOR.run(pool).await?;
+    backfill_search_keys(pool).await
+}
+
+/// Version of the latest migration applied successfully.
+pub async fn schema_version(pool: &SqlitePool) -> Result<Option<i64>, sqlx::Error> {
+    sqlx::query_scalar("SELECT MAX(version) FROM _sqlx_migrations WHERE success = 1")
+        .fetch_one(pool)
+        .await
 }
@@ -0,0 +1,94 @@
+//! Search keys: lowercased, unaccented copies of names stored next to them.
+//!
+//! Project names, client names and filenames each have a `*_search` column that the DB layer
+//! rewrites whenever the name is written, so name filters compare folded text with `LIKE`
+//! and "muller" finds "Müller". Rows written before the columns existed are keyed by
+//! [`backfill_search_keys`] when the schema is initialised.
+
+use sqlx::SqlitePool;
+use unicode_normalization::char::{decompose_compatible, is_combining_mark};
+
+/// Folds `text` for searching: lowercase, accents removed, compatibility forms such as
+/// ligatures and full-width letters spelled out, whitespace collapsed.
+pub fn search_key(text: &str) -> String {
+    let mut key = String::with_capacity(text.len());
+    for lower in text.chars().flat_map(char::to_lowercase) {
+        match lower {
+            'ß' => key.push_str("ss"),
+            'æ' => key.push_str("ae"),
+            'œ' => key.push_str("oe"),
+            'ø' => key.push('o'),
+            'ł' => key.push('l'),
+            'đ' => key.push('d'),
+            'ı' => key.push('i'),
+            _ => decompose_compatible(lower, |part| {
+                if !is_combining_mark(part) {
+                    key.push(part);
+                }
+            }),
+        }
+    }
+    key.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// `LIKE` pattern matching search keys that contain the folded `query`; use it with
+/// `ESCAPE '\'`.
+pub fn contains_pattern(query: &str) -> String {
+    let mut pattern = String::from("%");
+    for ch in search_key(query).chars() {
+        if matches!(ch, '%' | '_' | '\\') {
+            pattern.push('\\');
+        }
+        pattern.push(ch);
+    }
+    pattern.push('%');
+    pattern
+}
+
+/// Fills the search keys that are still missing.
+pub async fn backfill_search_keys(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    for (table, name_column, search_column) in [
+        ("projects", "project_name", "project_name_search"),
+        ("clients", "name", "name_search"),
+        ("project_files", "filename", "filename_search"),
+    ] {
+        let rows: Vec<(i64, String)> = sqlx::query_as(&format!(
+            "SELECT rowid, {name_column} FROM {table} WHERE {search_column} IS NULL"
+        ))
+        .fetch_all(pool)
+        .await?;
+        if rows.is_empty() {
+            continue;
+        }
+        let mut tx = pool.begin().await?;
+        for (rowid, name) in rows {
+            sqlx::query(&format!(
+                "UPDATE {table} SET {search_column} = ?1 WHERE rowid = ?2"
+            ))
+            .bind(search_key(&name))
+            .bind(rowid)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn folds_case_accents_and_compatibility_forms() {
+        assert_eq!(search_key("Müller"), "muller");
+        assert_eq!(
+            search_key("  ÉCOLE   Polytechnique "),
+            "ecole polytechnique"
+        );
+        assert_eq!(search_key("Straße Łódź"), "strasse lodz");
+        assert_eq!(search_key("ﬁnal_ＲＥＰＯＲＴ.docx"), "final_report.docx");
+        assert_eq!(contains_pattern("Mül"), "%mul%");
+        assert_eq!(contains_pattern("50%_off"), r"%50\%\_off%");
+    }
+}
@@ -11,6 +11,7 @@ pub enum ArtifactKind {
     Jliff,
     QaReport,
     Preview,
+    DeliveryPackage,
 }
 
 impl ArtifactKind {
@@ -21,6 +22,7 @@ impl ArtifactKind {
             ArtifactKind::Jliff => "jliff",
             ArtifactKind::QaReport => "qa_report",
             ArtifactKind::Preview => "preview",
+            ArtifactKind::DeliveryPackage => "delivery_package",
         }
     }
 
@@ -31,6 +33,7 @@ impl ArtifactKind {
             "jliff" => Some(Self::Jliff),
             "qa_report" => Some(Self::QaReport),
             "preview" => Some(Self::Preview),
+            "delivery_package" => Some(Self::DeliveryPackage),
             _ => None,
         }
     }
@@ -11,6 +11,9 @@ pub enum JobType {
     ExtractXliff,
     ConvertJliff,
     Validate,
+    Pretranslate,
+    Qa,
+    Merge,
 }
 
 impl JobType {
@@ -21,6 +24,9 @@ impl JobType {
             JobType::ExtractXliff => "EXTRACT_XLIFF",
             JobType::ConvertJliff => "CONVERT_JLIFF",
             JobType::Validate => "VALIDATE",
+            JobType::Pretranslate => "PRETRANSLATE",
+            JobType::Qa => "QA",
+            JobType::Merge => "MERGE",
         }
     }
 
@@ -31,6 +37,9 @@ impl JobType {
             "EXTRACT_XLIFF" => Some(Self::ExtractXliff),
             "CONVERT_JLIFF" => Some(Self::ConvertJliff),
             "VALIDATE" => Some(Self::Validate),
+            "PRETRANSLATE" => Some(Self::Pretranslate),
+            "QA" => Some(Self::Qa),
+            "MERGE" => Some(Self::Merge),
             _ => None,
         }
     }
@@ -21,7 +21,7 @@ pub use conversion::{
 pub use file_target::{FileTarget, FileTargetStatus};
 pub use job::{Job, JobState, JobType};
 pub use language_pair::LanguagePair;
-pub use note::Note;
+pub use note::{NewNoteArgs, Note, NoteMention, NotePage, UpdateNoteArgs};
 pub use project::{
     NewProject, NewProjectFile, ProjectDetails, ProjectFileDetails, ProjectFileImportStatus,
     ProjectFileRole, ProjectFileStorageState, ProjectFileWithConversions, ProjectLifecycleStatus,
@@ -1,5 +1,3 @@
-#![allow(dead_code)]
-
 //! Project note types authored by users within the workspace.
 
 use uuid::Uuid;
@@ -11,5 +9,47 @@ pub struct Note {
     pub project_id: Uuid,
     pub author_user_id: String,
     pub body: String,
+    /// Whether `body` is Markdown rather than plain text.
+    pub is_markdown: bool,
+    /// Pinned notes are listed before all others.
+    pub pinned: bool,
     pub created_at: String,
+    pub updated_at: Option<String>,
+    pub mentions: Vec<NoteMention>,
+}
+
+/// An `@handle` written in a note body.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NoteMention {
+    /// The handle without `@`, lowercased.
+    pub mention: String,
+    /// The user whose username matches the handle, if any.
+    pub user_uuid: Option<Uuid>,
+}
+
+/// Arguments for creating a note.
+#[derive(Debug, Clone)]
+pub struct NewNoteArgs {
+    pub project_id: Uuid,
+    pub author_user_id: String,
+    pub body: String,
+    pub is_markdown: bool,
+    pub pinned: bool,
+}
+
+/// Changes to a note; `None` leaves a field as it is.
+#[derive(Debug, Clone, Default)]
+pub struct UpdateNoteArgs {
+    pub note_id: Uuid,
+    pub body: Option<String>,
+    pub is_markdown: Option<bool>,
+    pub pinned: Option<bool>,
+}
+
+/// A page of a project's notes.
+#[derive(Debug, Clone)]
+pub struct NotePage {
+    /// Notes of the project before paging.
+    pub total: i64,
+    pub notes: Vec<Note>,
 }
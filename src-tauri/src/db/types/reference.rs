@@ -18,9 +18,10 @@ pub struct Client {
     pub name: String,
 }
 
-/// Domain classification used to categorize projects.
-#[derive(Debug, Clone)]
+/// Domain classification used to categorize projects and termbases.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Domain {
     pub domain_id: String,
     pub name: String,
+    pub description: Option<String>,
 }
@@ -4,6 +4,7 @@
 //! tables so that higher layers can rely on strong typing when composing
 //! queries and assembling aggregates.
 
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::{FromRow, types::Json};
 use uuid::Uuid;
@@ -43,6 +44,55 @@ pub struct ClientRecord {
     pub address: Option<String>,
     pub vat_number: Option<String>,
     pub note: Option<String>,
+    /// ISO 4217 code the client is billed in; `None` means the base currency.
+    pub currency: Option<String>,
+    #[sqlx(flatten)]
+    pub postal_address: PostalAddress,
+    #[sqlx(flatten)]
+    pub vat_validation: VatValidation,
+}
+
+/// Structured postal address of a client, stored in the `address_*` columns of `clients`.
+/// `address` keeps the free-text form shown where no formatting applies.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct PostalAddress {
+    #[sqlx(rename = "address_street")]
+    pub street: Option<String>,
+    #[sqlx(rename = "address_street_number")]
+    pub street_number: Option<String>,
+    #[sqlx(rename = "address_postal_code")]
+    pub postal_code: Option<String>,
+    #[sqlx(rename = "address_locality")]
+    pub locality: Option<String>,
+    /// State, province or county.
+    #[sqlx(rename = "address_region")]
+    pub region: Option<String>,
+    /// ISO 3166-1 alpha-2 code.
+    #[sqlx(rename = "address_country_code")]
+    pub country_code: Option<String>,
+    /// Country name as it should be printed.
+    #[sqlx(rename = "address_country")]
+    pub country: Option<String>,
+    /// Google Places id the address was resolved from; `None` for manual entries.
+    #[sqlx(rename = "address_place_id")]
+    pub place_id: Option<String>,
+}
+
+impl PostalAddress {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// How far a client's VAT number was verified, stored in the `vat_*` columns of `clients`.
+/// Both fields are `None` while the client has no VAT number.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct VatValidation {
+    /// `vies`, `checksum`, `format` or `unverified`; see `tax::TaxIdStatus`.
+    #[sqlx(rename = "vat_status")]
+    pub status: Option<String>,
+    #[sqlx(rename = "vat_checked_at")]
+    pub checked_at: Option<String>,
 }
 
 /// Row representation of the `projects` table.
@@ -57,6 +107,11 @@ pub struct ProjectRecord {
     pub client_uuid: Option<Uuid>,
     pub r#type: String,
     pub notes: Option<String>,
+    pub project_number: Option<String>,
+    /// Date, or date and time, the project is due.
+    pub due_date: Option<String>,
+    /// The client forbids sending the project's text to external MT providers.
+    pub no_external_mt: bool,
 }
 
 /// Summary row used when listing projects with aggregate metadata.
@@ -72,6 +127,9 @@ pub struct ProjectListRecord {
     pub client_name: Option<String>,
     pub r#type: String,
     pub notes: Option<String>,
+    pub project_number: Option<String>,
+    pub due_date: Option<String>,
+    pub no_external_mt: bool,
     pub subjects: Json<Vec<String>>,
     pub file_count: i64,
 }
@@ -136,13 +194,606 @@ pub struct ArtifactRecord {
 }
 
 /// Row representation of the `jobs` table.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow)]
 pub struct JobRecord {
     pub artifact_uuid: Uuid,
     pub job_type: String,
     pub project_uuid: Uuid,
     pub job_status: String,
     pub error_log: Option<String>,
+    pub processed_units: i64,
+    pub total_units: Option<i64>,
+    /// Rolling throughput; see [`crate::jobs::progress`].
+    pub units_per_second: Option<f64>,
+    pub started_at: Option<String>,
+    pub progress_updated_at: Option<String>,
+}
+
+/// Row representation of the `job_dependencies` table: the job waits for the
+/// `depends_on_*` job to succeed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct JobDependencyRecord {
+    pub artifact_uuid: Uuid,
+    pub job_type: String,
+    pub depends_on_artifact_uuid: Uuid,
+    pub depends_on_job_type: String,
+}
+
+/// Row representation of the `qa_findings` table.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct QaFindingRecord {
+    pub finding_uuid: Uuid,
+    pub project_uuid: Uuid,
+    pub jliff_rel_path: String,
+    pub transunit_id: String,
+    pub check_type: String,
+    pub severity: String,
+    pub message: String,
+    pub details: Option<String>,
+    pub created_at: String,
+    pub resolved_at: Option<String>,
+}
+
+/// Row representation of the `segment_revisions` table.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct SegmentRevisionRecord {
+    pub revision_uuid: Uuid,
+    pub project_uuid: Uuid,
+    pub jliff_rel_path: String,
+    pub transunit_id: String,
+    pub previous_target: String,
+    pub new_target: String,
+    pub origin: String,
+    pub author: Option<String>,
+    pub created_at: String,
+}
+
+/// Confirmations of one user on one day, aggregated from `segment_confirmations`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct DailyProductivityRecord {
+    /// `YYYY-MM-DD` (UTC).
+    pub day: String,
+    pub segments: i64,
+    pub words: i64,
+}
+
+/// Workspace-wide activity of one language pair, with lowercased language codes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow)]
+pub struct LanguagePairActivityRecord {
+    pub source_lang: String,
+    pub target_lang: String,
+    /// Projects with the pair.
+    pub project_count: i64,
+    /// Delivery packages of the pair handed over with a delivery.
+    pub delivered_packages: i64,
+    /// Mean hours from project creation to delivery of the pair's package.
+    pub average_turnaround_hours: Option<f64>,
+    pub tm_entries: i64,
+}
+
+/// Row representation of the `saved_segment_filters` table.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct SavedSegmentFilterRecord {
+    pub filter_uuid: Uuid,
+    pub user_uuid: Uuid,
+    pub name: String,
+    pub filter_json: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Row representation of the `delivery_gate_overrides` table.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct DeliveryGateOverrideRecord {
+    pub override_uuid: Uuid,
+    pub project_uuid: Uuid,
+    pub user_uuid: Option<Uuid>,
+    pub reason: String,
+    pub critical_findings: i64,
+    pub empty_targets: i64,
+    pub created_at: String,
+}
+
+/// Row representation of the `delivery_packages` table.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct DeliveryPackageRecord {
+    pub artifact_uuid: Uuid,
+    pub project_uuid: Uuid,
+    pub source_lang: String,
+    pub target_lang: String,
+    pub rel_path: String,
+    pub document_count: i64,
+    pub bilingual_count: i64,
+    pub open_findings: i64,
+    pub created_at: String,
+    pub delivered_at: Option<String>,
+}
+
+/// Row representation of the `delivery_targets` table: an SFTP server or S3 bucket a client
+/// receives packages on.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct DeliveryTargetRecord {
+    pub target_uuid: Uuid,
+    pub client_uuid: Uuid,
+    pub name: String,
+    /// `sftp` or `s3`.
+    pub transport: String,
+    pub settings_json: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Row representation of the `delivery_receipts` table: the outcome of one package upload.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct DeliveryReceiptRecord {
+    pub receipt_uuid: Uuid,
+    pub artifact_uuid: Uuid,
+    pub project_uuid: Uuid,
+    /// `None` once the target was deleted.
+    pub target_uuid: Option<Uuid>,
+    pub transport: String,
+    /// `uploaded` or `failed`.
+    pub status: String,
+    pub remote_location: Option<String>,
+    pub size_bytes: Option<i64>,
+    pub sha256: Option<String>,
+    pub error: Option<String>,
+    pub started_at: String,
+    pub completed_at: String,
+}
+
+/// Row representation of the `segment_references` table: a reference asset linked to a
+/// transunit.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct SegmentReferenceRecord {
+    pub reference_uuid: Uuid,
+    pub project_uuid: Uuid,
+    pub jliff_rel_path: String,
+    pub transunit_id: String,
+    pub file_uuid: Uuid,
+    /// Part of the asset the segment refers to, e.g. a page or section.
+    pub anchor: Option<String>,
+    pub note: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Row representation of the `project_health` table: the last health evaluation of a
+/// project.
+#[derive(Debug, Clone, PartialEq, Eq, FromRow)]
+pub struct ProjectHealthRecord {
+    pub project_uuid: Uuid,
+    /// `healthy`, `at_risk` or `critical`.
+    pub level: String,
+    pub score: i64,
+    pub flags: Json<Vec<String>>,
+    pub evaluated_at: String,
+}
+
+/// Row representation of the `provider_usage` table: what one project sent to a provider in
+/// one month.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct ProviderUsageRecord {
+    pub provider: String,
+    pub project_uuid: Uuid,
+    /// `YYYY-MM` (UTC).
+    pub usage_month: String,
+    pub characters: i64,
+    pub tokens: i64,
+    pub requests: i64,
+    pub updated_at: String,
+}
+
+/// Usage of one provider in one month across all projects.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct ProviderMonthlyUsageRecord {
+    pub provider: String,
+    pub usage_month: String,
+    pub characters: i64,
+    pub tokens: i64,
+    pub requests: i64,
+}
+
+/// Row representation of the `exchange_rates` table: on `rate_date`, one unit of
+/// `base_currency` was worth `rate` units of `quote_currency`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow)]
+pub struct ExchangeRateRecord {
+    pub base_currency: String,
+    pub quote_currency: String,
+    /// `YYYY-MM-DD`.
+    pub rate_date: String,
+    pub rate: f64,
+    /// `manual` or `ecb`.
+    pub source: String,
+    pub recorded_at: String,
+}
+
+/// Arguments for recording an exchange-rate snapshot.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NewExchangeRateArgs {
+    pub base_currency: String,
+    pub quote_currency: String,
+    pub rate_date: String,
+    pub rate: f64,
+    pub source: String,
+}
+
+/// Row representation of the `provider_budgets` table. Unset caps are unlimited.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct ProviderBudgetRecord {
+    pub provider: String,
+    pub monthly_character_cap: Option<i64>,
+    pub monthly_token_cap: Option<i64>,
+    pub updated_at: String,
+}
+
+/// Cache entries of one provider model, aggregated from `provider_translation_cache`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct ProviderCacheStatsRecord {
+    pub provider: String,
+    pub model: String,
+    pub entries: i64,
+    pub expired_entries: i64,
+    pub hits: i64,
+}
+
+/// Row representation of the `provider_rate_limits` table. Unset limits are not enforced.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct ProviderRateLimitRecord {
+    pub provider: String,
+    pub requests_per_minute: Option<i64>,
+    pub tokens_per_minute: Option<i64>,
+    pub updated_at: String,
+}
+
+/// Row representation of the `prompt_templates` table.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct PromptTemplateRecord {
+    pub template_uuid: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub definition_json: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Row representation of the `prompt_template_assignments` table. Empty languages match any
+/// language.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct PromptTemplateAssignmentRecord {
+    pub project_uuid: Uuid,
+    pub source_lang: String,
+    pub target_lang: String,
+    pub template_uuid: Uuid,
+    pub assigned_at: String,
+}
+
+/// Row representation of the `provider_candidates` table.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow)]
+pub struct ProviderCandidateRecord {
+    pub project_uuid: Uuid,
+    pub jliff_rel_path: String,
+    pub transunit_id: String,
+    pub provider: String,
+    pub source_text: String,
+    pub target_text: String,
+    pub qe_score: f64,
+    pub created_at: String,
+}
+
+/// Row representation of the `preferred_providers` table.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct PreferredProviderRecord {
+    pub project_uuid: Uuid,
+    pub source_lang: String,
+    pub target_lang: String,
+    pub provider: String,
+    pub updated_at: String,
+}
+
+/// Row representation of the `provider_routing_rules` table.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct ProviderRoutingRuleRecord {
+    pub rule_uuid: Uuid,
+    pub project_uuid: Option<Uuid>,
+    pub client_uuid: Option<Uuid>,
+    pub position: i64,
+    pub source_lang: Option<String>,
+    pub target_lang: Option<String>,
+    pub subject: Option<String>,
+    pub provider: String,
+    pub note: Option<String>,
+    pub created_at: String,
+}
+
+/// Row representation of the `scheduled_task_runs` table.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct ScheduledTaskRunRecord {
+    pub task_id: String,
+    pub last_started_at: String,
+    pub last_finished_at: Option<String>,
+    /// `running`, `succeeded` or `failed`.
+    pub last_status: String,
+    pub last_error: Option<String>,
+    pub run_count: i64,
+}
+
+/// Row representation of the `event_outbox` table.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct OutboxEventRecord {
+    pub event_id: i64,
+    /// Event name the payload is published under.
+    pub topic: String,
+    pub payload_json: String,
+    pub created_at: String,
+    pub attempts: i64,
+    /// Undelivered events are held back until then after a failed publish.
+    pub next_attempt_at: Option<String>,
+    pub last_error: Option<String>,
+    pub delivered_at: Option<String>,
+}
+
+/// Row representation of the `qa_profiles` table.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct QaProfileRecord {
+    pub profile_uuid: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub definition_json: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Row representation of the `pipeline_presets` table.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct PipelinePresetRecord {
+    pub preset_uuid: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    /// Project type the preset is the default of.
+    pub project_type: Option<String>,
+    pub definition_json: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Row representation of the `automation_scripts` table.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct AutomationScriptRecord {
+    pub script_uuid: Uuid,
+    pub name: String,
+    /// `on_segment_confirm`, `on_project_create` or `on_qa_finding`.
+    pub hook: String,
+    pub source: String,
+    pub enabled: bool,
+    pub last_run_at: Option<String>,
+    /// Error of the last run; `None` when it succeeded.
+    pub last_error: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Row representation of the `offline_operations` table.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct OfflineOperationRecord {
+    pub operation_uuid: Uuid,
+    /// Name of the command to replay, such as `upload_delivery_package_v2`.
+    pub operation: String,
+    /// Payload of the command, as it was sent.
+    pub payload_json: String,
+    pub project_uuid: Option<Uuid>,
+    pub created_at: String,
+}
+
+/// Row representation of the `outbound_requests` table.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct OutboundRequestRecord {
+    pub request_uuid: Uuid,
+    pub provider: String,
+    pub project_uuid: Option<Uuid>,
+    pub segment_count: i64,
+    pub char_count: i64,
+    /// Host the text was sent to, when the provider is reached over HTTP.
+    pub destination_host: Option<String>,
+    pub sent_at: String,
+}
+
+/// Row representation of the `plugins` table.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct PluginRecord {
+    pub plugin_id: String,
+    /// Folder holding the manifest and entry point.
+    pub plugin_dir: String,
+    /// The manifest as it was when the plugin was registered.
+    pub manifest_json: String,
+    pub registered_at: String,
+    pub updated_at: String,
+}
+
+/// Row representation of the `plugin_settings` table.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct PluginSettingRecord {
+    pub plugin_id: String,
+    pub setting_key: String,
+    pub value_json: String,
+}
+
+/// Row representation of the `translation_memory_entries` table.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct TranslationMemoryEntryRecord {
+    pub entry_uuid: Uuid,
+    pub source_lang: String,
+    pub target_lang: String,
+    pub source_text: String,
+    pub target_text: String,
+    pub project_uuid: Option<Uuid>,
+    pub origin: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Row representation of the `project_tm_assignments` table.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct ProjectTmAssignmentRecord {
+    pub project_uuid: Uuid,
+    pub tm_origin: String,
+    pub priority: i64,
+    pub penalty: i64,
+    pub created_at: String,
+}
+
+/// Row representation of the `project_output_naming` table; `None` uses the global template.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct ProjectOutputNamingRecord {
+    pub project_uuid: Uuid,
+    pub xliff_template: Option<String>,
+    pub jliff_template: Option<String>,
+    /// `language_first` or `source_first`; language-first when unset.
+    pub translations_layout: Option<String>,
+    pub updated_at: String,
+}
+
+/// Row representation of the `conversion_output_names` table.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct ConversionOutputNameRecord {
+    pub project_uuid: Uuid,
+    pub file_uuid: Uuid,
+    pub source_lang: String,
+    pub target_lang: String,
+    /// Template `xliff_rel_path` was rendered from.
+    pub xliff_template: String,
+    pub xliff_rel_path: String,
+    /// Template `jliff_prefix` was rendered from.
+    pub jliff_template: String,
+    /// JLIFF file name without `.jliff.json`.
+    pub jliff_prefix: String,
+    pub created_at: String,
+}
+
+/// Arguments recording the output names of a file and language pair.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NewConversionOutputNameArgs {
+    pub project_uuid: Uuid,
+    pub file_uuid: Uuid,
+    pub source_lang: String,
+    pub target_lang: String,
+    pub xliff_template: String,
+    pub xliff_rel_path: String,
+    pub jliff_template: String,
+    pub jliff_prefix: String,
+}
+
+/// Row representation of the `project_protected_terms` table.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct ProtectedTermRecord {
+    pub term_uuid: Uuid,
+    pub project_uuid: Uuid,
+    pub term: String,
+    pub case_sensitive: bool,
+    pub note: Option<String>,
+    pub created_at: String,
+}
+
+/// Row representation of the `project_protection_rules` table.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct ProtectionRuleRecord {
+    pub rule_uuid: Uuid,
+    pub project_uuid: Uuid,
+    pub name: String,
+    pub pattern: String,
+    pub is_enabled: bool,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Row representation of the `alignments` table.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct AlignmentRecord {
+    pub alignment_uuid: Uuid,
+    pub source_lang: String,
+    pub target_lang: String,
+    pub source_name: String,
+    pub target_name: String,
+    pub created_at: String,
+    pub committed_at: Option<String>,
+}
+
+/// Row representation of the `alignment_pairs` table.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow)]
+pub struct AlignmentPairRecord {
+    pub alignment_uuid: Uuid,
+    pub position: i64,
+    pub source_text: String,
+    pub target_text: String,
+    pub score: f64,
+    pub status: String,
+}
+
+/// Row representation of the `termbase_entries` table.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct TermbaseEntryRecord {
+    pub entry_uuid: Uuid,
+    pub termbase: String,
+    pub source_lang: String,
+    pub target_lang: String,
+    pub source_term: String,
+    /// Empty for terms recorded without an equivalent.
+    pub target_term: String,
+    pub project_uuid: Option<Uuid>,
+    pub created_at: String,
+}
+
+/// A managed subject with its aliases.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SubjectTaxonomyRecord {
+    pub subject: String,
+    /// Alternative spellings resolved to `subject`, in alphabetical order.
+    pub aliases: Vec<String>,
+    pub created_at: String,
+}
+
+/// A subject proposed for a typed prefix.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SubjectSuggestionRecord {
+    pub subject: String,
+    /// Alias the prefix matched, when it did not match the subject itself.
+    pub matched_alias: Option<String>,
+    /// Whether the subject is managed rather than only used by projects.
+    pub managed: bool,
+    /// Projects tagged with the subject.
+    pub project_count: i64,
+}
+
+/// Row representation of the `term_candidates` table.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow)]
+pub struct TermCandidateRecord {
+    pub candidate_uuid: Uuid,
+    pub project_uuid: Uuid,
+    pub source_lang: String,
+    pub target_lang: String,
+    pub term: String,
+    pub term_key: String,
+    pub target_term: Option<String>,
+    pub frequency: i64,
+    pub score: f64,
+    pub status: String,
+    pub created_at: String,
+}
+
+/// Row representation of the `segmentation_exceptions` table.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct SegmentationExceptionRecord {
+    pub exception_uuid: Uuid,
+    /// Project the exception belongs to; global exceptions have none.
+    pub project_uuid: Option<Uuid>,
+    pub language: String,
+    /// `abbreviation`, `no_break` or `break`.
+    pub kind: String,
+    /// The abbreviation, or the pattern matching the text before the break.
+    pub before_text: String,
+    /// Pattern matching the text after the break; empty for abbreviations.
+    pub after_text: String,
+    pub created_at: String,
 }
 
 /// Aggregated view of a user and their associated roles and permission overrides.
@@ -163,7 +814,7 @@ pub struct ProjectFileBundle {
 }
 
 /// Aggregated view of a project alongside related collections.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ProjectBundle {
     pub project: ProjectRecord,
     pub subjects: Vec<ProjectSubjectRecord>,
@@ -277,6 +928,9 @@ pub struct NewClientArgs {
     pub address: Option<String>,
     pub vat_number: Option<String>,
     pub note: Option<String>,
+    pub currency: Option<String>,
+    pub postal_address: PostalAddress,
+    pub vat_validation: VatValidation,
 }
 
 /// Arguments for updating a client.
@@ -289,6 +943,11 @@ pub struct UpdateClientArgs {
     pub address: Option<Option<String>>,
     pub vat_number: Option<Option<String>>,
     pub note: Option<Option<String>>,
+    pub currency: Option<Option<String>>,
+    /// Replaces every structured address field; an empty address clears them.
+    pub postal_address: Option<PostalAddress>,
+    /// Set together with `vat_number`.
+    pub vat_validation: Option<VatValidation>,
 }
 
 /// Arguments describing a project language pair.
@@ -314,6 +973,8 @@ pub struct NewProjectArgs {
     pub client_uuid: Option<Uuid>,
     pub r#type: String,
     pub notes: Option<String>,
+    /// Deadline as a date or RFC 3339 timestamp.
+    pub due_date: Option<String>,
     pub subjects: Vec<ProjectSubjectInput>,
     pub language_pairs: Vec<ProjectLanguagePairInput>,
 }
@@ -328,6 +989,8 @@ pub struct UpdateProjectArgs {
     pub client_uuid: Option<Option<Uuid>>,
     pub r#type: Option<String>,
     pub notes: Option<Option<String>>,
+    pub due_date: Option<Option<String>>,
+    pub no_external_mt: Option<bool>,
     pub subjects: Option<Vec<ProjectSubjectInput>>,
     pub language_pairs: Option<Vec<ProjectLanguagePairInput>>,
 }
@@ -395,6 +1058,17 @@ pub struct NewJobArgs {
     pub error_log: Option<String>,
 }
 
+/// Arguments recording the progress of a running job.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JobProgressArgs {
+    pub artifact_uuid: Uuid,
+    pub job_type: String,
+    pub processed_units: i64,
+    /// Keeps the stored total when `None`.
+    pub total_units: Option<i64>,
+    pub recorded_at: DateTime<Utc>,
+}
+
 /// Arguments to update job status.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct UpdateJobStatusArgs {
@@ -403,3 +1077,384 @@ pub struct UpdateJobStatusArgs {
     pub job_status: String,
     pub error_log: Option<String>,
 }
+
+/// Arguments describing a QA finding insert.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NewQaFindingArgs {
+    pub transunit_id: String,
+    pub severity: String,
+    pub message: String,
+    pub details: Option<String>,
+}
+
+/// Arguments replacing the findings of one check type for a JLIFF document.
+///
+/// When `transunit_ids` is `Some`, only findings for those units are replaced; otherwise the
+/// whole document's findings for `check_type` are cleared before inserting `findings`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReplaceQaFindingsArgs {
+    pub project_uuid: Uuid,
+    pub jliff_rel_path: String,
+    pub check_type: String,
+    pub transunit_ids: Option<Vec<String>>,
+    pub findings: Vec<NewQaFindingArgs>,
+}
+
+/// Arguments describing a single target change.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NewSegmentRevisionArgs {
+    pub transunit_id: String,
+    pub previous_target: String,
+    pub new_target: String,
+}
+
+/// Arguments recording target changes applied to a JLIFF document in one operation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordSegmentRevisionsArgs {
+    pub project_uuid: Uuid,
+    pub jliff_rel_path: String,
+    /// Where the change came from (e.g. `external_review`).
+    pub origin: String,
+    pub author: Option<String>,
+    pub revisions: Vec<NewSegmentRevisionArgs>,
+}
+
+/// Arguments recording a segment confirmation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NewSegmentConfirmationArgs {
+    pub project_uuid: Uuid,
+    pub jliff_rel_path: String,
+    pub transunit_id: String,
+    pub user_uuid: Option<Uuid>,
+    /// Source words of the segment when it was confirmed.
+    pub word_count: i64,
+}
+
+/// Arguments describing a saved segment filter insert; saving an existing name replaces
+/// its definition.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NewSavedSegmentFilterArgs {
+    pub user_uuid: Uuid,
+    pub name: String,
+    pub filter_json: String,
+}
+
+/// Arguments recording a delivery that bypassed the QA delivery gate.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NewDeliveryGateOverrideArgs {
+    pub project_uuid: Uuid,
+    pub user_uuid: Uuid,
+    pub reason: String,
+    pub critical_findings: i64,
+    pub empty_targets: i64,
+}
+
+/// Arguments recording a delivery package whose artifact is already stored.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NewDeliveryPackageArgs {
+    pub artifact_uuid: Uuid,
+    pub project_uuid: Uuid,
+    pub source_lang: String,
+    pub target_lang: String,
+    pub rel_path: String,
+    pub document_count: i64,
+    pub bilingual_count: i64,
+    pub open_findings: i64,
+}
+
+/// Arguments creating a delivery target or replacing the settings of an existing one.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NewDeliveryTargetArgs {
+    pub target_uuid: Uuid,
+    pub client_uuid: Uuid,
+    pub name: String,
+    pub transport: String,
+    pub settings_json: String,
+}
+
+/// Arguments recording the outcome of a package upload.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NewDeliveryReceiptArgs {
+    pub receipt_uuid: Uuid,
+    pub artifact_uuid: Uuid,
+    pub project_uuid: Uuid,
+    pub target_uuid: Option<Uuid>,
+    pub transport: String,
+    pub status: String,
+    pub remote_location: Option<String>,
+    pub size_bytes: Option<i64>,
+    pub sha256: Option<String>,
+    pub error: Option<String>,
+    pub started_at: String,
+}
+
+/// Arguments linking a reference asset to a transunit.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NewSegmentReferenceArgs {
+    pub reference_uuid: Uuid,
+    pub project_uuid: Uuid,
+    pub jliff_rel_path: String,
+    pub transunit_id: String,
+    pub file_uuid: Uuid,
+    pub anchor: Option<String>,
+    pub note: Option<String>,
+}
+
+/// Arguments describing a QA profile upsert. An existing profile with the same name is
+/// replaced.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NewQaProfileArgs {
+    pub name: String,
+    pub description: Option<String>,
+    pub definition_json: String,
+}
+
+/// Arguments storing a pipeline preset under its name.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NewPipelinePresetArgs {
+    pub name: String,
+    pub description: Option<String>,
+    pub project_type: Option<String>,
+    pub definition_json: String,
+}
+
+/// Arguments storing an automation script; a script with the same name is replaced.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NewAutomationScriptArgs {
+    pub name: String,
+    pub hook: String,
+    pub source: String,
+    pub enabled: bool,
+}
+
+/// Arguments queueing an outbound operation requested while offline.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NewOfflineOperationArgs {
+    pub operation: String,
+    pub payload_json: String,
+    pub project_uuid: Option<Uuid>,
+}
+
+/// Arguments recording a request that sent document text to a provider.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NewOutboundRequestArgs {
+    pub provider: String,
+    pub project_uuid: Option<Uuid>,
+    pub segment_count: i64,
+    pub char_count: i64,
+    pub destination_host: Option<String>,
+}
+
+/// Arguments adding one provider request to the usage of a project in a month.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordProviderUsageArgs {
+    pub provider: String,
+    pub project_uuid: Uuid,
+    pub usage_month: String,
+    pub characters: i64,
+    pub tokens: i64,
+}
+
+/// Arguments setting the monthly caps of a provider.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProviderBudgetArgs {
+    pub provider: String,
+    pub monthly_character_cap: Option<i64>,
+    pub monthly_token_cap: Option<i64>,
+}
+
+/// Identifies a cached provider translation. Hashes are hex SHA-256 digests of the source text
+/// and of the context sent with it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ProviderCacheKey {
+    pub provider: String,
+    pub model: String,
+    pub source_lang: String,
+    pub target_lang: String,
+    pub source_hash: String,
+    pub context_hash: String,
+}
+
+/// Arguments storing a provider translation in the cache.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NewProviderCacheEntryArgs {
+    pub key: ProviderCacheKey,
+    pub target_text: String,
+}
+
+/// Arguments describing a prompt template insert.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NewPromptTemplateArgs {
+    pub name: String,
+    pub description: Option<String>,
+    pub definition_json: String,
+}
+
+/// Arguments describing a prompt template update; unset fields are kept.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UpdatePromptTemplateArgs {
+    pub template_uuid: Uuid,
+    pub name: Option<String>,
+    pub description: Option<Option<String>>,
+    pub definition_json: Option<String>,
+}
+
+/// Arguments describing a provider candidate for one segment.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NewProviderCandidateArgs {
+    pub transunit_id: String,
+    pub provider: String,
+    pub source_text: String,
+    pub target_text: String,
+    pub qe_score: f64,
+}
+
+/// Arguments describing a provider routing rule insert.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NewProviderRoutingRuleArgs {
+    pub source_lang: Option<String>,
+    pub target_lang: Option<String>,
+    pub subject: Option<String>,
+    pub provider: String,
+    pub note: Option<String>,
+}
+
+/// Owner of a set of provider routing rules.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RoutingRuleOwner {
+    Project(Uuid),
+    Client(Uuid),
+}
+
+/// What a QA profile is assigned to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum QaProfileTarget {
+    Project(Uuid),
+    Client(Uuid),
+}
+
+/// Arguments describing a translation memory entry insert. Re-adding an existing
+/// source/target pair refreshes its `updated_at`, origin and project.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NewTranslationMemoryEntryArgs {
+    pub source_lang: String,
+    pub target_lang: String,
+    pub source_text: String,
+    pub target_text: String,
+    pub project_uuid: Option<Uuid>,
+    /// Where the entry came from (e.g. `editor`).
+    pub origin: String,
+}
+
+/// Arguments adding a term to a termbase.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NewTermbaseEntryArgs {
+    pub termbase: String,
+    pub source_lang: String,
+    pub target_lang: String,
+    pub source_term: String,
+    /// Empty for a term without an equivalent.
+    pub target_term: String,
+    pub project_uuid: Option<Uuid>,
+}
+
+/// A translation memory assigned to a project, identified by the origin of its entries.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProjectTmAssignmentArgs {
+    pub tm_origin: String,
+    /// Lower values are consulted first and win ties.
+    pub priority: i64,
+    /// Percentage points taken off every match from this memory.
+    pub penalty: i64,
+}
+
+/// Arguments describing a document alignment insert; pairs are stored in order.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NewAlignmentArgs {
+    pub alignment_uuid: Uuid,
+    pub source_lang: String,
+    pub target_lang: String,
+    pub source_name: String,
+    pub target_name: String,
+    pub pairs: Vec<NewAlignmentPairArgs>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NewAlignmentPairArgs {
+    pub source_text: String,
+    pub target_text: String,
+    pub score: f64,
+}
+
+/// Review changes to an alignment pair; unset fields are left as they are.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UpdateAlignmentPairArgs {
+    pub position: i64,
+    pub status: Option<String>,
+    pub source_text: Option<String>,
+    pub target_text: Option<String>,
+}
+
+/// A term candidate produced by an extraction run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NewTermCandidateArgs {
+    pub term: String,
+    pub term_key: String,
+    pub target_term: Option<String>,
+    pub frequency: i64,
+    pub score: f64,
+}
+
+/// A review decision on a term candidate; an unset target keeps the proposed one.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TermCandidateDecisionArgs {
+    pub candidate_uuid: Uuid,
+    pub status: String,
+    pub target_term: Option<String>,
+}
+
+/// New texts for an existing translation memory entry.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UpdateTranslationMemoryEntryArgs {
+    pub entry_uuid: Uuid,
+    pub source_text: String,
+    pub target_text: String,
+}
+
+/// Arguments describing a protected ("do not translate") term insert.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NewProtectedTermArgs {
+    pub term: String,
+    pub case_sensitive: bool,
+    pub note: Option<String>,
+}
+
+/// Arguments describing a regex protection rule insert.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NewProtectionRuleArgs {
+    pub rule_uuid: Uuid,
+    pub project_uuid: Uuid,
+    pub name: String,
+    pub pattern: String,
+    pub is_enabled: bool,
+}
+
+/// Arguments describing a new segmentation exception.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NewSegmentationExceptionArgs {
+    pub exception_uuid: Uuid,
+    pub project_uuid: Option<Uuid>,
+    pub language: String,
+    pub kind: String,
+    pub before_text: String,
+    pub after_text: String,
+}
+
+/// Arguments describing a regex protection rule update.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UpdateProtectionRuleArgs {
+    pub rule_uuid: Uuid,
+    pub name: Option<String>,
+    pub pattern: Option<String>,
+    pub is_enabled: Option<bool>,
+}
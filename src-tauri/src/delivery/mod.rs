@@ -0,0 +1,273 @@
+//! Delivery packages: the ZIP sent to a client for one language pair of a project.
+//!
+//! A package holds the translated documents under `target/`, the bilingual XLIFF files under
+//! `bilingual/`, the open QA findings as `qa-report.json` and a plain-text delivery note
+//! listing what is included. Packages are written to the `Deliveries` folder of the project
+//! and named after the project, the language pair and the day they were built; a second
+//! package of the same day gets a ` (2)` suffix instead of replacing the first.
+
+pub mod return_package;
+pub mod transports;
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use zip::CompressionMethod;
+use zip::write::{SimpleFileOptions, ZipWriter};
+
+/// Folder of the project holding delivery packages.
+pub const DELIVERIES_DIR: &str = "Deliveries";
+pub const TARGET_FOLDER: &str = "target";
+pub const BILINGUAL_FOLDER: &str = "bilingual";
+pub const QA_REPORT_NAME: &str = "qa-report.json";
+pub const DELIVERY_NOTE_NAME: &str = "delivery-note.txt";
+
+/// What a package entry is written from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PackageSource {
+    File(PathBuf),
+    Contents(Vec<u8>),
+}
+
+/// One file of a package, `name` being its path inside the archive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackageEntry {
+    pub name: String,
+    pub source: PackageSource,
+}
+
+/// Facts the delivery note is written from.
+#[derive(Debug, Clone, Default)]
+pub struct DeliveryNote<'a> {
+    pub project_name: &'a str,
+    pub project_number: Option<&'a str>,
+    pub client_name: Option<&'a str>,
+    pub source_lang: &'a str,
+    pub target_lang: &'a str,
+    pub date: Option<NaiveDate>,
+    pub documents: &'a [String],
+    pub bilingual_files: &'a [String],
+    /// Source files whose translated document was not found.
+    pub missing_documents: &'a [String],
+    /// Open QA findings counted by severity.
+    pub open_findings: BTreeMap<String, usize>,
+    /// Free text from the project manager.
+    pub message: Option<&'a str>,
+}
+
+/// File name of a package: `<base>_<pair>_<YYYY-MM-DD>.zip`.
+pub fn package_file_name(base: &str, pair_dir: &str, date: NaiveDate) -> String {
+    let base: String = base
+        .chars()
+        .map(|ch| {
+            if ch.is_alphanumeric() || matches!(ch, '-' | '_' | '.') {
+                ch
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    let base = base.trim_matches(['-', '.']);
+    let base = if base.is_empty() { "delivery" } else { base };
+    format!("{base}_{pair_dir}_{}.zip", date.format("%Y-%m-%d"))
+}
+
+/// `dir/file_name`, or the first of `dir/<stem> (2).<ext>`, `dir/<stem> (3).<ext>`, … not
+/// taken.
+pub fn unused_package_path(dir: &Path, file_name: &str) -> PathBuf {
+    let (stem, extension) = file_name.rsplit_once('.').unwrap_or((file_name, "zip"));
+    let mut candidate = dir.join(file_name);
+    let mut counter = 2;
+    while candidate.exists() {
+        candidate = dir.join(format!("{stem} ({counter}).{extension}"));
+        counter += 1;
+    }
+    candidate
+}
+
+/// Writes `entries` into a new archive at `path` and returns its size in bytes. Nothing is
+/// left at `path` when an entry cannot be written.
+pub fn write_package(path: &Path, entries: &[PackageEntry]) -> Result<u64> {
+    let file = File::create_new(path)
+        .with_context(|| format!("{} could not be created", path.display()))?;
+    let result = write_entries(BufWriter::new(file), entries);
+    if result.is_err() {
+        let _ = fs::remove_file(path);
+    }
+    result?;
+    let size = fs::metadata(path)
+        .with_context(|| format!("{} could not be read", path.display()))?
+        .len();
+    Ok(size)
+}
+
+fn write_entries(output: BufWriter<File>, entries: &[PackageEntry]) -> Result<()> {
+    let mut writer = ZipWriter::new(output);
+    let options = SimpleFileOptions::default()
+        .compression_method(CompressionMethod::Deflated)
+        .large_file(true);
+    for entry in entries {
+        writer
+            .start_file(entry.name.as_str(), options)
+            .with_context(|| format!("'{}' could not be added", entry.name))?;
+        match &entry.source {
+            PackageSource::File(path) => {
+                let mut input = File::open(path)
+                    .with_context(|| format!("{} could not be opened", path.display()))?;
+                io::copy(&mut input, &mut writer)
+                    .with_context(|| format!("{} could not be packaged", path.display()))?;
+            }
+            PackageSource::Contents(contents) => writer
+                .write_all(contents)
+                .with_context(|| format!("'{}' could not be written", entry.name))?,
+        }
+    }
+    writer
+        .finish()
+        .context("the package could not be finished")?
+        .flush()
+        .context("the package could not be written")?;
+    Ok(())
+}
+
+/// Renders the delivery note as plain text.
+pub fn render_delivery_note(note: &DeliveryNote<'_>) -> String {
+    let mut text = String::new();
+    let _ = writeln!(text, "Delivery note");
+    let _ = writeln!(text);
+    let _ = writeln!(text, "Project: {}", note.project_name);
+    if let Some(number) = note.project_number {
+        let _ = writeln!(text, "Project number: {number}");
+    }
+    if let Some(client) = note.client_name {
+        let _ = writeln!(text, "Client: {client}");
+    }
+    let _ = writeln!(
+        text,
+        "Languages: {} → {}",
+        note.source_lang, note.target_lang
+    );
+    if let Some(date) = note.date {
+        let _ = writeln!(text, "Date: {}", date.format("%Y-%m-%d"));
+    }
+    if let Some(message) = note.message.map(str::trim).filter(|text| !text.is_empty()) {
+        let _ = writeln!(text);
+        let _ = writeln!(text, "{message}");
+    }
+
+    write_list(&mut text, "Translated documents", note.documents);
+    write_list(&mut text, "Bilingual files", note.bilingual_files);
+    write_list(
+        &mut text,
+        "Not included (no translated document yet)",
+        note.missing_documents,
+    );
+
+    let _ = writeln!(text);
+    let total: usize = note.open_findings.values().sum();
+    if total == 0 {
+        let _ = writeln!(text, "Quality assurance: no open findings.");
+    } else {
+        let counts: Vec<String> = note
+            .open_findings
+            .iter()
+            .map(|(severity, count)| format!("{count} {severity}"))
+            .collect();
+        let _ = writeln!(
+            text,
+            "Quality assurance: {total} open finding(s) ({}), see {QA_REPORT_NAME}.",
+            counts.join(", ")
+        );
+    }
+    text
+}
+
+fn write_list(text: &mut String, title: &str, items: &[String]) {
+    if items.is_empty() {
+        return;
+    }
+    let _ = writeln!(text);
+    let _ = writeln!(text, "{title}:");
+    for item in items {
+        let _ = writeln!(text, "- {item}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use zip::ZipArchive;
+
+    use super::*;
+
+    #[test]
+    fn writes_dated_packages_without_replacing_earlier_ones() {
+        let dir = tempfile::tempdir().unwrap();
+        let document = dir.path().join("manual.docx");
+        fs::write(&document, b"translated").unwrap();
+        let date = NaiveDate::from_ymd_opt(2026, 3, 9).unwrap();
+        let file_name = package_file_name("ACME-2026-0042", "en-US_de-DE", date);
+        assert_eq!(file_name, "ACME-2026-0042_en-US_de-DE_2026-03-09.zip");
+
+        let entries = vec![
+            PackageEntry {
+                name: format!("{TARGET_FOLDER}/manual.docx"),
+                source: PackageSource::File(document.clone()),
+            },
+            PackageEntry {
+                name: DELIVERY_NOTE_NAME.into(),
+                source: PackageSource::Contents(b"note".to_vec()),
+            },
+        ];
+        let first = unused_package_path(dir.path(), &file_name);
+        assert!(write_package(&first, &entries).unwrap() > 0);
+        let second = unused_package_path(dir.path(), &file_name);
+        assert_eq!(
+            second,
+            dir.path()
+                .join("ACME-2026-0042_en-US_de-DE_2026-03-09 (2).zip")
+        );
+
+        let mut archive = ZipArchive::new(File::open(&first).unwrap()).unwrap();
+        let mut contents = String::new();
+        archive
+            .by_name("target/manual.docx")
+            .unwrap()
+            .read_to_string(&mut contents)
+            .unwrap();
+        assert_eq!(contents, "translated");
+
+        // A source that cannot be read leaves no partial archive behind.
+        fs::remove_file(&document).unwrap();
+        assert!(write_package(&second, &entries).is_err());
+        assert!(!second.exists());
+    }
+
+    #[test]
+    fn delivery_note_lists_contents_and_open_findings() {
+        let documents = vec!["manual.docx".to_string()];
+        let missing = vec!["flyer.idml".to_string()];
+        let findings = BTreeMap::from([("major".to_string(), 2), ("minor".to_string(), 1)]);
+        let text = render_delivery_note(&DeliveryNote {
+            project_name: "Manual",
+            project_number: Some("ACME-2026-0042"),
+            source_lang: "en-US",
+            target_lang: "de-DE",
+            documents: &documents,
+            missing_documents: &missing,
+            open_findings: findings,
+            ..DeliveryNote::default()
+        });
+        assert!(text.contains("Project number: ACME-2026-0042"));
+        assert!(text.contains("Translated documents:\n- manual.docx"));
+        assert!(text.contains("- flyer.idml"));
+        assert!(!text.contains("Bilingual files"));
+        assert!(text.contains("3 open finding(s) (2 major, 1 minor)"));
+    }
+}
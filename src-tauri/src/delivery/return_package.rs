@@ -0,0 +1,167 @@
+//! Return packages: the translated bilingual documents of a project created from a Trados or
+//! memoQ package, sent back in the structure of that package so the client's CAT tool can
+//! import the delivery as it is.
+//!
+//! Each document goes back to the path it had in the package, and the manifest is carried
+//! over; a Trados manifest is marked as belonging to a return package. Trados return
+//! packages are `.sdlrpx` files and memoQ ones `.mqback` files, both ZIP archives.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+
+use super::{PackageEntry, PackageSource};
+use crate::intake::cat_package::{CatPackage, CatPackageKind};
+
+/// Contents of a return package.
+#[derive(Debug, Clone, Default)]
+pub struct ReturnPackage {
+    pub entries: Vec<PackageEntry>,
+    /// Paths inside the package of the documents included.
+    pub documents: Vec<String>,
+    /// Paths inside the package of the documents without a translated file.
+    pub missing_documents: Vec<String>,
+}
+
+/// File name of the return package of the package at `package_path`: its stem with the
+/// return extension of `kind`.
+pub fn return_package_file_name(package_path: &Path, kind: CatPackageKind) -> String {
+    let stem = package_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .filter(|stem| !stem.trim().is_empty())
+        .unwrap_or_else(|| "return".to_string());
+    format!("{stem}.{}", kind.return_extension())
+}
+
+/// Lays out the return package of the package at `package_path`. `translated` maps the
+/// staged name of a document to the file holding its translation.
+pub fn return_package_entries(
+    package_path: &Path,
+    package: &CatPackage,
+    translated: &HashMap<String, PathBuf>,
+) -> Result<ReturnPackage> {
+    let mut contents = ReturnPackage::default();
+    if let Some((manifest_path, data)) = package.read_manifest(package_path)? {
+        let data = match package.kind {
+            CatPackageKind::Trados => mark_as_return_package(data),
+            CatPackageKind::MemoQ => data,
+        };
+        contents.entries.push(PackageEntry {
+            name: entry_name(&manifest_path),
+            source: PackageSource::Contents(data),
+        });
+    }
+    for document in package.documents() {
+        let name = entry_name(document.relative_path());
+        match translated.get(&document.staged_name) {
+            Some(path) => {
+                contents.entries.push(PackageEntry {
+                    name: name.clone(),
+                    source: PackageSource::File(path.clone()),
+                });
+                contents.documents.push(name);
+            }
+            None => contents.missing_documents.push(name),
+        }
+    }
+    Ok(contents)
+}
+
+/// Archive entry name of a path: its components joined with `/`.
+fn entry_name(relative_path: &Path) -> String {
+    relative_path
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Trados tells project and return packages apart by the `PackageType` of the manifest.
+fn mark_as_return_package(data: Vec<u8>) -> Vec<u8> {
+    match String::from_utf8(data) {
+        Ok(text) => text
+            .replacen(
+                "PackageType=\"ProjectPackage\"",
+                "PackageType=\"ReturnPackage\"",
+                1,
+            )
+            .into_bytes(),
+        Err(error) => error.into_bytes(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::{self, File};
+    use std::io::{Read, Write};
+
+    use zip::ZipArchive;
+    use zip::write::{SimpleFileOptions, ZipWriter};
+
+    use super::*;
+    use crate::delivery::write_package;
+    use crate::intake::cat_package::read_package;
+
+    #[test]
+    fn translated_documents_go_back_to_their_package_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let package_path = dir.path().join("ACME_Q3.sdlppx");
+        let mut writer = ZipWriter::new(File::create(&package_path).unwrap());
+        for (name, contents) in [
+            (
+                "ACME_Q3.sdlproj",
+                r#"<PackageProject PackageType="ProjectPackage">
+                  <LanguageDirection SourceLanguageCode="en-US" TargetLanguageCode="de-DE" />
+                  <LanguageDirection SourceLanguageCode="en-US" TargetLanguageCode="fr-FR" />
+                </PackageProject>"#,
+            ),
+            ("de-DE/manual.docx.sdlxliff", "<xliff/>"),
+            ("fr-FR/manual.docx.sdlxliff", "<xliff/>"),
+        ] {
+            writer
+                .start_file(name, SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(contents.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap();
+
+        let package = read_package(&package_path).unwrap();
+        let documents = package.documents();
+        assert_eq!(documents[0].staged_name, "manual.docx.sdlxliff");
+        assert_eq!(documents[0].target_language.as_deref(), Some("de-DE"));
+        assert_eq!(documents[1].staged_name, "manual.docx (2).sdlxliff");
+        assert_eq!(documents[1].target_language.as_deref(), Some("fr-FR"));
+
+        let german = dir.path().join("translated.sdlxliff");
+        fs::write(&german, "<xliff>übersetzt</xliff>").unwrap();
+        let translated = HashMap::from([("manual.docx.sdlxliff".to_string(), german)]);
+        let contents = return_package_entries(&package_path, &package, &translated).unwrap();
+        assert_eq!(contents.documents, vec!["de-DE/manual.docx.sdlxliff"]);
+        assert_eq!(
+            contents.missing_documents,
+            vec!["fr-FR/manual.docx.sdlxliff"]
+        );
+
+        let file_name = return_package_file_name(&package_path, package.kind);
+        assert_eq!(file_name, "ACME_Q3.sdlrpx");
+        let return_path = dir.path().join(file_name);
+        write_package(&return_path, &contents.entries).unwrap();
+        let mut archive = ZipArchive::new(File::open(&return_path).unwrap()).unwrap();
+        let mut manifest = String::new();
+        archive
+            .by_name("ACME_Q3.sdlproj")
+            .unwrap()
+            .read_to_string(&mut manifest)
+            .unwrap();
+        assert!(manifest.contains(r#"PackageType="ReturnPackage""#));
+        let mut document = String::new();
+        archive
+            .by_name("de-DE/manual.docx.sdlxliff")
+            .unwrap()
+            .read_to_string(&mut document)
+            .unwrap();
+        assert_eq!(document, "<xliff>übersetzt</xliff>");
+    }
+}
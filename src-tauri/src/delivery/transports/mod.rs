@@ -0,0 +1,268 @@
+//! Uploads of delivery packages to a client's SFTP server or S3 bucket.
+//!
+//! A client configures its targets once: connection settings are stored with the client
+//! while passwords and secret keys go to the secrets vault. An upload reports progress as
+//! bytes are sent, writes under a temporary name (SFTP) or as one object (S3) so the client
+//! never picks up a partial package, and returns a receipt with the remote location and the
+//! SHA-256 of what was sent.
+
+pub mod s3;
+pub mod sftp;
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Minimum number of bytes between two progress reports.
+const PROGRESS_STEP_BYTES: u64 = 256 * 1024;
+
+#[derive(Debug, Error)]
+pub enum TransportError {
+    #[error("the upload request failed: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("{operation} returned HTTP {status}: {message}")]
+    Status {
+        operation: &'static str,
+        status: u16,
+        message: String,
+    },
+    #[error("SSH: {0}")]
+    Ssh(#[from] ssh2::Error),
+    #[error("the host key of {host} is {actual}, but {expected} was confirmed")]
+    HostKeyMismatch {
+        host: String,
+        expected: String,
+        actual: String,
+    },
+    #[error("the host key of {host} has not been confirmed; its fingerprint is {fingerprint}")]
+    UnconfirmedHostKey { host: String, fingerprint: String },
+    #[error("{0}")]
+    Invalid(String),
+    #[error("failed to transfer the package: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Where a client receives its packages.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "transport", rename_all = "lowercase")]
+pub enum TargetSettings {
+    Sftp(SftpSettings),
+    S3(S3Settings),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SftpSettings {
+    pub host: String,
+    #[serde(default = "default_sftp_port")]
+    pub port: u16,
+    pub username: String,
+    /// Folder receiving the packages; relative folders start at the login folder.
+    #[serde(default)]
+    pub remote_dir: String,
+    /// Private key authenticating the user; the vault secret is then its passphrase instead
+    /// of the password.
+    #[serde(default)]
+    pub private_key_path: Option<String>,
+    /// SHA-256 fingerprint of the server's host key as printed by `ssh-keygen -l`, e.g.
+    /// `SHA256:nThbg6kXUpJWGl7E1IGOCspRomTxdCARLviKw6E5SY8`. Uploads fail until it is set.
+    #[serde(default)]
+    pub host_key_fingerprint: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct S3Settings {
+    /// Endpoint of an S3-compatible service; AWS when empty.
+    #[serde(default)]
+    pub endpoint: Option<String>,
+    pub region: String,
+    pub bucket: String,
+    /// Key prefix the packages are stored under, such as `deliveries/`.
+    #[serde(default)]
+    pub prefix: String,
+    pub access_key_id: String,
+    /// Addresses the bucket in the path instead of the host name, as most S3-compatible
+    /// services require.
+    #[serde(default)]
+    pub path_style: bool,
+}
+
+fn default_sftp_port() -> u16 {
+    22
+}
+
+impl TargetSettings {
+    pub fn transport(&self) -> &'static str {
+        match self {
+            Self::Sftp(_) => "sftp",
+            Self::S3(_) => "s3",
+        }
+    }
+
+    /// Checks that the settings are complete; nothing is contacted.
+    pub fn validate(&self) -> Result<(), TransportError> {
+        let required = |value: &str, field: &str| {
+            if value.trim().is_empty() {
+                Err(TransportError::Invalid(format!("{field} is required.")))
+            } else {
+                Ok(())
+            }
+        };
+        match self {
+            Self::Sftp(settings) => {
+                required(&settings.host, "host")?;
+                required(&settings.username, "username")?;
+                if settings.port == 0 {
+                    return Err(TransportError::Invalid("port must not be 0.".into()));
+                }
+                Ok(())
+            }
+            Self::S3(settings) => {
+                required(&settings.region, "region")?;
+                required(&settings.bucket, "bucket")?;
+                required(&settings.access_key_id, "accessKeyId")?;
+                if let Some(endpoint) = settings.endpoint.as_deref()
+                    && !endpoint.trim().is_empty()
+                    && reqwest::Url::parse(endpoint.trim()).is_err()
+                {
+                    return Err(TransportError::Invalid(
+                        "endpoint must be a URL such as https://s3.example.com.".into(),
+                    ));
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Bytes sent so far.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TransferProgress {
+    pub sent: u64,
+    pub total: u64,
+}
+
+/// What the target confirmed receiving.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UploadReceipt {
+    /// `sftp://user@host:port/path` or `s3://bucket/key`.
+    pub remote_location: String,
+    pub size_bytes: u64,
+    /// SHA-256 of the bytes sent, hex encoded.
+    pub sha256: String,
+}
+
+/// Uploads the package at `path` to `settings` under its file name. `secret` is the vault
+/// secret of the target.
+pub async fn upload(
+    settings: &TargetSettings,
+    secret: Option<String>,
+    path: &Path,
+    on_progress: impl Fn(TransferProgress) + Send + Sync + 'static,
+) -> Result<UploadReceipt, TransportError> {
+    settings.validate()?;
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .ok_or_else(|| TransportError::Invalid("The package has no file name.".into()))?;
+    match settings {
+        TargetSettings::S3(settings) => {
+            let secret = secret.ok_or_else(|| {
+                TransportError::Invalid("The S3 target has no secret access key.".into())
+            })?;
+            s3::upload(settings, &secret, path, &file_name, &on_progress).await
+        }
+        TargetSettings::Sftp(settings) => {
+            let settings = settings.clone();
+            let path = path.to_path_buf();
+            tokio::task::spawn_blocking(move || {
+                sftp::upload(
+                    &settings,
+                    secret.as_deref(),
+                    &path,
+                    &file_name,
+                    &on_progress,
+                )
+            })
+            .await
+            .map_err(|error| TransportError::Invalid(format!("The upload was aborted: {error}")))?
+        }
+    }
+}
+
+/// Counts bytes sent and reports them every [`PROGRESS_STEP_BYTES`].
+struct ProgressCounter<'a> {
+    on_progress: &'a (dyn Fn(TransferProgress) + Send + Sync),
+    sent: u64,
+    reported: u64,
+    total: u64,
+}
+
+impl<'a> ProgressCounter<'a> {
+    fn new(total: u64, on_progress: &'a (dyn Fn(TransferProgress) + Send + Sync)) -> Self {
+        on_progress(TransferProgress { sent: 0, total });
+        Self {
+            on_progress,
+            sent: 0,
+            reported: 0,
+            total,
+        }
+    }
+
+    fn add(&mut self, bytes: u64) {
+        self.sent += bytes;
+        if self.sent - self.reported >= PROGRESS_STEP_BYTES {
+            self.report();
+        }
+    }
+
+    fn finish(&mut self) {
+        if self.reported != self.sent {
+            self.report();
+        }
+    }
+
+    fn report(&mut self) {
+        self.reported = self.sent;
+        (self.on_progress)(TransferProgress {
+            sent: self.sent,
+            total: self.total,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn settings_are_tagged_by_transport_and_validated() {
+        let settings: TargetSettings = serde_json::from_value(json!({
+            "transport": "sftp",
+            "host": "files.acme.example",
+            "username": "delivery",
+            "remoteDir": "incoming"
+        }))
+        .unwrap();
+        let TargetSettings::Sftp(sftp) = &settings else {
+            panic!("expected SFTP settings");
+        };
+        assert_eq!(sftp.port, 22);
+        assert_eq!(settings.transport(), "sftp");
+        assert!(settings.validate().is_ok());
+
+        let settings: TargetSettings = serde_json::from_value(json!({
+            "transport": "s3",
+            "region": "eu-central-1",
+            "bucket": "",
+            "accessKeyId": "AKIDEXAMPLE"
+        }))
+        .unwrap();
+        let error = settings.validate().unwrap_err();
+        assert_eq!(error.to_string(), "bucket is required.");
+    }
+}
@@ -0,0 +1,189 @@
+//! Uploads to SFTP servers.
+//!
+//! The server must present the host key confirmed in the target settings. Packages are
+//! written as `<name>.part` and renamed once complete, so a client polling the folder never
+//! picks up a partial file.
+
+use std::fs::File;
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use sha2::{Digest, Sha256};
+use ssh2::{HashType, Session, Sftp};
+
+use super::{ProgressCounter, SftpSettings, TransferProgress, TransportError, UploadReceipt};
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(15);
+const SESSION_TIMEOUT_MS: u32 = 60_000;
+const CHUNK_SIZE: usize = 64 * 1024;
+
+pub(super) fn upload(
+    settings: &SftpSettings,
+    secret: Option<&str>,
+    path: &Path,
+    file_name: &str,
+    on_progress: &(dyn Fn(TransferProgress) + Send + Sync),
+) -> Result<UploadReceipt, TransportError> {
+    let host = settings.host.trim();
+    let username = settings.username.trim();
+    let session = connect(host, settings.port)?;
+    verify_host_key(&session, host, settings.host_key_fingerprint.as_deref())?;
+    match settings
+        .private_key_path
+        .as_deref()
+        .map(str::trim)
+        .filter(|key| !key.is_empty())
+    {
+        Some(key) => session.userauth_pubkey_file(username, None, Path::new(key), secret)?,
+        None => {
+            let password = secret.ok_or_else(|| {
+                TransportError::Invalid("The SFTP target has neither a key nor a password.".into())
+            })?;
+            session.userauth_password(username, password)?;
+        }
+    }
+    if !session.authenticated() {
+        return Err(TransportError::Invalid(format!(
+            "{username} could not sign in to {host}."
+        )));
+    }
+
+    let sftp = session.sftp()?;
+    let remote_dir = settings.remote_dir.trim().trim_end_matches('/');
+    create_dir_all(&sftp, remote_dir)?;
+    let remote_path = if remote_dir.is_empty() {
+        PathBuf::from(file_name)
+    } else {
+        Path::new(remote_dir).join(file_name)
+    };
+    let partial_path = remote_path.with_file_name(format!("{file_name}.part"));
+
+    let mut input = File::open(path)?;
+    let total = input.metadata()?.len();
+    let mut progress = ProgressCounter::new(total, on_progress);
+    let mut hasher = Sha256::new();
+    let mut output = sftp.create(&partial_path)?;
+    let mut buffer = vec![0u8; CHUNK_SIZE];
+    loop {
+        let read = input.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        output.write_all(&buffer[..read])?;
+        hasher.update(&buffer[..read]);
+        progress.add(read as u64);
+    }
+    output.flush()?;
+    drop(output);
+
+    // SFTP v3 servers refuse to rename onto an existing file.
+    if sftp.stat(&remote_path).is_ok() {
+        sftp.unlink(&remote_path)?;
+    }
+    sftp.rename(&partial_path, &remote_path, None)?;
+    progress.finish();
+
+    let remote_path = remote_path.to_string_lossy().replace('\\', "/");
+    let separator = if remote_path.starts_with('/') {
+        ""
+    } else {
+        "/"
+    };
+    Ok(UploadReceipt {
+        remote_location: format!(
+            "sftp://{username}@{host}:{}{separator}{remote_path}",
+            settings.port
+        ),
+        size_bytes: total,
+        sha256: format!("{:x}", hasher.finalize()),
+    })
+}
+
+fn connect(host: &str, port: u16) -> Result<Session, TransportError> {
+    let address = (host, port)
+        .to_socket_addrs()?
+        .next()
+        .ok_or_else(|| TransportError::Invalid(format!("{host} could not be resolved.")))?;
+    let stream = TcpStream::connect_timeout(&address, CONNECT_TIMEOUT)?;
+    let mut session = Session::new()?;
+    session.set_timeout(SESSION_TIMEOUT_MS);
+    session.set_tcp_stream(stream);
+    session.handshake()?;
+    Ok(session)
+}
+
+fn verify_host_key(
+    session: &Session,
+    host: &str,
+    expected: Option<&str>,
+) -> Result<(), TransportError> {
+    let hash = session
+        .host_key_hash(HashType::Sha256)
+        .ok_or_else(|| TransportError::Invalid(format!("{host} did not present a host key.")))?;
+    let actual = format!("SHA256:{}", base64_unpadded(hash));
+    let Some(expected) = expected.map(str::trim).filter(|value| !value.is_empty()) else {
+        return Err(TransportError::UnconfirmedHostKey {
+            host: host.to_string(),
+            fingerprint: actual,
+        });
+    };
+    let normalized = expected.strip_prefix("SHA256:").unwrap_or(expected);
+    if normalized.trim_end_matches('=') != &actual["SHA256:".len()..] {
+        return Err(TransportError::HostKeyMismatch {
+            host: host.to_string(),
+            expected: expected.to_string(),
+            actual,
+        });
+    }
+    Ok(())
+}
+
+/// Creates `dir` and its missing parents.
+fn create_dir_all(sftp: &Sftp, dir: &str) -> Result<(), TransportError> {
+    let mut current = PathBuf::new();
+    if dir.starts_with('/') {
+        current.push("/");
+    }
+    for part in dir.split('/').filter(|part| !part.is_empty()) {
+        current.push(part);
+        if sftp.stat(&current).is_err() {
+            sftp.mkdir(&current, 0o755)?;
+        }
+    }
+    Ok(())
+}
+
+/// Base64 without padding, as `ssh-keygen` prints fingerprints.
+fn base64_unpadded(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let value = chunk.iter().enumerate().fold(0u32, |value, (index, byte)| {
+            value | (u32::from(*byte) << (16 - 8 * index))
+        });
+        for index in 0..=chunk.len() {
+            encoded.push(char::from(
+                ALPHABET[(value >> (18 - 6 * index)) as usize & 63],
+            ));
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fingerprints_are_unpadded_base64() {
+        assert_eq!(base64_unpadded(b"hello"), "aGVsbG8");
+        assert_eq!(base64_unpadded(b"hel"), "aGVs");
+        assert_eq!(base64_unpadded(b"h"), "aA");
+        assert_eq!(
+            base64_unpadded(&Sha256::digest(b"")),
+            "47DEQpj8HBSa+/TImW+5JCeuQeRkm5NMpJWZG3hSuFU"
+        );
+    }
+}
@@ -0,0 +1,364 @@
+//! Resumable, checksum-verified HTTP downloads shared by features that fetch large assets
+//! (spellcheck dictionaries, toolchains, local models).
+//!
+//! A download is written to `<destination>.part` and renamed once complete and verified, so
+//! an interrupted transfer never leaves a truncated file under the final name. A later
+//! attempt resumes the `.part` file with an HTTP `Range` request; servers that ignore ranges
+//! answer with the full body and the download starts over. Assets of the managed cache live
+//! under `<app_folder>/downloads/<category>/`.
+
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use reqwest::StatusCode;
+use reqwest::header::{CONTENT_RANGE, RANGE};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use tokio::fs;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex as AsyncMutex;
+
+use crate::http::HttpClient;
+
+/// Directory (relative to the app folder) holding the download cache.
+pub const DOWNLOADS_DIR: &str = "downloads";
+/// Suffix of files still being downloaded.
+const PART_SUFFIX: &str = ".part";
+/// Minimum number of bytes between two progress reports.
+const PROGRESS_STEP_BYTES: u64 = 256 * 1024;
+
+/// Errors raised while downloading.
+#[derive(Debug, Error)]
+pub enum DownloadError {
+    #[error("the download request failed: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("{url} returned HTTP {status}")]
+    Status { url: String, status: u16 },
+    #[error("checksum mismatch: expected sha256 {expected}, got {actual}")]
+    ChecksumMismatch { expected: String, actual: String },
+    #[error("invalid download name '{0}'")]
+    InvalidName(String),
+    #[error("failed to write the download: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Bytes received so far.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DownloadProgress {
+    pub downloaded: u64,
+    /// Size announced by the server, when known.
+    pub total: Option<u64>,
+}
+
+/// A file of the managed cache.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CachedDownload {
+    pub category: String,
+    pub file_name: String,
+    pub path: PathBuf,
+    pub size: u64,
+    /// `true` while only a `.part` file exists.
+    pub partial: bool,
+}
+
+/// Downloader registered as Tauri state. Concurrent downloads of the same destination are
+/// serialised so they do not write to the same `.part` file.
+pub struct Downloader {
+    client: HttpClient,
+    locks: Mutex<HashMap<PathBuf, Arc<AsyncMutex<()>>>>,
+}
+
+impl Default for Downloader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Downloader {
+    pub fn new() -> Self {
+        let client = HttpClient::new(|builder| builder.connect_timeout(Duration::from_secs(15)));
+        Self {
+            client,
+            locks: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Downloads `url` into the managed cache as `<cache_root>/<category>/<file_name>`.
+    pub async fn fetch(
+        &self,
+        cache_root: &Path,
+        category: &str,
+        file_name: &str,
+        url: &str,
+        sha256: Option<&str>,
+        on_progress: impl FnMut(DownloadProgress),
+    ) -> Result<PathBuf, DownloadError> {
+        let destination = cache_path(cache_root, category, file_name)?;
+        self.download_to(url, &destination, sha256, on_progress)
+            .await?;
+        Ok(destination)
+    }
+
+    /// Downloads `url` to `destination`, resuming an earlier partial download. An existing
+    /// destination is kept when it matches `sha256` (or when no checksum is given).
+    pub async fn download_to(
+        &self,
+        url: &str,
+        destination: &Path,
+        sha256: Option<&str>,
+        mut on_progress: impl FnMut(DownloadProgress),
+    ) -> Result<(), DownloadError> {
+        let lock = self.lock_for(destination);
+        let _guard = lock.lock().await;
+        let expected = sha256.map(|value| value.trim().to_ascii_lowercase());
+
+        if fs::try_exists(destination).await.unwrap_or(false) {
+            match &expected {
+                None => return Ok(()),
+                Some(expected) if &sha256_file(destination).await? == expected => return Ok(()),
+                Some(_) => fs::remove_file(destination).await?,
+            }
+        }
+        if let Some(parent) = destination.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+
+        let part = part_path(destination);
+        let offset = fs::metadata(&part)
+            .await
+            .map(|meta| meta.len())
+            .unwrap_or(0);
+        let mut request = self.client.current().get(url);
+        if offset > 0 {
+            request = request.header(RANGE, format!("bytes={offset}-"));
+        }
+        let mut response = request.send().await?;
+        let status = response.status();
+
+        if status == StatusCode::RANGE_NOT_SATISFIABLE {
+            // The part file is at least as long as the resource; verify it as it is.
+        } else if !status.is_success() {
+            return Err(DownloadError::Status {
+                url: url.to_string(),
+                status: status.as_u16(),
+            });
+        } else {
+            let content_range = response
+                .headers()
+                .get(CONTENT_RANGE)
+                .and_then(|value| value.to_str().ok());
+            let resumed = resume_offset(status.as_u16(), content_range, offset);
+            if status == StatusCode::PARTIAL_CONTENT && resumed.is_none() {
+                // A range we did not ask for; drop the part so the next attempt starts over.
+                let _ = fs::remove_file(&part).await;
+                return Err(DownloadError::Status {
+                    url: url.to_string(),
+                    status: status.as_u16(),
+                });
+            }
+            let start = resumed.unwrap_or(0);
+            let total = content_range
+                .and_then(content_range_total)
+                .or_else(|| response.content_length().map(|length| start + length));
+
+            let mut file = fs::OpenOptions::new()
+                .create(true)
+                .write(true)
+                .append(resumed.is_some())
+                .truncate(resumed.is_none())
+                .open(&part)
+                .await?;
+            let mut downloaded = start;
+            let mut reported = start;
+            on_progress(DownloadProgress { downloaded, total });
+            while let Some(chunk) = response.chunk().await? {
+                file.write_all(&chunk).await?;
+                downloaded += chunk.len() as u64;
+                if downloaded - reported >= PROGRESS_STEP_BYTES {
+                    reported = downloaded;
+                    on_progress(DownloadProgress { downloaded, total });
+                }
+            }
+            file.flush().await?;
+            if reported != downloaded {
+                on_progress(DownloadProgress { downloaded, total });
+            }
+        }
+
+        if let Some(expected) = expected {
+            let actual = sha256_file(&part).await?;
+            if actual != expected {
+                // A corrupt part cannot be resumed; the next attempt starts over.
+                fs::remove_file(&part).await?;
+                return Err(DownloadError::ChecksumMismatch { expected, actual });
+            }
+        }
+        fs::rename(&part, destination).await?;
+        log::info!(
+            target: "downloads",
+            "downloaded {url} into {}",
+            destination.display()
+        );
+        Ok(())
+    }
+
+    fn lock_for(&self, destination: &Path) -> Arc<AsyncMutex<()>> {
+        Arc::clone(
+            self.locks
+                .lock()
+                .expect("download locks poisoned")
+                .entry(destination.to_path_buf())
+                .or_default(),
+        )
+    }
+}
+
+/// Path of a cached asset, rejecting names that would escape the cache.
+pub fn cache_path(
+    cache_root: &Path,
+    category: &str,
+    file_name: &str,
+) -> Result<PathBuf, DownloadError> {
+    for name in [category, file_name] {
+        let valid = !name.is_empty()
+            && name != "."
+            && name != ".."
+            && !name.ends_with(PART_SUFFIX)
+            && !name.contains(['/', '\\', ':']);
+        if !valid {
+            return Err(DownloadError::InvalidName(name.to_string()));
+        }
+    }
+    Ok(cache_root.join(category).join(file_name))
+}
+
+/// Lists the files of the managed cache, including partial downloads.
+pub async fn list_cached(cache_root: &Path) -> Result<Vec<CachedDownload>, DownloadError> {
+    let mut entries = Vec::new();
+    let Ok(mut categories) = fs::read_dir(cache_root).await else {
+        return Ok(entries);
+    };
+    while let Some(category) = categories.next_entry().await? {
+        if !category.file_type().await?.is_dir() {
+            continue;
+        }
+        let category_name = category.file_name().to_string_lossy().into_owned();
+        let mut files = fs::read_dir(category.path()).await?;
+        while let Some(file) = files.next_entry().await? {
+            let metadata = file.metadata().await?;
+            if !metadata.is_file() {
+                continue;
+            }
+            let name = file.file_name().to_string_lossy().into_owned();
+            let (file_name, partial) = match name.strip_suffix(PART_SUFFIX) {
+                Some(stem) => (stem.to_string(), true),
+                None => (name, false),
+            };
+            entries.push(CachedDownload {
+                category: category_name.clone(),
+                file_name,
+                path: file.path(),
+                size: metadata.len(),
+                partial,
+            });
+        }
+    }
+    entries.sort_by(|a, b| (&a.category, &a.file_name).cmp(&(&b.category, &b.file_name)));
+    Ok(entries)
+}
+
+/// Removes a cached asset and its partial download. Returns whether anything was removed.
+pub async fn remove_cached(
+    cache_root: &Path,
+    category: &str,
+    file_name: &str,
+) -> Result<bool, DownloadError> {
+    let destination = cache_path(cache_root, category, file_name)?;
+    let mut removed = false;
+    for path in [part_path(&destination), destination] {
+        match fs::remove_file(&path).await {
+            Ok(()) => removed = true,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {}
+            Err(error) => return Err(error.into()),
+        }
+    }
+    Ok(removed)
+}
+
+fn part_path(destination: &Path) -> PathBuf {
+    let mut name = destination.as_os_str().to_os_string();
+    name.push(PART_SUFFIX);
+    PathBuf::from(name)
+}
+
+/// Offset the response body continues from, or `None` when it holds the whole resource.
+fn resume_offset(status: u16, content_range: Option<&str>, requested: u64) -> Option<u64> {
+    if status != 206 || requested == 0 {
+        return None;
+    }
+    // `bytes <start>-<end>/<total>`; only a continuation of our part file can be appended.
+    let start = content_range?
+        .strip_prefix("bytes ")?
+        .split('-')
+        .next()?
+        .trim()
+        .parse::<u64>()
+        .ok()?;
+    (start == requested).then_some(start)
+}
+
+fn content_range_total(content_range: &str) -> Option<u64> {
+    content_range.rsplit('/').next()?.trim().parse().ok()
+}
+
+/// Lowercase hex SHA-256 digest of a file.
+pub async fn sha256_file(path: &Path) -> Result<String, DownloadError> {
+    let path = path.to_path_buf();
+    let digest = tokio::task::spawn_blocking(move || -> std::io::Result<String> {
+        let mut file = std::fs::File::open(path)?;
+        let mut hasher = Sha256::new();
+        let mut buffer = vec![0; 64 * 1024];
+        loop {
+            let read = file.read(&mut buffer)?;
+            if read == 0 {
+                break;
+            }
+            hasher.update(&buffer[..read]);
+        }
+        Ok(format!("{:x}", hasher.finalize()))
+    })
+    .await
+    .map_err(|error| std::io::Error::other(error.to_string()))??;
+    Ok(digest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resumes_only_matching_partial_responses() {
+        assert_eq!(
+            resume_offset(206, Some("bytes 100-999/1000"), 100),
+            Some(100)
+        );
+        // The server ignored the range or answered from another offset: start over.
+        assert_eq!(resume_offset(200, None, 100), None);
+        assert_eq!(resume_offset(206, Some("bytes 0-999/1000"), 100), None);
+        assert_eq!(resume_offset(206, None, 100), None);
+        assert_eq!(content_range_total("bytes 100-999/1000"), Some(1000));
+        assert_eq!(content_range_total("bytes 100-999/*"), None);
+
+        let root = Path::new("/cache");
+        assert_eq!(
+            cache_path(root, "models", "qwen.gguf").unwrap(),
+            Path::new("/cache/models/qwen.gguf")
+        );
+        assert!(cache_path(root, "models", "../secrets").is_err());
+        assert!(cache_path(root, "..", "file").is_err());
+        assert!(cache_path(root, "models", "qwen.gguf.part").is_err());
+    }
+}
@@ -0,0 +1,258 @@
+//! Supported file formats and what the app can do with them.
+//!
+//! The capabilities come from a versioned manifest: the one embedded in the app, or
+//! `formats.json` in the app folder when present. [`current`] checks the override's
+//! modification time on every call, so edits apply without a restart; an override that
+//! cannot be read or is invalid is logged and the embedded manifest is used instead.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+use std::time::SystemTime;
+
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+
+/// File in the app folder overriding the embedded manifest.
+pub const FORMATS_MANIFEST_FILE: &str = "formats.json";
+/// Newest manifest version this build understands.
+pub const FORMATS_MANIFEST_VERSION: u32 = 1;
+
+const EMBEDDED_MANIFEST: &str = include_str!("formats.json");
+
+/// Role files of a format get when no rule or choice assigns one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FormatRole {
+    Processable,
+    Reference,
+    Image,
+}
+
+/// One supported format.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FormatCapabilities {
+    pub id: String,
+    pub label: String,
+    /// Heading the wizard lists the format under, e.g. `Microsoft Office`.
+    pub group: String,
+    /// Extensions without the dot, lowercase.
+    pub extensions: Vec<String>,
+    pub role: FormatRole,
+    /// Converter filter processable files of the format are extracted with.
+    #[serde(default)]
+    pub filter: Option<String>,
+    /// Whether files of the format are XLIFF already and need no conversion.
+    #[serde(default)]
+    pub bilingual: bool,
+}
+
+/// The supported formats.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FormatsManifest {
+    pub version: u32,
+    pub formats: Vec<FormatCapabilities>,
+    /// Whether the manifest was read from the app folder.
+    #[serde(skip)]
+    pub overridden: bool,
+}
+
+impl FormatsManifest {
+    /// Parses and checks a manifest. Extensions are normalized to lowercase without a dot.
+    pub fn parse(json: &str) -> Result<Self> {
+        let mut manifest: FormatsManifest =
+            serde_json::from_str(json).context("The formats manifest is not valid JSON.")?;
+        if manifest.version == 0 || manifest.version > FORMATS_MANIFEST_VERSION {
+            bail!(
+                "Formats manifest version {} is not supported; this version of the app reads up \
+                 to version {FORMATS_MANIFEST_VERSION}.",
+                manifest.version
+            );
+        }
+
+        let mut ids = HashSet::new();
+        let mut extensions = HashSet::new();
+        for format in &mut manifest.formats {
+            if format.id.trim().is_empty() || !ids.insert(format.id.clone()) {
+                bail!("Format ids must be unique and not empty ('{}').", format.id);
+            }
+            if format.extensions.is_empty() {
+                bail!("The format '{}' has no extensions.", format.id);
+            }
+            for extension in &mut format.extensions {
+                *extension = extension.trim().trim_start_matches('.').to_lowercase();
+                if extension.is_empty() || !extensions.insert(extension.clone()) {
+                    bail!(
+                        "The format '{}' repeats or leaves empty the extension '{extension}'.",
+                        format.id
+                    );
+                }
+            }
+            if format.role != FormatRole::Processable
+                && (format.bilingual || format.filter.is_some())
+            {
+                bail!(
+                    "Only processable formats can be bilingual or have a filter ('{}').",
+                    format.id
+                );
+            }
+        }
+        Ok(manifest)
+    }
+
+    /// The format of an extension, with or without the dot, in any case.
+    pub fn format_for(&self, extension: &str) -> Option<&FormatCapabilities> {
+        let extension = extension.trim().trim_start_matches('.');
+        self.formats.iter().find(|format| {
+            format
+                .extensions
+                .iter()
+                .any(|candidate| candidate.eq_ignore_ascii_case(extension))
+        })
+    }
+
+    /// Role of files with the extension; files of unknown formats are references.
+    pub fn default_role(&self, extension: &str) -> FormatRole {
+        self.format_for(extension)
+            .map(|format| format.role)
+            .unwrap_or(FormatRole::Reference)
+    }
+
+    /// Whether files with the extension are processable and XLIFF already.
+    pub fn is_bilingual(&self, extension: &str) -> bool {
+        self.format_for(extension)
+            .is_some_and(|format| format.role == FormatRole::Processable && format.bilingual)
+    }
+
+    /// Whether files with the extension are converted to XLIFF.
+    pub fn is_convertible(&self, extension: &str) -> bool {
+        self.format_for(extension)
+            .is_some_and(|format| format.role == FormatRole::Processable && !format.bilingual)
+    }
+
+    /// The converter filter for files with the extension.
+    pub fn filter_for(&self, extension: &str) -> Option<&str> {
+        self.format_for(extension)
+            .and_then(|format| format.filter.as_deref())
+    }
+}
+
+/// The manifest built into the app.
+pub fn embedded() -> &'static FormatsManifest {
+    static EMBEDDED: OnceLock<FormatsManifest> = OnceLock::new();
+    EMBEDDED.get_or_init(|| {
+        FormatsManifest::parse(EMBEDDED_MANIFEST).expect("the embedded formats manifest is valid")
+    })
+}
+
+struct LoadedManifest {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    manifest: Arc<FormatsManifest>,
+}
+
+static LOADED: OnceLock<Mutex<Option<LoadedManifest>>> = OnceLock::new();
+
+/// The manifest in effect for an app folder, re-read when the override changed.
+pub async fn current(app_folder: &Path) -> Arc<FormatsManifest> {
+    let path = app_folder.join(FORMATS_MANIFEST_FILE);
+    let modified = tokio::fs::metadata(&path)
+        .await
+        .and_then(|metadata| metadata.modified())
+        .ok();
+
+    let mut loaded = LOADED.get_or_init(|| Mutex::new(None)).lock().await;
+    if let Some(loaded) = loaded.as_ref()
+        && loaded.path == path
+        && loaded.modified == modified
+    {
+        return Arc::clone(&loaded.manifest);
+    }
+
+    let manifest = match modified {
+        Some(_) => match read_override(&path).await {
+            Ok(manifest) => {
+                log::info!(target: "formats", "loaded formats manifest {}", path.display());
+                manifest
+            }
+            Err(error) => {
+                log::warn!(
+                    target: "formats",
+                    "ignoring formats manifest {}: {error:#}",
+                    path.display()
+                );
+                embedded().clone()
+            }
+        },
+        None => embedded().clone(),
+    };
+    let manifest = Arc::new(manifest);
+    *loaded = Some(LoadedManifest {
+        path,
+        modified,
+        manifest: Arc::clone(&manifest),
+    });
+    manifest
+}
+
+async fn read_override(path: &Path) -> Result<FormatsManifest> {
+    let json = tokio::fs::read_to_string(path).await?;
+    let mut manifest = FormatsManifest::parse(&json)?;
+    manifest.overridden = true;
+    Ok(manifest)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn embedded_manifest_applies_until_the_app_folder_overrides_it() {
+        let manifest = embedded();
+        assert!(manifest.is_bilingual("SDLXLIFF"));
+        assert!(manifest.is_convertible(".docx"));
+        assert_eq!(manifest.filter_for("odt"), Some("opendocument"));
+        assert_eq!(manifest.default_role("png"), FormatRole::Image);
+        assert_eq!(manifest.default_role("pdf"), FormatRole::Reference);
+        assert_eq!(manifest.default_role("zip"), FormatRole::Reference);
+
+        let dir = tempfile::tempdir().unwrap();
+        assert!(!current(dir.path()).await.overridden);
+
+        let path = dir.path().join(FORMATS_MANIFEST_FILE);
+        std::fs::write(
+            &path,
+            r#"{"version": 1, "formats": [{"id": "po", "label": "PO file", "group": "Software",
+                "extensions": [".PO"], "role": "processable", "filter": "po"}]}"#,
+        )
+        .unwrap();
+        let reloaded = current(dir.path()).await;
+        assert!(reloaded.overridden);
+        assert!(reloaded.is_convertible("po"));
+        assert!(!reloaded.is_convertible("docx"));
+
+        // A broken edit falls back to the embedded manifest.
+        std::fs::write(&path, r#"{"version": 2, "formats": []}"#).unwrap();
+        let later = SystemTime::now() + Duration::from_secs(5);
+        std::fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(later)
+            .unwrap();
+        assert!(!current(dir.path()).await.overridden);
+
+        assert!(
+            FormatsManifest::parse(
+                r#"{"version": 1, "formats": [{"id": "pdf", "label": "PDF", "group": "Other",
+                    "extensions": ["pdf"], "role": "reference", "bilingual": true}]}"#
+            )
+            .is_err()
+        );
+    }
+}
@@ -0,0 +1,210 @@
+//! Read-only guest mode for external reviewers.
+//!
+//! A project is exported as a review bundle: a ZIP with a `review.json` manifest and the
+//! project's JLIFF documents under `documents/`. Opening a bundle puts the app in guest mode
+//! (see `ipc::state::ReadOnlyContext`): documents are read straight from the archive, nothing
+//! touches the database, and [`enforce_read_only`] rejects every command outside
+//! [`GUEST_COMMANDS`] until the bundle is closed.
+
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use tauri::ipc::Invoke;
+use tauri::{Manager, Runtime};
+use zip::ZipArchive;
+
+use crate::delivery::{PackageEntry, PackageSource};
+use crate::ipc::state::ReadOnlyContext;
+use crate::jliff::JliffDocument;
+
+/// Extension of review bundle files.
+pub const REVIEW_BUNDLE_EXTENSION: &str = "wegreview";
+pub const MANIFEST_NAME: &str = "review.json";
+const DOCUMENTS_FOLDER: &str = "documents";
+/// Manifest format written by this version; newer bundles are refused.
+pub const FORMAT_VERSION: u32 = 1;
+/// Largest document read from a bundle.
+const MAX_DOCUMENT_BYTES: u64 = 256 << 20;
+
+/// Commands available in guest mode: reading the open bundle, closing it, and what the shell
+/// needs to run.
+pub const GUEST_COMMANDS: &[&str] = &[
+    "open_review_bundle_v2",
+    "get_review_bundle_v2",
+    "get_review_bundle_segments_v2",
+    "close_review_bundle_v2",
+    "health_check",
+    "notify_shell_ready",
+    "get_app_settings",
+    "get_ipc_metrics_v2",
+    "get_workspace_lock_state_v2",
+    "get_offline_status_v2",
+    "report_workspace_activity_v2",
+    "lock_workspace_v2",
+    "unlock_workspace_v2",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewBundleManifest {
+    pub format_version: u32,
+    pub project_uuid: String,
+    pub project_name: String,
+    #[serde(default)]
+    pub project_number: Option<String>,
+    pub exported_at: String,
+    pub documents: Vec<BundledDocument>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundledDocument {
+    /// Path of the document in the project, with `/` separators.
+    pub jliff_rel_path: String,
+    pub source_lang: String,
+    pub target_lang: String,
+    pub segments: usize,
+}
+
+impl ReviewBundleManifest {
+    pub fn document(&self, jliff_rel_path: &str) -> Option<&BundledDocument> {
+        self.documents
+            .iter()
+            .find(|document| document.jliff_rel_path == jliff_rel_path)
+    }
+}
+
+/// Archive entries of a bundle: the manifest, then every document read from `project_root`.
+pub fn bundle_entries(
+    manifest: &ReviewBundleManifest,
+    project_root: &Path,
+) -> Result<Vec<PackageEntry>> {
+    let mut entries = vec![PackageEntry {
+        name: MANIFEST_NAME.into(),
+        source: PackageSource::Contents(
+            serde_json::to_vec_pretty(manifest).context("the manifest could not be written")?,
+        ),
+    }];
+    entries.extend(manifest.documents.iter().map(|document| PackageEntry {
+        name: document_entry_name(&document.jliff_rel_path),
+        source: PackageSource::File(project_root.join(&document.jliff_rel_path)),
+    }));
+    Ok(entries)
+}
+
+/// Reads and checks the manifest of the bundle at `path`.
+pub fn read_manifest(path: &Path) -> Result<ReviewBundleManifest> {
+    let bytes = read_entry(path, MANIFEST_NAME)?;
+    let manifest: ReviewBundleManifest =
+        serde_json::from_slice(&bytes).context("the bundle manifest is not valid")?;
+    if manifest.format_version > FORMAT_VERSION {
+        bail!(
+            "the bundle was written by a newer version of the app (format {})",
+            manifest.format_version
+        );
+    }
+    Ok(manifest)
+}
+
+/// Reads a document listed in the manifest from the bundle at `path`.
+pub fn read_document(path: &Path, jliff_rel_path: &str) -> Result<JliffDocument> {
+    let bytes = read_entry(path, &document_entry_name(jliff_rel_path))?;
+    serde_json::from_slice(&bytes)
+        .with_context(|| format!("'{jliff_rel_path}' is not a valid JLIFF document"))
+}
+
+pub fn allowed_in_guest_mode(command: &str) -> bool {
+    GUEST_COMMANDS.contains(&command)
+}
+
+/// Wraps the invoke handler so that, while a review bundle is open, commands outside
+/// [`GUEST_COMMANDS`] are rejected before they run.
+pub fn enforce_read_only<R, H>(handler: H) -> impl Fn(Invoke<R>) -> bool + Send + Sync + 'static
+where
+    R: Runtime,
+    H: Fn(Invoke<R>) -> bool + Send + Sync + 'static,
+{
+    move |invoke| {
+        let command = invoke.message.command();
+        let blocked = !allowed_in_guest_mode(command)
+            && invoke
+                .message
+                .webview_ref()
+                .try_state::<ReadOnlyContext>()
+                .is_some_and(|context| context.is_active());
+        if blocked {
+            let message = format!(
+                "'{command}' is not available while a review bundle is open. Close the bundle \
+                 to work on your own projects."
+            );
+            log::warn!(target: "guest", "rejected {command} in read-only guest mode");
+            invoke.resolver.reject(message);
+            return true;
+        }
+        handler(invoke)
+    }
+}
+
+fn document_entry_name(jliff_rel_path: &str) -> String {
+    format!("{DOCUMENTS_FOLDER}/{}", jliff_rel_path.replace('\\', "/"))
+}
+
+fn read_entry(path: &Path, name: &str) -> Result<Vec<u8>> {
+    let file =
+        File::open(path).with_context(|| format!("{} could not be opened", path.display()))?;
+    let mut archive = ZipArchive::new(file).context("the file is not a review bundle")?;
+    let entry = archive
+        .by_name(name)
+        .with_context(|| format!("the bundle has no '{name}'"))?;
+    if entry.size() > MAX_DOCUMENT_BYTES {
+        bail!("'{name}' is too large to open");
+    }
+    let mut bytes = Vec::with_capacity(entry.size() as usize);
+    entry
+        .take(MAX_DOCUMENT_BYTES)
+        .read_to_end(&mut bytes)
+        .with_context(|| format!("'{name}' could not be read"))?;
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::delivery::write_package;
+
+    #[test]
+    fn bundle_manifest_round_trips_and_commands_are_restricted() {
+        let dir = tempfile::tempdir().unwrap();
+        let manifest = ReviewBundleManifest {
+            format_version: FORMAT_VERSION,
+            project_uuid: "6f1c2d4e-0000-4000-8000-000000000001".into(),
+            project_name: "Manual".into(),
+            project_number: Some("P-7".into()),
+            exported_at: "2026-10-15T09:00:00Z".into(),
+            documents: vec![BundledDocument {
+                jliff_rel_path: "Translations/en-de/manual.jliff.json".into(),
+                source_lang: "en".into(),
+                target_lang: "de".into(),
+                segments: 2,
+            }],
+        };
+        let document = dir.path().join("Translations/en-de/manual.jliff.json");
+        std::fs::create_dir_all(document.parent().unwrap()).unwrap();
+        std::fs::write(&document, b"{}").unwrap();
+
+        let bundle = dir.path().join(format!("manual.{REVIEW_BUNDLE_EXTENSION}"));
+        write_package(&bundle, &bundle_entries(&manifest, dir.path()).unwrap()).unwrap();
+        assert_eq!(read_manifest(&bundle).unwrap(), manifest);
+        assert_eq!(
+            read_entry(&bundle, "documents/Translations/en-de/manual.jliff.json").unwrap(),
+            b"{}"
+        );
+        assert!(read_document(&bundle, "Translations/en-de/other.jliff.json").is_err());
+
+        assert!(allowed_in_guest_mode("get_review_bundle_segments_v2"));
+        assert!(!allowed_in_guest_mode("confirm_segment_v2"));
+    }
+}
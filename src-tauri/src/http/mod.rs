@@ -0,0 +1,163 @@
+//! Construction of outbound HTTP clients. Every integration builds its client here so the
+//! proxy settings apply to all of them.
+//!
+//! The proxy is process-wide: [`set_proxy`] replaces it, and each [`HttpClient`] rebuilds its
+//! client on next use. Without a configured proxy, reqwest's defaults apply, including the
+//! `HTTP_PROXY`/`HTTPS_PROXY` environment variables.
+
+use std::sync::{Mutex, RwLock};
+
+use anyhow::{Result, bail};
+use reqwest::{Client, ClientBuilder, NoProxy, Proxy, Url};
+
+/// Name of the proxy password in the secrets vault.
+pub const PROXY_PASSWORD_SECRET: &str = "proxy-password";
+
+/// Proxy all outbound requests go through, except to the hosts in `no_proxy`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProxyConfig {
+    pub url: String,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    /// Hosts, domains (`.example.com`) or CIDR ranges reached directly.
+    pub no_proxy: Vec<String>,
+}
+
+struct ProxyState {
+    /// Incremented on every change, so clients built before it can be told apart.
+    generation: u64,
+    config: Option<ProxyConfig>,
+}
+
+static PROXY: RwLock<ProxyState> = RwLock::new(ProxyState {
+    generation: 0,
+    config: None,
+});
+
+/// Replaces the proxy; `None` restores direct connections.
+pub fn set_proxy(config: Option<ProxyConfig>) {
+    let mut state = PROXY
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    state.generation += 1;
+    state.config = config;
+}
+
+/// The configured proxy, if any.
+pub fn proxy() -> Option<ProxyConfig> {
+    PROXY
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .config
+        .clone()
+}
+
+fn generation() -> u64 {
+    PROXY
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .generation
+}
+
+/// Checks a proxy URL: reqwest is built without SOCKS support, so only HTTP(S) proxies work.
+pub fn validate_proxy_url(url: &str) -> Result<()> {
+    let Ok(parsed) = Url::parse(url) else {
+        bail!("'{url}' is not a valid proxy URL");
+    };
+    if !matches!(parsed.scheme(), "http" | "https") || parsed.host_str().is_none() {
+        bail!("'{url}' is not an http(s) proxy URL such as http://proxy.example.com:8080");
+    }
+    Ok(())
+}
+
+fn build_proxy(config: &ProxyConfig) -> Result<Proxy> {
+    let mut proxy = Proxy::all(&config.url)?;
+    if let Some(username) = &config.username {
+        proxy = proxy.basic_auth(username, config.password.as_deref().unwrap_or_default());
+    }
+    Ok(proxy.no_proxy(NoProxy::from_string(&config.no_proxy.join(","))))
+}
+
+/// A client builder with the current proxy applied.
+pub fn client_builder() -> ClientBuilder {
+    let builder = Client::builder();
+    let Some(config) = proxy() else {
+        return builder;
+    };
+    match build_proxy(&config) {
+        Ok(proxy) => builder.proxy(proxy),
+        Err(error) => {
+            log::warn!(target: "http", "ignoring proxy {}: {error:#}", config.url);
+            builder
+        }
+    }
+}
+
+/// A client kept by a long-lived service, rebuilt when the proxy changes.
+pub struct HttpClient {
+    configure: fn(ClientBuilder) -> ClientBuilder,
+    cached: Mutex<Option<(u64, Client)>>,
+}
+
+impl HttpClient {
+    /// `configure` sets the timeouts, user agent and the like on top of the proxy.
+    pub fn new(configure: fn(ClientBuilder) -> ClientBuilder) -> Self {
+        Self {
+            configure,
+            cached: Mutex::new(None),
+        }
+    }
+
+    /// The client for the current proxy settings.
+    pub fn current(&self) -> Client {
+        let generation = generation();
+        let mut cached = self
+            .cached
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some((built_for, client)) = cached.as_ref()
+            && *built_for == generation
+        {
+            return client.clone();
+        }
+        let client = (self.configure)(client_builder())
+            .build()
+            .unwrap_or_else(|error| {
+                log::warn!(target: "http", "failed to construct HTTP client: {error}");
+                Client::new()
+            });
+        *cached = Some((generation, client.clone()));
+        client
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn proxy_urls_must_be_http() {
+        assert!(validate_proxy_url("http://proxy.example.com:8080").is_ok());
+        assert!(validate_proxy_url("https://user@proxy.example.com").is_ok());
+        assert!(validate_proxy_url("socks5://proxy.example.com:1080").is_err());
+        assert!(validate_proxy_url("proxy.example.com:8080").is_err());
+    }
+
+    #[test]
+    fn clients_are_rebuilt_when_the_proxy_changes() {
+        let client = HttpClient::new(|builder| builder);
+        client.current();
+        let (built_for, _) = client.cached.lock().unwrap().clone().unwrap();
+
+        set_proxy(Some(ProxyConfig {
+            url: "http://proxy.example.com:8080".into(),
+            username: Some("translator".into()),
+            password: Some("secret".into()),
+            no_proxy: vec!["localhost".into(), ".internal.example.com".into()],
+        }));
+        client.current();
+        let (rebuilt_for, _) = client.cached.lock().unwrap().clone().unwrap();
+        assert!(rebuilt_for > built_for);
+        set_proxy(None);
+    }
+}
@@ -0,0 +1,314 @@
+//! Safe extraction of ZIP archives clients send their files in.
+//!
+//! Entries are listed from the central directory before anything is written: names that
+//! would land outside the destination (`../`, absolute paths), symlinks and encrypted entries
+//! are rejected, and declared sizes are checked against [`ArchiveLimits`]. Extraction counts
+//! the bytes actually inflated, so an archive understating its sizes stops at the limits as
+//! well. Folder metadata left by macOS and Windows (`__MACOSX`, `.DS_Store`, `Thumbs.db`) is
+//! skipped.
+
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+use zip::ZipArchive;
+
+const IGNORED_FOLDERS: &[&str] = &["__MACOSX"];
+const IGNORED_FILES: &[&str] = &[".DS_Store", "Thumbs.db", "desktop.ini"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArchiveLimits {
+    pub max_entries: usize,
+    /// Uncompressed size of one file.
+    pub max_entry_bytes: u64,
+    /// Uncompressed size of all files together.
+    pub max_total_bytes: u64,
+}
+
+impl Default for ArchiveLimits {
+    fn default() -> Self {
+        Self {
+            max_entries: 10_000,
+            max_entry_bytes: 1 << 30,
+            max_total_bytes: 4 << 30,
+        }
+    }
+}
+
+/// A file of an archive, with its path inside the archive made safe to join onto a folder.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArchiveEntry {
+    index: usize,
+    pub relative_path: PathBuf,
+    /// Uncompressed size declared by the archive.
+    pub size: u64,
+}
+
+/// Lists the files of the archive at `path`, failing on the first unsafe entry.
+pub fn list_entries(path: &Path, limits: &ArchiveLimits) -> Result<Vec<ArchiveEntry>> {
+    let mut archive = open(path)?;
+    let mut entries = Vec::new();
+    let mut seen = HashSet::new();
+    let mut total: u64 = 0;
+    for index in 0..archive.len() {
+        let entry = archive
+            .by_index_raw(index)
+            .with_context(|| format!("entry {} of the archive could not be read", index + 1))?;
+        if entry.is_dir() {
+            continue;
+        }
+        let name = entry.name().to_string();
+        let Some(relative_path) = entry.enclosed_name() else {
+            bail!("'{name}' points outside the archive folder.");
+        };
+        if is_ignored(&relative_path) {
+            continue;
+        }
+        if entry.is_symlink() {
+            bail!("'{name}' is a symbolic link.");
+        }
+        if entry.encrypted() {
+            bail!("'{name}' is encrypted.");
+        }
+        if entry.size() > limits.max_entry_bytes {
+            bail!(
+                "'{name}' exceeds the limit of {} MB per file.",
+                limits.max_entry_bytes >> 20
+            );
+        }
+        total = total.saturating_add(entry.size());
+        if total > limits.max_total_bytes {
+            bail!(
+                "The archive exceeds the limit of {} MB in total.",
+                limits.max_total_bytes >> 20
+            );
+        }
+        // Two names differing only in case collide on Windows and macOS file systems.
+        if !seen.insert(relative_path.to_string_lossy().to_lowercase()) {
+            bail!("'{name}' appears twice in the archive.");
+        }
+        entries.push(ArchiveEntry {
+            index,
+            relative_path,
+            size: entry.size(),
+        });
+        if entries.len() > limits.max_entries {
+            bail!("The archive holds more than {} files.", limits.max_entries);
+        }
+    }
+    Ok(entries)
+}
+
+fn is_ignored(relative_path: &Path) -> bool {
+    let in_ignored_folder = relative_path.iter().any(|component| {
+        IGNORED_FOLDERS
+            .iter()
+            .any(|folder| component.eq_ignore_ascii_case(folder))
+    });
+    let file_name = relative_path
+        .file_name()
+        .map(|name| name.to_string_lossy())
+        .unwrap_or_default();
+    in_ignored_folder
+        || file_name.starts_with("._")
+        || IGNORED_FILES
+            .iter()
+            .any(|ignored| file_name.eq_ignore_ascii_case(ignored))
+}
+
+/// Writes each entry to its destination, creating parent folders and never overwriting an
+/// existing file. Files and folders written are removed again when an entry fails.
+pub fn extract_entries(
+    path: &Path,
+    targets: &[(ArchiveEntry, PathBuf)],
+    limits: &ArchiveLimits,
+) -> Result<()> {
+    let mut archive = open(path)?;
+    let mut created = Created::default();
+    let mut total: u64 = 0;
+    for (entry, destination) in targets {
+        if let Err(error) = extract_entry(
+            &mut archive,
+            entry,
+            destination,
+            limits,
+            &mut total,
+            &mut created,
+        ) {
+            created.remove();
+            return Err(error);
+        }
+    }
+    Ok(())
+}
+
+/// Reads one entry into memory, failing when it inflates beyond `max_bytes`.
+pub fn read_entry(path: &Path, entry: &ArchiveEntry, max_bytes: u64) -> Result<Vec<u8>> {
+    let mut archive = open(path)?;
+    let display = entry.relative_path.display();
+    let file = archive
+        .by_index(entry.index)
+        .with_context(|| format!("'{display}' could not be read"))?;
+    let mut data = Vec::new();
+    file.take(max_bytes + 1)
+        .read_to_end(&mut data)
+        .with_context(|| format!("'{display}' could not be extracted"))?;
+    if data.len() as u64 > max_bytes {
+        bail!("'{display}' exceeds the limit of {} MB.", max_bytes >> 20);
+    }
+    Ok(data)
+}
+
+/// Files and folders an extraction created, in creation order.
+#[derive(Default)]
+struct Created {
+    files: Vec<PathBuf>,
+    folders: Vec<PathBuf>,
+}
+
+impl Created {
+    fn remove(self) {
+        for file in &self.files {
+            if let Err(error) = fs::remove_file(file) {
+                log::warn!(
+                    target: "import::archive",
+                    "failed to remove extracted file '{}': {error}",
+                    file.display()
+                );
+            }
+        }
+        for folder in self.folders.iter().rev() {
+            let _ = fs::remove_dir(folder);
+        }
+    }
+}
+
+fn extract_entry(
+    archive: &mut ZipArchive<File>,
+    entry: &ArchiveEntry,
+    destination: &Path,
+    limits: &ArchiveLimits,
+    total: &mut u64,
+    created: &mut Created,
+) -> Result<()> {
+    let display = entry.relative_path.display();
+    if let Some(parent) = destination.parent() {
+        let mut missing: Vec<PathBuf> = parent
+            .ancestors()
+            .take_while(|folder| !folder.exists())
+            .map(Path::to_path_buf)
+            .collect();
+        fs::create_dir_all(parent)
+            .with_context(|| format!("the folder for '{display}' could not be created"))?;
+        missing.reverse();
+        created.folders.extend(missing);
+    }
+    let mut output = File::create_new(destination)
+        .with_context(|| format!("'{}' could not be created", destination.display()))?;
+    created.files.push(destination.to_path_buf());
+    let file = archive
+        .by_index(entry.index)
+        .with_context(|| format!("'{display}' could not be read"))?;
+    let allowed = limits
+        .max_entry_bytes
+        .min(limits.max_total_bytes.saturating_sub(*total));
+    let copied = io::copy(&mut file.take(allowed + 1), &mut output)
+        .with_context(|| format!("'{display}' could not be extracted"))?;
+    if copied > allowed {
+        bail!("'{display}' inflates beyond the size limits.");
+    }
+    *total += copied;
+    Ok(())
+}
+
+fn open(path: &Path) -> Result<ZipArchive<File>> {
+    let file =
+        File::open(path).with_context(|| format!("{} could not be opened", path.display()))?;
+    ZipArchive::new(file).context("the file is not a ZIP archive")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use zip::write::{SimpleFileOptions, ZipWriter};
+
+    fn write_archive(path: &Path, files: &[(&str, &str)]) {
+        let mut writer = ZipWriter::new(File::create(path).unwrap());
+        for (name, data) in files {
+            writer
+                .start_file(*name, SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(data.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap();
+    }
+
+    #[test]
+    fn lists_and_extracts_files_keeping_their_folders() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("package.zip");
+        write_archive(
+            &archive,
+            &[
+                ("source/manual.docx", "manual"),
+                ("source/img/logo.png", "logo"),
+                ("__MACOSX/source/._manual.docx", "junk"),
+                ("source/.DS_Store", "junk"),
+            ],
+        );
+        let entries = list_entries(&archive, &ArchiveLimits::default()).unwrap();
+        let paths: Vec<PathBuf> = entries
+            .iter()
+            .map(|entry| entry.relative_path.clone())
+            .collect();
+        assert_eq!(
+            paths,
+            vec![
+                PathBuf::from("source/manual.docx"),
+                PathBuf::from("source/img/logo.png")
+            ]
+        );
+
+        let out = dir.path().join("out");
+        let targets: Vec<(ArchiveEntry, PathBuf)> = entries
+            .into_iter()
+            .map(|entry| {
+                let destination = out.join(&entry.relative_path);
+                (entry, destination)
+            })
+            .collect();
+        extract_entries(&archive, &targets, &ArchiveLimits::default()).unwrap();
+        assert_eq!(
+            fs::read(out.join("source/img/logo.png")).unwrap(),
+            b"logo".to_vec()
+        );
+
+        // Nothing is overwritten, and a failed extraction leaves no files behind.
+        fs::remove_file(out.join("source/img/logo.png")).unwrap();
+        assert!(extract_entries(&archive, &targets, &ArchiveLimits::default()).is_err());
+        assert!(!out.join("source/img/logo.png").exists());
+        assert!(out.join("source/manual.docx").exists());
+    }
+
+    #[test]
+    fn rejects_unsafe_and_oversized_archives() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("slip.zip");
+        write_archive(&archive, &[("../../evil.sh", "echo")]);
+        assert!(list_entries(&archive, &ArchiveLimits::default()).is_err());
+
+        let archive = dir.path().join("big.zip");
+        write_archive(&archive, &[("a.txt", "aaaa"), ("A.TXT", "b")]);
+        let error = list_entries(&archive, &ArchiveLimits::default()).unwrap_err();
+        assert!(error.to_string().contains("appears twice"));
+
+        let limits = ArchiveLimits {
+            max_entry_bytes: 3,
+            ..ArchiveLimits::default()
+        };
+        assert!(list_entries(&archive, &limits).is_err());
+    }
+}
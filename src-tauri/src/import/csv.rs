@@ -0,0 +1,102 @@
+//! Minimal CSV reader for spreadsheet exports.
+//!
+//! Handles what Excel and LibreOffice write: a UTF-8 byte order mark, comma, semicolon or
+//! tab delimiters, and double-quoted fields with escaped quotes and embedded line breaks.
+
+use anyhow::{Result, bail};
+
+/// A CSV file split into its header and data rows.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvTable {
+    pub header: Vec<String>,
+    /// Data rows, without blank ones.
+    pub rows: Vec<CsvRow>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvRow {
+    /// Position of the record in the file as a spreadsheet shows it; the header is row 1.
+    pub number: usize,
+    pub cells: Vec<String>,
+}
+
+impl CsvTable {
+    /// Parses CSV text, taking the delimiter (comma, semicolon or tab) from the header line.
+    pub fn parse(text: &str) -> Result<Self> {
+        let text = text.trim_start_matches('\u{feff}');
+        let header_line = text.lines().next().unwrap_or_default();
+        let delimiter = [',', ';', '\t']
+            .into_iter()
+            .max_by_key(|candidate| header_line.matches(*candidate).count())
+            .unwrap_or(',');
+
+        let mut records = csv_records(text, delimiter).into_iter();
+        let Some(header) = records.next() else {
+            bail!("The CSV file is empty.");
+        };
+        let rows = records
+            .enumerate()
+            .map(|(index, cells)| CsvRow {
+                number: index + 2,
+                cells,
+            })
+            .filter(|row| row.cells.iter().any(|cell| !cell.trim().is_empty()))
+            .collect();
+        Ok(Self { header, rows })
+    }
+
+    /// Index of the header cell equal to `name`, ignoring case and surrounding spaces.
+    pub fn column(&self, name: &str) -> Option<usize> {
+        self.header
+            .iter()
+            .position(|cell| cell.trim().eq_ignore_ascii_case(name.trim()))
+    }
+}
+
+impl CsvRow {
+    /// Trimmed cell at `index`; empty when the row is shorter than the header.
+    pub fn cell(&self, index: usize) -> &str {
+        self.cells
+            .get(index)
+            .map(|cell| cell.trim())
+            .unwrap_or_default()
+    }
+}
+
+/// Splits CSV text into records, honouring double-quoted fields with escaped quotes and
+/// embedded line breaks.
+fn csv_records(text: &str, delimiter: char) -> Vec<Vec<String>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if quoted {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    field.push('"');
+                    chars.next();
+                }
+                '"' => quoted = false,
+                _ => field.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' => quoted = true,
+            '\r' => {}
+            '\n' => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            _ if c == delimiter => record.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    records
+}
@@ -0,0 +1,454 @@
+//! Bulk onboarding of clients and users from spreadsheet CSV exports.
+//!
+//! An import is planned before anything is written: each row is mapped to a record through
+//! the column mapping, validated, and checked for duplicates against the stored records and
+//! the rows above it. The plan holds the records to create and an issue for every row left
+//! out, so a dry run reports exactly what the real import would do.
+
+pub mod archive;
+pub mod csv;
+pub mod roles;
+
+use std::collections::{BTreeMap, HashMap};
+
+use anyhow::{Result, bail};
+use uuid::Uuid;
+
+use self::csv::{CsvRow, CsvTable};
+use crate::currency::normalize_currency;
+use crate::db::search::search_key;
+use crate::db::types::{
+    ClientRecord, NewClientArgs, NewUserArgs, PostalAddress, UserRecord, VatValidation,
+};
+use crate::db::utils::now_iso8601;
+use crate::tax::check_tax_id;
+
+/// Fields a client import can fill; `name` is required.
+pub const CLIENT_FIELDS: [&str; 7] = [
+    "name",
+    "email",
+    "phone",
+    "address",
+    "vatNumber",
+    "note",
+    "currency",
+];
+/// Fields a user import can fill; `username` and `email` are required. `roles` holds role
+/// names separated by commas, semicolons or `|`.
+pub const USER_FIELDS: [&str; 5] = ["username", "email", "phone", "address", "roles"];
+
+/// Why a row was left out of an import.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RowIssue {
+    pub row: usize,
+    pub field: Option<&'static str>,
+    pub message: String,
+    /// Whether the row repeats a stored record or an earlier row, rather than being invalid.
+    pub duplicate: bool,
+    /// Stored record the row duplicates.
+    pub duplicate_of: Option<Uuid>,
+}
+
+/// Records an import would create, and the rows it leaves out.
+#[derive(Debug, Clone)]
+pub struct ImportPlan<T> {
+    pub records: Vec<T>,
+    pub issues: Vec<RowIssue>,
+}
+
+/// Plans a client import. Rows duplicate a client when its email or its name (ignoring case
+/// and accents) is already taken.
+pub fn plan_client_import(
+    table: &CsvTable,
+    mapping: &BTreeMap<String, String>,
+    existing: &[ClientRecord],
+) -> Result<ImportPlan<NewClientArgs>> {
+    let columns = Columns::resolve(table, &CLIENT_FIELDS, &["name"], mapping)?;
+    let mut emails = SeenKeys::default();
+    let mut names = SeenKeys::default();
+    for client in existing {
+        if let Some(email) = &client.email {
+            emails.insert_existing(email_key(email), client.client_uuid);
+        }
+        names.insert_existing(search_key(&client.name), client.client_uuid);
+    }
+
+    let mut plan = ImportPlan {
+        records: Vec::new(),
+        issues: Vec::new(),
+    };
+    for row in &table.rows {
+        let Some(name) = columns.value(row, "name") else {
+            plan.issues
+                .push(invalid(row, "name", "The name is missing."));
+            continue;
+        };
+        let email = columns.value(row, "email");
+        if let Some(email) = &email
+            && !is_valid_email(email)
+        {
+            plan.issues.push(invalid(
+                row,
+                "email",
+                &format!("'{email}' is not an email address."),
+            ));
+            continue;
+        }
+        let vat_check = match columns
+            .value(row, "vatNumber")
+            .map(|raw| check_tax_id(&raw))
+        {
+            Some(Err(error)) => {
+                plan.issues
+                    .push(invalid(row, "vatNumber", &error.to_string()));
+                continue;
+            }
+            Some(Ok(check)) => Some(check),
+            None => None,
+        };
+        let currency = match columns
+            .value(row, "currency")
+            .map(|raw| normalize_currency(&raw))
+        {
+            Some(Err(error)) => {
+                plan.issues
+                    .push(invalid(row, "currency", &error.to_string()));
+                continue;
+            }
+            Some(Ok(currency)) => Some(currency),
+            None => None,
+        };
+
+        let email_key = email.as_deref().map(email_key);
+        let name_key = search_key(&name);
+        if let Some(issue) = email_key
+            .as_ref()
+            .and_then(|key| emails.duplicate(row, "email", key, "client"))
+            .or_else(|| names.duplicate(row, "name", &name_key, "client"))
+        {
+            plan.issues.push(issue);
+            continue;
+        }
+        if let Some(key) = email_key {
+            emails.insert_row(key, row.number);
+        }
+        names.insert_row(name_key, row.number);
+
+        plan.records.push(NewClientArgs {
+            client_uuid: Uuid::new_v4(),
+            name,
+            email,
+            phone: columns.value(row, "phone"),
+            address: columns.value(row, "address"),
+            vat_validation: vat_check
+                .as_ref()
+                .map(|check| VatValidation {
+                    status: Some(check.status.as_str().to_string()),
+                    checked_at: Some(now_iso8601()),
+                })
+                .unwrap_or_default(),
+            vat_number: vat_check.map(|check| check.normalized),
+            note: columns.value(row, "note"),
+            currency,
+            postal_address: PostalAddress::default(),
+        });
+    }
+    Ok(plan)
+}
+
+/// Plans a user import. Rows duplicate a user when its email or its username (ignoring
+/// case) is already taken.
+pub fn plan_user_import(
+    table: &CsvTable,
+    mapping: &BTreeMap<String, String>,
+    existing: &[UserRecord],
+) -> Result<ImportPlan<NewUserArgs>> {
+    let columns = Columns::resolve(table, &USER_FIELDS, &["username", "email"], mapping)?;
+    let mut emails = SeenKeys::default();
+    let mut usernames = SeenKeys::default();
+    for user in existing {
+        emails.insert_existing(email_key(&user.email), user.user_uuid);
+        usernames.insert_existing(user.username.trim().to_lowercase(), user.user_uuid);
+    }
+
+    let mut plan = ImportPlan {
+        records: Vec::new(),
+        issues: Vec::new(),
+    };
+    for row in &table.rows {
+        let Some(username) = columns.value(row, "username") else {
+            plan.issues
+                .push(invalid(row, "username", "The username is missing."));
+            continue;
+        };
+        let Some(email) = columns.value(row, "email") else {
+            plan.issues
+                .push(invalid(row, "email", "The email is missing."));
+            continue;
+        };
+        if !is_valid_email(&email) {
+            plan.issues.push(invalid(
+                row,
+                "email",
+                &format!("'{email}' is not an email address."),
+            ));
+            continue;
+        }
+
+        let email_key = email_key(&email);
+        let username_key = username.to_lowercase();
+        if let Some(issue) = emails
+            .duplicate(row, "email", &email_key, "user")
+            .or_else(|| usernames.duplicate(row, "username", &username_key, "user"))
+        {
+            plan.issues.push(issue);
+            continue;
+        }
+        emails.insert_row(email_key, row.number);
+        usernames.insert_row(username_key, row.number);
+
+        let mut roles: Vec<String> = Vec::new();
+        for role in columns
+            .value(row, "roles")
+            .unwrap_or_default()
+            .split([',', ';', '|'])
+            .map(|role| role.trim().to_lowercase())
+            .filter(|role| !role.is_empty())
+        {
+            if !roles.contains(&role) {
+                roles.push(role);
+            }
+        }
+
+        plan.records.push(NewUserArgs {
+            user_uuid: Uuid::new_v4(),
+            username,
+            email,
+            phone: columns.value(row, "phone"),
+            address: columns.value(row, "address"),
+            roles,
+            permission_overrides: Vec::new(),
+        });
+    }
+    Ok(plan)
+}
+
+/// Where each field is read from.
+struct Columns {
+    indices: HashMap<&'static str, usize>,
+}
+
+impl Columns {
+    /// A field is read from the header named in `mapping`, otherwise from the header named
+    /// like the field. Headers match ignoring case, spaces, `_` and `-`, so `vatNumber` is
+    /// found under "VAT number" or "vat_number".
+    fn resolve(
+        table: &CsvTable,
+        fields: &[&'static str],
+        required: &[&'static str],
+        mapping: &BTreeMap<String, String>,
+    ) -> Result<Self> {
+        if let Some(unknown) = mapping
+            .keys()
+            .find(|field| !fields.contains(&field.as_str()))
+        {
+            bail!(
+                "Unknown field '{unknown}' in the column mapping. Use {}.",
+                fields.join(", ")
+            );
+        }
+        let headers: Vec<String> = table
+            .header
+            .iter()
+            .map(|header| header_key(header))
+            .collect();
+
+        let mut indices = HashMap::new();
+        for field in fields {
+            let mapped = mapping.get(*field);
+            let wanted = header_key(mapped.map(String::as_str).unwrap_or(field));
+            match headers.iter().position(|header| *header == wanted) {
+                Some(index) => {
+                    indices.insert(*field, index);
+                }
+                None if mapped.is_some() => {
+                    bail!(
+                        "The CSV file has no '{}' column for {field}.",
+                        mapped.map(String::as_str).unwrap_or_default()
+                    );
+                }
+                None if required.contains(field) => {
+                    bail!("The CSV file has no column for {field}; map one in the column mapping.");
+                }
+                None => {}
+            }
+        }
+        Ok(Self { indices })
+    }
+
+    /// Trimmed value of `field` in `row`, `None` when the column is absent or the cell empty.
+    fn value(&self, row: &CsvRow, field: &str) -> Option<String> {
+        let cell = row.cell(*self.indices.get(field)?);
+        (!cell.is_empty()).then(|| cell.to_string())
+    }
+}
+
+/// First holder of each key: a stored record or a row of the file.
+#[derive(Default)]
+struct SeenKeys {
+    holders: HashMap<String, Holder>,
+}
+
+enum Holder {
+    Existing(Uuid),
+    Row(usize),
+}
+
+impl SeenKeys {
+    fn insert_existing(&mut self, key: String, uuid: Uuid) {
+        self.holders.entry(key).or_insert(Holder::Existing(uuid));
+    }
+
+    fn insert_row(&mut self, key: String, row: usize) {
+        self.holders.entry(key).or_insert(Holder::Row(row));
+    }
+
+    fn duplicate(
+        &self,
+        row: &CsvRow,
+        field: &'static str,
+        key: &str,
+        entity: &str,
+    ) -> Option<RowIssue> {
+        let (message, duplicate_of) = match self.holders.get(key)? {
+            Holder::Existing(uuid) => (
+                format!("A {entity} with the same {field} already exists."),
+                Some(*uuid),
+            ),
+            Holder::Row(number) => (format!("Row {number} has the same {field}."), None),
+        };
+        Some(RowIssue {
+            row: row.number,
+            field: Some(field),
+            message,
+            duplicate: true,
+            duplicate_of,
+        })
+    }
+}
+
+fn invalid(row: &CsvRow, field: &'static str, message: &str) -> RowIssue {
+    RowIssue {
+        row: row.number,
+        field: Some(field),
+        message: message.to_string(),
+        duplicate: false,
+        duplicate_of: None,
+    }
+}
+
+fn header_key(header: &str) -> String {
+    header
+        .chars()
+        .filter(|c| !matches!(c, ' ' | '_' | '-'))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+fn email_key(email: &str) -> String {
+    email.trim().to_lowercase()
+}
+
+/// A single `@` between a non-empty local part and a dotted domain, without spaces.
+fn is_valid_email(value: &str) -> bool {
+    let Some((local, domain)) = value.split_once('@') else {
+        return false;
+    };
+    !local.is_empty()
+        && !domain.contains('@')
+        && domain.contains('.')
+        && !domain.starts_with('.')
+        && !domain.ends_with('.')
+        && !value.chars().any(char::is_whitespace)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plans_client_rows_and_reports_each_left_out() {
+        let table = CsvTable::parse(
+            "Company;E-mail;VAT number\n\
+             Müller GmbH;info@mueller.de;DE136695976\n\
+             Acme;sales@acme;\n\
+             ;lost@example.com;\n\
+             Nordic AB;hello@nordic.se;\n\
+             nordic ab;other@nordic.se;\n\
+             Bolt;INFO@MUELLER.DE;de 136 695 976\n\
+             Zeta;zeta@example.com;DE136695977\n",
+        )
+        .unwrap();
+        let mapping = BTreeMap::from([("name".to_string(), "Company".to_string())]);
+        let existing_uuid = Uuid::new_v4();
+        let existing = [ClientRecord {
+            client_uuid: existing_uuid,
+            name: "Muller GmbH".into(),
+            email: None,
+            phone: None,
+            address: None,
+            vat_number: None,
+            note: None,
+            currency: None,
+            postal_address: PostalAddress::default(),
+            vat_validation: VatValidation::default(),
+        }];
+
+        let plan = plan_client_import(&table, &mapping, &existing).unwrap();
+        let names: Vec<&str> = plan.records.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["Nordic AB", "Bolt"]);
+        assert_eq!(plan.records[1].email.as_deref(), Some("INFO@MUELLER.DE"));
+        assert_eq!(plan.records[1].vat_number.as_deref(), Some("DE136695976"));
+        assert_eq!(
+            plan.records[1].vat_validation.status.as_deref(),
+            Some("checksum")
+        );
+
+        let issues: Vec<(usize, Option<&str>, Option<Uuid>)> = plan
+            .issues
+            .iter()
+            .map(|issue| (issue.row, issue.field, issue.duplicate_of))
+            .collect();
+        assert_eq!(
+            issues,
+            [
+                (2, Some("name"), Some(existing_uuid)),
+                (3, Some("email"), None),
+                (4, Some("name"), None),
+                (6, Some("name"), None),
+                (8, Some("vatNumber"), None),
+            ]
+        );
+        assert_eq!(plan.issues[3].message, "Row 5 has the same name.");
+
+        let unmapped = BTreeMap::from([("fax".to_string(), "Fax".to_string())]);
+        assert!(plan_client_import(&table, &unmapped, &existing).is_err());
+        assert!(plan_client_import(&table, &BTreeMap::new(), &existing).is_err());
+    }
+
+    #[test]
+    fn reads_user_roles_and_requires_an_email() {
+        let table = CsvTable::parse(
+            "username,email,roles\n\
+             anna,anna@example.com,\"Translator, reviewer|translator\"\n\
+             ben,,admin\n",
+        )
+        .unwrap();
+        let plan = plan_user_import(&table, &BTreeMap::new(), &[]).unwrap();
+        assert_eq!(plan.records.len(), 1);
+        assert_eq!(plan.records[0].roles, ["translator", "reviewer"]);
+        assert_eq!(plan.issues.len(), 1);
+        assert_eq!(plan.issues[0].row, 3);
+        assert_eq!(plan.issues[0].field, Some("email"));
+    }
+}
@@ -0,0 +1,284 @@
+//! Rule-based classification of imported files into project asset roles.
+//!
+//! Rules are checked in order and the first one whose conditions all hold assigns the role.
+//! A rule can test the extension, the MIME type sniffed from the file's first bytes and a
+//! glob on the file name; files no rule claims keep the role their extension implies.
+
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use anyhow::{Result, bail};
+use serde::{Deserialize, Serialize};
+
+/// Most rules the settings accept.
+pub const MAX_ROLE_RULES: usize = 100;
+
+/// Role a rule assigns. OCR sources are never classified automatically.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleRole {
+    Processable,
+    Reference,
+    Instructions,
+    Image,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RoleRule {
+    /// Extensions without the dot, compared case-insensitively.
+    #[serde(default)]
+    pub extensions: Vec<String>,
+    /// MIME type such as `application/pdf`, or a whole family as `image/*`.
+    #[serde(default)]
+    pub mime: Option<String>,
+    /// Case-insensitive glob on the file name, `*` and `?` being the wildcards.
+    #[serde(default)]
+    pub pattern: Option<String>,
+    pub role: RuleRole,
+}
+
+impl RoleRule {
+    fn matches(&self, file_name: &str, extension: &str, mime: Option<&str>) -> bool {
+        if !self.extensions.is_empty()
+            && !self.extensions.iter().any(|candidate| {
+                candidate
+                    .trim_start_matches('.')
+                    .eq_ignore_ascii_case(extension)
+            })
+        {
+            return false;
+        }
+        if let Some(expected) = self.mime.as_deref() {
+            let Some(mime) = mime else {
+                return false;
+            };
+            let matched = match expected.strip_suffix("/*") {
+                Some(family) => mime
+                    .split_once('/')
+                    .is_some_and(|(prefix, _)| prefix.eq_ignore_ascii_case(family)),
+                None => mime.eq_ignore_ascii_case(expected),
+            };
+            if !matched {
+                return false;
+            }
+        }
+        if let Some(pattern) = self.pattern.as_deref()
+            && !glob_matches(&pattern.to_lowercase(), &file_name.to_lowercase())
+        {
+            return false;
+        }
+        true
+    }
+}
+
+/// Rules new installations start with: briefs and style guides are instructions.
+pub fn default_role_rules() -> Vec<RoleRule> {
+    ["*instruction*", "*brief*", "*style*guide*"]
+        .into_iter()
+        .map(|pattern| RoleRule {
+            extensions: Vec::new(),
+            mime: None,
+            pattern: Some(pattern.to_string()),
+            role: RuleRole::Instructions,
+        })
+        .collect()
+}
+
+/// Rejects rules without any condition and malformed MIME types or patterns.
+pub fn validate_role_rules(rules: &[RoleRule]) -> Result<()> {
+    if rules.len() > MAX_ROLE_RULES {
+        bail!("At most {MAX_ROLE_RULES} classification rules are supported.");
+    }
+    for (index, rule) in rules.iter().enumerate() {
+        let position = index + 1;
+        if rule.extensions.is_empty() && rule.mime.is_none() && rule.pattern.is_none() {
+            bail!("Rule {position} needs an extension, a MIME type or a file name pattern.");
+        }
+        if rule
+            .extensions
+            .iter()
+            .any(|extension| extension.trim_start_matches('.').trim().is_empty())
+        {
+            bail!("Rule {position} has an empty extension.");
+        }
+        if let Some(mime) = rule.mime.as_deref() {
+            let valid = mime
+                .split_once('/')
+                .is_some_and(|(kind, subtype)| !kind.is_empty() && !subtype.is_empty());
+            if !valid {
+                bail!("Rule {position} has an invalid MIME type '{mime}'.");
+            }
+        }
+        if rule
+            .pattern
+            .as_deref()
+            .is_some_and(|pattern| pattern.trim().is_empty())
+        {
+            bail!("Rule {position} has an empty file name pattern.");
+        }
+    }
+    Ok(())
+}
+
+/// Index and role of the first rule claiming the file.
+pub fn classify(
+    rules: &[RoleRule],
+    file_name: &str,
+    mime: Option<&str>,
+) -> Option<(usize, RuleRole)> {
+    let extension = Path::new(file_name)
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    rules
+        .iter()
+        .position(|rule| rule.matches(file_name, &extension, mime))
+        .map(|index| (index, rules[index].role))
+}
+
+/// MIME type of a file from its leading bytes, falling back to its extension.
+pub fn sniff_mime(path: &Path) -> Option<&'static str> {
+    let mut head = [0u8; 16];
+    let read = File::open(path)
+        .and_then(|mut file| file.read(&mut head))
+        .unwrap_or(0);
+    mime_from_magic(&head[..read]).or_else(|| {
+        let extension = path.extension()?.to_string_lossy().to_lowercase();
+        mime_from_extension(&extension)
+    })
+}
+
+fn mime_from_magic(head: &[u8]) -> Option<&'static str> {
+    const SIGNATURES: &[(&[u8], &str)] = &[
+        (b"\x89PNG\r\n\x1a\n", "image/png"),
+        (b"\xff\xd8\xff", "image/jpeg"),
+        (b"GIF87a", "image/gif"),
+        (b"GIF89a", "image/gif"),
+        (b"II*\0", "image/tiff"),
+        (b"MM\0*", "image/tiff"),
+        (b"BM", "image/bmp"),
+        (b"%PDF", "application/pdf"),
+        (b"PK\x03\x04", "application/zip"),
+    ];
+    if head.len() >= 12 && head.starts_with(b"RIFF") && &head[8..12] == b"WEBP" {
+        return Some("image/webp");
+    }
+    SIGNATURES
+        .iter()
+        .find(|(signature, _)| head.starts_with(signature))
+        .map(|(_, mime)| *mime)
+}
+
+fn mime_from_extension(extension: &str) -> Option<&'static str> {
+    let mime = match extension {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "tif" | "tiff" => "image/tiff",
+        "bmp" => "image/bmp",
+        "svg" => "image/svg+xml",
+        "pdf" => "application/pdf",
+        "doc" => "application/msword",
+        "docx" => "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+        "xlsx" => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        "pptx" => "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+        "xlf" | "xliff" | "xml" => "application/xml",
+        "html" | "htm" => "text/html",
+        "txt" => "text/plain",
+        "csv" => "text/csv",
+        "md" => "text/markdown",
+        "json" => "application/json",
+        _ => return None,
+    };
+    Some(mime)
+}
+
+/// Matches `name` against a glob where `*` spans any run of characters and `?` one.
+fn glob_matches(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, n));
+                p += 1;
+            }
+            Some(&expected) if expected == '?' || expected == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match backtrack {
+                Some((star, consumed)) => {
+                    p = star + 1;
+                    n = consumed + 1;
+                    backtrack = Some((star, consumed + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_matching_rule_assigns_the_role() {
+        let mut rules = default_role_rules();
+        rules.push(RoleRule {
+            extensions: Vec::new(),
+            mime: Some("image/*".into()),
+            pattern: None,
+            role: RuleRole::Reference,
+        });
+        rules.push(RoleRule {
+            extensions: vec![".PDF".into()],
+            mime: None,
+            pattern: Some("client_?_*".into()),
+            role: RuleRole::Processable,
+        });
+
+        assert_eq!(
+            classify(&rules, "Translator Brief v2.docx", None),
+            Some((1, RuleRole::Instructions))
+        );
+        assert_eq!(
+            classify(&rules, "STYLE-GUIDE.pdf", Some("application/pdf")),
+            Some((2, RuleRole::Instructions))
+        );
+        assert_eq!(
+            classify(&rules, "scan", Some("image/png")),
+            Some((3, RuleRole::Reference))
+        );
+        assert_eq!(
+            classify(&rules, "client_a_manual.pdf", None),
+            Some((4, RuleRole::Processable))
+        );
+        assert_eq!(classify(&rules, "client_ab_manual.pdf", None), None);
+        assert_eq!(classify(&rules, "manual.docx", None), None);
+    }
+
+    #[test]
+    fn rules_without_conditions_are_rejected() {
+        let rule = RoleRule {
+            extensions: Vec::new(),
+            mime: None,
+            pattern: None,
+            role: RuleRole::Reference,
+        };
+        assert!(validate_role_rules(&[rule.clone()]).is_err());
+        let rule = RoleRule {
+            mime: Some("image".into()),
+            ..rule
+        };
+        assert!(validate_role_rules(&[rule]).is_err());
+        assert!(validate_role_rules(&default_role_rules()).is_ok());
+    }
+}
@@ -0,0 +1,420 @@
+//! Project intake from packages of other CAT tools: Trados project packages (`.sdlppx`) and
+//! memoQ handoff packages (`.mqout`).
+//!
+//! Both are ZIP archives with a manifest next to the bilingual documents: the `.sdlproj` file
+//! of a Trados package, an XML description at the root of a memoQ package. The manifest gives
+//! the project name, the languages and the deadline; the SDLXLIFF or memoQ XLIFF documents are
+//! staged like email attachments so a project draft can import them. The package itself is
+//! staged too and kept with the project as a reference, so the translated documents can be
+//! put back where they came from in a return package; see [`crate::delivery::return_package`].
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+use chrono::NaiveDateTime;
+use quick_xml::Reader;
+use quick_xml::events::Event;
+
+use super::{StagedFile, safe_file_name, unique_name};
+use crate::db::health::parse_timestamp;
+use crate::import::archive::{
+    ArchiveEntry, ArchiveLimits, extract_entries, list_entries, read_entry,
+};
+
+/// Largest manifest read into memory.
+const MAX_MANIFEST_BYTES: u64 = 16 << 20;
+
+/// memoQ's three-letter language codes of the most common languages, with their ISO 639-1
+/// codes. Regional variants keep their region: `ger-AT` becomes `de-AT`.
+const MEMOQ_LANGUAGES: &[(&str, &str)] = &[
+    ("ara", "ar"),
+    ("bul", "bg"),
+    ("chi", "zh"),
+    ("cze", "cs"),
+    ("dan", "da"),
+    ("dut", "nl"),
+    ("eng", "en"),
+    ("est", "et"),
+    ("fin", "fi"),
+    ("fre", "fr"),
+    ("ger", "de"),
+    ("gre", "el"),
+    ("heb", "he"),
+    ("hrv", "hr"),
+    ("hun", "hu"),
+    ("ita", "it"),
+    ("jpn", "ja"),
+    ("kor", "ko"),
+    ("lav", "lv"),
+    ("lit", "lt"),
+    ("nor", "nb"),
+    ("pol", "pl"),
+    ("por", "pt"),
+    ("rum", "ro"),
+    ("rus", "ru"),
+    ("slo", "sk"),
+    ("slv", "sl"),
+    ("spa", "es"),
+    ("swe", "sv"),
+    ("tur", "tr"),
+    ("ukr", "uk"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CatPackageKind {
+    Trados,
+    MemoQ,
+}
+
+impl CatPackageKind {
+    /// The kind of package `path` is, from its extension.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_string_lossy().to_lowercase();
+        match extension.as_str() {
+            "sdlppx" => Some(Self::Trados),
+            "mqout" => Some(Self::MemoQ),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Trados => "trados",
+            Self::MemoQ => "memoq",
+        }
+    }
+
+    /// Extension of the package sent back to the client: `.sdlrpx` for Trados, `.mqback`
+    /// for memoQ.
+    pub fn return_extension(self) -> &'static str {
+        match self {
+            Self::Trados => "sdlrpx",
+            Self::MemoQ => "mqback",
+        }
+    }
+
+    fn is_manifest(self, relative_path: &Path) -> bool {
+        let extension = extension_of(relative_path);
+        match self {
+            Self::Trados => extension == "sdlproj",
+            Self::MemoQ => extension == "xml" && relative_path.components().count() == 1,
+        }
+    }
+
+    fn is_document(self, relative_path: &Path) -> bool {
+        let extension = extension_of(relative_path);
+        match self {
+            Self::Trados => extension == "sdlxliff",
+            Self::MemoQ => matches!(extension.as_str(), "mqxliff" | "xlf" | "xliff"),
+        }
+    }
+}
+
+/// What a package manifest says about the project.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PackageManifest {
+    pub project_name: Option<String>,
+    pub source_language: Option<String>,
+    /// Target languages in manifest order, without duplicates.
+    pub target_languages: Vec<String>,
+    /// Deadline as a date or timestamp [`parse_timestamp`] accepts.
+    pub due_date: Option<String>,
+}
+
+/// A bilingual document of a package.
+#[derive(Debug, Clone)]
+pub struct PackageDocument {
+    entry: ArchiveEntry,
+    /// Name of the staged copy, which the project file made from it keeps.
+    pub staged_name: String,
+    /// Language the document is translated into: the language folder it sits in, or the
+    /// only target language of the package.
+    pub target_language: Option<String>,
+}
+
+impl PackageDocument {
+    /// Path of the document inside the package.
+    pub fn relative_path(&self) -> &Path {
+        &self.entry.relative_path
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CatPackage {
+    pub kind: CatPackageKind,
+    pub manifest: PackageManifest,
+    manifest_entry: Option<ArchiveEntry>,
+    documents: Vec<PackageDocument>,
+}
+
+impl CatPackage {
+    pub fn documents(&self) -> &[PackageDocument] {
+        &self.documents
+    }
+
+    pub fn document_count(&self) -> usize {
+        self.documents.len()
+    }
+
+    /// Path of the manifest inside the package and its contents, when there is one.
+    pub fn read_manifest(&self, path: &Path) -> Result<Option<(PathBuf, Vec<u8>)>> {
+        self.manifest_entry
+            .as_ref()
+            .map(|entry| {
+                let data = read_entry(path, entry, MAX_MANIFEST_BYTES)?;
+                Ok((entry.relative_path.clone(), data))
+            })
+            .transpose()
+    }
+}
+
+/// Lists the package at `path` and reads its manifest. A package without a manifest still
+/// opens with an empty one; a package without documents does not.
+pub fn read_package(path: &Path) -> Result<CatPackage> {
+    let Some(kind) = CatPackageKind::from_path(path) else {
+        bail!("only .sdlppx and .mqout packages can be imported");
+    };
+    let entries = list_entries(path, &ArchiveLimits::default())?;
+    let manifest_entry = entries
+        .iter()
+        .find(|entry| kind.is_manifest(&entry.relative_path))
+        .cloned();
+    let manifest = match &manifest_entry {
+        Some(entry) => {
+            let data = read_entry(path, entry, MAX_MANIFEST_BYTES)?;
+            parse_manifest(&data, kind)
+                .with_context(|| format!("'{}' is not valid XML", entry.relative_path.display()))?
+        }
+        None => PackageManifest::default(),
+    };
+    // Documents are staged flat, so their names are made unique in package order.
+    let mut taken = HashSet::new();
+    let documents: Vec<PackageDocument> = entries
+        .into_iter()
+        .filter(|entry| kind.is_document(&entry.relative_path))
+        .map(|entry| PackageDocument {
+            staged_name: unique_name(
+                &safe_file_name(&entry.relative_path.to_string_lossy()),
+                &mut taken,
+            ),
+            target_language: document_language(&entry.relative_path, &manifest, kind),
+            entry,
+        })
+        .collect();
+    if documents.is_empty() {
+        bail!("the package holds no documents to translate");
+    }
+    Ok(CatPackage {
+        kind,
+        manifest,
+        manifest_entry,
+        documents,
+    })
+}
+
+/// Extracts the documents of `package` into `dir` under their staged names, and copies the
+/// package itself next to them. The package comes last.
+pub fn stage_package(path: &Path, package: &CatPackage, dir: &Path) -> Result<Vec<StagedFile>> {
+    let mut targets = Vec::with_capacity(package.documents.len());
+    let mut staged = Vec::with_capacity(package.documents.len() + 1);
+    for document in &package.documents {
+        let destination = dir.join(&document.staged_name);
+        targets.push((document.entry.clone(), destination.clone()));
+        staged.push(StagedFile {
+            name: document.staged_name.clone(),
+            path: destination,
+            instructions: false,
+        });
+    }
+    extract_entries(path, &targets, &ArchiveLimits::default())?;
+
+    let mut taken: HashSet<String> = package
+        .documents
+        .iter()
+        .map(|document| document.staged_name.to_lowercase())
+        .collect();
+    let name = unique_name(
+        &safe_file_name(&path.file_name().unwrap_or_default().to_string_lossy()),
+        &mut taken,
+    );
+    let destination = dir.join(&name);
+    std::fs::copy(path, &destination)
+        .with_context(|| format!("{} could not be staged", path.display()))?;
+    staged.push(StagedFile {
+        name,
+        path: destination,
+        instructions: false,
+    });
+    Ok(staged)
+}
+
+/// Target language of a document: Trados keeps the documents of each target language in a
+/// folder named after it.
+fn document_language(
+    relative_path: &Path,
+    manifest: &PackageManifest,
+    kind: CatPackageKind,
+) -> Option<String> {
+    let folder = relative_path
+        .parent()
+        .and_then(|parent| parent.components().next())
+        .map(|component| normalize_language(&component.as_os_str().to_string_lossy(), kind));
+    let from_folder = folder.and_then(|folder| {
+        manifest
+            .target_languages
+            .iter()
+            .find(|language| language.eq_ignore_ascii_case(&folder))
+    });
+    match (from_folder, manifest.target_languages.as_slice()) {
+        (Some(language), _) | (None, [language]) => Some(language.clone()),
+        _ => None,
+    }
+}
+
+/// Reads the project name, languages and deadline from a manifest. Both formats are scanned
+/// for the same few names, as attributes or as elements, wherever they appear.
+fn parse_manifest(data: &[u8], kind: CatPackageKind) -> Result<PackageManifest> {
+    let mut reader = Reader::from_reader(data);
+    reader.config_mut().trim_text(true);
+    let decoder = reader.decoder();
+    let mut manifest = PackageManifest::default();
+    let mut buf = Vec::new();
+    let mut open_element: Option<String> = None;
+    loop {
+        buf.clear();
+        let event = reader.read_event_into(&mut buf)?;
+        match event {
+            Event::Eof => break,
+            Event::Start(ref start) | Event::Empty(ref start) => {
+                let element = decoder.decode(start.local_name().as_ref())?.into_owned();
+                for attr in start.attributes().with_checks(false) {
+                    let attr = attr?;
+                    let key = decoder.decode(attr.key.local_name().as_ref())?.into_owned();
+                    let value = attr.decode_and_unescape_value(decoder)?;
+                    let key = if element == "GeneralProjectInfo" && key == "Name" {
+                        "ProjectName".to_string()
+                    } else {
+                        key
+                    };
+                    manifest.record(&key, &value, kind);
+                }
+                open_element = matches!(event, Event::Start(_)).then_some(element);
+            }
+            Event::Text(text) => {
+                if let Some(element) = &open_element {
+                    let value = text.xml_content()?;
+                    manifest.record(element, &value, kind);
+                }
+            }
+            Event::End(_) => open_element = None,
+            _ => {}
+        }
+    }
+    Ok(manifest)
+}
+
+impl PackageManifest {
+    fn record(&mut self, key: &str, value: &str, kind: CatPackageKind) {
+        let value = value.trim();
+        if value.is_empty() {
+            return;
+        }
+        match key.to_ascii_lowercase().as_str() {
+            "projectname" | "packagename" if self.project_name.is_none() => {
+                self.project_name = Some(value.to_string());
+            }
+            "sourcelanguage" | "sourcelanguagecode" | "sourcelang" | "sourcelangcode"
+                if self.source_language.is_none() =>
+            {
+                self.source_language = Some(normalize_language(value, kind));
+            }
+            "targetlanguage" | "targetlanguagecode" | "targetlang" | "targetlangcode" => {
+                let language = normalize_language(value, kind);
+                if !self.target_languages.contains(&language) {
+                    self.target_languages.push(language);
+                }
+            }
+            "duedate" | "deadline" if self.due_date.is_none() => {
+                self.due_date = normalize_due_date(value);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn normalize_language(code: &str, kind: CatPackageKind) -> String {
+    let code = code.trim().replace('_', "-");
+    if kind == CatPackageKind::MemoQ {
+        let (language, region) = match code.split_once('-') {
+            Some((language, region)) => (language, Some(region)),
+            None => (code.as_str(), None),
+        };
+        if let Some((_, iso)) = MEMOQ_LANGUAGES
+            .iter()
+            .find(|(memoq, _)| memoq.eq_ignore_ascii_case(language))
+        {
+            return match region {
+                Some(region) => format!("{iso}-{}", region.to_ascii_uppercase()),
+                None => (*iso).to_string(),
+            };
+        }
+    }
+    code
+}
+
+/// Keeps deadlines the rest of the app understands; Trados writes local times without an
+/// offset, which are stored as `YYYY-MM-DD HH:MM:SS`.
+fn normalize_due_date(value: &str) -> Option<String> {
+    if parse_timestamp(value).is_some() {
+        return Some(value.to_string());
+    }
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f")
+        .ok()
+        .map(|parsed| parsed.format("%Y-%m-%d %H:%M:%S").to_string())
+}
+
+fn extension_of(path: &Path) -> String {
+    path.extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn manifests_of_both_tools_give_languages_and_deadline() {
+        let sdlproj = br#"<?xml version="1.0" encoding="utf-8"?>
+            <PackageProject PackageName="ACME_Q3_Manuals">
+              <GeneralProjectInfo Name="ACME Q3 Manuals" DueDate="2026-05-12T17:00:00" />
+              <LanguageDirections>
+                <LanguageDirection SourceLanguageCode="en-US" TargetLanguageCode="de-DE" />
+                <LanguageDirection SourceLanguageCode="en-US" TargetLanguageCode="fr-FR" />
+              </LanguageDirections>
+            </PackageProject>"#;
+        let manifest = parse_manifest(sdlproj, CatPackageKind::Trados).unwrap();
+        assert_eq!(
+            manifest,
+            PackageManifest {
+                project_name: Some("ACME_Q3_Manuals".into()),
+                source_language: Some("en-US".into()),
+                target_languages: vec!["de-DE".into(), "fr-FR".into()],
+                due_date: Some("2026-05-12 17:00:00".into()),
+            }
+        );
+
+        let memoq = br#"<PackageDescription>
+              <ProjectName>Website relaunch</ProjectName>
+              <SourceLanguage>eng</SourceLanguage>
+              <TargetLanguages><TargetLanguage>ger-AT</TargetLanguage></TargetLanguages>
+              <Deadline>2026-05-20T09:00:00Z</Deadline>
+            </PackageDescription>"#;
+        let manifest = parse_manifest(memoq, CatPackageKind::MemoQ).unwrap();
+        assert_eq!(manifest.source_language.as_deref(), Some("en"));
+        assert_eq!(manifest.target_languages, vec!["de-AT".to_string()]);
+        assert_eq!(manifest.due_date.as_deref(), Some("2026-05-20T09:00:00Z"));
+        assert_eq!(manifest.project_name.as_deref(), Some("Website relaunch"));
+    }
+}
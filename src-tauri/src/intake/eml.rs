@@ -0,0 +1,106 @@
+//! Internet messages (`.eml`), as saved by most mail clients.
+
+use anyhow::{Result, bail};
+use mail_parser::{MessageParser, MessagePart, MimeHeaders};
+
+use super::{EmailAttachment, IntakeEmail};
+
+pub fn parse_eml(bytes: &[u8]) -> Result<IntakeEmail> {
+    let Some(message) = MessageParser::default().parse(bytes) else {
+        bail!("The file is not an email message.");
+    };
+    if message.from().is_none() && message.subject().is_none() {
+        bail!("The file is not an email message.");
+    }
+    let sender = message.from().and_then(|from| from.first());
+    let attachments = message
+        .attachments()
+        .filter(|part| !is_embedded_image(part))
+        .enumerate()
+        .map(|(index, part)| EmailAttachment {
+            name: attachment_name(part, index),
+            data: part.contents().to_vec(),
+        })
+        .collect();
+    Ok(IntakeEmail {
+        subject: message.subject().map(str::to_string),
+        sender_name: sender.and_then(|sender| sender.name()).map(str::to_string),
+        sender_address: sender
+            .and_then(|sender| sender.address())
+            .map(str::to_string),
+        body: message.body_text(0).map(|body| body.into_owned()),
+        attachments,
+    })
+}
+
+/// Images referenced from the HTML body, such as signature logos.
+fn is_embedded_image(part: &MessagePart<'_>) -> bool {
+    part.content_id().is_some()
+        && part
+            .content_type()
+            .is_some_and(|content_type| content_type.ctype().eq_ignore_ascii_case("image"))
+        && !part
+            .content_disposition()
+            .is_some_and(|disposition| disposition.is_attachment())
+}
+
+fn attachment_name(part: &MessagePart<'_>, index: usize) -> String {
+    match part.attachment_name() {
+        Some(name) if !name.trim().is_empty() => name.to_string(),
+        _ if part.is_message() => format!("message-{}.eml", index + 1),
+        _ => format!("attachment-{}", index + 1),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_sender_subject_body_and_attachments() {
+        let eml = concat!(
+            "From: \"Anna M\u{fc}ller\" <Anna.Mueller@acme.example>\r\n",
+            "To: pm@agency.example\r\n",
+            "Subject: =?UTF-8?Q?AW:_Brosch=C3=BCre_Q3?=\r\n",
+            "MIME-Version: 1.0\r\n",
+            "Content-Type: multipart/mixed; boundary=\"b1\"\r\n",
+            "\r\n",
+            "--b1\r\n",
+            "Content-Type: text/plain; charset=utf-8\r\n",
+            "\r\n",
+            "Please translate into French by Friday.\r\n",
+            "--b1\r\n",
+            "Content-Type: application/octet-stream; name=\"brochure.docx\"\r\n",
+            "Content-Disposition: attachment; filename=\"brochure.docx\"\r\n",
+            "Content-Transfer-Encoding: base64\r\n",
+            "\r\n",
+            "aGVsbG8=\r\n",
+            "--b1\r\n",
+            "Content-Type: image/png\r\n",
+            "Content-ID: <logo@acme>\r\n",
+            "Content-Disposition: inline\r\n",
+            "Content-Transfer-Encoding: base64\r\n",
+            "\r\n",
+            "iVBORw0KGgo=\r\n",
+            "--b1--\r\n",
+        );
+        let email = parse_eml(eml.as_bytes()).unwrap();
+        assert_eq!(email.subject.as_deref(), Some("AW: Broschüre Q3"));
+        assert_eq!(email.sender_name.as_deref(), Some("Anna Müller"));
+        assert_eq!(
+            email.sender_address.as_deref(),
+            Some("Anna.Mueller@acme.example")
+        );
+        assert_eq!(
+            email.body.as_deref().map(str::trim),
+            Some("Please translate into French by Friday.")
+        );
+        assert_eq!(
+            email.attachments,
+            vec![EmailAttachment {
+                name: "brochure.docx".into(),
+                data: b"hello".to_vec(),
+            }]
+        );
+    }
+}
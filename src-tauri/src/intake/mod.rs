@@ -0,0 +1,383 @@
+//! Project intake from client emails.
+//!
+//! A dropped `.eml` or Outlook `.msg` file is read into an [`IntakeEmail`]: sender, subject,
+//! plain-text body and attached files. The attachments and the body are then staged as files
+//! under the intake folder so a project draft can reference them like any other asset until
+//! the project is created; drafts left behind are pruned after a week.
+
+pub mod cat_package;
+pub mod eml;
+pub mod msg;
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use anyhow::{Context, Result, bail};
+
+use crate::db::types::ClientRecord;
+
+/// Folder of the application data directory holding staged intake drafts.
+pub const INTAKE_DIR: &str = "intake";
+/// Name of the staged file holding the email body.
+pub const INSTRUCTIONS_FILE_NAME: &str = "instructions.txt";
+
+const STALE_DRAFT_AGE: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Webmail domains shared by unrelated senders, never used to match a client.
+const FREEMAIL_DOMAINS: &[&str] = &[
+    "aol.com",
+    "gmail.com",
+    "gmx.de",
+    "gmx.net",
+    "googlemail.com",
+    "hotmail.com",
+    "icloud.com",
+    "libero.it",
+    "live.com",
+    "mail.ru",
+    "me.com",
+    "outlook.com",
+    "proton.me",
+    "protonmail.com",
+    "web.de",
+    "yahoo.com",
+    "yandex.ru",
+];
+
+/// Reply and forward markers of common mail clients, e.g. German `AW:`/`WG:`.
+const SUBJECT_PREFIXES: &[&str] = &[
+    "re", "fw", "fwd", "aw", "wg", "tr", "rv", "sv", "vs", "r", "i", "antw", "doorst",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmailAttachment {
+    pub name: String,
+    pub data: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IntakeEmail {
+    pub subject: Option<String>,
+    pub sender_name: Option<String>,
+    pub sender_address: Option<String>,
+    pub body: Option<String>,
+    pub attachments: Vec<EmailAttachment>,
+}
+
+/// A file written to the staging folder of a draft.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StagedFile {
+    pub name: String,
+    pub path: PathBuf,
+    /// Whether the file holds the email body rather than an attachment.
+    pub instructions: bool,
+}
+
+/// How a client was matched to the sender of an email.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClientMatch {
+    /// The client's email is the sender's address.
+    Email,
+    /// The client's email is at the sender's domain.
+    Domain,
+}
+
+impl ClientMatch {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Email => "email",
+            Self::Domain => "domain",
+        }
+    }
+}
+
+/// Reads the email at `path`, as MIME for `.eml` and as an Outlook message for `.msg`.
+pub fn parse_email(path: &Path) -> Result<IntakeEmail> {
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    let bytes = fs::read(path).with_context(|| format!("{} could not be read", path.display()))?;
+    let mut email = match extension.as_str() {
+        "eml" => eml::parse_eml(&bytes)?,
+        "msg" => msg::parse_msg(&bytes)?,
+        _ => bail!("Only .eml and .msg files can be used for intake."),
+    };
+    email.subject = clean(email.subject);
+    email.sender_name = clean(email.sender_name);
+    email.sender_address = clean(email.sender_address).map(|address| address.to_lowercase());
+    email.body = clean(email.body);
+    email
+        .attachments
+        .retain(|attachment| !attachment.data.is_empty());
+    Ok(email)
+}
+
+fn clean(value: Option<String>) -> Option<String> {
+    value
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+/// Writes the attachments of `email`, then its body as [`INSTRUCTIONS_FILE_NAME`], into
+/// `dir`. File names are made safe and unique within the draft.
+pub fn stage_email(email: &IntakeEmail, dir: &Path) -> Result<Vec<StagedFile>> {
+    fs::create_dir_all(dir).with_context(|| format!("{} could not be created", dir.display()))?;
+    let mut taken = HashSet::new();
+    let mut staged = Vec::new();
+    let body = email
+        .body
+        .as_ref()
+        .map(|body| (INSTRUCTIONS_FILE_NAME, body.as_bytes(), true));
+    let attachments = email
+        .attachments
+        .iter()
+        .map(|attachment| (attachment.name.as_str(), attachment.data.as_slice(), false));
+    for (name, data, instructions) in attachments.chain(body) {
+        let name = unique_name(&safe_file_name(name), &mut taken);
+        let path = dir.join(&name);
+        fs::write(&path, data)
+            .with_context(|| format!("{} could not be written", path.display()))?;
+        staged.push(StagedFile {
+            name,
+            path,
+            instructions,
+        });
+    }
+    Ok(staged)
+}
+
+/// Keeps the last path component of an attachment name and replaces the characters file
+/// systems reject.
+fn safe_file_name(name: &str) -> String {
+    let base = name.rsplit(['/', '\\']).next().unwrap_or_default();
+    let safe: String = base
+        .chars()
+        .map(|ch| {
+            if ch.is_control() || matches!(ch, '<' | '>' | ':' | '"' | '|' | '?' | '*') {
+                '_'
+            } else {
+                ch
+            }
+        })
+        .collect();
+    let safe = safe.trim().trim_matches('.');
+    if safe.is_empty() {
+        "attachment".to_string()
+    } else {
+        safe.to_string()
+    }
+}
+
+/// Appends ` (2)`, ` (3)`, … before the extension of names already used in the draft.
+fn unique_name(name: &str, taken: &mut HashSet<String>) -> String {
+    let (stem, extension) = match name.rsplit_once('.') {
+        Some((stem, extension)) if !stem.is_empty() => (stem, format!(".{extension}")),
+        _ => (name, String::new()),
+    };
+    let mut candidate = name.to_string();
+    let mut counter = 2;
+    while !taken.insert(candidate.to_lowercase()) {
+        candidate = format!("{stem} ({counter}){extension}");
+        counter += 1;
+    }
+    candidate
+}
+
+/// Removes staged drafts older than a week; their projects were created or abandoned.
+pub fn prune_stale_drafts(intake_dir: &Path) {
+    let Ok(entries) = fs::read_dir(intake_dir) else {
+        return;
+    };
+    let now = SystemTime::now();
+    for entry in entries.flatten() {
+        let stale = entry
+            .metadata()
+            .and_then(|metadata| metadata.modified())
+            .is_ok_and(|modified| {
+                now.duration_since(modified)
+                    .is_ok_and(|age| age > STALE_DRAFT_AGE)
+            });
+        if stale && let Err(error) = fs::remove_dir_all(entry.path()) {
+            log::warn!(
+                target: "intake",
+                "failed to remove stale intake draft {}: {error}",
+                entry.path().display()
+            );
+        }
+    }
+}
+
+/// Project name suggested by an email subject: the subject without reply and forward
+/// markers such as `Re:` or `Fwd:` and without leading tags such as `[EXTERNAL]`.
+pub fn project_name_from_subject(subject: &str) -> String {
+    let mut rest = subject.trim();
+    loop {
+        let before = rest;
+        if rest.starts_with('[')
+            && let Some(end) = rest.find(']')
+        {
+            rest = rest[end + 1..].trim_start();
+        }
+        if let Some((marker, after)) = rest.split_once(':') {
+            let marker = marker.trim().to_lowercase();
+            // "Fw[2]:" and "Re(3):" count replies in some clients.
+            let marker = marker
+                .split(['[', '('])
+                .next()
+                .unwrap_or_default()
+                .trim_end();
+            if SUBJECT_PREFIXES.contains(&marker) {
+                rest = after.trim_start();
+            }
+        }
+        if rest == before {
+            return rest.to_string();
+        }
+    }
+}
+
+/// Project folder name derived from a project name: letters, digits, `-`, `_` and `.`,
+/// with every other run of characters turned into a single `-`.
+pub fn folder_name_for(project_name: &str) -> String {
+    const MAX_LEN: usize = 80;
+    let mut folder = String::new();
+    for ch in project_name.chars() {
+        if ch.is_alphanumeric() || matches!(ch, '_' | '.') {
+            folder.push(ch);
+        } else if !folder.ends_with('-') {
+            folder.push('-');
+        }
+        if folder.len() >= MAX_LEN {
+            break;
+        }
+    }
+    let folder = folder.trim_matches(['-', '.']);
+    if folder.is_empty() {
+        "email-intake".to_string()
+    } else {
+        folder.to_string()
+    }
+}
+
+/// Clients matching the sender address: those with that exact email first, then those at
+/// the same domain unless it is a webmail domain.
+pub fn match_clients<'a>(
+    sender_address: &str,
+    clients: &'a [ClientRecord],
+) -> Vec<(&'a ClientRecord, ClientMatch)> {
+    let sender_address = sender_address.trim().to_lowercase();
+    let Some((_, sender_domain)) = sender_address.rsplit_once('@') else {
+        return Vec::new();
+    };
+    let domain_matches = !FREEMAIL_DOMAINS.contains(&sender_domain);
+    let mut matches: Vec<(&ClientRecord, ClientMatch)> = clients
+        .iter()
+        .filter_map(|client| {
+            let email = client.email.as_deref()?.trim().to_lowercase();
+            if email == sender_address {
+                Some((client, ClientMatch::Email))
+            } else if domain_matches && email.rsplit_once('@')?.1 == sender_domain {
+                Some((client, ClientMatch::Domain))
+            } else {
+                None
+            }
+        })
+        .collect();
+    matches.sort_by_key(|(_, kind)| *kind == ClientMatch::Domain);
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::types::{PostalAddress, VatValidation};
+    use uuid::Uuid;
+
+    fn client(name: &str, email: &str) -> ClientRecord {
+        ClientRecord {
+            client_uuid: Uuid::new_v4(),
+            name: name.into(),
+            email: Some(email.into()),
+            phone: None,
+            address: None,
+            vat_number: None,
+            note: None,
+            currency: None,
+            postal_address: PostalAddress::default(),
+            vat_validation: VatValidation::default(),
+        }
+    }
+
+    #[test]
+    fn suggests_names_folders_and_clients() {
+        assert_eq!(
+            project_name_from_subject("AW: [EXTERNAL] Fwd: Re[2]: Q3 brochure: DE/FR"),
+            "Q3 brochure: DE/FR"
+        );
+        assert_eq!(project_name_from_subject("Report 2026"), "Report 2026");
+        assert_eq!(folder_name_for("Q3 brochure: DE/FR"), "Q3-brochure-DE-FR");
+        assert_eq!(folder_name_for("  ***  "), "email-intake");
+
+        let clients = vec![
+            client("Acme Marketing", "marketing@acme.example"),
+            client("Acme Legal", "legal@ACME.example"),
+            client("Someone", "other@gmail.com"),
+        ];
+        let matches = match_clients("Legal@acme.example", &clients);
+        let names: Vec<(&str, ClientMatch)> = matches
+            .iter()
+            .map(|(client, kind)| (client.name.as_str(), *kind))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                ("Acme Legal", ClientMatch::Email),
+                ("Acme Marketing", ClientMatch::Domain)
+            ]
+        );
+        assert!(match_clients("someone.else@gmail.com", &clients).is_empty());
+    }
+
+    #[test]
+    fn stages_attachments_under_safe_unique_names() {
+        let dir = tempfile::tempdir().unwrap();
+        let email = IntakeEmail {
+            body: Some("Please translate into French.".into()),
+            attachments: vec![
+                EmailAttachment {
+                    name: "C:\\Users\\pm\\brochure.docx".into(),
+                    data: b"one".to_vec(),
+                },
+                EmailAttachment {
+                    name: "brochure.docx".into(),
+                    data: b"two".to_vec(),
+                },
+                EmailAttachment {
+                    name: "instructions.txt".into(),
+                    data: b"three".to_vec(),
+                },
+            ],
+            ..IntakeEmail::default()
+        };
+        let staged = stage_email(&email, dir.path()).unwrap();
+        let names: Vec<&str> = staged.iter().map(|file| file.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "brochure.docx",
+                "brochure (2).docx",
+                "instructions.txt",
+                "instructions (2).txt"
+            ]
+        );
+        assert!(staged[3].instructions);
+        assert_eq!(
+            fs::read_to_string(&staged[3].path).unwrap(),
+            "Please translate into French."
+        );
+    }
+}
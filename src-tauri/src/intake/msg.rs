@@ -0,0 +1,180 @@
+//! Outlook messages (`.msg`): a compound file with one stream per MAPI property.
+//!
+//! Properties are stored as `__substg1.0_<tag><type>` streams, strings as UTF-16LE (`001F`)
+//! or in the message code page (`001E`), and each attachment as an
+//! `__attach_version1.0_#<n>` storage holding its own properties.
+
+use std::io::{Cursor, Read};
+
+use anyhow::{Context, Result};
+use cfb::CompoundFile;
+
+use super::{EmailAttachment, IntakeEmail};
+
+const SUBJECT: &str = "0037";
+const BODY: &str = "1000";
+const SENDER_NAME: &str = "0C1A";
+const SENDER_EMAIL_ADDRESS: &str = "0C1F";
+const SENDER_SMTP_ADDRESS: &str = "5D01";
+const ATTACH_DATA_BINARY: &str = "37010102";
+const ATTACH_FILENAME: &str = "3704";
+const ATTACH_LONG_FILENAME: &str = "3707";
+const ATTACH_MIME_TAG: &str = "370E";
+const ATTACH_CONTENT_ID: &str = "3712";
+const ATTACHMENT_STORAGE_PREFIX: &str = "__attach_version1.0_";
+
+type MsgFile<'a> = CompoundFile<Cursor<&'a [u8]>>;
+
+pub fn parse_msg(bytes: &[u8]) -> Result<IntakeEmail> {
+    let mut file =
+        CompoundFile::open(Cursor::new(bytes)).context("The file is not an Outlook message.")?;
+
+    // Exchange senders carry an X.500 address in 0C1F; the SMTP address is stored apart.
+    let sender_address = read_string(&mut file, "", SENDER_SMTP_ADDRESS).or_else(|| {
+        read_string(&mut file, "", SENDER_EMAIL_ADDRESS).filter(|address| address.contains('@'))
+    });
+
+    let mut storages: Vec<String> = file
+        .read_root_storage()
+        .filter(|entry| entry.is_storage() && entry.name().starts_with(ATTACHMENT_STORAGE_PREFIX))
+        .map(|entry| format!("/{}", entry.name()))
+        .collect();
+    storages.sort();
+    let mut attachments = Vec::new();
+    for (index, storage) in storages.iter().enumerate() {
+        // Embedded messages keep their data in a nested storage instead of a stream.
+        let Some(data) = read_stream(
+            &mut file,
+            &format!("{storage}/__substg1.0_{ATTACH_DATA_BINARY}"),
+        ) else {
+            continue;
+        };
+        let embedded_image = read_string(&mut file, storage, ATTACH_CONTENT_ID).is_some()
+            && read_string(&mut file, storage, ATTACH_MIME_TAG)
+                .is_some_and(|mime| mime.to_ascii_lowercase().starts_with("image/"));
+        if embedded_image {
+            continue;
+        }
+        let name = read_string(&mut file, storage, ATTACH_LONG_FILENAME)
+            .or_else(|| read_string(&mut file, storage, ATTACH_FILENAME))
+            .unwrap_or_else(|| format!("attachment-{}", index + 1));
+        attachments.push(EmailAttachment { name, data });
+    }
+
+    Ok(IntakeEmail {
+        subject: read_string(&mut file, "", SUBJECT),
+        sender_name: read_string(&mut file, "", SENDER_NAME),
+        sender_address,
+        body: read_string(&mut file, "", BODY),
+        attachments,
+    })
+}
+
+/// String property `tag` of the message (`storage` empty) or of an attachment storage.
+fn read_string(file: &mut MsgFile<'_>, storage: &str, tag: &str) -> Option<String> {
+    if let Some(bytes) = read_stream(file, &format!("{storage}/__substg1.0_{tag}001F")) {
+        let units: Vec<u16> = bytes
+            .chunks_exact(2)
+            .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+            .collect();
+        return Some(
+            String::from_utf16_lossy(&units)
+                .trim_end_matches('\0')
+                .to_string(),
+        );
+    }
+    read_stream(file, &format!("{storage}/__substg1.0_{tag}001E")).map(|bytes| {
+        String::from_utf8_lossy(&bytes)
+            .trim_end_matches('\0')
+            .to_string()
+    })
+}
+
+fn read_stream(file: &mut MsgFile<'_>, path: &str) -> Option<Vec<u8>> {
+    let mut stream = file.open_stream(path).ok()?;
+    let mut bytes = Vec::new();
+    stream.read_to_end(&mut bytes).ok()?;
+    Some(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn utf16(text: &str) -> Vec<u8> {
+        text.encode_utf16().flat_map(u16::to_le_bytes).collect()
+    }
+
+    fn write_stream(file: &mut CompoundFile<Cursor<Vec<u8>>>, path: &str, data: &[u8]) {
+        file.create_stream(path).unwrap().write_all(data).unwrap();
+    }
+
+    #[test]
+    fn reads_properties_and_attachments() {
+        let mut file = CompoundFile::create(Cursor::new(Vec::new())).unwrap();
+        write_stream(
+            &mut file,
+            "/__substg1.0_0037001F",
+            &utf16("RE: Legal review"),
+        );
+        write_stream(&mut file, "/__substg1.0_0C1A001F", &utf16("Legal Team"));
+        write_stream(
+            &mut file,
+            "/__substg1.0_0C1F001F",
+            &utf16("/O=ACME/OU=EXCHANGE/CN=LEGAL"),
+        );
+        write_stream(
+            &mut file,
+            "/__substg1.0_5D01001F",
+            &utf16("legal@acme.example"),
+        );
+        write_stream(
+            &mut file,
+            "/__substg1.0_1000001E",
+            b"See the attached contract.\0",
+        );
+        let contract = "/__attach_version1.0_#00000000";
+        let logo = "/__attach_version1.0_#00000001";
+        for storage in [contract, logo] {
+            file.create_storage(storage).unwrap();
+        }
+        write_stream(
+            &mut file,
+            &format!("{contract}/__substg1.0_3707001F"),
+            &utf16("contract.pdf"),
+        );
+        write_stream(
+            &mut file,
+            &format!("{contract}/__substg1.0_37010102"),
+            b"%PDF",
+        );
+        write_stream(
+            &mut file,
+            &format!("{logo}/__substg1.0_3712001F"),
+            &utf16("logo@acme"),
+        );
+        write_stream(
+            &mut file,
+            &format!("{logo}/__substg1.0_370E001F"),
+            &utf16("image/png"),
+        );
+        write_stream(&mut file, &format!("{logo}/__substg1.0_37010102"), b"png");
+        file.flush().unwrap();
+        let bytes = file.into_inner().into_inner();
+
+        let email = parse_msg(&bytes).unwrap();
+        assert_eq!(email.subject.as_deref(), Some("RE: Legal review"));
+        assert_eq!(email.sender_name.as_deref(), Some("Legal Team"));
+        assert_eq!(email.sender_address.as_deref(), Some("legal@acme.example"));
+        assert_eq!(email.body.as_deref(), Some("See the attached contract."));
+        assert_eq!(
+            email.attachments,
+            vec![EmailAttachment {
+                name: "contract.pdf".into(),
+                data: b"%PDF".to_vec(),
+            }]
+        );
+        assert!(parse_msg(b"not a compound file").is_err());
+    }
+}
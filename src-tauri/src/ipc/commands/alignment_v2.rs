@@ -0,0 +1,56 @@
+use tauri::State;
+use uuid::Uuid;
+
+use super::shared::load_project_jliff;
+use crate::db::DbManager;
+use crate::ipc::dto::{AlignSegmentPayload, SegmentAlignmentDto};
+use crate::ipc::error::{IpcError, IpcResult};
+use crate::jliff::alignment::align_segment;
+use crate::metrics;
+use crate::settings::SettingsManager;
+
+/// Aligns a segment's source with a target and suggests where the source placeholders the
+/// target lacks should go. Nothing is written: the editor shows the suggested target and lets
+/// the linguist accept it.
+#[tauri::command]
+pub async fn align_segment_v2(
+    db: State<'_, DbManager>,
+    settings: State<'_, SettingsManager>,
+    payload: AlignSegmentPayload,
+) -> IpcResult<SegmentAlignmentDto> {
+    metrics::track("align_segment_v2", async {
+        let project_uuid = parse_uuid(&payload.project_uuid, "projectUuid")?;
+        let (_, document) = load_project_jliff(
+            db.inner(),
+            settings.inner(),
+            project_uuid,
+            &payload.jliff_rel_path,
+        )
+        .await?;
+        let unit = document
+            .transunits
+            .iter()
+            .find(|unit| unit.transunit_id == payload.transunit_id)
+            .ok_or_else(|| {
+                IpcError::Validation(format!(
+                    "Segment '{}' was not found in '{}'.",
+                    payload.transunit_id, payload.jliff_rel_path
+                ))
+            })?;
+
+        let target = payload
+            .target
+            .as_deref()
+            .unwrap_or_else(|| unit.effective_target());
+        Ok(SegmentAlignmentDto {
+            transunit_id: unit.transunit_id.clone(),
+            alignment: align_segment(&unit.source, target),
+        })
+    })
+    .await
+}
+
+fn parse_uuid(value: &str, field: &str) -> Result<Uuid, IpcError> {
+    Uuid::parse_str(value)
+        .map_err(|_| IpcError::Validation(format!("invalid {field}: expected UUID, got '{value}'")))
+}
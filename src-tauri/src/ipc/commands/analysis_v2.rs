@@ -0,0 +1,146 @@
+use tauri::State;
+use uuid::Uuid;
+
+use super::projects_v2::resolve_project_root;
+use super::shared::fs_error;
+use super::tm_assignments_v2::map_tm_assignment_record;
+use super::translation_memory_v2::{load_pair_documents, load_tm_matcher};
+use crate::db::DbManager;
+use crate::ipc::dto::{
+    AnalyzeProjectPayload, LeverageAnalysisDto, LeverageBandDto, LeverageFileDto,
+};
+use crate::ipc::error::{IpcError, IpcResult};
+use crate::jliff::analysis::{LeverageAnalyzer, LeverageBreakdown, MatchBand};
+use crate::metrics;
+use crate::settings::SettingsManager;
+
+/// Project folder the analysis reports are written to.
+const REPORTS_DIR: &str = "Reports";
+
+/// Analyses every JLIFF document of a language pair against the translation memory:
+/// repetitions, exact and fuzzy-band matches and new words, per file and in total. The
+/// result is also written as a JSON report under the project's `Reports` folder.
+#[tauri::command]
+pub async fn analyze_project_v2(
+    db: State<'_, DbManager>,
+    settings: State<'_, SettingsManager>,
+    payload: AnalyzeProjectPayload,
+) -> IpcResult<LeverageAnalysisDto> {
+    metrics::track("analyze_project_v2", async {
+        let project_uuid = parse_uuid(&payload.project_uuid, "projectUuid")?;
+        let source_lang = payload.source_lang.trim().to_string();
+        let target_lang = payload.target_lang.trim().to_string();
+
+        let mut documents = load_pair_documents(
+            db.inner(),
+            settings.inner(),
+            project_uuid,
+            &source_lang,
+            &target_lang,
+        )
+        .await?;
+        if documents.is_empty() {
+            return Err(IpcError::Validation(format!(
+                "The project has no JLIFF documents for {source_lang} → {target_lang}."
+            ))
+            .into());
+        }
+        documents.sort_by(|(left, _), (right, _)| left.cmp(right));
+
+        let matcher =
+            load_tm_matcher(db.inner(), Some(project_uuid), &source_lang, &target_lang).await?;
+        let tm_assignments = db
+            .list_project_tm_assignments(project_uuid)
+            .await
+            .map_err(IpcError::from)?
+            .into_iter()
+            .map(map_tm_assignment_record)
+            .collect();
+
+        let mut analyzer = LeverageAnalyzer::new(&matcher);
+        let mut total = LeverageBreakdown::default();
+        let mut files = Vec::with_capacity(documents.len());
+        for (jliff_rel_path, document) in &documents {
+            let breakdown = analyzer.analyze(document);
+            total.add(&breakdown);
+            let file_total = breakdown.total();
+            files.push(LeverageFileDto {
+                jliff_rel_path: jliff_rel_path.clone(),
+                bands: map_bands(&breakdown),
+                total_segments: file_total.segments,
+                total_words: file_total.words,
+            });
+        }
+
+        let now = chrono::Utc::now();
+        let report_rel_path = format!(
+            "{REPORTS_DIR}/analysis_{}_{}_{}.json",
+            file_name_part(&source_lang),
+            file_name_part(&target_lang),
+            now.format("%Y%m%dT%H%M%SZ")
+        );
+        let grand_total = total.total();
+        let analysis = LeverageAnalysisDto {
+            project_uuid: project_uuid.to_string(),
+            source_lang,
+            target_lang,
+            created_at: now.to_rfc3339(),
+            report_rel_path,
+            bands: map_bands(&total),
+            total_segments: grand_total.segments,
+            total_words: grand_total.words,
+            files,
+            tm_assignments,
+        };
+
+        let project_root = resolve_project_root(db.inner(), settings.inner(), project_uuid).await?;
+        let report_path = project_root.join(&analysis.report_rel_path);
+        if let Some(parent) = report_path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|error| fs_error("create the reports folder", error))?;
+        }
+        let report = serde_json::to_string_pretty(&analysis).map_err(|error| {
+            IpcError::Internal(format!("Failed to serialize analysis report: {error}"))
+        })?;
+        tokio::fs::write(&report_path, report)
+            .await
+            .map_err(|error| fs_error("write the analysis report", error))?;
+
+        Ok(analysis)
+    })
+    .await
+}
+
+fn map_bands(breakdown: &LeverageBreakdown) -> Vec<LeverageBandDto> {
+    MatchBand::ALL
+        .iter()
+        .map(|band| {
+            let count = breakdown.get(*band);
+            LeverageBandDto {
+                band: band.as_str().to_string(),
+                segments: count.segments,
+                words: count.words,
+            }
+        })
+        .collect()
+}
+
+/// `language` with anything but ASCII letters, digits and hyphens replaced by `_`.
+fn file_name_part(language: &str) -> String {
+    language
+        .chars()
+        .map(|ch| {
+            if ch.is_ascii_alphanumeric() || ch == '-' {
+                ch
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+fn parse_uuid(value: &str, field: &str) -> Result<Uuid, IpcError> {
+    Uuid::parse_str(value)
+        .map_err(|_| IpcError::Validation(format!("invalid {field}: expected UUID, got '{value}'")))
+}
@@ -5,18 +5,22 @@ use crate::db::DbManager;
 use crate::db::types::{ArtifactRecord, NewArtifactArgs, UpdateArtifactStatusArgs};
 use crate::ipc::dto::{ArtifactV2Dto, UpdateArtifactStatusPayload, UpsertArtifactPayload};
 use crate::ipc::error::{IpcError, IpcResult};
+use crate::metrics;
 
 #[tauri::command]
 pub async fn upsert_artifact_record_v2(
     db: State<'_, DbManager>,
     payload: UpsertArtifactPayload,
 ) -> IpcResult<ArtifactV2Dto> {
-    let args = map_new_artifact_args(payload)?;
-    let record = db
-        .upsert_artifact_record(args)
-        .await
-        .map_err(IpcError::from)?;
-    Ok(map_artifact_record(record))
+    metrics::track("upsert_artifact_record_v2", async {
+        let args = map_new_artifact_args(payload)?;
+        let record = db
+            .upsert_artifact_record(args)
+            .await
+            .map_err(IpcError::from)?;
+        Ok(map_artifact_record(record))
+    })
+    .await
 }
 
 #[tauri::command]
@@ -24,12 +28,15 @@ pub async fn update_artifact_status_v2(
     db: State<'_, DbManager>,
     payload: UpdateArtifactStatusPayload,
 ) -> IpcResult<Option<ArtifactV2Dto>> {
-    let args = map_update_artifact_status_args(payload)?;
-    let record = db
-        .update_artifact_status(args)
-        .await
-        .map_err(IpcError::from)?;
-    Ok(record.map(map_artifact_record))
+    metrics::track("update_artifact_status_v2", async {
+        let args = map_update_artifact_status_args(payload)?;
+        let record = db
+            .update_artifact_status(args)
+            .await
+            .map_err(IpcError::from)?;
+        Ok(record.map(map_artifact_record))
+    })
+    .await
 }
 
 #[tauri::command]
@@ -37,11 +44,14 @@ pub async fn delete_artifact_record_v2(
     db: State<'_, DbManager>,
     artifact_uuid: String,
 ) -> IpcResult<()> {
-    let artifact_uuid = parse_uuid(&artifact_uuid, "artifactUuid")?;
-    db.delete_artifact_record(artifact_uuid)
-        .await
-        .map_err(IpcError::from)?;
-    Ok(())
+    metrics::track("delete_artifact_record_v2", async {
+        let artifact_uuid = parse_uuid(&artifact_uuid, "artifactUuid")?;
+        db.delete_artifact_record(artifact_uuid)
+            .await
+            .map_err(IpcError::from)?;
+        Ok(())
+    })
+    .await
 }
 
 #[tauri::command]
@@ -50,13 +60,16 @@ pub async fn list_artifacts_for_file_v2(
     project_uuid: String,
     file_uuid: String,
 ) -> IpcResult<Vec<ArtifactV2Dto>> {
-    let project_uuid = parse_uuid(&project_uuid, "projectUuid")?;
-    let file_uuid = parse_uuid(&file_uuid, "fileUuid")?;
-    let artifacts = db
-        .list_artifacts_for_file(project_uuid, file_uuid)
-        .await
-        .map_err(IpcError::from)?;
-    Ok(artifacts.into_iter().map(map_artifact_record).collect())
+    metrics::track("list_artifacts_for_file_v2", async {
+        let project_uuid = parse_uuid(&project_uuid, "projectUuid")?;
+        let file_uuid = parse_uuid(&file_uuid, "fileUuid")?;
+        let artifacts = db
+            .list_artifacts_for_file(project_uuid, file_uuid)
+            .await
+            .map_err(IpcError::from)?;
+        Ok(artifacts.into_iter().map(map_artifact_record).collect())
+    })
+    .await
 }
 
 fn map_new_artifact_args(payload: UpsertArtifactPayload) -> Result<NewArtifactArgs, IpcError> {
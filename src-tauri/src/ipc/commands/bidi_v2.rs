@@ -0,0 +1,209 @@
+use std::collections::HashSet;
+
+use serde_json::json;
+use tauri::State;
+use uuid::Uuid;
+
+use super::qa_v2::{map_qa_finding_record, project_qa_profile};
+use super::shared::{load_project_jliff, update_project_jliff};
+use crate::db::DbManager;
+use crate::db::types::{
+    NewQaFindingArgs, NewSegmentRevisionArgs, RecordSegmentRevisionsArgs, ReplaceQaFindingsArgs,
+};
+use crate::ipc::dto::{
+    BidiControlsQaDto, BidiSanitizeDto, CheckBidiControlsPayload, SanitizeBidiControlsPayload,
+};
+use crate::ipc::error::{IpcError, IpcResult};
+use crate::jliff::JliffDocument;
+use crate::jliff::bidi::{
+    direction_for_language, find_bidi_issues, sanitize_bidi_controls, target_direction,
+};
+use crate::metrics;
+use crate::settings::SettingsManager;
+
+/// QA check identifier used when persisting bidi control findings.
+const BIDI_CONTROLS_CHECK: &str = "bidi_controls";
+/// Revision origin of targets cleaned by [`sanitize_bidi_controls_v2`].
+const BIDI_SANITIZE_ORIGIN: &str = "bidi_sanitize";
+
+/// Reports unbalanced bidi controls, controls inside placeholders and directional marks in
+/// left-to-right targets, and replaces the stored findings of the check for the document.
+#[tauri::command]
+pub async fn check_bidi_controls_v2(
+    db: State<'_, DbManager>,
+    settings: State<'_, SettingsManager>,
+    payload: CheckBidiControlsPayload,
+) -> IpcResult<BidiControlsQaDto> {
+    metrics::track("check_bidi_controls_v2", async {
+        let project_uuid = parse_uuid(&payload.project_uuid, "projectUuid")?;
+        let (_, document) = load_project_jliff(
+            db.inner(),
+            settings.inner(),
+            project_uuid,
+            &payload.jliff_rel_path,
+        )
+        .await?;
+        run_bidi_controls_check(db.inner(), project_uuid, payload.jliff_rel_path, &document)
+            .await
+            .map_err(Into::into)
+    })
+    .await
+}
+
+/// Removes the controls reported by [`check_bidi_controls_v2`] from the targets, records
+/// the changes as revisions and clears the findings of the cleaned segments.
+#[tauri::command]
+pub async fn sanitize_bidi_controls_v2(
+    db: State<'_, DbManager>,
+    settings: State<'_, SettingsManager>,
+    payload: SanitizeBidiControlsPayload,
+) -> IpcResult<BidiSanitizeDto> {
+    metrics::track("sanitize_bidi_controls_v2", async {
+        let project_uuid = parse_uuid(&payload.project_uuid, "projectUuid")?;
+        let selected: Option<HashSet<String>> =
+            payload.transunit_ids.map(|ids| ids.into_iter().collect());
+
+        let (revisions, removed_controls) = update_project_jliff(
+            db.inner(),
+            settings.inner(),
+            project_uuid,
+            &payload.jliff_rel_path,
+            move |document| {
+                let fallback = direction_for_language(&document.target_language);
+                let mut revisions = Vec::new();
+                let mut removed = 0;
+                for unit in &mut document.transunits {
+                    if selected
+                        .as_ref()
+                        .is_some_and(|ids| !ids.contains(&unit.transunit_id))
+                    {
+                        continue;
+                    }
+                    let direction = unit
+                        .metadata
+                        .as_ref()
+                        .and_then(|metadata| metadata.target_direction)
+                        .unwrap_or(fallback);
+                    let target = unit.effective_target_mut();
+                    let cleaned = sanitize_bidi_controls(target, direction);
+                    if cleaned == *target {
+                        continue;
+                    }
+                    removed += target.chars().count() - cleaned.chars().count();
+                    let previous_target = std::mem::replace(target, cleaned.clone());
+                    revisions.push(NewSegmentRevisionArgs {
+                        transunit_id: unit.transunit_id.clone(),
+                        previous_target,
+                        new_target: cleaned,
+                    });
+                }
+                Ok((revisions, removed))
+            },
+        )
+        .await?;
+
+        let updated_segments = revisions.len();
+        if !revisions.is_empty() {
+            let cleaned_ids = revisions
+                .iter()
+                .map(|revision| revision.transunit_id.clone())
+                .collect();
+            db.replace_qa_findings(ReplaceQaFindingsArgs {
+                project_uuid,
+                jliff_rel_path: payload.jliff_rel_path.clone(),
+                check_type: BIDI_CONTROLS_CHECK.into(),
+                transunit_ids: Some(cleaned_ids),
+                findings: Vec::new(),
+            })
+            .await
+            .map_err(IpcError::from)?;
+            db.record_segment_revisions(RecordSegmentRevisionsArgs {
+                project_uuid,
+                jliff_rel_path: payload.jliff_rel_path.clone(),
+                origin: BIDI_SANITIZE_ORIGIN.to_string(),
+                author: None,
+                revisions,
+            })
+            .await
+            .map_err(IpcError::from)?;
+        }
+
+        Ok(BidiSanitizeDto {
+            jliff_rel_path: payload.jliff_rel_path,
+            updated_segments,
+            removed_controls,
+        })
+    })
+    .await
+}
+
+/// Runs the bidi control check on `document` and replaces its stored findings.
+pub(super) async fn run_bidi_controls_check(
+    db: &DbManager,
+    project_uuid: Uuid,
+    jliff_rel_path: String,
+    document: &JliffDocument,
+) -> Result<BidiControlsQaDto, IpcError> {
+    let check = project_qa_profile(db, project_uuid)
+        .await?
+        .check(BIDI_CONTROLS_CHECK, "warning");
+
+    let mut findings = Vec::new();
+    let mut segments_checked = 0;
+    for unit in document.transunits.iter().filter(|_| check.enabled) {
+        let target = unit.effective_target();
+        if target.trim().is_empty() {
+            continue;
+        }
+        segments_checked += 1;
+
+        let direction = target_direction(&document, unit);
+        let issues = find_bidi_issues(target, direction);
+        if issues.is_empty() {
+            continue;
+        }
+        let summary = issues
+            .iter()
+            .map(|issue| {
+                format!(
+                    "{} at {} {}",
+                    issue.control,
+                    issue.offset,
+                    issue.kind.describe()
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("; ");
+        findings.push(NewQaFindingArgs {
+            transunit_id: unit.transunit_id.clone(),
+            severity: check.severity.clone(),
+            message: format!("Stray bidi control characters: {summary}."),
+            details: Some(json!({ "direction": direction.as_str(), "issues": issues }).to_string()),
+        });
+    }
+
+    let records = db
+        .replace_qa_findings(ReplaceQaFindingsArgs {
+            project_uuid,
+            jliff_rel_path: jliff_rel_path.clone(),
+            check_type: BIDI_CONTROLS_CHECK.into(),
+            transunit_ids: None,
+            findings,
+        })
+        .await
+        .map_err(IpcError::from)?;
+
+    Ok(BidiControlsQaDto {
+        jliff_rel_path,
+        target_direction: direction_for_language(&document.target_language)
+            .as_str()
+            .to_string(),
+        segments_checked,
+        findings: records.into_iter().map(map_qa_finding_record).collect(),
+    })
+}
+
+fn parse_uuid(value: &str, field: &str) -> Result<Uuid, IpcError> {
+    Uuid::parse_str(value)
+        .map_err(|_| IpcError::Validation(format!("invalid {field}: expected UUID, got '{value}'")))
+}
@@ -1,35 +1,83 @@
+use std::path::Path;
+
+use log::warn;
 use tauri::State;
 use uuid::Uuid;
 
+use super::sensitive_actions_v2::redeem_confirmation;
+use crate::address::{format_postal_address, normalize_postal_address};
+use crate::currency::normalize_currency;
 use crate::db::DbManager;
-use crate::db::types::{ClientRecord, NewClientArgs, UpdateClientArgs};
-use crate::ipc::dto::{ClientDto, CreateClientPayload, UpdateClientPayload};
+use crate::db::encryption::generate_key;
+use crate::db::pii::{FieldCipher, PII_KEY_SECRET};
+use crate::db::types::{
+    ClientRecord, NewClientArgs, PostalAddress, UpdateClientArgs, VatValidation,
+};
+use crate::db::utils::now_iso8601;
+use crate::ipc::dto::{
+    ClientDto, CreateClientPayload, FormatClientAddressPayload, FormattedAddressDto,
+    PostalAddressDto, TaxIdValidationDto, UpdateClientPayload,
+};
 use crate::ipc::error::{IpcError, IpcResult};
+use crate::ipc::state::{OfflineMode, SensitiveActionTokens};
+use crate::metrics;
+use crate::secrets::{SecretsVault, workspace_secret};
+use crate::settings::SettingsManager;
+use crate::tax::vies::{ViesClient, ViesRegistration};
+use crate::tax::{TaxIdCheck, TaxIdStatus, check_tax_id};
 
 #[tauri::command]
 pub async fn create_client_record_v2(
     db: State<'_, DbManager>,
+    settings: State<'_, SettingsManager>,
+    vies: State<'_, ViesClient>,
+    offline: State<'_, OfflineMode>,
     payload: CreateClientPayload,
 ) -> IpcResult<ClientDto> {
-    let args = map_new_client_args(payload)?;
-    let record = db
-        .create_client_record(args)
-        .await
-        .map_err(IpcError::from)?;
-    Ok(map_client_record(record))
+    metrics::track("create_client_record_v2", async {
+        let mut args = map_new_client_args(payload)?;
+        if let Some(raw) = args.vat_number.take().filter(|raw| !raw.trim().is_empty()) {
+            let (check, _) = verify_vat_number(&settings, &vies, &offline, &raw).await?;
+            args.vat_validation = vat_validation(&check);
+            args.vat_number = Some(check.normalized);
+        }
+        let record = db
+            .create_client_record(args)
+            .await
+            .map_err(IpcError::from)?;
+        Ok(map_client_record(record))
+    })
+    .await
 }
 
 #[tauri::command]
 pub async fn update_client_record_v2(
     db: State<'_, DbManager>,
+    settings: State<'_, SettingsManager>,
+    vies: State<'_, ViesClient>,
+    offline: State<'_, OfflineMode>,
     payload: UpdateClientPayload,
 ) -> IpcResult<Option<ClientDto>> {
-    let args = map_update_client_args(payload)?;
-    let record = db
-        .update_client_record(args)
-        .await
-        .map_err(IpcError::from)?;
-    Ok(record.map(map_client_record))
+    metrics::track("update_client_record_v2", async {
+        let mut args = map_update_client_args(payload)?;
+        if let Some(vat_number) = args.vat_number.take() {
+            let (vat_number, validation) = match vat_number.filter(|raw| !raw.trim().is_empty()) {
+                Some(raw) => {
+                    let (check, _) = verify_vat_number(&settings, &vies, &offline, &raw).await?;
+                    (Some(check.normalized.clone()), vat_validation(&check))
+                }
+                None => (None, VatValidation::default()),
+            };
+            args.vat_number = Some(vat_number);
+            args.vat_validation = Some(validation);
+        }
+        let record = db
+            .update_client_record(args)
+            .await
+            .map_err(IpcError::from)?;
+        Ok(record.map(map_client_record))
+    })
+    .await
 }
 
 #[tauri::command]
@@ -37,11 +85,14 @@ pub async fn delete_client_record_v2(
     db: State<'_, DbManager>,
     client_uuid: String,
 ) -> IpcResult<()> {
-    let uuid = parse_uuid(&client_uuid, "clientUuid")?;
-    db.delete_client_record(uuid)
-        .await
-        .map_err(IpcError::from)?;
-    Ok(())
+    metrics::track("delete_client_record_v2", async {
+        let uuid = parse_uuid(&client_uuid, "clientUuid")?;
+        db.delete_client_record(uuid)
+            .await
+            .map_err(IpcError::from)?;
+        Ok(())
+    })
+    .await
 }
 
 #[tauri::command]
@@ -49,15 +100,201 @@ pub async fn get_client_record_v2(
     db: State<'_, DbManager>,
     client_uuid: String,
 ) -> IpcResult<Option<ClientDto>> {
-    let uuid = parse_uuid(&client_uuid, "clientUuid")?;
-    let record = db.get_client_record(uuid).await.map_err(IpcError::from)?;
-    Ok(record.map(map_client_record))
+    metrics::track("get_client_record_v2", async {
+        let uuid = parse_uuid(&client_uuid, "clientUuid")?;
+        let record = db.get_client_record(uuid).await.map_err(IpcError::from)?;
+        Ok(record.map(map_client_record))
+    })
+    .await
+}
+
+/// Lists clients; `query` keeps those whose name contains it, ignoring case and accents.
+#[tauri::command]
+pub async fn list_client_records_v2(
+    db: State<'_, DbManager>,
+    query: Option<String>,
+) -> IpcResult<Vec<ClientDto>> {
+    metrics::track("list_client_records_v2", async {
+        let records = db
+            .list_client_records(query.as_deref())
+            .await
+            .map_err(IpcError::from)?;
+        Ok(records.into_iter().map(map_client_record).collect())
+    })
+    .await
 }
 
+/// Replaces the key sealing client contact details and notes of the current workspace with a
+/// new one and re-seals every client with it. Returns how many clients were rewritten. Needs
+/// a confirmation token from `request_sensitive_action_v2`.
 #[tauri::command]
-pub async fn list_client_records_v2(db: State<'_, DbManager>) -> IpcResult<Vec<ClientDto>> {
-    let records = db.list_client_records().await.map_err(IpcError::from)?;
-    Ok(records.into_iter().map(map_client_record).collect())
+pub async fn rotate_client_pii_key_v2(
+    db: State<'_, DbManager>,
+    settings: State<'_, SettingsManager>,
+    vault: State<'_, SecretsVault>,
+    tokens: State<'_, SensitiveActionTokens>,
+    confirmation_token: String,
+) -> IpcResult<u64> {
+    metrics::track("rotate_client_pii_key_v2", async {
+        redeem_confirmation(&tokens, &confirmation_token, "rotate_client_pii_key_v2")?;
+        let secret = workspace_secret(PII_KEY_SECRET, &settings.current().await.app_folder);
+        let previous = vault.read(&secret).map_err(|error| {
+            warn!(target: "ipc::clients", "failed to read the client data key: {error}");
+            IpcError::Internal("Unable to read the client data key from the keychain.".into())
+        })?;
+        let key = generate_key();
+        let cipher = FieldCipher::from_hex(&key)
+            .ok_or_else(|| IpcError::Internal("Generated an invalid client data key.".into()))?;
+        // The new key is stored first: rows sealed with a key that is not kept are lost.
+        vault.store(&secret, &key).map_err(|error| {
+            warn!(target: "ipc::clients", "failed to store the client data key: {error}");
+            IpcError::Internal("Unable to store the client data key in the keychain.".into())
+        })?;
+        match db.rotate_client_pii_key(cipher).await {
+            Ok(rewritten) => Ok(rewritten),
+            Err(error) => {
+                warn!(target: "ipc::clients", "failed to rotate the client data key: {error}");
+                let restored = match previous {
+                    Some(previous) => vault.store(&secret, &previous),
+                    None => vault.delete(&secret),
+                };
+                if let Err(error) = restored {
+                    warn!(target: "ipc::clients", "failed to restore the client data key: {error}");
+                }
+                Err(IpcError::from(error).into())
+            }
+        }
+    })
+    .await
+}
+
+/// Loads the client data key of the workspace in `app_folder`, at startup and when switching
+/// workspaces. On first use a key is created and the existing clients are sealed with it;
+/// when the vault is unavailable client data stays in plaintext.
+pub(crate) async fn load_client_pii_key(db: &DbManager, vault: &SecretsVault, app_folder: &Path) {
+    match vault.read_workspace(PII_KEY_SECRET, app_folder) {
+        Ok(Some(key)) => match FieldCipher::from_hex(&key) {
+            Some(cipher) => db.set_pii_cipher(cipher),
+            None => warn!(target: "ipc::clients", "ignoring malformed client data key"),
+        },
+        Ok(None) => {
+            let key = generate_key();
+            let Some(cipher) = FieldCipher::from_hex(&key) else {
+                return;
+            };
+            if let Err(error) = vault.store(&workspace_secret(PII_KEY_SECRET, app_folder), &key) {
+                warn!(target: "ipc::clients", "client data stays unencrypted: {error}");
+                return;
+            }
+            if let Err(error) = db.rotate_client_pii_key(cipher).await {
+                warn!(target: "ipc::clients", "failed to encrypt existing client data: {error}");
+                db.set_pii_cipher(cipher);
+            }
+        }
+        Err(error) => warn!(target: "ipc::clients", "client data stays unencrypted: {error}"),
+    }
+}
+
+/// Formats a client's address for reports and invoices in the layout of its country. Clients
+/// without a structured address fall back to their free-text address, one line per line.
+#[tauri::command]
+pub async fn format_client_address_v2(
+    db: State<'_, DbManager>,
+    payload: FormatClientAddressPayload,
+) -> IpcResult<FormattedAddressDto> {
+    metrics::track("format_client_address_v2", async {
+        let uuid = parse_uuid(&payload.client_uuid, "clientUuid")?;
+        let record = db
+            .get_client_record(uuid)
+            .await
+            .map_err(IpcError::from)?
+            .ok_or_else(|| IpcError::Validation(format!("Client {uuid} was not found.")))?;
+        let name = payload.include_name.then_some(record.name.as_str());
+        let lines = if record.postal_address.is_empty() {
+            name.into_iter()
+                .chain(record.address.as_deref().unwrap_or_default().lines())
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .map(str::to_string)
+                .collect()
+        } else {
+            format_postal_address(
+                name,
+                &record.postal_address,
+                payload.home_country_code.as_deref(),
+            )
+        };
+        Ok(FormattedAddressDto {
+            single_line: lines.join(", "),
+            lines,
+        })
+    })
+    .await
+}
+
+/// Checks a VAT or tax number without saving it, so forms can flag it while it is typed.
+/// EU numbers are confirmed in VIES when lookups are enabled.
+#[tauri::command]
+pub async fn validate_tax_id_v2(
+    settings: State<'_, SettingsManager>,
+    vies: State<'_, ViesClient>,
+    offline: State<'_, OfflineMode>,
+    tax_id: String,
+) -> IpcResult<TaxIdValidationDto> {
+    metrics::track("validate_tax_id_v2", async {
+        let (check, registration) = verify_vat_number(&settings, &vies, &offline, &tax_id).await?;
+        let (registered_name, registered_address) = registration
+            .map(|registration| (registration.name, registration.address))
+            .unwrap_or_default();
+        Ok(TaxIdValidationDto {
+            normalized: check.normalized,
+            status: check.status.as_str().to_string(),
+            eu_vat: check.eu_vat,
+            registered_name,
+            registered_address,
+        })
+    })
+    .await
+}
+
+/// Checks `raw` locally and, when VIES lookups are enabled, confirms EU VAT numbers there.
+/// Numbers VIES reports as unregistered are rejected; when VIES cannot answer, or the app is
+/// offline, the local result stands.
+async fn verify_vat_number(
+    settings: &SettingsManager,
+    vies: &ViesClient,
+    offline: &OfflineMode,
+    raw: &str,
+) -> Result<(TaxIdCheck, Option<ViesRegistration>), IpcError> {
+    let mut check = check_tax_id(raw).map_err(|error| IpcError::Validation(error.to_string()))?;
+    if !check.eu_vat || !settings.current().await.vies_lookup || offline.is_offline() {
+        return Ok((check, None));
+    }
+    match vies.lookup(&check).await {
+        Ok(registration) if !registration.valid => Err(IpcError::Validation(format!(
+            "{} is not registered for VAT according to VIES.",
+            check.normalized
+        ))),
+        Ok(registration) => {
+            check.status = TaxIdStatus::Vies;
+            Ok((check, Some(registration)))
+        }
+        Err(error) => {
+            warn!(
+                target: "ipc::clients",
+                "VIES lookup for {} failed: {error:#}",
+                check.normalized
+            );
+            Ok((check, None))
+        }
+    }
+}
+
+fn vat_validation(check: &TaxIdCheck) -> VatValidation {
+    VatValidation {
+        status: Some(check.status.as_str().to_string()),
+        checked_at: Some(now_iso8601()),
+    }
 }
 
 fn map_new_client_args(payload: CreateClientPayload) -> Result<NewClientArgs, IpcError> {
@@ -67,29 +304,75 @@ fn map_new_client_args(payload: CreateClientPayload) -> Result<NewClientArgs, Ip
         .map(|value| parse_uuid(value, "clientUuid"))
         .transpose()?
         .unwrap_or_else(Uuid::new_v4);
+    let postal_address = payload
+        .postal_address
+        .map(map_postal_address)
+        .transpose()?
+        .unwrap_or_default();
 
     Ok(NewClientArgs {
         client_uuid,
         name: payload.name,
         email: payload.email,
         phone: payload.phone,
-        address: payload.address,
+        address: payload
+            .address
+            .or_else(|| single_line_address(&postal_address)),
         vat_number: payload.vat_number,
         note: payload.note,
+        currency: payload
+            .currency
+            .as_deref()
+            .map(parse_currency)
+            .transpose()?,
+        postal_address,
+        vat_validation: VatValidation::default(),
     })
 }
 
 fn map_update_client_args(payload: UpdateClientPayload) -> Result<UpdateClientArgs, IpcError> {
     let client_uuid = parse_uuid(&payload.client_uuid, "clientUuid")?;
+    let postal_address = payload.postal_address.map(map_postal_address).transpose()?;
+    // A new structured address also rewrites the free-text one unless that is sent too.
+    let address = payload.address.or_else(|| {
+        postal_address
+            .as_ref()
+            .and_then(single_line_address)
+            .map(Some)
+    });
     Ok(UpdateClientArgs {
         client_uuid,
         name: payload.name,
         email: payload.email,
         phone: payload.phone,
-        address: payload.address,
+        address,
         vat_number: payload.vat_number,
         note: payload.note,
+        currency: payload
+            .currency
+            .map(|currency| currency.as_deref().map(parse_currency).transpose())
+            .transpose()?,
+        postal_address,
+        vat_validation: None,
+    })
+}
+
+fn map_postal_address(dto: PostalAddressDto) -> Result<PostalAddress, IpcError> {
+    normalize_postal_address(PostalAddress {
+        street: dto.street,
+        street_number: dto.street_number,
+        postal_code: dto.postal_code,
+        locality: dto.locality,
+        region: dto.region,
+        country_code: dto.country_code,
+        country: dto.country,
+        place_id: dto.place_id,
     })
+    .map_err(|error| IpcError::Validation(error.to_string()))
+}
+
+fn single_line_address(address: &PostalAddress) -> Option<String> {
+    (!address.is_empty()).then(|| format_postal_address(None, address, None).join(", "))
 }
 
 fn map_client_record(record: ClientRecord) -> ClientDto {
@@ -100,10 +383,30 @@ fn map_client_record(record: ClientRecord) -> ClientDto {
         phone: record.phone,
         address: record.address,
         vat_number: record.vat_number,
+        vat_status: record.vat_validation.status,
+        vat_checked_at: record.vat_validation.checked_at,
         note: record.note,
+        currency: record.currency,
+        postal_address: (!record.postal_address.is_empty()).then(|| {
+            let address = record.postal_address;
+            PostalAddressDto {
+                street: address.street,
+                street_number: address.street_number,
+                postal_code: address.postal_code,
+                locality: address.locality,
+                region: address.region,
+                country_code: address.country_code,
+                country: address.country,
+                place_id: address.place_id,
+            }
+        }),
     }
 }
 
+fn parse_currency(value: &str) -> Result<String, IpcError> {
+    normalize_currency(value).map_err(|error| IpcError::Validation(error.to_string()))
+}
+
 fn parse_uuid(value: &str, field: &str) -> Result<Uuid, IpcError> {
     Uuid::parse_str(value)
         .map_err(|_| IpcError::Validation(format!("invalid {field}: expected UUID, got '{value}'")))
@@ -0,0 +1,246 @@
+use std::collections::{BTreeMap, HashSet};
+
+use serde_json::json;
+use tauri::State;
+use uuid::Uuid;
+
+use super::qa_v2::project_qa_profile;
+use super::shared::update_project_jliff;
+use super::translation_memory_v2::load_pair_documents;
+use crate::db::DbManager;
+use crate::db::types::{
+    NewQaFindingArgs, NewSegmentRevisionArgs, RecordSegmentRevisionsArgs, ReplaceQaFindingsArgs,
+};
+use crate::ipc::dto::{
+    ApplyConsistencyVariantPayload, CheckConsistencyPayload, ConsistencyFixDto,
+    ConsistencyFixFileDto, ConsistencyQaDto, InconsistencyGroupDto, SegmentLocationDto,
+    TargetVariantDto,
+};
+use crate::ipc::error::{IpcError, IpcResult};
+use crate::jliff::consistency::{
+    InconsistencyGroup, InconsistencyKind, SegmentLocation, find_inconsistencies,
+};
+use crate::jliff::placeholder_consistency::placeholder_tokens;
+use crate::metrics;
+use crate::settings::SettingsManager;
+
+/// QA check identifier used when persisting consistency findings.
+const CONSISTENCY_CHECK: &str = "consistency";
+/// Revision origin of targets harmonized by [`apply_consistency_variant_v2`].
+const CONSISTENCY_ORIGIN: &str = "consistency";
+
+/// Finds identical sources translated differently, and near-identical sources with very
+/// different translations, across every document of a language pair. Replaces the stored
+/// findings of the check on each document.
+#[tauri::command]
+pub async fn check_consistency_v2(
+    db: State<'_, DbManager>,
+    settings: State<'_, SettingsManager>,
+    payload: CheckConsistencyPayload,
+) -> IpcResult<ConsistencyQaDto> {
+    metrics::track("check_consistency_v2", async {
+        let project_uuid = parse_uuid(&payload.project_uuid, "projectUuid")?;
+        let documents = load_pair_documents(
+            db.inner(),
+            settings.inner(),
+            project_uuid,
+            &payload.source_lang,
+            &payload.target_lang,
+        )
+        .await?;
+        let check = project_qa_profile(db.inner(), project_uuid)
+            .await?
+            .check(CONSISTENCY_CHECK, "warning");
+
+        let groups = if check.enabled {
+            find_inconsistencies(&documents)
+        } else {
+            Vec::new()
+        };
+
+        let mut findings: BTreeMap<&str, Vec<NewQaFindingArgs>> = documents
+            .iter()
+            .map(|(jliff_rel_path, _)| (jliff_rel_path.as_str(), Vec::new()))
+            .collect();
+        for group in &groups {
+            let message = match group.kind {
+                InconsistencyKind::SameSource => format!(
+                    "The same source is translated {} different ways in the project.",
+                    group.variants.len()
+                ),
+                InconsistencyKind::SimilarSource => format!(
+                    "A near-identical source ({}% similar) is translated very differently.",
+                    group.source_similarity
+                ),
+            };
+            let details = json!({
+                "kind": group.kind.as_str(),
+                "targets": group.variants.iter().map(|variant| &variant.target).collect::<Vec<_>>(),
+            })
+            .to_string();
+            for location in group.variants.iter().flat_map(|variant| &variant.locations) {
+                if let Some(document_findings) = findings.get_mut(location.jliff_rel_path.as_str())
+                {
+                    document_findings.push(NewQaFindingArgs {
+                        transunit_id: location.transunit_id.clone(),
+                        severity: check.severity.clone(),
+                        message: message.clone(),
+                        details: Some(details.clone()),
+                    });
+                }
+            }
+        }
+
+        let mut findings_recorded = 0;
+        for (jliff_rel_path, findings) in findings {
+            findings_recorded += db
+                .replace_qa_findings(ReplaceQaFindingsArgs {
+                    project_uuid,
+                    jliff_rel_path: jliff_rel_path.to_string(),
+                    check_type: CONSISTENCY_CHECK.into(),
+                    transunit_ids: None,
+                    findings,
+                })
+                .await
+                .map_err(IpcError::from)?
+                .len();
+        }
+
+        Ok(ConsistencyQaDto {
+            groups: groups.into_iter().map(map_group).collect(),
+            findings_recorded,
+        })
+    })
+    .await
+}
+
+/// Writes the chosen target variant to the given segments, records the changes as
+/// revisions and clears their consistency findings. A segment is skipped when the variant's
+/// placeholders match neither its source nor its current target.
+#[tauri::command]
+pub async fn apply_consistency_variant_v2(
+    db: State<'_, DbManager>,
+    settings: State<'_, SettingsManager>,
+    payload: ApplyConsistencyVariantPayload,
+) -> IpcResult<ConsistencyFixDto> {
+    metrics::track("apply_consistency_variant_v2", async {
+        let project_uuid = parse_uuid(&payload.project_uuid, "projectUuid")?;
+        if payload.target.trim().is_empty() {
+            return Err(IpcError::Validation("The chosen target cannot be empty.".into()).into());
+        }
+        let mut by_document: BTreeMap<String, HashSet<String>> = BTreeMap::new();
+        for segment in payload.segments {
+            by_document
+                .entry(segment.jliff_rel_path)
+                .or_default()
+                .insert(segment.transunit_id);
+        }
+
+        let mut files = Vec::new();
+        for (jliff_rel_path, transunit_ids) in by_document {
+            let target = payload.target.clone();
+            let (revisions, skipped_transunit_ids) = update_project_jliff(
+                db.inner(),
+                settings.inner(),
+                project_uuid,
+                &jliff_rel_path,
+                move |document| {
+                    let mut revisions = Vec::new();
+                    let mut skipped = Vec::new();
+                    for unit in &mut document.transunits {
+                        if !transunit_ids.contains(&unit.transunit_id)
+                            || unit.effective_target() == target
+                        {
+                            continue;
+                        }
+                        if placeholder_tokens(unit.effective_target())
+                            != placeholder_tokens(&target)
+                            && placeholder_tokens(&unit.source) != placeholder_tokens(&target)
+                        {
+                            skipped.push(unit.transunit_id.clone());
+                            continue;
+                        }
+                        let previous_target =
+                            std::mem::replace(unit.effective_target_mut(), target.clone());
+                        revisions.push(NewSegmentRevisionArgs {
+                            transunit_id: unit.transunit_id.clone(),
+                            previous_target,
+                            new_target: target.clone(),
+                        });
+                    }
+                    Ok((revisions, skipped))
+                },
+            )
+            .await?;
+            if revisions.is_empty() && skipped_transunit_ids.is_empty() {
+                continue;
+            }
+
+            files.push(ConsistencyFixFileDto {
+                jliff_rel_path: jliff_rel_path.clone(),
+                updated_segments: revisions.len(),
+                skipped_transunit_ids,
+            });
+            if revisions.is_empty() {
+                continue;
+            }
+            db.replace_qa_findings(ReplaceQaFindingsArgs {
+                project_uuid,
+                jliff_rel_path: jliff_rel_path.clone(),
+                check_type: CONSISTENCY_CHECK.into(),
+                transunit_ids: Some(
+                    revisions
+                        .iter()
+                        .map(|revision| revision.transunit_id.clone())
+                        .collect(),
+                ),
+                findings: Vec::new(),
+            })
+            .await
+            .map_err(IpcError::from)?;
+            db.record_segment_revisions(RecordSegmentRevisionsArgs {
+                project_uuid,
+                jliff_rel_path,
+                origin: CONSISTENCY_ORIGIN.to_string(),
+                author: None,
+                revisions,
+            })
+            .await
+            .map_err(IpcError::from)?;
+        }
+
+        Ok(ConsistencyFixDto {
+            updated_segments: files.iter().map(|file| file.updated_segments).sum(),
+            files,
+        })
+    })
+    .await
+}
+
+fn map_group(group: InconsistencyGroup) -> InconsistencyGroupDto {
+    InconsistencyGroupDto {
+        kind: group.kind.as_str().to_string(),
+        source_similarity: group.source_similarity,
+        variants: group
+            .variants
+            .into_iter()
+            .map(|variant| TargetVariantDto {
+                source: variant.source,
+                target: variant.target,
+                locations: variant.locations.into_iter().map(map_location).collect(),
+            })
+            .collect(),
+    }
+}
+
+fn map_location(location: SegmentLocation) -> SegmentLocationDto {
+    SegmentLocationDto {
+        jliff_rel_path: location.jliff_rel_path,
+        transunit_id: location.transunit_id,
+    }
+}
+
+fn parse_uuid(value: &str, field: &str) -> Result<Uuid, IpcError> {
+    Uuid::parse_str(value)
+        .map_err(|_| IpcError::Validation(format!("invalid {field}: expected UUID, got '{value}'")))
+}
@@ -0,0 +1,135 @@
+use std::time::Duration;
+
+use chrono::Utc;
+use tauri::{AppHandle, Manager, State};
+
+use super::offline_v2::ensure_online;
+use crate::crash::{CrashReport, CrashReporter};
+use crate::http;
+use crate::ipc::dto::{CrashReportDto, CrashReportsDto, UploadCrashReportsDto};
+use crate::ipc::error::{IpcError, IpcResult};
+use crate::ipc::state::OfflineMode;
+use crate::metrics;
+use crate::settings::SettingsManager;
+use crate::support::Anonymizer;
+
+/// Environment variable holding the endpoint crash reports are posted to.
+const CRASH_REPORT_URL_VAR: &str = "CRASH_REPORT_URL";
+
+/// Lists the local crash reports, newest first, and tells whether the previous session
+/// ended without shutting down cleanly.
+#[tauri::command]
+pub async fn list_crash_reports_v2(
+    settings: State<'_, SettingsManager>,
+    crash: State<'_, CrashReporter>,
+) -> IpcResult<CrashReportsDto> {
+    metrics::track("list_crash_reports_v2", async {
+        let reports = crash.list_reports().map_err(|error| {
+            IpcError::Internal(format!("Failed to read crash reports: {error}"))
+        })?;
+        Ok(CrashReportsDto {
+            previous_session_report_id: crash.previous_session_report().map(|id| id.to_string()),
+            upload_available: settings.current().await.crash_report_upload
+                && crash_report_url().is_some(),
+            reports: reports.into_iter().map(map_crash_report).collect(),
+        })
+    })
+    .await
+}
+
+/// Sends the reports not uploaded yet to the crash report endpoint. Requires the user to
+/// have opted in; paths and e-mail addresses are masked before anything leaves the machine.
+#[tauri::command]
+pub async fn upload_crash_reports_v2(
+    app: AppHandle,
+    settings: State<'_, SettingsManager>,
+    crash: State<'_, CrashReporter>,
+) -> IpcResult<UploadCrashReportsDto> {
+    metrics::track("upload_crash_reports_v2", async {
+        let current = settings.current().await;
+        if !current.crash_report_upload {
+            return Err(IpcError::Validation(
+                "Enable crash report uploads in the settings first.".into(),
+            )
+            .into());
+        }
+        let url = crash_report_url().ok_or_else(|| {
+            IpcError::Validation(format!(
+                "No crash report endpoint is configured ({CRASH_REPORT_URL_VAR})."
+            ))
+        })?;
+        ensure_online(&app.state::<OfflineMode>(), "Uploading crash reports")?;
+        let anonymizer =
+            Anonymizer::new(app.path().home_dir().ok().as_deref(), &current.app_folder);
+        let pending: Vec<CrashReport> = crash
+            .list_reports()
+            .map_err(|error| IpcError::Internal(format!("Failed to read crash reports: {error}")))?
+            .into_iter()
+            .filter(|report| report.uploaded_at.is_none())
+            .collect();
+
+        let client = http::client_builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .map_err(|error| IpcError::Internal(format!("HTTP client unavailable: {error}")))?;
+        let mut uploaded = Vec::new();
+        for mut report in pending {
+            report.message = anonymizer.apply(&report.message);
+            for text in [
+                &mut report.thread,
+                &mut report.location,
+                &mut report.backtrace,
+            ]
+            .into_iter()
+            .flatten()
+            {
+                *text = anonymizer.apply(text);
+            }
+            client
+                .post(&url)
+                .json(&report)
+                .send()
+                .await
+                .and_then(|response| response.error_for_status())
+                .map_err(|error| {
+                    log::warn!(target: "ipc::crash", "crash report upload failed: {error}");
+                    IpcError::Internal(format!(
+                        "Failed to upload crash reports after {} of them: {error}",
+                        uploaded.len()
+                    ))
+                })?;
+            crash
+                .mark_uploaded(report.id, Utc::now())
+                .map_err(|error| {
+                    IpcError::Internal(format!("Failed to update crash report: {error}"))
+                })?;
+            uploaded.push(report.id.to_string());
+        }
+        Ok(UploadCrashReportsDto { uploaded })
+    })
+    .await
+}
+
+fn crash_report_url() -> Option<String> {
+    std::env::var(CRASH_REPORT_URL_VAR)
+        .ok()
+        .map(|url| url.trim().to_string())
+        .filter(|url| url.starts_with("https://") || url.starts_with("http://"))
+}
+
+fn map_crash_report(report: CrashReport) -> CrashReportDto {
+    CrashReportDto {
+        id: report.id.to_string(),
+        kind: report.kind.as_str().to_string(),
+        occurred_at: report.occurred_at.to_rfc3339(),
+        session_started_at: report.session_started_at.map(|at| at.to_rfc3339()),
+        app_version: report.app_version,
+        os: report.os,
+        arch: report.arch,
+        thread: report.thread,
+        message: report.message,
+        location: report.location,
+        backtrace: report.backtrace,
+        uploaded_at: report.uploaded_at.map(|at| at.to_rfc3339()),
+    }
+}
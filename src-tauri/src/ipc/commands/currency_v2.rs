@@ -0,0 +1,219 @@
+use chrono::{NaiveDate, Utc};
+use tauri::State;
+
+use super::offline_v2::ensure_online;
+use crate::currency::{RateTable, ecb, normalize_currency, round_amount};
+use crate::db::DbManager;
+use crate::db::types::{ExchangeRateRecord, NewExchangeRateArgs};
+use crate::ipc::dto::{
+    ConvertAmountsPayload, ConvertedAmountDto, ConvertedAmountsDto, DeleteExchangeRatePayload,
+    ExchangeRateDto, SetExchangeRatePayload,
+};
+use crate::ipc::error::{IpcError, IpcResult};
+use crate::ipc::state::OfflineMode;
+use crate::metrics;
+use crate::settings::SettingsManager;
+
+/// Lists the rate in effect for every currency pair on `on_date` (today when omitted).
+#[tauri::command]
+pub async fn list_exchange_rates_v2(
+    db: State<'_, DbManager>,
+    on_date: Option<String>,
+) -> IpcResult<Vec<ExchangeRateDto>> {
+    metrics::track("list_exchange_rates_v2", async {
+        let on_date = parse_date(on_date.as_deref(), "onDate")?;
+        let records = db
+            .exchange_rates_on(&on_date)
+            .await
+            .map_err(IpcError::from)?;
+        Ok(records.into_iter().map(map_exchange_rate).collect())
+    })
+    .await
+}
+
+/// Lists every snapshot of a currency pair, newest first.
+#[tauri::command]
+pub async fn list_exchange_rate_history_v2(
+    db: State<'_, DbManager>,
+    base_currency: String,
+    quote_currency: String,
+) -> IpcResult<Vec<ExchangeRateDto>> {
+    metrics::track("list_exchange_rate_history_v2", async {
+        let base_currency = parse_currency(&base_currency)?;
+        let quote_currency = parse_currency(&quote_currency)?;
+        let records = db
+            .exchange_rate_history(&base_currency, &quote_currency)
+            .await
+            .map_err(IpcError::from)?;
+        Ok(records.into_iter().map(map_exchange_rate).collect())
+    })
+    .await
+}
+
+/// Records a manual rate for a day, replacing any snapshot of that pair and day, and
+/// returns the pair's history.
+#[tauri::command]
+pub async fn set_exchange_rate_v2(
+    db: State<'_, DbManager>,
+    payload: SetExchangeRatePayload,
+) -> IpcResult<Vec<ExchangeRateDto>> {
+    metrics::track("set_exchange_rate_v2", async {
+        let base_currency = parse_currency(&payload.base_currency)?;
+        let quote_currency = parse_currency(&payload.quote_currency)?;
+        if base_currency == quote_currency {
+            return Err(IpcError::Validation(
+                "The two currencies of a rate must differ.".into(),
+            ));
+        }
+        if !payload.rate.is_finite() || payload.rate <= 0.0 {
+            return Err(IpcError::Validation(
+                "The rate must be a positive number.".into(),
+            ));
+        }
+        let rate_date = parse_date(payload.rate_date.as_deref(), "rateDate")?;
+        db.record_exchange_rates(&[NewExchangeRateArgs {
+            base_currency: base_currency.clone(),
+            quote_currency: quote_currency.clone(),
+            rate_date,
+            rate: payload.rate,
+            source: "manual".into(),
+        }])
+        .await
+        .map_err(IpcError::from)?;
+        let records = db
+            .exchange_rate_history(&base_currency, &quote_currency)
+            .await
+            .map_err(IpcError::from)?;
+        Ok(records.into_iter().map(map_exchange_rate).collect())
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn delete_exchange_rate_v2(
+    db: State<'_, DbManager>,
+    payload: DeleteExchangeRatePayload,
+) -> IpcResult<bool> {
+    metrics::track("delete_exchange_rate_v2", async {
+        let base_currency = parse_currency(&payload.base_currency)?;
+        let quote_currency = parse_currency(&payload.quote_currency)?;
+        let rate_date = parse_date(Some(&payload.rate_date), "rateDate")?;
+        db.delete_exchange_rate(&base_currency, &quote_currency, &rate_date)
+            .await
+            .map_err(IpcError::from)
+    })
+    .await
+}
+
+/// Fetches the European Central Bank's latest euro reference rates, stores them as a
+/// snapshot of their publication day, and returns the rates in effect on that day.
+#[tauri::command]
+pub async fn refresh_exchange_rates_v2(
+    db: State<'_, DbManager>,
+    offline: State<'_, OfflineMode>,
+) -> IpcResult<Vec<ExchangeRateDto>> {
+    metrics::track("refresh_exchange_rates_v2", async {
+        ensure_online(&offline, "Refreshing exchange rates")?;
+        let fetched = ecb::fetch_reference_rates().await.map_err(|error| {
+            IpcError::Internal(format!(
+                "The exchange rates could not be fetched: {error:#}"
+            ))
+        })?;
+        let rate_date = fetched[0].rate_date.clone();
+        let rates: Vec<NewExchangeRateArgs> = fetched
+            .into_iter()
+            .map(|rate| NewExchangeRateArgs {
+                base_currency: "EUR".into(),
+                quote_currency: rate.currency,
+                rate_date: rate.rate_date,
+                rate: rate.rate,
+                source: "ecb".into(),
+            })
+            .collect();
+        db.record_exchange_rates(&rates)
+            .await
+            .map_err(IpcError::from)?;
+        let records = db
+            .exchange_rates_on(&rate_date)
+            .await
+            .map_err(IpcError::from)?;
+        Ok(records.into_iter().map(map_exchange_rate).collect())
+    })
+    .await
+}
+
+/// Converts amounts in any currency into one and totals them with the rates in effect on a
+/// day, for reports that present totals in the agency's base currency.
+#[tauri::command]
+pub async fn convert_amounts_v2(
+    db: State<'_, DbManager>,
+    settings: State<'_, SettingsManager>,
+    payload: ConvertAmountsPayload,
+) -> IpcResult<ConvertedAmountsDto> {
+    metrics::track("convert_amounts_v2", async {
+        let currency = match payload.target_currency.as_deref() {
+            Some(code) => parse_currency(code)?,
+            None => settings.current().await.base_currency,
+        };
+        let on_date = parse_date(payload.on_date.as_deref(), "onDate")?;
+        let table = RateTable::new(
+            db.exchange_rates_on(&on_date)
+                .await
+                .map_err(IpcError::from)?,
+        );
+
+        let mut lines = Vec::with_capacity(payload.amounts.len());
+        for money in payload.amounts {
+            let from = parse_currency(&money.currency)?;
+            let (converted, conversion) = table
+                .convert(money.amount, &from, &currency)
+                .ok_or_else(|| {
+                    IpcError::Validation(format!(
+                        "No exchange rate from {from} to {currency} on or before {on_date}."
+                    ))
+                })?;
+            lines.push(ConvertedAmountDto {
+                amount: money.amount,
+                currency: from,
+                converted,
+                rate: conversion.rate,
+                rate_date: conversion.rate_date,
+            });
+        }
+        Ok(ConvertedAmountsDto {
+            total: round_amount(lines.iter().map(|line| line.converted).sum(), &currency),
+            currency,
+            lines,
+        })
+    })
+    .await
+}
+
+fn map_exchange_rate(record: ExchangeRateRecord) -> ExchangeRateDto {
+    ExchangeRateDto {
+        base_currency: record.base_currency,
+        quote_currency: record.quote_currency,
+        rate_date: record.rate_date,
+        rate: record.rate,
+        source: record.source,
+        recorded_at: record.recorded_at,
+    }
+}
+
+fn parse_currency(value: &str) -> Result<String, IpcError> {
+    normalize_currency(value).map_err(|error| IpcError::Validation(error.to_string()))
+}
+
+/// `YYYY-MM-DD` of `value`, or today (UTC) when absent.
+fn parse_date(value: Option<&str>, field: &str) -> Result<String, IpcError> {
+    match value.map(str::trim) {
+        Some(value) => NaiveDate::parse_from_str(value, "%Y-%m-%d")
+            .map(|date| date.to_string())
+            .map_err(|_| {
+                IpcError::Validation(format!(
+                    "invalid {field}: expected YYYY-MM-DD, got '{value}'"
+                ))
+            }),
+        None => Ok(Utc::now().date_naive().to_string()),
+    }
+}
@@ -0,0 +1,373 @@
+use chrono::Utc;
+use log::warn;
+use tauri::ipc::InvokeError;
+use tauri::{AppHandle, Emitter, Manager, State};
+use uuid::Uuid;
+
+use super::offline_v2::queue_offline_operation;
+use super::projects_v2::resolve_project_root;
+use crate::db::DbManager;
+use crate::db::types::{
+    DeliveryReceiptRecord, DeliveryTargetRecord, NewDeliveryReceiptArgs, NewDeliveryTargetArgs,
+};
+use crate::delivery::transports::{self, TargetSettings, TransferProgress};
+use crate::ipc::dto::{
+    DeliveryReceiptDto, DeliveryTargetDto, DeliveryUploadEventPayload, SaveDeliveryTargetPayload,
+    UploadDeliveryPackagePayload,
+};
+use crate::ipc::error::{IpcError, IpcResult};
+use crate::ipc::events::DELIVERY_UPLOAD_PROGRESS;
+use crate::ipc::state::OfflineMode;
+use crate::metrics;
+use crate::secrets::SecretsVault;
+use crate::settings::SettingsManager;
+
+const RECEIPT_UPLOADED: &str = "uploaded";
+const RECEIPT_FAILED: &str = "failed";
+
+/// Lists the SFTP servers and S3 buckets a client receives packages on.
+#[tauri::command]
+pub async fn list_delivery_targets_v2(
+    db: State<'_, DbManager>,
+    vault: State<'_, SecretsVault>,
+    client_uuid: String,
+) -> IpcResult<Vec<DeliveryTargetDto>> {
+    metrics::track("list_delivery_targets_v2", async {
+        let client_uuid = parse_uuid(&client_uuid, "clientUuid")?;
+        let records = db
+            .list_delivery_targets(client_uuid)
+            .await
+            .map_err(IpcError::from)?;
+        let targets = records
+            .into_iter()
+            .map(|record| map_delivery_target_record(record, &vault))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(targets)
+    })
+    .await
+}
+
+/// Creates or updates a delivery target. Its secret goes to the secrets vault; the database
+/// only keeps the connection settings.
+#[tauri::command]
+pub async fn save_delivery_target_v2(
+    db: State<'_, DbManager>,
+    vault: State<'_, SecretsVault>,
+    payload: SaveDeliveryTargetPayload,
+) -> IpcResult<DeliveryTargetDto> {
+    metrics::track("save_delivery_target_v2", async {
+        let client_uuid = parse_uuid(&payload.client_uuid, "clientUuid")?;
+        let name = payload.name.trim().to_string();
+        if name.is_empty() {
+            return Err(IpcError::Validation("name is required.".into()).into());
+        }
+        payload
+            .settings
+            .validate()
+            .map_err(|error| IpcError::Validation(error.to_string()))?;
+        if db
+            .get_client_record(client_uuid)
+            .await
+            .map_err(IpcError::from)?
+            .is_none()
+        {
+            return Err(
+                IpcError::Validation(format!("Client {client_uuid} was not found.")).into(),
+            );
+        }
+
+        let target_uuid = match payload.target_uuid.as_deref() {
+            Some(value) => {
+                let target_uuid = parse_uuid(value, "targetUuid")?;
+                let existing = find_target(&db, target_uuid).await?;
+                if existing.client_uuid != client_uuid {
+                    return Err(IpcError::Validation(
+                        "A delivery target cannot be moved to another client.".into(),
+                    )
+                    .into());
+                }
+                target_uuid
+            }
+            None => Uuid::new_v4(),
+        };
+        let settings_json = serde_json::to_string(&payload.settings)
+            .map_err(|error| IpcError::Internal(error.to_string()))?;
+        let record = db
+            .upsert_delivery_target(NewDeliveryTargetArgs {
+                target_uuid,
+                client_uuid,
+                name,
+                transport: payload.settings.transport().to_string(),
+                settings_json,
+            })
+            .await
+            .map_err(IpcError::from)?;
+
+        let key = secret_key(target_uuid);
+        match payload.secret.as_deref() {
+            Some("") => vault.delete(&key),
+            Some(secret) => vault.store(&key, secret),
+            None => Ok(()),
+        }
+        .map_err(|error| IpcError::Internal(error.to_string()))?;
+
+        Ok(map_delivery_target_record(record, &vault)?)
+    })
+    .await
+}
+
+/// Deletes a delivery target and its secret. Receipts of earlier uploads are kept.
+#[tauri::command]
+pub async fn delete_delivery_target_v2(
+    db: State<'_, DbManager>,
+    vault: State<'_, SecretsVault>,
+    target_uuid: String,
+) -> IpcResult<()> {
+    metrics::track("delete_delivery_target_v2", async {
+        let target_uuid = parse_uuid(&target_uuid, "targetUuid")?;
+        if !db
+            .delete_delivery_target(target_uuid)
+            .await
+            .map_err(IpcError::from)?
+        {
+            return Err(IpcError::Validation(format!(
+                "Delivery target {target_uuid} was not found."
+            ))
+            .into());
+        }
+        vault
+            .delete(&secret_key(target_uuid))
+            .map_err(|error| IpcError::Internal(error.to_string()))?;
+        Ok(())
+    })
+    .await
+}
+
+/// Uploads a delivery package to one of the targets of the project's client. Progress is
+/// reported on `delivery:upload-progress`; the outcome is recorded as a receipt, which is
+/// returned for failed uploads too. While offline, the upload is queued and an `OFFLINE`
+/// error carrying the queued operation is returned instead.
+#[tauri::command]
+pub async fn upload_delivery_package_v2(
+    app: AppHandle,
+    db: State<'_, DbManager>,
+    settings: State<'_, SettingsManager>,
+    vault: State<'_, SecretsVault>,
+    payload: UploadDeliveryPackagePayload,
+) -> IpcResult<DeliveryReceiptDto> {
+    metrics::track("upload_delivery_package_v2", async {
+        let project_uuid = parse_uuid(&payload.project_uuid, "projectUuid")?;
+        let artifact_uuid = parse_uuid(&payload.artifact_uuid, "artifactUuid")?;
+        let target_uuid = parse_uuid(&payload.target_uuid, "targetUuid")?;
+        let bundle = db
+            .get_project_bundle(project_uuid)
+            .await
+            .map_err(IpcError::from)?
+            .ok_or_else(|| {
+                IpcError::Validation(format!("Project {project_uuid} was not found."))
+            })?;
+        let target = find_target(&db, target_uuid).await?;
+        if bundle.project.client_uuid != Some(target.client_uuid) {
+            return Err(IpcError::Validation(
+                "The delivery target does not belong to the client of the project.".into(),
+            )
+            .into());
+        }
+        let package = db
+            .list_delivery_packages(project_uuid)
+            .await
+            .map_err(IpcError::from)?
+            .into_iter()
+            .find(|package| package.artifact_uuid == artifact_uuid)
+            .ok_or_else(|| {
+                IpcError::Validation(format!("Delivery package {artifact_uuid} was not found."))
+            })?;
+        let project_root = resolve_project_root(db.inner(), settings.inner(), project_uuid).await?;
+        let path = project_root.join(&package.rel_path);
+        if !path.is_file() {
+            return Err(IpcError::Validation(format!(
+                "The package file {} is missing.",
+                package.rel_path
+            ))
+            .into());
+        }
+        if app.state::<OfflineMode>().is_offline() {
+            let queued = queue_offline_operation(
+                db.inner(),
+                "upload_delivery_package_v2",
+                &payload,
+                Some(project_uuid),
+            )
+            .await?;
+            return Err(InvokeError::from(queued));
+        }
+        let target_settings: TargetSettings = serde_json::from_str(&target.settings_json)
+            .map_err(|error| IpcError::Internal(error.to_string()))?;
+        let secret = vault
+            .read(&secret_key(target_uuid))
+            .map_err(|error| IpcError::Internal(error.to_string()))?;
+
+        let started_at = Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
+        let event = {
+            let upload_id = Uuid::new_v4().to_string();
+            let artifact_uuid = artifact_uuid.to_string();
+            let target_uuid = target_uuid.to_string();
+            move |state: &str, progress: TransferProgress, error: Option<String>| {
+                DeliveryUploadEventPayload {
+                    upload_id: upload_id.clone(),
+                    artifact_uuid: artifact_uuid.clone(),
+                    target_uuid: target_uuid.clone(),
+                    state: state.into(),
+                    sent: progress.sent,
+                    total: progress.total,
+                    error,
+                }
+            }
+        };
+        let on_progress = {
+            let app = app.clone();
+            let event = event.clone();
+            move |progress| emit_upload_event(&app, event("progress", progress, None))
+        };
+        let result = transports::upload(&target_settings, secret, &path, on_progress).await;
+
+        let mut receipt = NewDeliveryReceiptArgs {
+            receipt_uuid: Uuid::new_v4(),
+            artifact_uuid,
+            project_uuid,
+            target_uuid: Some(target_uuid),
+            transport: target.transport.clone(),
+            status: RECEIPT_UPLOADED.into(),
+            remote_location: None,
+            size_bytes: None,
+            sha256: None,
+            error: None,
+            started_at,
+        };
+        match result {
+            Ok(upload) => {
+                let size = upload.size_bytes;
+                emit_upload_event(
+                    &app,
+                    event(
+                        "completed",
+                        TransferProgress {
+                            sent: size,
+                            total: size,
+                        },
+                        None,
+                    ),
+                );
+                receipt.remote_location = Some(upload.remote_location);
+                receipt.size_bytes = Some(size as i64);
+                receipt.sha256 = Some(upload.sha256);
+            }
+            Err(error) => {
+                warn!(
+                    target: "ipc::delivery",
+                    "upload of package {artifact_uuid} to {} failed: {error}",
+                    target.name
+                );
+                emit_upload_event(
+                    &app,
+                    event(
+                        "failed",
+                        TransferProgress { sent: 0, total: 0 },
+                        Some(error.to_string()),
+                    ),
+                );
+                receipt.status = RECEIPT_FAILED.into();
+                receipt.error = Some(error.to_string());
+            }
+        }
+        let record = db
+            .record_delivery_receipt(receipt)
+            .await
+            .map_err(IpcError::from)?;
+        Ok(map_delivery_receipt_record(record))
+    })
+    .await
+}
+
+/// Lists the upload receipts of a project, newest first.
+#[tauri::command]
+pub async fn list_delivery_receipts_v2(
+    db: State<'_, DbManager>,
+    project_uuid: String,
+) -> IpcResult<Vec<DeliveryReceiptDto>> {
+    metrics::track("list_delivery_receipts_v2", async {
+        let project_uuid = parse_uuid(&project_uuid, "projectUuid")?;
+        let records = db
+            .list_delivery_receipts(project_uuid)
+            .await
+            .map_err(IpcError::from)?;
+        Ok(records
+            .into_iter()
+            .map(map_delivery_receipt_record)
+            .collect())
+    })
+    .await
+}
+
+/// Vault key of the secret of a target.
+fn secret_key(target_uuid: Uuid) -> String {
+    format!("delivery-target/{target_uuid}")
+}
+
+async fn find_target(db: &DbManager, target_uuid: Uuid) -> Result<DeliveryTargetRecord, IpcError> {
+    db.get_delivery_target(target_uuid)
+        .await
+        .map_err(IpcError::from)?
+        .ok_or_else(|| {
+            IpcError::Validation(format!("Delivery target {target_uuid} was not found."))
+        })
+}
+
+fn emit_upload_event(app: &AppHandle, payload: DeliveryUploadEventPayload) {
+    if let Err(error) = app.emit(DELIVERY_UPLOAD_PROGRESS, payload) {
+        warn!(target: "ipc::delivery", "failed to emit upload event: {error}");
+    }
+}
+
+fn map_delivery_target_record(
+    record: DeliveryTargetRecord,
+    vault: &SecretsVault,
+) -> Result<DeliveryTargetDto, IpcError> {
+    let settings = serde_json::from_str(&record.settings_json)
+        .map_err(|error| IpcError::Internal(error.to_string()))?;
+    let has_secret = vault
+        .read(&secret_key(record.target_uuid))
+        .map_err(|error| IpcError::Internal(error.to_string()))?
+        .is_some();
+    Ok(DeliveryTargetDto {
+        target_uuid: record.target_uuid.to_string(),
+        client_uuid: record.client_uuid.to_string(),
+        name: record.name,
+        settings,
+        has_secret,
+        created_at: record.created_at,
+        updated_at: record.updated_at,
+    })
+}
+
+fn map_delivery_receipt_record(record: DeliveryReceiptRecord) -> DeliveryReceiptDto {
+    DeliveryReceiptDto {
+        receipt_uuid: record.receipt_uuid.to_string(),
+        artifact_uuid: record.artifact_uuid.to_string(),
+        project_uuid: record.project_uuid.to_string(),
+        target_uuid: record.target_uuid.map(|uuid| uuid.to_string()),
+        transport: record.transport,
+        status: record.status,
+        remote_location: record.remote_location,
+        size_bytes: record.size_bytes,
+        sha256: record.sha256,
+        error: record.error,
+        started_at: record.started_at,
+        completed_at: record.completed_at,
+    }
+}
+
+fn parse_uuid(value: &str, field: &str) -> Result<Uuid, IpcError> {
+    Uuid::parse_str(value)
+        .map_err(|_| IpcError::Validation(format!("invalid {field}: expected UUID, got '{value}'")))
+}
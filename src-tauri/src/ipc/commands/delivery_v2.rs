@@ -0,0 +1,746 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use log::info;
+use tauri::State;
+use tauri::ipc::InvokeError;
+use uuid::Uuid;
+
+use super::output_naming_v2::OutputLocations;
+use super::projects_v2::{
+    language_pair_directory_name, map_project_file_bundle, resolve_project_root,
+};
+use super::qa_v2::map_qa_finding_record;
+use super::shared::{fs_error, list_project_jliff_paths, load_project_jliff};
+use crate::db::DbManager;
+use crate::db::types::{
+    ArtifactKind, ArtifactStatus, DeliveryPackageRecord, FileLanguagePairInput, NewArtifactArgs,
+    NewDeliveryGateOverrideArgs, NewDeliveryPackageArgs, NewFileInfoArgs, NewProjectFileArgs,
+    ProjectFileBundle,
+};
+use crate::delivery::return_package::{return_package_entries, return_package_file_name};
+use crate::delivery::{
+    BILINGUAL_FOLDER, DELIVERIES_DIR, DELIVERY_NOTE_NAME, DeliveryNote, PackageEntry,
+    PackageSource, QA_REPORT_NAME, TARGET_FOLDER, package_file_name, render_delivery_note,
+    unused_package_path, write_package,
+};
+use crate::intake::cat_package::{CatPackageKind, read_package};
+use crate::ipc::dto::{
+    BuildDeliveryPackagePayload, BuildDeliveryPackageResponseDto, DeliveryGateReportDto,
+    DeliveryOverridePayload, DeliveryPackageDto, EmptyTargetDto, ExportReturnPackageResponseDto,
+    ProjectLanguagePairDto,
+};
+use crate::ipc::error::{IpcError, IpcResult};
+use crate::metrics;
+use crate::settings::SettingsManager;
+
+/// Project status guarded by the delivery gate.
+pub(super) const DELIVERED_STATUS: &str = "delivered";
+
+/// Permission allowing a user to deliver despite open QA issues. Users with one of
+/// [`DELIVERY_OVERRIDE_ROLES`] hold it unless an override denies it.
+const DELIVERY_OVERRIDE_PERMISSION: &str = "delivery.override";
+const DELIVERY_OVERRIDE_ROLES: [&str; 2] = ["admin", "project_manager"];
+
+const DELIVERY_BLOCKED: &str = "DELIVERY_BLOCKED";
+const DELIVERY_READY: &str = "DELIVERY_READY";
+
+/// Type of the project file holding a delivery package.
+const DELIVERY_FILE_TYPE: &str = "delivery";
+
+/// Reports what would block the delivery of a project: unresolved critical QA findings and
+/// segments without a target.
+#[tauri::command]
+pub async fn check_delivery_gate_v2(
+    db: State<'_, DbManager>,
+    settings: State<'_, SettingsManager>,
+    project_uuid: String,
+) -> IpcResult<DeliveryGateReportDto> {
+    metrics::track("check_delivery_gate_v2", async {
+        let project_uuid = parse_uuid(&project_uuid, "projectUuid")?;
+        build_report(db.inner(), settings.inner(), project_uuid)
+            .await
+            .map_err(Into::into)
+    })
+    .await
+}
+
+/// Lets a transition to `delivered` through when the gate is clear, or when an authorized
+/// user overrides it. Blocked transitions fail with the [`DeliveryGateReportDto`] as the
+/// error payload; overrides are recorded in the audit trail.
+pub(super) async fn enforce_delivery_gate(
+    db: &DbManager,
+    settings: &SettingsManager,
+    project_uuid: Uuid,
+    delivery_override: Option<&DeliveryOverridePayload>,
+) -> Result<(), InvokeError> {
+    let report = build_report(db, settings, project_uuid).await?;
+    if report.code != DELIVERY_BLOCKED {
+        return Ok(());
+    }
+    let Some(delivery_override) = delivery_override else {
+        return Err(InvokeError::from(report));
+    };
+
+    let user_uuid = parse_uuid(&delivery_override.user_uuid, "deliveryOverride.userUuid")?;
+    let reason = delivery_override.reason.trim();
+    if reason.is_empty() {
+        return Err(IpcError::Validation(
+            "A reason is required to override the delivery gate.".into(),
+        )
+        .into());
+    }
+    let profile = db
+        .get_user_profile(user_uuid)
+        .await
+        .map_err(IpcError::from)?
+        .ok_or_else(|| IpcError::Validation(format!("User {user_uuid} was not found.")))?;
+    let explicit = profile
+        .permission_overrides
+        .iter()
+        .find(|entry| entry.permission == DELIVERY_OVERRIDE_PERMISSION)
+        .map(|entry| entry.is_allowed);
+    let by_role = profile
+        .roles
+        .iter()
+        .any(|role| DELIVERY_OVERRIDE_ROLES.contains(&role.role.as_str()));
+    if !explicit.unwrap_or(by_role) {
+        return Err(IpcError::Validation(format!(
+            "{} is not allowed to override the delivery gate.",
+            profile.user.username
+        ))
+        .into());
+    }
+
+    db.record_delivery_gate_override(NewDeliveryGateOverrideArgs {
+        project_uuid,
+        user_uuid,
+        reason: reason.to_string(),
+        critical_findings: report.critical_findings.len() as i64,
+        empty_targets: report.empty_targets.len() as i64,
+    })
+    .await
+    .map_err(IpcError::from)?;
+    info!(
+        target: "ipc::delivery",
+        "delivery gate of project {project_uuid} overridden by {user_uuid} with {} critical findings and {} empty targets: {reason}",
+        report.critical_findings.len(),
+        report.empty_targets.len()
+    );
+    Ok(())
+}
+
+async fn build_report(
+    db: &DbManager,
+    settings: &SettingsManager,
+    project_uuid: Uuid,
+) -> Result<DeliveryGateReportDto, IpcError> {
+    let critical_findings: Vec<_> = db
+        .list_qa_findings(project_uuid, None)
+        .await
+        .map_err(IpcError::from)?
+        .into_iter()
+        .filter(|record| record.severity == "critical" && record.resolved_at.is_none())
+        .map(map_qa_finding_record)
+        .collect();
+
+    let project_root = resolve_project_root(db, settings, project_uuid).await?;
+    let mut empty_targets = Vec::new();
+    for jliff_rel_path in list_project_jliff_paths(&project_root).await? {
+        let (_, document) = load_project_jliff(db, settings, project_uuid, &jliff_rel_path).await?;
+        empty_targets.extend(
+            document
+                .transunits
+                .iter()
+                .filter(|unit| unit.effective_target().trim().is_empty())
+                .map(|unit| EmptyTargetDto {
+                    jliff_rel_path: jliff_rel_path.clone(),
+                    transunit_id: unit.transunit_id.clone(),
+                }),
+        );
+    }
+
+    let (code, message) = if critical_findings.is_empty() && empty_targets.is_empty() {
+        (DELIVERY_READY, "The project can be delivered.".to_string())
+    } else {
+        (
+            DELIVERY_BLOCKED,
+            format!(
+                "Delivery is blocked: {} unresolved critical QA finding(s) and {} empty target(s) remain.",
+                critical_findings.len(),
+                empty_targets.len()
+            ),
+        )
+    };
+    Ok(DeliveryGateReportDto {
+        code: code.to_string(),
+        message,
+        project_uuid: project_uuid.to_string(),
+        critical_findings,
+        empty_targets,
+    })
+}
+
+/// Builds the delivery package of one language pair: the translated documents found next to
+/// the bilingual files, those bilingual files, the open QA findings and a delivery note,
+/// zipped into the `Deliveries` folder of the project. The package is registered as a
+/// project file with a `delivery_package` artifact.
+#[tauri::command]
+pub async fn build_delivery_package_v2(
+    db: State<'_, DbManager>,
+    settings: State<'_, SettingsManager>,
+    payload: BuildDeliveryPackagePayload,
+) -> IpcResult<BuildDeliveryPackageResponseDto> {
+    metrics::track("build_delivery_package_v2", async {
+        let project_uuid = parse_uuid(&payload.project_uuid, "projectUuid")?;
+        let options = payload.options;
+        let bundle = db
+            .get_project_bundle(project_uuid)
+            .await
+            .map_err(IpcError::from)?
+            .ok_or_else(|| IpcError::Validation(format!("Project {project_uuid} was not found.")))?;
+        let is_pair = |source_lang: &str, target_lang: &str| {
+            source_lang.eq_ignore_ascii_case(payload.source_lang.trim())
+                && target_lang.eq_ignore_ascii_case(payload.target_lang.trim())
+        };
+        let pair = bundle
+            .language_pairs
+            .iter()
+            .find(|pair| is_pair(&pair.source_lang, &pair.target_lang))
+            .map(|pair| ProjectLanguagePairDto {
+                source_lang: pair.source_lang.clone(),
+                target_lang: pair.target_lang.clone(),
+            })
+            .ok_or_else(|| {
+                IpcError::Validation(format!(
+                    "The project has no {} → {} language pair.",
+                    payload.source_lang.trim(),
+                    payload.target_lang.trim()
+                ))
+            })?;
+
+        let project_root = resolve_project_root(db.inner(), settings.inner(), project_uuid).await?;
+        let pair_dir = language_pair_directory_name(&pair);
+        let locations = OutputLocations::load(db.inner(), project_uuid).await?;
+
+        let mut entries = Vec::new();
+        let mut names = HashSet::new();
+        let mut documents = Vec::new();
+        let mut bilingual_files = Vec::new();
+        let mut missing_documents = Vec::new();
+        let mut output_dirs = HashSet::new();
+        for file_bundle in &bundle.files {
+            let link = &file_bundle.link;
+            let in_pair = file_bundle.language_pairs.is_empty()
+                || file_bundle
+                    .language_pairs
+                    .iter()
+                    .any(|file_pair| is_pair(&file_pair.source_lang, &file_pair.target_lang));
+            if !link.r#type.eq_ignore_ascii_case("processable") || !in_pair {
+                continue;
+            }
+
+            // Documents merged from the XLIFF are written next to it under the source name.
+            let output_dir = locations.dir(link.file_uuid, Path::new(&link.stored_at), &pair);
+            let language_dir = project_root.join(&output_dir);
+            output_dirs.insert(format!(
+                "{}/",
+                output_dir.to_string_lossy().replace('\\', "/")
+            ));
+            let document = language_dir.join(&link.filename);
+            let document_name = format!("{TARGET_FOLDER}/{}", link.filename);
+            if !is_file(&document).await {
+                missing_documents.push(link.filename.clone());
+            } else if names.insert(document_name.clone()) {
+                entries.push(PackageEntry {
+                    name: document_name,
+                    source: PackageSource::File(document),
+                });
+                documents.push(link.filename.clone());
+            }
+
+            if options.include_bilingual {
+                // The XLIFF keeps the name it was planned with, or is named after the file.
+                let xliff_name = locations
+                    .xliff_rel_path(link.file_uuid, &pair)
+                    .and_then(Path::file_name)
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| {
+                        let file_stem = Path::new(&link.filename)
+                            .file_stem()
+                            .and_then(|stem| stem.to_str())
+                            .unwrap_or("artifact");
+                        format!("{file_stem}.xlf")
+                    });
+                let xliff = language_dir.join(&xliff_name);
+                let entry_name = format!("{BILINGUAL_FOLDER}/{xliff_name}");
+                if is_file(&xliff).await && names.insert(entry_name.clone()) {
+                    entries.push(PackageEntry {
+                        name: entry_name,
+                        source: PackageSource::File(xliff),
+                    });
+                    bilingual_files.push(xliff_name);
+                }
+            }
+        }
+        if documents.is_empty() && bilingual_files.is_empty() {
+            return Err(IpcError::Validation(format!(
+                "Nothing to deliver for {} → {}: no translated documents or bilingual files were found.",
+                pair.source_lang, pair.target_lang
+            ))
+            .into());
+        }
+
+        let findings: Vec<_> = db
+            .list_qa_findings(project_uuid, None)
+            .await
+            .map_err(IpcError::from)?
+            .into_iter()
+            .filter(|record| {
+                let jliff_rel_path = record.jliff_rel_path.replace('\\', "/");
+                record.resolved_at.is_none()
+                    && output_dirs.iter().any(|dir| jliff_rel_path.starts_with(dir))
+            })
+            .map(map_qa_finding_record)
+            .collect();
+        let mut open_findings = BTreeMap::new();
+        for finding in &findings {
+            *open_findings.entry(finding.severity.clone()).or_insert(0) += 1;
+        }
+        if options.include_qa_report {
+            let report = serde_json::to_vec_pretty(&findings).map_err(|error| {
+                IpcError::Internal(format!("Failed to serialize the QA report: {error}"))
+            })?;
+            entries.push(PackageEntry {
+                name: QA_REPORT_NAME.into(),
+                source: PackageSource::Contents(report),
+            });
+        }
+
+        let client_name = match bundle.project.client_uuid {
+            Some(client_uuid) => db
+                .get_client_record(client_uuid)
+                .await
+                .map_err(IpcError::from)?
+                .map(|client| client.name),
+            None => None,
+        };
+        let date = chrono::Local::now().date_naive();
+        let note = render_delivery_note(&DeliveryNote {
+            project_name: &bundle.project.project_name,
+            project_number: bundle.project.project_number.as_deref(),
+            client_name: client_name.as_deref(),
+            source_lang: &pair.source_lang,
+            target_lang: &pair.target_lang,
+            date: Some(date),
+            documents: &documents,
+            bilingual_files: &bilingual_files,
+            missing_documents: &missing_documents,
+            open_findings,
+            message: options.note.as_deref(),
+        });
+        entries.push(PackageEntry {
+            name: DELIVERY_NOTE_NAME.into(),
+            source: PackageSource::Contents(note.into_bytes()),
+        });
+
+        let deliveries_dir = project_root.join(DELIVERIES_DIR);
+        tokio::fs::create_dir_all(&deliveries_dir)
+            .await
+            .map_err(|error| fs_error("create the Deliveries folder", error))?;
+        let base = bundle.project.project_number.clone().unwrap_or_else(|| {
+            project_root
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default()
+        });
+        let file_name = package_file_name(&base, &pair_dir, date);
+        let (package_path, size_bytes) =
+            tokio::task::spawn_blocking(move || -> anyhow::Result<(PathBuf, u64)> {
+                let package_path = unused_package_path(&deliveries_dir, &file_name);
+                let size_bytes = write_package(&package_path, &entries)?;
+                Ok((package_path, size_bytes))
+            })
+            .await
+            .map_err(|error| IpcError::Internal(format!("Packaging failed: {error}")))?
+            .map_err(|error| {
+                log::error!(target: "ipc::delivery", "failed to write delivery package: {error:#}");
+                IpcError::Internal("The delivery package could not be written.".into())
+            })?;
+
+        let package = PackageFile {
+            path: &package_path,
+            rel_path: Path::new(DELIVERIES_DIR)
+                .join(package_path.file_name().unwrap_or_default())
+                .to_string_lossy()
+                .into_owned(),
+            size_bytes: i64::try_from(size_bytes).ok(),
+        };
+        let registered = register_package(
+            db.inner(),
+            project_uuid,
+            &pair,
+            &package,
+            NewDeliveryPackageArgs {
+                artifact_uuid: Uuid::new_v4(),
+                project_uuid,
+                source_lang: pair.source_lang.clone(),
+                target_lang: pair.target_lang.clone(),
+                rel_path: package.rel_path.clone(),
+                document_count: documents.len() as i64,
+                bilingual_count: bilingual_files.len() as i64,
+                open_findings: findings.len() as i64,
+            },
+        )
+        .await;
+        let (file_bundle, record) = match registered {
+            Ok(registered) => registered,
+            Err(error) => {
+                let _ = tokio::fs::remove_file(&package_path).await;
+                return Err(error.into());
+            }
+        };
+        info!(
+            target: "ipc::delivery",
+            "built delivery package {} for project {project_uuid} with {} document(s)",
+            record.rel_path,
+            documents.len()
+        );
+
+        Ok(BuildDeliveryPackageResponseDto {
+            package: map_delivery_package_record(record),
+            file: map_project_file_bundle(file_bundle),
+            missing_documents,
+        })
+    })
+    .await
+}
+
+/// Sends the translated bilingual documents of a project created from a Trados or memoQ
+/// package back in a return package (`.sdlrpx` or `.mqback`) built from that package, which
+/// the project keeps as a reference. A document is taken from the folder of its language
+/// pair when a merged copy exists there, and otherwise from the project file itself. The
+/// package is written to the `Deliveries` folder and registered as a project file.
+#[tauri::command]
+pub async fn export_return_package_v2(
+    db: State<'_, DbManager>,
+    settings: State<'_, SettingsManager>,
+    project_uuid: String,
+) -> IpcResult<ExportReturnPackageResponseDto> {
+    metrics::track("export_return_package_v2", async {
+        let project_uuid = parse_uuid(&project_uuid, "projectUuid")?;
+        let bundle = db
+            .get_project_bundle(project_uuid)
+            .await
+            .map_err(IpcError::from)?
+            .ok_or_else(|| {
+                IpcError::Validation(format!("Project {project_uuid} was not found."))
+            })?;
+        let project_root = resolve_project_root(db.inner(), settings.inner(), project_uuid).await?;
+        let original = bundle
+            .files
+            .iter()
+            .map(|file_bundle| &file_bundle.link)
+            .find(|link| {
+                link.r#type.eq_ignore_ascii_case("reference")
+                    && CatPackageKind::from_path(Path::new(&link.filename)).is_some()
+            })
+            .map(|link| project_root.join(&link.stored_at))
+            .ok_or_else(|| {
+                IpcError::Validation(
+                    "The project was not created from a Trados or memoQ package.".into(),
+                )
+            })?;
+
+        let package = {
+            let original = original.clone();
+            tokio::task::spawn_blocking(move || read_package(&original))
+                .await
+                .map_err(|error| IpcError::Internal(format!("Packaging failed: {error}")))?
+                .map_err(|error| {
+                    IpcError::Validation(format!("The original package cannot be read: {error:#}"))
+                })?
+        };
+
+        let locations = OutputLocations::load(db.inner(), project_uuid).await?;
+        let mut translated = HashMap::new();
+        let mut pairs = Vec::new();
+        for document in package.documents() {
+            let Some(link) = bundle
+                .files
+                .iter()
+                .map(|file_bundle| &file_bundle.link)
+                .find(|link| {
+                    link.r#type.eq_ignore_ascii_case("processable")
+                        && link.filename.eq_ignore_ascii_case(&document.staged_name)
+                })
+            else {
+                continue;
+            };
+            let pair = document.target_language.as_deref().and_then(|target_lang| {
+                bundle
+                    .language_pairs
+                    .iter()
+                    .find(|pair| pair.target_lang.eq_ignore_ascii_case(target_lang))
+                    .map(|pair| ProjectLanguagePairDto {
+                        source_lang: pair.source_lang.clone(),
+                        target_lang: pair.target_lang.clone(),
+                    })
+            });
+            // Documents merged from the XLIFF are written next to it under the source name.
+            let merged = pair.as_ref().map(|pair| {
+                project_root
+                    .join(locations.dir(link.file_uuid, Path::new(&link.stored_at), pair))
+                    .join(&link.filename)
+            });
+            let path = match merged {
+                Some(merged) if is_file(&merged).await => merged,
+                _ => project_root.join(&link.stored_at),
+            };
+            if !is_file(&path).await {
+                continue;
+            }
+            translated.insert(document.staged_name.clone(), path);
+            if let Some(pair) = pair
+                && !pairs
+                    .iter()
+                    .any(|known: &ProjectLanguagePairDto| known.target_lang == pair.target_lang)
+            {
+                pairs.push(pair);
+            }
+        }
+        if translated.is_empty() {
+            return Err(IpcError::Validation(
+                "Nothing to return: no translated document of the package was found.".into(),
+            )
+            .into());
+        }
+
+        let deliveries_dir = project_root.join(DELIVERIES_DIR);
+        tokio::fs::create_dir_all(&deliveries_dir)
+            .await
+            .map_err(|error| fs_error("create the Deliveries folder", error))?;
+        let file_name = return_package_file_name(&original, package.kind);
+        let package_kind = package.kind;
+        let (package_path, size_bytes, contents) = tokio::task::spawn_blocking(move || {
+            let contents = return_package_entries(&original, &package, &translated)?;
+            let package_path = unused_package_path(&deliveries_dir, &file_name);
+            let size_bytes = write_package(&package_path, &contents.entries)?;
+            anyhow::Ok((package_path, size_bytes, contents))
+        })
+        .await
+        .map_err(|error| IpcError::Internal(format!("Packaging failed: {error}")))?
+        .map_err(|error| {
+            log::error!(target: "ipc::delivery", "failed to write return package: {error:#}");
+            IpcError::Internal("The return package could not be written.".into())
+        })?;
+
+        let file_name = package_path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let file_uuid = Uuid::new_v4();
+        let attached = db
+            .attach_project_file(
+                NewFileInfoArgs {
+                    file_uuid,
+                    ext: package_kind.return_extension().into(),
+                    r#type: DELIVERY_FILE_TYPE.into(),
+                    size_bytes: i64::try_from(size_bytes).ok(),
+                    segment_count: None,
+                    token_count: None,
+                    notes: None,
+                },
+                NewProjectFileArgs {
+                    project_uuid,
+                    file_uuid,
+                    stored_at: Path::new(DELIVERIES_DIR)
+                        .join(&file_name)
+                        .to_string_lossy()
+                        .into_owned(),
+                    filename: file_name,
+                    r#type: DELIVERY_FILE_TYPE.into(),
+                    language_pairs: pairs
+                        .into_iter()
+                        .map(|pair| FileLanguagePairInput {
+                            source_lang: pair.source_lang,
+                            target_lang: pair.target_lang,
+                        })
+                        .collect(),
+                },
+            )
+            .await;
+        let file_bundle = match attached {
+            Ok(file_bundle) => file_bundle,
+            Err(error) => {
+                let _ = tokio::fs::remove_file(&package_path).await;
+                return Err(IpcError::from(error).into());
+            }
+        };
+        info!(
+            target: "ipc::delivery",
+            "built return package {} for project {project_uuid} with {} document(s)",
+            package_path.display(),
+            contents.documents.len()
+        );
+
+        Ok(ExportReturnPackageResponseDto {
+            file: map_project_file_bundle(file_bundle),
+            package_kind: package_kind.as_str().to_string(),
+            documents: contents.documents,
+            missing_documents: contents.missing_documents,
+        })
+    })
+    .await
+}
+
+/// Lists the delivery packages of a project, newest first.
+#[tauri::command]
+pub async fn list_delivery_packages_v2(
+    db: State<'_, DbManager>,
+    project_uuid: String,
+) -> IpcResult<Vec<DeliveryPackageDto>> {
+    metrics::track("list_delivery_packages_v2", async {
+        let project_uuid = parse_uuid(&project_uuid, "projectUuid")?;
+        let records = db
+            .list_delivery_packages(project_uuid)
+            .await
+            .map_err(IpcError::from)?;
+        Ok(records
+            .into_iter()
+            .map(map_delivery_package_record)
+            .collect())
+    })
+    .await
+}
+
+/// Package referenced by a transition to `delivered`: the one named by the update, or the
+/// newest package of the project when it names none.
+pub(super) async fn delivery_package_for_transition(
+    db: &DbManager,
+    project_uuid: Uuid,
+    delivery_package_uuid: Option<&str>,
+) -> Result<Option<Uuid>, IpcError> {
+    let packages = db
+        .list_delivery_packages(project_uuid)
+        .await
+        .map_err(IpcError::from)?;
+    let Some(delivery_package_uuid) = delivery_package_uuid else {
+        return Ok(packages.first().map(|package| package.artifact_uuid));
+    };
+    let artifact_uuid = parse_uuid(delivery_package_uuid, "deliveryPackageUuid")?;
+    if !packages
+        .iter()
+        .any(|package| package.artifact_uuid == artifact_uuid)
+    {
+        return Err(IpcError::Validation(format!(
+            "Delivery package {artifact_uuid} was not found in project {project_uuid}."
+        )));
+    }
+    Ok(Some(artifact_uuid))
+}
+
+struct PackageFile<'a> {
+    path: &'a Path,
+    rel_path: String,
+    size_bytes: Option<i64>,
+}
+
+/// Attaches the package to the project with its artifact and package record, detaching it
+/// again when a later step fails.
+async fn register_package(
+    db: &DbManager,
+    project_uuid: Uuid,
+    pair: &ProjectLanguagePairDto,
+    package: &PackageFile<'_>,
+    args: NewDeliveryPackageArgs,
+) -> Result<(ProjectFileBundle, DeliveryPackageRecord), IpcError> {
+    let file_uuid = Uuid::new_v4();
+    let mut file_bundle = db
+        .attach_project_file(
+            NewFileInfoArgs {
+                file_uuid,
+                ext: "zip".into(),
+                r#type: DELIVERY_FILE_TYPE.into(),
+                size_bytes: package.size_bytes,
+                segment_count: None,
+                token_count: None,
+                notes: None,
+            },
+            NewProjectFileArgs {
+                project_uuid,
+                file_uuid,
+                filename: package
+                    .path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_default(),
+                stored_at: package.rel_path.clone(),
+                r#type: DELIVERY_FILE_TYPE.into(),
+                language_pairs: vec![FileLanguagePairInput {
+                    source_lang: pair.source_lang.clone(),
+                    target_lang: pair.target_lang.clone(),
+                }],
+            },
+        )
+        .await
+        .map_err(IpcError::from)?;
+
+    let artifact = db
+        .upsert_artifact_record(NewArtifactArgs {
+            artifact_uuid: args.artifact_uuid,
+            project_uuid,
+            file_uuid,
+            artifact_type: ArtifactKind::DeliveryPackage.as_str().into(),
+            size_bytes: package.size_bytes,
+            segment_count: None,
+            token_count: None,
+            status: ArtifactStatus::Generated.as_str().into(),
+        })
+        .await;
+    let record = match artifact {
+        Ok(artifact) => {
+            file_bundle.artifacts.push(artifact);
+            db.record_delivery_package(args).await
+        }
+        Err(error) => Err(error),
+    };
+    match record {
+        Ok(record) => Ok((file_bundle, record)),
+        Err(error) => {
+            if let Err(detach_error) = db.detach_project_file(project_uuid, file_uuid).await {
+                log::warn!(
+                    target: "ipc::delivery",
+                    "failed to detach delivery package file {file_uuid}: {detach_error}"
+                );
+            }
+            Err(IpcError::from(error))
+        }
+    }
+}
+
+async fn is_file(path: &Path) -> bool {
+    tokio::fs::metadata(path)
+        .await
+        .is_ok_and(|metadata| metadata.is_file())
+}
+
+fn map_delivery_package_record(record: DeliveryPackageRecord) -> DeliveryPackageDto {
+    DeliveryPackageDto {
+        artifact_uuid: record.artifact_uuid.to_string(),
+        project_uuid: record.project_uuid.to_string(),
+        source_lang: record.source_lang,
+        target_lang: record.target_lang,
+        rel_path: record.rel_path,
+        document_count: record.document_count,
+        bilingual_count: record.bilingual_count,
+        open_findings: record.open_findings,
+        created_at: record.created_at,
+        delivered_at: record.delivered_at,
+    }
+}
+
+fn parse_uuid(value: &str, field: &str) -> Result<Uuid, IpcError> {
+    Uuid::parse_str(value)
+        .map_err(|_| IpcError::Validation(format!("invalid {field}: expected UUID, got '{value}'")))
+}
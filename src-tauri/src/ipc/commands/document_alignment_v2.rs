@@ -0,0 +1,314 @@
+//! Alignment of legacy documents with their translations into translation memory entries.
+//! An alignment is stored for review first: pairs are accepted, rejected or corrected, and
+//! only the accepted ones reach the memory when the alignment is committed.
+
+use std::path::{Path, PathBuf};
+
+use tauri::State;
+use uuid::Uuid;
+
+use super::segmentation_exceptions_v2::exception_rules;
+use crate::db::DbManager;
+use crate::db::types::{
+    AlignmentPairRecord, AlignmentRecord, NewAlignmentArgs, NewAlignmentPairArgs,
+    NewTranslationMemoryEntryArgs, UpdateAlignmentPairArgs,
+};
+use crate::ipc::dto::{
+    AlignDocumentsPayload, AlignmentPairDto, CommitAlignmentDto, DocumentAlignmentDto,
+    ReviewAlignmentPayload,
+};
+use crate::ipc::error::{IpcError, IpcResult};
+use crate::metrics;
+use crate::tm::alignment::{ALIGNMENT_ORIGIN, align_sentences, document_sentences};
+
+const PAIR_STATUSES: [&str; 3] = ["pending", "accepted", "rejected"];
+
+/// Sentence-aligns two plain-text documents and stores the pairs for review.
+#[tauri::command]
+pub async fn align_documents_v2(
+    db: State<'_, DbManager>,
+    payload: AlignDocumentsPayload,
+) -> IpcResult<DocumentAlignmentDto> {
+    metrics::track("align_documents_v2", async {
+        let source_lang = payload.pair.source_lang.trim().to_string();
+        let target_lang = payload.pair.target_lang.trim().to_string();
+        if source_lang.is_empty() || target_lang.is_empty() {
+            return Err(
+                IpcError::Validation("pair needs a source and a target language.".into()).into(),
+            );
+        }
+        let source_path = PathBuf::from(payload.source_path.trim());
+        let target_path = PathBuf::from(payload.target_path.trim());
+        let source_text = read_document(&source_path, "sourcePath").await?;
+        let target_text = read_document(&target_path, "targetPath").await?;
+
+        let source_rules = exception_rules(&db, None, &source_lang).await?;
+        let target_rules = exception_rules(&db, None, &target_lang).await?;
+        let pairs = {
+            let (source_lang, target_lang) = (source_lang.clone(), target_lang.clone());
+            tokio::task::spawn_blocking(move || {
+                let source = document_sentences(&source_text, &source_lang, &source_rules)?;
+                let target = document_sentences(&target_text, &target_lang, &target_rules)?;
+                if source.is_empty() || target.is_empty() {
+                    anyhow::bail!("both documents must contain text");
+                }
+                align_sentences(&source, &target, &source_lang, &target_lang)
+            })
+            .await
+            .map_err(|error| IpcError::Internal(format!("Alignment failed: {error}")))?
+            .map_err(|error| {
+                IpcError::Validation(format!("The documents could not be aligned: {error:#}"))
+            })?
+        };
+
+        let alignment_uuid = Uuid::new_v4();
+        let record = db
+            .create_alignment(&NewAlignmentArgs {
+                alignment_uuid,
+                source_lang,
+                target_lang,
+                source_name: file_name(&source_path),
+                target_name: file_name(&target_path),
+                pairs: pairs
+                    .into_iter()
+                    .map(|pair| NewAlignmentPairArgs {
+                        source_text: pair.source_text,
+                        target_text: pair.target_text,
+                        score: pair.score,
+                    })
+                    .collect(),
+            })
+            .await
+            .map_err(IpcError::from)?;
+        let pairs = db
+            .list_alignment_pairs(alignment_uuid)
+            .await
+            .map_err(IpcError::from)?;
+        Ok(map_alignment(record, Some(pairs)))
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn list_alignments_v2(db: State<'_, DbManager>) -> IpcResult<Vec<DocumentAlignmentDto>> {
+    metrics::track("list_alignments_v2", async {
+        let records = db.list_alignments().await.map_err(IpcError::from)?;
+        Ok(records
+            .into_iter()
+            .map(|record| map_alignment(record, None))
+            .collect())
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn get_alignment_v2(
+    db: State<'_, DbManager>,
+    alignment_uuid: String,
+) -> IpcResult<DocumentAlignmentDto> {
+    metrics::track("get_alignment_v2", async {
+        let alignment_uuid = parse_uuid(&alignment_uuid, "alignmentUuid")?;
+        let record = find_alignment(&db, alignment_uuid).await?;
+        let pairs = db
+            .list_alignment_pairs(alignment_uuid)
+            .await
+            .map_err(IpcError::from)?;
+        Ok(map_alignment(record, Some(pairs)))
+    })
+    .await
+}
+
+/// Records review decisions and corrections on the pairs of an alignment.
+#[tauri::command]
+pub async fn review_alignment_v2(
+    db: State<'_, DbManager>,
+    payload: ReviewAlignmentPayload,
+) -> IpcResult<DocumentAlignmentDto> {
+    metrics::track("review_alignment_v2", async {
+        let alignment_uuid = parse_uuid(&payload.alignment_uuid, "alignmentUuid")?;
+        let record = find_alignment(&db, alignment_uuid).await?;
+
+        let mut updates = Vec::with_capacity(payload.updates.len());
+        if let Some(min_score) = payload.accept_min_score {
+            let pairs = db
+                .list_alignment_pairs(alignment_uuid)
+                .await
+                .map_err(IpcError::from)?;
+            updates.extend(
+                pairs
+                    .iter()
+                    .filter(|pair| pair.status == "pending" && pair.score >= min_score)
+                    .filter(|pair| !pair.source_text.is_empty() && !pair.target_text.is_empty())
+                    .map(|pair| UpdateAlignmentPairArgs {
+                        position: pair.position,
+                        status: Some("accepted".into()),
+                        source_text: None,
+                        target_text: None,
+                    }),
+            );
+        }
+        for update in payload.updates {
+            let status = update.status.map(|status| status.trim().to_string());
+            if let Some(status) = status.as_deref()
+                && !PAIR_STATUSES.contains(&status)
+            {
+                return Err(IpcError::Validation(format!(
+                    "Unknown pair status '{status}'. Use pending, accepted or rejected."
+                ))
+                .into());
+            }
+            updates.push(UpdateAlignmentPairArgs {
+                position: update.position,
+                status,
+                source_text: update.source_text.map(|text| text.trim().to_string()),
+                target_text: update.target_text.map(|text| text.trim().to_string()),
+            });
+        }
+        db.update_alignment_pairs(alignment_uuid, &updates)
+            .await
+            .map_err(IpcError::from)?;
+
+        let pairs = db
+            .list_alignment_pairs(alignment_uuid)
+            .await
+            .map_err(IpcError::from)?;
+        Ok(map_alignment(record, Some(pairs)))
+    })
+    .await
+}
+
+/// Writes the accepted pairs of an alignment to the translation memory. Committing again
+/// after further review adds the newly accepted pairs and refreshes the others.
+#[tauri::command]
+pub async fn commit_alignment_v2(
+    db: State<'_, DbManager>,
+    alignment_uuid: String,
+) -> IpcResult<CommitAlignmentDto> {
+    metrics::track("commit_alignment_v2", async {
+        let alignment_uuid = parse_uuid(&alignment_uuid, "alignmentUuid")?;
+        let record = find_alignment(&db, alignment_uuid).await?;
+        let pairs = db
+            .list_alignment_pairs(alignment_uuid)
+            .await
+            .map_err(IpcError::from)?;
+        let entries: Vec<NewTranslationMemoryEntryArgs> = pairs
+            .iter()
+            .filter(|pair| pair.status == "accepted")
+            .filter(|pair| !pair.source_text.is_empty() && !pair.target_text.is_empty())
+            .map(|pair| NewTranslationMemoryEntryArgs {
+                source_lang: record.source_lang.clone(),
+                target_lang: record.target_lang.clone(),
+                source_text: pair.source_text.clone(),
+                target_text: pair.target_text.clone(),
+                project_uuid: None,
+                origin: ALIGNMENT_ORIGIN.to_string(),
+            })
+            .collect();
+        if entries.is_empty() {
+            return Err(IpcError::Validation(
+                "Accept at least one complete pair before committing the alignment.".into(),
+            )
+            .into());
+        }
+
+        db.upsert_translation_memory_entries(&entries)
+            .await
+            .map_err(IpcError::from)?;
+        let record = db
+            .mark_alignment_committed(alignment_uuid)
+            .await
+            .map_err(IpcError::from)?
+            .unwrap_or(record);
+        log::info!(
+            target: "ipc::alignment",
+            "committed {} aligned pair(s) of '{}' to the {} → {} memory",
+            entries.len(),
+            record.source_name,
+            record.source_lang,
+            record.target_lang
+        );
+        Ok(CommitAlignmentDto {
+            alignment: map_alignment(record, Some(pairs)),
+            committed_entries: entries.len(),
+        })
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn delete_alignment_v2(
+    db: State<'_, DbManager>,
+    alignment_uuid: String,
+) -> IpcResult<()> {
+    metrics::track("delete_alignment_v2", async {
+        let alignment_uuid = parse_uuid(&alignment_uuid, "alignmentUuid")?;
+        db.delete_alignment(alignment_uuid)
+            .await
+            .map_err(IpcError::from)?;
+        Ok(())
+    })
+    .await
+}
+
+async fn read_document(path: &Path, field: &str) -> Result<String, IpcError> {
+    if !path.is_absolute() {
+        return Err(IpcError::Validation(format!(
+            "{field} must be an absolute path."
+        )));
+    }
+    let bytes = tokio::fs::read(path).await.map_err(|error| {
+        IpcError::Validation(format!("'{}' could not be read: {error}", path.display()))
+    })?;
+    let text = String::from_utf8(bytes).map_err(|_| {
+        IpcError::Validation(format!(
+            "'{}' is not UTF-8 plain text. Save the document as text before aligning it.",
+            path.display()
+        ))
+    })?;
+    Ok(text.trim_start_matches('\u{feff}').to_string())
+}
+
+async fn find_alignment(db: &DbManager, alignment_uuid: Uuid) -> Result<AlignmentRecord, IpcError> {
+    db.get_alignment(alignment_uuid)
+        .await
+        .map_err(IpcError::from)?
+        .ok_or_else(|| IpcError::Validation(format!("Alignment '{alignment_uuid}' not found.")))
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+fn map_alignment(
+    record: AlignmentRecord,
+    pairs: Option<Vec<AlignmentPairRecord>>,
+) -> DocumentAlignmentDto {
+    DocumentAlignmentDto {
+        alignment_uuid: record.alignment_uuid.to_string(),
+        source_lang: record.source_lang,
+        target_lang: record.target_lang,
+        source_name: record.source_name,
+        target_name: record.target_name,
+        created_at: record.created_at,
+        committed_at: record.committed_at,
+        pairs: pairs.map(|pairs| {
+            pairs
+                .into_iter()
+                .map(|pair| AlignmentPairDto {
+                    position: pair.position,
+                    source_text: pair.source_text,
+                    target_text: pair.target_text,
+                    score: pair.score,
+                    status: pair.status,
+                })
+                .collect()
+        }),
+    }
+}
+
+fn parse_uuid(value: &str, field: &str) -> Result<Uuid, IpcError> {
+    Uuid::parse_str(value)
+        .map_err(|_| IpcError::Validation(format!("invalid {field}: expected UUID, got '{value}'")))
+}
@@ -0,0 +1,221 @@
+//! Subject-matter domains: create, rename and delete them, and link them to projects and
+//! termbases. Provider routing treats a project's domains as subjects, and segment term
+//! lookups skip termbases linked only to domains the project does not have.
+
+use std::collections::HashMap;
+
+use tauri::State;
+use uuid::Uuid;
+
+use crate::db::DbManager;
+use crate::db::types::Domain;
+use crate::ipc::dto::{
+    CreateDomainPayload, DomainDto, SetProjectDomainsPayload, SetTermbaseDomainsPayload,
+    TermbaseDomainsDto, UpdateDomainPayload,
+};
+use crate::ipc::error::{IpcError, IpcResult};
+use crate::metrics;
+
+/// Longest domain name, in characters.
+const MAX_NAME_CHARS: usize = 100;
+
+#[tauri::command]
+pub async fn list_domains_v2(db: State<'_, DbManager>) -> IpcResult<Vec<DomainDto>> {
+    metrics::track("list_domains_v2", async {
+        let domains = db.list_domains().await.map_err(IpcError::from)?;
+        Ok(domains.into_iter().map(map_domain).collect())
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn create_domain_v2(
+    db: State<'_, DbManager>,
+    payload: CreateDomainPayload,
+) -> IpcResult<DomainDto> {
+    metrics::track("create_domain_v2", async {
+        let name = validate_name(&payload.name)?;
+        let description = normalize_description(payload.description.as_deref());
+        let domain = db
+            .create_domain(&name, description.as_deref())
+            .await
+            .map_err(IpcError::from)?;
+        Ok(map_domain(domain))
+    })
+    .await
+}
+
+/// Renames a domain and replaces its description. Returns `null` when the domain does not
+/// exist.
+#[tauri::command]
+pub async fn update_domain_v2(
+    db: State<'_, DbManager>,
+    payload: UpdateDomainPayload,
+) -> IpcResult<Option<DomainDto>> {
+    metrics::track("update_domain_v2", async {
+        let name = validate_name(&payload.name)?;
+        let description = normalize_description(payload.description.as_deref());
+        let domain = db
+            .update_domain(&payload.domain_id, &name, description.as_deref())
+            .await
+            .map_err(IpcError::from)?;
+        Ok(domain.map(map_domain))
+    })
+    .await
+}
+
+/// Deletes a domain and unlinks it from every project and termbase.
+#[tauri::command]
+pub async fn delete_domain_v2(db: State<'_, DbManager>, domain_id: String) -> IpcResult<()> {
+    metrics::track("delete_domain_v2", async {
+        if !db.delete_domain(&domain_id).await.map_err(IpcError::from)? {
+            return Err(IpcError::Validation(format!("Domain {domain_id} was not found.")).into());
+        }
+        Ok(())
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn get_project_domains_v2(
+    db: State<'_, DbManager>,
+    project_uuid: String,
+) -> IpcResult<Vec<DomainDto>> {
+    metrics::track("get_project_domains_v2", async {
+        let project_uuid = parse_uuid(&project_uuid, "projectUuid")?;
+        let domains = db
+            .list_project_domains(project_uuid)
+            .await
+            .map_err(IpcError::from)?;
+        Ok(domains.into_iter().map(map_domain).collect())
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn set_project_domains_v2(
+    db: State<'_, DbManager>,
+    payload: SetProjectDomainsPayload,
+) -> IpcResult<Vec<DomainDto>> {
+    metrics::track("set_project_domains_v2", async {
+        let project_uuid = parse_uuid(&payload.project_uuid, "projectUuid")?;
+        if db
+            .get_project_bundle(project_uuid)
+            .await
+            .map_err(IpcError::from)?
+            .is_none()
+        {
+            return Err(
+                IpcError::Validation(format!("Project {project_uuid} was not found.")).into(),
+            );
+        }
+        ensure_domains_exist(db.inner(), &payload.domain_ids).await?;
+
+        let domains = db
+            .set_project_domains(project_uuid, &payload.domain_ids)
+            .await
+            .map_err(IpcError::from)?;
+        Ok(domains.into_iter().map(map_domain).collect())
+    })
+    .await
+}
+
+/// Every termbase with the domains it is limited to.
+#[tauri::command]
+pub async fn list_termbase_domains_v2(
+    db: State<'_, DbManager>,
+) -> IpcResult<Vec<TermbaseDomainsDto>> {
+    metrics::track("list_termbase_domains_v2", async {
+        let names = db.list_termbase_names().await.map_err(IpcError::from)?;
+        let mut links: HashMap<String, Vec<DomainDto>> = HashMap::new();
+        for (termbase, domain) in db.list_termbase_domains().await.map_err(IpcError::from)? {
+            links.entry(termbase).or_default().push(map_domain(domain));
+        }
+        Ok(names
+            .into_iter()
+            .map(|termbase| TermbaseDomainsDto {
+                domains: links.remove(&termbase).unwrap_or_default(),
+                termbase,
+            })
+            .collect())
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn set_termbase_domains_v2(
+    db: State<'_, DbManager>,
+    payload: SetTermbaseDomainsPayload,
+) -> IpcResult<TermbaseDomainsDto> {
+    metrics::track("set_termbase_domains_v2", async {
+        let termbase = payload.termbase.trim();
+        if termbase.is_empty() {
+            return Err(IpcError::Validation("termbase is required.".into()).into());
+        }
+        let domains = ensure_domains_exist(db.inner(), &payload.domain_ids).await?;
+
+        db.set_termbase_domains(termbase, &payload.domain_ids)
+            .await
+            .map_err(IpcError::from)?;
+        Ok(TermbaseDomainsDto {
+            termbase: termbase.to_string(),
+            domains: domains.into_iter().map(map_domain).collect(),
+        })
+    })
+    .await
+}
+
+/// The domains with the given identifiers, by name; rejects unknown identifiers.
+async fn ensure_domains_exist(
+    db: &DbManager,
+    domain_ids: &[String],
+) -> Result<Vec<Domain>, IpcError> {
+    let known = db.list_domains().await.map_err(IpcError::from)?;
+    if let Some(unknown) = domain_ids
+        .iter()
+        .find(|id| !known.iter().any(|domain| &domain.domain_id == *id))
+    {
+        return Err(IpcError::Validation(format!(
+            "Domain {unknown} was not found."
+        )));
+    }
+    Ok(known
+        .into_iter()
+        .filter(|domain| domain_ids.contains(&domain.domain_id))
+        .collect())
+}
+
+fn validate_name(name: &str) -> Result<String, IpcError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(IpcError::Validation(
+            "A domain name cannot be empty.".into(),
+        ));
+    }
+    if name.chars().count() > MAX_NAME_CHARS {
+        return Err(IpcError::Validation(format!(
+            "A domain name can have at most {MAX_NAME_CHARS} characters."
+        )));
+    }
+    Ok(name.to_string())
+}
+
+fn normalize_description(description: Option<&str>) -> Option<String> {
+    description
+        .map(str::trim)
+        .filter(|description| !description.is_empty())
+        .map(str::to_string)
+}
+
+fn map_domain(domain: Domain) -> DomainDto {
+    DomainDto {
+        domain_id: domain.domain_id,
+        name: domain.name,
+        description: domain.description,
+    }
+}
+
+fn parse_uuid(value: &str, field: &str) -> Result<Uuid, IpcError> {
+    Uuid::parse_str(value)
+        .map_err(|_| IpcError::Validation(format!("invalid {field}: expected UUID, got '{value}'")))
+}
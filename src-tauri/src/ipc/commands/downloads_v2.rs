@@ -0,0 +1,171 @@
+use log::warn;
+use tauri::{AppHandle, Emitter, Manager, State};
+use uuid::Uuid;
+
+use super::offline_v2::ensure_online;
+use crate::downloads::{DownloadProgress, Downloader, cache_path, list_cached, remove_cached};
+use crate::ipc::dto::{
+    CachedDownloadDto, DownloadEventPayload, DownloadStartedDto, StartDownloadPayload,
+};
+use crate::ipc::error::{IpcError, IpcResult};
+use crate::ipc::events::DOWNLOAD_PROGRESS;
+use crate::ipc::state::OfflineMode;
+use crate::metrics;
+use crate::settings::SettingsManager;
+
+/// Starts downloading `url` into the managed cache and returns immediately. Progress is
+/// reported on `download:progress`, ending with a `completed` or `failed` event; an
+/// interrupted download resumes when started again.
+#[tauri::command]
+pub async fn start_download_v2(
+    app: AppHandle,
+    settings: State<'_, SettingsManager>,
+    payload: StartDownloadPayload,
+) -> IpcResult<DownloadStartedDto> {
+    metrics::track("start_download_v2", async {
+        let url = payload.url.trim().to_string();
+        if !(url.starts_with("https://") || url.starts_with("http://")) {
+            return Err(IpcError::Validation("url must be an http(s) URL.".into()).into());
+        }
+        let category = payload.category.trim().to_string();
+        let file_name = payload
+            .file_name
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty())
+            .or_else(|| file_name_from_url(&url))
+            .ok_or_else(|| {
+                IpcError::Validation("fileName is required when the URL has no file name.".into())
+            })?;
+        let sha256 = payload
+            .sha256
+            .map(|digest| digest.trim().to_ascii_lowercase())
+            .filter(|digest| !digest.is_empty());
+        if let Some(digest) = &sha256
+            && (digest.len() != 64 || !digest.chars().all(|c| c.is_ascii_hexdigit()))
+        {
+            return Err(
+                IpcError::Validation("sha256 must be a 64-character hex digest.".into()).into(),
+            );
+        }
+
+        ensure_online(&app.state::<OfflineMode>(), "Downloading")?;
+        let cache_root = settings.current().await.downloads_dir();
+        let path = cache_path(&cache_root, &category, &file_name)
+            .map_err(|error| IpcError::Validation(error.to_string()))?;
+        let download_id = Uuid::new_v4().to_string();
+
+        let started = DownloadStartedDto {
+            download_id: download_id.clone(),
+            category: category.clone(),
+            file_name: file_name.clone(),
+            path: path.to_string_lossy().into_owned(),
+        };
+        tauri::async_runtime::spawn(async move {
+            let event = |state: &str, progress: DownloadProgress, error: Option<String>| {
+                DownloadEventPayload {
+                    download_id: download_id.clone(),
+                    category: category.clone(),
+                    file_name: file_name.clone(),
+                    state: state.into(),
+                    downloaded: progress.downloaded,
+                    total: progress.total,
+                    error,
+                }
+            };
+            let mut last = DownloadProgress {
+                downloaded: 0,
+                total: None,
+            };
+            let result = app
+                .state::<Downloader>()
+                .fetch(
+                    &cache_root,
+                    &category,
+                    &file_name,
+                    &url,
+                    sha256.as_deref(),
+                    |progress| {
+                        last = progress;
+                        emit_download_event(&app, event("progress", progress, None));
+                    },
+                )
+                .await;
+            let payload = match result {
+                Ok(path) => {
+                    let size = tokio::fs::metadata(&path)
+                        .await
+                        .map(|meta| meta.len())
+                        .unwrap_or(last.downloaded);
+                    event(
+                        "completed",
+                        DownloadProgress {
+                            downloaded: size,
+                            total: Some(size),
+                        },
+                        None,
+                    )
+                }
+                Err(error) => {
+                    warn!(target: "ipc::downloads", "download of {url} failed: {error}");
+                    event("failed", last, Some(error.to_string()))
+                }
+            };
+            emit_download_event(&app, payload);
+        });
+
+        Ok(started)
+    })
+    .await
+}
+
+/// Lists the files of the download cache, including interrupted downloads.
+#[tauri::command]
+pub async fn list_downloads_v2(
+    settings: State<'_, SettingsManager>,
+) -> IpcResult<Vec<CachedDownloadDto>> {
+    metrics::track("list_downloads_v2", async {
+        let cache_root = settings.current().await.downloads_dir();
+        let entries = list_cached(&cache_root)
+            .await
+            .map_err(|error| IpcError::Internal(error.to_string()))?;
+        Ok(entries
+            .into_iter()
+            .map(|entry| CachedDownloadDto {
+                category: entry.category,
+                file_name: entry.file_name,
+                path: entry.path.to_string_lossy().into_owned(),
+                size: entry.size,
+                partial: entry.partial,
+            })
+            .collect())
+    })
+    .await
+}
+
+/// Deletes a cached file and any partial download of it.
+#[tauri::command]
+pub async fn delete_download_v2(
+    settings: State<'_, SettingsManager>,
+    category: String,
+    file_name: String,
+) -> IpcResult<bool> {
+    metrics::track("delete_download_v2", async {
+        let cache_root = settings.current().await.downloads_dir();
+        remove_cached(&cache_root, category.trim(), file_name.trim())
+            .await
+            .map_err(|error| IpcError::Validation(error.to_string()).into())
+    })
+    .await
+}
+
+fn file_name_from_url(url: &str) -> Option<String> {
+    let path = url.split(['?', '#']).next()?;
+    let (_, name) = path.split_once("://")?.1.rsplit_once('/')?;
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+fn emit_download_event(app: &AppHandle, payload: DownloadEventPayload) {
+    if let Err(error) = app.emit(DOWNLOAD_PROGRESS, payload) {
+        warn!(target: "ipc::downloads", "failed to emit download event: {error}");
+    }
+}
@@ -0,0 +1,169 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use tauri::State;
+use uuid::Uuid;
+
+use super::shared::{fs_error, load_project_jliff, update_project_jliff};
+use crate::db::DbManager;
+use crate::db::types::{NewSegmentRevisionArgs, RecordSegmentRevisionsArgs};
+use crate::ipc::dto::{
+    ExportExternalReviewPayload, ExternalReviewConflictDto, ExternalReviewExportDto,
+    ExternalReviewImportDto, ImportExternalReviewPayload,
+};
+use crate::ipc::error::{IpcError, IpcResult};
+use crate::jliff::external_review::{
+    ReviewDecision, parse_review_rtf, render_review_rtf, review_decision,
+};
+use crate::metrics;
+use crate::settings::SettingsManager;
+
+/// Revision origin recorded for targets changed by an external review import.
+const EXTERNAL_REVIEW_ORIGIN: &str = "external_review";
+
+/// Writes a bilingual review table of a JLIFF document that reviewers can edit in any word
+/// processor.
+#[tauri::command]
+pub async fn export_external_review_v2(
+    db: State<'_, DbManager>,
+    settings: State<'_, SettingsManager>,
+    payload: ExportExternalReviewPayload,
+) -> IpcResult<ExternalReviewExportDto> {
+    metrics::track("export_external_review_v2", async {
+        let project_uuid = parse_uuid(&payload.project_uuid, "projectUuid")?;
+        if !payload.format.trim().eq_ignore_ascii_case("rtf") {
+            return Err(IpcError::Validation(format!(
+                "Unsupported review format '{}'. Supported formats: rtf.",
+                payload.format
+            ))
+            .into());
+        }
+        let output_path = absolute_path(&payload.output_path, "outputPath")?;
+
+        let (_, document) = load_project_jliff(
+            db.inner(),
+            settings.inner(),
+            project_uuid,
+            &payload.jliff_rel_path,
+        )
+        .await?;
+
+        tokio::fs::write(&output_path, render_review_rtf(&document))
+            .await
+            .map_err(|error| fs_error("write the external review file", error))?;
+
+        Ok(ExternalReviewExportDto {
+            output_path: payload.output_path,
+            segment_count: document.transunits.len(),
+        })
+    })
+    .await
+}
+
+/// Applies the target edits of a returned review table as segment revisions. Edits that
+/// clash with changes made in the app since the export, or that alter placeholders, are
+/// reported instead of applied.
+#[tauri::command]
+pub async fn import_external_review_v2(
+    db: State<'_, DbManager>,
+    settings: State<'_, SettingsManager>,
+    payload: ImportExternalReviewPayload,
+) -> IpcResult<ExternalReviewImportDto> {
+    metrics::track("import_external_review_v2", async {
+        let project_uuid = parse_uuid(&payload.project_uuid, "projectUuid")?;
+        let input_path = absolute_path(&payload.input_path, "inputPath")?;
+
+        let bytes = tokio::fs::read(&input_path)
+            .await
+            .map_err(|error| fs_error("read the external review file", error))?;
+        let rows = parse_review_rtf(&String::from_utf8_lossy(&bytes)).map_err(|error| {
+            IpcError::Validation(format!("The review file could not be read: {error}"))
+        })?;
+
+        let (result, revisions) = update_project_jliff(
+            db.inner(),
+            settings.inner(),
+            project_uuid,
+            &payload.jliff_rel_path,
+            move |document| {
+                let positions: HashMap<String, usize> = document
+                    .transunits
+                    .iter()
+                    .enumerate()
+                    .map(|(index, unit)| (unit.transunit_id.clone(), index))
+                    .collect();
+
+                let mut result = ExternalReviewImportDto {
+                    applied: 0,
+                    unchanged: 0,
+                    conflicts: Vec::new(),
+                    unknown_transunit_ids: Vec::new(),
+                };
+                let mut revisions = Vec::new();
+                for row in rows {
+                    let Some(&index) = positions.get(&row.transunit_id) else {
+                        result.unknown_transunit_ids.push(row.transunit_id);
+                        continue;
+                    };
+                    let unit = &mut document.transunits[index];
+                    let current_target = unit.effective_target().to_string();
+                    match review_decision(&current_target, &row) {
+                        ReviewDecision::Unchanged => result.unchanged += 1,
+                        ReviewDecision::Apply => {
+                            *unit.effective_target_mut() = row.target.clone();
+                            result.applied += 1;
+                            revisions.push(NewSegmentRevisionArgs {
+                                transunit_id: row.transunit_id,
+                                previous_target: current_target,
+                                new_target: row.target,
+                            });
+                        }
+                        ReviewDecision::Conflict(conflict) => {
+                            result.conflicts.push(ExternalReviewConflictDto {
+                                transunit_id: row.transunit_id,
+                                reason: conflict.as_str().to_string(),
+                                current_target,
+                                reviewer_target: row.target,
+                            })
+                        }
+                    }
+                }
+                Ok((result, revisions))
+            },
+        )
+        .await?;
+
+        if !revisions.is_empty() {
+            db.record_segment_revisions(RecordSegmentRevisionsArgs {
+                project_uuid,
+                jliff_rel_path: payload.jliff_rel_path,
+                origin: EXTERNAL_REVIEW_ORIGIN.to_string(),
+                author: payload
+                    .reviewer
+                    .map(|reviewer| reviewer.trim().to_string())
+                    .filter(|reviewer| !reviewer.is_empty()),
+                revisions,
+            })
+            .await
+            .map_err(IpcError::from)?;
+        }
+
+        Ok(result)
+    })
+    .await
+}
+
+fn absolute_path(value: &str, field: &str) -> Result<PathBuf, IpcError> {
+    let path = PathBuf::from(value.trim());
+    if !path.is_absolute() {
+        return Err(IpcError::Validation(format!(
+            "{field} must be an absolute path."
+        )));
+    }
+    Ok(path)
+}
+
+fn parse_uuid(value: &str, field: &str) -> Result<Uuid, IpcError> {
+    Uuid::parse_str(value)
+        .map_err(|_| IpcError::Validation(format!("invalid {field}: expected UUID, got '{value}'")))
+}
@@ -0,0 +1,164 @@
+use std::sync::Arc;
+
+use tauri::State;
+use uuid::Uuid;
+
+use super::projects_v2::resolve_project_root;
+use super::shared::{
+    list_project_jliff_paths, load_project_jliff, record_project_history, update_project_jliff,
+};
+use crate::db::DbManager;
+use crate::db::types::{NewSegmentRevisionArgs, RecordSegmentRevisionsArgs};
+use crate::ipc::dto::{
+    FindReplaceChangeDto, FindReplaceFileDto, FindReplaceResultDto, FindReplaceTargetsPayload,
+};
+use crate::ipc::error::{IpcError, IpcResult};
+use crate::jliff::find_replace::{DocumentReplaceResult, TargetReplace, replace_in_targets};
+use crate::jliff::history::HistoryEvent;
+use crate::metrics;
+use crate::settings::SettingsManager;
+
+/// Revision origin recorded for targets changed by a batch replacement.
+const FIND_REPLACE_ORIGIN: &str = "find_replace";
+
+/// Replaces text in the targets of one document or the whole project. Each document is
+/// rewritten under its file lock and every changed target is recorded as a revision. A dry
+/// run reports the same changes without writing anything.
+#[tauri::command]
+pub async fn find_replace_targets_v2(
+    db: State<'_, DbManager>,
+    settings: State<'_, SettingsManager>,
+    payload: FindReplaceTargetsPayload,
+) -> IpcResult<FindReplaceResultDto> {
+    metrics::track("find_replace_targets_v2", async {
+        let project_uuid = parse_uuid(&payload.project_uuid, "projectUuid")?;
+        if payload.pattern.is_empty() {
+            return Err(IpcError::Validation("pattern must not be empty.".into()).into());
+        }
+        let replace = TargetReplace::new(
+            &payload.pattern,
+            &payload.replacement,
+            payload.regex,
+            payload.case_sensitive.unwrap_or(true),
+        )
+        .map_err(|error| IpcError::Validation(format!("Invalid pattern: {error}")))?;
+
+        let documents = match payload.scope.trim().to_ascii_lowercase().as_str() {
+            "file" => vec![payload.jliff_rel_path.clone().ok_or_else(|| {
+                IpcError::Validation("jliffRelPath is required for the 'file' scope.".into())
+            })?],
+            "project" => {
+                let project_root =
+                    resolve_project_root(db.inner(), settings.inner(), project_uuid).await?;
+                list_project_jliff_paths(&project_root).await?
+            }
+            _ => {
+                return Err(IpcError::Validation(format!(
+                    "Unknown scope '{}'. Use 'file' or 'project'.",
+                    payload.scope
+                ))
+                .into());
+            }
+        };
+
+        let replace = Arc::new(replace);
+        let mut files = Vec::new();
+        for jliff_rel_path in documents {
+            let result = if payload.dry_run {
+                let (_, mut document) =
+                    load_project_jliff(db.inner(), settings.inner(), project_uuid, &jliff_rel_path)
+                        .await?;
+                replace_in_targets(&mut document, &replace, false)
+            } else {
+                let replace = Arc::clone(&replace);
+                update_project_jliff(
+                    db.inner(),
+                    settings.inner(),
+                    project_uuid,
+                    &jliff_rel_path,
+                    move |document| Ok(replace_in_targets(document, &replace, true)),
+                )
+                .await?
+            };
+            if result.replacements.is_empty() && result.skipped_transunit_ids.is_empty() {
+                continue;
+            }
+
+            if !payload.dry_run && !result.replacements.is_empty() {
+                db.record_segment_revisions(RecordSegmentRevisionsArgs {
+                    project_uuid,
+                    jliff_rel_path: jliff_rel_path.clone(),
+                    origin: FIND_REPLACE_ORIGIN.to_string(),
+                    author: None,
+                    revisions: result
+                        .replacements
+                        .iter()
+                        .map(|replacement| NewSegmentRevisionArgs {
+                            transunit_id: replacement.transunit_id.clone(),
+                            previous_target: replacement.previous_target.clone(),
+                            new_target: replacement.new_target.clone(),
+                        })
+                        .collect(),
+                })
+                .await
+                .map_err(IpcError::from)?;
+            }
+            files.push(map_file(jliff_rel_path, result));
+        }
+
+        if !payload.dry_run && files.iter().any(|file| !file.changes.is_empty()) {
+            record_project_history(
+                db.inner(),
+                settings.inner(),
+                project_uuid,
+                HistoryEvent {
+                    kind: "find-replace",
+                    summary: format!(
+                        "Replace '{}' with '{}'",
+                        payload.pattern, payload.replacement
+                    ),
+                    files: files
+                        .iter()
+                        .filter(|file| !file.changes.is_empty())
+                        .map(|file| file.jliff_rel_path.clone())
+                        .collect(),
+                },
+            )
+            .await;
+        }
+
+        Ok(FindReplaceResultDto {
+            dry_run: payload.dry_run,
+            replaced_segments: files.iter().map(|file| file.changes.len()).sum(),
+            replaced_matches: files
+                .iter()
+                .flat_map(|file| &file.changes)
+                .map(|change| change.matches)
+                .sum(),
+            files,
+        })
+    })
+    .await
+}
+
+fn map_file(jliff_rel_path: String, result: DocumentReplaceResult) -> FindReplaceFileDto {
+    FindReplaceFileDto {
+        jliff_rel_path,
+        changes: result
+            .replacements
+            .into_iter()
+            .map(|replacement| FindReplaceChangeDto {
+                transunit_id: replacement.transunit_id,
+                previous_target: replacement.previous_target,
+                new_target: replacement.new_target,
+                matches: replacement.matches,
+            })
+            .collect(),
+        skipped_transunit_ids: result.skipped_transunit_ids,
+    }
+}
+
+fn parse_uuid(value: &str, field: &str) -> Result<Uuid, IpcError> {
+    Uuid::parse_str(value)
+        .map_err(|_| IpcError::Validation(format!("invalid {field}: expected UUID, got '{value}'")))
+}
@@ -0,0 +1,38 @@
+//! Supported file formats for the project wizard, read from the formats manifest.
+
+use tauri::State;
+
+use super::projects_v2::map_format_role;
+use crate::formats::{self, FormatCapabilities};
+use crate::ipc::dto::{SupportedFormatDto, SupportedFormatsDto};
+use crate::ipc::error::IpcResult;
+use crate::metrics;
+use crate::settings::SettingsManager;
+
+/// The formats in effect; edits to `formats.json` in the app folder show up on the next call.
+#[tauri::command]
+pub async fn get_supported_formats_v2(
+    settings: State<'_, SettingsManager>,
+) -> IpcResult<SupportedFormatsDto> {
+    metrics::track("get_supported_formats_v2", async {
+        let manifest = formats::current(&settings.app_folder().await).await;
+        Ok(SupportedFormatsDto {
+            version: manifest.version,
+            overridden: manifest.overridden,
+            formats: manifest.formats.iter().map(map_format).collect(),
+        })
+    })
+    .await
+}
+
+fn map_format(format: &FormatCapabilities) -> SupportedFormatDto {
+    SupportedFormatDto {
+        id: format.id.clone(),
+        label: format.label.clone(),
+        group: format.group.clone(),
+        extensions: format.extensions.clone(),
+        role: map_format_role(format.role),
+        filter: format.filter.clone(),
+        bilingual: format.bilingual,
+    }
+}
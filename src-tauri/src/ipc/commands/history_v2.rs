@@ -0,0 +1,166 @@
+use tauri::State;
+use uuid::Uuid;
+
+use super::projects_v2::resolve_project_root;
+use super::shared::{fs_error, resolve_project_relative_path, with_project_file_lock};
+use crate::db::DbManager;
+use crate::ipc::dto::{
+    CheckoutProjectSnapshotDto, CheckoutProjectSnapshotPayload, ListProjectHistoryPayload,
+    ProjectHistoryDto, ProjectHistoryEntryDto,
+};
+use crate::ipc::error::{IpcError, IpcResult};
+use crate::jliff::history::{self, HistoryEntry, HistoryEvent};
+use crate::metrics;
+use crate::settings::SettingsManager;
+
+const DEFAULT_HISTORY_LIMIT: usize = 100;
+
+/// Starts the git history of a project and commits its current documents. Enabling a
+/// project that already has a history only commits pending changes.
+#[tauri::command]
+pub async fn enable_project_history_v2(
+    db: State<'_, DbManager>,
+    settings: State<'_, SettingsManager>,
+    project_uuid: String,
+) -> IpcResult<ProjectHistoryDto> {
+    metrics::track("enable_project_history_v2", async {
+        let project_uuid = parse_uuid(&project_uuid, "projectUuid")?;
+        let project_root = resolve_project_root(db.inner(), settings.inner(), project_uuid).await?;
+        let entries = tokio::task::spawn_blocking(move || {
+            history::enable(&project_root)?;
+            history::list(&project_root, DEFAULT_HISTORY_LIMIT)
+        })
+        .await
+        .map_err(|join_err| IpcError::Internal(format!("Failed to enable history: {join_err}")))?
+        .map_err(|error| IpcError::Internal(format!("Failed to enable history: {error:#}")))?;
+        Ok(ProjectHistoryDto {
+            enabled: true,
+            entries: entries.into_iter().map(map_history_entry).collect(),
+        })
+    })
+    .await
+}
+
+/// Lists the commits of a project's history, newest first. Projects without a history
+/// report `enabled: false` and no entries.
+#[tauri::command]
+pub async fn list_project_history_v2(
+    db: State<'_, DbManager>,
+    settings: State<'_, SettingsManager>,
+    payload: ListProjectHistoryPayload,
+) -> IpcResult<ProjectHistoryDto> {
+    metrics::track("list_project_history_v2", async {
+        let project_uuid = parse_uuid(&payload.project_uuid, "projectUuid")?;
+        let limit = payload.limit.unwrap_or(DEFAULT_HISTORY_LIMIT);
+        if limit == 0 {
+            return Err(IpcError::Validation("limit must be at least 1.".into()).into());
+        }
+        let project_root = resolve_project_root(db.inner(), settings.inner(), project_uuid).await?;
+        if !history::is_enabled(&project_root) {
+            return Ok(ProjectHistoryDto {
+                enabled: false,
+                entries: Vec::new(),
+            });
+        }
+        let entries = tokio::task::spawn_blocking(move || history::list(&project_root, limit))
+            .await
+            .map_err(|join_err| IpcError::Internal(format!("Failed to read history: {join_err}")))?
+            .map_err(|error| IpcError::Internal(format!("Failed to read history: {error:#}")))?;
+        Ok(ProjectHistoryDto {
+            enabled: true,
+            entries: entries.into_iter().map(map_history_entry).collect(),
+        })
+    })
+    .await
+}
+
+/// Writes the JLIFF documents and tag maps of a commit back into the project, each under its
+/// file lock, and commits the result so the checkout can itself be undone. Documents added
+/// after that commit are left in place.
+#[tauri::command]
+pub async fn checkout_project_snapshot_v2(
+    db: State<'_, DbManager>,
+    settings: State<'_, SettingsManager>,
+    payload: CheckoutProjectSnapshotPayload,
+) -> IpcResult<CheckoutProjectSnapshotDto> {
+    metrics::track("checkout_project_snapshot_v2", async {
+        let project_uuid = parse_uuid(&payload.project_uuid, "projectUuid")?;
+        let commit_id = payload.commit_id.trim().to_ascii_lowercase();
+        let project_root = resolve_project_root(db.inner(), settings.inner(), project_uuid).await?;
+        if !history::is_enabled(&project_root) {
+            return Err(IpcError::Validation(
+                "The project has no history. Enable it first.".into(),
+            )
+            .into());
+        }
+
+        let files = {
+            let project_root = project_root.clone();
+            let commit_id = commit_id.clone();
+            tokio::task::spawn_blocking(move || history::read_commit(&project_root, &commit_id))
+                .await
+                .map_err(|join_err| {
+                    IpcError::Internal(format!("Failed to read commit: {join_err}"))
+                })?
+                .map_err(|error| {
+                    IpcError::Validation(format!(
+                        "Commit '{}' could not be checked out: {error:#}",
+                        payload.commit_id
+                    ))
+                })?
+        };
+
+        let mut restored_files = Vec::with_capacity(files.len());
+        for (rel_path, contents) in files {
+            let path = resolve_project_relative_path(&project_root, &rel_path)?;
+            with_project_file_lock(&path, || async {
+                if let Some(parent) = path.parent() {
+                    tokio::fs::create_dir_all(parent)
+                        .await
+                        .map_err(|error| fs_error("restore a project document", error))?;
+                }
+                tokio::fs::write(&path, contents)
+                    .await
+                    .map_err(|error| fs_error("restore a project document", error))
+            })
+            .await?;
+            restored_files.push(rel_path);
+        }
+
+        let event = HistoryEvent {
+            kind: "checkout",
+            summary: format!("Check out {}", &commit_id[..commit_id.len().min(12)]),
+            files: restored_files.clone(),
+        };
+        let entry = tokio::task::spawn_blocking(move || history::commit(&project_root, &event))
+            .await
+            .map_err(|join_err| {
+                IpcError::Internal(format!("Failed to record the checkout: {join_err}"))
+            })?
+            .map_err(|error| {
+                IpcError::Internal(format!("Failed to record the checkout: {error:#}"))
+            })?;
+
+        Ok(CheckoutProjectSnapshotDto {
+            commit_id,
+            restored_files,
+            entry: entry.map(map_history_entry),
+        })
+    })
+    .await
+}
+
+fn map_history_entry(entry: HistoryEntry) -> ProjectHistoryEntryDto {
+    ProjectHistoryEntryDto {
+        commit_id: entry.commit_id,
+        summary: entry.summary,
+        event: entry.event,
+        files: entry.files,
+        committed_at: entry.committed_at.to_rfc3339(),
+    }
+}
+
+fn parse_uuid(value: &str, field: &str) -> Result<Uuid, IpcError> {
+    Uuid::parse_str(value)
+        .map_err(|_| IpcError::Validation(format!("invalid {field}: expected UUID, got '{value}'")))
+}
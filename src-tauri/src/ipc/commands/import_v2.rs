@@ -0,0 +1,326 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+use tauri::State;
+use tokio::task;
+use uuid::Uuid;
+
+use super::projects_v2::{
+    cleanup_files, default_asset_role, file_language_pairs_for_role, locate_project_root,
+    map_asset_role_to_file_info_type, map_asset_role_to_project_file_type, map_project_file_bundle,
+    map_project_language_pair_record, resolve_asset_directory,
+};
+use super::shared::fs_error;
+use crate::db::DbManager;
+use crate::db::types::{NewFileInfoArgs, NewProjectFileArgs, ProjectFileBundle};
+use crate::formats::{self, FormatsManifest};
+use crate::import::archive::{ArchiveEntry, ArchiveLimits, extract_entries, list_entries};
+use crate::import::csv::CsvTable;
+use crate::import::{ImportPlan, plan_client_import, plan_user_import};
+use crate::ipc::dto::{
+    CsvImportReportDto, CsvRowIssueDto, ImportCsvPayload, ImportZipAssetsPayload,
+    ImportZipAssetsResponseDto, ProjectAssetRoleDto, ProjectLanguagePairDto,
+};
+use crate::ipc::error::{IpcError, IpcResult};
+use crate::metrics;
+use crate::settings::SettingsManager;
+
+/// Creates clients from the rows of a CSV file. Invalid rows and rows duplicating a client
+/// by email or name are left out and reported; the others are created together.
+#[tauri::command]
+pub async fn import_clients_csv_v2(
+    db: State<'_, DbManager>,
+    payload: ImportCsvPayload,
+) -> IpcResult<CsvImportReportDto> {
+    metrics::track("import_clients_csv_v2", async {
+        let table = read_csv(&payload.input_path).await?;
+        let existing = db.list_client_records(None).await.map_err(IpcError::from)?;
+        let plan = plan_client_import(&table, &payload.column_mapping, &existing)
+            .map_err(|error| IpcError::Validation(error.to_string()))?;
+        if !payload.dry_run {
+            db.import_client_records(&plan.records)
+                .await
+                .map_err(IpcError::from)?;
+        }
+        Ok(build_report(&table, &plan, payload.dry_run, |client| {
+            client.client_uuid
+        }))
+    })
+    .await
+}
+
+/// Creates users from the rows of a CSV file. Invalid rows and rows duplicating a user by
+/// email or username are left out and reported; the others are created together.
+#[tauri::command]
+pub async fn import_users_csv_v2(
+    db: State<'_, DbManager>,
+    payload: ImportCsvPayload,
+) -> IpcResult<CsvImportReportDto> {
+    metrics::track("import_users_csv_v2", async {
+        let table = read_csv(&payload.input_path).await?;
+        let existing: Vec<_> = db
+            .list_user_profiles()
+            .await
+            .map_err(IpcError::from)?
+            .into_iter()
+            .map(|profile| profile.user)
+            .collect();
+        let plan = plan_user_import(&table, &payload.column_mapping, &existing)
+            .map_err(|error| IpcError::Validation(error.to_string()))?;
+        if !payload.dry_run {
+            db.import_user_profiles(&plan.records)
+                .await
+                .map_err(IpcError::from)?;
+        }
+        Ok(build_report(&table, &plan, payload.dry_run, |user| {
+            user.user_uuid
+        }))
+    })
+    .await
+}
+
+/// Extracts a ZIP archive into a project and registers every file in it. Files keep their
+/// folder structure under the directory of their role, taken from `role_mapping` or from
+/// the extension. Nothing is extracted when an entry is unsafe, too large or would replace
+/// a project file.
+#[tauri::command]
+pub async fn import_zip_as_assets_v2(
+    db: State<'_, DbManager>,
+    settings: State<'_, SettingsManager>,
+    payload: ImportZipAssetsPayload,
+) -> IpcResult<ImportZipAssetsResponseDto> {
+    metrics::track("import_zip_as_assets_v2", async {
+        let project_uuid = parse_uuid(&payload.project_uuid, "projectUuid")?;
+        let zip_path = PathBuf::from(payload.zip_path.trim());
+        if !zip_path.is_absolute() {
+            return Err(IpcError::Validation("zipPath must be an absolute path.".into()).into());
+        }
+        let bundle = db
+            .get_project_bundle(project_uuid)
+            .await
+            .map_err(IpcError::from)?
+            .ok_or_else(|| {
+                IpcError::Validation(format!("Project {project_uuid} was not found."))
+            })?;
+        let current = settings.current().await;
+        let projects_root = current.projects_dir();
+        let project_root = locate_project_root(&projects_root, project_uuid, &bundle).await?;
+        let formats = formats::current(&current.app_folder).await;
+
+        let limits = ArchiveLimits::default();
+        let entries = {
+            let zip_path = zip_path.clone();
+            task::spawn_blocking(move || list_entries(&zip_path, &limits))
+                .await
+                .map_err(|error| IpcError::Internal(format!("ZIP import failed: {error}")))?
+                .map_err(|error| {
+                    IpcError::Validation(format!("The archive cannot be imported: {error}"))
+                })?
+        };
+        if entries.is_empty() {
+            return Err(IpcError::Validation("The archive holds no files.".into()).into());
+        }
+
+        let folder_roles = normalize_role_mapping(&payload.role_mapping);
+        let targets: Vec<(ArchiveEntry, PathBuf)> = entries
+            .into_iter()
+            .map(|entry| {
+                let role = role_for_entry(&entry.relative_path, &folder_roles, &formats);
+                let destination =
+                    resolve_asset_directory(&project_root, role).join(&entry.relative_path);
+                (entry, destination)
+            })
+            .collect();
+        if let Some((entry, _)) = targets.iter().find(|(_, destination)| destination.exists()) {
+            return Err(IpcError::Validation(format!(
+                "'{}' already exists in the project.",
+                entry.relative_path.display()
+            ))
+            .into());
+        }
+        {
+            let zip_path = zip_path.clone();
+            let targets = targets.clone();
+            task::spawn_blocking(move || extract_entries(&zip_path, &targets, &limits))
+                .await
+                .map_err(|error| IpcError::Internal(format!("ZIP import failed: {error}")))?
+                .map_err(|error| {
+                    IpcError::Validation(format!("The archive could not be extracted: {error}"))
+                })?;
+        }
+
+        let language_pairs: Vec<ProjectLanguagePairDto> = bundle
+            .language_pairs
+            .into_iter()
+            .map(map_project_language_pair_record)
+            .collect();
+        let mut files = Vec::with_capacity(targets.len());
+        for (entry, destination) in &targets {
+            let role = role_for_entry(&entry.relative_path, &folder_roles, &formats);
+            match register_extracted_file(
+                db.inner(),
+                project_uuid,
+                &project_root,
+                destination,
+                role,
+                &language_pairs,
+            )
+            .await
+            {
+                Ok(file) => files.push(file),
+                Err(error) => {
+                    for file in &files {
+                        let _ = db
+                            .detach_project_file(project_uuid, file.link.file_uuid)
+                            .await;
+                    }
+                    let extracted: Vec<PathBuf> = targets
+                        .iter()
+                        .map(|(_, destination)| destination.clone())
+                        .collect();
+                    cleanup_files(&extracted);
+                    return Err(error.into());
+                }
+            }
+        }
+        Ok(ImportZipAssetsResponseDto {
+            files: files.into_iter().map(map_project_file_bundle).collect(),
+        })
+    })
+    .await
+}
+
+/// Folder paths of a role mapping, lowercased with `/` separators; `""` is the whole archive.
+fn normalize_role_mapping(
+    mapping: &BTreeMap<String, ProjectAssetRoleDto>,
+) -> HashMap<String, ProjectAssetRoleDto> {
+    mapping
+        .iter()
+        .map(|(folder, role)| {
+            let folder = folder.trim().replace('\\', "/");
+            (folder.trim_matches('/').to_lowercase(), *role)
+        })
+        .collect()
+}
+
+fn role_for_entry(
+    relative_path: &Path,
+    folder_roles: &HashMap<String, ProjectAssetRoleDto>,
+    formats: &FormatsManifest,
+) -> ProjectAssetRoleDto {
+    for folder in relative_path.parent().into_iter().flat_map(Path::ancestors) {
+        let key = folder
+            .iter()
+            .map(|component| component.to_string_lossy().to_lowercase())
+            .collect::<Vec<_>>()
+            .join("/");
+        if let Some(role) = folder_roles.get(&key) {
+            return *role;
+        }
+    }
+    let extension = relative_path
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    default_asset_role(formats, &extension)
+}
+
+pub(super) async fn register_extracted_file(
+    db: &DbManager,
+    project_uuid: Uuid,
+    project_root: &Path,
+    path: &Path,
+    role: ProjectAssetRoleDto,
+    language_pairs: &[ProjectLanguagePairDto],
+) -> Result<ProjectFileBundle, IpcError> {
+    let file_uuid = Uuid::new_v4();
+    let filename = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let stored_at = path
+        .strip_prefix(project_root)
+        .map(|relative| relative.to_string_lossy().into_owned())
+        .map_err(|_| IpcError::Internal(format!("'{}' is outside the project.", path.display())))?;
+    let size_bytes = tokio::fs::metadata(path)
+        .await
+        .ok()
+        .and_then(|metadata| i64::try_from(metadata.len()).ok());
+    let file_info = NewFileInfoArgs {
+        file_uuid,
+        ext: path
+            .extension()
+            .map(|extension| extension.to_string_lossy().to_lowercase())
+            .unwrap_or_default(),
+        r#type: map_asset_role_to_file_info_type(role),
+        size_bytes,
+        segment_count: None,
+        token_count: None,
+        notes: None,
+    };
+    let link = NewProjectFileArgs {
+        project_uuid,
+        file_uuid,
+        filename,
+        stored_at,
+        r#type: map_asset_role_to_project_file_type(role),
+        language_pairs: file_language_pairs_for_role(role, language_pairs),
+    };
+    db.attach_project_file(file_info, link)
+        .await
+        .map_err(IpcError::from)
+}
+
+async fn read_csv(input_path: &str) -> Result<CsvTable, IpcError> {
+    let input_path = PathBuf::from(input_path.trim());
+    if !input_path.is_absolute() {
+        return Err(IpcError::Validation(
+            "inputPath must be an absolute path.".into(),
+        ));
+    }
+    let bytes = tokio::fs::read(&input_path)
+        .await
+        .map_err(|error| fs_error("read the CSV file", error))?;
+    CsvTable::parse(&String::from_utf8_lossy(&bytes))
+        .map_err(|error| IpcError::Validation(format!("The CSV file could not be read: {error}")))
+}
+
+fn build_report<T>(
+    table: &CsvTable,
+    plan: &ImportPlan<T>,
+    dry_run: bool,
+    uuid_of: impl Fn(&T) -> Uuid,
+) -> CsvImportReportDto {
+    let duplicates = plan.issues.iter().filter(|issue| issue.duplicate).count();
+    CsvImportReportDto {
+        dry_run,
+        total_rows: table.rows.len(),
+        created: plan.records.len(),
+        created_uuids: if dry_run {
+            Vec::new()
+        } else {
+            plan.records
+                .iter()
+                .map(|record| uuid_of(record).to_string())
+                .collect()
+        },
+        duplicates,
+        invalid: plan.issues.len() - duplicates,
+        issues: plan
+            .issues
+            .iter()
+            .map(|issue| CsvRowIssueDto {
+                row: issue.row,
+                field: issue.field.map(str::to_string),
+                message: issue.message.clone(),
+                duplicate: issue.duplicate,
+                duplicate_of: issue.duplicate_of.map(|uuid| uuid.to_string()),
+            })
+            .collect(),
+    }
+}
+
+fn parse_uuid(value: &str, field: &str) -> Result<Uuid, IpcError> {
+    Uuid::parse_str(value)
+        .map_err(|_| IpcError::Validation(format!("invalid {field}: expected UUID, got '{value}'")))
+}
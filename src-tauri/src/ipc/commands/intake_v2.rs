@@ -0,0 +1,357 @@
+use std::path::{Path, PathBuf};
+
+use tauri::State;
+use uuid::Uuid;
+
+use super::projects_v2::default_asset_role;
+use crate::db::DbManager;
+use crate::formats::{self, FormatsManifest};
+use crate::import::roles::{RoleRule, RuleRole, classify, sniff_mime};
+use crate::intake::cat_package::{CatPackageKind, read_package, stage_package};
+use crate::intake::{
+    ClientMatch, StagedFile, folder_name_for, match_clients, parse_email,
+    project_name_from_subject, prune_stale_drafts, stage_email,
+};
+use crate::ipc::dto::{
+    CatPackageDraftDto, CatPackageIntakePayload, CreateProjectWithAssetsPayload,
+    EmailIntakeDraftDto, EmailIntakePayload, IntakeClientMatchDto, ProjectAssetDescriptorDto,
+    ProjectAssetRoleDto, ProjectLanguagePairDto, StageDroppedFilesPayload, StagedAssetDto,
+    default_project_status,
+};
+use crate::ipc::error::{IpcError, IpcResult};
+use crate::metrics;
+use crate::settings::SettingsManager;
+
+/// Drafts a project from a dropped email without creating anything: the attachments and
+/// the body are staged as assets, the subject names the project and the sender suggests
+/// the client. The draft is reviewed and submitted like one from the project wizard.
+#[tauri::command]
+pub async fn draft_project_from_email_v2(
+    settings: State<'_, SettingsManager>,
+    db: State<'_, DbManager>,
+    payload: EmailIntakePayload,
+) -> IpcResult<EmailIntakeDraftDto> {
+    metrics::track("draft_project_from_email_v2", async {
+        let input_path = PathBuf::from(payload.input_path.trim());
+        if !input_path.is_absolute() {
+            return Err(IpcError::Validation("inputPath must be an absolute path.".into()).into());
+        }
+        parse_uuid(&payload.user_uuid, "userUuid")?;
+
+        let email = {
+            let input_path = input_path.clone();
+            tokio::task::spawn_blocking(move || parse_email(&input_path))
+                .await
+                .map_err(|error| IpcError::Internal(format!("Email intake failed: {error}")))?
+                .map_err(|error| {
+                    IpcError::Validation(format!("The email could not be read: {error}"))
+                })?
+        };
+
+        let current = settings.current().await;
+        let formats = formats::current(&current.app_folder).await;
+        let intake_dir = current.intake_dir();
+        let staging_dir = intake_dir.join(Uuid::new_v4().to_string());
+        let staged = {
+            let email = email.clone();
+            let staging_dir = staging_dir.clone();
+            tokio::task::spawn_blocking(move || {
+                prune_stale_drafts(&intake_dir);
+                stage_email(&email, &staging_dir)
+            })
+            .await
+            .map_err(|error| IpcError::Internal(format!("Email intake failed: {error}")))?
+            .map_err(|error| {
+                log::error!(target: "ipc::intake", "failed to stage email attachments: {error:#}");
+                IpcError::Internal("The email attachments could not be saved.".into())
+            })?
+        };
+
+        let clients = match email.sender_address.as_deref() {
+            Some(_) => db.list_client_records(None).await.map_err(IpcError::from)?,
+            None => Vec::new(),
+        };
+        let matches = email
+            .sender_address
+            .as_deref()
+            .map(|address| match_clients(address, &clients))
+            .unwrap_or_default();
+        let mut exact = matches
+            .iter()
+            .filter(|(_, matched_by)| *matched_by == ClientMatch::Email);
+        let client_uuid = match (exact.next(), exact.next()) {
+            (Some((client, _)), None) => Some(client.client_uuid.to_string()),
+            _ => None,
+        };
+
+        let project_name = email
+            .subject
+            .as_deref()
+            .map(project_name_from_subject)
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| {
+                input_path
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().into_owned())
+                    .unwrap_or_default()
+            });
+        let draft = CreateProjectWithAssetsPayload {
+            project_folder_name: folder_name_for(&project_name),
+            project_name,
+            project_status: default_project_status(),
+            user_uuid: payload.user_uuid,
+            client_uuid,
+            r#type: "translation".into(),
+            notes: None,
+            due_date: None,
+            subjects: Vec::new(),
+            language_pairs: vec![ProjectLanguagePairDto {
+                source_lang: current.default_source_language.clone(),
+                target_lang: current.default_target_language.clone(),
+            }],
+            assets: staged
+                .iter()
+                .map(|file| map_staged_file(file, &current.asset_role_rules, &formats))
+                .collect(),
+            pipeline_preset_uuid: None,
+        };
+
+        Ok(EmailIntakeDraftDto {
+            draft,
+            staging_dir: staging_dir.to_string_lossy().into_owned(),
+            sender_name: email.sender_name,
+            sender_address: email.sender_address,
+            client_matches: matches
+                .into_iter()
+                .map(|(client, matched_by)| IntakeClientMatchDto {
+                    client_uuid: client.client_uuid.to_string(),
+                    name: client.name.clone(),
+                    matched_by: matched_by.as_str().to_string(),
+                })
+                .collect(),
+            instructions: email.body,
+        })
+    })
+    .await
+}
+
+/// Drafts a project from a Trados or memoQ package: the bilingual documents are staged as
+/// processable assets and the package as a reference, and the manifest names the project
+/// and gives its languages and due date. Whatever the manifest leaves out comes from the
+/// settings and is listed in the warnings. The draft is reviewed and submitted like one
+/// from the project wizard.
+#[tauri::command]
+pub async fn draft_project_from_cat_package_v2(
+    settings: State<'_, SettingsManager>,
+    payload: CatPackageIntakePayload,
+) -> IpcResult<CatPackageDraftDto> {
+    metrics::track("draft_project_from_cat_package_v2", async {
+        let input_path = PathBuf::from(payload.input_path.trim());
+        if !input_path.is_absolute() {
+            return Err(IpcError::Validation("inputPath must be an absolute path.".into()).into());
+        }
+        parse_uuid(&payload.user_uuid, "userUuid")?;
+
+        let current = settings.current().await;
+        let formats = formats::current(&current.app_folder).await;
+        let intake_dir = current.intake_dir();
+        let staging_dir = intake_dir.join(Uuid::new_v4().to_string());
+        let (package, staged) = {
+            let input_path = input_path.clone();
+            let staging_dir = staging_dir.clone();
+            tokio::task::spawn_blocking(move || {
+                prune_stale_drafts(&intake_dir);
+                let package = read_package(&input_path)?;
+                let staged = stage_package(&input_path, &package, &staging_dir)?;
+                anyhow::Ok((package, staged))
+            })
+            .await
+            .map_err(|error| IpcError::Internal(format!("Package intake failed: {error}")))?
+            .map_err(|error| {
+                IpcError::Validation(format!("The package could not be imported: {error:#}"))
+            })?
+        };
+
+        let manifest = package.manifest;
+        let mut warnings = Vec::new();
+        let source_lang = manifest.source_language.unwrap_or_else(|| {
+            warnings.push("The package names no source language.".to_string());
+            current.default_source_language.clone()
+        });
+        let mut target_langs = manifest.target_languages;
+        if target_langs.is_empty() {
+            warnings.push("The package names no target language.".to_string());
+            target_langs.push(current.default_target_language.clone());
+        }
+        if manifest.due_date.is_none() {
+            warnings.push("The package has no due date.".to_string());
+        }
+        let project_name = manifest
+            .project_name
+            .filter(|name| !name.trim().is_empty())
+            .unwrap_or_else(|| {
+                input_path
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().into_owned())
+                    .unwrap_or_default()
+            });
+        let draft = CreateProjectWithAssetsPayload {
+            project_folder_name: folder_name_for(&project_name),
+            project_name,
+            project_status: default_project_status(),
+            user_uuid: payload.user_uuid,
+            client_uuid: None,
+            r#type: "translation".into(),
+            notes: None,
+            due_date: manifest.due_date,
+            subjects: Vec::new(),
+            language_pairs: target_langs
+                .into_iter()
+                .map(|target_lang| ProjectLanguagePairDto {
+                    source_lang: source_lang.clone(),
+                    target_lang,
+                })
+                .collect(),
+            assets: staged
+                .iter()
+                .map(|file| {
+                    // The package is kept to build the return package from.
+                    let role = match CatPackageKind::from_path(&file.path) {
+                        Some(_) => ProjectAssetRoleDto::Reference,
+                        None => ProjectAssetRoleDto::Processable,
+                    };
+                    classify_asset(
+                        &file.path,
+                        file.name.clone(),
+                        &current.asset_role_rules,
+                        &formats,
+                        Some(role),
+                    )
+                    .asset
+                })
+                .collect(),
+            pipeline_preset_uuid: None,
+        };
+
+        Ok(CatPackageDraftDto {
+            draft,
+            staging_dir: staging_dir.to_string_lossy().into_owned(),
+            package_kind: package.kind.as_str().to_string(),
+            warnings,
+        })
+    })
+    .await
+}
+
+/// Classifies files dropped on the project wizard into asset roles using the rules in the
+/// settings. Nothing is copied; the descriptors are reviewed, roles can still be changed,
+/// and they are submitted with `create_project_with_assets_v2`.
+#[tauri::command]
+pub async fn stage_dropped_files_v2(
+    settings: State<'_, SettingsManager>,
+    payload: StageDroppedFilesPayload,
+) -> IpcResult<Vec<StagedAssetDto>> {
+    metrics::track("stage_dropped_files_v2", async {
+        let mut files = Vec::with_capacity(payload.paths.len());
+        for raw_path in &payload.paths {
+            let path = PathBuf::from(raw_path.trim());
+            if !path.is_absolute() {
+                return Err(IpcError::Validation(format!(
+                    "Dropped file '{raw_path}' must be an absolute path."
+                ))
+                .into());
+            }
+            if !path.is_file() {
+                return Err(IpcError::Validation(format!(
+                    "Dropped file '{raw_path}' was not found or is a folder."
+                ))
+                .into());
+            }
+            let name = path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            let override_role = payload.role_overrides.get(raw_path).copied();
+            files.push((path, name, override_role));
+        }
+
+        let current = settings.current().await;
+        let rules = current.asset_role_rules;
+        let formats = formats::current(&current.app_folder).await;
+        let staged = tokio::task::spawn_blocking(move || {
+            files
+                .into_iter()
+                .map(|(path, name, override_role)| {
+                    classify_asset(&path, name, &rules, &formats, override_role)
+                })
+                .collect::<Vec<_>>()
+        })
+        .await
+        .map_err(|error| {
+            IpcError::Internal(format!("Classifying dropped files failed: {error}"))
+        })?;
+        Ok(staged)
+    })
+    .await
+}
+
+fn map_staged_file(
+    file: &StagedFile,
+    rules: &[RoleRule],
+    formats: &FormatsManifest,
+) -> ProjectAssetDescriptorDto {
+    let override_role = file
+        .instructions
+        .then_some(ProjectAssetRoleDto::Instructions);
+    classify_asset(&file.path, file.name.clone(), rules, formats, override_role).asset
+}
+
+/// Describes a file for the wizard. A role picked by hand wins; otherwise the first rule
+/// matching the file assigns it, and files no rule claims get the role the formats manifest
+/// gives their extension.
+fn classify_asset(
+    path: &Path,
+    name: String,
+    rules: &[RoleRule],
+    formats: &FormatsManifest,
+    override_role: Option<ProjectAssetRoleDto>,
+) -> StagedAssetDto {
+    let extension = Path::new(&name)
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let mime = sniff_mime(path);
+    let (role, role_source, matched_rule) = match override_role {
+        Some(role) => (role, "override", None),
+        None => match classify(rules, &name, mime) {
+            Some((index, role)) => (map_rule_role(role), "rule", Some(index)),
+            None => (default_asset_role(formats, &extension), "extension", None),
+        },
+    };
+    StagedAssetDto {
+        asset: ProjectAssetDescriptorDto {
+            draft_id: Uuid::new_v4().to_string(),
+            name,
+            extension,
+            role,
+            path: path.to_string_lossy().into_owned(),
+        },
+        mime: mime.map(str::to_string),
+        role_source: role_source.to_string(),
+        matched_rule,
+    }
+}
+
+fn map_rule_role(role: RuleRole) -> ProjectAssetRoleDto {
+    match role {
+        RuleRole::Processable => ProjectAssetRoleDto::Processable,
+        RuleRole::Reference => ProjectAssetRoleDto::Reference,
+        RuleRole::Instructions => ProjectAssetRoleDto::Instructions,
+        RuleRole::Image => ProjectAssetRoleDto::Image,
+    }
+}
+
+fn parse_uuid(value: &str, field: &str) -> Result<Uuid, IpcError> {
+    Uuid::parse_str(value)
+        .map_err(|_| IpcError::Validation(format!("invalid {field}: expected UUID, got '{value}'")))
+}
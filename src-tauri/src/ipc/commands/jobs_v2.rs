@@ -1,19 +1,44 @@
+use chrono::Utc;
 use tauri::State;
+use tauri::ipc::InvokeError;
 use uuid::Uuid;
 
+use super::provider_usage_v2::provider_budget_exceeded;
 use crate::db::DbManager;
-use crate::db::types::{JobRecord, NewJobArgs, UpdateJobStatusArgs};
-use crate::ipc::dto::{JobV2Dto, UpdateJobStatusPayload, UpsertJobPayload};
+use crate::db::types::{JobProgressArgs, JobRecord, NewJobArgs, UpdateJobStatusArgs};
+use crate::ipc::dto::{
+    JobRefDto, JobV2Dto, ProviderBudgetExceededDto, UpdateJobProgressPayload,
+    UpdateJobStatusPayload, UpsertJobPayload,
+};
 use crate::ipc::error::{IpcError, IpcResult};
+use crate::jobs::progress::eta_secs;
+use crate::metrics;
+
+/// Status stored for provider jobs held back by an exhausted monthly budget.
+const PAUSED_JOB_STATUS: &str = "PAUSED";
+/// Statuses under which a job is about to send text to its provider.
+const ACTIVE_JOB_STATUSES: [&str; 2] = ["PENDING", "RUNNING"];
 
 #[tauri::command]
 pub async fn upsert_job_record_v2(
     db: State<'_, DbManager>,
     payload: UpsertJobPayload,
 ) -> IpcResult<JobV2Dto> {
-    let args = map_new_job_args(payload)?;
-    let record = db.upsert_job_record(args).await.map_err(IpcError::from)?;
-    Ok(map_job_record(record))
+    metrics::track("upsert_job_record_v2", async {
+        let exceeded =
+            budget_block(db.inner(), payload.provider.as_deref(), &payload.job_status).await?;
+        let mut args = map_new_job_args(payload)?;
+        if let Some(exceeded) = &exceeded {
+            args.job_status = PAUSED_JOB_STATUS.into();
+            args.error_log = Some(exceeded.message.clone());
+        }
+        let record = db.upsert_job_record(args).await.map_err(IpcError::from)?;
+        match exceeded {
+            Some(exceeded) => Err(InvokeError::from(exceeded)),
+            None => Ok(map_job_record(record)),
+        }
+    })
+    .await
 }
 
 #[tauri::command]
@@ -21,12 +46,54 @@ pub async fn update_job_status_v2(
     db: State<'_, DbManager>,
     payload: UpdateJobStatusPayload,
 ) -> IpcResult<Option<JobV2Dto>> {
-    let args = map_update_job_status_args(payload)?;
-    let record = db
-        .update_job_status_record(args)
-        .await
-        .map_err(IpcError::from)?;
-    Ok(record.map(map_job_record))
+    metrics::track("update_job_status_v2", async {
+        let exceeded =
+            budget_block(db.inner(), payload.provider.as_deref(), &payload.job_status).await?;
+        let mut args = map_update_job_status_args(payload)?;
+        if let Some(exceeded) = &exceeded {
+            args.job_status = PAUSED_JOB_STATUS.into();
+            args.error_log = Some(exceeded.message.clone());
+        }
+        let record = db
+            .update_job_status_record(args)
+            .await
+            .map_err(IpcError::from)?;
+        match exceeded {
+            Some(exceeded) if record.is_some() => Err(InvokeError::from(exceeded)),
+            _ => Ok(record.map(map_job_record)),
+        }
+    })
+    .await
+}
+
+/// Stores how far a running job got so progress bars and estimates survive a frontend
+/// restart; `list_jobs_for_project_v2` returns the counters, throughput and ETA.
+#[tauri::command]
+pub async fn update_job_progress_v2(
+    db: State<'_, DbManager>,
+    payload: UpdateJobProgressPayload,
+) -> IpcResult<Option<JobV2Dto>> {
+    metrics::track("update_job_progress_v2", async {
+        let artifact_uuid = parse_uuid(&payload.artifact_uuid, "artifactUuid")?;
+        if payload.processed_units < 0 || payload.total_units.is_some_and(|total| total < 0) {
+            return Err(IpcError::Validation(
+                "processedUnits and totalUnits must not be negative.".into(),
+            )
+            .into());
+        }
+        let record = db
+            .record_job_progress(JobProgressArgs {
+                artifact_uuid,
+                job_type: payload.job_type,
+                processed_units: payload.processed_units,
+                total_units: payload.total_units,
+                recorded_at: Utc::now(),
+            })
+            .await
+            .map_err(IpcError::from)?;
+        Ok(record.map(map_job_record))
+    })
+    .await
 }
 
 #[tauri::command]
@@ -35,11 +102,14 @@ pub async fn delete_job_record_v2(
     artifact_uuid: String,
     job_type: String,
 ) -> IpcResult<()> {
-    let artifact_uuid = parse_uuid(&artifact_uuid, "artifactUuid")?;
-    db.delete_job_record(artifact_uuid, &job_type)
-        .await
-        .map_err(IpcError::from)?;
-    Ok(())
+    metrics::track("delete_job_record_v2", async {
+        let artifact_uuid = parse_uuid(&artifact_uuid, "artifactUuid")?;
+        db.delete_job_record(artifact_uuid, &job_type)
+            .await
+            .map_err(IpcError::from)?;
+        Ok(())
+    })
+    .await
 }
 
 #[tauri::command]
@@ -47,12 +117,57 @@ pub async fn list_jobs_for_project_v2(
     db: State<'_, DbManager>,
     project_uuid: String,
 ) -> IpcResult<Vec<JobV2Dto>> {
-    let project_uuid = parse_uuid(&project_uuid, "projectUuid")?;
-    let jobs = db
-        .list_jobs_for_project(project_uuid)
-        .await
-        .map_err(IpcError::from)?;
-    Ok(jobs.into_iter().map(map_job_record).collect())
+    metrics::track("list_jobs_for_project_v2", async {
+        let project_uuid = parse_uuid(&project_uuid, "projectUuid")?;
+        let jobs = db
+            .list_jobs_for_project(project_uuid)
+            .await
+            .map_err(IpcError::from)?;
+        let dependencies = db
+            .list_job_dependencies_for_project(project_uuid)
+            .await
+            .map_err(IpcError::from)?;
+        Ok(jobs
+            .into_iter()
+            .map(|record| {
+                let depends_on = dependencies
+                    .iter()
+                    .filter(|dependency| {
+                        dependency.artifact_uuid == record.artifact_uuid
+                            && dependency.job_type == record.job_type
+                    })
+                    .map(|dependency| JobRefDto {
+                        artifact_uuid: dependency.depends_on_artifact_uuid.to_string(),
+                        job_type: dependency.depends_on_job_type.clone(),
+                    })
+                    .collect();
+                JobV2Dto {
+                    depends_on,
+                    ..map_job_record(record)
+                }
+            })
+            .collect())
+    })
+    .await
+}
+
+/// Checks the budget of the job's provider when the job is about to run. A job whose provider
+/// is over budget is stored as paused and the caller receives the exceeded report.
+async fn budget_block(
+    db: &DbManager,
+    provider: Option<&str>,
+    job_status: &str,
+) -> Result<Option<ProviderBudgetExceededDto>, IpcError> {
+    let Some(provider) = provider else {
+        return Ok(None);
+    };
+    if !ACTIVE_JOB_STATUSES
+        .iter()
+        .any(|status| status.eq_ignore_ascii_case(job_status.trim()))
+    {
+        return Ok(None);
+    }
+    provider_budget_exceeded(db, provider).await
 }
 
 fn map_new_job_args(payload: UpsertJobPayload) -> Result<NewJobArgs, IpcError> {
@@ -79,13 +194,24 @@ fn map_update_job_status_args(
     })
 }
 
-fn map_job_record(record: JobRecord) -> JobV2Dto {
+pub(super) fn map_job_record(record: JobRecord) -> JobV2Dto {
     JobV2Dto {
         artifact_uuid: record.artifact_uuid.to_string(),
         job_type: record.job_type,
         project_uuid: record.project_uuid.to_string(),
         job_status: record.job_status,
         error_log: record.error_log,
+        depends_on: Vec::new(),
+        processed_units: record.processed_units,
+        total_units: record.total_units,
+        units_per_second: record.units_per_second,
+        eta_seconds: eta_secs(
+            record.processed_units,
+            record.total_units,
+            record.units_per_second,
+        ),
+        started_at: record.started_at,
+        progress_updated_at: record.progress_updated_at,
     }
 }
 
@@ -0,0 +1,279 @@
+//! Export and import of the application-wide language assets (translation memory,
+//! termbases, QA profiles and global segmentation rules) as one portable package.
+
+use std::path::PathBuf;
+
+use tauri::State;
+
+use super::qa_v2::store_profile;
+use super::segmentation_exceptions_v2::validated_exception;
+use crate::db::DbManager;
+use crate::db::types::{NewTermbaseEntryArgs, NewTranslationMemoryEntryArgs, QaProfileRecord};
+use crate::delivery::write_package;
+use crate::ipc::dto::{
+    ExportLanguageAssetsPayload, ImportLanguageAssetsPayload, LanguageAssetsExportDto,
+    LanguageAssetsImportDto,
+};
+use crate::ipc::error::{IpcError, IpcResult};
+use crate::language_assets::{
+    LanguageAssets, SegmentationRuleAsset, TermbaseAsset, TranslationMemoryAsset, package_entries,
+    read_package,
+};
+use crate::metrics;
+use crate::qa::{QaProfileDefinition, QaProfileFile};
+
+/// Writes the whole translation memory, every termbase and QA profile, and the global
+/// segmentation exceptions into one package.
+#[tauri::command]
+pub async fn export_language_assets_v2(
+    db: State<'_, DbManager>,
+    payload: ExportLanguageAssetsPayload,
+) -> IpcResult<LanguageAssetsExportDto> {
+    metrics::track("export_language_assets_v2", async {
+        let output_path = absolute_path(&payload.output_path, "outputPath")?;
+        let assets = collect_assets(db.inner()).await?;
+        let manifest = assets.manifest(chrono::Utc::now().to_rfc3339());
+
+        let path = output_path.clone();
+        let written = manifest.clone();
+        let size_bytes = tokio::task::spawn_blocking(move || -> anyhow::Result<u64> {
+            let entries = package_entries(&written, &assets)?;
+            if path.is_file() {
+                std::fs::remove_file(&path)?;
+            }
+            write_package(&path, &entries)
+        })
+        .await
+        .map_err(|error| IpcError::Internal(format!("Packaging failed: {error}")))?
+        .map_err(|error| {
+            log::error!(
+                target: "ipc::language_assets",
+                "failed to write language asset package: {error:#}"
+            );
+            IpcError::Internal("The language asset package could not be written.".into())
+        })?;
+
+        Ok(LanguageAssetsExportDto {
+            path: output_path.to_string_lossy().into_owned(),
+            size_bytes,
+            translation_memory_entries: manifest.translation_memory_entries,
+            termbase_entries: manifest.termbase_entries,
+            qa_profiles: manifest.qa_profiles,
+            segmentation_rules: manifest.segmentation_rules,
+        })
+    })
+    .await
+}
+
+/// Merges a package into the local assets. The whole package is checked before anything is
+/// stored, so a package with one bad entry changes nothing.
+#[tauri::command]
+pub async fn import_language_assets_v2(
+    db: State<'_, DbManager>,
+    payload: ImportLanguageAssetsPayload,
+) -> IpcResult<LanguageAssetsImportDto> {
+    metrics::track("import_language_assets_v2", async {
+        let input_path = absolute_path(&payload.input_path, "inputPath")?;
+        let (manifest, assets) = {
+            let input_path = input_path.clone();
+            tokio::task::spawn_blocking(move || read_package(&input_path))
+                .await
+                .map_err(|error| IpcError::Internal(format!("Reading failed: {error}")))?
+                .map_err(|error| {
+                    IpcError::Validation(format!(
+                        "'{}' cannot be imported as a language asset package: {error:#}",
+                        input_path.display()
+                    ))
+                })?
+        };
+
+        let memory = assets
+            .translation_memory
+            .into_iter()
+            .map(translation_memory_args)
+            .collect::<Result<Vec<_>, _>>()?;
+        let terms = assets
+            .termbases
+            .into_iter()
+            .map(termbase_args)
+            .collect::<Result<Vec<_>, _>>()?;
+        let rules = assets
+            .segmentation_rules
+            .into_iter()
+            .map(|rule| {
+                validated_exception(
+                    None,
+                    &rule.language,
+                    &rule.kind,
+                    rule.before_text,
+                    rule.after_text,
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        for profile in &assets.qa_profiles {
+            profile.definition.validate().map_err(|error| {
+                IpcError::Validation(format!("QA profile '{}': {error:#}", profile.name))
+            })?;
+        }
+
+        let stored_memory = db
+            .upsert_translation_memory_entries(&memory)
+            .await
+            .map_err(IpcError::from)?;
+        let added_terms = db
+            .add_termbase_entries(&terms)
+            .await
+            .map_err(IpcError::from)?;
+        let added_rules = db
+            .add_missing_segmentation_exceptions(&rules)
+            .await
+            .map_err(IpcError::from)?;
+        let profile_count = assets.qa_profiles.len();
+        for profile in assets.qa_profiles {
+            store_profile(db.inner(), profile).await?;
+        }
+
+        log::info!(
+            target: "ipc::language_assets",
+            "imported language assets from {}",
+            input_path.display()
+        );
+        Ok(LanguageAssetsImportDto {
+            exported_at: manifest.exported_at,
+            translation_memory_entries: stored_memory.len(),
+            termbase_entries: added_terms.len(),
+            qa_profiles: profile_count,
+            segmentation_rules: added_rules.len(),
+        })
+    })
+    .await
+}
+
+async fn collect_assets(db: &DbManager) -> Result<LanguageAssets, IpcError> {
+    let translation_memory = db
+        .list_all_translation_memory_entries()
+        .await
+        .map_err(IpcError::from)?
+        .into_iter()
+        .map(|entry| TranslationMemoryAsset {
+            source_lang: entry.source_lang,
+            target_lang: entry.target_lang,
+            source_text: entry.source_text,
+            target_text: entry.target_text,
+            origin: entry.origin,
+        })
+        .collect();
+    let termbases = db
+        .list_all_termbase_entries()
+        .await
+        .map_err(IpcError::from)?
+        .into_iter()
+        .map(|entry| TermbaseAsset {
+            termbase: entry.termbase,
+            source_lang: entry.source_lang,
+            target_lang: entry.target_lang,
+            source_term: entry.source_term,
+            target_term: entry.target_term,
+        })
+        .collect();
+    let qa_profiles = db
+        .list_qa_profiles()
+        .await
+        .map_err(IpcError::from)?
+        .into_iter()
+        .map(profile_file)
+        .collect::<Result<Vec<_>, _>>()?;
+    let segmentation_rules = db
+        .list_segmentation_exceptions(None)
+        .await
+        .map_err(IpcError::from)?
+        .into_iter()
+        .map(|exception| SegmentationRuleAsset {
+            language: exception.language,
+            kind: exception.kind,
+            before_text: exception.before_text,
+            after_text: exception.after_text,
+        })
+        .collect();
+    Ok(LanguageAssets {
+        translation_memory,
+        termbases,
+        qa_profiles,
+        segmentation_rules,
+    })
+}
+
+fn profile_file(record: QaProfileRecord) -> Result<QaProfileFile, IpcError> {
+    let definition: QaProfileDefinition =
+        serde_json::from_str(&record.definition_json).map_err(|error| {
+            IpcError::Internal(format!(
+                "The definition of QA profile '{}' is unreadable: {error}",
+                record.name
+            ))
+        })?;
+    Ok(QaProfileFile::new(
+        record.name,
+        record.description,
+        definition,
+    ))
+}
+
+fn translation_memory_args(
+    entry: TranslationMemoryAsset,
+) -> Result<NewTranslationMemoryEntryArgs, IpcError> {
+    if [
+        &entry.source_lang,
+        &entry.target_lang,
+        &entry.source_text,
+        &entry.target_text,
+    ]
+    .iter()
+    .any(|value| value.trim().is_empty())
+    {
+        return Err(IpcError::Validation(
+            "Translation memory entries need languages, a source and a target.".into(),
+        ));
+    }
+    Ok(NewTranslationMemoryEntryArgs {
+        source_lang: entry.source_lang,
+        target_lang: entry.target_lang,
+        source_text: entry.source_text,
+        target_text: entry.target_text,
+        project_uuid: None,
+        origin: entry.origin,
+    })
+}
+
+fn termbase_args(entry: TermbaseAsset) -> Result<NewTermbaseEntryArgs, IpcError> {
+    if [
+        &entry.termbase,
+        &entry.source_lang,
+        &entry.target_lang,
+        &entry.source_term,
+    ]
+    .iter()
+    .any(|value| value.trim().is_empty())
+    {
+        return Err(IpcError::Validation(
+            "Termbase entries need a termbase, languages and a source term.".into(),
+        ));
+    }
+    Ok(NewTermbaseEntryArgs {
+        termbase: entry.termbase,
+        source_lang: entry.source_lang,
+        target_lang: entry.target_lang,
+        source_term: entry.source_term,
+        target_term: entry.target_term,
+        project_uuid: None,
+    })
+}
+
+fn absolute_path(value: &str, field: &str) -> Result<PathBuf, IpcError> {
+    let path = PathBuf::from(value.trim());
+    if !path.is_absolute() {
+        return Err(IpcError::Validation(format!(
+            "{field} must be an absolute path."
+        )));
+    }
+    Ok(path)
+}
@@ -0,0 +1,126 @@
+//! Sanity checks on the direction of project language pairs. A sample of each pair's
+//! source segments is run through language detection; a sample that reads as the target
+//! language means the pair was most likely entered the wrong way round, which the swap
+//! command corrects in one step.
+
+use tauri::State;
+use uuid::Uuid;
+
+use super::projects_v2::map_project_bundle;
+use super::translation_memory_v2::load_pair_documents;
+use crate::db::DbManager;
+use crate::ipc::dto::{
+    LanguageDirectionCheckDto, LanguageDirectionDto, ProjectBundleV2Dto, SwapLanguagePairPayload,
+};
+use crate::ipc::error::{IpcError, IpcResult};
+use crate::language_detection::{PairDirection, check_direction};
+use crate::metrics;
+use crate::settings::SettingsManager;
+
+/// Most source segments sampled per language pair, spread over its documents.
+const SAMPLE_SEGMENTS: usize = 200;
+
+/// Checks every language pair of a project against the detected language of its converted
+/// source text. Pairs without converted documents are reported as undetermined.
+#[tauri::command]
+pub async fn check_language_direction_v2(
+    db: State<'_, DbManager>,
+    settings: State<'_, SettingsManager>,
+    project_uuid: String,
+) -> IpcResult<Vec<LanguageDirectionCheckDto>> {
+    metrics::track("check_language_direction_v2", async {
+        let project_uuid = parse_uuid(&project_uuid, "projectUuid")?;
+        let bundle = db
+            .get_project_bundle(project_uuid)
+            .await
+            .map_err(IpcError::from)?
+            .ok_or_else(|| {
+                IpcError::Validation(format!("Project {project_uuid} was not found."))
+            })?;
+
+        let mut checks = Vec::with_capacity(bundle.language_pairs.len());
+        for pair in &bundle.language_pairs {
+            let documents = load_pair_documents(
+                db.inner(),
+                settings.inner(),
+                project_uuid,
+                &pair.source_lang,
+                &pair.target_lang,
+            )
+            .await?;
+            let sources: Vec<&str> = documents
+                .iter()
+                .flat_map(|(_, document)| document.transunits.iter())
+                .map(|unit| unit.source.as_str())
+                .filter(|source| !source.trim().is_empty())
+                .collect();
+            let step = sources.len().div_ceil(SAMPLE_SEGMENTS).max(1);
+            let sample: Vec<&str> = sources.into_iter().step_by(step).collect();
+
+            let (detection, direction) =
+                check_direction(&sample, &pair.source_lang, &pair.target_lang);
+            if direction == PairDirection::Reversed {
+                log::warn!(
+                    target: "ipc::language_direction",
+                    "source text of project {project_uuid} reads as {} ({} → {} looks reversed)",
+                    pair.target_lang,
+                    pair.source_lang,
+                    pair.target_lang
+                );
+            }
+            checks.push(LanguageDirectionCheckDto {
+                source_lang: pair.source_lang.clone(),
+                target_lang: pair.target_lang.clone(),
+                sampled_segments: sample.len(),
+                detected_language: detection.map(|detection| detection.language.to_string()),
+                confidence: detection.map(|detection| detection.confidence),
+                direction: match direction {
+                    PairDirection::Consistent => LanguageDirectionDto::Consistent,
+                    PairDirection::Reversed => LanguageDirectionDto::Reversed,
+                    PairDirection::Unexpected => LanguageDirectionDto::Unexpected,
+                    PairDirection::Undetermined => LanguageDirectionDto::Undetermined,
+                },
+            });
+        }
+        Ok(checks)
+    })
+    .await
+}
+
+/// Reverses a project language pair, and the file language pairs using it. Documents
+/// converted for the old direction are left in place; the next conversion plan converts
+/// the files for the new one.
+#[tauri::command]
+pub async fn swap_language_pair_v2(
+    db: State<'_, DbManager>,
+    payload: SwapLanguagePairPayload,
+) -> IpcResult<ProjectBundleV2Dto> {
+    metrics::track("swap_language_pair_v2", async {
+        let project_uuid = parse_uuid(&payload.project_uuid, "projectUuid")?;
+        let source_lang = payload.pair.source_lang.trim();
+        let target_lang = payload.pair.target_lang.trim();
+        if source_lang.is_empty() || target_lang.is_empty() {
+            return Err(
+                IpcError::Validation("pair needs a source and a target language.".into()).into(),
+            );
+        }
+        let bundle = db
+            .swap_project_language_pair(project_uuid, source_lang, target_lang)
+            .await
+            .map_err(IpcError::from)?
+            .ok_or_else(|| {
+                IpcError::Validation(format!("Project {project_uuid} was not found."))
+            })?;
+        log::info!(
+            target: "ipc::language_direction",
+            "swapped language pair {source_lang} → {target_lang} of project {project_uuid}"
+        );
+        Ok(map_project_bundle(bundle))
+    })
+    .await
+}
+
+fn parse_uuid(value: &str, field: &str) -> Result<Uuid, IpcError> {
+    Uuid::parse_str(value)
+        .map_err(|_| IpcError::Validation(format!("invalid {field}: expected UUID, got '{value}'")))
+}
@@ -0,0 +1,120 @@
+use std::collections::BTreeMap;
+
+use log::warn;
+use tauri::State;
+
+use super::projects_v2::resolve_project_root;
+use super::shared::{list_project_jliff_paths, load_project_jliff};
+use crate::db::DbManager;
+use crate::db::types::LanguagePairActivityRecord;
+use crate::ipc::dto::LanguagePairMatrixEntryDto;
+use crate::ipc::error::{IpcError, IpcResult};
+use crate::jliff::statistics::{WordCountStrategy, source_word_count};
+use crate::metrics;
+use crate::settings::SettingsManager;
+
+/// Summarizes every language pair across all projects: source words and how many of them
+/// are translated, from the JLIFF documents on disk, plus delivery turnaround and TM size
+/// from the database. Projects whose folder or documents cannot be read are left out of the
+/// word counts.
+#[tauri::command]
+pub async fn get_language_pair_matrix_v2(
+    db: State<'_, DbManager>,
+    settings: State<'_, SettingsManager>,
+) -> IpcResult<Vec<LanguagePairMatrixEntryDto>> {
+    metrics::track("get_language_pair_matrix_v2", async {
+        let mut entries: BTreeMap<(String, String), LanguagePairMatrixEntryDto> = db
+            .list_language_pair_activity()
+            .await
+            .map_err(IpcError::from)?
+            .into_iter()
+            .map(|record| {
+                (
+                    (record.source_lang.clone(), record.target_lang.clone()),
+                    map_activity_record(record),
+                )
+            })
+            .collect();
+
+        let projects = db
+            .list_project_records(None)
+            .await
+            .map_err(IpcError::from)?;
+        for project in projects {
+            let project_uuid = project.project_uuid;
+            let project_root =
+                match resolve_project_root(db.inner(), settings.inner(), project_uuid).await {
+                    Ok(root) => root,
+                    Err(error) => {
+                        warn!(
+                            target: "ipc::language_pairs",
+                            "skipping project {project_uuid} in the language pair matrix: {error}"
+                        );
+                        continue;
+                    }
+                };
+            let Ok(jliff_paths) = list_project_jliff_paths(&project_root).await else {
+                continue;
+            };
+            for jliff_rel_path in jliff_paths {
+                let Ok((_, document)) =
+                    load_project_jliff(db.inner(), settings.inner(), project_uuid, &jliff_rel_path)
+                        .await
+                else {
+                    continue;
+                };
+                let source_lang = document.source_language.trim().to_lowercase();
+                let target_lang = document.target_language.trim().to_lowercase();
+                let entry = entries
+                    .entry((source_lang.clone(), target_lang.clone()))
+                    .or_insert_with(|| empty_entry(source_lang, target_lang));
+                let strategy = WordCountStrategy::for_source(&document);
+                entry.document_count += 1;
+                for unit in &document.transunits {
+                    let words = source_word_count(unit, strategy);
+                    entry.total_words += words;
+                    if unit.effective_status().is_confirmed() {
+                        entry.translated_words += words;
+                    }
+                }
+            }
+        }
+
+        Ok(entries
+            .into_values()
+            .map(|mut entry| {
+                if entry.total_words > 0 {
+                    entry.completion_percent =
+                        entry.translated_words as f64 / entry.total_words as f64 * 100.0;
+                }
+                entry
+            })
+            .collect())
+    })
+    .await
+}
+
+fn map_activity_record(record: LanguagePairActivityRecord) -> LanguagePairMatrixEntryDto {
+    LanguagePairMatrixEntryDto {
+        project_count: record.project_count,
+        delivered_packages: record.delivered_packages,
+        average_turnaround_hours: record.average_turnaround_hours,
+        tm_entries: record.tm_entries,
+        ..empty_entry(record.source_lang, record.target_lang)
+    }
+}
+
+fn empty_entry(source_lang: String, target_lang: String) -> LanguagePairMatrixEntryDto {
+    LanguagePairMatrixEntryDto {
+        source_lang,
+        target_lang,
+        project_count: 0,
+        document_count: 0,
+        total_words: 0,
+        translated_words: 0,
+        completion_percent: 0.0,
+        delivered_packages: 0,
+        average_turnaround_hours: None,
+        tm_entries: 0,
+    }
+}
@@ -0,0 +1,235 @@
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use serde_json::json;
+use tauri::State;
+use uuid::Uuid;
+
+use super::qa_v2::{map_qa_finding_record, project_qa_profile};
+use super::shared::{fs_error, load_cached_tag_map, load_project_jliff, update_project_jliff};
+use crate::db::DbManager;
+use crate::db::types::{NewQaFindingArgs, ReplaceQaFindingsArgs};
+use crate::ipc::dto::{
+    CheckLengthLimitsPayload, FileLengthLimitDto, ImportLengthLimitsPayload, LengthLimitsImportDto,
+    LengthLimitsQaDto, SetFileLengthLimitPayload,
+};
+use crate::ipc::error::{IpcError, IpcResult};
+use crate::jliff::length_limits::{LengthLimitIssue, check_length_limit, parse_length_limits_csv};
+use crate::metrics;
+use crate::settings::SettingsManager;
+
+/// QA check identifier used when persisting length-limit findings.
+pub(super) const LENGTH_LIMIT_CHECK: &str = "length_limit";
+
+/// Checks every target against the `slr:sizeRestriction` carried in its unit metadata, or the
+/// QA profile's limit for the target language, and replaces the stored length-limit
+/// findings for the document.
+#[tauri::command]
+pub async fn check_length_limits_v2(
+    db: State<'_, DbManager>,
+    settings: State<'_, SettingsManager>,
+    payload: CheckLengthLimitsPayload,
+) -> IpcResult<LengthLimitsQaDto> {
+    metrics::track("check_length_limits_v2", async {
+        let project_uuid = parse_uuid(&payload.project_uuid, "projectUuid")?;
+        let (jliff_path, document) = load_project_jliff(
+            db.inner(),
+            settings.inner(),
+            project_uuid,
+            &payload.jliff_rel_path,
+        )
+        .await?;
+
+        let profile = project_qa_profile(db.inner(), project_uuid).await?;
+        let check = profile.check(LENGTH_LIMIT_CHECK, "critical");
+        let default_max_length = profile.max_length_for(&document.target_language);
+
+        // Without a tag map protected terms count as zero-length placeholders.
+        let tag_map = load_cached_tag_map(&jliff_path, &payload.jliff_rel_path)
+            .await
+            .ok();
+
+        let mut findings = Vec::new();
+        let mut segments_checked = 0;
+        for unit in document.transunits.iter().filter(|_| check.enabled) {
+            let has_limit = default_max_length.is_some()
+                || unit
+                    .metadata
+                    .as_ref()
+                    .is_some_and(|metadata| metadata.max_length.is_some());
+            if !has_limit || unit.effective_target().trim().is_empty() {
+                continue;
+            }
+            segments_checked += 1;
+
+            let segment = tag_map
+                .as_ref()
+                .and_then(|tag_map| tag_map.segment_for(&unit.unit_id, &unit.transunit_id));
+            if let Some(issue) = check_length_limit(unit, segment, default_max_length) {
+                findings.push(length_limit_finding(
+                    &unit.transunit_id,
+                    &issue,
+                    &check.severity,
+                ));
+            }
+        }
+
+        let records = db
+            .replace_qa_findings(ReplaceQaFindingsArgs {
+                project_uuid,
+                jliff_rel_path: payload.jliff_rel_path.clone(),
+                check_type: LENGTH_LIMIT_CHECK.into(),
+                transunit_ids: None,
+                findings,
+            })
+            .await
+            .map_err(IpcError::from)?;
+
+        Ok(LengthLimitsQaDto {
+            jliff_rel_path: payload.jliff_rel_path,
+            segments_checked,
+            findings: records.into_iter().map(map_qa_finding_record).collect(),
+        })
+    })
+    .await
+}
+
+/// Sets segment length limits from a CSV column, matching rows to units by unit id or
+/// segment id (a unit id covers all of its segments).
+#[tauri::command]
+pub async fn import_length_limits_v2(
+    db: State<'_, DbManager>,
+    settings: State<'_, SettingsManager>,
+    payload: ImportLengthLimitsPayload,
+) -> IpcResult<LengthLimitsImportDto> {
+    metrics::track("import_length_limits_v2", async {
+        let project_uuid = parse_uuid(&payload.project_uuid, "projectUuid")?;
+        let input_path = PathBuf::from(payload.input_path.trim());
+        if !input_path.is_absolute() {
+            return Err(IpcError::Validation("inputPath must be an absolute path.".into()).into());
+        }
+        let bytes = tokio::fs::read(&input_path)
+            .await
+            .map_err(|error| fs_error("read the length limit file", error))?;
+        let parsed = parse_length_limits_csv(
+            &String::from_utf8_lossy(&bytes),
+            payload.id_column.as_deref().unwrap_or("id"),
+            payload.max_length_column.as_deref().unwrap_or("max_length"),
+        )
+        .map_err(|error| {
+            IpcError::Validation(format!("The length limit file could not be read: {error}"))
+        })?;
+        let skipped_rows = parsed.skipped_rows;
+
+        let (updated_segments, unknown_ids) = update_project_jliff(
+            db.inner(),
+            settings.inner(),
+            project_uuid,
+            &payload.jliff_rel_path,
+            move |document| {
+                let mut updated = HashSet::new();
+                let mut unknown_ids = Vec::new();
+                for (id, max_length) in parsed.limits {
+                    let mut matched = false;
+                    for unit in document
+                        .transunits
+                        .iter_mut()
+                        .filter(|unit| unit.unit_id == id || unit.transunit_id == id)
+                    {
+                        matched = true;
+                        unit.metadata
+                            .get_or_insert_with(Default::default)
+                            .max_length = Some(max_length);
+                        updated.insert(unit.transunit_id.clone());
+                    }
+                    if !matched {
+                        unknown_ids.push(id);
+                    }
+                }
+                Ok((updated.len(), unknown_ids))
+            },
+        )
+        .await?;
+
+        Ok(LengthLimitsImportDto {
+            jliff_rel_path: payload.jliff_rel_path,
+            updated_segments,
+            skipped_rows,
+            unknown_ids,
+        })
+    })
+    .await
+}
+
+/// Applies one length limit to the segments of a file, e.g. for a string table whose
+/// entries share a display width. Existing limits are kept unless `overwrite` is set.
+#[tauri::command]
+pub async fn set_file_length_limit_v2(
+    db: State<'_, DbManager>,
+    settings: State<'_, SettingsManager>,
+    payload: SetFileLengthLimitPayload,
+) -> IpcResult<FileLengthLimitDto> {
+    metrics::track("set_file_length_limit_v2", async {
+        let project_uuid = parse_uuid(&payload.project_uuid, "projectUuid")?;
+        if payload.max_length == Some(0) {
+            return Err(IpcError::Validation("maxLength must be greater than zero.".into()).into());
+        }
+        let max_length = payload.max_length;
+        let overwrite = payload.overwrite;
+
+        let updated_segments = update_project_jliff(
+            db.inner(),
+            settings.inner(),
+            project_uuid,
+            &payload.jliff_rel_path,
+            move |document| {
+                let mut updated = 0;
+                for unit in &mut document.transunits {
+                    let current = unit
+                        .metadata
+                        .as_ref()
+                        .and_then(|metadata| metadata.max_length);
+                    if current == max_length || (current.is_some() && !overwrite) {
+                        continue;
+                    }
+                    unit.metadata
+                        .get_or_insert_with(Default::default)
+                        .max_length = max_length;
+                    updated += 1;
+                }
+                Ok(updated)
+            },
+        )
+        .await?;
+
+        Ok(FileLengthLimitDto {
+            jliff_rel_path: payload.jliff_rel_path,
+            updated_segments,
+        })
+    })
+    .await
+}
+
+/// QA finding describing a target that exceeds its length limit.
+pub(super) fn length_limit_finding(
+    transunit_id: &str,
+    issue: &LengthLimitIssue,
+    severity: &str,
+) -> NewQaFindingArgs {
+    NewQaFindingArgs {
+        transunit_id: transunit_id.to_string(),
+        severity: severity.to_string(),
+        message: format!(
+            "Target is {} characters long, {} over the limit of {}.",
+            issue.length,
+            issue.length - issue.max_length,
+            issue.max_length
+        ),
+        details: Some(json!(issue).to_string()),
+    }
+}
+
+fn parse_uuid(value: &str, field: &str) -> Result<Uuid, IpcError> {
+    Uuid::parse_str(value)
+        .map_err(|_| IpcError::Validation(format!("invalid {field}: expected UUID, got '{value}'")))
+}
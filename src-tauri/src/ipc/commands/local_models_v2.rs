@@ -0,0 +1,139 @@
+use tauri::State;
+use uuid::Uuid;
+
+use super::outbound_audit_v2::record_outbound_request;
+use super::prompt_templates_v2::load_project_prompt;
+use crate::db::DbManager;
+use crate::ipc::dto::{
+    LocalModelCapabilitiesDto, LocalModelTranslationDto, LocalModelTranslationPayload,
+};
+use crate::ipc::error::{IpcError, IpcResult};
+use crate::metrics;
+use crate::providers::{LOCAL_PROVIDER, LocalModelClient, chunk_budget, chunk_text};
+use crate::settings::SettingsManager;
+
+/// Detects the local model server at `baseUrl` (the configured address by default) and
+/// reports its models and context window.
+#[tauri::command]
+pub async fn probe_local_model_v2(
+    settings: State<'_, SettingsManager>,
+    local: State<'_, LocalModelClient>,
+    base_url: Option<String>,
+    model: Option<String>,
+) -> IpcResult<LocalModelCapabilitiesDto> {
+    metrics::track("probe_local_model_v2", async {
+        let current = settings.current().await;
+        let base_url = base_url
+            .map(|value| value.trim().trim_end_matches('/').to_string())
+            .filter(|value| !value.is_empty())
+            .unwrap_or(current.local_model_base_url);
+        let model = model
+            .or(Some(current.local_model_name))
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty());
+
+        let capabilities = local
+            .probe(&base_url, model.as_deref())
+            .await
+            .map_err(|error| IpcError::Validation(error.to_string()))?;
+        Ok(LocalModelCapabilitiesDto {
+            base_url,
+            server: capabilities.server.as_str().to_string(),
+            model: model.or_else(|| capabilities.models.first().cloned()),
+            models: capabilities.models,
+            context_tokens: capabilities.context_tokens,
+            chunk_chars: chunk_budget(capabilities.context_tokens),
+        })
+    })
+    .await
+}
+
+/// Translates text with the configured local model. Text that does not fit the model's
+/// context is split at paragraph or sentence boundaries and translated chunk by chunk.
+/// With a `projectUuid`, the project's prompt template and protected terms are used.
+#[tauri::command]
+pub async fn translate_with_local_model_v2(
+    db: State<'_, DbManager>,
+    settings: State<'_, SettingsManager>,
+    local: State<'_, LocalModelClient>,
+    payload: LocalModelTranslationPayload,
+) -> IpcResult<LocalModelTranslationDto> {
+    metrics::track("translate_with_local_model_v2", async {
+        let current = settings.current().await;
+        let base_url = current.local_model_base_url;
+        let model = current.local_model_name.trim().to_string();
+        if model.is_empty() {
+            return Err(IpcError::Validation(
+                "No local model is selected. Choose one in the local model settings.".into(),
+            )
+            .into());
+        }
+
+        let prompt = load_project_prompt(
+            db.inner(),
+            payload.project_uuid.as_deref(),
+            &payload.source_lang,
+            &payload.target_lang,
+            payload.terms,
+        )
+        .await?;
+
+        // Size chunks from the reported context; an unreachable server fails on the first
+        // request with a clearer error.
+        let context_tokens = local
+            .probe(&base_url, Some(&model))
+            .await
+            .ok()
+            .and_then(|capabilities| capabilities.context_tokens);
+        let chunks = chunk_text(&payload.source, chunk_budget(context_tokens));
+        let project_uuid = payload
+            .project_uuid
+            .as_deref()
+            .map(|value| parse_uuid(value, "projectUuid"))
+            .transpose()?;
+        record_outbound_request(
+            db.inner(),
+            LOCAL_PROVIDER,
+            project_uuid,
+            1,
+            payload.source.chars().count() as i64,
+            Some(&base_url),
+        )
+        .await?;
+
+        let mut text = String::new();
+        for chunk in &chunks {
+            // Whitespace at chunk edges is kept verbatim; models tend to drop it.
+            let trimmed = chunk.trim();
+            let leading = &chunk[..chunk.len() - chunk.trim_start().len()];
+            let trailing = &chunk[chunk.trim_end().len()..];
+            text.push_str(leading);
+            if !trimmed.is_empty() {
+                let messages = prompt.render(
+                    &payload.source_lang,
+                    &payload.target_lang,
+                    trimmed,
+                    payload.context.as_deref(),
+                );
+                let translated = local
+                    .complete(&base_url, &model, &messages)
+                    .await
+                    .map_err(|error| IpcError::Internal(error.to_string()))?;
+                text.push_str(&translated);
+            }
+            text.push_str(trailing);
+        }
+
+        Ok(LocalModelTranslationDto {
+            text,
+            model,
+            chunks: chunks.len(),
+        })
+    })
+    .await
+}
+
+fn parse_uuid(value: &str, field: &str) -> Result<Uuid, IpcError> {
+    Uuid::parse_str(value)
+        .map_err(|_| IpcError::Validation(format!("invalid {field}: expected UUID, got '{value}'")))
+}
@@ -0,0 +1,43 @@
+use crate::ipc::dto::IpcCommandMetricsDto;
+use crate::ipc::error::IpcResult;
+use crate::metrics::{self, CommandMetrics};
+
+/// Returns latency, failure and payload counters of every IPC command called since the
+/// app started, slowest in total first.
+#[tauri::command]
+pub async fn get_ipc_metrics_v2() -> IpcResult<Vec<IpcCommandMetricsDto>> {
+    metrics::track("get_ipc_metrics_v2", async {
+        Ok(metrics::ipc_metrics()
+            .snapshot()
+            .into_iter()
+            .map(map_command_metrics)
+            .collect())
+    })
+    .await
+}
+
+/// Clears the IPC counters, e.g. before reproducing a slow workflow.
+#[tauri::command]
+pub async fn reset_ipc_metrics_v2() -> IpcResult<()> {
+    metrics::track("reset_ipc_metrics_v2", async {
+        metrics::ipc_metrics().reset();
+        Ok(())
+    })
+    .await
+}
+
+fn map_command_metrics(metrics: CommandMetrics) -> IpcCommandMetricsDto {
+    let calls = metrics.calls.max(1) as f64;
+    IpcCommandMetricsDto {
+        command: metrics.command,
+        calls: metrics.calls,
+        failures: metrics.failures,
+        error_rate: metrics.failures as f64 / calls,
+        slow_calls: metrics.slow_calls,
+        avg_duration_ms: metrics.total_duration.as_secs_f64() * 1000.0
+            / metrics.timed_calls.max(1) as f64,
+        max_duration_ms: metrics.max_duration.as_secs_f64() * 1000.0,
+        payload_bytes: metrics.payload_bytes,
+        avg_payload_bytes: metrics.payload_bytes as f64 / calls,
+    }
+}
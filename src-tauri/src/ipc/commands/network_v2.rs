@@ -0,0 +1,82 @@
+//! Connectivity checks for the outbound integrations, sent through the shared HTTP client so
+//! they show whether the proxy settings work.
+
+use std::time::{Duration, Instant};
+
+use tauri::State;
+
+use super::places::GOOGLE_AUTOCOMPLETE_URL;
+use crate::currency::ecb::ECB_DAILY_URL;
+use crate::http;
+use crate::ipc::dto::ConnectivityResultDto;
+use crate::ipc::error::{IpcError, IpcResult};
+use crate::metrics;
+use crate::providers::LlmClient;
+use crate::settings::SettingsManager;
+use crate::tax::vies::VIES_URL;
+use crate::updater::Updater;
+
+/// Services `test_connectivity_v2` can reach.
+const CONNECTIVITY_PROVIDERS: [&str; 7] = [
+    "llm",
+    "local_model",
+    "places",
+    "vies",
+    "ecb",
+    "updates",
+    "webhook",
+];
+const CONNECTIVITY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Sends one request to the endpoint of `provider`. Any HTTP answer, an error status
+/// included, counts as reachable: only the connection is tested, not the credentials.
+#[tauri::command]
+pub async fn test_connectivity_v2(
+    settings: State<'_, SettingsManager>,
+    llm: State<'_, LlmClient>,
+    updater: State<'_, Updater>,
+    provider: String,
+) -> IpcResult<ConnectivityResultDto> {
+    metrics::track("test_connectivity_v2", async {
+        let provider = provider.trim();
+        let url = match provider {
+            "llm" => Some(llm.base_url().to_string()),
+            "local_model" => Some(settings.current().await.local_model_base_url),
+            "places" => Some(GOOGLE_AUTOCOMPLETE_URL.to_string()),
+            "vies" => Some(VIES_URL.to_string()),
+            "ecb" => Some(ECB_DAILY_URL.to_string()),
+            "updates" => updater.feed_url().map(str::to_string),
+            "webhook" => settings.current().await.event_webhook_url,
+            _ => {
+                return Err(IpcError::Validation(format!(
+                    "unknown provider '{provider}'. Expected one of: {}.",
+                    CONNECTIVITY_PROVIDERS.join(", ")
+                ))
+                .into());
+            }
+        }
+        .ok_or_else(|| IpcError::Validation(format!("No endpoint is set up for '{provider}'.")))?;
+
+        let client = http::client_builder()
+            .timeout(CONNECTIVITY_TIMEOUT)
+            .build()
+            .map_err(|error| IpcError::Internal(format!("HTTP client unavailable: {error}")))?;
+        let started = Instant::now();
+        let response = client.get(&url).send().await;
+        let latency_ms = u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX);
+        let (status, error) = match response {
+            Ok(response) => (Some(response.status().as_u16()), None),
+            Err(error) => (None, Some(format!("{:#}", anyhow::Error::from(error)))),
+        };
+        Ok(ConnectivityResultDto {
+            provider: provider.to_string(),
+            url,
+            reachable: status.is_some(),
+            status,
+            latency_ms,
+            proxy_url: http::proxy().map(|proxy| proxy.url),
+            error,
+        })
+    })
+    .await
+}
@@ -0,0 +1,198 @@
+//! Project notes: create, edit, pin and delete notes, list them page by page, and list the
+//! notes that mention a user.
+
+use tauri::State;
+use uuid::Uuid;
+
+use crate::db::DbManager;
+use crate::db::types::{NewNoteArgs, Note, UpdateNoteArgs};
+use crate::ipc::dto::{
+    CreateProjectNotePayload, ListProjectNotesPayload, NoteMentionDto, ProjectNoteDto,
+    ProjectNotePageDto, UpdateProjectNotePayload,
+};
+use crate::ipc::error::{IpcError, IpcResult};
+use crate::metrics;
+
+/// Notes returned per page unless the request asks for fewer.
+const MAX_NOTES_PER_PAGE: usize = 100;
+/// Longest note body, in characters.
+const MAX_NOTE_CHARS: usize = 20_000;
+
+#[tauri::command]
+pub async fn create_project_note_v2(
+    db: State<'_, DbManager>,
+    payload: CreateProjectNotePayload,
+) -> IpcResult<ProjectNoteDto> {
+    metrics::track("create_project_note_v2", async {
+        let project_uuid = parse_uuid(&payload.project_uuid, "projectUuid")?;
+        let author_user_uuid = parse_uuid(&payload.author_user_uuid, "authorUserUuid")?;
+        let body = validate_body(&payload.body)?;
+        if db
+            .get_project_bundle(project_uuid)
+            .await
+            .map_err(IpcError::from)?
+            .is_none()
+        {
+            return Err(
+                IpcError::Validation(format!("Project {project_uuid} was not found.")).into(),
+            );
+        }
+
+        let note = db
+            .insert_note(NewNoteArgs {
+                project_id: project_uuid,
+                author_user_id: author_user_uuid.to_string(),
+                body,
+                is_markdown: payload.is_markdown,
+                pinned: payload.pinned,
+            })
+            .await
+            .map_err(IpcError::from)?;
+        Ok(map_note(note))
+    })
+    .await
+}
+
+/// Edits, pins or unpins a note. Returns `null` when the note does not exist.
+#[tauri::command]
+pub async fn update_project_note_v2(
+    db: State<'_, DbManager>,
+    payload: UpdateProjectNotePayload,
+) -> IpcResult<Option<ProjectNoteDto>> {
+    metrics::track("update_project_note_v2", async {
+        let note_uuid = parse_uuid(&payload.note_uuid, "noteUuid")?;
+        let body = payload.body.as_deref().map(validate_body).transpose()?;
+        let note = db
+            .update_note(UpdateNoteArgs {
+                note_id: note_uuid,
+                body,
+                is_markdown: payload.is_markdown,
+                pinned: payload.pinned,
+            })
+            .await
+            .map_err(IpcError::from)?;
+        Ok(note.map(map_note))
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn delete_project_note_v2(db: State<'_, DbManager>, note_uuid: String) -> IpcResult<()> {
+    metrics::track("delete_project_note_v2", async {
+        let note_uuid = parse_uuid(&note_uuid, "noteUuid")?;
+        if db
+            .get_note(note_uuid)
+            .await
+            .map_err(IpcError::from)?
+            .is_none()
+        {
+            return Err(IpcError::Validation(format!("Note {note_uuid} was not found.")).into());
+        }
+        db.delete_note(note_uuid).await.map_err(IpcError::from)?;
+        Ok(())
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn get_project_note_v2(
+    db: State<'_, DbManager>,
+    note_uuid: String,
+) -> IpcResult<Option<ProjectNoteDto>> {
+    metrics::track("get_project_note_v2", async {
+        let note_uuid = parse_uuid(&note_uuid, "noteUuid")?;
+        let note = db.get_note(note_uuid).await.map_err(IpcError::from)?;
+        Ok(note.map(map_note))
+    })
+    .await
+}
+
+/// A page of a project's notes, pinned notes first, then from newest to oldest.
+#[tauri::command]
+pub async fn list_project_notes_v2(
+    db: State<'_, DbManager>,
+    payload: ListProjectNotesPayload,
+) -> IpcResult<ProjectNotePageDto> {
+    metrics::track("list_project_notes_v2", async {
+        let project_uuid = parse_uuid(&payload.project_uuid, "projectUuid")?;
+        let limit = validate_limit(payload.limit)?;
+        let offset = payload.offset.unwrap_or(0);
+        let page = db
+            .list_notes_for_project(project_uuid, limit as i64, offset as i64)
+            .await
+            .map_err(IpcError::from)?;
+        Ok(ProjectNotePageDto {
+            total: page.total,
+            notes: page.notes.into_iter().map(map_note).collect(),
+        })
+    })
+    .await
+}
+
+/// The latest notes that mention a user, across projects.
+#[tauri::command]
+pub async fn list_mentioning_notes_v2(
+    db: State<'_, DbManager>,
+    user_uuid: String,
+    limit: Option<usize>,
+) -> IpcResult<Vec<ProjectNoteDto>> {
+    metrics::track("list_mentioning_notes_v2", async {
+        let user_uuid = parse_uuid(&user_uuid, "userUuid")?;
+        let limit = validate_limit(limit)?;
+        let notes = db
+            .list_notes_mentioning(user_uuid, limit as i64)
+            .await
+            .map_err(IpcError::from)?;
+        Ok(notes.into_iter().map(map_note).collect())
+    })
+    .await
+}
+
+fn validate_body(body: &str) -> Result<String, IpcError> {
+    let body = body.trim();
+    if body.is_empty() {
+        return Err(IpcError::Validation("A note cannot be empty.".into()));
+    }
+    if body.chars().count() > MAX_NOTE_CHARS {
+        return Err(IpcError::Validation(format!(
+            "A note can have at most {MAX_NOTE_CHARS} characters."
+        )));
+    }
+    Ok(body.to_string())
+}
+
+fn validate_limit(limit: Option<usize>) -> Result<usize, IpcError> {
+    let limit = limit.unwrap_or(MAX_NOTES_PER_PAGE);
+    if limit == 0 || limit > MAX_NOTES_PER_PAGE {
+        return Err(IpcError::Validation(format!(
+            "limit must be between 1 and {MAX_NOTES_PER_PAGE}."
+        )));
+    }
+    Ok(limit)
+}
+
+fn map_note(note: Note) -> ProjectNoteDto {
+    ProjectNoteDto {
+        note_uuid: note.note_id.to_string(),
+        project_uuid: note.project_id.to_string(),
+        author_user_uuid: note.author_user_id,
+        body: note.body,
+        is_markdown: note.is_markdown,
+        pinned: note.pinned,
+        created_at: note.created_at,
+        updated_at: note.updated_at,
+        mentions: note
+            .mentions
+            .into_iter()
+            .map(|mention| NoteMentionDto {
+                mention: mention.mention,
+                user_uuid: mention.user_uuid.map(|uuid| uuid.to_string()),
+            })
+            .collect(),
+    }
+}
+
+fn parse_uuid(value: &str, field: &str) -> Result<Uuid, IpcError> {
+    Uuid::parse_str(value)
+        .map_err(|_| IpcError::Validation(format!("invalid {field}: expected UUID, got '{value}'")))
+}
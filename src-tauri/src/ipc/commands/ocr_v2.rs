@@ -0,0 +1,162 @@
+use std::path::Path;
+
+use tauri::State;
+use uuid::Uuid;
+
+use super::import_v2::register_extracted_file;
+use super::projects_v2::{
+    cleanup_files, locate_project_root, map_project_file_bundle, map_project_language_pair_record,
+    resolve_asset_directory,
+};
+use super::shared::fs_error;
+use crate::db::DbManager;
+use crate::ipc::dto::{
+    ExtractImageTextPayload, ImageTextExtractionDto, ProjectAssetRoleDto, ProjectLanguagePairDto,
+};
+use crate::ipc::error::{IpcError, IpcResult};
+use crate::jliff::preview::escape_html;
+use crate::metrics;
+use crate::ocr::{recognize, tesseract_languages};
+use crate::settings::SettingsManager;
+
+/// Reads the text of an image asset with the configured OCR engine, hinting the source
+/// languages of the project. The text is added to the project as an OCR text file, or as a
+/// processable HTML document when `translatable` is set so it can be converted and
+/// translated like any other file.
+#[tauri::command]
+pub async fn extract_image_text_v2(
+    db: State<'_, DbManager>,
+    settings: State<'_, SettingsManager>,
+    payload: ExtractImageTextPayload,
+) -> IpcResult<ImageTextExtractionDto> {
+    metrics::track("extract_image_text_v2", async {
+        let project_uuid = parse_uuid(&payload.project_uuid, "projectUuid")?;
+        let file_uuid = parse_uuid(&payload.file_uuid, "fileUuid")?;
+        let current = settings.current().await;
+        let Some(command) = current.ocr_command.clone() else {
+            return Err(IpcError::Validation(
+                "No OCR engine is configured. Set one in the settings first.".into(),
+            )
+            .into());
+        };
+        let bundle = db
+            .get_project_bundle(project_uuid)
+            .await
+            .map_err(IpcError::from)?
+            .ok_or_else(|| {
+                IpcError::Validation(format!("Project {project_uuid} was not found."))
+            })?;
+        let image = bundle
+            .files
+            .iter()
+            .find(|file| file.link.file_uuid == file_uuid)
+            .map(|file| file.link.clone())
+            .ok_or_else(|| {
+                IpcError::Validation(format!("File {file_uuid} is not part of the project."))
+            })?;
+        if image.r#type != "image" {
+            return Err(IpcError::Validation(format!(
+                "'{}' is not an image asset.",
+                image.filename
+            ))
+            .into());
+        }
+        let project_root =
+            locate_project_root(&current.projects_dir(), project_uuid, &bundle).await?;
+        let image_path = project_root.join(&image.stored_at);
+
+        let language_pairs: Vec<ProjectLanguagePairDto> = bundle
+            .language_pairs
+            .into_iter()
+            .map(map_project_language_pair_record)
+            .collect();
+        let languages =
+            tesseract_languages(language_pairs.iter().map(|pair| pair.source_lang.as_str()));
+        let text = recognize(&command, &image_path, &languages)
+            .await
+            .map_err(|error| IpcError::Validation(error.to_string()))?;
+        if text.trim().is_empty() {
+            return Err(IpcError::Validation(format!(
+                "No text was recognized in '{}'.",
+                image.filename
+            ))
+            .into());
+        }
+
+        let stem = Path::new(&image.filename)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_else(|| file_uuid.to_string());
+        let (role, file_name, contents) = if payload.translatable {
+            (
+                ProjectAssetRoleDto::Processable,
+                format!("{stem}.ocr.html"),
+                text_to_html(&stem, &text),
+            )
+        } else {
+            (
+                ProjectAssetRoleDto::Ocr,
+                format!("{stem}.ocr.txt"),
+                text.clone(),
+            )
+        };
+        let directory = resolve_asset_directory(&project_root, role);
+        let destination = directory.join(&file_name);
+        if destination.exists() {
+            return Err(IpcError::Validation(format!(
+                "'{file_name}' already exists in the project."
+            ))
+            .into());
+        }
+        tokio::fs::create_dir_all(&directory)
+            .await
+            .map_err(|error| fs_error("create the asset folder", error))?;
+        tokio::fs::write(&destination, contents)
+            .await
+            .map_err(|error| fs_error("save the recognized text", error))?;
+
+        let file = match register_extracted_file(
+            db.inner(),
+            project_uuid,
+            &project_root,
+            &destination,
+            role,
+            &language_pairs,
+        )
+        .await
+        {
+            Ok(file) => file,
+            Err(error) => {
+                cleanup_files(std::slice::from_ref(&destination));
+                return Err(error.into());
+            }
+        };
+        Ok(ImageTextExtractionDto {
+            file: map_project_file_bundle(file),
+            text,
+            languages: languages.into_iter().map(str::to_string).collect(),
+        })
+    })
+    .await
+}
+
+/// HTML document with a paragraph per block of recognized text, lines kept with `<br>`.
+fn text_to_html(title: &str, text: &str) -> String {
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n</head>\n<body>\n",
+        escape_html(title)
+    );
+    for paragraph in text.split("\n\n").map(str::trim).filter(|p| !p.is_empty()) {
+        let lines: Vec<String> = paragraph.lines().map(escape_html).collect();
+        html.push_str("<p>");
+        html.push_str(&lines.join("<br>\n"));
+        html.push_str("</p>\n");
+    }
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+fn parse_uuid(value: &str, field: &str) -> Result<Uuid, IpcError> {
+    Uuid::parse_str(value)
+        .map_err(|_| IpcError::Validation(format!("invalid {field}: expected UUID, got '{value}'")))
+}
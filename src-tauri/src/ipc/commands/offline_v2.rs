@@ -0,0 +1,162 @@
+//! Offline mode: while the app is offline, commands that need the network fail fast with an
+//! `OFFLINE` error instead of waiting for their timeouts, and uploads are queued to be sent
+//! again later. The user can force offline mode; otherwise it follows a connectivity probe
+//! the scheduler runs every minute.
+
+use std::time::Duration;
+
+use log::{info, warn};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, Runtime, State};
+use uuid::Uuid;
+
+use crate::db::DbManager;
+use crate::db::types::{NewOfflineOperationArgs, OfflineOperationRecord};
+use crate::http;
+use crate::ipc::dto::{OfflineErrorDto, OfflineOperationDto, OfflineStatusDto};
+use crate::ipc::error::{IpcError, IpcResult, OFFLINE};
+use crate::ipc::events::NETWORK_OFFLINE_STATE;
+use crate::ipc::state::OfflineMode;
+use crate::metrics;
+
+/// Overrides the URL probed for connectivity, for networks blocking the default one.
+const CONNECTIVITY_PROBE_URL_ENV: &str = "CONNECTIVITY_PROBE_URL";
+const DEFAULT_CONNECTIVITY_PROBE_URL: &str = "https://www.gstatic.com/generate_204";
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[tauri::command]
+pub async fn get_offline_status_v2(offline: State<'_, OfflineMode>) -> IpcResult<OfflineStatusDto> {
+    metrics::track("get_offline_status_v2", async { Ok(offline.status()) }).await
+}
+
+/// Forces offline mode on, or hands it back to the connectivity probe, which then runs
+/// straight away. Changes are announced on `network://offline_state`.
+#[tauri::command]
+pub async fn set_offline_mode_v2(
+    app: AppHandle,
+    offline: State<'_, OfflineMode>,
+    enabled: bool,
+) -> IpcResult<OfflineStatusDto> {
+    metrics::track("set_offline_mode_v2", async {
+        if offline.set_forced(enabled) {
+            emit_offline_state(&app, &offline);
+        }
+        if !enabled {
+            probe_connectivity(&app).await;
+        }
+        Ok(offline.status())
+    })
+    .await
+}
+
+/// Lists the operations queued while offline, oldest first, for the frontend to send again.
+#[tauri::command]
+pub async fn list_offline_operations_v2(
+    db: State<'_, DbManager>,
+) -> IpcResult<Vec<OfflineOperationDto>> {
+    metrics::track("list_offline_operations_v2", async {
+        let records = db.list_offline_operations().await.map_err(IpcError::from)?;
+        Ok(records.into_iter().map(map_offline_operation).collect())
+    })
+    .await
+}
+
+/// Removes a queued operation, once it was sent again or is no longer wanted.
+#[tauri::command]
+pub async fn discard_offline_operation_v2(
+    db: State<'_, DbManager>,
+    operation_uuid: String,
+) -> IpcResult<bool> {
+    metrics::track("discard_offline_operation_v2", async {
+        let operation_uuid = Uuid::parse_str(&operation_uuid).map_err(|_| {
+            IpcError::Validation(format!(
+                "invalid operationUuid: expected UUID, got '{operation_uuid}'"
+            ))
+        })?;
+        let removed = db
+            .delete_offline_operation(operation_uuid)
+            .await
+            .map_err(IpcError::from)?;
+        Ok(removed)
+    })
+    .await
+}
+
+/// Fails with an `OFFLINE` error when `feature` cannot reach the network.
+pub fn ensure_online(offline: &OfflineMode, feature: &str) -> Result<(), IpcError> {
+    if offline.is_offline() {
+        return Err(IpcError::Offline(format!(
+            "{feature} needs a network connection, but the app is offline."
+        )));
+    }
+    Ok(())
+}
+
+/// Queues `operation` with its `payload` and returns the `OFFLINE` error telling the frontend
+/// it was queued.
+pub async fn queue_offline_operation<T: Serialize>(
+    db: &DbManager,
+    operation: &str,
+    payload: &T,
+    project_uuid: Option<Uuid>,
+) -> Result<OfflineErrorDto, IpcError> {
+    let payload_json =
+        serde_json::to_string(payload).map_err(|error| IpcError::Internal(error.to_string()))?;
+    let record = db
+        .queue_offline_operation(NewOfflineOperationArgs {
+            operation: operation.to_string(),
+            payload_json,
+            project_uuid,
+        })
+        .await
+        .map_err(IpcError::from)?;
+    info!(target: "ipc::offline", "queued {operation} until the app is back online");
+    Ok(OfflineErrorDto {
+        code: OFFLINE.to_string(),
+        message: "The app is offline. The request was queued to be sent once it is back online."
+            .into(),
+        queued_operation_uuid: Some(record.operation_uuid.to_string()),
+    })
+}
+
+/// Checks whether the internet is reachable and records the outcome, announcing the app
+/// going on- or offline. Any HTTP answer counts as reachable.
+pub async fn probe_connectivity<R: Runtime>(app: &AppHandle<R>) -> bool {
+    let url = std::env::var(CONNECTIVITY_PROBE_URL_ENV)
+        .ok()
+        .filter(|url| !url.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_CONNECTIVITY_PROBE_URL.to_string());
+    let reachable = match http::client_builder().timeout(PROBE_TIMEOUT).build() {
+        Ok(client) => client.head(&url).send().await.is_ok(),
+        Err(error) => {
+            warn!(target: "ipc::offline", "failed to construct the probe client: {error}");
+            return !app.state::<OfflineMode>().is_offline();
+        }
+    };
+    let offline = app.state::<OfflineMode>();
+    if offline.record_probe(reachable) {
+        info!(
+            target: "ipc::offline",
+            "connectivity probe of {url} {}",
+            if reachable { "succeeded, back online" } else { "failed, going offline" }
+        );
+        emit_offline_state(app, &offline);
+    }
+    reachable
+}
+
+fn emit_offline_state<R: Runtime>(app: &AppHandle<R>, offline: &OfflineMode) {
+    if let Err(error) = app.emit(NETWORK_OFFLINE_STATE, offline.status()) {
+        warn!(target: "ipc::offline", "failed to emit offline state event: {error}");
+    }
+}
+
+fn map_offline_operation(record: OfflineOperationRecord) -> OfflineOperationDto {
+    OfflineOperationDto {
+        operation_uuid: record.operation_uuid.to_string(),
+        operation: record.operation,
+        payload: serde_json::from_str(&record.payload_json).unwrap_or_default(),
+        project_uuid: record.project_uuid.map(|uuid| uuid.to_string()),
+        created_at: record.created_at,
+    }
+}
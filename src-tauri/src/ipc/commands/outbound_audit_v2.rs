@@ -0,0 +1,118 @@
+//! Outbound request audit: every request sending document text to a translation provider is
+//! recorded with its provider, project, size and destination, for clients who must know
+//! where their content went. Projects flagged `noExternalMt` are only translated by
+//! providers that keep the text on this machine.
+
+use reqwest::Url;
+use tauri::State;
+use uuid::Uuid;
+
+use crate::db::DbManager;
+use crate::db::types::{NewOutboundRequestArgs, OutboundRequestRecord};
+use crate::ipc::dto::{ListOutboundRequestsPayload, OutboundRequestDto};
+use crate::ipc::error::{IpcError, IpcResult};
+use crate::metrics;
+use crate::providers::{LOCAL_PROVIDER, MOCK_PROVIDER};
+
+/// Providers that keep document text on this machine; every other one is external MT. The
+/// local model server is always on this machine, see [`crate::providers::is_loopback_url`].
+const ON_DEVICE_PROVIDERS: [&str; 2] = [LOCAL_PROVIDER, MOCK_PROVIDER];
+const DEFAULT_REQUEST_LIMIT: i64 = 500;
+const MAX_REQUEST_LIMIT: i64 = 5_000;
+
+/// Lists the audited outbound requests, newest first, of one project or of all of them.
+#[tauri::command]
+pub async fn list_outbound_requests_v2(
+    db: State<'_, DbManager>,
+    payload: ListOutboundRequestsPayload,
+) -> IpcResult<Vec<OutboundRequestDto>> {
+    metrics::track("list_outbound_requests_v2", async {
+        let limit = payload.limit.unwrap_or(DEFAULT_REQUEST_LIMIT);
+        if !(1..=MAX_REQUEST_LIMIT).contains(&limit) {
+            return Err(IpcError::Validation(format!(
+                "limit must be between 1 and {MAX_REQUEST_LIMIT}."
+            ))
+            .into());
+        }
+        let project_uuid = payload
+            .project_uuid
+            .as_deref()
+            .map(|value| {
+                Uuid::parse_str(value).map_err(|_| {
+                    IpcError::Validation(format!(
+                        "invalid projectUuid: expected UUID, got '{value}'"
+                    ))
+                })
+            })
+            .transpose()?;
+        let records = db
+            .list_outbound_requests(project_uuid, limit)
+            .await
+            .map_err(IpcError::from)?;
+        Ok(records.into_iter().map(map_outbound_request).collect())
+    })
+    .await
+}
+
+/// Fails when `provider` is an external MT provider and the project forbids those.
+pub async fn ensure_external_mt_allowed(
+    db: &DbManager,
+    project_uuid: Uuid,
+    provider: &str,
+) -> Result<(), IpcError> {
+    if ON_DEVICE_PROVIDERS.contains(&provider) {
+        return Ok(());
+    }
+    if db
+        .project_forbids_external_mt(project_uuid)
+        .await
+        .map_err(IpcError::from)?
+    {
+        return Err(IpcError::Validation(format!(
+            "This project must not be sent to external machine translation; '{provider}' \
+             cannot translate it. Use the local model instead."
+        )));
+    }
+    Ok(())
+}
+
+/// Records that `segment_count` segments of `char_count` characters are being sent to
+/// `provider` at `base_url`, when the backend knows it. Called before the request goes out,
+/// so requests that fail on the way are audited too.
+pub async fn record_outbound_request(
+    db: &DbManager,
+    provider: &str,
+    project_uuid: Option<Uuid>,
+    segment_count: i64,
+    char_count: i64,
+    base_url: Option<&str>,
+) -> Result<(), IpcError> {
+    db.record_outbound_request(NewOutboundRequestArgs {
+        provider: provider.to_string(),
+        project_uuid,
+        segment_count,
+        char_count,
+        destination_host: base_url.and_then(destination_host),
+    })
+    .await
+    .map_err(IpcError::from)?;
+    Ok(())
+}
+
+fn destination_host(base_url: &str) -> Option<String> {
+    Url::parse(base_url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_string))
+}
+
+fn map_outbound_request(record: OutboundRequestRecord) -> OutboundRequestDto {
+    OutboundRequestDto {
+        request_uuid: record.request_uuid.to_string(),
+        provider: record.provider,
+        project_uuid: record.project_uuid.map(|uuid| uuid.to_string()),
+        segment_count: record.segment_count,
+        char_count: record.char_count,
+        destination_host: record.destination_host,
+        sent_at: record.sent_at,
+    }
+}
@@ -0,0 +1,45 @@
+use tauri::State;
+
+use crate::db::DbManager;
+use crate::db::types::OutboxEventRecord;
+use crate::ipc::dto::{DomainEventDto, ListDomainEventsPayload};
+use crate::ipc::error::{IpcError, IpcResult};
+use crate::metrics;
+
+const DEFAULT_EVENT_LIMIT: i64 = 200;
+const MAX_EVENT_LIMIT: i64 = 1_000;
+
+/// Lists domain events after a known event id so a window that was closed or reloaded can
+/// catch up on what it missed. Delivered events are kept for a week.
+#[tauri::command]
+pub async fn list_domain_events_v2(
+    db: State<'_, DbManager>,
+    payload: ListDomainEventsPayload,
+) -> IpcResult<Vec<DomainEventDto>> {
+    metrics::track("list_domain_events_v2", async {
+        let limit = payload.limit.unwrap_or(DEFAULT_EVENT_LIMIT);
+        if !(1..=MAX_EVENT_LIMIT).contains(&limit) {
+            return Err(IpcError::Validation(format!(
+                "limit must be between 1 and {MAX_EVENT_LIMIT}."
+            ))
+            .into());
+        }
+        let events = db
+            .list_outbox_events_after(payload.after_event_id.unwrap_or(0), limit)
+            .await
+            .map_err(IpcError::from)?;
+        Ok(events.into_iter().map(map_event_record).collect())
+    })
+    .await
+}
+
+fn map_event_record(record: OutboxEventRecord) -> DomainEventDto {
+    DomainEventDto {
+        event_id: record.event_id,
+        payload: serde_json::from_str(&record.payload_json)
+            .unwrap_or(serde_json::Value::String(record.payload_json)),
+        topic: record.topic,
+        created_at: record.created_at,
+        delivered_at: record.delivered_at,
+    }
+}
@@ -0,0 +1,360 @@
+//! Output naming: per-project overrides of the global XLIFF and JLIFF name templates, the
+//! project's Translations folder layout, and the [`OutputNamer`] conversion planning and
+//! the pipeline use to name their outputs.
+//!
+//! Names chosen for a file and language pair are recorded with the templates they came
+//! from, so re-planning and pipeline runs reuse them (and overwrite the same files) until
+//! the templates or the layout change. Collisions are checked against every recorded name
+//! of the project.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use tauri::State;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use super::projects_v2::language_pair_directory_name;
+use crate::db::DbManager;
+use crate::db::types::{
+    ConversionOutputNameRecord, NewConversionOutputNameArgs, ProjectOutputNamingRecord,
+};
+use crate::ipc::dto::{
+    ProjectLanguagePairDto, ProjectOutputNamingDto, SetProjectOutputNamingPayload,
+};
+use crate::ipc::error::{IpcError, IpcResult};
+use crate::metrics;
+use crate::output_naming::{NameValues, OutputNameTemplate, TranslationsLayout};
+use crate::settings::{AppSettings, SettingsManager};
+
+#[tauri::command]
+pub async fn get_project_output_naming_v2(
+    db: State<'_, DbManager>,
+    settings: State<'_, SettingsManager>,
+    project_uuid: String,
+) -> IpcResult<ProjectOutputNamingDto> {
+    metrics::track("get_project_output_naming_v2", async {
+        let project_uuid = parse_uuid(&project_uuid, "projectUuid")?;
+        let record = db
+            .get_project_output_naming(project_uuid)
+            .await
+            .map_err(IpcError::from)?;
+        Ok(map_naming(project_uuid, record, &settings.current().await))
+    })
+    .await
+}
+
+/// Sets the templates a project's outputs are named with and the layout of its Translations
+/// folder; an empty or missing template falls back to the global one, a missing layout to
+/// the language-first one. Files already converted get the new names and folder the next
+/// time their conversion is planned.
+#[tauri::command]
+pub async fn set_project_output_naming_v2(
+    db: State<'_, DbManager>,
+    settings: State<'_, SettingsManager>,
+    payload: SetProjectOutputNamingPayload,
+) -> IpcResult<ProjectOutputNamingDto> {
+    metrics::track("set_project_output_naming_v2", async {
+        let project_uuid = parse_uuid(&payload.project_uuid, "projectUuid")?;
+        let xliff_template = normalize_template(payload.xliff_template.as_deref())?;
+        let jliff_template = normalize_template(payload.jliff_template.as_deref())?;
+        let translations_layout = payload
+            .translations_layout
+            .as_deref()
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(|value| {
+                TranslationsLayout::parse(value).ok_or_else(|| {
+                    IpcError::Validation(format!(
+                        "Unknown translations layout '{value}'. Use language_first or source_first."
+                    ))
+                })
+            })
+            .transpose()?;
+        if db
+            .get_project_bundle(project_uuid)
+            .await
+            .map_err(IpcError::from)?
+            .is_none()
+        {
+            return Err(
+                IpcError::Validation(format!("Project {project_uuid} was not found.")).into(),
+            );
+        }
+
+        let record = db
+            .set_project_output_naming(
+                project_uuid,
+                xliff_template.as_deref(),
+                jliff_template.as_deref(),
+                translations_layout.map(TranslationsLayout::as_str),
+            )
+            .await
+            .map_err(IpcError::from)?;
+        Ok(map_naming(project_uuid, record, &settings.current().await))
+    })
+    .await
+}
+
+/// Relative XLIFF path and JLIFF prefix of a file in a language pair.
+pub(super) struct OutputNames {
+    pub xliff_rel_path: PathBuf,
+    pub jliff_prefix: String,
+}
+
+/// Names the conversion outputs of one project.
+pub(super) struct OutputNamer {
+    project_uuid: Uuid,
+    xliff_source: String,
+    xliff: OutputNameTemplate,
+    jliff_source: String,
+    jliff: OutputNameTemplate,
+    layout: TranslationsLayout,
+    version: String,
+    date: OffsetDateTime,
+    recorded: Vec<ConversionOutputNameRecord>,
+    /// Recorded XLIFF paths, lowercased so names differing only in case collide too.
+    taken: HashSet<String>,
+}
+
+impl OutputNamer {
+    pub(super) async fn load(
+        db: &DbManager,
+        settings: &AppSettings,
+        project_uuid: Uuid,
+    ) -> Result<Self, IpcError> {
+        let overrides = db
+            .get_project_output_naming(project_uuid)
+            .await
+            .map_err(IpcError::from)?;
+        let layout = overrides.as_ref().map(stored_layout).unwrap_or_default();
+        let (xliff_override, jliff_override) = overrides
+            .map(|record| (record.xliff_template, record.jliff_template))
+            .unwrap_or_default();
+        let xliff_source = xliff_override.unwrap_or_else(|| settings.xliff_name_template.clone());
+        let jliff_source = jliff_override.unwrap_or_else(|| settings.jliff_name_template.clone());
+        let recorded = db
+            .list_conversion_output_names(project_uuid)
+            .await
+            .map_err(IpcError::from)?;
+
+        Ok(Self {
+            project_uuid,
+            xliff: parse_template(&xliff_source)?,
+            xliff_source,
+            jliff: parse_template(&jliff_source)?,
+            jliff_source,
+            layout,
+            version: settings.default_xliff_version.clone(),
+            date: OffsetDateTime::now_utc(),
+            taken: recorded
+                .iter()
+                .map(|record| record.xliff_rel_path.to_lowercase())
+                .collect(),
+            recorded,
+        })
+    }
+
+    /// The output names of a file stored at `stored_at` in a language pair: the recorded
+    /// ones while the templates and the layout are unchanged, otherwise new ones, which are
+    /// recorded.
+    pub(super) async fn names(
+        &mut self,
+        db: &DbManager,
+        file_uuid: Uuid,
+        stored_at: &Path,
+        stem: &str,
+        pair: &ProjectLanguagePairDto,
+        artifact_uuid: Uuid,
+    ) -> Result<OutputNames, IpcError> {
+        let position = self.recorded.iter().position(|record| {
+            record.file_uuid == file_uuid
+                && record.source_lang.eq_ignore_ascii_case(&pair.source_lang)
+                && record.target_lang.eq_ignore_ascii_case(&pair.target_lang)
+        });
+        let language_dir = language_pair_directory_name(pair);
+        let dir = self.layout.output_dir(stored_at, &language_dir);
+        if let Some(record) = position.map(|index| &self.recorded[index]) {
+            if record.xliff_template == self.xliff_source
+                && record.jliff_template == self.jliff_source
+                && Path::new(&record.xliff_rel_path).parent() == Some(dir.as_path())
+            {
+                return Ok(OutputNames {
+                    xliff_rel_path: PathBuf::from(&record.xliff_rel_path),
+                    jliff_prefix: record.jliff_prefix.clone(),
+                });
+            }
+            self.taken.remove(&record.xliff_rel_path.to_lowercase());
+        }
+
+        let uuid = artifact_uuid.to_string();
+        let values = NameValues {
+            stem,
+            source_lang: &pair.source_lang,
+            target_lang: &pair.target_lang,
+            pair: &language_dir,
+            version: &self.version,
+            uuid: &uuid,
+            date: self.date,
+        };
+        let xliff_name = self.xliff.unique_name(&values, "xlf", |name| {
+            self.taken
+                .contains(&dir.join(name).to_string_lossy().to_lowercase())
+        });
+        let xliff_rel_path = dir.join(&xliff_name);
+        // The JLIFF takes its stem from the XLIFF, which is already unique in the folder.
+        let xliff_stem = xliff_name.strip_suffix(".xlf").unwrap_or(&xliff_name);
+        let jliff_prefix = self.jliff.render(
+            &NameValues {
+                stem: xliff_stem,
+                ..values
+            },
+            1,
+        );
+
+        let record = db
+            .record_conversion_output_name(NewConversionOutputNameArgs {
+                project_uuid: self.project_uuid,
+                file_uuid,
+                source_lang: pair.source_lang.clone(),
+                target_lang: pair.target_lang.clone(),
+                xliff_template: self.xliff_source.clone(),
+                xliff_rel_path: xliff_rel_path.to_string_lossy().into_owned(),
+                jliff_template: self.jliff_source.clone(),
+                jliff_prefix: jliff_prefix.clone(),
+            })
+            .await
+            .map_err(IpcError::from)?;
+        self.taken.insert(record.xliff_rel_path.to_lowercase());
+        match position {
+            Some(index) => self.recorded[index] = record,
+            None => self.recorded.push(record),
+        }
+
+        Ok(OutputNames {
+            xliff_rel_path,
+            jliff_prefix,
+        })
+    }
+}
+
+/// Where the outputs of a project's files are: next to the recorded XLIFF names, or where
+/// the project's layout puts them for files planned before names were recorded.
+pub(super) struct OutputLocations {
+    layout: TranslationsLayout,
+    recorded: Vec<ConversionOutputNameRecord>,
+}
+
+impl OutputLocations {
+    pub(super) async fn load(db: &DbManager, project_uuid: Uuid) -> Result<Self, IpcError> {
+        let layout = db
+            .get_project_output_naming(project_uuid)
+            .await
+            .map_err(IpcError::from)?
+            .as_ref()
+            .map(stored_layout)
+            .unwrap_or_default();
+        let recorded = db
+            .list_conversion_output_names(project_uuid)
+            .await
+            .map_err(IpcError::from)?;
+        Ok(Self { layout, recorded })
+    }
+
+    /// The recorded XLIFF of a file in a language pair, relative to the project root.
+    pub(super) fn xliff_rel_path(
+        &self,
+        file_uuid: Uuid,
+        pair: &ProjectLanguagePairDto,
+    ) -> Option<&Path> {
+        self.recorded
+            .iter()
+            .find(|record| {
+                record.file_uuid == file_uuid
+                    && record.source_lang.eq_ignore_ascii_case(&pair.source_lang)
+                    && record.target_lang.eq_ignore_ascii_case(&pair.target_lang)
+            })
+            .map(|record| Path::new(&record.xliff_rel_path))
+    }
+
+    /// Folder, relative to the project root, of the outputs of the file stored at
+    /// `stored_at` in a language pair; documents merged from the XLIFF are written there too.
+    pub(super) fn dir(
+        &self,
+        file_uuid: Uuid,
+        stored_at: &Path,
+        pair: &ProjectLanguagePairDto,
+    ) -> PathBuf {
+        self.xliff_rel_path(file_uuid, pair)
+            .and_then(Path::parent)
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| {
+                self.layout
+                    .output_dir(stored_at, &language_pair_directory_name(pair))
+            })
+    }
+}
+
+/// The JLIFF prefix recorded for an XLIFF of the project, if its name was planned.
+pub(super) async fn recorded_jliff_prefix(
+    db: &DbManager,
+    project_uuid: Uuid,
+    xliff_rel_path: &Path,
+) -> Result<Option<String>, IpcError> {
+    let xliff_rel_path = xliff_rel_path.to_string_lossy();
+    Ok(db
+        .list_conversion_output_names(project_uuid)
+        .await
+        .map_err(IpcError::from)?
+        .into_iter()
+        .find(|record| record.xliff_rel_path == xliff_rel_path)
+        .map(|record| record.jliff_prefix))
+}
+
+fn stored_layout(record: &ProjectOutputNamingRecord) -> TranslationsLayout {
+    record
+        .translations_layout
+        .as_deref()
+        .and_then(TranslationsLayout::parse)
+        .unwrap_or_default()
+}
+
+fn normalize_template(template: Option<&str>) -> Result<Option<String>, IpcError> {
+    let Some(template) = template.map(str::trim).filter(|value| !value.is_empty()) else {
+        return Ok(None);
+    };
+    parse_template(template)?;
+    Ok(Some(template.to_string()))
+}
+
+fn parse_template(template: &str) -> Result<OutputNameTemplate, IpcError> {
+    OutputNameTemplate::parse(template).map_err(|error| IpcError::Validation(error.to_string()))
+}
+
+fn map_naming(
+    project_uuid: Uuid,
+    record: Option<ProjectOutputNamingRecord>,
+    settings: &AppSettings,
+) -> ProjectOutputNamingDto {
+    let translations_layout = record.as_ref().map(stored_layout).unwrap_or_default();
+    let (xliff_template, jliff_template) = record
+        .map(|record| (record.xliff_template, record.jliff_template))
+        .unwrap_or_default();
+    ProjectOutputNamingDto {
+        project_uuid: project_uuid.to_string(),
+        effective_xliff_template: xliff_template
+            .clone()
+            .unwrap_or_else(|| settings.xliff_name_template.clone()),
+        effective_jliff_template: jliff_template
+            .clone()
+            .unwrap_or_else(|| settings.jliff_name_template.clone()),
+        xliff_template,
+        jliff_template,
+        translations_layout: translations_layout.as_str().to_string(),
+    }
+}
+
+fn parse_uuid(value: &str, field: &str) -> Result<Uuid, IpcError> {
+    Uuid::parse_str(value)
+        .map_err(|_| IpcError::Validation(format!("invalid {field}: expected UUID, got '{value}'")))
+}
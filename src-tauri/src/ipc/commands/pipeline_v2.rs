@@ -0,0 +1,637 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::bail;
+use tauri::{AppHandle, Manager, State};
+use uuid::Uuid;
+
+use super::bidi_v2::run_bidi_controls_check;
+use super::jobs_v2::map_job_record;
+use super::output_naming_v2::OutputNamer;
+use super::plugins_v2::{load_plugin_instance, run_plugin_on_file};
+use super::projects_v2::{convert_project_xliff, ensure_conversion_artifact, resolve_project_root};
+use super::segment_status_v2::sync_project_xliff;
+use super::shared::load_project_jliff;
+use super::translation_memory_v2::{load_tm_matcher, pretranslate_file};
+use super::unicode_text_v2::run_unicode_text_check;
+use crate::db::DbManager;
+use crate::db::types::{
+    JobState, NewJobArgs, NewPipelinePresetArgs, PipelinePresetRecord, ProjectType,
+    UpdateJobStatusArgs,
+};
+use crate::ipc::dto::{
+    AssignPipelinePresetPayload, JobRefDto, JobV2Dto, PipelineFileDto, PipelinePresetDto,
+    PipelineRunDto, ProjectLanguagePairDto, RunPipelinePayload, SavePipelinePresetPayload,
+};
+use crate::ipc::error::{IpcError, IpcResult};
+use crate::jobs::pipeline::{PipelineDefinition, PipelineStage, StageSetting};
+use crate::jobs::{JobGraph, JobRunner, run_graph};
+use crate::metrics;
+use crate::plugins::PluginInstance;
+use crate::settings::SettingsManager;
+use crate::tm::TmMatcher;
+
+/// One stage of one file; stored as the `jobs` row `(artifact_uuid, stage job type)`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct PipelineJob {
+    artifact_uuid: Uuid,
+    stage: PipelineStage,
+    /// Plugin of a `plugin` stage.
+    plugin: Option<String>,
+    file_name: String,
+}
+
+impl PipelineJob {
+    fn job_type(&self) -> String {
+        self.stage.job_type(self.plugin.as_deref())
+    }
+}
+
+impl fmt::Display for PipelineJob {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.plugin {
+            Some(plugin) => write!(f, "plugin {plugin} of {}", self.file_name),
+            None => write!(f, "{} of {}", self.stage.as_str(), self.file_name),
+        }
+    }
+}
+
+struct PipelineFile {
+    xliff_path: PathBuf,
+    xliff_rel_path: String,
+    jliff_rel_path: String,
+}
+
+struct PipelineRunner {
+    app: AppHandle,
+    project_uuid: Uuid,
+    files: BTreeMap<Uuid, PipelineFile>,
+    definition: PipelineDefinition,
+    matcher: Option<Arc<TmMatcher>>,
+    plugins: HashMap<String, PluginInstance>,
+    operator: Option<String>,
+}
+
+/// Queues the stages of a pipeline preset for every processable file of a language pair and
+/// runs them in the background: each stage starts once the previous stage of the same file
+/// succeeded, files run side by side, and a failure cancels the remaining stages of its file.
+/// Without ad-hoc stages or an explicit preset, the project's preset is used, then its
+/// type's default, then `convert → translate → qa → merge`. Ad-hoc stages name plugins as
+/// `plugin:<id>`. The XLIFF files must already have been extracted by the conversion plan.
+#[tauri::command]
+pub async fn run_pipeline_v2(
+    app: AppHandle,
+    db: State<'_, DbManager>,
+    settings: State<'_, SettingsManager>,
+    payload: RunPipelinePayload,
+) -> IpcResult<PipelineRunDto> {
+    metrics::track("run_pipeline_v2", async {
+        let project_uuid = parse_uuid(&payload.project_uuid, "projectUuid")?;
+        let (preset, mut definition) = match payload.stages.as_deref() {
+            Some(values) => (
+                None,
+                PipelineDefinition {
+                    stages: parse_stages(values)?,
+                },
+            ),
+            None => {
+                let preset = match payload.preset_uuid.as_deref() {
+                    Some(value) => {
+                        Some(find_preset(db.inner(), parse_uuid(value, "presetUuid")?).await?)
+                    }
+                    None => db
+                        .pipeline_preset_for_project(project_uuid)
+                        .await
+                        .map_err(IpcError::from)?,
+                };
+                let definition = preset
+                    .as_ref()
+                    .map(parse_definition)
+                    .unwrap_or_else(PipelineDefinition::full);
+                (preset, definition)
+            }
+        };
+        if let Some(min_match) = payload.min_match
+            && let Some(translate) = definition
+                .stages
+                .iter_mut()
+                .find(|setting| setting.stage == PipelineStage::Translate)
+        {
+            translate.min_match = Some(min_match);
+        }
+        definition
+            .validate()
+            .map_err(|error| IpcError::Validation(format!("{error:#}")))?;
+        let mut plugins = HashMap::new();
+        for plugin_id in definition.plugins() {
+            let plugin = load_plugin_instance(db.inner(), settings.inner(), plugin_id).await?;
+            plugins.insert(plugin_id.to_string(), plugin);
+        }
+        let file_filter: Option<HashSet<Uuid>> = payload
+            .file_uuids
+            .as_ref()
+            .map(|ids| ids.iter().map(|id| parse_uuid(id, "fileUuid")).collect())
+            .transpose()?;
+        let pair = ProjectLanguagePairDto {
+            source_lang: payload.source_lang.trim().to_string(),
+            target_lang: payload.target_lang.trim().to_string(),
+        };
+        let has_pair = |source_lang: &str, target_lang: &str| {
+            source_lang.eq_ignore_ascii_case(&pair.source_lang)
+                && target_lang.eq_ignore_ascii_case(&pair.target_lang)
+        };
+
+        let bundle = db
+            .get_project_bundle(project_uuid)
+            .await
+            .map_err(IpcError::from)?
+            .ok_or_else(|| IpcError::Validation(format!("Project '{}' not found", project_uuid)))?;
+        let project_root = resolve_project_root(db.inner(), settings.inner(), project_uuid).await?;
+        let mut namer =
+            OutputNamer::load(db.inner(), &settings.current().await, project_uuid).await?;
+
+        let mut files = BTreeMap::new();
+        let mut file_dtos = Vec::new();
+        let mut file_names = BTreeMap::new();
+        for file_bundle in &bundle.files {
+            let link = &file_bundle.link;
+            if !link.r#type.eq_ignore_ascii_case("processable")
+                || file_filter
+                    .as_ref()
+                    .is_some_and(|filter| !filter.contains(&link.file_uuid))
+            {
+                continue;
+            }
+            let in_pair = if file_bundle.language_pairs.is_empty() {
+                bundle.language_pairs.iter().any(|language_pair| {
+                    has_pair(&language_pair.source_lang, &language_pair.target_lang)
+                })
+            } else {
+                file_bundle.language_pairs.iter().any(|language_pair| {
+                    has_pair(&language_pair.source_lang, &language_pair.target_lang)
+                })
+            };
+            if !in_pair {
+                continue;
+            }
+
+            let file_stem = Path::new(&link.filename)
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .unwrap_or("artifact");
+            let artifact_uuid =
+                ensure_conversion_artifact(db.inner(), project_uuid, link.file_uuid).await?;
+            // Names recorded by the conversion plan, which the conversion names the JLIFF with.
+            let names = namer
+                .names(
+                    db.inner(),
+                    link.file_uuid,
+                    Path::new(&link.stored_at),
+                    file_stem,
+                    &pair,
+                    artifact_uuid,
+                )
+                .await?;
+            let xliff_rel_path = names.xliff_rel_path;
+            // The conversion writes the JLIFF next to the XLIFF.
+            let jliff_rel_path =
+                xliff_rel_path.with_file_name(format!("{}.jliff.json", names.jliff_prefix));
+            let file = PipelineFile {
+                xliff_path: project_root.join(&xliff_rel_path),
+                xliff_rel_path: xliff_rel_path.to_string_lossy().into_owned(),
+                jliff_rel_path: jliff_rel_path.to_string_lossy().into_owned(),
+            };
+            file_dtos.push(PipelineFileDto {
+                file_uuid: link.file_uuid.to_string(),
+                file_name: link.filename.clone(),
+                artifact_uuid: artifact_uuid.to_string(),
+                xliff_rel_path: file.xliff_rel_path.clone(),
+                jliff_rel_path: file.jliff_rel_path.clone(),
+            });
+            file_names.insert(artifact_uuid, link.filename.clone());
+            files.insert(artifact_uuid, file);
+        }
+        if files.is_empty() {
+            return Err(IpcError::Validation(format!(
+                "The project has no processable files for {} → {}.",
+                pair.source_lang, pair.target_lang
+            ))
+            .into());
+        }
+
+        let running = JobState::Running.as_str();
+        if let Some(busy) = db
+            .list_jobs_for_project(project_uuid)
+            .await
+            .map_err(IpcError::from)?
+            .into_iter()
+            .find(|job| job.job_status == running && files.contains_key(&job.artifact_uuid))
+        {
+            return Err(IpcError::Validation(format!(
+                "A pipeline is already running for '{}'.",
+                file_names[&busy.artifact_uuid]
+            ))
+            .into());
+        }
+
+        let mut graph = JobGraph::new();
+        let mut jobs = Vec::new();
+        for (artifact_uuid, file_name) in &file_names {
+            let mut previous: Option<PipelineJob> = None;
+            for setting in &definition.stages {
+                let job = PipelineJob {
+                    artifact_uuid: *artifact_uuid,
+                    stage: setting.stage,
+                    plugin: setting.plugin.clone(),
+                    file_name: file_name.clone(),
+                };
+                let job_type = setting.job_type();
+                let record = db
+                    .upsert_job_record(NewJobArgs {
+                        artifact_uuid: *artifact_uuid,
+                        job_type: job_type.clone(),
+                        project_uuid,
+                        job_status: JobState::Pending.as_str().to_string(),
+                        error_log: None,
+                    })
+                    .await
+                    .map_err(IpcError::from)?;
+                let depends_on: Vec<(Uuid, String)> = previous
+                    .iter()
+                    .map(|prerequisite| (prerequisite.artifact_uuid, prerequisite.job_type()))
+                    .collect();
+                db.replace_job_dependencies(*artifact_uuid, &job_type, &depends_on)
+                    .await
+                    .map_err(IpcError::from)?;
+
+                graph.add_job(job.clone());
+                if let Some(prerequisite) = &previous {
+                    graph
+                        .add_dependency(&job, prerequisite)
+                        .map_err(|error| IpcError::Internal(error.to_string()))?;
+                }
+                jobs.push(JobV2Dto {
+                    depends_on: depends_on
+                        .into_iter()
+                        .map(|(artifact_uuid, job_type)| JobRefDto {
+                            artifact_uuid: artifact_uuid.to_string(),
+                            job_type,
+                        })
+                        .collect(),
+                    ..map_job_record(record)
+                });
+                previous = Some(job);
+            }
+        }
+
+        let matcher = if definition.stage(PipelineStage::Translate).is_some() {
+            Some(Arc::new(
+                load_tm_matcher(
+                    db.inner(),
+                    Some(project_uuid),
+                    &pair.source_lang,
+                    &pair.target_lang,
+                )
+                .await?,
+            ))
+        } else {
+            None
+        };
+        let runner = Arc::new(PipelineRunner {
+            app: app.clone(),
+            project_uuid,
+            files,
+            definition: definition.clone(),
+            matcher,
+            plugins,
+            operator: payload.operator.clone(),
+        });
+        tauri::async_runtime::spawn(async move {
+            let states = run_graph(graph, runner).await;
+            let failed = states
+                .values()
+                .filter(|state| **state == JobState::Failed)
+                .count();
+            log::info!(
+                target: "ipc::pipeline",
+                "pipeline of project {project_uuid} finished: {} jobs, {failed} failed",
+                states.len()
+            );
+        });
+
+        Ok(PipelineRunDto {
+            project_uuid: project_uuid.to_string(),
+            source_lang: pair.source_lang,
+            target_lang: pair.target_lang,
+            preset_uuid: preset.as_ref().map(|record| record.preset_uuid.to_string()),
+            preset_name: preset.map(|record| record.name),
+            stages: definition.stages.iter().map(StageSetting::name).collect(),
+            files: file_dtos,
+            jobs,
+        })
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn list_pipeline_presets_v2(
+    db: State<'_, DbManager>,
+) -> IpcResult<Vec<PipelinePresetDto>> {
+    metrics::track("list_pipeline_presets_v2", async {
+        let records = db.list_pipeline_presets().await.map_err(IpcError::from)?;
+        Ok(records
+            .into_iter()
+            .map(map_pipeline_preset_record)
+            .collect())
+    })
+    .await
+}
+
+/// Creates a pipeline preset, or replaces the preset with the same name. Giving a project
+/// type makes the preset that type's default.
+#[tauri::command]
+pub async fn save_pipeline_preset_v2(
+    db: State<'_, DbManager>,
+    payload: SavePipelinePresetPayload,
+) -> IpcResult<PipelinePresetDto> {
+    metrics::track("save_pipeline_preset_v2", async {
+        let name = payload.name.trim().to_string();
+        if name.is_empty() {
+            return Err(
+                IpcError::Validation("Pipeline preset name must not be empty.".into()).into(),
+            );
+        }
+        let project_type = payload
+            .project_type
+            .as_deref()
+            .map(|value| {
+                ProjectType::from_str(&value.trim().to_ascii_lowercase())
+                    .map(|project_type| project_type.as_str().to_string())
+                    .ok_or_else(|| {
+                        IpcError::Validation(format!(
+                            "Unknown project type '{value}'. Use translation or rag."
+                        ))
+                    })
+            })
+            .transpose()?;
+        payload
+            .definition
+            .validate()
+            .map_err(|error| IpcError::Validation(format!("{error:#}")))?;
+        let definition_json = serde_json::to_string(&payload.definition).map_err(|error| {
+            IpcError::Internal(format!("Failed to serialize pipeline preset: {error}"))
+        })?;
+
+        let record = db
+            .upsert_pipeline_preset(NewPipelinePresetArgs {
+                name,
+                description: payload
+                    .description
+                    .map(|description| description.trim().to_string())
+                    .filter(|description| !description.is_empty()),
+                project_type,
+                definition_json,
+            })
+            .await
+            .map_err(IpcError::from)?;
+        Ok(map_pipeline_preset_record(record))
+    })
+    .await
+}
+
+/// Deletes a pipeline preset. Projects it was assigned to use their type's default again.
+#[tauri::command]
+pub async fn delete_pipeline_preset_v2(
+    db: State<'_, DbManager>,
+    preset_uuid: String,
+) -> IpcResult<bool> {
+    metrics::track("delete_pipeline_preset_v2", async {
+        let preset_uuid = parse_uuid(&preset_uuid, "presetUuid")?;
+        let deleted = db
+            .delete_pipeline_preset(preset_uuid)
+            .await
+            .map_err(IpcError::from)?;
+        Ok(deleted)
+    })
+    .await
+}
+
+/// Chooses the pipeline preset of a project; `null` falls back to its type's default.
+#[tauri::command]
+pub async fn assign_pipeline_preset_v2(
+    db: State<'_, DbManager>,
+    payload: AssignPipelinePresetPayload,
+) -> IpcResult<()> {
+    metrics::track("assign_pipeline_preset_v2", async {
+        let project_uuid = parse_uuid(&payload.project_uuid, "projectUuid")?;
+        let preset_uuid = payload
+            .preset_uuid
+            .as_deref()
+            .map(|value| parse_uuid(value, "presetUuid"))
+            .transpose()?;
+        if let Some(preset_uuid) = preset_uuid {
+            find_preset(db.inner(), preset_uuid).await?;
+        }
+        db.assign_pipeline_preset(project_uuid, preset_uuid)
+            .await
+            .map_err(IpcError::from)?;
+        Ok(())
+    })
+    .await
+}
+
+impl JobRunner<PipelineJob> for PipelineRunner {
+    async fn run(&self, job: &PipelineJob) -> anyhow::Result<()> {
+        let Some(file) = self.files.get(&job.artifact_uuid) else {
+            bail!("no file is queued for artifact {}", job.artifact_uuid);
+        };
+        let Some(setting) = self.definition.stage(job.stage) else {
+            bail!("the pipeline has no {} stage", job.stage.as_str());
+        };
+        let db = self.app.state::<DbManager>();
+        let settings = self.app.state::<SettingsManager>();
+        match job.stage {
+            PipelineStage::Convert => {
+                if !file.xliff_path.is_file() {
+                    bail!(
+                        "'{}' has not been extracted yet; run the conversion plan first.",
+                        file.xliff_rel_path
+                    );
+                }
+                convert_project_xliff(
+                    db.inner(),
+                    settings.inner(),
+                    self.project_uuid,
+                    job.artifact_uuid,
+                    &file.xliff_path,
+                    self.operator.clone(),
+                    None,
+                )
+                .await?;
+            }
+            PipelineStage::Translate => {
+                let Some(matcher) = &self.matcher else {
+                    bail!("the translation memory was not loaded");
+                };
+                pretranslate_file(
+                    db.inner(),
+                    settings.inner(),
+                    self.project_uuid,
+                    &file.jliff_rel_path,
+                    Arc::clone(matcher),
+                    setting.min_match(),
+                    setting.overwrite_policy(),
+                )
+                .await?;
+            }
+            PipelineStage::Plugin => {
+                let Some(plugin) = job.plugin.as_ref().and_then(|id| self.plugins.get(id)) else {
+                    bail!("the plugin of {job} was not loaded");
+                };
+                run_plugin_on_file(
+                    db.inner(),
+                    settings.inner(),
+                    self.project_uuid,
+                    &file.jliff_rel_path,
+                    plugin,
+                    self.operator.clone(),
+                )
+                .await?;
+            }
+            PipelineStage::Qa => {
+                let (_, document) = load_project_jliff(
+                    db.inner(),
+                    settings.inner(),
+                    self.project_uuid,
+                    &file.jliff_rel_path,
+                )
+                .await?;
+                if setting.runs_check("unicode_text") {
+                    run_unicode_text_check(
+                        db.inner(),
+                        self.project_uuid,
+                        file.jliff_rel_path.clone(),
+                        &document,
+                    )
+                    .await?;
+                }
+                if setting.runs_check("bidi_controls") {
+                    run_bidi_controls_check(
+                        db.inner(),
+                        self.project_uuid,
+                        file.jliff_rel_path.clone(),
+                        &document,
+                    )
+                    .await?;
+                }
+            }
+            PipelineStage::Merge => {
+                let critical = db
+                    .list_qa_findings(self.project_uuid, Some(&file.jliff_rel_path))
+                    .await?
+                    .into_iter()
+                    .filter(|finding| {
+                        finding.severity == "critical" && finding.resolved_at.is_none()
+                    })
+                    .count();
+                if critical > 0 && setting.block_on_critical() {
+                    bail!("{critical} critical QA findings are still open.");
+                }
+                let (jliff_path, document) = load_project_jliff(
+                    db.inner(),
+                    settings.inner(),
+                    self.project_uuid,
+                    &file.jliff_rel_path,
+                )
+                .await?;
+                sync_project_xliff(
+                    file.xliff_path.clone(),
+                    &file.xliff_rel_path,
+                    jliff_path,
+                    document,
+                )
+                .await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn report(&self, job: &PipelineJob, state: JobState, error_log: Option<String>) {
+        let db = self.app.state::<DbManager>();
+        if let Err(error) = db
+            .update_job_status_record(UpdateJobStatusArgs {
+                artifact_uuid: job.artifact_uuid,
+                job_type: job.job_type(),
+                job_status: state.as_str().to_string(),
+                error_log,
+            })
+            .await
+        {
+            log::warn!(target: "ipc::pipeline", "failed to record the state of {job}: {error}");
+        }
+    }
+}
+
+/// Stages in pipeline order; plugins run in the order given.
+fn parse_stages(values: &[String]) -> Result<Vec<StageSetting>, IpcError> {
+    let mut stages = values
+        .iter()
+        .map(|value| {
+            if let Some((stage, plugin)) = value.split_once(':')
+                && PipelineStage::parse(stage) == Some(PipelineStage::Plugin)
+            {
+                return Ok(StageSetting::plugin(plugin.trim()));
+            }
+            match PipelineStage::parse(value) {
+                Some(stage) if stage != PipelineStage::Plugin => Ok(StageSetting::new(stage)),
+                _ => Err(IpcError::Validation(format!(
+                    "Unknown pipeline stage '{value}'. Use convert, translate, plugin:<id>, qa \
+                     or merge."
+                ))),
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    stages.sort_by_key(|setting| setting.stage);
+    let mut seen = HashSet::new();
+    stages.retain(|setting| seen.insert(setting.name()));
+    if stages.is_empty() {
+        return Err(IpcError::Validation("stages must not be empty.".into()));
+    }
+    Ok(stages)
+}
+
+async fn find_preset(db: &DbManager, preset_uuid: Uuid) -> Result<PipelinePresetRecord, IpcError> {
+    db.get_pipeline_preset(preset_uuid)
+        .await
+        .map_err(IpcError::from)?
+        .ok_or_else(|| IpcError::Validation(format!("Pipeline preset {preset_uuid} not found.")))
+}
+
+fn parse_definition(record: &PipelinePresetRecord) -> PipelineDefinition {
+    serde_json::from_str(&record.definition_json).unwrap_or_else(|error| {
+        log::warn!(
+            target: "ipc::pipeline",
+            "ignoring unreadable definition of pipeline preset {}: {error}",
+            record.preset_uuid
+        );
+        PipelineDefinition::full()
+    })
+}
+
+fn map_pipeline_preset_record(record: PipelinePresetRecord) -> PipelinePresetDto {
+    PipelinePresetDto {
+        preset_uuid: record.preset_uuid.to_string(),
+        definition: parse_definition(&record),
+        name: record.name,
+        description: record.description,
+        project_type: record.project_type,
+        updated_at: record.updated_at,
+    }
+}
+
+fn parse_uuid(value: &str, field: &str) -> Result<Uuid, IpcError> {
+    Uuid::parse_str(value)
+        .map_err(|_| IpcError::Validation(format!("invalid {field}: expected UUID, got '{value}'")))
+}
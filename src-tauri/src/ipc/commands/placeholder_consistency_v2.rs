@@ -0,0 +1,166 @@
+use std::collections::BTreeMap;
+
+use serde_json::json;
+use tauri::State;
+use uuid::Uuid;
+
+use super::projects_v2::resolve_project_root;
+use super::qa_v2::project_qa_profile;
+use super::shared::{list_project_jliff_paths, load_project_jliff};
+use crate::db::DbManager;
+use crate::db::types::{NewQaFindingArgs, ReplaceQaFindingsArgs};
+use crate::ipc::dto::{
+    CheckCrossPairPlaceholdersPayload, CrossPairDocumentDto, CrossPairFileReportDto,
+    CrossPairPlaceholdersQaDto, LanguagePlaceholdersDto, PlaceholderDivergenceDto,
+};
+use crate::ipc::error::{IpcError, IpcResult};
+use crate::jliff::JliffDocument;
+use crate::jliff::placeholder_consistency::find_divergences;
+use crate::metrics;
+use crate::settings::SettingsManager;
+
+/// QA check identifier used when persisting cross-pair placeholder findings.
+const CROSS_PAIR_PLACEHOLDER_CHECK: &str = "cross_pair_placeholders";
+
+/// Compares the placeholders of every target language of each source file and records a
+/// finding on the documents whose target diverges from the other languages.
+#[tauri::command]
+pub async fn check_cross_pair_placeholders_v2(
+    db: State<'_, DbManager>,
+    settings: State<'_, SettingsManager>,
+    payload: CheckCrossPairPlaceholdersPayload,
+) -> IpcResult<CrossPairPlaceholdersQaDto> {
+    metrics::track("check_cross_pair_placeholders_v2", async {
+    let project_uuid = parse_uuid(&payload.project_uuid, "projectUuid")?;
+    let check = project_qa_profile(db.inner(), project_uuid)
+        .await?
+        .check(CROSS_PAIR_PLACEHOLDER_CHECK, "warning");
+
+    // Documents of the same source file share the `file` header and source language.
+    let project_root = resolve_project_root(db.inner(), settings.inner(), project_uuid).await?;
+    let mut groups: BTreeMap<(String, String), Vec<(String, JliffDocument)>> = BTreeMap::new();
+    for jliff_rel_path in list_project_jliff_paths(&project_root).await? {
+        let (_, document) =
+            load_project_jliff(db.inner(), settings.inner(), project_uuid, &jliff_rel_path).await?;
+        if payload
+            .file
+            .as_deref()
+            .is_some_and(|file| file != document.file)
+        {
+            continue;
+        }
+        groups
+            .entry((document.file.clone(), document.source_language.clone()))
+            .or_default()
+            .push((jliff_rel_path, document));
+    }
+
+    let mut files = Vec::new();
+    let mut findings_recorded = 0;
+    for ((file, source_lang), documents) in groups {
+        if documents.len() < 2 {
+            continue;
+        }
+        let path_of = |target_lang: &str| {
+            documents
+                .iter()
+                .find(|(_, document)| document.target_language == target_lang)
+                .map(|(jliff_rel_path, _)| jliff_rel_path.clone())
+                .unwrap_or_default()
+        };
+        let divergences: Vec<PlaceholderDivergenceDto> = find_divergences(
+            &documents
+                .iter()
+                .map(|(_, document)| document)
+                .collect::<Vec<_>>(),
+        )
+        .into_iter()
+        .map(|divergence| PlaceholderDivergenceDto {
+            transunit_id: divergence.transunit_id,
+            source_placeholders: divergence.source_placeholders,
+            expected_placeholders: divergence.expected,
+            targets: divergence
+                .targets
+                .into_iter()
+                .map(|target| LanguagePlaceholdersDto {
+                    jliff_rel_path: path_of(&target.target_language),
+                    target_lang: target.target_language,
+                    placeholders: target.placeholders,
+                })
+                .collect(),
+            divergent_languages: divergence.divergent_languages,
+        })
+        .collect();
+
+        for (jliff_rel_path, document) in &documents {
+            let findings = divergences
+                .iter()
+                .filter(|_| check.enabled)
+                .filter_map(|divergence| {
+                    let target = divergence
+                        .targets
+                        .iter()
+                        .find(|target| target.target_lang == document.target_language)
+                        .filter(|_| {
+                            divergence
+                                .divergent_languages
+                                .contains(&document.target_language)
+                        })?;
+                    Some(NewQaFindingArgs {
+                        transunit_id: divergence.transunit_id.clone(),
+                        severity: check.severity.clone(),
+                        message: format!(
+                            "Placeholders differ from the other target languages: expected [{}], found [{}].",
+                            divergence.expected_placeholders.join(", "),
+                            target.placeholders.join(", ")
+                        ),
+                        details: Some(
+                            json!({
+                                "expected": divergence.expected_placeholders,
+                                "found": target.placeholders,
+                                "languages": divergence.targets.iter().map(|target| &target.target_lang).collect::<Vec<_>>(),
+                            })
+                            .to_string(),
+                        ),
+                    })
+                })
+                .collect();
+            findings_recorded += db
+                .replace_qa_findings(ReplaceQaFindingsArgs {
+                    project_uuid,
+                    jliff_rel_path: jliff_rel_path.clone(),
+                    check_type: CROSS_PAIR_PLACEHOLDER_CHECK.into(),
+                    transunit_ids: None,
+                    findings,
+                })
+                .await
+                .map_err(IpcError::from)?
+                .len();
+        }
+
+        files.push(CrossPairFileReportDto {
+            file,
+            source_lang,
+            documents: documents
+                .iter()
+                .map(|(jliff_rel_path, document)| CrossPairDocumentDto {
+                    jliff_rel_path: jliff_rel_path.clone(),
+                    target_lang: document.target_language.clone(),
+                })
+                .collect(),
+            divergences,
+        });
+    }
+
+    Ok(CrossPairPlaceholdersQaDto {
+        total_divergences: files.iter().map(|file| file.divergences.len()).sum(),
+        files,
+        findings_recorded,
+    })
+}).await
+}
+
+fn parse_uuid(value: &str, field: &str) -> Result<Uuid, IpcError> {
+    Uuid::parse_str(value)
+        .map_err(|_| IpcError::Validation(format!("invalid {field}: expected UUID, got '{value}'")))
+}
@@ -2,14 +2,20 @@ use std::collections::VecDeque;
 use std::sync::Mutex;
 use std::time::{Duration, Instant};
 
+use super::offline_v2::ensure_online;
+use crate::http::HttpClient;
+use crate::ipc::dto::PostalAddressDto;
 use crate::ipc::error::IpcError;
+use crate::ipc::state::OfflineMode;
+use crate::metrics;
 use log::{error, warn};
-use reqwest::{Client, StatusCode};
+use reqwest::StatusCode;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use tauri::State;
 
-const GOOGLE_AUTOCOMPLETE_URL: &str = "https://places.googleapis.com/v1/places:autocomplete";
+pub(super) const GOOGLE_AUTOCOMPLETE_URL: &str =
+    "https://places.googleapis.com/v1/places:autocomplete";
 const GOOGLE_PLACE_DETAILS_URL: &str = "https://places.googleapis.com/v1";
 const FIELD_MASK_AUTOCOMPLETE: &str = "suggestions.placePrediction.placeId,suggestions.placePrediction.place,\
 suggestions.placePrediction.structuredFormat,suggestions.placePrediction.text,\
@@ -24,7 +30,7 @@ const BURST_WINDOW_MAX_REQUESTS: usize = 120;
 const BURST_WINDOW_DURATION: Duration = Duration::from_secs(60);
 
 pub struct GooglePlacesService {
-    client: Client,
+    client: HttpClient,
     api_key: Option<String>,
     rate_limiter: Mutex<RateLimiter>,
 }
@@ -44,16 +50,13 @@ impl GooglePlacesService {
             );
         }
 
-        let client = Client::builder()
-            .user_agent(USER_AGENT)
-            .gzip(true)
-            .brotli(true)
-            .timeout(Duration::from_secs(10))
-            .build()
-            .unwrap_or_else(|err| {
-                error!("Failed to construct HTTP client for Google Places: {err}");
-                Client::new()
-            });
+        let client = HttpClient::new(|builder| {
+            builder
+                .user_agent(USER_AGENT)
+                .gzip(true)
+                .brotli(true)
+                .timeout(Duration::from_secs(10))
+        });
 
         Self {
             client,
@@ -98,6 +101,7 @@ impl GooglePlacesService {
 
         let response = self
             .client
+            .current()
             .post(GOOGLE_AUTOCOMPLETE_URL)
             .header("X-Goog-Api-Key", api_key)
             .header("X-Goog-FieldMask", FIELD_MASK_AUTOCOMPLETE)
@@ -143,6 +147,7 @@ impl GooglePlacesService {
 
         let mut request = self
             .client
+            .current()
             .get(format!("{GOOGLE_PLACE_DETAILS_URL}/{normalized}"))
             .header("X-Goog-Api-Key", api_key)
             .header("X-Goog-FieldMask", FIELD_MASK_DETAILS);
@@ -377,6 +382,8 @@ pub struct PlaceDetailsDto {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub display_name: Option<String>,
     pub components: AddressComponentsDto,
+    /// The components in the shape clients store, ready to save with the client.
+    pub postal_address: PostalAddressDto,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub location: Option<LocationDto>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -390,17 +397,27 @@ pub struct PlaceDetailsDto {
 #[tauri::command]
 pub async fn places_autocomplete(
     state: State<'_, GooglePlacesService>,
+    offline: State<'_, OfflineMode>,
     payload: PlacesAutocompletePayload,
 ) -> Result<PlacesAutocompleteResponse, IpcError> {
-    state.autocomplete(payload).await
+    metrics::track("places_autocomplete", async {
+        ensure_online(&offline, "Address lookup")?;
+        state.autocomplete(payload).await
+    })
+    .await
 }
 
 #[tauri::command]
 pub async fn places_resolve_details(
     state: State<'_, GooglePlacesService>,
+    offline: State<'_, OfflineMode>,
     payload: PlaceDetailsPayload,
 ) -> Result<PlaceDetailsResponse, IpcError> {
-    state.place_details(payload).await
+    metrics::track("places_resolve_details", async {
+        ensure_online(&offline, "Address lookup")?;
+        state.place_details(payload).await
+    })
+    .await
 }
 
 #[derive(Serialize)]
@@ -596,9 +613,20 @@ fn map_place_details_response(json: Value) -> PlaceDetailsResponse {
     }
 
     let components = collect_address_components(place_value.get("addressComponents"));
+    let id = get_string(place_value, &["id"]).map(|s| s.to_string());
+    let postal_address = PostalAddressDto {
+        street: components.route.clone(),
+        street_number: components.street_number.clone(),
+        postal_code: components.postal_code.clone(),
+        locality: components.locality.clone(),
+        region: components.administrative_area_level_1.clone(),
+        country_code: components.country_code.clone(),
+        country: components.country.clone(),
+        place_id: id.clone(),
+    };
 
     let place = PlaceDetailsDto {
-        id: get_string(place_value, &["id"]).map(|s| s.to_string()),
+        id,
         resource_name: get_string(place_value, &["resourceName"])
             .or_else(|| get_string(place_value, &["name"]))
             .map(|s| s.to_string()),
@@ -607,6 +635,7 @@ fn map_place_details_response(json: Value) -> PlaceDetailsResponse {
             .map(|s| s.to_string()),
         display_name: get_string(place_value, &["displayName", "text"]).map(|s| s.to_string()),
         components,
+        postal_address,
         location: place_value
             .get("location")
             .and_then(|value| parse_location(value)),
@@ -943,6 +972,8 @@ mod tests {
         assert_eq!(place.display_name.as_deref(), Some("Example HQ"));
         assert_eq!(place.components.locality.as_deref(), Some("Brussels"));
         assert_eq!(place.components.country_code.as_deref(), Some("BE"));
+        assert_eq!(place.postal_address.locality.as_deref(), Some("Brussels"));
+        assert_eq!(place.postal_address.place_id.as_deref(), Some("ChIJ123456"));
         assert_eq!(place.types, Some(vec!["street_address".into()]));
         assert_eq!(
             place
@@ -0,0 +1,308 @@
+//! Plugin registration and settings, and the `plugin` pipeline stage.
+
+use std::path::{Path, PathBuf};
+
+use serde_json::{Map, Value};
+use tauri::State;
+use uuid::Uuid;
+
+use super::sensitive_actions_v2::redeem_confirmation;
+use super::shared::{load_project_jliff, update_project_jliff};
+use crate::db::DbManager;
+use crate::db::types::{
+    NewSegmentRevisionArgs, PluginRecord, PluginSettingRecord, RecordSegmentRevisionsArgs,
+};
+use crate::ipc::dto::{
+    PluginDto, PluginSettingSpecDto, RegisterPluginPayload, SetPluginSettingsPayload,
+};
+use crate::ipc::error::{IpcError, IpcResult};
+use crate::ipc::state::SensitiveActionTokens;
+use crate::metrics;
+use crate::plugins::{PLUGINS_DIR, PluginInstance, PluginManifest, apply_response};
+use crate::settings::SettingsManager;
+
+#[tauri::command]
+pub async fn list_plugins_v2(db: State<'_, DbManager>) -> IpcResult<Vec<PluginDto>> {
+    metrics::track("list_plugins_v2", async {
+        let records = db.list_plugins().await.map_err(IpcError::from)?;
+        let mut plugins = Vec::with_capacity(records.len());
+        for record in records {
+            let stored = db
+                .list_plugin_settings(&record.plugin_id)
+                .await
+                .map_err(IpcError::from)?;
+            plugins.push(map_plugin(record, &stored)?);
+        }
+        Ok(plugins)
+    })
+    .await
+}
+
+/// Registers the plugin in a folder holding a `plugin.json`, or refreshes a registered
+/// plugin from its folder. Stored settings the new manifest no longer declares, or declares
+/// with another type, are dropped. Native executables are only accepted once the user
+/// allowed native plugins.
+#[tauri::command]
+pub async fn register_plugin_v2(
+    db: State<'_, DbManager>,
+    settings: State<'_, SettingsManager>,
+    payload: RegisterPluginPayload,
+) -> IpcResult<PluginDto> {
+    metrics::track("register_plugin_v2", async {
+        let dir = PathBuf::from(payload.path.trim());
+        let dir = dir.canonicalize().map_err(|error| {
+            IpcError::Validation(format!(
+                "Cannot open the plugin folder '{}': {error}",
+                payload.path
+            ))
+        })?;
+        let manifest = read_manifest(&dir, settings.current().await.allow_native_plugins)?;
+        let manifest_json = serde_json::to_string(&manifest).map_err(|error| {
+            IpcError::Internal(format!("Failed to serialize the plugin manifest: {error}"))
+        })?;
+
+        let record = db
+            .register_plugin(&manifest.id, &dir.to_string_lossy(), &manifest_json)
+            .await
+            .map_err(IpcError::from)?;
+        let stored = db
+            .list_plugin_settings(&manifest.id)
+            .await
+            .map_err(IpcError::from)?;
+        let kept = manifest
+            .resolve_settings(&stored_values(&stored), true)
+            .map_err(|error| IpcError::Internal(format!("{error:#}")))?
+            .into_iter()
+            .filter(|(key, _)| stored.iter().any(|setting| &setting.setting_key == key))
+            .collect();
+        let stored = db
+            .replace_plugin_settings(&manifest.id, &encode_values(&kept)?)
+            .await
+            .map_err(IpcError::from)?;
+        log::info!(
+            target: "ipc::plugins",
+            "registered plugin {} {} from {}",
+            manifest.id,
+            manifest.version,
+            dir.display()
+        );
+        Ok(map_plugin(record, &stored)?)
+    })
+    .await
+}
+
+/// Unregisters a plugin and drops its settings. Its folder and data folder are left alone.
+#[tauri::command]
+pub async fn unregister_plugin_v2(db: State<'_, DbManager>, plugin_id: String) -> IpcResult<bool> {
+    metrics::track("unregister_plugin_v2", async {
+        let removed = db
+            .unregister_plugin(plugin_id.trim())
+            .await
+            .map_err(IpcError::from)?;
+        Ok(removed)
+    })
+    .await
+}
+
+/// Replaces the stored settings of a plugin after checking them against its manifest. Needs
+/// a confirmation token from `request_sensitive_action_v2`, as settings hold API keys.
+#[tauri::command]
+pub async fn set_plugin_settings_v2(
+    db: State<'_, DbManager>,
+    tokens: State<'_, SensitiveActionTokens>,
+    payload: SetPluginSettingsPayload,
+    confirmation_token: String,
+) -> IpcResult<PluginDto> {
+    metrics::track("set_plugin_settings_v2", async {
+        redeem_confirmation(&tokens, &confirmation_token, "set_plugin_settings_v2")?;
+        let record = find_plugin(db.inner(), payload.plugin_id.trim()).await?;
+        let manifest = stored_manifest(&record)?;
+        manifest
+            .resolve_settings(&payload.values, false)
+            .map_err(|error| IpcError::Validation(format!("{error:#}")))?;
+        let values: Map<String, Value> = payload
+            .values
+            .into_iter()
+            .filter(|(_, value)| !value.is_null())
+            .collect();
+        let stored = db
+            .replace_plugin_settings(&record.plugin_id, &encode_values(&values)?)
+            .await
+            .map_err(IpcError::from)?;
+        Ok(map_plugin(record, &stored)?)
+    })
+    .await
+}
+
+/// Loads a registered plugin for a run: the manifest is read again from its folder, and the
+/// stored settings must satisfy it.
+pub(super) async fn load_plugin_instance(
+    db: &DbManager,
+    settings: &SettingsManager,
+    plugin_id: &str,
+) -> Result<PluginInstance, IpcError> {
+    let record = find_plugin(db, plugin_id).await?;
+    let dir = PathBuf::from(&record.plugin_dir);
+    let current = settings.current().await;
+    let manifest = read_manifest(&dir, current.allow_native_plugins)?;
+    if manifest.id != record.plugin_id {
+        return Err(IpcError::Validation(format!(
+            "The folder of the plugin '{plugin_id}' now holds '{}'; register it again.",
+            manifest.id
+        )));
+    }
+    let stored = db
+        .list_plugin_settings(plugin_id)
+        .await
+        .map_err(IpcError::from)?;
+    let known: Map<String, Value> = stored_values(&stored)
+        .into_iter()
+        .filter(|(key, _)| manifest.settings.iter().any(|spec| &spec.key == key))
+        .collect();
+    let values = manifest.resolve_settings(&known, false).map_err(|error| {
+        IpcError::Validation(format!(
+            "The settings of the plugin '{plugin_id}' are incomplete: {error:#}"
+        ))
+    })?;
+    Ok(PluginInstance {
+        data_dir: current.app_folder.join(PLUGINS_DIR).join(plugin_id),
+        manifest,
+        dir,
+        settings: values,
+        wasm_runtime: current.plugin_wasm_runtime,
+        native_allowed: current.allow_native_plugins,
+    })
+}
+
+/// Runs a plugin over one document, batch by batch, and records the targets it changed as
+/// revisions with the origin `plugin:<id>`. The document is only locked while a response is
+/// applied, not while the plugin runs.
+pub(super) async fn run_plugin_on_file(
+    db: &DbManager,
+    settings: &SettingsManager,
+    project_uuid: Uuid,
+    jliff_rel_path: &str,
+    plugin: &PluginInstance,
+    author: Option<String>,
+) -> Result<usize, IpcError> {
+    let (_, document) = load_project_jliff(db, settings, project_uuid, jliff_rel_path).await?;
+    let mut changed = 0;
+    for request in plugin.requests(&document) {
+        let response = plugin
+            .run(&request)
+            .await
+            .map_err(|error| IpcError::Internal(error.to_string()))?;
+        let sent = request.segments;
+        let edits = update_project_jliff(
+            db,
+            settings,
+            project_uuid,
+            jliff_rel_path,
+            move |document| {
+                apply_response(document, &sent, response)
+                    .map_err(|error| IpcError::Validation(format!("{error:#}")))
+            },
+        )
+        .await?;
+        if edits.is_empty() {
+            continue;
+        }
+        changed += edits.len();
+        db.record_segment_revisions(RecordSegmentRevisionsArgs {
+            project_uuid,
+            jliff_rel_path: jliff_rel_path.to_string(),
+            origin: format!("plugin:{}", plugin.manifest.id),
+            author: author.clone(),
+            revisions: edits
+                .into_iter()
+                .map(|edit| NewSegmentRevisionArgs {
+                    transunit_id: edit.transunit_id,
+                    previous_target: edit.previous_target,
+                    new_target: edit.new_target,
+                })
+                .collect(),
+        })
+        .await
+        .map_err(IpcError::from)?;
+    }
+    Ok(changed)
+}
+
+fn read_manifest(dir: &Path, native_allowed: bool) -> Result<PluginManifest, IpcError> {
+    let manifest =
+        PluginManifest::load(dir).map_err(|error| IpcError::Validation(format!("{error:#}")))?;
+    manifest
+        .check_entry(dir, native_allowed)
+        .map_err(|error| IpcError::Validation(format!("{error:#}")))?;
+    Ok(manifest)
+}
+
+async fn find_plugin(db: &DbManager, plugin_id: &str) -> Result<PluginRecord, IpcError> {
+    db.get_plugin(plugin_id)
+        .await
+        .map_err(IpcError::from)?
+        .ok_or_else(|| IpcError::Validation(format!("Plugin '{plugin_id}' is not registered.")))
+}
+
+fn stored_manifest(record: &PluginRecord) -> Result<PluginManifest, IpcError> {
+    serde_json::from_str(&record.manifest_json).map_err(|error| {
+        IpcError::Internal(format!(
+            "The stored manifest of the plugin '{}' is unreadable: {error}",
+            record.plugin_id
+        ))
+    })
+}
+
+fn stored_values(stored: &[PluginSettingRecord]) -> Map<String, Value> {
+    stored
+        .iter()
+        .filter_map(|setting| {
+            serde_json::from_str(&setting.value_json)
+                .ok()
+                .map(|value| (setting.setting_key.clone(), value))
+        })
+        .collect()
+}
+
+fn encode_values(values: &Map<String, Value>) -> Result<Vec<(String, String)>, IpcError> {
+    values
+        .iter()
+        .map(|(key, value)| {
+            serde_json::to_string(value)
+                .map(|json| (key.clone(), json))
+                .map_err(|error| IpcError::Internal(format!("Failed to store '{key}': {error}")))
+        })
+        .collect()
+}
+
+fn map_plugin(record: PluginRecord, stored: &[PluginSettingRecord]) -> Result<PluginDto, IpcError> {
+    let manifest = stored_manifest(&record)?;
+    let dir = Path::new(&record.plugin_dir);
+    let values = manifest
+        .resolve_settings(&stored_values(stored), true)
+        .map_err(|error| IpcError::Internal(format!("{error:#}")))?;
+    Ok(PluginDto {
+        available: PluginManifest::load(dir).is_ok_and(|current| current.id == manifest.id)
+            && dir.join(&manifest.entry).is_file(),
+        settings: manifest
+            .settings
+            .iter()
+            .map(|spec| PluginSettingSpecDto {
+                key: spec.key.clone(),
+                label: spec.label.clone(),
+                kind: spec.kind.as_str().to_string(),
+                default: spec.default.clone(),
+                required: spec.required,
+            })
+            .collect(),
+        values,
+        runtime: manifest.runtime.as_str().to_string(),
+        id: manifest.id,
+        name: manifest.name,
+        version: manifest.version,
+        description: manifest.description,
+        plugin_dir: record.plugin_dir,
+        registered_at: record.registered_at,
+        updated_at: record.updated_at,
+    })
+}
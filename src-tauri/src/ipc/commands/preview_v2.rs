@@ -0,0 +1,129 @@
+use std::path::PathBuf;
+
+use tauri::State;
+use uuid::Uuid;
+
+use super::shared::{fs_error, load_cached_tag_map, load_project_jliff};
+use crate::db::DbManager;
+use crate::ipc::dto::{
+    ExportIncontextPreviewPayload, IncontextPreviewExportDto, SegmentPreviewDto,
+    SegmentPreviewPayload,
+};
+use crate::ipc::error::{IpcError, IpcResult};
+use crate::jliff::document::find_transunit;
+use crate::jliff::incontext_preview::render_incontext_html;
+use crate::jliff::preview::render_segment_html;
+use crate::metrics;
+use crate::settings::SettingsManager;
+
+/// Renders a segment's source and current target as HTML, approximating the original inline
+/// formatting recorded in the tag map. When the tag map is unavailable, placeholders are shown
+/// as neutral tag chips.
+#[tauri::command]
+pub async fn render_segment_preview_v2(
+    db: State<'_, DbManager>,
+    settings: State<'_, SettingsManager>,
+    payload: SegmentPreviewPayload,
+) -> IpcResult<SegmentPreviewDto> {
+    metrics::track("render_segment_preview_v2", async {
+        let project_uuid = parse_uuid(&payload.project_uuid, "projectUuid")?;
+        let (jliff_path, document) = load_project_jliff(
+            db.inner(),
+            settings.inner(),
+            project_uuid,
+            &payload.jliff_rel_path,
+        )
+        .await?;
+
+        let unit = find_transunit(&document, &payload.transunit_id).ok_or_else(|| {
+            IpcError::Validation(format!(
+                "Segment '{}' was not found in '{}'.",
+                payload.transunit_id, payload.jliff_rel_path
+            ))
+        })?;
+
+        // A missing or unreadable tag map only degrades the preview to tag chips.
+        let tag_map = load_cached_tag_map(&jliff_path, &payload.jliff_rel_path)
+            .await
+            .ok();
+        let segment = tag_map
+            .as_ref()
+            .and_then(|tag_map| tag_map.segment_for(&unit.unit_id, &unit.transunit_id));
+
+        let source = render_segment_html(&unit.source, segment);
+        let target = render_segment_html(unit.effective_target(), segment);
+
+        let mut unresolved_placeholders = source.unresolved;
+        for placeholder in target.unresolved {
+            if !unresolved_placeholders.contains(&placeholder) {
+                unresolved_placeholders.push(placeholder);
+            }
+        }
+
+        Ok(SegmentPreviewDto {
+            transunit_id: unit.transunit_id.clone(),
+            source_html: source.html,
+            target_html: target.html,
+            unresolved_placeholders,
+        })
+    })
+    .await
+}
+
+/// Writes a standalone HTML page that lays the document's translations out in reading
+/// order, one paragraph per unit, with every segment anchored by its transunit id and
+/// colored by status, so reviewers can check translations in context.
+#[tauri::command]
+pub async fn export_incontext_preview_v2(
+    db: State<'_, DbManager>,
+    settings: State<'_, SettingsManager>,
+    payload: ExportIncontextPreviewPayload,
+) -> IpcResult<IncontextPreviewExportDto> {
+    metrics::track("export_incontext_preview_v2", async {
+        let project_uuid = parse_uuid(&payload.project_uuid, "projectUuid")?;
+        let output_path = PathBuf::from(payload.output_path.trim());
+        if !output_path.is_absolute() {
+            return Err(IpcError::Validation("outputPath must be an absolute path.".into()).into());
+        }
+
+        let (jliff_path, document) = load_project_jliff(
+            db.inner(),
+            settings.inner(),
+            project_uuid,
+            &payload.jliff_rel_path,
+        )
+        .await?;
+        let source_lang = payload.source_lang.trim();
+        let target_lang = payload.target_lang.trim();
+        if !document.source_language.eq_ignore_ascii_case(source_lang)
+            || !document.target_language.eq_ignore_ascii_case(target_lang)
+        {
+            return Err(IpcError::Validation(format!(
+                "'{}' is a {} → {} document, not {source_lang} → {target_lang}.",
+                payload.jliff_rel_path, document.source_language, document.target_language
+            ))
+            .into());
+        }
+
+        // Without a tag map inline codes are shown as tag chips.
+        let tag_map = load_cached_tag_map(&jliff_path, &payload.jliff_rel_path)
+            .await
+            .ok();
+        let preview = render_incontext_html(&document, tag_map.as_deref());
+        tokio::fs::write(&output_path, preview.html)
+            .await
+            .map_err(|error| fs_error("write the in-context preview", error))?;
+
+        Ok(IncontextPreviewExportDto {
+            output_path: payload.output_path,
+            segment_count: preview.segment_count,
+            untranslated_segments: preview.untranslated_segments,
+        })
+    })
+    .await
+}
+
+fn parse_uuid(value: &str, field: &str) -> Result<Uuid, IpcError> {
+    Uuid::parse_str(value)
+        .map_err(|_| IpcError::Validation(format!("invalid {field}: expected UUID, got '{value}'")))
+}
@@ -0,0 +1,88 @@
+use chrono::{Days, NaiveDate};
+use tauri::State;
+use uuid::Uuid;
+
+use crate::db::DbManager;
+use crate::ipc::dto::{DailyProductivityDto, ProductivityReportDto, ProductivityReportPayload};
+use crate::ipc::error::{IpcError, IpcResult};
+use crate::metrics;
+
+/// Longest accepted report range, in days.
+const MAX_REPORT_DAYS: i64 = 366;
+
+/// Aggregates the segments and source words a linguist confirmed per day between two
+/// dates (inclusive, UTC), with averages over the days they were active.
+#[tauri::command]
+pub async fn get_productivity_report_v2(
+    db: State<'_, DbManager>,
+    payload: ProductivityReportPayload,
+) -> IpcResult<ProductivityReportDto> {
+    metrics::track("get_productivity_report_v2", async {
+        let user_uuid = parse_uuid(&payload.user_uuid, "userUuid")?;
+        let from = parse_day(&payload.from, "from")?;
+        let to = parse_day(&payload.to, "to")?;
+        if to < from {
+            return Err(IpcError::Validation("to must not be before from.".into()).into());
+        }
+        if (to - from).num_days() >= MAX_REPORT_DAYS {
+            return Err(IpcError::Validation(format!(
+                "The report range cannot exceed {MAX_REPORT_DAYS} days."
+            ))
+            .into());
+        }
+        let until = to + Days::new(1);
+
+        let records = db
+            .daily_productivity(
+                user_uuid,
+                &from.format("%Y-%m-%d 00:00:00").to_string(),
+                &until.format("%Y-%m-%d 00:00:00").to_string(),
+            )
+            .await
+            .map_err(IpcError::from)?;
+
+        let total_segments: i64 = records.iter().map(|record| record.segments).sum();
+        let total_words: i64 = records.iter().map(|record| record.words).sum();
+        let active_days = records.len();
+        let average = |total: i64| {
+            if active_days == 0 {
+                0.0
+            } else {
+                total as f64 / active_days as f64
+            }
+        };
+
+        Ok(ProductivityReportDto {
+            user_uuid: user_uuid.to_string(),
+            from: from.to_string(),
+            to: to.to_string(),
+            total_segments,
+            total_words,
+            active_days,
+            average_words_per_active_day: average(total_words),
+            average_segments_per_active_day: average(total_segments),
+            days: records
+                .into_iter()
+                .map(|record| DailyProductivityDto {
+                    day: record.day,
+                    segments: record.segments,
+                    words: record.words,
+                })
+                .collect(),
+        })
+    })
+    .await
+}
+
+fn parse_day(value: &str, field: &str) -> Result<NaiveDate, IpcError> {
+    NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d").map_err(|_| {
+        IpcError::Validation(format!(
+            "invalid {field}: expected YYYY-MM-DD, got '{value}'"
+        ))
+    })
+}
+
+fn parse_uuid(value: &str, field: &str) -> Result<Uuid, IpcError> {
+    Uuid::parse_str(value)
+        .map_err(|_| IpcError::Validation(format!("invalid {field}: expected UUID, got '{value}'")))
+}
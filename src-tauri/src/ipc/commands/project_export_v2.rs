@@ -0,0 +1,193 @@
+//! Export of a whole project into one archive, to hand it to a colleague on another machine.
+
+use std::path::{Path, PathBuf};
+
+use tauri::State;
+use uuid::Uuid;
+
+use super::projects_v2::resolve_project_root;
+use super::shared::with_project_file_locks;
+use crate::db::DbManager;
+use crate::db::types::ProjectBundle;
+use crate::delivery::{PackageSource, write_package};
+use crate::ipc::dto::{ExportProjectBundlePayload, ProjectBundleExportDto};
+use crate::ipc::error::{IpcError, IpcResult};
+use crate::metrics;
+use crate::project_export::{
+    ExportedFile, ExportedLanguagePair, ExportedProject, FORMAT_VERSION, PROJECT_BUNDLE_EXTENSION,
+    ProjectBundleManifest, bundle_entries,
+};
+use crate::settings::SettingsManager;
+
+/// Writes the project folder (translations, references, instructions and JLIFF documents)
+/// and a manifest of the project's metadata, language pairs and files into one ZIP at
+/// `outputPath`. An existing file there is only replaced once the new archive is complete.
+#[tauri::command]
+pub async fn export_project_bundle_v2(
+    db: State<'_, DbManager>,
+    settings: State<'_, SettingsManager>,
+    payload: ExportProjectBundlePayload,
+) -> IpcResult<ProjectBundleExportDto> {
+    metrics::track("export_project_bundle_v2", async {
+        let project_uuid = Uuid::parse_str(&payload.project_uuid).map_err(|_| {
+            IpcError::Validation(format!(
+                "invalid projectUuid: expected UUID, got '{}'",
+                payload.project_uuid
+            ))
+        })?;
+        let mut output_path = PathBuf::from(payload.output_path.trim());
+        if !output_path.is_absolute() {
+            return Err(IpcError::Validation("outputPath must be an absolute path.".into()).into());
+        }
+        if output_path.extension().is_none() {
+            output_path.set_extension(PROJECT_BUNDLE_EXTENSION);
+        }
+        let bundle = db
+            .get_project_bundle(project_uuid)
+            .await
+            .map_err(IpcError::from)?
+            .ok_or_else(|| {
+                IpcError::Validation(format!("Project {project_uuid} was not found."))
+            })?;
+        let project_root = resolve_project_root(db.inner(), settings.inner(), project_uuid).await?;
+        if output_path.starts_with(&project_root) {
+            return Err(IpcError::Validation(
+                "Choose a destination outside the project folder.".into(),
+            )
+            .into());
+        }
+        let client_name = match bundle.project.client_uuid {
+            Some(client_uuid) => db
+                .get_client_record(client_uuid)
+                .await
+                .map_err(IpcError::from)?
+                .map(|client| client.name),
+            None => None,
+        };
+        let manifest = build_manifest(bundle, client_name);
+
+        let root = project_root.clone();
+        let listed = manifest.clone();
+        let entries = tokio::task::spawn_blocking(move || bundle_entries(&listed, &root))
+            .await
+            .map_err(|error| IpcError::Internal(format!("Packaging failed: {error}")))?
+            .map_err(|error| {
+                log::error!(
+                    target: "ipc::project_export",
+                    "failed to list project files: {error:#}"
+                );
+                IpcError::Internal("The project files could not be read.".into())
+            })?;
+        let locked: Vec<PathBuf> = entries
+            .iter()
+            .filter_map(|entry| match &entry.source {
+                PackageSource::File(path) => Some(path.clone()),
+                PackageSource::Contents(_) => None,
+            })
+            .collect();
+
+        // Hold the project's file locks so no edit lands half-way through packaging, and
+        // write next to the destination so a failed export leaves an existing file intact.
+        let path = output_path.clone();
+        let (size_bytes, files) = with_project_file_locks(&locked, || async move {
+            tokio::task::spawn_blocking(move || -> anyhow::Result<(u64, usize)> {
+                let partial = partial_path(&path);
+                if partial.is_file() {
+                    std::fs::remove_file(&partial)?;
+                }
+                let written = write_package(&partial, &entries).and_then(|size| {
+                    std::fs::rename(&partial, &path)?;
+                    Ok(size)
+                });
+                if written.is_err() {
+                    let _ = std::fs::remove_file(&partial);
+                }
+                // The manifest is the one entry that is not a project file.
+                Ok((written?, entries.len() - 1))
+            })
+            .await
+        })
+        .await
+        .map_err(|error| IpcError::Internal(format!("Packaging failed: {error}")))?
+        .map_err(|error| {
+            log::error!(
+                target: "ipc::project_export",
+                "failed to write project bundle: {error:#}"
+            );
+            IpcError::Internal("The project bundle could not be written.".into())
+        })?;
+
+        log::info!(
+            target: "ipc::project_export",
+            "exported project {project_uuid} to {}",
+            output_path.display()
+        );
+        Ok(ProjectBundleExportDto {
+            path: output_path.to_string_lossy().into_owned(),
+            size_bytes,
+            files,
+            language_pairs: manifest.language_pairs.len(),
+        })
+    })
+    .await
+}
+
+/// Sibling of `path` the archive is written to before it is renamed into place.
+fn partial_path(path: &Path) -> PathBuf {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    path.with_file_name(format!(".{name}.partial"))
+}
+
+fn build_manifest(bundle: ProjectBundle, client_name: Option<String>) -> ProjectBundleManifest {
+    let project = bundle.project;
+    ProjectBundleManifest {
+        format_version: FORMAT_VERSION,
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        project: ExportedProject {
+            project_uuid: project.project_uuid.to_string(),
+            name: project.project_name,
+            number: project.project_number,
+            project_type: project.r#type,
+            status: project.project_status,
+            client_name,
+            subjects: bundle
+                .subjects
+                .into_iter()
+                .map(|record| record.subject)
+                .collect(),
+            notes: project.notes,
+            due_date: project.due_date,
+            created_at: project.creation_date,
+            updated_at: project.update_date,
+        },
+        language_pairs: bundle
+            .language_pairs
+            .into_iter()
+            .map(|pair| ExportedLanguagePair {
+                source_lang: pair.source_lang,
+                target_lang: pair.target_lang,
+            })
+            .collect(),
+        files: bundle
+            .files
+            .into_iter()
+            .map(|file| ExportedFile {
+                filename: file.link.filename,
+                stored_at: file.link.stored_at,
+                role: file.link.r#type,
+                language_pairs: file
+                    .language_pairs
+                    .into_iter()
+                    .map(|pair| ExportedLanguagePair {
+                        source_lang: pair.source_lang,
+                        target_lang: pair.target_lang,
+                    })
+                    .collect(),
+            })
+            .collect(),
+    }
+}
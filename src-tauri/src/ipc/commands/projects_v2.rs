@@ -1,5 +1,6 @@
 use serde_json::json;
-use std::collections::HashSet;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::io;
 use std::path::{Path, PathBuf};
@@ -8,29 +9,45 @@ use tauri::{AppHandle, Emitter, Runtime, State};
 use tokio::task;
 use uuid::Uuid;
 
+use super::delivery_v2::{
+    DELIVERED_STATUS, delivery_package_for_transition, enforce_delivery_gate,
+};
+use super::jobs_v2::map_job_record;
+use super::output_naming_v2::{OutputNamer, recorded_jliff_prefix};
+use super::segmentation_exceptions_v2::exception_rules;
+use super::shared::record_project_history;
 use crate::db::DbManager;
+use crate::db::health::parse_timestamp;
 use crate::db::types::{
     FileInfoRecord, FileLanguagePairInput, NewArtifactArgs, NewFileInfoArgs, NewJobArgs,
     NewProjectArgs, NewProjectFileArgs, ProjectBundle, ProjectConversionStats, ProjectFileBundle,
-    ProjectFileTotals, ProjectJobStats, ProjectLanguagePairInput, ProjectListRecord,
-    ProjectProgressStats, ProjectRecord, ProjectStatistics, ProjectSubjectInput,
+    ProjectFileTotals, ProjectHealthRecord, ProjectJobStats, ProjectLanguagePairInput,
+    ProjectListRecord, ProjectProgressStats, ProjectRecord, ProjectStatistics, ProjectSubjectInput,
     ProjectWarningStats, UpdateArtifactStatusArgs, UpdateProjectArgs,
 };
+use crate::formats::{self, FormatRole, FormatsManifest};
 use crate::ipc::dto::{
     ArtifactV2Dto, AttachProjectFilePayload, ConversionPlanDto, ConversionTaskDto,
     ConvertXliffToJliffPayload, CreateProjectPayload, CreateProjectWithAssetsPayload,
     CreateProjectWithAssetsResponseDto, EnsureConversionPlanPayload, FileInfoV2Dto,
-    FileIntegrityAlertDto, FileLanguagePairDto, JliffConversionResultDto, JobV2Dto,
+    FileIntegrityAlertDto, FileLanguagePairDto, JliffConversionResultDto,
     ProjectAssetDescriptorDto, ProjectAssetResultDto, ProjectAssetRoleDto, ProjectBundleV2Dto,
     ProjectConversionStatsDto, ProjectFileBundleV2Dto, ProjectFileLinkDto, ProjectFileTotalsDto,
-    ProjectJobStatsDto, ProjectLanguagePairDto, ProjectProgressStatsDto, ProjectRecordV2Dto,
-    ProjectStatisticsDto, ProjectWarningStatsDto, UpdateConversionStatusPayload,
-    UpdateProjectPayload,
+    ProjectHealthDto, ProjectJobStatsDto, ProjectLanguagePairDto, ProjectProgressStatsDto,
+    ProjectRecordV2Dto, ProjectStatisticsDto, ProjectWarningStatsDto,
+    UpdateConversionStatusPayload, UpdateProjectPayload, XliffValidationFailureDto,
 };
 use crate::ipc::error::{IpcError, IpcResult};
 use crate::ipc::events::{PROJECT_CREATE_COMPLETE, PROJECT_CREATE_PROGRESS};
-use crate::jliff::{ConversionOptions, convert_xliff};
-use crate::settings::SettingsManager;
+use crate::jliff::history::HistoryEvent;
+use crate::jliff::segmentation;
+use crate::jliff::xliff_validation::validate_xliff;
+use crate::jliff::{ConversionOptions, ProtectedTerm, ProtectionRule, convert_xliff};
+use crate::metrics;
+use crate::settings::{AppSettings, SettingsManager};
+
+/// Folder of the app data holding the segmentation rules handed to the converter.
+const SEGMENTATION_DIR: &str = "segmentation";
 
 #[tauri::command]
 pub async fn create_project_with_assets_v2(
@@ -39,7 +56,10 @@ pub async fn create_project_with_assets_v2(
     settings: State<'_, SettingsManager>,
     payload: CreateProjectWithAssetsPayload,
 ) -> IpcResult<CreateProjectWithAssetsResponseDto> {
-    create_project_with_assets_impl(app, db.inner(), settings.inner(), payload).await
+    metrics::track("create_project_with_assets_v2", async {
+        create_project_with_assets_impl(app, db.inner(), settings.inner(), payload).await
+    })
+    .await
 }
 
 pub async fn create_project_with_assets_impl<R: Runtime>(
@@ -55,6 +75,7 @@ pub async fn create_project_with_assets_impl<R: Runtime>(
     );
 
     let folder_name = validate_project_folder_name(&payload.project_folder_name)?;
+    let pipeline_preset = find_pipeline_preset(db, payload.pipeline_preset_uuid.as_deref()).await?;
     emit_progress_event(
         &app,
         folder_name,
@@ -93,6 +114,15 @@ pub async fn create_project_with_assets_impl<R: Runtime>(
 
     let project_uuid = project_bundle.project.project_uuid;
 
+    if let Some(preset_uuid) = pipeline_preset
+        && let Err(error) = db
+            .assign_pipeline_preset(project_uuid, Some(preset_uuid))
+            .await
+    {
+        rollback_project_creation(db, project_uuid).await;
+        return Err(IpcError::from(error).into());
+    }
+
     emit_progress_event(
         &app,
         folder_name,
@@ -178,6 +208,7 @@ pub async fn create_project_with_assets_impl<R: Runtime>(
         db,
         project_uuid,
         &destination,
+        &settings_snapshot,
         &copied_assets,
         &payload.language_pairs,
     )
@@ -241,25 +272,80 @@ pub async fn create_project_bundle_v2(
     db: State<'_, DbManager>,
     payload: CreateProjectPayload,
 ) -> IpcResult<ProjectBundleV2Dto> {
-    let args = map_new_project_args(payload)?;
-    let bundle = db
-        .create_project_bundle(args)
-        .await
-        .map_err(IpcError::from)?;
-    Ok(map_project_bundle(bundle))
+    metrics::track("create_project_bundle_v2", async {
+        let pipeline_preset =
+            find_pipeline_preset(db.inner(), payload.pipeline_preset_uuid.as_deref()).await?;
+        let args = map_new_project_args(payload)?;
+        let bundle = db
+            .create_project_bundle(args)
+            .await
+            .map_err(IpcError::from)?;
+        if let Some(preset_uuid) = pipeline_preset {
+            db.assign_pipeline_preset(bundle.project.project_uuid, Some(preset_uuid))
+                .await
+                .map_err(IpcError::from)?;
+        }
+        Ok(map_project_bundle(bundle))
+    })
+    .await
 }
 
 #[tauri::command]
 pub async fn update_project_bundle_v2(
     db: State<'_, DbManager>,
+    settings: State<'_, SettingsManager>,
     payload: UpdateProjectPayload,
 ) -> IpcResult<Option<ProjectBundleV2Dto>> {
-    let args = map_update_project_args(payload)?;
-    let bundle = db
-        .update_project_bundle(args)
-        .await
-        .map_err(IpcError::from)?;
-    Ok(bundle.map(map_project_bundle))
+    metrics::track("update_project_bundle_v2", async {
+        let delivery_override = payload.delivery_override.clone();
+        let delivery_package_uuid = payload.delivery_package_uuid.clone();
+        let args = map_update_project_args(payload)?;
+        let mut delivered_package = None;
+        if args
+            .project_status
+            .as_deref()
+            .is_some_and(|status| status.trim().eq_ignore_ascii_case(DELIVERED_STATUS))
+        {
+            let already_delivered = db
+                .get_project_bundle(args.project_uuid)
+                .await
+                .map_err(IpcError::from)?
+                .is_some_and(|bundle| {
+                    bundle
+                        .project
+                        .project_status
+                        .eq_ignore_ascii_case(DELIVERED_STATUS)
+                });
+            if !already_delivered {
+                enforce_delivery_gate(
+                    db.inner(),
+                    settings.inner(),
+                    args.project_uuid,
+                    delivery_override.as_ref(),
+                )
+                .await?;
+                delivered_package = delivery_package_for_transition(
+                    db.inner(),
+                    args.project_uuid,
+                    delivery_package_uuid.as_deref(),
+                )
+                .await?;
+            }
+        }
+        let bundle = db
+            .update_project_bundle(args)
+            .await
+            .map_err(IpcError::from)?;
+        if bundle.is_some()
+            && let Some(artifact_uuid) = delivered_package
+        {
+            db.mark_delivery_package_delivered(artifact_uuid)
+                .await
+                .map_err(IpcError::from)?;
+        }
+        Ok(bundle.map(map_project_bundle))
+    })
+    .await
 }
 
 #[tauri::command]
@@ -267,11 +353,14 @@ pub async fn delete_project_bundle_v2(
     db: State<'_, DbManager>,
     project_uuid: String,
 ) -> IpcResult<()> {
-    let uuid = parse_uuid(&project_uuid, "projectUuid")?;
-    db.delete_project_bundle(uuid)
-        .await
-        .map_err(IpcError::from)?;
-    Ok(())
+    metrics::track("delete_project_bundle_v2", async {
+        let uuid = parse_uuid(&project_uuid, "projectUuid")?;
+        db.delete_project_bundle(uuid)
+            .await
+            .map_err(IpcError::from)?;
+        Ok(())
+    })
+    .await
 }
 
 #[tauri::command]
@@ -279,9 +368,12 @@ pub async fn get_project_bundle_v2(
     db: State<'_, DbManager>,
     project_uuid: String,
 ) -> IpcResult<Option<ProjectBundleV2Dto>> {
-    let uuid = parse_uuid(&project_uuid, "projectUuid")?;
-    let bundle = db.get_project_bundle(uuid).await.map_err(IpcError::from)?;
-    Ok(bundle.map(map_project_bundle))
+    metrics::track("get_project_bundle_v2", async {
+        let uuid = parse_uuid(&project_uuid, "projectUuid")?;
+        let bundle = db.get_project_bundle(uuid).await.map_err(IpcError::from)?;
+        Ok(bundle.map(map_project_bundle))
+    })
+    .await
 }
 
 #[tauri::command]
@@ -289,20 +381,47 @@ pub async fn get_project_statistics_v2(
     db: State<'_, DbManager>,
     project_uuid: String,
 ) -> IpcResult<Option<ProjectStatisticsDto>> {
-    let uuid = parse_uuid(&project_uuid, "projectUuid")?;
-    let stats = db
-        .get_project_statistics(uuid)
-        .await
-        .map_err(IpcError::from)?;
-    Ok(stats.map(map_project_statistics))
+    metrics::track("get_project_statistics_v2", async {
+        let uuid = parse_uuid(&project_uuid, "projectUuid")?;
+        let stats = db
+            .get_project_statistics(uuid)
+            .await
+            .map_err(IpcError::from)?;
+        Ok(stats.map(map_project_statistics))
+    })
+    .await
 }
 
+/// Lists projects; `query` keeps those whose name, client name or a filename contains it,
+/// ignoring case and accents.
 #[tauri::command]
 pub async fn list_project_records_v2(
     db: State<'_, DbManager>,
+    query: Option<String>,
 ) -> IpcResult<Vec<ProjectRecordV2Dto>> {
-    let records = db.list_project_records().await.map_err(IpcError::from)?;
-    Ok(records.into_iter().map(map_project_list_record).collect())
+    metrics::track("list_project_records_v2", async {
+        let records = db
+            .list_project_records(query.as_deref())
+            .await
+            .map_err(IpcError::from)?;
+        let mut health: HashMap<Uuid, ProjectHealthRecord> = db
+            .refresh_project_health()
+            .await
+            .map_err(IpcError::from)?
+            .into_iter()
+            .map(|record| (record.project_uuid, record))
+            .collect();
+        Ok(records
+            .into_iter()
+            .map(|record| {
+                let project_health = health.remove(&record.project_uuid);
+                let mut dto = map_project_list_record(record);
+                dto.health = project_health.map(map_project_health);
+                dto
+            })
+            .collect())
+    })
+    .await
 }
 
 #[tauri::command]
@@ -310,14 +429,17 @@ pub async fn attach_project_file_v2(
     db: State<'_, DbManager>,
     payload: AttachProjectFilePayload,
 ) -> IpcResult<ProjectFileBundleV2Dto> {
-    let file_uuid = resolve_attachment_file_uuid(&payload)?;
-    let file_info = map_new_file_info_args(&payload, file_uuid);
-    let link_args = map_new_project_file_args(&payload, file_uuid)?;
-    let bundle = db
-        .attach_project_file(file_info, link_args)
-        .await
-        .map_err(IpcError::from)?;
-    Ok(map_project_file_bundle(bundle))
+    metrics::track("attach_project_file_v2", async {
+        let file_uuid = resolve_attachment_file_uuid(&payload)?;
+        let file_info = map_new_file_info_args(&payload, file_uuid);
+        let link_args = map_new_project_file_args(&payload, file_uuid)?;
+        let bundle = db
+            .attach_project_file(file_info, link_args)
+            .await
+            .map_err(IpcError::from)?;
+        Ok(map_project_file_bundle(bundle))
+    })
+    .await
 }
 
 #[tauri::command]
@@ -326,12 +448,15 @@ pub async fn detach_project_file_v2(
     project_uuid: String,
     file_uuid: String,
 ) -> IpcResult<()> {
-    let project_uuid = parse_uuid(&project_uuid, "projectUuid")?;
-    let file_uuid = parse_uuid(&file_uuid, "fileUuid")?;
-    db.detach_project_file(project_uuid, file_uuid)
-        .await
-        .map_err(IpcError::from)?;
-    Ok(())
+    metrics::track("detach_project_file_v2", async {
+        let project_uuid = parse_uuid(&project_uuid, "projectUuid")?;
+        let file_uuid = parse_uuid(&file_uuid, "fileUuid")?;
+        db.detach_project_file(project_uuid, file_uuid)
+            .await
+            .map_err(IpcError::from)?;
+        Ok(())
+    })
+    .await
 }
 
 #[tauri::command]
@@ -340,155 +465,200 @@ pub async fn ensure_project_conversions_plan_v2(
     settings: State<'_, SettingsManager>,
     payload: EnsureConversionPlanPayload,
 ) -> IpcResult<ConversionPlanDto> {
-    let project_uuid = parse_uuid(&payload.project_uuid, "projectUuid")?;
-    let filter_ids: Option<HashSet<Uuid>> = payload
-        .file_uuids
-        .as_ref()
-        .map(|ids| {
-            let mut parsed = HashSet::with_capacity(ids.len());
-            for id in ids {
-                let uuid = parse_uuid(id, "fileUuid")?;
-                parsed.insert(uuid);
-            }
-            Ok::<_, IpcError>(parsed)
-        })
-        .transpose()?;
-
-    let bundle = db
-        .get_project_bundle(project_uuid)
-        .await
-        .map_err(IpcError::from)?
-        .ok_or_else(|| IpcError::Validation(format!("Project '{}' not found", project_uuid)))?;
+    metrics::track("ensure_project_conversions_plan_v2", async {
+        let project_uuid = parse_uuid(&payload.project_uuid, "projectUuid")?;
+        let filter_ids: Option<HashSet<Uuid>> = payload
+            .file_uuids
+            .as_ref()
+            .map(|ids| {
+                let mut parsed = HashSet::with_capacity(ids.len());
+                for id in ids {
+                    let uuid = parse_uuid(id, "fileUuid")?;
+                    parsed.insert(uuid);
+                }
+                Ok::<_, IpcError>(parsed)
+            })
+            .transpose()?;
 
-    let settings_snapshot = settings.current().await;
-    let projects_root = settings_snapshot.projects_dir();
-    let project_root = locate_project_root(&projects_root, project_uuid, &bundle).await?;
-    let default_version = settings_snapshot.default_xliff_version.clone();
+        let bundle = db
+            .get_project_bundle(project_uuid)
+            .await
+            .map_err(IpcError::from)?
+            .ok_or_else(|| IpcError::Validation(format!("Project '{}' not found", project_uuid)))?;
+
+        let settings_snapshot = settings.current().await;
+        let projects_root = settings_snapshot.projects_dir();
+        let project_root = locate_project_root(&projects_root, project_uuid, &bundle).await?;
+        let default_version = settings_snapshot.default_xliff_version.clone();
+        let formats = formats::current(&settings_snapshot.app_folder).await;
+        let mut namer = OutputNamer::load(db.inner(), &settings_snapshot, project_uuid).await?;
+
+        let mut tasks: Vec<ConversionTaskDto> = Vec::new();
+        let mut alerts: Vec<FileIntegrityAlertDto> = Vec::new();
+        let mut validation_failures: Vec<XliffValidationFailureDto> = Vec::new();
+
+        for file_bundle in &bundle.files {
+            if !file_bundle.link.r#type.eq_ignore_ascii_case("processable") {
+                continue;
+            }
 
-    let mut tasks: Vec<ConversionTaskDto> = Vec::new();
-    let mut alerts: Vec<FileIntegrityAlertDto> = Vec::new();
+            if let Some(filters) = filter_ids.as_ref() {
+                if !filters.contains(&file_bundle.link.file_uuid) {
+                    continue;
+                }
+            }
 
-    for file_bundle in &bundle.files {
-        if !file_bundle.link.r#type.eq_ignore_ascii_case("processable") {
-            continue;
-        }
+            let input_rel = Path::new(&file_bundle.link.stored_at);
+            let input_abs = project_root.join(input_rel);
 
-        if let Some(filters) = filter_ids.as_ref() {
-            if !filters.contains(&file_bundle.link.file_uuid) {
+            if !input_abs.is_file() {
+                alerts.push(FileIntegrityAlertDto {
+                    file_uuid: file_bundle.link.file_uuid.to_string(),
+                    file_name: file_bundle.link.filename.clone(),
+                    expected_hash: None,
+                    actual_hash: None,
+                });
                 continue;
             }
-        }
 
-        let input_rel = Path::new(&file_bundle.link.stored_at);
-        let input_abs = project_root.join(input_rel);
+            let extension = input_rel
+                .extension()
+                .map(|extension| extension.to_string_lossy().to_lowercase())
+                .unwrap_or_default();
+            let is_xliff = formats.is_bilingual(&extension);
+            let filter = formats.filter_for(&extension).map(str::to_string);
+            if payload.validate_xliff && is_xliff {
+                let path = input_abs.clone();
+                let report = task::spawn_blocking(move || validate_xliff(&path, None))
+                    .await
+                    .map_err(|error| IpcError::Internal(format!("Validation failed: {error}")))?
+                    .map_err(|error| IpcError::Internal(format!("{error:#}")))?;
+                if !report.passed() {
+                    validation_failures.push(XliffValidationFailureDto {
+                        file_uuid: file_bundle.link.file_uuid.to_string(),
+                        file_name: file_bundle.link.filename.clone(),
+                        diagnostics: report.diagnostics,
+                    });
+                    continue;
+                }
+            }
 
-        if !input_abs.is_file() {
-            alerts.push(FileIntegrityAlertDto {
-                file_uuid: file_bundle.link.file_uuid.to_string(),
-                file_name: file_bundle.link.filename.clone(),
-                expected_hash: None,
-                actual_hash: None,
-            });
-            continue;
-        }
+            let artifact_uuid =
+                ensure_conversion_artifact(db.inner(), project_uuid, file_bundle.link.file_uuid)
+                    .await?;
 
-        let artifact_uuid =
-            ensure_conversion_artifact(db.inner(), project_uuid, file_bundle.link.file_uuid)
-                .await?;
+            db.update_artifact_status(UpdateArtifactStatusArgs {
+                artifact_uuid,
+                status: "PENDING".into(),
+                size_bytes: None,
+                segment_count: None,
+                token_count: None,
+            })
+            .await
+            .map_err(IpcError::from)?;
+
+            ensure_conversion_job(db.inner(), project_uuid, artifact_uuid, "pending", None).await?;
+
+            let file_pairs: Vec<ProjectLanguagePairDto> = if !file_bundle.language_pairs.is_empty()
+            {
+                file_bundle
+                    .language_pairs
+                    .iter()
+                    .map(|pair| ProjectLanguagePairDto {
+                        source_lang: pair.source_lang.clone(),
+                        target_lang: pair.target_lang.clone(),
+                    })
+                    .collect()
+            } else {
+                bundle
+                    .language_pairs
+                    .iter()
+                    .map(|pair| ProjectLanguagePairDto {
+                        source_lang: pair.source_lang.clone(),
+                        target_lang: pair.target_lang.clone(),
+                    })
+                    .collect()
+            };
 
-        db.update_artifact_status(UpdateArtifactStatusArgs {
-            artifact_uuid,
-            status: "PENDING".into(),
-            size_bytes: None,
-            segment_count: None,
-            token_count: None,
-        })
-        .await
-        .map_err(IpcError::from)?;
+            if file_pairs.is_empty() {
+                alerts.push(FileIntegrityAlertDto {
+                    file_uuid: file_bundle.link.file_uuid.to_string(),
+                    file_name: file_bundle.link.filename.clone(),
+                    expected_hash: None,
+                    actual_hash: None,
+                });
+                continue;
+            }
 
-        ensure_conversion_job(db.inner(), project_uuid, artifact_uuid, "pending", None).await?;
+            let file_stem = Path::new(&file_bundle.link.filename)
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .map(str::to_owned)
+                .unwrap_or_else(|| "artifact".to_string());
+
+            let source_path_str = input_abs.to_string_lossy().into_owned();
+
+            for pair in file_pairs {
+                let output_rel_path = namer
+                    .names(
+                        db.inner(),
+                        file_bundle.link.file_uuid,
+                        input_rel,
+                        &file_stem,
+                        &pair,
+                        artifact_uuid,
+                    )
+                    .await?
+                    .xliff_rel_path;
+                let output_abs_path = project_root.join(&output_rel_path);
+
+                if let Some(parent) = output_abs_path.parent() {
+                    if let Err(error) = tokio::fs::create_dir_all(parent).await {
+                        return Err(IpcError::Internal(format!(
+                            "Failed to prepare output directory '{}': {}",
+                            parent.display(),
+                            error
+                        ))
+                        .into());
+                    }
+                }
 
-        let file_pairs: Vec<ProjectLanguagePairDto> = if !file_bundle.language_pairs.is_empty() {
-            file_bundle
-                .language_pairs
-                .iter()
-                .map(|pair| ProjectLanguagePairDto {
-                    source_lang: pair.source_lang.clone(),
-                    target_lang: pair.target_lang.clone(),
-                })
-                .collect()
-        } else {
-            bundle
-                .language_pairs
-                .iter()
-                .map(|pair| ProjectLanguagePairDto {
+                let output_rel_path_str = output_rel_path.to_string_lossy().into_owned();
+                let output_abs_path_str = output_abs_path.to_string_lossy().into_owned();
+
+                tasks.push(ConversionTaskDto {
+                    draft_id: file_bundle.link.file_uuid.to_string(),
+                    file_uuid: Some(file_bundle.link.file_uuid.to_string()),
+                    artifact_uuid: Some(artifact_uuid.to_string()),
+                    job_type: Some("xliff_conversion".into()),
                     source_lang: pair.source_lang.clone(),
                     target_lang: pair.target_lang.clone(),
-                })
-                .collect()
-        };
-
-        if file_pairs.is_empty() {
-            alerts.push(FileIntegrityAlertDto {
-                file_uuid: file_bundle.link.file_uuid.to_string(),
-                file_name: file_bundle.link.filename.clone(),
-                expected_hash: None,
-                actual_hash: None,
-            });
-            continue;
-        }
-
-        let file_stem = Path::new(&file_bundle.link.filename)
-            .file_stem()
-            .and_then(|stem| stem.to_str())
-            .map(str::to_owned)
-            .unwrap_or_else(|| "artifact".to_string());
-
-        let source_path_str = input_abs.to_string_lossy().into_owned();
-
-        for pair in file_pairs {
-            let language_dir = language_pair_directory_name(&pair);
-            let output_rel_path = Path::new("Translations")
-                .join(&language_dir)
-                .join(format!("{file_stem}.xlf"));
-            let output_abs_path = project_root.join(&output_rel_path);
-
-            if let Some(parent) = output_abs_path.parent() {
-                if let Err(error) = tokio::fs::create_dir_all(parent).await {
-                    return Err(IpcError::Internal(format!(
-                        "Failed to prepare output directory '{}': {}",
-                        parent.display(),
-                        error
-                    ))
-                    .into());
-                }
+                    source_path: source_path_str.clone(),
+                    xliff_rel_path: output_rel_path_str,
+                    xliff_abs_path: Some(output_abs_path_str),
+                    version: Some(default_version.clone()),
+                    paragraph: Some(true),
+                    embed: Some(true),
+                    srx: segmentation_rules_for(
+                        &db,
+                        project_uuid,
+                        &settings_snapshot.app_folder,
+                        &pair.source_lang,
+                    )
+                    .await?,
+                    filter: filter.clone(),
+                });
             }
-
-            let output_rel_path_str = output_rel_path.to_string_lossy().into_owned();
-            let output_abs_path_str = output_abs_path.to_string_lossy().into_owned();
-
-            tasks.push(ConversionTaskDto {
-                draft_id: file_bundle.link.file_uuid.to_string(),
-                file_uuid: Some(file_bundle.link.file_uuid.to_string()),
-                artifact_uuid: Some(artifact_uuid.to_string()),
-                job_type: Some("xliff_conversion".into()),
-                source_lang: pair.source_lang.clone(),
-                target_lang: pair.target_lang.clone(),
-                source_path: source_path_str.clone(),
-                xliff_rel_path: output_rel_path_str,
-                xliff_abs_path: Some(output_abs_path_str),
-                version: Some(default_version.clone()),
-                paragraph: Some(true),
-                embed: Some(true),
-            });
         }
-    }
 
-    Ok(ConversionPlanDto {
-        project_uuid: project_uuid.to_string(),
-        tasks,
-        integrity_alerts: alerts,
+        Ok(ConversionPlanDto {
+            project_uuid: project_uuid.to_string(),
+            tasks,
+            integrity_alerts: alerts,
+            validation_failures,
+        })
     })
+    .await
 }
 
 #[tauri::command]
@@ -496,38 +666,43 @@ pub async fn update_conversion_status_v2(
     db: State<'_, DbManager>,
     payload: UpdateConversionStatusPayload,
 ) -> IpcResult<ArtifactV2Dto> {
-    let artifact_uuid = parse_uuid(&payload.artifact_uuid, "artifactUuid")?;
-    let status_upper = payload.status.to_uppercase();
-    let job_status = payload.status.to_lowercase();
+    metrics::track("update_conversion_status_v2", async {
+        let artifact_uuid = parse_uuid(&payload.artifact_uuid, "artifactUuid")?;
+        let status_upper = payload.status.to_uppercase();
+        let job_status = payload.status.to_lowercase();
 
-    let updated = db
-        .update_artifact_status(UpdateArtifactStatusArgs {
-            artifact_uuid,
-            status: status_upper,
-            size_bytes: payload.size_bytes,
-            segment_count: payload.segment_count,
-            token_count: payload.token_count,
-        })
-        .await
-        .map_err(IpcError::from)?
-        .ok_or_else(|| IpcError::Validation("artifact not found for conversion update".into()))?;
+        let updated = db
+            .update_artifact_status(UpdateArtifactStatusArgs {
+                artifact_uuid,
+                status: status_upper,
+                size_bytes: payload.size_bytes,
+                segment_count: payload.segment_count,
+                token_count: payload.token_count,
+            })
+            .await
+            .map_err(IpcError::from)?
+            .ok_or_else(|| {
+                IpcError::Validation("artifact not found for conversion update".into())
+            })?;
 
-    let error_log = if job_status == "failed" {
-        payload.error_message.clone()
-    } else {
-        None
-    };
+        let error_log = if job_status == "failed" {
+            payload.error_message.clone()
+        } else {
+            None
+        };
 
-    ensure_conversion_job(
-        db.inner(),
-        updated.project_uuid,
-        artifact_uuid,
-        &job_status,
-        error_log,
-    )
-    .await?;
+        ensure_conversion_job(
+            db.inner(),
+            updated.project_uuid,
+            artifact_uuid,
+            &job_status,
+            error_log,
+        )
+        .await?;
 
-    Ok(map_artifact_record(updated))
+        Ok(map_artifact_record(updated))
+    })
+    .await
 }
 
 #[tauri::command]
@@ -536,9 +711,36 @@ pub async fn convert_xliff_to_jliff_v2(
     settings: State<'_, SettingsManager>,
     payload: ConvertXliffToJliffPayload,
 ) -> IpcResult<JliffConversionResultDto> {
-    let project_uuid = parse_uuid(&payload.project_uuid, "projectUuid")?;
-    let conversion_uuid = parse_uuid(&payload.conversion_id, "conversionId")?;
-    let xliff_path = PathBuf::from(&payload.xliff_abs_path);
+    metrics::track("convert_xliff_to_jliff_v2", async {
+        let project_uuid = parse_uuid(&payload.project_uuid, "projectUuid")?;
+        let conversion_uuid = parse_uuid(&payload.conversion_id, "conversionId")?;
+        convert_project_xliff(
+            db.inner(),
+            settings.inner(),
+            project_uuid,
+            conversion_uuid,
+            Path::new(&payload.xliff_abs_path),
+            payload.operator.clone(),
+            payload.schema_abs_path.as_deref().map(PathBuf::from),
+        )
+        .await
+        .map_err(Into::into)
+    })
+    .await
+}
+
+/// Converts an XLIFF of the project to JLIFF next to it, named with the prefix recorded when
+/// the XLIFF was planned (or after `conversion_uuid`), with the project's protected terms and
+/// protection rules applied.
+pub(super) async fn convert_project_xliff(
+    db: &DbManager,
+    settings: &SettingsManager,
+    project_uuid: Uuid,
+    conversion_uuid: Uuid,
+    xliff_path: &Path,
+    operator: Option<String>,
+    schema_path: Option<PathBuf>,
+) -> Result<JliffConversionResultDto, IpcError> {
     let xliff_dir = xliff_path.parent().ok_or_else(|| {
         IpcError::Validation("xliffAbsPath must reference a file within a directory".into())
     })?;
@@ -554,21 +756,49 @@ pub async fn convert_xliff_to_jliff_v2(
     let project_root = locate_project_root(&projects_root, project_uuid, &bundle).await?;
 
     let mut options = ConversionOptions::new(
-        xliff_path.clone(),
+        xliff_path.to_path_buf(),
         xliff_dir.to_path_buf(),
         bundle.project.project_name.clone(),
         project_uuid.to_string(),
-        payload
-            .operator
-            .clone()
-            .unwrap_or_else(|| "operator".into()),
+        operator.unwrap_or_else(|| "operator".into()),
     );
 
-    options.file_prefix = Some(conversion_uuid.to_string());
+    let xliff_rel_path = xliff_path.strip_prefix(&project_root).unwrap_or(xliff_path);
+    let prefix = recorded_jliff_prefix(db, project_uuid, xliff_rel_path).await?;
+    options.file_prefix = Some(prefix.unwrap_or_else(|| conversion_uuid.to_string()));
+    options.normalize_unicode = settings_snapshot.normalize_unicode_nfc;
 
-    if let Some(schema_path) = payload.schema_abs_path.as_ref() {
-        options.schema_path = Some(PathBuf::from(schema_path));
-    }
+    options.schema_path = schema_path;
+
+    options.protected_terms = db
+        .list_protected_terms(project_uuid)
+        .await
+        .map_err(IpcError::from)?
+        .into_iter()
+        .map(|record| ProtectedTerm::new(record.term, record.case_sensitive))
+        .collect();
+
+    options.protection_rules = db
+        .list_protection_rules(project_uuid)
+        .await
+        .map_err(IpcError::from)?
+        .into_iter()
+        .filter(|record| record.is_enabled)
+        .filter_map(
+            |record| match ProtectionRule::compile(&record.name, &record.pattern) {
+                Ok(rule) => Some(rule),
+                Err(error) => {
+                    log::warn!(
+                        target: "ipc::projects_v2",
+                        "skipping invalid protection rule '{}': {}",
+                        record.name,
+                        error
+                    );
+                    None
+                }
+            },
+        )
+        .collect();
 
     let generated = convert_xliff(&options).map_err(|err| IpcError::Internal(err.to_string()))?;
 
@@ -580,6 +810,23 @@ pub async fn convert_xliff_to_jliff_v2(
     let tag_map_abs_path = primary.tag_map_path.to_string_lossy().into_owned();
     let jliff_rel_path = relative_to_project(&primary.jliff_path, &project_root)?;
     let tag_map_rel_path = relative_to_project(&primary.tag_map_path, &project_root)?;
+    record_project_history(
+        db,
+        settings,
+        project_uuid,
+        HistoryEvent {
+            kind: "conversion",
+            summary: format!(
+                "Convert {} to JLIFF",
+                xliff_path
+                    .file_name()
+                    .map(|name| name.to_string_lossy())
+                    .unwrap_or_default()
+            ),
+            files: vec![jliff_rel_path.clone(), tag_map_rel_path.clone()],
+        },
+    )
+    .await;
 
     Ok(JliffConversionResultDto {
         file_id: primary.file_id,
@@ -597,16 +844,36 @@ pub async fn update_project_file_role_v2(
     file_uuid: String,
     next_role: String,
 ) -> IpcResult<ProjectFileBundleV2Dto> {
-    let project_uuid = parse_uuid(&project_uuid, "projectUuid")?;
-    let file_uuid = parse_uuid(&file_uuid, "fileUuid")?;
-    let normalized_role = normalize_project_file_role(&next_role)?;
+    metrics::track("update_project_file_role_v2", async {
+        let project_uuid = parse_uuid(&project_uuid, "projectUuid")?;
+        let file_uuid = parse_uuid(&file_uuid, "fileUuid")?;
+        let normalized_role = normalize_project_file_role(&next_role)?;
 
-    let bundle = db
-        .update_project_file_role(project_uuid, file_uuid, &normalized_role)
-        .await
-        .map_err(IpcError::from)?;
+        let bundle = db
+            .update_project_file_role(project_uuid, file_uuid, &normalized_role)
+            .await
+            .map_err(IpcError::from)?;
+
+        Ok(map_project_file_bundle(bundle))
+    })
+    .await
+}
 
-    Ok(map_project_file_bundle(bundle))
+/// Checks that the pipeline preset chosen for a new project exists.
+async fn find_pipeline_preset(
+    db: &DbManager,
+    value: Option<&str>,
+) -> Result<Option<Uuid>, IpcError> {
+    let Some(value) = value else {
+        return Ok(None);
+    };
+    let preset_uuid = parse_uuid(value, "pipelinePresetUuid")?;
+    if db.get_pipeline_preset(preset_uuid).await?.is_none() {
+        return Err(IpcError::Validation(format!(
+            "Pipeline preset '{preset_uuid}' not found."
+        )));
+    }
+    Ok(Some(preset_uuid))
 }
 
 fn map_new_project_args(payload: CreateProjectPayload) -> Result<NewProjectArgs, IpcError> {
@@ -643,6 +910,7 @@ fn map_new_project_args(payload: CreateProjectPayload) -> Result<NewProjectArgs,
         client_uuid,
         r#type: payload.r#type,
         notes: payload.notes,
+        due_date: None,
         subjects: payload
             .subjects
             .into_iter()
@@ -671,6 +939,8 @@ fn map_new_project_args_from_assets_payload(
         None => None,
     };
 
+    let due_date = parse_due_date(payload.due_date.clone())?;
+
     let subjects = payload
         .subjects
         .iter()
@@ -693,11 +963,28 @@ fn map_new_project_args_from_assets_payload(
         client_uuid,
         r#type: payload.r#type.clone(),
         notes: payload.notes.clone(),
+        due_date,
         subjects,
         language_pairs,
     })
 }
 
+/// Trims a due date and checks it is a date or RFC 3339 timestamp; blank means none.
+fn parse_due_date(value: Option<String>) -> Result<Option<String>, IpcError> {
+    let Some(value) = value.map(|value| value.trim().to_string()) else {
+        return Ok(None);
+    };
+    if value.is_empty() {
+        return Ok(None);
+    }
+    if parse_timestamp(&value).is_none() {
+        return Err(IpcError::Validation(format!(
+            "invalid dueDate: expected a date or RFC 3339 timestamp, got '{value}'"
+        )));
+    }
+    Ok(Some(value))
+}
+
 #[allow(dead_code)]
 #[derive(Debug)]
 struct CopiedAssetInfo {
@@ -803,7 +1090,25 @@ async fn copy_project_assets(
     copied.map_err(InvokeError::from)
 }
 
-fn resolve_asset_directory(root: &Path, role: ProjectAssetRoleDto) -> PathBuf {
+/// Role of a file by extension, as the formats manifest assigns it: documents the pipeline
+/// can translate are processable and pictures are images; anything else, such as a PDF of
+/// the print layout, is a reference.
+pub(crate) fn default_asset_role(
+    formats: &FormatsManifest,
+    extension: &str,
+) -> ProjectAssetRoleDto {
+    map_format_role(formats.default_role(extension))
+}
+
+pub(super) fn map_format_role(role: FormatRole) -> ProjectAssetRoleDto {
+    match role {
+        FormatRole::Processable => ProjectAssetRoleDto::Processable,
+        FormatRole::Image => ProjectAssetRoleDto::Image,
+        FormatRole::Reference => ProjectAssetRoleDto::Reference,
+    }
+}
+
+pub(crate) fn resolve_asset_directory(root: &Path, role: ProjectAssetRoleDto) -> PathBuf {
     match role {
         ProjectAssetRoleDto::Processable => root.join("Translations"),
         ProjectAssetRoleDto::Reference | ProjectAssetRoleDto::Image => root.join("References"),
@@ -835,7 +1140,7 @@ fn build_destination_filename(descriptor: &ProjectAssetDescriptorDto) -> String
     }
 }
 
-fn cleanup_files(paths: &[PathBuf]) {
+pub(crate) fn cleanup_files(paths: &[PathBuf]) {
     for path in paths.iter().rev() {
         if let Err(error) = fs::remove_file(path) {
             log::warn!(
@@ -912,7 +1217,7 @@ fn sanitize_locale_segment(input: &str) -> String {
     }
 }
 
-fn language_pair_directory_name(pair: &ProjectLanguagePairDto) -> String {
+pub(super) fn language_pair_directory_name(pair: &ProjectLanguagePairDto) -> String {
     let source = sanitize_locale_segment(&pair.source_lang);
     let target = sanitize_locale_segment(&pair.target_lang);
     format!("{source}_{target}")
@@ -961,6 +1266,43 @@ async fn create_language_pair_directories(
     creation_result.map_err(InvokeError::from)
 }
 
+/// Path of the SRX rules for `source_lang` in a project, writing them to the app folder
+/// first. Projects with segmentation exceptions for the language get rules of their own;
+/// otherwise the default rules are used when the language is segmented better by them than
+/// by the converter's built-in rules.
+async fn segmentation_rules_for(
+    db: &DbManager,
+    project_uuid: Uuid,
+    app_folder: &Path,
+    source_lang: &str,
+) -> IpcResult<Option<String>> {
+    let rules = exception_rules(db, Some(project_uuid), source_lang).await?;
+    if rules.is_empty() && !segmentation::uses_cjk_rules(source_lang) {
+        return Ok(None);
+    }
+    let rules_dir = app_folder.join(SEGMENTATION_DIR);
+    let path = task::spawn_blocking(move || {
+        if rules.is_empty() {
+            return segmentation::write_default_srx(&rules_dir);
+        }
+        // Named after the content, so plans of different projects never share a file that
+        // one of them is rewriting.
+        let srx = segmentation::custom_srx(&rules);
+        let digest: String = Sha256::digest(srx.as_bytes())[..8]
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect();
+        let name = format!("custom-{digest}.srx");
+        segmentation::write_srx(&rules_dir, &name, &srx)
+    })
+    .await
+    .map_err(|join_err| {
+        IpcError::Internal(format!("Failed to write segmentation rules: {join_err}"))
+    })?
+    .map_err(|error| IpcError::Internal(format!("{error:#}")))?;
+    Ok(Some(path.to_string_lossy().into_owned()))
+}
+
 async fn cleanup_seeded_artifacts_and_jobs(
     db: &DbManager,
     jobs: &[(Uuid, String)],
@@ -1004,6 +1346,7 @@ async fn prepare_conversion_plan(
     db: &DbManager,
     project_uuid: Uuid,
     project_dir: &Path,
+    settings: &AppSettings,
     copied_assets: &[CopiedAssetInfo],
     language_pairs: &[ProjectLanguagePairDto],
 ) -> Result<Option<ConversionPlanDto>, InvokeError> {
@@ -1024,12 +1367,15 @@ async fn prepare_conversion_plan(
             project_uuid: project_uuid.to_string(),
             tasks: Vec::new(),
             integrity_alerts: Vec::new(),
+            validation_failures: Vec::new(),
         }));
     }
 
     let mut tasks = Vec::new();
     let mut created_artifacts = Vec::new();
     let mut created_jobs = Vec::new();
+    let mut namer = OutputNamer::load(db, settings, project_uuid).await?;
+    let formats = formats::current(&settings.app_folder).await;
 
     for asset in processable_assets {
         let source_path = asset.absolute_path.to_string_lossy().into_owned();
@@ -1041,14 +1387,27 @@ async fn prepare_conversion_plan(
             .unwrap_or_else(|| "artifact".to_string());
 
         for pair in language_pairs {
-            let language_dir = language_pair_directory_name(pair);
-            let output_rel_path = Path::new("Translations")
-                .join(&language_dir)
-                .join(format!("{file_stem}.xlf"));
+            let artifact_uuid = Uuid::new_v4();
+            let output_rel_path = match namer
+                .names(
+                    db,
+                    asset.file_uuid,
+                    stored_rel_path,
+                    &file_stem,
+                    pair,
+                    artifact_uuid,
+                )
+                .await
+            {
+                Ok(names) => names.xliff_rel_path,
+                Err(error) => {
+                    cleanup_seeded_artifacts_and_jobs(db, &created_jobs, &created_artifacts).await;
+                    return Err(error.into());
+                }
+            };
             let output_rel_path_str = output_rel_path.to_string_lossy().into_owned();
             let output_abs_path = project_dir.join(&output_rel_path);
             let output_abs_path_str = output_abs_path.to_string_lossy().into_owned();
-            let artifact_uuid = Uuid::new_v4();
             let job_type = "xliff_conversion".to_string();
 
             let artifact_args = NewArtifactArgs {
@@ -1095,6 +1454,16 @@ async fn prepare_conversion_plan(
                 version: None,
                 paragraph: Some(true),
                 embed: Some(true),
+                srx: segmentation_rules_for(
+                    db,
+                    project_uuid,
+                    &settings.app_folder,
+                    &pair.source_lang,
+                )
+                .await?,
+                filter: formats
+                    .filter_for(&asset.original_extension)
+                    .map(str::to_string),
             });
         }
     }
@@ -1110,10 +1479,26 @@ async fn prepare_conversion_plan(
         project_uuid: project_uuid.to_string(),
         tasks,
         integrity_alerts: Vec::new(),
+        validation_failures: Vec::new(),
     }))
 }
 
-async fn locate_project_root(
+/// Resolves the on-disk root folder of a project using the current settings.
+pub(crate) async fn resolve_project_root(
+    db: &DbManager,
+    settings: &SettingsManager,
+    project_uuid: Uuid,
+) -> Result<PathBuf, IpcError> {
+    let bundle = db
+        .get_project_bundle(project_uuid)
+        .await
+        .map_err(IpcError::from)?
+        .ok_or_else(|| IpcError::Validation(format!("Project '{}' not found", project_uuid)))?;
+    let projects_root = settings.current().await.projects_dir();
+    locate_project_root(&projects_root, project_uuid, &bundle).await
+}
+
+pub(crate) async fn locate_project_root(
     projects_root: &Path,
     project_uuid: Uuid,
     bundle: &ProjectBundle,
@@ -1178,7 +1563,7 @@ async fn locate_project_root(
     })
 }
 
-async fn ensure_conversion_artifact(
+pub(super) async fn ensure_conversion_artifact(
     db: &DbManager,
     project_uuid: Uuid,
     file_uuid: Uuid,
@@ -1242,7 +1627,7 @@ fn relative_to_project(path: &Path, project_root: &Path) -> Result<String, IpcEr
     Ok(relative.to_string_lossy().into_owned())
 }
 
-fn map_asset_role_to_file_info_type(role: ProjectAssetRoleDto) -> String {
+pub(crate) fn map_asset_role_to_file_info_type(role: ProjectAssetRoleDto) -> String {
     match role {
         ProjectAssetRoleDto::Processable => "processable".to_string(),
         ProjectAssetRoleDto::Reference => "reference".to_string(),
@@ -1252,7 +1637,7 @@ fn map_asset_role_to_file_info_type(role: ProjectAssetRoleDto) -> String {
     }
 }
 
-fn map_asset_role_to_project_file_type(role: ProjectAssetRoleDto) -> String {
+pub(crate) fn map_asset_role_to_project_file_type(role: ProjectAssetRoleDto) -> String {
     match role {
         ProjectAssetRoleDto::Processable => "processable".to_string(),
         ProjectAssetRoleDto::Reference => "reference".to_string(),
@@ -1262,7 +1647,7 @@ fn map_asset_role_to_project_file_type(role: ProjectAssetRoleDto) -> String {
     }
 }
 
-fn file_language_pairs_for_role(
+pub(crate) fn file_language_pairs_for_role(
     role: ProjectAssetRoleDto,
     pairs: &[ProjectLanguagePairDto],
 ) -> Vec<FileLanguagePairInput> {
@@ -1308,6 +1693,11 @@ fn map_update_project_args(payload: UpdateProjectPayload) -> Result<UpdateProjec
         None => None,
     };
 
+    let due_date = match payload.due_date {
+        Some(value) => Some(parse_due_date(value)?),
+        None => None,
+    };
+
     let subjects = payload.subjects.map(|list| {
         list.into_iter()
             .map(|subject| ProjectSubjectInput { subject })
@@ -1322,6 +1712,8 @@ fn map_update_project_args(payload: UpdateProjectPayload) -> Result<UpdateProjec
         client_uuid,
         r#type: payload.r#type,
         notes: payload.notes,
+        due_date,
+        no_external_mt: payload.no_external_mt,
         subjects,
         language_pairs,
     })
@@ -1420,7 +1812,7 @@ fn map_project_statistics(stats: ProjectStatistics) -> ProjectStatisticsDto {
     }
 }
 
-fn map_project_bundle(bundle: ProjectBundle) -> ProjectBundleV2Dto {
+pub(super) fn map_project_bundle(bundle: ProjectBundle) -> ProjectBundleV2Dto {
     ProjectBundleV2Dto {
         project: map_project_record(bundle.project),
         subjects: bundle
@@ -1454,8 +1846,21 @@ fn map_project_record(record: ProjectRecord) -> ProjectRecordV2Dto {
         client_name: None,
         r#type: record.r#type,
         notes: record.notes,
+        project_number: record.project_number,
         subjects: None,
         file_count: None,
+        due_date: record.due_date,
+        no_external_mt: record.no_external_mt,
+        health: None,
+    }
+}
+
+fn map_project_health(record: ProjectHealthRecord) -> ProjectHealthDto {
+    ProjectHealthDto {
+        level: record.level,
+        score: record.score,
+        flags: record.flags.0,
+        evaluated_at: record.evaluated_at,
     }
 }
 
@@ -1471,12 +1876,16 @@ fn map_project_list_record(record: ProjectListRecord) -> ProjectRecordV2Dto {
         client_name: record.client_name,
         r#type: record.r#type,
         notes: record.notes,
+        project_number: record.project_number,
         subjects: Some(record.subjects.0),
         file_count: Some(record.file_count),
+        due_date: record.due_date,
+        no_external_mt: record.no_external_mt,
+        health: None,
     }
 }
 
-fn map_project_file_bundle(bundle: ProjectFileBundle) -> ProjectFileBundleV2Dto {
+pub(crate) fn map_project_file_bundle(bundle: ProjectFileBundle) -> ProjectFileBundleV2Dto {
     ProjectFileBundleV2Dto {
         file: map_project_file_record(bundle.link),
         info: map_file_info_record(bundle.info),
@@ -1522,7 +1931,7 @@ fn map_project_language_pair_input(dto: ProjectLanguagePairDto) -> ProjectLangua
     }
 }
 
-fn map_project_language_pair_record(
+pub(crate) fn map_project_language_pair_record(
     record: crate::db::types::ProjectLanguagePairRecord,
 ) -> ProjectLanguagePairDto {
     ProjectLanguagePairDto {
@@ -1560,16 +1969,6 @@ fn map_artifact_record(record: crate::db::types::ArtifactRecord) -> ArtifactV2Dt
     }
 }
 
-fn map_job_record(record: crate::db::types::JobRecord) -> JobV2Dto {
-    JobV2Dto {
-        artifact_uuid: record.artifact_uuid.to_string(),
-        job_type: record.job_type,
-        project_uuid: record.project_uuid.to_string(),
-        job_status: record.job_status,
-        error_log: record.error_log,
-    }
-}
-
 fn parse_uuid(value: &str, field: &str) -> Result<Uuid, IpcError> {
     Uuid::parse_str(value)
         .map_err(|_| IpcError::Validation(format!("invalid {field}: expected UUID, got '{value}'")))
@@ -1880,9 +2279,13 @@ pub mod test_support {
     #[allow(dead_code)]
     pub fn build_settings_manager(app_folder: PathBuf) -> SettingsManager {
         let settings_path = app_folder.join("settings.yaml");
+        SettingsManager::new(settings_path, default_settings(app_folder))
+    }
 
-        let settings = AppSettings {
-            app_folder: app_folder.clone(),
+    /// Settings used by tests: fixed values instead of whatever the host machine has.
+    pub fn default_settings(app_folder: PathBuf) -> AppSettings {
+        AppSettings {
+            app_folder,
             auto_convert_on_open: true,
             theme: "auto".into(),
             ui_language: "en".into(),
@@ -1892,10 +2295,39 @@ pub mod test_support {
             show_notifications: true,
             enable_sound_notifications: false,
             max_parallel_conversions: 4,
+            auto_propagate_repetitions: true,
+            propagation_scope: "file".into(),
+            propagation_case_sensitive: true,
+            snapshot_interval_minutes: 10,
+            snapshot_edit_threshold: 50,
+            snapshot_retention: 20,
+            length_limit_enforcement: "warn".into(),
+            provider_cache_ttl_hours: 720,
+            local_model_base_url: "http://127.0.0.1:11434".into(),
+            local_model_name: String::new(),
+            mock_provider_enabled: false,
+            mock_provider_latency_ms: 0,
+            mock_provider_failure_rate: 0,
+            normalize_unicode_nfc: true,
+            event_webhook_url: None,
+            vies_lookup: false,
+            base_currency: "EUR".into(),
+            project_number_template: "{client_code}-{yyyy}-{seq:4}".into(),
+            asset_role_rules: crate::import::roles::default_role_rules(),
+            ocr_command: None,
+            plugin_wasm_runtime: None,
+            allow_native_plugins: false,
+            database_encryption: false,
+            auto_lock_minutes: None,
+            xliff_name_template: "{stem}".into(),
+            jliff_name_template: "{uuid}".into(),
+            workspaces: Vec::new(),
+            proxy_url: None,
+            proxy_username: None,
+            no_proxy: Vec::new(),
+            crash_report_upload: false,
             database_journal_mode: "WAL".into(),
             database_synchronous: "NORMAL".into(),
-        };
-
-        SettingsManager::new(settings_path, settings)
+        }
     }
 }
@@ -0,0 +1,394 @@
+use std::collections::HashSet;
+
+use tauri::State;
+use uuid::Uuid;
+
+use crate::db::DbManager;
+use crate::db::types::{
+    NewPromptTemplateArgs, PromptTemplateAssignmentRecord, PromptTemplateRecord,
+    UpdatePromptTemplateArgs,
+};
+use crate::ipc::dto::{
+    AssignPromptTemplatePayload, CreatePromptTemplatePayload, PreviewPromptPayload,
+    PromptMessageDto, PromptPreviewDto, PromptTemplateAssignmentDto, PromptTemplateDto,
+    UpdatePromptTemplatePayload,
+};
+use crate::ipc::error::{IpcError, IpcResult};
+use crate::metrics;
+use crate::providers::{ChatMessage, PromptTemplateDefinition, PromptTerm, PromptVariables};
+
+/// Template, project name and terminology used to prompt a provider for one project language
+/// pair.
+pub(super) struct ProjectPrompt {
+    pub template_uuid: Option<Uuid>,
+    pub definition: PromptTemplateDefinition,
+    pub project_name: Option<String>,
+    pub terms: Vec<PromptTerm>,
+}
+
+impl ProjectPrompt {
+    pub fn render(
+        &self,
+        source_lang: &str,
+        target_lang: &str,
+        source: &str,
+        context: Option<&str>,
+    ) -> Vec<ChatMessage> {
+        self.definition.render(&PromptVariables {
+            source_lang,
+            target_lang,
+            source,
+            context,
+            project_name: self.project_name.as_deref(),
+            terms: &self.terms,
+        })
+    }
+}
+
+/// Loads the prompt for a language pair: the project's assigned template (or the built-in
+/// one) and `terms` followed by the project's protected terms.
+pub(super) async fn load_project_prompt(
+    db: &DbManager,
+    project_uuid: Option<&str>,
+    source_lang: &str,
+    target_lang: &str,
+    terms: Vec<PromptTerm>,
+) -> Result<ProjectPrompt, IpcError> {
+    let mut prompt = ProjectPrompt {
+        template_uuid: None,
+        definition: PromptTemplateDefinition::default(),
+        project_name: None,
+        terms: Vec::new(),
+    };
+    let mut seen = HashSet::new();
+    for term in terms {
+        let source = term.source.trim().to_string();
+        if !source.is_empty() && seen.insert(source.clone()) {
+            prompt.terms.push(PromptTerm { source, ..term });
+        }
+    }
+
+    let Some(project_uuid) = project_uuid else {
+        return Ok(prompt);
+    };
+    let project_uuid = parse_uuid(project_uuid, "projectUuid")?;
+    let bundle = db
+        .get_project_bundle(project_uuid)
+        .await
+        .map_err(IpcError::from)?
+        .ok_or_else(|| IpcError::Validation(format!("project {project_uuid} not found")))?;
+    prompt.project_name = Some(bundle.project.project_name);
+
+    if let Some(record) = db
+        .prompt_template_for_pair(project_uuid, source_lang.trim(), target_lang.trim())
+        .await
+        .map_err(IpcError::from)?
+    {
+        prompt.template_uuid = Some(record.template_uuid);
+        prompt.definition = parse_definition(&record)?;
+    }
+
+    for record in db
+        .list_protected_terms(project_uuid)
+        .await
+        .map_err(IpcError::from)?
+    {
+        if seen.insert(record.term.clone()) {
+            prompt.terms.push(PromptTerm {
+                source: record.term,
+                target: None,
+            });
+        }
+    }
+    Ok(prompt)
+}
+
+#[tauri::command]
+pub async fn list_prompt_templates_v2(
+    db: State<'_, DbManager>,
+) -> IpcResult<Vec<PromptTemplateDto>> {
+    metrics::track("list_prompt_templates_v2", async {
+        let records = db.list_prompt_templates().await.map_err(IpcError::from)?;
+        records
+            .into_iter()
+            .map(|record| map_prompt_template_record(record).map_err(Into::into))
+            .collect()
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn create_prompt_template_v2(
+    db: State<'_, DbManager>,
+    payload: CreatePromptTemplatePayload,
+) -> IpcResult<PromptTemplateDto> {
+    metrics::track("create_prompt_template_v2", async {
+        let name = normalize_name(&payload.name)?;
+        ensure_unique_name(db.inner(), &name, None).await?;
+        let definition_json = serialize_definition(&payload.definition)?;
+
+        let record = db
+            .create_prompt_template(NewPromptTemplateArgs {
+                name,
+                description: payload
+                    .description
+                    .map(|description| description.trim().to_string())
+                    .filter(|description| !description.is_empty()),
+                definition_json,
+            })
+            .await
+            .map_err(IpcError::from)?;
+        Ok(map_prompt_template_record(record)?)
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn update_prompt_template_v2(
+    db: State<'_, DbManager>,
+    payload: UpdatePromptTemplatePayload,
+) -> IpcResult<PromptTemplateDto> {
+    metrics::track("update_prompt_template_v2", async {
+        let template_uuid = parse_uuid(&payload.template_uuid, "templateUuid")?;
+        let name = match payload.name {
+            Some(name) => {
+                let name = normalize_name(&name)?;
+                ensure_unique_name(db.inner(), &name, Some(template_uuid)).await?;
+                Some(name)
+            }
+            None => None,
+        };
+        let definition_json = payload
+            .definition
+            .as_ref()
+            .map(serialize_definition)
+            .transpose()?;
+
+        let record = db
+            .update_prompt_template(UpdatePromptTemplateArgs {
+                template_uuid,
+                name,
+                description: payload.description.map(|description| {
+                    Some(description.trim().to_string())
+                        .filter(|description| !description.is_empty())
+                }),
+                definition_json,
+            })
+            .await
+            .map_err(IpcError::from)?
+            .ok_or_else(|| {
+                IpcError::Validation(format!("prompt template {template_uuid} not found"))
+            })?;
+        Ok(map_prompt_template_record(record)?)
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn delete_prompt_template_v2(
+    db: State<'_, DbManager>,
+    template_uuid: String,
+) -> IpcResult<()> {
+    metrics::track("delete_prompt_template_v2", async {
+        let template_uuid = parse_uuid(&template_uuid, "templateUuid")?;
+        let deleted = db
+            .delete_prompt_template(template_uuid)
+            .await
+            .map_err(IpcError::from)?;
+        if !deleted {
+            return Err(
+                IpcError::Validation(format!("prompt template {template_uuid} not found")).into(),
+            );
+        }
+        Ok(())
+    })
+    .await
+}
+
+/// Assigns a template to a project, for one language pair or, leaving a language out, for
+/// every language on that side. The most specific assignment wins.
+#[tauri::command]
+pub async fn assign_prompt_template_v2(
+    db: State<'_, DbManager>,
+    payload: AssignPromptTemplatePayload,
+) -> IpcResult<Vec<PromptTemplateAssignmentDto>> {
+    metrics::track("assign_prompt_template_v2", async {
+        let project_uuid = parse_uuid(&payload.project_uuid, "projectUuid")?;
+        let template_uuid = match payload.template_uuid.as_deref() {
+            Some(value) => {
+                let template_uuid = parse_uuid(value, "templateUuid")?;
+                db.get_prompt_template(template_uuid)
+                    .await
+                    .map_err(IpcError::from)?
+                    .ok_or_else(|| {
+                        IpcError::Validation(format!("prompt template {template_uuid} not found"))
+                    })?;
+                Some(template_uuid)
+            }
+            None => None,
+        };
+        let source_lang = payload.source_lang.unwrap_or_default();
+        let target_lang = payload.target_lang.unwrap_or_default();
+
+        db.assign_prompt_template(
+            project_uuid,
+            source_lang.trim(),
+            target_lang.trim(),
+            template_uuid,
+        )
+        .await
+        .map_err(IpcError::from)?;
+
+        let records = db
+            .list_prompt_template_assignments(project_uuid)
+            .await
+            .map_err(IpcError::from)?;
+        Ok(records.into_iter().map(map_assignment_record).collect())
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn list_prompt_template_assignments_v2(
+    db: State<'_, DbManager>,
+    project_uuid: String,
+) -> IpcResult<Vec<PromptTemplateAssignmentDto>> {
+    metrics::track("list_prompt_template_assignments_v2", async {
+        let project_uuid = parse_uuid(&project_uuid, "projectUuid")?;
+        let records = db
+            .list_prompt_template_assignments(project_uuid)
+            .await
+            .map_err(IpcError::from)?;
+        Ok(records.into_iter().map(map_assignment_record).collect())
+    })
+    .await
+}
+
+/// Renders the messages a provider would receive, from an unsaved definition, a stored
+/// template or the template assigned to the project.
+#[tauri::command]
+pub async fn preview_prompt_v2(
+    db: State<'_, DbManager>,
+    payload: PreviewPromptPayload,
+) -> IpcResult<PromptPreviewDto> {
+    metrics::track("preview_prompt_v2", async {
+        let mut prompt = load_project_prompt(
+            db.inner(),
+            payload.project_uuid.as_deref(),
+            &payload.source_lang,
+            &payload.target_lang,
+            payload.terms,
+        )
+        .await?;
+
+        if let Some(definition) = payload.definition {
+            definition
+                .validate()
+                .map_err(|error| IpcError::Validation(error.to_string()))?;
+            prompt.template_uuid = None;
+            prompt.definition = definition;
+        } else if let Some(value) = payload.template_uuid.as_deref() {
+            let template_uuid = parse_uuid(value, "templateUuid")?;
+            let record = db
+                .get_prompt_template(template_uuid)
+                .await
+                .map_err(IpcError::from)?
+                .ok_or_else(|| {
+                    IpcError::Validation(format!("prompt template {template_uuid} not found"))
+                })?;
+            prompt.template_uuid = Some(template_uuid);
+            prompt.definition = parse_definition(&record)?;
+        }
+
+        let messages = prompt.render(
+            &payload.source_lang,
+            &payload.target_lang,
+            &payload.source,
+            payload.context.as_deref(),
+        );
+        Ok(PromptPreviewDto {
+            template_uuid: prompt.template_uuid.map(|uuid| uuid.to_string()),
+            messages: messages
+                .into_iter()
+                .map(|message| PromptMessageDto {
+                    role: message.role.to_string(),
+                    content: message.content,
+                })
+                .collect(),
+        })
+    })
+    .await
+}
+
+async fn ensure_unique_name(
+    db: &DbManager,
+    name: &str,
+    template_uuid: Option<Uuid>,
+) -> Result<(), IpcError> {
+    let taken = db
+        .list_prompt_templates()
+        .await
+        .map_err(IpcError::from)?
+        .into_iter()
+        .any(|record| {
+            Some(record.template_uuid) != template_uuid && record.name.eq_ignore_ascii_case(name)
+        });
+    if taken {
+        return Err(IpcError::Validation(format!(
+            "a prompt template named '{name}' already exists"
+        )));
+    }
+    Ok(())
+}
+
+fn normalize_name(name: &str) -> Result<String, IpcError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(IpcError::Validation(
+            "prompt template name cannot be empty".into(),
+        ));
+    }
+    Ok(name.to_string())
+}
+
+fn serialize_definition(definition: &PromptTemplateDefinition) -> Result<String, IpcError> {
+    definition
+        .validate()
+        .map_err(|error| IpcError::Validation(error.to_string()))?;
+    serde_json::to_string(definition).map_err(|error| IpcError::Internal(error.to_string()))
+}
+
+fn parse_definition(record: &PromptTemplateRecord) -> Result<PromptTemplateDefinition, IpcError> {
+    serde_json::from_str(&record.definition_json).map_err(|error| {
+        IpcError::Internal(format!(
+            "prompt template {} is unreadable: {error}",
+            record.template_uuid
+        ))
+    })
+}
+
+fn map_prompt_template_record(record: PromptTemplateRecord) -> Result<PromptTemplateDto, IpcError> {
+    Ok(PromptTemplateDto {
+        definition: parse_definition(&record)?,
+        template_uuid: record.template_uuid.to_string(),
+        name: record.name,
+        description: record.description,
+        created_at: record.created_at,
+        updated_at: record.updated_at,
+    })
+}
+
+fn map_assignment_record(record: PromptTemplateAssignmentRecord) -> PromptTemplateAssignmentDto {
+    PromptTemplateAssignmentDto {
+        project_uuid: record.project_uuid.to_string(),
+        source_lang: Some(record.source_lang).filter(|lang| !lang.is_empty()),
+        target_lang: Some(record.target_lang).filter(|lang| !lang.is_empty()),
+        template_uuid: record.template_uuid.to_string(),
+        assigned_at: record.assigned_at,
+    }
+}
+
+fn parse_uuid(value: &str, field: &str) -> Result<Uuid, IpcError> {
+    Uuid::parse_str(value)
+        .map_err(|_| IpcError::Validation(format!("invalid {field}: expected UUID, got '{value}'")))
+}
@@ -0,0 +1,173 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use tauri::State;
+use uuid::Uuid;
+
+use super::projects_v2::resolve_project_root;
+use super::shared::{
+    list_project_jliff_paths, load_project_jliff, record_project_history, update_project_jliff,
+};
+use crate::db::DbManager;
+use crate::db::types::{NewSegmentRevisionArgs, RecordSegmentRevisionsArgs};
+use crate::ipc::dto::{PropagateRepetitionsPayload, PropagationFileDto, PropagationResultDto};
+use crate::ipc::error::{IpcError, IpcResult};
+use crate::jliff::history::HistoryEvent;
+use crate::jliff::propagation::{
+    ConfirmedTranslation, PropagationScope, confirmed_translations, propagate_translations,
+};
+use crate::metrics;
+use crate::settings::SettingsManager;
+
+/// Revision origin recorded for targets copied onto repetitions.
+const PROPAGATION_ORIGIN: &str = "propagation";
+
+/// Propagates every confirmed segment of a JLIFF document to the unconfirmed repetitions in
+/// the requested scope. Scope and case sensitivity default to the app settings.
+#[tauri::command]
+pub async fn propagate_repetitions_v2(
+    db: State<'_, DbManager>,
+    settings: State<'_, SettingsManager>,
+    payload: PropagateRepetitionsPayload,
+) -> IpcResult<PropagationResultDto> {
+    metrics::track("propagate_repetitions_v2", async {
+        let project_uuid = parse_uuid(&payload.project_uuid, "projectUuid")?;
+        let current = settings.current().await;
+        let scope = match payload.scope.as_deref() {
+            Some(value) => parse_scope(value)?,
+            None => parse_scope(&current.propagation_scope)?,
+        };
+        let case_sensitive = payload
+            .case_sensitive
+            .unwrap_or(current.propagation_case_sensitive);
+
+        let (_, document) = load_project_jliff(
+            db.inner(),
+            settings.inner(),
+            project_uuid,
+            &payload.jliff_rel_path,
+        )
+        .await?;
+        let translations =
+            confirmed_translations(&document, &payload.jliff_rel_path, case_sensitive);
+
+        let files = propagate_in_scope(
+            db.inner(),
+            settings.inner(),
+            project_uuid,
+            &payload.jliff_rel_path,
+            translations,
+            scope,
+            case_sensitive,
+        )
+        .await?;
+        if !files.is_empty() {
+            let propagated: usize = files.iter().map(|file| file.propagated).sum();
+            record_project_history(
+                db.inner(),
+                settings.inner(),
+                project_uuid,
+                HistoryEvent {
+                    kind: "propagation",
+                    summary: format!(
+                        "Propagate {} to {propagated} repetition(s)",
+                        payload.jliff_rel_path
+                    ),
+                    files: files
+                        .iter()
+                        .map(|file| file.jliff_rel_path.clone())
+                        .collect(),
+                },
+            )
+            .await;
+        }
+
+        Ok(PropagationResultDto {
+            scope: scope.as_str().to_string(),
+            propagated: files.iter().map(|file| file.propagated).sum(),
+            files,
+        })
+    })
+    .await
+}
+
+/// Applies `translations` to every document in `scope` around `origin_rel_path` and records
+/// the replaced targets as revisions. Only documents that received targets are returned.
+pub(super) async fn propagate_in_scope(
+    db: &DbManager,
+    settings: &SettingsManager,
+    project_uuid: Uuid,
+    origin_rel_path: &str,
+    translations: HashMap<String, ConfirmedTranslation>,
+    scope: PropagationScope,
+    case_sensitive: bool,
+) -> Result<Vec<PropagationFileDto>, IpcError> {
+    if translations.is_empty() {
+        return Ok(Vec::new());
+    }
+    let targets = match scope {
+        PropagationScope::File => vec![origin_rel_path.to_string()],
+        PropagationScope::Project => {
+            let project_root = resolve_project_root(db, settings, project_uuid).await?;
+            list_project_jliff_paths(&project_root).await?
+        }
+    };
+
+    let translations = Arc::new(translations);
+    let mut files = Vec::new();
+    for jliff_rel_path in targets {
+        let translations = Arc::clone(&translations);
+        let propagated = update_project_jliff(
+            db,
+            settings,
+            project_uuid,
+            &jliff_rel_path,
+            move |document| {
+                Ok(propagate_translations(
+                    document,
+                    &translations,
+                    case_sensitive,
+                ))
+            },
+        )
+        .await?;
+        if propagated.is_empty() {
+            continue;
+        }
+
+        files.push(PropagationFileDto {
+            jliff_rel_path: jliff_rel_path.clone(),
+            propagated: propagated.len(),
+        });
+        db.record_segment_revisions(RecordSegmentRevisionsArgs {
+            project_uuid,
+            jliff_rel_path,
+            origin: PROPAGATION_ORIGIN.to_string(),
+            author: None,
+            revisions: propagated
+                .into_iter()
+                .map(|target| NewSegmentRevisionArgs {
+                    transunit_id: target.transunit_id,
+                    previous_target: target.previous_target,
+                    new_target: target.new_target,
+                })
+                .collect(),
+        })
+        .await
+        .map_err(IpcError::from)?;
+    }
+    Ok(files)
+}
+
+pub(super) fn parse_scope(value: &str) -> Result<PropagationScope, IpcError> {
+    PropagationScope::parse(value).ok_or_else(|| {
+        IpcError::Validation(format!(
+            "Unknown propagation scope '{value}'. Use 'file' or 'project'."
+        ))
+    })
+}
+
+fn parse_uuid(value: &str, field: &str) -> Result<Uuid, IpcError> {
+    Uuid::parse_str(value)
+        .map_err(|_| IpcError::Validation(format!("invalid {field}: expected UUID, got '{value}'")))
+}
@@ -0,0 +1,170 @@
+use std::collections::HashSet;
+
+use serde_json::json;
+use tauri::State;
+use uuid::Uuid;
+
+use super::qa_v2::{map_qa_finding_record, project_qa_profile};
+use super::shared::{load_cached_tag_map, load_project_jliff};
+use crate::db::DbManager;
+use crate::db::types::{
+    NewProtectedTermArgs, NewQaFindingArgs, ProtectedTermRecord, ReplaceQaFindingsArgs,
+};
+use crate::ipc::dto::{
+    CheckProtectedTermsPayload, ProtectedTermDto, ProtectedTermsQaDto, ReplaceProtectedTermsPayload,
+};
+use crate::ipc::error::{IpcError, IpcResult};
+use crate::jliff::protected_terms::{ProtectedTerm, check_protected_terms};
+use crate::metrics;
+use crate::settings::SettingsManager;
+
+/// QA check identifier used when persisting protected-term findings.
+const PROTECTED_TERM_CHECK: &str = "protected_term";
+
+#[tauri::command]
+pub async fn list_protected_terms_v2(
+    db: State<'_, DbManager>,
+    project_uuid: String,
+) -> IpcResult<Vec<ProtectedTermDto>> {
+    metrics::track("list_protected_terms_v2", async {
+        let project_uuid = parse_uuid(&project_uuid, "projectUuid")?;
+        let records = db
+            .list_protected_terms(project_uuid)
+            .await
+            .map_err(IpcError::from)?;
+        Ok(records.into_iter().map(map_protected_term_record).collect())
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn replace_protected_terms_v2(
+    db: State<'_, DbManager>,
+    payload: ReplaceProtectedTermsPayload,
+) -> IpcResult<Vec<ProtectedTermDto>> {
+    metrics::track("replace_protected_terms_v2", async {
+        let project_uuid = parse_uuid(&payload.project_uuid, "projectUuid")?;
+
+        let mut seen = HashSet::new();
+        let mut terms = Vec::with_capacity(payload.terms.len());
+        for input in payload.terms {
+            let term = input.term.trim().to_string();
+            if term.is_empty() {
+                return Err(IpcError::Validation("protected terms cannot be empty".into()).into());
+            }
+            if !seen.insert(term.clone()) {
+                continue;
+            }
+            terms.push(NewProtectedTermArgs {
+                term,
+                case_sensitive: input.case_sensitive,
+                note: input.note.filter(|note| !note.trim().is_empty()),
+            });
+        }
+
+        db.replace_protected_terms(project_uuid, &terms)
+            .await
+            .map_err(IpcError::from)?;
+
+        // Re-read so the response follows the canonical (longest-first) ordering.
+        let records = db
+            .list_protected_terms(project_uuid)
+            .await
+            .map_err(IpcError::from)?;
+        Ok(records.into_iter().map(map_protected_term_record).collect())
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn check_protected_terms_v2(
+    db: State<'_, DbManager>,
+    settings: State<'_, SettingsManager>,
+    payload: CheckProtectedTermsPayload,
+) -> IpcResult<ProtectedTermsQaDto> {
+    metrics::track("check_protected_terms_v2", async {
+        let project_uuid = parse_uuid(&payload.project_uuid, "projectUuid")?;
+        let (jliff_path, document) = load_project_jliff(
+            db.inner(),
+            settings.inner(),
+            project_uuid,
+            &payload.jliff_rel_path,
+        )
+        .await?;
+
+        let terms: Vec<ProtectedTerm> = db
+            .list_protected_terms(project_uuid)
+            .await
+            .map_err(IpcError::from)?
+            .into_iter()
+            .map(|record| ProtectedTerm::new(record.term, record.case_sensitive))
+            .collect();
+
+        let check = project_qa_profile(db.inner(), project_uuid)
+            .await?
+            .check(PROTECTED_TERM_CHECK, "critical");
+
+        // Without a tag map placeholders are reported by their token.
+        let tag_map = load_cached_tag_map(&jliff_path, &payload.jliff_rel_path)
+            .await
+            .ok();
+
+        let mut findings = Vec::new();
+        let mut segments_checked = 0;
+        for unit in document.transunits.iter().filter(|_| check.enabled) {
+            let target = unit.effective_target();
+            if target.trim().is_empty() {
+                continue;
+            }
+            segments_checked += 1;
+            let segment = tag_map
+                .as_ref()
+                .and_then(|tag_map| tag_map.segment_for(&unit.unit_id, &unit.transunit_id));
+            for issue in check_protected_terms(&unit.source, target, &terms, segment) {
+                findings.push(NewQaFindingArgs {
+                    transunit_id: unit.transunit_id.clone(),
+                    severity: check.severity.clone(),
+                    message: format!(
+                        "Protected term '{}' was altered or removed in the target ({} of {} kept).",
+                        issue.term, issue.found, issue.expected
+                    ),
+                    details: Some(json!(issue).to_string()),
+                });
+            }
+        }
+
+        let records = db
+            .replace_qa_findings(ReplaceQaFindingsArgs {
+                project_uuid,
+                jliff_rel_path: payload.jliff_rel_path.clone(),
+                check_type: PROTECTED_TERM_CHECK.into(),
+                transunit_ids: None,
+                findings,
+            })
+            .await
+            .map_err(IpcError::from)?;
+
+        Ok(ProtectedTermsQaDto {
+            jliff_rel_path: payload.jliff_rel_path,
+            segments_checked,
+            findings: records.into_iter().map(map_qa_finding_record).collect(),
+        })
+    })
+    .await
+}
+
+fn map_protected_term_record(record: ProtectedTermRecord) -> ProtectedTermDto {
+    ProtectedTermDto {
+        term_uuid: record.term_uuid.to_string(),
+        project_uuid: record.project_uuid.to_string(),
+        term: record.term,
+        case_sensitive: record.case_sensitive,
+        note: record.note,
+        created_at: record.created_at,
+    }
+}
+
+fn parse_uuid(value: &str, field: &str) -> Result<Uuid, IpcError> {
+    Uuid::parse_str(value)
+        .map_err(|_| IpcError::Validation(format!("invalid {field}: expected UUID, got '{value}'")))
+}
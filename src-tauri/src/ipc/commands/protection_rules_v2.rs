@@ -0,0 +1,223 @@
+use tauri::State;
+use uuid::Uuid;
+
+use crate::db::DbManager;
+use crate::db::types::{NewProtectionRuleArgs, ProtectionRuleRecord, UpdateProtectionRuleArgs};
+use crate::ipc::dto::{
+    CreateProtectionRulePayload, PreviewProtectionRulesPayload, ProtectionMatchDto,
+    ProtectionPreviewDto, ProtectionRuleDto, UpdateProtectionRulePayload,
+};
+use crate::ipc::error::{IpcError, IpcResult};
+use crate::jliff::protected_terms::{
+    ProtectedSource, ProtectedTerm, ProtectionRule, find_protected_spans,
+};
+use crate::metrics;
+
+#[tauri::command]
+pub async fn list_protection_rules_v2(
+    db: State<'_, DbManager>,
+    project_uuid: String,
+) -> IpcResult<Vec<ProtectionRuleDto>> {
+    metrics::track("list_protection_rules_v2", async {
+        let project_uuid = parse_uuid(&project_uuid, "projectUuid")?;
+        let records = db
+            .list_protection_rules(project_uuid)
+            .await
+            .map_err(IpcError::from)?;
+        Ok(records
+            .into_iter()
+            .map(map_protection_rule_record)
+            .collect())
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn create_protection_rule_v2(
+    db: State<'_, DbManager>,
+    payload: CreateProtectionRulePayload,
+) -> IpcResult<ProtectionRuleDto> {
+    metrics::track("create_protection_rule_v2", async {
+        let project_uuid = parse_uuid(&payload.project_uuid, "projectUuid")?;
+        let name = normalize_rule_name(&payload.name)?;
+        validate_pattern(&name, &payload.pattern)?;
+
+        let record = db
+            .create_protection_rule(NewProtectionRuleArgs {
+                rule_uuid: Uuid::new_v4(),
+                project_uuid,
+                name,
+                pattern: payload.pattern,
+                is_enabled: payload.is_enabled,
+            })
+            .await
+            .map_err(IpcError::from)?;
+        Ok(map_protection_rule_record(record))
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn update_protection_rule_v2(
+    db: State<'_, DbManager>,
+    payload: UpdateProtectionRulePayload,
+) -> IpcResult<Option<ProtectionRuleDto>> {
+    metrics::track("update_protection_rule_v2", async {
+        let rule_uuid = parse_uuid(&payload.rule_uuid, "ruleUuid")?;
+        let name = payload
+            .name
+            .as_deref()
+            .map(normalize_rule_name)
+            .transpose()?;
+        if let Some(pattern) = payload.pattern.as_deref() {
+            validate_pattern(name.as_deref().unwrap_or("rule"), pattern)?;
+        }
+
+        let record = db
+            .update_protection_rule(UpdateProtectionRuleArgs {
+                rule_uuid,
+                name,
+                pattern: payload.pattern,
+                is_enabled: payload.is_enabled,
+            })
+            .await
+            .map_err(IpcError::from)?;
+        Ok(record.map(map_protection_rule_record))
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn delete_protection_rule_v2(
+    db: State<'_, DbManager>,
+    rule_uuid: String,
+) -> IpcResult<()> {
+    metrics::track("delete_protection_rule_v2", async {
+        let rule_uuid = parse_uuid(&rule_uuid, "ruleUuid")?;
+        db.delete_protection_rule(rule_uuid)
+            .await
+            .map_err(IpcError::from)?;
+        Ok(())
+    })
+    .await
+}
+
+/// Previews how protection would apply to `sampleText`. Explicit `rules` take precedence over
+/// the project's enabled rules; the project's protected terms are included when a project is
+/// given.
+#[tauri::command]
+pub async fn preview_protection_rules_v2(
+    db: State<'_, DbManager>,
+    payload: PreviewProtectionRulesPayload,
+) -> IpcResult<ProtectionPreviewDto> {
+    metrics::track("preview_protection_rules_v2", async {
+        let project_uuid = payload
+            .project_uuid
+            .as_deref()
+            .map(|value| parse_uuid(value, "projectUuid"))
+            .transpose()?;
+
+        let mut terms = Vec::new();
+        let mut rules = Vec::new();
+        if let Some(project_uuid) = project_uuid {
+            terms = db
+                .list_protected_terms(project_uuid)
+                .await
+                .map_err(IpcError::from)?
+                .into_iter()
+                .map(|record| ProtectedTerm::new(record.term, record.case_sensitive))
+                .collect();
+            if payload.rules.is_none() {
+                for record in db
+                    .list_protection_rules(project_uuid)
+                    .await
+                    .map_err(IpcError::from)?
+                    .into_iter()
+                    .filter(|record| record.is_enabled)
+                {
+                    rules.push(compile_rule(&record.name, &record.pattern)?);
+                }
+            }
+        }
+        for input in payload.rules.unwrap_or_default() {
+            rules.push(compile_rule(&input.name, &input.pattern)?);
+        }
+
+        let text = payload.sample_text;
+        let spans = find_protected_spans(&text, &terms, &rules);
+
+        let mut protected_text = String::with_capacity(text.len());
+        let mut matches = Vec::with_capacity(spans.len());
+        let mut last = 0;
+        for span in spans {
+            let placeholder = span.placeholder();
+            protected_text.push_str(&text[last..span.start]);
+            protected_text.push_str(&placeholder);
+            last = span.end;
+
+            let (kind, name) = match span.source {
+                ProtectedSource::Term(index) => ("term", terms[index].term.clone()),
+                ProtectedSource::Rule(index) => ("rule", rules[index].name.clone()),
+            };
+            matches.push(ProtectionMatchDto {
+                kind: kind.into(),
+                name,
+                placeholder,
+                text: text[span.start..span.end].to_string(),
+                start: text[..span.start].chars().count(),
+                end: text[..span.end].chars().count(),
+            });
+        }
+        protected_text.push_str(&text[last..]);
+
+        Ok(ProtectionPreviewDto {
+            protected_text,
+            matches,
+        })
+    })
+    .await
+}
+
+fn normalize_rule_name(name: &str) -> Result<String, IpcError> {
+    let trimmed = name.trim();
+    if trimmed.is_empty() {
+        return Err(IpcError::Validation(
+            "protection rule name cannot be empty".into(),
+        ));
+    }
+    Ok(trimmed.to_string())
+}
+
+fn validate_pattern(name: &str, pattern: &str) -> Result<(), IpcError> {
+    compile_rule(name, pattern).map(|_| ())
+}
+
+fn compile_rule(name: &str, pattern: &str) -> Result<ProtectionRule, IpcError> {
+    if pattern.is_empty() {
+        return Err(IpcError::Validation(format!(
+            "protection rule '{name}' has an empty pattern"
+        )));
+    }
+    ProtectionRule::compile(name, pattern).map_err(|error| {
+        IpcError::Validation(format!(
+            "protection rule '{name}' has an invalid pattern: {error}"
+        ))
+    })
+}
+
+fn map_protection_rule_record(record: ProtectionRuleRecord) -> ProtectionRuleDto {
+    ProtectionRuleDto {
+        rule_uuid: record.rule_uuid.to_string(),
+        project_uuid: record.project_uuid.to_string(),
+        name: record.name,
+        pattern: record.pattern,
+        is_enabled: record.is_enabled,
+        created_at: record.created_at,
+        updated_at: record.updated_at,
+    }
+}
+
+fn parse_uuid(value: &str, field: &str) -> Result<Uuid, IpcError> {
+    Uuid::parse_str(value)
+        .map_err(|_| IpcError::Validation(format!("invalid {field}: expected UUID, got '{value}'")))
+}
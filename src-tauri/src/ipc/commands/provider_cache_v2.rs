@@ -0,0 +1,159 @@
+use sha2::{Digest, Sha256};
+use tauri::State;
+
+use crate::db::DbManager;
+use crate::db::types::{NewProviderCacheEntryArgs, ProviderCacheKey};
+use crate::ipc::dto::{
+    ProviderCacheLookupDto, ProviderCacheModelStatsDto, ProviderCachePayload,
+    ProviderCacheStatsDto, PurgeProviderCachePayload,
+};
+use crate::ipc::error::{IpcError, IpcResult};
+use crate::metrics;
+use crate::settings::SettingsManager;
+
+/// Returns the cached provider translation of each segment, counting hits. Every segment
+/// misses while the cache is disabled.
+#[tauri::command]
+pub async fn lookup_provider_cache_v2(
+    db: State<'_, DbManager>,
+    settings: State<'_, SettingsManager>,
+    payload: ProviderCachePayload,
+) -> IpcResult<ProviderCacheLookupDto> {
+    metrics::track("lookup_provider_cache_v2", async {
+        let keys = cache_keys(&payload)?;
+        let targets = if settings.current().await.provider_cache_ttl_hours == 0 {
+            vec![None; keys.len()]
+        } else {
+            db.lookup_provider_cache(&keys)
+                .await
+                .map_err(IpcError::from)?
+        };
+        let hits = targets.iter().filter(|target| target.is_some()).count();
+        Ok(ProviderCacheLookupDto {
+            misses: targets.len() - hits,
+            hits,
+            targets,
+        })
+    })
+    .await
+}
+
+/// Caches the provider output of each segment for the configured TTL. Returns the number of
+/// stored entries.
+#[tauri::command]
+pub async fn store_provider_cache_v2(
+    db: State<'_, DbManager>,
+    settings: State<'_, SettingsManager>,
+    payload: ProviderCachePayload,
+) -> IpcResult<u64> {
+    metrics::track("store_provider_cache_v2", async {
+        let ttl_hours = settings.current().await.provider_cache_ttl_hours;
+        if ttl_hours == 0 {
+            return Ok(0);
+        }
+        let entries = cache_keys(&payload)?
+            .into_iter()
+            .zip(&payload.segments)
+            .filter_map(|(key, segment)| {
+                let target_text = segment.target.clone()?;
+                Some(NewProviderCacheEntryArgs { key, target_text })
+            })
+            .collect();
+        db.store_provider_cache(entries, ttl_hours)
+            .await
+            .map_err(|error| IpcError::from(error).into())
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn get_provider_cache_stats_v2(
+    db: State<'_, DbManager>,
+    settings: State<'_, SettingsManager>,
+) -> IpcResult<ProviderCacheStatsDto> {
+    metrics::track("get_provider_cache_stats_v2", async {
+        let models: Vec<ProviderCacheModelStatsDto> = db
+            .provider_cache_stats()
+            .await
+            .map_err(IpcError::from)?
+            .into_iter()
+            .map(|record| ProviderCacheModelStatsDto {
+                provider: record.provider,
+                model: record.model,
+                entries: record.entries,
+                expired_entries: record.expired_entries,
+                hits: record.hits,
+            })
+            .collect();
+        Ok(ProviderCacheStatsDto {
+            ttl_hours: settings.current().await.provider_cache_ttl_hours,
+            entries: models.iter().map(|model| model.entries).sum(),
+            expired_entries: models.iter().map(|model| model.expired_entries).sum(),
+            hits: models.iter().map(|model| model.hits).sum(),
+            models,
+        })
+    })
+    .await
+}
+
+/// Removes cached translations, optionally only those of one provider and/or expired ones.
+/// Returns the number of removed entries.
+#[tauri::command]
+pub async fn purge_provider_cache_v2(
+    db: State<'_, DbManager>,
+    payload: Option<PurgeProviderCachePayload>,
+) -> IpcResult<u64> {
+    metrics::track("purge_provider_cache_v2", async {
+        let payload = payload.unwrap_or_default();
+        let provider = payload
+            .provider
+            .as_deref()
+            .map(normalize_identifier)
+            .filter(|provider| !provider.is_empty());
+        db.purge_provider_cache(provider.as_deref(), payload.expired_only)
+            .await
+            .map_err(|error| IpcError::from(error).into())
+    })
+    .await
+}
+
+fn cache_keys(payload: &ProviderCachePayload) -> Result<Vec<ProviderCacheKey>, IpcError> {
+    let provider = normalize_identifier(&payload.provider);
+    let model = payload.model.trim().to_string();
+    let source_lang = normalize_identifier(&payload.source_lang);
+    let target_lang = normalize_identifier(&payload.target_lang);
+    for (value, field) in [
+        (&provider, "provider"),
+        (&model, "model"),
+        (&source_lang, "sourceLang"),
+        (&target_lang, "targetLang"),
+    ] {
+        if value.is_empty() {
+            return Err(IpcError::Validation(format!("{field} must not be empty.")));
+        }
+    }
+
+    Ok(payload
+        .segments
+        .iter()
+        .map(|segment| ProviderCacheKey {
+            provider: provider.clone(),
+            model: model.clone(),
+            source_lang: source_lang.clone(),
+            target_lang: target_lang.clone(),
+            source_hash: sha256_hex(&segment.source),
+            context_hash: sha256_hex(segment.context.as_deref().unwrap_or_default()),
+        })
+        .collect())
+}
+
+fn normalize_identifier(value: &str) -> String {
+    value.trim().to_ascii_lowercase()
+}
+
+fn sha256_hex(text: &str) -> String {
+    Sha256::digest(text.as_bytes())
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
@@ -0,0 +1,366 @@
+use std::collections::{HashMap, HashSet};
+
+use tauri::State;
+use tauri::ipc::InvokeError;
+use uuid::Uuid;
+
+use super::prompt_templates_v2::load_project_prompt;
+use super::provider_routing_v2::{
+    BACKEND_PROVIDERS, ProviderBackends, ProviderRequest, audit_dispatch, backend_provider,
+    dispatch_translation,
+};
+use super::provider_usage_v2::{current_month, normalize_provider, provider_budget_exceeded};
+use super::shared::load_project_jliff;
+use crate::db::DbManager;
+use crate::db::types::{
+    NewProviderCandidateArgs, PreferredProviderRecord, ProviderCandidateRecord,
+    RecordProviderUsageArgs,
+};
+use crate::ipc::dto::{
+    CompareProvidersPayload, PreferredProviderDto, ProviderCandidateDto, ProviderComparisonDto,
+    ProviderComparisonSegmentDto, ProviderComparisonSummaryDto, SetPreferredProviderPayload,
+};
+use crate::ipc::error::{IpcError, IpcResult};
+use crate::ipc::state::OfflineMode;
+use crate::jliff::quality_estimate::estimate_quality;
+use crate::metrics;
+use crate::providers::{LlmClient, LocalModelClient, ProviderRateLimiter};
+use crate::settings::SettingsManager;
+
+/// Upper bound on segments per comparison, since every segment is billed twice.
+const MAX_COMPARED_SEGMENTS: usize = 50;
+
+/// Translates the same segments with two providers and returns the candidates side by side
+/// with quality estimates. Candidates are stored for later review; the document is not
+/// changed.
+#[tauri::command]
+pub async fn compare_providers_v2(
+    db: State<'_, DbManager>,
+    settings: State<'_, SettingsManager>,
+    llm: State<'_, LlmClient>,
+    local: State<'_, LocalModelClient>,
+    limiter: State<'_, ProviderRateLimiter>,
+    offline: State<'_, OfflineMode>,
+    payload: CompareProvidersPayload,
+) -> IpcResult<ProviderComparisonDto> {
+    metrics::track("compare_providers_v2", async {
+        let project_uuid = parse_uuid(&payload.project_uuid, "projectUuid")?;
+        let mut providers = Vec::new();
+        for provider in &payload.providers {
+            let provider = normalize_provider(provider)?;
+            if !BACKEND_PROVIDERS.contains(&provider.as_str()) {
+                return Err(IpcError::Validation(format!(
+                    "provider '{provider}' cannot be compared. Available providers: {}.",
+                    BACKEND_PROVIDERS.join(", ")
+                ))
+                .into());
+            }
+            if !providers.contains(&provider) {
+                providers.push(provider);
+            }
+        }
+        if providers.len() != 2 {
+            return Err(
+                IpcError::Validation("choose two different providers to compare.".into()).into(),
+            );
+        }
+        if payload.transunit_ids.is_empty() || payload.transunit_ids.len() > MAX_COMPARED_SEGMENTS {
+            return Err(IpcError::Validation(format!(
+                "choose between 1 and {MAX_COMPARED_SEGMENTS} segments to compare."
+            ))
+            .into());
+        }
+
+        let (_, document) = load_project_jliff(
+            db.inner(),
+            settings.inner(),
+            project_uuid,
+            &payload.jliff_rel_path,
+        )
+        .await?;
+        let units: HashMap<&str, _> = document
+            .transunits
+            .iter()
+            .map(|unit| (unit.transunit_id.as_str(), unit))
+            .collect();
+        let mut seen = HashSet::new();
+        let mut selected = Vec::new();
+        for transunit_id in &payload.transunit_ids {
+            let unit = units.get(transunit_id.as_str()).ok_or_else(|| {
+                IpcError::Validation(format!("segment '{transunit_id}' not found"))
+            })?;
+            if seen.insert(transunit_id.as_str()) && !unit.source.trim().is_empty() {
+                selected.push(*unit);
+            }
+        }
+
+        for provider in &providers {
+            if let Some(exceeded) = provider_budget_exceeded(db.inner(), provider).await? {
+                return Err(InvokeError::from(exceeded));
+            }
+        }
+
+        let source_lang = document.source_language.clone();
+        let target_lang = document.target_language.clone();
+        let prompt = load_project_prompt(
+            db.inner(),
+            Some(&payload.project_uuid),
+            &source_lang,
+            &target_lang,
+            Vec::new(),
+        )
+        .await?;
+        let current = settings.current().await;
+        let backends = ProviderBackends {
+            db: db.inner(),
+            llm: &llm,
+            local: &local,
+            limiter: &limiter,
+            offline: &offline,
+        };
+        let selected_characters: i64 = selected
+            .iter()
+            .map(|unit| unit.source.chars().count() as i64)
+            .sum();
+        for provider in &providers {
+            audit_dispatch(
+                backend_provider(provider, &current),
+                &backends,
+                &current,
+                project_uuid,
+                selected.len() as i64,
+                selected_characters,
+            )
+            .await?;
+        }
+
+        let mut candidates = Vec::with_capacity(selected.len() * providers.len());
+        let mut characters: HashMap<&str, i64> = HashMap::new();
+        for unit in &selected {
+            let messages = prompt.render(&source_lang, &target_lang, &unit.source, None);
+            let request = ProviderRequest {
+                project_uuid,
+                target_lang: &target_lang,
+                source: &unit.source,
+                messages: &messages,
+            };
+            for provider in &providers {
+                let backend = backend_provider(provider, &current);
+                let target = dispatch_translation(backend, &backends, &current, &request).await?;
+                *characters.entry(backend).or_default() += unit.source.chars().count() as i64;
+                candidates.push(NewProviderCandidateArgs {
+                    transunit_id: unit.transunit_id.clone(),
+                    provider: provider.clone(),
+                    source_text: unit.source.clone(),
+                    qe_score: estimate_quality(&unit.source, &target),
+                    target_text: target,
+                });
+            }
+        }
+
+        db.store_provider_candidates(project_uuid, &payload.jliff_rel_path, &candidates)
+            .await
+            .map_err(IpcError::from)?;
+        let usage_month = current_month();
+        for (provider, characters) in characters {
+            db.record_provider_usage(RecordProviderUsageArgs {
+                provider: provider.to_string(),
+                project_uuid,
+                usage_month: usage_month.clone(),
+                characters,
+                // Same rough estimate as the rate limiter: ~4 characters a token, both ways.
+                tokens: characters / 2,
+            })
+            .await
+            .map_err(IpcError::from)?;
+        }
+
+        let preferred_provider = db
+            .list_preferred_providers(project_uuid)
+            .await
+            .map_err(IpcError::from)?
+            .into_iter()
+            .find(|record| record.source_lang == source_lang && record.target_lang == target_lang)
+            .map(|record| record.provider);
+
+        // Stored candidates carry the timestamps; keep the requested segment order.
+        let mut stored: HashMap<(String, String), ProviderCandidateRecord> = db
+            .list_provider_candidates(project_uuid, &payload.jliff_rel_path)
+            .await
+            .map_err(IpcError::from)?
+            .into_iter()
+            .map(|record| {
+                (
+                    (record.transunit_id.clone(), record.provider.clone()),
+                    record,
+                )
+            })
+            .collect();
+        let segments: Vec<ProviderComparisonSegmentDto> = selected
+            .iter()
+            .map(|unit| ProviderComparisonSegmentDto {
+                transunit_id: unit.transunit_id.clone(),
+                source: unit.source.clone(),
+                current_target: unit.effective_target().to_string(),
+                candidates: providers
+                    .iter()
+                    .filter_map(|provider| {
+                        stored.remove(&(unit.transunit_id.clone(), provider.clone()))
+                    })
+                    .map(map_candidate_record)
+                    .collect(),
+            })
+            .collect();
+
+        Ok(ProviderComparisonDto {
+            jliff_rel_path: payload.jliff_rel_path,
+            source_lang,
+            target_lang,
+            providers: summarize(&providers, &segments),
+            preferred_provider,
+            segments,
+        })
+    })
+    .await
+}
+
+/// Returns the candidates stored by earlier comparisons of a document.
+#[tauri::command]
+pub async fn list_provider_candidates_v2(
+    db: State<'_, DbManager>,
+    project_uuid: String,
+    jliff_rel_path: String,
+) -> IpcResult<Vec<ProviderCandidateDto>> {
+    metrics::track("list_provider_candidates_v2", async {
+        let project_uuid = parse_uuid(&project_uuid, "projectUuid")?;
+        let records = db
+            .list_provider_candidates(project_uuid, &jliff_rel_path)
+            .await
+            .map_err(IpcError::from)?;
+        Ok(records.into_iter().map(map_candidate_record).collect())
+    })
+    .await
+}
+
+/// Sets the provider used by default for a language pair of the project.
+#[tauri::command]
+pub async fn set_preferred_provider_v2(
+    db: State<'_, DbManager>,
+    payload: SetPreferredProviderPayload,
+) -> IpcResult<Vec<PreferredProviderDto>> {
+    metrics::track("set_preferred_provider_v2", async {
+        let project_uuid = parse_uuid(&payload.project_uuid, "projectUuid")?;
+        let source_lang = payload.source_lang.trim();
+        let target_lang = payload.target_lang.trim();
+        if source_lang.is_empty() || target_lang.is_empty() {
+            return Err(IpcError::Validation(
+                "sourceLang and targetLang must not be empty.".into(),
+            )
+            .into());
+        }
+        let provider = payload
+            .provider
+            .as_deref()
+            .map(normalize_provider)
+            .transpose()?;
+
+        db.set_preferred_provider(project_uuid, source_lang, target_lang, provider.as_deref())
+            .await
+            .map_err(IpcError::from)?;
+        list_preferred(db.inner(), project_uuid)
+            .await
+            .map_err(Into::into)
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn list_preferred_providers_v2(
+    db: State<'_, DbManager>,
+    project_uuid: String,
+) -> IpcResult<Vec<PreferredProviderDto>> {
+    metrics::track("list_preferred_providers_v2", async {
+        let project_uuid = parse_uuid(&project_uuid, "projectUuid")?;
+        list_preferred(db.inner(), project_uuid)
+            .await
+            .map_err(Into::into)
+    })
+    .await
+}
+
+fn summarize(
+    providers: &[String],
+    segments: &[ProviderComparisonSegmentDto],
+) -> Vec<ProviderComparisonSummaryDto> {
+    providers
+        .iter()
+        .map(|provider| {
+            let mut total = 0.0;
+            let mut wins = 0;
+            for segment in segments {
+                let Some(own) = segment
+                    .candidates
+                    .iter()
+                    .find(|candidate| &candidate.provider == provider)
+                else {
+                    continue;
+                };
+                total += own.qe_score;
+                if segment
+                    .candidates
+                    .iter()
+                    .filter(|candidate| &candidate.provider != provider)
+                    .all(|other| own.qe_score > other.qe_score)
+                {
+                    wins += 1;
+                }
+            }
+            let average = if segments.is_empty() {
+                0.0
+            } else {
+                total / segments.len() as f64
+            };
+            ProviderComparisonSummaryDto {
+                provider: provider.clone(),
+                average_qe_score: (average * 100.0).round() / 100.0,
+                wins,
+            }
+        })
+        .collect()
+}
+
+async fn list_preferred(
+    db: &DbManager,
+    project_uuid: Uuid,
+) -> Result<Vec<PreferredProviderDto>, IpcError> {
+    let records = db
+        .list_preferred_providers(project_uuid)
+        .await
+        .map_err(IpcError::from)?;
+    Ok(records.into_iter().map(map_preferred_record).collect())
+}
+
+fn map_candidate_record(record: ProviderCandidateRecord) -> ProviderCandidateDto {
+    ProviderCandidateDto {
+        transunit_id: record.transunit_id,
+        provider: record.provider,
+        source: record.source_text,
+        target: record.target_text,
+        qe_score: record.qe_score,
+        created_at: record.created_at,
+    }
+}
+
+fn map_preferred_record(record: PreferredProviderRecord) -> PreferredProviderDto {
+    PreferredProviderDto {
+        source_lang: record.source_lang,
+        target_lang: record.target_lang,
+        provider: record.provider,
+        updated_at: record.updated_at,
+    }
+}
+
+fn parse_uuid(value: &str, field: &str) -> Result<Uuid, IpcError> {
+    Uuid::parse_str(value)
+        .map_err(|_| IpcError::Validation(format!("invalid {field}: expected UUID, got '{value}'")))
+}
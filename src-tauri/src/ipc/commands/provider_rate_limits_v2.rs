@@ -0,0 +1,170 @@
+use std::time::Duration;
+
+use tauri::{AppHandle, Emitter, Runtime, State};
+
+use super::provider_usage_v2::normalize_provider;
+use crate::db::DbManager;
+use crate::db::types::ProviderRateLimitRecord;
+use crate::ipc::dto::{
+    AcquireProviderPermitPayload, ProviderPermitDto, ProviderRateLimitDto,
+    ProviderRateLimitEventPayload, SetProviderRateLimitPayload,
+};
+use crate::ipc::error::{IpcError, IpcResult};
+use crate::ipc::events::PROVIDER_RATE_LIMIT;
+use crate::metrics;
+use crate::providers::{ProviderRateLimiter, RateLimit};
+
+/// Back-off applied when a throttled response carries no `Retry-After`.
+const DEFAULT_RETRY_AFTER_SECONDS: u64 = 30;
+
+/// Waits for the provider's rate limit before a request is sent. While the request is held
+/// back, `provider:rate-limit` events report the expected wait and the queue length.
+#[tauri::command]
+pub async fn acquire_provider_permit_v2(
+    app: AppHandle,
+    limiter: State<'_, ProviderRateLimiter>,
+    payload: AcquireProviderPermitPayload,
+) -> IpcResult<ProviderPermitDto> {
+    metrics::track("acquire_provider_permit_v2", async {
+        let provider = normalize_provider(&payload.provider)?;
+        let mut waited = false;
+        let elapsed = limiter
+            .acquire(&provider, payload.tokens, |wait| {
+                waited = true;
+                emit_rate_limit_event(
+                    &app,
+                    ProviderRateLimitEventPayload {
+                        provider: provider.clone(),
+                        request_id: payload.request_id.clone(),
+                        state: "waiting".into(),
+                        wait_ms: wait.wait.as_millis() as u64,
+                        queued: wait.queued,
+                    },
+                );
+            })
+            .await;
+        if waited {
+            emit_rate_limit_event(
+                &app,
+                ProviderRateLimitEventPayload {
+                    provider: provider.clone(),
+                    request_id: payload.request_id,
+                    state: "ready".into(),
+                    wait_ms: 0,
+                    queued: 0,
+                },
+            );
+        }
+        Ok(ProviderPermitDto {
+            provider,
+            waited_ms: elapsed.as_millis() as u64,
+        })
+    })
+    .await
+}
+
+/// Holds back all queued requests to a provider after it answered with HTTP 429.
+#[tauri::command]
+pub async fn report_provider_throttled_v2(
+    limiter: State<'_, ProviderRateLimiter>,
+    provider: String,
+    retry_after_seconds: Option<u64>,
+) -> IpcResult<()> {
+    metrics::track("report_provider_throttled_v2", async {
+        let provider = normalize_provider(&provider)?;
+        limiter.pause(
+            &provider,
+            Duration::from_secs(retry_after_seconds.unwrap_or(DEFAULT_RETRY_AFTER_SECONDS)),
+        );
+        Ok(())
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn list_provider_rate_limits_v2(
+    db: State<'_, DbManager>,
+) -> IpcResult<Vec<ProviderRateLimitDto>> {
+    metrics::track("list_provider_rate_limits_v2", async {
+        let records = db
+            .list_provider_rate_limits()
+            .await
+            .map_err(IpcError::from)?;
+        Ok(records.into_iter().map(map_rate_limit_record).collect())
+    })
+    .await
+}
+
+/// Sets the requests and tokens per minute of a provider, applied to the running limiter
+/// immediately. Leaving both unset removes the limits.
+#[tauri::command]
+pub async fn set_provider_rate_limit_v2(
+    db: State<'_, DbManager>,
+    limiter: State<'_, ProviderRateLimiter>,
+    payload: SetProviderRateLimitPayload,
+) -> IpcResult<ProviderRateLimitDto> {
+    metrics::track("set_provider_rate_limit_v2", async {
+        let provider = normalize_provider(&payload.provider)?;
+        for (limit, field) in [
+            (payload.requests_per_minute, "requestsPerMinute"),
+            (payload.tokens_per_minute, "tokensPerMinute"),
+        ] {
+            if limit == Some(0) {
+                return Err(
+                    IpcError::Validation(format!("{field} must be greater than zero.")).into(),
+                );
+            }
+        }
+
+        db.set_provider_rate_limit(
+            &provider,
+            payload.requests_per_minute.map(i64::from),
+            payload.tokens_per_minute.map(i64::from),
+        )
+        .await
+        .map_err(IpcError::from)?;
+        limiter.set_limit(
+            &provider,
+            RateLimit {
+                requests_per_minute: payload.requests_per_minute,
+                tokens_per_minute: payload.tokens_per_minute,
+            },
+        );
+        Ok(ProviderRateLimitDto {
+            provider,
+            requests_per_minute: payload.requests_per_minute,
+            tokens_per_minute: payload.tokens_per_minute,
+        })
+    })
+    .await
+}
+
+/// Limiter configuration of a stored rate limit row.
+pub(crate) fn rate_limit_from_record(record: &ProviderRateLimitRecord) -> RateLimit {
+    RateLimit {
+        requests_per_minute: record
+            .requests_per_minute
+            .and_then(|value| u32::try_from(value).ok()),
+        tokens_per_minute: record
+            .tokens_per_minute
+            .and_then(|value| u32::try_from(value).ok()),
+    }
+}
+
+fn map_rate_limit_record(record: ProviderRateLimitRecord) -> ProviderRateLimitDto {
+    let limit = rate_limit_from_record(&record);
+    ProviderRateLimitDto {
+        provider: record.provider,
+        requests_per_minute: limit.requests_per_minute,
+        tokens_per_minute: limit.tokens_per_minute,
+    }
+}
+
+fn emit_rate_limit_event<R: Runtime>(app: &AppHandle<R>, payload: ProviderRateLimitEventPayload) {
+    if let Err(error) = app.emit(PROVIDER_RATE_LIMIT, payload) {
+        log::warn!(
+            target: "ipc::provider_rate_limits_v2",
+            "failed to emit provider rate limit event: {error}"
+        );
+    }
+}
@@ -0,0 +1,468 @@
+use tauri::State;
+use tauri::ipc::InvokeError;
+use uuid::Uuid;
+
+use super::offline_v2::ensure_online;
+use super::outbound_audit_v2::{ensure_external_mt_allowed, record_outbound_request};
+use super::prompt_templates_v2::load_project_prompt;
+use super::provider_usage_v2::{current_month, normalize_provider, provider_budget_exceeded};
+use crate::db::DbManager;
+use crate::db::types::{
+    NewProviderRoutingRuleArgs, ProviderRoutingRuleRecord, RecordProviderUsageArgs,
+    RoutingRuleOwner,
+};
+use crate::ipc::dto::{
+    ExplainRoutingPayload, ProviderRoutingRuleDto, ReplaceProviderRoutingRulesPayload,
+    RoutedTranslationDto, RoutingExplanationDto, RoutingRuleEvaluationDto, TranslateSegmentPayload,
+};
+use crate::ipc::error::{IpcError, IpcResult};
+use crate::ipc::state::OfflineMode;
+use crate::metrics;
+use crate::providers::{
+    ChatMessage, LLM_PROVIDER, LOCAL_PROVIDER, LlmClient, LocalModelClient, MOCK_PROVIDER,
+    MockTranslator, ProviderRateLimiter, RoutingContext, RoutingRule, route,
+};
+use crate::settings::{AppSettings, SettingsManager};
+
+/// Providers called by the backend itself; the app calls every other provider.
+pub(super) const BACKEND_PROVIDERS: [&str; 3] = [LLM_PROVIDER, LOCAL_PROVIDER, MOCK_PROVIDER];
+
+/// The state backend providers are called with.
+pub(super) struct ProviderBackends<'a> {
+    pub db: &'a DbManager,
+    pub llm: &'a LlmClient,
+    pub local: &'a LocalModelClient,
+    pub limiter: &'a ProviderRateLimiter,
+    pub offline: &'a OfflineMode,
+}
+
+/// A segment on its way to a backend provider.
+pub(super) struct ProviderRequest<'a> {
+    pub project_uuid: Uuid,
+    pub target_lang: &'a str,
+    pub source: &'a str,
+    pub messages: &'a [ChatMessage],
+}
+
+#[tauri::command]
+pub async fn list_provider_routing_rules_v2(
+    db: State<'_, DbManager>,
+    project_uuid: Option<String>,
+    client_uuid: Option<String>,
+) -> IpcResult<Vec<ProviderRoutingRuleDto>> {
+    metrics::track("list_provider_routing_rules_v2", async {
+        let owner = parse_owner(project_uuid.as_deref(), client_uuid.as_deref())?;
+        let records = db
+            .list_provider_routing_rules(owner)
+            .await
+            .map_err(IpcError::from)?;
+        Ok(records.into_iter().map(map_rule_record).collect())
+    })
+    .await
+}
+
+/// Replaces the routing rules of a project or client. Rules are evaluated in the given order.
+#[tauri::command]
+pub async fn replace_provider_routing_rules_v2(
+    db: State<'_, DbManager>,
+    payload: ReplaceProviderRoutingRulesPayload,
+) -> IpcResult<Vec<ProviderRoutingRuleDto>> {
+    metrics::track("replace_provider_routing_rules_v2", async {
+        let owner = parse_owner(
+            payload.project_uuid.as_deref(),
+            payload.client_uuid.as_deref(),
+        )?;
+        let condition = |value: Option<String>| {
+            value
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+        };
+        let mut rules = Vec::with_capacity(payload.rules.len());
+        for input in payload.rules {
+            rules.push(NewProviderRoutingRuleArgs {
+                source_lang: condition(input.source_lang),
+                target_lang: condition(input.target_lang),
+                subject: condition(input.subject),
+                provider: normalize_provider(&input.provider)?,
+                note: condition(input.note),
+            });
+        }
+
+        db.replace_provider_routing_rules(owner, &rules)
+            .await
+            .map_err(IpcError::from)?;
+        let records = db
+            .list_provider_routing_rules(owner)
+            .await
+            .map_err(IpcError::from)?;
+        Ok(records.into_iter().map(map_rule_record).collect())
+    })
+    .await
+}
+
+/// Shows which provider a language pair of the project is routed to and why: every rule
+/// with the reason it did or did not apply.
+#[tauri::command]
+pub async fn explain_routing_v2(
+    db: State<'_, DbManager>,
+    payload: ExplainRoutingPayload,
+) -> IpcResult<RoutingExplanationDto> {
+    metrics::track("explain_routing_v2", async {
+        let project_uuid = parse_uuid(&payload.project_uuid, "projectUuid")?;
+        route_project(
+            db.inner(),
+            project_uuid,
+            &payload.source_lang,
+            &payload.target_lang,
+        )
+        .await
+        .map_err(Into::into)
+    })
+    .await
+}
+
+/// Routes a segment and, when the chosen provider is run by the backend, translates it. For
+/// other providers only the routing decision is returned and the app sends the request,
+/// unless the mock provider is enabled, which then answers for every provider.
+#[tauri::command]
+pub async fn translate_segment_v2(
+    db: State<'_, DbManager>,
+    settings: State<'_, SettingsManager>,
+    llm: State<'_, LlmClient>,
+    local: State<'_, LocalModelClient>,
+    limiter: State<'_, ProviderRateLimiter>,
+    offline: State<'_, OfflineMode>,
+    payload: TranslateSegmentPayload,
+) -> IpcResult<RoutedTranslationDto> {
+    metrics::track("translate_segment_v2", async {
+        let project_uuid = parse_uuid(&payload.project_uuid, "projectUuid")?;
+        if payload.source.trim().is_empty() {
+            return Err(IpcError::Validation("source must not be empty.".into()).into());
+        }
+        let routing = route_project(
+            db.inner(),
+            project_uuid,
+            &payload.source_lang,
+            &payload.target_lang,
+        )
+        .await?;
+        let current = settings.current().await;
+        if let Some(provider) = routing.provider.as_deref() {
+            let provider = backend_provider(provider, &current);
+            ensure_external_mt_allowed(db.inner(), project_uuid, provider).await?;
+        }
+        let Some(provider) = routing
+            .provider
+            .as_deref()
+            .map(|provider| backend_provider(provider, &current).to_string())
+            .filter(|provider| BACKEND_PROVIDERS.contains(&provider.as_str()))
+        else {
+            // The app sends this request itself; it is audited here, where it is decided.
+            if let Some(provider) = routing.provider.as_deref() {
+                record_outbound_request(
+                    db.inner(),
+                    provider,
+                    Some(project_uuid),
+                    1,
+                    payload.source.chars().count() as i64,
+                    None,
+                )
+                .await?;
+            }
+            return Ok(RoutedTranslationDto {
+                provider: routing.provider.clone(),
+                text: None,
+                routing,
+            });
+        };
+
+        if let Some(exceeded) = provider_budget_exceeded(db.inner(), &provider).await? {
+            return Err(InvokeError::from(exceeded));
+        }
+        let prompt = load_project_prompt(
+            db.inner(),
+            Some(&payload.project_uuid),
+            &payload.source_lang,
+            &payload.target_lang,
+            Vec::new(),
+        )
+        .await?;
+        let messages = prompt.render(
+            &payload.source_lang,
+            &payload.target_lang,
+            &payload.source,
+            payload.context.as_deref(),
+        );
+        let backends = ProviderBackends {
+            db: db.inner(),
+            llm: &llm,
+            local: &local,
+            limiter: &limiter,
+            offline: &offline,
+        };
+        let request = ProviderRequest {
+            project_uuid,
+            target_lang: &payload.target_lang,
+            source: &payload.source,
+            messages: &messages,
+        };
+        let characters = payload.source.chars().count() as i64;
+        audit_dispatch(&provider, &backends, &current, project_uuid, 1, characters).await?;
+        let text = dispatch_translation(&provider, &backends, &current, &request).await?;
+
+        db.record_provider_usage(RecordProviderUsageArgs {
+            provider: provider.clone(),
+            project_uuid,
+            usage_month: current_month(),
+            characters,
+            tokens: characters / 2,
+        })
+        .await
+        .map_err(IpcError::from)?;
+
+        Ok(RoutedTranslationDto {
+            provider: Some(provider),
+            text: Some(text),
+            routing,
+        })
+    })
+    .await
+}
+
+/// Evaluates the project's routing rules, then its preferred provider for the pair.
+pub(super) async fn route_project(
+    db: &DbManager,
+    project_uuid: Uuid,
+    source_lang: &str,
+    target_lang: &str,
+) -> Result<RoutingExplanationDto, IpcError> {
+    let bundle = db
+        .get_project_bundle(project_uuid)
+        .await
+        .map_err(IpcError::from)?
+        .ok_or_else(|| IpcError::Validation(format!("project {project_uuid} not found")))?;
+    let mut subjects: Vec<String> = bundle
+        .subjects
+        .into_iter()
+        .map(|record| record.subject)
+        .collect();
+    // Linked domains route like subjects, so rules can key off either.
+    for domain in db
+        .list_project_domains(project_uuid)
+        .await
+        .map_err(IpcError::from)?
+    {
+        if !subjects
+            .iter()
+            .any(|subject| subject.eq_ignore_ascii_case(&domain.name))
+        {
+            subjects.push(domain.name);
+        }
+    }
+    let context = RoutingContext {
+        source_lang: source_lang.trim(),
+        target_lang: target_lang.trim(),
+        subjects: &subjects,
+    };
+
+    let records = db
+        .provider_routing_rules_for_project(project_uuid)
+        .await
+        .map_err(IpcError::from)?;
+    let rules: Vec<RoutingRule> = records
+        .iter()
+        .map(|record| RoutingRule {
+            source_lang: record.source_lang.clone(),
+            target_lang: record.target_lang.clone(),
+            subject: record.subject.clone(),
+            provider: record.provider.clone(),
+        })
+        .collect();
+    let matched = route(&rules, &context);
+
+    let evaluated = records
+        .into_iter()
+        .zip(&rules)
+        .enumerate()
+        .map(|(index, (record, rule))| {
+            let reason = match matched {
+                Some(matched) if index == matched => None,
+                Some(matched) if index > matched => Some("an earlier rule matched".to_string()),
+                _ => rule.mismatch(&context),
+            };
+            RoutingRuleEvaluationDto {
+                rule: map_rule_record(record),
+                matched: matched == Some(index),
+                reason,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    if let Some(index) = matched {
+        let rule = evaluated[index].rule.clone();
+        return Ok(RoutingExplanationDto {
+            provider: Some(rule.provider.clone()),
+            decided_by: "rule".into(),
+            matched_rule: Some(rule),
+            evaluated,
+        });
+    }
+
+    let preferred = db
+        .list_preferred_providers(project_uuid)
+        .await
+        .map_err(IpcError::from)?
+        .into_iter()
+        .find(|record| {
+            record.source_lang.eq_ignore_ascii_case(context.source_lang)
+                && record.target_lang.eq_ignore_ascii_case(context.target_lang)
+        })
+        .map(|record| record.provider);
+    Ok(RoutingExplanationDto {
+        decided_by: if preferred.is_some() {
+            "preferred"
+        } else {
+            "default"
+        }
+        .into(),
+        provider: preferred,
+        matched_rule: None,
+        evaluated,
+    })
+}
+
+/// The provider that handles a request routed to `provider`: the mock provider while it is
+/// enabled in the settings, otherwise `provider` itself.
+pub(super) fn backend_provider<'a>(provider: &'a str, settings: &AppSettings) -> &'a str {
+    if settings.mock_provider_enabled {
+        MOCK_PROVIDER
+    } else {
+        provider
+    }
+}
+
+/// Records that `segment_count` segments of `char_count` characters are about to be sent to
+/// `provider` for the project. Callers audit before their first [`dispatch_translation`],
+/// once per batch; the mock provider answers in-process and is not audited.
+pub(super) async fn audit_dispatch(
+    provider: &str,
+    backends: &ProviderBackends<'_>,
+    settings: &AppSettings,
+    project_uuid: Uuid,
+    segment_count: i64,
+    char_count: i64,
+) -> Result<(), IpcError> {
+    let base_url = match provider {
+        LLM_PROVIDER => {
+            ensure_external_mt_allowed(backends.db, project_uuid, provider).await?;
+            ensure_online(backends.offline, "Machine translation")?;
+            backends.llm.base_url()
+        }
+        LOCAL_PROVIDER => settings.local_model_base_url.as_str(),
+        _ => return Ok(()),
+    };
+    record_outbound_request(
+        backends.db,
+        provider,
+        Some(project_uuid),
+        segment_count,
+        char_count,
+        Some(base_url),
+    )
+    .await
+}
+
+/// Sends a request to a provider run by the backend and returns its answer. External
+/// providers are refused for projects that forbid them; callers audit the request with
+/// [`audit_dispatch`] first.
+pub(super) async fn dispatch_translation(
+    provider: &str,
+    backends: &ProviderBackends<'_>,
+    settings: &AppSettings,
+    request: &ProviderRequest<'_>,
+) -> Result<String, IpcError> {
+    let ProviderBackends {
+        db,
+        llm,
+        local,
+        limiter,
+        offline,
+    } = backends;
+    let messages = request.messages;
+    match provider {
+        LLM_PROVIDER => {
+            ensure_external_mt_allowed(db, request.project_uuid, provider).await?;
+            ensure_online(offline, "Machine translation")?;
+            // Rough token estimate: prompt and answer at ~4 characters a token.
+            let tokens = u32::try_from(
+                messages
+                    .iter()
+                    .map(|message| message.content.len())
+                    .sum::<usize>()
+                    / 2,
+            )
+            .unwrap_or(u32::MAX);
+            limiter.acquire(LLM_PROVIDER, tokens, |_| {}).await;
+            let text = llm
+                .stream_chat(llm.default_model(), messages, |_| {})
+                .await
+                .map_err(|error| IpcError::Internal(error.to_string()))?;
+            Ok(text.trim().to_string())
+        }
+        LOCAL_PROVIDER => local
+            .complete(
+                &settings.local_model_base_url,
+                settings.local_model_name.trim(),
+                messages,
+            )
+            .await
+            .map_err(|error| IpcError::Internal(error.to_string())),
+        MOCK_PROVIDER => MockTranslator::new(settings.mock_provider())
+            .translate(request.target_lang, request.source)
+            .await
+            .map_err(|error| IpcError::Internal(error.to_string())),
+        other => Err(IpcError::Validation(format!(
+            "provider '{other}' is not run by the backend. Available providers: {}.",
+            BACKEND_PROVIDERS.join(", ")
+        ))),
+    }
+}
+
+fn parse_owner(
+    project_uuid: Option<&str>,
+    client_uuid: Option<&str>,
+) -> Result<RoutingRuleOwner, IpcError> {
+    match (project_uuid, client_uuid) {
+        (Some(project_uuid), None) => Ok(RoutingRuleOwner::Project(parse_uuid(
+            project_uuid,
+            "projectUuid",
+        )?)),
+        (None, Some(client_uuid)) => Ok(RoutingRuleOwner::Client(parse_uuid(
+            client_uuid,
+            "clientUuid",
+        )?)),
+        _ => Err(IpcError::Validation(
+            "Routing rules belong to either a project or a client; pass exactly one of projectUuid and clientUuid.".into(),
+        )),
+    }
+}
+
+fn map_rule_record(record: ProviderRoutingRuleRecord) -> ProviderRoutingRuleDto {
+    ProviderRoutingRuleDto {
+        rule_uuid: record.rule_uuid.to_string(),
+        project_uuid: record.project_uuid.map(|uuid| uuid.to_string()),
+        client_uuid: record.client_uuid.map(|uuid| uuid.to_string()),
+        position: record.position,
+        source_lang: record.source_lang,
+        target_lang: record.target_lang,
+        subject: record.subject,
+        provider: record.provider,
+        note: record.note,
+        created_at: record.created_at,
+    }
+}
+
+fn parse_uuid(value: &str, field: &str) -> Result<Uuid, IpcError> {
+    Uuid::parse_str(value)
+        .map_err(|_| IpcError::Validation(format!("invalid {field}: expected UUID, got '{value}'")))
+}
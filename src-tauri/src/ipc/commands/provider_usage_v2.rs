@@ -0,0 +1,261 @@
+use chrono::Utc;
+use tauri::State;
+use uuid::Uuid;
+
+use crate::db::DbManager;
+use crate::db::types::{
+    ProviderBudgetArgs, ProviderBudgetRecord, ProviderMonthlyUsageRecord, ProviderUsageRecord,
+    RecordProviderUsageArgs,
+};
+use crate::ipc::dto::{
+    GetProviderUsagePayload, ProviderBudgetExceededDto, ProviderBudgetStatusDto, ProviderUsageDto,
+    ProviderUsageEntryDto, RecordProviderUsagePayload, SetProviderBudgetPayload,
+};
+use crate::ipc::error::{IpcError, IpcResult};
+use crate::metrics;
+
+const PROVIDER_BUDGET_EXCEEDED: &str = "PROVIDER_BUDGET_EXCEEDED";
+
+/// Adds the characters and tokens of one provider request to the project's usage for the
+/// current month. Usage is always recorded; `exceeded` tells the caller to stop sending.
+#[tauri::command]
+pub async fn record_provider_usage_v2(
+    db: State<'_, DbManager>,
+    payload: RecordProviderUsagePayload,
+) -> IpcResult<ProviderBudgetStatusDto> {
+    metrics::track("record_provider_usage_v2", async {
+        let provider = normalize_provider(&payload.provider)?;
+        let project_uuid = parse_uuid(&payload.project_uuid, "projectUuid")?;
+        if payload.characters < 0 || payload.tokens < 0 {
+            return Err(IpcError::Validation("Usage amounts must not be negative.".into()).into());
+        }
+
+        let usage_month = current_month();
+        db.record_provider_usage(RecordProviderUsageArgs {
+            provider: provider.clone(),
+            project_uuid,
+            usage_month: usage_month.clone(),
+            characters: payload.characters,
+            tokens: payload.tokens,
+        })
+        .await
+        .map_err(IpcError::from)?;
+        budget_status(db.inner(), &provider, &usage_month)
+            .await
+            .map_err(Into::into)
+    })
+    .await
+}
+
+/// Returns provider usage per project and the monthly totals of every provider against its
+/// budget.
+#[tauri::command]
+pub async fn get_provider_usage_v2(
+    db: State<'_, DbManager>,
+    payload: Option<GetProviderUsagePayload>,
+) -> IpcResult<ProviderUsageDto> {
+    metrics::track("get_provider_usage_v2", async {
+        let payload = payload.unwrap_or_default();
+        let project_uuid = payload
+            .project_uuid
+            .as_deref()
+            .map(|value| parse_uuid(value, "projectUuid"))
+            .transpose()?;
+        let month = match payload.month {
+            Some(month) => parse_month(&month)?,
+            None => current_month(),
+        };
+
+        let entries = db
+            .list_provider_usage(project_uuid, Some(&month))
+            .await
+            .map_err(IpcError::from)?
+            .into_iter()
+            .map(map_usage_record)
+            .collect();
+
+        let totals = db
+            .provider_monthly_totals(&month)
+            .await
+            .map_err(IpcError::from)?;
+        let budgets = db.list_provider_budgets().await.map_err(IpcError::from)?;
+        let mut providers: Vec<String> = totals
+            .iter()
+            .map(|total| total.provider.clone())
+            .chain(budgets.iter().map(|budget| budget.provider.clone()))
+            .collect();
+        providers.sort();
+        providers.dedup();
+        let providers = providers
+            .into_iter()
+            .map(|provider| {
+                map_budget_status(
+                    &provider,
+                    &month,
+                    totals.iter().find(|total| total.provider == provider),
+                    budgets.iter().find(|budget| budget.provider == provider),
+                )
+            })
+            .collect();
+
+        Ok(ProviderUsageDto {
+            month,
+            entries,
+            providers,
+        })
+    })
+    .await
+}
+
+/// Sets the monthly character and token caps of a provider. Leaving both unset removes the
+/// budget.
+#[tauri::command]
+pub async fn set_provider_budget_v2(
+    db: State<'_, DbManager>,
+    payload: SetProviderBudgetPayload,
+) -> IpcResult<ProviderBudgetStatusDto> {
+    metrics::track("set_provider_budget_v2", async {
+        let provider = normalize_provider(&payload.provider)?;
+        for (cap, field) in [
+            (payload.monthly_character_cap, "monthlyCharacterCap"),
+            (payload.monthly_token_cap, "monthlyTokenCap"),
+        ] {
+            if cap.is_some_and(|cap| cap <= 0) {
+                return Err(
+                    IpcError::Validation(format!("{field} must be greater than zero.")).into(),
+                );
+            }
+        }
+
+        db.set_provider_budget(ProviderBudgetArgs {
+            provider: provider.clone(),
+            monthly_character_cap: payload.monthly_character_cap,
+            monthly_token_cap: payload.monthly_token_cap,
+        })
+        .await
+        .map_err(IpcError::from)?;
+        budget_status(db.inner(), &provider, &current_month())
+            .await
+            .map_err(Into::into)
+    })
+    .await
+}
+
+/// Reports why `provider` may not be used right now, or `None` while it is within its budget
+/// for the current month.
+pub(super) async fn provider_budget_exceeded(
+    db: &DbManager,
+    provider: &str,
+) -> Result<Option<ProviderBudgetExceededDto>, IpcError> {
+    let provider = normalize_provider(provider)?;
+    let budget = budget_status(db, &provider, &current_month()).await?;
+    if !budget.exceeded {
+        return Ok(None);
+    }
+    Ok(Some(ProviderBudgetExceededDto {
+        code: PROVIDER_BUDGET_EXCEEDED.to_string(),
+        message: budget_exceeded_message(&budget),
+        budget,
+    }))
+}
+
+/// Explains which cap of `budget` was reached.
+fn budget_exceeded_message(budget: &ProviderBudgetStatusDto) -> String {
+    let mut reached = Vec::new();
+    if let Some(cap) = budget.monthly_character_cap
+        && budget.characters >= cap
+    {
+        reached.push(format!("{} of {cap} characters", budget.characters));
+    }
+    if let Some(cap) = budget.monthly_token_cap
+        && budget.tokens >= cap
+    {
+        reached.push(format!("{} of {cap} tokens", budget.tokens));
+    }
+    format!(
+        "The monthly budget of provider '{}' is exhausted for {} ({} used). Machine translation with this provider is paused until the budget is raised or the month ends.",
+        budget.provider,
+        budget.month,
+        reached.join(", ")
+    )
+}
+
+async fn budget_status(
+    db: &DbManager,
+    provider: &str,
+    usage_month: &str,
+) -> Result<ProviderBudgetStatusDto, IpcError> {
+    let totals = db
+        .provider_monthly_totals(usage_month)
+        .await
+        .map_err(IpcError::from)?;
+    let budgets = db.list_provider_budgets().await.map_err(IpcError::from)?;
+    Ok(map_budget_status(
+        provider,
+        usage_month,
+        totals.iter().find(|total| total.provider == provider),
+        budgets.iter().find(|budget| budget.provider == provider),
+    ))
+}
+
+fn map_budget_status(
+    provider: &str,
+    usage_month: &str,
+    total: Option<&ProviderMonthlyUsageRecord>,
+    budget: Option<&ProviderBudgetRecord>,
+) -> ProviderBudgetStatusDto {
+    let (characters, tokens, requests) = total
+        .map(|total| (total.characters, total.tokens, total.requests))
+        .unwrap_or_default();
+    let monthly_character_cap = budget.and_then(|budget| budget.monthly_character_cap);
+    let monthly_token_cap = budget.and_then(|budget| budget.monthly_token_cap);
+    ProviderBudgetStatusDto {
+        provider: provider.to_string(),
+        month: usage_month.to_string(),
+        characters,
+        tokens,
+        requests,
+        monthly_character_cap,
+        monthly_token_cap,
+        exceeded: monthly_character_cap.is_some_and(|cap| characters >= cap)
+            || monthly_token_cap.is_some_and(|cap| tokens >= cap),
+    }
+}
+
+fn map_usage_record(record: ProviderUsageRecord) -> ProviderUsageEntryDto {
+    ProviderUsageEntryDto {
+        provider: record.provider,
+        project_uuid: record.project_uuid.to_string(),
+        month: record.usage_month,
+        characters: record.characters,
+        tokens: record.tokens,
+        requests: record.requests,
+        updated_at: record.updated_at,
+    }
+}
+
+pub(super) fn normalize_provider(value: &str) -> Result<String, IpcError> {
+    let provider = value.trim().to_ascii_lowercase();
+    if provider.is_empty() {
+        return Err(IpcError::Validation("provider must not be empty.".into()));
+    }
+    Ok(provider)
+}
+
+pub(super) fn current_month() -> String {
+    Utc::now().format("%Y-%m").to_string()
+}
+
+fn parse_month(value: &str) -> Result<String, IpcError> {
+    let value = value.trim();
+    chrono::NaiveDate::parse_from_str(&format!("{value}-01"), "%Y-%m-%d")
+        .map(|_| value.to_string())
+        .map_err(|_| {
+            IpcError::Validation(format!("invalid month: expected YYYY-MM, got '{value}'"))
+        })
+}
+
+fn parse_uuid(value: &str, field: &str) -> Result<Uuid, IpcError> {
+    Uuid::parse_str(value)
+        .map_err(|_| IpcError::Validation(format!("invalid {field}: expected UUID, got '{value}'")))
+}
@@ -0,0 +1,302 @@
+use std::path::PathBuf;
+
+use log::warn;
+use tauri::State;
+use uuid::Uuid;
+
+use super::shared::fs_error;
+use crate::db::DbManager;
+use crate::db::types::{NewQaProfileArgs, QaFindingRecord, QaProfileRecord, QaProfileTarget};
+use crate::ipc::dto::{
+    AssignQaProfilePayload, ExportQaProfilePayload, ImportQaProfilePayload, ListQaFindingsPayload,
+    QaFindingDto, QaProfileDto, QaProfileExportDto, SaveQaProfilePayload,
+};
+use crate::ipc::error::{IpcError, IpcResult};
+use crate::metrics;
+use crate::qa::{QaProfileDefinition, QaProfileFile};
+
+#[tauri::command]
+pub async fn list_qa_findings_v2(
+    db: State<'_, DbManager>,
+    payload: ListQaFindingsPayload,
+) -> IpcResult<Vec<QaFindingDto>> {
+    metrics::track("list_qa_findings_v2", async {
+        let project_uuid = parse_uuid(&payload.project_uuid, "projectUuid")?;
+        let records = db
+            .list_qa_findings(project_uuid, payload.jliff_rel_path.as_deref())
+            .await
+            .map_err(IpcError::from)?;
+        Ok(records
+            .into_iter()
+            .filter(|record| payload.include_resolved || record.resolved_at.is_none())
+            .map(map_qa_finding_record)
+            .collect())
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn resolve_qa_finding_v2(
+    db: State<'_, DbManager>,
+    finding_uuid: String,
+    resolved: bool,
+) -> IpcResult<Option<QaFindingDto>> {
+    metrics::track("resolve_qa_finding_v2", async {
+        let finding_uuid = parse_uuid(&finding_uuid, "findingUuid")?;
+        let record = db
+            .set_qa_finding_resolved(finding_uuid, resolved)
+            .await
+            .map_err(IpcError::from)?;
+        Ok(record.map(map_qa_finding_record))
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn list_qa_profiles_v2(db: State<'_, DbManager>) -> IpcResult<Vec<QaProfileDto>> {
+    metrics::track("list_qa_profiles_v2", async {
+        let records = db.list_qa_profiles().await.map_err(IpcError::from)?;
+        Ok(records.into_iter().map(map_qa_profile_record).collect())
+    })
+    .await
+}
+
+/// Creates a QA profile, or replaces the definition of the profile with the same name.
+#[tauri::command]
+pub async fn save_qa_profile_v2(
+    db: State<'_, DbManager>,
+    payload: SaveQaProfilePayload,
+) -> IpcResult<QaProfileDto> {
+    metrics::track("save_qa_profile_v2", async {
+        let file = QaProfileFile::new(
+            payload.name.trim().to_string(),
+            payload.description,
+            payload.definition,
+        );
+        store_profile(db.inner(), file).await.map_err(Into::into)
+    })
+    .await
+}
+
+/// Writes a QA profile to a portable JSON file that other installations can import.
+#[tauri::command]
+pub async fn export_qa_profile_v2(
+    db: State<'_, DbManager>,
+    payload: ExportQaProfilePayload,
+) -> IpcResult<QaProfileExportDto> {
+    metrics::track("export_qa_profile_v2", async {
+        let profile_uuid = parse_uuid(&payload.profile_uuid, "profileUuid")?;
+        let output_path = absolute_path(&payload.output_path, "outputPath")?;
+        let record = db
+            .get_qa_profile(profile_uuid)
+            .await
+            .map_err(IpcError::from)?
+            .ok_or_else(|| IpcError::Validation(format!("QA profile {profile_uuid} not found.")))?;
+
+        let file = QaProfileFile::new(
+            record.name.clone(),
+            record.description.clone(),
+            parse_definition(&record),
+        );
+        let contents = serde_json::to_vec_pretty(&file).map_err(|error| {
+            IpcError::Internal(format!("Failed to serialize QA profile: {error}"))
+        })?;
+        tokio::fs::write(&output_path, contents)
+            .await
+            .map_err(|error| fs_error("write the QA profile file", error))?;
+
+        Ok(QaProfileExportDto {
+            output_path: payload.output_path,
+            name: record.name,
+        })
+    })
+    .await
+}
+
+/// Imports an exported QA profile, replacing a local profile with the same name, and
+/// optionally assigns it to a project or client.
+#[tauri::command]
+pub async fn import_qa_profile_v2(
+    db: State<'_, DbManager>,
+    payload: ImportQaProfilePayload,
+) -> IpcResult<QaProfileDto> {
+    metrics::track("import_qa_profile_v2", async {
+        let input_path = absolute_path(&payload.input_path, "inputPath")?;
+        let target = parse_optional_target(
+            payload.project_uuid.as_deref(),
+            payload.client_uuid.as_deref(),
+        )?;
+        let bytes = tokio::fs::read(&input_path)
+            .await
+            .map_err(|error| fs_error("read the QA profile file", error))?;
+        let file = QaProfileFile::parse(&bytes).map_err(|error| {
+            IpcError::Validation(format!("The QA profile could not be imported: {error:#}"))
+        })?;
+
+        let profile = store_profile(db.inner(), file).await?;
+        if let Some(target) = target {
+            let profile_uuid = parse_uuid(&profile.profile_uuid, "profileUuid")?;
+            db.assign_qa_profile(target, Some(profile_uuid))
+                .await
+                .map_err(IpcError::from)?;
+        }
+        Ok(profile)
+    })
+    .await
+}
+
+/// Assigns a QA profile to a project or a client. Projects without their own profile use
+/// their client's.
+#[tauri::command]
+pub async fn assign_qa_profile_v2(
+    db: State<'_, DbManager>,
+    payload: AssignQaProfilePayload,
+) -> IpcResult<()> {
+    metrics::track("assign_qa_profile_v2", async {
+        let target = parse_optional_target(
+            payload.project_uuid.as_deref(),
+            payload.client_uuid.as_deref(),
+        )?
+        .ok_or_else(|| {
+            IpcError::Validation("Either projectUuid or clientUuid is required.".into())
+        })?;
+        let profile_uuid = payload
+            .profile_uuid
+            .as_deref()
+            .map(|value| parse_uuid(value, "profileUuid"))
+            .transpose()?;
+        if let Some(profile_uuid) = profile_uuid
+            && db
+                .get_qa_profile(profile_uuid)
+                .await
+                .map_err(IpcError::from)?
+                .is_none()
+        {
+            return Err(
+                IpcError::Validation(format!("QA profile {profile_uuid} not found.")).into(),
+            );
+        }
+
+        db.assign_qa_profile(target, profile_uuid)
+            .await
+            .map_err(IpcError::from)?;
+        Ok(())
+    })
+    .await
+}
+
+/// Profile definition governing the QA checks of a project; the built-in behaviour when no
+/// profile is assigned.
+pub(super) async fn project_qa_profile(
+    db: &DbManager,
+    project_uuid: Uuid,
+) -> Result<QaProfileDefinition, IpcError> {
+    let record = db
+        .qa_profile_for_project(project_uuid)
+        .await
+        .map_err(IpcError::from)?;
+    Ok(record.as_ref().map(parse_definition).unwrap_or_default())
+}
+
+pub(super) async fn store_profile(
+    db: &DbManager,
+    file: QaProfileFile,
+) -> Result<QaProfileDto, IpcError> {
+    if file.name.is_empty() {
+        return Err(IpcError::Validation(
+            "QA profile name must not be empty.".into(),
+        ));
+    }
+    file.definition
+        .validate()
+        .map_err(|error| IpcError::Validation(format!("{error:#}")))?;
+    let definition_json = serde_json::to_string(&file.definition)
+        .map_err(|error| IpcError::Internal(format!("Failed to serialize QA profile: {error}")))?;
+
+    let record = db
+        .upsert_qa_profile(NewQaProfileArgs {
+            name: file.name.trim().to_string(),
+            description: file
+                .description
+                .map(|description| description.trim().to_string())
+                .filter(|description| !description.is_empty()),
+            definition_json,
+        })
+        .await
+        .map_err(IpcError::from)?;
+    Ok(map_qa_profile_record(record))
+}
+
+fn parse_definition(record: &QaProfileRecord) -> QaProfileDefinition {
+    serde_json::from_str(&record.definition_json).unwrap_or_else(|error| {
+        warn!(
+            target: "ipc::qa",
+            "ignoring unreadable definition of QA profile {}: {error}",
+            record.profile_uuid
+        );
+        QaProfileDefinition::default()
+    })
+}
+
+fn map_qa_profile_record(record: QaProfileRecord) -> QaProfileDto {
+    QaProfileDto {
+        profile_uuid: record.profile_uuid.to_string(),
+        definition: parse_definition(&record),
+        name: record.name,
+        description: record.description,
+        updated_at: record.updated_at,
+    }
+}
+
+fn parse_optional_target(
+    project_uuid: Option<&str>,
+    client_uuid: Option<&str>,
+) -> Result<Option<QaProfileTarget>, IpcError> {
+    match (project_uuid, client_uuid) {
+        (Some(_), Some(_)) => Err(IpcError::Validation(
+            "Assign a QA profile to either a project or a client, not both.".into(),
+        )),
+        (Some(project_uuid), None) => Ok(Some(QaProfileTarget::Project(parse_uuid(
+            project_uuid,
+            "projectUuid",
+        )?))),
+        (None, Some(client_uuid)) => Ok(Some(QaProfileTarget::Client(parse_uuid(
+            client_uuid,
+            "clientUuid",
+        )?))),
+        (None, None) => Ok(None),
+    }
+}
+
+fn absolute_path(value: &str, field: &str) -> Result<PathBuf, IpcError> {
+    let path = PathBuf::from(value.trim());
+    if !path.is_absolute() {
+        return Err(IpcError::Validation(format!(
+            "{field} must be an absolute path."
+        )));
+    }
+    Ok(path)
+}
+
+pub(super) fn map_qa_finding_record(record: QaFindingRecord) -> QaFindingDto {
+    QaFindingDto {
+        finding_uuid: record.finding_uuid.to_string(),
+        project_uuid: record.project_uuid.to_string(),
+        jliff_rel_path: record.jliff_rel_path,
+        transunit_id: record.transunit_id,
+        check_type: record.check_type,
+        severity: record.severity,
+        message: record.message,
+        details: record
+            .details
+            .as_deref()
+            .and_then(|raw| serde_json::from_str(raw).ok()),
+        created_at: record.created_at,
+        resolved_at: record.resolved_at,
+    }
+}
+
+fn parse_uuid(value: &str, field: &str) -> Result<Uuid, IpcError> {
+    Uuid::parse_str(value)
+        .map_err(|_| IpcError::Validation(format!("invalid {field}: expected UUID, got '{value}'")))
+}
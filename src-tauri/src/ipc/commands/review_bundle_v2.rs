@@ -0,0 +1,219 @@
+//! Review bundles and read-only guest mode. A project manager exports a bundle; an external
+//! reviewer opens it and browses the documents without a project, a database or any way to
+//! change them. While a bundle is open only the guest commands run (see `guest`).
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use tauri::State;
+use uuid::Uuid;
+
+use super::projects_v2::resolve_project_root;
+use super::segments_v2::{page_segments, validate_limit};
+use super::shared::{fs_error, list_project_jliff_paths, load_project_jliff};
+use crate::db::DbManager;
+use crate::delivery::{DELIVERIES_DIR, package_file_name, unused_package_path, write_package};
+use crate::guest::{
+    BundledDocument, FORMAT_VERSION, REVIEW_BUNDLE_EXTENSION, ReviewBundleManifest, bundle_entries,
+    read_document, read_manifest,
+};
+use crate::ipc::dto::{
+    GetReviewBundleSegmentsPayload, ReviewBundleDto, ReviewBundleExportDto, SegmentPageDto,
+};
+use crate::ipc::error::{IpcError, IpcResult};
+use crate::ipc::state::{GuestSession, ReadOnlyContext};
+use crate::metrics;
+use crate::settings::SettingsManager;
+
+/// Writes every JLIFF document of a project into a review bundle in its `Deliveries`
+/// folder.
+#[tauri::command]
+pub async fn export_review_bundle_v2(
+    db: State<'_, DbManager>,
+    settings: State<'_, SettingsManager>,
+    project_uuid: String,
+) -> IpcResult<ReviewBundleExportDto> {
+    metrics::track("export_review_bundle_v2", async {
+        let project_uuid = parse_uuid(&project_uuid, "projectUuid")?;
+        let bundle = db
+            .get_project_bundle(project_uuid)
+            .await
+            .map_err(IpcError::from)?
+            .ok_or_else(|| {
+                IpcError::Validation(format!("Project {project_uuid} was not found."))
+            })?;
+        let project_root = resolve_project_root(db.inner(), settings.inner(), project_uuid).await?;
+
+        let mut documents = Vec::new();
+        for jliff_rel_path in list_project_jliff_paths(&project_root).await? {
+            let (_, document) =
+                load_project_jliff(db.inner(), settings.inner(), project_uuid, &jliff_rel_path)
+                    .await?;
+            documents.push(BundledDocument {
+                jliff_rel_path,
+                source_lang: document.source_language,
+                target_lang: document.target_language,
+                segments: document.transunits.len(),
+            });
+        }
+        if documents.is_empty() {
+            return Err(IpcError::Validation(
+                "The project has no converted documents to review.".into(),
+            )
+            .into());
+        }
+
+        let now = chrono::Utc::now();
+        let manifest = ReviewBundleManifest {
+            format_version: FORMAT_VERSION,
+            project_uuid: project_uuid.to_string(),
+            project_name: bundle.project.project_name.clone(),
+            project_number: bundle.project.project_number.clone(),
+            exported_at: now.to_rfc3339(),
+            documents,
+        };
+        let deliveries_dir = project_root.join(DELIVERIES_DIR);
+        tokio::fs::create_dir_all(&deliveries_dir)
+            .await
+            .map_err(|error| fs_error("create the Deliveries folder", error))?;
+        let base = bundle.project.project_number.clone().unwrap_or_else(|| {
+            project_root
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default()
+        });
+        let file_name = Path::new(&package_file_name(&base, "review", now.date_naive()))
+            .with_extension(REVIEW_BUNDLE_EXTENSION)
+            .to_string_lossy()
+            .into_owned();
+        let document_count = manifest.documents.len();
+        let (path, size_bytes) = tokio::task::spawn_blocking(
+            move || -> anyhow::Result<(PathBuf, u64)> {
+                let entries = bundle_entries(&manifest, &project_root)?;
+                let path = unused_package_path(&deliveries_dir, &file_name);
+                let size_bytes = write_package(&path, &entries)?;
+                Ok((path, size_bytes))
+            },
+        )
+        .await
+        .map_err(|error| IpcError::Internal(format!("Packaging failed: {error}")))?
+        .map_err(|error| {
+            log::error!(target: "ipc::review_bundle", "failed to write review bundle: {error:#}");
+            IpcError::Internal("The review bundle could not be written.".into())
+        })?;
+
+        Ok(ReviewBundleExportDto {
+            path: path.to_string_lossy().into_owned(),
+            documents: document_count,
+            size_bytes,
+        })
+    })
+    .await
+}
+
+/// Opens a review bundle and switches the app to read-only guest mode.
+#[tauri::command]
+pub async fn open_review_bundle_v2(
+    context: State<'_, ReadOnlyContext>,
+    path: String,
+) -> IpcResult<ReviewBundleDto> {
+    metrics::track("open_review_bundle_v2", async {
+        let bundle_path = PathBuf::from(path.trim());
+        if !bundle_path.is_absolute() {
+            return Err(IpcError::Validation("path must be an absolute path.".into()).into());
+        }
+        let manifest = {
+            let bundle_path = bundle_path.clone();
+            tokio::task::spawn_blocking(move || read_manifest(&bundle_path))
+                .await
+                .map_err(|error| IpcError::Internal(format!("Opening failed: {error}")))?
+                .map_err(|error| {
+                    IpcError::Validation(format!(
+                        "'{}' cannot be opened as a review bundle: {error:#}",
+                        bundle_path.display()
+                    ))
+                })?
+        };
+
+        let session = GuestSession {
+            bundle_path,
+            manifest,
+        };
+        context.enter(session.clone());
+        log::info!(
+            target: "ipc::review_bundle",
+            "opened review bundle of '{}' in read-only guest mode",
+            session.manifest.project_name
+        );
+        Ok(map_session(session))
+    })
+    .await
+}
+
+/// The bundle open in guest mode, or `null` outside guest mode.
+#[tauri::command]
+pub async fn get_review_bundle_v2(
+    context: State<'_, ReadOnlyContext>,
+) -> IpcResult<Option<ReviewBundleDto>> {
+    metrics::track("get_review_bundle_v2", async {
+        Ok(context.session().map(map_session))
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn get_review_bundle_segments_v2(
+    context: State<'_, ReadOnlyContext>,
+    payload: GetReviewBundleSegmentsPayload,
+) -> IpcResult<SegmentPageDto> {
+    metrics::track("get_review_bundle_segments_v2", async {
+        let limit = validate_limit(payload.limit)?;
+        let session = context
+            .session()
+            .ok_or_else(|| IpcError::Validation("No review bundle is open.".into()))?;
+        if session.manifest.document(&payload.jliff_rel_path).is_none() {
+            return Err(IpcError::Validation(format!(
+                "'{}' is not part of the review bundle.",
+                payload.jliff_rel_path
+            ))
+            .into());
+        }
+
+        let document = {
+            let jliff_rel_path = payload.jliff_rel_path.clone();
+            tokio::task::spawn_blocking(move || {
+                read_document(&session.bundle_path, &jliff_rel_path)
+            })
+            .await
+            .map_err(|error| IpcError::Internal(format!("Reading failed: {error}")))?
+            .map_err(|error| {
+                IpcError::Validation(format!("The document cannot be read: {error:#}"))
+            })?
+        };
+        Ok(page_segments(
+            document.transunits.iter().collect(),
+            payload.offset,
+            limit,
+            &HashMap::new(),
+        ))
+    })
+    .await
+}
+
+/// Closes the review bundle and leaves guest mode.
+#[tauri::command]
+pub async fn close_review_bundle_v2(context: State<'_, ReadOnlyContext>) -> IpcResult<bool> {
+    metrics::track("close_review_bundle_v2", async { Ok(context.leave()) }).await
+}
+
+fn map_session(session: GuestSession) -> ReviewBundleDto {
+    ReviewBundleDto {
+        bundle_path: session.bundle_path.to_string_lossy().into_owned(),
+        manifest: session.manifest,
+    }
+}
+
+fn parse_uuid(value: &str, field: &str) -> Result<Uuid, IpcError> {
+    Uuid::parse_str(value)
+        .map_err(|_| IpcError::Validation(format!("invalid {field}: expected UUID, got '{value}'")))
+}
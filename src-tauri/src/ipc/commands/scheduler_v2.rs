@@ -0,0 +1,57 @@
+use tauri::{AppHandle, State};
+
+use crate::db::DbManager;
+use crate::ipc::dto::ScheduledTaskDto;
+use crate::ipc::error::{IpcError, IpcResult};
+use crate::metrics;
+use crate::scheduler::Scheduler;
+
+/// Lists the background tasks with their schedule, next run and last outcome.
+#[tauri::command]
+pub async fn list_scheduled_tasks_v2(
+    db: State<'_, DbManager>,
+    scheduler: State<'_, Scheduler>,
+) -> IpcResult<Vec<ScheduledTaskDto>> {
+    metrics::track("list_scheduled_tasks_v2", async {
+        let runs = db
+            .list_scheduled_task_runs()
+            .await
+            .map_err(IpcError::from)?;
+        Ok(scheduler
+            .statuses()
+            .into_iter()
+            .map(|status| {
+                let run = runs.iter().find(|run| run.task_id == status.id);
+                ScheduledTaskDto {
+                    id: status.id.to_string(),
+                    description: status.description.to_string(),
+                    schedule: status.schedule.to_string(),
+                    next_run_at: status.next_run_at.map(|at| at.to_rfc3339()),
+                    running: status.running,
+                    last_started_at: run.map(|run| run.last_started_at.clone()),
+                    last_finished_at: run.and_then(|run| run.last_finished_at.clone()),
+                    last_status: run.map(|run| run.last_status.clone()),
+                    last_error: run.and_then(|run| run.last_error.clone()),
+                    run_count: run.map(|run| run.run_count).unwrap_or(0),
+                }
+            })
+            .collect())
+    })
+    .await
+}
+
+/// Starts a background task now, outside its schedule. Progress shows up in
+/// `list_scheduled_tasks_v2`.
+#[tauri::command]
+pub async fn run_task_now_v2(
+    app: AppHandle,
+    scheduler: State<'_, Scheduler>,
+    task_id: String,
+) -> IpcResult<()> {
+    metrics::track("run_task_now_v2", async {
+        scheduler
+            .run_now(&app, task_id.trim())
+            .map_err(|error| IpcError::Validation(error.to_string()).into())
+    })
+    .await
+}
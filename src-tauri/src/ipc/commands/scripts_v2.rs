@@ -0,0 +1,464 @@
+//! Automation scripts, and the hook runner the outbox dispatcher calls after each event.
+
+use serde_json::{Value, json};
+use tauri::{AppHandle, Emitter, Manager, State};
+use uuid::Uuid;
+
+use super::shared::load_project_jliff;
+use crate::db::DbManager;
+use crate::db::types::{
+    AutomationScriptRecord, NewAutomationScriptArgs, NewNoteArgs, NewQaFindingArgs, ProjectBundle,
+    ReplaceQaFindingsArgs,
+};
+use crate::ipc::dto::{
+    AutomationScriptDto, SaveAutomationScriptPayload, ScriptActionDto, ScriptNotificationPayload,
+    ScriptTestResultDto, SetAutomationScriptEnabledPayload, TestAutomationScriptPayload,
+};
+use crate::ipc::error::{IpcError, IpcResult};
+use crate::ipc::events::{
+    PROJECTS_UPDATED, QA_FINDINGS_RAISED, SCRIPT_NOTIFICATION, SEGMENT_CONFIRMED,
+};
+use crate::metrics;
+use crate::scripting::{self, SCRIPT_CHECK_PREFIX, ScriptAction, ScriptHook};
+use crate::settings::SettingsManager;
+
+#[tauri::command]
+pub async fn list_automation_scripts_v2(
+    db: State<'_, DbManager>,
+) -> IpcResult<Vec<AutomationScriptDto>> {
+    metrics::track("list_automation_scripts_v2", async {
+        let records = db.list_automation_scripts().await.map_err(IpcError::from)?;
+        Ok(records.into_iter().map(map_script).collect())
+    })
+    .await
+}
+
+/// Saves a script after checking that it parses. Saving clears the error of the last run.
+#[tauri::command]
+pub async fn save_automation_script_v2(
+    db: State<'_, DbManager>,
+    payload: SaveAutomationScriptPayload,
+) -> IpcResult<AutomationScriptDto> {
+    metrics::track("save_automation_script_v2", async {
+        let name = payload.name.trim().to_string();
+        if name.is_empty() {
+            return Err(IpcError::Validation("Script name must not be empty.".into()).into());
+        }
+        let hook = parse_hook(&payload.hook)?;
+        scripting::check(&payload.source).map_err(|error| {
+            IpcError::Validation(format!("The script '{name}' does not parse: {error}"))
+        })?;
+        let args = NewAutomationScriptArgs {
+            name,
+            hook: hook.as_str().to_string(),
+            source: payload.source,
+            enabled: payload.enabled.unwrap_or(true),
+        };
+        let record = match payload.script_uuid.as_deref() {
+            Some(script_uuid) => {
+                let script_uuid = parse_uuid(script_uuid, "scriptUuid")?;
+                db.update_automation_script(script_uuid, args)
+                    .await
+                    .map_err(IpcError::from)?
+                    .ok_or_else(|| {
+                        IpcError::Validation(format!("Script {script_uuid} was not found."))
+                    })?
+            }
+            None => db
+                .upsert_automation_script(args)
+                .await
+                .map_err(IpcError::from)?,
+        };
+        Ok(map_script(record))
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn set_automation_script_enabled_v2(
+    db: State<'_, DbManager>,
+    payload: SetAutomationScriptEnabledPayload,
+) -> IpcResult<AutomationScriptDto> {
+    metrics::track("set_automation_script_enabled_v2", async {
+        let script_uuid = parse_uuid(&payload.script_uuid, "scriptUuid")?;
+        let record = db
+            .set_automation_script_enabled(script_uuid, payload.enabled)
+            .await
+            .map_err(IpcError::from)?
+            .ok_or_else(|| IpcError::Validation(format!("Script {script_uuid} was not found.")))?;
+        Ok(map_script(record))
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn delete_automation_script_v2(
+    db: State<'_, DbManager>,
+    script_uuid: String,
+) -> IpcResult<bool> {
+    metrics::track("delete_automation_script_v2", async {
+        let script_uuid = parse_uuid(&script_uuid, "scriptUuid")?;
+        let removed = db
+            .delete_automation_script(script_uuid)
+            .await
+            .map_err(IpcError::from)?;
+        Ok(removed)
+    })
+    .await
+}
+
+/// Runs a script against a sample event without applying what it asks for.
+#[tauri::command]
+pub async fn test_automation_script_v2(
+    payload: TestAutomationScriptPayload,
+) -> IpcResult<ScriptTestResultDto> {
+    metrics::track("test_automation_script_v2", async {
+        let hook = parse_hook(&payload.hook)?;
+        let event = if payload.event.is_null() {
+            json!({})
+        } else {
+            payload.event
+        };
+        let result = match scripting::run(&payload.source, hook, &event) {
+            Ok(outcome) => ScriptTestResultDto {
+                actions: outcome.actions.iter().map(map_action).collect(),
+                logs: outcome.logs,
+                error: None,
+            },
+            Err(error) => ScriptTestResultDto {
+                actions: Vec::new(),
+                logs: Vec::new(),
+                error: Some(error.to_string()),
+            },
+        };
+        Ok(result)
+    })
+    .await
+}
+
+/// Where a script event came from, for applying the actions of its scripts.
+struct HookContext {
+    project: ProjectBundle,
+    jliff_rel_path: Option<String>,
+    transunit_id: Option<String>,
+    finding_uuid: Option<Uuid>,
+}
+
+/// Runs the enabled scripts of the hook an outbox event belongs to and applies what they
+/// ask for. A failing script does not stop the others; its error is stored on the script.
+pub async fn run_script_hooks(app: &AppHandle, topic: &str, payload: &Value) {
+    let Some(hook) = hook_for_event(topic, payload) else {
+        return;
+    };
+    let db = app.state::<DbManager>();
+    let scripts = match db.list_enabled_automation_scripts(hook.as_str()).await {
+        Ok(scripts) => scripts,
+        Err(error) => {
+            log::warn!(target: "ipc::scripts", "failed to list {} scripts: {error}", hook.as_str());
+            return;
+        }
+    };
+    if scripts.is_empty() {
+        return;
+    }
+    let settings = app.state::<SettingsManager>();
+    let events = match hook_events(&db, &settings, hook, payload).await {
+        Ok(events) => events,
+        Err(error) => {
+            log::warn!(
+                target: "ipc::scripts",
+                "skipped the {} scripts for a {topic} event: {error}",
+                hook.as_str()
+            );
+            return;
+        }
+    };
+
+    for (context, event) in &events {
+        for script in &scripts {
+            let error = match scripting::run(&script.source, hook, event) {
+                Ok(outcome) => apply_actions(app, &db, script, context, outcome.actions)
+                    .await
+                    .err()
+                    .map(|error| error.to_string()),
+                Err(error) => Some(error.to_string()),
+            };
+            if let Some(error) = error.as_deref() {
+                log::warn!(
+                    target: "ipc::scripts",
+                    "automation script '{}' failed: {error}",
+                    script.name
+                );
+            }
+            if let Err(error) = db
+                .record_automation_script_run(script.script_uuid, error.as_deref())
+                .await
+            {
+                log::warn!(
+                    target: "ipc::scripts",
+                    "failed to record the run of script '{}': {error}",
+                    script.name
+                );
+            }
+        }
+    }
+}
+
+fn hook_for_event(topic: &str, payload: &Value) -> Option<ScriptHook> {
+    match topic {
+        SEGMENT_CONFIRMED => Some(ScriptHook::SegmentConfirm),
+        PROJECTS_UPDATED if payload["kind"] == "created" => Some(ScriptHook::ProjectCreate),
+        // Findings raised by scripts do not run scripts again.
+        QA_FINDINGS_RAISED
+            if !payload["checkType"]
+                .as_str()
+                .is_some_and(|check| check.starts_with(SCRIPT_CHECK_PREFIX)) =>
+        {
+            Some(ScriptHook::QaFinding)
+        }
+        _ => None,
+    }
+}
+
+/// Builds the `event` maps the scripts of `hook` see for an outbox payload: one per
+/// confirmation or project, one per raised finding.
+async fn hook_events(
+    db: &DbManager,
+    settings: &SettingsManager,
+    hook: ScriptHook,
+    payload: &Value,
+) -> Result<Vec<(HookContext, Value)>, IpcError> {
+    let project_key = match hook {
+        ScriptHook::ProjectCreate => "projectId",
+        ScriptHook::SegmentConfirm | ScriptHook::QaFinding => "projectUuid",
+    };
+    let project_uuid = parse_uuid(
+        payload[project_key].as_str().unwrap_or_default(),
+        project_key,
+    )?;
+    let project = db
+        .get_project_bundle(project_uuid)
+        .await
+        .map_err(IpcError::from)?
+        .ok_or_else(|| IpcError::Validation(format!("Project {project_uuid} was not found.")))?;
+    let jliff_rel_path = payload["jliffRelPath"].as_str().map(str::to_string);
+
+    let events = match hook {
+        ScriptHook::ProjectCreate => {
+            let event = json!({
+                "project_uuid": project_uuid.to_string(),
+                "name": project.project.project_name,
+                "type": project.project.r#type,
+                "project_number": project.project.project_number,
+                "due_date": project.project.due_date,
+                "subjects": project
+                    .subjects
+                    .iter()
+                    .map(|subject| subject.subject.clone())
+                    .collect::<Vec<_>>(),
+                "language_pairs": project
+                    .language_pairs
+                    .iter()
+                    .map(|pair| json!({ "source": pair.source_lang, "target": pair.target_lang }))
+                    .collect::<Vec<_>>(),
+            });
+            vec![(context(project, None, None, None), event)]
+        }
+        ScriptHook::SegmentConfirm => {
+            let jliff_rel_path = jliff_rel_path.unwrap_or_default();
+            let transunit_id = payload["transunitId"].as_str().unwrap_or_default();
+            let (_, document) =
+                load_project_jliff(db, settings, project_uuid, &jliff_rel_path).await?;
+            let unit = document
+                .transunits
+                .iter()
+                .find(|unit| unit.transunit_id == transunit_id)
+                .ok_or_else(|| {
+                    IpcError::Validation(format!(
+                        "Segment '{transunit_id}' is not in '{jliff_rel_path}'."
+                    ))
+                })?;
+            let event = json!({
+                "project_uuid": project_uuid.to_string(),
+                "project_name": project.project.project_name,
+                "jliff_rel_path": jliff_rel_path,
+                "transunit_id": unit.transunit_id,
+                "source": unit.source,
+                "target": unit.effective_target(),
+                "status": unit.effective_status().as_str(),
+                "source_language": document.source_language,
+                "target_language": document.target_language,
+                "word_count": payload["wordCount"],
+                "user_uuid": payload["userUuid"],
+            });
+            let transunit_id = unit.transunit_id.clone();
+            vec![(
+                context(project, Some(jliff_rel_path), Some(transunit_id), None),
+                event,
+            )]
+        }
+        ScriptHook::QaFinding => {
+            let findings = payload["findings"].as_array().cloned().unwrap_or_default();
+            let mut events = Vec::with_capacity(findings.len());
+            for finding in findings {
+                let finding_uuid = parse_uuid(
+                    finding["findingUuid"].as_str().unwrap_or_default(),
+                    "findingUuid",
+                )?;
+                let transunit_id = finding["transunitId"].as_str().map(str::to_string);
+                let event = json!({
+                    "project_uuid": project_uuid.to_string(),
+                    "project_name": project.project.project_name,
+                    "jliff_rel_path": jliff_rel_path,
+                    "check_type": payload["checkType"],
+                    "finding_uuid": finding_uuid.to_string(),
+                    "transunit_id": transunit_id,
+                    "severity": finding["severity"],
+                    "message": finding["message"],
+                });
+                events.push((
+                    context(
+                        project.clone(),
+                        jliff_rel_path.clone(),
+                        transunit_id,
+                        Some(finding_uuid),
+                    ),
+                    event,
+                ));
+            }
+            events
+        }
+    };
+    Ok(events)
+}
+
+fn context(
+    project: ProjectBundle,
+    jliff_rel_path: Option<String>,
+    transunit_id: Option<String>,
+    finding_uuid: Option<Uuid>,
+) -> HookContext {
+    HookContext {
+        project,
+        jliff_rel_path,
+        transunit_id,
+        finding_uuid,
+    }
+}
+
+async fn apply_actions(
+    app: &AppHandle,
+    db: &DbManager,
+    script: &AutomationScriptRecord,
+    context: &HookContext,
+    actions: Vec<ScriptAction>,
+) -> Result<(), IpcError> {
+    let project_uuid = context.project.project.project_uuid;
+    let mut flags = Vec::new();
+    for action in actions {
+        match action {
+            ScriptAction::Notify { message } => {
+                let payload = ScriptNotificationPayload {
+                    script_uuid: script.script_uuid.to_string(),
+                    script_name: script.name.clone(),
+                    message,
+                    project_uuid: Some(project_uuid.to_string()),
+                };
+                if let Err(error) = app.emit(SCRIPT_NOTIFICATION, payload) {
+                    log::warn!(target: "ipc::scripts", "failed to emit a script notification: {error}");
+                }
+            }
+            ScriptAction::AddNote { body } => {
+                db.insert_note(NewNoteArgs {
+                    project_id: project_uuid,
+                    author_user_id: context.project.project.user_uuid.to_string(),
+                    body,
+                    is_markdown: false,
+                    pinned: false,
+                })
+                .await
+                .map_err(IpcError::from)?;
+            }
+            ScriptAction::Flag { message, severity } => flags.push((message, severity)),
+            ScriptAction::ResolveFinding => {
+                if let Some(finding_uuid) = context.finding_uuid {
+                    db.set_qa_finding_resolved(finding_uuid, true)
+                        .await
+                        .map_err(IpcError::from)?;
+                }
+            }
+        }
+    }
+
+    // The flags of a run replace those the script raised on the segment before, so a
+    // segment fixed and confirmed again loses them.
+    if let (Some(jliff_rel_path), Some(transunit_id)) = (
+        context.jliff_rel_path.as_ref(),
+        context.transunit_id.as_ref(),
+    ) && context.finding_uuid.is_none()
+    {
+        db.replace_qa_findings(ReplaceQaFindingsArgs {
+            project_uuid,
+            jliff_rel_path: jliff_rel_path.clone(),
+            check_type: format!("{SCRIPT_CHECK_PREFIX}{}", script.name),
+            transunit_ids: Some(vec![transunit_id.clone()]),
+            findings: flags
+                .into_iter()
+                .map(|(message, severity)| NewQaFindingArgs {
+                    transunit_id: transunit_id.clone(),
+                    severity,
+                    message,
+                    details: None,
+                })
+                .collect(),
+        })
+        .await
+        .map_err(IpcError::from)?;
+    }
+    Ok(())
+}
+
+fn parse_hook(value: &str) -> Result<ScriptHook, IpcError> {
+    ScriptHook::parse(value).ok_or_else(|| {
+        let known: Vec<&str> = ScriptHook::ALL.iter().map(|hook| hook.as_str()).collect();
+        IpcError::Validation(format!(
+            "Unknown script hook '{value}'; use one of {}.",
+            known.join(", ")
+        ))
+    })
+}
+
+fn map_script(record: AutomationScriptRecord) -> AutomationScriptDto {
+    AutomationScriptDto {
+        script_uuid: record.script_uuid.to_string(),
+        name: record.name,
+        hook: record.hook,
+        source: record.source,
+        enabled: record.enabled,
+        last_run_at: record.last_run_at,
+        last_error: record.last_error,
+        created_at: record.created_at,
+        updated_at: record.updated_at,
+    }
+}
+
+fn map_action(action: &ScriptAction) -> ScriptActionDto {
+    let (kind, message, severity) = match action {
+        ScriptAction::Notify { message } => ("notify", Some(message.clone()), None),
+        ScriptAction::AddNote { body } => ("add_note", Some(body.clone()), None),
+        ScriptAction::Flag { message, severity } => {
+            ("flag", Some(message.clone()), Some(severity.clone()))
+        }
+        ScriptAction::ResolveFinding => ("resolve", None, None),
+    };
+    ScriptActionDto {
+        kind: kind.to_string(),
+        message,
+        severity,
+    }
+}
+
+fn parse_uuid(value: &str, field: &str) -> Result<Uuid, IpcError> {
+    Uuid::parse_str(value)
+        .map_err(|_| IpcError::Validation(format!("invalid {field}: expected UUID, got '{value}'")))
+}
@@ -0,0 +1,194 @@
+use std::collections::HashMap;
+
+use tauri::State;
+use uuid::Uuid;
+
+use super::shared::load_project_jliff;
+use crate::db::DbManager;
+use crate::db::types::{NewSegmentReferenceArgs, ProjectFileRecord, SegmentReferenceRecord};
+use crate::ipc::dto::{
+    CreateSegmentReferencePayload, ListSegmentReferencesPayload, SegmentReferenceDto,
+    UpdateSegmentReferencePayload,
+};
+use crate::ipc::error::{IpcError, IpcResult};
+use crate::metrics;
+use crate::settings::SettingsManager;
+
+/// Project file roles that can be linked to segments.
+const REFERENCE_ROLES: [&str; 4] = ["reference", "image", "instructions", "ocr"];
+
+/// Links a reference asset of the project to a transunit of one of its JLIFF documents.
+#[tauri::command]
+pub async fn create_segment_reference_v2(
+    db: State<'_, DbManager>,
+    settings: State<'_, SettingsManager>,
+    payload: CreateSegmentReferencePayload,
+) -> IpcResult<SegmentReferenceDto> {
+    metrics::track("create_segment_reference_v2", async {
+        let project_uuid = parse_uuid(&payload.project_uuid, "projectUuid")?;
+        let file_uuid = parse_uuid(&payload.file_uuid, "fileUuid")?;
+        let files = project_files(&db, project_uuid).await?;
+        let file = files.get(&file_uuid).ok_or_else(|| {
+            IpcError::Validation(format!("File {file_uuid} is not part of the project."))
+        })?;
+        if !REFERENCE_ROLES.contains(&file.r#type.as_str()) {
+            return Err(IpcError::Validation(format!(
+                "'{}' is a {} file and cannot be linked as a reference.",
+                file.filename, file.r#type
+            ))
+            .into());
+        }
+
+        let (_, document) = load_project_jliff(
+            db.inner(),
+            settings.inner(),
+            project_uuid,
+            &payload.jliff_rel_path,
+        )
+        .await?;
+        if !document
+            .transunits
+            .iter()
+            .any(|unit| unit.transunit_id == payload.transunit_id)
+        {
+            return Err(IpcError::Validation(format!(
+                "Segment '{}' was not found in {}.",
+                payload.transunit_id, payload.jliff_rel_path
+            ))
+            .into());
+        }
+
+        let record = db
+            .create_segment_reference(NewSegmentReferenceArgs {
+                reference_uuid: Uuid::new_v4(),
+                project_uuid,
+                jliff_rel_path: payload.jliff_rel_path,
+                transunit_id: payload.transunit_id,
+                file_uuid,
+                anchor: normalize_text(payload.anchor),
+                note: normalize_text(payload.note),
+            })
+            .await
+            .map_err(IpcError::from)?;
+        Ok(map_segment_reference(record, &files))
+    })
+    .await
+}
+
+/// Lists the references of a document, or of one segment, in the order they were linked.
+#[tauri::command]
+pub async fn list_segment_references_v2(
+    db: State<'_, DbManager>,
+    payload: ListSegmentReferencesPayload,
+) -> IpcResult<Vec<SegmentReferenceDto>> {
+    metrics::track("list_segment_references_v2", async {
+        let project_uuid = parse_uuid(&payload.project_uuid, "projectUuid")?;
+        let records = db
+            .list_segment_references(
+                project_uuid,
+                &payload.jliff_rel_path,
+                payload.transunit_id.as_deref(),
+            )
+            .await
+            .map_err(IpcError::from)?;
+        let files = project_files(&db, project_uuid).await?;
+        Ok(records
+            .into_iter()
+            .map(|record| map_segment_reference(record, &files))
+            .collect())
+    })
+    .await
+}
+
+/// Replaces the anchor and note of a reference; blank values clear them.
+#[tauri::command]
+pub async fn update_segment_reference_v2(
+    db: State<'_, DbManager>,
+    payload: UpdateSegmentReferencePayload,
+) -> IpcResult<SegmentReferenceDto> {
+    metrics::track("update_segment_reference_v2", async {
+        let reference_uuid = parse_uuid(&payload.reference_uuid, "referenceUuid")?;
+        let anchor = normalize_text(payload.anchor);
+        let note = normalize_text(payload.note);
+        let record = db
+            .update_segment_reference(reference_uuid, anchor.as_deref(), note.as_deref())
+            .await
+            .map_err(IpcError::from)?
+            .ok_or_else(|| {
+                IpcError::Validation(format!("Segment reference {reference_uuid} was not found."))
+            })?;
+        let files = project_files(&db, record.project_uuid).await?;
+        Ok(map_segment_reference(record, &files))
+    })
+    .await
+}
+
+/// Unlinks a reference from its segment. The asset stays in the project.
+#[tauri::command]
+pub async fn delete_segment_reference_v2(
+    db: State<'_, DbManager>,
+    reference_uuid: String,
+) -> IpcResult<()> {
+    metrics::track("delete_segment_reference_v2", async {
+        let reference_uuid = parse_uuid(&reference_uuid, "referenceUuid")?;
+        if !db
+            .delete_segment_reference(reference_uuid)
+            .await
+            .map_err(IpcError::from)?
+        {
+            return Err(IpcError::Validation(format!(
+                "Segment reference {reference_uuid} was not found."
+            ))
+            .into());
+        }
+        Ok(())
+    })
+    .await
+}
+
+async fn project_files(
+    db: &DbManager,
+    project_uuid: Uuid,
+) -> Result<HashMap<Uuid, ProjectFileRecord>, IpcError> {
+    let bundle = db
+        .get_project_bundle(project_uuid)
+        .await
+        .map_err(IpcError::from)?
+        .ok_or_else(|| IpcError::Validation(format!("Project {project_uuid} was not found.")))?;
+    Ok(bundle
+        .files
+        .into_iter()
+        .map(|file| (file.link.file_uuid, file.link))
+        .collect())
+}
+
+fn normalize_text(value: Option<String>) -> Option<String> {
+    value
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+fn map_segment_reference(
+    record: SegmentReferenceRecord,
+    files: &HashMap<Uuid, ProjectFileRecord>,
+) -> SegmentReferenceDto {
+    let file = files.get(&record.file_uuid);
+    SegmentReferenceDto {
+        reference_uuid: record.reference_uuid.to_string(),
+        jliff_rel_path: record.jliff_rel_path,
+        transunit_id: record.transunit_id,
+        file_uuid: record.file_uuid.to_string(),
+        filename: file.map(|file| file.filename.clone()).unwrap_or_default(),
+        stored_at: file.map(|file| file.stored_at.clone()).unwrap_or_default(),
+        role: file.map(|file| file.r#type.clone()).unwrap_or_default(),
+        anchor: record.anchor,
+        note: record.note,
+        created_at: record.created_at,
+        updated_at: record.updated_at,
+    }
+}
+
+fn parse_uuid(value: &str, field: &str) -> Result<Uuid, IpcError> {
+    Uuid::parse_str(value)
+        .map_err(|_| IpcError::Validation(format!("invalid {field}: expected UUID, got '{value}'")))
+}
@@ -0,0 +1,189 @@
+use std::collections::{BTreeMap, HashSet};
+use std::path::PathBuf;
+
+use tauri::State;
+use uuid::Uuid;
+
+use super::projects_v2::resolve_project_root;
+use super::shared::{
+    load_project_jliff, resolve_project_relative_path, update_project_jliff, with_project_file_lock,
+};
+use crate::db::DbManager;
+use crate::ipc::dto::{
+    FileStatisticsDto, FileStatisticsPayload, SegmentStatusUpdateDto, SyncJliffToXliffPayload,
+    UpdateSegmentStatusPayload, XliffSyncDto,
+};
+use crate::ipc::error::{IpcError, IpcResult};
+use crate::jliff::JliffDocument;
+use crate::jliff::document::{read_tag_map, tag_map_path_for};
+use crate::jliff::model::SegmentStatus;
+use crate::jliff::xliff_sync::{XliffSyncSummary, sync_xliff_from_jliff};
+use crate::metrics;
+use crate::settings::SettingsManager;
+
+/// Sets the workflow status of one or more segments. The previous `subState` is dropped
+/// unless a new one is given, since it refined the old status.
+#[tauri::command]
+pub async fn update_segment_status_v2(
+    db: State<'_, DbManager>,
+    settings: State<'_, SettingsManager>,
+    payload: UpdateSegmentStatusPayload,
+) -> IpcResult<SegmentStatusUpdateDto> {
+    metrics::track("update_segment_status_v2", async {
+        let project_uuid = parse_uuid(&payload.project_uuid, "projectUuid")?;
+        let status = SegmentStatus::parse(&payload.status).ok_or_else(|| {
+            IpcError::Validation(format!("unknown segment status '{}'", payload.status))
+        })?;
+        let sub_state = payload
+            .sub_state
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty());
+        let requested: HashSet<String> = payload.transunit_ids.iter().cloned().collect();
+
+        let result = update_project_jliff(
+            db.inner(),
+            settings.inner(),
+            project_uuid,
+            &payload.jliff_rel_path,
+            move |document| {
+                let mut found = HashSet::new();
+                for unit in document
+                    .transunits
+                    .iter_mut()
+                    .filter(|unit| requested.contains(&unit.transunit_id))
+                {
+                    unit.status = Some(status);
+                    unit.sub_state = sub_state.clone();
+                    found.insert(unit.transunit_id.clone());
+                }
+                let mut missing_transunit_ids: Vec<String> =
+                    requested.difference(&found).cloned().collect();
+                missing_transunit_ids.sort();
+                Ok(SegmentStatusUpdateDto {
+                    updated: found.len(),
+                    missing_transunit_ids,
+                })
+            },
+        )
+        .await?;
+        Ok(result)
+    })
+    .await
+}
+
+/// Copies segment statuses and unit notes from a JLIFF document onto its source XLIFF so
+/// the merge step carries them into the delivered file.
+#[tauri::command]
+pub async fn sync_jliff_to_xliff_v2(
+    db: State<'_, DbManager>,
+    settings: State<'_, SettingsManager>,
+    payload: SyncJliffToXliffPayload,
+) -> IpcResult<XliffSyncDto> {
+    metrics::track("sync_jliff_to_xliff_v2", async {
+        let project_uuid = parse_uuid(&payload.project_uuid, "projectUuid")?;
+        let (jliff_path, document) = load_project_jliff(
+            db.inner(),
+            settings.inner(),
+            project_uuid,
+            &payload.jliff_rel_path,
+        )
+        .await?;
+
+        let project_root = resolve_project_root(db.inner(), settings.inner(), project_uuid).await?;
+        let xliff_path = resolve_project_relative_path(&project_root, &payload.xliff_rel_path)?;
+
+        let summary =
+            sync_project_xliff(xliff_path, &payload.xliff_rel_path, jliff_path, document).await?;
+
+        Ok(XliffSyncDto {
+            xliff_rel_path: payload.xliff_rel_path,
+            updated_segments: summary.updated_segments,
+            updated_units: summary.updated_units,
+            restructured_units: summary.restructured_units,
+        })
+    })
+    .await
+}
+
+/// Copies the statuses, notes and segment structure of `document`, read from `jliff_path`,
+/// onto the XLIFF at `xliff_path` under its file lock.
+pub(super) async fn sync_project_xliff(
+    xliff_path: PathBuf,
+    xliff_rel_path: &str,
+    jliff_path: PathBuf,
+    document: JliffDocument,
+) -> Result<XliffSyncSummary, IpcError> {
+    with_project_file_lock(&xliff_path, || async {
+        let path = xliff_path.clone();
+        tokio::task::spawn_blocking(move || {
+            // Only joined or split segments need the tag map.
+            let tag_map = match tag_map_path_for(&jliff_path)
+                .filter(|_| !document.segment_operations.is_empty())
+            {
+                Some(tag_map_path) => Some(read_tag_map(&tag_map_path)?),
+                None => None,
+            };
+            sync_xliff_from_jliff(&path, &document, tag_map.as_ref())
+        })
+        .await
+        .map_err(|join_err| IpcError::Internal(format!("Failed to update XLIFF: {join_err}")))?
+        .map_err(|error| {
+            log::error!(
+                target: "ipc::segment_status",
+                "unable to sync JLIFF data to {}: {error:#}",
+                xliff_path.display()
+            );
+            IpcError::Validation(format!(
+                "Segment data could not be written to '{xliff_rel_path}'."
+            ))
+        })
+    })
+    .await
+}
+
+/// Returns per-file segment statistics, including how segments are distributed across
+/// statuses (as imported from upstream tools or set in the editor).
+#[tauri::command]
+pub async fn get_file_statistics_v2(
+    db: State<'_, DbManager>,
+    settings: State<'_, SettingsManager>,
+    payload: FileStatisticsPayload,
+) -> IpcResult<FileStatisticsDto> {
+    metrics::track("get_file_statistics_v2", async {
+        let project_uuid = parse_uuid(&payload.project_uuid, "projectUuid")?;
+        let (_, document) = load_project_jliff(
+            db.inner(),
+            settings.inner(),
+            project_uuid,
+            &payload.jliff_rel_path,
+        )
+        .await?;
+
+        let status_distribution = document
+            .status_distribution()
+            .into_iter()
+            .map(|(status, count)| (status.as_str().to_string(), count))
+            .collect();
+        let mut sub_state_distribution = BTreeMap::new();
+        for sub_state in document
+            .transunits
+            .iter()
+            .filter_map(|unit| unit.sub_state.as_ref())
+        {
+            *sub_state_distribution.entry(sub_state.clone()).or_default() += 1;
+        }
+
+        Ok(FileStatisticsDto {
+            jliff_rel_path: payload.jliff_rel_path,
+            segment_count: document.transunits.len(),
+            status_distribution,
+            sub_state_distribution,
+        })
+    })
+    .await
+}
+
+fn parse_uuid(value: &str, field: &str) -> Result<Uuid, IpcError> {
+    Uuid::parse_str(value)
+        .map_err(|_| IpcError::Validation(format!("invalid {field}: expected UUID, got '{value}'")))
+}
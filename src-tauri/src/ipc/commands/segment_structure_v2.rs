@@ -0,0 +1,133 @@
+//! Joining and splitting segments. The JLIFF document and its tag map change together under
+//! the document's file lock, and the XLIFF follows on the next sync.
+
+use log::{error, warn};
+use tauri::State;
+use uuid::Uuid;
+
+use super::projects_v2::resolve_project_root;
+use super::shared::{resolve_project_relative_path, update_project_jliff};
+use crate::db::DbManager;
+use crate::ipc::dto::{MergeSegmentsPayload, SegmentStructureDto, SplitSegmentPayload};
+use crate::ipc::error::{IpcError, IpcResult};
+use crate::jliff::JliffDocument;
+use crate::jliff::document::{read_tag_map, tag_map_path_for, write_tag_map};
+use crate::jliff::restructure::{merge_segments, split_segment};
+use crate::jliff::tag_map::TagMapDoc;
+use crate::metrics;
+use crate::settings::SettingsManager;
+
+#[tauri::command]
+pub async fn split_segment_v2(
+    db: State<'_, DbManager>,
+    settings: State<'_, SettingsManager>,
+    payload: SplitSegmentPayload,
+) -> IpcResult<SegmentStructureDto> {
+    metrics::track("split_segment_v2", async {
+        let project_uuid = parse_uuid(&payload.project_uuid, "projectUuid")?;
+        let transunit_id = payload.transunit_id;
+        let offset = payload.offset;
+        restructure(
+            db.inner(),
+            settings.inner(),
+            project_uuid,
+            &payload.jliff_rel_path,
+            move |document, tag_map| split_segment(document, tag_map, &transunit_id, offset),
+        )
+        .await
+        .map_err(Into::into)
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn merge_segments_v2(
+    db: State<'_, DbManager>,
+    settings: State<'_, SettingsManager>,
+    payload: MergeSegmentsPayload,
+) -> IpcResult<SegmentStructureDto> {
+    metrics::track("merge_segments_v2", async {
+        let project_uuid = parse_uuid(&payload.project_uuid, "projectUuid")?;
+        let transunit_ids = payload.transunit_ids;
+        restructure(
+            db.inner(),
+            settings.inner(),
+            project_uuid,
+            &payload.jliff_rel_path,
+            move |document, tag_map| merge_segments(document, tag_map, &transunit_ids),
+        )
+        .await
+        .map_err(Into::into)
+    })
+    .await
+}
+
+/// Applies `operation` to a document and its tag map, returning the segment it reports.
+async fn restructure<F>(
+    db: &DbManager,
+    settings: &SettingsManager,
+    project_uuid: Uuid,
+    jliff_rel_path: &str,
+    operation: F,
+) -> Result<SegmentStructureDto, IpcError>
+where
+    F: FnOnce(&mut JliffDocument, &mut TagMapDoc) -> anyhow::Result<String> + Send + 'static,
+{
+    let project_root = resolve_project_root(db, settings, project_uuid).await?;
+    let jliff_path = resolve_project_relative_path(&project_root, jliff_rel_path)?;
+    let tag_map_path = tag_map_path_for(&jliff_path).ok_or_else(|| {
+        IpcError::Validation(format!(
+            "'{jliff_rel_path}' is not a JLIFF artifact with an associated tag map."
+        ))
+    })?;
+    let rel_path = jliff_rel_path.to_string();
+    update_project_jliff(
+        db,
+        settings,
+        project_uuid,
+        jliff_rel_path,
+        move |document| {
+            let mut tag_map = read_tag_map(&tag_map_path).map_err(|error| {
+                warn!(
+                    target: "ipc::segment_structure",
+                    "unable to load tag map {}: {error:#}",
+                    tag_map_path.display()
+                );
+                IpcError::Validation(format!("The tag map for '{rel_path}' could not be opened."))
+            })?;
+            let transunit_id = operation(document, &mut tag_map)
+                .map_err(|error| IpcError::Validation(format!("{error:#}")))?;
+            write_tag_map(&tag_map_path, &tag_map).map_err(|error| {
+                error!(
+                    target: "ipc::segment_structure",
+                    "unable to write tag map {}: {error:#}",
+                    tag_map_path.display()
+                );
+                IpcError::Internal(format!("The tag map for '{rel_path}' could not be saved."))
+            })?;
+
+            let unit_id = document
+                .transunits
+                .iter()
+                .find(|unit| unit.transunit_id == transunit_id)
+                .map(|unit| unit.unit_id.clone())
+                .unwrap_or_default();
+            Ok(SegmentStructureDto {
+                jliff_rel_path: rel_path,
+                unit_transunit_ids: document
+                    .transunits
+                    .iter()
+                    .filter(|unit| unit.unit_id == unit_id)
+                    .map(|unit| unit.transunit_id.clone())
+                    .collect(),
+                transunit_id,
+            })
+        },
+    )
+    .await
+}
+
+fn parse_uuid(value: &str, field: &str) -> Result<Uuid, IpcError> {
+    Uuid::parse_str(value)
+        .map_err(|_| IpcError::Validation(format!("invalid {field}: expected UUID, got '{value}'")))
+}
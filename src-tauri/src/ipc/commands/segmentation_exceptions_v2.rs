@@ -0,0 +1,199 @@
+//! Abbreviation lists and break or no-break exceptions per source language, kept for a
+//! project or for the whole workspace. They are written into the SRX rules handed to the
+//! converter ahead of the default rules.
+
+use tauri::State;
+use uuid::Uuid;
+
+use crate::db::DbManager;
+use crate::db::types::{NewSegmentationExceptionArgs, SegmentationExceptionRecord};
+use crate::ipc::dto::{
+    CreateSegmentationExceptionPayload, PreviewSegmentationPayload, SegmentationExceptionDto,
+    SegmentationPreviewDto,
+};
+use crate::ipc::error::{IpcError, IpcResult};
+use crate::jliff::segmentation::{self, CustomRule};
+use crate::metrics;
+
+const EXCEPTION_KINDS: [&str; 3] = ["abbreviation", "no_break", "break"];
+
+/// Lists the exceptions of a project, or the global ones when no project is given.
+#[tauri::command]
+pub async fn list_segmentation_exceptions_v2(
+    db: State<'_, DbManager>,
+    project_uuid: Option<String>,
+) -> IpcResult<Vec<SegmentationExceptionDto>> {
+    metrics::track("list_segmentation_exceptions_v2", async {
+        let project_uuid = project_uuid
+            .as_deref()
+            .map(|value| parse_uuid(value, "projectUuid"))
+            .transpose()?;
+        let records = db
+            .list_segmentation_exceptions(project_uuid)
+            .await
+            .map_err(IpcError::from)?;
+        Ok(records.into_iter().map(map_exception_record).collect())
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn create_segmentation_exception_v2(
+    db: State<'_, DbManager>,
+    payload: CreateSegmentationExceptionPayload,
+) -> IpcResult<SegmentationExceptionDto> {
+    metrics::track("create_segmentation_exception_v2", async {
+        let project_uuid = payload
+            .project_uuid
+            .as_deref()
+            .map(|value| parse_uuid(value, "projectUuid"))
+            .transpose()?;
+        let args = validated_exception(
+            project_uuid,
+            &payload.language,
+            &payload.kind,
+            payload.before_text,
+            payload.after_text,
+        )?;
+        let record = db
+            .create_segmentation_exception(args)
+            .await
+            .map_err(IpcError::from)?;
+        Ok(map_exception_record(record))
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn delete_segmentation_exception_v2(
+    db: State<'_, DbManager>,
+    exception_uuid: String,
+) -> IpcResult<()> {
+    metrics::track("delete_segmentation_exception_v2", async {
+        let exception_uuid = parse_uuid(&exception_uuid, "exceptionUuid")?;
+        db.delete_segmentation_exception(exception_uuid)
+            .await
+            .map_err(IpcError::from)?;
+        Ok(())
+    })
+    .await
+}
+
+/// Splits `sampleText` with the exceptions applying to `language` in the project (or only
+/// the global ones) followed by the default rules.
+#[tauri::command]
+pub async fn preview_segmentation_v2(
+    db: State<'_, DbManager>,
+    payload: PreviewSegmentationPayload,
+) -> IpcResult<SegmentationPreviewDto> {
+    metrics::track("preview_segmentation_v2", async {
+        let project_uuid = payload
+            .project_uuid
+            .as_deref()
+            .map(|value| parse_uuid(value, "projectUuid"))
+            .transpose()?;
+        let rules = exception_rules(&db, project_uuid, &payload.language).await?;
+        let sentences =
+            segmentation::split_sentences_with(&payload.sample_text, &payload.language, &rules)
+                .map_err(|error| IpcError::Validation(format!("{error:#}")))?;
+        Ok(SegmentationPreviewDto {
+            sentences: sentences.into_iter().map(str::to_string).collect(),
+            exception_count: rules.len(),
+        })
+    })
+    .await
+}
+
+/// The exceptions applying to `language` in a project, as rules in the order they apply.
+pub(super) async fn exception_rules(
+    db: &DbManager,
+    project_uuid: Option<Uuid>,
+    language: &str,
+) -> Result<Vec<CustomRule>, IpcError> {
+    let records = db
+        .segmentation_exceptions_for_language(project_uuid, language)
+        .await
+        .map_err(IpcError::from)?;
+    Ok(records
+        .iter()
+        .map(|record| custom_rule(&record.kind, &record.before_text, &record.after_text))
+        .collect())
+}
+
+/// Checks an exception and normalizes its language and texts: abbreviations must be a
+/// single word, and the patterns of the other kinds must compile.
+pub(super) fn validated_exception(
+    project_uuid: Option<Uuid>,
+    language: &str,
+    kind: &str,
+    before_text: String,
+    after_text: String,
+) -> Result<NewSegmentationExceptionArgs, IpcError> {
+    let language = language.trim().replace('_', "-");
+    if language.is_empty() {
+        return Err(IpcError::Validation("language cannot be empty".into()));
+    }
+    let kind = kind.trim();
+    if !EXCEPTION_KINDS.contains(&kind) {
+        return Err(IpcError::Validation(format!(
+            "unknown exception kind '{kind}'. Expected one of: {}.",
+            EXCEPTION_KINDS.join(", ")
+        )));
+    }
+    let (before_text, after_text) = if kind == "abbreviation" {
+        let abbreviation = before_text.trim();
+        if abbreviation.is_empty() || abbreviation.contains(char::is_whitespace) {
+            return Err(IpcError::Validation(
+                "an abbreviation must be a single word, such as 'z.B.'".into(),
+            ));
+        }
+        (abbreviation.to_string(), String::new())
+    } else {
+        (before_text, after_text)
+    };
+    let rule = custom_rule(kind, &before_text, &after_text);
+    if rule.before.is_empty() {
+        return Err(IpcError::Validation(
+            "the text before the break cannot be empty".into(),
+        ));
+    }
+    segmentation::split_sentences_with("", &language, &[rule])
+        .map_err(|error| IpcError::Validation(format!("{error:#}")))?;
+
+    Ok(NewSegmentationExceptionArgs {
+        exception_uuid: Uuid::new_v4(),
+        project_uuid,
+        language,
+        kind: kind.to_string(),
+        before_text,
+        after_text,
+    })
+}
+
+fn custom_rule(kind: &str, before_text: &str, after_text: &str) -> CustomRule {
+    match kind {
+        "abbreviation" => segmentation::abbreviation_rule(before_text),
+        _ => CustomRule {
+            break_here: kind == "break",
+            before: before_text.to_string(),
+            after: after_text.to_string(),
+        },
+    }
+}
+
+fn map_exception_record(record: SegmentationExceptionRecord) -> SegmentationExceptionDto {
+    SegmentationExceptionDto {
+        exception_uuid: record.exception_uuid.to_string(),
+        project_uuid: record.project_uuid.map(|uuid| uuid.to_string()),
+        language: record.language,
+        kind: record.kind,
+        before_text: record.before_text,
+        after_text: record.after_text,
+        created_at: record.created_at,
+    }
+}
+
+fn parse_uuid(value: &str, field: &str) -> Result<Uuid, IpcError> {
+    Uuid::parse_str(value)
+        .map_err(|_| IpcError::Validation(format!("invalid {field}: expected UUID, got '{value}'")))
+}
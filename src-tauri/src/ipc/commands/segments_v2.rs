@@ -0,0 +1,616 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use regex::RegexBuilder;
+use tauri::State;
+use uuid::Uuid;
+
+use super::length_limits_v2::{LENGTH_LIMIT_CHECK, length_limit_finding};
+use super::projects_v2::resolve_project_root;
+use super::propagation_v2::{parse_scope, propagate_in_scope};
+use super::qa_v2::project_qa_profile;
+use super::shared::{
+    load_cached_tag_map, load_project_jliff, resolve_project_relative_path, update_project_jliff,
+};
+use crate::db::DbManager;
+use crate::db::types::{
+    NewSavedSegmentFilterArgs, NewSegmentConfirmationArgs, NewSegmentRevisionArgs,
+    NewTranslationMemoryEntryArgs, RecordSegmentRevisionsArgs, ReplaceQaFindingsArgs,
+    SavedSegmentFilterRecord,
+};
+use crate::ipc::dto::{
+    ConfirmSegmentDto, ConfirmSegmentPayload, GetSegmentsPayload, PropagationSourceDto,
+    QuerySegmentsPayload, SaveSegmentFilterPayload, SavedSegmentFilterDto, SegmentDto,
+    SegmentFilterDto, SegmentNoteDto, SegmentPageDto,
+};
+use crate::ipc::error::{IpcError, IpcResult};
+use crate::jliff::auto_fix::{AutoFix, apply_auto_fixes};
+use crate::jliff::length_limits::{LengthLimitEnforcement, check_length_limit};
+use crate::jliff::model::{PropagationSource, SegmentStatus, TransUnit, XliffNote};
+use crate::jliff::propagation::{ConfirmedTranslation, repetition_key};
+use crate::jliff::segment_filter::{MatchBand, SegmentFilter};
+use crate::jliff::statistics::{WordCountStrategy, source_word_count};
+use crate::metrics;
+use crate::settings::SettingsManager;
+
+/// Upper bound on segments per page; the editor asks for the visible page only.
+const MAX_SEGMENTS_PER_PAGE: usize = 500;
+
+/// Severities accepted by `qaSeverities`, matching the `qa_findings` check constraint.
+const QA_SEVERITIES: [&str; 3] = ["info", "warning", "critical"];
+
+/// Revision origin recorded for targets confirmed in the editor.
+const EDITOR_ORIGIN: &str = "editor";
+
+/// Returns a page of segments of a JLIFF artifact in document order, together with the
+/// metadata and notes carried over from the XLIFF.
+#[tauri::command]
+pub async fn get_segments_v2(
+    db: State<'_, DbManager>,
+    settings: State<'_, SettingsManager>,
+    payload: GetSegmentsPayload,
+) -> IpcResult<SegmentPageDto> {
+    metrics::track("get_segments_v2", async {
+        let project_uuid = parse_uuid(&payload.project_uuid, "projectUuid")?;
+        let limit = validate_limit(payload.limit)?;
+
+        let (_, document) = load_project_jliff(
+            db.inner(),
+            settings.inner(),
+            project_uuid,
+            &payload.jliff_rel_path,
+        )
+        .await?;
+
+        let requested: Option<HashSet<&str>> = payload
+            .transunit_ids
+            .as_ref()
+            .map(|ids| ids.iter().map(String::as_str).collect());
+        let matching: Vec<&TransUnit> = document
+            .transunits
+            .iter()
+            .filter(|unit| {
+                requested
+                    .as_ref()
+                    .is_none_or(|ids| ids.contains(unit.transunit_id.as_str()))
+            })
+            .collect();
+
+        let reference_counts = db
+            .count_segment_references(project_uuid, &payload.jliff_rel_path)
+            .await
+            .map_err(IpcError::from)?;
+        Ok(page_segments(
+            matching,
+            payload.offset,
+            limit,
+            &reference_counts,
+        ))
+    })
+    .await
+}
+
+/// Returns a page of the segments matching a compound filter, evaluated here so the editor
+/// does not need the whole document to filter.
+#[tauri::command]
+pub async fn query_segments_v2(
+    db: State<'_, DbManager>,
+    settings: State<'_, SettingsManager>,
+    payload: QuerySegmentsPayload,
+) -> IpcResult<SegmentPageDto> {
+    metrics::track("query_segments_v2", async {
+        let project_uuid = parse_uuid(&payload.project_uuid, "projectUuid")?;
+        let limit = validate_limit(payload.limit)?;
+        let filter = build_segment_filter(&payload.filter)?;
+
+        let qa_flagged: Option<(bool, HashSet<String>)> = match payload.filter.has_qa_findings {
+            Some(expected) => {
+                let severities = payload.filter.qa_severities.as_ref();
+                let findings = db
+                    .list_qa_findings(project_uuid, Some(&payload.jliff_rel_path))
+                    .await
+                    .map_err(IpcError::from)?;
+                let flagged = findings
+                    .into_iter()
+                    .filter(|finding| finding.resolved_at.is_none())
+                    .filter(|finding| {
+                        severities.is_none_or(|severities| {
+                            severities
+                                .iter()
+                                .any(|severity| severity.eq_ignore_ascii_case(&finding.severity))
+                        })
+                    })
+                    .map(|finding| finding.transunit_id)
+                    .collect();
+                Some((expected, flagged))
+            }
+            None => None,
+        };
+        let modified: Option<HashSet<String>> = match payload.filter.modified_since.as_deref() {
+            Some(value) => {
+                let since = parse_modified_since(value)?;
+                let ids = db
+                    .list_revised_transunits_since(project_uuid, &payload.jliff_rel_path, &since)
+                    .await
+                    .map_err(IpcError::from)?;
+                Some(ids.into_iter().collect())
+            }
+            None => None,
+        };
+
+        let (_, document) = load_project_jliff(
+            db.inner(),
+            settings.inner(),
+            project_uuid,
+            &payload.jliff_rel_path,
+        )
+        .await?;
+
+        let matching: Vec<&TransUnit> = document
+            .transunits
+            .iter()
+            .filter(|unit| filter.matches(unit))
+            .filter(|unit| {
+                qa_flagged.as_ref().is_none_or(|(expected, flagged)| {
+                    flagged.contains(&unit.transunit_id) == *expected
+                })
+            })
+            .filter(|unit| {
+                modified
+                    .as_ref()
+                    .is_none_or(|ids| ids.contains(&unit.transunit_id))
+            })
+            .collect();
+
+        let reference_counts = db
+            .count_segment_references(project_uuid, &payload.jliff_rel_path)
+            .await
+            .map_err(IpcError::from)?;
+        Ok(page_segments(
+            matching,
+            payload.offset,
+            limit,
+            &reference_counts,
+        ))
+    })
+    .await
+}
+
+/// Saves a named filter for a user; saving under an existing name replaces it.
+#[tauri::command]
+pub async fn save_segment_filter_v2(
+    db: State<'_, DbManager>,
+    payload: SaveSegmentFilterPayload,
+) -> IpcResult<SavedSegmentFilterDto> {
+    metrics::track("save_segment_filter_v2", async {
+        let user_uuid = parse_uuid(&payload.user_uuid, "userUuid")?;
+        let name = payload.name.trim();
+        if name.is_empty() {
+            return Err(IpcError::Validation("Filter name cannot be empty.".into()).into());
+        }
+        build_segment_filter(&payload.filter)?;
+        let filter_json = serde_json::to_string(&payload.filter).map_err(|error| {
+            IpcError::Internal(format!("Failed to serialize segment filter: {error}"))
+        })?;
+
+        let record = db
+            .save_segment_filter(NewSavedSegmentFilterArgs {
+                user_uuid,
+                name: name.to_string(),
+                filter_json,
+            })
+            .await
+            .map_err(IpcError::from)?;
+        map_saved_filter(record).map_err(Into::into)
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn list_saved_segment_filters_v2(
+    db: State<'_, DbManager>,
+    user_uuid: String,
+) -> IpcResult<Vec<SavedSegmentFilterDto>> {
+    metrics::track("list_saved_segment_filters_v2", async {
+        let user_uuid = parse_uuid(&user_uuid, "userUuid")?;
+        let records = db
+            .list_saved_segment_filters(user_uuid)
+            .await
+            .map_err(IpcError::from)?;
+        records
+            .into_iter()
+            .map(map_saved_filter)
+            .collect::<Result<Vec<_>, IpcError>>()
+            .map_err(Into::into)
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn delete_saved_segment_filter_v2(
+    db: State<'_, DbManager>,
+    filter_uuid: String,
+) -> IpcResult<()> {
+    metrics::track("delete_saved_segment_filter_v2", async {
+        let filter_uuid = parse_uuid(&filter_uuid, "filterUuid")?;
+        db.delete_saved_segment_filter(filter_uuid)
+            .await
+            .map_err(IpcError::from)?;
+        Ok(())
+    })
+    .await
+}
+
+/// Stores a translator's target as confirmed, adds it to the translation memory and, unless
+/// disabled, copies it onto the unconfirmed repetitions in the configured propagation scope.
+/// Targets over their length limit are rejected or flagged depending on the configured
+/// enforcement.
+#[tauri::command]
+pub async fn confirm_segment_v2(
+    db: State<'_, DbManager>,
+    settings: State<'_, SettingsManager>,
+    payload: ConfirmSegmentPayload,
+) -> IpcResult<ConfirmSegmentDto> {
+    metrics::track("confirm_segment_v2", async {
+    let project_uuid = parse_uuid(&payload.project_uuid, "projectUuid")?;
+    let user_uuid = payload
+        .user_uuid
+        .as_deref()
+        .map(|value| parse_uuid(value, "userUuid"))
+        .transpose()?;
+    let status = match payload.status.as_deref() {
+        Some(value) => SegmentStatus::parse(value)
+            .filter(|status| status.is_confirmed())
+            .ok_or_else(|| {
+                IpcError::Validation(format!(
+                    "Status '{value}' does not confirm a segment. Use translated, reviewed or approved."
+                ))
+            })?,
+        None => SegmentStatus::Translated,
+    };
+    if payload.target.trim().is_empty() {
+        return Err(IpcError::Validation("A confirmed target cannot be empty.".into()).into());
+    }
+
+    let current = settings.current().await;
+    let propagate = payload
+        .propagate
+        .unwrap_or(current.auto_propagate_repetitions);
+    let scope = parse_scope(&current.propagation_scope)?;
+    let case_sensitive = current.propagation_case_sensitive;
+
+    let profile = project_qa_profile(db.inner(), project_uuid).await?;
+    let auto_fixes: Vec<AutoFix> = AutoFix::ALL
+        .into_iter()
+        .filter(|fix| profile.opted_in(fix.check_type()))
+        .collect();
+    let length_check = profile.check(LENGTH_LIMIT_CHECK, "critical");
+    let enforcement = match LengthLimitEnforcement::parse(&current.length_limit_enforcement) {
+        _ if !length_check.enabled => LengthLimitEnforcement::Off,
+        Some(enforcement) => enforcement,
+        None => LengthLimitEnforcement::Warn,
+    };
+    // Protected terms only count at their real length when the tag map is available.
+    let tag_map = if enforcement == LengthLimitEnforcement::Off {
+        None
+    } else {
+        let project_root = resolve_project_root(db.inner(), settings.inner(), project_uuid).await?;
+        let jliff_path = resolve_project_relative_path(&project_root, &payload.jliff_rel_path)?;
+        load_cached_tag_map(&jliff_path, &payload.jliff_rel_path)
+            .await
+            .ok()
+    };
+
+    let transunit_id = payload.transunit_id.clone();
+    let target = payload.target.clone();
+    let (source, target, applied_fixes, previous_target, languages, word_count, length_issue) =
+        update_project_jliff(
+            db.inner(),
+            settings.inner(),
+            project_uuid,
+            &payload.jliff_rel_path,
+            move |document| {
+                let languages = (
+                    document.source_language.clone(),
+                    document.target_language.clone(),
+                );
+                let unit = document
+                    .transunits
+                    .iter_mut()
+                    .find(|unit| unit.transunit_id == transunit_id)
+                    .ok_or_else(|| {
+                        IpcError::Validation(format!("Segment '{transunit_id}' does not exist."))
+                    })?;
+                let (target, applied_fixes) =
+                    apply_auto_fixes(&unit.source, &target, &languages.1, &auto_fixes);
+                let previous_target = std::mem::replace(unit.effective_target_mut(), target.clone());
+                let length_issue = match enforcement {
+                    LengthLimitEnforcement::Off => None,
+                    _ => {
+                        let segment = tag_map
+                            .as_ref()
+                            .and_then(|tag_map| tag_map.segment_for(&unit.unit_id, &unit.transunit_id));
+                        check_length_limit(unit, segment, profile.max_length_for(&languages.1))
+                    }
+                };
+                if enforcement == LengthLimitEnforcement::Block
+                    && let Some(issue) = &length_issue
+                {
+                    return Err(IpcError::Validation(format!(
+                        "The target is {} characters long; the limit is {}.",
+                        issue.length, issue.max_length
+                    )));
+                }
+                unit.status = Some(status);
+                unit.sub_state = None;
+                unit.propagated_from = None;
+                Ok((
+                    unit.source.clone(),
+                    target,
+                    applied_fixes,
+                    previous_target,
+                    languages,
+                    source_word_count(unit, WordCountStrategy::for_language(&languages.0)),
+                    length_issue,
+                ))
+            },
+        )
+        .await?;
+
+    if previous_target != target {
+        db.record_segment_revisions(RecordSegmentRevisionsArgs {
+            project_uuid,
+            jliff_rel_path: payload.jliff_rel_path.clone(),
+            origin: EDITOR_ORIGIN.to_string(),
+            author: None,
+            revisions: vec![NewSegmentRevisionArgs {
+                transunit_id: payload.transunit_id.clone(),
+                previous_target,
+                new_target: target.clone(),
+            }],
+        })
+        .await
+        .map_err(IpcError::from)?;
+    }
+
+    if enforcement != LengthLimitEnforcement::Off {
+        db.replace_qa_findings(ReplaceQaFindingsArgs {
+            project_uuid,
+            jliff_rel_path: payload.jliff_rel_path.clone(),
+            check_type: LENGTH_LIMIT_CHECK.into(),
+            transunit_ids: Some(vec![payload.transunit_id.clone()]),
+            findings: length_issue
+                .iter()
+                .map(|issue| {
+                    length_limit_finding(&payload.transunit_id, issue, &length_check.severity)
+                })
+                .collect(),
+        })
+        .await
+        .map_err(IpcError::from)?;
+    }
+
+    db.record_segment_confirmation(NewSegmentConfirmationArgs {
+        project_uuid,
+        jliff_rel_path: payload.jliff_rel_path.clone(),
+        transunit_id: payload.transunit_id.clone(),
+        user_uuid,
+        word_count: i64::try_from(word_count).unwrap_or(i64::MAX),
+    })
+    .await
+    .map_err(IpcError::from)?;
+
+    let (source_lang, target_lang) = languages;
+    db.upsert_translation_memory_entries(&[NewTranslationMemoryEntryArgs {
+        source_lang,
+        target_lang,
+        source_text: source.clone(),
+        target_text: target.clone(),
+        project_uuid: Some(project_uuid),
+        origin: EDITOR_ORIGIN.to_string(),
+    }])
+    .await
+    .map_err(IpcError::from)?;
+
+    let propagated_files = if propagate {
+        let translations = HashMap::from([(
+            repetition_key(&source, case_sensitive),
+            ConfirmedTranslation {
+                source: PropagationSource {
+                    file: payload.jliff_rel_path.clone(),
+                    transunit_id: payload.transunit_id.clone(),
+                },
+                target: target.clone(),
+            },
+        )]);
+        propagate_in_scope(
+            db.inner(),
+            settings.inner(),
+            project_uuid,
+            &payload.jliff_rel_path,
+            translations,
+            scope,
+            case_sensitive,
+        )
+        .await?
+    } else {
+        Vec::new()
+    };
+
+    Ok(ConfirmSegmentDto {
+        transunit_id: payload.transunit_id,
+        status: status.as_str().to_string(),
+        target,
+        auto_fixes: applied_fixes
+            .into_iter()
+            .map(|fix| fix.check_type().to_string())
+            .collect(),
+        length_limit: length_issue,
+        propagated: propagated_files.iter().map(|file| file.propagated).sum(),
+        propagated_files,
+    })
+}).await
+}
+
+pub(super) fn validate_limit(limit: Option<usize>) -> Result<usize, IpcError> {
+    let limit = limit.unwrap_or(MAX_SEGMENTS_PER_PAGE);
+    if limit == 0 || limit > MAX_SEGMENTS_PER_PAGE {
+        return Err(IpcError::Validation(format!(
+            "limit must be between 1 and {MAX_SEGMENTS_PER_PAGE}."
+        )));
+    }
+    Ok(limit)
+}
+
+pub(super) fn page_segments(
+    matching: Vec<&TransUnit>,
+    offset: Option<usize>,
+    limit: usize,
+    reference_counts: &HashMap<String, i64>,
+) -> SegmentPageDto {
+    SegmentPageDto {
+        total: matching.len(),
+        segments: matching
+            .into_iter()
+            .skip(offset.unwrap_or(0))
+            .take(limit)
+            .map(|unit| map_segment(unit, reference_counts))
+            .collect(),
+    }
+}
+
+/// Validates the content conditions of a filter DTO and compiles them.
+fn build_segment_filter(dto: &SegmentFilterDto) -> Result<SegmentFilter, IpcError> {
+    let statuses = dto
+        .statuses
+        .iter()
+        .flatten()
+        .map(|value| {
+            SegmentStatus::parse(value)
+                .ok_or_else(|| IpcError::Validation(format!("Unknown segment status '{value}'.")))
+        })
+        .collect::<Result<_, _>>()?;
+    let match_bands = dto
+        .match_bands
+        .iter()
+        .flatten()
+        .map(|value| {
+            MatchBand::parse(value).ok_or_else(|| {
+                IpcError::Validation(format!(
+                    "Unknown match band '{value}'. Use exact, fuzzy or no_match."
+                ))
+            })
+        })
+        .collect::<Result<_, _>>()?;
+    if let Some(severity) = dto
+        .qa_severities
+        .iter()
+        .flatten()
+        .find(|severity| !QA_SEVERITIES.contains(&severity.to_ascii_lowercase().as_str()))
+    {
+        return Err(IpcError::Validation(format!(
+            "Unknown QA severity '{severity}'. Use info, warning or critical."
+        )));
+    }
+    if let Some(value) = dto.modified_since.as_deref() {
+        parse_modified_since(value)?;
+    }
+
+    let case_insensitive = dto.case_insensitive.unwrap_or(false);
+    let compile = |pattern: &Option<String>, field: &str| {
+        pattern
+            .as_deref()
+            .map(|pattern| {
+                RegexBuilder::new(pattern)
+                    .case_insensitive(case_insensitive)
+                    .build()
+                    .map_err(|error| {
+                        IpcError::Validation(format!("Invalid {field} pattern: {error}"))
+                    })
+            })
+            .transpose()
+    };
+
+    Ok(SegmentFilter {
+        statuses,
+        match_bands,
+        has_comments: dto.has_comments,
+        source_pattern: compile(&dto.source_regex, "sourceRegex")?,
+        target_pattern: compile(&dto.target_regex, "targetRegex")?,
+    })
+}
+
+/// Converts `modifiedSince` into the `YYYY-MM-DD HH:MM:SS` UTC form stored by SQLite.
+fn parse_modified_since(value: &str) -> Result<String, IpcError> {
+    let value = value.trim();
+    let timestamp = DateTime::parse_from_rfc3339(value)
+        .map(|timestamp| timestamp.with_timezone(&Utc).naive_utc())
+        .or_else(|_| {
+            NaiveDate::parse_from_str(value, "%Y-%m-%d").map(|date| date.and_time(NaiveTime::MIN))
+        })
+        .map_err(|_| {
+            IpcError::Validation(format!(
+                "Invalid modifiedSince '{value}': expected an RFC 3339 timestamp or YYYY-MM-DD."
+            ))
+        })?;
+    Ok(timestamp.format("%Y-%m-%d %H:%M:%S").to_string())
+}
+
+fn map_saved_filter(record: SavedSegmentFilterRecord) -> Result<SavedSegmentFilterDto, IpcError> {
+    let filter = serde_json::from_str(&record.filter_json).map_err(|error| {
+        IpcError::Internal(format!(
+            "Saved filter '{}' could not be read: {error}",
+            record.name
+        ))
+    })?;
+    Ok(SavedSegmentFilterDto {
+        filter_uuid: record.filter_uuid.to_string(),
+        user_uuid: record.user_uuid.to_string(),
+        name: record.name,
+        filter,
+        created_at: record.created_at,
+        updated_at: record.updated_at,
+    })
+}
+
+fn map_segment(unit: &TransUnit, reference_counts: &HashMap<String, i64>) -> SegmentDto {
+    let metadata = unit.metadata.as_ref();
+    SegmentDto {
+        transunit_id: unit.transunit_id.clone(),
+        unit_id: unit.unit_id.clone(),
+        source: unit.source.clone(),
+        target: unit.effective_target().to_string(),
+        status: unit.effective_status().as_str().to_string(),
+        sub_state: unit.sub_state.clone(),
+        match_quality: metadata.and_then(|metadata| metadata.match_quality),
+        match_origin: metadata.and_then(|metadata| metadata.match_origin.clone()),
+        max_length: metadata.and_then(|metadata| metadata.max_length),
+        notes: unit.notes.iter().map(map_note).collect(),
+        propagated_from: unit
+            .propagated_from
+            .as_ref()
+            .map(|source| PropagationSourceDto {
+                jliff_rel_path: source.file.clone(),
+                transunit_id: source.transunit_id.clone(),
+            }),
+        reference_count: reference_counts
+            .get(&unit.transunit_id)
+            .copied()
+            .unwrap_or(0),
+    }
+}
+
+fn map_note(note: &XliffNote) -> SegmentNoteDto {
+    SegmentNoteDto {
+        id: note.id.clone(),
+        category: note.category.clone(),
+        priority: note.priority,
+        applies_to: note.applies_to.clone(),
+        text: note.text.clone(),
+    }
+}
+
+fn parse_uuid(value: &str, field: &str) -> Result<Uuid, IpcError> {
+    Uuid::parse_str(value)
+        .map_err(|_| IpcError::Validation(format!("invalid {field}: expected UUID, got '{value}'")))
+}
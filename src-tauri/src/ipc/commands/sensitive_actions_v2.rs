@@ -0,0 +1,142 @@
+use std::time::Duration;
+
+use log::info;
+use tauri::State;
+use uuid::Uuid;
+
+use crate::db::DbManager;
+use crate::ipc::dto::{RequestSensitiveActionPayload, SensitiveActionTokenDto};
+use crate::ipc::error::{IpcError, IpcResult};
+use crate::ipc::state::{ActiveProfile, SensitiveActionTokens};
+use crate::metrics;
+
+/// Commands that need a confirmation token from [`request_sensitive_action_v2`]. Plugin
+/// settings hold the API keys of provider plugins.
+const SENSITIVE_ACTIONS: [&str; 10] = [
+    "enable_database_encryption",
+    "rotate_client_pii_key_v2",
+    "set_plugin_settings_v2",
+    "switch_workspace_v2",
+    "update_app_folder",
+    "update_event_webhook_url",
+    "update_local_model_settings",
+    "update_ocr_command",
+    "update_plugin_runtime_settings",
+    "update_proxy_settings",
+];
+
+/// Permission allowing a user to change sensitive settings. Users with one of
+/// [`SETTINGS_ADMIN_ROLES`] hold it unless an override denies it.
+const SETTINGS_PERMISSION: &str = "settings.manage";
+const SETTINGS_ADMIN_ROLES: [&str; 1] = ["admin"];
+
+/// How long a confirmation token stays valid.
+const TOKEN_TTL: Duration = Duration::from_secs(120);
+
+/// Records the profile the user signed in with, or none when they signed out. Tokens issued
+/// to the previous profile are dropped.
+#[tauri::command]
+pub async fn set_active_profile_v2(
+    db: State<'_, DbManager>,
+    profile: State<'_, ActiveProfile>,
+    tokens: State<'_, SensitiveActionTokens>,
+    user_uuid: Option<String>,
+) -> IpcResult<()> {
+    metrics::track("set_active_profile_v2", async {
+        let user_uuid = user_uuid
+            .as_deref()
+            .map(|value| parse_uuid(value, "userUuid"))
+            .transpose()?;
+        if let Some(user_uuid) = user_uuid
+            && db
+                .get_user_profile(user_uuid)
+                .await
+                .map_err(IpcError::from)?
+                .is_none()
+        {
+            return Err(IpcError::Validation(format!("User {user_uuid} was not found.")).into());
+        }
+        if profile.set(user_uuid) {
+            tokens.clear();
+        }
+        Ok(())
+    })
+    .await
+}
+
+/// Issues a short-lived, single-use token letting the active profile, when authorized, run
+/// one sensitive settings command. The command must be called with the token as
+/// `confirmationToken`.
+#[tauri::command]
+pub async fn request_sensitive_action_v2(
+    db: State<'_, DbManager>,
+    active: State<'_, ActiveProfile>,
+    tokens: State<'_, SensitiveActionTokens>,
+    payload: RequestSensitiveActionPayload,
+) -> IpcResult<SensitiveActionTokenDto> {
+    metrics::track("request_sensitive_action_v2", async {
+        let user_uuid = active
+            .get()
+            .ok_or_else(|| IpcError::Validation("Sign in to confirm this change.".into()))?;
+        let action = payload.action.trim();
+        if !SENSITIVE_ACTIONS.contains(&action) {
+            return Err(IpcError::Validation(format!(
+                "'{action}' is not a sensitive action. Expected one of: {}.",
+                SENSITIVE_ACTIONS.join(", ")
+            ))
+            .into());
+        }
+        let profile = db
+            .get_user_profile(user_uuid)
+            .await
+            .map_err(IpcError::from)?
+            .ok_or_else(|| IpcError::Validation(format!("User {user_uuid} was not found.")))?;
+        let explicit = profile
+            .permission_overrides
+            .iter()
+            .find(|entry| entry.permission == SETTINGS_PERMISSION)
+            .map(|entry| entry.is_allowed);
+        let by_role = profile
+            .roles
+            .iter()
+            .any(|role| SETTINGS_ADMIN_ROLES.contains(&role.role.as_str()));
+        if !explicit.unwrap_or(by_role) {
+            return Err(IpcError::Validation(format!(
+                "{} is not allowed to change this setting.",
+                profile.user.username
+            ))
+            .into());
+        }
+
+        let token = tokens.issue(action, user_uuid, TOKEN_TTL);
+        Ok(SensitiveActionTokenDto {
+            token,
+            action: action.to_string(),
+            expires_in_seconds: TOKEN_TTL.as_secs(),
+        })
+    })
+    .await
+}
+
+/// Spends the confirmation token presented to a sensitive command, failing when it is
+/// missing, expired, already used or issued for another action.
+pub(super) fn redeem_confirmation(
+    tokens: &SensitiveActionTokens,
+    confirmation_token: &str,
+    action: &str,
+) -> Result<(), IpcError> {
+    let user_uuid = tokens
+        .redeem(confirmation_token.trim(), action)
+        .ok_or_else(|| {
+            IpcError::Validation(
+                "This change needs a valid confirmation. Request a new one and retry.".into(),
+            )
+        })?;
+    info!(target: "ipc::settings", "{action} confirmed by user {user_uuid}");
+    Ok(())
+}
+
+fn parse_uuid(value: &str, field: &str) -> Result<Uuid, IpcError> {
+    Uuid::parse_str(value)
+        .map_err(|_| IpcError::Validation(format!("invalid {field}: expected UUID, got '{value}'")))
+}
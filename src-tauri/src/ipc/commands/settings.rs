@@ -4,12 +4,33 @@ use log::{error, warn};
 use tauri::{AppHandle, Manager, State};
 use tokio::fs;
 
+use super::propagation_v2::parse_scope;
+use super::sensitive_actions_v2::redeem_confirmation;
 use super::shared::{directory_is_empty, fs_error, path_exists_bool};
+use crate::currency::normalize_currency;
+use crate::db::encryption::{DATABASE_KEY_SECRET, generate_key};
+use crate::db::numbering::ProjectNumberTemplate;
+use crate::db::pii::PII_KEY_SECRET;
 use crate::db::{DbManager, SQLITE_DB_FILE};
-use crate::ipc::dto::AppSettingsDto;
+use crate::http::{self, PROXY_PASSWORD_SECRET, ProxyConfig};
+use crate::import::roles::{RoleRule, validate_role_rules};
+use crate::ipc::dto::{AppSettingsDto, UpdateProxySettingsPayload};
 use crate::ipc::error::{IpcError, IpcResult};
-use crate::ipc::state::TranslationState;
-use crate::settings::{SettingsManager, move_directory};
+use crate::ipc::state::{SensitiveActionTokens, TranslationState};
+use crate::jliff::length_limits::LengthLimitEnforcement;
+use crate::metrics;
+use crate::output_naming::OutputNameTemplate;
+use crate::plugins::check_wasm_runtime;
+use crate::providers::is_loopback_url;
+use crate::secrets::{SecretsVault, VaultError, workspace_secret};
+use crate::settings::{AppSettings, SettingsManager, move_directory};
+
+/// Longest latency the mock provider can be configured with.
+const MAX_MOCK_LATENCY_MS: u32 = 30_000;
+/// Longest auto-lock delay: one day.
+const MAX_AUTO_LOCK_MINUTES: u32 = 24 * 60;
+/// Keys the secrets vault keeps per workspace folder.
+const WORKSPACE_SECRETS: [&str; 2] = [DATABASE_KEY_SECRET, PII_KEY_SECRET];
 
 /// Builds the DTO consumed by the front-end settings panel. The helper inspects
 /// both the persisted configuration and the filesystem to provide actionable
@@ -57,6 +78,38 @@ pub(super) async fn build_app_settings_dto(
         show_notifications: current.show_notifications,
         enable_sound_notifications: current.enable_sound_notifications,
         max_parallel_conversions: current.max_parallel_conversions,
+        auto_propagate_repetitions: current.auto_propagate_repetitions,
+        propagation_scope: current.propagation_scope,
+        propagation_case_sensitive: current.propagation_case_sensitive,
+        snapshot_interval_minutes: current.snapshot_interval_minutes,
+        snapshot_edit_threshold: current.snapshot_edit_threshold,
+        snapshot_retention: current.snapshot_retention,
+        length_limit_enforcement: current.length_limit_enforcement.clone(),
+        provider_cache_ttl_hours: current.provider_cache_ttl_hours,
+        local_model_base_url: current.local_model_base_url.clone(),
+        local_model_name: current.local_model_name.clone(),
+        mock_provider_enabled: current.mock_provider_enabled,
+        mock_provider_latency_ms: current.mock_provider_latency_ms,
+        mock_provider_failure_rate: current.mock_provider_failure_rate,
+        normalize_unicode_nfc: current.normalize_unicode_nfc,
+        event_webhook_url: current.event_webhook_url,
+        vies_lookup: current.vies_lookup,
+        base_currency: current.base_currency,
+        project_number_template: current.project_number_template,
+        asset_role_rules: current.asset_role_rules,
+        ocr_command: current.ocr_command,
+        plugin_wasm_runtime: current
+            .plugin_wasm_runtime
+            .map(|path| path.to_string_lossy().into_owned()),
+        allow_native_plugins: current.allow_native_plugins,
+        database_encryption: current.database_encryption,
+        auto_lock_minutes: current.auto_lock_minutes,
+        xliff_name_template: current.xliff_name_template,
+        jliff_name_template: current.jliff_name_template,
+        proxy_url: current.proxy_url,
+        proxy_username: current.proxy_username,
+        no_proxy: current.no_proxy,
+        crash_report_upload: current.crash_report_upload,
         database_journal_mode: current.database_journal_mode,
         database_synchronous: current.database_synchronous,
     })
@@ -69,22 +122,30 @@ pub async fn get_app_settings(
     app: AppHandle,
     settings: State<'_, SettingsManager>,
 ) -> IpcResult<AppSettingsDto> {
-    build_app_settings_dto(&app, &settings)
-        .await
-        .map_err(Into::into)
+    metrics::track("get_app_settings", async {
+        build_app_settings_dto(&app, &settings)
+            .await
+            .map_err(Into::into)
+    })
+    .await
 }
 
 /// Moves the application data folder to a new location. The function performs
 /// several guard checks to protect user data and ensures we roll back
-/// gracefully if the database fails to reopen.
+/// gracefully if the database fails to reopen. Needs a confirmation token from
+/// `request_sensitive_action_v2`.
 #[tauri::command]
 pub async fn update_app_folder(
     app: AppHandle,
     settings: State<'_, SettingsManager>,
     db: State<'_, DbManager>,
     translation_state: State<'_, TranslationState>,
+    tokens: State<'_, SensitiveActionTokens>,
     new_folder: String,
+    confirmation_token: String,
 ) -> IpcResult<AppSettingsDto> {
+    metrics::track("update_app_folder", async {
+    redeem_confirmation(&tokens, &confirmation_token, "update_app_folder")?;
     let candidate_raw = new_folder.trim();
     if candidate_raw.is_empty() {
         return Err(IpcError::Validation("Select a destination folder.".into()).into());
@@ -154,6 +215,17 @@ pub async fn update_app_folder(
             .map_err(|error| fs_error("prepare destination directory", error))?;
     }
 
+    // The keys of the workspace are kept per folder and move along with it.
+    let vault = app.state::<SecretsVault>();
+    let copied = copy_workspace_secrets(&vault, &current_settings.app_folder, &candidate_path);
+    if let Err(error) = copied {
+        warn!(target: "ipc::settings", "failed to copy the workspace keys: {error}");
+        return Err(IpcError::Internal(
+            "Unable to move the workspace keys in the keychain.".into(),
+        )
+        .into());
+    }
+
     match move_directory(&current_settings.app_folder, &candidate_path).await {
         Ok(_) => {}
         Err(error) => {
@@ -250,9 +322,26 @@ pub async fn update_app_folder(
         .into());
     }
 
+    for name in WORKSPACE_SECRETS {
+        if let Err(error) = vault.delete(&workspace_secret(name, &current_settings.app_folder)) {
+            warn!(target: "ipc::settings", "failed to remove a key of the old folder: {error}");
+        }
+    }
+
     build_app_settings_dto(&app, &settings)
         .await
         .map_err(Into::into)
+}).await
+}
+
+/// Copies the keys kept for the workspace in `from` to the workspace in `to`.
+fn copy_workspace_secrets(vault: &SecretsVault, from: &Path, to: &Path) -> Result<(), VaultError> {
+    for name in WORKSPACE_SECRETS {
+        if let Some(secret) = vault.read(&workspace_secret(name, from))? {
+            vault.store(&workspace_secret(name, to), &secret)?;
+        }
+    }
+    Ok(())
 }
 
 /// Toggles the automatic conversion behaviour that kicks in whenever a project
@@ -264,13 +353,18 @@ pub async fn update_auto_convert_on_open(
     _db: State<'_, DbManager>,
     enabled: bool,
 ) -> IpcResult<AppSettingsDto> {
-    if let Err(error) = settings.update_and_save_auto_convert_on_open(enabled).await {
-        warn!(target: "ipc::settings", "failed to update auto-convert flag: {error}");
-        return Err(IpcError::Internal("Unable to update setting. Please retry.".into()).into());
-    }
-    build_app_settings_dto(&app, &settings)
-        .await
-        .map_err(Into::into)
+    metrics::track("update_auto_convert_on_open", async {
+        if let Err(error) = settings.update_and_save_auto_convert_on_open(enabled).await {
+            warn!(target: "ipc::settings", "failed to update auto-convert flag: {error}");
+            return Err(
+                IpcError::Internal("Unable to update setting. Please retry.".into()).into(),
+            );
+        }
+        build_app_settings_dto(&app, &settings)
+            .await
+            .map_err(Into::into)
+    })
+    .await
 }
 
 #[tauri::command]
@@ -279,28 +373,38 @@ pub async fn update_theme(
     settings: State<'_, SettingsManager>,
     theme: String,
 ) -> IpcResult<AppSettingsDto> {
-    if let Err(error) = settings.update_and_save_theme(theme).await {
-        warn!(target: "ipc::settings", "failed to update theme: {error}");
-        return Err(IpcError::Internal("Unable to update theme. Please retry.".into()).into());
-    }
-    build_app_settings_dto(&app, &settings)
-        .await
-        .map_err(Into::into)
+    metrics::track("update_theme", async {
+        if let Err(error) = settings.update_and_save_theme(theme).await {
+            warn!(target: "ipc::settings", "failed to update theme: {error}");
+            return Err(IpcError::Internal("Unable to update theme. Please retry.".into()).into());
+        }
+        build_app_settings_dto(&app, &settings)
+            .await
+            .map_err(Into::into)
+    })
+    .await
 }
 
 #[tauri::command]
 pub async fn update_ui_language(
     app: AppHandle,
     settings: State<'_, SettingsManager>,
+    db: State<'_, DbManager>,
     language: String,
 ) -> IpcResult<AppSettingsDto> {
-    if let Err(error) = settings.update_and_save_ui_language(language).await {
-        warn!(target: "ipc::settings", "failed to update UI language: {error}");
-        return Err(IpcError::Internal("Unable to update language. Please retry.".into()).into());
-    }
-    build_app_settings_dto(&app, &settings)
-        .await
-        .map_err(Into::into)
+    metrics::track("update_ui_language", async {
+        db.set_collation_language(&language);
+        if let Err(error) = settings.update_and_save_ui_language(language).await {
+            warn!(target: "ipc::settings", "failed to update UI language: {error}");
+            return Err(
+                IpcError::Internal("Unable to update language. Please retry.".into()).into(),
+            );
+        }
+        build_app_settings_dto(&app, &settings)
+            .await
+            .map_err(Into::into)
+    })
+    .await
 }
 
 #[tauri::command]
@@ -310,18 +414,22 @@ pub async fn update_default_languages(
     source_language: String,
     target_language: String,
 ) -> IpcResult<AppSettingsDto> {
-    if let Err(error) = settings
-        .update_and_save_default_languages(source_language, target_language)
-        .await
-    {
-        warn!(target: "ipc::settings", "failed to update default languages: {error}");
-        return Err(
-            IpcError::Internal("Unable to update default languages. Please retry.".into()).into(),
-        );
-    }
-    build_app_settings_dto(&app, &settings)
-        .await
-        .map_err(Into::into)
+    metrics::track("update_default_languages", async {
+        if let Err(error) = settings
+            .update_and_save_default_languages(source_language, target_language)
+            .await
+        {
+            warn!(target: "ipc::settings", "failed to update default languages: {error}");
+            return Err(IpcError::Internal(
+                "Unable to update default languages. Please retry.".into(),
+            )
+            .into());
+        }
+        build_app_settings_dto(&app, &settings)
+            .await
+            .map_err(Into::into)
+    })
+    .await
 }
 
 #[tauri::command]
@@ -330,15 +438,18 @@ pub async fn update_xliff_version(
     settings: State<'_, SettingsManager>,
     version: String,
 ) -> IpcResult<AppSettingsDto> {
-    if let Err(error) = settings.update_and_save_xliff_version(version).await {
-        warn!(target: "ipc::settings", "failed to update XLIFF version: {error}");
-        return Err(
-            IpcError::Internal("Unable to update XLIFF version. Please retry.".into()).into(),
-        );
-    }
-    build_app_settings_dto(&app, &settings)
-        .await
-        .map_err(Into::into)
+    metrics::track("update_xliff_version", async {
+        if let Err(error) = settings.update_and_save_xliff_version(version).await {
+            warn!(target: "ipc::settings", "failed to update XLIFF version: {error}");
+            return Err(
+                IpcError::Internal("Unable to update XLIFF version. Please retry.".into()).into(),
+            );
+        }
+        build_app_settings_dto(&app, &settings)
+            .await
+            .map_err(Into::into)
+    })
+    .await
 }
 
 #[tauri::command]
@@ -348,18 +459,21 @@ pub async fn update_notifications(
     show_notifications: bool,
     enable_sound: bool,
 ) -> IpcResult<AppSettingsDto> {
-    if let Err(error) = settings
-        .update_and_save_notifications(show_notifications, enable_sound)
-        .await
-    {
-        warn!(target: "ipc::settings", "failed to update notifications: {error}");
-        return Err(
-            IpcError::Internal("Unable to update notifications. Please retry.".into()).into(),
-        );
-    }
-    build_app_settings_dto(&app, &settings)
-        .await
-        .map_err(Into::into)
+    metrics::track("update_notifications", async {
+        if let Err(error) = settings
+            .update_and_save_notifications(show_notifications, enable_sound)
+            .await
+        {
+            warn!(target: "ipc::settings", "failed to update notifications: {error}");
+            return Err(
+                IpcError::Internal("Unable to update notifications. Please retry.".into()).into(),
+            );
+        }
+        build_app_settings_dto(&app, &settings)
+            .await
+            .map_err(Into::into)
+    })
+    .await
 }
 
 #[tauri::command]
@@ -368,25 +482,658 @@ pub async fn update_max_parallel_conversions(
     settings: State<'_, SettingsManager>,
     max_parallel: u32,
 ) -> IpcResult<AppSettingsDto> {
-    if let Err(error) = settings.update_and_save_max_parallel(max_parallel).await {
-        warn!(target: "ipc::settings", "failed to update max parallel conversions: {error}");
-        return Err(IpcError::Internal(
-            "Unable to update max parallel conversions. Please retry.".into(),
-        )
-        .into());
-    }
-    build_app_settings_dto(&app, &settings)
-        .await
-        .map_err(Into::into)
+    metrics::track("update_max_parallel_conversions", async {
+        if let Err(error) = settings.update_and_save_max_parallel(max_parallel).await {
+            warn!(target: "ipc::settings", "failed to update max parallel conversions: {error}");
+            return Err(IpcError::Internal(
+                "Unable to update max parallel conversions. Please retry.".into(),
+            )
+            .into());
+        }
+        build_app_settings_dto(&app, &settings)
+            .await
+            .map_err(Into::into)
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn update_propagation_settings(
+    app: AppHandle,
+    settings: State<'_, SettingsManager>,
+    auto_propagate: bool,
+    scope: String,
+    case_sensitive: bool,
+) -> IpcResult<AppSettingsDto> {
+    metrics::track("update_propagation_settings", async {
+        let scope = parse_scope(&scope)?;
+        if let Err(error) = settings
+            .update_and_save_propagation(auto_propagate, scope.as_str().to_string(), case_sensitive)
+            .await
+        {
+            warn!(target: "ipc::settings", "failed to update propagation settings: {error}");
+            return Err(IpcError::Internal(
+                "Unable to update propagation settings. Please retry.".into(),
+            )
+            .into());
+        }
+        build_app_settings_dto(&app, &settings)
+            .await
+            .map_err(Into::into)
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn update_length_limit_enforcement(
+    app: AppHandle,
+    settings: State<'_, SettingsManager>,
+    mode: String,
+) -> IpcResult<AppSettingsDto> {
+    metrics::track("update_length_limit_enforcement", async {
+        let mode = mode.trim().to_ascii_lowercase();
+        if LengthLimitEnforcement::parse(&mode).is_none() {
+            return Err(IpcError::Validation(format!(
+                "Unknown length limit enforcement '{mode}'. Use off, warn or block."
+            ))
+            .into());
+        }
+        if let Err(error) = settings
+            .update_and_save_length_limit_enforcement(mode)
+            .await
+        {
+            warn!(target: "ipc::settings", "failed to update length limit enforcement: {error}");
+            return Err(IpcError::Internal(
+                "Unable to update length limit enforcement. Please retry.".into(),
+            )
+            .into());
+        }
+        build_app_settings_dto(&app, &settings)
+            .await
+            .map_err(Into::into)
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn update_snapshot_settings(
+    app: AppHandle,
+    settings: State<'_, SettingsManager>,
+    interval_minutes: u32,
+    edit_threshold: u32,
+    retention: u32,
+) -> IpcResult<AppSettingsDto> {
+    metrics::track("update_snapshot_settings", async {
+        if retention == 0 {
+            return Err(IpcError::Validation("At least one snapshot must be kept.".into()).into());
+        }
+        if let Err(error) = settings
+            .update_and_save_snapshots(interval_minutes, edit_threshold, retention)
+            .await
+        {
+            warn!(target: "ipc::settings", "failed to update snapshot settings: {error}");
+            return Err(IpcError::Internal(
+                "Unable to update snapshot settings. Please retry.".into(),
+            )
+            .into());
+        }
+        build_app_settings_dto(&app, &settings)
+            .await
+            .map_err(Into::into)
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn update_provider_cache_ttl(
+    app: AppHandle,
+    settings: State<'_, SettingsManager>,
+    hours: u32,
+) -> IpcResult<AppSettingsDto> {
+    metrics::track("update_provider_cache_ttl", async {
+        if let Err(error) = settings.update_and_save_provider_cache_ttl(hours).await {
+            warn!(target: "ipc::settings", "failed to update provider cache TTL: {error}");
+            return Err(IpcError::Internal(
+                "Unable to update the provider cache lifetime. Please retry.".into(),
+            )
+            .into());
+        }
+        build_app_settings_dto(&app, &settings)
+            .await
+            .map_err(Into::into)
+    })
+    .await
+}
+
+/// Points the local provider at the model server at `baseUrl`, which must run on this
+/// machine. Needs a confirmation token from `request_sensitive_action_v2`.
+#[tauri::command]
+pub async fn update_local_model_settings(
+    app: AppHandle,
+    settings: State<'_, SettingsManager>,
+    tokens: State<'_, SensitiveActionTokens>,
+    base_url: String,
+    model: String,
+    confirmation_token: String,
+) -> IpcResult<AppSettingsDto> {
+    metrics::track("update_local_model_settings", async {
+        redeem_confirmation(&tokens, &confirmation_token, "update_local_model_settings")?;
+        let base_url = base_url.trim().trim_end_matches('/').to_string();
+        if !(base_url.starts_with("http://") || base_url.starts_with("https://")) {
+            return Err(IpcError::Validation(format!(
+                "Invalid local model address '{base_url}'. Use an http:// or https:// URL."
+            ))
+            .into());
+        }
+        if !is_loopback_url(&base_url) {
+            return Err(IpcError::Validation(format!(
+                "Invalid local model address '{base_url}'. The local model must run on this \
+                 machine, e.g. http://127.0.0.1:11434."
+            ))
+            .into());
+        }
+        if let Err(error) = settings
+            .update_and_save_local_model(base_url, model.trim().to_string())
+            .await
+        {
+            warn!(target: "ipc::settings", "failed to update local model settings: {error}");
+            return Err(IpcError::Internal(
+                "Unable to update local model settings. Please retry.".into(),
+            )
+            .into());
+        }
+        build_app_settings_dto(&app, &settings)
+            .await
+            .map_err(Into::into)
+    })
+    .await
+}
+
+/// Switches backend translation to the offline mock provider, e.g. for demos without API
+/// keys. `failureRate` is the percentage of segments answered with an error.
+#[tauri::command]
+pub async fn update_mock_provider_settings(
+    app: AppHandle,
+    settings: State<'_, SettingsManager>,
+    enabled: bool,
+    latency_ms: u32,
+    failure_rate: u32,
+) -> IpcResult<AppSettingsDto> {
+    metrics::track("update_mock_provider_settings", async {
+        if failure_rate > 100 {
+            return Err(IpcError::Validation(format!(
+                "Invalid failure rate {failure_rate}. Use a percentage between 0 and 100."
+            ))
+            .into());
+        }
+        if latency_ms > MAX_MOCK_LATENCY_MS {
+            return Err(IpcError::Validation(format!(
+                "Invalid latency {latency_ms} ms. Use at most {MAX_MOCK_LATENCY_MS} ms."
+            ))
+            .into());
+        }
+        if let Err(error) = settings
+            .update_and_save_mock_provider(enabled, latency_ms, failure_rate)
+            .await
+        {
+            warn!(target: "ipc::settings", "failed to update mock provider settings: {error}");
+            return Err(IpcError::Internal(
+                "Unable to update mock provider settings. Please retry.".into(),
+            )
+            .into());
+        }
+        build_app_settings_dto(&app, &settings)
+            .await
+            .map_err(Into::into)
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn update_unicode_normalization(
+    app: AppHandle,
+    settings: State<'_, SettingsManager>,
+    enabled: bool,
+) -> IpcResult<AppSettingsDto> {
+    metrics::track("update_unicode_normalization", async {
+        if let Err(error) = settings
+            .update_and_save_unicode_normalization(enabled)
+            .await
+        {
+            warn!(target: "ipc::settings", "failed to update unicode normalization: {error}");
+            return Err(IpcError::Internal(
+                "Unable to update Unicode normalization preferences. Please retry.".into(),
+            )
+            .into());
+        }
+        build_app_settings_dto(&app, &settings)
+            .await
+            .map_err(Into::into)
+    })
+    .await
+}
+
+/// Sets the endpoint domain events are POSTed to; `None` or an empty URL turns webhooks off.
+/// Needs a confirmation token from `request_sensitive_action_v2`.
+#[tauri::command]
+pub async fn update_event_webhook_url(
+    app: AppHandle,
+    settings: State<'_, SettingsManager>,
+    tokens: State<'_, SensitiveActionTokens>,
+    url: Option<String>,
+    confirmation_token: String,
+) -> IpcResult<AppSettingsDto> {
+    metrics::track("update_event_webhook_url", async {
+        redeem_confirmation(&tokens, &confirmation_token, "update_event_webhook_url")?;
+        let url = url
+            .map(|url| url.trim().to_string())
+            .filter(|url| !url.is_empty());
+        if let Some(url) = &url
+            && !reqwest::Url::parse(url)
+                .is_ok_and(|parsed| matches!(parsed.scheme(), "http" | "https"))
+        {
+            return Err(
+                IpcError::Validation(format!("'{url}' is not a valid http(s) URL.")).into(),
+            );
+        }
+        if let Err(error) = settings.update_and_save_event_webhook_url(url).await {
+            warn!(target: "ipc::settings", "failed to update event webhook URL: {error}");
+            return Err(IpcError::Internal(
+                "Unable to update the event webhook. Please retry.".into(),
+            )
+            .into());
+        }
+        build_app_settings_dto(&app, &settings)
+            .await
+            .map_err(Into::into)
+    })
+    .await
+}
+
+/// Routes outbound requests through an HTTP(S) proxy, or connects directly again when the
+/// URL is empty. Needs a confirmation token from `request_sensitive_action_v2`.
+#[tauri::command]
+pub async fn update_proxy_settings(
+    app: AppHandle,
+    settings: State<'_, SettingsManager>,
+    vault: State<'_, SecretsVault>,
+    tokens: State<'_, SensitiveActionTokens>,
+    payload: UpdateProxySettingsPayload,
+    confirmation_token: String,
+) -> IpcResult<AppSettingsDto> {
+    metrics::track("update_proxy_settings", async {
+        redeem_confirmation(&tokens, &confirmation_token, "update_proxy_settings")?;
+        let url = payload
+            .url
+            .map(|url| url.trim().to_string())
+            .filter(|url| !url.is_empty());
+        if let Some(url) = &url {
+            http::validate_proxy_url(url)
+                .map_err(|error| IpcError::Validation(format!("{error:#}.")))?;
+        }
+        let username = payload
+            .username
+            .map(|username| username.trim().to_string())
+            .filter(|username| url.is_some() && !username.is_empty());
+        let no_proxy = payload
+            .no_proxy
+            .iter()
+            .map(|host| host.trim())
+            .filter(|host| !host.is_empty())
+            .map(str::to_string)
+            .collect();
+
+        let stored = match payload.password {
+            _ if username.is_none() => vault.delete(PROXY_PASSWORD_SECRET),
+            Some(password) if password.is_empty() => vault.delete(PROXY_PASSWORD_SECRET),
+            Some(password) => vault.store(PROXY_PASSWORD_SECRET, &password),
+            None => Ok(()),
+        };
+        stored.map_err(|error| {
+            warn!(target: "ipc::settings", "failed to update the proxy password: {error}");
+            IpcError::Internal("Unable to update the proxy password in the keychain.".into())
+        })?;
+        if let Err(error) = settings
+            .update_and_save_proxy(url, username, no_proxy)
+            .await
+        {
+            warn!(target: "ipc::settings", "failed to update proxy settings: {error}");
+            return Err(IpcError::Internal(
+                "Unable to update the proxy settings. Please retry.".into(),
+            )
+            .into());
+        }
+        http::set_proxy(proxy_config(&settings.current().await, &vault));
+        build_app_settings_dto(&app, &settings)
+            .await
+            .map_err(Into::into)
+    })
+    .await
+}
+
+/// The proxy described by `settings`, with its password from the keychain.
+pub(crate) fn proxy_config(settings: &AppSettings, vault: &SecretsVault) -> Option<ProxyConfig> {
+    let url = settings.proxy_url.clone()?;
+    let password = match &settings.proxy_username {
+        Some(_) => vault.read(PROXY_PASSWORD_SECRET).unwrap_or_else(|error| {
+            warn!(target: "ipc::settings", "failed to read the proxy password: {error}");
+            None
+        }),
+        None => None,
+    };
+    Some(ProxyConfig {
+        url,
+        username: settings.proxy_username.clone(),
+        password,
+        no_proxy: settings.no_proxy.clone(),
+    })
+}
+
+#[tauri::command]
+pub async fn update_crash_report_upload(
+    app: AppHandle,
+    settings: State<'_, SettingsManager>,
+    enabled: bool,
+) -> IpcResult<AppSettingsDto> {
+    metrics::track("update_crash_report_upload", async {
+        if let Err(error) = settings.update_and_save_crash_report_upload(enabled).await {
+            warn!(target: "ipc::settings", "failed to update crash report upload: {error}");
+            return Err(IpcError::Internal(
+                "Unable to update crash reporting preferences. Please retry.".into(),
+            )
+            .into());
+        }
+        build_app_settings_dto(&app, &settings)
+            .await
+            .map_err(Into::into)
+    })
+    .await
+}
+
+/// Sets the currency reports convert totals into.
+#[tauri::command]
+pub async fn update_base_currency(
+    app: AppHandle,
+    settings: State<'_, SettingsManager>,
+    currency: String,
+) -> IpcResult<AppSettingsDto> {
+    metrics::track("update_base_currency", async {
+        let currency = normalize_currency(&currency)
+            .map_err(|error| IpcError::Validation(error.to_string()))?;
+        if let Err(error) = settings.update_and_save_base_currency(currency).await {
+            warn!(target: "ipc::settings", "failed to update base currency: {error}");
+            return Err(IpcError::Internal(
+                "Unable to update the base currency. Please retry.".into(),
+            )
+            .into());
+        }
+        build_app_settings_dto(&app, &settings)
+            .await
+            .map_err(Into::into)
+    })
+    .await
+}
+
+/// Sets the template projects created from now on are numbered with.
+#[tauri::command]
+pub async fn update_project_number_template(
+    app: AppHandle,
+    settings: State<'_, SettingsManager>,
+    db: State<'_, DbManager>,
+    template: String,
+) -> IpcResult<AppSettingsDto> {
+    metrics::track("update_project_number_template", async {
+        let template = template.trim().to_string();
+        let parsed = ProjectNumberTemplate::parse(&template)
+            .map_err(|error| IpcError::Validation(error.to_string()))?;
+        if let Err(error) = settings
+            .update_and_save_project_number_template(template)
+            .await
+        {
+            warn!(target: "ipc::settings", "failed to update project number template: {error}");
+            return Err(IpcError::Internal(
+                "Unable to update the project number template. Please retry.".into(),
+            )
+            .into());
+        }
+        db.set_project_number_template(parsed);
+        build_app_settings_dto(&app, &settings)
+            .await
+            .map_err(Into::into)
+    })
+    .await
+}
+
+/// Sets the templates conversion outputs are named with, unless a project overrides them.
+#[tauri::command]
+pub async fn update_output_name_templates(
+    app: AppHandle,
+    settings: State<'_, SettingsManager>,
+    xliff_template: String,
+    jliff_template: String,
+) -> IpcResult<AppSettingsDto> {
+    metrics::track("update_output_name_templates", async {
+        let xliff_template = xliff_template.trim().to_string();
+        let jliff_template = jliff_template.trim().to_string();
+        for template in [&xliff_template, &jliff_template] {
+            OutputNameTemplate::parse(template)
+                .map_err(|error| IpcError::Validation(error.to_string()))?;
+        }
+        if let Err(error) = settings
+            .update_and_save_output_name_templates(xliff_template, jliff_template)
+            .await
+        {
+            warn!(target: "ipc::settings", "failed to update output name templates: {error}");
+            return Err(IpcError::Internal(
+                "Unable to update the output name templates. Please retry.".into(),
+            )
+            .into());
+        }
+        build_app_settings_dto(&app, &settings)
+            .await
+            .map_err(Into::into)
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn update_asset_role_rules(
+    app: AppHandle,
+    settings: State<'_, SettingsManager>,
+    rules: Vec<RoleRule>,
+) -> IpcResult<AppSettingsDto> {
+    metrics::track("update_asset_role_rules", async {
+        validate_role_rules(&rules).map_err(|error| IpcError::Validation(error.to_string()))?;
+        if let Err(error) = settings.update_and_save_asset_role_rules(rules).await {
+            warn!(target: "ipc::settings", "failed to update asset role rules: {error}");
+            return Err(IpcError::Internal(
+                "Unable to update the file classification rules. Please retry.".into(),
+            )
+            .into());
+        }
+        build_app_settings_dto(&app, &settings)
+            .await
+            .map_err(Into::into)
+    })
+    .await
+}
+
+/// Sets the OCR engine command run on image assets. Needs a confirmation token from
+/// `request_sensitive_action_v2`.
+#[tauri::command]
+pub async fn update_ocr_command(
+    app: AppHandle,
+    settings: State<'_, SettingsManager>,
+    tokens: State<'_, SensitiveActionTokens>,
+    command: Option<String>,
+    confirmation_token: String,
+) -> IpcResult<AppSettingsDto> {
+    metrics::track("update_ocr_command", async {
+        redeem_confirmation(&tokens, &confirmation_token, "update_ocr_command")?;
+        let command = command
+            .map(|command| command.trim().to_string())
+            .filter(|command| !command.is_empty());
+        if let Err(error) = settings.update_and_save_ocr_command(command).await {
+            warn!(target: "ipc::settings", "failed to update OCR command: {error}");
+            return Err(IpcError::Internal(
+                "Unable to update the OCR engine. Please retry.".into(),
+            )
+            .into());
+        }
+        build_app_settings_dto(&app, &settings)
+            .await
+            .map_err(Into::into)
+    })
+    .await
+}
+
+/// Sets the WASI runtime WASM plugins run with, an absolute path to an existing file, and
+/// whether native executables may run as plugins. Needs a confirmation token from
+/// `request_sensitive_action_v2`.
+#[tauri::command]
+pub async fn update_plugin_runtime_settings(
+    app: AppHandle,
+    settings: State<'_, SettingsManager>,
+    tokens: State<'_, SensitiveActionTokens>,
+    wasm_runtime: Option<String>,
+    allow_native_plugins: bool,
+    confirmation_token: String,
+) -> IpcResult<AppSettingsDto> {
+    metrics::track("update_plugin_runtime_settings", async {
+        redeem_confirmation(
+            &tokens,
+            &confirmation_token,
+            "update_plugin_runtime_settings",
+        )?;
+        let wasm_runtime = wasm_runtime
+            .map(|path| PathBuf::from(path.trim()))
+            .filter(|path| !path.as_os_str().is_empty());
+        if let Some(path) = &wasm_runtime {
+            check_wasm_runtime(path).map_err(|error| IpcError::Validation(format!("{error:#}")))?;
+        }
+        if let Err(error) = settings
+            .update_and_save_plugin_runtime(wasm_runtime, allow_native_plugins)
+            .await
+        {
+            warn!(target: "ipc::settings", "failed to update plugin runtime settings: {error}");
+            return Err(IpcError::Internal(
+                "Unable to update the plugin runtime. Please retry.".into(),
+            )
+            .into());
+        }
+        build_app_settings_dto(&app, &settings)
+            .await
+            .map_err(Into::into)
+    })
+    .await
+}
+
+/// Encrypts the database with a new key kept in the OS keychain. Needs a confirmation token
+/// from `request_sensitive_action_v2`.
+#[tauri::command]
+pub async fn enable_database_encryption(
+    app: AppHandle,
+    settings: State<'_, SettingsManager>,
+    db: State<'_, DbManager>,
+    vault: State<'_, SecretsVault>,
+    tokens: State<'_, SensitiveActionTokens>,
+    confirmation_token: String,
+) -> IpcResult<AppSettingsDto> {
+    metrics::track("enable_database_encryption", async {
+        redeem_confirmation(&tokens, &confirmation_token, "enable_database_encryption")?;
+        let current = settings.current().await;
+        if db.is_encrypted() && current.database_encryption {
+            return Err(IpcError::Validation("The database is already encrypted.".into()).into());
+        }
+        // A previous attempt may have encrypted the database but failed to save the setting.
+        if !db.is_encrypted() {
+            let key = generate_key();
+            let secret = workspace_secret(DATABASE_KEY_SECRET, &current.app_folder);
+            vault.store(&secret, &key).map_err(|error| {
+                warn!(target: "ipc::settings", "failed to store the database key: {error}");
+                IpcError::Internal("Unable to store the database key in the keychain.".into())
+            })?;
+            if let Err(error) = db.encrypt_database(&current.app_folder, &key).await {
+                warn!(target: "ipc::settings", "failed to encrypt the database: {error}");
+                if let Err(error) = vault.delete(&secret) {
+                    warn!(target: "ipc::settings", "failed to remove the unused key: {error}");
+                }
+                return Err(IpcError::from(error).into());
+            }
+        }
+        if let Err(error) = settings.update_and_save_database_encryption(true).await {
+            error!(target: "ipc::settings", "failed to save database encryption: {error}");
+            return Err(IpcError::Internal(
+                "The database was encrypted but the setting was not saved. Please retry.".into(),
+            )
+            .into());
+        }
+        build_app_settings_dto(&app, &settings)
+            .await
+            .map_err(Into::into)
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn update_vies_lookup(
+    app: AppHandle,
+    settings: State<'_, SettingsManager>,
+    enabled: bool,
+) -> IpcResult<AppSettingsDto> {
+    metrics::track("update_vies_lookup", async {
+        if let Err(error) = settings.update_and_save_vies_lookup(enabled).await {
+            warn!(target: "ipc::settings", "failed to update VIES lookup: {error}");
+            return Err(IpcError::Internal(
+                "Unable to update VAT number checks. Please retry.".into(),
+            )
+            .into());
+        }
+        build_app_settings_dto(&app, &settings)
+            .await
+            .map_err(Into::into)
+    })
+    .await
+}
+
+/// Sets how many idle minutes lock the workspace; `None` turns the auto-lock off.
+#[tauri::command]
+pub async fn update_auto_lock_minutes(
+    app: AppHandle,
+    settings: State<'_, SettingsManager>,
+    minutes: Option<u32>,
+) -> IpcResult<AppSettingsDto> {
+    metrics::track("update_auto_lock_minutes", async {
+        if minutes.is_some_and(|minutes| !(1..=MAX_AUTO_LOCK_MINUTES).contains(&minutes)) {
+            return Err(IpcError::Validation(format!(
+                "The auto-lock delay must be between 1 and {MAX_AUTO_LOCK_MINUTES} minutes."
+            ))
+            .into());
+        }
+        if let Err(error) = settings.update_and_save_auto_lock_minutes(minutes).await {
+            warn!(target: "ipc::settings", "failed to update the auto-lock delay: {error}");
+            return Err(IpcError::Internal(
+                "Unable to update the auto-lock delay. Please retry.".into(),
+            )
+            .into());
+        }
+        build_app_settings_dto(&app, &settings)
+            .await
+            .map_err(Into::into)
+    })
+    .await
 }
 
 /// Lightweight helper exposed to the renderer to check arbitrary filesystem
 /// paths without performing any privileged operation.
 #[tauri::command]
 pub async fn path_exists(path: String) -> Result<(bool, bool, bool), ()> {
-    let p = Path::new(&path);
-    let exists = p.exists();
-    let is_file = exists && p.is_file();
-    let is_dir = exists && p.is_dir();
-    Ok((exists, is_file, is_dir))
+    metrics::track("path_exists", async {
+        let p = Path::new(&path);
+        let exists = p.exists();
+        let is_file = exists && p.is_file();
+        let is_dir = exists && p.is_dir();
+        Ok((exists, is_file, is_dir))
+    })
+    .await
 }
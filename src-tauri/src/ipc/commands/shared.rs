@@ -1,12 +1,29 @@
 use std::collections::HashMap;
 use std::future::Future;
-use std::path::{Path, PathBuf};
-use std::sync::{Arc, OnceLock};
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
 
+use chrono::Utc;
 use log::{error, warn};
 use tokio::{fs, sync::Mutex as AsyncMutex};
+use uuid::Uuid;
 
+use crate::db::DbManager;
 use crate::ipc::error::IpcError;
+use crate::jliff::JliffDocument;
+use crate::jliff::cache::{DEFAULT_CACHE_CAPACITY, DocumentCache};
+use crate::jliff::document::{
+    read_jliff_document, read_tag_map, tag_map_path_for, write_jliff_document,
+};
+use crate::jliff::history::{self, HistoryEvent};
+use crate::jliff::snapshots::{
+    SnapshotPolicy, SnapshotTracker, prune_snapshots, snapshot_dir_for, write_snapshot,
+};
+use crate::jliff::tag_map::TagMapDoc;
+use crate::settings::SettingsManager;
+
+use super::projects_v2::resolve_project_root;
 
 /// Global async mutex type used to guard exclusive file access while we update
 /// artifacts on disk.
@@ -54,6 +71,131 @@ where
     work().await
 }
 
+/// Runs asynchronous work while holding the mutexes of several files at once, e.g. to read
+/// a consistent copy of a whole project. Locks are taken in sorted order so two callers
+/// with overlapping paths cannot deadlock each other.
+pub async fn with_project_file_locks<F, Fut, T>(paths: &[PathBuf], work: F) -> T
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = T>,
+{
+    let mut sorted: Vec<&PathBuf> = paths.iter().collect();
+    sorted.sort();
+    sorted.dedup();
+    let mut guards = Vec::with_capacity(sorted.len());
+    for path in sorted {
+        let lock = file_lock_registry().lock_for_path(path).await;
+        guards.push(lock.lock_owned().await);
+    }
+    work().await
+}
+
+/// Edit counters per JLIFF path, driving the periodic snapshots taken by
+/// [`update_project_jliff`].
+static SNAPSHOT_TRACKERS: OnceLock<Mutex<HashMap<PathBuf, SnapshotTracker>>> = OnceLock::new();
+
+fn snapshot_due(jliff_path: &Path, policy: SnapshotPolicy) -> bool {
+    let now = Instant::now();
+    let mut trackers = SNAPSHOT_TRACKERS
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    trackers
+        .entry(jliff_path.to_path_buf())
+        .or_insert_with(|| SnapshotTracker::new(now))
+        .record_edit(now, policy)
+}
+
+/// Writes a snapshot of `document` and prunes the oldest ones. Failures are only logged:
+/// snapshots must never block saving the document itself.
+pub(crate) fn take_document_snapshot(
+    project_root: &Path,
+    jliff_rel_path: &str,
+    document: &JliffDocument,
+    retention: usize,
+) -> Option<String> {
+    let dir = snapshot_dir_for(project_root, jliff_rel_path);
+    let path = match write_snapshot(&dir, document, Utc::now()) {
+        Ok(path) => path,
+        Err(error) => {
+            warn!(
+                target: "ipc::jliff",
+                "unable to snapshot JLIFF document {jliff_rel_path}: {error:#}"
+            );
+            return None;
+        }
+    };
+    if let Err(error) = prune_snapshots(&dir, retention) {
+        warn!(
+            target: "ipc::jliff",
+            "unable to prune snapshots of {jliff_rel_path}: {error:#}"
+        );
+    }
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+}
+
+/// Commits the project's documents to its history when the project opted in. Failures are
+/// only logged: the history must never block the change it records.
+pub(crate) async fn record_project_history(
+    db: &DbManager,
+    settings: &SettingsManager,
+    project_uuid: Uuid,
+    event: HistoryEvent,
+) {
+    let Ok(project_root) = resolve_project_root(db, settings, project_uuid).await else {
+        return;
+    };
+    if !history::is_enabled(&project_root) {
+        return;
+    }
+    let kind = event.kind;
+    match tokio::task::spawn_blocking(move || history::commit(&project_root, &event)).await {
+        Ok(Ok(_)) => {}
+        Ok(Err(error)) => warn!(
+            target: "ipc::history",
+            "unable to record {kind} in the history of project {project_uuid}: {error:#}"
+        ),
+        Err(join_err) => warn!(
+            target: "ipc::history",
+            "unable to record {kind} in the history of project {project_uuid}: {join_err}"
+        ),
+    }
+}
+
+static TAG_MAP_CACHE: OnceLock<DocumentCache<TagMapDoc>> = OnceLock::new();
+
+fn tag_map_cache() -> &'static DocumentCache<TagMapDoc> {
+    TAG_MAP_CACHE.get_or_init(|| DocumentCache::new(DEFAULT_CACHE_CAPACITY))
+}
+
+/// Loads the tag map that sits next to `jliff_path` through the shared document cache, so
+/// repeated lookups for the same file only parse it once per change on disk.
+pub(crate) async fn load_cached_tag_map(
+    jliff_path: &Path,
+    jliff_rel_path: &str,
+) -> Result<Arc<TagMapDoc>, IpcError> {
+    let tag_map_path = tag_map_path_for(jliff_path).ok_or_else(|| {
+        IpcError::Validation(format!(
+            "'{jliff_rel_path}' is not a JLIFF artifact with an associated tag map."
+        ))
+    })?;
+    let path_for_read = tag_map_path.clone();
+    tokio::task::spawn_blocking(move || tag_map_cache().get_or_load(&path_for_read, read_tag_map))
+        .await
+        .map_err(|join_err| IpcError::Internal(format!("Failed to read tag map: {join_err}")))?
+        .map_err(|error| {
+            warn!(
+                target: "ipc::jliff",
+                "unable to load tag map {}: {error:#}",
+                tag_map_path.display()
+            );
+            IpcError::Validation(format!(
+                "The tag map for '{jliff_rel_path}' could not be opened."
+            ))
+        })
+}
+
 /// Safe async wrapper around `tokio::fs::try_exists` that logs failures instead
 /// of bubbling them to higher layers. We intentionally swallow the error to
 /// avoid breaking settings views when the filesystem is transiently unavailable.
@@ -89,3 +231,149 @@ pub(crate) fn fs_error(action: &str, error: std::io::Error) -> IpcError {
     );
     IpcError::Internal("File system operation failed. Check folder permissions and retry.".into())
 }
+
+/// Joins a project-relative path onto the project root, rejecting absolute paths
+/// and `..` components so IPC callers cannot escape the project folder.
+pub(crate) fn resolve_project_relative_path(
+    project_root: &Path,
+    rel_path: &str,
+) -> Result<PathBuf, IpcError> {
+    let candidate = Path::new(rel_path);
+    let escapes = candidate.components().any(|component| {
+        matches!(
+            component,
+            Component::ParentDir | Component::RootDir | Component::Prefix(_)
+        )
+    });
+    if rel_path.trim().is_empty() || escapes {
+        return Err(IpcError::Validation(format!(
+            "Path '{rel_path}' must be relative to the project folder."
+        )));
+    }
+    Ok(project_root.join(candidate))
+}
+
+/// Resolves a project's JLIFF artifact and parses it, returning the absolute path
+/// alongside the document so callers can write it back under the file lock.
+pub(crate) async fn load_project_jliff(
+    db: &DbManager,
+    settings: &SettingsManager,
+    project_uuid: Uuid,
+    jliff_rel_path: &str,
+) -> Result<(PathBuf, JliffDocument), IpcError> {
+    let project_root = resolve_project_root(db, settings, project_uuid).await?;
+    let jliff_path = resolve_project_relative_path(&project_root, jliff_rel_path)?;
+    let path_for_read = jliff_path.clone();
+    let document = tokio::task::spawn_blocking(move || read_jliff_document(&path_for_read))
+        .await
+        .map_err(|join_err| {
+            IpcError::Internal(format!("Failed to read JLIFF document: {join_err}"))
+        })?
+        .map_err(|error| {
+            warn!(
+                target: "ipc::jliff",
+                "unable to load JLIFF document {}: {error:#}",
+                jliff_path.display()
+            );
+            IpcError::Validation(format!(
+                "JLIFF document '{jliff_rel_path}' could not be opened."
+            ))
+        })?;
+    Ok((jliff_path, document))
+}
+
+/// Applies `mutate` to a project's JLIFF document and writes it back, holding the per-file
+/// lock for the whole read-modify-write cycle.
+pub(crate) async fn update_project_jliff<T, F>(
+    db: &DbManager,
+    settings: &SettingsManager,
+    project_uuid: Uuid,
+    jliff_rel_path: &str,
+    mutate: F,
+) -> Result<T, IpcError>
+where
+    F: FnOnce(&mut JliffDocument) -> Result<T, IpcError> + Send + 'static,
+    T: Send + 'static,
+{
+    let project_root = resolve_project_root(db, settings, project_uuid).await?;
+    let jliff_path = resolve_project_relative_path(&project_root, jliff_rel_path)?;
+    let rel_path = jliff_rel_path.to_string();
+    let current = settings.current().await;
+    let policy = SnapshotPolicy {
+        interval: Duration::from_secs(u64::from(current.snapshot_interval_minutes) * 60),
+        edit_threshold: current.snapshot_edit_threshold,
+    };
+    let retention = current.snapshot_retention as usize;
+
+    with_project_file_lock(&jliff_path, || async {
+        let path = jliff_path.clone();
+        let project_root = project_root.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut document = read_jliff_document(&path).map_err(|error| {
+                warn!(
+                    target: "ipc::jliff",
+                    "unable to load JLIFF document {}: {error:#}",
+                    path.display()
+                );
+                IpcError::Validation(format!("JLIFF document '{rel_path}' could not be opened."))
+            })?;
+            let result = mutate(&mut document)?;
+            write_jliff_document(&path, &document).map_err(|error| {
+                error!(
+                    target: "ipc::jliff",
+                    "unable to write JLIFF document {}: {error:#}",
+                    path.display()
+                );
+                IpcError::Internal(format!("JLIFF document '{rel_path}' could not be saved."))
+            })?;
+            if snapshot_due(&path, policy) {
+                take_document_snapshot(&project_root, &rel_path, &document, retention);
+            }
+            Ok(result)
+        })
+        .await
+        .map_err(|join_err| {
+            IpcError::Internal(format!("Failed to update JLIFF document: {join_err}"))
+        })?
+    })
+    .await
+}
+
+/// Lists the project-relative paths (with `/` separators) of every JLIFF document under the
+/// project root. Parts written by a document split are skipped so repetitions are not
+/// counted twice.
+pub(crate) async fn list_project_jliff_paths(project_root: &Path) -> Result<Vec<String>, IpcError> {
+    let mut pending = vec![project_root.to_path_buf()];
+    let mut paths = Vec::new();
+    while let Some(dir) = pending.pop() {
+        let mut entries = fs::read_dir(&dir)
+            .await
+            .map_err(|error| fs_error("list the project folder", error))?;
+        while let Some(entry) = entries
+            .next_entry()
+            .await
+            .map_err(|error| fs_error("list the project folder", error))?
+        {
+            let path = entry.path();
+            let file_type = entry
+                .file_type()
+                .await
+                .map_err(|error| fs_error("list the project folder", error))?;
+            if file_type.is_dir() {
+                if entry.file_name() != "split" {
+                    pending.push(path);
+                }
+            } else if entry.file_name().to_string_lossy().ends_with(".jliff.json")
+                && let Ok(relative) = path.strip_prefix(project_root)
+            {
+                let components: Vec<String> = relative
+                    .components()
+                    .map(|component| component.as_os_str().to_string_lossy().into_owned())
+                    .collect();
+                paths.push(components.join("/"));
+            }
+        }
+    }
+    paths.sort();
+    Ok(paths)
+}
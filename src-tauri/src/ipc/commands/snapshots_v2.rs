@@ -0,0 +1,125 @@
+use tauri::State;
+use uuid::Uuid;
+
+use super::projects_v2::resolve_project_root;
+use super::shared::{
+    record_project_history, resolve_project_relative_path, take_document_snapshot,
+    update_project_jliff,
+};
+use crate::db::DbManager;
+use crate::ipc::dto::{
+    DocumentSnapshotDto, ListDocumentSnapshotsPayload, RestoreDocumentSnapshotDto,
+    RestoreDocumentSnapshotPayload,
+};
+use crate::ipc::error::{IpcError, IpcResult};
+use crate::jliff::history::HistoryEvent;
+use crate::jliff::snapshots::{list_snapshots, read_snapshot, snapshot_dir_for};
+use crate::metrics;
+use crate::settings::SettingsManager;
+
+/// Lists the automatic snapshots of a JLIFF document, newest first.
+#[tauri::command]
+pub async fn list_document_snapshots_v2(
+    db: State<'_, DbManager>,
+    settings: State<'_, SettingsManager>,
+    payload: ListDocumentSnapshotsPayload,
+) -> IpcResult<Vec<DocumentSnapshotDto>> {
+    metrics::track("list_document_snapshots_v2", async {
+        let project_uuid = parse_uuid(&payload.project_uuid, "projectUuid")?;
+        let project_root = resolve_project_root(db.inner(), settings.inner(), project_uuid).await?;
+        resolve_project_relative_path(&project_root, &payload.jliff_rel_path)?;
+        let dir = snapshot_dir_for(&project_root, &payload.jliff_rel_path);
+
+        let snapshots = tokio::task::spawn_blocking(move || list_snapshots(&dir))
+            .await
+            .map_err(|join_err| {
+                IpcError::Internal(format!("Failed to list snapshots: {join_err}"))
+            })?
+            .map_err(|error| IpcError::Internal(format!("Failed to list snapshots: {error:#}")))?;
+
+        Ok(snapshots
+            .into_iter()
+            .map(|snapshot| DocumentSnapshotDto {
+                snapshot_id: snapshot.id,
+                created_at: snapshot.created_at.to_rfc3339(),
+                size_bytes: snapshot.size_bytes,
+            })
+            .collect())
+    })
+    .await
+}
+
+/// Replaces a JLIFF document with one of its snapshots. The current content is snapshotted
+/// first so the restore itself can be reverted.
+#[tauri::command]
+pub async fn restore_document_snapshot_v2(
+    db: State<'_, DbManager>,
+    settings: State<'_, SettingsManager>,
+    payload: RestoreDocumentSnapshotPayload,
+) -> IpcResult<RestoreDocumentSnapshotDto> {
+    metrics::track("restore_document_snapshot_v2", async {
+        let project_uuid = parse_uuid(&payload.project_uuid, "projectUuid")?;
+        let project_root = resolve_project_root(db.inner(), settings.inner(), project_uuid).await?;
+        resolve_project_relative_path(&project_root, &payload.jliff_rel_path)?;
+        let dir = snapshot_dir_for(&project_root, &payload.jliff_rel_path);
+
+        let snapshot_id = payload.snapshot_id.trim().to_string();
+        let restored = {
+            let snapshot_id = snapshot_id.clone();
+            tokio::task::spawn_blocking(move || read_snapshot(&dir, &snapshot_id))
+                .await
+                .map_err(|join_err| {
+                    IpcError::Internal(format!("Failed to read snapshot: {join_err}"))
+                })?
+                .map_err(|error| {
+                    IpcError::Validation(format!(
+                        "Snapshot '{}' could not be restored: {error:#}",
+                        payload.snapshot_id
+                    ))
+                })?
+        };
+
+        // The pre-restore snapshot is kept on top of the configured retention.
+        let retention = settings.current().await.snapshot_retention as usize + 1;
+        let jliff_rel_path = payload.jliff_rel_path.clone();
+        let (pre_restore_snapshot_id, transunit_count) = update_project_jliff(
+            db.inner(),
+            settings.inner(),
+            project_uuid,
+            &payload.jliff_rel_path,
+            move |document| {
+                let pre_restore =
+                    take_document_snapshot(&project_root, &jliff_rel_path, document, retention);
+                *document = restored;
+                Ok((pre_restore, document.transunits.len()))
+            },
+        )
+        .await?;
+        record_project_history(
+            db.inner(),
+            settings.inner(),
+            project_uuid,
+            HistoryEvent {
+                kind: "snapshot-restore",
+                summary: format!(
+                    "Restore {} from snapshot {snapshot_id}",
+                    payload.jliff_rel_path
+                ),
+                files: vec![payload.jliff_rel_path.clone()],
+            },
+        )
+        .await;
+
+        Ok(RestoreDocumentSnapshotDto {
+            snapshot_id,
+            pre_restore_snapshot_id,
+            transunit_count,
+        })
+    })
+    .await
+}
+
+fn parse_uuid(value: &str, field: &str) -> Result<Uuid, IpcError> {
+    Uuid::parse_str(value)
+        .map_err(|_| IpcError::Validation(format!("invalid {field}: expected UUID, got '{value}'")))
+}
@@ -0,0 +1,248 @@
+use std::sync::Arc;
+
+use serde_json::json;
+use spellbook::Dictionary;
+use tauri::State;
+use uuid::Uuid;
+
+use super::qa_v2::project_qa_profile;
+use super::shared::load_project_jliff;
+use crate::db::DbManager;
+use crate::db::types::{NewQaFindingArgs, ReplaceQaFindingsArgs};
+use crate::ipc::dto::{
+    DocumentSpellcheckDto, SegmentSpellcheckDto, SpellcheckDocumentPayload,
+    SpellcheckSegmentPayload,
+};
+use crate::ipc::error::{IpcError, IpcResult};
+use crate::jliff::document::find_transunit;
+use crate::metrics;
+use crate::settings::SettingsManager;
+use crate::spellcheck::{Misspelling, SpellcheckError, SpellcheckService, check_text};
+
+/// QA check identifier used when persisting spelling findings.
+const SPELLING_CHECK: &str = "spelling";
+
+#[tauri::command]
+pub async fn spellcheck_segment_v2(
+    db: State<'_, DbManager>,
+    settings: State<'_, SettingsManager>,
+    spellcheck: State<'_, SpellcheckService>,
+    payload: SpellcheckSegmentPayload,
+) -> IpcResult<SegmentSpellcheckDto> {
+    metrics::track("spellcheck_segment_v2", async {
+        let project_uuid = parse_uuid(&payload.project_uuid, "projectUuid")?;
+        let (_, document) = load_project_jliff(
+            db.inner(),
+            settings.inner(),
+            project_uuid,
+            &payload.jliff_rel_path,
+        )
+        .await?;
+
+        let unit = find_transunit(&document, &payload.transunit_id).ok_or_else(|| {
+            IpcError::Validation(format!(
+                "Segment '{}' was not found in '{}'.",
+                payload.transunit_id, payload.jliff_rel_path
+            ))
+        })?;
+
+        let language = payload
+            .language
+            .clone()
+            .unwrap_or_else(|| document.target_language.clone());
+        let dictionary = resolve_dictionary(
+            settings.inner(),
+            spellcheck.inner(),
+            &language,
+            payload.allow_download.unwrap_or(true),
+        )
+        .await?;
+
+        let misspellings = check_text(&dictionary, unit.effective_target());
+
+        if payload.persist_findings.unwrap_or(true) {
+            record_findings(
+                db.inner(),
+                project_uuid,
+                &payload.jliff_rel_path,
+                Some(vec![payload.transunit_id.clone()]),
+                &[(payload.transunit_id.as_str(), misspellings.as_slice())],
+            )
+            .await?;
+        }
+
+        Ok(SegmentSpellcheckDto {
+            transunit_id: payload.transunit_id,
+            misspellings,
+        })
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn spellcheck_document_v2(
+    db: State<'_, DbManager>,
+    settings: State<'_, SettingsManager>,
+    spellcheck: State<'_, SpellcheckService>,
+    payload: SpellcheckDocumentPayload,
+) -> IpcResult<DocumentSpellcheckDto> {
+    metrics::track("spellcheck_document_v2", async {
+        let project_uuid = parse_uuid(&payload.project_uuid, "projectUuid")?;
+        let (_, document) = load_project_jliff(
+            db.inner(),
+            settings.inner(),
+            project_uuid,
+            &payload.jliff_rel_path,
+        )
+        .await?;
+
+        let language = payload
+            .language
+            .clone()
+            .unwrap_or_else(|| document.target_language.clone());
+        let dictionary = resolve_dictionary(
+            settings.inner(),
+            spellcheck.inner(),
+            &language,
+            payload.allow_download.unwrap_or(true),
+        )
+        .await?;
+
+        let checked = tokio::task::spawn_blocking({
+            let dictionary = Arc::clone(&dictionary);
+            move || {
+                document
+                    .transunits
+                    .iter()
+                    .map(|unit| SegmentSpellcheckDto {
+                        transunit_id: unit.transunit_id.clone(),
+                        misspellings: check_text(&dictionary, unit.effective_target()),
+                    })
+                    .filter(|segment| !segment.misspellings.is_empty())
+                    .collect::<Vec<_>>()
+            }
+        })
+        .await
+        .map_err(|join_err| IpcError::Internal(format!("Spellcheck task failed: {join_err}")))?;
+
+        let total_misspellings = checked
+            .iter()
+            .map(|segment| segment.misspellings.len())
+            .sum();
+
+        let findings_recorded = if payload.persist_findings.unwrap_or(true) {
+            let grouped: Vec<(&str, &[Misspelling])> = checked
+                .iter()
+                .map(|segment| {
+                    (
+                        segment.transunit_id.as_str(),
+                        segment.misspellings.as_slice(),
+                    )
+                })
+                .collect();
+            record_findings(
+                db.inner(),
+                project_uuid,
+                &payload.jliff_rel_path,
+                None,
+                &grouped,
+            )
+            .await?
+        } else {
+            0
+        };
+
+        Ok(DocumentSpellcheckDto {
+            jliff_rel_path: payload.jliff_rel_path,
+            language,
+            segments: checked,
+            total_misspellings,
+            findings_recorded,
+        })
+    })
+    .await
+}
+
+async fn resolve_dictionary(
+    settings: &SettingsManager,
+    spellcheck: &SpellcheckService,
+    language: &str,
+    allow_download: bool,
+) -> Result<Arc<Dictionary>, IpcError> {
+    let app_folder = settings.app_folder().await;
+    spellcheck
+        .dictionary_for(&app_folder, language, allow_download)
+        .await
+        .map_err(map_spellcheck_error)
+}
+
+/// Replaces the stored spelling findings. The project's QA profile decides their severity;
+/// when it disables the spelling check, the stored findings are only cleared.
+async fn record_findings(
+    db: &DbManager,
+    project_uuid: Uuid,
+    jliff_rel_path: &str,
+    transunit_ids: Option<Vec<String>>,
+    segments: &[(&str, &[Misspelling])],
+) -> Result<usize, IpcError> {
+    let check = project_qa_profile(db, project_uuid)
+        .await?
+        .check(SPELLING_CHECK, "warning");
+    let severity = &check.severity;
+    let findings = segments
+        .iter()
+        .filter(|_| check.enabled)
+        .flat_map(|(transunit_id, misspellings)| {
+            misspellings.iter().map(move |entry| NewQaFindingArgs {
+                transunit_id: transunit_id.to_string(),
+                severity: severity.clone(),
+                message: format!("Possible misspelling: '{}'", entry.word),
+                details: Some(
+                    json!({
+                        "word": entry.word,
+                        "start": entry.start,
+                        "end": entry.end,
+                        "suggestions": entry.suggestions,
+                    })
+                    .to_string(),
+                ),
+            })
+        })
+        .collect();
+
+    let inserted = db
+        .replace_qa_findings(ReplaceQaFindingsArgs {
+            project_uuid,
+            jliff_rel_path: jliff_rel_path.to_string(),
+            check_type: SPELLING_CHECK.into(),
+            transunit_ids,
+            findings,
+        })
+        .await
+        .map_err(IpcError::from)?;
+    Ok(inserted.len())
+}
+
+fn map_spellcheck_error(error: SpellcheckError) -> IpcError {
+    match error {
+        SpellcheckError::DictionaryUnavailable(language) => IpcError::Validation(format!(
+            "No spellcheck dictionary is installed for '{language}'."
+        )),
+        SpellcheckError::Download(reason) => {
+            log::warn!(target: "ipc::spellcheck", "dictionary download failed: {reason}");
+            IpcError::Internal(
+                "Unable to download the spellcheck dictionary. Check your connection and retry."
+                    .into(),
+            )
+        }
+        other => {
+            log::error!(target: "ipc::spellcheck", "spellcheck failed: {other}");
+            IpcError::Internal("Spellcheck dictionary could not be loaded.".into())
+        }
+    }
+}
+
+fn parse_uuid(value: &str, field: &str) -> Result<Uuid, IpcError> {
+    Uuid::parse_str(value)
+        .map_err(|_| IpcError::Validation(format!("invalid {field}: expected UUID, got '{value}'")))
+}
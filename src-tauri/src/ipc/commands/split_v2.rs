@@ -0,0 +1,246 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::Context;
+use tauri::State;
+use uuid::Uuid;
+
+use super::projects_v2::resolve_project_root;
+use super::shared::{load_project_jliff, resolve_project_relative_path, update_project_jliff};
+use crate::db::DbManager;
+use crate::ipc::dto::{
+    MergeSplitDocumentsDto, MergeSplitDocumentsPayload, SplitDocumentDto, SplitDocumentPayload,
+    SplitPartDto,
+};
+use crate::ipc::error::{IpcError, IpcResult};
+use crate::jliff::document::{
+    read_jliff_document, read_tag_map, tag_map_path_for, write_jliff_document, write_tag_map,
+};
+use crate::jliff::split::{
+    SPLIT_MANIFEST_VERSION, SplitManifest, SplitPart, SplitStrategy, merge_split_documents,
+    split_document, tag_map_for_part,
+};
+use crate::jliff::statistics::{WordCountStrategy, source_word_count};
+use crate::metrics;
+use crate::settings::SettingsManager;
+
+/// Folder, next to the split document, that receives the parts and their manifest.
+const SPLIT_FOLDER: &str = "split";
+
+/// Splits a JLIFF document into parts that can be handed to different linguists. Parts
+/// and a `<prefix>.split.json` manifest are written to a `split/` folder next to the
+/// document; each part gets a tag map restricted to its units.
+#[tauri::command]
+pub async fn split_document_v2(
+    db: State<'_, DbManager>,
+    settings: State<'_, SettingsManager>,
+    payload: SplitDocumentPayload,
+) -> IpcResult<SplitDocumentDto> {
+    metrics::track("split_document_v2", async {
+        let project_uuid = parse_uuid(&payload.project_uuid, "projectUuid")?;
+        let strategy = match (payload.parts, payload.words_per_part) {
+            (Some(parts), None) => SplitStrategy::Parts(parts),
+            (None, Some(words)) => SplitStrategy::WordsPerPart(words),
+            _ => {
+                return Err(
+                    IpcError::Validation("Provide either parts or wordsPerPart.".into()).into(),
+                );
+            }
+        };
+
+        let (jliff_path, document) = load_project_jliff(
+            db.inner(),
+            settings.inner(),
+            project_uuid,
+            &payload.jliff_rel_path,
+        )
+        .await?;
+        let prefix = jliff_path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_suffix(".jliff.json"))
+            .map(str::to_string)
+            .ok_or_else(|| {
+                IpcError::Validation(format!(
+                    "'{}' is not a JLIFF document.",
+                    payload.jliff_rel_path
+                ))
+            })?;
+        let split_rel_dir = sibling_rel_path(&payload.jliff_rel_path, SPLIT_FOLDER);
+        let manifest_rel_path = format!("{split_rel_dir}/{prefix}.split.json");
+
+        let parts = split_document(&document, strategy)
+            .map_err(|error| IpcError::Validation(error.to_string()))?;
+        let word_count = WordCountStrategy::for_source(&document);
+        let manifest = SplitManifest {
+            version: SPLIT_MANIFEST_VERSION,
+            source_jliff: payload.jliff_rel_path.clone(),
+            source_transunit_count: document.transunits.len(),
+            parts: parts
+                .iter()
+                .enumerate()
+                .map(|(position, part)| SplitPart {
+                    index: position + 1,
+                    jliff_file: format!("{prefix}.part{:02}.jliff.json", position + 1),
+                    word_count: part
+                        .transunits
+                        .iter()
+                        .map(|unit| source_word_count(unit, word_count))
+                        .sum(),
+                    transunit_ids: part
+                        .transunits
+                        .iter()
+                        .map(|unit| unit.transunit_id.clone())
+                        .collect(),
+                })
+                .collect(),
+        };
+
+        let split_dir = jliff_path
+            .parent()
+            .map(|dir| dir.join(SPLIT_FOLDER))
+            .ok_or_else(|| IpcError::Internal("JLIFF document has no parent folder.".into()))?;
+        let manifest_for_write = manifest.clone();
+        let manifest_name = format!("{prefix}.split.json");
+        tokio::task::spawn_blocking(move || -> anyhow::Result<()> {
+            fs::create_dir_all(&split_dir)
+                .with_context(|| format!("Failed to create {}", split_dir.display()))?;
+            let tag_map = match tag_map_path_for(&jliff_path) {
+                Some(path) if path.exists() => Some(read_tag_map(&path)?),
+                _ => None,
+            };
+            for (part, entry) in parts.iter().zip(&manifest_for_write.parts) {
+                let part_path = split_dir.join(&entry.jliff_file);
+                write_jliff_document(&part_path, part)?;
+                if let (Some(tag_map), Some(tag_map_path)) =
+                    (tag_map.as_ref(), tag_map_path_for(&part_path))
+                {
+                    write_tag_map(&tag_map_path, &tag_map_for_part(tag_map, part))?;
+                }
+            }
+            let payload = serde_json::to_string_pretty(&manifest_for_write)
+                .context("Failed to serialize split manifest")?;
+            let manifest_path = split_dir.join(manifest_name);
+            fs::write(&manifest_path, payload)
+                .with_context(|| format!("Failed to write {}", manifest_path.display()))
+        })
+        .await
+        .map_err(|join_err| IpcError::Internal(format!("Failed to split document: {join_err}")))?
+        .map_err(|error| {
+            log::error!(target: "ipc::split", "unable to write split parts: {error:#}");
+            IpcError::Internal("Split parts could not be written.".into())
+        })?;
+
+        Ok(SplitDocumentDto {
+            manifest_rel_path,
+            parts: manifest
+                .parts
+                .iter()
+                .map(|part| SplitPartDto {
+                    index: part.index,
+                    jliff_rel_path: format!("{split_rel_dir}/{}", part.jliff_file),
+                    word_count: part.word_count,
+                    transunit_count: part.transunit_ids.len(),
+                })
+                .collect(),
+        })
+    })
+    .await
+}
+
+/// Recombines the parts listed in a split manifest into the original JLIFF document.
+/// Fails without touching the document unless every transunit is covered exactly once.
+#[tauri::command]
+pub async fn merge_split_documents_v2(
+    db: State<'_, DbManager>,
+    settings: State<'_, SettingsManager>,
+    payload: MergeSplitDocumentsPayload,
+) -> IpcResult<MergeSplitDocumentsDto> {
+    metrics::track("merge_split_documents_v2", async {
+        let project_uuid = parse_uuid(&payload.project_uuid, "projectUuid")?;
+        let project_root = resolve_project_root(db.inner(), settings.inner(), project_uuid).await?;
+        let manifest_path =
+            resolve_project_relative_path(&project_root, &payload.manifest_rel_path)?;
+
+        let manifest: SplitManifest = {
+            let bytes = tokio::fs::read(&manifest_path).await.map_err(|_| {
+                IpcError::Validation(format!(
+                    "Split manifest '{}' could not be read.",
+                    payload.manifest_rel_path
+                ))
+            })?;
+            serde_json::from_slice(&bytes).map_err(|error| {
+                IpcError::Validation(format!(
+                    "Split manifest '{}' is not valid: {error}",
+                    payload.manifest_rel_path
+                ))
+            })?
+        };
+        if manifest.version > SPLIT_MANIFEST_VERSION {
+            return Err(IpcError::Validation(format!(
+                "Split manifest version {} is not supported.",
+                manifest.version
+            ))
+            .into());
+        }
+
+        let mut part_paths = Vec::with_capacity(manifest.parts.len());
+        for part in &manifest.parts {
+            if Path::new(&part.jliff_file).components().count() != 1 {
+                return Err(IpcError::Validation(format!(
+                    "Split part '{}' must be a file name.",
+                    part.jliff_file
+                ))
+                .into());
+            }
+            let rel_path = sibling_rel_path(&payload.manifest_rel_path, &part.jliff_file);
+            part_paths.push((
+                rel_path.clone(),
+                resolve_project_relative_path(&project_root, &rel_path)?,
+            ));
+        }
+
+        let jliff_rel_path = manifest.source_jliff.clone();
+        let merged_parts = part_paths.len();
+        let transunit_count = update_project_jliff(
+            db.inner(),
+            settings.inner(),
+            project_uuid,
+            &jliff_rel_path,
+            move |document| {
+                let mut parts = Vec::with_capacity(part_paths.len());
+                for (rel_path, path) in &part_paths {
+                    parts.push(read_jliff_document(path).map_err(|_| {
+                        IpcError::Validation(format!(
+                            "Split part '{rel_path}' could not be opened."
+                        ))
+                    })?);
+                }
+                *document = merge_split_documents(document, &parts)
+                    .map_err(|error| IpcError::Validation(error.to_string()))?;
+                Ok(document.transunits.len())
+            },
+        )
+        .await?;
+
+        Ok(MergeSplitDocumentsDto {
+            jliff_rel_path,
+            merged_parts,
+            transunit_count,
+        })
+    })
+    .await
+}
+
+/// Joins `name` onto the folder of a project-relative path.
+fn sibling_rel_path(rel_path: &str, name: &str) -> String {
+    match rel_path.rsplit_once('/') {
+        Some((dir, _)) => format!("{dir}/{name}"),
+        None => name.to_string(),
+    }
+}
+
+fn parse_uuid(value: &str, field: &str) -> Result<Uuid, IpcError> {
+    Uuid::parse_str(value)
+        .map_err(|_| IpcError::Validation(format!("invalid {field}: expected UUID, got '{value}'")))
+}
@@ -0,0 +1,114 @@
+//! Managed subject taxonomy: subjects with aliases that project subjects are normalized to,
+//! and typeahead suggestions for the subject field.
+
+use tauri::State;
+
+use crate::db::DbManager;
+use crate::db::types::SubjectTaxonomyRecord;
+use crate::ipc::dto::{SubjectSuggestionDto, SubjectTaxonomyDto, UpsertSubjectPayload};
+use crate::ipc::error::{IpcError, IpcResult};
+use crate::metrics;
+
+/// Suggestions returned unless the request asks for fewer.
+const MAX_SUGGESTIONS: usize = 20;
+/// Longest subject or alias, in characters.
+const MAX_SUBJECT_CHARS: usize = 100;
+
+#[tauri::command]
+pub async fn list_subject_taxonomy_v2(
+    db: State<'_, DbManager>,
+) -> IpcResult<Vec<SubjectTaxonomyDto>> {
+    metrics::track("list_subject_taxonomy_v2", async {
+        let records = db.list_subject_taxonomy().await.map_err(IpcError::from)?;
+        Ok(records.into_iter().map(map_subject).collect())
+    })
+    .await
+}
+
+/// Adds a managed subject, or renames the one spelled alike, and replaces its aliases.
+/// Project subjects matching the subject or an alias are rewritten to it.
+#[tauri::command]
+pub async fn upsert_subject_v2(
+    db: State<'_, DbManager>,
+    payload: UpsertSubjectPayload,
+) -> IpcResult<SubjectTaxonomyDto> {
+    metrics::track("upsert_subject_v2", async {
+        validate_subject(&payload.subject, "subject")?;
+        for alias in &payload.aliases {
+            validate_subject(alias, "alias")?;
+        }
+        let record = db
+            .upsert_subject(&payload.subject, &payload.aliases)
+            .await
+            .map_err(IpcError::from)?;
+        Ok(map_subject(record))
+    })
+    .await
+}
+
+/// Removes a managed subject and its aliases; projects keep the subject as free text.
+#[tauri::command]
+pub async fn delete_subject_v2(db: State<'_, DbManager>, subject: String) -> IpcResult<()> {
+    metrics::track("delete_subject_v2", async {
+        if !db.delete_subject(&subject).await.map_err(IpcError::from)? {
+            return Err(IpcError::Validation(format!("Subject '{subject}' was not found.")).into());
+        }
+        Ok(())
+    })
+    .await
+}
+
+/// Subjects with a word starting with `prefix`, managed subjects first, then the subjects
+/// projects use, most used first.
+#[tauri::command]
+pub async fn suggest_subjects_v2(
+    db: State<'_, DbManager>,
+    prefix: String,
+    limit: Option<usize>,
+) -> IpcResult<Vec<SubjectSuggestionDto>> {
+    metrics::track("suggest_subjects_v2", async {
+        let limit = limit.unwrap_or(MAX_SUGGESTIONS);
+        if limit == 0 || limit > MAX_SUGGESTIONS {
+            return Err(IpcError::Validation(format!(
+                "limit must be between 1 and {MAX_SUGGESTIONS}."
+            ))
+            .into());
+        }
+        let suggestions = db
+            .suggest_subjects(&prefix, limit)
+            .await
+            .map_err(IpcError::from)?;
+        Ok(suggestions
+            .into_iter()
+            .map(|suggestion| SubjectSuggestionDto {
+                subject: suggestion.subject,
+                matched_alias: suggestion.matched_alias,
+                managed: suggestion.managed,
+                project_count: suggestion.project_count,
+            })
+            .collect())
+    })
+    .await
+}
+
+fn validate_subject(value: &str, field: &str) -> Result<(), IpcError> {
+    if !value.chars().any(char::is_alphanumeric) {
+        return Err(IpcError::Validation(format!(
+            "{field} must contain a letter or digit."
+        )));
+    }
+    if value.trim().chars().count() > MAX_SUBJECT_CHARS {
+        return Err(IpcError::Validation(format!(
+            "{field} can have at most {MAX_SUBJECT_CHARS} characters."
+        )));
+    }
+    Ok(())
+}
+
+fn map_subject(record: SubjectTaxonomyRecord) -> SubjectTaxonomyDto {
+    SubjectTaxonomyDto {
+        subject: record.subject,
+        aliases: record.aliases,
+        created_at: record.created_at,
+    }
+}
@@ -0,0 +1,189 @@
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use chrono::Utc;
+use serde_json::{Value, json};
+use tauri::{AppHandle, Manager, State};
+use tokio::fs;
+
+use super::health_check;
+use super::jobs_v2::map_job_record;
+use super::settings::build_app_settings_dto;
+use crate::db::DbManager;
+use crate::ipc::dto::{CreateSupportBundlePayload, SupportBundleDto};
+use crate::ipc::error::{IpcError, IpcResult};
+use crate::metrics;
+use crate::settings::SettingsManager;
+use crate::support::{Anonymizer, BundleEntry, write_zip};
+
+/// Folder under the app folder receiving support bundles.
+const SUPPORT_DIR: &str = "support";
+/// Most recent log files included.
+const MAX_LOG_FILES: usize = 5;
+/// Log files older than this are left out.
+const MAX_LOG_AGE: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+/// Only the tail of larger log files is included.
+const MAX_LOG_BYTES: usize = 2 * 1024 * 1024;
+/// Failing job rows included.
+const MAX_FAILING_JOBS: i64 = 200;
+/// Environment variables whose presence (never their value) is reported.
+const REPORTED_ENV_VARS: [&str; 4] = [
+    "LLM_API_KEY",
+    "LLM_API_BASE_URL",
+    "LLM_MODEL",
+    "GOOGLE_MAPS_API_KEY",
+];
+
+/// Zips recent logs, a diagnostics report, the settings (secrets stripped), the schema
+/// version and failing job rows into one anonymised file for bug reports. No document
+/// content is included.
+#[tauri::command]
+pub async fn create_support_bundle_v2(
+    app: AppHandle,
+    db: State<'_, DbManager>,
+    settings: State<'_, SettingsManager>,
+    payload: Option<CreateSupportBundlePayload>,
+) -> IpcResult<SupportBundleDto> {
+    metrics::track("create_support_bundle_v2", async {
+        let payload = payload.unwrap_or_default();
+        let current = settings.current().await;
+        let destination_dir = match payload.destination_dir.as_deref().map(str::trim) {
+            Some(dir) if !dir.is_empty() => {
+                let dir = PathBuf::from(dir);
+                if !dir.is_absolute() {
+                    return Err(IpcError::Validation(
+                        "destinationDir must be an absolute path.".into(),
+                    )
+                    .into());
+                }
+                dir
+            }
+            _ => current.app_folder.join(SUPPORT_DIR),
+        };
+        let anonymizer =
+            Anonymizer::new(app.path().home_dir().ok().as_deref(), &current.app_folder);
+
+        let schema_version = db.schema_version().await.map_err(IpcError::from)?;
+        let failing_jobs: Vec<_> = db
+            .list_failing_jobs(MAX_FAILING_JOBS)
+            .await
+            .map_err(IpcError::from)?
+            .into_iter()
+            .map(map_job_record)
+            .collect();
+
+        let mut diagnostics = json!({
+            "generatedAt": Utc::now().to_rfc3339(),
+            "health": health_check(app.clone()).await,
+            "os": std::env::consts::OS,
+            "arch": std::env::consts::ARCH,
+            "schemaVersion": schema_version,
+            "database": {
+                "journalMode": current.database_journal_mode,
+                "synchronous": current.database_synchronous,
+            },
+            "environment": REPORTED_ENV_VARS
+                .iter()
+                .map(|name| (name.to_string(), Value::Bool(std::env::var_os(name).is_some())))
+                .collect::<serde_json::Map<_, _>>(),
+            "failingJobCount": failing_jobs.len(),
+        });
+        let mut settings_report =
+            serde_json::to_value(build_app_settings_dto(&app, &settings).await?)
+                .map_err(|error| IpcError::Internal(error.to_string()))?;
+        let mut jobs_report = serde_json::to_value(&failing_jobs)
+            .map_err(|error| IpcError::Internal(error.to_string()))?;
+        for report in [&mut diagnostics, &mut settings_report, &mut jobs_report] {
+            anonymizer.apply_json(report);
+        }
+
+        let mut entries = vec![
+            json_entry("diagnostics.json", &diagnostics)?,
+            json_entry("settings.json", &settings_report)?,
+            json_entry("schema_version.txt", &json!(schema_version))?,
+            json_entry("failing_jobs.json", &jobs_report)?,
+        ];
+        match app.path().app_log_dir() {
+            Ok(log_dir) => {
+                for (name, text) in recent_logs(&log_dir).await {
+                    entries.push(BundleEntry {
+                        name: format!("logs/{name}"),
+                        contents: anonymizer.apply(&text).into_bytes(),
+                    });
+                }
+            }
+            Err(error) => log::warn!(target: "ipc::support", "log directory unavailable: {error}"),
+        }
+
+        fs::create_dir_all(&destination_dir)
+            .await
+            .map_err(|error| {
+                IpcError::Internal(format!("Failed to create {SUPPORT_DIR} folder: {error}"))
+            })?;
+        let path = destination_dir.join(format!(
+            "support-bundle-{}.zip",
+            Utc::now().format("%Y%m%d-%H%M%S")
+        ));
+        let files = entries.iter().map(|entry| entry.name.clone()).collect();
+        let zip_path = path.clone();
+        tokio::task::spawn_blocking(move || write_zip(&zip_path, &entries))
+            .await
+            .map_err(|error| IpcError::Internal(error.to_string()))?
+            .map_err(|error| {
+                IpcError::Internal(format!("Failed to write support bundle: {error}"))
+            })?;
+        let size = fs::metadata(&path)
+            .await
+            .map(|meta| meta.len())
+            .unwrap_or_default();
+
+        Ok(SupportBundleDto {
+            path: path.to_string_lossy().into_owned(),
+            size,
+            files,
+        })
+    })
+    .await
+}
+
+fn json_entry(name: &str, value: &Value) -> Result<BundleEntry, IpcError> {
+    let contents =
+        serde_json::to_vec_pretty(value).map_err(|error| IpcError::Internal(error.to_string()))?;
+    Ok(BundleEntry {
+        name: name.to_string(),
+        contents,
+    })
+}
+
+/// The most recent log files of the last week, each cut to its tail.
+async fn recent_logs(log_dir: &Path) -> Vec<(String, String)> {
+    let Ok(mut dir) = fs::read_dir(log_dir).await else {
+        return Vec::new();
+    };
+    let cutoff = SystemTime::now() - MAX_LOG_AGE;
+    let mut files = Vec::new();
+    while let Ok(Some(entry)) = dir.next_entry().await {
+        let Ok(metadata) = entry.metadata().await else {
+            continue;
+        };
+        let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+        if metadata.is_file() && modified >= cutoff {
+            files.push((modified, entry.path()));
+        }
+    }
+    files.sort_by(|a, b| b.0.cmp(&a.0));
+
+    let mut logs = Vec::new();
+    for (_, path) in files.into_iter().take(MAX_LOG_FILES) {
+        let Ok(bytes) = fs::read(&path).await else {
+            continue;
+        };
+        let tail = &bytes[bytes.len().saturating_sub(MAX_LOG_BYTES)..];
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        logs.push((name, String::from_utf8_lossy(tail).into_owned()));
+    }
+    logs
+}
@@ -0,0 +1,89 @@
+use std::collections::HashSet;
+
+use tauri::State;
+use uuid::Uuid;
+
+use super::projects_v2::resolve_project_root;
+use super::shared::{load_cached_tag_map, resolve_project_relative_path};
+use crate::db::DbManager;
+use crate::ipc::dto::{
+    GetTagMapEntriesPayload, TagMapEntriesDto, TagMapEntryDto, TagPlaceholderDto,
+};
+use crate::ipc::error::{IpcError, IpcResult};
+use crate::jliff::tag_map::TagInstance;
+use crate::metrics;
+use crate::settings::SettingsManager;
+
+/// Upper bound on transunits per request; the editor asks for the visible page only.
+const MAX_TRANSUNITS_PER_REQUEST: usize = 500;
+
+/// Returns the tag-map placeholders for the requested transunits of a JLIFF artifact, so the
+/// editor can show placeholder details without loading the whole `.tags.json` file.
+#[tauri::command]
+pub async fn get_tag_map_entries_v2(
+    db: State<'_, DbManager>,
+    settings: State<'_, SettingsManager>,
+    payload: GetTagMapEntriesPayload,
+) -> IpcResult<TagMapEntriesDto> {
+    metrics::track("get_tag_map_entries_v2", async {
+        let project_uuid = parse_uuid(&payload.project_uuid, "projectUuid")?;
+        if payload.transunit_ids.len() > MAX_TRANSUNITS_PER_REQUEST {
+            return Err(IpcError::Validation(format!(
+                "At most {MAX_TRANSUNITS_PER_REQUEST} transunits can be requested at once."
+            ))
+            .into());
+        }
+
+        let project_root = resolve_project_root(db.inner(), settings.inner(), project_uuid).await?;
+        let jliff_path = resolve_project_relative_path(&project_root, &payload.jliff_rel_path)?;
+        let tag_map = load_cached_tag_map(&jliff_path, &payload.jliff_rel_path).await?;
+
+        let requested: HashSet<&str> = payload.transunit_ids.iter().map(String::as_str).collect();
+        let mut found: Vec<TagMapEntryDto> = tag_map
+            .segments_by_transunit()
+            .filter(|(transunit_id, _)| requested.contains(transunit_id.as_str()))
+            .map(|(transunit_id, segment)| TagMapEntryDto {
+                transunit_id,
+                placeholders: segment.placeholders.iter().map(map_tag_instance).collect(),
+            })
+            .collect();
+
+        // Answer in request order, once per id.
+        let mut seen = HashSet::new();
+        let mut entries = Vec::with_capacity(found.len());
+        let mut missing_transunit_ids = Vec::new();
+        for transunit_id in &payload.transunit_ids {
+            if !seen.insert(transunit_id.as_str()) {
+                continue;
+            }
+            match found
+                .iter()
+                .position(|entry| &entry.transunit_id == transunit_id)
+            {
+                Some(index) => entries.push(found.swap_remove(index)),
+                None => missing_transunit_ids.push(transunit_id.clone()),
+            }
+        }
+
+        Ok(TagMapEntriesDto {
+            entries,
+            missing_transunit_ids,
+        })
+    })
+    .await
+}
+
+fn map_tag_instance(instance: &TagInstance) -> TagPlaceholderDto {
+    TagPlaceholderDto {
+        placeholder: instance.placeholder.clone(),
+        elem: instance.elem.clone(),
+        id: instance.id.clone(),
+        attrs: instance.attrs.clone(),
+        original_data: instance.original_data.clone(),
+    }
+}
+
+fn parse_uuid(value: &str, field: &str) -> Result<Uuid, IpcError> {
+    Uuid::parse_str(value)
+        .map_err(|_| IpcError::Validation(format!("invalid {field}: expected UUID, got '{value}'")))
+}
@@ -0,0 +1,95 @@
+use tauri::State;
+use uuid::Uuid;
+
+use super::shared::{load_project_jliff, update_project_jliff};
+use crate::db::DbManager;
+use crate::ipc::dto::{RepairSegmentTagsPayload, SegmentTagRepairDto};
+use crate::ipc::error::{IpcError, IpcResult};
+use crate::jliff::JliffDocument;
+use crate::jliff::tag_repair::repair_tags;
+use crate::metrics;
+use crate::settings::SettingsManager;
+
+/// Repairs missing, duplicated, or reordered placeholders in a segment's current target.
+/// The repaired text is written back unless `dryRun` is set; either way the report lists what
+/// was changed and what still needs human attention.
+#[tauri::command]
+pub async fn repair_segment_tags_v2(
+    db: State<'_, DbManager>,
+    settings: State<'_, SettingsManager>,
+    payload: RepairSegmentTagsPayload,
+) -> IpcResult<SegmentTagRepairDto> {
+    metrics::track("repair_segment_tags_v2", async {
+        let project_uuid = parse_uuid(&payload.project_uuid, "projectUuid")?;
+
+        if payload.dry_run {
+            let (_, mut document) = load_project_jliff(
+                db.inner(),
+                settings.inner(),
+                project_uuid,
+                &payload.jliff_rel_path,
+            )
+            .await?;
+            let mut result = repair_unit(&mut document, &payload)?;
+            result.applied = false;
+            return Ok(result);
+        }
+
+        let request = payload.clone();
+        let result = update_project_jliff(
+            db.inner(),
+            settings.inner(),
+            project_uuid,
+            &payload.jliff_rel_path,
+            move |document| repair_unit(document, &request),
+        )
+        .await?;
+
+        if result.applied {
+            log::info!(
+                target: "ipc::tag_repair",
+                "repaired tags of segment '{}' in '{}' ({} change(s), {} open issue(s))",
+                result.transunit_id,
+                payload.jliff_rel_path,
+                result.report.repairs.len(),
+                result.report.needs_attention.len()
+            );
+        }
+        Ok(result)
+    })
+    .await
+}
+
+fn repair_unit(
+    document: &mut JliffDocument,
+    payload: &RepairSegmentTagsPayload,
+) -> Result<SegmentTagRepairDto, IpcError> {
+    let unit = document
+        .transunits
+        .iter_mut()
+        .find(|unit| unit.transunit_id == payload.transunit_id)
+        .ok_or_else(|| {
+            IpcError::Validation(format!(
+                "Segment '{}' was not found in '{}'.",
+                payload.transunit_id, payload.jliff_rel_path
+            ))
+        })?;
+
+    let original_text = unit.effective_target().to_string();
+    let report = repair_tags(&unit.source, &original_text);
+    if report.changed {
+        *unit.effective_target_mut() = report.repaired_text.clone();
+    }
+
+    Ok(SegmentTagRepairDto {
+        transunit_id: unit.transunit_id.clone(),
+        original_text,
+        applied: report.changed,
+        report,
+    })
+}
+
+fn parse_uuid(value: &str, field: &str) -> Result<Uuid, IpcError> {
+    Uuid::parse_str(value)
+        .map_err(|_| IpcError::Validation(format!("invalid {field}: expected UUID, got '{value}'")))
+}
@@ -0,0 +1,379 @@
+//! Term extraction from project content and review of the resulting candidates. Accepted
+//! candidates become termbase entries; rejected ones are remembered so later extractions do
+//! not propose them again. Segments are looked up in the termbase with inflection-tolerant
+//! matching.
+
+use std::collections::HashSet;
+
+use tauri::State;
+use uuid::Uuid;
+
+use super::shared::load_project_jliff;
+use super::translation_memory_v2::load_pair_documents;
+use crate::db::DbManager;
+use crate::db::types::{
+    NewTermCandidateArgs, TermCandidateDecisionArgs, TermCandidateRecord, TermbaseEntryRecord,
+};
+use crate::ipc::dto::{
+    ExtractTermsPayload, ReviewTermCandidatesPayload, SegmentTermHitDto, SegmentTermsPayload,
+    TermCandidateDto, TermExtractionDto, TermReviewDto, TermbaseEntryDto,
+};
+use crate::ipc::error::{IpcError, IpcResult};
+use crate::metrics;
+use crate::settings::SettingsManager;
+use crate::terminology::extraction::{ExtractionOptions, extract_terms, pair_targets};
+use crate::terminology::matching::TermMatcher;
+
+const CANDIDATE_STATUSES: [&str; 3] = ["pending", "accepted", "rejected"];
+/// Termbase accepted candidates go to unless the review names another.
+const DEFAULT_TERMBASE: &str = "project";
+
+/// Extracts term candidates from the source segments of a project language pair, replacing
+/// the pending candidates of earlier runs.
+#[tauri::command]
+pub async fn extract_terms_v2(
+    db: State<'_, DbManager>,
+    settings: State<'_, SettingsManager>,
+    payload: ExtractTermsPayload,
+) -> IpcResult<TermExtractionDto> {
+    metrics::track("extract_terms_v2", async {
+        let project_uuid = parse_uuid(&payload.project_uuid, "projectUuid")?;
+        let source_lang = payload.pair.source_lang.trim().to_lowercase();
+        let target_lang = payload.pair.target_lang.trim().to_lowercase();
+        if source_lang.is_empty() || target_lang.is_empty() {
+            return Err(
+                IpcError::Validation("pair needs a source and a target language.".into()).into(),
+            );
+        }
+        let defaults = ExtractionOptions::default();
+        let options = ExtractionOptions {
+            max_words: payload
+                .options
+                .max_words
+                .unwrap_or(defaults.max_words)
+                .clamp(1, 6),
+            min_frequency: payload
+                .options
+                .min_frequency
+                .unwrap_or(defaults.min_frequency),
+            limit: payload.options.limit.unwrap_or(defaults.limit),
+        };
+
+        let documents = load_pair_documents(
+            db.inner(),
+            settings.inner(),
+            project_uuid,
+            &source_lang,
+            &target_lang,
+        )
+        .await?;
+        let units = documents
+            .iter()
+            .flat_map(|(_, document)| document.transunits.iter());
+        let sources: Vec<&str> = units.clone().map(|unit| unit.source.as_str()).collect();
+        let translations: Vec<(&str, &str)> = units
+            .filter(|unit| unit.effective_status().is_confirmed())
+            .filter(|unit| !unit.effective_target().trim().is_empty())
+            .map(|unit| (unit.source.as_str(), unit.effective_target()))
+            .collect();
+
+        let existing = pair_candidates(&db, project_uuid, &source_lang, &target_lang).await?;
+        let decided: HashSet<&str> = existing
+            .iter()
+            .filter(|candidate| candidate.status != "pending")
+            .map(|candidate| candidate.term_key.as_str())
+            .collect();
+        let mut candidates = extract_terms(
+            &sources,
+            &source_lang,
+            ExtractionOptions {
+                limit: usize::MAX,
+                ..options
+            },
+        );
+        let extracted = candidates.len();
+        candidates.retain(|candidate| !decided.contains(candidate.key.as_str()));
+        let skipped_decided = extracted - candidates.len();
+        candidates.truncate(options.limit);
+        if payload.options.pair_targets {
+            pair_targets(
+                &mut candidates,
+                &translations,
+                &target_lang,
+                options.max_words,
+            );
+        }
+
+        let candidates: Vec<NewTermCandidateArgs> = candidates
+            .into_iter()
+            .map(|candidate| NewTermCandidateArgs {
+                term: candidate.term,
+                term_key: candidate.key,
+                target_term: candidate.target_term,
+                frequency: candidate.frequency as i64,
+                score: candidate.score,
+            })
+            .collect();
+        db.replace_pending_term_candidates(project_uuid, &source_lang, &target_lang, &candidates)
+            .await
+            .map_err(IpcError::from)?;
+        log::info!(
+            target: "ipc::terminology",
+            "extracted {} term candidates from {} segments of project {project_uuid} \
+             ({source_lang} → {target_lang})",
+            candidates.len(),
+            sources.len()
+        );
+
+        let pending = pair_candidates(&db, project_uuid, &source_lang, &target_lang)
+            .await?
+            .into_iter()
+            .filter(|candidate| candidate.status == "pending")
+            .map(map_candidate)
+            .collect();
+        Ok(TermExtractionDto {
+            segments: sources.len(),
+            confirmed_segments: translations.len(),
+            candidates: pending,
+            skipped_decided,
+        })
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn list_term_candidates_v2(
+    db: State<'_, DbManager>,
+    project_uuid: String,
+    status: Option<String>,
+) -> IpcResult<Vec<TermCandidateDto>> {
+    metrics::track("list_term_candidates_v2", async {
+        let project_uuid = parse_uuid(&project_uuid, "projectUuid")?;
+        let status = status.map(|status| status.trim().to_string());
+        if let Some(status) = status.as_deref() {
+            validate_status(status)?;
+        }
+        let records = db
+            .list_term_candidates(project_uuid, status.as_deref())
+            .await
+            .map_err(IpcError::from)?;
+        Ok(records.into_iter().map(map_candidate).collect())
+    })
+    .await
+}
+
+/// Accepts, rejects or reopens term candidates. Accepted candidates are added to the
+/// termbase with their (possibly corrected) target term.
+#[tauri::command]
+pub async fn review_term_candidates_v2(
+    db: State<'_, DbManager>,
+    payload: ReviewTermCandidatesPayload,
+) -> IpcResult<TermReviewDto> {
+    metrics::track("review_term_candidates_v2", async {
+        let project_uuid = parse_uuid(&payload.project_uuid, "projectUuid")?;
+        let termbase = payload
+            .termbase
+            .as_deref()
+            .map(str::trim)
+            .filter(|termbase| !termbase.is_empty())
+            .unwrap_or(DEFAULT_TERMBASE)
+            .to_string();
+
+        let mut decisions = Vec::with_capacity(payload.decisions.len());
+        for decision in payload.decisions {
+            let status = decision.status.trim().to_string();
+            validate_status(&status)?;
+            decisions.push(TermCandidateDecisionArgs {
+                candidate_uuid: parse_uuid(&decision.candidate_uuid, "candidateUuid")?,
+                status,
+                target_term: decision.target_term.map(|term| term.trim().to_string()),
+            });
+        }
+        let added = db
+            .decide_term_candidates(project_uuid, &termbase, &decisions)
+            .await
+            .map_err(IpcError::from)?;
+
+        let candidates = db
+            .list_term_candidates(project_uuid, None)
+            .await
+            .map_err(IpcError::from)?;
+        Ok(TermReviewDto {
+            candidates: candidates.into_iter().map(map_candidate).collect(),
+            added_entries: added.into_iter().map(map_termbase_entry).collect(),
+        })
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn list_termbase_entries_v2(
+    db: State<'_, DbManager>,
+    source_lang: String,
+    target_lang: String,
+) -> IpcResult<Vec<TermbaseEntryDto>> {
+    metrics::track("list_termbase_entries_v2", async {
+        if source_lang.trim().is_empty() || target_lang.trim().is_empty() {
+            return Err(
+                IpcError::Validation("sourceLang and targetLang are required.".into()).into(),
+            );
+        }
+        let records = db
+            .list_termbase_entries(&source_lang, &target_lang)
+            .await
+            .map_err(IpcError::from)?;
+        Ok(records.into_iter().map(map_termbase_entry).collect())
+    })
+    .await
+}
+
+/// Termbase entries whose source term occurs in a segment, in inflected forms too, and
+/// whether the segment's target uses their target term.
+#[tauri::command]
+pub async fn find_segment_terms_v2(
+    db: State<'_, DbManager>,
+    settings: State<'_, SettingsManager>,
+    payload: SegmentTermsPayload,
+) -> IpcResult<Vec<SegmentTermHitDto>> {
+    metrics::track("find_segment_terms_v2", async {
+        let project_uuid = parse_uuid(&payload.project_uuid, "projectUuid")?;
+        let (_, document) = load_project_jliff(
+            db.inner(),
+            settings.inner(),
+            project_uuid,
+            &payload.jliff_rel_path,
+        )
+        .await?;
+        let unit = document
+            .transunits
+            .iter()
+            .find(|unit| unit.transunit_id == payload.transunit_id)
+            .ok_or_else(|| {
+                IpcError::Validation(format!(
+                    "Segment '{}' was not found in '{}'.",
+                    payload.transunit_id, payload.jliff_rel_path
+                ))
+            })?;
+
+        let entries = db
+            .list_termbase_entries(&document.source_language, &document.target_language)
+            .await
+            .map_err(IpcError::from)?;
+        let entries = filter_by_project_domains(db.inner(), project_uuid, entries).await?;
+        let terms: Vec<&str> = entries
+            .iter()
+            .map(|entry| entry.source_term.as_str())
+            .collect();
+        let target = unit.effective_target();
+        let hits = TermMatcher::new(&terms, &document.source_language).find(&unit.source);
+        Ok(hits
+            .into_iter()
+            .map(|hit| {
+                let entry = &entries[hit.term_index];
+                let target_found = (!entry.target_term.is_empty() && !target.trim().is_empty())
+                    .then(|| {
+                        TermMatcher::contains(target, &entry.target_term, &document.target_language)
+                    });
+                SegmentTermHitDto {
+                    entry: map_termbase_entry(entry.clone()),
+                    start: hit.start,
+                    end: hit.end,
+                    matched_text: unit.source[hit.start..hit.end].to_string(),
+                    target_found,
+                }
+            })
+            .collect())
+    })
+    .await
+}
+
+/// Keeps entries from termbases that are linked to none of the domains, or to one of the
+/// project's domains.
+async fn filter_by_project_domains(
+    db: &DbManager,
+    project_uuid: Uuid,
+    entries: Vec<TermbaseEntryRecord>,
+) -> Result<Vec<TermbaseEntryRecord>, IpcError> {
+    let links = db.list_termbase_domains().await.map_err(IpcError::from)?;
+    if links.is_empty() {
+        return Ok(entries);
+    }
+    let project_domains: HashSet<String> = db
+        .list_project_domains(project_uuid)
+        .await
+        .map_err(IpcError::from)?
+        .into_iter()
+        .map(|domain| domain.domain_id)
+        .collect();
+    let mut linked: HashSet<&str> = HashSet::new();
+    let mut allowed: HashSet<&str> = HashSet::new();
+    for (termbase, domain) in &links {
+        linked.insert(termbase);
+        if project_domains.contains(&domain.domain_id) {
+            allowed.insert(termbase);
+        }
+    }
+    Ok(entries
+        .into_iter()
+        .filter(|entry| {
+            !linked.contains(entry.termbase.as_str()) || allowed.contains(entry.termbase.as_str())
+        })
+        .collect())
+}
+
+async fn pair_candidates(
+    db: &DbManager,
+    project_uuid: Uuid,
+    source_lang: &str,
+    target_lang: &str,
+) -> Result<Vec<TermCandidateRecord>, IpcError> {
+    let records = db
+        .list_term_candidates(project_uuid, None)
+        .await
+        .map_err(IpcError::from)?;
+    Ok(records
+        .into_iter()
+        .filter(|record| record.source_lang == source_lang && record.target_lang == target_lang)
+        .collect())
+}
+
+fn validate_status(status: &str) -> Result<(), IpcError> {
+    if CANDIDATE_STATUSES.contains(&status) {
+        Ok(())
+    } else {
+        Err(IpcError::Validation(format!(
+            "Unknown candidate status '{status}'. Use pending, accepted or rejected."
+        )))
+    }
+}
+
+fn map_candidate(record: TermCandidateRecord) -> TermCandidateDto {
+    TermCandidateDto {
+        candidate_uuid: record.candidate_uuid.to_string(),
+        source_lang: record.source_lang,
+        target_lang: record.target_lang,
+        term: record.term,
+        target_term: record.target_term,
+        frequency: record.frequency,
+        score: record.score,
+        status: record.status,
+    }
+}
+
+fn map_termbase_entry(record: TermbaseEntryRecord) -> TermbaseEntryDto {
+    TermbaseEntryDto {
+        entry_uuid: record.entry_uuid.to_string(),
+        termbase: record.termbase,
+        source_lang: record.source_lang,
+        target_lang: record.target_lang,
+        source_term: record.source_term,
+        target_term: record.target_term,
+        project_uuid: record.project_uuid.map(|uuid| uuid.to_string()),
+        created_at: record.created_at,
+    }
+}
+
+fn parse_uuid(value: &str, field: &str) -> Result<Uuid, IpcError> {
+    Uuid::parse_str(value)
+        .map_err(|_| IpcError::Validation(format!("invalid {field}: expected UUID, got '{value}'")))
+}
@@ -0,0 +1,89 @@
+//! Translation memories a project draws on, with their priorities and match penalties.
+//! Pretranslation, concordance search and analysis all rank matches through them.
+
+use std::collections::HashSet;
+
+use tauri::State;
+use uuid::Uuid;
+
+use crate::db::DbManager;
+use crate::db::types::{ProjectTmAssignmentArgs, ProjectTmAssignmentRecord};
+use crate::ipc::dto::{ProjectTmAssignmentDto, ReplaceProjectTmAssignmentsPayload};
+use crate::ipc::error::{IpcError, IpcResult};
+use crate::metrics;
+
+#[tauri::command]
+pub async fn list_project_tm_assignments_v2(
+    db: State<'_, DbManager>,
+    project_uuid: String,
+) -> IpcResult<Vec<ProjectTmAssignmentDto>> {
+    metrics::track("list_project_tm_assignments_v2", async {
+        let project_uuid = parse_uuid(&project_uuid, "projectUuid")?;
+        let records = db
+            .list_project_tm_assignments(project_uuid)
+            .await
+            .map_err(IpcError::from)?;
+        Ok(records.into_iter().map(map_tm_assignment_record).collect())
+    })
+    .await
+}
+
+/// Replaces the memories assigned to a project. An empty list restores the default of
+/// using every entry of the language pair without penalty.
+#[tauri::command]
+pub async fn replace_project_tm_assignments_v2(
+    db: State<'_, DbManager>,
+    payload: ReplaceProjectTmAssignmentsPayload,
+) -> IpcResult<Vec<ProjectTmAssignmentDto>> {
+    metrics::track("replace_project_tm_assignments_v2", async {
+        let project_uuid = parse_uuid(&payload.project_uuid, "projectUuid")?;
+
+        let mut seen = HashSet::new();
+        let mut assignments = Vec::with_capacity(payload.assignments.len());
+        for input in payload.assignments {
+            let tm_origin = input.tm_origin.trim().to_string();
+            if tm_origin.is_empty() {
+                return Err(IpcError::Validation("tmOrigin cannot be empty".into()).into());
+            }
+            if input.penalty > 100 {
+                return Err(IpcError::Validation(format!(
+                    "The penalty of '{tm_origin}' must be between 0 and 100."
+                ))
+                .into());
+            }
+            if !seen.insert(tm_origin.clone()) {
+                return Err(IpcError::Validation(format!(
+                    "'{tm_origin}' is assigned more than once."
+                ))
+                .into());
+            }
+            assignments.push(ProjectTmAssignmentArgs {
+                tm_origin,
+                priority: input.priority,
+                penalty: i64::from(input.penalty),
+            });
+        }
+
+        let records = db
+            .replace_project_tm_assignments(project_uuid, &assignments)
+            .await
+            .map_err(IpcError::from)?;
+        Ok(records.into_iter().map(map_tm_assignment_record).collect())
+    })
+    .await
+}
+
+pub(super) fn map_tm_assignment_record(
+    record: ProjectTmAssignmentRecord,
+) -> ProjectTmAssignmentDto {
+    ProjectTmAssignmentDto {
+        tm_origin: record.tm_origin,
+        priority: record.priority,
+        penalty: record.penalty.clamp(0, 100) as u8,
+    }
+}
+
+fn parse_uuid(value: &str, field: &str) -> Result<Uuid, IpcError> {
+    Uuid::parse_str(value)
+        .map_err(|_| IpcError::Validation(format!("invalid {field}: expected UUID, got '{value}'")))
+}
@@ -0,0 +1,281 @@
+//! Translation memory housekeeping over one language pair. Every command that changes the
+//! memory accepts `dryRun` and then reports exactly what it would have done.
+
+use std::collections::HashSet;
+
+use tauri::State;
+use uuid::Uuid;
+
+use crate::db::DbManager;
+use crate::db::types::{TranslationMemoryEntryRecord, UpdateTranslationMemoryEntryArgs};
+use crate::ipc::dto::{
+    BatchEditTmPayload, TmBatchEditDto, TmDeduplicationDto, TmDuplicateGroupDto, TmEntryChangeDto,
+    TmInconsistencyDto, TmPairPayload, TmReversalDto, TmTargetVariantDto,
+};
+use crate::ipc::error::{IpcError, IpcResult};
+use crate::metrics;
+use crate::tm::maintenance::{
+    EntryFilter, TextField, find_duplicates, find_inconsistencies, plan_replacements,
+    reversed_entries,
+};
+
+/// Removes entries that repeat another entry's pair once whitespace is collapsed, keeping
+/// the most recently updated copy.
+#[tauri::command]
+pub async fn deduplicate_tm_v2(
+    db: State<'_, DbManager>,
+    payload: TmPairPayload,
+) -> IpcResult<TmDeduplicationDto> {
+    metrics::track("deduplicate_tm_v2", async {
+        let entries = load_entries(&db, &payload.source_lang, &payload.target_lang).await?;
+        let groups = find_duplicates(&entries);
+        let removed: Vec<Uuid> = groups
+            .iter()
+            .flat_map(|group| group.removed.iter().copied())
+            .collect();
+        if !payload.dry_run && !removed.is_empty() {
+            db.delete_translation_memory_entries(&removed)
+                .await
+                .map_err(IpcError::from)?;
+            log::info!(
+                target: "ipc::tm_maintenance",
+                "removed {} duplicate TM entries of {} → {}",
+                removed.len(),
+                payload.source_lang,
+                payload.target_lang
+            );
+        }
+        Ok(TmDeduplicationDto {
+            applied: !payload.dry_run && !removed.is_empty(),
+            removed_entries: removed.len(),
+            groups: groups
+                .into_iter()
+                .map(|group| TmDuplicateGroupDto {
+                    kept_entry_uuid: group.kept.to_string(),
+                    removed_entry_uuids: group.removed.iter().map(Uuid::to_string).collect(),
+                    source_text: group.source_text,
+                    target_text: group.target_text,
+                })
+                .collect(),
+        })
+    })
+    .await
+}
+
+/// Lists sources the memory translates in more than one way.
+#[tauri::command]
+pub async fn find_tm_inconsistencies_v2(
+    db: State<'_, DbManager>,
+    payload: TmPairPayload,
+) -> IpcResult<Vec<TmInconsistencyDto>> {
+    metrics::track("find_tm_inconsistencies_v2", async {
+        let entries = load_entries(&db, &payload.source_lang, &payload.target_lang).await?;
+        Ok(find_inconsistencies(&entries)
+            .into_iter()
+            .map(|source| TmInconsistencyDto {
+                source_text: source.source_text,
+                variants: source
+                    .variants
+                    .into_iter()
+                    .map(|variant| TmTargetVariantDto {
+                        target_text: variant.target_text,
+                        entry_uuids: variant.entry_uuids.iter().map(Uuid::to_string).collect(),
+                        updated_at: variant.updated_at,
+                    })
+                    .collect(),
+            })
+            .collect())
+    })
+    .await
+}
+
+/// Rewrites (`replace`) or removes (`delete`) the entries matching a filter.
+#[tauri::command]
+pub async fn batch_edit_tm_v2(
+    db: State<'_, DbManager>,
+    payload: BatchEditTmPayload,
+) -> IpcResult<TmBatchEditDto> {
+    metrics::track("batch_edit_tm_v2", async {
+        let filter = EntryFilter {
+            source_contains: non_empty(payload.filter.source_contains.as_deref()),
+            target_contains: non_empty(payload.filter.target_contains.as_deref()),
+            origin: non_empty(payload.filter.origin.as_deref()),
+            project_uuid: payload
+                .filter
+                .project_uuid
+                .as_deref()
+                .map(|value| parse_uuid(value, "filter.projectUuid"))
+                .transpose()?,
+        };
+        let entries = load_entries(&db, &payload.source_lang, &payload.target_lang).await?;
+
+        match payload.action.trim() {
+            "replace" => {
+                let find = payload.find.as_deref().unwrap_or_default();
+                if find.is_empty() {
+                    return Err(IpcError::Validation(
+                        "find is required to replace text in TM entries.".into(),
+                    )
+                    .into());
+                }
+                let field = match payload.field.as_deref().map(str::trim) {
+                    None => TextField::Target,
+                    Some(value) => TextField::parse(value).ok_or_else(|| {
+                        IpcError::Validation(format!(
+                            "Unknown field '{value}'. Use source, target or both."
+                        ))
+                    })?,
+                };
+                let replace = payload.replace.as_deref().unwrap_or_default();
+                let edits = plan_replacements(&entries, &filter, find, replace, field);
+
+                let mut merged_entries = 0;
+                if !payload.dry_run && !edits.is_empty() {
+                    let updates: Vec<UpdateTranslationMemoryEntryArgs> = edits
+                        .iter()
+                        .map(|edit| UpdateTranslationMemoryEntryArgs {
+                            entry_uuid: edit.entry_uuid,
+                            source_text: edit.source_text.clone(),
+                            target_text: edit.target_text.clone(),
+                        })
+                        .collect();
+                    merged_entries = db
+                        .update_translation_memory_entries(&updates)
+                        .await
+                        .map_err(IpcError::from)?;
+                }
+                let changes = edits
+                    .into_iter()
+                    .filter_map(|edit| {
+                        let entry = entries
+                            .iter()
+                            .find(|entry| entry.entry_uuid == edit.entry_uuid)?;
+                        Some(TmEntryChangeDto {
+                            new_source_text: Some(edit.source_text),
+                            new_target_text: Some(edit.target_text),
+                            ..map_change(entry)
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                Ok(TmBatchEditDto {
+                    applied: !payload.dry_run && !changes.is_empty(),
+                    changes,
+                    merged_entries,
+                })
+            }
+            "delete" => {
+                if filter.is_empty() {
+                    return Err(IpcError::Validation(
+                        "Deleting TM entries needs at least one filter criterion.".into(),
+                    )
+                    .into());
+                }
+                let matched: Vec<&TranslationMemoryEntryRecord> = entries
+                    .iter()
+                    .filter(|entry| filter.matches(entry))
+                    .collect();
+                if !payload.dry_run && !matched.is_empty() {
+                    let entry_uuids: Vec<Uuid> =
+                        matched.iter().map(|entry| entry.entry_uuid).collect();
+                    db.delete_translation_memory_entries(&entry_uuids)
+                        .await
+                        .map_err(IpcError::from)?;
+                }
+                Ok(TmBatchEditDto {
+                    applied: !payload.dry_run && !matched.is_empty(),
+                    changes: matched.into_iter().map(map_change).collect(),
+                    merged_entries: 0,
+                })
+            }
+            other => Err(IpcError::Validation(format!(
+                "Unknown batch action '{other}'. Use replace or delete."
+            ))
+            .into()),
+        }
+    })
+    .await
+}
+
+/// Copies the memory into the opposite language direction, swapping sources and targets.
+/// Pairs the opposite direction already holds are refreshed rather than duplicated.
+#[tauri::command]
+pub async fn reverse_tm_v2(
+    db: State<'_, DbManager>,
+    payload: TmPairPayload,
+) -> IpcResult<TmReversalDto> {
+    metrics::track("reverse_tm_v2", async {
+        let entries = load_entries(&db, &payload.source_lang, &payload.target_lang).await?;
+        if entries.is_empty() {
+            return Err(IpcError::Validation(format!(
+                "The translation memory has no entries for {} → {}.",
+                payload.source_lang, payload.target_lang
+            ))
+            .into());
+        }
+        let reversed = reversed_entries(&entries);
+        let existing: HashSet<(String, String)> = db
+            .list_translation_memory_entries(&payload.target_lang, &payload.source_lang)
+            .await
+            .map_err(IpcError::from)?
+            .into_iter()
+            .map(|entry| (entry.source_text, entry.target_text))
+            .collect();
+        let existing_entries = reversed
+            .iter()
+            .filter(|entry| {
+                existing.contains(&(entry.source_text.clone(), entry.target_text.clone()))
+            })
+            .count();
+
+        if !payload.dry_run {
+            db.upsert_translation_memory_entries(&reversed)
+                .await
+                .map_err(IpcError::from)?;
+        }
+        Ok(TmReversalDto {
+            applied: !payload.dry_run,
+            source_lang: payload.target_lang.trim().to_lowercase(),
+            target_lang: payload.source_lang.trim().to_lowercase(),
+            entries: reversed.len(),
+            existing_entries,
+        })
+    })
+    .await
+}
+
+async fn load_entries(
+    db: &DbManager,
+    source_lang: &str,
+    target_lang: &str,
+) -> Result<Vec<TranslationMemoryEntryRecord>, IpcError> {
+    if source_lang.trim().is_empty() || target_lang.trim().is_empty() {
+        return Err(IpcError::Validation(
+            "sourceLang and targetLang are required.".into(),
+        ));
+    }
+    db.list_translation_memory_entries(source_lang, target_lang)
+        .await
+        .map_err(IpcError::from)
+}
+
+fn map_change(entry: &TranslationMemoryEntryRecord) -> TmEntryChangeDto {
+    TmEntryChangeDto {
+        entry_uuid: entry.entry_uuid.to_string(),
+        source_text: entry.source_text.clone(),
+        target_text: entry.target_text.clone(),
+        new_source_text: None,
+        new_target_text: None,
+    }
+}
+
+fn non_empty(value: Option<&str>) -> Option<String> {
+    value
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+}
+
+fn parse_uuid(value: &str, field: &str) -> Result<Uuid, IpcError> {
+    Uuid::parse_str(value)
+        .map_err(|_| IpcError::Validation(format!("invalid {field}: expected UUID, got '{value}'")))
+}
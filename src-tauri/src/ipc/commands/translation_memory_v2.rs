@@ -0,0 +1,437 @@
+use std::sync::Arc;
+
+use tauri::State;
+use uuid::Uuid;
+
+use super::projects_v2::resolve_project_root;
+use super::shared::{
+    list_project_jliff_paths, load_project_jliff, record_project_history, update_project_jliff,
+};
+use crate::db::DbManager;
+use crate::db::types::{
+    NewSegmentRevisionArgs, RecordSegmentRevisionsArgs, TranslationMemoryEntryRecord,
+};
+use crate::ipc::dto::{
+    ConcordanceHitDto, ConcordanceResultDto, ConcordanceSearchPayload, MatchBreakdownDto,
+    PretranslateProjectPayload, PretranslationDto, PretranslationFileDto,
+};
+use crate::ipc::error::{IpcError, IpcResult};
+use crate::jliff::JliffDocument;
+use crate::jliff::history::HistoryEvent;
+use crate::jliff::pretranslation::{
+    DocumentPretranslation, MIN_MATCH_FLOOR, MatchBreakdown, OverwritePolicy, pretranslate_document,
+};
+use crate::metrics;
+use crate::settings::SettingsManager;
+use crate::tm::{TmCandidate, TmMatcher, find_occurrence};
+
+/// Revision origin recorded for targets filled from the translation memory.
+const PRETRANSLATION_ORIGIN: &str = "pretranslation";
+
+/// Shorter concordance queries match nearly every segment.
+const MIN_CONCORDANCE_QUERY_CHARS: usize = 2;
+const DEFAULT_CONCORDANCE_LIMIT: usize = 50;
+const MAX_CONCORDANCE_LIMIT: usize = 200;
+
+/// Fills the targets of every JLIFF document of a language pair from translation memory
+/// matches at or above `minMatch`, and reports the exact/fuzzy/no-match breakdown.
+#[tauri::command]
+pub async fn pretranslate_project_v2(
+    db: State<'_, DbManager>,
+    settings: State<'_, SettingsManager>,
+    payload: PretranslateProjectPayload,
+) -> IpcResult<PretranslationDto> {
+    metrics::track("pretranslate_project_v2", async {
+        let project_uuid = parse_uuid(&payload.project_uuid, "projectUuid")?;
+        if !(MIN_MATCH_FLOOR..=100).contains(&payload.min_match) {
+            return Err(IpcError::Validation(format!(
+                "minMatch must be between {MIN_MATCH_FLOOR} and 100."
+            ))
+            .into());
+        }
+        let policy = match payload.overwrite_policy.as_deref() {
+            Some(value) => OverwritePolicy::parse(value).ok_or_else(|| {
+                IpcError::Validation(format!(
+                    "Unknown overwrite policy '{value}'. Use empty_only, unconfirmed or all."
+                ))
+            })?,
+            None => OverwritePolicy::EmptyOnly,
+        };
+        let source_lang = payload.source_lang.trim().to_string();
+        let target_lang = payload.target_lang.trim().to_string();
+
+        let pair_paths: Vec<String> = load_pair_documents(
+            db.inner(),
+            settings.inner(),
+            project_uuid,
+            &source_lang,
+            &target_lang,
+        )
+        .await?
+        .into_iter()
+        .map(|(jliff_rel_path, _)| jliff_rel_path)
+        .collect();
+        if pair_paths.is_empty() {
+            return Err(IpcError::Validation(format!(
+                "The project has no JLIFF documents for {source_lang} → {target_lang}."
+            ))
+            .into());
+        }
+
+        let matcher = Arc::new(
+            load_tm_matcher(db.inner(), Some(project_uuid), &source_lang, &target_lang).await?,
+        );
+
+        let min_match = payload.min_match;
+        let mut total = MatchBreakdown::default();
+        let mut files = Vec::with_capacity(pair_paths.len());
+        for jliff_rel_path in pair_paths {
+            let result = pretranslate_file(
+                db.inner(),
+                settings.inner(),
+                project_uuid,
+                &jliff_rel_path,
+                Arc::clone(&matcher),
+                min_match,
+                policy,
+            )
+            .await?;
+
+            total.add(&result.breakdown);
+            files.push(PretranslationFileDto {
+                jliff_rel_path,
+                filled_segments: result.filled.len(),
+                breakdown: map_breakdown(&result.breakdown),
+            });
+        }
+
+        let filled_segments: usize = files.iter().map(|file| file.filled_segments).sum();
+        if filled_segments > 0 {
+            record_project_history(
+                db.inner(),
+                settings.inner(),
+                project_uuid,
+                HistoryEvent {
+                    kind: "pretranslation",
+                    summary: format!(
+                        "Pretranslate {filled_segments} segment(s) of {source_lang} → {target_lang}"
+                    ),
+                    files: files
+                        .iter()
+                        .filter(|file| file.filled_segments > 0)
+                        .map(|file| file.jliff_rel_path.clone())
+                        .collect(),
+                },
+            )
+            .await;
+        }
+
+        Ok(PretranslationDto {
+            source_lang,
+            target_lang,
+            min_match,
+            overwrite_policy: policy.as_str().to_string(),
+            filled_segments,
+            breakdown: map_breakdown(&total),
+            files,
+        })
+    })
+    .await
+}
+
+/// Searches the translation memory and/or the project's existing translations for a word or
+/// phrase, returning occurrences ranked by how closely the text matches the query.
+#[tauri::command]
+pub async fn concordance_search_v2(
+    db: State<'_, DbManager>,
+    settings: State<'_, SettingsManager>,
+    payload: ConcordanceSearchPayload,
+) -> IpcResult<ConcordanceResultDto> {
+    metrics::track("concordance_search_v2", async {
+        let query = payload.query.trim();
+        if query.chars().count() < MIN_CONCORDANCE_QUERY_CHARS {
+            return Err(IpcError::Validation(format!(
+                "query must contain at least {MIN_CONCORDANCE_QUERY_CHARS} characters."
+            ))
+            .into());
+        }
+        let limit = payload.limit.unwrap_or(DEFAULT_CONCORDANCE_LIMIT);
+        if limit == 0 || limit > MAX_CONCORDANCE_LIMIT {
+            return Err(IpcError::Validation(format!(
+                "limit must be between 1 and {MAX_CONCORDANCE_LIMIT}."
+            ))
+            .into());
+        }
+        let (search_tm, search_project) = match payload.scope.as_deref().map(str::trim) {
+            None | Some("all") => (true, payload.project_uuid.is_some()),
+            Some("tm") => (true, false),
+            Some("project") => (false, true),
+            Some(other) => {
+                return Err(IpcError::Validation(format!(
+                    "Unknown concordance scope '{other}'. Use tm, project or all."
+                ))
+                .into());
+            }
+        };
+        let (in_source, in_target) = match payload.search_in.as_deref().map(str::trim) {
+            None | Some("source") => (true, false),
+            Some("target") => (false, true),
+            Some("both") => (true, true),
+            Some(other) => {
+                return Err(IpcError::Validation(format!(
+                    "Unknown searchIn value '{other}'. Use source, target or both."
+                ))
+                .into());
+            }
+        };
+        let locate = |source: &str, target: &str| {
+            let in_source = in_source
+                .then(|| find_occurrence(source, query))
+                .flatten()
+                .map(|occurrence| ("source", occurrence));
+            let in_target = in_target
+                .then(|| find_occurrence(target, query))
+                .flatten()
+                .map(|occurrence| ("target", occurrence));
+            match (in_source, in_target) {
+                (Some(left), Some(right)) if right.1.score > left.1.score => Some(right),
+                (Some(left), _) => Some(left),
+                (None, right) => right,
+            }
+        };
+
+        let project_uuid = payload
+            .project_uuid
+            .as_deref()
+            .map(|value| parse_uuid(value, "projectUuid"))
+            .transpose()?;
+
+        let mut hits = Vec::new();
+        if search_tm {
+            let entries = ranked_tm_entries(
+                db.inner(),
+                project_uuid,
+                &payload.source_lang,
+                &payload.target_lang,
+            )
+            .await?;
+            for (entry, penalty) in entries {
+                let Some((matched_in, occurrence)) = locate(&entry.source_text, &entry.target_text)
+                else {
+                    continue;
+                };
+                hits.push(ConcordanceHitDto {
+                    origin: "tm".into(),
+                    source: entry.source_text,
+                    target: entry.target_text,
+                    matched_in: matched_in.into(),
+                    match_start: occurrence.start,
+                    match_end: occurrence.end,
+                    score: occurrence.score.saturating_sub(penalty),
+                    penalty: Some(penalty),
+                    jliff_rel_path: None,
+                    transunit_id: None,
+                    context_before: None,
+                    context_after: None,
+                    updated_at: Some(entry.updated_at),
+                });
+            }
+        }
+
+        if search_project {
+            let Some(project_uuid) = project_uuid else {
+                return Err(IpcError::Validation(
+                    "projectUuid is required to search project translations.".into(),
+                )
+                .into());
+            };
+            let documents = load_pair_documents(
+                db.inner(),
+                settings.inner(),
+                project_uuid,
+                &payload.source_lang,
+                &payload.target_lang,
+            )
+            .await?;
+            for (jliff_rel_path, document) in documents {
+                let units = &document.transunits;
+                for (index, unit) in units.iter().enumerate() {
+                    let target = unit.effective_target();
+                    if target.trim().is_empty() {
+                        continue;
+                    }
+                    let Some((matched_in, occurrence)) = locate(&unit.source, target) else {
+                        continue;
+                    };
+                    hits.push(ConcordanceHitDto {
+                        origin: "project".into(),
+                        source: unit.source.clone(),
+                        target: target.to_string(),
+                        matched_in: matched_in.into(),
+                        match_start: occurrence.start,
+                        match_end: occurrence.end,
+                        score: occurrence.score,
+                        penalty: None,
+                        jliff_rel_path: Some(jliff_rel_path.clone()),
+                        transunit_id: Some(unit.transunit_id.clone()),
+                        context_before: index
+                            .checked_sub(1)
+                            .map(|previous| units[previous].source.clone()),
+                        context_after: units.get(index + 1).map(|next| next.source.clone()),
+                        updated_at: None,
+                    });
+                }
+            }
+        }
+
+        hits.sort_by(|left, right| {
+            right
+                .score
+                .cmp(&left.score)
+                .then_with(|| left.source.cmp(&right.source))
+        });
+        let total = hits.len();
+        hits.truncate(limit);
+        Ok(ConcordanceResultDto { total, hits })
+    })
+    .await
+}
+
+/// Pretranslates one document from `matcher` and records the filled targets as revisions.
+pub(super) async fn pretranslate_file(
+    db: &DbManager,
+    settings: &SettingsManager,
+    project_uuid: Uuid,
+    jliff_rel_path: &str,
+    matcher: Arc<TmMatcher>,
+    min_match: u8,
+    policy: OverwritePolicy,
+) -> Result<DocumentPretranslation, IpcError> {
+    let result = update_project_jliff(
+        db,
+        settings,
+        project_uuid,
+        jliff_rel_path,
+        move |document| Ok(pretranslate_document(document, &matcher, min_match, policy)),
+    )
+    .await?;
+    if result.filled.is_empty() {
+        return Ok(result);
+    }
+    db.record_segment_revisions(RecordSegmentRevisionsArgs {
+        project_uuid,
+        jliff_rel_path: jliff_rel_path.to_string(),
+        origin: PRETRANSLATION_ORIGIN.to_string(),
+        author: None,
+        revisions: result
+            .filled
+            .iter()
+            .map(|target| NewSegmentRevisionArgs {
+                transunit_id: target.transunit_id.clone(),
+                previous_target: target.previous_target.clone(),
+                new_target: target.new_target.clone(),
+            })
+            .collect(),
+    })
+    .await
+    .map_err(IpcError::from)?;
+    Ok(result)
+}
+
+/// Matcher over the translation memory entries of a language pair, ranked and penalised as
+/// configured for the project.
+pub(super) async fn load_tm_matcher(
+    db: &DbManager,
+    project_uuid: Option<Uuid>,
+    source_lang: &str,
+    target_lang: &str,
+) -> Result<TmMatcher, IpcError> {
+    let entries = ranked_tm_entries(db, project_uuid, source_lang, target_lang).await?;
+    Ok(TmMatcher::new(entries.into_iter().map(
+        |(entry, penalty)| TmCandidate {
+            source: entry.source_text,
+            target: entry.target_text,
+            penalty,
+        },
+    )))
+}
+
+/// The translation memory entries of a language pair with their penalties. When the project
+/// has memories assigned, only their entries are returned, highest priority first; otherwise
+/// every entry is, without penalty. Within a memory the most recent entries come first.
+pub(super) async fn ranked_tm_entries(
+    db: &DbManager,
+    project_uuid: Option<Uuid>,
+    source_lang: &str,
+    target_lang: &str,
+) -> Result<Vec<(TranslationMemoryEntryRecord, u8)>, IpcError> {
+    let entries = db
+        .list_translation_memory_entries(source_lang, target_lang)
+        .await
+        .map_err(IpcError::from)?;
+    let assignments = match project_uuid {
+        Some(project_uuid) => db
+            .list_project_tm_assignments(project_uuid)
+            .await
+            .map_err(IpcError::from)?,
+        None => Vec::new(),
+    };
+    if assignments.is_empty() {
+        return Ok(entries.into_iter().map(|entry| (entry, 0)).collect());
+    }
+
+    let mut ranked: Vec<(usize, TranslationMemoryEntryRecord, u8)> = entries
+        .into_iter()
+        .filter_map(|entry| {
+            let rank = assignments
+                .iter()
+                .position(|assignment| assignment.tm_origin == entry.origin)?;
+            let penalty = assignments[rank].penalty.clamp(0, 100) as u8;
+            Some((rank, entry, penalty))
+        })
+        .collect();
+    ranked.sort_by_key(|(rank, _, _)| *rank);
+    Ok(ranked
+        .into_iter()
+        .map(|(_, entry, penalty)| (entry, penalty))
+        .collect())
+}
+
+/// Loads the project's JLIFF documents whose header matches the language pair.
+pub(super) async fn load_pair_documents(
+    db: &DbManager,
+    settings: &SettingsManager,
+    project_uuid: Uuid,
+    source_lang: &str,
+    target_lang: &str,
+) -> Result<Vec<(String, JliffDocument)>, IpcError> {
+    let source_lang = source_lang.trim();
+    let target_lang = target_lang.trim();
+    let project_root = resolve_project_root(db, settings, project_uuid).await?;
+    let mut documents = Vec::new();
+    for jliff_rel_path in list_project_jliff_paths(&project_root).await? {
+        let (_, document) = load_project_jliff(db, settings, project_uuid, &jliff_rel_path).await?;
+        if document.source_language.eq_ignore_ascii_case(source_lang)
+            && document.target_language.eq_ignore_ascii_case(target_lang)
+        {
+            documents.push((jliff_rel_path, document));
+        }
+    }
+    Ok(documents)
+}
+
+fn map_breakdown(breakdown: &MatchBreakdown) -> MatchBreakdownDto {
+    MatchBreakdownDto {
+        exact_segments: breakdown.exact_segments,
+        exact_words: breakdown.exact_words,
+        fuzzy_segments: breakdown.fuzzy_segments,
+        fuzzy_words: breakdown.fuzzy_words,
+        no_match_segments: breakdown.no_match_segments,
+        no_match_words: breakdown.no_match_words,
+    }
+}
+
+fn parse_uuid(value: &str, field: &str) -> Result<Uuid, IpcError> {
+    Uuid::parse_str(value)
+        .map_err(|_| IpcError::Validation(format!("invalid {field}: expected UUID, got '{value}'")))
+}
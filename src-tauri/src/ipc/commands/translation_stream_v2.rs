@@ -0,0 +1,205 @@
+use tauri::{AppHandle, Emitter, Manager, Runtime, State};
+use uuid::Uuid;
+
+use super::offline_v2::ensure_online;
+use super::outbound_audit_v2::{ensure_external_mt_allowed, record_outbound_request};
+use super::prompt_templates_v2::load_project_prompt;
+use crate::db::DbManager;
+use crate::ipc::dto::{
+    StreamSegmentTranslationPayload, TranslationStreamEventPayload, TranslationStreamStartedDto,
+};
+use crate::ipc::error::{IpcError, IpcResult};
+use crate::ipc::events::TRANSLATION_STREAM_PREFIX;
+use crate::ipc::state::{OfflineMode, TranslationStreams};
+use crate::metrics;
+use crate::providers::{LLM_PROVIDER, LlmClient, MockTranslator, ProviderRateLimiter};
+use crate::settings::SettingsManager;
+
+/// Starts an LLM translation of one segment and returns immediately. The output is streamed
+/// on `translation:stream:{jobId}` as `delta` events, followed by one `completed`, `failed`
+/// or `cancelled` event. With a `projectUuid`, the prompt template assigned to the project's
+/// language pair and its protected terms are used. While the mock provider is enabled it
+/// answers instead of the LLM.
+#[tauri::command]
+pub async fn stream_segment_translation_v2(
+    app: AppHandle,
+    db: State<'_, DbManager>,
+    llm: State<'_, LlmClient>,
+    settings: State<'_, SettingsManager>,
+    streams: State<'_, TranslationStreams>,
+    offline: State<'_, OfflineMode>,
+    payload: StreamSegmentTranslationPayload,
+) -> IpcResult<TranslationStreamStartedDto> {
+    metrics::track("stream_segment_translation_v2", async {
+    if payload.source.trim().is_empty() {
+        return Err(IpcError::Validation("source must not be empty.".into()).into());
+    }
+    let current = settings.current().await;
+    let mock = current
+        .mock_provider_enabled
+        .then(|| MockTranslator::new(current.mock_provider()));
+    if mock.is_none() && !llm.is_configured() {
+        return Err(IpcError::Internal(
+            "LLM translation is not configured. Contact your administrator to set LLM_API_KEY."
+                .into(),
+        )
+        .into());
+    }
+    let project_uuid = payload
+        .project_uuid
+        .as_deref()
+        .map(|value| parse_uuid(value, "projectUuid"))
+        .transpose()?;
+    if mock.is_none() {
+        if let Some(project_uuid) = project_uuid {
+            ensure_external_mt_allowed(db.inner(), project_uuid, LLM_PROVIDER).await?;
+        }
+        ensure_online(&offline, "LLM translation")?;
+    }
+
+    let prompt = load_project_prompt(
+        db.inner(),
+        payload.project_uuid.as_deref(),
+        &payload.source_lang,
+        &payload.target_lang,
+        payload.terms,
+    )
+    .await?;
+    let messages = prompt.render(
+        &payload.source_lang,
+        &payload.target_lang,
+        &payload.source,
+        payload.context.as_deref(),
+    );
+
+    if mock.is_none() {
+        record_outbound_request(
+            db.inner(),
+            LLM_PROVIDER,
+            project_uuid,
+            1,
+            payload.source.chars().count() as i64,
+            Some(llm.base_url()),
+        )
+        .await?;
+    }
+
+    let job_id = Uuid::new_v4();
+    let event = format!("{TRANSLATION_STREAM_PREFIX}{job_id}");
+    let cancel = streams.register(job_id);
+    let model = payload
+        .model
+        .clone()
+        .filter(|model| !model.trim().is_empty())
+        .unwrap_or_else(|| llm.default_model().to_string());
+    // Rough token estimate for the rate limiter: prompt and answer at ~4 characters a token.
+    let tokens = u32::try_from(
+        messages
+            .iter()
+            .map(|message| message.content.len())
+            .sum::<usize>()
+            / 2,
+    )
+    .unwrap_or(u32::MAX);
+
+    let task_event = event.clone();
+    let target_lang = payload.target_lang.clone();
+    let source = payload.source.clone();
+    tauri::async_runtime::spawn(async move {
+        let llm = app.state::<LlmClient>();
+        let limiter = app.state::<ProviderRateLimiter>();
+        let mut text = String::new();
+        let outcome = tokio::select! {
+            _ = cancel.notified() => None,
+            result = async {
+                let mut on_delta = |delta: &str| {
+                    text.push_str(delta);
+                    emit_stream_event(&app, &task_event, job_id, "delta", Some(delta), &text, None);
+                };
+                if let Some(mock) = mock {
+                    return mock
+                        .stream(&target_lang, &source, on_delta)
+                        .await
+                        .map_err(|error| error.to_string());
+                }
+                limiter.acquire(LLM_PROVIDER, tokens, |_| {}).await;
+                llm.stream_chat(&model, &messages, on_delta)
+                    .await
+                    .map_err(|error| error.to_string())
+            } => Some(result),
+        };
+        app.state::<TranslationStreams>().finish(job_id);
+
+        match outcome {
+            None => emit_stream_event(&app, &task_event, job_id, "cancelled", None, &text, None),
+            Some(Ok(full)) => {
+                emit_stream_event(&app, &task_event, job_id, "completed", None, &full, None)
+            }
+            Some(Err(error)) => {
+                log::warn!(
+                    target: "ipc::translation_stream_v2",
+                    "streaming translation {job_id} failed: {error}"
+                );
+                emit_stream_event(
+                    &app,
+                    &task_event,
+                    job_id,
+                    "failed",
+                    None,
+                    &text,
+                    Some(error),
+                )
+            }
+        }
+    });
+
+    Ok(TranslationStreamStartedDto {
+        job_id: job_id.to_string(),
+        event,
+    })
+}).await
+}
+
+/// Stops a running streaming translation. Returns `false` when it already finished.
+#[tauri::command]
+pub async fn cancel_translation_stream_v2(
+    streams: State<'_, TranslationStreams>,
+    job_id: String,
+) -> IpcResult<bool> {
+    metrics::track("cancel_translation_stream_v2", async {
+        let job_id = Uuid::parse_str(&job_id).map_err(|_| {
+            IpcError::Validation(format!("invalid jobId: expected UUID, got '{job_id}'"))
+        })?;
+        Ok(streams.cancel(job_id))
+    })
+    .await
+}
+
+fn emit_stream_event<R: Runtime>(
+    app: &AppHandle<R>,
+    event: &str,
+    job_id: Uuid,
+    kind: &str,
+    delta: Option<&str>,
+    text: &str,
+    error: Option<String>,
+) {
+    let payload = TranslationStreamEventPayload {
+        job_id: job_id.to_string(),
+        kind: kind.to_string(),
+        delta: delta.map(str::to_string),
+        text: text.to_string(),
+        error,
+    };
+    if let Err(error) = app.emit(event, payload) {
+        log::warn!(
+            target: "ipc::translation_stream_v2",
+            "failed to emit streaming translation event: {error}"
+        );
+    }
+}
+
+fn parse_uuid(value: &str, field: &str) -> Result<Uuid, IpcError> {
+    Uuid::parse_str(value)
+        .map_err(|_| IpcError::Validation(format!("invalid {field}: expected UUID, got '{value}'")))
+}
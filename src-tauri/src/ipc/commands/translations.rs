@@ -11,12 +11,13 @@ use uuid::Uuid;
 use crate::ipc::dto::{JobAccepted, TranslationHistoryRecord};
 use crate::ipc::error::{IpcError, IpcResult};
 use crate::ipc::state::JobRecord;
+use crate::metrics;
 
 #[tauri::command]
 pub async fn list_active_jobs(
     _state: State<'_, crate::ipc::state::TranslationState>,
 ) -> IpcResult<Vec<JobRecord>> {
-    Ok(Vec::new())
+    metrics::track("list_active_jobs", async { Ok(Vec::new()) }).await
 }
 
 #[tauri::command]
@@ -26,10 +27,13 @@ pub async fn start_translation(
     _db: State<'_, crate::db::DbManager>,
     _request: crate::ipc::dto::TranslationRequest,
 ) -> IpcResult<JobAccepted> {
-    Err(IpcError::Internal(
-        "Translation pipeline is not yet implemented for the new schema.".into(),
-    )
-    .into())
+    metrics::track("start_translation", async {
+        Err(IpcError::Internal(
+            "Translation pipeline is not yet implemented for the new schema.".into(),
+        )
+        .into())
+    })
+    .await
 }
 
 #[tauri::command]
@@ -40,7 +44,7 @@ pub async fn fail_translation(
     _job_id: Uuid,
     _reason: Option<String>,
 ) -> IpcResult<()> {
-    Ok(())
+    metrics::track("fail_translation", async { Ok(()) }).await
 }
 
 #[tauri::command]
@@ -49,12 +53,12 @@ pub async fn list_translation_history(
     _limit: Option<i64>,
     _offset: Option<i64>,
 ) -> IpcResult<Vec<TranslationHistoryRecord>> {
-    Ok(Vec::new())
+    metrics::track("list_translation_history", async { Ok(Vec::new()) }).await
 }
 
 #[tauri::command]
 pub async fn clear_translation_history(_db: State<'_, crate::db::DbManager>) -> IpcResult<u64> {
-    Ok(0)
+    metrics::track("clear_translation_history", async { Ok(0) }).await
 }
 
 #[tauri::command]
@@ -62,5 +66,5 @@ pub async fn get_translation_job(
     _db: State<'_, crate::db::DbManager>,
     _job_id: Uuid,
 ) -> IpcResult<Option<TranslationHistoryRecord>> {
-    Ok(None)
+    metrics::track("get_translation_job", async { Ok(None) }).await
 }
@@ -0,0 +1,210 @@
+use serde_json::json;
+use tauri::State;
+use uuid::Uuid;
+
+use super::projects_v2::resolve_project_root;
+use super::qa_v2::{map_qa_finding_record, project_qa_profile};
+use super::shared::{list_project_jliff_paths, load_project_jliff, update_project_jliff};
+use crate::db::DbManager;
+use crate::db::types::{
+    NewQaFindingArgs, NewSegmentRevisionArgs, RecordSegmentRevisionsArgs, ReplaceQaFindingsArgs,
+};
+use crate::ipc::dto::{
+    CheckUnicodeTextPayload, FixUnicodeTextPayload, UnicodeFixDto, UnicodeFixFileDto,
+    UnicodeTextQaDto,
+};
+use crate::ipc::error::{IpcError, IpcResult};
+use crate::jliff::JliffDocument;
+use crate::jliff::model::TransUnit;
+use crate::jliff::unicode_text::{UnicodeIssue, find_unicode_issues, fix_unicode_text};
+use crate::metrics;
+use crate::settings::SettingsManager;
+
+/// QA check identifier used when persisting Unicode text findings.
+const UNICODE_TEXT_CHECK: &str = "unicode_text";
+/// Revision origin of targets cleaned by [`fix_unicode_text_v2`].
+const UNICODE_FIX_ORIGIN: &str = "unicode_fix";
+
+/// Reports zero-width spaces, byte order marks and other invisible characters, and text
+/// not in NFC, in the sources and targets of a document. Replaces the stored findings of
+/// the check for the document.
+#[tauri::command]
+pub async fn check_unicode_text_v2(
+    db: State<'_, DbManager>,
+    settings: State<'_, SettingsManager>,
+    payload: CheckUnicodeTextPayload,
+) -> IpcResult<UnicodeTextQaDto> {
+    metrics::track("check_unicode_text_v2", async {
+        let project_uuid = parse_uuid(&payload.project_uuid, "projectUuid")?;
+        let (_, document) = load_project_jliff(
+            db.inner(),
+            settings.inner(),
+            project_uuid,
+            &payload.jliff_rel_path,
+        )
+        .await?;
+        run_unicode_text_check(db.inner(), project_uuid, payload.jliff_rel_path, &document)
+            .await
+            .map_err(Into::into)
+    })
+    .await
+}
+
+/// Removes invisible characters from the targets of the given documents (every document
+/// of the project by default) and normalizes them to NFC. Changes are recorded as
+/// revisions and the findings of the fixed segments are refreshed; sources are left as
+/// extracted, so source issues stay reported.
+#[tauri::command]
+pub async fn fix_unicode_text_v2(
+    db: State<'_, DbManager>,
+    settings: State<'_, SettingsManager>,
+    payload: FixUnicodeTextPayload,
+) -> IpcResult<UnicodeFixDto> {
+    metrics::track("fix_unicode_text_v2", async {
+        let project_uuid = parse_uuid(&payload.project_uuid, "projectUuid")?;
+        let documents = match payload.jliff_rel_paths {
+            Some(paths) => paths,
+            None => {
+                let project_root =
+                    resolve_project_root(db.inner(), settings.inner(), project_uuid).await?;
+                list_project_jliff_paths(&project_root).await?
+            }
+        };
+        let check = project_qa_profile(db.inner(), project_uuid)
+            .await?
+            .check(UNICODE_TEXT_CHECK, "warning");
+
+        let mut files = Vec::new();
+        for jliff_rel_path in documents {
+            let severity = check.severity.clone();
+            let (revisions, findings) = update_project_jliff(
+                db.inner(),
+                settings.inner(),
+                project_uuid,
+                &jliff_rel_path,
+                move |document| {
+                    let mut revisions = Vec::new();
+                    let mut findings = Vec::new();
+                    for unit in &mut document.transunits {
+                        let target = unit.effective_target_mut();
+                        let fixed = fix_unicode_text(target);
+                        if fixed == *target {
+                            continue;
+                        }
+                        let previous_target = std::mem::replace(target, fixed.clone());
+                        revisions.push(NewSegmentRevisionArgs {
+                            transunit_id: unit.transunit_id.clone(),
+                            previous_target,
+                            new_target: fixed,
+                        });
+                        findings.extend(unicode_finding(unit, &severity));
+                    }
+                    Ok((revisions, findings))
+                },
+            )
+            .await?;
+            if revisions.is_empty() {
+                continue;
+            }
+
+            files.push(UnicodeFixFileDto {
+                jliff_rel_path: jliff_rel_path.clone(),
+                updated_segments: revisions.len(),
+            });
+            db.replace_qa_findings(ReplaceQaFindingsArgs {
+                project_uuid,
+                jliff_rel_path: jliff_rel_path.clone(),
+                check_type: UNICODE_TEXT_CHECK.into(),
+                transunit_ids: Some(
+                    revisions
+                        .iter()
+                        .map(|revision| revision.transunit_id.clone())
+                        .collect(),
+                ),
+                findings: if check.enabled { findings } else { Vec::new() },
+            })
+            .await
+            .map_err(IpcError::from)?;
+            db.record_segment_revisions(RecordSegmentRevisionsArgs {
+                project_uuid,
+                jliff_rel_path,
+                origin: UNICODE_FIX_ORIGIN.to_string(),
+                author: None,
+                revisions,
+            })
+            .await
+            .map_err(IpcError::from)?;
+        }
+
+        Ok(UnicodeFixDto {
+            updated_segments: files.iter().map(|file| file.updated_segments).sum(),
+            files,
+        })
+    })
+    .await
+}
+
+/// Finding for the Unicode issues of a segment's source and target, if it has any.
+/// Runs the Unicode text check on `document` and replaces its stored findings.
+pub(super) async fn run_unicode_text_check(
+    db: &DbManager,
+    project_uuid: Uuid,
+    jliff_rel_path: String,
+    document: &JliffDocument,
+) -> Result<UnicodeTextQaDto, IpcError> {
+    let check = project_qa_profile(db, project_uuid)
+        .await?
+        .check(UNICODE_TEXT_CHECK, "warning");
+
+    let mut findings = Vec::new();
+    let mut segments_checked = 0;
+    for unit in document.transunits.iter().filter(|_| check.enabled) {
+        segments_checked += 1;
+        findings.extend(unicode_finding(unit, &check.severity));
+    }
+
+    let records = db
+        .replace_qa_findings(ReplaceQaFindingsArgs {
+            project_uuid,
+            jliff_rel_path: jliff_rel_path.clone(),
+            check_type: UNICODE_TEXT_CHECK.into(),
+            transunit_ids: None,
+            findings,
+        })
+        .await
+        .map_err(IpcError::from)?;
+
+    Ok(UnicodeTextQaDto {
+        jliff_rel_path,
+        segments_checked,
+        findings: records.into_iter().map(map_qa_finding_record).collect(),
+    })
+}
+
+fn unicode_finding(unit: &TransUnit, severity: &str) -> Option<NewQaFindingArgs> {
+    let source = find_unicode_issues(&unit.source);
+    let target = find_unicode_issues(unit.effective_target());
+    if source.is_empty() && target.is_empty() {
+        return None;
+    }
+    let summary = [("source", &source), ("target", &target)]
+        .into_iter()
+        .filter(|(_, issues)| !issues.is_empty())
+        .map(|(side, issues)| {
+            let described: Vec<String> = issues.iter().map(UnicodeIssue::describe).collect();
+            format!("{side}: {}", described.join(", "))
+        })
+        .collect::<Vec<_>>()
+        .join("; ");
+    Some(NewQaFindingArgs {
+        transunit_id: unit.transunit_id.clone(),
+        severity: severity.to_string(),
+        message: format!("Invisible or denormalized characters ({summary})."),
+        details: Some(json!({ "source": source, "target": target }).to_string()),
+    })
+}
+
+fn parse_uuid(value: &str, field: &str) -> Result<Uuid, IpcError> {
+    Uuid::parse_str(value)
+        .map_err(|_| IpcError::Validation(format!("invalid {field}: expected UUID, got '{value}'")))
+}
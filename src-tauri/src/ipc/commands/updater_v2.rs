@@ -0,0 +1,154 @@
+use std::time::Duration;
+
+use log::{info, warn};
+use serde_json::json;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use super::offline_v2::ensure_online;
+use crate::db::DbManager;
+use crate::downloads::Downloader;
+use crate::ipc::dto::{DownloadEventPayload, UpdateStatusDto};
+use crate::ipc::error::{IpcError, IpcResult};
+use crate::ipc::events::{DOWNLOAD_PROGRESS, UPDATE_READY, UPDATE_STATUS_CHANGED};
+use crate::ipc::state::OfflineMode;
+use crate::metrics;
+use crate::settings::SettingsManager;
+use crate::updater::{
+    UPDATES_CATEGORY, UpdateError, UpdateState, UpdateStatus, Updater, launch_installer,
+};
+
+/// Time the reply to `install_update_v2` gets to reach the window before the app exits.
+const EXIT_DELAY: Duration = Duration::from_millis(500);
+
+/// Returns the state of the updater. With `check`, a check for a newer release, and its
+/// download, starts in the background; changes are reported on `updates://status_changed`.
+/// Asking for a check while offline fails with an `OFFLINE` error.
+#[tauri::command]
+pub async fn get_update_status_v2(
+    app: AppHandle,
+    updater: State<'_, Updater>,
+    check: Option<bool>,
+) -> IpcResult<UpdateStatusDto> {
+    metrics::track("get_update_status_v2", async {
+        if check.unwrap_or(false) && updater.is_enabled() {
+            ensure_online(&app.state::<OfflineMode>(), "Checking for updates")?;
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                let _ = check_for_updates(&app).await;
+            });
+        }
+        Ok(map_update_status(&updater.status()))
+    })
+    .await
+}
+
+/// Launches the staged installer after verifying its signature again, then quits so the
+/// installer can replace the app.
+#[tauri::command]
+pub async fn install_update_v2(app: AppHandle, updater: State<'_, Updater>) -> IpcResult<()> {
+    metrics::track("install_update_v2", async {
+        let path = updater
+            .verified_installer()
+            .await
+            .map_err(|error| match error {
+                UpdateError::Disabled | UpdateError::NotReady | UpdateError::InvalidSignature => {
+                    IpcError::Validation(error.to_string())
+                }
+                error => IpcError::Internal(error.to_string()),
+            })?;
+        launch_installer(&path).map_err(|error| IpcError::Internal(error.to_string()))?;
+        info!(target: "ipc::updater", "launched the installer {}", path.display());
+
+        tauri::async_runtime::spawn(async move {
+            tokio::time::sleep(EXIT_DELAY).await;
+            app.exit(0);
+        });
+        Ok(())
+    })
+    .await
+}
+
+/// Checks for an update and stages it. Every change is emitted on `updates://status_changed`
+/// and the installer download on `download:progress`; a newly staged update is also
+/// published as an `updates://ready` domain event.
+pub async fn check_for_updates(app: &AppHandle) -> Result<UpdateStatus, UpdateError> {
+    let updater = app.state::<Updater>();
+    let cache_root = app
+        .state::<SettingsManager>()
+        .current()
+        .await
+        .downloads_dir();
+    let was_ready = updater.status().state == UpdateState::Ready;
+
+    let mut downloading = false;
+    let result = updater
+        .check(&app.state::<Downloader>(), &cache_root, |status| {
+            emit_update_status(app, status, &mut downloading)
+        })
+        .await;
+
+    if let Ok(status) = &result
+        && status.state == UpdateState::Ready
+        && !was_ready
+        && let Some(release) = &status.release
+    {
+        let payload = json!({
+            "version": release.version,
+            "notes": release.notes,
+        });
+        if let Err(error) = app
+            .state::<DbManager>()
+            .publish_outbox_event(UPDATE_READY, &payload)
+            .await
+        {
+            warn!(target: "ipc::updater", "failed to publish the update event: {error}");
+        }
+    }
+    result
+}
+
+/// Emits the status and, while the installer is being downloaded, the matching download
+/// event. `downloading` remembers whether a download was reported.
+fn emit_update_status(app: &AppHandle, status: &UpdateStatus, downloading: &mut bool) {
+    if let Err(error) = app.emit(UPDATE_STATUS_CHANGED, map_update_status(status)) {
+        warn!(target: "ipc::updater", "failed to emit update status: {error}");
+    }
+
+    let download_state = match status.state {
+        UpdateState::Downloading => "progress",
+        UpdateState::Ready if *downloading => "completed",
+        UpdateState::Failed if *downloading => "failed",
+        _ => return,
+    };
+    *downloading = status.state == UpdateState::Downloading;
+    let Some(release) = &status.release else {
+        return;
+    };
+    let payload = DownloadEventPayload {
+        download_id: format!("update-{}", release.version),
+        category: UPDATES_CATEGORY.into(),
+        file_name: release.file_name(),
+        state: download_state.into(),
+        downloaded: status.downloaded,
+        total: status.total,
+        error: status.error.clone(),
+    };
+    if let Err(error) = app.emit(DOWNLOAD_PROGRESS, payload) {
+        warn!(target: "ipc::updater", "failed to emit download event: {error}");
+    }
+}
+
+fn map_update_status(status: &UpdateStatus) -> UpdateStatusDto {
+    let release = status.release.as_ref();
+    UpdateStatusDto {
+        state: status.state.as_str().into(),
+        current_version: env!("CARGO_PKG_VERSION").into(),
+        available_version: release.map(|release| release.version.clone()),
+        notes: release.and_then(|release| release.notes.clone()),
+        pub_date: release.and_then(|release| release.pub_date.clone()),
+        downloaded: status.downloaded,
+        total: status.total,
+        error: status.error.clone(),
+        checked_at: status.checked_at.map(|checked_at| checked_at.to_rfc3339()),
+    }
+}
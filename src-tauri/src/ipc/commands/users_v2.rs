@@ -7,15 +7,19 @@ use crate::ipc::dto::{
     CreateUserPayload, PermissionOverrideDto, UpdateUserPayload, UserProfileDto,
 };
 use crate::ipc::error::{IpcError, IpcResult};
+use crate::metrics;
 
 #[tauri::command]
 pub async fn create_user_profile_v2(
     db: State<'_, DbManager>,
     payload: CreateUserPayload,
 ) -> IpcResult<UserProfileDto> {
-    let args = map_new_user_args(payload)?;
-    let profile = db.create_user_profile(args).await.map_err(IpcError::from)?;
-    Ok(map_user_profile(profile))
+    metrics::track("create_user_profile_v2", async {
+        let args = map_new_user_args(payload)?;
+        let profile = db.create_user_profile(args).await.map_err(IpcError::from)?;
+        Ok(map_user_profile(profile))
+    })
+    .await
 }
 
 #[tauri::command]
@@ -23,16 +27,22 @@ pub async fn update_user_profile_v2(
     db: State<'_, DbManager>,
     payload: UpdateUserPayload,
 ) -> IpcResult<Option<UserProfileDto>> {
-    let args = map_update_user_args(payload)?;
-    let profile = db.update_user_profile(args).await.map_err(IpcError::from)?;
-    Ok(profile.map(map_user_profile))
+    metrics::track("update_user_profile_v2", async {
+        let args = map_update_user_args(payload)?;
+        let profile = db.update_user_profile(args).await.map_err(IpcError::from)?;
+        Ok(profile.map(map_user_profile))
+    })
+    .await
 }
 
 #[tauri::command]
 pub async fn delete_user_profile_v2(db: State<'_, DbManager>, user_uuid: String) -> IpcResult<()> {
-    let uuid = parse_uuid(&user_uuid, "userUuid")?;
-    db.delete_user_profile(uuid).await.map_err(IpcError::from)?;
-    Ok(())
+    metrics::track("delete_user_profile_v2", async {
+        let uuid = parse_uuid(&user_uuid, "userUuid")?;
+        db.delete_user_profile(uuid).await.map_err(IpcError::from)?;
+        Ok(())
+    })
+    .await
 }
 
 #[tauri::command]
@@ -40,15 +50,21 @@ pub async fn get_user_profile_v2(
     db: State<'_, DbManager>,
     user_uuid: String,
 ) -> IpcResult<Option<UserProfileDto>> {
-    let uuid = parse_uuid(&user_uuid, "userUuid")?;
-    let profile = db.get_user_profile(uuid).await.map_err(IpcError::from)?;
-    Ok(profile.map(map_user_profile))
+    metrics::track("get_user_profile_v2", async {
+        let uuid = parse_uuid(&user_uuid, "userUuid")?;
+        let profile = db.get_user_profile(uuid).await.map_err(IpcError::from)?;
+        Ok(profile.map(map_user_profile))
+    })
+    .await
 }
 
 #[tauri::command]
 pub async fn list_user_profiles_v2(db: State<'_, DbManager>) -> IpcResult<Vec<UserProfileDto>> {
-    let profiles = db.list_user_profiles().await.map_err(IpcError::from)?;
-    Ok(profiles.into_iter().map(map_user_profile).collect())
+    metrics::track("list_user_profiles_v2", async {
+        let profiles = db.list_user_profiles().await.map_err(IpcError::from)?;
+        Ok(profiles.into_iter().map(map_user_profile).collect())
+    })
+    .await
 }
 
 fn map_new_user_args(payload: CreateUserPayload) -> Result<NewUserArgs, IpcError> {
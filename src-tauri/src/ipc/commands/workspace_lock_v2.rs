@@ -0,0 +1,263 @@
+//! Workspace lock: after a period without user activity, or on request, the workspace locks.
+//! Only a workspace with a local passphrase can lock. Locking forgets the client data key, the
+//! signed-in profile and outstanding confirmation tokens, and [`enforce_workspace_lock`]
+//! rejects every command outside [`UNLOCK_COMMANDS`] until the passphrase is entered again.
+
+use argon2::Argon2;
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use chacha20poly1305::aead::OsRng;
+use log::warn;
+use tauri::ipc::Invoke;
+use tauri::{AppHandle, Emitter, Manager, Runtime, State};
+
+use super::clients_v2::load_client_pii_key;
+use crate::db::DbManager;
+use crate::db::pii::FieldCipher;
+use crate::ipc::dto::{
+    SetWorkspacePassphrasePayload, WorkspaceLockEventPayload, WorkspaceLockStateDto,
+};
+use crate::ipc::error::{IpcError, IpcResult};
+use crate::ipc::events::WORKSPACE_LOCK_STATE;
+use crate::ipc::state::{ActiveProfile, SensitiveActionTokens, WorkspaceLock};
+use crate::metrics;
+use crate::secrets::SecretsVault;
+use crate::settings::SettingsManager;
+
+/// Name of the passphrase hash in the secrets vault, an Argon2id PHC string.
+const WORKSPACE_PASSPHRASE_SECRET: &str = "workspace-passphrase";
+const MIN_PASSPHRASE_CHARS: usize = 8;
+
+/// Commands available while the workspace is locked: unlocking it and what the shell needs
+/// to run.
+pub const UNLOCK_COMMANDS: &[&str] = &[
+    "health_check",
+    "notify_shell_ready",
+    "get_app_settings",
+    "get_offline_status_v2",
+    "get_workspace_lock_state_v2",
+    "report_workspace_activity_v2",
+    "lock_workspace_v2",
+    "unlock_workspace_v2",
+];
+
+#[tauri::command]
+pub async fn get_workspace_lock_state_v2(
+    lock: State<'_, WorkspaceLock>,
+    vault: State<'_, SecretsVault>,
+) -> IpcResult<WorkspaceLockStateDto> {
+    metrics::track("get_workspace_lock_state_v2", async {
+        Ok(WorkspaceLockStateDto {
+            locked: lock.is_locked(),
+            has_passphrase: stored_passphrase(&vault)?.is_some(),
+        })
+    })
+    .await
+}
+
+/// Restarts the inactivity clock. The frontend calls this, throttled, on user input.
+#[tauri::command]
+pub async fn report_workspace_activity_v2(lock: State<'_, WorkspaceLock>) -> IpcResult<()> {
+    metrics::track("report_workspace_activity_v2", async {
+        lock.touch();
+        Ok(())
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn lock_workspace_v2(app: AppHandle) -> IpcResult<()> {
+    metrics::track("lock_workspace_v2", async {
+        lock_workspace(&app, "manual").map_err(Into::into)
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn unlock_workspace_v2(app: AppHandle, passphrase: Option<String>) -> IpcResult<()> {
+    metrics::track("unlock_workspace_v2", async {
+        unlock_workspace(&app, passphrase.as_deref())
+            .await
+            .map_err(Into::into)
+    })
+    .await
+}
+
+/// Sets, changes or (with no `passphrase`) removes the local unlock passphrase. Changing or
+/// removing one needs the current passphrase; without one the workspace cannot lock.
+#[tauri::command]
+pub async fn set_workspace_passphrase_v2(
+    lock: State<'_, WorkspaceLock>,
+    vault: State<'_, SecretsVault>,
+    payload: SetWorkspacePassphrasePayload,
+) -> IpcResult<WorkspaceLockStateDto> {
+    metrics::track("set_workspace_passphrase_v2", async {
+        if lock.is_locked() {
+            return Err(IpcError::Validation("Unlock the workspace first.".into()).into());
+        }
+        if let Some(stored) = stored_passphrase(&vault)? {
+            let current = payload.current_passphrase.as_deref().unwrap_or_default();
+            if !verify_passphrase(&stored, current) {
+                return Err(IpcError::Validation("The current passphrase is wrong.".into()).into());
+            }
+        }
+        let result = match payload.passphrase.as_deref() {
+            Some(passphrase) => {
+                if passphrase.chars().count() < MIN_PASSPHRASE_CHARS {
+                    return Err(IpcError::Validation(format!(
+                        "The passphrase needs at least {MIN_PASSPHRASE_CHARS} characters."
+                    ))
+                    .into());
+                }
+                vault.store(WORKSPACE_PASSPHRASE_SECRET, &seal_passphrase(passphrase)?)
+            }
+            None => vault.delete(WORKSPACE_PASSPHRASE_SECRET),
+        };
+        result.map_err(|error| {
+            warn!(target: "ipc::workspace_lock", "failed to save the passphrase: {error}");
+            IpcError::Internal("Unable to save the passphrase in the keychain.".into())
+        })?;
+        Ok(WorkspaceLockStateDto {
+            locked: false,
+            has_passphrase: payload.passphrase.is_some(),
+        })
+    })
+    .await
+}
+
+/// Locks the workspace and tells the frontend why. Does nothing when it is already locked;
+/// fails when no passphrase is set, as nothing could keep the workspace locked.
+pub fn lock_workspace<R: Runtime>(app: &AppHandle<R>, reason: &str) -> Result<(), IpcError> {
+    if !has_passphrase(&app.state::<SecretsVault>())? {
+        return Err(IpcError::Validation(
+            "Set a passphrase before locking the workspace.".into(),
+        ));
+    }
+    if !app.state::<WorkspaceLock>().lock() {
+        return Ok(());
+    }
+    let db = app.state::<DbManager>();
+    if db.pii_cipher().is_enabled() {
+        db.set_pii_cipher(FieldCipher::locked());
+    }
+    app.state::<ActiveProfile>().set(None);
+    app.state::<SensitiveActionTokens>().clear();
+    emit_lock_state(app, true, reason);
+    Ok(())
+}
+
+/// Unlocks the workspace after checking `passphrase` against the stored one, and loads the
+/// client data key again.
+pub async fn unlock_workspace<R: Runtime>(
+    app: &AppHandle<R>,
+    passphrase: Option<&str>,
+) -> Result<(), IpcError> {
+    let lock = app.state::<WorkspaceLock>();
+    if !lock.is_locked() {
+        return Ok(());
+    }
+    let vault = app.state::<SecretsVault>();
+    let verified = stored_passphrase(&vault)?
+        .zip(passphrase)
+        .is_some_and(|(stored, passphrase)| verify_passphrase(&stored, passphrase));
+    if !verified {
+        return Err(IpcError::Validation("The passphrase is wrong.".into()));
+    }
+    let app_folder = app.state::<SettingsManager>().current().await.app_folder;
+    load_client_pii_key(&app.state::<DbManager>(), &vault, &app_folder).await;
+    lock.unlock();
+    emit_lock_state(app, false, "unlocked");
+    Ok(())
+}
+
+/// Whether a local passphrase is set, which locking the workspace needs.
+pub(crate) fn has_passphrase(vault: &SecretsVault) -> Result<bool, IpcError> {
+    Ok(stored_passphrase(vault)?.is_some())
+}
+
+pub fn allowed_while_locked(command: &str) -> bool {
+    UNLOCK_COMMANDS.contains(&command)
+}
+
+/// Wraps the invoke handler so that, while the workspace is locked, commands outside
+/// [`UNLOCK_COMMANDS`] are rejected before they run.
+pub fn enforce_workspace_lock<R, H>(
+    handler: H,
+) -> impl Fn(Invoke<R>) -> bool + Send + Sync + 'static
+where
+    R: Runtime,
+    H: Fn(Invoke<R>) -> bool + Send + Sync + 'static,
+{
+    move |invoke| {
+        let command = invoke.message.command();
+        let blocked = !allowed_while_locked(command)
+            && invoke
+                .message
+                .webview_ref()
+                .try_state::<WorkspaceLock>()
+                .is_some_and(|lock| lock.is_locked());
+        if blocked {
+            warn!(target: "ipc::workspace_lock", "rejected {command} while locked");
+            invoke.resolver.reject(format!(
+                "'{command}' is not available while the workspace is locked."
+            ));
+            return true;
+        }
+        handler(invoke)
+    }
+}
+
+fn emit_lock_state<R: Runtime>(app: &AppHandle<R>, locked: bool, reason: &str) {
+    let payload = WorkspaceLockEventPayload {
+        locked,
+        reason: reason.to_string(),
+    };
+    if let Err(error) = app.emit(WORKSPACE_LOCK_STATE, payload) {
+        warn!(target: "ipc::workspace_lock", "failed to emit lock state event: {error}");
+    }
+}
+
+fn stored_passphrase(vault: &SecretsVault) -> Result<Option<String>, IpcError> {
+    vault.read(WORKSPACE_PASSPHRASE_SECRET).map_err(|error| {
+        warn!(target: "ipc::workspace_lock", "failed to read the passphrase: {error}");
+        IpcError::Internal("Unable to read the passphrase from the keychain.".into())
+    })
+}
+
+/// Argon2id hash of a passphrase in PHC format, as kept in the vault.
+fn seal_passphrase(passphrase: &str) -> Result<String, IpcError> {
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(passphrase.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|error| IpcError::Internal(format!("Unable to hash the passphrase: {error}")))
+}
+
+/// Checks `passphrase` against a stored hash; the comparison runs in constant time.
+fn verify_passphrase(stored: &str, passphrase: &str) -> bool {
+    PasswordHash::new(stored).is_ok_and(|hash| {
+        Argon2::default()
+            .verify_password(passphrase.as_bytes(), &hash)
+            .is_ok()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn passphrases_are_salted_and_verified() {
+        let stored = seal_passphrase("correct horse").unwrap();
+        assert!(stored.starts_with("$argon2id$"));
+        assert!(verify_passphrase(&stored, "correct horse"));
+        assert!(!verify_passphrase(&stored, "correct horse "));
+        assert_ne!(seal_passphrase("correct horse").unwrap(), stored);
+        assert!(!verify_passphrase("garbage", "correct horse"));
+    }
+
+    #[test]
+    fn only_unlocking_is_allowed_while_locked() {
+        assert!(allowed_while_locked("unlock_workspace_v2"));
+        assert!(!allowed_while_locked("create_client_record_v2"));
+    }
+}
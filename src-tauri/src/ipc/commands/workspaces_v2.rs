@@ -0,0 +1,358 @@
+//! Workspaces: separate app folders, each with its own database and projects, such as a
+//! personal and an agency workspace. Switching reopens the database from the other folder
+//! and reloads the state read from it, without restarting the app. Each workspace keeps its
+//! own database and client data keys in the secrets vault.
+
+use std::path::{Path, PathBuf};
+
+use log::{error, info, warn};
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::fs;
+
+use super::clients_v2::load_client_pii_key;
+use super::provider_rate_limits_v2::rate_limit_from_record;
+use super::sensitive_actions_v2::redeem_confirmation;
+use super::shared::{fs_error, path_exists_bool};
+use crate::db::encryption::{DATABASE_KEY_SECRET, generate_key};
+use crate::db::pii::FieldCipher;
+use crate::db::{DbManager, SQLITE_DB_FILE};
+use crate::ipc::dto::{SwitchWorkspacePayload, WorkspaceDto};
+use crate::ipc::error::{IpcError, IpcResult};
+use crate::ipc::events::WORKSPACE_SWITCHED;
+use crate::ipc::state::{ActiveProfile, SensitiveActionTokens, TranslationState, WorkspaceLock};
+use crate::metrics;
+use crate::providers::ProviderRateLimiter;
+use crate::secrets::{SecretsVault, VaultError, workspace_secret};
+use crate::settings::{SettingsError, SettingsManager, WorkspaceEntry};
+
+/// Lists the configured workspaces; the app folder in use is always among them.
+#[tauri::command]
+pub async fn list_workspaces_v2(
+    settings: State<'_, SettingsManager>,
+) -> IpcResult<Vec<WorkspaceDto>> {
+    metrics::track("list_workspaces_v2", async {
+        let current = settings.current().await;
+        Ok(workspace_dtos(&current.app_folder, &current.workspaces).await)
+    })
+    .await
+}
+
+/// Makes `folder` the app folder. The database of the current workspace is closed and the
+/// one in `folder` opened, or created when the folder is new; a failure leaves the current
+/// workspace in use. Refused while translations or pipeline jobs are running. Needs a
+/// confirmation token from `request_sensitive_action_v2`.
+#[tauri::command]
+pub async fn switch_workspace_v2(
+    app: AppHandle,
+    settings: State<'_, SettingsManager>,
+    db: State<'_, DbManager>,
+    limiter: State<'_, ProviderRateLimiter>,
+    vault: State<'_, SecretsVault>,
+    payload: SwitchWorkspacePayload,
+    confirmation_token: String,
+) -> IpcResult<Vec<WorkspaceDto>> {
+    metrics::track("switch_workspace_v2", async {
+        let tokens = app.state::<SensitiveActionTokens>();
+        redeem_confirmation(&tokens, &confirmation_token, "switch_workspace_v2")?;
+        let folder = PathBuf::from(payload.folder.trim());
+        if !folder.is_absolute() {
+            return Err(IpcError::Validation(
+                "Select an absolute path for the workspace folder.".into(),
+            )
+            .into());
+        }
+        let name = payload
+            .name
+            .as_deref()
+            .map(str::trim)
+            .filter(|name| !name.is_empty());
+        if app.state::<WorkspaceLock>().is_locked() {
+            return Err(
+                IpcError::Validation("Unlock the workspace before switching.".into()).into(),
+            );
+        }
+
+        let current = settings.current().await;
+        let workspaces = remember_workspace(
+            remember_workspace(current.workspaces.clone(), &current.app_folder, None),
+            &folder,
+            name,
+        );
+        if folder == current.app_folder {
+            settings
+                .update_and_save_workspace(folder, workspaces)
+                .await
+                .map_err(|error| settings_error(&error))?;
+            let current = settings.current().await;
+            return Ok(workspace_dtos(&current.app_folder, &current.workspaces).await);
+        }
+        if folder.starts_with(&current.app_folder) || current.app_folder.starts_with(&folder) {
+            return Err(IpcError::Validation(
+                "Select a folder that is not nested within the current workspace.".into(),
+            )
+            .into());
+        }
+        if !app.state::<TranslationState>().snapshot().is_empty() {
+            return Err(IpcError::Validation(
+                "Finish or cancel active translation jobs before switching workspaces.".into(),
+            )
+            .into());
+        }
+        let running = db.count_running_jobs().await.map_err(IpcError::from)?;
+        if running > 0 {
+            return Err(IpcError::Validation(format!(
+                "Wait for the {running} running job(s) to finish before switching workspaces."
+            ))
+            .into());
+        }
+        if path_exists_bool(&folder).await {
+            let metadata = fs::metadata(&folder)
+                .await
+                .map_err(|error| fs_error("inspect workspace folder", error))?;
+            if !metadata.is_dir() {
+                return Err(IpcError::Validation(
+                    "The selected path points to a file. Choose a folder instead.".into(),
+                )
+                .into());
+            }
+        }
+
+        let previous_key = db.encryption_key();
+        let key = workspace_database_key(&vault, &folder, current.database_encryption).map_err(
+            |error| {
+                warn!(target: "ipc::workspaces", "failed to read the database key: {error}");
+                IpcError::Internal("Unable to read the database key from the keychain.".into())
+            },
+        )?;
+        if let Err(error) = open_workspace(&db, &limiter, &vault, &folder, key).await {
+            error!(
+                target: "ipc::workspaces",
+                "failed to open the workspace in {}: {error}",
+                folder.display()
+            );
+            if let Err(revert_error) = open_workspace(
+                &db,
+                &limiter,
+                &vault,
+                &current.app_folder,
+                previous_key.clone(),
+            )
+            .await
+            {
+                error!(
+                    target: "ipc::workspaces",
+                    "failed to reopen the previous workspace: {revert_error}"
+                );
+            }
+            return Err(IpcError::Internal(
+                "The database of the selected workspace could not be opened. The current \
+                 workspace is still in use."
+                    .into(),
+            )
+            .into());
+        }
+
+        if let Err(error) = settings
+            .update_and_save_workspace(folder.clone(), workspaces)
+            .await
+        {
+            warn!(target: "ipc::workspaces", "failed to persist the workspace switch: {error}");
+            if let Err(revert_error) =
+                open_workspace(&db, &limiter, &vault, &current.app_folder, previous_key).await
+            {
+                error!(
+                    target: "ipc::workspaces",
+                    "failed to reopen the previous workspace: {revert_error}"
+                );
+            }
+            return Err(IpcError::Internal(
+                "Unable to persist the workspace switch. The previous workspace was reopened."
+                    .into(),
+            )
+            .into());
+        }
+        // Profiles and confirmation tokens belong to the previous workspace.
+        app.state::<ActiveProfile>().set(None);
+        tokens.clear();
+        info!(
+            target: "ipc::workspaces",
+            "switched workspace from {} to {}",
+            current.app_folder.display(),
+            folder.display()
+        );
+
+        let current = settings.current().await;
+        let workspaces = workspace_dtos(&current.app_folder, &current.workspaces).await;
+        if let Some(active) = workspaces.iter().find(|workspace| workspace.active)
+            && let Err(error) = app.emit(WORKSPACE_SWITCHED, active.clone())
+        {
+            warn!(target: "ipc::workspaces", "failed to emit workspace switch event: {error}");
+        }
+        Ok(workspaces)
+    })
+    .await
+}
+
+/// Swaps the database pool for the one in `folder`, encrypted with `key`, and reloads what
+/// was read from it along with the workspace's client data key.
+async fn open_workspace(
+    db: &DbManager,
+    limiter: &ProviderRateLimiter,
+    vault: &SecretsVault,
+    folder: &Path,
+    key: Option<String>,
+) -> Result<(), IpcError> {
+    db.reopen_with_key(folder, key)
+        .await
+        .map_err(IpcError::from)?;
+    // Until the workspace's own key is loaded, client data can be neither read nor written.
+    db.set_pii_cipher(FieldCipher::locked());
+    load_client_pii_key(db, vault, folder).await;
+    reload_rate_limits(db, limiter).await
+}
+
+/// Key of the database in `folder` when databases are encrypted. A workspace without a
+/// database yet gets a key of its own; one without a key of its own uses the key shared by
+/// all workspaces before they had their own.
+pub(crate) fn workspace_database_key(
+    vault: &SecretsVault,
+    folder: &Path,
+    encryption: bool,
+) -> Result<Option<String>, VaultError> {
+    if !encryption {
+        return Ok(None);
+    }
+    let secret = workspace_secret(DATABASE_KEY_SECRET, folder);
+    if let Some(key) = vault.read(&secret)? {
+        return Ok(Some(key));
+    }
+    if !folder.join(SQLITE_DB_FILE).exists() {
+        let key = generate_key();
+        vault.store(&secret, &key)?;
+        return Ok(Some(key));
+    }
+    vault.read(DATABASE_KEY_SECRET)
+}
+
+async fn reload_rate_limits(db: &DbManager, limiter: &ProviderRateLimiter) -> Result<(), IpcError> {
+    let records = db
+        .list_provider_rate_limits()
+        .await
+        .map_err(IpcError::from)?;
+    limiter.replace_limits(
+        records
+            .iter()
+            .map(|record| (record.provider.clone(), rate_limit_from_record(record))),
+    );
+    Ok(())
+}
+
+fn settings_error(error: &SettingsError) -> IpcError {
+    warn!(target: "ipc::workspaces", "failed to persist workspaces: {error}");
+    IpcError::Internal("Unable to persist the workspace settings.".into())
+}
+
+/// Adds `folder` to `workspaces` unless it is listed, and renames it when `name` is given.
+/// New workspaces are named after their folder.
+fn remember_workspace(
+    mut workspaces: Vec<WorkspaceEntry>,
+    folder: &Path,
+    name: Option<&str>,
+) -> Vec<WorkspaceEntry> {
+    match workspaces.iter_mut().find(|entry| entry.folder == folder) {
+        Some(entry) => {
+            if let Some(name) = name {
+                entry.name = name.to_string();
+            }
+        }
+        None => workspaces.push(WorkspaceEntry {
+            name: name.map_or_else(|| folder_name(folder), str::to_string),
+            folder: folder.to_path_buf(),
+        }),
+    }
+    workspaces
+}
+
+fn folder_name(folder: &Path) -> String {
+    folder
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| folder.to_string_lossy().into_owned())
+}
+
+async fn workspace_dtos(app_folder: &Path, workspaces: &[WorkspaceEntry]) -> Vec<WorkspaceDto> {
+    let mut dtos = Vec::new();
+    for entry in remember_workspace(workspaces.to_vec(), app_folder, None) {
+        dtos.push(WorkspaceDto {
+            active: entry.folder == app_folder,
+            exists: path_exists_bool(&entry.folder).await,
+            name: entry.name,
+            folder: entry.folder.to_string_lossy().into_owned(),
+        });
+    }
+    dtos
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn workspaces_are_remembered_once_per_folder() {
+        let personal = PathBuf::from("/data/Personal");
+        let agency = PathBuf::from("/data/agency");
+
+        let workspaces = remember_workspace(Vec::new(), &personal, None);
+        assert_eq!(workspaces[0].name, "Personal");
+
+        let workspaces = remember_workspace(workspaces, &agency, Some("Agency"));
+        let workspaces = remember_workspace(workspaces, &personal, None);
+        assert_eq!(
+            workspaces,
+            vec![
+                WorkspaceEntry {
+                    name: "Personal".into(),
+                    folder: personal.clone(),
+                },
+                WorkspaceEntry {
+                    name: "Agency".into(),
+                    folder: agency,
+                },
+            ]
+        );
+
+        let renamed = remember_workspace(workspaces, &personal, Some("Own clients"));
+        assert_eq!(renamed.len(), 2);
+        assert_eq!(renamed[0].name, "Own clients");
+    }
+
+    #[test]
+    fn each_workspace_has_its_own_database_key() {
+        let vault = SecretsVault::in_memory();
+        let personal = tempfile::tempdir().unwrap();
+        let agency = tempfile::tempdir().unwrap();
+        assert_eq!(
+            workspace_database_key(&vault, personal.path(), false).unwrap(),
+            None
+        );
+
+        let key = workspace_database_key(&vault, personal.path(), true)
+            .unwrap()
+            .expect("a new workspace gets a key");
+        assert_eq!(
+            workspace_database_key(&vault, personal.path(), true).unwrap(),
+            Some(key.clone())
+        );
+        let other = workspace_database_key(&vault, agency.path(), true).unwrap();
+        assert!(other.is_some_and(|other| other != key));
+
+        // A database from before workspaces had their own key uses the shared one.
+        let legacy = tempfile::tempdir().unwrap();
+        std::fs::write(legacy.path().join(SQLITE_DB_FILE), b"").unwrap();
+        vault.store(DATABASE_KEY_SECRET, "shared").unwrap();
+        assert_eq!(
+            workspace_database_key(&vault, legacy.path(), true).unwrap(),
+            Some("shared".into())
+        );
+    }
+}
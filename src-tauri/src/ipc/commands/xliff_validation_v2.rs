@@ -0,0 +1,53 @@
+use std::path::PathBuf;
+
+use crate::ipc::dto::XliffValidationDto;
+use crate::ipc::error::{IpcError, IpcResult};
+use crate::jliff::xliff_validation::{XliffVersion, validate_xliff};
+use crate::metrics;
+
+/// Checks an XLIFF file for well-formedness, against the core schema of its version and
+/// against the lint rules of the CAT tool that wrote it. `version` (`1.2`, `2.0` or `2.1`)
+/// overrides the version the file declares.
+#[tauri::command]
+pub async fn validate_xliff_v2(
+    path: String,
+    version: Option<String>,
+) -> IpcResult<XliffValidationDto> {
+    metrics::track("validate_xliff_v2", async {
+        let file_path = PathBuf::from(path.trim());
+        if !file_path.is_absolute() {
+            return Err(IpcError::Validation("path must be an absolute path.".into()).into());
+        }
+        let version = match version.as_deref().map(str::trim) {
+            None | Some("") => None,
+            Some(value) => Some(XliffVersion::parse(value).ok_or_else(|| {
+                IpcError::Validation(format!(
+                    "Unsupported XLIFF version '{value}'. Expected 1.2, 2.0 or 2.1."
+                ))
+            })?),
+        };
+
+        let report = {
+            let file_path = file_path.clone();
+            tokio::task::spawn_blocking(move || validate_xliff(&file_path, version))
+                .await
+                .map_err(|error| IpcError::Internal(format!("Validation failed: {error}")))?
+                .map_err(|error| IpcError::Validation(format!("{error:#}")))?
+        };
+        if !report.passed() {
+            log::debug!(
+                target: "ipc::xliff_validation",
+                "{} has {} XLIFF error(s)",
+                file_path.display(),
+                report.error_count()
+            );
+        }
+
+        Ok(XliffValidationDto {
+            path: file_path.to_string_lossy().into_owned(),
+            passed: report.passed(),
+            report,
+        })
+    })
+    .await
+}
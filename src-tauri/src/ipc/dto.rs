@@ -1,6 +1,13 @@
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::delivery::transports::TargetSettings;
+use crate::guest::ReviewBundleManifest;
+use crate::import::roles::RoleRule;
+use crate::providers::{PromptTemplateDefinition, PromptTerm};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TranslationRequest {
@@ -189,8 +196,162 @@ pub struct ClientDto {
     pub address: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub vat_number: Option<String>,
+    /// How far the VAT number was verified: `vies`, `checksum`, `format` or `unverified`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vat_status: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vat_checked_at: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub note: Option<String>,
+    /// ISO 4217 billing currency; absent means the base currency.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub currency: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub postal_address: Option<PostalAddressDto>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaxIdValidationDto {
+    /// Prefix and number without separators, as it is stored.
+    pub normalized: String,
+    /// `vies`, `checksum`, `format` or `unverified`.
+    pub status: String,
+    /// Whether the number can be confirmed in VIES.
+    pub eu_vat: bool,
+    /// Trader name and address registered in VIES, when looked up and disclosed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub registered_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub registered_address: Option<String>,
+}
+
+/// Structured client address, as resolved by `places_resolve_details` or entered by hand.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PostalAddressDto {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub street: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub street_number: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub postal_code: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locality: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub country_code: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub country: Option<String>,
+    /// Google Places id of the resolved address; manual entries have none and are validated.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub place_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FormatClientAddressPayload {
+    pub client_uuid: String,
+    /// Country the document is sent from; the country line is left out for addresses there.
+    #[serde(default)]
+    pub home_country_code: Option<String>,
+    /// Put the client name on the first line.
+    #[serde(default)]
+    pub include_name: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FormattedAddressDto {
+    pub lines: Vec<String>,
+    /// The lines joined with ", ".
+    pub single_line: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportCsvPayload {
+    /// Absolute path of the CSV file.
+    pub input_path: String,
+    /// CSV header to read each field from, e.g. `{"name": "Company"}`. Unmapped fields are
+    /// read from the header named like the field.
+    #[serde(default)]
+    pub column_mapping: BTreeMap<String, String>,
+    /// Validate and report without creating anything.
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportZipAssetsPayload {
+    pub project_uuid: String,
+    /// Absolute path of the ZIP archive.
+    pub zip_path: String,
+    /// Role of the files under a folder of the archive, keyed by folder path such as
+    /// `"source"` or `"docs/reference"`; the deepest listed folder wins. Other files get the
+    /// role of their extension.
+    #[serde(default)]
+    pub role_mapping: BTreeMap<String, ProjectAssetRoleDto>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportZipAssetsResponseDto {
+    /// Files registered with the project, in archive order.
+    pub files: Vec<ProjectFileBundleV2Dto>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtractImageTextPayload {
+    pub project_uuid: String,
+    /// Project file with the image role to read.
+    pub file_uuid: String,
+    /// Adds the text as a processable HTML document instead of an OCR text file.
+    #[serde(default)]
+    pub translatable: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageTextExtractionDto {
+    /// Project file holding the recognized text.
+    pub file: ProjectFileBundleV2Dto,
+    pub text: String,
+    /// Tesseract languages the engine was run with.
+    pub languages: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CsvImportReportDto {
+    pub dry_run: bool,
+    /// Non-blank data rows in the file.
+    pub total_rows: usize,
+    /// Records created, or that would be created by a dry run.
+    pub created: usize,
+    pub created_uuids: Vec<String>,
+    pub duplicates: usize,
+    pub invalid: usize,
+    /// One entry per row left out, in file order.
+    pub issues: Vec<CsvRowIssueDto>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CsvRowIssueDto {
+    /// Spreadsheet row number; the header is row 1.
+    pub row: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+    pub message: String,
+    /// Whether the row repeats a stored record or an earlier row, rather than being invalid.
+    pub duplicate: bool,
+    /// Stored record the row duplicates.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duplicate_of: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -209,6 +370,10 @@ pub struct CreateClientPayload {
     pub vat_number: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub note: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub currency: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub postal_address: Option<PostalAddressDto>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -227,6 +392,11 @@ pub struct UpdateClientPayload {
     pub vat_number: Option<Option<String>>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub note: Option<Option<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub currency: Option<Option<String>>,
+    /// Replaces the structured address; an empty object clears it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub postal_address: Option<PostalAddressDto>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -261,12 +431,19 @@ pub struct CreateProjectPayload {
     #[serde(default)]
     pub subjects: Vec<String>,
     pub language_pairs: Vec<ProjectLanguagePairDto>,
+    /// Pipeline preset of the project; the default of its type when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pipeline_preset_uuid: Option<String>,
 }
 
-fn default_project_status() -> String {
+pub(crate) fn default_project_status() -> String {
     "active".to_string()
 }
 
+fn default_true() -> bool {
+    true
+}
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum ProjectAssetRoleDto {
@@ -320,6 +497,12 @@ pub struct ConversionTaskDto {
     pub paragraph: Option<bool>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub embed: Option<bool>,
+    /// SRX rules replacing the converter's defaults when segmenting by sentence.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub srx: Option<String>,
+    /// Converter filter the formats manifest names for the source file's format.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filter: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -330,6 +513,26 @@ pub struct ConversionPlanDto {
     pub tasks: Vec<ConversionTaskDto>,
     #[serde(default)]
     pub integrity_alerts: Vec<FileIntegrityAlertDto>,
+    /// XLIFF inputs left out of the plan because they failed validation.
+    #[serde(default)]
+    pub validation_failures: Vec<XliffValidationFailureDto>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct XliffValidationFailureDto {
+    pub file_uuid: String,
+    pub file_name: String,
+    pub diagnostics: Vec<crate::jliff::xliff_validation::XliffDiagnostic>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct XliffValidationDto {
+    pub path: String,
+    /// Whether the file has no errors; warnings do not count.
+    pub passed: bool,
+    pub report: crate::jliff::xliff_validation::XliffValidationReport,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -349,6 +552,9 @@ pub struct EnsureConversionPlanPayload {
     pub project_uuid: String,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub file_uuids: Option<Vec<String>>,
+    /// Validates XLIFF inputs first and plans no task for those with errors.
+    #[serde(default)]
+    pub validate_xliff: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -413,12 +619,18 @@ pub struct CreateProjectWithAssetsPayload {
     pub r#type: String,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub notes: Option<String>,
+    /// Deadline as a date or RFC 3339 timestamp.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub due_date: Option<String>,
     #[serde(default)]
     pub subjects: Vec<String>,
     #[serde(default)]
     pub language_pairs: Vec<ProjectLanguagePairDto>,
     #[serde(default)]
     pub assets: Vec<ProjectAssetDescriptorDto>,
+    /// Pipeline preset of the project; the default of its type when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pipeline_preset_uuid: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -432,6 +644,89 @@ pub struct CreateProjectWithAssetsResponseDto {
     pub conversion_plan: Option<ConversionPlanDto>,
 }
 
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmailIntakePayload {
+    /// Absolute path of the dropped `.eml` or `.msg` file.
+    pub input_path: String,
+    /// Owner of the drafted project.
+    pub user_uuid: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmailIntakeDraftDto {
+    /// Project prefilled from the email, to review and pass to
+    /// `create_project_with_assets_v2`. Assets point into `staging_dir`.
+    pub draft: CreateProjectWithAssetsPayload,
+    pub staging_dir: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sender_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sender_address: Option<String>,
+    /// Clients the sender may belong to, best match first; the draft uses the first one
+    /// when it is the only exact match.
+    pub client_matches: Vec<IntakeClientMatchDto>,
+    /// Body of the email, also staged as an instructions asset.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instructions: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CatPackageIntakePayload {
+    /// Absolute path of the Trados `.sdlppx` or memoQ `.mqout` package.
+    pub input_path: String,
+    /// Owner of the drafted project.
+    pub user_uuid: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CatPackageDraftDto {
+    /// Project prefilled from the package, to review and pass to
+    /// `create_project_with_assets_v2`. Assets point into `staging_dir`.
+    pub draft: CreateProjectWithAssetsPayload,
+    pub staging_dir: String,
+    /// `trados` or `memoq`.
+    pub package_kind: String,
+    /// What the package did not say and the draft filled in from the settings.
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StageDroppedFilesPayload {
+    /// Absolute paths of the files dropped on the project wizard.
+    pub paths: Vec<String>,
+    /// Roles picked by hand, keyed by path; they take precedence over the rules.
+    #[serde(default)]
+    pub role_overrides: BTreeMap<String, ProjectAssetRoleDto>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StagedAssetDto {
+    #[serde(flatten)]
+    pub asset: ProjectAssetDescriptorDto,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mime: Option<String>,
+    /// `override`, `rule` or `extension`: what decided the role.
+    pub role_source: String,
+    /// Position of the classification rule that assigned the role.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub matched_rule: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntakeClientMatchDto {
+    pub client_uuid: String,
+    pub name: String,
+    /// `email` for the sender's own address, `domain` for a colleague at the same domain.
+    pub matched_by: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateProjectPayload {
@@ -448,10 +743,30 @@ pub struct UpdateProjectPayload {
     pub r#type: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub notes: Option<Option<String>>,
+    /// `YYYY-MM-DD` or an RFC 3339 date and time; `null` clears it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub due_date: Option<Option<String>>,
+    /// Keeps the project's text away from external MT providers.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub no_external_mt: Option<bool>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub subjects: Option<Vec<String>>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub language_pairs: Option<Vec<ProjectLanguagePairDto>>,
+    /// Delivers the project despite open critical QA findings or empty targets.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delivery_override: Option<DeliveryOverridePayload>,
+    /// Delivery package sent with a transition to `delivered`; defaults to the newest one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delivery_package_uuid: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeliveryOverridePayload {
+    /// User authorizing the override; needs the `delivery.override` permission.
+    pub user_uuid: String,
+    pub reason: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -471,9 +786,30 @@ pub struct ProjectRecordV2Dto {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub notes: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub project_number: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub subjects: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub file_count: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub due_date: Option<String>,
+    /// Only local providers may translate the project.
+    #[serde(default)]
+    pub no_external_mt: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub health: Option<ProjectHealthDto>,
+}
+
+/// Health of a project: a 0–100 score, its level and the risks that lowered it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectHealthDto {
+    /// `healthy`, `at_risk` or `critical`.
+    pub level: String,
+    pub score: i64,
+    /// `failed_jobs`, `stale`, `deadline_at_risk`, `overdue` or `qa_criticals`.
+    pub flags: Vec<String>,
+    pub evaluated_at: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -527,6 +863,117 @@ pub struct JobV2Dto {
     pub job_status: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error_log: Option<String>,
+    /// Jobs that must succeed before this one starts.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub depends_on: Vec<JobRefDto>,
+    #[serde(default)]
+    pub processed_units: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total_units: Option<i64>,
+    /// Rolling throughput over the last reports.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub units_per_second: Option<f64>,
+    /// Estimated seconds left; absent until a total and a throughput are known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub eta_seconds: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub progress_updated_at: Option<String>,
+}
+
+/// Identifies a job by its artifact and type.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobRefDto {
+    pub artifact_uuid: String,
+    pub job_type: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunPipelinePayload {
+    pub project_uuid: String,
+    pub source_lang: String,
+    pub target_lang: String,
+    /// Restricts the run to these files; every processable file of the pair by default.
+    #[serde(default)]
+    pub file_uuids: Option<Vec<String>>,
+    /// Preset to run; the project's preset (or its type's default) when unset.
+    #[serde(default)]
+    pub preset_uuid: Option<String>,
+    /// Ad-hoc stages among `convert`, `translate`, `qa` and `merge`, always run in that order
+    /// with default options; overrides the preset.
+    #[serde(default)]
+    pub stages: Option<Vec<String>>,
+    /// Lowest translation memory match accepted by the `translate` stage; overrides the
+    /// preset's threshold.
+    #[serde(default)]
+    pub min_match: Option<u8>,
+    #[serde(default)]
+    pub operator: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PipelineFileDto {
+    pub file_uuid: String,
+    pub file_name: String,
+    pub artifact_uuid: String,
+    pub xliff_rel_path: String,
+    pub jliff_rel_path: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PipelineRunDto {
+    pub project_uuid: String,
+    pub source_lang: String,
+    pub target_lang: String,
+    /// Preset the stages come from; absent for ad-hoc stages and the built-in full pipeline.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preset_uuid: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preset_name: Option<String>,
+    pub stages: Vec<String>,
+    pub files: Vec<PipelineFileDto>,
+    /// The queued jobs; their progress shows up in `list_jobs_for_project_v2`.
+    pub jobs: Vec<JobV2Dto>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PipelinePresetDto {
+    pub preset_uuid: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Project type (`translation`, `rag`) the preset is the default of.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project_type: Option<String>,
+    pub definition: crate::jobs::pipeline::PipelineDefinition,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SavePipelinePresetPayload {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Makes the preset the default of this project type, replacing the previous default.
+    #[serde(default)]
+    pub project_type: Option<String>,
+    pub definition: crate::jobs::pipeline::PipelineDefinition,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssignPipelinePresetPayload {
+    pub project_uuid: String,
+    /// Preset to assign; `null` falls back to the project type's default.
+    #[serde(default)]
+    pub preset_uuid: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -673,6 +1120,10 @@ pub struct UpsertJobPayload {
     pub job_status: String,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub error_log: Option<String>,
+    /// MT/LLM provider the job sends text to; such jobs are paused while the provider's
+    /// monthly budget is exhausted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -683,6 +1134,43 @@ pub struct UpdateJobStatusPayload {
     pub job_status: String,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub error_log: Option<String>,
+    /// MT/LLM provider the job sends text to; such jobs are paused while the provider's
+    /// monthly budget is exhausted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListDomainEventsPayload {
+    /// Returns events after this id; from the oldest kept event when absent.
+    #[serde(default)]
+    pub after_event_id: Option<i64>,
+    #[serde(default)]
+    pub limit: Option<i64>,
+}
+
+/// A domain event from the outbox, as published under `topic`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DomainEventDto {
+    pub event_id: i64,
+    pub topic: String,
+    pub payload: serde_json::Value,
+    pub created_at: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delivered_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateJobProgressPayload {
+    pub artifact_uuid: String,
+    pub job_type: String,
+    pub processed_units: i64,
+    /// Total units of work; the stored total is kept when absent.
+    #[serde(default)]
+    pub total_units: Option<i64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -699,6 +1187,7 @@ pub struct AppHealthReport {
     pub app_version: String,
     pub tauri_version: String,
     pub build_profile: String,
+    pub connectivity: OfflineStatusDto,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -794,6 +1283,36 @@ pub struct AppSettingsDto {
     pub show_notifications: bool,
     pub enable_sound_notifications: bool,
     pub max_parallel_conversions: u32,
+    pub auto_propagate_repetitions: bool,
+    pub propagation_scope: String,
+    pub propagation_case_sensitive: bool,
+    pub snapshot_interval_minutes: u32,
+    pub snapshot_edit_threshold: u32,
+    pub snapshot_retention: u32,
+    pub length_limit_enforcement: String,
+    pub provider_cache_ttl_hours: u32,
+    pub local_model_base_url: String,
+    pub local_model_name: String,
+    pub mock_provider_enabled: bool,
+    pub mock_provider_latency_ms: u32,
+    pub mock_provider_failure_rate: u32,
+    pub normalize_unicode_nfc: bool,
+    pub event_webhook_url: Option<String>,
+    pub vies_lookup: bool,
+    pub base_currency: String,
+    pub project_number_template: String,
+    pub asset_role_rules: Vec<RoleRule>,
+    pub ocr_command: Option<String>,
+    pub plugin_wasm_runtime: Option<String>,
+    pub allow_native_plugins: bool,
+    pub database_encryption: bool,
+    pub auto_lock_minutes: Option<u32>,
+    pub xliff_name_template: String,
+    pub jliff_name_template: String,
+    pub proxy_url: Option<String>,
+    pub proxy_username: Option<String>,
+    pub no_proxy: Vec<String>,
+    pub crash_report_upload: bool,
     pub database_journal_mode: String,
     pub database_synchronous: String,
 }
@@ -863,3 +1382,3251 @@ pub struct ProjectDetailsDto {
     pub root_path: String,
     pub files: Vec<ProjectFileWithConversionsDto>,
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QaFindingDto {
+    pub finding_uuid: String,
+    pub project_uuid: String,
+    pub jliff_rel_path: String,
+    pub transunit_id: String,
+    pub check_type: String,
+    pub severity: String,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<serde_json::Value>,
+    pub created_at: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resolved_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListQaFindingsPayload {
+    pub project_uuid: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jliff_rel_path: Option<String>,
+    #[serde(default)]
+    pub include_resolved: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpellcheckSegmentPayload {
+    pub project_uuid: String,
+    pub jliff_rel_path: String,
+    pub transunit_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allow_download: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub persist_findings: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpellcheckDocumentPayload {
+    pub project_uuid: String,
+    pub jliff_rel_path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allow_download: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub persist_findings: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SegmentSpellcheckDto {
+    pub transunit_id: String,
+    pub misspellings: Vec<crate::spellcheck::Misspelling>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentSpellcheckDto {
+    pub jliff_rel_path: String,
+    pub language: String,
+    pub segments: Vec<SegmentSpellcheckDto>,
+    pub total_misspellings: usize,
+    pub findings_recorded: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProtectedTermDto {
+    pub term_uuid: String,
+    pub project_uuid: String,
+    pub term: String,
+    pub case_sensitive: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProtectedTermInput {
+    pub term: String,
+    #[serde(default = "default_true")]
+    pub case_sensitive: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplaceProtectedTermsPayload {
+    pub project_uuid: String,
+    pub terms: Vec<ProtectedTermInput>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckProtectedTermsPayload {
+    pub project_uuid: String,
+    pub jliff_rel_path: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProtectedTermsQaDto {
+    pub jliff_rel_path: String,
+    pub segments_checked: usize,
+    pub findings: Vec<QaFindingDto>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckLengthLimitsPayload {
+    pub project_uuid: String,
+    pub jliff_rel_path: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LengthLimitsQaDto {
+    pub jliff_rel_path: String,
+    /// Segments that carry a maximum length and have a target.
+    pub segments_checked: usize,
+    pub findings: Vec<QaFindingDto>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportLengthLimitsPayload {
+    pub project_uuid: String,
+    pub jliff_rel_path: String,
+    /// Absolute path of the CSV file.
+    pub input_path: String,
+    /// Header of the column holding unit or segment ids; defaults to `id`.
+    #[serde(default)]
+    pub id_column: Option<String>,
+    /// Header of the column holding the limits; defaults to `max_length`.
+    #[serde(default)]
+    pub max_length_column: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LengthLimitsImportDto {
+    pub jliff_rel_path: String,
+    pub updated_segments: usize,
+    pub skipped_rows: usize,
+    /// Ids of the CSV that match no unit or segment of the document.
+    pub unknown_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetFileLengthLimitPayload {
+    pub project_uuid: String,
+    pub jliff_rel_path: String,
+    /// Limit for every segment of the file; `null` with `overwrite` removes the limits.
+    #[serde(default)]
+    pub max_length: Option<usize>,
+    /// Also replace limits that segments already carry.
+    #[serde(default)]
+    pub overwrite: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileLengthLimitDto {
+    pub jliff_rel_path: String,
+    pub updated_segments: usize,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckBidiControlsPayload {
+    pub project_uuid: String,
+    pub jliff_rel_path: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BidiControlsQaDto {
+    pub jliff_rel_path: String,
+    /// `ltr` or `rtl`, from the document's target language.
+    pub target_direction: String,
+    pub segments_checked: usize,
+    pub findings: Vec<QaFindingDto>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SanitizeBidiControlsPayload {
+    pub project_uuid: String,
+    pub jliff_rel_path: String,
+    /// Segments to clean; every segment of the document when omitted.
+    #[serde(default)]
+    pub transunit_ids: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BidiSanitizeDto {
+    pub jliff_rel_path: String,
+    pub updated_segments: usize,
+    pub removed_controls: usize,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckUnicodeTextPayload {
+    pub project_uuid: String,
+    pub jliff_rel_path: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnicodeTextQaDto {
+    pub jliff_rel_path: String,
+    pub segments_checked: usize,
+    pub findings: Vec<QaFindingDto>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FixUnicodeTextPayload {
+    pub project_uuid: String,
+    /// Documents to fix; every document of the project when absent.
+    #[serde(default)]
+    pub jliff_rel_paths: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnicodeFixFileDto {
+    pub jliff_rel_path: String,
+    pub updated_segments: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnicodeFixDto {
+    pub updated_segments: usize,
+    /// Documents with at least one fixed target.
+    pub files: Vec<UnicodeFixFileDto>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateSegmentStatusPayload {
+    pub project_uuid: String,
+    pub jliff_rel_path: String,
+    pub transunit_ids: Vec<String>,
+    /// One of `new`, `draft`, `translated`, `reviewed`, `approved`.
+    pub status: String,
+    #[serde(default)]
+    pub sub_state: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SegmentStatusUpdateDto {
+    pub updated: usize,
+    pub missing_transunit_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncJliffToXliffPayload {
+    pub project_uuid: String,
+    pub jliff_rel_path: String,
+    /// XLIFF the JLIFF was converted from; rewritten in place before merging.
+    pub xliff_rel_path: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct XliffSyncDto {
+    pub xliff_rel_path: String,
+    pub updated_segments: usize,
+    /// Units whose `<notes>` were rewritten.
+    pub updated_units: usize,
+    /// Units whose segments were rebuilt after being joined or split.
+    pub restructured_units: usize,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileStatisticsPayload {
+    pub project_uuid: String,
+    pub jliff_rel_path: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileStatisticsDto {
+    pub jliff_rel_path: String,
+    pub segment_count: usize,
+    /// Segments per status; every status is listed, including empty ones.
+    pub status_distribution: BTreeMap<String, usize>,
+    /// Segments per `subState` value for segments that carry one.
+    pub sub_state_distribution: BTreeMap<String, usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProtectionRuleDto {
+    pub rule_uuid: String,
+    pub project_uuid: String,
+    pub name: String,
+    pub pattern: String,
+    pub is_enabled: bool,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateProtectionRulePayload {
+    pub project_uuid: String,
+    pub name: String,
+    pub pattern: String,
+    #[serde(default = "default_true")]
+    pub is_enabled: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateProtectionRulePayload {
+    pub rule_uuid: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub is_enabled: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProtectionRuleInput {
+    pub name: String,
+    pub pattern: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreviewProtectionRulesPayload {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project_uuid: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rules: Option<Vec<ProtectionRuleInput>>,
+    pub sample_text: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProtectionMatchDto {
+    /// `"term"` or `"rule"`.
+    pub kind: String,
+    pub name: String,
+    pub placeholder: String,
+    pub text: String,
+    /// Character offsets into the sample text.
+    pub start: usize,
+    pub end: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProtectionPreviewDto {
+    pub protected_text: String,
+    pub matches: Vec<ProtectionMatchDto>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SegmentationExceptionDto {
+    pub exception_uuid: String,
+    /// `None` for global exceptions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project_uuid: Option<String>,
+    pub language: String,
+    /// `"abbreviation"`, `"no_break"` or `"break"`.
+    pub kind: String,
+    /// The abbreviation, or the pattern matching the text before the break.
+    pub before_text: String,
+    /// Pattern matching the text after the break; empty for abbreviations.
+    pub after_text: String,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateSegmentationExceptionPayload {
+    /// Omit to create a global exception.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project_uuid: Option<String>,
+    pub language: String,
+    pub kind: String,
+    pub before_text: String,
+    #[serde(default)]
+    pub after_text: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreviewSegmentationPayload {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project_uuid: Option<String>,
+    pub language: String,
+    pub sample_text: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SegmentationPreviewDto {
+    pub sentences: Vec<String>,
+    /// Number of project and global exceptions applied.
+    pub exception_count: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SplitSegmentPayload {
+    pub project_uuid: String,
+    pub jliff_rel_path: String,
+    pub transunit_id: String,
+    /// Characters of the source, placeholders included, that stay in the first segment.
+    pub offset: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeSegmentsPayload {
+    pub project_uuid: String,
+    pub jliff_rel_path: String,
+    /// Consecutive segments of one paragraph; they are joined into the first.
+    pub transunit_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SegmentStructureDto {
+    pub jliff_rel_path: String,
+    /// The segment split off by a split, or the segment kept by a merge.
+    pub transunit_id: String,
+    /// Segments of the paragraph after the change, in order.
+    pub unit_transunit_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepairSegmentTagsPayload {
+    pub project_uuid: String,
+    pub jliff_rel_path: String,
+    pub transunit_id: String,
+    /// When `true`, the repair is only reported and the document is left untouched.
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SegmentTagRepairDto {
+    pub transunit_id: String,
+    pub original_text: String,
+    pub applied: bool,
+    #[serde(flatten)]
+    pub report: crate::jliff::tag_repair::TagRepairReport,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlignSegmentPayload {
+    pub project_uuid: String,
+    pub jliff_rel_path: String,
+    pub transunit_id: String,
+    /// Target to align, such as a machine translation not saved yet; the segment's current
+    /// target when omitted.
+    #[serde(default)]
+    pub target: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SegmentAlignmentDto {
+    pub transunit_id: String,
+    #[serde(flatten)]
+    pub alignment: crate::jliff::alignment::SegmentAlignment,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SegmentPreviewPayload {
+    pub project_uuid: String,
+    pub jliff_rel_path: String,
+    pub transunit_id: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SegmentPreviewDto {
+    pub transunit_id: String,
+    pub source_html: String,
+    pub target_html: String,
+    /// Placeholders that could not be resolved through the tag map and are shown as chips.
+    pub unresolved_placeholders: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportIncontextPreviewPayload {
+    pub project_uuid: String,
+    pub jliff_rel_path: String,
+    /// Language pair the document is expected to have.
+    pub source_lang: String,
+    pub target_lang: String,
+    /// Absolute destination chosen by the user.
+    pub output_path: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IncontextPreviewExportDto {
+    pub output_path: String,
+    pub segment_count: usize,
+    /// Segments shown with their source because they have no target yet.
+    pub untranslated_segments: usize,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetTagMapEntriesPayload {
+    pub project_uuid: String,
+    pub jliff_rel_path: String,
+    pub transunit_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagPlaceholderDto {
+    pub placeholder: String,
+    pub elem: String,
+    pub id: Option<String>,
+    pub attrs: BTreeMap<String, Option<String>>,
+    pub original_data: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagMapEntryDto {
+    pub transunit_id: String,
+    pub placeholders: Vec<TagPlaceholderDto>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagMapEntriesDto {
+    pub entries: Vec<TagMapEntryDto>,
+    /// Requested transunit ids with no segment in the tag map.
+    pub missing_transunit_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetSegmentsPayload {
+    pub project_uuid: String,
+    pub jliff_rel_path: String,
+    /// Restricts the page to these transunits; all transunits when omitted.
+    #[serde(default)]
+    pub transunit_ids: Option<Vec<String>>,
+    #[serde(default)]
+    pub offset: Option<usize>,
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SegmentNoteDto {
+    pub id: Option<String>,
+    pub category: Option<String>,
+    pub priority: Option<u8>,
+    pub applies_to: Option<String>,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SegmentDto {
+    pub transunit_id: String,
+    pub unit_id: String,
+    pub source: String,
+    /// Latest target (post-edit, QA, then translation).
+    pub target: String,
+    pub status: String,
+    pub sub_state: Option<String>,
+    pub match_quality: Option<f64>,
+    pub match_origin: Option<String>,
+    pub max_length: Option<usize>,
+    pub notes: Vec<SegmentNoteDto>,
+    /// Set when the target was copied from a confirmed repetition.
+    pub propagated_from: Option<PropagationSourceDto>,
+    /// Reference assets linked to the segment.
+    pub reference_count: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PropagationSourceDto {
+    pub jliff_rel_path: String,
+    pub transunit_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateSegmentReferencePayload {
+    pub project_uuid: String,
+    pub jliff_rel_path: String,
+    pub transunit_id: String,
+    /// Project file to link; processable files cannot be references.
+    pub file_uuid: String,
+    /// Part of the asset the segment refers to, e.g. `p. 12` or a section heading.
+    #[serde(default)]
+    pub anchor: Option<String>,
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListSegmentReferencesPayload {
+    pub project_uuid: String,
+    pub jliff_rel_path: String,
+    /// Limits the list to one segment; the whole document otherwise.
+    #[serde(default)]
+    pub transunit_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateSegmentReferencePayload {
+    pub reference_uuid: String,
+    #[serde(default)]
+    pub anchor: Option<String>,
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SegmentReferenceDto {
+    pub reference_uuid: String,
+    pub jliff_rel_path: String,
+    pub transunit_id: String,
+    pub file_uuid: String,
+    pub filename: String,
+    /// Path of the asset relative to the project folder.
+    pub stored_at: String,
+    /// Role of the asset, e.g. `reference` or `image`.
+    pub role: String,
+    pub anchor: Option<String>,
+    pub note: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SegmentPageDto {
+    /// Segments matching the request before paging.
+    pub total: usize,
+    pub segments: Vec<SegmentDto>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewBundleExportDto {
+    pub path: String,
+    pub documents: usize,
+    pub size_bytes: u64,
+}
+
+/// The review bundle open in guest mode.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewBundleDto {
+    pub bundle_path: String,
+    #[serde(flatten)]
+    pub manifest: ReviewBundleManifest,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetReviewBundleSegmentsPayload {
+    pub jliff_rel_path: String,
+    #[serde(default)]
+    pub offset: Option<usize>,
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportExternalReviewPayload {
+    pub project_uuid: String,
+    pub jliff_rel_path: String,
+    /// Review file format; only `rtf` is supported.
+    pub format: String,
+    /// Absolute destination chosen by the user.
+    pub output_path: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExternalReviewExportDto {
+    pub output_path: String,
+    pub segment_count: usize,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportExternalReviewPayload {
+    pub project_uuid: String,
+    pub jliff_rel_path: String,
+    /// Absolute path of the review file returned by the reviewer.
+    pub input_path: String,
+    #[serde(default)]
+    pub reviewer: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExternalReviewConflictDto {
+    pub transunit_id: String,
+    /// `changed_since_export` or `placeholders_changed`.
+    pub reason: String,
+    pub current_target: String,
+    pub reviewer_target: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExternalReviewImportDto {
+    pub applied: usize,
+    pub unchanged: usize,
+    pub conflicts: Vec<ExternalReviewConflictDto>,
+    /// Rows whose segment id is not in the document.
+    pub unknown_transunit_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SplitDocumentPayload {
+    pub project_uuid: String,
+    pub jliff_rel_path: String,
+    /// Number of parts; mutually exclusive with `words_per_part`.
+    #[serde(default)]
+    pub parts: Option<usize>,
+    #[serde(default)]
+    pub words_per_part: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SplitPartDto {
+    pub index: usize,
+    pub jliff_rel_path: String,
+    pub word_count: usize,
+    pub transunit_count: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SplitDocumentDto {
+    pub manifest_rel_path: String,
+    pub parts: Vec<SplitPartDto>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeSplitDocumentsPayload {
+    pub project_uuid: String,
+    pub manifest_rel_path: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeSplitDocumentsDto {
+    pub jliff_rel_path: String,
+    pub merged_parts: usize,
+    pub transunit_count: usize,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfirmSegmentPayload {
+    pub project_uuid: String,
+    pub jliff_rel_path: String,
+    pub transunit_id: String,
+    pub target: String,
+    /// Confirmed status to set (`translated`, `reviewed` or `approved`); defaults to
+    /// `translated`.
+    #[serde(default)]
+    pub status: Option<String>,
+    /// Overrides the auto-propagation setting for this confirmation.
+    #[serde(default)]
+    pub propagate: Option<bool>,
+    /// Confirming linguist, credited in the productivity report.
+    #[serde(default)]
+    pub user_uuid: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfirmSegmentDto {
+    pub transunit_id: String,
+    pub status: String,
+    /// Stored target, after the auto-fixes enabled in the QA profile.
+    pub target: String,
+    /// Check types of the auto-fixes that changed the target.
+    pub auto_fixes: Vec<String>,
+    /// Set when the target exceeds its length limit and enforcement only warns.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub length_limit: Option<crate::jliff::length_limits::LengthLimitIssue>,
+    /// Number of repetitions that received the confirmed target.
+    pub propagated: usize,
+    pub propagated_files: Vec<PropagationFileDto>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PropagateRepetitionsPayload {
+    pub project_uuid: String,
+    /// Document whose confirmed segments are propagated.
+    pub jliff_rel_path: String,
+    /// `file` or `project`; defaults to the app setting.
+    #[serde(default)]
+    pub scope: Option<String>,
+    #[serde(default)]
+    pub case_sensitive: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PropagationFileDto {
+    pub jliff_rel_path: String,
+    pub propagated: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PropagationResultDto {
+    pub scope: String,
+    pub propagated: usize,
+    pub files: Vec<PropagationFileDto>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FindReplaceTargetsPayload {
+    pub project_uuid: String,
+    /// `file` (needs `jliff_rel_path`) or `project`.
+    pub scope: String,
+    #[serde(default)]
+    pub jliff_rel_path: Option<String>,
+    pub pattern: String,
+    pub replacement: String,
+    /// Treat `pattern` as a regular expression; `replacement` may then use `$1`.
+    #[serde(default)]
+    pub regex: bool,
+    #[serde(default)]
+    pub case_sensitive: Option<bool>,
+    /// Report the changes without writing them.
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FindReplaceChangeDto {
+    pub transunit_id: String,
+    pub previous_target: String,
+    pub new_target: String,
+    pub matches: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FindReplaceFileDto {
+    pub jliff_rel_path: String,
+    pub changes: Vec<FindReplaceChangeDto>,
+    /// Segments left untouched because the replacement would alter their placeholders.
+    pub skipped_transunit_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FindReplaceResultDto {
+    pub dry_run: bool,
+    pub replaced_segments: usize,
+    pub replaced_matches: usize,
+    /// Documents with changes or skipped segments.
+    pub files: Vec<FindReplaceFileDto>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PretranslateProjectPayload {
+    pub project_uuid: String,
+    pub source_lang: String,
+    pub target_lang: String,
+    /// Lowest match percentage applied to segments (50-100).
+    pub min_match: u8,
+    /// `empty_only` (default), `unconfirmed` or `all`.
+    #[serde(default)]
+    pub overwrite_policy: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MatchBreakdownDto {
+    pub exact_segments: usize,
+    pub exact_words: usize,
+    pub fuzzy_segments: usize,
+    pub fuzzy_words: usize,
+    pub no_match_segments: usize,
+    pub no_match_words: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PretranslationFileDto {
+    pub jliff_rel_path: String,
+    pub filled_segments: usize,
+    pub breakdown: MatchBreakdownDto,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PretranslationDto {
+    pub source_lang: String,
+    pub target_lang: String,
+    pub min_match: u8,
+    pub overwrite_policy: String,
+    pub filled_segments: usize,
+    /// Totals over every file; the basis for quoting.
+    pub breakdown: MatchBreakdownDto,
+    pub files: Vec<PretranslationFileDto>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnalyzeProjectPayload {
+    pub project_uuid: String,
+    pub source_lang: String,
+    pub target_lang: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LeverageBandDto {
+    /// `100`, `repetition`, `95-99`, `85-94`, `75-84`, `50-74` or `new`.
+    pub band: String,
+    pub segments: usize,
+    pub words: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LeverageFileDto {
+    pub jliff_rel_path: String,
+    pub bands: Vec<LeverageBandDto>,
+    pub total_segments: usize,
+    pub total_words: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LeverageAnalysisDto {
+    pub project_uuid: String,
+    pub source_lang: String,
+    pub target_lang: String,
+    pub created_at: String,
+    /// Project-relative path of the JSON report written for this analysis.
+    pub report_rel_path: String,
+    /// Totals over every file; the basis for quoting.
+    pub bands: Vec<LeverageBandDto>,
+    pub total_segments: usize,
+    pub total_words: usize,
+    pub files: Vec<LeverageFileDto>,
+    /// Memories the matches were drawn from, with their penalties; empty when the project
+    /// uses every entry of the pair without penalty.
+    pub tm_assignments: Vec<ProjectTmAssignmentDto>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConcordanceSearchPayload {
+    pub query: String,
+    /// `tm`, `project` or `all` (default). Project results need `project_uuid`.
+    #[serde(default)]
+    pub scope: Option<String>,
+    #[serde(default)]
+    pub project_uuid: Option<String>,
+    pub source_lang: String,
+    pub target_lang: String,
+    /// Search `source` (default), `target` or `both` texts.
+    #[serde(default)]
+    pub search_in: Option<String>,
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConcordanceHitDto {
+    /// `tm` or `project`.
+    pub origin: String,
+    pub source: String,
+    pub target: String,
+    /// `source` or `target`: the text the offsets refer to.
+    pub matched_in: String,
+    /// Character offsets of the occurrence.
+    pub match_start: usize,
+    pub match_end: usize,
+    pub score: u8,
+    /// Penalty of the hit's memory, already taken off `score`, for TM hits.
+    pub penalty: Option<u8>,
+    pub jliff_rel_path: Option<String>,
+    pub transunit_id: Option<String>,
+    /// Sources of the neighbouring segments, for project hits.
+    pub context_before: Option<String>,
+    pub context_after: Option<String>,
+    /// Last update of the TM entry, for TM hits.
+    pub updated_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConcordanceResultDto {
+    pub total: usize,
+    pub hits: Vec<ConcordanceHitDto>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlignDocumentsPayload {
+    /// Absolute path of the original document, as UTF-8 plain text.
+    pub source_path: String,
+    /// Absolute path of its translation, as UTF-8 plain text.
+    pub target_path: String,
+    pub pair: ProjectLanguagePairDto,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlignmentPairDto {
+    pub position: i64,
+    /// Empty when a target sentence has no counterpart in the source.
+    pub source_text: String,
+    /// Empty when a source sentence has no counterpart in the target.
+    pub target_text: String,
+    /// Plausibility of the pair from its lengths, 0 to 1.
+    pub score: f64,
+    /// `pending`, `accepted` or `rejected`.
+    pub status: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentAlignmentDto {
+    pub alignment_uuid: String,
+    pub source_lang: String,
+    pub target_lang: String,
+    pub source_name: String,
+    pub target_name: String,
+    pub created_at: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub committed_at: Option<String>,
+    /// Omitted when alignments are listed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pairs: Option<Vec<AlignmentPairDto>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlignmentPairUpdateDto {
+    pub position: i64,
+    #[serde(default)]
+    pub status: Option<String>,
+    #[serde(default)]
+    pub source_text: Option<String>,
+    #[serde(default)]
+    pub target_text: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewAlignmentPayload {
+    pub alignment_uuid: String,
+    #[serde(default)]
+    pub updates: Vec<AlignmentPairUpdateDto>,
+    /// Accepts every pending pair scoring at least this much.
+    #[serde(default)]
+    pub accept_min_score: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommitAlignmentDto {
+    pub alignment: DocumentAlignmentDto,
+    /// Accepted pairs written to the translation memory.
+    pub committed_entries: usize,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TermExtractionOptionsDto {
+    /// Longest candidate in words (default 3).
+    #[serde(default)]
+    pub max_words: Option<usize>,
+    /// Fewest occurrences for a candidate (default 2).
+    #[serde(default)]
+    pub min_frequency: Option<usize>,
+    /// Most candidates proposed (default 200).
+    #[serde(default)]
+    pub limit: Option<usize>,
+    /// Proposes target equivalents from confirmed translations.
+    #[serde(default)]
+    pub pair_targets: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtractTermsPayload {
+    pub project_uuid: String,
+    pub pair: ProjectLanguagePairDto,
+    #[serde(default)]
+    pub options: TermExtractionOptionsDto,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TermCandidateDto {
+    pub candidate_uuid: String,
+    pub source_lang: String,
+    pub target_lang: String,
+    pub term: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_term: Option<String>,
+    pub frequency: i64,
+    pub score: f64,
+    /// `pending`, `accepted` or `rejected`.
+    pub status: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TermExtractionDto {
+    /// Segments whose sources were scanned.
+    pub segments: usize,
+    /// Segments with a confirmed translation used to propose targets.
+    pub confirmed_segments: usize,
+    /// Pending candidates of the pair after the run.
+    pub candidates: Vec<TermCandidateDto>,
+    /// Candidates left out because they were already accepted or rejected.
+    pub skipped_decided: usize,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TermCandidateDecisionDto {
+    pub candidate_uuid: String,
+    /// `accepted`, `rejected` or `pending`.
+    pub status: String,
+    /// Replaces the proposed target term.
+    #[serde(default)]
+    pub target_term: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewTermCandidatesPayload {
+    pub project_uuid: String,
+    pub decisions: Vec<TermCandidateDecisionDto>,
+    /// Termbase accepted candidates are added to; defaults to `project`.
+    #[serde(default)]
+    pub termbase: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TermbaseEntryDto {
+    pub entry_uuid: String,
+    pub termbase: String,
+    pub source_lang: String,
+    pub target_lang: String,
+    pub source_term: String,
+    /// Empty for terms without a recorded equivalent.
+    pub target_term: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project_uuid: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TermReviewDto {
+    pub candidates: Vec<TermCandidateDto>,
+    /// Termbase entries created by accepted candidates.
+    pub added_entries: Vec<TermbaseEntryDto>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SegmentTermsPayload {
+    pub project_uuid: String,
+    pub jliff_rel_path: String,
+    pub transunit_id: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SegmentTermHitDto {
+    pub entry: TermbaseEntryDto,
+    /// Byte offsets of the matched (possibly inflected) words in the source.
+    pub start: usize,
+    pub end: usize,
+    pub matched_text: String,
+    /// Whether the target uses the entry's target term; omitted for entries without one or
+    /// untranslated segments.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_found: Option<bool>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LanguageDirectionDto {
+    Consistent,
+    Reversed,
+    Unexpected,
+    Undetermined,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LanguageDirectionCheckDto {
+    pub source_lang: String,
+    pub target_lang: String,
+    /// Source segments the detection ran on.
+    pub sampled_segments: usize,
+    /// Primary language subtag detected in the sample, such as `de`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detected_language: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f64>,
+    pub direction: LanguageDirectionDto,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SwapLanguagePairPayload {
+    pub project_uuid: String,
+    /// The pair as it is now; it becomes `targetLang → sourceLang`.
+    pub pair: ProjectLanguagePairDto,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectTmAssignmentDto {
+    /// Origin shared by the memory's entries, such as `editor` or `reversed`.
+    pub tm_origin: String,
+    /// Lower values are consulted first and win ties.
+    #[serde(default)]
+    pub priority: i64,
+    /// Percentage points taken off every match from this memory.
+    #[serde(default)]
+    pub penalty: u8,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplaceProjectTmAssignmentsPayload {
+    pub project_uuid: String,
+    pub assignments: Vec<ProjectTmAssignmentDto>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TmPairPayload {
+    pub source_lang: String,
+    pub target_lang: String,
+    /// When `true`, the changes are only reported and the memory is left untouched.
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TmDuplicateGroupDto {
+    pub kept_entry_uuid: String,
+    pub removed_entry_uuids: Vec<String>,
+    pub source_text: String,
+    pub target_text: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TmDeduplicationDto {
+    pub applied: bool,
+    pub removed_entries: usize,
+    pub groups: Vec<TmDuplicateGroupDto>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TmTargetVariantDto {
+    pub target_text: String,
+    pub entry_uuids: Vec<String>,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TmInconsistencyDto {
+    pub source_text: String,
+    /// Most recently updated translation first.
+    pub variants: Vec<TmTargetVariantDto>,
+}
+
+/// Selects translation memory entries; every field that is set must match.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TmEntryFilterDto {
+    #[serde(default)]
+    pub source_contains: Option<String>,
+    #[serde(default)]
+    pub target_contains: Option<String>,
+    #[serde(default)]
+    pub origin: Option<String>,
+    #[serde(default)]
+    pub project_uuid: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchEditTmPayload {
+    pub source_lang: String,
+    pub target_lang: String,
+    #[serde(default)]
+    pub filter: TmEntryFilterDto,
+    /// `replace` or `delete`.
+    pub action: String,
+    /// Text to replace; required by `replace`.
+    #[serde(default)]
+    pub find: Option<String>,
+    #[serde(default)]
+    pub replace: Option<String>,
+    /// Texts rewritten by `replace`: `source`, `target` (default) or `both`.
+    #[serde(default)]
+    pub field: Option<String>,
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TmEntryChangeDto {
+    pub entry_uuid: String,
+    pub source_text: String,
+    pub target_text: String,
+    /// New texts; absent for deleted entries.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new_source_text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new_target_text: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TmBatchEditDto {
+    pub applied: bool,
+    pub changes: Vec<TmEntryChangeDto>,
+    /// Edited entries that became identical to another entry and were merged into it.
+    pub merged_entries: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TmReversalDto {
+    pub applied: bool,
+    /// The language pair the reversed entries belong to.
+    pub source_lang: String,
+    pub target_lang: String,
+    pub entries: usize,
+    /// Reversed pairs the opposite direction already held; they are only refreshed.
+    pub existing_entries: usize,
+}
+
+/// Compound segment filter; every field that is set must match.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SegmentFilterDto {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub statuses: Option<Vec<String>>,
+    /// Segments with (or without) unresolved QA findings.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub has_qa_findings: Option<bool>,
+    /// Restricts `has_qa_findings` to these severities (`info`, `warning`, `critical`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub qa_severities: Option<Vec<String>>,
+    /// `exact`, `fuzzy` or `no_match`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub match_bands: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub has_comments: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_regex: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_regex: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub case_insensitive: Option<bool>,
+    /// RFC 3339 timestamp or `YYYY-MM-DD`; keeps segments revised since then.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub modified_since: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuerySegmentsPayload {
+    pub project_uuid: String,
+    pub jliff_rel_path: String,
+    #[serde(default)]
+    pub filter: SegmentFilterDto,
+    #[serde(default)]
+    pub offset: Option<usize>,
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SaveSegmentFilterPayload {
+    pub user_uuid: String,
+    pub name: String,
+    pub filter: SegmentFilterDto,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedSegmentFilterDto {
+    pub filter_uuid: String,
+    pub user_uuid: String,
+    pub name: String,
+    pub filter: SegmentFilterDto,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProductivityReportPayload {
+    pub user_uuid: String,
+    /// First day of the report (`YYYY-MM-DD`, UTC).
+    pub from: String,
+    /// Last day of the report, inclusive.
+    pub to: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DailyProductivityDto {
+    pub day: String,
+    pub segments: i64,
+    pub words: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProductivityReportDto {
+    pub user_uuid: String,
+    pub from: String,
+    pub to: String,
+    pub total_segments: i64,
+    pub total_words: i64,
+    /// Days with at least one confirmation.
+    pub active_days: usize,
+    pub average_words_per_active_day: f64,
+    pub average_segments_per_active_day: f64,
+    pub days: Vec<DailyProductivityDto>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestSensitiveActionPayload {
+    /// Name of the settings command to confirm, e.g. `update_app_folder`.
+    pub action: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SensitiveActionTokenDto {
+    pub token: String,
+    pub action: String,
+    pub expires_in_seconds: u64,
+}
+
+/// One row of the workspace language pair matrix. Language codes are lowercased.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LanguagePairMatrixEntryDto {
+    pub source_lang: String,
+    pub target_lang: String,
+    pub project_count: i64,
+    pub document_count: usize,
+    /// Source words of the pair's JLIFF documents.
+    pub total_words: usize,
+    /// Source words of segments that are translated, reviewed or approved.
+    pub translated_words: usize,
+    pub completion_percent: f64,
+    pub delivered_packages: i64,
+    /// Mean hours from project creation to delivery, when the pair was delivered.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub average_turnaround_hours: Option<f64>,
+    pub tm_entries: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceLockStateDto {
+    pub locked: bool,
+    /// Whether unlocking asks for the local passphrase rather than a new sign-in.
+    pub has_passphrase: bool,
+}
+
+/// Payload of `workspace://lock_state`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceLockEventPayload {
+    pub locked: bool,
+    /// `manual`, `inactivity` or `unlocked`.
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetWorkspacePassphrasePayload {
+    /// Required when a passphrase is already set.
+    pub current_passphrase: Option<String>,
+    /// The new passphrase; `None` removes it.
+    pub passphrase: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListDocumentSnapshotsPayload {
+    pub project_uuid: String,
+    pub jliff_rel_path: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentSnapshotDto {
+    pub snapshot_id: String,
+    /// RFC 3339 time the snapshot was taken.
+    pub created_at: String,
+    pub size_bytes: u64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreDocumentSnapshotPayload {
+    pub project_uuid: String,
+    pub jliff_rel_path: String,
+    pub snapshot_id: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreDocumentSnapshotDto {
+    pub snapshot_id: String,
+    /// Snapshot of the document as it was before the restore, so the restore can be undone.
+    pub pre_restore_snapshot_id: Option<String>,
+    pub transunit_count: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QaProfileDto {
+    pub profile_uuid: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub definition: crate::qa::QaProfileDefinition,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SaveQaProfilePayload {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub definition: crate::qa::QaProfileDefinition,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportQaProfilePayload {
+    pub profile_uuid: String,
+    /// Absolute path of the JSON file to write.
+    pub output_path: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QaProfileExportDto {
+    pub output_path: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportQaProfilePayload {
+    /// Absolute path of an exported profile.
+    pub input_path: String,
+    /// Project or client to assign the imported profile to.
+    #[serde(default)]
+    pub project_uuid: Option<String>,
+    #[serde(default)]
+    pub client_uuid: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssignQaProfilePayload {
+    /// Profile to assign; `null` removes the current assignment.
+    #[serde(default)]
+    pub profile_uuid: Option<String>,
+    #[serde(default)]
+    pub project_uuid: Option<String>,
+    #[serde(default)]
+    pub client_uuid: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmptyTargetDto {
+    pub jliff_rel_path: String,
+    pub transunit_id: String,
+}
+
+/// Outcome of the delivery gate. Returned as the error payload when a transition to
+/// `delivered` is blocked.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeliveryGateReportDto {
+    /// `DELIVERY_BLOCKED` when the gate refuses delivery, `DELIVERY_READY` otherwise.
+    pub code: String,
+    pub message: String,
+    pub project_uuid: String,
+    pub critical_findings: Vec<QaFindingDto>,
+    pub empty_targets: Vec<EmptyTargetDto>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildDeliveryPackagePayload {
+    pub project_uuid: String,
+    pub source_lang: String,
+    pub target_lang: String,
+    #[serde(default)]
+    pub options: DeliveryPackageOptionsDto,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeliveryPackageOptionsDto {
+    /// Adds the bilingual XLIFF files under `bilingual/`.
+    #[serde(default = "default_true")]
+    pub include_bilingual: bool,
+    /// Adds the open QA findings of the language pair as `qa-report.json`.
+    #[serde(default = "default_true")]
+    pub include_qa_report: bool,
+    /// Message for the client, written into the delivery note.
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+impl Default for DeliveryPackageOptionsDto {
+    fn default() -> Self {
+        Self {
+            include_bilingual: true,
+            include_qa_report: true,
+            note: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeliveryPackageDto {
+    pub artifact_uuid: String,
+    pub project_uuid: String,
+    pub source_lang: String,
+    pub target_lang: String,
+    /// Path of the ZIP relative to the project folder.
+    pub rel_path: String,
+    pub document_count: i64,
+    pub bilingual_count: i64,
+    pub open_findings: i64,
+    pub created_at: String,
+    /// Set once the project was delivered with this package.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delivered_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildDeliveryPackageResponseDto {
+    pub package: DeliveryPackageDto,
+    /// The package as a project file, carrying its `delivery_package` artifact.
+    pub file: ProjectFileBundleV2Dto,
+    /// Source files left out because no translated document was found for them.
+    pub missing_documents: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportReturnPackageResponseDto {
+    /// The `.sdlrpx` or `.mqback` package as a project file.
+    pub file: ProjectFileBundleV2Dto,
+    /// `trados` or `memoq`.
+    pub package_kind: String,
+    /// Paths inside the package of the documents sent back.
+    pub documents: Vec<String>,
+    /// Paths inside the package of the documents left out for want of a translated file.
+    pub missing_documents: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListProjectHistoryPayload {
+    pub project_uuid: String,
+    /// Most recent commits returned; 100 when absent.
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectHistoryEntryDto {
+    pub commit_id: String,
+    pub summary: String,
+    /// Event that triggered the commit, such as `pretranslation` or `checkout`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event: Option<String>,
+    pub files: Vec<String>,
+    pub committed_at: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectHistoryDto {
+    /// Whether the project opted in to git history.
+    pub enabled: bool,
+    /// Newest first.
+    pub entries: Vec<ProjectHistoryEntryDto>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckoutProjectSnapshotPayload {
+    pub project_uuid: String,
+    pub commit_id: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckoutProjectSnapshotDto {
+    pub commit_id: String,
+    /// Project-relative documents written back from the commit.
+    pub restored_files: Vec<String>,
+    /// Commit recording the checkout; absent when the documents already matched.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entry: Option<ProjectHistoryEntryDto>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SaveDeliveryTargetPayload {
+    /// Updates this target; a new one is created when absent.
+    #[serde(default)]
+    pub target_uuid: Option<String>,
+    pub client_uuid: String,
+    pub name: String,
+    pub settings: TargetSettings,
+    /// Password, key passphrase or S3 secret access key. Kept in the secrets vault; an update
+    /// without one keeps the stored secret, an empty one removes it.
+    #[serde(default)]
+    pub secret: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeliveryTargetDto {
+    pub target_uuid: String,
+    pub client_uuid: String,
+    pub name: String,
+    pub settings: TargetSettings,
+    /// Whether the vault holds a secret for the target; the secret itself is never returned.
+    pub has_secret: bool,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadDeliveryPackagePayload {
+    pub project_uuid: String,
+    /// Artifact of the package, as returned when it was built.
+    pub artifact_uuid: String,
+    pub target_uuid: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeliveryReceiptDto {
+    pub receipt_uuid: String,
+    pub artifact_uuid: String,
+    pub project_uuid: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_uuid: Option<String>,
+    pub transport: String,
+    /// `uploaded` or `failed`.
+    pub status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remote_location: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size_bytes: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub started_at: String,
+    pub completed_at: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeliveryUploadEventPayload {
+    pub upload_id: String,
+    pub artifact_uuid: String,
+    pub target_uuid: String,
+    /// `progress`, `completed` or `failed`.
+    pub state: String,
+    pub sent: u64,
+    pub total: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckCrossPairPlaceholdersPayload {
+    pub project_uuid: String,
+    /// Restricts the check to one source file (the JLIFF `file` header).
+    #[serde(default)]
+    pub file: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrossPairDocumentDto {
+    pub jliff_rel_path: String,
+    pub target_lang: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LanguagePlaceholdersDto {
+    pub target_lang: String,
+    pub jliff_rel_path: String,
+    pub placeholders: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaceholderDivergenceDto {
+    pub transunit_id: String,
+    pub source_placeholders: Vec<String>,
+    /// Placeholder set most target languages agree on.
+    pub expected_placeholders: Vec<String>,
+    pub targets: Vec<LanguagePlaceholdersDto>,
+    pub divergent_languages: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrossPairFileReportDto {
+    pub file: String,
+    pub source_lang: String,
+    pub documents: Vec<CrossPairDocumentDto>,
+    pub divergences: Vec<PlaceholderDivergenceDto>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrossPairPlaceholdersQaDto {
+    /// Source files translated into at least two languages.
+    pub files: Vec<CrossPairFileReportDto>,
+    pub total_divergences: usize,
+    pub findings_recorded: usize,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckConsistencyPayload {
+    pub project_uuid: String,
+    pub source_lang: String,
+    pub target_lang: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SegmentLocationDto {
+    pub jliff_rel_path: String,
+    pub transunit_id: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TargetVariantDto {
+    pub source: String,
+    pub target: String,
+    pub locations: Vec<SegmentLocationDto>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InconsistencyGroupDto {
+    /// `same_source` or `similar_source`.
+    pub kind: String,
+    pub source_similarity: u8,
+    /// Most frequent variant first.
+    pub variants: Vec<TargetVariantDto>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConsistencyQaDto {
+    pub groups: Vec<InconsistencyGroupDto>,
+    pub findings_recorded: usize,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApplyConsistencyVariantPayload {
+    pub project_uuid: String,
+    /// Chosen variant, written to every listed segment.
+    pub target: String,
+    pub segments: Vec<SegmentLocationDto>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConsistencyFixFileDto {
+    pub jliff_rel_path: String,
+    pub updated_segments: usize,
+    /// Segments left untouched because the variant's placeholders do not fit them.
+    pub skipped_transunit_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConsistencyFixDto {
+    pub updated_segments: usize,
+    pub files: Vec<ConsistencyFixFileDto>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordProviderUsagePayload {
+    pub provider: String,
+    pub project_uuid: String,
+    #[serde(default)]
+    pub characters: i64,
+    #[serde(default)]
+    pub tokens: i64,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetProviderUsagePayload {
+    #[serde(default)]
+    pub project_uuid: Option<String>,
+    /// `YYYY-MM`; the current month when omitted.
+    #[serde(default)]
+    pub month: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetProviderBudgetPayload {
+    pub provider: String,
+    #[serde(default)]
+    pub monthly_character_cap: Option<i64>,
+    #[serde(default)]
+    pub monthly_token_cap: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderUsageEntryDto {
+    pub provider: String,
+    pub project_uuid: String,
+    pub month: String,
+    pub characters: i64,
+    pub tokens: i64,
+    pub requests: i64,
+    pub updated_at: String,
+}
+
+/// Monthly usage of a provider across all projects, against its budget.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderBudgetStatusDto {
+    pub provider: String,
+    pub month: String,
+    pub characters: i64,
+    pub tokens: i64,
+    pub requests: i64,
+    pub monthly_character_cap: Option<i64>,
+    pub monthly_token_cap: Option<i64>,
+    pub exceeded: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderUsageDto {
+    pub month: String,
+    pub entries: Vec<ProviderUsageEntryDto>,
+    pub providers: Vec<ProviderBudgetStatusDto>,
+}
+
+/// Error payload returned when an MT job is paused because its provider is over budget.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderBudgetExceededDto {
+    /// Always `PROVIDER_BUDGET_EXCEEDED`.
+    pub code: String,
+    pub message: String,
+    pub budget: ProviderBudgetStatusDto,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderCachePayload {
+    pub provider: String,
+    pub model: String,
+    pub source_lang: String,
+    pub target_lang: String,
+    pub segments: Vec<ProviderCacheSegmentPayload>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderCacheSegmentPayload {
+    pub source: String,
+    /// Prompt context sent with the source (glossary, neighbouring segments, ...).
+    #[serde(default)]
+    pub context: Option<String>,
+    /// Provider output; only read when storing.
+    #[serde(default)]
+    pub target: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderCacheLookupDto {
+    /// Cached target of each requested segment, in request order; `null` on a miss.
+    pub targets: Vec<Option<String>>,
+    pub hits: usize,
+    pub misses: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderCacheModelStatsDto {
+    pub provider: String,
+    pub model: String,
+    pub entries: i64,
+    pub expired_entries: i64,
+    pub hits: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderCacheStatsDto {
+    pub ttl_hours: u32,
+    pub entries: i64,
+    pub expired_entries: i64,
+    pub hits: i64,
+    pub models: Vec<ProviderCacheModelStatsDto>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PurgeProviderCachePayload {
+    #[serde(default)]
+    pub provider: Option<String>,
+    #[serde(default)]
+    pub expired_only: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AcquireProviderPermitPayload {
+    pub provider: String,
+    /// Estimated tokens of the request, counted against `tokensPerMinute`.
+    #[serde(default)]
+    pub tokens: u32,
+    /// Caller reference echoed in rate limit events (e.g. a batch or job id).
+    #[serde(default)]
+    pub request_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderPermitDto {
+    pub provider: String,
+    /// Time spent queued behind the rate limit.
+    pub waited_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderRateLimitEventPayload {
+    pub provider: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+    /// `waiting` while held back, `ready` once the request may be sent.
+    pub state: String,
+    pub wait_ms: u64,
+    pub queued: usize,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetProviderRateLimitPayload {
+    pub provider: String,
+    #[serde(default)]
+    pub requests_per_minute: Option<u32>,
+    #[serde(default)]
+    pub tokens_per_minute: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderRateLimitDto {
+    pub provider: String,
+    pub requests_per_minute: Option<u32>,
+    pub tokens_per_minute: Option<u32>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamSegmentTranslationPayload {
+    pub source_lang: String,
+    pub target_lang: String,
+    pub source: String,
+    #[serde(default)]
+    pub context: Option<String>,
+    /// Overrides the configured `LLM_MODEL`.
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Project whose prompt template and protected terms are used.
+    #[serde(default)]
+    pub project_uuid: Option<String>,
+    /// Terminology injected in addition to the project's protected terms.
+    #[serde(default)]
+    pub terms: Vec<PromptTerm>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranslationStreamStartedDto {
+    pub job_id: String,
+    /// Event channel carrying the partial output (`translation:stream:{jobId}`).
+    pub event: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranslationStreamEventPayload {
+    pub job_id: String,
+    /// `delta`, `completed`, `failed` or `cancelled`.
+    pub kind: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delta: Option<String>,
+    /// Output received so far.
+    pub text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalModelCapabilitiesDto {
+    pub base_url: String,
+    /// `ollama`, `llama.cpp` or `openai-compatible`.
+    pub server: String,
+    pub models: Vec<String>,
+    /// Model the capabilities were probed for.
+    pub model: Option<String>,
+    pub context_tokens: Option<u32>,
+    /// Largest source chunk, in characters, sent in one request.
+    pub chunk_chars: usize,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalModelTranslationPayload {
+    pub source_lang: String,
+    pub target_lang: String,
+    pub source: String,
+    #[serde(default)]
+    pub context: Option<String>,
+    /// Project whose prompt template and protected terms are used.
+    #[serde(default)]
+    pub project_uuid: Option<String>,
+    /// Terminology injected in addition to the project's protected terms.
+    #[serde(default)]
+    pub terms: Vec<PromptTerm>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalModelTranslationDto {
+    pub text: String,
+    pub model: String,
+    /// Number of requests the source was split into.
+    pub chunks: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptTemplateDto {
+    pub template_uuid: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub definition: PromptTemplateDefinition,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatePromptTemplatePayload {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub definition: PromptTemplateDefinition,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdatePromptTemplatePayload {
+    pub template_uuid: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    /// An empty description clears it.
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub definition: Option<PromptTemplateDefinition>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssignPromptTemplatePayload {
+    pub project_uuid: String,
+    /// Omit to apply to every source language.
+    #[serde(default)]
+    pub source_lang: Option<String>,
+    /// Omit to apply to every target language.
+    #[serde(default)]
+    pub target_lang: Option<String>,
+    /// Omit to remove the assignment.
+    #[serde(default)]
+    pub template_uuid: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptTemplateAssignmentDto {
+    pub project_uuid: String,
+    pub source_lang: Option<String>,
+    pub target_lang: Option<String>,
+    pub template_uuid: String,
+    pub assigned_at: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreviewPromptPayload {
+    /// Unsaved definition to preview; takes precedence over `templateUuid`.
+    #[serde(default)]
+    pub definition: Option<PromptTemplateDefinition>,
+    #[serde(default)]
+    pub template_uuid: Option<String>,
+    /// Project providing the template (when none is given), name and protected terms.
+    #[serde(default)]
+    pub project_uuid: Option<String>,
+    pub source_lang: String,
+    pub target_lang: String,
+    pub source: String,
+    #[serde(default)]
+    pub context: Option<String>,
+    #[serde(default)]
+    pub terms: Vec<PromptTerm>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptMessageDto {
+    pub role: String,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptPreviewDto {
+    /// Template used, `None` for the built-in prompt or an unsaved definition.
+    pub template_uuid: Option<String>,
+    pub messages: Vec<PromptMessageDto>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompareProvidersPayload {
+    pub project_uuid: String,
+    pub jliff_rel_path: String,
+    pub transunit_ids: Vec<String>,
+    /// The two providers to compare, e.g. `llm` and `local`.
+    pub providers: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderCandidateDto {
+    pub transunit_id: String,
+    pub provider: String,
+    pub source: String,
+    pub target: String,
+    /// Estimated quality from 0 to 1; only meaningful relative to other candidates.
+    pub qe_score: f64,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderComparisonSegmentDto {
+    pub transunit_id: String,
+    pub source: String,
+    /// Target currently stored in the document, left untouched by the comparison.
+    pub current_target: String,
+    pub candidates: Vec<ProviderCandidateDto>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderComparisonSummaryDto {
+    pub provider: String,
+    pub average_qe_score: f64,
+    /// Segments where this provider scored strictly higher than the other.
+    pub wins: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderComparisonDto {
+    pub jliff_rel_path: String,
+    pub source_lang: String,
+    pub target_lang: String,
+    pub providers: Vec<ProviderComparisonSummaryDto>,
+    /// Provider currently preferred for the language pair.
+    pub preferred_provider: Option<String>,
+    pub segments: Vec<ProviderComparisonSegmentDto>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetPreferredProviderPayload {
+    pub project_uuid: String,
+    pub source_lang: String,
+    pub target_lang: String,
+    /// Omit to clear the preference.
+    #[serde(default)]
+    pub provider: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreferredProviderDto {
+    pub source_lang: String,
+    pub target_lang: String,
+    pub provider: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderRoutingRuleDto {
+    pub rule_uuid: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project_uuid: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_uuid: Option<String>,
+    pub position: i64,
+    pub source_lang: Option<String>,
+    pub target_lang: Option<String>,
+    pub subject: Option<String>,
+    pub provider: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderRoutingRuleInput {
+    /// Unset conditions match anything.
+    #[serde(default)]
+    pub source_lang: Option<String>,
+    #[serde(default)]
+    pub target_lang: Option<String>,
+    #[serde(default)]
+    pub subject: Option<String>,
+    pub provider: String,
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplaceProviderRoutingRulesPayload {
+    #[serde(default)]
+    pub project_uuid: Option<String>,
+    #[serde(default)]
+    pub client_uuid: Option<String>,
+    /// Rules in evaluation order.
+    pub rules: Vec<ProviderRoutingRuleInput>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExplainRoutingPayload {
+    pub project_uuid: String,
+    pub source_lang: String,
+    pub target_lang: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RoutingRuleEvaluationDto {
+    pub rule: ProviderRoutingRuleDto,
+    pub matched: bool,
+    /// Why the rule was skipped.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RoutingExplanationDto {
+    /// Provider chosen, `None` when the app default applies.
+    pub provider: Option<String>,
+    /// `rule`, `preferred` (the pair's preferred provider) or `default`.
+    pub decided_by: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub matched_rule: Option<ProviderRoutingRuleDto>,
+    pub evaluated: Vec<RoutingRuleEvaluationDto>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranslateSegmentPayload {
+    pub project_uuid: String,
+    pub source_lang: String,
+    pub target_lang: String,
+    pub source: String,
+    #[serde(default)]
+    pub context: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RoutedTranslationDto {
+    pub provider: Option<String>,
+    /// Translation from a provider run by the backend; `None` when the app calls `provider`.
+    pub text: Option<String>,
+    pub routing: RoutingExplanationDto,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StartDownloadPayload {
+    pub url: String,
+    /// Cache folder, e.g. `models`, `dictionaries` or `toolchains`.
+    pub category: String,
+    /// Defaults to the last segment of the URL path.
+    #[serde(default)]
+    pub file_name: Option<String>,
+    /// Expected SHA-256 digest (hex); the file is rejected when it differs.
+    #[serde(default)]
+    pub sha256: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadStartedDto {
+    pub download_id: String,
+    pub category: String,
+    pub file_name: String,
+    pub path: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadEventPayload {
+    pub download_id: String,
+    pub category: String,
+    pub file_name: String,
+    /// `progress`, `completed` or `failed`.
+    pub state: String,
+    pub downloaded: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CachedDownloadDto {
+    pub category: String,
+    pub file_name: String,
+    pub path: String,
+    pub size: u64,
+    /// The download was interrupted and will resume on the next attempt.
+    pub partial: bool,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateSupportBundlePayload {
+    /// Absolute folder receiving the bundle; `<app_folder>/support` by default.
+    #[serde(default)]
+    pub destination_dir: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SupportBundleDto {
+    pub path: String,
+    pub size: u64,
+    /// Names of the files inside the bundle.
+    pub files: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashReportDto {
+    pub id: String,
+    /// `panic` or `unclean_shutdown`.
+    pub kind: String,
+    pub occurred_at: String,
+    pub session_started_at: Option<String>,
+    pub app_version: String,
+    pub os: String,
+    pub arch: String,
+    pub thread: Option<String>,
+    pub message: String,
+    pub location: Option<String>,
+    pub backtrace: Option<String>,
+    pub uploaded_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashReportsDto {
+    /// Report explaining why the previous session did not end cleanly, if it did not.
+    pub previous_session_report_id: Option<String>,
+    /// The user opted in and a crash report endpoint is configured.
+    pub upload_available: bool,
+    pub reports: Vec<CrashReportDto>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadCrashReportsDto {
+    pub uploaded: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduledTaskDto {
+    pub id: String,
+    pub description: String,
+    pub schedule: String,
+    pub next_run_at: Option<String>,
+    pub running: bool,
+    pub last_started_at: Option<String>,
+    pub last_finished_at: Option<String>,
+    /// `running`, `succeeded` or `failed`; `None` before the first run.
+    pub last_status: Option<String>,
+    pub last_error: Option<String>,
+    pub run_count: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IpcCommandMetricsDto {
+    pub command: String,
+    pub calls: u64,
+    pub failures: u64,
+    /// `failures / calls`, 0 when the command was never called.
+    pub error_rate: f64,
+    pub slow_calls: u64,
+    pub avg_duration_ms: f64,
+    pub max_duration_ms: f64,
+    pub payload_bytes: u64,
+    pub avg_payload_bytes: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExchangeRateDto {
+    pub base_currency: String,
+    pub quote_currency: String,
+    pub rate_date: String,
+    /// Units of the quote currency one unit of the base currency was worth.
+    pub rate: f64,
+    pub source: String,
+    pub recorded_at: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetExchangeRatePayload {
+    pub base_currency: String,
+    pub quote_currency: String,
+    pub rate: f64,
+    /// `YYYY-MM-DD`; today when omitted.
+    #[serde(default)]
+    pub rate_date: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteExchangeRatePayload {
+    pub base_currency: String,
+    pub quote_currency: String,
+    pub rate_date: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MoneyDto {
+    pub amount: f64,
+    pub currency: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConvertAmountsPayload {
+    pub amounts: Vec<MoneyDto>,
+    /// Currency of the total; the base currency from the settings when omitted.
+    #[serde(default)]
+    pub target_currency: Option<String>,
+    /// Day whose rates apply, `YYYY-MM-DD`; today when omitted.
+    #[serde(default)]
+    pub on_date: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConvertedAmountsDto {
+    pub currency: String,
+    pub total: f64,
+    /// One line per amount, in request order.
+    pub lines: Vec<ConvertedAmountDto>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConvertedAmountDto {
+    pub amount: f64,
+    pub currency: String,
+    pub converted: f64,
+    pub rate: f64,
+    /// Snapshot date the rate rests on; absent for amounts already in the target currency.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rate_date: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateProjectNotePayload {
+    pub project_uuid: String,
+    pub author_user_uuid: String,
+    pub body: String,
+    /// Whether the body is Markdown; plain text otherwise.
+    #[serde(default = "default_true")]
+    pub is_markdown: bool,
+    #[serde(default)]
+    pub pinned: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateProjectNotePayload {
+    pub note_uuid: String,
+    #[serde(default)]
+    pub body: Option<String>,
+    #[serde(default)]
+    pub is_markdown: Option<bool>,
+    #[serde(default)]
+    pub pinned: Option<bool>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListProjectNotesPayload {
+    pub project_uuid: String,
+    #[serde(default)]
+    pub offset: Option<usize>,
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectNoteDto {
+    pub note_uuid: String,
+    pub project_uuid: String,
+    pub author_user_uuid: String,
+    pub body: String,
+    /// Render `body` as Markdown rather than plain text.
+    pub is_markdown: bool,
+    pub pinned: bool,
+    pub created_at: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<String>,
+    pub mentions: Vec<NoteMentionDto>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteMentionDto {
+    /// Handle as written after `@`, lowercased.
+    pub mention: String,
+    /// User the handle resolved to; absent when no username matches.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_uuid: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectNotePageDto {
+    /// Notes of the project before paging.
+    pub total: i64,
+    pub notes: Vec<ProjectNoteDto>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DomainDto {
+    pub domain_id: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateDomainPayload {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateDomainPayload {
+    pub domain_id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetProjectDomainsPayload {
+    pub project_uuid: String,
+    /// Replaces the project's domains; empty unlinks them all.
+    pub domain_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetTermbaseDomainsPayload {
+    pub termbase: String,
+    /// Replaces the termbase's domains; empty makes it apply to every project.
+    pub domain_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TermbaseDomainsDto {
+    pub termbase: String,
+    /// Domains the termbase is limited to; empty when it applies to every project.
+    pub domains: Vec<DomainDto>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubjectTaxonomyDto {
+    pub subject: String,
+    pub aliases: Vec<String>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpsertSubjectPayload {
+    /// Renames the managed subject with the same match key, if there is one.
+    pub subject: String,
+    /// Replaces the subject's aliases.
+    #[serde(default)]
+    pub aliases: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubjectSuggestionDto {
+    pub subject: String,
+    /// Alias the prefix matched, when it did not match the subject itself.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub matched_alias: Option<String>,
+    /// Whether the subject is in the managed taxonomy rather than only used by projects.
+    pub managed: bool,
+    pub project_count: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectOutputNamingDto {
+    pub project_uuid: String,
+    /// Project override; absent when the global template applies.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub xliff_template: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jliff_template: Option<String>,
+    /// Template XLIFF files of the project are named with.
+    pub effective_xliff_template: String,
+    /// Template JLIFF documents of the project are named with.
+    pub effective_jliff_template: String,
+    /// Layout of the Translations folder: `language_first` (`<pair>/<source folders>`) or
+    /// `source_first` (`<source folders>/<pair>`).
+    pub translations_layout: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetProjectOutputNamingPayload {
+    pub project_uuid: String,
+    #[serde(default)]
+    pub xliff_template: Option<String>,
+    #[serde(default)]
+    pub jliff_template: Option<String>,
+    /// `language_first` or `source_first`; the language-first default when absent.
+    #[serde(default)]
+    pub translations_layout: Option<String>,
+}
+
+/// Formats the app supports, from the embedded manifest or its override in the app folder.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SupportedFormatsDto {
+    pub version: u32,
+    /// Whether `formats.json` in the app folder replaces the embedded manifest.
+    pub overridden: bool,
+    pub formats: Vec<SupportedFormatDto>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SupportedFormatDto {
+    pub id: String,
+    pub label: String,
+    /// Heading the wizard lists the format under.
+    pub group: String,
+    /// Extensions without the dot.
+    pub extensions: Vec<String>,
+    /// Role files of the format get unless a rule or the user picks another.
+    pub role: ProjectAssetRoleDto,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter: Option<String>,
+    /// XLIFF already; used without conversion.
+    pub bilingual: bool,
+}
+
+/// State of the self-updater, also the payload of `updates://status_changed`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateStatusDto {
+    /// `disabled`, `idle`, `checking`, `up_to_date`, `downloading`, `ready` or `failed`.
+    pub state: String,
+    pub current_version: String,
+    /// Newer release found by the last check.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub available_version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pub_date: Option<String>,
+    /// Bytes of the installer downloaded so far.
+    pub downloaded: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub checked_at: Option<String>,
+}
+
+/// A setting a plugin declares.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginSettingSpecDto {
+    pub key: String,
+    pub label: String,
+    /// `string`, `number` or `boolean`.
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default: Option<serde_json::Value>,
+    pub required: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginDto {
+    pub id: String,
+    pub name: String,
+    pub version: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// `executable` or `wasm`.
+    pub runtime: String,
+    pub plugin_dir: String,
+    /// Whether the manifest and entry point are still in the plugin folder.
+    pub available: bool,
+    pub settings: Vec<PluginSettingSpecDto>,
+    /// Stored setting values, with defaults filled in.
+    pub values: serde_json::Map<String, serde_json::Value>,
+    pub registered_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegisterPluginPayload {
+    /// Plugin folder, the one holding `plugin.json`.
+    pub path: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetPluginSettingsPayload {
+    pub plugin_id: String,
+    /// Replaces the stored values; declared settings left out fall back to their defaults.
+    pub values: serde_json::Map<String, serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutomationScriptDto {
+    pub script_uuid: String,
+    pub name: String,
+    /// `on_segment_confirm`, `on_project_create` or `on_qa_finding`.
+    pub hook: String,
+    pub source: String,
+    pub enabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_run_at: Option<String>,
+    /// Error of the last run; absent when it succeeded.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SaveAutomationScriptPayload {
+    /// Script to replace; without it, the script with the same name is replaced or a new
+    /// one is added.
+    #[serde(default)]
+    pub script_uuid: Option<String>,
+    pub name: String,
+    pub hook: String,
+    pub source: String,
+    #[serde(default)]
+    pub enabled: Option<bool>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetAutomationScriptEnabledPayload {
+    pub script_uuid: String,
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TestAutomationScriptPayload {
+    pub hook: String,
+    pub source: String,
+    /// Sample `event` map the script sees.
+    #[serde(default)]
+    pub event: serde_json::Value,
+}
+
+/// An action a script queued.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptActionDto {
+    /// `notify`, `add_note`, `flag` or `resolve`.
+    pub kind: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub severity: Option<String>,
+}
+
+/// Result of a dry run: the actions are listed, not applied.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptTestResultDto {
+    pub actions: Vec<ScriptActionDto>,
+    pub logs: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Payload of `scripts://notification`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptNotificationPayload {
+    pub script_uuid: String,
+    pub script_name: String,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project_uuid: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportLanguageAssetsPayload {
+    /// Absolute path of the package to write; an existing file is replaced.
+    pub output_path: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LanguageAssetsExportDto {
+    pub path: String,
+    pub size_bytes: u64,
+    pub translation_memory_entries: usize,
+    pub termbase_entries: usize,
+    pub qa_profiles: usize,
+    pub segmentation_rules: usize,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportLanguageAssetsPayload {
+    /// Absolute path of the package to import.
+    pub input_path: String,
+}
+
+/// What an import changed.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LanguageAssetsImportDto {
+    /// When the package was exported.
+    pub exported_at: String,
+    /// Translation memory entries stored, including those that were already there.
+    pub translation_memory_entries: usize,
+    /// Terms added; terms already in their termbase are skipped.
+    pub termbase_entries: usize,
+    /// QA profiles stored; each replaces the local profile with the same name.
+    pub qa_profiles: usize,
+    /// Global segmentation exceptions added; existing ones are skipped.
+    pub segmentation_rules: usize,
+}
+
+/// A configured workspace, i.e. an app folder with its own database and projects.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceDto {
+    pub name: String,
+    pub folder: String,
+    /// Whether this is the app folder in use.
+    pub active: bool,
+    /// Whether the folder is still on disk; a missing folder starts empty when switched to.
+    pub exists: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SwitchWorkspacePayload {
+    /// Absolute path of the app folder to switch to; a new folder starts an empty workspace.
+    pub folder: String,
+    /// Name shown in the switcher; defaults to the folder name for a new workspace.
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateProxySettingsPayload {
+    /// HTTP(S) proxy URL; empty or missing turns the proxy off.
+    #[serde(default)]
+    pub url: Option<String>,
+    #[serde(default)]
+    pub username: Option<String>,
+    /// Kept in the keychain. Missing keeps the stored password; empty removes it.
+    #[serde(default)]
+    pub password: Option<String>,
+    /// Hosts, domains (`.example.com`) or CIDR ranges reached without the proxy.
+    #[serde(default)]
+    pub no_proxy: Vec<String>,
+}
+
+/// Outcome of `test_connectivity_v2`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectivityResultDto {
+    pub provider: String,
+    pub url: String,
+    /// Whether the service answered, whatever the status.
+    pub reachable: bool,
+    pub status: Option<u16>,
+    pub latency_ms: u64,
+    /// Proxy configured when the request was sent; no-proxy hosts are reached directly.
+    pub proxy_url: Option<String>,
+    pub error: Option<String>,
+}
+
+/// Whether the app is offline, and why.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OfflineStatusDto {
+    pub offline: bool,
+    /// Offline mode was switched on by the user.
+    pub forced: bool,
+    /// The last connectivity probe failed.
+    pub detected_offline: bool,
+    /// When connectivity was last probed.
+    pub checked_at: Option<String>,
+}
+
+/// Error payload returned when a command needs the network while the app is offline.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OfflineErrorDto {
+    /// Always `OFFLINE`.
+    pub code: String,
+    pub message: String,
+    /// Set when the request was queued to run once the app is back online.
+    pub queued_operation_uuid: Option<String>,
+}
+
+/// An outbound operation queued while offline.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OfflineOperationDto {
+    pub operation_uuid: String,
+    /// Command to send again, such as `upload_delivery_package_v2`.
+    pub operation: String,
+    /// Payload to send it with.
+    pub payload: serde_json::Value,
+    pub project_uuid: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportProjectBundlePayload {
+    pub project_uuid: String,
+    /// Absolute path of the archive to write, outside the project folder; an existing file
+    /// is replaced.
+    pub output_path: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectBundleExportDto {
+    pub path: String,
+    pub size_bytes: u64,
+    /// Files of the project folder in the archive.
+    pub files: usize,
+    pub language_pairs: usize,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListOutboundRequestsPayload {
+    /// Limits the audit to one project; every project when absent.
+    #[serde(default)]
+    pub project_uuid: Option<String>,
+    /// Defaults to 500.
+    #[serde(default)]
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutboundRequestDto {
+    pub request_uuid: String,
+    pub provider: String,
+    pub project_uuid: Option<String>,
+    pub segment_count: i64,
+    pub char_count: i64,
+    pub destination_host: Option<String>,
+    pub sent_at: String,
+}
@@ -3,6 +3,10 @@ use tauri::ipc::InvokeError;
 use thiserror::Error;
 
 use crate::db::DbError;
+use crate::ipc::dto::OfflineErrorDto;
+
+/// Code of the error returned while the app is offline.
+pub const OFFLINE: &str = "OFFLINE";
 
 #[derive(Debug, Error)]
 pub enum IpcError {
@@ -10,13 +14,24 @@ pub enum IpcError {
     Validation(String),
     #[error("{0}")]
     Internal(String),
+    /// The command needs the network but the app is offline. Surfaced as an
+    /// [`OfflineErrorDto`] so the UI can tell it apart from other failures.
+    #[error("{0}")]
+    Offline(String),
 }
 
 pub type IpcResult<T> = Result<T, InvokeError>;
 
 impl From<IpcError> for InvokeError {
     fn from(error: IpcError) -> Self {
-        InvokeError::from_anyhow(anyhow!(error))
+        match error {
+            IpcError::Offline(message) => InvokeError::from(OfflineErrorDto {
+                code: OFFLINE.to_string(),
+                message,
+                queued_operation_uuid: None,
+            }),
+            error => InvokeError::from_anyhow(anyhow!(error)),
+        }
     }
 }
 
@@ -25,8 +40,14 @@ fn map_constraint_message(raw: &str) -> String {
 
     if lower.contains("project_language_pairs") {
         "Each project language pair must be unique.".into()
+    } else if lower.contains("subject_aliases") {
+        "Each alias can belong to only one subject.".into()
+    } else if lower.contains("subject_taxonomy") {
+        "A subject with this name already exists.".into()
     } else if lower.contains("project_subjects") {
         "Each project subject can only be added once.".into()
+    } else if lower.contains("domains.name") {
+        "A domain with this name already exists.".into()
     } else if lower.contains("project_file_conversions") {
         "A conversion for this language pair and version already exists for the file.".into()
     } else if lower.contains("file language pair must match existing project language pair") {
@@ -79,6 +100,12 @@ impl From<DbError> for IpcError {
             DbError::ConstraintViolation(message) => {
                 IpcError::Validation(map_constraint_message(&message))
             }
+            DbError::EncryptionUnavailable => {
+                IpcError::Validation("This build of the app cannot encrypt the database.".into())
+            }
+            DbError::PiiKeyMissing | DbError::PiiDecryption(_) => IpcError::Internal(
+                "Client details could not be decrypted. Check the keychain and retry.".into(),
+            ),
             DbError::Sqlx(ref db_error) => {
                 log::error!(
                     target: "ipc::error",
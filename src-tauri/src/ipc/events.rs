@@ -2,6 +2,20 @@ pub const TRANSLATION_PROGRESS: &str = "translation://progress";
 pub const TRANSLATION_COMPLETED: &str = "translation://completed";
 pub const TRANSLATION_FAILED: &str = "translation://failed";
 pub const PROJECTS_UPDATED: &str = "projects://updated";
+pub const PROJECT_HEALTH_DEGRADED: &str = "projects://health_degraded";
+pub const JOB_STATUS_CHANGED: &str = "jobs://status_changed";
+pub const SEGMENT_CONFIRMED: &str = "segments://confirmed";
+pub const QA_FINDINGS_RAISED: &str = "qa://findings_raised";
 pub const PIPELINE_JOBS_NEED_ATTENTION: &str = "pipeline://jobs_need_attention";
 pub const PROJECT_CREATE_PROGRESS: &str = "project:create:progress";
 pub const PROJECT_CREATE_COMPLETE: &str = "project:create:complete";
+pub const PROVIDER_RATE_LIMIT: &str = "provider:rate-limit";
+pub const TRANSLATION_STREAM_PREFIX: &str = "translation:stream:";
+pub const DOWNLOAD_PROGRESS: &str = "download:progress";
+pub const DELIVERY_UPLOAD_PROGRESS: &str = "delivery:upload-progress";
+pub const WORKSPACE_LOCK_STATE: &str = "workspace://lock_state";
+pub const UPDATE_STATUS_CHANGED: &str = "updates://status_changed";
+pub const UPDATE_READY: &str = "updates://ready";
+pub const SCRIPT_NOTIFICATION: &str = "scripts://notification";
+pub const WORKSPACE_SWITCHED: &str = "workspace://switched";
+pub const NETWORK_OFFLINE_STATE: &str = "network://offline_state";
@@ -3,21 +3,95 @@ pub mod dto;
 pub mod error;
 pub mod events;
 pub mod state;
+pub mod test_app;
 
 pub use commands::{
-    attach_project_file_v2, clear_translation_history, convert_xliff_to_jliff_v2,
-    create_client_record_v2, create_project_bundle_v2, create_project_with_assets_v2,
-    create_user_profile_v2, delete_artifact_record_v2, delete_client_record_v2,
-    delete_job_record_v2, delete_project_bundle_v2, delete_user_profile_v2, detach_project_file_v2,
-    ensure_project_conversions_plan_v2, fail_translation, get_app_settings, get_client_record_v2,
-    get_project_bundle_v2, get_project_statistics_v2, get_translation_job, get_user_profile_v2,
-    health_check, list_active_jobs, list_artifacts_for_file_v2, list_client_records_v2,
-    list_jobs_for_project_v2, list_project_records_v2, list_translation_history,
-    list_user_profiles_v2, path_exists, places_autocomplete, places_resolve_details,
-    start_translation, update_app_folder, update_artifact_status_v2, update_auto_convert_on_open,
-    update_client_record_v2, update_conversion_status_v2, update_default_languages,
-    update_job_status_v2, update_max_parallel_conversions, update_notifications,
-    update_project_bundle_v2, update_project_file_role_v2, update_theme, update_ui_language,
-    update_user_profile_v2, update_xliff_version, upsert_artifact_record_v2, upsert_job_record_v2,
+    acquire_provider_permit_v2, align_documents_v2, align_segment_v2, analyze_project_v2,
+    apply_consistency_variant_v2, assign_pipeline_preset_v2, assign_prompt_template_v2,
+    assign_qa_profile_v2, attach_project_file_v2, batch_edit_tm_v2, build_delivery_package_v2,
+    cancel_translation_stream_v2, check_bidi_controls_v2, check_consistency_v2,
+    check_cross_pair_placeholders_v2, check_delivery_gate_v2, check_language_direction_v2,
+    check_length_limits_v2, check_protected_terms_v2, check_unicode_text_v2,
+    checkout_project_snapshot_v2, clear_translation_history, close_review_bundle_v2,
+    commit_alignment_v2, compare_providers_v2, concordance_search_v2, confirm_segment_v2,
+    convert_amounts_v2, convert_xliff_to_jliff_v2, create_client_record_v2, create_domain_v2,
+    create_project_bundle_v2, create_project_note_v2, create_project_with_assets_v2,
+    create_prompt_template_v2, create_protection_rule_v2, create_segment_reference_v2,
+    create_segmentation_exception_v2, create_support_bundle_v2, create_user_profile_v2,
+    deduplicate_tm_v2, delete_alignment_v2, delete_artifact_record_v2, delete_automation_script_v2,
+    delete_client_record_v2, delete_delivery_target_v2, delete_domain_v2, delete_download_v2,
+    delete_exchange_rate_v2, delete_job_record_v2, delete_pipeline_preset_v2,
+    delete_project_bundle_v2, delete_project_note_v2, delete_prompt_template_v2,
+    delete_protection_rule_v2, delete_saved_segment_filter_v2, delete_segment_reference_v2,
+    delete_segmentation_exception_v2, delete_subject_v2, delete_user_profile_v2,
+    detach_project_file_v2, discard_offline_operation_v2, draft_project_from_cat_package_v2,
+    draft_project_from_email_v2, enable_database_encryption, enable_project_history_v2,
+    ensure_project_conversions_plan_v2, explain_routing_v2, export_external_review_v2,
+    export_incontext_preview_v2, export_language_assets_v2, export_project_bundle_v2,
+    export_qa_profile_v2, export_return_package_v2, export_review_bundle_v2, extract_image_text_v2,
+    extract_terms_v2, fail_translation, find_replace_targets_v2, find_segment_terms_v2,
+    find_tm_inconsistencies_v2, fix_unicode_text_v2, format_client_address_v2, get_alignment_v2,
+    get_app_settings, get_client_record_v2, get_file_statistics_v2, get_ipc_metrics_v2,
+    get_language_pair_matrix_v2, get_offline_status_v2, get_productivity_report_v2,
+    get_project_bundle_v2, get_project_domains_v2, get_project_note_v2,
+    get_project_output_naming_v2, get_project_statistics_v2, get_provider_cache_stats_v2,
+    get_provider_usage_v2, get_review_bundle_segments_v2, get_review_bundle_v2, get_segments_v2,
+    get_supported_formats_v2, get_tag_map_entries_v2, get_translation_job, get_update_status_v2,
+    get_user_profile_v2, get_workspace_lock_state_v2, health_check, import_clients_csv_v2,
+    import_external_review_v2, import_language_assets_v2, import_length_limits_v2,
+    import_qa_profile_v2, import_users_csv_v2, import_zip_as_assets_v2, install_update_v2,
+    list_active_jobs, list_alignments_v2, list_artifacts_for_file_v2, list_automation_scripts_v2,
+    list_client_records_v2, list_crash_reports_v2, list_delivery_packages_v2,
+    list_delivery_receipts_v2, list_delivery_targets_v2, list_document_snapshots_v2,
+    list_domain_events_v2, list_domains_v2, list_downloads_v2, list_exchange_rate_history_v2,
+    list_exchange_rates_v2, list_jobs_for_project_v2, list_mentioning_notes_v2,
+    list_offline_operations_v2, list_outbound_requests_v2, list_pipeline_presets_v2,
+    list_plugins_v2, list_preferred_providers_v2, list_project_history_v2, list_project_notes_v2,
+    list_project_records_v2, list_project_tm_assignments_v2, list_prompt_template_assignments_v2,
+    list_prompt_templates_v2, list_protected_terms_v2, list_protection_rules_v2,
+    list_provider_candidates_v2, list_provider_rate_limits_v2, list_provider_routing_rules_v2,
+    list_qa_findings_v2, list_qa_profiles_v2, list_saved_segment_filters_v2,
+    list_scheduled_tasks_v2, list_segment_references_v2, list_segmentation_exceptions_v2,
+    list_subject_taxonomy_v2, list_term_candidates_v2, list_termbase_domains_v2,
+    list_termbase_entries_v2, list_translation_history, list_user_profiles_v2, list_workspaces_v2,
+    lock_workspace_v2, lookup_provider_cache_v2, merge_segments_v2, merge_split_documents_v2,
+    open_review_bundle_v2, path_exists, places_autocomplete, places_resolve_details,
+    pretranslate_project_v2, preview_prompt_v2, preview_protection_rules_v2,
+    preview_segmentation_v2, probe_local_model_v2, propagate_repetitions_v2,
+    purge_provider_cache_v2, query_segments_v2, record_provider_usage_v2,
+    refresh_exchange_rates_v2, register_plugin_v2, render_segment_preview_v2,
+    repair_segment_tags_v2, replace_project_tm_assignments_v2, replace_protected_terms_v2,
+    replace_provider_routing_rules_v2, report_provider_throttled_v2, report_workspace_activity_v2,
+    request_sensitive_action_v2, reset_ipc_metrics_v2, resolve_qa_finding_v2,
+    restore_document_snapshot_v2, reverse_tm_v2, review_alignment_v2, review_term_candidates_v2,
+    rotate_client_pii_key_v2, run_pipeline_v2, run_task_now_v2, sanitize_bidi_controls_v2,
+    save_automation_script_v2, save_delivery_target_v2, save_pipeline_preset_v2,
+    save_qa_profile_v2, save_segment_filter_v2, set_active_profile_v2,
+    set_automation_script_enabled_v2, set_exchange_rate_v2, set_file_length_limit_v2,
+    set_offline_mode_v2, set_plugin_settings_v2, set_preferred_provider_v2, set_project_domains_v2,
+    set_project_output_naming_v2, set_provider_budget_v2, set_provider_rate_limit_v2,
+    set_termbase_domains_v2, set_workspace_passphrase_v2, spellcheck_document_v2,
+    spellcheck_segment_v2, split_document_v2, split_segment_v2, stage_dropped_files_v2,
+    start_download_v2, start_translation, store_provider_cache_v2, stream_segment_translation_v2,
+    suggest_subjects_v2, swap_language_pair_v2, switch_workspace_v2, sync_jliff_to_xliff_v2,
+    test_automation_script_v2, test_connectivity_v2, translate_segment_v2,
+    translate_with_local_model_v2, unlock_workspace_v2, unregister_plugin_v2, update_app_folder,
+    update_artifact_status_v2, update_asset_role_rules, update_auto_convert_on_open,
+    update_auto_lock_minutes, update_base_currency, update_client_record_v2,
+    update_conversion_status_v2, update_crash_report_upload, update_default_languages,
+    update_domain_v2, update_event_webhook_url, update_job_progress_v2, update_job_status_v2,
+    update_length_limit_enforcement, update_local_model_settings, update_max_parallel_conversions,
+    update_mock_provider_settings, update_notifications, update_ocr_command,
+    update_output_name_templates, update_plugin_runtime_settings, update_project_bundle_v2,
+    update_project_file_role_v2, update_project_note_v2, update_project_number_template,
+    update_prompt_template_v2, update_propagation_settings, update_protection_rule_v2,
+    update_provider_cache_ttl, update_proxy_settings, update_segment_reference_v2,
+    update_segment_status_v2, update_snapshot_settings, update_theme, update_ui_language,
+    update_unicode_normalization, update_user_profile_v2, update_vies_lookup, update_xliff_version,
+    upload_crash_reports_v2, upload_delivery_package_v2, upsert_artifact_record_v2,
+    upsert_job_record_v2, upsert_subject_v2, validate_tax_id_v2, validate_xliff_v2,
+};
+pub use state::{
+    ActiveProfile, GuestSession, OfflineMode, ReadOnlyContext, SensitiveActionTokens,
+    TranslationState, TranslationStreams, WorkspaceLock,
 };
-pub use state::TranslationState;
@@ -1,12 +1,17 @@
 use std::{
     collections::HashMap,
+    path::PathBuf,
     sync::{Arc, Mutex},
+    time::{Duration, Instant},
 };
 
+use chrono::{DateTime, Utc};
 use serde::Serialize;
+use tokio::sync::Notify;
 use uuid::Uuid;
 
-use super::dto::{StoredTranslationJob, TranslationRequest, TranslationStage};
+use super::dto::{OfflineStatusDto, StoredTranslationJob, TranslationRequest, TranslationStage};
+use crate::guest::ReviewBundleManifest;
 
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -86,3 +91,291 @@ impl TranslationState {
         }
     }
 }
+
+/// Cancellation handles of running streaming translations, keyed by job id.
+#[derive(Clone, Default)]
+pub struct TranslationStreams {
+    inner: Arc<Mutex<HashMap<Uuid, Arc<Notify>>>>,
+}
+
+impl TranslationStreams {
+    pub fn register(&self, job_id: Uuid) -> Arc<Notify> {
+        let cancel = Arc::new(Notify::new());
+        if let Ok(mut map) = self.inner.lock() {
+            map.insert(job_id, cancel.clone());
+        }
+        cancel
+    }
+
+    /// Signals the stream to stop; returns `false` when it is not running.
+    pub fn cancel(&self, job_id: Uuid) -> bool {
+        let cancel = self
+            .inner
+            .lock()
+            .ok()
+            .and_then(|map| map.get(&job_id).cloned());
+        match cancel {
+            Some(cancel) => {
+                // `notify_one` keeps the permit if the stream is not awaiting it yet.
+                cancel.notify_one();
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn finish(&self, job_id: Uuid) {
+        if let Ok(mut map) = self.inner.lock() {
+            map.remove(&job_id);
+        }
+    }
+}
+
+/// The user profile signed in to the app, as reported by the frontend. Confirmation tokens
+/// are only issued to it.
+#[derive(Clone, Default)]
+pub struct ActiveProfile {
+    inner: Arc<Mutex<Option<Uuid>>>,
+}
+
+impl ActiveProfile {
+    pub fn get(&self) -> Option<Uuid> {
+        self.inner.lock().ok().and_then(|profile| *profile)
+    }
+
+    /// Replaces the active profile; returns whether it changed.
+    pub fn set(&self, user_uuid: Option<Uuid>) -> bool {
+        match self.inner.lock() {
+            Ok(mut profile) => std::mem::replace(&mut *profile, user_uuid) != user_uuid,
+            Err(_) => false,
+        }
+    }
+}
+
+/// Single-use confirmation tokens for sensitive settings changes, keyed by token.
+#[derive(Clone, Default)]
+pub struct SensitiveActionTokens {
+    inner: Arc<Mutex<HashMap<String, SensitiveActionGrant>>>,
+}
+
+struct SensitiveActionGrant {
+    action: String,
+    user_uuid: Uuid,
+    expires_at: Instant,
+}
+
+impl SensitiveActionTokens {
+    /// Issues a token allowing `user_uuid` to perform `action` once within `ttl`. Expired
+    /// tokens are dropped on the way.
+    pub fn issue(&self, action: &str, user_uuid: Uuid, ttl: Duration) -> String {
+        let token = Uuid::new_v4().simple().to_string();
+        if let Ok(mut map) = self.inner.lock() {
+            let now = Instant::now();
+            map.retain(|_, grant| grant.expires_at > now);
+            map.insert(
+                token.clone(),
+                SensitiveActionGrant {
+                    action: action.to_string(),
+                    user_uuid,
+                    expires_at: now + ttl,
+                },
+            );
+        }
+        token
+    }
+
+    /// Redeems `token` for `action`, returning the user it was issued to. A token is spent by
+    /// its first redemption, even for another action.
+    pub fn redeem(&self, token: &str, action: &str) -> Option<Uuid> {
+        let grant = self.inner.lock().ok()?.remove(token)?;
+        (grant.action == action && grant.expires_at > Instant::now()).then_some(grant.user_uuid)
+    }
+
+    /// Drops every outstanding token.
+    pub fn clear(&self) {
+        if let Ok(mut map) = self.inner.lock() {
+            map.clear();
+        }
+    }
+}
+
+/// Whether the workspace is locked and when the user was last active.
+#[derive(Clone)]
+pub struct WorkspaceLock {
+    inner: Arc<Mutex<WorkspaceLockState>>,
+}
+
+struct WorkspaceLockState {
+    locked: bool,
+    last_activity: Instant,
+}
+
+impl Default for WorkspaceLock {
+    fn default() -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(WorkspaceLockState {
+                locked: false,
+                last_activity: Instant::now(),
+            })),
+        }
+    }
+}
+
+impl WorkspaceLock {
+    pub fn is_locked(&self) -> bool {
+        self.inner.lock().map(|state| state.locked).unwrap_or(true)
+    }
+
+    /// Records user activity; ignored while locked.
+    pub fn touch(&self) {
+        if let Ok(mut state) = self.inner.lock()
+            && !state.locked
+        {
+            state.last_activity = Instant::now();
+        }
+    }
+
+    /// Time since the last recorded activity.
+    pub fn idle_for(&self) -> Duration {
+        self.inner
+            .lock()
+            .map(|state| state.last_activity.elapsed())
+            .unwrap_or_default()
+    }
+
+    /// Locks the workspace; returns `false` when it already was.
+    pub fn lock(&self) -> bool {
+        match self.inner.lock() {
+            Ok(mut state) => !std::mem::replace(&mut state.locked, true),
+            Err(_) => false,
+        }
+    }
+
+    /// Unlocks the workspace and restarts the inactivity clock.
+    pub fn unlock(&self) {
+        if let Ok(mut state) = self.inner.lock() {
+            state.locked = false;
+            state.last_activity = Instant::now();
+        }
+    }
+}
+
+/// The review bundle open in read-only guest mode, if any. While one is open, commands
+/// outside the guest allowlist are rejected (see `guest::enforce_read_only`).
+#[derive(Clone, Default)]
+pub struct ReadOnlyContext {
+    inner: Arc<Mutex<Option<GuestSession>>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct GuestSession {
+    pub bundle_path: PathBuf,
+    pub manifest: ReviewBundleManifest,
+}
+
+impl ReadOnlyContext {
+    pub fn is_active(&self) -> bool {
+        // A poisoned lock keeps the app read-only rather than opening it up.
+        self.inner
+            .lock()
+            .map(|session| session.is_some())
+            .unwrap_or(true)
+    }
+
+    pub fn session(&self) -> Option<GuestSession> {
+        self.inner.lock().ok()?.clone()
+    }
+
+    /// Opens `session`, replacing the bundle open before.
+    pub fn enter(&self, session: GuestSession) {
+        if let Ok(mut current) = self.inner.lock() {
+            *current = Some(session);
+        }
+    }
+
+    /// Leaves guest mode; returns `false` when no bundle was open.
+    pub fn leave(&self) -> bool {
+        self.inner
+            .lock()
+            .map(|mut session| session.take().is_some())
+            .unwrap_or(false)
+    }
+}
+
+/// Whether outbound requests should be attempted. The app is offline while the user forces it
+/// or the last connectivity probe failed; features then fail fast instead of timing out.
+#[derive(Clone, Default)]
+pub struct OfflineMode {
+    inner: Arc<Mutex<OfflineModeState>>,
+}
+
+#[derive(Default)]
+struct OfflineModeState {
+    forced: bool,
+    detected: bool,
+    checked_at: Option<DateTime<Utc>>,
+}
+
+impl OfflineModeState {
+    fn is_offline(&self) -> bool {
+        self.forced || self.detected
+    }
+}
+
+impl OfflineMode {
+    pub fn is_offline(&self) -> bool {
+        // A poisoned lock keeps features usable; requests then fail on their own.
+        self.inner
+            .lock()
+            .map(|state| state.is_offline())
+            .unwrap_or(false)
+    }
+
+    /// Whether the user switched offline mode on.
+    pub fn is_forced(&self) -> bool {
+        self.inner.lock().map(|state| state.forced).unwrap_or(false)
+    }
+
+    /// Forces offline mode on or off; returns whether the app went on- or offline.
+    pub fn set_forced(&self, forced: bool) -> bool {
+        match self.inner.lock() {
+            Ok(mut state) => {
+                let was_offline = state.is_offline();
+                state.forced = forced;
+                was_offline != state.is_offline()
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// Records the outcome of a connectivity probe; returns whether the app went on- or
+    /// offline.
+    pub fn record_probe(&self, reachable: bool) -> bool {
+        match self.inner.lock() {
+            Ok(mut state) => {
+                let was_offline = state.is_offline();
+                state.detected = !reachable;
+                state.checked_at = Some(Utc::now());
+                was_offline != state.is_offline()
+            }
+            Err(_) => false,
+        }
+    }
+
+    pub fn status(&self) -> OfflineStatusDto {
+        match self.inner.lock() {
+            Ok(state) => OfflineStatusDto {
+                offline: state.is_offline(),
+                forced: state.forced,
+                detected_offline: state.detected,
+                checked_at: state.checked_at.map(|checked_at| checked_at.to_rfc3339()),
+            },
+            Err(_) => OfflineStatusDto {
+                offline: false,
+                forced: false,
+                detected_offline: false,
+                checked_at: None,
+            },
+        }
+    }
+}
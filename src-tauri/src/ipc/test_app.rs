@@ -0,0 +1,188 @@
+//! Mock Tauri app for integration tests of IPC commands.
+//!
+//! [`TestAppBuilder`] wires the state the v2 commands expect — an in-memory [`DbManager`]
+//! with the schema applied, a [`SettingsManager`] rooted in a throwaway directory and the
+//! in-process services — into a `tauri::test` mock app, and records the events commands
+//! emit so tests can assert on them. Nothing touches the network or the user's app folder.
+
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use serde_json::Value;
+use sqlx::sqlite::SqlitePoolOptions;
+use tauri::ipc::InvokeError;
+use tauri::test::{MockRuntime, mock_builder, mock_context, noop_assets};
+use tauri::{App, AppHandle, Listener, Manager, State};
+use uuid::Uuid;
+
+use super::commands::projects_v2::test_support::default_settings;
+use super::state::{
+    ActiveProfile, OfflineMode, ReadOnlyContext, SensitiveActionTokens, TranslationState,
+    TranslationStreams, WorkspaceLock,
+};
+use crate::db::{DbManager, initialise_schema};
+use crate::downloads::Downloader;
+use crate::providers::{LocalModelClient, ProviderRateLimiter};
+use crate::secrets::SecretsVault;
+use crate::settings::{AppSettings, SettingsManager};
+use crate::spellcheck::SpellcheckService;
+use crate::tax::vies::ViesClient;
+
+type SettingsOverride = Box<dyn FnOnce(&mut AppSettings)>;
+
+/// An event emitted while a test ran.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CapturedEvent {
+    pub name: String,
+    /// The payload as JSON; `Value::Null` for events without one.
+    pub payload: Value,
+}
+
+#[derive(Default)]
+pub struct TestAppBuilder {
+    settings_override: Option<SettingsOverride>,
+    captured_events: Vec<String>,
+}
+
+impl TestAppBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adjusts the default test settings before the settings manager is created.
+    pub fn with_settings(mut self, configure: impl FnOnce(&mut AppSettings) + 'static) -> Self {
+        self.settings_override = Some(Box::new(configure));
+        self
+    }
+
+    /// Records every emission of `event` for [`TestApp::events`].
+    pub fn capture_event(mut self, event: impl Into<String>) -> Self {
+        self.captured_events.push(event.into());
+        self
+    }
+
+    pub async fn build(self) -> TestApp {
+        let app_folder =
+            std::env::temp_dir().join(format!("weg-translator-test-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&app_folder).expect("failed to create test app folder");
+
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect(":memory:")
+            .await
+            .expect("failed to open in-memory SQLite");
+        initialise_schema(&pool)
+            .await
+            .expect("schema bootstrap should succeed");
+
+        let mut settings = default_settings(app_folder.clone());
+        if let Some(configure) = self.settings_override {
+            configure(&mut settings);
+        }
+
+        let app = mock_builder()
+            .manage(DbManager::from_pool(pool))
+            .manage(SettingsManager::new(
+                app_folder.join("settings.yaml"),
+                settings,
+            ))
+            .manage(TranslationState::new())
+            .manage(TranslationStreams::default())
+            .manage(SensitiveActionTokens::default())
+            .manage(ActiveProfile::default())
+            .manage(WorkspaceLock::default())
+            .manage(OfflineMode::default())
+            .manage(ReadOnlyContext::default())
+            .manage(ProviderRateLimiter::new(Vec::new()))
+            .manage(LocalModelClient::new())
+            .manage(Downloader::new())
+            .manage(SpellcheckService::new())
+            .manage(ViesClient::new())
+            .manage(SecretsVault::in_memory())
+            .build(mock_context(noop_assets()))
+            .expect("mock app should build");
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        for name in self.captured_events {
+            let events = Arc::clone(&events);
+            let event_name = name.clone();
+            app.listen_any(name, move |event| {
+                let payload = serde_json::from_str(event.payload()).unwrap_or(Value::Null);
+                events
+                    .lock()
+                    .expect("captured events poisoned")
+                    .push(CapturedEvent {
+                        name: event_name.clone(),
+                        payload,
+                    });
+            });
+        }
+
+        TestApp {
+            app,
+            app_folder,
+            events,
+        }
+    }
+}
+
+/// A running mock app; the app folder is deleted on drop.
+pub struct TestApp {
+    app: App<MockRuntime>,
+    app_folder: PathBuf,
+    events: Arc<Mutex<Vec<CapturedEvent>>>,
+}
+
+impl TestApp {
+    /// Managed state, ready to pass to a command.
+    pub fn state<T: Send + Sync + 'static>(&self) -> State<'_, T> {
+        self.app.state::<T>()
+    }
+
+    pub fn handle(&self) -> &AppHandle<MockRuntime> {
+        self.app.handle()
+    }
+
+    pub fn db(&self) -> &DbManager {
+        self.state::<DbManager>().inner()
+    }
+
+    pub fn settings(&self) -> &SettingsManager {
+        self.state::<SettingsManager>().inner()
+    }
+
+    pub fn app_folder(&self) -> &Path {
+        &self.app_folder
+    }
+
+    /// Events captured so far, in emission order.
+    pub fn events(&self) -> Vec<CapturedEvent> {
+        self.events
+            .lock()
+            .expect("captured events poisoned")
+            .clone()
+    }
+
+    /// Payloads of the captured emissions of `name`.
+    pub fn event_payloads(&self, name: &str) -> Vec<Value> {
+        self.events()
+            .into_iter()
+            .filter(|event| event.name == name)
+            .map(|event| event.payload)
+            .collect()
+    }
+}
+
+impl Drop for TestApp {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.app_folder);
+    }
+}
+
+/// The user-facing message of a command error.
+pub fn error_message(error: &InvokeError) -> String {
+    match &error.0 {
+        Value::String(message) => message.clone(),
+        other => other.to_string(),
+    }
+}
@@ -0,0 +1,421 @@
+//! Source/target alignment for placing inline codes in a translation that lacks them.
+//!
+//! Machine translation usually returns plain text, leaving the linguist to put every
+//! `{{...}}` placeholder back by hand. [`align_segment`] aligns the words of the source with
+//! the words of the target and suggests where each missing placeholder belongs:
+//!
+//! 1. words are linked one-to-one by a score mixing character-bigram similarity (identical
+//!    words, numbers, names and cognates) with a prior for similar relative positions;
+//! 2. links that keep source order become anchors of a piecewise-linear map from source to
+//!    target character offsets;
+//! 3. a placeholder glued to a linked word follows that word; any other placeholder lands
+//!    on the mapped offset, snapped to the nearest word boundary, and is flagged as an
+//!    estimate.
+//!
+//! Placeholders already in the target stay where they are.
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use super::restructure::placeholder_spans;
+
+/// Lowest character similarity for two words to be linked.
+const MIN_SIMILARITY: f64 = 0.5;
+/// Weight of the relative-position prior in a link score.
+const POSITION_WEIGHT: f64 = 0.25;
+
+/// How much the alignment trusts a suggested position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlacementConfidence {
+    /// The placeholder is attached to a source word whose translation was found.
+    High,
+    /// The position was interpolated between linked words.
+    Low,
+}
+
+/// A source word linked to a target word.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WordLink {
+    pub source: String,
+    pub target: String,
+    pub score: f64,
+}
+
+/// Suggested position of a placeholder missing from the target.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagPlacement {
+    pub placeholder: String,
+    /// Character offset of the placeholder in the suggested target.
+    pub offset: usize,
+    pub confidence: PlacementConfidence,
+}
+
+/// Outcome of [`align_segment`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SegmentAlignment {
+    pub links: Vec<WordLink>,
+    pub placements: Vec<TagPlacement>,
+    /// The target with every suggested placeholder inserted.
+    pub suggested_target: String,
+}
+
+/// A word of a placeholder-free text, with character offsets.
+#[derive(Debug, Clone)]
+struct Word {
+    start: usize,
+    end: usize,
+    folded: String,
+}
+
+/// Aligns `source` with `target` and suggests positions for the source placeholders the
+/// target is missing.
+pub fn align_segment(source: &str, target: &str) -> SegmentAlignment {
+    let (source_plain, source_tags) = strip_placeholders(source);
+    let (target_plain, target_tags) = strip_placeholders(target);
+    let source_words = words(&source_plain);
+    let target_words = words(&target_plain);
+    let source_len = source_plain.chars().count();
+    let target_len = target_plain.chars().count();
+
+    let pairs = link_words(&source_words, &target_words, source_len, target_len);
+    let linked: HashMap<usize, usize> = pairs.iter().map(|(s, t, _)| (*s, *t)).collect();
+    let anchors = anchors(&pairs, &source_words, &target_words, source_len, target_len);
+
+    let mut available: HashMap<&str, usize> = HashMap::new();
+    for (_, token) in &target_tags {
+        *available.entry(token.as_str()).or_default() += 1;
+    }
+    let mut missing = Vec::new();
+    for (position, token) in &source_tags {
+        match available.get_mut(token.as_str()) {
+            Some(count) if *count > 0 => *count -= 1,
+            _ => missing.push((*position, token.clone())),
+        }
+    }
+
+    let mut placed = Vec::with_capacity(missing.len());
+    let mut floor = 0;
+    for (position, token) in missing {
+        let after_word = source_words
+            .iter()
+            .position(|word| word.end == position)
+            .and_then(|index| linked.get(&index))
+            .map(|target| target_words[*target].end);
+        let before_word = source_words
+            .iter()
+            .position(|word| word.start == position)
+            .and_then(|index| linked.get(&index))
+            .map(|target| target_words[*target].start);
+        let (offset, confidence) = match (closes(&token), after_word, before_word) {
+            (true, Some(offset), _) | (_, _, Some(offset)) | (_, Some(offset), None) => {
+                (offset, PlacementConfidence::High)
+            }
+            _ => {
+                let estimate = map_offset(&anchors, position);
+                (
+                    snap(&target_words, target_len, estimate, &token),
+                    PlacementConfidence::Low,
+                )
+            }
+        };
+        // Keep the source order, so paired codes never cross.
+        let offset = offset.max(floor);
+        floor = offset;
+        placed.push((offset, token, confidence));
+    }
+
+    let links = pairs
+        .iter()
+        .map(|(s, t, score)| WordLink {
+            source: slice(&source_plain, &source_words[*s]),
+            target: slice(&target_plain, &target_words[*t]),
+            score: (score * 100.0).round() / 100.0,
+        })
+        .collect();
+    let (suggested_target, placements) = rebuild(&target_plain, &target_tags, placed);
+    SegmentAlignment {
+        links,
+        placements,
+        suggested_target,
+    }
+}
+
+/// Removes placeholders from `text`, returning the plain text and each placeholder with its
+/// character offset in it.
+fn strip_placeholders(text: &str) -> (String, Vec<(usize, String)>) {
+    let mut plain = String::with_capacity(text.len());
+    let mut tags = Vec::new();
+    let mut cursor = 0;
+    for (start, end) in placeholder_spans(text) {
+        plain.push_str(&text[cursor..start]);
+        tags.push((plain.chars().count(), text[start..end].to_string()));
+        cursor = end;
+    }
+    plain.push_str(&text[cursor..]);
+    (plain, tags)
+}
+
+/// Splits text into words: runs of letters and digits, with every ideograph or kana on its
+/// own since those scripts do not separate words.
+fn words(text: &str) -> Vec<Word> {
+    let mut words = Vec::new();
+    let mut current: Option<Word> = None;
+    for (index, ch) in text.chars().enumerate() {
+        if is_ideographic(ch) {
+            words.extend(current.take());
+            words.push(Word {
+                start: index,
+                end: index + 1,
+                folded: ch.to_string(),
+            });
+        } else if ch.is_alphanumeric() {
+            let word = current.get_or_insert_with(|| Word {
+                start: index,
+                end: index,
+                folded: String::new(),
+            });
+            word.end = index + 1;
+            word.folded.extend(ch.to_lowercase());
+        } else {
+            words.extend(current.take());
+        }
+    }
+    words.extend(current);
+    words
+}
+
+fn is_ideographic(ch: char) -> bool {
+    matches!(ch, '\u{3040}'..='\u{30FF}' | '\u{3400}'..='\u{4DBF}' | '\u{4E00}'..='\u{9FFF}')
+}
+
+/// Links words one-to-one, best scores first, returning `(source, target, score)` triples.
+fn link_words(
+    source: &[Word],
+    target: &[Word],
+    source_len: usize,
+    target_len: usize,
+) -> Vec<(usize, usize, f64)> {
+    let mut candidates = Vec::new();
+    for (s, source_word) in source.iter().enumerate() {
+        for (t, target_word) in target.iter().enumerate() {
+            let similarity = similarity(&source_word.folded, &target_word.folded);
+            if similarity < MIN_SIMILARITY {
+                continue;
+            }
+            let drift = (relative_center(source_word, source_len)
+                - relative_center(target_word, target_len))
+            .abs();
+            let score = similarity * (1.0 - POSITION_WEIGHT) + (1.0 - drift) * POSITION_WEIGHT;
+            candidates.push((s, t, score));
+        }
+    }
+    candidates.sort_by(|a, b| b.2.total_cmp(&a.2).then(a.0.cmp(&b.0)));
+
+    let mut source_used = vec![false; source.len()];
+    let mut target_used = vec![false; target.len()];
+    let mut links = Vec::new();
+    for (s, t, score) in candidates {
+        if source_used[s] || target_used[t] {
+            continue;
+        }
+        source_used[s] = true;
+        target_used[t] = true;
+        links.push((s, t, score));
+    }
+    links.sort_by_key(|(s, _, _)| *s);
+    links
+}
+
+/// Dice coefficient of the character bigrams of two words; 1 for identical words.
+fn similarity(a: &str, b: &str) -> f64 {
+    if a == b {
+        return 1.0;
+    }
+    let bigrams = |word: &str| -> Vec<(char, char)> {
+        let chars: Vec<char> = word.chars().collect();
+        chars.windows(2).map(|pair| (pair[0], pair[1])).collect()
+    };
+    let (left, mut right) = (bigrams(a), bigrams(b));
+    if left.is_empty() || right.is_empty() {
+        return 0.0;
+    }
+    let total = left.len() + right.len();
+    let mut shared = 0;
+    for bigram in left {
+        if let Some(index) = right.iter().position(|candidate| *candidate == bigram) {
+            right.swap_remove(index);
+            shared += 1;
+        }
+    }
+    (2 * shared) as f64 / total as f64
+}
+
+fn relative_center(word: &Word, len: usize) -> f64 {
+    (word.start + word.end) as f64 / 2.0 / len.max(1) as f64
+}
+
+/// Points of the source-to-target offset map: both ends of the texts and of every linked
+/// word pair that keeps the source order.
+fn anchors(
+    links: &[(usize, usize, f64)],
+    source: &[Word],
+    target: &[Word],
+    source_len: usize,
+    target_len: usize,
+) -> Vec<(usize, usize)> {
+    let mut anchors = vec![(0, 0)];
+    for (s, t, _) in links {
+        let (source_word, target_word) = (&source[*s], &target[*t]);
+        let &(last_source, last_target) = anchors.last().expect("anchors start non-empty");
+        if source_word.start >= last_source && target_word.start >= last_target {
+            anchors.push((source_word.start, target_word.start));
+            anchors.push((source_word.end, target_word.end));
+        }
+    }
+    let &(last_source, last_target) = anchors.last().expect("anchors start non-empty");
+    if source_len >= last_source && target_len >= last_target {
+        anchors.push((source_len, target_len));
+    }
+    anchors
+}
+
+/// Maps a source offset onto the target by linear interpolation between anchors.
+fn map_offset(anchors: &[(usize, usize)], position: usize) -> f64 {
+    for pair in anchors.windows(2) {
+        let ((source_start, target_start), (source_end, target_end)) = (pair[0], pair[1]);
+        if position <= source_end {
+            if source_end == source_start {
+                return target_start as f64;
+            }
+            let ratio =
+                (position - source_start.min(position)) as f64 / (source_end - source_start) as f64;
+            return target_start as f64 + ratio * (target_end - target_start) as f64;
+        }
+    }
+    anchors.last().map_or(0.0, |(_, target)| *target as f64)
+}
+
+/// The word boundary nearest to `estimate`: word starts for opening codes, word ends for
+/// closing codes, either for standalone ones.
+fn snap(words: &[Word], len: usize, estimate: f64, token: &str) -> usize {
+    let mut boundaries = vec![0, len];
+    for word in words {
+        if !closes(token) {
+            boundaries.push(word.start);
+        }
+        if !opens(token) {
+            boundaries.push(word.end);
+        }
+    }
+    boundaries
+        .into_iter()
+        .min_by(|a, b| {
+            (*a as f64 - estimate)
+                .abs()
+                .total_cmp(&(*b as f64 - estimate).abs())
+        })
+        .unwrap_or(0)
+}
+
+fn opens(token: &str) -> bool {
+    token.ends_with(":start}}") || token.starts_with("{{sc:")
+}
+
+fn closes(token: &str) -> bool {
+    token.ends_with(":end}}") || token.starts_with("{{ec:")
+}
+
+fn slice(text: &str, word: &Word) -> String {
+    text.chars()
+        .skip(word.start)
+        .take(word.end - word.start)
+        .collect()
+}
+
+/// Inserts the target's own placeholders and the suggested ones into the plain target,
+/// returning the text and where each suggested placeholder ended up.
+fn rebuild(
+    plain: &str,
+    existing: &[(usize, String)],
+    suggested: Vec<(usize, String, PlacementConfidence)>,
+) -> (String, Vec<TagPlacement>) {
+    let mut inserts: Vec<(usize, usize, String, Option<PlacementConfidence>)> = existing
+        .iter()
+        .enumerate()
+        .map(|(order, (position, token))| (*position, order, token.clone(), None))
+        .collect();
+    inserts.extend(suggested.into_iter().enumerate().map(
+        |(order, (position, token, confidence))| {
+            (position, existing.len() + order, token, Some(confidence))
+        },
+    ));
+    inserts.sort_by_key(|(position, order, _, _)| (*position, *order));
+
+    let mut text = String::with_capacity(plain.len());
+    let mut placements = Vec::new();
+    let mut written = 0;
+    let mut chars = plain.chars();
+    for (position, _, token, confidence) in inserts {
+        text.extend(chars.by_ref().take(position - written.min(position)));
+        written = written.max(position);
+        if let Some(confidence) = confidence {
+            placements.push(TagPlacement {
+                offset: text.chars().count(),
+                placeholder: token.clone(),
+                confidence,
+            });
+        }
+        text.push_str(&token);
+    }
+    text.extend(chars);
+    (text, placements)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn places_codes_next_to_linked_words() {
+        let alignment = align_segment(
+            "Click {{pc:1:start}}Save{{pc:1:end}} to keep {{ph:2}}version 2.",
+            "Klicken Sie auf Save, um Version 2 zu behalten.",
+        );
+        assert_eq!(
+            alignment.suggested_target,
+            "Klicken Sie auf {{pc:1:start}}Save{{pc:1:end}}, um {{ph:2}}Version 2 zu behalten."
+        );
+        assert!(
+            alignment
+                .placements
+                .iter()
+                .all(|placement| placement.confidence == PlacementConfidence::High)
+        );
+        assert_eq!(alignment.placements[0].offset, 16);
+        assert!(
+            alignment
+                .links
+                .iter()
+                .any(|link| link.source == "version" && link.target == "Version")
+        );
+    }
+
+    #[test]
+    fn interpolates_unattached_codes_and_keeps_existing_ones() {
+        let alignment = align_segment("Hello {{ph:1}} world{{ph:2}}", "Hallo Welt{{ph:2}}");
+        assert_eq!(alignment.suggested_target, "Hallo {{ph:1}}Welt{{ph:2}}");
+        assert_eq!(
+            alignment.placements,
+            [TagPlacement {
+                placeholder: "{{ph:1}}".into(),
+                offset: 6,
+                confidence: PlacementConfidence::Low,
+            }]
+        );
+    }
+}
@@ -0,0 +1,229 @@
+//! Leverage analysis of JLIFF documents against the translation memory.
+//!
+//! Every segment with a source falls into one band: an exact TM match, a repetition of a
+//! source seen earlier in the analysis, a fuzzy band by best TM score, or new. Exact
+//! matches win over repetitions so the first and later occurrences of a sentence the TM
+//! already knows are priced the same; a repetition wins over any fuzzy match because the
+//! first occurrence will be in the TM by the time the later ones are translated. Words are
+//! counted with the source language's [`WordCountStrategy`].
+
+use std::collections::HashSet;
+
+use super::model::JliffDocument;
+use super::statistics::{WordCountStrategy, source_word_count};
+use crate::tm::{TmMatcher, normalize};
+
+/// Lowest TM score that still counts as a fuzzy match.
+pub const MIN_FUZZY_SCORE: u8 = 50;
+
+/// Analysis band of a segment, in report order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MatchBand {
+    Exact,
+    Repetition,
+    Fuzzy95,
+    Fuzzy85,
+    Fuzzy75,
+    Fuzzy50,
+    New,
+}
+
+impl MatchBand {
+    pub const ALL: [MatchBand; 7] = [
+        MatchBand::Exact,
+        MatchBand::Repetition,
+        MatchBand::Fuzzy95,
+        MatchBand::Fuzzy85,
+        MatchBand::Fuzzy75,
+        MatchBand::Fuzzy50,
+        MatchBand::New,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            MatchBand::Exact => "100",
+            MatchBand::Repetition => "repetition",
+            MatchBand::Fuzzy95 => "95-99",
+            MatchBand::Fuzzy85 => "85-94",
+            MatchBand::Fuzzy75 => "75-84",
+            MatchBand::Fuzzy50 => "50-74",
+            MatchBand::New => "new",
+        }
+    }
+
+    /// Fuzzy band of a TM score below 100.
+    fn for_fuzzy_score(score: u8) -> Self {
+        match score {
+            95.. => MatchBand::Fuzzy95,
+            85..=94 => MatchBand::Fuzzy85,
+            75..=84 => MatchBand::Fuzzy75,
+            MIN_FUZZY_SCORE..=74 => MatchBand::Fuzzy50,
+            _ => MatchBand::New,
+        }
+    }
+
+    fn index(self) -> usize {
+        MatchBand::ALL
+            .iter()
+            .position(|band| *band == self)
+            .expect("every band is listed in ALL")
+    }
+}
+
+/// Segments and source words of one band.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BandCount {
+    pub segments: usize,
+    pub words: usize,
+}
+
+/// Counts per [`MatchBand`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LeverageBreakdown {
+    counts: [BandCount; 7],
+}
+
+impl LeverageBreakdown {
+    pub fn get(&self, band: MatchBand) -> BandCount {
+        self.counts[band.index()]
+    }
+
+    pub fn record(&mut self, band: MatchBand, words: usize) {
+        let count = &mut self.counts[band.index()];
+        count.segments += 1;
+        count.words += words;
+    }
+
+    pub fn add(&mut self, other: &LeverageBreakdown) {
+        for (count, other) in self.counts.iter_mut().zip(&other.counts) {
+            count.segments += other.segments;
+            count.words += other.words;
+        }
+    }
+
+    pub fn total(&self) -> BandCount {
+        self.counts
+            .iter()
+            .fold(BandCount::default(), |total, count| BandCount {
+                segments: total.segments + count.segments,
+                words: total.words + count.words,
+            })
+    }
+}
+
+/// Analyses documents one after the other, so repetitions are found across all of them.
+pub struct LeverageAnalyzer<'a> {
+    matcher: &'a TmMatcher,
+    seen: HashSet<String>,
+}
+
+impl<'a> LeverageAnalyzer<'a> {
+    pub fn new(matcher: &'a TmMatcher) -> Self {
+        Self {
+            matcher,
+            seen: HashSet::new(),
+        }
+    }
+
+    pub fn analyze(&mut self, document: &JliffDocument) -> LeverageBreakdown {
+        let strategy = WordCountStrategy::for_source(document);
+        let mut breakdown = LeverageBreakdown::default();
+        for unit in &document.transunits {
+            let key = normalize(&unit.source);
+            if key.is_empty() {
+                continue;
+            }
+            let best = self.matcher.best_match(&unit.source, MIN_FUZZY_SCORE);
+            let repeated = !self.seen.insert(key);
+            let band = match best {
+                Some(found) if found.is_exact() => MatchBand::Exact,
+                _ if repeated => MatchBand::Repetition,
+                Some(found) => MatchBand::for_fuzzy_score(found.score),
+                None => MatchBand::New,
+            };
+            breakdown.record(band, source_word_count(unit, strategy));
+        }
+        breakdown
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jliff::model::TransUnit;
+    use crate::tm::TmCandidate;
+
+    fn document(sources: &[&str]) -> JliffDocument {
+        JliffDocument {
+            project_name: "Demo".into(),
+            project_id: "demo".into(),
+            file: "demo.xlf".into(),
+            user: "tester".into(),
+            source_language: "en-US".into(),
+            target_language: "de-DE".into(),
+            transunits: sources
+                .iter()
+                .enumerate()
+                .map(|(index, source)| TransUnit {
+                    unit_id: format!("u{index}"),
+                    transunit_id: format!("u{index}-s1"),
+                    source: source.to_string(),
+                    target_translation: String::new(),
+                    target_qa_1: None,
+                    target_qa_2: None,
+                    target_postedit: None,
+                    translation_notes: None,
+                    qa_notes: None,
+                    source_notes: None,
+                    metadata: None,
+                    status: None,
+                    sub_state: None,
+                    notes: Vec::new(),
+                    propagated_from: None,
+                })
+                .collect(),
+            segment_operations: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn bands_exact_repeated_fuzzy_and_new_segments() {
+        let matcher = TmMatcher::new([
+            TmCandidate {
+                source: "Save the file.".into(),
+                target: "Datei speichern.".into(),
+                penalty: 0,
+            },
+            TmCandidate {
+                source: "Close the window now.".into(),
+                target: "Fenster jetzt schließen.".into(),
+                penalty: 0,
+            },
+        ]);
+        let mut analyzer = LeverageAnalyzer::new(&matcher);
+
+        let first = analyzer.analyze(&document(&[
+            "Save the file.",
+            "Close the window now!",
+            "Print {{ph:1}} everything",
+            "",
+        ]));
+        assert_eq!(first.get(MatchBand::Exact).words, 3);
+        assert_eq!(first.get(MatchBand::Fuzzy95).segments, 1);
+        assert_eq!(first.get(MatchBand::New).words, 2);
+        assert_eq!(first.total().segments, 3);
+
+        let second = analyzer.analyze(&document(&[
+            "Save  the file.",
+            "Print {{ph:1}} everything",
+            "Close the window now!",
+        ]));
+        assert_eq!(second.get(MatchBand::Exact).segments, 1);
+        assert_eq!(second.get(MatchBand::Repetition).segments, 2);
+        assert_eq!(second.get(MatchBand::Repetition).words, 6);
+
+        let mut total = first;
+        total.add(&second);
+        assert_eq!(total.total().segments, 6);
+    }
+}
@@ -0,0 +1,385 @@
+//! Casing and punctuation fix-ups applied to a target when it is confirmed.
+//!
+//! Each [`AutoFix`] is a small, mechanical correction translators would otherwise make by
+//! hand: capitalizing the first letter like the source, mirroring the source's closing
+//! punctuation, collapsing double spaces and turning straight quotes into the typographic
+//! quotes of the target locale. Fixes are opt-in per QA profile under their
+//! [`AutoFix::check_type`]. Text inside `{{...}}` placeholders is never touched.
+
+/// A fix-up applied on confirm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AutoFix {
+    /// Capitalizes the first letter of the target when the source starts with a capital.
+    LeadingCapital,
+    /// Adds the source's closing punctuation when the target has none, and drops a closing
+    /// full stop the source does not have.
+    TrailingPunctuation,
+    /// Collapses runs of spaces into one, unless the source has such runs itself.
+    DoubleSpaces,
+    /// Replaces straight quotes with the target locale's quotation marks and apostrophes.
+    CurlyQuotes,
+}
+
+impl AutoFix {
+    /// Fixes in the order they are applied.
+    pub const ALL: [AutoFix; 4] = [
+        AutoFix::DoubleSpaces,
+        AutoFix::CurlyQuotes,
+        AutoFix::LeadingCapital,
+        AutoFix::TrailingPunctuation,
+    ];
+
+    /// QA profile entry that enables the fix.
+    pub fn check_type(self) -> &'static str {
+        match self {
+            AutoFix::LeadingCapital => "autofix_leading_capital",
+            AutoFix::TrailingPunctuation => "autofix_trailing_punctuation",
+            AutoFix::DoubleSpaces => "autofix_double_spaces",
+            AutoFix::CurlyQuotes => "autofix_curly_quotes",
+        }
+    }
+
+    fn apply(self, source: &str, target: &str, target_language: &str) -> String {
+        match self {
+            AutoFix::LeadingCapital => fix_leading_capital(source, target),
+            AutoFix::TrailingPunctuation => {
+                fix_trailing_punctuation(source, target, target_language)
+            }
+            AutoFix::DoubleSpaces if source.contains("  ") => target.to_string(),
+            AutoFix::DoubleSpaces => map_text(target, |text| {
+                let mut collapsed = String::with_capacity(text.len());
+                for ch in text.chars() {
+                    if !(ch == ' ' && collapsed.ends_with(' ')) {
+                        collapsed.push(ch);
+                    }
+                }
+                collapsed
+            }),
+            AutoFix::CurlyQuotes => fix_quotes(target, QuoteStyle::for_language(target_language)),
+        }
+    }
+}
+
+/// Applies `fixes` to `target` in [`AutoFix::ALL`] order and returns the fixed target
+/// with the fixes that changed it.
+pub fn apply_auto_fixes(
+    source: &str,
+    target: &str,
+    target_language: &str,
+    fixes: &[AutoFix],
+) -> (String, Vec<AutoFix>) {
+    let mut fixed = target.to_string();
+    let mut applied = Vec::new();
+    for fix in AutoFix::ALL.into_iter().filter(|fix| fixes.contains(fix)) {
+        let next = fix.apply(source, &fixed, target_language);
+        if next != fixed {
+            fixed = next;
+            applied.push(fix);
+        }
+    }
+    (fixed, applied)
+}
+
+/// Rewrites the text between placeholders with `map`, keeping placeholders verbatim.
+fn map_text(text: &str, mut map: impl FnMut(&str) -> String) -> String {
+    let mut mapped = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        let Some(length) = rest[start..].find("}}") else {
+            break;
+        };
+        mapped.push_str(&map(&rest[..start]));
+        mapped.push_str(&rest[start..start + length + 2]);
+        rest = &rest[start + length + 2..];
+    }
+    mapped.push_str(&map(rest));
+    mapped
+}
+
+/// Byte offset and value of the first letter of `text` outside placeholders.
+fn first_letter(text: &str) -> Option<(usize, char)> {
+    let mut placeholder_end = 0;
+    for (index, ch) in text.char_indices() {
+        if index < placeholder_end {
+            continue;
+        }
+        if text[index..].starts_with("{{")
+            && let Some(close) = text[index..].find("}}")
+        {
+            placeholder_end = index + close + 2;
+            continue;
+        }
+        if ch.is_alphabetic() {
+            return Some((index, ch));
+        }
+        if ch.is_numeric() {
+            return None;
+        }
+    }
+    None
+}
+
+fn fix_leading_capital(source: &str, target: &str) -> String {
+    let source_capital = first_letter(source).is_some_and(|(_, ch)| ch.is_uppercase());
+    match first_letter(target) {
+        Some((index, ch)) if source_capital && ch.is_lowercase() => {
+            let mut fixed = String::with_capacity(target.len() + 2);
+            fixed.push_str(&target[..index]);
+            fixed.extend(ch.to_uppercase());
+            fixed.push_str(&target[index + ch.len_utf8()..]);
+            fixed
+        }
+        _ => target.to_string(),
+    }
+}
+
+/// Closing punctuation that ends a sentence or label.
+fn is_closing_punctuation(ch: char) -> bool {
+    matches!(
+        ch,
+        '.' | '!' | '?' | ':' | ';' | '…' | '。' | '！' | '？' | '：' | '；' | '｡'
+    )
+}
+
+/// `punctuation` as written in `target_language`.
+fn localized_punctuation(punctuation: char, target_language: &str) -> char {
+    let full_width = target_language
+        .split(['-', '_'])
+        .next()
+        .is_some_and(|primary| {
+            ["zh", "ja", "yue", "cmn", "lzh", "wuu"]
+                .contains(&primary.to_ascii_lowercase().as_str())
+        });
+    match (punctuation, full_width) {
+        ('.' | '｡', true) => '。',
+        ('!', true) => '！',
+        ('?', true) => '？',
+        (':', true) => '：',
+        (';', true) => '；',
+        ('。' | '｡', false) => '.',
+        ('！', false) => '!',
+        ('？', false) => '?',
+        ('：', false) => ':',
+        ('；', false) => ';',
+        (other, _) => other,
+    }
+}
+
+fn fix_trailing_punctuation(source: &str, target: &str, target_language: &str) -> String {
+    let source_end = source.trim_end().chars().last();
+    let body = target.trim_end();
+    let trailing = &target[body.len()..];
+    let Some(target_end) = body.chars().last() else {
+        return target.to_string();
+    };
+    match source_end {
+        Some(end) if is_closing_punctuation(end) && !is_closing_punctuation(target_end) => {
+            let punctuation = localized_punctuation(end, target_language);
+            format!("{body}{punctuation}{trailing}")
+        }
+        // Keep ellipses and abbreviations such as `z.B.`.
+        Some(end)
+            if end.is_alphanumeric()
+                && matches!(target_end, '.' | '。')
+                && !body
+                    .rsplit(char::is_whitespace)
+                    .next()
+                    .is_some_and(|word| word.matches(['.', '。']).count() > 1) =>
+        {
+            format!("{}{trailing}", &body[..body.len() - target_end.len_utf8()])
+        }
+        _ => target.to_string(),
+    }
+}
+
+/// Quotation marks of a locale.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct QuoteStyle {
+    open_double: &'static str,
+    close_double: &'static str,
+    open_single: &'static str,
+    close_single: &'static str,
+}
+
+impl QuoteStyle {
+    fn for_language(language: &str) -> Self {
+        let mut subtags = language.trim().split(['-', '_']);
+        let primary = subtags.next().unwrap_or_default().to_ascii_lowercase();
+        let region = subtags
+            .find(|subtag| subtag.len() == 2)
+            .map(str::to_ascii_uppercase);
+        let (open_double, close_double, open_single, close_single) = match primary.as_str() {
+            "de" if region.as_deref() == Some("CH") || region.as_deref() == Some("LI") => {
+                ("«", "»", "‹", "›")
+            }
+            "de" | "cs" | "sk" | "sl" | "is" | "lt" | "et" | "bg" | "ka" => ("„", "“", "‚", "‘"),
+            "fr" => ("«\u{a0}", "\u{a0}»", "‹\u{a0}", "\u{a0}›"),
+            "es" | "it" | "ca" | "el" | "no" | "nb" | "nn" | "ru" | "uk" | "be" | "hy" | "fa" => {
+                ("«", "»", "“", "”")
+            }
+            "pt" if region.as_deref() != Some("BR") => ("«", "»", "“", "”"),
+            "pl" | "ro" | "hu" | "hr" => ("„", "”", "«", "»"),
+            "sv" | "fi" => ("”", "”", "’", "’"),
+            "da" => ("»", "«", "›", "‹"),
+            "ja" => ("「", "」", "『", "』"),
+            "he" => ("”", "”", "’", "’"),
+            _ => ("“", "”", "‘", "’"),
+        };
+        Self {
+            open_double,
+            close_double,
+            open_single,
+            close_single,
+        }
+    }
+}
+
+fn fix_quotes(target: &str, style: QuoteStyle) -> String {
+    // Carried across placeholders so quotes around inline codes still pair up.
+    let mut previous: Option<char> = None;
+    let mut double_open = false;
+    let mut single_open = false;
+    map_text(target, |text| {
+        let chars: Vec<char> = text.chars().collect();
+        let mut fixed = String::with_capacity(text.len() + 8);
+        let mut skip_space = false;
+        for (index, &ch) in chars.iter().enumerate() {
+            if std::mem::take(&mut skip_space) && ch == ' ' {
+                continue;
+            }
+            let before = if index == 0 {
+                previous
+            } else {
+                Some(chars[index - 1])
+            };
+            let after = chars.get(index + 1).copied();
+            let opens = before.is_none_or(|ch| ch.is_whitespace() || "([{—–-/".contains(ch));
+            let mark = match ch {
+                '\'' if before.is_some_and(char::is_alphanumeric)
+                    && after.is_some_and(char::is_alphanumeric) =>
+                {
+                    "’"
+                }
+                '"' => {
+                    double_open = !double_open && opens;
+                    if double_open {
+                        style.open_double
+                    } else {
+                        style.close_double
+                    }
+                }
+                '\'' => {
+                    single_open = !single_open && opens;
+                    if single_open {
+                        style.open_single
+                    } else {
+                        style.close_single
+                    }
+                }
+                _ => {
+                    fixed.push(ch);
+                    continue;
+                }
+            };
+            // Marks with built-in spacing absorb a space typed inside the quotes.
+            if mark.starts_with('\u{a0}') && fixed.ends_with(' ') {
+                fixed.pop();
+            }
+            skip_space = mark.ends_with('\u{a0}');
+            fixed.push_str(mark);
+        }
+        if let Some(&last) = chars.last() {
+            previous = Some(last);
+        }
+        fixed
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fix(source: &str, target: &str, language: &str, fix: AutoFix) -> String {
+        apply_auto_fixes(source, target, language, &[fix]).0
+    }
+
+    #[test]
+    fn capitalizes_like_the_source() {
+        let fixed = fix(
+            "{{ph:1}}\"Save\" now",
+            "{{ph:1}}\"speichern\" jetzt",
+            "de",
+            AutoFix::LeadingCapital,
+        );
+        assert_eq!(fixed, "{{ph:1}}\"Speichern\" jetzt");
+        assert_eq!(
+            fix(
+                "iPhone setup",
+                "iPhone-Einrichtung",
+                "de",
+                AutoFix::LeadingCapital
+            ),
+            "iPhone-Einrichtung"
+        );
+        assert_eq!(
+            fix("Save", "éditer", "fr", AutoFix::LeadingCapital),
+            "Éditer"
+        );
+    }
+
+    #[test]
+    fn mirrors_trailing_punctuation() {
+        let fixed =
+            |source, target, language| fix(source, target, language, AutoFix::TrailingPunctuation);
+        assert_eq!(
+            fixed("Save the file.", "Datei speichern ", "de"),
+            "Datei speichern. "
+        );
+        assert_eq!(fixed("Done!", "完成", "zh-CN"), "完成！");
+        assert_eq!(fixed("保存しました。", "Saved", "en"), "Saved.");
+        assert_eq!(fixed("File menu", "Dateimenü.", "de"), "Dateimenü");
+        assert_eq!(fixed("File menu", "Menü usw...", "de"), "Menü usw...");
+        assert_eq!(
+            fixed("For example", "Zum Beispiel z.B.", "de"),
+            "Zum Beispiel z.B."
+        );
+        assert_eq!(fixed("Continue?", "Weiter!", "de"), "Weiter!");
+    }
+
+    #[test]
+    fn collapses_double_spaces_outside_placeholders() {
+        assert_eq!(
+            fix(
+                "a b",
+                "Ein  Text {{ph:1  x}}   hier",
+                "de",
+                AutoFix::DoubleSpaces
+            ),
+            "Ein Text {{ph:1  x}} hier"
+        );
+        assert_eq!(fix("a  b", "x  y", "de", AutoFix::DoubleSpaces), "x  y");
+    }
+
+    #[test]
+    fn applies_locale_quotes_and_apostrophes() {
+        let quotes = |target, language| fix("", target, language, AutoFix::CurlyQuotes);
+        assert_eq!(
+            quotes("Click \"Save\" or 'Exit'.", "en-US"),
+            "Click “Save” or ‘Exit’."
+        );
+        assert_eq!(
+            quotes("Klicken Sie auf \"Speichern\".", "de-DE"),
+            "Klicken Sie auf „Speichern“."
+        );
+        assert_eq!(
+            quotes("Cliquez sur \"Enregistrer\".", "fr"),
+            "Cliquez sur «\u{a0}Enregistrer\u{a0}»."
+        );
+        assert_eq!(quotes("Sur \" OK \".", "fr"), "Sur «\u{a0}OK\u{a0}».");
+        assert_eq!(
+            quotes("l'option {{ph:1 a=\"b\"}}", "fr"),
+            "l’option {{ph:1 a=\"b\"}}"
+        );
+        assert_eq!(quotes("\"保存\"を押す", "ja"), "「保存」を押す");
+    }
+}
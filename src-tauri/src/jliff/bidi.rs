@@ -0,0 +1,257 @@
+//! Right-to-left targets and Unicode bidi control characters.
+//!
+//! Arabic, Hebrew and the other right-to-left scripts are laid out by the Unicode
+//! bidirectional algorithm, which translators steer with invisible controls: marks (LRM,
+//! RLM, ALM), embeddings and overrides (LRE, RLE, LRO, RLO closed by PDF) and isolates (LRI,
+//! RLI, FSI closed by PDI). Once a segment is merged back into its paragraph, a control left
+//! open reorders the text that follows it, and a control inside a `{{...}}` placeholder
+//! keeps the inline code from being restored. [`find_bidi_issues`] reports those, plus
+//! directional marks in left-to-right targets; [`sanitize_bidi_controls`] removes them.
+
+use serde::Serialize;
+
+use super::model::{JliffDocument, TextDirection, TransUnit};
+
+/// Languages written right to left unless a script subtag says otherwise.
+const RTL_LANGUAGES: [&str; 14] = [
+    "ar", "arc", "ckb", "dv", "fa", "he", "iw", "ji", "ks", "ps", "sd", "ug", "ur", "yi",
+];
+/// ISO 15924 codes of right-to-left scripts.
+const RTL_SCRIPTS: [&str; 7] = ["adlm", "arab", "hebr", "nkoo", "rohg", "syrc", "thaa"];
+
+/// Base direction of text in `language` (a BCP 47 tag such as `ar-EG` or `az-Arab`).
+pub fn direction_for_language(language: &str) -> TextDirection {
+    let mut subtags = language.trim().split(['-', '_']);
+    let primary = subtags.next().unwrap_or_default().to_ascii_lowercase();
+    let script = subtags
+        .take_while(|subtag| subtag.len() != 1)
+        .find(|subtag| subtag.len() == 4 && subtag.chars().all(|ch| ch.is_ascii_alphabetic()))
+        .map(str::to_ascii_lowercase);
+    let rtl = match script {
+        Some(script) => RTL_SCRIPTS.contains(&script.as_str()),
+        None => RTL_LANGUAGES.contains(&primary.as_str()),
+    };
+    if rtl {
+        TextDirection::Rtl
+    } else {
+        TextDirection::Ltr
+    }
+}
+
+/// Direction of the unit's target: its metadata, else the document's target language.
+pub fn target_direction(document: &JliffDocument, unit: &TransUnit) -> TextDirection {
+    unit.metadata
+        .as_ref()
+        .and_then(|metadata| metadata.target_direction)
+        .unwrap_or_else(|| direction_for_language(&document.target_language))
+}
+
+/// Short name of a bidi control character, or `None` for any other character.
+pub fn bidi_control_name(ch: char) -> Option<&'static str> {
+    Some(match ch {
+        '\u{200E}' => "LRM",
+        '\u{200F}' => "RLM",
+        '\u{061C}' => "ALM",
+        '\u{202A}' => "LRE",
+        '\u{202B}' => "RLE",
+        '\u{202C}' => "PDF",
+        '\u{202D}' => "LRO",
+        '\u{202E}' => "RLO",
+        '\u{2066}' => "LRI",
+        '\u{2067}' => "RLI",
+        '\u{2068}' => "FSI",
+        '\u{2069}' => "PDI",
+        _ => return None,
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BidiIssueKind {
+    /// An embedding, override or isolate that is never closed, or a closing control
+    /// without an opener.
+    Unbalanced,
+    /// Any control inside a `{{...}}` placeholder.
+    InsidePlaceholder,
+    /// A directional mark in a left-to-right target.
+    MarkInLtrText,
+}
+
+impl BidiIssueKind {
+    pub fn describe(self) -> &'static str {
+        match self {
+            BidiIssueKind::Unbalanced => "is not balanced",
+            BidiIssueKind::InsidePlaceholder => "sits inside a placeholder",
+            BidiIssueKind::MarkInLtrText => "is a directional mark in left-to-right text",
+        }
+    }
+}
+
+/// A stray bidi control in a target.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BidiIssue {
+    pub kind: BidiIssueKind,
+    /// Short name of the control, e.g. `RLM`.
+    pub control: &'static str,
+    /// Position in characters.
+    pub offset: usize,
+}
+
+/// Stray bidi controls of `text`, a target written in `direction`, in text order.
+pub fn find_bidi_issues(text: &str, direction: TextDirection) -> Vec<BidiIssue> {
+    let placeholders = placeholder_ranges(text);
+    let mut issues = Vec::new();
+    // Open embeddings and isolates: (offset, control, is_isolate).
+    let mut open: Vec<(usize, &'static str, bool)> = Vec::new();
+
+    for (offset, (byte, ch)) in text.char_indices().enumerate() {
+        let Some(control) = bidi_control_name(ch) else {
+            continue;
+        };
+        let issue = |kind| BidiIssue {
+            kind,
+            control,
+            offset,
+        };
+        if placeholders.iter().any(|range| range.contains(&byte)) {
+            issues.push(issue(BidiIssueKind::InsidePlaceholder));
+            continue;
+        }
+        match control {
+            "LRM" | "RLM" | "ALM" => {
+                if direction == TextDirection::Ltr {
+                    issues.push(issue(BidiIssueKind::MarkInLtrText));
+                }
+            }
+            "LRE" | "RLE" | "LRO" | "RLO" => open.push((offset, control, false)),
+            "LRI" | "RLI" | "FSI" => open.push((offset, control, true)),
+            "PDF" => match open.last() {
+                Some((_, _, false)) => {
+                    open.pop();
+                }
+                _ => issues.push(issue(BidiIssueKind::Unbalanced)),
+            },
+            _ => {
+                // PDI closes its isolate and any embedding left open inside it.
+                if !open.iter().any(|(_, _, isolate)| *isolate) {
+                    issues.push(issue(BidiIssueKind::Unbalanced));
+                    continue;
+                }
+                while let Some((opened_at, opener, isolate)) = open.pop() {
+                    if isolate {
+                        break;
+                    }
+                    issues.push(BidiIssue {
+                        kind: BidiIssueKind::Unbalanced,
+                        control: opener,
+                        offset: opened_at,
+                    });
+                }
+            }
+        }
+    }
+
+    issues.extend(open.into_iter().map(|(offset, control, _)| BidiIssue {
+        kind: BidiIssueKind::Unbalanced,
+        control,
+        offset,
+    }));
+    issues.sort_by_key(|issue| issue.offset);
+    issues
+}
+
+/// `text` without the controls reported by [`find_bidi_issues`].
+pub fn sanitize_bidi_controls(text: &str, direction: TextDirection) -> String {
+    let issues = find_bidi_issues(text, direction);
+    if issues.is_empty() {
+        return text.to_string();
+    }
+    text.chars()
+        .enumerate()
+        .filter(|(offset, _)| {
+            issues
+                .binary_search_by_key(offset, |issue| issue.offset)
+                .is_err()
+        })
+        .map(|(_, ch)| ch)
+        .collect()
+}
+
+fn placeholder_ranges(text: &str) -> Vec<std::ops::Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut cursor = 0;
+    while let Some(offset) = text[cursor..].find("{{") {
+        let start = cursor + offset;
+        let Some(close) = text[start..].find("}}") else {
+            break;
+        };
+        cursor = start + close + 2;
+        ranges.push(start..cursor);
+    }
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(text: &str, direction: TextDirection) -> Vec<(BidiIssueKind, &'static str)> {
+        find_bidi_issues(text, direction)
+            .into_iter()
+            .map(|issue| (issue.kind, issue.control))
+            .collect()
+    }
+
+    #[test]
+    fn resolves_direction_from_language_and_script() {
+        assert_eq!(direction_for_language("ar-EG"), TextDirection::Rtl);
+        assert_eq!(direction_for_language("he"), TextDirection::Rtl);
+        assert_eq!(direction_for_language("az-Arab-IR"), TextDirection::Rtl);
+        assert_eq!(direction_for_language("ku-Latn"), TextDirection::Ltr);
+        assert_eq!(direction_for_language("ug-Latn"), TextDirection::Ltr);
+        assert_eq!(direction_for_language("de-DE"), TextDirection::Ltr);
+        assert_eq!(direction_for_language(""), TextDirection::Ltr);
+    }
+
+    #[test]
+    fn keeps_balanced_controls_and_marks_in_rtl_text() {
+        let text = "\u{200F}الطلب \u{2067}ABC-12\u{2069} {{ph:1}} \u{202B}نص\u{202C}";
+        assert!(find_bidi_issues(text, TextDirection::Rtl).is_empty());
+        assert_eq!(sanitize_bidi_controls(text, TextDirection::Rtl), text);
+    }
+
+    #[test]
+    fn reports_unbalanced_controls() {
+        assert_eq!(
+            kinds("\u{202B}open \u{2069}", TextDirection::Rtl),
+            [
+                (BidiIssueKind::Unbalanced, "RLE"),
+                (BidiIssueKind::Unbalanced, "PDI"),
+            ]
+        );
+        // The PDI closes the isolate and the embedding left open inside it.
+        assert_eq!(
+            kinds("\u{2066}a\u{202A}b\u{2069}", TextDirection::Ltr),
+            [(BidiIssueKind::Unbalanced, "LRE")]
+        );
+    }
+
+    #[test]
+    fn reports_controls_in_placeholders_and_marks_in_ltr_text() {
+        assert_eq!(
+            kinds("Order {{ph\u{200F}:1}} now\u{200E}", TextDirection::Ltr),
+            [
+                (BidiIssueKind::InsidePlaceholder, "RLM"),
+                (BidiIssueKind::MarkInLtrText, "LRM"),
+            ]
+        );
+        assert_eq!(
+            sanitize_bidi_controls(
+                "\u{202E}Order {{ph\u{200F}:1}} now\u{200E}",
+                TextDirection::Ltr
+            ),
+            "Order {{ph:1}} now"
+        );
+    }
+}
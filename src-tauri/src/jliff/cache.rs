@@ -0,0 +1,182 @@
+//! In-memory cache for parsed artifact documents.
+//!
+//! Entries are keyed by absolute path and validated against the file's modification time and
+//! length on every lookup, so edits made on disk (by us or by the user) are picked up without
+//! explicit invalidation. Writers that can produce the same stamp within the filesystem's time
+//! resolution should still call [`DocumentCache::invalidate`] after writing.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+use anyhow::Result;
+
+/// Default number of documents kept per cache.
+pub const DEFAULT_CACHE_CAPACITY: usize = 32;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FileStamp {
+    modified: Option<SystemTime>,
+    len: u64,
+}
+
+impl FileStamp {
+    fn read(path: &Path) -> Option<Self> {
+        let metadata = fs::metadata(path).ok()?;
+        Some(Self {
+            modified: metadata.modified().ok(),
+            len: metadata.len(),
+        })
+    }
+}
+
+struct CachedDocument<T> {
+    stamp: FileStamp,
+    value: Arc<T>,
+    last_used: u64,
+}
+
+struct CacheState<T> {
+    entries: HashMap<PathBuf, CachedDocument<T>>,
+    clock: u64,
+}
+
+/// Path-keyed cache of parsed documents with least-recently-used eviction.
+pub struct DocumentCache<T> {
+    state: Mutex<CacheState<T>>,
+    capacity: usize,
+}
+
+impl<T> DocumentCache<T> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            state: Mutex::new(CacheState {
+                entries: HashMap::new(),
+                clock: 0,
+            }),
+            capacity: capacity.max(1),
+        }
+    }
+
+    /// Returns the cached document for `path`, parsing it with `load` when the file changed
+    /// since it was cached (or was never cached). This performs blocking I/O.
+    pub fn get_or_load<F>(&self, path: &Path, load: F) -> Result<Arc<T>>
+    where
+        F: FnOnce(&Path) -> Result<T>,
+    {
+        let Some(stamp) = FileStamp::read(path) else {
+            self.invalidate(path);
+            return load(path).map(Arc::new);
+        };
+
+        {
+            let mut state = self.lock();
+            state.clock += 1;
+            let clock = state.clock;
+            if let Some(entry) = state.entries.get_mut(path)
+                && entry.stamp == stamp
+            {
+                entry.last_used = clock;
+                return Ok(Arc::clone(&entry.value));
+            }
+        }
+
+        let value = Arc::new(load(path)?);
+
+        let mut state = self.lock();
+        state.clock += 1;
+        let clock = state.clock;
+        state.entries.insert(
+            path.to_path_buf(),
+            CachedDocument {
+                stamp,
+                value: Arc::clone(&value),
+                last_used: clock,
+            },
+        );
+        while state.entries.len() > self.capacity {
+            let oldest = state
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(path, _)| path.clone());
+            match oldest {
+                Some(path) => state.entries.remove(&path),
+                None => break,
+            };
+        }
+        Ok(value)
+    }
+
+    /// Drops the cached entry for `path`, if any.
+    pub fn invalidate(&self, path: &Path) {
+        self.lock().entries.remove(path);
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, CacheState<T>> {
+        // A poisoned cache only holds derived data, so keep serving it.
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+
+    #[test]
+    fn reuses_documents_until_the_file_changes() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("doc.txt");
+        fs::write(&path, "one")?;
+
+        let cache = DocumentCache::new(4);
+        let loads = Cell::new(0);
+        let load = |path: &Path| {
+            loads.set(loads.get() + 1);
+            Ok(fs::read_to_string(path)?)
+        };
+
+        assert_eq!(*cache.get_or_load(&path, load)?, "one");
+        assert_eq!(*cache.get_or_load(&path, load)?, "one");
+        assert_eq!(loads.get(), 1);
+
+        fs::write(&path, "three")?;
+        assert_eq!(*cache.get_or_load(&path, load)?, "three");
+        assert_eq!(loads.get(), 2);
+
+        cache.invalidate(&path);
+        cache.get_or_load(&path, load)?;
+        assert_eq!(loads.get(), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn evicts_least_recently_used_entries() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let paths: Vec<PathBuf> = (0..3)
+            .map(|index| dir.path().join(format!("{index}.txt")))
+            .collect();
+        for path in &paths {
+            fs::write(path, "x")?;
+        }
+
+        let cache = DocumentCache::new(2);
+        let load = |path: &Path| Ok(fs::read_to_string(path)?);
+        cache.get_or_load(&paths[0], load)?;
+        cache.get_or_load(&paths[1], load)?;
+        cache.get_or_load(&paths[0], load)?;
+        cache.get_or_load(&paths[2], load)?;
+
+        let state = cache.lock();
+        assert!(state.entries.contains_key(&paths[0]));
+        assert!(!state.entries.contains_key(&paths[1]));
+        assert!(state.entries.contains_key(&paths[2]));
+        Ok(())
+    }
+}
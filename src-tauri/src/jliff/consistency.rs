@@ -0,0 +1,233 @@
+//! Translation consistency across the documents of a language pair.
+//!
+//! Two kinds of inconsistency are reported. The same source (after collapsing whitespace)
+//! translated in different ways, and near-identical sources, such as a sentence and its
+//! variant with one word changed, whose translations have little in common. Each finding
+//! is an [`InconsistencyGroup`] listing the target variants and where they occur, most
+//! frequent first, so a reviewer can pick one and apply it everywhere.
+
+use std::collections::BTreeMap;
+
+use super::model::JliffDocument;
+use crate::tm::{normalize, similarity};
+
+/// Lowest source similarity for two different sources to count as near-identical.
+pub const SIMILAR_SOURCE_MIN_SCORE: u8 = 90;
+/// Highest target similarity at which translations of near-identical sources count as
+/// divergent.
+pub const DIVERGENT_TARGET_MAX_SCORE: u8 = 50;
+/// Shorter sources (labels, single words) differ in too few characters to compare.
+const MIN_SIMILAR_SOURCE_CHARS: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InconsistencyKind {
+    /// Identical sources with different targets.
+    SameSource,
+    /// Near-identical sources with very different targets.
+    SimilarSource,
+}
+
+impl InconsistencyKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            InconsistencyKind::SameSource => "same_source",
+            InconsistencyKind::SimilarSource => "similar_source",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SegmentLocation {
+    pub jliff_rel_path: String,
+    pub transunit_id: String,
+}
+
+/// One target and the segments that have it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TargetVariant {
+    pub source: String,
+    pub target: String,
+    pub locations: Vec<SegmentLocation>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InconsistencyGroup {
+    pub kind: InconsistencyKind,
+    /// Similarity of the sources; 100 for [`InconsistencyKind::SameSource`].
+    pub source_similarity: u8,
+    /// Most frequent variant first.
+    pub variants: Vec<TargetVariant>,
+}
+
+/// Target variants of one source, keyed by the normalized target.
+type SourceVariants = BTreeMap<String, TargetVariant>;
+
+/// Finds inconsistent translations in `documents`, given as `(jliff_rel_path, document)`.
+/// Segments without a target are ignored.
+pub fn find_inconsistencies(documents: &[(String, JliffDocument)]) -> Vec<InconsistencyGroup> {
+    let mut sources: BTreeMap<String, SourceVariants> = BTreeMap::new();
+    for (jliff_rel_path, document) in documents {
+        for unit in &document.transunits {
+            let source_key = normalize(&unit.source);
+            let target = unit.effective_target();
+            if source_key.is_empty() || target.trim().is_empty() {
+                continue;
+            }
+            sources
+                .entry(source_key)
+                .or_default()
+                .entry(normalize(target))
+                .or_insert_with(|| TargetVariant {
+                    source: unit.source.clone(),
+                    target: target.to_string(),
+                    locations: Vec::new(),
+                })
+                .locations
+                .push(SegmentLocation {
+                    jliff_rel_path: jliff_rel_path.clone(),
+                    transunit_id: unit.transunit_id.clone(),
+                });
+        }
+    }
+
+    let mut groups: Vec<InconsistencyGroup> = sources
+        .values()
+        .filter(|variants| variants.len() > 1)
+        .map(|variants| InconsistencyGroup {
+            kind: InconsistencyKind::SameSource,
+            source_similarity: 100,
+            variants: by_frequency(variants.values().cloned().collect()),
+        })
+        .collect();
+
+    // Compare each source with the longer ones it can still be similar to.
+    let mut candidates: Vec<(&String, usize, &TargetVariant)> = sources
+        .iter()
+        .filter_map(|(source_key, variants)| {
+            let length = source_key.chars().count();
+            let dominant = variants
+                .values()
+                .max_by_key(|variant| variant.locations.len())?;
+            (length >= MIN_SIMILAR_SOURCE_CHARS).then_some((source_key, length, dominant))
+        })
+        .collect();
+    candidates.sort_by_key(|(_, length, _)| *length);
+    for (index, (left_key, left_length, left)) in candidates.iter().enumerate() {
+        for (right_key, right_length, right) in &candidates[index + 1..] {
+            if left_length * 100 / right_length < usize::from(SIMILAR_SOURCE_MIN_SCORE) {
+                break;
+            }
+            let source_similarity = similarity(left_key, right_key);
+            if source_similarity < SIMILAR_SOURCE_MIN_SCORE
+                || similarity(&left.target, &right.target) > DIVERGENT_TARGET_MAX_SCORE
+            {
+                continue;
+            }
+            groups.push(InconsistencyGroup {
+                kind: InconsistencyKind::SimilarSource,
+                source_similarity,
+                variants: by_frequency(vec![(*left).clone(), (*right).clone()]),
+            });
+        }
+    }
+    groups
+}
+
+fn by_frequency(mut variants: Vec<TargetVariant>) -> Vec<TargetVariant> {
+    variants.sort_by(|left, right| {
+        right
+            .locations
+            .len()
+            .cmp(&left.locations.len())
+            .then_with(|| left.locations.cmp(&right.locations))
+    });
+    variants
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jliff::model::TransUnit;
+
+    fn document(segments: &[(&str, &str)]) -> JliffDocument {
+        JliffDocument {
+            project_name: "Demo".into(),
+            project_id: "demo".into(),
+            file: "demo.xlf".into(),
+            user: "tester".into(),
+            source_language: "en-US".into(),
+            target_language: "de-DE".into(),
+            transunits: segments
+                .iter()
+                .enumerate()
+                .map(|(index, (source, target))| TransUnit {
+                    unit_id: format!("{index}"),
+                    transunit_id: format!("u{index}-s1"),
+                    source: source.to_string(),
+                    target_translation: target.to_string(),
+                    target_qa_1: None,
+                    target_qa_2: None,
+                    target_postedit: None,
+                    translation_notes: None,
+                    qa_notes: None,
+                    source_notes: None,
+                    metadata: None,
+                    status: None,
+                    sub_state: None,
+                    notes: Vec::new(),
+                    propagated_from: None,
+                })
+                .collect(),
+            segment_operations: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn groups_same_and_similar_sources_with_divergent_targets() {
+        let documents = vec![
+            (
+                "a.jliff.json".to_string(),
+                document(&[
+                    ("Save the file.", "Datei speichern."),
+                    ("Save  the file.", "Datei  speichern."),
+                    (
+                        "Restart the application now.",
+                        "Starten Sie die Anwendung neu.",
+                    ),
+                    ("Open", ""),
+                ]),
+            ),
+            (
+                "b.jliff.json".to_string(),
+                document(&[
+                    ("Save the file.", "Die Datei sichern."),
+                    ("Restart the applications now.", "Programme jetzt beenden!"),
+                    (
+                        "Restart the application later.",
+                        "Starten Sie die Anwendung später neu.",
+                    ),
+                ]),
+            ),
+        ];
+
+        let groups = find_inconsistencies(&documents);
+        assert_eq!(groups.len(), 2);
+
+        let same = &groups[0];
+        assert_eq!(same.kind, InconsistencyKind::SameSource);
+        assert_eq!(same.variants[0].target, "Datei speichern.");
+        assert_eq!(same.variants[0].locations.len(), 2);
+        assert_eq!(
+            same.variants[1].locations,
+            [SegmentLocation {
+                jliff_rel_path: "b.jliff.json".into(),
+                transunit_id: "u0-s1".into(),
+            }]
+        );
+
+        let similar = &groups[1];
+        assert_eq!(similar.kind, InconsistencyKind::SimilarSource);
+        assert!(similar.source_similarity >= SIMILAR_SOURCE_MIN_SCORE);
+        assert_eq!(similar.variants.len(), 2);
+    }
+}
@@ -16,6 +16,8 @@
 //! - `xliff_parser`: XLIFF structure parsing (root, file, unit, segment)
 //! - `segment_builder`: Text content assembly with placeholder management
 //! - `inline_tags`: Processing of XLIFF inline code elements
+//! - `modules`: XLIFF 2 module data (match candidates, ITS, size restrictions)
+//! - `notes`: Unit `<notes>` extraction
 //! - `original_data`: Original data bucket handling
 //! - `text_container`: Text container parsing with nested element support
 //!
@@ -37,11 +39,15 @@
 //! ```
 
 mod inline_tags;
+mod modules;
+pub(super) mod notes;
 mod original_data;
+#[cfg(test)]
+mod property_tests;
 mod segment_builder;
 mod text_container;
 mod xliff_parser;
-mod xml_reader;
+pub(super) mod xml_reader;
 
 use anyhow::Result;
 
@@ -0,0 +1,386 @@
+//! XLIFF 2 Module Support
+//!
+//! This module extracts the optional XLIFF 2 module data that the editor surfaces as
+//! JLIFF unit metadata:
+//!
+//! - **Translation Candidates** (`mtc:matches`): the best match quality per segment
+//! - **ITS** (`its:localeFilterList` / `its:localeFilterType`): locale filters
+//! - **Size and Length Restriction** (`slr:sizeRestriction`): maximum target length
+//!
+//! The core `srcDir` / `trgDir` attributes are read alongside them, since they share the
+//! same inheritance from `<file>` to `<unit>`.
+//!
+//! ## Example
+//!
+//! ```xml
+//! <unit id="u1" slr:sizeRestriction="40" its:localeFilterList="de-DE, fr">
+//!   <mtc:matches>
+//!     <mtc:match ref="#s1" matchQuality="87.5" origin="Project TM">...</mtc:match>
+//!   </mtc:matches>
+//!   <segment id="s1">...</segment>
+//! </unit>
+//! ```
+//!
+//! Module attributes declared on `<file>` are inherited by its units unless a unit
+//! overrides them. Size restrictions are interpreted with the default
+//! `xliff:codepoints` profile, i.e. as a number of characters.
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::BufReader;
+
+use anyhow::{Result, anyhow, bail};
+use log::warn;
+use quick_xml::encoding::Decoder;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::name::{Namespace, QName, ResolveResult};
+use quick_xml::reader::NsReader;
+
+use super::xml_reader::{decode_end_name, decode_local_name, skip_current_element};
+use crate::jliff::bidi::direction_for_language;
+use crate::jliff::model::{LocaleFilter, LocaleFilterType, TextDirection, UnitMetadata};
+
+/// Namespace of the XLIFF 2 Translation Candidates module.
+pub const MTC_NAMESPACE: &str = "urn:oasis:names:tc:xliff:matches:2.0";
+
+/// Namespace of the W3C Internationalization Tag Set.
+pub const ITS_NAMESPACE: &str = "http://www.w3.org/2005/11/its";
+
+/// Namespace of the XLIFF 2 Size and Length Restriction module.
+pub const SLR_NAMESPACE: &str = "urn:oasis:names:tc:xliff:sizerestriction:2.0";
+
+/// Module attributes that apply to every segment of a unit.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ModuleAttributes {
+    /// ITS locale filter
+    pub locale_filter: Option<LocaleFilter>,
+    /// Maximum length in characters
+    pub max_length: Option<usize>,
+    /// Explicit `srcDir` (`auto` leaves it unset)
+    pub source_direction: Option<TextDirection>,
+    /// Explicit `trgDir` (`auto` leaves it unset)
+    pub target_direction: Option<TextDirection>,
+}
+
+impl ModuleAttributes {
+    /// Reads ITS and slr attributes from an element that was just read by `reader`.
+    ///
+    /// Invalid values are logged and ignored so a malformed annotation never blocks
+    /// conversion.
+    pub fn from_start(
+        reader: &NsReader<BufReader<File>>,
+        start: &BytesStart<'_>,
+        decoder: Decoder,
+    ) -> Result<Self> {
+        let mut filter_list = None;
+        let mut filter_type = None;
+        let mut max_length = None;
+        let mut source_direction = None;
+        let mut target_direction = None;
+
+        for attr in start.attributes().with_checks(false) {
+            let attr = attr?;
+            match attr.key.as_ref() {
+                b"srcDir" => source_direction = parse_direction(&attr.value),
+                b"trgDir" => target_direction = parse_direction(&attr.value),
+                _ => {}
+            }
+            let Some((namespace, local)) = module_attribute(reader, attr.key, decoder)? else {
+                continue;
+            };
+            let value = attr
+                .decode_and_unescape_value(decoder)
+                .map_err(|err| anyhow!(err))?
+                .into_owned();
+
+            match (namespace, local.as_str()) {
+                (ITS_NAMESPACE, "localeFilterList") => filter_list = Some(value),
+                (ITS_NAMESPACE, "localeFilterType") => filter_type = Some(value),
+                (SLR_NAMESPACE, "sizeRestriction") => match value.trim().parse::<usize>() {
+                    Ok(limit) => max_length = Some(limit),
+                    Err(_) => warn!(
+                        target: "jliff::convert",
+                        "Ignoring invalid slr:sizeRestriction value '{value}'"
+                    ),
+                },
+                _ => {}
+            }
+        }
+
+        let locale_filter = filter_list.map(|list| LocaleFilter {
+            filter_type: match filter_type.as_deref().map(str::trim) {
+                Some("exclude") => LocaleFilterType::Exclude,
+                _ => LocaleFilterType::Include,
+            },
+            locales: list
+                .split(',')
+                .map(str::trim)
+                .filter(|locale| !locale.is_empty())
+                .map(str::to_string)
+                .collect(),
+        });
+
+        Ok(Self {
+            locale_filter,
+            max_length,
+            source_direction,
+            target_direction,
+        })
+    }
+
+    /// Fills unset values from `parent` (e.g. the enclosing `<file>`).
+    pub fn inherit(mut self, parent: &ModuleAttributes) -> Self {
+        if self.locale_filter.is_none() {
+            self.locale_filter = parent.locale_filter.clone();
+        }
+        if self.max_length.is_none() {
+            self.max_length = parent.max_length;
+        }
+        if self.source_direction.is_none() {
+            self.source_direction = parent.source_direction;
+        }
+        if self.target_direction.is_none() {
+            self.target_direction = parent.target_direction;
+        }
+        self
+    }
+
+    /// Fills unset directions from the document languages when either side is written
+    /// right to left; purely left-to-right documents carry no direction metadata.
+    pub fn with_language_directions(mut self, src_lang: &str, trg_lang: &str) -> Self {
+        let source = self
+            .source_direction
+            .unwrap_or_else(|| direction_for_language(src_lang));
+        let target = self
+            .target_direction
+            .unwrap_or_else(|| direction_for_language(trg_lang));
+        if source == TextDirection::Rtl || target == TextDirection::Rtl {
+            self.source_direction = Some(source);
+            self.target_direction = Some(target);
+        }
+        self
+    }
+}
+
+/// A translation candidate from `<mtc:matches>`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MatchCandidate {
+    /// Target of the `ref` attribute without the leading `#`
+    pub reference: Option<String>,
+    /// Match quality percentage (0-100)
+    pub quality: Option<f64>,
+    /// Origin of the candidate (e.g. TM name, MT engine)
+    pub origin: Option<String>,
+}
+
+/// Parses the children of an `<mtc:matches>` element up to and including its end tag.
+pub fn parse_matches(
+    reader: &mut NsReader<BufReader<File>>,
+    decoder: Decoder,
+) -> Result<Vec<MatchCandidate>> {
+    let mut buf = Vec::new();
+    let mut candidates = Vec::new();
+
+    loop {
+        match reader.read_resolved_event_into(&mut buf)? {
+            (ResolveResult::Bound(Namespace(ns)), Event::Start(start))
+                if ns == MTC_NAMESPACE.as_bytes() =>
+            {
+                let is_match = decode_local_name(&start, decoder)? == "match";
+                let owned_start = start.to_owned();
+                if is_match {
+                    candidates.push(parse_match_attributes(&owned_start, decoder)?);
+                }
+                // Candidate source/target content is not carried into JLIFF.
+                skip_current_element(reader, owned_start, &mut buf)?;
+            }
+            (ResolveResult::Bound(Namespace(ns)), Event::Empty(empty))
+                if ns == MTC_NAMESPACE.as_bytes() =>
+            {
+                if decode_local_name(&empty, decoder)? == "match" {
+                    candidates.push(parse_match_attributes(&empty, decoder)?);
+                }
+            }
+            (_, Event::Start(start)) => {
+                let owned_start = start.to_owned();
+                skip_current_element(reader, owned_start, &mut buf)?;
+            }
+            (_, Event::End(end)) => {
+                if decode_end_name(&end, decoder)? == "matches" {
+                    break;
+                }
+            }
+            (_, Event::Eof) => bail!("Unexpected EOF inside <mtc:matches>"),
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok(candidates)
+}
+
+/// Picks the best candidate for each segment of a unit.
+///
+/// A candidate applies to the segment whose id equals its `ref`. When the reference
+/// points elsewhere (e.g. to an `<mrk>`) or is missing, it applies to the unit's only
+/// segment; in multi-segment units such candidates are ignored.
+pub fn best_matches<'a>(
+    candidates: &'a [MatchCandidate],
+    segment_ids: &[String],
+) -> BTreeMap<String, &'a MatchCandidate> {
+    let mut best: BTreeMap<String, &MatchCandidate> = BTreeMap::new();
+    for candidate in candidates {
+        let segment_id = match candidate.reference.as_deref() {
+            Some(reference) if segment_ids.iter().any(|id| id == reference) => reference,
+            _ if segment_ids.len() == 1 => segment_ids[0].as_str(),
+            _ => continue,
+        };
+        let replace = match best.get(segment_id) {
+            None => true,
+            Some(current) => {
+                candidate.quality.unwrap_or(f64::MIN) > current.quality.unwrap_or(f64::MIN)
+            }
+        };
+        if replace {
+            best.insert(segment_id.to_string(), candidate);
+        }
+    }
+    best
+}
+
+/// Combines unit-level attributes and the segment's best match into JLIFF metadata.
+pub fn unit_metadata(
+    attributes: &ModuleAttributes,
+    best_match: Option<&MatchCandidate>,
+) -> Option<UnitMetadata> {
+    let metadata = UnitMetadata {
+        match_quality: best_match.and_then(|candidate| candidate.quality),
+        match_origin: best_match.and_then(|candidate| candidate.origin.clone()),
+        locale_filter: attributes.locale_filter.clone(),
+        max_length: attributes.max_length,
+        source_direction: attributes.source_direction,
+        target_direction: attributes.target_direction,
+    };
+    (!metadata.is_empty()).then_some(metadata)
+}
+
+fn parse_match_attributes(start: &BytesStart<'_>, decoder: Decoder) -> Result<MatchCandidate> {
+    let mut candidate = MatchCandidate {
+        reference: None,
+        quality: None,
+        origin: None,
+    };
+
+    // Attributes of <mtc:match> are unprefixed and belong to no namespace.
+    for attr in start.attributes().with_checks(false) {
+        let attr = attr?;
+        let value = attr
+            .decode_and_unescape_value(decoder)
+            .map_err(|err| anyhow!(err))?
+            .into_owned();
+        match attr.key.as_ref() {
+            b"ref" => candidate.reference = Some(value.trim_start_matches('#').to_string()),
+            b"matchQuality" => match value.trim().parse::<f64>() {
+                Ok(quality) if (0.0..=100.0).contains(&quality) => {
+                    candidate.quality = Some(quality)
+                }
+                _ => warn!(
+                    target: "jliff::convert",
+                    "Ignoring invalid mtc:match matchQuality value '{value}'"
+                ),
+            },
+            b"origin" => candidate.origin = Some(value),
+            _ => {}
+        }
+    }
+
+    Ok(candidate)
+}
+
+/// Reads a `srcDir` / `trgDir` value; `auto` and invalid values give `None`.
+fn parse_direction(value: &[u8]) -> Option<TextDirection> {
+    match value {
+        b"ltr" => Some(TextDirection::Ltr),
+        b"rtl" => Some(TextDirection::Rtl),
+        _ => None,
+    }
+}
+
+/// Resolves an attribute name to `(module namespace, local name)` for the namespaces this
+/// module understands.
+fn module_attribute(
+    reader: &NsReader<BufReader<File>>,
+    key: QName<'_>,
+    decoder: Decoder,
+) -> Result<Option<(&'static str, String)>> {
+    let (resolved, local) = reader.resolve_attribute(key);
+    let ResolveResult::Bound(Namespace(ns)) = resolved else {
+        return Ok(None);
+    };
+    let namespace = [ITS_NAMESPACE, SLR_NAMESPACE]
+        .into_iter()
+        .find(|candidate| candidate.as_bytes() == ns);
+    let Some(namespace) = namespace else {
+        return Ok(None);
+    };
+    let local = decoder
+        .decode(local.as_ref())
+        .map_err(|err| anyhow!(err))?
+        .into_owned();
+    Ok(Some((namespace, local)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(reference: Option<&str>, quality: Option<f64>) -> MatchCandidate {
+        MatchCandidate {
+            reference: reference.map(str::to_string),
+            quality,
+            origin: None,
+        }
+    }
+
+    #[test]
+    fn picks_highest_quality_match_per_segment() {
+        let candidates = vec![
+            candidate(Some("s1"), Some(75.0)),
+            candidate(Some("s1"), Some(92.5)),
+            candidate(Some("s2"), None),
+            candidate(Some("m7"), Some(99.0)),
+        ];
+        let segments = vec!["s1".to_string(), "s2".to_string()];
+        let best = best_matches(&candidates, &segments);
+
+        assert_eq!(best["s1"].quality, Some(92.5));
+        assert_eq!(best["s2"].quality, None);
+        assert_eq!(best.len(), 2);
+    }
+
+    #[test]
+    fn unreferenced_matches_apply_to_single_segment_units() {
+        let candidates = vec![candidate(Some("m1"), Some(80.0))];
+        let best = best_matches(&candidates, &["1".to_string()]);
+        assert_eq!(best["1"].quality, Some(80.0));
+    }
+
+    #[test]
+    fn directions_follow_languages_only_for_rtl_documents() {
+        let ltr = ModuleAttributes::default().with_language_directions("en-US", "de-DE");
+        assert_eq!(ltr.source_direction, None);
+        assert_eq!(ltr.target_direction, None);
+
+        let rtl = ModuleAttributes::default().with_language_directions("en-US", "he-IL");
+        assert_eq!(rtl.source_direction, Some(TextDirection::Ltr));
+        assert_eq!(rtl.target_direction, Some(TextDirection::Rtl));
+
+        let explicit = ModuleAttributes {
+            target_direction: Some(TextDirection::Ltr),
+            ..ModuleAttributes::default()
+        }
+        .with_language_directions("ar", "fr");
+        assert_eq!(explicit.source_direction, Some(TextDirection::Rtl));
+        assert_eq!(explicit.target_direction, Some(TextDirection::Ltr));
+    }
+}
@@ -0,0 +1,139 @@
+//! Unit Notes Extraction
+//!
+//! This module parses the XLIFF `<notes>` container of a unit into JLIFF notes.
+//!
+//! ## Notes Structure
+//!
+//! ```xml
+//! <notes>
+//!   <note id="n1" category="context" priority="2" appliesTo="source">Button label</note>
+//! </notes>
+//! ```
+//!
+//! Note content is plain text; markup from other namespaces nested inside a note is
+//! dropped.
+
+use std::fs::File;
+use std::io::BufReader;
+
+use anyhow::{Result, anyhow, bail};
+use log::warn;
+use quick_xml::encoding::Decoder;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::reader::NsReader;
+
+use super::xml_reader::{
+    decode_cdata, decode_end_name, decode_general_ref, decode_local_name, decode_qname,
+    decode_text, skip_current_element,
+};
+use crate::jliff::model::XliffNote;
+
+/// Parses the children of a `<notes>` element up to and including its end tag.
+///
+/// ## Arguments
+///
+/// * `reader` - Mutable reference to the XML reader, positioned after `<notes>`
+/// * `decoder` - XML decoder for text processing
+///
+/// ## Returns
+///
+/// * `Ok(Vec<XliffNote>)` - Notes in document order
+/// * `Err(anyhow::Error)` - Malformed XML structure
+pub fn parse_notes(
+    reader: &mut NsReader<BufReader<File>>,
+    decoder: Decoder,
+) -> Result<Vec<XliffNote>> {
+    let mut buf = Vec::new();
+    let mut notes = Vec::new();
+
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(start) => {
+                let owned_start = start.to_owned();
+                if decode_local_name(&owned_start, decoder)? == "note" {
+                    let mut note = note_from_start(&owned_start, decoder)?;
+                    note.text = read_note_text(reader, decoder)?;
+                    notes.push(note);
+                } else {
+                    skip_current_element(reader, owned_start, &mut buf)?;
+                }
+            }
+            Event::Empty(empty) => {
+                if decode_local_name(&empty, decoder)? == "note" {
+                    notes.push(note_from_start(&empty, decoder)?);
+                }
+            }
+            Event::End(end) => {
+                if decode_end_name(&end, decoder)? == "notes" {
+                    break;
+                }
+            }
+            Event::Eof => bail!("Unexpected EOF inside <notes>"),
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok(notes)
+}
+
+/// Builds a note from the attributes of a `<note>` element.
+pub fn note_from_start(start: &BytesStart<'_>, decoder: Decoder) -> Result<XliffNote> {
+    let mut note = XliffNote {
+        id: None,
+        category: None,
+        priority: None,
+        applies_to: None,
+        text: String::new(),
+    };
+
+    for attr in start.attributes().with_checks(false) {
+        let attr = attr?;
+        let key = decode_qname(attr.key, decoder)?;
+        let value = attr
+            .decode_and_unescape_value(decoder)
+            .map_err(|err| anyhow!(err))?
+            .into_owned();
+        match key.as_str() {
+            "id" => note.id = Some(value),
+            "category" => note.category = Some(value),
+            "priority" => match value.trim().parse::<u8>() {
+                Ok(priority) if (1..=10).contains(&priority) => note.priority = Some(priority),
+                _ => warn!(
+                    target: "jliff::convert",
+                    "Ignoring invalid note priority '{value}'"
+                ),
+            },
+            "appliesTo" => note.applies_to = Some(value),
+            _ => {}
+        }
+    }
+
+    Ok(note)
+}
+
+/// Reads the text of a `<note>` up to and including its end tag.
+fn read_note_text(reader: &mut NsReader<BufReader<File>>, decoder: Decoder) -> Result<String> {
+    let mut buf = Vec::new();
+    let mut text = String::new();
+
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Text(content) => text.push_str(&decode_text(&content)?),
+            Event::CData(cdata) => text.push_str(&decode_cdata(&cdata, decoder)?),
+            Event::GeneralRef(reference) => {
+                text.push_str(&decode_general_ref(&reference, decoder)?)
+            }
+            Event::Start(start) => {
+                let owned_start = start.to_owned();
+                skip_current_element(reader, owned_start, &mut buf)?;
+            }
+            Event::End(_) => break,
+            Event::Eof => bail!("Unexpected EOF inside <note>"),
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok(text)
+}
@@ -0,0 +1,171 @@
+//! Property tests for the XLIFF parser: arbitrary input never panics, segment text is
+//! conserved and placeholders keep their document order.
+
+use std::fs;
+
+use anyhow::Result;
+use proptest::prelude::*;
+use tempfile::tempdir;
+
+use super::{FileConversion, convert};
+use crate::jliff::options::ConversionOptions;
+
+const HEADER: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xliff xmlns="urn:oasis:names:tc:xliff:document:2.0" version="2.0" srcLang="en-US" trgLang="it-IT">"#;
+
+/// Fragments glued together to build XLIFF-shaped documents that are frequently
+/// malformed: unbalanced elements, unknown entities, invalid character references.
+const FRAGMENTS: &[&str] = &[
+    r#"<file id="f1">"#,
+    "</file>",
+    r#"<unit id="u1">"#,
+    "</unit>",
+    r#"<segment id="s1" state="translated">"#,
+    "</segment>",
+    "<source>",
+    "</source>",
+    "<target>",
+    "</target>",
+    r#"<ph id="1"/>"#,
+    r#"<ph id="2" dataRef="d1"/>"#,
+    r#"<pc id="3">"#,
+    "</pc>",
+    r#"<sc id="4"/>"#,
+    r#"<ec startRef="4"/>"#,
+    r#"<cp hex="FFFF"/>"#,
+    r#"<originalData><data id="d1">&lt;b&gt;</data></originalData>"#,
+    "<notes><note>n</note></notes>",
+    "&amp;",
+    "&bogus;",
+    "&#xD800;",
+    "&#233;",
+    "<![CDATA[<b>]]>",
+    "<!-- comment -->",
+    "text ",
+    "漢字",
+    "<",
+    ">",
+    "\"",
+];
+
+#[derive(Debug, Clone)]
+enum Piece {
+    Text(String),
+    Placeholder,
+}
+
+fn piece() -> impl Strategy<Value = Piece> {
+    prop_oneof![
+        3 => "[a-zA-Z0-9 .,;:!?àéüß漢字<>&'\"-]{1,12}".prop_map(Piece::Text),
+        1 => Just(Piece::Placeholder),
+    ]
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Renders the pieces as XLIFF inline content and as the JLIFF text the parser should
+/// produce for it. Placeholders are numbered in document order.
+fn render(pieces: &[Piece]) -> (String, String, Vec<String>) {
+    let mut xml = String::new();
+    let mut expected = String::new();
+    let mut placeholders = Vec::new();
+    for piece in pieces {
+        match piece {
+            Piece::Text(text) => {
+                xml.push_str(&escape(text));
+                expected.push_str(text);
+            }
+            Piece::Placeholder => {
+                let id = format!("p{}", placeholders.len());
+                xml.push_str(&format!(r#"<ph id="{id}"/>"#));
+                let placeholder = format!("{{{{ph:{id}}}}}");
+                expected.push_str(&placeholder);
+                placeholders.push(placeholder);
+            }
+        }
+    }
+    (xml, expected, placeholders)
+}
+
+fn convert_str(xml: &str) -> Result<Vec<FileConversion>> {
+    let dir = tempdir()?;
+    let input = dir.path().join("input.xlf");
+    fs::write(&input, xml)?;
+    let opts = ConversionOptions::new(
+        input,
+        dir.path().join("out"),
+        "Property".to_string(),
+        "proj-1".to_string(),
+        "user@example.com".to_string(),
+    );
+    convert(&opts)
+}
+
+proptest! {
+    #[test]
+    fn arbitrary_bytes_never_panic(bytes in proptest::collection::vec(any::<u8>(), 0..512)) {
+        let dir = tempdir().unwrap();
+        let input = dir.path().join("input.xlf");
+        fs::write(&input, &bytes).unwrap();
+        let opts = ConversionOptions::new(
+            input,
+            dir.path().join("out"),
+            "Property".to_string(),
+            "proj-1".to_string(),
+            "user@example.com".to_string(),
+        );
+        let _ = convert(&opts);
+    }
+
+    #[test]
+    fn xliff_shaped_garbage_never_panics(
+        fragments in proptest::collection::vec(proptest::sample::select(FRAGMENTS), 0..40),
+        close_root in any::<bool>(),
+    ) {
+        let mut xml = String::from(HEADER);
+        xml.extend(fragments);
+        if close_root {
+            xml.push_str("</xliff>");
+        }
+        let _ = convert_str(&xml);
+    }
+
+    #[test]
+    fn conserves_text_and_placeholder_order(
+        source in proptest::collection::vec(piece(), 0..12),
+        target in proptest::collection::vec(piece(), 0..12),
+    ) {
+        let (source_xml, expected_source, expected_placeholders) = render(&source);
+        let (target_xml, expected_target, _) = render(&target);
+        let xml = format!(
+            r#"{HEADER}
+  <file id="f1">
+    <unit id="u1">
+      <segment id="s1">
+        <source>{source_xml}</source>
+        <target>{target_xml}</target>
+      </segment>
+    </unit>
+  </file>
+</xliff>"#
+        );
+
+        let conversions = convert_str(&xml).unwrap();
+        prop_assert_eq!(conversions.len(), 1);
+        let conversion = &conversions[0];
+        let unit = &conversion.jliff.transunits[0];
+        prop_assert_eq!(&unit.source, &expected_source);
+        prop_assert_eq!(&unit.target_translation, &expected_target);
+
+        let placeholders: Vec<String> = conversion.tag_map.units[0].segments[0]
+            .placeholders
+            .iter()
+            .map(|tag| tag.placeholder.clone())
+            .collect();
+        prop_assert_eq!(placeholders, expected_placeholders);
+    }
+}
@@ -30,8 +30,8 @@ use quick_xml::reader::NsReader;
 use super::inline_tags::is_inline_code;
 use super::segment_builder::SegmentBuilder;
 use super::xml_reader::{
-    decode_cdata, decode_end_name, decode_local_name, decode_qname, decode_start_name, decode_text,
-    skip_current_element,
+    decode_cdata, decode_end_name, decode_general_ref, decode_local_name, decode_qname,
+    decode_start_name, decode_text, skip_current_element,
 };
 
 /// Parses a text container element (source or target) with inline code processing.
@@ -105,6 +105,11 @@ pub fn parse_text_container(
                 builder.push_text(cdata_content);
             }
 
+            // Entity or character reference (`&amp;`, `&#233;`) between text runs
+            (_, Event::GeneralRef(reference)) => {
+                builder.push_text(decode_general_ref(&reference, decoder)?);
+            }
+
             // Start of nested element
             (_, Event::Start(start)) => {
                 let name = decode_local_name(&start, decoder)?;
@@ -232,6 +237,11 @@ pub fn read_textual_content(
                 output.push_str(&cdata_content);
             }
 
+            // Entity or character reference - append resolved text
+            (_, Event::GeneralRef(reference)) => {
+                output.push_str(&decode_general_ref(&reference, decoder)?);
+            }
+
             // End of the container element
             (_, Event::End(end)) => {
                 let end_name = decode_end_name(&end, decoder)?;
@@ -28,11 +28,14 @@ use std::fs::File;
 use std::io::BufReader;
 
 use anyhow::{Result, anyhow, bail};
+use log::debug;
 use quick_xml::encoding::Decoder;
 use quick_xml::events::{BytesStart, Event};
-use quick_xml::name::ResolveResult;
+use quick_xml::name::{Namespace, ResolveResult};
 use quick_xml::reader::NsReader;
 
+use super::modules::{MTC_NAMESPACE, ModuleAttributes, best_matches, parse_matches, unit_metadata};
+use super::notes::parse_notes;
 use super::original_data::parse_original_data;
 use super::segment_builder::SegmentBuilder;
 use super::text_container::parse_text_container;
@@ -40,9 +43,11 @@ use super::xml_reader::{
     decode_end_name, decode_local_name, decode_qname, locate_root, open_reader,
     skip_current_element,
 };
-use crate::jliff::model::{JliffDocument, TransUnit};
+use crate::jliff::model::{JliffDocument, SegmentStatus, TransUnit};
 use crate::jliff::options::ConversionOptions;
-use crate::jliff::tag_map::{TagMapDoc, TagMapSegment, TagMapUnit};
+use crate::jliff::protected_terms::{protect_segment, protect_target};
+use crate::jliff::tag_map::{TAG_MAP_VERSION, TagMapDoc, TagMapSegment, TagMapUnit};
+use crate::jliff::unicode_text::to_nfc;
 
 use super::FileConversion;
 
@@ -142,7 +147,9 @@ pub fn parse_xliff_document(opts: &ConversionOptions) -> Result<Vec<FileConversi
                 let name = decode_local_name(&start, decoder)?;
                 if name == "file" {
                     // Parse XLIFF file element
-                    let file_ctx = FileContext::from_start(&start, decoder)?;
+                    let mut file_ctx = FileContext::from_start(&start, decoder)?;
+                    file_ctx.modules = ModuleAttributes::from_start(&reader, &start, decoder)?
+                        .with_language_directions(&src_lang, &trg_lang);
                     let file_result =
                         parse_file(&mut reader, &file_ctx, opts, decoder, &src_lang, &trg_lang)?;
                     results.push(file_result);
@@ -254,6 +261,8 @@ struct FileContext {
     id: String,
     /// Original file path or name
     original: String,
+    /// ITS/slr module attributes inherited by the file's units
+    modules: ModuleAttributes,
 }
 
 impl FileContext {
@@ -297,6 +306,7 @@ impl FileContext {
         Ok(FileContext {
             id,
             original: original.unwrap_or_default(),
+            modules: ModuleAttributes::default(),
         })
     }
 }
@@ -346,7 +356,7 @@ fn parse_file(
                 let owned_start = start.to_owned();
                 if name == "unit" {
                     // Parse translation unit
-                    let unit = parse_unit(reader, owned_start, opts, decoder)?;
+                    let unit = parse_unit(reader, owned_start, &file_ctx.modules, opts, decoder)?;
                     units.push(unit);
                 } else {
                     // Skip unsupported elements (e.g., skeleton, notes)
@@ -391,10 +401,12 @@ fn parse_file(
         source_language: src_lang.to_string(),
         target_language: trg_lang.to_string(),
         transunits: units.iter().flat_map(|u| u.trans_units.clone()).collect(),
+        segment_operations: Vec::new(),
     };
 
     // Build tag map document structure
     let tag_map = TagMapDoc {
+        version: TAG_MAP_VERSION,
         file_id: file_ctx.id.clone(),
         original_path: file_ctx.original.clone(),
         source_language: src_lang.to_string(),
@@ -424,8 +436,9 @@ struct UnitOutput {
 
 /// Parses a single XLIFF unit element and its contained segments.
 ///
-/// This function processes a `<unit>` element, handling original data references
-/// and extracting all segments within the unit.
+/// This function processes a `<unit>` element, handling original data references,
+/// XLIFF 2 module data (match candidates, ITS, size restrictions), and extracting all
+/// segments within the unit.
 ///
 /// ## Unit Structure
 ///
@@ -445,6 +458,7 @@ struct UnitOutput {
 ///
 /// * `reader` - Mutable reference to the XML reader
 /// * `start` - The unit element start tag (consumed)
+/// * `inherited` - Module attributes declared on the enclosing `<file>`
 /// * `opts` - Conversion options and preferences
 /// * `decoder` - XML decoder for text processing
 ///
@@ -455,6 +469,7 @@ struct UnitOutput {
 fn parse_unit(
     reader: &mut NsReader<BufReader<File>>,
     start: BytesStart<'static>,
+    inherited: &ModuleAttributes,
     opts: &ConversionOptions,
     decoder: Decoder,
 ) -> Result<UnitOutput> {
@@ -473,14 +488,25 @@ fn parse_unit(
         }
     }
     let unit_id = unit_id.ok_or_else(|| anyhow!("<unit> missing id attribute"))?;
+    let modules = ModuleAttributes::from_start(reader, &start, decoder)?.inherit(inherited);
 
-    // Storage for original data references
+    // Storage for original data references and translation candidates
     let mut original_data: BTreeMap<String, String> = BTreeMap::new();
+    let mut candidates = Vec::new();
+    let mut notes = Vec::new();
     let mut segments = Vec::new();
 
     // Process elements within the unit
     loop {
         match reader.read_resolved_event_into(&mut buf)? {
+            // Translation candidates module
+            (ResolveResult::Bound(Namespace(ns)), Event::Start(start))
+                if ns == MTC_NAMESPACE.as_bytes()
+                    && decode_local_name(&start, decoder)? == "matches" =>
+            {
+                candidates.extend(parse_matches(reader, decoder)?);
+            }
+
             // Start of a new element
             (_, Event::Start(start)) => {
                 let name = decode_local_name(&start, decoder)?;
@@ -490,6 +516,10 @@ fn parse_unit(
                         // Parse original data bucket for inline element references
                         parse_original_data(reader, decoder, &mut original_data)?;
                     }
+                    "notes" => {
+                        // Unit notes apply to every segment of the unit
+                        notes.extend(parse_notes(reader, decoder)?);
+                    }
                     "segment" => {
                         // Parse translation segment
                         let segment = parse_segment(
@@ -503,7 +533,7 @@ fn parse_unit(
                         segments.push(segment);
                     }
                     _ => {
-                        // Skip unsupported elements (e.g., notes, metadata)
+                        // Skip unsupported elements (e.g., metadata)
                         skip_current_element(reader, owned_start, &mut buf)?
                     }
                 }
@@ -527,10 +557,20 @@ fn parse_unit(
         buf.clear();
     }
 
+    // Attach module metadata to each segment
+    let segment_ids: Vec<String> = segments
+        .iter()
+        .map(|seg| seg.tag_segment.segment_id.clone())
+        .collect();
+    let best = best_matches(&candidates, &segment_ids);
+
     // Separate translation units from tag segments
     let mut trans_units = Vec::new();
     let mut tag_segments = Vec::new();
-    for seg in segments {
+    for mut seg in segments {
+        let best_match = best.get(&seg.tag_segment.segment_id).copied();
+        seg.trans_unit.metadata = unit_metadata(&modules, best_match);
+        seg.trans_unit.notes = notes.clone();
         trans_units.push(seg.trans_unit);
         tag_segments.push(seg.tag_segment);
     }
@@ -575,6 +615,8 @@ struct SegmentOutput {
 /// - Placeholder generation for inline codes
 /// - Original data reference resolution
 /// - Configurable inline element handling
+/// - Protected-term and regex-rule placeholders for "do not translate" content
+/// - `state`/`subState` mapping onto the segment status workflow
 /// - Parallel source and target processing
 ///
 /// ## Arguments
@@ -600,17 +642,24 @@ fn parse_segment(
 ) -> Result<SegmentOutput> {
     let mut buf = Vec::new();
 
-    // Extract segment ID from attributes (defaults to "0" if missing)
+    // Extract segment ID (defaults to "0" if missing) and workflow state from attributes
     let mut segment_id = None;
+    let mut state = None;
+    let mut sub_state = None;
     for attr in start.attributes().with_checks(false) {
         let attr = attr?;
-        if decode_qname(attr.key, decoder)?.as_str() == "id" {
-            segment_id = Some(
-                attr.decode_and_unescape_value(decoder)
-                    .map_err(|err| anyhow!(err))?
-                    .into_owned(),
-            );
-        }
+        let key = decode_qname(attr.key, decoder)?;
+        let slot = match key.as_str() {
+            "id" => &mut segment_id,
+            "state" => &mut state,
+            "subState" => &mut sub_state,
+            _ => continue,
+        };
+        *slot = Some(
+            attr.decode_and_unescape_value(decoder)
+                .map_err(|err| anyhow!(err))?
+                .into_owned(),
+        );
     }
     let segment_id = segment_id.unwrap_or_else(|| "0".to_string());
 
@@ -668,19 +717,56 @@ fn parse_segment(
 
     // Extract placeholder information from source builder (authoritative)
     let placeholders = source_builder.placeholders.clone();
+    let mut source_text = source_builder.into_text();
+    let mut target_text = target_builder.into_text();
+    if opts.normalize_unicode {
+        source_text = to_nfc(&source_text);
+        target_text = to_nfc(&target_text);
+    }
+
+    // Protect "do not translate" terms and regex rule matches so MT cannot alter them
+    let (source_text, placeholders) = protect_segment(
+        &source_text,
+        &opts.protected_terms,
+        &opts.protection_rules,
+        placeholders,
+    );
+    let target_text = protect_target(
+        &target_text,
+        &opts.protected_terms,
+        &opts.protection_rules,
+        &placeholders,
+    );
+
+    // Map the XLIFF state onto the segment status workflow
+    let status = state.as_deref().and_then(|value| {
+        let status = SegmentStatus::from_xliff_state(value, !target_text.trim().is_empty());
+        if status.is_none() {
+            debug!(
+                target: "jliff::convert",
+                "Ignoring unknown segment state '{value}' on segment '{segment_id}'"
+            );
+        }
+        status
+    });
 
     // Build translation unit for JLIFF document
     let trans_unit = TransUnit {
         unit_id: unit_id.to_string(),
         transunit_id: format!("u{}-s{}", unit_id, segment_id),
-        source: source_builder.into_text(),
-        target_translation: target_builder.into_text(),
+        source: source_text,
+        target_translation: target_text,
         target_qa_1: None,
         target_qa_2: None,
         target_postedit: None,
         translation_notes: None,
         qa_notes: None,
         source_notes: None,
+        metadata: None,
+        sub_state: status.and(sub_state),
+        notes: Vec::new(),
+        propagated_from: None,
+        status,
     };
 
     // Build tag map segment for inline element reconstruction
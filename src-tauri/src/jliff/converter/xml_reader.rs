@@ -18,7 +18,8 @@ use std::path::Path;
 
 use anyhow::{Context, Result, anyhow};
 use quick_xml::encoding::Decoder;
-use quick_xml::events::{BytesCData, BytesEnd, BytesStart, BytesText, Event};
+use quick_xml::escape::resolve_predefined_entity;
+use quick_xml::events::{BytesCData, BytesEnd, BytesRef, BytesStart, BytesText, Event};
 use quick_xml::name::{Namespace, QName, ResolveResult};
 use quick_xml::reader::NsReader;
 
@@ -250,3 +251,27 @@ pub fn decode_cdata(cdata: &BytesCData<'_>, decoder: Decoder) -> Result<String>
         .map_err(|err| anyhow!(err))?
         .into_owned())
 }
+
+/// Resolves an entity or character reference (e.g. `&amp;`, `&#233;`) to its text.
+///
+/// Unknown named entities are kept verbatim so no content is lost.
+///
+/// ## Arguments
+///
+/// * `reference` - The reference event content (name without `&` and `;`)
+/// * `decoder` - XML decoder for text processing
+///
+/// ## Returns
+///
+/// * `Ok(String)` - The referenced text
+/// * `Err(anyhow::Error)` - Invalid character reference or decoding error
+pub fn decode_general_ref(reference: &BytesRef<'_>, decoder: Decoder) -> Result<String> {
+    if let Some(ch) = reference.resolve_char_ref().map_err(|err| anyhow!(err))? {
+        return Ok(ch.to_string());
+    }
+    let name = decoder.decode(reference).map_err(|err| anyhow!(err))?;
+    Ok(match resolve_predefined_entity(&name) {
+        Some(value) => value.to_string(),
+        None => format!("&{name};"),
+    })
+}
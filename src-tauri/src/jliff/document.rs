@@ -0,0 +1,104 @@
+//! Helpers for reading and writing JLIFF documents that already live on disk.
+//!
+//! Conversion produces the artifacts; editor-facing features (spellcheck, QA,
+//! previews) reopen them through these helpers so every caller applies the same
+//! parsing rules and error context.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use log::{info, warn};
+use serde_json::Value;
+
+use super::model::{JliffDocument, TransUnit};
+use super::tag_map::{TAG_MAP_VERSION, TagMapDoc, upgrade_tag_map_value, validate_tag_map_value};
+
+/// Reads and deserializes a JLIFF document from disk.
+pub fn read_jliff_document(path: &Path) -> Result<JliffDocument> {
+    let payload = fs::read_to_string(path)
+        .with_context(|| format!("Unable to read JLIFF document {}", path.display()))?;
+    serde_json::from_str(&payload)
+        .with_context(|| format!("JLIFF document {} is not valid", path.display()))
+}
+
+/// Serializes and writes a JLIFF document, replacing the existing file atomically.
+pub fn write_jliff_document(path: &Path, document: &JliffDocument) -> Result<()> {
+    let payload =
+        serde_json::to_string_pretty(document).context("Failed to serialize JLIFF document")?;
+    write_atomically(path, &payload)
+}
+
+/// Derives the tag-map path (`<prefix>.tags.json`) that sits next to a `<prefix>.jliff.json`.
+pub fn tag_map_path_for(jliff_path: &Path) -> Option<PathBuf> {
+    let name = jliff_path.file_name()?.to_str()?;
+    let prefix = name.strip_suffix(".jliff.json")?;
+    Some(jliff_path.with_file_name(format!("{prefix}.tags.json")))
+}
+
+/// Reads a tag-map document from disk, validating it against the embedded schema.
+///
+/// Tag maps written in an older format are upgraded to [`TAG_MAP_VERSION`] and persisted the
+/// first time they are opened, so later reads skip the migration.
+pub fn read_tag_map(path: &Path) -> Result<TagMapDoc> {
+    let payload = fs::read_to_string(path)
+        .with_context(|| format!("Unable to read tag map {}", path.display()))?;
+    let mut value: Value = serde_json::from_str(&payload)
+        .with_context(|| format!("Tag map {} is not valid JSON", path.display()))?;
+
+    let upgraded = upgrade_tag_map_value(&mut value)
+        .with_context(|| format!("Unable to upgrade tag map {}", path.display()))?;
+    validate_tag_map_value(&value)
+        .with_context(|| format!("Tag map {} is not valid", path.display()))?;
+
+    if upgraded {
+        let pretty = payload.trim_start().starts_with("{\n");
+        let serialized = if pretty {
+            serde_json::to_string_pretty(&value)?
+        } else {
+            serde_json::to_string(&value)?
+        };
+        match write_atomically(path, &serialized) {
+            Ok(()) => info!(
+                target: "jliff::tag_map",
+                "upgraded tag map {} to version {TAG_MAP_VERSION}",
+                path.display()
+            ),
+            // The in-memory upgrade is still usable; the migration is retried next time.
+            Err(error) => warn!(
+                target: "jliff::tag_map",
+                "unable to persist upgraded tag map {}: {error:#}",
+                path.display()
+            ),
+        }
+    }
+
+    serde_json::from_value(value)
+        .with_context(|| format!("Tag map {} is not valid", path.display()))
+}
+
+/// Serializes and writes a tag-map document, replacing the existing file atomically.
+pub fn write_tag_map(path: &Path, tag_map: &TagMapDoc) -> Result<()> {
+    let payload = serde_json::to_string_pretty(tag_map).context("Failed to serialize tag map")?;
+    write_atomically(path, &payload)
+}
+
+/// Finds a translation unit by its `transunit_id`.
+pub fn find_transunit<'a>(
+    document: &'a JliffDocument,
+    transunit_id: &str,
+) -> Option<&'a TransUnit> {
+    document
+        .transunits
+        .iter()
+        .find(|unit| unit.transunit_id == transunit_id)
+}
+
+/// Writes `payload` next to `path` and renames it into place so readers never observe a
+/// partially written file.
+fn write_atomically(path: &Path, payload: &str) -> Result<()> {
+    let tmp_path = path.with_extension("json.tmp");
+    fs::write(&tmp_path, payload)
+        .with_context(|| format!("Failed to write {}", tmp_path.display()))?;
+    fs::rename(&tmp_path, path).with_context(|| format!("Failed to replace {}", path.display()))
+}
@@ -0,0 +1,494 @@
+//! Bilingual review tables for reviewers working outside the app.
+//!
+//! A JLIFF document is exported as an RTF table with one row per segment:
+//!
+//! | Segment | Source | Target | Check |
+//! |---------|--------|--------|-------|
+//!
+//! Reviewers edit the *Target* column in any word processor and send the file back. The
+//! *Check* column holds a short hash of the exported target, which lets the import tell
+//! reviewer edits apart from edits made in the app since the export.
+//!
+//! Placeholders (`{{ph:1}}`, ...) are exported verbatim; an edit that adds, drops or
+//! alters placeholders is reported instead of applied.
+
+use anyhow::{Result, bail};
+use sha2::{Digest, Sha256};
+
+use super::model::JliffDocument;
+use super::placeholder_consistency::placeholder_tokens;
+
+/// Header cells of the review table.
+pub const REVIEW_COLUMNS: [&str; 4] = ["Segment", "Source", "Target", "Check"];
+
+/// Right edges of the table cells in twips (A4 landscape, 0.5" margins).
+const CELL_EDGES: [u32; 4] = [2000, 8000, 14000, 15398];
+
+/// Destinations whose content is never document text.
+const SKIPPED_DESTINATIONS: &[&str] = &[
+    "fonttbl",
+    "colortbl",
+    "stylesheet",
+    "info",
+    "pict",
+    "header",
+    "headerl",
+    "headerr",
+    "headerf",
+    "footer",
+    "footerl",
+    "footerr",
+    "footerf",
+    "listtable",
+    "listoverridetable",
+    "rsidtbl",
+    "generator",
+    "xmlnstbl",
+    "themedata",
+    "colorschememapping",
+    "latentstyles",
+    "datastore",
+    "pgdsctbl",
+    "revtbl",
+    "filetbl",
+    "object",
+    "fldinst",
+];
+
+/// One segment row read back from a review table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReviewRow {
+    pub transunit_id: String,
+    pub source: String,
+    pub target: String,
+    pub check: String,
+}
+
+/// Why a reviewer edit was not applied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReviewConflict {
+    /// The target was also edited in the app after the export.
+    ChangedSinceExport,
+    /// The reviewer added, removed or altered placeholders.
+    PlaceholdersChanged,
+}
+
+impl ReviewConflict {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ReviewConflict::ChangedSinceExport => "changed_since_export",
+            ReviewConflict::PlaceholdersChanged => "placeholders_changed",
+        }
+    }
+}
+
+/// Outcome of comparing a review row with the current target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReviewDecision {
+    Unchanged,
+    Apply,
+    Conflict(ReviewConflict),
+}
+
+/// Short fingerprint of an exported target, stored in the *Check* column.
+pub fn target_check(target: &str) -> String {
+    let digest = Sha256::digest(target.as_bytes());
+    digest[..6]
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+/// Decides what to do with a review row given the segment's current target.
+pub fn review_decision(current_target: &str, row: &ReviewRow) -> ReviewDecision {
+    if row.target == current_target || target_check(&row.target) == row.check {
+        return ReviewDecision::Unchanged;
+    }
+    if target_check(current_target) != row.check {
+        return ReviewDecision::Conflict(ReviewConflict::ChangedSinceExport);
+    }
+    if placeholder_tokens(&row.target) != placeholder_tokens(current_target) {
+        return ReviewDecision::Conflict(ReviewConflict::PlaceholdersChanged);
+    }
+    ReviewDecision::Apply
+}
+
+/// Renders `document` as an RTF review table.
+pub fn render_review_rtf(document: &JliffDocument) -> String {
+    let mut out = String::from(
+        "{\\rtf1\\ansi\\ansicpg1252\\deff0{\\fonttbl{\\f0\\fswiss Calibri;}}\n\
+         \\paperw16838\\paperh11906\\landscape\\margl720\\margr720\\margt720\\margb720\n\
+         \\f0\\fs20\n",
+    );
+    out.push_str("\\pard{\\b ");
+    push_escaped(
+        &mut out,
+        &format!(
+            "{} \u{2013} {} ({} \u{2192} {})",
+            document.project_name,
+            document.file,
+            document.source_language,
+            document.target_language
+        ),
+    );
+    out.push_str("}\\par\n");
+
+    push_row(&mut out, &REVIEW_COLUMNS, true);
+    for unit in &document.transunits {
+        let target = unit.effective_target();
+        let check = target_check(target);
+        push_row(
+            &mut out,
+            &[&unit.transunit_id, &unit.source, target, &check],
+            false,
+        );
+    }
+    out.push_str("\\pard\\par\n}\n");
+    out
+}
+
+/// Reads the segment rows of a review table, skipping the header and any row that does
+/// not have the four review columns.
+pub fn parse_review_rtf(content: &str) -> Result<Vec<ReviewRow>> {
+    if !content.trim_start().starts_with("{\\rtf") {
+        bail!("File is not an RTF document");
+    }
+    Ok(read_table_rows(content)
+        .into_iter()
+        .filter(|cells| cells.len() == REVIEW_COLUMNS.len())
+        .filter(|cells| cells[0].trim() != REVIEW_COLUMNS[0])
+        .map(|cells| ReviewRow {
+            transunit_id: cells[0].trim().to_string(),
+            source: cells[1].clone(),
+            target: cells[2].clone(),
+            check: cells[3].trim().to_string(),
+        })
+        .filter(|row| !row.transunit_id.is_empty())
+        .collect())
+}
+
+fn push_row(out: &mut String, cells: &[&str], header: bool) {
+    out.push_str("\\trowd\\trgaph70");
+    if header {
+        out.push_str("\\trhdr");
+    }
+    for edge in CELL_EDGES {
+        out.push_str(&format!(
+            "\\clbrdrt\\brdrs\\brdrw10\\clbrdrl\\brdrs\\brdrw10\\clbrdrb\\brdrs\\brdrw10\\clbrdrr\\brdrs\\brdrw10\\cellx{edge}"
+        ));
+    }
+    out.push('\n');
+    for cell in cells {
+        out.push_str("\\pard\\intbl ");
+        if header {
+            out.push_str("{\\b ");
+            push_escaped(out, cell);
+            out.push('}');
+        } else {
+            push_escaped(out, cell);
+        }
+        out.push_str("\\cell\n");
+    }
+    out.push_str("\\row\n");
+}
+
+fn push_escaped(out: &mut String, text: &str) {
+    for ch in text.chars() {
+        match ch {
+            '\\' | '{' | '}' => {
+                out.push('\\');
+                out.push(ch);
+            }
+            '\n' => out.push_str("\\line "),
+            '\t' => out.push_str("\\tab "),
+            '\r' => {}
+            ' '..='~' => out.push(ch),
+            _ => {
+                let mut units = [0u16; 2];
+                for unit in ch.encode_utf16(&mut units) {
+                    out.push_str(&format!("\\u{}?", *unit as i16));
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+struct GroupState {
+    skip: bool,
+    unicode_skip: usize,
+    at_start: bool,
+}
+
+/// Extracts the text of every table row as a list of cells.
+fn read_table_rows(content: &str) -> Vec<Vec<String>> {
+    let chars: Vec<char> = content.chars().collect();
+    let mut rows = Vec::new();
+    let mut row: Vec<String> = Vec::new();
+    let mut cell = String::new();
+    let mut in_table = false;
+    let mut pending_skip = 0usize;
+    let mut high_surrogate: Option<u16> = None;
+
+    let mut stack: Vec<GroupState> = Vec::new();
+    let mut state = GroupState {
+        skip: false,
+        unicode_skip: 1,
+        at_start: false,
+    };
+
+    let mut i = 0;
+    while i < chars.len() {
+        let ch = chars[i];
+        match ch {
+            '{' => {
+                stack.push(state);
+                state.at_start = true;
+                i += 1;
+            }
+            '}' => {
+                state = stack.pop().unwrap_or(state);
+                i += 1;
+            }
+            '\\' => {
+                let at_start = std::mem::replace(&mut state.at_start, false);
+                let Some(&next) = chars.get(i + 1) else {
+                    break;
+                };
+                if next.is_ascii_alphabetic() {
+                    let word_start = i + 1;
+                    let mut j = word_start;
+                    while j < chars.len() && chars[j].is_ascii_alphabetic() {
+                        j += 1;
+                    }
+                    let word: String = chars[word_start..j].iter().collect();
+                    let param_start = j;
+                    if j < chars.len() && (chars[j] == '-' || chars[j].is_ascii_digit()) {
+                        j += 1;
+                        while j < chars.len() && chars[j].is_ascii_digit() {
+                            j += 1;
+                        }
+                    }
+                    let param: Option<i32> = chars[param_start..j]
+                        .iter()
+                        .collect::<String>()
+                        .parse()
+                        .ok();
+                    if j < chars.len() && chars[j] == ' ' {
+                        j += 1;
+                    }
+                    i = j;
+
+                    if at_start && SKIPPED_DESTINATIONS.contains(&word.as_str()) {
+                        state.skip = true;
+                    }
+                    if state.skip {
+                        continue;
+                    }
+                    match word.as_str() {
+                        "uc" => state.unicode_skip = param.unwrap_or(1).max(0) as usize,
+                        "u" => {
+                            let unit = param.unwrap_or(0) as i16 as u16;
+                            push_utf16(&mut cell, &mut high_surrogate, unit);
+                            pending_skip = state.unicode_skip;
+                        }
+                        "pard" => in_table = false,
+                        "intbl" => in_table = true,
+                        "par" => {
+                            if in_table {
+                                cell.push('\n');
+                            } else {
+                                cell.clear();
+                            }
+                        }
+                        "line" => cell.push('\n'),
+                        "tab" => cell.push('\t'),
+                        "cell" => {
+                            let text = std::mem::take(&mut cell);
+                            row.push(text.trim_end_matches('\n').to_string());
+                        }
+                        "row" => {
+                            rows.push(std::mem::take(&mut row));
+                            cell.clear();
+                        }
+                        "emdash" => cell.push('\u{2014}'),
+                        "endash" => cell.push('\u{2013}'),
+                        "lquote" => cell.push('\u{2018}'),
+                        "rquote" => cell.push('\u{2019}'),
+                        "ldblquote" => cell.push('\u{201C}'),
+                        "rdblquote" => cell.push('\u{201D}'),
+                        "bullet" => cell.push('\u{2022}'),
+                        _ => {}
+                    }
+                } else {
+                    i += 2;
+                    match next {
+                        '*' if at_start => state.skip = true,
+                        '\'' => {
+                            let hex: String = chars.iter().skip(i).take(2).collect();
+                            i += hex.len();
+                            if state.skip {
+                                continue;
+                            }
+                            if pending_skip > 0 {
+                                pending_skip -= 1;
+                                continue;
+                            }
+                            if let Ok(byte) = u8::from_str_radix(&hex, 16) {
+                                cell.push(decode_cp1252(byte));
+                            }
+                        }
+                        _ if state.skip => {}
+                        '\\' | '{' | '}' => cell.push(next),
+                        '~' => cell.push('\u{A0}'),
+                        '_' => cell.push('\u{2011}'),
+                        _ => {}
+                    }
+                }
+            }
+            '\r' | '\n' => i += 1,
+            _ => {
+                state.at_start = false;
+                i += 1;
+                if state.skip {
+                    continue;
+                }
+                if pending_skip > 0 {
+                    pending_skip -= 1;
+                    continue;
+                }
+                cell.push(ch);
+            }
+        }
+    }
+    rows
+}
+
+fn push_utf16(out: &mut String, high_surrogate: &mut Option<u16>, unit: u16) {
+    if (0xD800..0xDC00).contains(&unit) {
+        *high_surrogate = Some(unit);
+        return;
+    }
+    let units = match high_surrogate.take() {
+        Some(high) => vec![high, unit],
+        None => vec![unit],
+    };
+    out.extend(char::decode_utf16(units).map(|ch| ch.unwrap_or('\u{FFFD}')));
+}
+
+/// Decodes a Windows-1252 byte (the code page of `\ansicpg1252` documents).
+fn decode_cp1252(byte: u8) -> char {
+    const HIGH: [char; 32] = [
+        '\u{20AC}', '\u{FFFD}', '\u{201A}', '\u{0192}', '\u{201E}', '\u{2026}', '\u{2020}',
+        '\u{2021}', '\u{02C6}', '\u{2030}', '\u{0160}', '\u{2039}', '\u{0152}', '\u{FFFD}',
+        '\u{017D}', '\u{FFFD}', '\u{FFFD}', '\u{2018}', '\u{2019}', '\u{201C}', '\u{201D}',
+        '\u{2022}', '\u{2013}', '\u{2014}', '\u{02DC}', '\u{2122}', '\u{0161}', '\u{203A}',
+        '\u{0153}', '\u{FFFD}', '\u{017E}', '\u{0178}',
+    ];
+    match byte {
+        0x80..=0x9F => HIGH[(byte - 0x80) as usize],
+        _ => byte as char,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jliff::model::TransUnit;
+
+    fn unit(transunit_id: &str, source: &str, target: &str) -> TransUnit {
+        TransUnit {
+            unit_id: "1".into(),
+            transunit_id: transunit_id.into(),
+            source: source.into(),
+            target_translation: target.into(),
+            target_qa_1: None,
+            target_qa_2: None,
+            target_postedit: None,
+            translation_notes: None,
+            qa_notes: None,
+            source_notes: None,
+            metadata: None,
+            status: None,
+            sub_state: None,
+            notes: Vec::new(),
+            propagated_from: None,
+        }
+    }
+
+    #[test]
+    fn review_table_round_trips_through_rtf() -> Result<()> {
+        let document = JliffDocument {
+            project_name: "Demo".into(),
+            project_id: "p".into(),
+            file: "ui.json".into(),
+            user: "u".into(),
+            source_language: "en".into(),
+            target_language: "de".into(),
+            transunits: vec![
+                unit(
+                    "u1-s1",
+                    "Save {{ph:1}}",
+                    "Speichern {{ph:1}} \u{2013} gr\u{fc}\u{df}e",
+                ),
+                unit("u1-s2", "Path C:\\tmp\nnext", "\u{1F600} {braces}"),
+            ],
+            segment_operations: Vec::new(),
+        };
+
+        let rows = parse_review_rtf(&render_review_rtf(&document))?;
+        assert_eq!(rows.len(), 2);
+        for (row, unit) in rows.iter().zip(&document.transunits) {
+            assert_eq!(row.transunit_id, unit.transunit_id);
+            assert_eq!(row.source, unit.source);
+            assert_eq!(row.target, unit.target_translation);
+            assert_eq!(row.check, target_check(&unit.target_translation));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn reads_word_processor_output() -> Result<()> {
+        let rtf = r#"{\rtf1\ansi\ansicpg1252\uc1{\fonttbl{\f0 Calibri;}}{\*\generator Word;}
+\trowd\cellx2000\cellx8000\cellx14000\cellx15398
+\pard\plain\intbl u1-s1\cell \pard\intbl Caf\'e9\cell \pard\intbl {\rtlch Kaffee }\u8364\'80 5\par zwei\cell \pard\intbl abc\cell \row }"#;
+        let rows = parse_review_rtf(rtf)?;
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].source, "Café");
+        assert_eq!(rows[0].target, "Kaffee \u{20AC} 5\nzwei");
+        assert_eq!(rows[0].check, "abc");
+        Ok(())
+    }
+
+    #[test]
+    fn detects_conflicts_and_placeholder_edits() {
+        let exported = "Hallo {{ph:1}}";
+        let row = |target: &str| ReviewRow {
+            transunit_id: "u1-s1".into(),
+            source: "Hello {{ph:1}}".into(),
+            target: target.into(),
+            check: target_check(exported),
+        };
+
+        assert_eq!(
+            review_decision(exported, &row(exported)),
+            ReviewDecision::Unchanged
+        );
+        assert_eq!(
+            review_decision("Edited in app", &row(exported)),
+            ReviewDecision::Unchanged
+        );
+        assert_eq!(
+            review_decision(exported, &row("Servus {{ph:1}}")),
+            ReviewDecision::Apply
+        );
+        assert_eq!(
+            review_decision("Edited in app", &row("Servus {{ph:1}}")),
+            ReviewDecision::Conflict(ReviewConflict::ChangedSinceExport)
+        );
+        assert_eq!(
+            review_decision(exported, &row("Servus")),
+            ReviewDecision::Conflict(ReviewConflict::PlaceholdersChanged)
+        );
+    }
+}
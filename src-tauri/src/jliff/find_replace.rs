@@ -0,0 +1,190 @@
+//! Batch find and replace in segment targets.
+//!
+//! A [`TargetReplace`] holds the compiled pattern and replacement of one run. Literal
+//! searches are escaped and replaced verbatim; regular expressions may refer to capture
+//! groups as `$1` or `${name}`. Inline codes must survive a replacement unchanged, so a
+//! segment whose `{{...}}` placeholders would be added, dropped or altered is skipped and
+//! reported instead of edited.
+
+use regex::{NoExpand, Regex, RegexBuilder};
+
+use super::model::JliffDocument;
+use super::placeholder_consistency::placeholder_tokens;
+
+/// Compiled find and replace of one run.
+#[derive(Debug, Clone)]
+pub struct TargetReplace {
+    pattern: Regex,
+    replacement: String,
+    expand: bool,
+}
+
+impl TargetReplace {
+    /// Compiles `pattern`, as a regular expression when `regex` is set and as literal text
+    /// otherwise.
+    pub fn new(
+        pattern: &str,
+        replacement: &str,
+        regex: bool,
+        case_sensitive: bool,
+    ) -> Result<Self, regex::Error> {
+        let source = if regex {
+            pattern.to_string()
+        } else {
+            regex::escape(pattern)
+        };
+        Ok(Self {
+            pattern: RegexBuilder::new(&source)
+                .case_insensitive(!case_sensitive)
+                .build()?,
+            replacement: replacement.to_string(),
+            expand: regex,
+        })
+    }
+
+    /// `text` with every match replaced, and the number of matches; `None` without matches.
+    pub fn apply(&self, text: &str) -> Option<(String, usize)> {
+        let matches = self
+            .pattern
+            .find_iter(text)
+            .filter(|found| !found.is_empty())
+            .count();
+        if matches == 0 {
+            return None;
+        }
+        let replaced = if self.expand {
+            self.pattern.replace_all(text, self.replacement.as_str())
+        } else {
+            self.pattern.replace_all(text, NoExpand(&self.replacement))
+        };
+        Some((replaced.into_owned(), matches))
+    }
+}
+
+/// A target changed (or, in a dry run, to be changed) by a replacement.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TargetReplacement {
+    pub transunit_id: String,
+    pub previous_target: String,
+    pub new_target: String,
+    pub matches: usize,
+}
+
+/// Outcome of a replacement over one document.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DocumentReplaceResult {
+    pub replacements: Vec<TargetReplacement>,
+    /// Segments left untouched because the replacement would alter their placeholders.
+    pub skipped_transunit_ids: Vec<String>,
+}
+
+/// Replaces matches in the effective targets of `document`. With `apply` unset the document
+/// is left unchanged and the result only reports what would change.
+pub fn replace_in_targets(
+    document: &mut JliffDocument,
+    replace: &TargetReplace,
+    apply: bool,
+) -> DocumentReplaceResult {
+    let mut result = DocumentReplaceResult::default();
+    for unit in &mut document.transunits {
+        let target = unit.effective_target();
+        let Some((new_target, matches)) = replace.apply(target) else {
+            continue;
+        };
+        if new_target == target {
+            continue;
+        }
+        if placeholder_tokens(&new_target) != placeholder_tokens(target) {
+            result.skipped_transunit_ids.push(unit.transunit_id.clone());
+            continue;
+        }
+        let previous_target = target.to_string();
+        if apply {
+            *unit.effective_target_mut() = new_target.clone();
+        }
+        result.replacements.push(TargetReplacement {
+            transunit_id: unit.transunit_id.clone(),
+            previous_target,
+            new_target,
+            matches,
+        });
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jliff::model::TransUnit;
+
+    fn document(targets: &[&str]) -> JliffDocument {
+        JliffDocument {
+            project_name: "Demo".into(),
+            project_id: "demo".into(),
+            file: "demo.xlf".into(),
+            user: "tester".into(),
+            source_language: "en-US".into(),
+            target_language: "de-DE".into(),
+            transunits: targets
+                .iter()
+                .enumerate()
+                .map(|(index, target)| TransUnit {
+                    unit_id: format!("{index}"),
+                    transunit_id: format!("u{index}-s1"),
+                    source: "Source".into(),
+                    target_translation: target.to_string(),
+                    target_qa_1: None,
+                    target_qa_2: None,
+                    target_postedit: None,
+                    translation_notes: None,
+                    qa_notes: None,
+                    source_notes: None,
+                    metadata: None,
+                    status: None,
+                    sub_state: None,
+                    notes: Vec::new(),
+                    propagated_from: None,
+                })
+                .collect(),
+            segment_operations: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn replaces_literal_text_verbatim() {
+        let replace = TargetReplace::new("e-mail", "E-Mail ($)", false, false).unwrap();
+        let mut document = document(&["Ihre e-mail und E-MAIL", "Nichts"]);
+
+        let preview = replace_in_targets(&mut document, &replace, false);
+        assert_eq!(preview.replacements.len(), 1);
+        assert_eq!(preview.replacements[0].matches, 2);
+        assert_eq!(
+            document.transunits[0].target_translation,
+            "Ihre e-mail und E-MAIL"
+        );
+
+        replace_in_targets(&mut document, &replace, true);
+        assert_eq!(
+            document.transunits[0].target_translation,
+            "Ihre E-Mail ($) und E-Mail ($)"
+        );
+    }
+
+    #[test]
+    fn expands_groups_and_skips_placeholder_changes() {
+        let replace = TargetReplace::new(r"(\d+) ?%", "$1 %", true, true).unwrap();
+        let mut document = document(&["50% von {{ph:1}}", "{{ph:2}}% 10%"]);
+        let result = replace_in_targets(&mut document, &replace, true);
+        assert_eq!(
+            document.transunits[0].target_translation,
+            "50 % von {{ph:1}}"
+        );
+        assert_eq!(document.transunits[1].target_translation, "{{ph:2}}% 10 %");
+
+        let strip = TargetReplace::new(r"\{\{ph:\d\}\}", "", true, true).unwrap();
+        let skipped = replace_in_targets(&mut document, &strip, true);
+        assert!(skipped.replacements.is_empty());
+        assert_eq!(skipped.skipped_transunit_ids, ["u0-s1", "u1-s1"]);
+        assert_eq!(result.replacements.len(), 2);
+    }
+}
@@ -0,0 +1,351 @@
+//! Optional git history of a project's JLIFF documents and tag maps.
+//!
+//! A project opts in by initialising a repository in its folder. Significant events such as
+//! conversions, pretranslation, find and replace or snapshot restores then commit the current
+//! documents with a structured message: a summary line followed by `Event:` and `Files:`
+//! trailers. Only `*.jliff.json` and `*.tags.json` files are versioned; sources, snapshots and
+//! deliveries stay out of the repository. Checking out a commit writes its files back and is
+//! committed in turn, so history stays linear and a checkout can be undone like any change.
+
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, Utc};
+use gix::ObjectId;
+use gix::objs::tree::{Entry, EntryKind};
+
+use super::snapshots::SNAPSHOTS_DIR;
+
+/// Repository folder inside the project folder.
+pub const HISTORY_DIR: &str = ".git";
+
+const TRACKED_SUFFIXES: [&str; 2] = [".jliff.json", ".tags.json"];
+const COMMITTER_NAME: &str = "WEG Translator";
+const COMMITTER_EMAIL: &str = "history@weg-translator.invalid";
+const EVENT_TRAILER: &str = "Event: ";
+const FILES_TRAILER: &str = "Files: ";
+
+/// What a commit records.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistoryEvent {
+    /// Short machine-readable name, such as `find-replace`.
+    pub kind: &'static str,
+    pub summary: String,
+    /// Project-relative documents the event changed; empty when it concerns the project.
+    pub files: Vec<String>,
+}
+
+/// One commit of a project's history.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistoryEntry {
+    pub commit_id: String,
+    pub summary: String,
+    pub event: Option<String>,
+    pub files: Vec<String>,
+    pub committed_at: DateTime<Utc>,
+}
+
+/// Whether the project folder has opted in to history.
+pub fn is_enabled(project_root: &Path) -> bool {
+    project_root.join(HISTORY_DIR).is_dir()
+}
+
+/// Initialises the repository of a project, unless it exists, and commits the current
+/// documents. Returns the commit, or `None` when nothing changed since the last one.
+pub fn enable(project_root: &Path) -> Result<Option<HistoryEntry>> {
+    if !is_enabled(project_root) {
+        gix::init(project_root).with_context(|| {
+            format!(
+                "unable to create a repository in {}",
+                project_root.display()
+            )
+        })?;
+        let git_dir = project_root.join(HISTORY_DIR);
+        let mut config = OpenOptions::new()
+            .append(true)
+            .open(git_dir.join("config"))
+            .context("unable to open the repository configuration")?;
+        writeln!(
+            config,
+            "[user]\n\tname = {COMMITTER_NAME}\n\temail = {COMMITTER_EMAIL}"
+        )
+        .context("unable to configure the repository")?;
+        // Keep `git status` in the project folder limited to what the history versions.
+        fs::create_dir_all(git_dir.join("info"))?;
+        fs::write(
+            git_dir.join("info").join("exclude"),
+            format!(
+                "*\n!*/\n!*{}\n!*{}\n/{SNAPSHOTS_DIR}/\n",
+                TRACKED_SUFFIXES[0], TRACKED_SUFFIXES[1]
+            ),
+        )
+        .context("unable to write the repository excludes")?;
+    }
+    commit(
+        project_root,
+        &HistoryEvent {
+            kind: "enable",
+            summary: "Start project history".into(),
+            files: Vec::new(),
+        },
+    )
+}
+
+/// Commits the current documents of the project. Returns the commit, or `None` when they
+/// did not change since the last one.
+pub fn commit(project_root: &Path, event: &HistoryEvent) -> Result<Option<HistoryEntry>> {
+    let repo = open(project_root)?;
+    let mut files = BTreeMap::new();
+    collect_tracked(project_root, project_root, &mut files)?;
+    let tree = write_tree(&repo, &files)?;
+
+    let parent = repo.head()?.id().map(|id| id.detach());
+    if let Some(parent) = parent
+        && repo.find_commit(parent)?.tree_id()?.detach() == tree
+    {
+        return Ok(None);
+    }
+    let id = repo
+        .commit("HEAD", format_message(event), tree, parent)
+        .context("unable to commit the project history")?
+        .detach();
+    entry(&repo, id).map(Some)
+}
+
+/// Lists up to `limit` commits, newest first.
+pub fn list(project_root: &Path, limit: usize) -> Result<Vec<HistoryEntry>> {
+    let repo = open(project_root)?;
+    let Some(head) = repo.head()?.id() else {
+        return Ok(Vec::new());
+    };
+    let mut entries = Vec::new();
+    for info in head.ancestors().all()? {
+        if entries.len() >= limit {
+            break;
+        }
+        entries.push(entry(&repo, info?.id)?);
+    }
+    Ok(entries)
+}
+
+/// Reads the documents of a commit as `(project-relative path, contents)` pairs.
+pub fn read_commit(project_root: &Path, commit_id: &str) -> Result<Vec<(String, Vec<u8>)>> {
+    let repo = open(project_root)?;
+    let id = ObjectId::from_hex(commit_id.trim().as_bytes())
+        .with_context(|| format!("'{commit_id}' is not a commit id"))?;
+    let commit = repo
+        .find_commit(id)
+        .with_context(|| format!("commit {commit_id} is not part of the project history"))?;
+    let mut files = Vec::new();
+    let mut pending = vec![(String::new(), commit.tree_id()?.detach())];
+    while let Some((prefix, tree_id)) = pending.pop() {
+        let tree = repo.find_tree(tree_id)?;
+        for entry in tree.iter() {
+            let entry = entry?;
+            let name = entry.filename().to_string();
+            if name.is_empty() || name == "." || name == ".." || name.contains(['/', '\\']) {
+                bail!("commit {commit_id} contains the invalid path '{name}'");
+            }
+            let rel_path = if prefix.is_empty() {
+                name
+            } else {
+                format!("{prefix}/{name}")
+            };
+            if entry.mode().is_tree() {
+                pending.push((rel_path, entry.oid().to_owned()));
+            } else if entry.mode().is_blob() {
+                let data = repo.find_object(entry.oid())?.detach().data;
+                files.push((rel_path, data));
+            }
+        }
+    }
+    files.sort_by(|left, right| left.0.cmp(&right.0));
+    Ok(files)
+}
+
+fn open(project_root: &Path) -> Result<gix::Repository> {
+    gix::open(project_root)
+        .with_context(|| format!("{} has no project history", project_root.display()))
+}
+
+fn entry(repo: &gix::Repository, id: ObjectId) -> Result<HistoryEntry> {
+    let commit = repo.find_commit(id)?;
+    let message = commit.message_raw_sloppy().to_string();
+    let seconds = commit.time()?.seconds;
+    let mut lines = message.lines();
+    let summary = lines.next().unwrap_or_default().trim().to_string();
+    let mut event = None;
+    let mut files = Vec::new();
+    for line in lines {
+        if let Some(kind) = line.strip_prefix(EVENT_TRAILER) {
+            event = Some(kind.trim().to_string());
+        } else if let Some(list) = line.strip_prefix(FILES_TRAILER) {
+            files = list.split(", ").map(str::to_string).collect();
+        }
+    }
+    Ok(HistoryEntry {
+        commit_id: id.to_string(),
+        summary,
+        event,
+        files,
+        committed_at: DateTime::from_timestamp(seconds, 0).unwrap_or_default(),
+    })
+}
+
+fn format_message(event: &HistoryEvent) -> String {
+    let mut message = format!(
+        "{}\n\n{EVENT_TRAILER}{}\n",
+        event.summary.trim(),
+        event.kind
+    );
+    if !event.files.is_empty() {
+        message.push_str(&format!("{FILES_TRAILER}{}\n", event.files.join(", ")));
+    }
+    message
+}
+
+/// Collects the versioned files under `dir`, keyed by their path relative to the project.
+fn collect_tracked(
+    project_root: &Path,
+    dir: &Path,
+    files: &mut BTreeMap<String, Vec<u8>>,
+) -> Result<()> {
+    for item in fs::read_dir(dir).with_context(|| format!("unable to list {}", dir.display()))? {
+        let item = item?;
+        let path = item.path();
+        let name = item.file_name().to_string_lossy().into_owned();
+        if item.file_type()?.is_dir() {
+            if dir == project_root && (name == HISTORY_DIR || name == SNAPSHOTS_DIR) {
+                continue;
+            }
+            collect_tracked(project_root, &path, files)?;
+        } else if TRACKED_SUFFIXES.iter().any(|suffix| name.ends_with(suffix)) {
+            let relative = path.strip_prefix(project_root)?;
+            let components: Vec<String> = relative
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy().into_owned())
+                .collect();
+            let contents =
+                fs::read(&path).with_context(|| format!("unable to read {}", path.display()))?;
+            files.insert(components.join("/"), contents);
+        }
+    }
+    Ok(())
+}
+
+/// Writes the blobs and trees of `files` and returns the id of the root tree.
+fn write_tree(repo: &gix::Repository, files: &BTreeMap<String, Vec<u8>>) -> Result<ObjectId> {
+    enum Node {
+        File(ObjectId),
+        Dir(BTreeMap<String, Node>),
+    }
+
+    fn write_dir(repo: &gix::Repository, nodes: &BTreeMap<String, Node>) -> Result<ObjectId> {
+        let mut tree = gix::objs::Tree::empty();
+        for (name, node) in nodes {
+            let (kind, oid) = match node {
+                Node::File(oid) => (EntryKind::Blob, *oid),
+                Node::Dir(children) => (EntryKind::Tree, write_dir(repo, children)?),
+            };
+            tree.entries.push(Entry {
+                mode: kind.into(),
+                filename: name.as_str().into(),
+                oid,
+            });
+        }
+        tree.entries.sort_by(git_order);
+        Ok(repo.write_object(&tree)?.detach())
+    }
+
+    let mut root = BTreeMap::new();
+    for (rel_path, contents) in files {
+        let oid = repo.write_blob(contents)?.detach();
+        let mut parts: Vec<&str> = rel_path.split('/').collect();
+        let file_name = parts.pop().unwrap_or_default();
+        let mut dir = &mut root;
+        for part in parts {
+            let node = dir
+                .entry(part.to_string())
+                .or_insert_with(|| Node::Dir(BTreeMap::new()));
+            dir = match node {
+                Node::Dir(children) => children,
+                Node::File(_) => bail!("'{rel_path}' is both a file and a folder"),
+            };
+        }
+        dir.insert(file_name.to_string(), Node::File(oid));
+    }
+    write_dir(repo, &root)
+}
+
+/// Git sorts tree entries by name, comparing folders as if their name ended in `/`.
+fn git_order(left: &Entry, right: &Entry) -> Ordering {
+    let key = |entry: &Entry| {
+        let mut key = entry.filename.to_vec();
+        if entry.mode.is_tree() {
+            key.push(b'/');
+        }
+        key
+    };
+    key(left).cmp(&key(right))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn commits_only_changes_and_reads_commits_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let documents = root.join("Translations").join("en-US_de-DE");
+        fs::create_dir_all(&documents).unwrap();
+        fs::create_dir_all(root.join(SNAPSHOTS_DIR)).unwrap();
+        fs::write(documents.join("manual.jliff.json"), "{\"v\":1}").unwrap();
+        fs::write(documents.join("manual.tags.json"), "{}").unwrap();
+        fs::write(root.join("manual.docx"), "source").unwrap();
+        fs::write(root.join(SNAPSHOTS_DIR).join("old.jliff.json"), "{}").unwrap();
+
+        let first = enable(root)
+            .unwrap()
+            .expect("the first commit records the documents");
+        assert_eq!(first.event.as_deref(), Some("enable"));
+        let event = HistoryEvent {
+            kind: "find-replace",
+            summary: "Replace 'colour' in 1 segment".into(),
+            files: vec!["Translations/en-US_de-DE/manual.jliff.json".into()],
+        };
+        assert!(commit(root, &event).unwrap().is_none());
+
+        fs::write(documents.join("manual.jliff.json"), "{\"v\":2}").unwrap();
+        let second = commit(root, &event)
+            .unwrap()
+            .expect("the change is committed");
+        assert_eq!(second.summary, "Replace 'colour' in 1 segment");
+        assert_eq!(second.files, event.files);
+
+        let history = list(root, 10).unwrap();
+        let ids: Vec<&str> = history
+            .iter()
+            .map(|entry| entry.commit_id.as_str())
+            .collect();
+        assert_eq!(
+            ids,
+            vec![second.commit_id.as_str(), first.commit_id.as_str()]
+        );
+
+        let files = read_commit(root, &first.commit_id).unwrap();
+        let paths: Vec<&str> = files.iter().map(|(path, _)| path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "Translations/en-US_de-DE/manual.jliff.json",
+                "Translations/en-US_de-DE/manual.tags.json"
+            ]
+        );
+        assert_eq!(files[0].1, b"{\"v\":1}");
+    }
+}
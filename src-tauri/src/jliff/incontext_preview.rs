@@ -0,0 +1,191 @@
+//! In-context review pages.
+//!
+//! Reviewers judge a translation better in the layout of the document than in a segment
+//! grid. [`render_incontext_html`] rebuilds an approximation of the document as a standalone
+//! HTML page: every JLIFF unit becomes a paragraph, its segments are rendered inline with
+//! [`render_segment_html`], and each segment is wrapped in an element anchored by its
+//! transunit id and colored by its status. Segments without a target show the source, so
+//! untranslated passages stand out instead of leaving gaps.
+
+use super::bidi::target_direction;
+use super::model::{JliffDocument, SegmentStatus};
+use super::preview::{escape_html, render_segment_html};
+use super::tag_map::TagMapDoc;
+
+/// Background color of each status in the page and its legend.
+const STATUS_COLORS: [(SegmentStatus, &str); 5] = [
+    (SegmentStatus::New, "#f8d7da"),
+    (SegmentStatus::Draft, "#fff3cd"),
+    (SegmentStatus::Translated, "#d1ecf1"),
+    (SegmentStatus::Reviewed, "#d4edda"),
+    (SegmentStatus::Approved, "#c3e6cb"),
+];
+
+/// A rendered review page.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IncontextPreview {
+    pub html: String,
+    pub segment_count: usize,
+    /// Segments shown with their source because the target is empty.
+    pub untranslated_segments: usize,
+}
+
+/// Anchor id of a segment in the page, usable as a `#fragment`.
+pub fn segment_anchor(transunit_id: &str) -> String {
+    let sanitized: String = transunit_id
+        .chars()
+        .map(|ch| {
+            if ch.is_ascii_alphanumeric() || ch == '-' || ch == '_' {
+                ch
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("seg-{sanitized}")
+}
+
+/// Renders `document` as a review page, resolving inline codes through `tag_map` when
+/// available.
+pub fn render_incontext_html(
+    document: &JliffDocument,
+    tag_map: Option<&TagMapDoc>,
+) -> IncontextPreview {
+    let mut body = String::new();
+    let mut untranslated_segments = 0;
+    let mut current_unit: Option<&str> = None;
+    for unit in &document.transunits {
+        if current_unit != Some(unit.unit_id.as_str()) {
+            if current_unit.is_some() {
+                body.push_str("</p>\n");
+            }
+            body.push_str(&format!(
+                "<p class=\"wt-unit\" data-unit=\"{}\">",
+                escape_html(&unit.unit_id)
+            ));
+            current_unit = Some(&unit.unit_id);
+        }
+
+        let segment =
+            tag_map.and_then(|tag_map| tag_map.segment_for(&unit.unit_id, &unit.transunit_id));
+        let target = unit.effective_target();
+        let untranslated = target.trim().is_empty();
+        let (text, direction) = if untranslated {
+            untranslated_segments += 1;
+            (unit.source.as_str(), None)
+        } else {
+            (target, Some(target_direction(document, unit)))
+        };
+        let status = unit.effective_status();
+        body.push_str(&format!(
+            "<span id=\"{}\" class=\"wt-seg wt-{}{}\" title=\"{} ({})\"{}>{}</span> ",
+            segment_anchor(&unit.transunit_id),
+            status.as_str(),
+            if untranslated { " wt-untranslated" } else { "" },
+            escape_html(&unit.transunit_id),
+            status.as_str(),
+            direction
+                .map(|direction| format!(" dir=\"{}\"", direction.as_str()))
+                .unwrap_or_default(),
+            render_segment_html(text, segment).html
+        ));
+    }
+    if current_unit.is_some() {
+        body.push_str("</p>\n");
+    }
+
+    let mut styles = String::from(
+        "body{font-family:sans-serif;max-width:52rem;margin:2rem auto;line-height:1.6}\
+         .wt-seg{padding:0 .15em;border-radius:3px}\
+         .wt-seg:target{outline:2px solid #0d6efd}\
+         .wt-untranslated{font-style:italic;text-decoration:underline dotted}\
+         .wt-tag{font-size:.75em;padding:0 .3em;border:1px solid #999;border-radius:3px}\
+         .wt-legend span{margin-right:1em;padding:0 .3em}",
+    );
+    let mut legend = String::from("<p class=\"wt-legend\">");
+    for (status, color) in STATUS_COLORS {
+        styles.push_str(&format!(".wt-{}{{background:{color}}}", status.as_str()));
+        legend.push_str(&format!(
+            "<span class=\"wt-{0}\">{0}</span>",
+            status.as_str()
+        ));
+    }
+    legend.push_str("</p>\n");
+
+    let html = format!(
+        "<!DOCTYPE html>\n<html lang=\"{lang}\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>{title}</title>\n<style>{styles}</style>\n</head>\n<body>\n\
+         <h1>{title}</h1>\n{legend}{body}</body>\n</html>\n",
+        lang = escape_html(&document.target_language),
+        title = escape_html(&document.file),
+    );
+    IncontextPreview {
+        html,
+        segment_count: document.transunits.len(),
+        untranslated_segments,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jliff::model::TransUnit;
+
+    fn unit(unit_id: &str, segment: u32, source: &str, target: &str) -> TransUnit {
+        TransUnit {
+            unit_id: unit_id.into(),
+            transunit_id: format!("u{unit_id}-s{segment}"),
+            source: source.into(),
+            target_translation: target.into(),
+            target_qa_1: None,
+            target_qa_2: None,
+            target_postedit: None,
+            translation_notes: None,
+            qa_notes: None,
+            source_notes: None,
+            metadata: None,
+            status: None,
+            sub_state: None,
+            notes: Vec::new(),
+            propagated_from: None,
+        }
+    }
+
+    #[test]
+    fn renders_units_as_paragraphs_with_anchored_segments() {
+        let mut approved = unit("1", 2, "Then close it.", "Dann schließen.");
+        approved.status = Some(SegmentStatus::Approved);
+        let document = JliffDocument {
+            project_name: "Demo".into(),
+            project_id: "demo".into(),
+            file: "guide <v2>.docx".into(),
+            user: "tester".into(),
+            source_language: "en-US".into(),
+            target_language: "de-DE".into(),
+            transunits: vec![
+                unit("1", 1, "Save the file.", "Datei speichern."),
+                approved,
+                unit("2", 1, "Fish & chips", ""),
+            ],
+            segment_operations: Vec::new(),
+        };
+
+        let preview = render_incontext_html(&document, None);
+        assert_eq!(preview.segment_count, 3);
+        assert_eq!(preview.untranslated_segments, 1);
+        assert!(
+            preview
+                .html
+                .contains("<title>guide &lt;v2&gt;.docx</title>")
+        );
+        assert!(preview.html.contains(
+            "<p class=\"wt-unit\" data-unit=\"1\"><span id=\"seg-u1-s1\" class=\"wt-seg wt-draft\" \
+             title=\"u1-s1 (draft)\" dir=\"ltr\">Datei speichern.</span> <span id=\"seg-u1-s2\" \
+             class=\"wt-seg wt-approved\""
+        ));
+        assert!(preview.html.contains(
+            "class=\"wt-seg wt-new wt-untranslated\" title=\"u2-s1 (new)\">Fish &amp; chips</span>"
+        ));
+        assert_eq!(preview.html.matches("<p class=\"wt-unit\"").count(), 2);
+    }
+}
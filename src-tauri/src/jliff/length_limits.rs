@@ -0,0 +1,160 @@
+//! Target length limits from XLIFF `slr:sizeRestriction`.
+//!
+//! Limits are expressed in characters (the default `xliff:codepoints` profile). Inline-code
+//! placeholders do not count towards the length, except protected-term placeholders, which
+//! count as the text they stand for when the tag map is available. Limits can also be
+//! imported from a CSV column for string tables that carry them outside the XLIFF.
+
+use anyhow::{Result, bail};
+use serde::Serialize;
+
+use super::model::TransUnit;
+use super::protected_terms::PROTECTED_TERM_ELEM;
+use super::tag_map::TagMapSegment;
+use crate::import::csv::CsvTable;
+
+/// A target that exceeds its unit's maximum length.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LengthLimitIssue {
+    pub max_length: usize,
+    pub length: usize,
+}
+
+/// Counts the characters of `text` as they will appear once placeholders are restored.
+pub fn measured_length(text: &str, segment: Option<&TagMapSegment>) -> usize {
+    let mut length = 0;
+    let mut cursor = 0;
+    while let Some(offset) = text[cursor..].find("{{") {
+        let start = cursor + offset;
+        let Some(close) = text[start..].find("}}") else {
+            break;
+        };
+        let end = start + close + 2;
+        length += text[cursor..start].chars().count();
+        cursor = end;
+
+        let token = &text[start..end];
+        let protected = segment
+            .and_then(|segment| segment.instance(token))
+            .filter(|instance| instance.elem == PROTECTED_TERM_ELEM)
+            .and_then(|instance| instance.original_data.as_deref());
+        if let Some(original) = protected {
+            length += original.chars().count();
+        }
+    }
+    length + text[cursor..].chars().count()
+}
+
+/// Reports when the unit's current target is longer than its `Max_length` metadata, or
+/// `default_max_length` for units without one.
+pub fn check_length_limit(
+    unit: &TransUnit,
+    segment: Option<&TagMapSegment>,
+    default_max_length: Option<usize>,
+) -> Option<LengthLimitIssue> {
+    let max_length = unit
+        .metadata
+        .as_ref()
+        .and_then(|metadata| metadata.max_length)
+        .or(default_max_length)?;
+    let length = measured_length(unit.effective_target(), segment);
+    (length > max_length).then_some(LengthLimitIssue { max_length, length })
+}
+
+/// How segment updates treat targets longer than their limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LengthLimitEnforcement {
+    /// No check while editing.
+    Off,
+    /// Accept the target and record a QA finding.
+    Warn,
+    /// Reject the target.
+    Block,
+}
+
+impl LengthLimitEnforcement {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "off" => Some(LengthLimitEnforcement::Off),
+            "warn" => Some(LengthLimitEnforcement::Warn),
+            "block" => Some(LengthLimitEnforcement::Block),
+            _ => None,
+        }
+    }
+}
+
+/// Limits read from a CSV file, keyed by unit or segment id.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CsvLengthLimits {
+    pub limits: Vec<(String, usize)>,
+    /// Rows without an id or with an empty, zero or non-numeric limit.
+    pub skipped_rows: usize,
+}
+
+/// Reads `id_column` and `max_length_column` (matched case-insensitively against the header
+/// row) from CSV text. The delimiter (comma, semicolon or tab) is taken from the header.
+pub fn parse_length_limits_csv(
+    text: &str,
+    id_column: &str,
+    max_length_column: &str,
+) -> Result<CsvLengthLimits> {
+    let table = CsvTable::parse(text)?;
+    let Some(id_index) = table.column(id_column) else {
+        bail!("The CSV file has no '{id_column}' column.");
+    };
+    let Some(max_index) = table.column(max_length_column) else {
+        bail!("The CSV file has no '{max_length_column}' column.");
+    };
+
+    let mut result = CsvLengthLimits::default();
+    for row in &table.rows {
+        let id = row.cell(id_index);
+        let max_length = row
+            .cell(max_index)
+            .parse::<usize>()
+            .ok()
+            .filter(|value| *value > 0);
+        match max_length {
+            Some(max_length) if !id.is_empty() => result.limits.push((id.to_string(), max_length)),
+            _ => result.skipped_rows += 1,
+        }
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::jliff::tag_map::TagInstance;
+
+    #[test]
+    fn ignores_inline_codes_but_counts_protected_terms() {
+        let segment = TagMapSegment {
+            segment_id: "1".into(),
+            placeholders: vec![TagInstance {
+                placeholder: "{{np:1}}".into(),
+                elem: PROTECTED_TERM_ELEM.into(),
+                id: Some("np1".into()),
+                attrs: BTreeMap::new(),
+                original_data: Some("Weg".into()),
+            }],
+            original_data_bucket: BTreeMap::new(),
+        };
+
+        assert_eq!(measured_length("Käse {{ph:1}}!", None), 6);
+        assert_eq!(measured_length("{{np:1}} rocks", Some(&segment)), 9);
+        assert_eq!(measured_length("{{np:1}} rocks", None), 6);
+    }
+
+    #[test]
+    fn reads_limits_from_csv_columns() {
+        let csv = "\u{feff}Key;Source;Max Length\r\nbtn.ok;\"OK; \"\"go\"\"\";12\r\nbtn.cancel;Cancel;\n;Orphan;5\nmenu.help;\"Help\nmenu\";abc\n";
+        let parsed = parse_length_limits_csv(csv, "key", "max length").unwrap();
+        assert_eq!(parsed.limits, [("btn.ok".to_string(), 12)]);
+        assert_eq!(parsed.skipped_rows, 3);
+        assert!(parse_length_limits_csv(csv, "id", "max length").is_err());
+    }
+}
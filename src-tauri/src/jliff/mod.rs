@@ -1,7 +1,35 @@
+pub mod alignment;
+pub mod analysis;
+pub mod auto_fix;
+pub mod bidi;
+pub mod cache;
+pub mod consistency;
 mod converter;
+pub mod document;
+pub mod external_review;
+pub mod find_replace;
+pub mod history;
+pub mod incontext_preview;
+pub mod length_limits;
 pub mod model;
 mod options;
-mod tag_map;
+pub mod placeholder_consistency;
+pub mod pretranslation;
+pub mod preview;
+pub mod propagation;
+pub mod protected_terms;
+pub mod quality_estimate;
+pub mod restructure;
+pub mod segment_filter;
+pub mod segmentation;
+pub mod snapshots;
+pub mod split;
+pub mod statistics;
+pub mod tag_map;
+pub mod tag_repair;
+pub mod unicode_text;
+pub mod xliff_sync;
+pub mod xliff_validation;
 
 use std::cmp::Reverse;
 use std::fs;
@@ -14,6 +42,7 @@ use serde_json::Value;
 
 pub use model::JliffDocument;
 pub use options::ConversionOptions;
+pub use protected_terms::{ProtectedTerm, ProtectionRule};
 
 /// Summary of the schema validation performed for a generated JLIFF artifact.
 #[derive(Debug, Clone)]
@@ -158,6 +187,8 @@ pub fn convert_xliff(opts: &ConversionOptions) -> Result<Vec<GeneratedArtifact>>
 
     let tag_map_value =
         serde_json::to_value(&primary.tag_map).context("Failed to serialize tag-map document")?;
+    tag_map::validate_tag_map_value(&tag_map_value)
+        .with_context(|| format!("Refusing to write {}", tag_map_path.display()))?;
     write_json(&tag_map_path, &tag_map_value, opts.pretty)?;
 
     Ok(vec![GeneratedArtifact {
@@ -346,6 +377,7 @@ mod tests {
 
         let tag_map_json: Value =
             serde_json::from_str(&fs::read_to_string(&artifacts[0].tag_map_path)?)?;
+        assert_eq!(tag_map_json["version"], tag_map::TAG_MAP_VERSION);
         assert_eq!(
             tag_map_json["units"][0]["segments"][0]["placeholders_in_order"]
                 .as_array()
@@ -357,6 +389,89 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn carries_xliff_module_data_states_and_notes_into_jliff() -> Result<()> {
+        let tmp_dir = tempdir()?;
+        let xliff_path = tmp_dir.path().join("modules.xlf");
+        let output_dir = tmp_dir.path().join("out");
+
+        let xliff_payload = r##"<?xml version="1.0" encoding="UTF-8"?>
+<xliff xmlns="urn:oasis:names:tc:xliff:document:2.0"
+       xmlns:mtc="urn:oasis:names:tc:xliff:matches:2.0"
+       xmlns:its="http://www.w3.org/2005/11/its"
+       xmlns:slr="urn:oasis:names:tc:xliff:sizerestriction:2.0"
+       version="2.0" srcLang="en-US" trgLang="de-DE">
+  <file original="ui.json" id="1" slr:sizeRestriction="80">
+    <unit id="u1" slr:sizeRestriction="12" its:localeFilterList="de-DE, fr" its:localeFilterType="exclude">
+      <mtc:matches>
+        <mtc:match ref="#s1" matchQuality="75" origin="Project TM">
+          <source>Save file</source>
+          <target>Datei sichern</target>
+        </mtc:match>
+        <mtc:match ref="#s1" matchQuality="98.5" origin="Main TM">
+          <source>Save the file</source>
+          <target>Datei speichern</target>
+        </mtc:match>
+      </mtc:matches>
+      <segment id="s1" state="reviewed" subState="acme:proofread">
+        <source>Save the file</source>
+        <target>Datei speichern</target>
+      </segment>
+    </unit>
+    <unit id="u2">
+      <notes>
+        <note id="n1" category="context" priority="2" appliesTo="source">Dialog &amp; toolbar button</note>
+        <note>Keep short</note>
+      </notes>
+      <segment id="s1">
+        <source>Cancel</source>
+      </segment>
+    </unit>
+  </file>
+</xliff>
+"##;
+        fs::write(&xliff_path, xliff_payload)?;
+
+        let opts = ConversionOptions::new(
+            xliff_path,
+            output_dir,
+            "Demo".to_string(),
+            "proj-1".to_string(),
+            "tester".to_string(),
+        );
+        let conversions = converter::convert(&opts)?;
+        let units = &conversions[0].jliff.transunits;
+
+        let first = units[0].metadata.as_ref().expect("metadata for u1");
+        assert_eq!(first.match_quality, Some(98.5));
+        assert_eq!(first.match_origin.as_deref(), Some("Main TM"));
+        assert_eq!(first.max_length, Some(12));
+        let filter = first.locale_filter.as_ref().expect("locale filter");
+        assert_eq!(filter.filter_type, model::LocaleFilterType::Exclude);
+        assert_eq!(filter.locales, vec!["de-DE", "fr"]);
+
+        assert_eq!(units[0].status, Some(model::SegmentStatus::Reviewed));
+        assert_eq!(units[0].sub_state.as_deref(), Some("acme:proofread"));
+        assert_eq!(units[1].status, None);
+
+        let second = units[1].metadata.as_ref().expect("metadata for u2");
+        assert_eq!(second.max_length, Some(80));
+        assert_eq!(second.match_quality, None);
+
+        assert!(units[0].notes.is_empty());
+        assert_eq!(units[1].notes.len(), 2);
+        assert_eq!(units[1].notes[0].id.as_deref(), Some("n1"));
+        assert_eq!(units[1].notes[0].category.as_deref(), Some("context"));
+        assert_eq!(units[1].notes[0].priority, Some(2));
+        assert_eq!(units[1].notes[0].applies_to.as_deref(), Some("source"));
+        assert_eq!(units[1].notes[0].text, "Dialog & toolbar button");
+        assert_eq!(units[1].notes[1].text, "Keep short");
+
+        let issue = length_limits::check_length_limit(&units[0], None, None).expect("too long");
+        assert_eq!(issue.length, 15);
+        Ok(())
+    }
+
     #[test]
     fn skips_files_without_transunits_and_removes_stale_artifacts() -> Result<()> {
         let tmp_dir = tempdir()?;
@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 
 /// Representation of the custom JLIFF document defined by `schema/jliff.schema.json`.
@@ -18,6 +20,28 @@ pub struct JliffDocument {
     pub target_language: String,
     #[serde(rename = "Transunits")]
     pub transunits: Vec<TransUnit>,
+    /// Segments joined or split in the editor, oldest first, so the XLIFF can be rebuilt to
+    /// match before merging.
+    #[serde(
+        rename = "Segment_operations",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub segment_operations: Vec<SegmentOperation>,
+}
+
+impl JliffDocument {
+    /// Counts segments per effective status, including statuses with no segments.
+    pub fn status_distribution(&self) -> BTreeMap<SegmentStatus, usize> {
+        let mut distribution: BTreeMap<SegmentStatus, usize> = SegmentStatus::ALL
+            .into_iter()
+            .map(|status| (status, 0))
+            .collect();
+        for unit in &self.transunits {
+            *distribution.entry(unit.effective_status()).or_default() += 1;
+        }
+        distribution
+    }
 }
 
 /// Translation unit payload mirroring the schema's `Transunits` entries.
@@ -64,6 +88,236 @@ pub struct TransUnit {
         skip_serializing_if = "Option::is_none"
     )]
     pub source_notes: Option<SourceNotes>,
+    #[serde(rename = "Metadata", default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<UnitMetadata>,
+    #[serde(rename = "Status", default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<SegmentStatus>,
+    /// Tool-specific refinement of the status, kept verbatim from XLIFF `subState`.
+    #[serde(rename = "Sub_state", default, skip_serializing_if = "Option::is_none")]
+    pub sub_state: Option<String>,
+    /// Notes attached to the XLIFF `<unit>` the segment belongs to.
+    #[serde(rename = "Notes", default, skip_serializing_if = "Vec::is_empty")]
+    pub notes: Vec<XliffNote>,
+    /// Segment whose confirmed target was copied here because the sources are identical.
+    #[serde(
+        rename = "Propagated_from",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub propagated_from: Option<PropagationSource>,
+}
+
+/// Origin of a propagated target.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PropagationSource {
+    /// Project-relative path of the JLIFF document holding the source segment.
+    #[serde(rename = "File")]
+    pub file: String,
+    #[serde(rename = "Transunit_id")]
+    pub transunit_id: String,
+}
+
+/// A join or split of segments inside one XLIFF `<unit>`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SegmentOperation {
+    #[serde(rename = "Kind")]
+    pub kind: SegmentOperationKind,
+    #[serde(rename = "Unit_id")]
+    pub unit_id: String,
+    /// For a split, the segment that was split and the new segment holding its tail; for a
+    /// merge, the segment that was kept followed by the segments joined into it.
+    #[serde(rename = "Transunit_ids")]
+    pub transunit_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SegmentOperationKind {
+    Split,
+    Merge,
+}
+
+/// A `<note>` carried over from XLIFF.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct XliffNote {
+    #[serde(rename = "Id", default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(rename = "Category", default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    /// 1 (highest) to 10 (lowest); XLIFF defaults to 1 when absent.
+    #[serde(rename = "Priority", default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<u8>,
+    /// `source` or `target` when the note only concerns one side.
+    #[serde(
+        rename = "Applies_to",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub applies_to: Option<String>,
+    #[serde(rename = "Text")]
+    pub text: String,
+}
+
+/// Workflow status of a segment.
+///
+/// XLIFF 2 `state` values map as `initial` -> `new`/`draft` (depending on whether a target
+/// exists), `translated` -> `translated`, `reviewed` -> `reviewed`, `final` -> `approved`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SegmentStatus {
+    New,
+    Draft,
+    Translated,
+    Reviewed,
+    Approved,
+}
+
+impl SegmentStatus {
+    pub const ALL: [SegmentStatus; 5] = [
+        SegmentStatus::New,
+        SegmentStatus::Draft,
+        SegmentStatus::Translated,
+        SegmentStatus::Reviewed,
+        SegmentStatus::Approved,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SegmentStatus::New => "new",
+            SegmentStatus::Draft => "draft",
+            SegmentStatus::Translated => "translated",
+            SegmentStatus::Reviewed => "reviewed",
+            SegmentStatus::Approved => "approved",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|status| status.as_str().eq_ignore_ascii_case(value.trim()))
+    }
+
+    /// Whether a translator has confirmed the target (translated or later).
+    pub fn is_confirmed(self) -> bool {
+        matches!(
+            self,
+            SegmentStatus::Translated | SegmentStatus::Reviewed | SegmentStatus::Approved
+        )
+    }
+
+    /// Maps an XLIFF 2 `state` attribute value.
+    pub fn from_xliff_state(state: &str, has_target: bool) -> Option<Self> {
+        match state.trim() {
+            "initial" if has_target => Some(SegmentStatus::Draft),
+            "initial" => Some(SegmentStatus::New),
+            "translated" => Some(SegmentStatus::Translated),
+            "reviewed" => Some(SegmentStatus::Reviewed),
+            "final" => Some(SegmentStatus::Approved),
+            _ => None,
+        }
+    }
+
+    /// The XLIFF 2 `state` attribute value written back on merge.
+    pub fn xliff_state(self) -> &'static str {
+        match self {
+            SegmentStatus::New | SegmentStatus::Draft => "initial",
+            SegmentStatus::Translated => "translated",
+            SegmentStatus::Reviewed => "reviewed",
+            SegmentStatus::Approved => "final",
+        }
+    }
+}
+
+/// Metadata carried over from XLIFF 2 modules: translation candidates (`mtc`), ITS locale
+/// filters, size restrictions (`slr`), and the text direction when either side is
+/// right-to-left.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct UnitMetadata {
+    /// Best `mtc:match` quality for the segment, as a percentage (0-100).
+    #[serde(
+        rename = "Match_quality",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub match_quality: Option<f64>,
+    #[serde(
+        rename = "Match_origin",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub match_origin: Option<String>,
+    #[serde(
+        rename = "Locale_filter",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub locale_filter: Option<LocaleFilter>,
+    /// Maximum target length in characters (`slr:sizeRestriction`).
+    #[serde(
+        rename = "Max_length",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub max_length: Option<usize>,
+    /// From `srcDir`, or implied by the source language.
+    #[serde(
+        rename = "Source_direction",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub source_direction: Option<TextDirection>,
+    /// From `trgDir`, or implied by the target language.
+    #[serde(
+        rename = "Target_direction",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub target_direction: Option<TextDirection>,
+}
+
+impl UnitMetadata {
+    pub fn is_empty(&self) -> bool {
+        self == &UnitMetadata::default()
+    }
+}
+
+/// ITS locale filter restricting which target locales a unit applies to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LocaleFilter {
+    #[serde(rename = "Type")]
+    pub filter_type: LocaleFilterType,
+    /// Locale ranges as listed in `its:localeFilterList`; `*` matches every locale.
+    #[serde(rename = "Locales")]
+    pub locales: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LocaleFilterType {
+    Include,
+    Exclude,
+}
+
+/// Base writing direction of a text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TextDirection {
+    Ltr,
+    Rtl,
+}
+
+impl TextDirection {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TextDirection::Ltr => "ltr",
+            TextDirection::Rtl => "rtl",
+        }
+    }
 }
 
 /// Notes container with WARNING/CRITICAL/SOURCE_ERROR buckets.
@@ -95,3 +349,38 @@ pub struct SourceNotes {
     )]
     pub source_error: Vec<String>,
 }
+
+impl TransUnit {
+    /// Returns the most advanced target text available: post-edit, then QA passes, then MT.
+    pub fn effective_target(&self) -> &str {
+        self.target_postedit
+            .as_deref()
+            .or(self.target_qa_2.as_deref())
+            .or(self.target_qa_1.as_deref())
+            .unwrap_or(&self.target_translation)
+    }
+
+    /// Mutable access to the field that [`TransUnit::effective_target`] reads from.
+    pub fn effective_target_mut(&mut self) -> &mut String {
+        if let Some(value) = self.target_postedit.as_mut() {
+            return value;
+        }
+        if let Some(value) = self.target_qa_2.as_mut() {
+            return value;
+        }
+        if let Some(value) = self.target_qa_1.as_mut() {
+            return value;
+        }
+        &mut self.target_translation
+    }
+
+    /// Returns the recorded status, or the one implied by the target when none was recorded.
+    pub fn effective_status(&self) -> SegmentStatus {
+        self.status
+            .unwrap_or(if self.effective_target().trim().is_empty() {
+                SegmentStatus::New
+            } else {
+                SegmentStatus::Draft
+            })
+    }
+}
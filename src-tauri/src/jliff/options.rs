@@ -1,5 +1,7 @@
 use std::path::PathBuf;
 
+use super::protected_terms::{ProtectedTerm, ProtectionRule};
+
 /// Supported placeholder flavours for inline code substitution.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum PlaceholderStyle {
@@ -40,6 +42,12 @@ pub struct ConversionOptions {
     pub keep_inline_in_source: bool,
     /// When `true`, JSON payloads are pretty formatted.
     pub pretty: bool,
+    /// "Do not translate" terms replaced by `{{np:N}}` placeholders in source and target.
+    pub protected_terms: Vec<ProtectedTerm>,
+    /// Regex rules whose matches are replaced by `{{np:rN}}` placeholders in source and target.
+    pub protection_rules: Vec<ProtectionRule>,
+    /// When `true`, source and target text are normalized to Unicode NFC.
+    pub normalize_unicode: bool,
 }
 
 impl ConversionOptions {
@@ -62,6 +70,9 @@ impl ConversionOptions {
             placeholder_style: PlaceholderStyle::DoubleCurly,
             keep_inline_in_source: false,
             pretty: false,
+            protected_terms: Vec::new(),
+            protection_rules: Vec::new(),
+            normalize_unicode: true,
         }
     }
 }
@@ -0,0 +1,173 @@
+//! Placeholder consistency across the target languages of one source file.
+//!
+//! Per-pair checks compare a target with its source; they cannot tell that German kept a
+//! `{{ph:2}}` that French and Italian dropped in the same way the source allows. Here the
+//! targets of every language are compared with each other: the placeholder set shared by
+//! most languages (the source's set on ties) is taken as the reference, and languages that
+//! differ from it are reported.
+
+use std::collections::{BTreeMap, HashMap};
+
+use super::model::JliffDocument;
+
+/// `{{...}}` placeholders of `text`, sorted so two texts can be compared as multisets.
+pub fn placeholder_tokens(text: &str) -> Vec<&str> {
+    let mut found = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start..].find("}}") else {
+            break;
+        };
+        found.push(&rest[start..start + len + 2]);
+        rest = &rest[start + len + 2..];
+    }
+    found.sort_unstable();
+    found
+}
+
+/// Placeholders of one language's target.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LanguagePlaceholders {
+    pub target_language: String,
+    pub placeholders: Vec<String>,
+}
+
+/// A segment whose targets do not carry the same placeholders in every language.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlaceholderDivergence {
+    pub transunit_id: String,
+    pub source_placeholders: Vec<String>,
+    /// The placeholder set most languages agree on.
+    pub expected: Vec<String>,
+    pub targets: Vec<LanguagePlaceholders>,
+    /// Languages whose target differs from `expected`.
+    pub divergent_languages: Vec<String>,
+}
+
+/// Compares the targets of `documents`, which must be translations of the same source file.
+/// Segments translated in fewer than two languages are skipped.
+pub fn find_divergences(documents: &[&JliffDocument]) -> Vec<PlaceholderDivergence> {
+    let mut order: Vec<&str> = Vec::new();
+    let mut sources: HashMap<&str, &str> = HashMap::new();
+    let mut targets: HashMap<&str, Vec<LanguagePlaceholders>> = HashMap::new();
+    for document in documents {
+        for unit in &document.transunits {
+            let id = unit.transunit_id.as_str();
+            if sources.insert(id, &unit.source).is_none() {
+                order.push(id);
+            }
+            let target = unit.effective_target();
+            if target.trim().is_empty() {
+                continue;
+            }
+            targets.entry(id).or_default().push(LanguagePlaceholders {
+                target_language: document.target_language.clone(),
+                placeholders: owned(placeholder_tokens(target)),
+            });
+        }
+    }
+
+    let mut divergences = Vec::new();
+    for id in order {
+        let Some(languages) = targets.remove(id) else {
+            continue;
+        };
+        if languages.len() < 2 {
+            continue;
+        }
+        let source_placeholders = owned(placeholder_tokens(sources[id]));
+
+        let mut counts: BTreeMap<&[String], usize> = BTreeMap::new();
+        for language in &languages {
+            *counts.entry(language.placeholders.as_slice()).or_default() += 1;
+        }
+        if counts.len() == 1 {
+            continue;
+        }
+        let top = counts.values().copied().max().unwrap_or_default();
+        let expected = if counts.get(&source_placeholders.as_slice()) == Some(&top) {
+            source_placeholders.clone()
+        } else {
+            counts
+                .iter()
+                .find(|(_, count)| **count == top)
+                .map(|(set, _)| set.to_vec())
+                .unwrap_or_default()
+        };
+
+        divergences.push(PlaceholderDivergence {
+            transunit_id: id.to_string(),
+            divergent_languages: languages
+                .iter()
+                .filter(|language| language.placeholders != expected)
+                .map(|language| language.target_language.clone())
+                .collect(),
+            source_placeholders,
+            expected,
+            targets: languages,
+        });
+    }
+    divergences
+}
+
+fn owned(tokens: Vec<&str>) -> Vec<String> {
+    tokens.into_iter().map(str::to_string).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jliff::model::TransUnit;
+
+    fn document(language: &str, targets: &[&str]) -> JliffDocument {
+        JliffDocument {
+            project_name: "Demo".into(),
+            project_id: "p".into(),
+            file: "ui.xlf".into(),
+            user: "u".into(),
+            source_language: "en".into(),
+            target_language: language.into(),
+            transunits: targets
+                .iter()
+                .enumerate()
+                .map(|(index, target)| TransUnit {
+                    unit_id: index.to_string(),
+                    transunit_id: format!("u{index}-s1"),
+                    source: "Press {{ph:1}} to {{ph:2}}save{{ph:3}}".into(),
+                    target_translation: (*target).into(),
+                    target_qa_1: None,
+                    target_qa_2: None,
+                    target_postedit: None,
+                    translation_notes: None,
+                    qa_notes: None,
+                    source_notes: None,
+                    metadata: None,
+                    status: None,
+                    sub_state: None,
+                    notes: Vec::new(),
+                    propagated_from: None,
+                })
+                .collect(),
+            segment_operations: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn flags_languages_that_differ_from_the_majority() {
+        let de = document(
+            "de",
+            &["{{ph:1}} drücken, um {{ph:2}}zu speichern{{ph:3}}", "A"],
+        );
+        let fr = document("fr", &["Appuyez sur {{ph:1}} pour enregistrer", "B"]);
+        let it = document("it", &["Premi {{ph:1}} per {{ph:2}}salvare{{ph:3}}", ""]);
+
+        let divergences = find_divergences(&[&de, &fr, &it]);
+
+        assert_eq!(divergences.len(), 1);
+        let divergence = &divergences[0];
+        assert_eq!(divergence.transunit_id, "u0-s1");
+        assert_eq!(divergence.expected, ["{{ph:1}}", "{{ph:2}}", "{{ph:3}}"]);
+        assert_eq!(divergence.divergent_languages, ["fr"]);
+        assert_eq!(divergence.targets.len(), 3);
+    }
+}
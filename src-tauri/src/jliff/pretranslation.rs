@@ -0,0 +1,226 @@
+//! Pretranslation of JLIFF documents from translation memory matches.
+//!
+//! Every segment is scored against the TM to build a quoting breakdown (exact, fuzzy and
+//! no-match words). Segments allowed by the [`OverwritePolicy`] receive the best match at
+//! or above the threshold as a `draft` target, with the match percentage and origin stored
+//! in the segment metadata.
+
+use super::model::{JliffDocument, SegmentStatus};
+use super::statistics::{WordCountStrategy, source_word_count};
+use crate::tm::TmMatcher;
+
+/// `Match_origin` written on segments filled from the translation memory.
+pub const TM_MATCH_ORIGIN: &str = "translation_memory";
+
+/// Lowest accepted match threshold; weaker fuzzy matches cost more to fix than to translate.
+pub const MIN_MATCH_FLOOR: u8 = 50;
+
+/// Which segments may receive a TM target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverwritePolicy {
+    /// Only segments without a target.
+    EmptyOnly,
+    /// Any segment that is not confirmed yet.
+    Unconfirmed,
+    /// Every segment, including confirmed ones.
+    All,
+}
+
+impl OverwritePolicy {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            OverwritePolicy::EmptyOnly => "empty_only",
+            OverwritePolicy::Unconfirmed => "unconfirmed",
+            OverwritePolicy::All => "all",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "empty_only" => Some(OverwritePolicy::EmptyOnly),
+            "unconfirmed" => Some(OverwritePolicy::Unconfirmed),
+            "all" => Some(OverwritePolicy::All),
+            _ => None,
+        }
+    }
+}
+
+/// Segment and source word counts per match band.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MatchBreakdown {
+    pub exact_segments: usize,
+    pub exact_words: usize,
+    pub fuzzy_segments: usize,
+    pub fuzzy_words: usize,
+    pub no_match_segments: usize,
+    pub no_match_words: usize,
+}
+
+impl MatchBreakdown {
+    pub fn add(&mut self, other: &MatchBreakdown) {
+        self.exact_segments += other.exact_segments;
+        self.exact_words += other.exact_words;
+        self.fuzzy_segments += other.fuzzy_segments;
+        self.fuzzy_words += other.fuzzy_words;
+        self.no_match_segments += other.no_match_segments;
+        self.no_match_words += other.no_match_words;
+    }
+}
+
+/// A segment whose target was replaced by a TM match.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PretranslatedTarget {
+    pub transunit_id: String,
+    pub previous_target: String,
+    pub new_target: String,
+}
+
+/// Outcome of pretranslating one document.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DocumentPretranslation {
+    pub breakdown: MatchBreakdown,
+    pub filled: Vec<PretranslatedTarget>,
+}
+
+/// Fills targets of `document` from `matcher` matches scoring at least `min_match`.
+pub fn pretranslate_document(
+    document: &mut JliffDocument,
+    matcher: &TmMatcher,
+    min_match: u8,
+    policy: OverwritePolicy,
+) -> DocumentPretranslation {
+    let mut result = DocumentPretranslation::default();
+    let strategy = WordCountStrategy::for_source(document);
+    for unit in &mut document.transunits {
+        let words = source_word_count(unit, strategy);
+        let Some(found) = matcher.best_match(&unit.source, min_match) else {
+            result.breakdown.no_match_segments += 1;
+            result.breakdown.no_match_words += words;
+            continue;
+        };
+        if found.is_exact() {
+            result.breakdown.exact_segments += 1;
+            result.breakdown.exact_words += words;
+        } else {
+            result.breakdown.fuzzy_segments += 1;
+            result.breakdown.fuzzy_words += words;
+        }
+
+        let previous_target = unit.effective_target().to_string();
+        let allowed = match policy {
+            OverwritePolicy::EmptyOnly => previous_target.trim().is_empty(),
+            OverwritePolicy::Unconfirmed => !unit.effective_status().is_confirmed(),
+            OverwritePolicy::All => true,
+        };
+        if !allowed || previous_target == found.target {
+            continue;
+        }
+
+        *unit.effective_target_mut() = found.target.clone();
+        unit.status = Some(SegmentStatus::Draft);
+        unit.sub_state = None;
+        unit.propagated_from = None;
+        let metadata = unit.metadata.get_or_insert_with(Default::default);
+        metadata.match_quality = Some(f64::from(found.score));
+        metadata.match_origin = Some(TM_MATCH_ORIGIN.to_string());
+        result.filled.push(PretranslatedTarget {
+            transunit_id: unit.transunit_id.clone(),
+            previous_target,
+            new_target: found.target,
+        });
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jliff::model::TransUnit;
+    use crate::tm::TmCandidate;
+
+    fn unit(id: &str, source: &str, target: &str, status: Option<SegmentStatus>) -> TransUnit {
+        TransUnit {
+            unit_id: id.into(),
+            transunit_id: format!("u{id}-s1"),
+            source: source.into(),
+            target_translation: target.into(),
+            target_qa_1: None,
+            target_qa_2: None,
+            target_postedit: None,
+            translation_notes: None,
+            qa_notes: None,
+            source_notes: None,
+            metadata: None,
+            status,
+            sub_state: None,
+            notes: Vec::new(),
+            propagated_from: None,
+        }
+    }
+
+    #[test]
+    fn fills_matches_above_threshold_and_counts_bands() {
+        let mut document = JliffDocument {
+            project_name: "Demo".into(),
+            project_id: "p".into(),
+            file: "a.docx".into(),
+            user: "u".into(),
+            source_language: "en".into(),
+            target_language: "de".into(),
+            transunits: vec![
+                unit("1", "Open the file.", "", None),
+                unit("2", "Close the files.", "MT draft", None),
+                unit(
+                    "3",
+                    "Open the file.",
+                    "Bestätigt",
+                    Some(SegmentStatus::Reviewed),
+                ),
+                unit("4", "Print the report now.", "", None),
+            ],
+            segment_operations: Vec::new(),
+        };
+        let matcher = TmMatcher::new([
+            TmCandidate {
+                source: "Open the file.".into(),
+                target: "Öffnen Sie die Datei.".into(),
+                penalty: 0,
+            },
+            TmCandidate {
+                source: "Close the file.".into(),
+                target: "Schließen Sie die Datei.".into(),
+                penalty: 0,
+            },
+        ]);
+
+        let result =
+            pretranslate_document(&mut document, &matcher, 75, OverwritePolicy::Unconfirmed);
+
+        assert_eq!(
+            result.breakdown,
+            MatchBreakdown {
+                exact_segments: 2,
+                exact_words: 6,
+                fuzzy_segments: 1,
+                fuzzy_words: 3,
+                no_match_segments: 1,
+                no_match_words: 4,
+            }
+        );
+        let filled: Vec<&str> = result
+            .filled
+            .iter()
+            .map(|target| target.transunit_id.as_str())
+            .collect();
+        assert_eq!(filled, ["u1-s1", "u2-s1"]);
+        assert_eq!(result.filled[1].previous_target, "MT draft");
+
+        let fuzzy = &document.transunits[1];
+        assert_eq!(fuzzy.target_translation, "Schließen Sie die Datei.");
+        assert_eq!(fuzzy.status, Some(SegmentStatus::Draft));
+        let metadata = fuzzy.metadata.as_ref().unwrap();
+        assert_eq!(metadata.match_quality, Some(93.0));
+        assert_eq!(metadata.match_origin.as_deref(), Some(TM_MATCH_ORIGIN));
+        assert_eq!(document.transunits[2].target_translation, "Bestätigt");
+    }
+}
@@ -0,0 +1,328 @@
+//! HTML preview rendering for JLIFF segments.
+//!
+//! Segment text stores inline codes as `{{...}}` placeholders. For display the editor wants an
+//! approximation of the original formatting instead, so [`render_segment_html`] walks the text,
+//! resolves each placeholder through the tag map, and maps recognisable original data (HTML-ish
+//! markup, OOXML run properties, XLIFF `subType` hints) onto a small, safe HTML vocabulary:
+//! `<strong>`, `<em>`, `<u>`, `<a>`, and `<br>`. Anything else is shown as a neutral tag chip.
+//! All text is escaped; only markup produced here reaches the output.
+
+use super::tag_map::{TagInstance, TagMapSegment};
+
+/// Formatting approximated from an inline code.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Format {
+    Bold,
+    Italic,
+    Underline,
+    Link(Option<String>),
+}
+
+impl Format {
+    fn open_tag(&self) -> String {
+        match self {
+            Format::Bold => "<strong>".into(),
+            Format::Italic => "<em>".into(),
+            Format::Underline => "<u>".into(),
+            Format::Link(Some(href)) => format!(
+                "<a href=\"{}\" target=\"_blank\" rel=\"noopener noreferrer\">",
+                escape_html(href)
+            ),
+            Format::Link(None) => "<a>".into(),
+        }
+    }
+
+    fn close_tag(&self) -> &'static str {
+        match self {
+            Format::Bold => "</strong>",
+            Format::Italic => "</em>",
+            Format::Underline => "</u>",
+            Format::Link(_) => "</a>",
+        }
+    }
+
+    fn same_kind(&self, other: &Format) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+}
+
+/// What a placeholder contributes to the preview.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Rendering {
+    Open(Format),
+    Close(Format),
+    LineBreak,
+    /// Protected or otherwise literal content rendered as text.
+    Literal(String),
+    Chip,
+}
+
+/// Result of rendering one side of a segment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RenderedSegment {
+    pub html: String,
+    /// Placeholders that had no entry in the tag map.
+    pub unresolved: Vec<String>,
+}
+
+/// Renders `text` as HTML, resolving placeholders through `segment` when available.
+pub fn render_segment_html(text: &str, segment: Option<&TagMapSegment>) -> RenderedSegment {
+    let mut html = String::with_capacity(text.len() + 32);
+    let mut unresolved = Vec::new();
+    let mut open: Vec<Format> = Vec::new();
+    let mut cursor = 0;
+
+    while let Some(offset) = text[cursor..].find("{{") {
+        let start = cursor + offset;
+        let Some(close) = text[start..].find("}}") else {
+            break;
+        };
+        let end = start + close + 2;
+        html.push_str(&escape_html(&text[cursor..start]));
+        cursor = end;
+
+        let token = &text[start..end];
+        let instance = segment.and_then(|segment| segment.instance(token));
+        if instance.is_none() {
+            unresolved.push(token.to_string());
+        }
+
+        match classify(token, instance) {
+            Rendering::Open(format) => {
+                html.push_str(&format.open_tag());
+                open.push(format);
+            }
+            Rendering::Close(format) => close_format(&mut html, &mut open, &format),
+            Rendering::LineBreak => html.push_str("<br>"),
+            Rendering::Literal(value) => {
+                html.push_str("<span class=\"wt-protected\">");
+                html.push_str(&escape_html(&value));
+                html.push_str("</span>");
+            }
+            Rendering::Chip => push_chip(&mut html, token, instance),
+        }
+    }
+    html.push_str(&escape_html(&text[cursor..]));
+
+    while let Some(format) = open.pop() {
+        html.push_str(format.close_tag());
+    }
+
+    RenderedSegment { html, unresolved }
+}
+
+/// Closes `format`, re-opening any formats that were nested inside it so the output stays
+/// well-formed even when the source markup overlaps.
+fn close_format(html: &mut String, open: &mut Vec<Format>, format: &Format) {
+    let Some(index) = open.iter().rposition(|entry| entry.same_kind(format)) else {
+        return;
+    };
+    let reopened: Vec<Format> = open.drain(index + 1..).collect();
+    for entry in reopened.iter().rev() {
+        html.push_str(entry.close_tag());
+    }
+    if let Some(closed) = open.pop() {
+        html.push_str(closed.close_tag());
+    }
+    for entry in reopened {
+        html.push_str(&entry.open_tag());
+        open.push(entry);
+    }
+}
+
+fn push_chip(html: &mut String, token: &str, instance: Option<&TagInstance>) {
+    let label = instance
+        .and_then(|instance| instance.id.clone())
+        .unwrap_or_else(|| token.trim_matches(|c| c == '{' || c == '}').to_string());
+    let title = instance
+        .and_then(|instance| instance.original_data.as_deref())
+        .unwrap_or(token);
+    html.push_str(&format!(
+        "<span class=\"wt-tag\" data-placeholder=\"{}\" title=\"{}\">{}</span>",
+        escape_html(token),
+        escape_html(title),
+        escape_html(&label)
+    ));
+}
+
+fn classify(token: &str, instance: Option<&TagInstance>) -> Rendering {
+    let Some(instance) = instance else {
+        return Rendering::Chip;
+    };
+
+    if instance.elem == super::protected_terms::PROTECTED_TERM_ELEM {
+        return match instance.original_data.as_ref() {
+            Some(value) => Rendering::Literal(value.clone()),
+            None => Rendering::Chip,
+        };
+    }
+
+    let is_end = token.ends_with(":end}}") || instance.elem == "ec";
+    let sub_type = instance
+        .attrs
+        .get("subType")
+        .cloned()
+        .flatten()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    let data = instance
+        .original_data
+        .as_deref()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+
+    if sub_type == "xlf:lb" || data.starts_with("<br") || data.contains("<w:br") {
+        return Rendering::LineBreak;
+    }
+
+    let closing_markup = data.starts_with("</");
+    let format = if sub_type == "xlf:b"
+        || matches_tag(&data, &["b", "strong"])
+        || data.contains("<w:b/>")
+        || data.contains("font-weight:bold")
+    {
+        Some(Format::Bold)
+    } else if sub_type == "xlf:i"
+        || matches_tag(&data, &["i", "em"])
+        || data.contains("<w:i/>")
+        || data.contains("font-style:italic")
+    {
+        Some(Format::Italic)
+    } else if sub_type == "xlf:u" || matches_tag(&data, &["u"]) || data.contains("<w:u ") {
+        Some(Format::Underline)
+    } else if matches_tag(&data, &["a"]) || data.contains("<w:hyperlink") {
+        Some(Format::Link(extract_href(
+            instance.original_data.as_deref().unwrap_or_default(),
+        )))
+    } else {
+        None
+    };
+
+    match format {
+        Some(format) if is_end || closing_markup => Rendering::Close(format),
+        // A standalone code carrying self-contained markup (e.g. `<b>x</b>`) has nothing to
+        // wrap; show it as a chip.
+        Some(_) if instance.elem == "ph" && !data.is_empty() && !is_paired_open(&data) => {
+            Rendering::Chip
+        }
+        Some(format) => Rendering::Open(format),
+        None => Rendering::Chip,
+    }
+}
+
+/// Returns `true` when `data` opens (or closes) one of the `names` HTML elements.
+fn matches_tag(data: &str, names: &[&str]) -> bool {
+    let trimmed = data.trim_start_matches('<').trim_start_matches('/');
+    names.iter().any(|name| {
+        trimmed
+            .strip_prefix(name)
+            .is_some_and(|rest| rest.starts_with('>') || rest.starts_with(' '))
+    }) && data.starts_with('<')
+}
+
+/// `true` when the markup opens an element without closing it in the same snippet.
+fn is_paired_open(data: &str) -> bool {
+    data.starts_with('<')
+        && !data.starts_with("</")
+        && !data.contains("</")
+        && !data.ends_with("/>")
+}
+
+/// Extracts a safe `href` (http, https, mailto) from link markup.
+fn extract_href(markup: &str) -> Option<String> {
+    let lower = markup.to_ascii_lowercase();
+    let index = lower.find("href=")?;
+    let rest = &markup[index + 5..];
+    let quote = rest.chars().next()?;
+    let value = if quote == '"' || quote == '\'' {
+        let inner = &rest[1..];
+        &inner[..inner.find(quote)?]
+    } else {
+        rest.split(|c: char| c.is_whitespace() || c == '>')
+            .next()
+            .unwrap_or_default()
+    };
+    let allowed = ["http://", "https://", "mailto:"];
+    let lowered = value.to_ascii_lowercase();
+    allowed
+        .iter()
+        .any(|scheme| lowered.starts_with(scheme))
+        .then(|| value.to_string())
+}
+
+pub(crate) fn escape_html(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for ch in value.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+
+    fn instance(placeholder: &str, elem: &str, data: Option<&str>) -> TagInstance {
+        TagInstance {
+            placeholder: placeholder.into(),
+            elem: elem.into(),
+            id: Some("1".into()),
+            attrs: BTreeMap::new(),
+            original_data: data.map(str::to_string),
+        }
+    }
+
+    fn segment(placeholders: Vec<TagInstance>) -> TagMapSegment {
+        TagMapSegment {
+            segment_id: "1".into(),
+            placeholders,
+            original_data_bucket: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn renders_bold_and_links_from_original_data() {
+        let segment = segment(vec![
+            instance("{{pc:1:start}}", "pc", Some("<b>")),
+            instance("{{pc:1:end}}", "pc", Some("</b>")),
+            instance("{{ph:2}}", "ph", Some("<a href=\"https://example.com\">")),
+            instance("{{ph:3}}", "ph", Some("</a>")),
+        ]);
+        let rendered = render_segment_html(
+            "Click {{pc:1:start}}Save{{pc:1:end}} & {{ph:2}}docs{{ph:3}}",
+            Some(&segment),
+        );
+        assert_eq!(
+            rendered.html,
+            "Click <strong>Save</strong> &amp; <a href=\"https://example.com\" target=\"_blank\" rel=\"noopener noreferrer\">docs</a>"
+        );
+        assert!(rendered.unresolved.is_empty());
+    }
+
+    #[test]
+    fn falls_back_to_chips_and_reports_unknown_placeholders() {
+        let rendered = render_segment_html("A {{ph:9}} <b>", None);
+        assert!(rendered.html.contains("class=\"wt-tag\""));
+        assert!(rendered.html.ends_with("&lt;b&gt;"));
+        assert_eq!(rendered.unresolved, vec!["{{ph:9}}".to_string()]);
+    }
+
+    #[test]
+    fn rejects_unsafe_link_targets() {
+        assert_eq!(extract_href("<a href=\"javascript:alert(1)\">"), None);
+        assert_eq!(
+            extract_href("<a href='mailto:team@example.com'>"),
+            Some("mailto:team@example.com".into())
+        );
+    }
+}
@@ -0,0 +1,193 @@
+//! Propagation of confirmed translations to repeated segments.
+//!
+//! Segments are repetitions when their sources are identical after collapsing whitespace
+//! (and, optionally, ignoring case). A confirmed target is copied onto repetitions that are
+//! not confirmed yet; confirmed segments are never overwritten. Propagated segments become
+//! `translated` and remember their source in `Propagated_from`.
+
+use std::collections::HashMap;
+
+use super::model::{JliffDocument, PropagationSource, SegmentStatus};
+
+/// Which documents receive propagated targets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PropagationScope {
+    /// Only the document of the confirmed segment.
+    File,
+    /// Every JLIFF document of the project.
+    Project,
+}
+
+impl PropagationScope {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PropagationScope::File => "file",
+            PropagationScope::Project => "project",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "file" => Some(PropagationScope::File),
+            "project" => Some(PropagationScope::Project),
+            _ => None,
+        }
+    }
+}
+
+/// A confirmed translation offered to repetitions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfirmedTranslation {
+    pub source: PropagationSource,
+    pub target: String,
+}
+
+/// A segment whose target was replaced by propagation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PropagatedTarget {
+    pub transunit_id: String,
+    pub previous_target: String,
+    pub new_target: String,
+}
+
+/// Normalizes a source so repetitions share the same key.
+pub fn repetition_key(source: &str, case_sensitive: bool) -> String {
+    let normalized = source.split_whitespace().collect::<Vec<_>>().join(" ");
+    if case_sensitive {
+        normalized
+    } else {
+        normalized.to_lowercase()
+    }
+}
+
+/// Collects the confirmed translations of `document`, keyed by [`repetition_key`]. When a
+/// source is confirmed several times, the first segment in document order wins.
+pub fn confirmed_translations(
+    document: &JliffDocument,
+    file: &str,
+    case_sensitive: bool,
+) -> HashMap<String, ConfirmedTranslation> {
+    let mut translations = HashMap::new();
+    for unit in &document.transunits {
+        let target = unit.effective_target();
+        if !unit.effective_status().is_confirmed() || target.trim().is_empty() {
+            continue;
+        }
+        translations
+            .entry(repetition_key(&unit.source, case_sensitive))
+            .or_insert_with(|| ConfirmedTranslation {
+                source: PropagationSource {
+                    file: file.to_string(),
+                    transunit_id: unit.transunit_id.clone(),
+                },
+                target: target.to_string(),
+            });
+    }
+    translations
+}
+
+/// Copies `translations` onto the unconfirmed repetitions of `document`.
+pub fn propagate_translations(
+    document: &mut JliffDocument,
+    translations: &HashMap<String, ConfirmedTranslation>,
+    case_sensitive: bool,
+) -> Vec<PropagatedTarget> {
+    let mut propagated = Vec::new();
+    if translations.is_empty() {
+        return propagated;
+    }
+    for unit in &mut document.transunits {
+        if unit.effective_status().is_confirmed() {
+            continue;
+        }
+        let Some(translation) = translations.get(&repetition_key(&unit.source, case_sensitive))
+        else {
+            continue;
+        };
+        let previous_target = unit.effective_target().to_string();
+        if previous_target == translation.target {
+            continue;
+        }
+        *unit.effective_target_mut() = translation.target.clone();
+        unit.status = Some(SegmentStatus::Translated);
+        unit.sub_state = None;
+        unit.propagated_from = Some(translation.source.clone());
+        propagated.push(PropagatedTarget {
+            transunit_id: unit.transunit_id.clone(),
+            previous_target,
+            new_target: translation.target.clone(),
+        });
+    }
+    propagated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jliff::model::TransUnit;
+
+    fn unit(id: &str, source: &str, target: &str, status: Option<SegmentStatus>) -> TransUnit {
+        TransUnit {
+            unit_id: id.into(),
+            transunit_id: format!("u{id}-s1"),
+            source: source.into(),
+            target_translation: target.into(),
+            target_qa_1: None,
+            target_qa_2: None,
+            target_postedit: None,
+            translation_notes: None,
+            qa_notes: None,
+            source_notes: None,
+            metadata: None,
+            status,
+            sub_state: None,
+            notes: Vec::new(),
+            propagated_from: None,
+        }
+    }
+
+    #[test]
+    fn propagates_to_unconfirmed_repetitions_only() {
+        let mut document = JliffDocument {
+            project_name: "Demo".into(),
+            project_id: "p".into(),
+            file: "a.docx".into(),
+            user: "u".into(),
+            source_language: "en".into(),
+            target_language: "de".into(),
+            transunits: vec![
+                unit(
+                    "1",
+                    "Save  file",
+                    "Datei speichern",
+                    Some(SegmentStatus::Translated),
+                ),
+                unit("2", "Save file", "MT draft", None),
+                unit("3", "save file", "", None),
+                unit("4", "Save file", "Sichern", Some(SegmentStatus::Reviewed)),
+            ],
+            segment_operations: Vec::new(),
+        };
+
+        let translations = confirmed_translations(&document, "a.jliff.json", true);
+        let propagated = propagate_translations(&mut document, &translations, true);
+        assert_eq!(propagated.len(), 1);
+        assert_eq!(propagated[0].transunit_id, "u2-s1");
+        assert_eq!(propagated[0].previous_target, "MT draft");
+        assert_eq!(document.transunits[1].target_translation, "Datei speichern");
+        assert_eq!(
+            document.transunits[1]
+                .propagated_from
+                .as_ref()
+                .map(|source| source.transunit_id.as_str()),
+            Some("u1-s1")
+        );
+        assert_eq!(document.transunits[2].target_translation, "");
+        assert_eq!(document.transunits[3].target_translation, "Sichern");
+
+        let translations = confirmed_translations(&document, "a.jliff.json", false);
+        let propagated = propagate_translations(&mut document, &translations, false);
+        assert_eq!(propagated.len(), 1);
+        assert_eq!(document.transunits[2].target_translation, "Datei speichern");
+    }
+}
@@ -0,0 +1,587 @@
+//! Protected ("do not translate") term handling.
+//!
+//! Projects can declare terms such as product names or code identifiers that must survive
+//! translation untouched. During conversion every match outside existing placeholders is
+//! replaced by a `{{np:N}}` placeholder (where `N` is the 1-based position of the term in the
+//! configured list, followed by `.K` for its K-th occurrence in the segment from the second
+//! on) so machine translation cannot alter it; the original text of each occurrence is kept
+//! in the tag map as `originalData`. After translation, [`check_protected_terms`] verifies that the
+//! target still carries every protected placeholder and, for documents converted before the
+//! list existed, every literal term found in the source.
+//!
+//! Regex [`ProtectionRule`]s (URLs, e-mails, version strings, ...) are applied in the same
+//! pass and produce `{{np:rN}}` (`{{np:rN.K}}`) placeholders, so the same QA check covers
+//! them.
+
+use std::collections::{BTreeMap, HashMap};
+
+use regex::Regex;
+use serde::Serialize;
+
+use super::tag_map::{TagInstance, TagMapSegment};
+
+/// Element name recorded in the tag map for protected-term placeholders.
+pub const PROTECTED_TERM_ELEM: &str = "np";
+
+/// A single "do not translate" entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProtectedTerm {
+    pub term: String,
+    pub case_sensitive: bool,
+}
+
+impl ProtectedTerm {
+    pub fn new(term: impl Into<String>, case_sensitive: bool) -> Self {
+        Self {
+            term: term.into(),
+            case_sensitive,
+        }
+    }
+}
+
+/// A compiled regex protection rule.
+#[derive(Debug, Clone)]
+pub struct ProtectionRule {
+    pub name: String,
+    pub regex: Regex,
+}
+
+impl ProtectionRule {
+    /// Compiles `pattern` into a rule, surfacing the regex error for invalid patterns.
+    pub fn compile(name: impl Into<String>, pattern: &str) -> Result<Self, regex::Error> {
+        Ok(Self {
+            name: name.into(),
+            regex: Regex::new(pattern)?,
+        })
+    }
+}
+
+/// What produced a protected span.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ProtectedSource {
+    /// Index into the protected-term list.
+    Term(usize),
+    /// Index into the protection-rule list.
+    Rule(usize),
+}
+
+/// A protected span inside a text, expressed in byte offsets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProtectedSpan {
+    pub source: ProtectedSource,
+    pub start: usize,
+    pub end: usize,
+    /// 1-based occurrence of the same term or rule in the text.
+    pub occurrence: usize,
+}
+
+impl ProtectedSpan {
+    /// Id of the term or rule: its 1-based position, prefixed with `r` for rules.
+    fn source_id(&self) -> String {
+        match self.source {
+            ProtectedSource::Term(index) => (index + 1).to_string(),
+            ProtectedSource::Rule(index) => format!("r{}", index + 1),
+        }
+    }
+
+    /// Tag map id of this span. Occurrences after the first get a `.K` suffix, so each one
+    /// is written back with its own text.
+    pub fn id(&self) -> String {
+        match self.occurrence {
+            0 | 1 => self.source_id(),
+            occurrence => format!("{}.{occurrence}", self.source_id()),
+        }
+    }
+
+    /// Placeholder token that replaces this span.
+    pub fn placeholder(&self) -> String {
+        protected_placeholder(&self.id())
+    }
+}
+
+/// Location of a protected term inside a text, expressed in byte offsets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TermMatch {
+    /// Index of the matched term in the list passed to [`find_term_matches`].
+    pub term_index: usize,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// A protected term that was not carried over to the target unchanged.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProtectedTermIssue {
+    /// The protected term, or the placeholder token when the term is no longer known.
+    pub term: String,
+    /// Placeholder token when the term was protected at conversion time.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub placeholder: Option<String>,
+    pub expected: usize,
+    pub found: usize,
+}
+
+/// Builds the placeholder token for a protected span's tag map `id`.
+pub fn protected_placeholder(id: &str) -> String {
+    format!("{{{{{PROTECTED_TERM_ELEM}:{id}}}}}")
+}
+
+/// Finds non-overlapping, word-bounded matches of `terms` in `text`, skipping `{{...}}`
+/// placeholders. Longer terms win when several start at the same position.
+pub fn find_term_matches(text: &str, terms: &[ProtectedTerm]) -> Vec<TermMatch> {
+    let mut order: Vec<usize> = (0..terms.len())
+        .filter(|index| !terms[*index].term.trim().is_empty())
+        .collect();
+    order.sort_by_key(|index| std::cmp::Reverse(terms[*index].term.chars().count()));
+
+    let mut matches = Vec::new();
+    let mut cursor = 0;
+    while cursor < text.len() {
+        let rest = &text[cursor..];
+        if rest.starts_with("{{") {
+            if let Some(close) = rest.find("}}") {
+                cursor += close + 2;
+                continue;
+            }
+        }
+
+        let found = order.iter().find_map(|index| {
+            let term = &terms[*index];
+            match_at(text, cursor, term).map(|end| TermMatch {
+                term_index: *index,
+                start: cursor,
+                end,
+            })
+        });
+
+        match found {
+            Some(entry) => {
+                cursor = entry.end;
+                matches.push(entry);
+            }
+            None => {
+                cursor += rest.chars().next().map(char::len_utf8).unwrap_or(1);
+            }
+        }
+    }
+    matches
+}
+
+/// Finds every protected span in `text`: term matches plus regex rule matches outside
+/// `{{...}}` placeholders. Overlaps are resolved in favour of the earliest, then longest, span.
+pub fn find_protected_spans(
+    text: &str,
+    terms: &[ProtectedTerm],
+    rules: &[ProtectionRule],
+) -> Vec<ProtectedSpan> {
+    let mut candidates: Vec<ProtectedSpan> = find_term_matches(text, terms)
+        .into_iter()
+        .map(|entry| ProtectedSpan {
+            source: ProtectedSource::Term(entry.term_index),
+            start: entry.start,
+            end: entry.end,
+            occurrence: 0,
+        })
+        .collect();
+
+    if !rules.is_empty() {
+        for (span_start, span_end) in plain_text_ranges(text) {
+            let span = &text[span_start..span_end];
+            for (rule_index, rule) in rules.iter().enumerate() {
+                for found in rule.regex.find_iter(span) {
+                    if found.is_empty() {
+                        continue;
+                    }
+                    candidates.push(ProtectedSpan {
+                        source: ProtectedSource::Rule(rule_index),
+                        start: span_start + found.start(),
+                        end: span_start + found.end(),
+                        occurrence: 0,
+                    });
+                }
+            }
+        }
+    }
+
+    candidates.sort_by_key(|span| (span.start, std::cmp::Reverse(span.end)));
+    let mut spans: Vec<ProtectedSpan> = Vec::with_capacity(candidates.len());
+    let mut occurrences: HashMap<ProtectedSource, usize> = HashMap::new();
+    for mut candidate in candidates {
+        if spans.last().is_some_and(|last| candidate.start < last.end) {
+            continue;
+        }
+        let occurrence = occurrences.entry(candidate.source).or_default();
+        *occurrence += 1;
+        candidate.occurrence = *occurrence;
+        spans.push(candidate);
+    }
+    spans
+}
+
+/// Replaces protected spans with placeholders and merges the resulting tag instances into
+/// `placeholders`, keeping the tag map in reading order.
+pub(crate) fn protect_segment(
+    text: &str,
+    terms: &[ProtectedTerm],
+    rules: &[ProtectionRule],
+    placeholders: Vec<TagInstance>,
+) -> (String, Vec<TagInstance>) {
+    let spans = find_protected_spans(text, terms, rules);
+    if spans.is_empty() {
+        return (text.to_string(), placeholders);
+    }
+
+    let mut protected = String::with_capacity(text.len());
+    let mut inserted = Vec::with_capacity(spans.len());
+    let mut last = 0;
+    for span in &spans {
+        protected.push_str(&text[last..span.start]);
+        let token = span.placeholder();
+        let position = protected.len();
+        protected.push_str(&token);
+        last = span.end;
+
+        let matched = &text[span.start..span.end];
+        let mut attrs = BTreeMap::new();
+        match span.source {
+            ProtectedSource::Term(index) => {
+                attrs.insert("term".to_string(), Some(terms[index].term.clone()));
+            }
+            ProtectedSource::Rule(index) => {
+                attrs.insert("rule".to_string(), Some(rules[index].name.clone()));
+                attrs.insert(
+                    "pattern".to_string(),
+                    Some(rules[index].regex.as_str().to_string()),
+                );
+            }
+        }
+        inserted.push((
+            position,
+            TagInstance {
+                placeholder: token,
+                elem: PROTECTED_TERM_ELEM.to_string(),
+                id: Some(span.id()),
+                attrs,
+                original_data: Some(matched.to_string()),
+            },
+        ));
+    }
+    protected.push_str(&text[last..]);
+
+    let merged = merge_in_reading_order(&protected, placeholders, inserted);
+    (protected, merged)
+}
+
+/// Replaces the protected spans of a target with the placeholders of the same text in its
+/// source, so each is written back with its own text whatever order the target puts them
+/// in. A term differing from the source only in case reuses a placeholder of that term; a
+/// span without a counterpart in the source stays plain text.
+pub(crate) fn protect_target(
+    text: &str,
+    terms: &[ProtectedTerm],
+    rules: &[ProtectionRule],
+    source_placeholders: &[TagInstance],
+) -> String {
+    let mut available: Vec<&TagInstance> = source_placeholders
+        .iter()
+        .filter(|instance| instance.elem == PROTECTED_TERM_ELEM)
+        .collect();
+    let mut protected = String::with_capacity(text.len());
+    let mut last = 0;
+    for span in find_protected_spans(text, terms, rules) {
+        let matched = &text[span.start..span.end];
+        let source_id = span.source_id();
+        let of_source = |instance: &&TagInstance| {
+            instance.id.as_deref().is_some_and(|id| {
+                id == source_id
+                    || id
+                        .strip_prefix(source_id.as_str())
+                        .is_some_and(|rest| rest.starts_with('.'))
+            })
+        };
+        let position = available
+            .iter()
+            .position(|instance| {
+                of_source(instance) && instance.original_data.as_deref() == Some(matched)
+            })
+            .or_else(|| match span.source {
+                ProtectedSource::Term(_) => available.iter().position(of_source),
+                ProtectedSource::Rule(_) => None,
+            });
+        let Some(position) = position else {
+            continue;
+        };
+        protected.push_str(&text[last..span.start]);
+        protected.push_str(&available.remove(position).placeholder);
+        last = span.end;
+    }
+    protected.push_str(&text[last..]);
+    protected
+}
+
+/// Compares `source` and `target`, reporting protected placeholders or literal terms that
+/// appear fewer times in the target than in the source. Placeholders are named after the
+/// text they protected, as recorded in the segment's tag map, so editing the term list later
+/// does not change what they stand for.
+pub fn check_protected_terms(
+    source: &str,
+    target: &str,
+    terms: &[ProtectedTerm],
+    tag_segment: Option<&TagMapSegment>,
+) -> Vec<ProtectedTermIssue> {
+    let mut issues = Vec::new();
+
+    let mut expected_tokens: BTreeMap<String, usize> = BTreeMap::new();
+    for token in placeholder_tokens(source) {
+        *expected_tokens.entry(token).or_default() += 1;
+    }
+    for (token, expected) in expected_tokens {
+        let found = target.matches(token.as_str()).count();
+        if found < expected {
+            let term = tag_segment
+                .and_then(|segment| segment.instance(&token))
+                .and_then(|instance| {
+                    instance
+                        .attrs
+                        .get("term")
+                        .cloned()
+                        .flatten()
+                        .or_else(|| instance.original_data.clone())
+                })
+                .unwrap_or_else(|| token.clone());
+            issues.push(ProtectedTermIssue {
+                term,
+                placeholder: Some(token),
+                expected,
+                found,
+            });
+        }
+    }
+
+    let mut expected_terms = vec![0usize; terms.len()];
+    for entry in find_term_matches(source, terms) {
+        expected_terms[entry.term_index] += 1;
+    }
+    let mut found_terms = vec![0usize; terms.len()];
+    for entry in find_term_matches(target, terms) {
+        found_terms[entry.term_index] += 1;
+    }
+    for (index, expected) in expected_terms.into_iter().enumerate() {
+        if expected > found_terms[index] {
+            issues.push(ProtectedTermIssue {
+                term: terms[index].term.clone(),
+                placeholder: None,
+                expected,
+                found: found_terms[index],
+            });
+        }
+    }
+
+    issues
+}
+
+fn placeholder_tokens(text: &str) -> Vec<String> {
+    let prefix = format!("{{{{{}:", PROTECTED_TERM_ELEM);
+    let mut tokens = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find(&prefix) {
+        let candidate = &rest[start..];
+        match candidate.find("}}") {
+            Some(close) => {
+                tokens.push(candidate[..close + 2].to_string());
+                rest = &candidate[close + 2..];
+            }
+            None => break,
+        }
+    }
+    tokens
+}
+
+/// Returns the byte ranges of `text` that lie outside `{{...}}` placeholders.
+fn plain_text_ranges(text: &str) -> Vec<(usize, usize)> {
+    let mut ranges = Vec::new();
+    let mut cursor = 0;
+    while let Some(offset) = text[cursor..].find("{{") {
+        let open = cursor + offset;
+        let Some(close) = text[open..].find("}}") else {
+            break;
+        };
+        if open > cursor {
+            ranges.push((cursor, open));
+        }
+        cursor = open + close + 2;
+    }
+    if cursor < text.len() {
+        ranges.push((cursor, text.len()));
+    }
+    ranges
+}
+
+/// Returns the byte offset one past the match when `term` matches `text` at `start`.
+fn match_at(text: &str, start: usize, term: &ProtectedTerm) -> Option<usize> {
+    let mut text_chars = text[start..].char_indices();
+    let mut end = start;
+    for expected in term.term.chars() {
+        let (offset, actual) = text_chars.next()?;
+        let equal = if term.case_sensitive {
+            actual == expected
+        } else {
+            actual.to_lowercase().eq(expected.to_lowercase())
+        };
+        if !equal {
+            return None;
+        }
+        end = start + offset + actual.len_utf8();
+    }
+
+    let first = term.term.chars().next()?;
+    let last = term.term.chars().next_back()?;
+    let before = text[..start].chars().next_back();
+    let after = text[end..].chars().next();
+    if first.is_alphanumeric() && before.is_some_and(char::is_alphanumeric) {
+        return None;
+    }
+    if last.is_alphanumeric() && after.is_some_and(char::is_alphanumeric) {
+        return None;
+    }
+    Some(end)
+}
+
+fn merge_in_reading_order(
+    text: &str,
+    existing: Vec<TagInstance>,
+    inserted: Vec<(usize, TagInstance)>,
+) -> Vec<TagInstance> {
+    let mut merged = Vec::with_capacity(existing.len() + inserted.len());
+    let mut pending = inserted.into_iter().peekable();
+    let mut cursor = 0;
+
+    for instance in existing {
+        if let Some(offset) = text[cursor..].find(&instance.placeholder) {
+            let position = cursor + offset;
+            while let Some((_, next)) = pending.next_if(|(at, _)| *at < position) {
+                merged.push(next);
+            }
+            cursor = position + instance.placeholder.len();
+        }
+        merged.push(instance);
+    }
+    merged.extend(pending.map(|(_, instance)| instance));
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn terms() -> Vec<ProtectedTerm> {
+        vec![
+            ProtectedTerm::new("Acme", true),
+            ProtectedTerm::new("Acme Cloud", true),
+            ProtectedTerm::new("getUser()", false),
+        ]
+    }
+
+    #[test]
+    fn protects_longest_word_bounded_matches() {
+        let (text, placeholders) = protect_segment(
+            "Open {{ph:1}}Acme Cloud and call GETUSER() on Acmes",
+            &terms(),
+            &[],
+            Vec::new(),
+        );
+        assert_eq!(text, "Open {{ph:1}}{{np:2}} and call {{np:3}} on Acmes");
+        assert_eq!(placeholders.len(), 2);
+        assert_eq!(placeholders[1].original_data.as_deref(), Some("GETUSER()"));
+    }
+
+    #[test]
+    fn protects_regex_rule_matches_outside_placeholders() {
+        let rules = vec![
+            ProtectionRule::compile("version", r"\d+\.\d+\.\d+").unwrap(),
+            ProtectionRule::compile("url", r"https?://\S+").unwrap(),
+        ];
+        let (text, placeholders) = protect_segment(
+            "Acme 2.10.1 from https://acme.example/v1.2.3 {{ph:1.0.0}}",
+            &terms(),
+            &rules,
+            Vec::new(),
+        );
+        assert_eq!(text, "{{np:1}} {{np:r1}} from {{np:r2}} {{ph:1.0.0}}");
+        assert_eq!(placeholders.len(), 3);
+        assert_eq!(
+            placeholders[2].original_data.as_deref(),
+            Some("https://acme.example/v1.2.3")
+        );
+    }
+
+    /// Writes placeholders back the way the XLIFF sync does: from their tag map instance.
+    fn restore(text: &str, placeholders: &[TagInstance]) -> String {
+        let segment = TagMapSegment {
+            segment_id: "1".into(),
+            placeholders: placeholders.to_vec(),
+            original_data_bucket: BTreeMap::new(),
+        };
+        let mut restored = text.to_string();
+        for instance in placeholders {
+            let original = segment
+                .instance(&instance.placeholder)
+                .and_then(|instance| instance.original_data.clone())
+                .unwrap();
+            restored = restored.replacen(&instance.placeholder, &original, 1);
+        }
+        restored
+    }
+
+    #[test]
+    fn every_occurrence_round_trips_with_its_own_text() {
+        let rules = vec![ProtectionRule::compile("url", r"https?://\S+").unwrap()];
+        let source = "See https://a.example and https://b.example or ACME and Acme";
+        let terms = vec![ProtectedTerm::new("acme", false)];
+        let (text, placeholders) = protect_segment(source, &terms, &rules, Vec::new());
+        assert_eq!(
+            text,
+            "See {{np:r1}} and {{np:r1.2}} or {{np:1}} and {{np:1.2}}"
+        );
+        assert_eq!(restore(&text, &placeholders), source);
+
+        let target = protect_target(
+            "Vedi https://b.example e https://a.example o Acme e ACME https://c.example",
+            &terms,
+            &rules,
+            &placeholders,
+        );
+        assert_eq!(
+            target,
+            "Vedi {{np:r1.2}} e {{np:r1}} o {{np:1.2}} e {{np:1}} https://c.example"
+        );
+        assert_eq!(
+            restore(&target, &placeholders),
+            "Vedi https://b.example e https://a.example o Acme e ACME https://c.example"
+        );
+    }
+
+    #[test]
+    fn reports_missing_placeholders_and_altered_terms() {
+        let (source, placeholders) =
+            protect_segment("Use Acme Cloud with", &terms(), &[], Vec::new());
+        let segment = TagMapSegment {
+            segment_id: "1".into(),
+            placeholders,
+            original_data_bucket: BTreeMap::new(),
+        };
+        // The term list changed since conversion: the placeholder still names its term.
+        let edited = vec![ProtectedTerm::new("Acme", true)];
+        let issues = check_protected_terms(
+            &format!("{source} Acme"),
+            "Usa il cloud con ACME",
+            &edited,
+            Some(&segment),
+        );
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0].term, "Acme Cloud");
+        assert_eq!(issues[0].placeholder.as_deref(), Some("{{np:2}}"));
+        assert_eq!(issues[1].term, "Acme");
+        assert_eq!(issues[1].found, 0);
+    }
+}
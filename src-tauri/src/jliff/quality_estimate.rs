@@ -0,0 +1,129 @@
+//! Reference-free quality estimate of a machine translation.
+//!
+//! The score (0 to 1) is built from signals that need no model: `{{...}}` placeholders and
+//! numbers carried over from the source, a plausible length ratio, a translated (not copied)
+//! text and matching final punctuation. It ranks candidates for the same segment; it is not
+//! an absolute measure of quality.
+
+use super::placeholder_consistency::placeholder_tokens;
+
+/// Sources shorter than this (in characters) are not judged on their length ratio.
+const MIN_LENGTH_FOR_RATIO: usize = 20;
+
+/// Estimated quality of `target` as a translation of `source`, from 0 to 1.
+pub fn estimate_quality(source: &str, target: &str) -> f64 {
+    let source = source.trim();
+    let target = target.trim();
+    if target.is_empty() {
+        return 0.0;
+    }
+
+    let mut score = 1.0;
+    score -=
+        0.25 * multiset_difference(&placeholder_tokens(source), &placeholder_tokens(target)) as f64;
+    let (source_text, target_text) = (without_placeholders(source), without_placeholders(target));
+    score -= 0.15 * multiset_difference(&numbers(&source_text), &numbers(&target_text)) as f64;
+
+    let source_chars = source.chars().count();
+    if source_chars >= MIN_LENGTH_FOR_RATIO {
+        let ratio = target.chars().count() as f64 / source_chars as f64;
+        if !(0.5..=2.0).contains(&ratio) {
+            score -= 0.2;
+        }
+    }
+    if source_chars > 3 && source == target && source.chars().any(char::is_alphabetic) {
+        score -= 0.3;
+    }
+    if final_punctuation(source) != final_punctuation(target) {
+        score -= 0.05;
+    }
+
+    (score.clamp(0.0, 1.0) * 100.0).round() / 100.0
+}
+
+/// `text` with its `{{...}}` placeholders removed, so their ids are not taken for numbers.
+fn without_placeholders(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start..].find("}}") else {
+            break;
+        };
+        output.push_str(&rest[..start]);
+        rest = &rest[start + len + 2..];
+    }
+    output.push_str(rest);
+    output
+}
+
+/// Digit runs of `text`, sorted.
+fn numbers(text: &str) -> Vec<&str> {
+    let mut found: Vec<&str> = text
+        .split(|c: char| !c.is_ascii_digit())
+        .filter(|run| !run.is_empty())
+        .collect();
+    found.sort_unstable();
+    found
+}
+
+/// Number of items in one sorted list but not the other.
+fn multiset_difference(left: &[&str], right: &[&str]) -> usize {
+    let (mut i, mut j, mut different) = (0, 0, 0);
+    while i < left.len() && j < right.len() {
+        match left[i].cmp(right[j]) {
+            std::cmp::Ordering::Equal => {
+                i += 1;
+                j += 1;
+            }
+            std::cmp::Ordering::Less => {
+                different += 1;
+                i += 1;
+            }
+            std::cmp::Ordering::Greater => {
+                different += 1;
+                j += 1;
+            }
+        }
+    }
+    different + (left.len() - i) + (right.len() - j)
+}
+
+fn final_punctuation(text: &str) -> Option<char> {
+    text.chars()
+        .last()
+        .filter(|c| matches!(c, '.' | '!' | '?' | ':' | ';' | '…'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn penalizes_lost_placeholders_numbers_and_copies() {
+        let source = "Click {{ph:1}}Save{{ph:2}} to store 3 files.";
+        assert_eq!(
+            estimate_quality(
+                source,
+                "Klicken Sie auf {{ph:1}}Speichern{{ph:2}}, um 3 Dateien zu speichern."
+            ),
+            1.0
+        );
+        assert_eq!(
+            estimate_quality(
+                source,
+                "Klicken Sie auf Speichern{{ph:2}}, um 3 Dateien zu speichern."
+            ),
+            0.75
+        );
+        assert_eq!(
+            estimate_quality(
+                source,
+                "Klicken Sie auf {{ph:1}}Speichern{{ph:2}}, um Dateien zu speichern"
+            ),
+            0.8
+        );
+        assert_eq!(estimate_quality(source, source), 0.7);
+        assert_eq!(estimate_quality(source, "Ja."), 0.15);
+        assert_eq!(estimate_quality(source, "  "), 0.0);
+    }
+}
@@ -0,0 +1,435 @@
+//! Joining and splitting segments in the editor.
+//!
+//! Both operations stay inside one XLIFF `<unit>`, the only place where the segment
+//! structure can change without touching the document around it. The JLIFF document and
+//! its tag map are updated together, and the operation is appended to the document's
+//! [`SegmentOperation`] log so [`super::xliff_sync`] can rebuild the unit's `<segment>`
+//! elements before the merge.
+//!
+//! A split keeps the translation with the first half, since there is no way to tell where
+//! it should be cut, and marks it as a draft. A merge joins sources and targets with a
+//! space unless the language is written without spaces, and takes the least advanced
+//! status of the merged segments.
+
+use std::collections::HashSet;
+
+use anyhow::{Context, Result, bail};
+
+use super::model::{
+    JliffDocument, SegmentOperation, SegmentOperationKind, SegmentStatus, TransUnit,
+};
+use super::segmentation::uses_cjk_rules;
+use super::tag_map::{TagMapDoc, TagMapSegment};
+
+/// Splits a segment `offset` characters into its source, returning the id of the new
+/// segment holding the tail.
+///
+/// The split point must lie between inline codes, and not between the start and end of a
+/// paired code.
+pub fn split_segment(
+    document: &mut JliffDocument,
+    tag_map: &mut TagMapDoc,
+    transunit_id: &str,
+    offset: usize,
+) -> Result<String> {
+    let index = position_of(document, transunit_id)?;
+    let unit = &document.transunits[index];
+    let segment_id = segment_id_of(unit)
+        .with_context(|| format!("Segment {transunit_id} cannot be restructured"))?
+        .to_string();
+    let split_at = unit
+        .source
+        .char_indices()
+        .nth(offset)
+        .map(|(byte, _)| byte)
+        .filter(|byte| *byte > 0)
+        .with_context(|| format!("Offset {offset} is not inside segment {transunit_id}"))?;
+    let spans = placeholder_spans(&unit.source);
+    if spans
+        .iter()
+        .any(|(start, end)| *start < split_at && split_at < *end)
+    {
+        bail!("Segments cannot be split inside an inline code");
+    }
+    let mut open_pairs = HashSet::new();
+    for (start, end) in spans.iter().filter(|(_, end)| *end <= split_at) {
+        let token = &unit.source[*start..*end];
+        if let Some(id) = paired_code_id(token, "start") {
+            open_pairs.insert(id);
+        } else if let Some(id) = paired_code_id(token, "end") {
+            open_pairs.remove(id);
+        }
+    }
+    if !open_pairs.is_empty() {
+        bail!("Segments cannot be split between the start and end of a paired inline code");
+    }
+
+    let taken: HashSet<&str> = document
+        .transunits
+        .iter()
+        .map(|unit| unit.transunit_id.as_str())
+        .collect();
+    let unit_id = unit.unit_id.clone();
+    let new_segment_id = (2..)
+        .map(|suffix| format!("{segment_id}-{suffix}"))
+        .find(|candidate| {
+            !taken.contains(format!("u{unit_id}-s{candidate}").as_str())
+                && tag_map
+                    .units
+                    .iter()
+                    .filter(|tag_unit| tag_unit.unit_id == unit_id)
+                    .all(|tag_unit| {
+                        tag_unit
+                            .segments
+                            .iter()
+                            .all(|segment| &segment.segment_id != candidate)
+                    })
+        })
+        .expect("an unused segment id exists");
+    let new_transunit_id = format!("u{unit_id}-s{new_segment_id}");
+
+    let first = &mut document.transunits[index];
+    let tail = first.source.split_off(split_at);
+    let mut second = first.clone();
+    second.transunit_id = new_transunit_id.clone();
+    second.source = tail;
+    second.target_translation.clear();
+    second.target_qa_1 = None;
+    second.target_qa_2 = None;
+    second.target_postedit = None;
+    second.translation_notes = None;
+    second.qa_notes = None;
+    second.source_notes = None;
+    second.metadata = None;
+    second.status = None;
+    second.sub_state = None;
+    second.propagated_from = None;
+    if !first.target_translation.trim().is_empty() {
+        first.status = Some(SegmentStatus::Draft);
+    }
+    first.sub_state = None;
+    first.propagated_from = None;
+
+    if let Some(tag_segments) = tag_segments_mut(tag_map, &unit_id) {
+        let position = tag_segments
+            .iter()
+            .position(|segment| segment.segment_id == segment_id);
+        if let Some(position) = position {
+            let original = &mut tag_segments[position];
+            // The translation stays whole, so codes it still uses stay with the first half too.
+            let moved = original
+                .placeholders
+                .iter()
+                .filter(|instance| second.source.contains(&instance.placeholder))
+                .cloned()
+                .collect();
+            original.placeholders.retain(|instance| {
+                first.source.contains(&instance.placeholder)
+                    || first.target_translation.contains(&instance.placeholder)
+                    || !second.source.contains(&instance.placeholder)
+            });
+            let new_segment = TagMapSegment {
+                segment_id: new_segment_id,
+                placeholders: moved,
+                original_data_bucket: original.original_data_bucket.clone(),
+            };
+            tag_segments.insert(position + 1, new_segment);
+        }
+    }
+
+    document.transunits.insert(index + 1, second);
+    document.segment_operations.push(SegmentOperation {
+        kind: SegmentOperationKind::Split,
+        unit_id,
+        transunit_ids: vec![transunit_id.to_string(), new_transunit_id.clone()],
+    });
+    Ok(new_transunit_id)
+}
+
+/// Joins consecutive segments of one unit into the first of them, returning its id.
+pub fn merge_segments(
+    document: &mut JliffDocument,
+    tag_map: &mut TagMapDoc,
+    transunit_ids: &[String],
+) -> Result<String> {
+    if transunit_ids.len() < 2 {
+        bail!("At least two segments are needed to merge");
+    }
+    let mut positions = transunit_ids
+        .iter()
+        .map(|id| position_of(document, id))
+        .collect::<Result<Vec<_>>>()?;
+    positions.sort_unstable();
+    positions.dedup();
+    if positions.len() != transunit_ids.len() {
+        bail!("A segment is listed more than once");
+    }
+    if positions.windows(2).any(|pair| pair[1] != pair[0] + 1) {
+        bail!("Only consecutive segments can be merged");
+    }
+    let (first, last) = (positions[0], positions[positions.len() - 1]);
+    let unit_id = document.transunits[first].unit_id.clone();
+    let merged = &document.transunits[first..=last];
+    if merged.iter().any(|unit| unit.unit_id != unit_id) {
+        bail!("Segments of different paragraphs cannot be merged");
+    }
+    if let Some(unit) = merged.iter().find(|unit| segment_id_of(unit).is_none()) {
+        bail!("Segment {} cannot be restructured", unit.transunit_id);
+    }
+
+    let source = join_texts(
+        merged.iter().map(|unit| unit.source.as_str()),
+        &document.source_language,
+    );
+    let target = join_texts(
+        merged.iter().map(|unit| unit.target_translation.as_str()),
+        &document.target_language,
+    );
+    let status = merged.iter().map(|unit| unit.status).min().flatten();
+    let absorbed: Vec<TransUnit> = document.transunits.drain(first + 1..=last).collect();
+    let kept = &mut document.transunits[first];
+    kept.source = source;
+    kept.target_translation = target;
+    kept.target_qa_1 = None;
+    kept.target_qa_2 = None;
+    kept.target_postedit = None;
+    if kept.status != status {
+        kept.sub_state = None;
+    }
+    kept.status = status;
+    kept.propagated_from = None;
+
+    let kept_segment_id = segment_id_of(kept).map(str::to_string);
+    let absorbed_segment_ids: Vec<&str> = absorbed.iter().filter_map(segment_id_of).collect();
+    if let Some(tag_segments) = tag_segments_mut(tag_map, &unit_id) {
+        let (moved, remaining): (Vec<_>, Vec<_>) = std::mem::take(tag_segments)
+            .into_iter()
+            .partition(|segment| absorbed_segment_ids.contains(&segment.segment_id.as_str()));
+        *tag_segments = remaining;
+        if let Some(target) = tag_segments
+            .iter_mut()
+            .find(|segment| Some(&segment.segment_id) == kept_segment_id.as_ref())
+        {
+            for segment in moved {
+                target.placeholders.extend(segment.placeholders);
+                target
+                    .original_data_bucket
+                    .extend(segment.original_data_bucket);
+            }
+        }
+    }
+
+    let kept_id = kept.transunit_id.clone();
+    let mut ids = vec![kept_id.clone()];
+    ids.extend(absorbed.into_iter().map(|unit| unit.transunit_id));
+    document.segment_operations.push(SegmentOperation {
+        kind: SegmentOperationKind::Merge,
+        unit_id,
+        transunit_ids: ids,
+    });
+    Ok(kept_id)
+}
+
+/// The XLIFF `<segment>` id a transunit was converted from.
+pub fn segment_id_of(unit: &TransUnit) -> Option<&str> {
+    unit.transunit_id
+        .strip_prefix('u')?
+        .strip_prefix(unit.unit_id.as_str())?
+        .strip_prefix("-s")
+}
+
+/// Byte ranges of the `{{...}}` placeholders in `text`.
+pub fn placeholder_spans(text: &str) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let mut cursor = 0;
+    while let Some(offset) = text[cursor..].find("{{") {
+        let start = cursor + offset;
+        let Some(close) = text[start..].find("}}") else {
+            break;
+        };
+        let end = start + close + 2;
+        spans.push((start, end));
+        cursor = end;
+    }
+    spans
+}
+
+/// Id of a `{{pc:<id>:<side>}}` placeholder.
+fn paired_code_id<'a>(token: &'a str, side: &str) -> Option<&'a str> {
+    token
+        .strip_prefix("{{pc:")?
+        .strip_suffix("}}")?
+        .strip_suffix(side)?
+        .strip_suffix(':')
+}
+
+fn join_texts<'a>(texts: impl Iterator<Item = &'a str>, language: &str) -> String {
+    let spaced = !uses_cjk_rules(language);
+    let mut joined = String::new();
+    for text in texts.filter(|text| !text.is_empty()) {
+        if spaced
+            && !joined.is_empty()
+            && !joined.ends_with(char::is_whitespace)
+            && !text.starts_with(char::is_whitespace)
+        {
+            joined.push(' ');
+        }
+        joined.push_str(text);
+    }
+    joined
+}
+
+fn position_of(document: &JliffDocument, transunit_id: &str) -> Result<usize> {
+    document
+        .transunits
+        .iter()
+        .position(|unit| unit.transunit_id == transunit_id)
+        .with_context(|| format!("Segment {transunit_id} was not found"))
+}
+
+fn tag_segments_mut<'a>(
+    tag_map: &'a mut TagMapDoc,
+    unit_id: &str,
+) -> Option<&'a mut Vec<TagMapSegment>> {
+    tag_map
+        .units
+        .iter_mut()
+        .find(|unit| unit.unit_id == unit_id)
+        .map(|unit| &mut unit.segments)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::jliff::tag_map::{TagInstance, TagMapUnit};
+
+    fn unit(segment_id: &str, source: &str, target: &str) -> TransUnit {
+        TransUnit {
+            unit_id: "1".into(),
+            transunit_id: format!("u1-s{segment_id}"),
+            source: source.into(),
+            target_translation: target.into(),
+            target_qa_1: None,
+            target_qa_2: None,
+            target_postedit: None,
+            translation_notes: None,
+            qa_notes: None,
+            source_notes: None,
+            metadata: None,
+            status: (!target.is_empty()).then_some(SegmentStatus::Translated),
+            sub_state: None,
+            notes: Vec::new(),
+            propagated_from: None,
+        }
+    }
+
+    fn instance(placeholder: &str) -> TagInstance {
+        TagInstance {
+            placeholder: placeholder.into(),
+            elem: "ph".into(),
+            id: None,
+            attrs: BTreeMap::new(),
+            original_data: None,
+        }
+    }
+
+    fn fixture() -> (JliffDocument, TagMapDoc) {
+        let document = JliffDocument {
+            project_name: "Demo".into(),
+            project_id: "p1".into(),
+            file: "ui.html".into(),
+            user: "demo".into(),
+            source_language: "en-US".into(),
+            target_language: "de-DE".into(),
+            transunits: vec![
+                unit(
+                    "1",
+                    "Save {{ph:1}}now. Then {{pc:2:start}}close{{pc:2:end}}.",
+                    "",
+                ),
+                unit("2", "Done.", "Fertig."),
+            ],
+            segment_operations: Vec::new(),
+        };
+        let tag_map = TagMapDoc {
+            version: 1,
+            file_id: "f1".into(),
+            original_path: "ui.html".into(),
+            source_language: "en-US".into(),
+            target_language: "de-DE".into(),
+            placeholder_style: "double_curly".into(),
+            units: vec![TagMapUnit {
+                unit_id: "1".into(),
+                segments: vec![
+                    TagMapSegment {
+                        segment_id: "1".into(),
+                        placeholders: vec![
+                            instance("{{ph:1}}"),
+                            instance("{{pc:2:start}}"),
+                            instance("{{pc:2:end}}"),
+                        ],
+                        original_data_bucket: BTreeMap::new(),
+                    },
+                    TagMapSegment {
+                        segment_id: "2".into(),
+                        placeholders: Vec::new(),
+                        original_data_bucket: BTreeMap::new(),
+                    },
+                ],
+            }],
+        };
+        (document, tag_map)
+    }
+
+    #[test]
+    fn splits_between_codes_and_merges_back() -> Result<()> {
+        let (mut document, mut tag_map) = fixture();
+        assert!(split_segment(&mut document, &mut tag_map, "u1-s1", 7).is_err());
+        assert!(split_segment(&mut document, &mut tag_map, "u1-s1", 40).is_err());
+
+        let new_id = split_segment(&mut document, &mut tag_map, "u1-s1", 18)?;
+        assert_eq!(new_id, "u1-s1-2");
+        assert_eq!(document.transunits[0].source, "Save {{ph:1}}now. ");
+        assert_eq!(
+            document.transunits[1].source,
+            "Then {{pc:2:start}}close{{pc:2:end}}."
+        );
+        let segments = &tag_map.units[0].segments;
+        assert_eq!(segments[1].segment_id, "1-2");
+        assert_eq!(segments[0].placeholders, [instance("{{ph:1}}")]);
+        assert_eq!(segments[1].placeholders.len(), 2);
+
+        let kept = merge_segments(
+            &mut document,
+            &mut tag_map,
+            &["u1-s2".to_string(), "u1-s1-2".to_string()],
+        )?;
+        assert_eq!(kept, "u1-s1-2");
+        assert_eq!(
+            document.transunits[1].source,
+            "Then {{pc:2:start}}close{{pc:2:end}}. Done."
+        );
+        assert_eq!(document.transunits[1].target_translation, "Fertig.");
+        assert_eq!(document.transunits[1].status, None);
+        assert_eq!(tag_map.units[0].segments.len(), 2);
+        assert_eq!(
+            document
+                .segment_operations
+                .iter()
+                .map(|operation| operation.kind)
+                .collect::<Vec<_>>(),
+            [SegmentOperationKind::Split, SegmentOperationKind::Merge]
+        );
+        assert!(
+            merge_segments(
+                &mut document,
+                &mut tag_map,
+                &["u1-s1".to_string(), "u9-s1".to_string()]
+            )
+            .is_err()
+        );
+        Ok(())
+    }
+}
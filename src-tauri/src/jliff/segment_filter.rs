@@ -0,0 +1,131 @@
+//! Segment filters evaluated against JLIFF transunits.
+//!
+//! A [`SegmentFilter`] combines optional conditions; a segment matches when it satisfies
+//! every condition that is set. Conditions that need database state (QA findings, revision
+//! times) are applied by the caller on top of [`SegmentFilter::matches`].
+
+use regex::Regex;
+
+use super::model::{SegmentStatus, TransUnit};
+
+/// Match band of a segment, derived from its `Match_quality` metadata.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchBand {
+    /// 100% match.
+    Exact,
+    /// Below 100%.
+    Fuzzy,
+    /// No match quality recorded.
+    NoMatch,
+}
+
+impl MatchBand {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "exact" => Some(MatchBand::Exact),
+            "fuzzy" => Some(MatchBand::Fuzzy),
+            "no_match" => Some(MatchBand::NoMatch),
+            _ => None,
+        }
+    }
+
+    pub fn of(unit: &TransUnit) -> Self {
+        match unit
+            .metadata
+            .as_ref()
+            .and_then(|metadata| metadata.match_quality)
+        {
+            Some(quality) if quality >= 100.0 => MatchBand::Exact,
+            Some(_) => MatchBand::Fuzzy,
+            None => MatchBand::NoMatch,
+        }
+    }
+}
+
+/// Conditions on the content of a segment. Empty lists and `None` match everything.
+#[derive(Debug, Clone, Default)]
+pub struct SegmentFilter {
+    pub statuses: Vec<SegmentStatus>,
+    pub match_bands: Vec<MatchBand>,
+    /// Whether the segment carries XLIFF notes or translation/QA notes.
+    pub has_comments: Option<bool>,
+    pub source_pattern: Option<Regex>,
+    pub target_pattern: Option<Regex>,
+}
+
+impl SegmentFilter {
+    pub fn matches(&self, unit: &TransUnit) -> bool {
+        if !self.statuses.is_empty() && !self.statuses.contains(&unit.effective_status()) {
+            return false;
+        }
+        if !self.match_bands.is_empty() && !self.match_bands.contains(&MatchBand::of(unit)) {
+            return false;
+        }
+        if let Some(expected) = self.has_comments
+            && has_comments(unit) != expected
+        {
+            return false;
+        }
+        if let Some(pattern) = &self.source_pattern
+            && !pattern.is_match(&unit.source)
+        {
+            return false;
+        }
+        if let Some(pattern) = &self.target_pattern
+            && !pattern.is_match(unit.effective_target())
+        {
+            return false;
+        }
+        true
+    }
+}
+
+fn has_comments(unit: &TransUnit) -> bool {
+    !unit.notes.is_empty() || unit.translation_notes.is_some() || unit.qa_notes.is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jliff::model::UnitMetadata;
+
+    fn unit(source: &str, target: &str, quality: Option<f64>) -> TransUnit {
+        TransUnit {
+            unit_id: "1".into(),
+            transunit_id: "u1-s1".into(),
+            source: source.into(),
+            target_translation: target.into(),
+            target_qa_1: None,
+            target_qa_2: None,
+            target_postedit: None,
+            translation_notes: None,
+            qa_notes: None,
+            source_notes: None,
+            metadata: quality.map(|quality| UnitMetadata {
+                match_quality: Some(quality),
+                ..UnitMetadata::default()
+            }),
+            status: None,
+            sub_state: None,
+            notes: Vec::new(),
+            propagated_from: None,
+        }
+    }
+
+    #[test]
+    fn combines_every_condition() {
+        let filter = SegmentFilter {
+            statuses: vec![SegmentStatus::Draft],
+            match_bands: vec![MatchBand::Fuzzy],
+            has_comments: Some(false),
+            source_pattern: Some(Regex::new("(?i)^open").unwrap()),
+            target_pattern: None,
+        };
+
+        assert!(filter.matches(&unit("Open the file.", "Datei öffnen", Some(85.0))));
+        assert!(!filter.matches(&unit("Open the file.", "Datei öffnen", Some(100.0))));
+        assert!(!filter.matches(&unit("Open the file.", "", Some(85.0))));
+        assert!(!filter.matches(&unit("Close the file.", "Datei schließen", Some(85.0))));
+        assert!(SegmentFilter::default().matches(&unit("Close", "", None)));
+    }
+}
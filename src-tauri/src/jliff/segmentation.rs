@@ -0,0 +1,395 @@
+//! Default sentence segmentation rules, written as SRX 2.0 for the converter.
+//!
+//! OpenXLIFF's built-in rules expect a space after a full stop, so Chinese and Japanese
+//! paragraphs, which end sentences with `。`, `！` or `？` and no space, come out as a single
+//! segment. [`default_srx`] renders rules that also break after full-width terminators
+//! (keeping closing brackets and quotes with their sentence) and after a Latin terminator
+//! directly followed by an ideograph or kana, cascading into the usual rules for Latin
+//! text. [`split_sentences`] applies the same rules in-process.
+//!
+//! Projects and the workspace can add their own abbreviations and break or no-break
+//! exceptions per language as [`CustomRule`]s; [`custom_srx`] and [`split_sentences_with`]
+//! apply them ahead of the defaults.
+
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use anyhow::{Context, Result};
+use quick_xml::escape::escape;
+use regex::Regex;
+
+/// File name of the rules written by [`write_default_srx`].
+pub const DEFAULT_SRX_FILE: &str = "default.srx";
+/// Name of the language rule holding [`CustomRule`]s in a rendered document.
+const CUSTOM_RULES_NAME: &str = "Custom";
+
+/// One SRX rule: whether to break between text matching `before` and text matching `after`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SegmentationRule {
+    pub break_here: bool,
+    pub before: &'static str,
+    pub after: &'static str,
+}
+
+/// A user-defined rule. Its patterns are regular expressions in the subset shared by Java
+/// and the regex crate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CustomRule {
+    pub break_here: bool,
+    pub before: String,
+    pub after: String,
+}
+
+/// A no-break rule keeping `abbreviation`, which includes its full stop, with the text
+/// after it.
+pub fn abbreviation_rule(abbreviation: &str) -> CustomRule {
+    let abbreviation = abbreviation.trim();
+    let boundary = if abbreviation.starts_with(|ch: char| ch.is_alphanumeric() || ch == '_') {
+        r"\b"
+    } else {
+        ""
+    };
+    CustomRule {
+        break_here: false,
+        before: format!("{boundary}{}", regex::escape(abbreviation)),
+        after: r"\s".into(),
+    }
+}
+
+/// The rules of one language group and the language codes they apply to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LanguageRules {
+    pub name: &'static str,
+    /// Regular expression matched against the whole language code.
+    pub language_pattern: &'static str,
+    pub rules: &'static [SegmentationRule],
+}
+
+/// Segmentation rules in cascade order: a language gets the rules of every group whose
+/// pattern it matches, and the first rule matching at a position decides.
+pub const DEFAULT_LANGUAGE_RULES: [LanguageRules; 2] = [
+    LanguageRules {
+        name: "Chinese and Japanese",
+        language_pattern: "(zh|ja|yue|cmn|lzh|wuu)([-_].*)?",
+        rules: &[
+            // Never between consecutive terminators or before a closing bracket or quote.
+            SegmentationRule {
+                break_here: false,
+                before: r"[。！？｡][」』】）”’]*",
+                after: r"[。！？｡」』】）”’]",
+            },
+            SegmentationRule {
+                break_here: true,
+                before: r"[。！？｡][」』】）”’]*",
+                after: "",
+            },
+            // Kana and ideographs, as `\uXXXX` escapes that Java and the regex crate share.
+            SegmentationRule {
+                break_here: true,
+                before: r"[.!?]",
+                after: r"[\u3040-\u30FF\u3400-\u4DBF\u4E00-\u9FFF]",
+            },
+        ],
+    },
+    LanguageRules {
+        name: "Default",
+        language_pattern: ".*",
+        rules: &[
+            SegmentationRule {
+                break_here: false,
+                before: r"\b(Mr|Mrs|Ms|Dr|Prof|St|vs|etc|e\.g|i\.e|No|Fig)\.",
+                after: r"\s",
+            },
+            SegmentationRule {
+                break_here: true,
+                before: r#"[.!?…]+['"”’)\]]*"#,
+                after: r"\s",
+            },
+        ],
+    },
+];
+
+struct CompiledRule {
+    break_here: bool,
+    before: Regex,
+    after: Regex,
+}
+
+struct CompiledLanguageRules {
+    language: Regex,
+    rules: Vec<CompiledRule>,
+}
+
+static COMPILED_RULES: LazyLock<Vec<CompiledLanguageRules>> = LazyLock::new(|| {
+    DEFAULT_LANGUAGE_RULES
+        .iter()
+        .map(|group| CompiledLanguageRules {
+            language: compile(&format!("(?i)^(?:{})$", group.language_pattern)),
+            rules: group
+                .rules
+                .iter()
+                .map(|rule| CompiledRule {
+                    break_here: rule.break_here,
+                    before: compile(&format!("(?:{})$", rule.before)),
+                    after: compile(&format!("^(?:{})", rule.after)),
+                })
+                .collect(),
+        })
+        .collect()
+});
+
+fn compile(pattern: &str) -> Regex {
+    Regex::new(pattern).expect("default segmentation rules are valid patterns")
+}
+
+fn compile_custom(rule: &CustomRule) -> Result<CompiledRule> {
+    let before = Regex::new(&format!("(?:{})$", rule.before))
+        .with_context(|| format!("Invalid text before break '{}'", rule.before))?;
+    let after = Regex::new(&format!("^(?:{})", rule.after))
+        .with_context(|| format!("Invalid text after break '{}'", rule.after))?;
+    Ok(CompiledRule {
+        break_here: rule.break_here,
+        before,
+        after,
+    })
+}
+
+/// Whether `language` gets the Chinese and Japanese rules on top of the default ones.
+pub fn uses_cjk_rules(language: &str) -> bool {
+    COMPILED_RULES[0].language.is_match(language.trim())
+}
+
+/// The default rules as an SRX 2.0 document.
+pub fn default_srx() -> String {
+    render_srx(&[])
+}
+
+/// `rules` followed by the default rules as an SRX 2.0 document. The custom rules apply to
+/// every language, so the document is meant for one source language.
+pub fn custom_srx(rules: &[CustomRule]) -> String {
+    render_srx(rules)
+}
+
+fn render_srx(custom: &[CustomRule]) -> String {
+    let mut srx = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <srx xmlns=\"http://www.lisa.org/srx20\" version=\"2.0\">\n  \
+         <header segmentsubflows=\"yes\" cascade=\"yes\">\n    \
+         <formathandle type=\"start\" include=\"no\"/>\n    \
+         <formathandle type=\"end\" include=\"yes\"/>\n    \
+         <formathandle type=\"isolated\" include=\"yes\"/>\n  \
+         </header>\n  <body>\n    <languagerules>\n",
+    );
+    if !custom.is_empty() {
+        srx.push_str(&format!(
+            "      <languagerule languagerulename=\"{CUSTOM_RULES_NAME}\">\n"
+        ));
+        for rule in custom {
+            push_rule(&mut srx, rule.break_here, &rule.before, &rule.after);
+        }
+        srx.push_str("      </languagerule>\n");
+    }
+    for group in &DEFAULT_LANGUAGE_RULES {
+        srx.push_str(&format!(
+            "      <languagerule languagerulename=\"{}\">\n",
+            escape(group.name)
+        ));
+        for rule in group.rules {
+            push_rule(&mut srx, rule.break_here, rule.before, rule.after);
+        }
+        srx.push_str("      </languagerule>\n");
+    }
+    srx.push_str("    </languagerules>\n    <maprules>\n");
+    if !custom.is_empty() {
+        srx.push_str(&format!(
+            "      <languagemap languagepattern=\".*\" languagerulename=\"{CUSTOM_RULES_NAME}\"/>\n"
+        ));
+    }
+    for group in &DEFAULT_LANGUAGE_RULES {
+        srx.push_str(&format!(
+            "      <languagemap languagepattern=\"{}\" languagerulename=\"{}\"/>\n",
+            escape(group.language_pattern),
+            escape(group.name)
+        ));
+    }
+    srx.push_str("    </maprules>\n  </body>\n</srx>\n");
+    srx
+}
+
+fn push_rule(srx: &mut String, break_here: bool, before: &str, after: &str) {
+    srx.push_str(&format!(
+        "        <rule break=\"{}\">\n          <beforebreak>{}</beforebreak>\n          \
+         <afterbreak>{}</afterbreak>\n        </rule>\n",
+        if break_here { "yes" } else { "no" },
+        escape(before),
+        escape(after)
+    ));
+}
+
+/// Writes [`default_srx`] to `dir` and returns its path.
+pub fn write_default_srx(dir: &Path) -> Result<PathBuf> {
+    write_srx(dir, DEFAULT_SRX_FILE, &default_srx())
+}
+
+/// Writes `srx` to `dir` as `file_name`, leaving an identical file untouched, and returns
+/// its path.
+pub fn write_srx(dir: &Path, file_name: &str, srx: &str) -> Result<PathBuf> {
+    let path = dir.join(file_name);
+    if std::fs::read_to_string(&path).is_ok_and(|existing| existing == srx) {
+        return Ok(path);
+    }
+    std::fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create directory {}", dir.display()))?;
+    std::fs::write(&path, srx).with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(path)
+}
+
+/// Splits `text` in `language` into sentences with the default rules. Whitespace after a
+/// break stays with the preceding sentence and `{{...}}` placeholders are never split.
+pub fn split_sentences<'a>(text: &'a str, language: &str) -> Vec<&'a str> {
+    split_sentences_with(text, language, &[]).expect("default segmentation rules compile")
+}
+
+/// Splits `text` like [`split_sentences`], trying `custom` rules before the default ones.
+pub fn split_sentences_with<'a>(
+    text: &'a str,
+    language: &str,
+    custom: &[CustomRule],
+) -> Result<Vec<&'a str>> {
+    let custom = custom
+        .iter()
+        .map(compile_custom)
+        .collect::<Result<Vec<_>>>()?;
+    let rules: Vec<&CompiledRule> = custom
+        .iter()
+        .chain(
+            COMPILED_RULES
+                .iter()
+                .filter(|group| group.language.is_match(language.trim()))
+                .flat_map(|group| &group.rules),
+        )
+        .collect();
+
+    let mut sentences = Vec::new();
+    let mut start = 0;
+    let mut placeholder_end = 0;
+    for (position, _) in text.char_indices() {
+        if position < placeholder_end {
+            continue;
+        }
+        if text[position..].starts_with("{{")
+            && let Some(close) = text[position..].find("}}")
+        {
+            placeholder_end = position + close + 2;
+        }
+        if position <= start {
+            continue;
+        }
+        let breaks = rules
+            .iter()
+            .find(|rule| {
+                rule.before.is_match(&text[start..position])
+                    && rule.after.is_match(&text[position..])
+            })
+            .is_some_and(|rule| rule.break_here);
+        if !breaks {
+            continue;
+        }
+        let trailing = text[position..].len() - text[position..].trim_start().len();
+        let end = position + trailing;
+        if end == text.len() {
+            break;
+        }
+        sentences.push(&text[start..end]);
+        start = end;
+    }
+    if start < text.len() {
+        sentences.push(&text[start..]);
+    }
+    Ok(sentences)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_chinese_and_japanese_without_spaces() {
+        assert_eq!(
+            split_sentences("请保存文件。然后关闭窗口！完成了吗？", "zh-CN"),
+            ["请保存文件。", "然后关闭窗口！", "完成了吗？"]
+        );
+        assert_eq!(
+            split_sentences("保存しますか？「はい」を押します。OK を押す。", "ja"),
+            ["保存しますか？", "「はい」を押します。", "OK を押す。"]
+        );
+        assert_eq!(
+            split_sentences("他说：“好。”然后走了。", "zh"),
+            ["他说：“好。”", "然后走了。"]
+        );
+        assert_eq!(
+            split_sentences("版本 2.5 已发布.请更新。", "zh-Hans"),
+            ["版本 2.5 已发布.", "请更新。"]
+        );
+    }
+
+    #[test]
+    fn default_rules_need_a_space_and_skip_abbreviations() {
+        assert_eq!(
+            split_sentences("Ask Dr. Smith first. Then save.", "en-US"),
+            ["Ask Dr. Smith first. ", "Then save."]
+        );
+        assert_eq!(
+            split_sentences("保存文件。关闭窗口。", "ko"),
+            ["保存文件。关闭窗口。"]
+        );
+        assert!(uses_cjk_rules("ja-JP"));
+        assert!(!uses_cjk_rules("jv"));
+    }
+
+    #[test]
+    fn never_splits_inside_placeholders() {
+        assert_eq!(
+            split_sentences("保存{{ph:1。2}}文件。好", "zh"),
+            ["保存{{ph:1。2}}文件。", "好"]
+        );
+    }
+
+    #[test]
+    fn custom_rules_come_before_the_defaults() {
+        let rules = [
+            abbreviation_rule("z.B."),
+            CustomRule {
+                break_here: true,
+                before: ";".into(),
+                after: r"\s".into(),
+            },
+        ];
+        assert_eq!(
+            split_sentences_with("Obst, z.B. Äpfel; dann Brot. Ende", "de-AT", &rules).unwrap(),
+            ["Obst, z.B. Äpfel; ", "dann Brot. ", "Ende"]
+        );
+        assert_eq!(
+            split_sentences("Obst, z.B. Äpfel", "de"),
+            ["Obst, z.B. ", "Äpfel"]
+        );
+        let srx = custom_srx(&rules);
+        assert!(srx.contains(r"<beforebreak>\bz\.B\.</beforebreak>"));
+        assert!(srx.find("\"Custom\"") < srx.find("\"Default\""));
+
+        let invalid = CustomRule {
+            break_here: false,
+            before: "(".into(),
+            after: String::new(),
+        };
+        assert!(split_sentences_with("Text.", "de", &[invalid]).is_err());
+    }
+
+    #[test]
+    fn renders_escaped_srx() {
+        let srx = default_srx();
+        assert!(srx.contains("cascade=\"yes\""));
+        assert!(srx.contains("<languagemap languagepattern=\".*\" languagerulename=\"Default\"/>"));
+        assert!(srx.contains(r"<beforebreak>[.!?…]+[&apos;&quot;”’)\]]*</beforebreak>"));
+    }
+}
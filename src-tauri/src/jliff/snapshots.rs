@@ -0,0 +1,224 @@
+//! Periodic whole-document snapshots of JLIFF files for disaster recovery.
+//!
+//! Snapshots are gzip-compressed copies stored under `<project>/snapshots/<document>/`,
+//! named after the UTC time they were taken. A [`SnapshotTracker`] counts the edits of one
+//! document and decides when the next snapshot is due; [`prune_snapshots`] keeps only the
+//! newest ones.
+
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+
+use super::model::JliffDocument;
+
+/// Folder, relative to the project root, holding all snapshots.
+pub const SNAPSHOTS_DIR: &str = "snapshots";
+
+const SNAPSHOT_SUFFIX: &str = ".jliff.json.gz";
+const SNAPSHOT_TIME_FORMAT: &str = "%Y%m%dT%H%M%S%3fZ";
+
+/// When snapshots are taken. A zero interval or threshold disables that trigger.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SnapshotPolicy {
+    pub interval: Duration,
+    pub edit_threshold: u32,
+}
+
+/// Edit bookkeeping for one document since its last snapshot.
+#[derive(Debug, Clone)]
+pub struct SnapshotTracker {
+    edits: u32,
+    last_snapshot: Instant,
+}
+
+impl SnapshotTracker {
+    pub fn new(now: Instant) -> Self {
+        Self {
+            edits: 0,
+            last_snapshot: now,
+        }
+    }
+
+    /// Counts an edit and returns whether a snapshot is due, resetting the counters if so.
+    pub fn record_edit(&mut self, now: Instant, policy: SnapshotPolicy) -> bool {
+        self.edits += 1;
+        let by_edits = policy.edit_threshold > 0 && self.edits >= policy.edit_threshold;
+        let by_time = !policy.interval.is_zero()
+            && now.saturating_duration_since(self.last_snapshot) >= policy.interval;
+        if by_edits || by_time {
+            self.edits = 0;
+            self.last_snapshot = now;
+            return true;
+        }
+        false
+    }
+}
+
+/// A snapshot file of one document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnapshotInfo {
+    /// File name, used as the snapshot id.
+    pub id: String,
+    pub path: PathBuf,
+    pub created_at: DateTime<Utc>,
+    pub size_bytes: u64,
+}
+
+/// Folder holding the snapshots of a project-relative JLIFF path.
+pub fn snapshot_dir_for(project_root: &Path, jliff_rel_path: &str) -> PathBuf {
+    let document = jliff_rel_path.trim_matches('/').replace(['/', '\\'], "__");
+    project_root.join(SNAPSHOTS_DIR).join(document)
+}
+
+/// Writes a compressed snapshot of `document` taken at `now`.
+pub fn write_snapshot(dir: &Path, document: &JliffDocument, now: DateTime<Utc>) -> Result<PathBuf> {
+    fs::create_dir_all(dir)
+        .with_context(|| format!("Unable to create snapshot folder {}", dir.display()))?;
+    let path = dir.join(format!(
+        "{}{SNAPSHOT_SUFFIX}",
+        now.format(SNAPSHOT_TIME_FORMAT)
+    ));
+    let payload = serde_json::to_vec(document).context("Failed to serialize JLIFF snapshot")?;
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(&payload)?;
+    let compressed = encoder
+        .finish()
+        .context("Failed to compress JLIFF snapshot")?;
+    fs::write(&path, compressed)
+        .with_context(|| format!("Unable to write snapshot {}", path.display()))?;
+    Ok(path)
+}
+
+/// Lists the snapshots in `dir`, newest first. A missing folder yields no snapshots.
+pub fn list_snapshots(dir: &Path) -> Result<Vec<SnapshotInfo>> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(error) => {
+            return Err(error)
+                .with_context(|| format!("Unable to list snapshots in {}", dir.display()));
+        }
+    };
+
+    let mut snapshots = Vec::new();
+    for entry in entries {
+        let entry = entry?;
+        let id = entry.file_name().to_string_lossy().into_owned();
+        let Some(created_at) = parse_snapshot_time(&id) else {
+            continue;
+        };
+        snapshots.push(SnapshotInfo {
+            path: entry.path(),
+            size_bytes: entry.metadata()?.len(),
+            created_at,
+            id,
+        });
+    }
+    snapshots.sort_by(|left, right| right.created_at.cmp(&left.created_at));
+    Ok(snapshots)
+}
+
+/// Reads the snapshot `id` from `dir`, rejecting ids that are not snapshot file names.
+pub fn read_snapshot(dir: &Path, id: &str) -> Result<JliffDocument> {
+    if id.contains(['/', '\\']) || parse_snapshot_time(id).is_none() {
+        bail!("'{id}' is not a snapshot id");
+    }
+    let path = dir.join(id);
+    let file = fs::File::open(&path)
+        .with_context(|| format!("Unable to open snapshot {}", path.display()))?;
+    let mut payload = Vec::new();
+    GzDecoder::new(file)
+        .read_to_end(&mut payload)
+        .with_context(|| format!("Snapshot {} is corrupted", path.display()))?;
+    serde_json::from_slice(&payload)
+        .with_context(|| format!("Snapshot {} is not a valid JLIFF document", path.display()))
+}
+
+/// Deletes all but the `keep` newest snapshots in `dir`, returning how many were removed.
+pub fn prune_snapshots(dir: &Path, keep: usize) -> Result<usize> {
+    let snapshots = list_snapshots(dir)?;
+    let mut removed = 0;
+    for snapshot in snapshots.iter().skip(keep) {
+        fs::remove_file(&snapshot.path)
+            .with_context(|| format!("Unable to delete snapshot {}", snapshot.path.display()))?;
+        removed += 1;
+    }
+    Ok(removed)
+}
+
+fn parse_snapshot_time(id: &str) -> Option<DateTime<Utc>> {
+    let stamp = id.strip_suffix(SNAPSHOT_SUFFIX)?;
+    NaiveDateTime::parse_from_str(stamp, SNAPSHOT_TIME_FORMAT)
+        .ok()
+        .map(|time| time.and_utc())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn document(file: &str) -> JliffDocument {
+        JliffDocument {
+            project_name: "Demo".into(),
+            project_id: "p".into(),
+            file: file.into(),
+            user: "u".into(),
+            source_language: "en".into(),
+            target_language: "de".into(),
+            transunits: Vec::new(),
+            segment_operations: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn tracker_triggers_on_edit_count_or_elapsed_time() {
+        let start = Instant::now();
+        let policy = SnapshotPolicy {
+            interval: Duration::from_secs(600),
+            edit_threshold: 3,
+        };
+        let mut tracker = SnapshotTracker::new(start);
+        assert!(!tracker.record_edit(start, policy));
+        assert!(!tracker.record_edit(start, policy));
+        assert!(tracker.record_edit(start, policy));
+        assert!(!tracker.record_edit(start + Duration::from_secs(599), policy));
+        assert!(tracker.record_edit(start + Duration::from_secs(600), policy));
+    }
+
+    #[test]
+    fn writes_lists_restores_and_prunes_snapshots() -> Result<()> {
+        let root = tempfile::tempdir()?;
+        let dir = snapshot_dir_for(root.path(), "jliff/a.jliff.json");
+        assert!(dir.ends_with("snapshots/jliff__a.jliff.json"));
+
+        for (second, name) in [(1, "first.docx"), (2, "second.docx"), (3, "third.docx")] {
+            let time = Utc.with_ymd_and_hms(2026, 10, 15, 9, 0, second).unwrap();
+            write_snapshot(&dir, &document(name), time)?;
+        }
+
+        let snapshots = list_snapshots(&dir)?;
+        assert_eq!(snapshots.len(), 3);
+        assert_eq!(snapshots[0].id, "20261015T090003000Z.jliff.json.gz");
+        assert_eq!(read_snapshot(&dir, &snapshots[2].id)?.file, "first.docx");
+        assert!(read_snapshot(&dir, "../a.jliff.json").is_err());
+
+        assert_eq!(prune_snapshots(&dir, 2)?, 1);
+        let remaining: Vec<String> = list_snapshots(&dir)?.into_iter().map(|s| s.id).collect();
+        assert_eq!(
+            remaining,
+            [
+                "20261015T090003000Z.jliff.json.gz",
+                "20261015T090002000Z.jliff.json.gz"
+            ]
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,330 @@
+//! Splitting a JLIFF document into parts for several linguists and merging them back.
+//!
+//! Parts hold disjoint, consecutive runs of transunits. Segments of the same XLIFF
+//! `<unit>` always stay in the same part so each linguist sees whole units. A manifest
+//! written next to the parts records which transunits went where; merging checks that the
+//! parts cover every transunit of the original exactly once.
+
+use std::collections::{HashMap, HashSet};
+
+use anyhow::{Result, bail};
+use serde::{Deserialize, Serialize};
+
+use super::model::{JliffDocument, TransUnit};
+use super::statistics::{WordCountStrategy, source_word_count};
+use super::tag_map::TagMapDoc;
+
+/// Current format version of [`SplitManifest`].
+pub const SPLIT_MANIFEST_VERSION: u32 = 1;
+
+/// How a document is divided.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SplitStrategy {
+    /// Exactly this many parts of roughly equal source word count.
+    Parts(usize),
+    /// As many parts as needed so each holds about this many source words.
+    WordsPerPart(usize),
+}
+
+/// Record of a split, stored as `<prefix>.split.json` next to the parts.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SplitManifest {
+    pub version: u32,
+    /// Project-relative path of the JLIFF document that was split.
+    pub source_jliff: String,
+    pub source_transunit_count: usize,
+    pub parts: Vec<SplitPart>,
+}
+
+/// One part listed in a [`SplitManifest`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SplitPart {
+    /// 1-based position of the part.
+    pub index: usize,
+    /// File name of the part's JLIFF, relative to the manifest.
+    pub jliff_file: String,
+    pub word_count: usize,
+    pub transunit_ids: Vec<String>,
+}
+
+/// Divides `document` into parts according to `strategy`.
+///
+/// Every part carries the header of the original and is never empty.
+pub fn split_document(
+    document: &JliffDocument,
+    strategy: SplitStrategy,
+) -> Result<Vec<JliffDocument>> {
+    if document.transunits.is_empty() {
+        bail!("Document has no transunits to split");
+    }
+
+    let strategy = WordCountStrategy::for_source(document);
+    // Consecutive transunits of one XLIFF unit form an indivisible group.
+    let mut groups: Vec<(usize, usize, usize)> = Vec::new();
+    for (index, unit) in document.transunits.iter().enumerate() {
+        let words = source_word_count(unit, strategy);
+        match groups.last_mut() {
+            Some((start, end, group_words))
+                if document.transunits[*start].unit_id == unit.unit_id =>
+            {
+                *end = index + 1;
+                *group_words += words;
+            }
+            _ => groups.push((index, index + 1, words)),
+        }
+    }
+
+    let ranges = match strategy {
+        SplitStrategy::Parts(count) => {
+            if count < 2 {
+                bail!("A document must be split into at least 2 parts");
+            }
+            if count > groups.len() {
+                bail!("Cannot split {} units into {count} parts", groups.len());
+            }
+            split_by_parts(&groups, count)
+        }
+        SplitStrategy::WordsPerPart(words) => {
+            if words == 0 {
+                bail!("Words per part must be greater than 0");
+            }
+            split_by_words(&groups, words)
+        }
+    };
+
+    Ok(ranges
+        .into_iter()
+        .map(|(start, end)| JliffDocument {
+            transunits: document.transunits[start..end].to_vec(),
+            ..header_of(document)
+        })
+        .collect())
+}
+
+/// Rebuilds `original` from completed parts, keeping the original transunit order.
+///
+/// Fails when a transunit is missing from every part, appears in several parts, or is not
+/// part of the original.
+pub fn merge_split_documents(
+    original: &JliffDocument,
+    parts: &[JliffDocument],
+) -> Result<JliffDocument> {
+    let known: HashSet<&str> = original
+        .transunits
+        .iter()
+        .map(|unit| unit.transunit_id.as_str())
+        .collect();
+
+    let mut merged: HashMap<&str, &TransUnit> = HashMap::new();
+    let mut duplicates = Vec::new();
+    let mut unknown = Vec::new();
+    for unit in parts.iter().flat_map(|part| &part.transunits) {
+        let id = unit.transunit_id.as_str();
+        if !known.contains(id) {
+            unknown.push(id);
+        } else if merged.insert(id, unit).is_some() {
+            duplicates.push(id);
+        }
+    }
+    let missing: Vec<&str> = original
+        .transunits
+        .iter()
+        .map(|unit| unit.transunit_id.as_str())
+        .filter(|id| !merged.contains_key(id))
+        .collect();
+
+    let mut problems = Vec::new();
+    for (label, ids) in [
+        ("missing", &missing),
+        ("duplicated", &duplicates),
+        ("unknown", &unknown),
+    ] {
+        if !ids.is_empty() {
+            problems.push(format!("{label}: {}", ids.join(", ")));
+        }
+    }
+    if !problems.is_empty() {
+        bail!(
+            "Split parts do not cover the document ({})",
+            problems.join("; ")
+        );
+    }
+
+    Ok(JliffDocument {
+        transunits: original
+            .transunits
+            .iter()
+            .map(|unit| merged[unit.transunit_id.as_str()].clone())
+            .collect(),
+        ..header_of(original)
+    })
+}
+
+/// Restricts a tag map to the units present in `document`.
+pub fn tag_map_for_part(tag_map: &TagMapDoc, document: &JliffDocument) -> TagMapDoc {
+    let unit_ids: HashSet<&str> = document
+        .transunits
+        .iter()
+        .map(|unit| unit.unit_id.as_str())
+        .collect();
+    TagMapDoc {
+        version: tag_map.version,
+        file_id: tag_map.file_id.clone(),
+        original_path: tag_map.original_path.clone(),
+        source_language: tag_map.source_language.clone(),
+        target_language: tag_map.target_language.clone(),
+        placeholder_style: tag_map.placeholder_style.clone(),
+        units: tag_map
+            .units
+            .iter()
+            .filter(|unit| unit_ids.contains(unit.unit_id.as_str()))
+            .cloned()
+            .collect(),
+    }
+}
+
+fn header_of(document: &JliffDocument) -> JliffDocument {
+    JliffDocument {
+        project_name: document.project_name.clone(),
+        project_id: document.project_id.clone(),
+        file: document.file.clone(),
+        user: document.user.clone(),
+        source_language: document.source_language.clone(),
+        target_language: document.target_language.clone(),
+        transunits: Vec::new(),
+        segment_operations: document.segment_operations.clone(),
+    }
+}
+
+fn split_by_parts(groups: &[(usize, usize, usize)], count: usize) -> Vec<(usize, usize)> {
+    let total: usize = groups.iter().map(|(_, _, words)| words).sum();
+    let mut ranges = Vec::with_capacity(count);
+    let mut start = groups[0].0;
+    let mut cumulative = 0;
+    for (position, (_, end, words)) in groups.iter().enumerate() {
+        cumulative += words;
+        let parts_after = count - ranges.len() - 1;
+        if parts_after == 0 {
+            continue;
+        }
+        let groups_after = groups.len() - position - 1;
+        let target = total * (ranges.len() + 1) / count;
+        if groups_after == parts_after || cumulative >= target {
+            ranges.push((start, *end));
+            start = *end;
+        }
+    }
+    ranges.push((start, groups[groups.len() - 1].1));
+    ranges
+}
+
+fn split_by_words(groups: &[(usize, usize, usize)], per_part: usize) -> Vec<(usize, usize)> {
+    let mut ranges = Vec::new();
+    let Some(first) = groups.first() else {
+        return ranges;
+    };
+    let mut start = first.0;
+    let mut words_in_part = 0;
+    for (position, (_, end, words)) in groups.iter().enumerate() {
+        words_in_part += words;
+        if words_in_part >= per_part && position + 1 < groups.len() {
+            ranges.push((start, *end));
+            start = *end;
+            words_in_part = 0;
+        }
+    }
+    ranges.push((start, groups[groups.len() - 1].1));
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unit(unit_id: &str, segment: usize, source: &str) -> TransUnit {
+        TransUnit {
+            unit_id: unit_id.into(),
+            transunit_id: format!("u{unit_id}-s{segment}"),
+            source: source.into(),
+            target_translation: String::new(),
+            target_qa_1: None,
+            target_qa_2: None,
+            target_postedit: None,
+            translation_notes: None,
+            qa_notes: None,
+            source_notes: None,
+            metadata: None,
+            status: None,
+            sub_state: None,
+            notes: Vec::new(),
+            propagated_from: None,
+        }
+    }
+
+    fn document() -> JliffDocument {
+        JliffDocument {
+            project_name: "Demo".into(),
+            project_id: "p".into(),
+            file: "a.docx".into(),
+            user: "u".into(),
+            source_language: "en".into(),
+            target_language: "de".into(),
+            transunits: vec![
+                unit("1", 1, "one two three"),
+                unit("1", 2, "four {{ph:1}}"),
+                unit("2", 1, "five six"),
+                unit("3", 1, "seven"),
+                unit("4", 1, "eight nine ten"),
+            ],
+            segment_operations: Vec::new(),
+        }
+    }
+
+    fn ids(part: &JliffDocument) -> Vec<&str> {
+        part.transunits
+            .iter()
+            .map(|unit| unit.transunit_id.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn splits_on_unit_boundaries() -> Result<()> {
+        let document = document();
+
+        let parts = split_document(&document, SplitStrategy::Parts(2))?;
+        assert_eq!(parts.len(), 2);
+        assert_eq!(ids(&parts[0]), ["u1-s1", "u1-s2", "u2-s1"]);
+        assert_eq!(ids(&parts[1]), ["u3-s1", "u4-s1"]);
+
+        let parts = split_document(&document, SplitStrategy::WordsPerPart(3))?;
+        assert_eq!(parts.len(), 3);
+        assert_eq!(ids(&parts[0]), ["u1-s1", "u1-s2"]);
+        assert_eq!(ids(&parts[1]), ["u2-s1", "u3-s1"]);
+        assert_eq!(ids(&parts[2]), ["u4-s1"]);
+
+        assert!(split_document(&document, SplitStrategy::Parts(5)).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn merge_requires_every_unit_exactly_once() -> Result<()> {
+        let document = document();
+        let mut parts = split_document(&document, SplitStrategy::Parts(3))?;
+        parts[1].transunits[0].target_translation = "fünf sechs".into();
+
+        let merged = merge_split_documents(&document, &parts)?;
+        assert_eq!(ids(&merged), ids(&document));
+        assert_eq!(merged.transunits[2].target_translation, "fünf sechs");
+
+        let mut duplicated = parts.clone();
+        duplicated[2]
+            .transunits
+            .push(parts[0].transunits[0].clone());
+        let error = merge_split_documents(&document, &duplicated).unwrap_err();
+        assert!(error.to_string().contains("duplicated: u1-s1"));
+
+        let error = merge_split_documents(&document, &parts[..2]).unwrap_err();
+        assert!(error.to_string().contains("missing"));
+        Ok(())
+    }
+}
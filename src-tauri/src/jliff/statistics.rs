@@ -0,0 +1,169 @@
+//! Language-aware word counts.
+//!
+//! Chinese and Japanese are written without spaces between words, so counting
+//! whitespace-separated tokens makes a whole sentence one "word". Like the usual CAT tools,
+//! those languages are counted by character instead: every ideograph or kana is one word,
+//! while embedded Latin words and numbers still count once each. Korean separates words with
+//! spaces and keeps the whitespace count.
+
+use super::model::{JliffDocument, TransUnit};
+
+/// Languages counted by character unless a script subtag says otherwise.
+const CHARACTER_COUNTED_LANGUAGES: [&str; 6] = ["cmn", "ja", "lzh", "wuu", "yue", "zh"];
+/// ISO 15924 codes of scripts counted by character.
+const CHARACTER_COUNTED_SCRIPTS: [&str; 6] = ["hani", "hans", "hant", "hira", "jpan", "kana"];
+
+/// How the words of a text are counted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WordCountStrategy {
+    /// One word per whitespace-separated token.
+    #[default]
+    Whitespace,
+    /// One word per Han ideograph or kana, plus one per whitespace-separated run of other
+    /// text containing letters or digits.
+    CjkCharacters,
+}
+
+impl WordCountStrategy {
+    /// Strategy for text in `language` (a BCP 47 tag such as `zh-CN` or `sr-Latn`).
+    pub fn for_language(language: &str) -> Self {
+        let mut subtags = language.trim().split(['-', '_']);
+        let primary = subtags.next().unwrap_or_default().to_ascii_lowercase();
+        let script = subtags
+            .take_while(|subtag| subtag.len() != 1)
+            .find(|subtag| subtag.len() == 4 && subtag.chars().all(|ch| ch.is_ascii_alphabetic()))
+            .map(str::to_ascii_lowercase);
+        let by_character = match script {
+            Some(script) => CHARACTER_COUNTED_SCRIPTS.contains(&script.as_str()),
+            None => CHARACTER_COUNTED_LANGUAGES.contains(&primary.as_str()),
+        };
+        if by_character {
+            WordCountStrategy::CjkCharacters
+        } else {
+            WordCountStrategy::Whitespace
+        }
+    }
+
+    /// Strategy for the source text of `document`.
+    pub fn for_source(document: &JliffDocument) -> Self {
+        Self::for_language(&document.source_language)
+    }
+}
+
+/// Counts the words of `text`, ignoring inline-code placeholders.
+pub fn count_words(text: &str, strategy: WordCountStrategy) -> usize {
+    match strategy {
+        WordCountStrategy::Whitespace => text
+            .split_whitespace()
+            .filter(|word| !(word.starts_with("{{") && word.ends_with("}}")))
+            .count(),
+        WordCountStrategy::CjkCharacters => {
+            let mut count = 0;
+            // Whether the current run of other characters was already counted.
+            let mut counted = false;
+            for ch in without_placeholders(text).chars() {
+                if is_cjk_character(ch) {
+                    count += 1;
+                    counted = false;
+                } else if ch.is_whitespace() {
+                    counted = false;
+                } else if ch.is_alphanumeric() && !counted {
+                    count += 1;
+                    counted = true;
+                }
+            }
+            count
+        }
+    }
+}
+
+/// Counts the words of a segment's source, ignoring inline-code placeholders.
+pub fn source_word_count(unit: &TransUnit, strategy: WordCountStrategy) -> usize {
+    count_words(&unit.source, strategy)
+}
+
+/// Whether `ch` is a Han ideograph, hiragana or katakana.
+pub fn is_cjk_character(ch: char) -> bool {
+    matches!(
+        ch,
+        '\u{3005}'..='\u{3007}'
+            | '\u{3040}'..='\u{309F}'
+            | '\u{30A0}'..='\u{30FF}'
+            | '\u{31F0}'..='\u{31FF}'
+            | '\u{3400}'..='\u{4DBF}'
+            | '\u{4E00}'..='\u{9FFF}'
+            | '\u{F900}'..='\u{FAFF}'
+            | '\u{FF66}'..='\u{FF9D}'
+            | '\u{20000}'..='\u{2EBEF}'
+            | '\u{30000}'..='\u{3134F}'
+    )
+}
+
+/// `text` with every `{{...}}` placeholder replaced by a space.
+fn without_placeholders(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        let Some(length) = rest[start..].find("}}") else {
+            break;
+        };
+        stripped.push_str(&rest[..start]);
+        stripped.push(' ');
+        rest = &rest[start + length + 2..];
+    }
+    stripped.push_str(rest);
+    stripped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn picks_strategy_from_language_and_script() {
+        assert_eq!(
+            WordCountStrategy::for_language("zh-CN"),
+            WordCountStrategy::CjkCharacters
+        );
+        assert_eq!(
+            WordCountStrategy::for_language("ja"),
+            WordCountStrategy::CjkCharacters
+        );
+        assert_eq!(
+            WordCountStrategy::for_language("yue-Hant-HK"),
+            WordCountStrategy::CjkCharacters
+        );
+        assert_eq!(
+            WordCountStrategy::for_language("zh-Latn-pinyin"),
+            WordCountStrategy::Whitespace
+        );
+        assert_eq!(
+            WordCountStrategy::for_language("ko-KR"),
+            WordCountStrategy::Whitespace
+        );
+        assert_eq!(
+            WordCountStrategy::for_language("en-US"),
+            WordCountStrategy::Whitespace
+        );
+    }
+
+    #[test]
+    fn counts_whitespace_separated_words_without_placeholders() {
+        let strategy = WordCountStrategy::Whitespace;
+        assert_eq!(count_words("Save {{ph:1}} the file", strategy), 3);
+        assert_eq!(count_words("  ", strategy), 0);
+        assert_eq!(count_words("이 파일을 저장합니다", strategy), 3);
+    }
+
+    #[test]
+    fn counts_cjk_characters_and_embedded_latin_words() {
+        let strategy = WordCountStrategy::CjkCharacters;
+        assert_eq!(count_words("保存文件。", strategy), 4);
+        assert_eq!(count_words("ファイルを保存", strategy), 7);
+        assert_eq!(
+            count_words("在 Windows 10 上{{ph:1}}安装{{/ph:1}}", strategy),
+            6
+        );
+        assert_eq!(count_words("version 2.5 的", strategy), 3);
+    }
+}
@@ -1,10 +1,27 @@
 use std::collections::BTreeMap;
+use std::sync::OnceLock;
 
-use serde::Serialize;
+use anyhow::{Result, anyhow, bail};
+use jsonschema::Validator;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Current tag-map format version written by the converter.
+///
+/// Version history:
+/// - `0`: original, unversioned format (no `version` field).
+/// - `1`: adds the `version` field; validated against the embedded schema.
+pub const TAG_MAP_VERSION: u32 = 1;
+
+/// JSON schema describing the current tag-map format.
+pub const TAG_MAP_SCHEMA: &str = include_str!("tag_map.schema.json");
 
 /// Metadata about inline tags mapped to placeholders for a single XLIFF <file>.
-#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct TagMapDoc {
+    /// Format version; absent in artifacts written before versioning was introduced.
+    #[serde(default)]
+    pub version: u32,
     pub file_id: String,
     pub original_path: String,
     pub source_language: String,
@@ -14,30 +31,184 @@ pub struct TagMapDoc {
 }
 
 /// Tag mapping for a specific <unit>.
-#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct TagMapUnit {
     pub unit_id: String,
     pub segments: Vec<TagMapSegment>,
 }
 
 /// Tag mapping for a specific <segment> inside a unit.
-#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct TagMapSegment {
     pub segment_id: String,
     #[serde(rename = "placeholders_in_order")]
     pub placeholders: Vec<TagInstance>,
-    #[serde(rename = "originalData_bucket")]
+    #[serde(rename = "originalData_bucket", default)]
     pub original_data_bucket: BTreeMap<String, String>,
 }
 
 /// Details for a single placeholder emitted in the output JSON.
-#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct TagInstance {
     pub placeholder: String,
     pub elem: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub id: Option<String>,
+    #[serde(default)]
     pub attrs: BTreeMap<String, Option<String>>,
-    #[serde(rename = "originalData", skip_serializing_if = "Option::is_none")]
+    #[serde(
+        rename = "originalData",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
     pub original_data: Option<String>,
 }
+
+impl TagMapDoc {
+    /// Finds the tag-map segment backing a JLIFF translation unit.
+    ///
+    /// Transunit ids are generated as `u{unit_id}-s{segment_id}` during conversion.
+    pub fn segment_for(&self, unit_id: &str, transunit_id: &str) -> Option<&TagMapSegment> {
+        let segment_id = transunit_id.strip_prefix(&format!("u{unit_id}-s"))?;
+        self.units
+            .iter()
+            .find(|unit| unit.unit_id == unit_id)?
+            .segments
+            .iter()
+            .find(|segment| segment.segment_id == segment_id)
+    }
+
+    /// Iterates every segment together with the JLIFF transunit id it was converted into.
+    pub fn segments_by_transunit(&self) -> impl Iterator<Item = (String, &TagMapSegment)> {
+        self.units.iter().flat_map(|unit| {
+            unit.segments.iter().map(move |segment| {
+                (
+                    format!("u{}-s{}", unit.unit_id, segment.segment_id),
+                    segment,
+                )
+            })
+        })
+    }
+}
+
+impl TagMapSegment {
+    /// Looks up the tag instance recorded for a placeholder token.
+    pub fn instance(&self, placeholder: &str) -> Option<&TagInstance> {
+        self.placeholders
+            .iter()
+            .find(|instance| instance.placeholder == placeholder)
+    }
+}
+
+/// Validates a serialized tag map against the embedded schema.
+pub fn validate_tag_map_value(value: &Value) -> Result<()> {
+    let errors: Vec<String> = schema_validator()
+        .iter_errors(value)
+        .map(|error| format!("{}: {error}", error.instance_path))
+        .collect();
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        bail!("tag map failed schema validation: {}", errors.join("; "))
+    }
+}
+
+/// Migrates a serialized tag map to [`TAG_MAP_VERSION`] in place.
+///
+/// Returns `true` when the value was changed and should be persisted. Tag maps written by a
+/// newer release are rejected rather than guessed at.
+pub fn upgrade_tag_map_value(value: &mut Value) -> Result<bool> {
+    let object = value
+        .as_object_mut()
+        .ok_or_else(|| anyhow!("tag map must be a JSON object"))?;
+    let version = match object.get("version") {
+        None => 0,
+        Some(raw) => raw
+            .as_u64()
+            .and_then(|version| u32::try_from(version).ok())
+            .ok_or_else(|| anyhow!("tag map version must be a non-negative integer"))?,
+    };
+    if version > TAG_MAP_VERSION {
+        bail!("tag map version {version} is newer than the supported version {TAG_MAP_VERSION}");
+    }
+
+    let mut current = version;
+    while current < TAG_MAP_VERSION {
+        match current {
+            // v0 -> v1: the payload is unchanged, only the version marker is added.
+            0 => {}
+            other => bail!("no tag map upgrade defined from version {other}"),
+        }
+        current += 1;
+    }
+
+    if version == TAG_MAP_VERSION {
+        return Ok(false);
+    }
+    object.insert("version".into(), Value::from(TAG_MAP_VERSION));
+    Ok(true)
+}
+
+fn schema_validator() -> &'static Validator {
+    static VALIDATOR: OnceLock<Validator> = OnceLock::new();
+    VALIDATOR.get_or_init(|| {
+        let schema: Value =
+            serde_json::from_str(TAG_MAP_SCHEMA).expect("embedded tag-map schema is valid JSON");
+        jsonschema::validator_for(&schema).expect("embedded tag-map schema compiles")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn legacy_tag_map() -> Value {
+        json!({
+            "file_id": "f1",
+            "original_path": "sample.docx",
+            "source_language": "en-US",
+            "target_language": "it-IT",
+            "placeholder_style": "double-curly",
+            "units": [{
+                "unit_id": "1",
+                "segments": [{
+                    "segment_id": "1",
+                    "placeholders_in_order": [
+                        { "placeholder": "{{ph:1}}", "elem": "ph", "id": "1", "attrs": {} }
+                    ],
+                    "originalData_bucket": {}
+                }]
+            }]
+        })
+    }
+
+    #[test]
+    fn upgrades_unversioned_tag_maps() -> Result<()> {
+        let mut value = legacy_tag_map();
+        assert!(validate_tag_map_value(&value).is_err());
+
+        assert!(upgrade_tag_map_value(&mut value)?);
+        assert_eq!(value["version"], TAG_MAP_VERSION);
+        validate_tag_map_value(&value)?;
+        assert!(!upgrade_tag_map_value(&mut value)?);
+
+        let doc: TagMapDoc = serde_json::from_value(value)?;
+        assert_eq!(doc.version, TAG_MAP_VERSION);
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_newer_and_malformed_tag_maps() {
+        let mut newer = legacy_tag_map();
+        newer["version"] = json!(TAG_MAP_VERSION + 1);
+        assert!(upgrade_tag_map_value(&mut newer).is_err());
+
+        let mut malformed = legacy_tag_map();
+        malformed["version"] = json!(TAG_MAP_VERSION);
+        malformed["units"][0]["segments"][0]["placeholders_in_order"][0]["placeholder"] = json!(7);
+        let error = validate_tag_map_value(&malformed).unwrap_err().to_string();
+        assert!(error.contains("placeholders_in_order"));
+    }
+}
@@ -0,0 +1,449 @@
+//! Inline tag integrity repair for translated segments.
+//!
+//! Machine translation regularly drops, duplicates, or reorders `{{...}}` placeholders, which
+//! corrupts the document when the target is merged back into XLIFF. [`repair_tags`] compares
+//! the target against the source and applies conservative heuristics:
+//!
+//! 1. placeholders that do not exist in the source, or exceed the source count, are removed;
+//! 2. missing placeholders are reinserted next to a neighbouring placeholder they were glued to
+//!    in the source, or otherwise at the proportional position snapped to a word boundary;
+//! 3. paired codes (`pc` start/end, `sc`/`ec`) whose closing tag precedes the opening tag are
+//!    swapped back into order.
+//!
+//! Every change is reported, and anything the heuristics cannot vouch for (estimated positions,
+//! crossing pairs) is listed separately so a linguist can review it.
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+/// Kind of change applied to the target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TagRepairKind {
+    RemovedUnknown,
+    RemovedDuplicate,
+    Reinserted,
+    Reordered,
+}
+
+/// How much the heuristic trusts a repair.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RepairConfidence {
+    High,
+    Low,
+}
+
+/// A single change applied to the target text.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagRepair {
+    pub kind: TagRepairKind,
+    pub placeholder: String,
+    pub confidence: RepairConfidence,
+}
+
+/// A problem that still needs human review after repair.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagRepairIssue {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub placeholder: Option<String>,
+    pub reason: String,
+}
+
+/// Outcome of [`repair_tags`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagRepairReport {
+    pub repaired_text: String,
+    pub changed: bool,
+    pub repairs: Vec<TagRepair>,
+    pub needs_attention: Vec<TagRepairIssue>,
+}
+
+/// A placeholder token located in a text, with byte offsets.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Token {
+    text: String,
+    start: usize,
+    end: usize,
+}
+
+/// A placeholder positioned in the tag-free target text.
+#[derive(Debug, Clone)]
+struct Placed {
+    /// Byte offset into the plain (tag-free) target.
+    position: usize,
+    /// Tie-breaker preserving the original order at equal positions.
+    order: usize,
+    /// Tie-breaker against the placeholder at the same `order`: 0 when reinserted before its
+    /// anchor, 1 for the placeholder itself, 2 when reinserted after its anchor.
+    side: u8,
+    token: String,
+}
+
+/// Where a missing placeholder goes next to a surviving neighbour.
+struct Anchor {
+    position: usize,
+    order: usize,
+    side: u8,
+}
+
+/// Repairs the placeholders of `target` so they match the placeholders of `source`.
+pub fn repair_tags(source: &str, target: &str) -> TagRepairReport {
+    let mut repairs = Vec::new();
+    let mut needs_attention = Vec::new();
+
+    let source_tokens = scan_tokens(source);
+    let mut expected: HashMap<&str, usize> = HashMap::new();
+    for token in &source_tokens {
+        *expected.entry(token.text.as_str()).or_default() += 1;
+    }
+
+    // Strip every placeholder from the target, keeping the legitimate ones with their
+    // position in the plain text.
+    let (plain, target_tokens) = strip_tokens(target);
+    let mut kept: HashMap<String, usize> = HashMap::new();
+    let mut placed = Vec::new();
+    for (order, (position, token)) in target_tokens.into_iter().enumerate() {
+        let allowed = expected.get(token.as_str()).copied().unwrap_or(0);
+        let seen = kept.entry(token.clone()).or_default();
+        if allowed == 0 {
+            repairs.push(TagRepair {
+                kind: TagRepairKind::RemovedUnknown,
+                placeholder: token,
+                confidence: RepairConfidence::High,
+            });
+            continue;
+        }
+        if *seen >= allowed {
+            repairs.push(TagRepair {
+                kind: TagRepairKind::RemovedDuplicate,
+                placeholder: token,
+                confidence: RepairConfidence::High,
+            });
+            continue;
+        }
+        *seen += 1;
+        placed.push(Placed {
+            position,
+            order,
+            side: 1,
+            token,
+        });
+    }
+
+    // Reinsert missing placeholders following source order.
+    let source_plain_len = strip_tokens(source).0.chars().count().max(1);
+    let mut next_order = placed.len();
+    let mut remaining = kept.clone();
+    for (index, token) in source_tokens.iter().enumerate() {
+        let available = remaining.entry(token.text.clone()).or_default();
+        if *available > 0 {
+            *available -= 1;
+            continue;
+        }
+
+        let anchor = anchor_position(source, &source_tokens, index, &placed);
+        let (anchor, confidence) = match anchor {
+            Some(anchor) => (anchor, RepairConfidence::High),
+            None => {
+                let source_offset = strip_tokens(&source[..token.start]).0.chars().count();
+                let plain_chars = plain.chars().count();
+                let estimate = source_offset * plain_chars / source_plain_len;
+                let anchor = Anchor {
+                    position: snap_to_boundary(&plain, estimate),
+                    order: next_order,
+                    side: 1,
+                };
+                (anchor, RepairConfidence::Low)
+            }
+        };
+        if confidence == RepairConfidence::Low {
+            needs_attention.push(TagRepairIssue {
+                placeholder: Some(token.text.clone()),
+                reason: "Placeholder was reinserted at an estimated position; verify placement."
+                    .into(),
+            });
+        }
+        repairs.push(TagRepair {
+            kind: TagRepairKind::Reinserted,
+            placeholder: token.text.clone(),
+            confidence,
+        });
+        placed.push(Placed {
+            position: anchor.position,
+            order: anchor.order,
+            side: anchor.side,
+            token: token.text.clone(),
+        });
+        next_order += 1;
+    }
+
+    // Stable, so placeholders sharing a position, order and side keep source order.
+    placed.sort_by_key(|entry| (entry.position, entry.order, entry.side));
+    balance_pairs(&mut placed, &mut repairs, &mut needs_attention);
+
+    let repaired_text = rebuild(&plain, &placed);
+    TagRepairReport {
+        changed: repaired_text != target,
+        repaired_text,
+        repairs,
+        needs_attention,
+    }
+}
+
+/// Finds every `{{...}}` token in `text`.
+fn scan_tokens(text: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut cursor = 0;
+    while let Some(offset) = text[cursor..].find("{{") {
+        let start = cursor + offset;
+        let Some(close) = text[start..].find("}}") else {
+            break;
+        };
+        let end = start + close + 2;
+        tokens.push(Token {
+            text: text[start..end].to_string(),
+            start,
+            end,
+        });
+        cursor = end;
+    }
+    tokens
+}
+
+/// Removes all tokens from `text`, returning the plain text and each token with its byte
+/// position in the plain text.
+fn strip_tokens(text: &str) -> (String, Vec<(usize, String)>) {
+    let mut plain = String::with_capacity(text.len());
+    let mut tokens = Vec::new();
+    let mut last = 0;
+    for token in scan_tokens(text) {
+        plain.push_str(&text[last..token.start]);
+        tokens.push((plain.len(), token.text));
+        last = token.end;
+    }
+    plain.push_str(&text[last..]);
+    (plain, tokens)
+}
+
+/// When the missing token was glued to a neighbouring token in the source (no text between
+/// them) and that neighbour survived in the target, returns the neighbour's position with
+/// the side of it the token goes on.
+fn anchor_position(
+    source: &str,
+    source_tokens: &[Token],
+    index: usize,
+    placed: &[Placed],
+) -> Option<Anchor> {
+    let token = &source_tokens[index];
+    let find = |text: &str| placed.iter().find(|entry| entry.token == text);
+
+    if let Some(previous) = index.checked_sub(1).map(|i| &source_tokens[i])
+        && source[previous.end..token.start].trim().is_empty()
+        && let Some(entry) = find(&previous.text)
+    {
+        return Some(Anchor {
+            position: entry.position,
+            order: entry.order,
+            // After a placeholder that was itself put before its anchor, stay on that side.
+            side: if entry.side == 0 { 0 } else { 2 },
+        });
+    }
+    if let Some(next) = source_tokens.get(index + 1)
+        && source[token.end..next.start].trim().is_empty()
+        && let Some(entry) = find(&next.text)
+    {
+        return Some(Anchor {
+            position: entry.position,
+            order: entry.order,
+            side: 0,
+        });
+    }
+    None
+}
+
+/// Converts a character estimate into a byte offset on the closest word boundary.
+fn snap_to_boundary(plain: &str, char_estimate: usize) -> usize {
+    let boundaries: Vec<(usize, usize)> = plain
+        .char_indices()
+        .enumerate()
+        .filter(|(_, (byte, ch))| {
+            *byte == 0
+                || ch.is_whitespace()
+                || plain[..*byte]
+                    .chars()
+                    .next_back()
+                    .is_some_and(char::is_whitespace)
+        })
+        .map(|(chars, (byte, _))| (chars, byte))
+        .chain(std::iter::once((plain.chars().count(), plain.len())))
+        .collect();
+
+    boundaries
+        .into_iter()
+        .min_by_key(|(chars, _)| chars.abs_diff(char_estimate))
+        .map(|(_, byte)| byte)
+        .unwrap_or(plain.len())
+}
+
+/// Identifies the pairing key and role of a paired placeholder.
+fn pair_role(token: &str) -> Option<(String, bool)> {
+    let inner = token.strip_prefix("{{")?.strip_suffix("}}")?;
+    let mut parts = inner.split(':');
+    let elem = parts.next()?;
+    let id = parts.next()?;
+    match (elem, parts.next()) {
+        ("pc", Some("start")) => Some((format!("pc:{id}"), true)),
+        ("pc", Some("end")) => Some((format!("pc:{id}"), false)),
+        ("sc", None) => Some((format!("sc:{id}"), true)),
+        ("ec", None) => Some((format!("sc:{id}"), false)),
+        _ => None,
+    }
+}
+
+/// Swaps closing/opening placeholders that appear in the wrong order and flags crossing pairs.
+fn balance_pairs(
+    placed: &mut [Placed],
+    repairs: &mut Vec<TagRepair>,
+    needs_attention: &mut Vec<TagRepairIssue>,
+) {
+    let mut pairs: HashMap<String, (Option<usize>, Option<usize>)> = HashMap::new();
+    for (index, entry) in placed.iter().enumerate() {
+        if let Some((key, is_open)) = pair_role(&entry.token) {
+            let slot = pairs.entry(key).or_default();
+            if is_open {
+                slot.0.get_or_insert(index);
+            } else {
+                slot.1.get_or_insert(index);
+            }
+        }
+    }
+
+    let mut spans = Vec::new();
+    for (key, slot) in pairs {
+        let (Some(open), Some(close)) = slot else {
+            continue;
+        };
+        if close < open {
+            let open_token = placed[open].token.clone();
+            placed[open].token = placed[close].token.clone();
+            placed[close].token = open_token.clone();
+            repairs.push(TagRepair {
+                kind: TagRepairKind::Reordered,
+                placeholder: open_token,
+                confidence: RepairConfidence::High,
+            });
+            spans.push((key, close, open));
+        } else {
+            spans.push((key, open, close));
+        }
+    }
+
+    spans.sort();
+    for (index, (key, open, close)) in spans.iter().enumerate() {
+        for (other_key, other_open, other_close) in &spans[index + 1..] {
+            let crosses = (open < other_open && other_open < close && close < other_close)
+                || (other_open < open && open < other_close && other_close < close);
+            if crosses {
+                needs_attention.push(TagRepairIssue {
+                    placeholder: None,
+                    reason: format!(
+                        "Paired codes '{key}' and '{other_key}' overlap; nesting must be fixed manually."
+                    ),
+                });
+            }
+        }
+    }
+}
+
+fn rebuild(plain: &str, placed: &[Placed]) -> String {
+    let mut text = String::with_capacity(plain.len() + placed.len() * 12);
+    let mut last = 0;
+    for entry in placed {
+        text.push_str(&plain[last..entry.position]);
+        text.push_str(&entry.token);
+        last = entry.position;
+    }
+    text.push_str(&plain[last..]);
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn leaves_valid_targets_untouched() {
+        let report = repair_tags("Hello {{ph:1}} world", "Ciao {{ph:1}} mondo");
+        assert!(!report.changed);
+        assert!(report.repairs.is_empty());
+    }
+
+    #[test]
+    fn removes_unknown_and_duplicate_placeholders() {
+        let report = repair_tags(
+            "Hello {{ph:1}} world",
+            "Ciao {{ph:1}}{{ph:1}} {{ph:9}}mondo",
+        );
+        assert_eq!(report.repaired_text, "Ciao {{ph:1}} mondo");
+        let kinds: Vec<_> = report.repairs.iter().map(|repair| repair.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                TagRepairKind::RemovedDuplicate,
+                TagRepairKind::RemovedUnknown
+            ]
+        );
+    }
+
+    #[test]
+    fn reinserts_missing_placeholder_next_to_anchor() {
+        let report = repair_tags(
+            "Click {{pc:1:start}}{{ph:2}}Save{{pc:1:end}} now",
+            "Fai clic su {{pc:1:start}}Salva{{pc:1:end}} ora",
+        );
+        assert_eq!(
+            report.repaired_text,
+            "Fai clic su {{pc:1:start}}{{ph:2}}Salva{{pc:1:end}} ora"
+        );
+        assert_eq!(report.repairs[0].confidence, RepairConfidence::High);
+        assert!(report.needs_attention.is_empty());
+    }
+
+    #[test]
+    fn reinserts_missing_placeholder_before_the_following_anchor() {
+        let report = repair_tags(
+            "Click {{ph:1}}{{pc:2:start}}Save{{pc:2:end}} now",
+            "Fai clic su {{pc:2:start}}Salva{{pc:2:end}} ora",
+        );
+        assert_eq!(
+            report.repaired_text,
+            "Fai clic su {{ph:1}}{{pc:2:start}}Salva{{pc:2:end}} ora"
+        );
+        assert_eq!(report.repairs[0].confidence, RepairConfidence::High);
+    }
+
+    #[test]
+    fn estimates_position_and_flags_for_review() {
+        let report = repair_tags("one two {{ph:1}} three four", "uno due tre quattro");
+        assert_eq!(report.repaired_text, "uno due {{ph:1}}tre quattro");
+        assert_eq!(report.needs_attention.len(), 1);
+    }
+
+    #[test]
+    fn swaps_reversed_pairs() {
+        let report = repair_tags(
+            "{{pc:1:start}}Bold{{pc:1:end}} text",
+            "{{pc:1:end}}Grassetto{{pc:1:start}} testo",
+        );
+        assert_eq!(
+            report.repaired_text,
+            "{{pc:1:start}}Grassetto{{pc:1:end}} testo"
+        );
+        assert_eq!(report.repairs[0].kind, TagRepairKind::Reordered);
+    }
+}
@@ -0,0 +1,156 @@
+//! Unicode normalization and invisible characters in segment text.
+//!
+//! The same accented letter can be stored precomposed (NFC, `é`) or as a base letter plus
+//! combining mark (NFD, `e` + U+0301). Both render identically, but TM lookups, search and
+//! downstream systems compare code points, so a target mixing the forms matches neither.
+//! Zero-width spaces, word joiners and byte order marks pasted from other tools are
+//! invisible in the editor and break tokenizers and parsers further down the line.
+//! [`find_unicode_issues`] reports both kinds; [`fix_unicode_text`] removes the invisible
+//! characters and normalizes to NFC.
+
+use serde::Serialize;
+use unicode_normalization::{UnicodeNormalization, is_nfc, is_nfd};
+
+/// Short name of an invisible character that should not appear in segment text.
+pub fn invisible_char_name(ch: char) -> Option<&'static str> {
+    Some(match ch {
+        '\u{200B}' => "ZWSP",
+        '\u{2060}' => "WJ",
+        '\u{180E}' => "MVS",
+        '\u{FEFF}' => "BOM",
+        _ => return None,
+    })
+}
+
+/// Normalization form a text is written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NormalizationForm {
+    /// Precomposed; also covers text without any composable characters.
+    Nfc,
+    /// Fully decomposed.
+    Nfd,
+    /// Partly composed and partly decomposed.
+    Mixed,
+}
+
+impl NormalizationForm {
+    pub fn of(text: &str) -> Self {
+        if is_nfc(text) {
+            NormalizationForm::Nfc
+        } else if is_nfd(text) {
+            NormalizationForm::Nfd
+        } else {
+            NormalizationForm::Mixed
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase", tag = "kind")]
+pub enum UnicodeIssue {
+    /// An invisible character at `offset`, counted in characters.
+    InvisibleCharacter {
+        character: &'static str,
+        offset: usize,
+    },
+    /// Text that is not in NFC.
+    NotNormalized { form: NormalizationForm },
+}
+
+impl UnicodeIssue {
+    pub fn describe(&self) -> String {
+        match self {
+            UnicodeIssue::InvisibleCharacter { character, offset } => {
+                format!("{character} at {offset}")
+            }
+            UnicodeIssue::NotNormalized {
+                form: NormalizationForm::Mixed,
+            } => "mixed normalization forms".into(),
+            UnicodeIssue::NotNormalized { .. } => "decomposed (NFD) text".into(),
+        }
+    }
+}
+
+/// Invisible characters of `text`, in text order, followed by a normalization issue when
+/// the text is not in NFC.
+pub fn find_unicode_issues(text: &str) -> Vec<UnicodeIssue> {
+    let mut issues: Vec<UnicodeIssue> = text
+        .chars()
+        .enumerate()
+        .filter_map(|(offset, ch)| {
+            invisible_char_name(ch)
+                .map(|character| UnicodeIssue::InvisibleCharacter { character, offset })
+        })
+        .collect();
+    let form = NormalizationForm::of(text);
+    if form != NormalizationForm::Nfc {
+        issues.push(UnicodeIssue::NotNormalized { form });
+    }
+    issues
+}
+
+/// `text` in NFC.
+pub fn to_nfc(text: &str) -> String {
+    if is_nfc(text) {
+        text.to_string()
+    } else {
+        text.nfc().collect()
+    }
+}
+
+/// `text` without invisible characters, in NFC.
+pub fn fix_unicode_text(text: &str) -> String {
+    let visible: String = text
+        .chars()
+        .filter(|&ch| invisible_char_name(ch).is_none())
+        .collect();
+    to_nfc(&visible)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_normalization_forms() {
+        assert_eq!(NormalizationForm::of("plain"), NormalizationForm::Nfc);
+        assert_eq!(NormalizationForm::of("caf\u{e9}"), NormalizationForm::Nfc);
+        assert_eq!(NormalizationForm::of("cafe\u{301}"), NormalizationForm::Nfd);
+        assert_eq!(
+            NormalizationForm::of("caf\u{e9} cafe\u{301}"),
+            NormalizationForm::Mixed
+        );
+    }
+
+    #[test]
+    fn reports_invisible_characters_and_denormalized_text() {
+        assert!(find_unicode_issues("Save {{ph:1}} caf\u{e9}").is_empty());
+        assert_eq!(
+            find_unicode_issues("\u{feff}Save\u{200b} caf\u{e9} cafe\u{301}"),
+            [
+                UnicodeIssue::InvisibleCharacter {
+                    character: "BOM",
+                    offset: 0
+                },
+                UnicodeIssue::InvisibleCharacter {
+                    character: "ZWSP",
+                    offset: 5
+                },
+                UnicodeIssue::NotNormalized {
+                    form: NormalizationForm::Mixed
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn fixes_text_to_visible_nfc() {
+        assert_eq!(
+            fix_unicode_text("\u{feff}Save\u{200b} cafe\u{301}\u{2060}"),
+            "Save caf\u{e9}"
+        );
+        // Joiners that shape Persian and emoji are kept.
+        assert_eq!(fix_unicode_text("می\u{200c}خواهم"), "می\u{200c}خواهم");
+    }
+}
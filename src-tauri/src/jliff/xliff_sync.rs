@@ -0,0 +1,905 @@
+//! Writing JLIFF data back into the source XLIFF.
+//!
+//! Merging is done by OpenXLIFF from the XLIFF file, so before a merge the data tracked in
+//! JLIFF is copied onto the XLIFF:
+//!
+//! - segment statuses become `<segment state="..." subState="...">` attributes;
+//! - unit notes replace the unit's `<notes>` element (or are inserted before its first
+//!   `<originalData>`/`<segment>`/`<ignorable>` when the unit has none);
+//! - units whose segments were joined or split in the editor get `<segment>` elements
+//!   matching the JLIFF ones, rebuilt from the JLIFF text and the tag map. Segments left
+//!   alone keep their markup, and `<ignorable>` whitespace between joined segments goes.
+//!
+//! The document is streamed event by event; everything else is written back byte-for-byte.
+//! Units without JLIFF notes keep their `<notes>` untouched, so JLIFF files created before
+//! notes were carried over never delete them.
+
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::BufReader;
+use std::path::Path;
+
+use anyhow::{Context, Result, anyhow, bail};
+use quick_xml::encoding::Decoder;
+use quick_xml::events::{BytesEnd, BytesStart, BytesText, Event};
+use quick_xml::{Reader, Writer};
+
+use super::converter::notes::note_from_start;
+use super::converter::xml_reader::{decode_cdata, decode_general_ref, decode_text};
+use super::model::{JliffDocument, SegmentStatus, TransUnit, XliffNote};
+use super::protected_terms::PROTECTED_TERM_ELEM;
+use super::restructure::{placeholder_spans, segment_id_of};
+use super::tag_map::{TagMapDoc, TagMapSegment};
+
+/// Changes applied by [`sync_xliff_from_jliff`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct XliffSyncSummary {
+    /// Segments whose `state`/`subState` were rewritten
+    pub updated_segments: usize,
+    /// Units whose `<notes>` were rewritten or inserted
+    pub updated_units: usize,
+    /// Units whose segments were rebuilt after being joined or split
+    pub restructured_units: usize,
+}
+
+/// Applies the statuses and notes of `document` to the XLIFF at `xliff_path`.
+///
+/// Only the `<file>` whose `original` matches the JLIFF `File` is touched; segments
+/// without a JLIFF status keep their current attributes. The file is only rewritten when
+/// something changed. Rebuilding joined or split segments needs the document's tag map.
+pub fn sync_xliff_from_jliff(
+    xliff_path: &Path,
+    document: &JliffDocument,
+    tag_map: Option<&TagMapDoc>,
+) -> Result<XliffSyncSummary> {
+    let states: HashMap<&str, (SegmentStatus, Option<&str>)> = document
+        .transunits
+        .iter()
+        .filter_map(|unit| {
+            unit.status.map(|status| {
+                (
+                    unit.transunit_id.as_str(),
+                    (status, unit.sub_state.as_deref()),
+                )
+            })
+        })
+        .collect();
+    // Every segment of a unit carries the unit's notes; the first one wins.
+    let mut unit_notes: HashMap<&str, &[XliffNote]> = HashMap::new();
+    for unit in document
+        .transunits
+        .iter()
+        .filter(|unit| !unit.notes.is_empty())
+    {
+        unit_notes
+            .entry(unit.unit_id.as_str())
+            .or_insert(unit.notes.as_slice());
+    }
+
+    let layouts = restructured_units(xliff_path, document)?;
+    if !layouts.is_empty() && tag_map.is_none() {
+        bail!("Joined or split segments cannot be written without the tag map");
+    }
+
+    let file = File::open(xliff_path)
+        .with_context(|| format!("Failed to open {}", xliff_path.display()))?;
+    let mut reader = Reader::from_reader(BufReader::new(file));
+    reader.config_mut().trim_text(false);
+    let decoder = reader.decoder();
+    let mut writer = Writer::new(Vec::new());
+    let mut buf = Vec::new();
+
+    let mut in_target_file = false;
+    let mut depth = 0usize;
+    let mut unit: Option<OpenUnit> = None;
+    let mut summary = XliffSyncSummary::default();
+
+    loop {
+        buf.clear();
+        let event = reader
+            .read_event_into(&mut buf)
+            .with_context(|| format!("Failed to parse {}", xliff_path.display()))?;
+        match event {
+            Event::Eof => break,
+            Event::Start(ref start) | Event::Empty(ref start) => {
+                let is_start = matches!(event, Event::Start(_));
+                let local_name = start.local_name();
+
+                // Direct children of a unit whose notes still need to be written.
+                if let Some(open) = unit.as_mut().filter(|open| open.is_pending_child(depth)) {
+                    match local_name.as_ref() {
+                        b"notes" => {
+                            let events = if is_start {
+                                read_subtree(&mut reader, start.to_owned())?
+                            } else {
+                                vec![Event::Empty(start.to_owned())]
+                            };
+                            if parse_notes(&events, decoder)? == open.notes {
+                                for event in events {
+                                    writer.write_event(event)?;
+                                }
+                            } else {
+                                write_notes(&mut writer, open.prefix.as_deref(), open.notes)?;
+                                summary.updated_units += 1;
+                            }
+                            open.written = true;
+                            continue;
+                        }
+                        b"originalData" | b"segment" | b"ignorable" => {
+                            write_notes(&mut writer, open.prefix.as_deref(), open.notes)?;
+                            summary.updated_units += 1;
+                            open.written = true;
+                        }
+                        _ => {}
+                    }
+                }
+
+                // Children of a unit whose segments were joined or split.
+                if let Some(open) = unit
+                    .as_mut()
+                    .filter(|open| open.layout.is_some() && depth == open.depth)
+                {
+                    match local_name.as_ref() {
+                        b"ignorable" => {
+                            let events = subtree(&mut reader, start, is_start)?;
+                            if let Some(previous) = open.pending_ignorable.replace(events) {
+                                write_events(&mut writer, previous)?;
+                            }
+                            continue;
+                        }
+                        b"segment" => {
+                            let segment_id =
+                                attribute(start, b"id", decoder)?.unwrap_or_else(|| "0".into());
+                            let events = subtree(&mut reader, start, is_start)?;
+                            rebuild_segment(&mut writer, open, events, &segment_id, tag_map)?;
+                            continue;
+                        }
+                        _ => {
+                            if let Some(previous) = open.pending_ignorable.take() {
+                                write_events(&mut writer, previous)?;
+                            }
+                        }
+                    }
+                }
+
+                let mut replacement = None;
+                match local_name.as_ref() {
+                    b"file" => {
+                        let original = attribute(start, b"original", decoder)?;
+                        in_target_file = original.unwrap_or_default() == document.file;
+                    }
+                    b"unit" if in_target_file && is_start => {
+                        let id = attribute(start, b"id", decoder)?.unwrap_or_default();
+                        let notes = unit_notes.get(id.as_str()).copied().unwrap_or_default();
+                        let layout = layouts.get(id.as_str());
+                        if layout.is_some() {
+                            summary.restructured_units += 1;
+                        }
+                        unit = Some(OpenUnit {
+                            depth: depth + 1,
+                            prefix: start.name().prefix().map(|prefix| {
+                                String::from_utf8_lossy(prefix.as_ref()).into_owned()
+                            }),
+                            id,
+                            notes,
+                            written: notes.is_empty(),
+                            layout,
+                            pending_ignorable: None,
+                        });
+                    }
+                    b"segment" if in_target_file => {
+                        let segment_id =
+                            attribute(start, b"id", decoder)?.unwrap_or_else(|| "0".into());
+                        let state = unit.as_ref().and_then(|open| {
+                            states.get(format!("u{}-s{segment_id}", open.id).as_str())
+                        });
+                        if let Some((status, sub_state)) = state {
+                            replacement = Some(with_state(start, *status, *sub_state)?);
+                        }
+                    }
+                    _ => {}
+                }
+                if is_start {
+                    depth += 1;
+                }
+                match replacement {
+                    Some(element) if !is_start => {
+                        writer.write_event(Event::Empty(element))?;
+                        summary.updated_segments += 1;
+                    }
+                    Some(element) => {
+                        writer.write_event(Event::Start(element))?;
+                        summary.updated_segments += 1;
+                    }
+                    None => writer.write_event(event.borrow())?,
+                }
+            }
+            Event::End(ref end) => {
+                depth = depth.saturating_sub(1);
+                match end.local_name().as_ref() {
+                    b"file" => in_target_file = false,
+                    b"unit" => {
+                        if let Some(previous) =
+                            unit.as_mut().and_then(|open| open.pending_ignorable.take())
+                        {
+                            write_events(&mut writer, previous)?;
+                        }
+                        // A unit without segments still gets its notes.
+                        if let Some(open) = unit.take().filter(|open| !open.written) {
+                            write_notes(&mut writer, open.prefix.as_deref(), open.notes)?;
+                            summary.updated_units += 1;
+                        }
+                    }
+                    _ => {}
+                }
+                writer.write_event(event.borrow())?;
+            }
+            other => writer.write_event(other)?,
+        }
+    }
+
+    if summary != XliffSyncSummary::default() {
+        let tmp_path = xliff_path.with_extension("xlf.tmp");
+        fs::write(&tmp_path, writer.into_inner())
+            .with_context(|| format!("Failed to write {}", tmp_path.display()))?;
+        fs::rename(&tmp_path, xliff_path)
+            .with_context(|| format!("Failed to replace {}", xliff_path.display()))?;
+    }
+    Ok(summary)
+}
+
+/// The `<unit>` currently being streamed.
+struct OpenUnit<'a> {
+    /// Element depth of the unit's children
+    depth: usize,
+    /// Namespace prefix of the `<unit>` element, reused for inserted elements
+    prefix: Option<String>,
+    id: String,
+    notes: &'a [XliffNote],
+    /// Whether the unit's notes are already in the output (or there is nothing to write)
+    written: bool,
+    /// Segment structure to write when the unit's segments were joined or split
+    layout: Option<&'a UnitLayout<'a>>,
+    /// `<ignorable>` held back until it is known whether the next segment survives
+    pending_ignorable: Option<Vec<Event<'static>>>,
+}
+
+/// Segments of a unit as they are in the JLIFF, for a unit whose XLIFF differs.
+struct UnitLayout<'a> {
+    /// Segment ids and transunits in document order
+    segments: Vec<(&'a str, &'a TransUnit)>,
+    /// Segment ids the XLIFF unit currently has
+    existing: HashSet<String>,
+    /// Segment ids taking part in a recorded join or split, whose content is rebuilt
+    touched: HashSet<&'a str>,
+}
+
+/// Layouts of the units with recorded joins or splits whose XLIFF segments do not match the
+/// JLIFF ones yet.
+fn restructured_units<'a>(
+    xliff_path: &Path,
+    document: &'a JliffDocument,
+) -> Result<HashMap<&'a str, UnitLayout<'a>>> {
+    if document.segment_operations.is_empty() {
+        return Ok(HashMap::new());
+    }
+    let mut xliff_segments = segment_ids(xliff_path, &document.file)?;
+    let mut layouts = HashMap::new();
+    for operation in &document.segment_operations {
+        let unit_id = operation.unit_id.as_str();
+        if layouts.contains_key(unit_id) {
+            continue;
+        }
+        let Some(existing) = xliff_segments.remove(unit_id) else {
+            continue;
+        };
+        let segments: Vec<(&str, &TransUnit)> = document
+            .transunits
+            .iter()
+            .filter(|unit| unit.unit_id == unit_id)
+            .filter_map(|unit| segment_id_of(unit).map(|id| (id, unit)))
+            .collect();
+        if segments
+            .iter()
+            .map(|(id, _)| *id)
+            .eq(existing.iter().map(String::as_str))
+        {
+            continue;
+        }
+        let touched = document
+            .segment_operations
+            .iter()
+            .filter(|operation| operation.unit_id == unit_id)
+            .flat_map(|operation| &operation.transunit_ids)
+            .filter_map(|transunit_id| {
+                segments
+                    .iter()
+                    .find(|(_, unit)| &unit.transunit_id == transunit_id)
+                    .map(|(id, _)| *id)
+            })
+            .collect();
+        layouts.insert(
+            unit_id,
+            UnitLayout {
+                segments,
+                existing: existing.into_iter().collect(),
+                touched,
+            },
+        );
+    }
+    Ok(layouts)
+}
+
+/// Segment ids per unit of the `<file>` whose `original` is `file`, in document order.
+fn segment_ids(xliff_path: &Path, file: &str) -> Result<HashMap<String, Vec<String>>> {
+    let source = File::open(xliff_path)
+        .with_context(|| format!("Failed to open {}", xliff_path.display()))?;
+    let mut reader = Reader::from_reader(BufReader::new(source));
+    let decoder = reader.decoder();
+    let mut buf = Vec::new();
+    let mut in_target_file = false;
+    let mut unit_id = None;
+    let mut segments: HashMap<String, Vec<String>> = HashMap::new();
+    loop {
+        buf.clear();
+        match reader
+            .read_event_into(&mut buf)
+            .with_context(|| format!("Failed to parse {}", xliff_path.display()))?
+        {
+            Event::Eof => break,
+            Event::Start(ref start) | Event::Empty(ref start) => {
+                match start.local_name().as_ref() {
+                    b"file" => {
+                        in_target_file =
+                            attribute(start, b"original", decoder)?.unwrap_or_default() == file;
+                    }
+                    b"unit" if in_target_file => {
+                        unit_id = attribute(start, b"id", decoder)?;
+                    }
+                    b"segment" if in_target_file => {
+                        if let Some(unit_id) = unit_id.clone() {
+                            let id =
+                                attribute(start, b"id", decoder)?.unwrap_or_else(|| "0".into());
+                            segments.entry(unit_id).or_default().push(id);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Event::End(ref end) if end.local_name().as_ref() == b"file" => in_target_file = false,
+            _ => {}
+        }
+    }
+    Ok(segments)
+}
+
+/// Writes the segments replacing `<segment id="{segment_id}">` in a restructured unit: the
+/// segment itself unless it was joined into the previous one, followed by the segments split
+/// off it.
+fn rebuild_segment(
+    writer: &mut Writer<Vec<u8>>,
+    open: &mut OpenUnit<'_>,
+    mut events: Vec<Event<'static>>,
+    segment_id: &str,
+    tag_map: Option<&TagMapDoc>,
+) -> Result<()> {
+    let layout = open.layout.expect("only restructured units are rebuilt");
+    let Some(position) = layout.segments.iter().position(|(id, _)| *id == segment_id) else {
+        // Joined into the previous segment, together with the whitespace in between.
+        open.pending_ignorable = None;
+        return Ok(());
+    };
+    if let Some(previous) = open.pending_ignorable.take() {
+        write_events(writer, previous)?;
+    }
+    let prefix = open.prefix.as_deref();
+    let (_, unit) = layout.segments[position];
+    let tag_segment = |unit: &TransUnit| {
+        tag_map.and_then(|map| map.segment_for(&unit.unit_id, &unit.transunit_id))
+    };
+    if layout.touched.contains(segment_id) {
+        let (Event::Start(start) | Event::Empty(start)) = &events[0] else {
+            bail!("Segment {segment_id} does not start with its element");
+        };
+        write_segment(
+            writer,
+            prefix,
+            Some(start),
+            segment_id,
+            unit,
+            tag_segment(unit),
+        )?;
+    } else {
+        if let Some(status) = unit.status {
+            events[0] = match &events[0] {
+                Event::Start(start) => {
+                    Event::Start(with_state(start, status, unit.sub_state.as_deref())?)
+                }
+                Event::Empty(start) => {
+                    Event::Empty(with_state(start, status, unit.sub_state.as_deref())?)
+                }
+                other => other.clone(),
+            };
+        }
+        write_events(writer, events)?;
+    }
+    for (id, unit) in layout.segments[position + 1..]
+        .iter()
+        .take_while(|(id, _)| !layout.existing.contains(*id))
+    {
+        write_segment(writer, prefix, None, id, unit, tag_segment(unit))?;
+    }
+    Ok(())
+}
+
+/// Writes a `<segment>` with its source and target rebuilt from the JLIFF text, keeping the
+/// attributes of `original` other than the id and the state.
+fn write_segment(
+    writer: &mut Writer<Vec<u8>>,
+    prefix: Option<&str>,
+    original: Option<&BytesStart<'_>>,
+    segment_id: &str,
+    unit: &TransUnit,
+    tag_segment: Option<&TagMapSegment>,
+) -> Result<()> {
+    let qualify = |local: &str| match prefix {
+        Some(prefix) => format!("{prefix}:{local}"),
+        None => local.to_string(),
+    };
+    let segment_name = qualify("segment");
+    let mut element = BytesStart::new(segment_name.as_str());
+    element.push_attribute(("id", segment_id));
+    if let Some(original) = original {
+        for attr in original.attributes().with_checks(false) {
+            let attr = attr.map_err(|err| anyhow!(err))?;
+            if !matches!(attr.key.as_ref(), b"id" | b"state" | b"subState") {
+                element.push_attribute(attr);
+            }
+        }
+    }
+    let element = match unit.status {
+        Some(status) => with_state(&element, status, unit.sub_state.as_deref())?,
+        None => element,
+    };
+    writer.write_event(Event::Start(element))?;
+    let mut containers = vec![("source", unit.source.as_str())];
+    let target = unit.effective_target();
+    if !target.is_empty() {
+        containers.push(("target", target));
+    }
+    for (local, text) in containers {
+        let name = qualify(local);
+        writer.write_event(Event::Start(BytesStart::new(name.as_str())))?;
+        write_inline(writer, &qualify, text, tag_segment)?;
+        writer.write_event(Event::End(BytesEnd::new(name.as_str())))?;
+    }
+    writer.write_event(Event::End(BytesEnd::new(segment_name.as_str())))?;
+    Ok(())
+}
+
+/// Writes JLIFF text as XLIFF inline content, turning placeholders back into the codes the
+/// tag map recorded for them. Unknown placeholders stay as text.
+fn write_inline(
+    writer: &mut Writer<Vec<u8>>,
+    qualify: &dyn Fn(&str) -> String,
+    text: &str,
+    tag_segment: Option<&TagMapSegment>,
+) -> Result<()> {
+    let mut cursor = 0;
+    for (start, end) in placeholder_spans(text) {
+        let token = &text[start..end];
+        let Some(instance) = tag_segment.and_then(|segment| segment.instance(token)) else {
+            continue;
+        };
+        write_text(writer, &text[cursor..start])?;
+        cursor = end;
+        if instance.elem == PROTECTED_TERM_ELEM {
+            write_text(writer, instance.original_data.as_deref().unwrap_or(token))?;
+            continue;
+        }
+        let name = qualify(&instance.elem);
+        if instance.elem == "pc" && token.ends_with(":end}}") {
+            writer.write_event(Event::End(BytesEnd::new(name.as_str())))?;
+            continue;
+        }
+        let mut element = BytesStart::new(name.as_str());
+        for (key, value) in &instance.attrs {
+            element.push_attribute((key.as_str(), value.as_deref().unwrap_or_default()));
+        }
+        if instance.elem == "pc" {
+            writer.write_event(Event::Start(element))?;
+        } else {
+            writer.write_event(Event::Empty(element))?;
+        }
+    }
+    write_text(writer, &text[cursor..])
+}
+
+fn write_text(writer: &mut Writer<Vec<u8>>, text: &str) -> Result<()> {
+    if !text.is_empty() {
+        writer.write_event(Event::Text(BytesText::new(text)))?;
+    }
+    Ok(())
+}
+
+fn write_events(writer: &mut Writer<Vec<u8>>, events: Vec<Event<'static>>) -> Result<()> {
+    for event in events {
+        writer.write_event(event)?;
+    }
+    Ok(())
+}
+
+/// The events of the element opened by `start`: its whole subtree, or the empty element.
+fn subtree(
+    reader: &mut Reader<BufReader<File>>,
+    start: &BytesStart<'_>,
+    is_start: bool,
+) -> Result<Vec<Event<'static>>> {
+    if is_start {
+        read_subtree(reader, start.to_owned())
+    } else {
+        Ok(vec![Event::Empty(start.to_owned())])
+    }
+}
+
+impl OpenUnit<'_> {
+    fn is_pending_child(&self, depth: usize) -> bool {
+        !self.written && depth == self.depth
+    }
+}
+
+/// Reads the rest of the element opened by `start`, including its end tag.
+fn read_subtree(
+    reader: &mut Reader<BufReader<File>>,
+    start: BytesStart<'static>,
+) -> Result<Vec<Event<'static>>> {
+    let name = start.local_name().as_ref().to_vec();
+    let mut buf = Vec::new();
+    let mut events = vec![Event::Start(start)];
+    let mut depth = 1usize;
+    while depth > 0 {
+        let event = reader.read_event_into(&mut buf)?.into_owned();
+        match event {
+            Event::Start(_) => depth += 1,
+            Event::End(_) => depth -= 1,
+            Event::Eof => bail!("Unexpected EOF inside <{}>", String::from_utf8_lossy(&name)),
+            _ => {}
+        }
+        events.push(event);
+        buf.clear();
+    }
+    Ok(events)
+}
+
+/// Extracts notes from the events of a `<notes>` element the same way the converter does.
+fn parse_notes(events: &[Event<'static>], decoder: Decoder) -> Result<Vec<XliffNote>> {
+    let mut notes = Vec::new();
+    let mut current: Option<XliffNote> = None;
+    // Depth relative to the current <note>; nested markup contributes no text.
+    let mut nested = 0usize;
+
+    for event in events {
+        match event {
+            Event::Start(_) if current.is_some() => nested += 1,
+            Event::Start(start) if start.local_name().as_ref() == b"note" => {
+                current = Some(note_from_start(start, decoder)?);
+            }
+            Event::Empty(empty) if current.is_none() && empty.local_name().as_ref() == b"note" => {
+                notes.push(note_from_start(empty, decoder)?);
+            }
+            Event::End(_) if nested > 0 => nested -= 1,
+            Event::End(_) => {
+                if let Some(note) = current.take() {
+                    notes.push(note);
+                }
+            }
+            Event::Text(text) if nested == 0 => {
+                if let Some(note) = current.as_mut() {
+                    note.text.push_str(&decode_text(text)?);
+                }
+            }
+            Event::CData(cdata) if nested == 0 => {
+                if let Some(note) = current.as_mut() {
+                    note.text.push_str(&decode_cdata(cdata, decoder)?);
+                }
+            }
+            Event::GeneralRef(reference) if nested == 0 => {
+                if let Some(note) = current.as_mut() {
+                    note.text.push_str(&decode_general_ref(reference, decoder)?);
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(notes)
+}
+
+/// Writes `notes` as a `<notes>` element, prefixed like the enclosing unit.
+fn write_notes(
+    writer: &mut Writer<Vec<u8>>,
+    prefix: Option<&str>,
+    notes: &[XliffNote],
+) -> Result<()> {
+    let qualify = |local: &str| match prefix {
+        Some(prefix) => format!("{prefix}:{local}"),
+        None => local.to_string(),
+    };
+    let notes_name = qualify("notes");
+    let note_name = qualify("note");
+
+    writer.write_event(Event::Start(BytesStart::new(notes_name.as_str())))?;
+    for note in notes {
+        let mut element = BytesStart::new(note_name.as_str());
+        if let Some(id) = note.id.as_deref() {
+            element.push_attribute(("id", id));
+        }
+        if let Some(category) = note.category.as_deref() {
+            element.push_attribute(("category", category));
+        }
+        if let Some(priority) = note.priority {
+            element.push_attribute(("priority", priority.to_string().as_str()));
+        }
+        if let Some(applies_to) = note.applies_to.as_deref() {
+            element.push_attribute(("appliesTo", applies_to));
+        }
+        writer.write_event(Event::Start(element))?;
+        writer.write_event(Event::Text(BytesText::new(&note.text)))?;
+        writer.write_event(Event::End(BytesEnd::new(note_name.as_str())))?;
+    }
+    writer.write_event(Event::End(BytesEnd::new(notes_name.as_str())))?;
+    Ok(())
+}
+
+/// Copies `start` with `state`/`subState` replaced. Other attributes keep their raw value.
+fn with_state(
+    start: &BytesStart<'_>,
+    status: SegmentStatus,
+    sub_state: Option<&str>,
+) -> Result<BytesStart<'static>> {
+    let mut element = start.to_owned();
+    element.clear_attributes();
+    for attr in start.attributes().with_checks(false) {
+        let attr = attr.map_err(|err| anyhow!(err))?;
+        if matches!(attr.key.as_ref(), b"state" | b"subState") {
+            continue;
+        }
+        element.push_attribute(attr);
+    }
+    element.push_attribute(("state", status.xliff_state()));
+    if let Some(sub_state) = sub_state {
+        element.push_attribute(("subState", sub_state));
+    }
+    Ok(element)
+}
+
+fn attribute(start: &BytesStart<'_>, name: &[u8], decoder: Decoder) -> Result<Option<String>> {
+    for attr in start.attributes().with_checks(false) {
+        let attr = attr.map_err(|err| anyhow!(err))?;
+        if attr.key.as_ref() == name {
+            let value = attr
+                .decode_and_unescape_value(decoder)
+                .map_err(|err| anyhow!(err))?;
+            return Ok(Some(value.into_owned()));
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::jliff::restructure::{merge_segments, split_segment};
+    use crate::jliff::tag_map::{TagInstance, TagMapUnit};
+
+    fn unit(transunit_id: &str, status: Option<SegmentStatus>) -> TransUnit {
+        TransUnit {
+            unit_id: "1".into(),
+            transunit_id: transunit_id.into(),
+            source: "Hello".into(),
+            target_translation: "Ciao".into(),
+            target_qa_1: None,
+            target_qa_2: None,
+            target_postedit: None,
+            translation_notes: None,
+            qa_notes: None,
+            source_notes: None,
+            metadata: None,
+            status,
+            sub_state: status.map(|_| "acme:checked".into()),
+            notes: Vec::new(),
+            propagated_from: None,
+        }
+    }
+
+    fn document(transunits: Vec<TransUnit>) -> JliffDocument {
+        JliffDocument {
+            project_name: "P".into(),
+            project_id: "p".into(),
+            file: "a.docx".into(),
+            user: "u".into(),
+            source_language: "en".into(),
+            target_language: "it".into(),
+            transunits,
+            segment_operations: Vec::new(),
+        }
+    }
+
+    fn note(id: &str, text: &str) -> XliffNote {
+        XliffNote {
+            id: Some(id.into()),
+            category: None,
+            priority: None,
+            applies_to: None,
+            text: text.into(),
+        }
+    }
+
+    #[test]
+    fn rewrites_state_attributes_of_matching_segments() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("doc.xlf");
+        fs::write(
+            &path,
+            r#"<xliff version="2.0"><file id="f" original="a.docx"><unit id="1"><segment id="1" state="initial" subState="old:x"><source>Hello &amp; bye</source></segment><segment id="2" state="initial"/></unit></file></xliff>"#,
+        )?;
+        let document = document(vec![
+            unit("u1-s1", Some(SegmentStatus::Approved)),
+            unit("u1-s2", None),
+        ]);
+
+        let summary = sync_xliff_from_jliff(&path, &document, None)?;
+        assert_eq!(summary.updated_segments, 1);
+        assert_eq!(summary.updated_units, 0);
+        let written = fs::read_to_string(&path)?;
+        assert!(written.contains(
+            r#"<segment id="1" state="final" subState="acme:checked"><source>Hello &amp; bye</source>"#
+        ));
+        assert!(written.contains(r#"<segment id="2" state="initial"/>"#));
+        Ok(())
+    }
+
+    #[test]
+    fn writes_changed_notes_and_keeps_unchanged_ones() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("doc.xlf");
+        fs::write(
+            &path,
+            r#"<xliff version="2.0"><file id="f" original="a.docx"><unit id="1"><notes><note id="n1">Fish &amp; chips</note></notes><segment id="1"><source>A</source></segment></unit><unit id="2"><notes><note>old</note></notes><segment id="1"><source>B</source></segment></unit><unit id="3"><segment id="1"><source>C</source></segment></unit></file></xliff>"#,
+        )?;
+        let mut first = unit("u1-s1", None);
+        first.notes = vec![note("n1", "Fish & chips")];
+        let mut second = unit("u2-s1", None);
+        second.unit_id = "2".into();
+        second.notes = vec![note("n2", "new <text>")];
+        let mut third = unit("u3-s1", None);
+        third.unit_id = "3".into();
+        third.notes = vec![note("n3", "Added")];
+
+        let summary = sync_xliff_from_jliff(&path, &document(vec![first, second, third]), None)?;
+        assert_eq!(summary.updated_units, 2);
+        let written = fs::read_to_string(&path)?;
+        assert!(written.contains(r#"<notes><note id="n1">Fish &amp; chips</note></notes>"#));
+        assert!(written.contains(r#"<notes><note id="n2">new &lt;text&gt;</note></notes>"#));
+        assert!(
+            written.contains(
+                r#"<unit id="3"><notes><note id="n3">Added</note></notes><segment id="1">"#
+            )
+        );
+        assert!(!written.contains("old"));
+        Ok(())
+    }
+
+    #[test]
+    fn rebuilds_joined_and_split_segments() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("doc.xlf");
+        fs::write(
+            &path,
+            r#"<xliff version="2.0"><file id="f" original="a.docx"><unit id="1"><segment id="1"><source>Save <ph id="1"/>now. Then <pc id="2">close</pc>.</source></segment><ignorable><source> </source></ignorable><segment id="2"><source>Done.</source><target>Fertig.</target></segment></unit></file></xliff>"#,
+        )?;
+        let mut first = unit("u1-s1", None);
+        first.source = "Save {{ph:1}}now. Then {{pc:2:start}}close{{pc:2:end}}.".into();
+        first.target_translation.clear();
+        let mut second = unit("u1-s2", None);
+        second.source = "Done.".into();
+        second.target_translation = "Fertig.".into();
+        let mut document = document(vec![first, second]);
+        let code = |placeholder: &str, elem: &str, id: &str| TagInstance {
+            placeholder: placeholder.into(),
+            elem: elem.into(),
+            id: Some(id.into()),
+            attrs: BTreeMap::from([("id".to_string(), Some(id.to_string()))]),
+            original_data: None,
+        };
+        let segment = |segment_id: &str, placeholders| TagMapSegment {
+            segment_id: segment_id.into(),
+            placeholders,
+            original_data_bucket: BTreeMap::new(),
+        };
+        let mut tag_map = TagMapDoc {
+            version: 1,
+            file_id: "f".into(),
+            original_path: "a.docx".into(),
+            source_language: "en".into(),
+            target_language: "it".into(),
+            placeholder_style: "double_curly".into(),
+            units: vec![TagMapUnit {
+                unit_id: "1".into(),
+                segments: vec![
+                    segment(
+                        "1",
+                        vec![
+                            code("{{ph:1}}", "ph", "1"),
+                            code("{{pc:2:start}}", "pc", "2"),
+                            code("{{pc:2:end}}", "pc", "2"),
+                        ],
+                    ),
+                    segment("2", Vec::new()),
+                ],
+            }],
+        };
+        let tail = split_segment(&mut document, &mut tag_map, "u1-s1", 18)?;
+        merge_segments(&mut document, &mut tag_map, &[tail, "u1-s2".into()])?;
+
+        let summary = sync_xliff_from_jliff(&path, &document, Some(&tag_map))?;
+        assert_eq!(summary.restructured_units, 1);
+        let written = fs::read_to_string(&path)?;
+        assert!(written.contains(
+            r#"<unit id="1"><segment id="1"><source>Save <ph id="1"/>now. </source></segment><segment id="1-2"><source>Then <pc id="2">close</pc>. Done.</source><target>Fertig.</target></segment></unit>"#
+        ));
+        assert_eq!(
+            sync_xliff_from_jliff(&path, &document, Some(&tag_map))?,
+            XliffSyncSummary::default()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn rebuilt_segments_carry_the_effective_target() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("doc.xlf");
+        fs::write(
+            &path,
+            r#"<xliff version="2.0"><file id="f" original="a.docx"><unit id="1"><segment id="1"><source>Save now.</source><target>Speichern.</target></segment><segment id="2"><source>Done.</source><target>Fertig.</target></segment></unit></file></xliff>"#,
+        )?;
+        let mut first = unit("u1-s1", None);
+        first.source = "Save now.".into();
+        first.target_translation = "Speichern.".into();
+        let mut second = unit("u1-s2", None);
+        second.source = "Done.".into();
+        second.target_translation = "Fertig.".into();
+        let mut document = document(vec![first, second]);
+        let segment = |segment_id: &str| TagMapSegment {
+            segment_id: segment_id.into(),
+            placeholders: Vec::new(),
+            original_data_bucket: BTreeMap::new(),
+        };
+        let mut tag_map = TagMapDoc {
+            version: 1,
+            file_id: "f".into(),
+            original_path: "a.docx".into(),
+            source_language: "en".into(),
+            target_language: "de".into(),
+            placeholder_style: "double_curly".into(),
+            units: vec![TagMapUnit {
+                unit_id: "1".into(),
+                segments: vec![segment("1"), segment("2")],
+            }],
+        };
+        merge_segments(
+            &mut document,
+            &mut tag_map,
+            &["u1-s1".into(), "u1-s2".into()],
+        )?;
+        document.transunits[0].target_postedit = Some("Jetzt speichern. Fertig.".into());
+
+        let summary = sync_xliff_from_jliff(&path, &document, Some(&tag_map))?;
+        assert_eq!(summary.restructured_units, 1);
+        let written = fs::read_to_string(&path)?;
+        assert!(written.contains("<target>Jetzt speichern. Fertig.</target>"));
+        assert!(!written.contains("<target>Speichern."));
+        Ok(())
+    }
+}
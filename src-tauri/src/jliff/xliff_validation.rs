@@ -0,0 +1,997 @@
+//! XLIFF validation with line and column diagnostics.
+//!
+//! A document is checked in three passes over one stream of events:
+//!
+//! 1. **Well-formedness**: the XML parses, every element is closed, entities are defined and
+//!    namespace prefixes are declared. Validation stops at the first such error.
+//! 2. **Schema**: the structure the XLIFF 1.2, 2.0 and 2.1 core schemas prescribe, bundled as
+//!    rule tables: where each core element may appear, its required attributes, the values of
+//!    enumerated attributes, and the uniqueness of unit and segment ids. Elements of other
+//!    namespaces are extensions and are not checked.
+//! 3. **Lint**: problems the schemas allow but CAT tools trip over, reported as warnings:
+//!    targets marked translated while empty, targets in another language than their file,
+//!    and Trados segments missing from the `sdl:seg-defs` of their unit.
+
+use std::collections::HashSet;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use quick_xml::encoding::Decoder;
+use quick_xml::escape::resolve_predefined_entity;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::name::ResolveResult;
+use quick_xml::reader::NsReader;
+use serde::{Deserialize, Serialize};
+
+const XLIFF_1_NAMESPACE: &[u8] = b"urn:oasis:names:tc:xliff:document:1.2";
+/// XLIFF 2.1 keeps the core namespace of 2.0.
+const XLIFF_2_NAMESPACE: &[u8] = b"urn:oasis:names:tc:xliff:document:2.0";
+const SDL_NAMESPACE: &[u8] = b"http://sdl.com/FileTypes/SdlXliff/1.0";
+const MEMOQ_NAMESPACE: &str = "MQXliff";
+
+/// Marks an element allowed only as the document root.
+const ROOT: &str = "";
+
+/// A core element: the elements it may appear in and the attributes it needs.
+struct ElementRule {
+    name: &'static str,
+    parents: &'static [&'static str],
+    required: &'static [&'static str],
+}
+
+const fn rule(
+    name: &'static str,
+    parents: &'static [&'static str],
+    required: &'static [&'static str],
+) -> ElementRule {
+    ElementRule {
+        name,
+        parents,
+        required,
+    }
+}
+
+const XLIFF_1_INLINE_PARENTS: &[&str] = &["source", "seg-source", "target", "g", "mrk"];
+const XLIFF_1_CODE_PARENTS: &[&str] = &["source", "seg-source", "target", "g", "mrk", "sub"];
+
+const XLIFF_1_RULES: &[ElementRule] = &[
+    rule("xliff", &[ROOT], &["version"]),
+    rule(
+        "file",
+        &["xliff"],
+        &["original", "source-language", "datatype"],
+    ),
+    rule("header", &["file"], &[]),
+    rule("skl", &["header"], &[]),
+    rule("internal-file", &["skl", "glossary", "reference"], &[]),
+    rule(
+        "external-file",
+        &["skl", "glossary", "reference"],
+        &["href"],
+    ),
+    rule("glossary", &["header"], &[]),
+    rule("reference", &["header"], &[]),
+    rule("phase-group", &["header"], &[]),
+    rule("phase", &["phase-group"], &["phase-name", "process-name"]),
+    rule("tool", &["header"], &["tool-id", "tool-name"]),
+    rule(
+        "note",
+        &[
+            "header",
+            "phase",
+            "group",
+            "trans-unit",
+            "alt-trans",
+            "bin-unit",
+        ],
+        &[],
+    ),
+    rule(
+        "count-group",
+        &["header", "group", "trans-unit", "bin-unit"],
+        &["name"],
+    ),
+    rule("count", &["count-group"], &["count-type"]),
+    rule(
+        "context-group",
+        &["group", "trans-unit", "alt-trans", "bin-unit"],
+        &[],
+    ),
+    rule("context", &["context-group"], &["context-type"]),
+    rule(
+        "prop-group",
+        &["header", "group", "trans-unit", "alt-trans", "bin-unit"],
+        &[],
+    ),
+    rule("prop", &["prop-group"], &["prop-type"]),
+    rule("body", &["file"], &[]),
+    rule("group", &["body", "group"], &[]),
+    rule("trans-unit", &["body", "group"], &["id"]),
+    rule("source", &["trans-unit", "alt-trans"], &[]),
+    rule("seg-source", &["trans-unit", "alt-trans"], &[]),
+    rule("target", &["trans-unit", "alt-trans"], &[]),
+    rule("alt-trans", &["trans-unit"], &[]),
+    rule("bin-unit", &["body", "group"], &["id", "mime-type"]),
+    rule("bin-source", &["bin-unit"], &[]),
+    rule("bin-target", &["bin-unit"], &[]),
+    rule("g", XLIFF_1_INLINE_PARENTS, &["id"]),
+    rule("mrk", XLIFF_1_INLINE_PARENTS, &["mtype"]),
+    rule("x", XLIFF_1_CODE_PARENTS, &["id"]),
+    rule("bx", XLIFF_1_CODE_PARENTS, &["id"]),
+    rule("ex", XLIFF_1_CODE_PARENTS, &["id"]),
+    rule("ph", XLIFF_1_CODE_PARENTS, &["id"]),
+    rule("bpt", XLIFF_1_CODE_PARENTS, &["id"]),
+    rule("ept", XLIFF_1_CODE_PARENTS, &["id"]),
+    rule("it", XLIFF_1_CODE_PARENTS, &["id", "pos"]),
+    rule("sub", &["ph", "bpt", "ept", "it"], &[]),
+];
+
+const XLIFF_2_INLINE_PARENTS: &[&str] = &["source", "target", "pc", "mrk"];
+
+const XLIFF_2_RULES: &[ElementRule] = &[
+    rule("xliff", &[ROOT], &["version", "srcLang"]),
+    rule("file", &["xliff"], &["id"]),
+    rule("skeleton", &["file"], &[]),
+    rule("notes", &["file", "group", "unit"], &[]),
+    rule("note", &["notes"], &[]),
+    rule("group", &["file", "group"], &["id"]),
+    rule("unit", &["file", "group"], &["id"]),
+    rule("originalData", &["unit"], &[]),
+    rule("data", &["originalData"], &["id"]),
+    rule("segment", &["unit"], &[]),
+    rule("ignorable", &["unit"], &[]),
+    rule("source", &["segment", "ignorable"], &[]),
+    rule("target", &["segment", "ignorable"], &[]),
+    rule("cp", XLIFF_2_INLINE_PARENTS, &["hex"]),
+    rule("ph", XLIFF_2_INLINE_PARENTS, &["id"]),
+    rule("pc", XLIFF_2_INLINE_PARENTS, &["id"]),
+    rule("sc", XLIFF_2_INLINE_PARENTS, &["id"]),
+    rule("ec", XLIFF_2_INLINE_PARENTS, &[]),
+    rule("mrk", XLIFF_2_INLINE_PARENTS, &["id"]),
+    rule("sm", XLIFF_2_INLINE_PARENTS, &["id"]),
+    rule("em", XLIFF_2_INLINE_PARENTS, &["startRef"]),
+];
+
+const YES_NO: &[&str] = &["yes", "no"];
+
+/// Enumerated attributes: element (`*` for any), attribute and allowed values. XLIFF 1.2
+/// also allows user-defined values starting with `x-` for `state`.
+const XLIFF_1_ENUMS: &[(&str, &str, &[&str])] = &[
+    (
+        "target",
+        "state",
+        &[
+            "final",
+            "needs-adaptation",
+            "needs-l10n",
+            "needs-review-adaptation",
+            "needs-review-l10n",
+            "needs-review-translation",
+            "needs-translation",
+            "new",
+            "signed-off",
+            "translated",
+        ],
+    ),
+    ("*", "translate", YES_NO),
+    ("trans-unit", "approved", YES_NO),
+    ("it", "pos", &["open", "close"]),
+];
+
+const XLIFF_2_ENUMS: &[(&str, &str, &[&str])] = &[
+    (
+        "segment",
+        "state",
+        &["initial", "translated", "reviewed", "final"],
+    ),
+    ("*", "translate", YES_NO),
+    ("*", "canResegment", YES_NO),
+    ("*", "canCopy", YES_NO),
+    ("*", "canDelete", YES_NO),
+    ("*", "canOverlap", YES_NO),
+    ("*", "canReorder", &["yes", "firstNo", "no"]),
+];
+
+/// Target states meaning the translation is done.
+const XLIFF_1_DONE_STATES: &[&str] = &["translated", "final", "signed-off"];
+const XLIFF_2_DONE_STATES: &[&str] = &["translated", "reviewed", "final"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum XliffVersion {
+    #[serde(rename = "1.2")]
+    V1_2,
+    #[serde(rename = "2.0")]
+    V2_0,
+    #[serde(rename = "2.1")]
+    V2_1,
+}
+
+impl XliffVersion {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim() {
+            "1.2" => Some(Self::V1_2),
+            "2.0" => Some(Self::V2_0),
+            "2.1" => Some(Self::V2_1),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::V1_2 => "1.2",
+            Self::V2_0 => "2.0",
+            Self::V2_1 => "2.1",
+        }
+    }
+
+    fn namespace(self) -> &'static [u8] {
+        match self {
+            Self::V1_2 => XLIFF_1_NAMESPACE,
+            Self::V2_0 | Self::V2_1 => XLIFF_2_NAMESPACE,
+        }
+    }
+
+    fn rules(self) -> &'static [ElementRule] {
+        match self {
+            Self::V1_2 => XLIFF_1_RULES,
+            Self::V2_0 | Self::V2_1 => XLIFF_2_RULES,
+        }
+    }
+
+    fn enums(self) -> &'static [(&'static str, &'static str, &'static [&'static str])] {
+        match self {
+            Self::V1_2 => XLIFF_1_ENUMS,
+            Self::V2_0 | Self::V2_1 => XLIFF_2_ENUMS,
+        }
+    }
+}
+
+impl fmt::Display for XliffVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// CAT tool a file was written by, as told by its namespace declarations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum XliffTool {
+    Trados,
+    MemoQ,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiagnosticSeverity {
+    Error,
+    Warning,
+}
+
+/// A problem found in the document, with its 1-based position.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct XliffDiagnostic {
+    pub severity: DiagnosticSeverity,
+    /// `well_formed`, `schema`, or the name of a lint rule such as `sdlxliff.missing_seg_def`.
+    pub rule: String,
+    pub message: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct XliffValidationReport {
+    /// Version the document was validated as; unknown when it is not XLIFF at all.
+    pub version: Option<XliffVersion>,
+    pub tool: Option<XliffTool>,
+    pub diagnostics: Vec<XliffDiagnostic>,
+}
+
+impl XliffValidationReport {
+    /// Whether the document has no errors; warnings do not count.
+    pub fn passed(&self) -> bool {
+        self.diagnostics
+            .iter()
+            .all(|diagnostic| diagnostic.severity != DiagnosticSeverity::Error)
+    }
+
+    pub fn error_count(&self) -> usize {
+        self.diagnostics
+            .iter()
+            .filter(|diagnostic| diagnostic.severity == DiagnosticSeverity::Error)
+            .count()
+    }
+}
+
+/// Validates the XLIFF file at `path`, as `version` when given and otherwise as the version
+/// its root declares. Fails only when the file cannot be read.
+pub fn validate_xliff(path: &Path, version: Option<XliffVersion>) -> Result<XliffValidationReport> {
+    let data = fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    Ok(validate_xliff_bytes(&data, version))
+}
+
+pub fn validate_xliff_bytes(data: &[u8], version: Option<XliffVersion>) -> XliffValidationReport {
+    let mut validator = Validator::new(data, version);
+    validator.run();
+    XliffValidationReport {
+        version: validator.version,
+        tool: validator.tool,
+        diagnostics: validator.diagnostics,
+    }
+}
+
+/// Namespace of an element, as far as validation cares.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Ns {
+    /// One of the XLIFF core namespaces.
+    Core(&'static [u8]),
+    Sdl,
+    Unbound,
+    Other,
+    Undeclared,
+}
+
+struct Frame {
+    name: String,
+    core: bool,
+}
+
+/// What a unit collected for the checks made when it closes.
+#[derive(Default)]
+struct UnitState {
+    offset: usize,
+    segment_ids: HashSet<String>,
+    /// `mid`s of the `<mrk mtype="seg">` of the Trados `<seg-source>`.
+    seg_mids: Vec<(String, usize)>,
+    /// `id`s of the `<sdl:seg>` definitions.
+    seg_defs: HashSet<String>,
+    has_seg_defs: bool,
+}
+
+struct TargetState {
+    offset: usize,
+    done: bool,
+    has_content: bool,
+}
+
+struct Validator<'a> {
+    data: &'a [u8],
+    line_starts: Vec<usize>,
+    requested: Option<XliffVersion>,
+    version: Option<XliffVersion>,
+    tool: Option<XliffTool>,
+    diagnostics: Vec<XliffDiagnostic>,
+    stack: Vec<Frame>,
+    /// Depth of extension elements the current element sits in.
+    foreign_depth: usize,
+    root_seen: bool,
+    root_unbound: bool,
+    file_ids: HashSet<String>,
+    file_target_language: Option<String>,
+    segment_state: Option<String>,
+    has_target: Option<usize>,
+    has_target_language: bool,
+    unit: Option<UnitState>,
+    target: Option<TargetState>,
+}
+
+impl<'a> Validator<'a> {
+    fn new(data: &'a [u8], requested: Option<XliffVersion>) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(
+                data.iter()
+                    .enumerate()
+                    .filter(|(_, byte)| **byte == b'\n')
+                    .map(|(index, _)| index + 1),
+            )
+            .collect();
+        Self {
+            data,
+            line_starts,
+            requested,
+            version: requested,
+            tool: None,
+            diagnostics: Vec::new(),
+            stack: Vec::new(),
+            foreign_depth: 0,
+            root_seen: false,
+            root_unbound: false,
+            file_ids: HashSet::new(),
+            file_target_language: None,
+            segment_state: None,
+            has_target: None,
+            has_target_language: false,
+            unit: None,
+            target: None,
+        }
+    }
+
+    fn run(&mut self) {
+        let mut reader = NsReader::from_reader(self.data);
+        reader.config_mut().trim_text(false);
+        let decoder = reader.decoder();
+        let mut buf = Vec::new();
+        loop {
+            buf.clear();
+            let offset = reader.buffer_position() as usize;
+            let (ns, event) = match reader.read_resolved_event_into(&mut buf) {
+                Ok((ns, event)) => (self.classify(&ns), event),
+                Err(error) => {
+                    let position = reader.error_position() as usize;
+                    self.error_at(position, "well_formed", error.to_string());
+                    return;
+                }
+            };
+            match event {
+                Event::Start(ref start) | Event::Empty(ref start) => {
+                    if ns == Ns::Undeclared {
+                        let name = String::from_utf8_lossy(start.name().as_ref()).into_owned();
+                        self.error_at(
+                            offset,
+                            "well_formed",
+                            format!("The namespace prefix of <{name}> is not declared."),
+                        );
+                        return;
+                    }
+                    let empty = matches!(event, Event::Empty(_));
+                    if !self.open_element(start, decoder, ns, offset, empty) {
+                        return;
+                    }
+                }
+                Event::End(_) => self.close_element(),
+                Event::Text(ref text) => {
+                    if text.iter().any(|byte| !byte.is_ascii_whitespace()) {
+                        if self.stack.is_empty() {
+                            self.error_at(
+                                offset,
+                                "well_formed",
+                                "Text is not allowed outside the root element.".into(),
+                            );
+                            return;
+                        }
+                        self.mark_target_content();
+                    }
+                }
+                Event::CData(_) => self.mark_target_content(),
+                Event::GeneralRef(ref reference) => {
+                    let name = String::from_utf8_lossy(reference).into_owned();
+                    if !name.starts_with('#') && resolve_predefined_entity(&name).is_none() {
+                        self.error_at(
+                            offset,
+                            "well_formed",
+                            format!("The entity '&{name};' is not defined."),
+                        );
+                        return;
+                    }
+                    self.mark_target_content();
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+        if let Some(frame) = self.stack.last() {
+            let message = format!("<{}> is not closed.", frame.name);
+            self.error_at(self.data.len(), "well_formed", message);
+        } else if !self.root_seen {
+            self.error_at(0, "well_formed", "The document has no root element.".into());
+        }
+    }
+
+    fn classify(&self, ns: &ResolveResult<'_>) -> Ns {
+        match ns {
+            ResolveResult::Bound(namespace) => {
+                let namespace = namespace.as_ref();
+                if namespace == XLIFF_1_NAMESPACE {
+                    Ns::Core(XLIFF_1_NAMESPACE)
+                } else if namespace == XLIFF_2_NAMESPACE {
+                    Ns::Core(XLIFF_2_NAMESPACE)
+                } else if namespace == SDL_NAMESPACE {
+                    Ns::Sdl
+                } else {
+                    Ns::Other
+                }
+            }
+            ResolveResult::Unbound => Ns::Unbound,
+            ResolveResult::Unknown(_) => Ns::Undeclared,
+        }
+    }
+
+    /// Checks an opening tag; returns `false` when validation cannot go on.
+    fn open_element(
+        &mut self,
+        start: &BytesStart<'_>,
+        decoder: Decoder,
+        ns: Ns,
+        offset: usize,
+        empty: bool,
+    ) -> bool {
+        let name = String::from_utf8_lossy(start.local_name().as_ref()).into_owned();
+        let attributes = match read_attributes(start, decoder) {
+            Ok(attributes) => attributes,
+            Err(message) => {
+                self.error_at(offset, "well_formed", message);
+                return false;
+            }
+        };
+
+        if !self.root_seen {
+            self.root_seen = true;
+            if !self.open_root(&name, ns, &attributes, offset) {
+                return false;
+            }
+        } else if self.stack.is_empty() {
+            self.error_at(
+                offset,
+                "well_formed",
+                "The document has more than one root.".into(),
+            );
+            return false;
+        }
+
+        let core = match ns {
+            Ns::Core(namespace) => self.version.map(XliffVersion::namespace) == Some(namespace),
+            Ns::Unbound => self.root_unbound,
+            _ => false,
+        };
+        if core && self.foreign_depth == 0 {
+            self.check_schema(&name, &attributes, offset);
+            self.track(&name, &attributes, offset);
+        } else {
+            self.mark_target_content();
+            if ns == Ns::Sdl {
+                self.track_sdl(&name, &attributes);
+            }
+        }
+
+        if empty {
+            self.close(&name, core);
+        } else {
+            if !core {
+                self.foreign_depth += 1;
+            }
+            self.stack.push(Frame { name, core });
+        }
+        true
+    }
+
+    fn open_root(
+        &mut self,
+        name: &str,
+        ns: Ns,
+        attributes: &[(String, String)],
+        offset: usize,
+    ) -> bool {
+        if name != "xliff" {
+            self.error_at(
+                offset,
+                "schema",
+                format!("The root is <{name}>, not <xliff>."),
+            );
+            self.version = None;
+            return false;
+        }
+        let declared = attribute(attributes, "version").and_then(XliffVersion::parse);
+        self.version = self.requested.or(declared);
+        let Some(version) = self.version else {
+            self.error_at(
+                offset,
+                "schema",
+                "The XLIFF version is missing or not one of 1.2, 2.0 or 2.1.".into(),
+            );
+            return false;
+        };
+        if let Some(declared) = declared
+            && declared != version
+        {
+            self.error_at(
+                offset,
+                "schema",
+                format!("The document declares XLIFF {declared}, not {version}."),
+            );
+        }
+        match ns {
+            Ns::Unbound if version == XliffVersion::V1_2 => {
+                self.root_unbound = true;
+                self.warning_at(
+                    offset,
+                    "xliff.missing_namespace",
+                    "<xliff> has no namespace; tools expect the XLIFF 1.2 namespace.".into(),
+                );
+            }
+            Ns::Core(namespace) if namespace == version.namespace() => {}
+            _ => {
+                self.error_at(
+                    offset,
+                    "schema",
+                    format!("<xliff> is not in the XLIFF {version} namespace."),
+                );
+                return false;
+            }
+        }
+        self.has_target_language = attribute(attributes, "trgLang").is_some();
+        self.tool = attributes.iter().find_map(|(key, value)| {
+            let declaration = key == "xmlns" || key.starts_with("xmlns:");
+            if !declaration {
+                None
+            } else if value.as_bytes() == SDL_NAMESPACE {
+                Some(XliffTool::Trados)
+            } else if value == MEMOQ_NAMESPACE {
+                Some(XliffTool::MemoQ)
+            } else {
+                None
+            }
+        });
+        true
+    }
+
+    fn check_schema(&mut self, name: &str, attributes: &[(String, String)], offset: usize) {
+        let Some(version) = self.version else {
+            return;
+        };
+        let parent = self
+            .stack
+            .last()
+            .map(|frame| if frame.core { frame.name.as_str() } else { "?" })
+            .unwrap_or(ROOT);
+        let Some(rule) = version.rules().iter().find(|rule| rule.name == name) else {
+            self.error_at(
+                offset,
+                "schema",
+                format!("<{name}> is not an XLIFF {version} element."),
+            );
+            return;
+        };
+        if !rule.parents.contains(&parent) {
+            let message = if parent == ROOT {
+                format!("<{name}> cannot be the root.")
+            } else {
+                format!("<{name}> is not allowed in <{parent}>.")
+            };
+            self.error_at(offset, "schema", message);
+        }
+        for required in rule.required {
+            if attribute(attributes, required).is_none() {
+                self.error_at(
+                    offset,
+                    "schema",
+                    format!("<{name}> is missing the required attribute '{required}'."),
+                );
+            }
+        }
+        for (element, key, values) in version.enums() {
+            if *element != "*" && *element != name {
+                continue;
+            }
+            let Some(value) = attribute(attributes, key) else {
+                continue;
+            };
+            let custom =
+                version == XliffVersion::V1_2 && *key == "state" && value.starts_with("x-");
+            if !values.contains(&value) && !custom {
+                self.error_at(
+                    offset,
+                    "schema",
+                    format!(
+                        "'{value}' is not a valid {key} of <{name}>; expected one of: {}.",
+                        values.join(", ")
+                    ),
+                );
+            }
+        }
+    }
+
+    /// Collects what the uniqueness and lint checks need.
+    fn track(&mut self, name: &str, attributes: &[(String, String)], offset: usize) {
+        let version = self.version.unwrap_or(XliffVersion::V1_2);
+        let id = attribute(attributes, "id");
+        match name {
+            "file" => {
+                self.file_ids.clear();
+                self.file_target_language =
+                    attribute(attributes, "target-language").map(str::to_string);
+            }
+            "trans-unit" | "unit" | "group" => {
+                // Units and groups share their ids in XLIFF 2; XLIFF 1.2 groups may repeat ids.
+                let unique = match (version, name) {
+                    (XliffVersion::V1_2, "group") => None,
+                    _ => id,
+                };
+                if let Some(id) = unique
+                    && !self.file_ids.insert(id.to_string())
+                {
+                    self.error_at(
+                        offset,
+                        "schema",
+                        format!("The id '{id}' of <{name}> is used twice in the same <file>."),
+                    );
+                }
+                if name != "group" {
+                    self.unit = Some(UnitState {
+                        offset,
+                        ..UnitState::default()
+                    });
+                }
+            }
+            "segment" | "ignorable" => {
+                self.segment_state = attribute(attributes, "state").map(str::to_string);
+                if let (Some(id), Some(unit)) = (id, self.unit.as_mut())
+                    && !unit.segment_ids.insert(id.to_string())
+                {
+                    let message = format!("The id '{id}' is used twice in the same <unit>.");
+                    self.error_at(offset, "schema", message);
+                }
+            }
+            "target" => {
+                self.mark_target_content();
+                self.has_target.get_or_insert(offset);
+                let in_unit = self
+                    .stack
+                    .last()
+                    .is_some_and(|frame| matches!(frame.name.as_str(), "trans-unit" | "segment"));
+                if !in_unit {
+                    return;
+                }
+                let done = match version {
+                    XliffVersion::V1_2 => attribute(attributes, "state")
+                        .is_some_and(|state| XLIFF_1_DONE_STATES.contains(&state)),
+                    XliffVersion::V2_0 | XliffVersion::V2_1 => self
+                        .segment_state
+                        .as_deref()
+                        .is_some_and(|state| XLIFF_2_DONE_STATES.contains(&state)),
+                };
+                self.target = Some(TargetState {
+                    offset,
+                    done,
+                    has_content: false,
+                });
+                let language = attribute(attributes, "xml:lang");
+                if let (Some(language), Some(expected)) = (language, &self.file_target_language)
+                    && !language.eq_ignore_ascii_case(expected)
+                {
+                    let message = format!(
+                        "The target is in '{language}' but its file translates into '{expected}'."
+                    );
+                    self.warning_at(offset, "xliff.target_language_mismatch", message);
+                }
+            }
+            "mrk" => {
+                self.mark_target_content();
+                let in_seg_source = self
+                    .stack
+                    .iter()
+                    .any(|frame| frame.core && frame.name == "seg-source");
+                if in_seg_source
+                    && attribute(attributes, "mtype") == Some("seg")
+                    && let (Some(mid), Some(unit)) =
+                        (attribute(attributes, "mid"), self.unit.as_mut())
+                {
+                    unit.seg_mids.push((mid.to_string(), offset));
+                }
+            }
+            _ => self.mark_target_content(),
+        }
+    }
+
+    fn track_sdl(&mut self, name: &str, attributes: &[(String, String)]) {
+        let Some(unit) = self.unit.as_mut() else {
+            return;
+        };
+        match name {
+            "seg-defs" => unit.has_seg_defs = true,
+            "seg" => {
+                if let Some(id) = attribute(attributes, "id") {
+                    unit.seg_defs.insert(id.to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn close_element(&mut self) {
+        let Some(frame) = self.stack.pop() else {
+            return;
+        };
+        if !frame.core {
+            self.foreign_depth = self.foreign_depth.saturating_sub(1);
+        }
+        self.close(&frame.name, frame.core);
+    }
+
+    fn close(&mut self, name: &str, core: bool) {
+        if !core || self.foreign_depth > 0 {
+            return;
+        }
+        match name {
+            "target" => {
+                if let Some(target) = self.target.take()
+                    && target.done
+                    && !target.has_content
+                {
+                    self.warning_at(
+                        target.offset,
+                        "xliff.empty_translated_target",
+                        "The target is marked as translated but is empty.".into(),
+                    );
+                }
+            }
+            "segment" | "ignorable" => self.segment_state = None,
+            "trans-unit" | "unit" => {
+                if let Some(unit) = self.unit.take() {
+                    self.lint_unit(unit);
+                }
+            }
+            "xliff" => {
+                let is_2 = self
+                    .version
+                    .is_some_and(|version| version != XliffVersion::V1_2);
+                if let Some(target_offset) = self.has_target
+                    && is_2
+                    && !self.has_target_language
+                {
+                    self.error_at(
+                        target_offset,
+                        "schema",
+                        "The document has targets but <xliff> has no trgLang.".into(),
+                    );
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn lint_unit(&mut self, unit: UnitState) {
+        if self.tool != Some(XliffTool::Trados) || unit.seg_mids.is_empty() {
+            return;
+        }
+        if !unit.has_seg_defs {
+            self.warning_at(
+                unit.offset,
+                "sdlxliff.missing_seg_defs",
+                "The unit has segments but no sdl:seg-defs; Trados will not open it.".into(),
+            );
+            return;
+        }
+        for (mid, offset) in &unit.seg_mids {
+            if !unit.seg_defs.contains(mid) {
+                self.warning_at(
+                    *offset,
+                    "sdlxliff.missing_seg_def",
+                    format!("Segment '{mid}' has no entry in the sdl:seg-defs of its unit."),
+                );
+            }
+        }
+    }
+
+    fn mark_target_content(&mut self) {
+        if let Some(target) = self.target.as_mut() {
+            target.has_content = true;
+        }
+    }
+
+    fn error_at(&mut self, offset: usize, rule: &str, message: String) {
+        self.push(DiagnosticSeverity::Error, offset, rule, message);
+    }
+
+    fn warning_at(&mut self, offset: usize, rule: &str, message: String) {
+        self.push(DiagnosticSeverity::Warning, offset, rule, message);
+    }
+
+    fn push(&mut self, severity: DiagnosticSeverity, offset: usize, rule: &str, message: String) {
+        let (line, column) = self.position(offset);
+        self.diagnostics.push(XliffDiagnostic {
+            severity,
+            rule: rule.to_string(),
+            message,
+            line,
+            column,
+        });
+    }
+
+    /// 1-based line and column of a byte offset, the column counted in characters.
+    fn position(&self, offset: usize) -> (usize, usize) {
+        let offset = offset.min(self.data.len());
+        let line = self.line_starts.partition_point(|start| *start <= offset);
+        let line_start = self.line_starts[line - 1];
+        let column = String::from_utf8_lossy(&self.data[line_start..offset])
+            .chars()
+            .count();
+        (line, column + 1)
+    }
+}
+
+/// Attributes by qualified name, with their values unescaped.
+fn read_attributes(
+    start: &BytesStart<'_>,
+    decoder: Decoder,
+) -> Result<Vec<(String, String)>, String> {
+    let mut attributes = Vec::new();
+    for attr in start.attributes() {
+        let attr = attr.map_err(|error| error.to_string())?;
+        let key = String::from_utf8_lossy(attr.key.as_ref()).into_owned();
+        let value = attr
+            .decode_and_unescape_value(decoder)
+            .map_err(|error| error.to_string())?;
+        attributes.push((key, value.into_owned()));
+    }
+    Ok(attributes)
+}
+
+fn attribute<'v>(attributes: &'v [(String, String)], name: &str) -> Option<&'v str> {
+    attributes
+        .iter()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(report: &XliffValidationReport) -> Vec<(&str, usize, usize)> {
+        report
+            .diagnostics
+            .iter()
+            .map(|diagnostic| (diagnostic.rule.as_str(), diagnostic.line, diagnostic.column))
+            .collect()
+    }
+
+    #[test]
+    fn reports_schema_and_lint_problems_with_positions() {
+        let xliff = br#"<?xml version="1.0"?>
+<xliff version="2.0" xmlns="urn:oasis:names:tc:xliff:document:2.0" srcLang="en">
+  <file id="f1">
+    <unit id="u1">
+      <segment state="done"><source>Hello</source><target>Hallo</target></segment>
+    </unit>
+    <unit id="u1">
+      <segment state="final"><source>Bye</source><target/></segment>
+      <source>stray</source>
+    </unit>
+  </file>
+</xliff>"#;
+        let report = validate_xliff_bytes(xliff, None);
+        assert_eq!(report.version, Some(XliffVersion::V2_0));
+        assert!(!report.passed());
+        assert_eq!(
+            rules(&report),
+            vec![
+                ("schema", 5, 7),
+                ("schema", 7, 5),
+                ("xliff.empty_translated_target", 8, 50),
+                ("schema", 9, 7),
+                ("schema", 5, 51),
+            ]
+        );
+        assert!(report.diagnostics[0].message.contains("'done'"));
+
+        let broken = b"<xliff version=\"1.2\">\n  <file></xliff>";
+        let report = validate_xliff_bytes(broken, Some(XliffVersion::V1_2));
+        assert_eq!(report.diagnostics.last().unwrap().rule, "well_formed");
+        assert_eq!(report.diagnostics.last().unwrap().line, 2);
+    }
+
+    #[test]
+    fn trados_segments_need_seg_defs() {
+        let sdlxliff = br#"<xliff version="1.2" xmlns="urn:oasis:names:tc:xliff:document:1.2"
+    xmlns:sdl="http://sdl.com/FileTypes/SdlXliff/1.0">
+  <file original="a.docx" source-language="en-US" target-language="de-DE" datatype="xml">
+    <body>
+      <trans-unit id="1">
+        <source>Hello. Bye.</source>
+        <seg-source><mrk mtype="seg" mid="1">Hello.</mrk>
+          <mrk mtype="seg" mid="2">Bye.</mrk></seg-source>
+        <target xml:lang="de-DE"><mrk mtype="seg" mid="1">Hallo.</mrk></target>
+        <sdl:seg-defs><sdl:seg id="1" conf="Translated"/></sdl:seg-defs>
+      </trans-unit>
+    </body>
+  </file>
+</xliff>"#;
+        let report = validate_xliff_bytes(sdlxliff, None);
+        assert_eq!(report.tool, Some(XliffTool::Trados));
+        assert!(report.passed());
+        assert_eq!(rules(&report), vec![("sdlxliff.missing_seg_def", 8, 11)]);
+    }
+}
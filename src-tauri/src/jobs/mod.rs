@@ -0,0 +1,285 @@
+//! Dependent jobs run as one flow.
+//!
+//! A [`JobGraph`] holds jobs and the prerequisites each of them waits for; an edge that would
+//! close a cycle is rejected when it is added. [`run_graph`] starts a job once all of its
+//! prerequisites succeeded, runs independent jobs concurrently, and cancels everything
+//! downstream of a failed job instead of starting it.
+
+pub mod pipeline;
+pub mod progress;
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Display;
+use std::future::Future;
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use thiserror::Error;
+use tokio::task::JoinSet;
+
+use crate::db::types::JobState;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum JobGraphError {
+    #[error("unknown job '{0}'")]
+    UnknownJob(String),
+    #[error("'{job}' cannot wait for '{prerequisite}': it would create a cycle")]
+    Cycle { job: String, prerequisite: String },
+}
+
+/// Jobs and their prerequisites.
+#[derive(Debug, Clone)]
+pub struct JobGraph<K> {
+    prerequisites: BTreeMap<K, BTreeSet<K>>,
+}
+
+impl<K: Ord + Clone + Display> JobGraph<K> {
+    pub fn new() -> Self {
+        Self {
+            prerequisites: BTreeMap::new(),
+        }
+    }
+
+    pub fn add_job(&mut self, job: K) {
+        self.prerequisites.entry(job).or_default();
+    }
+
+    /// Makes `job` wait for `prerequisite` to succeed.
+    pub fn add_dependency(&mut self, job: &K, prerequisite: &K) -> Result<(), JobGraphError> {
+        for key in [job, prerequisite] {
+            if !self.prerequisites.contains_key(key) {
+                return Err(JobGraphError::UnknownJob(key.to_string()));
+            }
+        }
+        if job == prerequisite || self.waits_for(prerequisite, job) {
+            return Err(JobGraphError::Cycle {
+                job: job.to_string(),
+                prerequisite: prerequisite.to_string(),
+            });
+        }
+        if let Some(prerequisites) = self.prerequisites.get_mut(job) {
+            prerequisites.insert(prerequisite.clone());
+        }
+        Ok(())
+    }
+
+    pub fn jobs(&self) -> impl Iterator<Item = &K> {
+        self.prerequisites.keys()
+    }
+
+    pub fn prerequisites(&self, job: &K) -> impl Iterator<Item = &K> {
+        self.prerequisites.get(job).into_iter().flatten()
+    }
+
+    /// Whether `job` waits, directly or transitively, for `other`.
+    fn waits_for(&self, job: &K, other: &K) -> bool {
+        let mut pending = vec![job];
+        let mut seen = BTreeSet::new();
+        while let Some(current) = pending.pop() {
+            for prerequisite in self.prerequisites(current) {
+                if prerequisite == other {
+                    return true;
+                }
+                if seen.insert(prerequisite) {
+                    pending.push(prerequisite);
+                }
+            }
+        }
+        false
+    }
+
+    /// Jobs waiting, directly or transitively, for `job`.
+    fn dependents(&self, job: &K) -> Vec<K> {
+        self.jobs()
+            .filter(|candidate| self.waits_for(candidate, job))
+            .cloned()
+            .collect()
+    }
+}
+
+impl<K: Ord + Clone + Display> Default for JobGraph<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Runs the jobs of a graph and records their state changes.
+pub trait JobRunner<K>: Send + Sync + 'static {
+    fn run(&self, job: &K) -> impl Future<Output = anyhow::Result<()>> + Send;
+
+    /// Called on every state change; `error` explains failures and cancellations.
+    fn report(
+        &self,
+        job: &K,
+        state: JobState,
+        error: Option<String>,
+    ) -> impl Future<Output = ()> + Send;
+}
+
+/// Runs every job of `graph` as soon as its prerequisites succeeded and returns the final
+/// state of each job. A failure cancels the jobs waiting for the failed one; unrelated
+/// branches keep going.
+pub async fn run_graph<K, R>(graph: JobGraph<K>, runner: Arc<R>) -> BTreeMap<K, JobState>
+where
+    K: Ord + Clone + Display + Send + Sync + 'static,
+    R: JobRunner<K>,
+{
+    let mut states: BTreeMap<K, JobState> = graph
+        .jobs()
+        .map(|job| (job.clone(), JobState::Pending))
+        .collect();
+    let mut running = JoinSet::new();
+    loop {
+        let ready: Vec<K> = states
+            .iter()
+            .filter(|(job, state)| {
+                **state == JobState::Pending
+                    && graph
+                        .prerequisites(job)
+                        .all(|prerequisite| states[prerequisite] == JobState::Succeeded)
+            })
+            .map(|(job, _)| job.clone())
+            .collect();
+        for job in ready {
+            states.insert(job.clone(), JobState::Running);
+            runner.report(&job, JobState::Running, None).await;
+            let runner = Arc::clone(&runner);
+            running.spawn(async move {
+                // Run the job in its own task so a panic fails the job, not the whole graph.
+                let task_job = job.clone();
+                let outcome = tokio::spawn(async move { runner.run(&task_job).await })
+                    .await
+                    .unwrap_or_else(|error| Err(anyhow!("the job stopped unexpectedly: {error}")));
+                (job, outcome)
+            });
+        }
+
+        let Some(joined) = running.join_next().await else {
+            break;
+        };
+        let (job, outcome) = joined.expect("job wrapper tasks do not panic");
+        match outcome {
+            Ok(()) => {
+                states.insert(job.clone(), JobState::Succeeded);
+                runner.report(&job, JobState::Succeeded, None).await;
+            }
+            Err(error) => {
+                states.insert(job.clone(), JobState::Failed);
+                runner
+                    .report(&job, JobState::Failed, Some(format!("{error:#}")))
+                    .await;
+                for dependent in graph.dependents(&job) {
+                    if states[&dependent] == JobState::Pending {
+                        states.insert(dependent.clone(), JobState::Cancelled);
+                        runner
+                            .report(
+                                &dependent,
+                                JobState::Cancelled,
+                                Some(format!("Cancelled because '{job}' failed.")),
+                            )
+                            .await;
+                    }
+                }
+            }
+        }
+    }
+    states
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use anyhow::bail;
+
+    use super::*;
+
+    #[test]
+    fn rejects_unknown_jobs_and_cycles() {
+        let mut graph = JobGraph::new();
+        for job in ["convert", "translate", "qa"] {
+            graph.add_job(job.to_string());
+        }
+        let key = |name: &str| name.to_string();
+        graph
+            .add_dependency(&key("translate"), &key("convert"))
+            .unwrap();
+        graph.add_dependency(&key("qa"), &key("translate")).unwrap();
+
+        assert_eq!(
+            graph.add_dependency(&key("merge"), &key("qa")),
+            Err(JobGraphError::UnknownJob("merge".into()))
+        );
+        assert!(matches!(
+            graph.add_dependency(&key("convert"), &key("qa")),
+            Err(JobGraphError::Cycle { .. })
+        ));
+        assert!(matches!(
+            graph.add_dependency(&key("qa"), &key("qa")),
+            Err(JobGraphError::Cycle { .. })
+        ));
+    }
+
+    struct Recorder {
+        failing: &'static str,
+        events: Mutex<Vec<(String, JobState)>>,
+    }
+
+    impl JobRunner<String> for Recorder {
+        async fn run(&self, job: &String) -> anyhow::Result<()> {
+            if job == self.failing {
+                bail!("{job} broke");
+            }
+            Ok(())
+        }
+
+        async fn report(&self, job: &String, state: JobState, _error: Option<String>) {
+            self.events.lock().unwrap().push((job.clone(), state));
+        }
+    }
+
+    #[tokio::test]
+    async fn runs_in_dependency_order_and_cancels_after_failures() {
+        let mut graph = JobGraph::new();
+        for job in [
+            "a:convert",
+            "a:translate",
+            "a:qa",
+            "b:convert",
+            "b:translate",
+        ] {
+            graph.add_job(job.to_string());
+        }
+        for (job, prerequisite) in [
+            ("a:translate", "a:convert"),
+            ("a:qa", "a:translate"),
+            ("b:translate", "b:convert"),
+        ] {
+            graph
+                .add_dependency(&job.to_string(), &prerequisite.to_string())
+                .unwrap();
+        }
+        let runner = Arc::new(Recorder {
+            failing: "a:translate",
+            events: Mutex::new(Vec::new()),
+        });
+
+        let states = run_graph(graph, Arc::clone(&runner)).await;
+        assert_eq!(states["a:convert"], JobState::Succeeded);
+        assert_eq!(states["a:translate"], JobState::Failed);
+        assert_eq!(states["a:qa"], JobState::Cancelled);
+        assert_eq!(states["b:translate"], JobState::Succeeded);
+
+        let events = runner.events.lock().unwrap();
+        let position = |job: &str, state: JobState| {
+            events
+                .iter()
+                .position(|event| event.0 == job && event.1 == state)
+                .unwrap()
+        };
+        assert!(
+            position("a:convert", JobState::Succeeded) < position("a:translate", JobState::Running)
+        );
+        assert!(!events.contains(&("a:qa".to_string(), JobState::Running)));
+    }
+}
@@ -0,0 +1,366 @@
+//! Pipeline definitions: the ordered stages a file goes through and their options.
+//!
+//! Presets store a [`PipelineDefinition`] as JSON, so flows such as "TM + light QA" or
+//! "human only" are data rather than code. Each stage of a file waits for the stage before
+//! it in the definition. `plugin` stages run a registered plugin over the file's segments;
+//! a pipeline may run several plugins, each once.
+
+use std::collections::HashSet;
+
+use anyhow::{Result, bail};
+use serde::{Deserialize, Serialize};
+
+use crate::db::types::JobType;
+use crate::jliff::pretranslation::{MIN_MATCH_FLOOR, OverwritePolicy};
+
+/// QA checks the `qa` stage can run.
+pub const PIPELINE_QA_CHECKS: [&str; 2] = ["unicode_text", "bidi_controls"];
+
+/// Prefix of the job type of `plugin` stages, followed by the plugin id.
+pub const PLUGIN_JOB_TYPE_PREFIX: &str = "PLUGIN:";
+
+/// Match threshold of the `translate` stage when none is configured: only exact matches are
+/// filled without a human looking at them.
+pub const DEFAULT_MIN_MATCH: u8 = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PipelineStage {
+    /// XLIFF to JLIFF.
+    Convert,
+    /// Translation memory pretranslation.
+    Translate,
+    /// A registered plugin run over the JLIFF segments.
+    Plugin,
+    /// Document QA checks.
+    Qa,
+    /// JLIFF statuses and notes written back into the XLIFF for the OpenXLIFF merge.
+    Merge,
+}
+
+impl PipelineStage {
+    pub const ALL: [PipelineStage; 5] = [
+        PipelineStage::Convert,
+        PipelineStage::Translate,
+        PipelineStage::Plugin,
+        PipelineStage::Qa,
+        PipelineStage::Merge,
+    ];
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|stage| stage.as_str().eq_ignore_ascii_case(value.trim()))
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PipelineStage::Convert => "convert",
+            PipelineStage::Translate => "translate",
+            PipelineStage::Plugin => "plugin",
+            PipelineStage::Qa => "qa",
+            PipelineStage::Merge => "merge",
+        }
+    }
+
+    /// Type of the `jobs` rows of the stage; `plugin` stages are typed by their plugin.
+    pub fn job_type(self, plugin: Option<&str>) -> String {
+        let job_type = match self {
+            PipelineStage::Convert => JobType::ConvertJliff,
+            PipelineStage::Translate => JobType::Pretranslate,
+            PipelineStage::Plugin => {
+                return format!("{PLUGIN_JOB_TYPE_PREFIX}{}", plugin.unwrap_or_default());
+            }
+            PipelineStage::Qa => JobType::Qa,
+            PipelineStage::Merge => JobType::Merge,
+        };
+        job_type.as_str().to_string()
+    }
+}
+
+/// Ordered stages of a pipeline.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PipelineDefinition {
+    pub stages: Vec<StageSetting>,
+}
+
+/// A stage and its options; options only apply to the stage named in their description.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StageSetting {
+    pub stage: PipelineStage,
+    /// `translate`: lowest TM match filled, [`DEFAULT_MIN_MATCH`] when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_match: Option<u8>,
+    /// `translate`: `empty_only` (default), `unconfirmed` or `all`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub overwrite_policy: Option<String>,
+    /// `qa`: checks to run, every one of [`PIPELINE_QA_CHECKS`] when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checks: Option<Vec<String>>,
+    /// `merge`: whether open critical QA findings fail the stage; true when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_on_critical: Option<bool>,
+    /// `plugin`: id of the registered plugin the stage runs; required.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub plugin: Option<String>,
+}
+
+impl StageSetting {
+    pub fn new(stage: PipelineStage) -> Self {
+        Self {
+            stage,
+            min_match: None,
+            overwrite_policy: None,
+            checks: None,
+            block_on_critical: None,
+            plugin: None,
+        }
+    }
+
+    /// A `plugin` stage running `plugin_id`.
+    pub fn plugin(plugin_id: &str) -> Self {
+        Self {
+            plugin: Some(plugin_id.to_string()),
+            ..Self::new(PipelineStage::Plugin)
+        }
+    }
+
+    /// The stage as ad-hoc runs name it: `translate`, or `plugin:<id>` for plugin stages.
+    pub fn name(&self) -> String {
+        match &self.plugin {
+            Some(plugin) => format!("{}:{plugin}", self.stage.as_str()),
+            None => self.stage.as_str().to_string(),
+        }
+    }
+
+    pub fn job_type(&self) -> String {
+        self.stage.job_type(self.plugin.as_deref())
+    }
+
+    pub fn min_match(&self) -> u8 {
+        self.min_match.unwrap_or(DEFAULT_MIN_MATCH)
+    }
+
+    pub fn overwrite_policy(&self) -> OverwritePolicy {
+        self.overwrite_policy
+            .as_deref()
+            .and_then(OverwritePolicy::parse)
+            .unwrap_or(OverwritePolicy::EmptyOnly)
+    }
+
+    /// Whether the `qa` stage runs `check_type`.
+    pub fn runs_check(&self, check_type: &str) -> bool {
+        self.checks.as_ref().is_none_or(|checks| {
+            checks
+                .iter()
+                .any(|check| check.trim().eq_ignore_ascii_case(check_type))
+        })
+    }
+
+    pub fn block_on_critical(&self) -> bool {
+        self.block_on_critical.unwrap_or(true)
+    }
+}
+
+impl PipelineDefinition {
+    /// Every built-in stage with its default options; used by projects without a preset.
+    pub fn full() -> Self {
+        Self {
+            stages: PipelineStage::ALL
+                .into_iter()
+                .filter(|stage| *stage != PipelineStage::Plugin)
+                .map(StageSetting::new)
+                .collect(),
+        }
+    }
+
+    pub fn stage(&self, stage: PipelineStage) -> Option<&StageSetting> {
+        self.stages.iter().find(|setting| setting.stage == stage)
+    }
+
+    /// Ids of the plugins the pipeline runs, in order.
+    pub fn plugins(&self) -> impl Iterator<Item = &str> {
+        self.stages
+            .iter()
+            .filter_map(|setting| setting.plugin.as_deref())
+    }
+
+    /// Rejects empty or repeated stages (plugins may repeat with different plugins), a
+    /// `convert` that is not first or a `merge` that is not last, and options that are
+    /// invalid or belong to another stage.
+    pub fn validate(&self) -> Result<()> {
+        let Some(last) = self.stages.len().checked_sub(1) else {
+            bail!("A pipeline needs at least one stage.");
+        };
+        let mut seen = HashSet::new();
+        for (index, setting) in self.stages.iter().enumerate() {
+            let stage = setting.stage;
+            let name = setting.name();
+            if !seen.insert((stage, setting.plugin.as_deref())) {
+                bail!("The {name} stage appears more than once.");
+            }
+            match stage {
+                PipelineStage::Convert if index != 0 => {
+                    bail!("The convert stage must come first.");
+                }
+                PipelineStage::Merge if index != last => {
+                    bail!("The merge stage must come last.");
+                }
+                _ => {}
+            }
+
+            if stage != PipelineStage::Translate
+                && (setting.min_match.is_some() || setting.overwrite_policy.is_some())
+            {
+                bail!("minMatch and overwritePolicy only apply to the translate stage.");
+            }
+            if stage != PipelineStage::Qa && setting.checks.is_some() {
+                bail!("checks only apply to the qa stage.");
+            }
+            if stage != PipelineStage::Merge && setting.block_on_critical.is_some() {
+                bail!("blockOnCritical only applies to the merge stage.");
+            }
+            match &setting.plugin {
+                Some(_) if stage != PipelineStage::Plugin => {
+                    bail!("plugin only applies to the plugin stage.");
+                }
+                Some(plugin) if plugin.trim().is_empty() => {
+                    bail!("The plugin stage needs a plugin id.");
+                }
+                None if stage == PipelineStage::Plugin => {
+                    bail!("The plugin stage needs a plugin id.");
+                }
+                _ => {}
+            }
+
+            if let Some(min_match) = setting.min_match
+                && !(MIN_MATCH_FLOOR..=100).contains(&min_match)
+            {
+                bail!("minMatch must be between {MIN_MATCH_FLOOR} and 100.");
+            }
+            if let Some(policy) = &setting.overwrite_policy
+                && OverwritePolicy::parse(policy).is_none()
+            {
+                bail!("Unknown overwrite policy '{policy}'. Use empty_only, unconfirmed or all.");
+            }
+            if let Some(checks) = &setting.checks {
+                if checks.is_empty() {
+                    bail!("The qa stage needs at least one check.");
+                }
+                if let Some(unknown) = checks.iter().find(|check| {
+                    !PIPELINE_QA_CHECKS
+                        .iter()
+                        .any(|known| known.eq_ignore_ascii_case(check.trim()))
+                }) {
+                    bail!(
+                        "Unknown QA check '{unknown}'. Use {}.",
+                        PIPELINE_QA_CHECKS.join(" or ")
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_stored_definitions_and_their_defaults() {
+        let definition: PipelineDefinition = serde_json::from_str(
+            r#"{"stages":[
+                {"stage":"convert"},
+                {"stage":"translate","minMatch":75},
+                {"stage":"qa","checks":["unicode_text"]}
+            ]}"#,
+        )
+        .unwrap();
+        definition.validate().unwrap();
+
+        let translate = definition.stage(PipelineStage::Translate).unwrap();
+        assert_eq!(translate.min_match(), 75);
+        assert_eq!(translate.overwrite_policy(), OverwritePolicy::EmptyOnly);
+        let qa = definition.stage(PipelineStage::Qa).unwrap();
+        assert!(qa.runs_check("unicode_text"));
+        assert!(!qa.runs_check("bidi_controls"));
+        assert!(definition.stage(PipelineStage::Merge).is_none());
+        assert_eq!(translate.job_type(), "PRETRANSLATE");
+
+        let full = PipelineDefinition::full();
+        full.validate().unwrap();
+        assert!(
+            full.stage(PipelineStage::Merge)
+                .unwrap()
+                .block_on_critical()
+        );
+    }
+
+    #[test]
+    fn rejects_misordered_stages_and_misplaced_options() {
+        let stages = |settings: Vec<StageSetting>| PipelineDefinition { stages: settings };
+        assert!(stages(Vec::new()).validate().is_err());
+        assert!(
+            stages(vec![
+                StageSetting::new(PipelineStage::Translate),
+                StageSetting::new(PipelineStage::Convert),
+            ])
+            .validate()
+            .is_err()
+        );
+        assert!(
+            stages(vec![
+                StageSetting::new(PipelineStage::Merge),
+                StageSetting::new(PipelineStage::Qa),
+            ])
+            .validate()
+            .is_err()
+        );
+
+        let mut qa = StageSetting::new(PipelineStage::Qa);
+        qa.min_match = Some(90);
+        assert!(stages(vec![qa]).validate().is_err());
+
+        let mut translate = StageSetting::new(PipelineStage::Translate);
+        translate.min_match = Some(30);
+        assert!(stages(vec![translate]).validate().is_err());
+    }
+
+    #[test]
+    fn plugin_stages_name_their_plugin_once() {
+        let definition = PipelineDefinition {
+            stages: vec![
+                StageSetting::new(PipelineStage::Translate),
+                StageSetting::plugin("acme.fixer"),
+                StageSetting::plugin("acme.casing"),
+                StageSetting::new(PipelineStage::Merge),
+            ],
+        };
+        definition.validate().unwrap();
+        assert_eq!(
+            definition.plugins().collect::<Vec<_>>(),
+            ["acme.fixer", "acme.casing"]
+        );
+        assert_eq!(definition.stages[1].job_type(), "PLUGIN:acme.fixer");
+        assert_eq!(definition.stages[1].name(), "plugin:acme.fixer");
+        assert!(PipelineDefinition::full().plugins().next().is_none());
+
+        let stages = |settings: Vec<StageSetting>| PipelineDefinition { stages: settings };
+        assert!(
+            stages(vec![StageSetting::plugin("a"), StageSetting::plugin("a")])
+                .validate()
+                .is_err()
+        );
+        assert!(
+            stages(vec![StageSetting::new(PipelineStage::Plugin)])
+                .validate()
+                .is_err()
+        );
+        let mut qa = StageSetting::new(PipelineStage::Qa);
+        qa.plugin = Some("acme.fixer".into());
+        assert!(stages(vec![qa]).validate().is_err());
+    }
+}
@@ -0,0 +1,64 @@
+//! Rolling throughput and ETA of long jobs.
+//!
+//! Throughput is an exponential moving average over time: each progress report blends the
+//! rate since the previous report in with a weight that grows with the time elapsed, so a
+//! burst after a stall neither dominates nor disappears.
+
+/// Time over which older samples lose most of their weight.
+pub const THROUGHPUT_WINDOW_SECS: f64 = 30.0;
+
+/// Blends the rate of `processed_delta` units over `elapsed_secs` into `previous`.
+/// Returns `previous` unchanged when no time passed or the counter went backwards.
+pub fn rolling_throughput(
+    previous: Option<f64>,
+    processed_delta: i64,
+    elapsed_secs: f64,
+) -> Option<f64> {
+    if elapsed_secs <= 0.0 || processed_delta < 0 {
+        return previous;
+    }
+    let sample = processed_delta as f64 / elapsed_secs;
+    Some(match previous {
+        Some(previous) => {
+            let weight = 1.0 - (-elapsed_secs / THROUGHPUT_WINDOW_SECS).exp();
+            previous + weight * (sample - previous)
+        }
+        None => sample,
+    })
+}
+
+/// Seconds left at `units_per_second`, or `None` without a total or a positive rate.
+pub fn eta_secs(processed: i64, total: Option<i64>, units_per_second: Option<f64>) -> Option<u64> {
+    let remaining = total?.saturating_sub(processed).max(0);
+    if remaining == 0 {
+        return Some(0);
+    }
+    let rate = units_per_second.filter(|rate| *rate > 0.0)?;
+    Some((remaining as f64 / rate).ceil() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn smooths_throughput_and_estimates_remaining_time() {
+        let first = rolling_throughput(None, 10, 1.0);
+        assert_eq!(first, Some(10.0));
+
+        // A short fast burst moves the average only a little.
+        let burst = rolling_throughput(first, 100, 1.0).unwrap();
+        assert!(burst > 10.0 && burst < 20.0);
+        // A long slow stretch takes over most of it.
+        let slow = rolling_throughput(Some(burst), 60, 120.0).unwrap();
+        assert!((slow - 0.5).abs() < 0.5);
+
+        assert_eq!(rolling_throughput(first, 5, 0.0), first);
+        assert_eq!(rolling_throughput(first, -5, 2.0), first);
+
+        assert_eq!(eta_secs(40, Some(100), Some(4.0)), Some(15));
+        assert_eq!(eta_secs(100, Some(100), None), Some(0));
+        assert_eq!(eta_secs(40, None, Some(4.0)), None);
+        assert_eq!(eta_secs(40, Some(100), Some(0.0)), None);
+    }
+}
@@ -0,0 +1,245 @@
+//! Language asset packages: the translation memory, termbases, QA profiles and workspace
+//! segmentation rules of an installation in one archive, for setting up a new workstation
+//! or sharing assets across a team.
+//!
+//! A package is a ZIP with an `assets.json` manifest and one JSON file per kind of asset.
+//! The segmentation rules are the global segmentation exceptions, which are written into the
+//! SRX rules handed to the converter; project exceptions stay with their projects.
+
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use zip::ZipArchive;
+use zip::result::ZipError;
+
+use crate::delivery::{PackageEntry, PackageSource};
+use crate::qa::QaProfileFile;
+
+/// Extension of language asset packages.
+pub const LANGUAGE_ASSETS_EXTENSION: &str = "wegassets";
+pub const MANIFEST_NAME: &str = "assets.json";
+/// Manifest format written by this version; newer packages are refused.
+pub const FORMAT_VERSION: u32 = 1;
+const TRANSLATION_MEMORY_NAME: &str = "translation_memory.json";
+const TERMBASES_NAME: &str = "termbases.json";
+const QA_PROFILES_NAME: &str = "qa_profiles.json";
+const SEGMENTATION_RULES_NAME: &str = "segmentation_rules.json";
+/// Largest file read from a package.
+const MAX_ENTRY_BYTES: u64 = 1 << 30;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LanguageAssetsManifest {
+    pub format_version: u32,
+    pub exported_at: String,
+    pub translation_memory_entries: usize,
+    pub termbase_entries: usize,
+    pub qa_profiles: usize,
+    pub segmentation_rules: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranslationMemoryAsset {
+    pub source_lang: String,
+    pub target_lang: String,
+    pub source_text: String,
+    pub target_text: String,
+    pub origin: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TermbaseAsset {
+    pub termbase: String,
+    pub source_lang: String,
+    pub target_lang: String,
+    pub source_term: String,
+    /// Empty for a term without an equivalent.
+    #[serde(default)]
+    pub target_term: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SegmentationRuleAsset {
+    pub language: String,
+    /// `abbreviation`, `no_break` or `break`.
+    pub kind: String,
+    pub before_text: String,
+    #[serde(default)]
+    pub after_text: String,
+}
+
+/// The contents of a package.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LanguageAssets {
+    pub translation_memory: Vec<TranslationMemoryAsset>,
+    pub termbases: Vec<TermbaseAsset>,
+    pub qa_profiles: Vec<QaProfileFile>,
+    pub segmentation_rules: Vec<SegmentationRuleAsset>,
+}
+
+impl LanguageAssets {
+    pub fn manifest(&self, exported_at: String) -> LanguageAssetsManifest {
+        LanguageAssetsManifest {
+            format_version: FORMAT_VERSION,
+            exported_at,
+            translation_memory_entries: self.translation_memory.len(),
+            termbase_entries: self.termbases.len(),
+            qa_profiles: self.qa_profiles.len(),
+            segmentation_rules: self.segmentation_rules.len(),
+        }
+    }
+}
+
+/// Archive entries of a package: the manifest, then one file per kind of asset.
+pub fn package_entries(
+    manifest: &LanguageAssetsManifest,
+    assets: &LanguageAssets,
+) -> Result<Vec<PackageEntry>> {
+    Ok(vec![
+        json_entry(MANIFEST_NAME, manifest)?,
+        json_entry(TRANSLATION_MEMORY_NAME, &assets.translation_memory)?,
+        json_entry(TERMBASES_NAME, &assets.termbases)?,
+        json_entry(QA_PROFILES_NAME, &assets.qa_profiles)?,
+        json_entry(SEGMENTATION_RULES_NAME, &assets.segmentation_rules)?,
+    ])
+}
+
+/// Reads the package at `path`. Every file must hold as many assets as the manifest lists;
+/// a file may be left out when it would be empty.
+pub fn read_package(path: &Path) -> Result<(LanguageAssetsManifest, LanguageAssets)> {
+    let file =
+        File::open(path).with_context(|| format!("{} could not be opened", path.display()))?;
+    let mut archive = ZipArchive::new(file).context("the file is not a language asset package")?;
+    let manifest: LanguageAssetsManifest =
+        read_entry(&mut archive, MANIFEST_NAME)?.context("the package has no manifest")?;
+    if manifest.format_version > FORMAT_VERSION {
+        bail!(
+            "the package was written by a newer version of the app (format {})",
+            manifest.format_version
+        );
+    }
+    let assets = LanguageAssets {
+        translation_memory: read_section(
+            &mut archive,
+            TRANSLATION_MEMORY_NAME,
+            manifest.translation_memory_entries,
+        )?,
+        termbases: read_section(&mut archive, TERMBASES_NAME, manifest.termbase_entries)?,
+        qa_profiles: read_section(&mut archive, QA_PROFILES_NAME, manifest.qa_profiles)?,
+        segmentation_rules: read_section(
+            &mut archive,
+            SEGMENTATION_RULES_NAME,
+            manifest.segmentation_rules,
+        )?,
+    };
+    Ok((manifest, assets))
+}
+
+fn json_entry<T: Serialize + ?Sized>(name: &str, value: &T) -> Result<PackageEntry> {
+    Ok(PackageEntry {
+        name: name.into(),
+        source: PackageSource::Contents(
+            serde_json::to_vec_pretty(value)
+                .with_context(|| format!("'{name}' could not be written"))?,
+        ),
+    })
+}
+
+fn read_section<T: DeserializeOwned>(
+    archive: &mut ZipArchive<File>,
+    name: &str,
+    expected: usize,
+) -> Result<Vec<T>> {
+    let items: Vec<T> = read_entry(archive, name)?.unwrap_or_default();
+    if items.len() != expected {
+        bail!(
+            "'{name}' holds {} entries but the manifest lists {expected}",
+            items.len()
+        );
+    }
+    Ok(items)
+}
+
+fn read_entry<T: DeserializeOwned>(
+    archive: &mut ZipArchive<File>,
+    name: &str,
+) -> Result<Option<T>> {
+    let entry = match archive.by_name(name) {
+        Ok(entry) => entry,
+        Err(ZipError::FileNotFound) => return Ok(None),
+        Err(error) => return Err(error).with_context(|| format!("'{name}' could not be read")),
+    };
+    if entry.size() > MAX_ENTRY_BYTES {
+        bail!("'{name}' is too large to import");
+    }
+    let mut bytes = Vec::with_capacity(entry.size() as usize);
+    entry
+        .take(MAX_ENTRY_BYTES)
+        .read_to_end(&mut bytes)
+        .with_context(|| format!("'{name}' could not be read"))?;
+    serde_json::from_slice(&bytes)
+        .map(Some)
+        .with_context(|| format!("'{name}' is not valid"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::delivery::write_package;
+
+    #[test]
+    fn packages_round_trip_and_must_match_their_manifest() {
+        let dir = tempfile::tempdir().unwrap();
+        let assets = LanguageAssets {
+            translation_memory: vec![TranslationMemoryAsset {
+                source_lang: "en".into(),
+                target_lang: "de".into(),
+                source_text: "Save".into(),
+                target_text: "Speichern".into(),
+                origin: "editor".into(),
+            }],
+            termbases: vec![TermbaseAsset {
+                termbase: "Product".into(),
+                source_lang: "en".into(),
+                target_lang: "de".into(),
+                source_term: "widget".into(),
+                target_term: "Widget".into(),
+            }],
+            qa_profiles: Vec::new(),
+            segmentation_rules: vec![SegmentationRuleAsset {
+                language: "de".into(),
+                kind: "abbreviation".into(),
+                before_text: "z.B.".into(),
+                after_text: String::new(),
+            }],
+        };
+        let manifest = assets.manifest("2026-10-15T09:00:00Z".into());
+
+        let package = dir
+            .path()
+            .join(format!("assets.{LANGUAGE_ASSETS_EXTENSION}"));
+        write_package(&package, &package_entries(&manifest, &assets).unwrap()).unwrap();
+        assert_eq!(read_package(&package).unwrap(), (manifest.clone(), assets));
+
+        // A package whose files disagree with its manifest is refused.
+        let short = dir.path().join("short.wegassets");
+        let entries = package_entries(&manifest, &LanguageAssets::default()).unwrap();
+        write_package(&short, &entries).unwrap();
+        assert!(read_package(&short).is_err());
+
+        let newer = dir.path().join("newer.wegassets");
+        let future = LanguageAssetsManifest {
+            format_version: FORMAT_VERSION + 1,
+            ..LanguageAssets::default().manifest("2026-10-15T09:00:00Z".into())
+        };
+        write_package(&newer, &[json_entry(MANIFEST_NAME, &future).unwrap()]).unwrap();
+        assert!(read_package(&newer).is_err());
+    }
+}
@@ -0,0 +1,347 @@
+//! Language detection for samples of segment text, used to catch projects whose language
+//! pair was entered the wrong way round.
+//!
+//! Detection first looks at the writing system: Greek, Hebrew, Arabic, Thai, Devanagari,
+//! Hangul and kana each point to one language, Han without kana to Chinese, and Cyrillic to
+//! Ukrainian or Russian depending on the letters used. Latin text is told apart by its
+//! function words, each weighted by how few of the known languages share it. Samples are
+//! short and technical, so the detector answers `None` rather than guess when the evidence
+//! is thin.
+
+use std::collections::HashMap;
+
+use crate::jliff::restructure::placeholder_spans;
+
+/// Fewest letters a sample needs before anything is detected.
+const MIN_LETTERS: usize = 40;
+/// Fewest function words a Latin-script sample needs.
+const MIN_FUNCTION_WORDS: usize = 5;
+/// Lowest confidence at which a detection is used to judge a language pair.
+pub const MIN_CONFIDENCE: f64 = 0.6;
+
+/// Function words of the Latin-script languages the detector knows.
+const LATIN_PROFILES: &[(&str, &[&str])] = &[
+    (
+        "en",
+        &[
+            "the", "and", "of", "to", "is", "are", "in", "for", "with", "this", "that", "you",
+            "your", "on", "be", "it", "not", "or", "from", "will", "can", "by", "an", "as",
+        ],
+    ),
+    (
+        "de",
+        &[
+            "der", "die", "das", "und", "ist", "nicht", "mit", "sie", "ein", "eine", "den", "dem",
+            "zu", "von", "für", "auf", "wird", "werden", "auch", "sich", "oder", "des", "im",
+            "bei",
+        ],
+    ),
+    (
+        "fr",
+        &[
+            "le", "la", "les", "des", "et", "est", "une", "un", "du", "pour", "dans", "que", "qui",
+            "pas", "sur", "avec", "vous", "au", "ce", "sont", "ne", "par",
+        ],
+    ),
+    (
+        "es",
+        &[
+            "el", "la", "los", "las", "y", "es", "en", "de", "que", "una", "un", "para", "con",
+            "por", "del", "se", "no", "su", "al", "como", "está",
+        ],
+    ),
+    (
+        "it",
+        &[
+            "il", "la", "di", "che", "e", "è", "un", "una", "per", "non", "con", "del", "della",
+            "le", "gli", "sono", "si", "da", "nel", "alla",
+        ],
+    ),
+    (
+        "pt",
+        &[
+            "o", "a", "os", "as", "de", "que", "e", "é", "um", "uma", "para", "com", "não", "do",
+            "da", "dos", "em", "no", "na", "por", "ao", "se",
+        ],
+    ),
+    (
+        "nl",
+        &[
+            "de", "het", "een", "en", "van", "is", "dat", "niet", "op", "te", "met", "voor",
+            "zijn", "wordt", "worden", "ook", "er", "aan", "bij",
+        ],
+    ),
+    (
+        "pl",
+        &[
+            "i", "w", "z", "na", "nie", "się", "jest", "do", "że", "to", "o", "jak", "dla", "od",
+            "są", "przez", "oraz", "lub", "ten", "być",
+        ],
+    ),
+    (
+        "sv",
+        &[
+            "och", "att", "det", "är", "en", "ett", "som", "på", "för", "med", "inte", "av",
+            "till", "den", "om", "har", "kan", "du", "de",
+        ],
+    ),
+    (
+        "cs",
+        &[
+            "a", "je", "se", "na", "v", "že", "to", "s", "z", "do", "pro", "jsou", "není", "jako",
+            "by", "ve", "od", "které", "nebo",
+        ],
+    ),
+];
+
+/// Languages identified by their writing system alone.
+const SCRIPT_LANGUAGES: &[&str] = &["el", "he", "ar", "th", "hi", "ko", "ja", "zh", "ru", "uk"];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Detection {
+    /// Primary language subtag, e.g. `de`.
+    pub language: &'static str,
+    /// Share of the evidence pointing to `language`, from 0 to 1.
+    pub confidence: f64,
+}
+
+/// How the detected language of a sample of source text relates to a language pair.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PairDirection {
+    /// The sample is in the source language.
+    Consistent,
+    /// The sample is in the target language: the pair is most likely reversed.
+    Reversed,
+    /// The sample is in a language that is neither the source nor the target.
+    Unexpected,
+    /// Too little text, or a language the detector does not know.
+    Undetermined,
+}
+
+/// Whether the detector can recognise a language (by its primary subtag).
+pub fn is_supported(language: &str) -> bool {
+    let primary = primary_subtag(language);
+    SCRIPT_LANGUAGES.contains(&primary.as_str())
+        || LATIN_PROFILES.iter().any(|(code, _)| *code == primary)
+}
+
+/// Detects the language of a sample made of several texts.
+pub fn detect<S: AsRef<str>>(texts: &[S]) -> Option<Detection> {
+    let mut scripts: HashMap<Script, usize> = HashMap::new();
+    let mut words: Vec<String> = Vec::new();
+    let mut cyrillic_ukrainian = 0;
+    for text in texts {
+        let text = without_placeholders(text.as_ref());
+        for ch in text.chars().filter(|ch| ch.is_alphabetic()) {
+            *scripts.entry(script_of(ch)).or_default() += 1;
+            if matches!(ch, 'і' | 'ї' | 'є' | 'ґ' | 'І' | 'Ї' | 'Є' | 'Ґ') {
+                cyrillic_ukrainian += 1;
+            }
+        }
+        words.extend(
+            text.split(|ch: char| !ch.is_alphabetic())
+                .filter(|word| !word.is_empty())
+                .map(str::to_lowercase),
+        );
+    }
+
+    let letters: usize = scripts.values().sum();
+    if letters < MIN_LETTERS {
+        return None;
+    }
+    let share = |script: Script| scripts.get(&script).copied().unwrap_or(0) as f64 / letters as f64;
+    let (&dominant, _) = scripts
+        .iter()
+        .max_by_key(|(script, count)| (**count, **script))?;
+    let by_script = |language| {
+        Some(Detection {
+            language,
+            confidence: share(dominant),
+        })
+    };
+    match dominant {
+        Script::Latin => detect_latin(&words),
+        Script::Other => None,
+        Script::Greek => by_script("el"),
+        Script::Hebrew => by_script("he"),
+        Script::Arabic => by_script("ar"),
+        Script::Thai => by_script("th"),
+        Script::Devanagari => by_script("hi"),
+        Script::Hangul => by_script("ko"),
+        // Japanese mixes kana with kanji; Chinese has no kana.
+        Script::Han | Script::Kana if share(Script::Kana) >= 0.1 => Some(Detection {
+            language: "ja",
+            confidence: share(Script::Han) + share(Script::Kana),
+        }),
+        Script::Han | Script::Kana => by_script("zh"),
+        Script::Cyrillic if cyrillic_ukrainian * 100 >= scripts[&Script::Cyrillic] => {
+            by_script("uk")
+        }
+        Script::Cyrillic => by_script("ru"),
+    }
+}
+
+/// Judges a language pair against a sample of its source text.
+pub fn check_direction<S: AsRef<str>>(
+    sources: &[S],
+    source_lang: &str,
+    target_lang: &str,
+) -> (Option<Detection>, PairDirection) {
+    let detection = detect(sources);
+    let source = primary_subtag(source_lang);
+    let target = primary_subtag(target_lang);
+    let direction = match detection {
+        Some(detection) if detection.confidence >= MIN_CONFIDENCE => {
+            if detection.language == source {
+                PairDirection::Consistent
+            } else if detection.language == target {
+                PairDirection::Reversed
+            } else if is_supported(&source) {
+                PairDirection::Unexpected
+            } else {
+                PairDirection::Undetermined
+            }
+        }
+        _ => PairDirection::Undetermined,
+    };
+    (detection, direction)
+}
+
+fn detect_latin(words: &[String]) -> Option<Detection> {
+    let mut sharing: HashMap<&str, usize> = HashMap::new();
+    for (_, function_words) in LATIN_PROFILES {
+        for word in *function_words {
+            *sharing.entry(word).or_default() += 1;
+        }
+    }
+
+    let mut scores = vec![0.0; LATIN_PROFILES.len()];
+    let mut function_words = 0;
+    for word in words {
+        let Some(shared_by) = sharing.get(word.as_str()) else {
+            continue;
+        };
+        function_words += 1;
+        for (index, (_, profile)) in LATIN_PROFILES.iter().enumerate() {
+            if profile.contains(&word.as_str()) {
+                scores[index] += 1.0 / *shared_by as f64;
+            }
+        }
+    }
+    if function_words < MIN_FUNCTION_WORDS {
+        return None;
+    }
+    let total: f64 = scores.iter().sum();
+    let (best, score) = scores
+        .iter()
+        .enumerate()
+        .max_by(|(_, left), (_, right)| left.total_cmp(right))?;
+    Some(Detection {
+        language: LATIN_PROFILES[best].0,
+        confidence: score / total,
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum Script {
+    Latin,
+    Cyrillic,
+    Greek,
+    Hebrew,
+    Arabic,
+    Thai,
+    Devanagari,
+    Hangul,
+    Kana,
+    Han,
+    Other,
+}
+
+fn script_of(ch: char) -> Script {
+    match ch as u32 {
+        0x0041..=0x024F | 0x1E00..=0x1EFF => Script::Latin,
+        0x0400..=0x052F => Script::Cyrillic,
+        0x0370..=0x03FF | 0x1F00..=0x1FFF => Script::Greek,
+        0x0590..=0x05FF => Script::Hebrew,
+        0x0600..=0x06FF | 0x0750..=0x077F => Script::Arabic,
+        0x0E00..=0x0E7F => Script::Thai,
+        0x0900..=0x097F => Script::Devanagari,
+        0x1100..=0x11FF | 0x3130..=0x318F | 0xAC00..=0xD7AF => Script::Hangul,
+        0x3040..=0x30FF | 0x31F0..=0x31FF => Script::Kana,
+        0x4E00..=0x9FFF | 0x3400..=0x4DBF => Script::Han,
+        _ => Script::Other,
+    }
+}
+
+fn without_placeholders(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut cursor = 0;
+    for (start, end) in placeholder_spans(text) {
+        stripped.push_str(&text[cursor..start]);
+        stripped.push(' ');
+        cursor = end;
+    }
+    stripped.push_str(&text[cursor..]);
+    stripped
+}
+
+fn primary_subtag(language: &str) -> String {
+    language
+        .trim()
+        .split(['-', '_'])
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_reversed_language_pairs() {
+        let german = [
+            "Die Einstellungen werden gespeichert, wenn Sie auf {{ph:1}}OK{{ph:2}} klicken.",
+            "Der Drucker ist nicht mit dem Netzwerk verbunden.",
+            "Sie können die Datei auch später öffnen.",
+        ];
+        let english = [
+            "The settings are saved when you click {{ph:1}}OK{{ph:2}}.",
+            "The printer is not connected to the network.",
+            "You can also open the file later.",
+        ];
+        assert_eq!(detect(&german).map(|d| d.language), Some("de"));
+        assert_eq!(detect(&english).map(|d| d.language), Some("en"));
+        assert_eq!(
+            detect(&["Настройки сохраняются при нажатии кнопки ОК в окне принтера."])
+                .map(|d| d.language),
+            Some("ru")
+        );
+        assert_eq!(
+            detect(&[
+                "設定は、OKをクリックすると保存されます。",
+                "プリンターがネットワークに接続されていません。ファイルは後で開くこともできます。",
+            ])
+            .map(|d| d.language),
+            Some("ja")
+        );
+        assert_eq!(detect(&["OK"]), None);
+
+        assert_eq!(
+            check_direction(&german, "de-DE", "en-US").1,
+            PairDirection::Consistent
+        );
+        assert_eq!(
+            check_direction(&german, "en-US", "de-DE").1,
+            PairDirection::Reversed
+        );
+        assert_eq!(
+            check_direction(&german, "fr-FR", "it-IT").1,
+            PairDirection::Unexpected
+        );
+        assert_eq!(
+            check_direction(&german, "ca", "it").1,
+            PairDirection::Undetermined
+        );
+    }
+}
@@ -0,0 +1,263 @@
+//! In-process IPC metrics: latency, failure rate and request size per command.
+//!
+//! The invoke handler is wrapped with [`instrument_handler`], which counts every call and
+//! the size of its request payload before dispatching, so no command can be left out of the
+//! counters. Tauri does not expose the response of a command to the handler, so each
+//! command body additionally runs inside [`track`] (or [`track_sync`]) to record how long it
+//! took and whether it returned an error. Calls slower than [`SLOW_COMMAND_THRESHOLD`] are
+//! logged so slow IPC shows up in the support logs. Counters live for the process lifetime
+//! and are not persisted.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::io;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+use tauri::Runtime;
+use tauri::ipc::{Invoke, InvokeBody};
+
+/// Calls taking longer than this are logged as slow.
+pub const SLOW_COMMAND_THRESHOLD: Duration = Duration::from_millis(750);
+
+static IPC_METRICS: LazyLock<IpcMetrics> = LazyLock::new(IpcMetrics::default);
+
+/// Aggregated counters of one command.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommandMetrics {
+    pub command: String,
+    /// Calls dispatched by the invoke handler.
+    pub calls: u64,
+    pub failures: u64,
+    pub slow_calls: u64,
+    /// Calls whose duration was measured by [`track`]; the average latency is over these.
+    pub timed_calls: u64,
+    pub total_duration: Duration,
+    pub max_duration: Duration,
+    pub payload_bytes: u64,
+}
+
+#[derive(Debug, Default)]
+pub struct IpcMetrics {
+    commands: Mutex<HashMap<String, CommandMetrics>>,
+}
+
+impl IpcMetrics {
+    /// Counts a dispatched call and the size of its request payload.
+    pub fn record_call(&self, command: &str, payload_bytes: u64) {
+        self.update(command, |entry| {
+            entry.calls += 1;
+            entry.payload_bytes += payload_bytes;
+        });
+    }
+
+    /// Counts a call no command handled, e.g. an unknown command name.
+    pub fn record_unhandled(&self, command: &str) {
+        self.update(command, |entry| entry.failures += 1);
+    }
+
+    /// Records how long a command body took and whether it failed.
+    pub fn record_completion(&self, command: &str, elapsed: Duration, failed: bool) {
+        let slow = elapsed >= SLOW_COMMAND_THRESHOLD;
+        self.update(command, |entry| {
+            entry.timed_calls += 1;
+            entry.failures += u64::from(failed);
+            entry.slow_calls += u64::from(slow);
+            entry.total_duration += elapsed;
+            entry.max_duration = entry.max_duration.max(elapsed);
+        });
+        if slow {
+            log::warn!(
+                target: "metrics::ipc",
+                "{}",
+                serde_json::json!({
+                    "event": "ipc.slow_command",
+                    "command": command,
+                    "durationMs": elapsed.as_millis() as u64,
+                    "failed": failed
+                })
+                .to_string()
+            );
+        }
+    }
+
+    /// Counters of every command seen so far, slowest in total first.
+    pub fn snapshot(&self) -> Vec<CommandMetrics> {
+        let mut metrics: Vec<CommandMetrics> = self
+            .commands
+            .lock()
+            .expect("ipc metrics poisoned")
+            .values()
+            .cloned()
+            .collect();
+        metrics.sort_by(|a, b| {
+            b.total_duration
+                .cmp(&a.total_duration)
+                .then_with(|| a.command.cmp(&b.command))
+        });
+        metrics
+    }
+
+    pub fn reset(&self) {
+        self.commands.lock().expect("ipc metrics poisoned").clear();
+    }
+
+    fn update(&self, command: &str, apply: impl FnOnce(&mut CommandMetrics)) {
+        let mut commands = self.commands.lock().expect("ipc metrics poisoned");
+        let entry = commands
+            .entry(command.to_string())
+            .or_insert_with(|| CommandMetrics {
+                command: command.to_string(),
+                ..CommandMetrics::default()
+            });
+        apply(entry);
+    }
+}
+
+/// The process-wide metrics all commands record into.
+pub fn ipc_metrics() -> &'static IpcMetrics {
+    &IPC_METRICS
+}
+
+/// Runs a command body, recording its duration and whether it failed.
+pub async fn track<F, T, E>(command: &'static str, future: F) -> Result<T, E>
+where
+    F: Future<Output = Result<T, E>>,
+{
+    let started = Instant::now();
+    let result = future.await;
+    ipc_metrics().record_completion(command, started.elapsed(), result.is_err());
+    result
+}
+
+/// Synchronous counterpart of [`track`].
+pub fn track_sync<T, E>(
+    command: &'static str,
+    body: impl FnOnce() -> Result<T, E>,
+) -> Result<T, E> {
+    let started = Instant::now();
+    let result = body();
+    ipc_metrics().record_completion(command, started.elapsed(), result.is_err());
+    result
+}
+
+/// Wraps the handler built by `tauri::generate_handler!` so every call and its request
+/// payload size are recorded before the command is dispatched.
+pub fn instrument_handler<R, H>(handler: H) -> impl Fn(Invoke<R>) -> bool + Send + Sync + 'static
+where
+    R: Runtime,
+    H: Fn(Invoke<R>) -> bool + Send + Sync + 'static,
+{
+    move |invoke| {
+        let command = invoke.message.command().to_string();
+        ipc_metrics().record_call(&command, payload_size(invoke.message.payload()));
+        let handled = handler(invoke);
+        if !handled {
+            ipc_metrics().record_unhandled(&command);
+        }
+        handled
+    }
+}
+
+fn payload_size(body: &InvokeBody) -> u64 {
+    match body {
+        InvokeBody::Json(value) => {
+            let mut counter = ByteCounter(0);
+            // Serialising a `Value` into a writer that never fails cannot fail either.
+            let _ = serde_json::to_writer(&mut counter, value);
+            counter.0
+        }
+        InvokeBody::Raw(bytes) => bytes.len() as u64,
+    }
+}
+
+/// Writer that only counts what is written to it.
+struct ByteCounter(u64);
+
+impl io::Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0 += buf.len() as u64;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aggregates_calls_failures_and_payloads() {
+        let metrics = IpcMetrics::default();
+        metrics.record_call("get_segments_v2", 120);
+        metrics.record_call("get_segments_v2", 80);
+        metrics.record_call("get_segments_v2", 0);
+        metrics.record_completion("get_segments_v2", Duration::from_millis(10), false);
+        metrics.record_completion("get_segments_v2", Duration::from_millis(30), true);
+        metrics.record_completion("get_segments_v2", SLOW_COMMAND_THRESHOLD, false);
+
+        let snapshot = metrics.snapshot();
+        assert_eq!(snapshot.len(), 1);
+        let entry = &snapshot[0];
+        assert_eq!(entry.calls, 3);
+        assert_eq!(entry.failures, 1);
+        assert_eq!(entry.slow_calls, 1);
+        assert_eq!(entry.max_duration, SLOW_COMMAND_THRESHOLD);
+        assert_eq!(
+            entry.total_duration,
+            Duration::from_millis(40) + SLOW_COMMAND_THRESHOLD
+        );
+        assert_eq!(entry.timed_calls, 3);
+        assert_eq!(entry.payload_bytes, 200);
+    }
+
+    #[test]
+    fn counts_calls_that_are_not_timed() {
+        let metrics = IpcMetrics::default();
+        metrics.record_call("untracked_command", 2);
+        metrics.record_call("missing_command", 2);
+        metrics.record_unhandled("missing_command");
+
+        let snapshot = metrics.snapshot();
+        let untracked = snapshot
+            .iter()
+            .find(|e| e.command == "untracked_command")
+            .unwrap();
+        assert_eq!(
+            (untracked.calls, untracked.timed_calls, untracked.failures),
+            (1, 0, 0)
+        );
+        let missing = snapshot
+            .iter()
+            .find(|e| e.command == "missing_command")
+            .unwrap();
+        assert_eq!((missing.calls, missing.failures), (1, 1));
+    }
+
+    #[test]
+    fn snapshot_orders_by_total_duration() {
+        let metrics = IpcMetrics::default();
+        metrics.record_completion("fast", Duration::from_millis(1), false);
+        metrics.record_completion("slow", Duration::from_millis(50), false);
+        let order: Vec<String> = metrics
+            .snapshot()
+            .into_iter()
+            .map(|entry| entry.command)
+            .collect();
+        assert_eq!(order, ["slow", "fast"]);
+
+        metrics.reset();
+        assert!(metrics.snapshot().is_empty());
+    }
+
+    #[test]
+    fn measures_json_payload_size() {
+        let value = serde_json::json!({ "projectId": "abc" });
+        let expected = serde_json::to_vec(&value).unwrap().len() as u64;
+        assert_eq!(payload_size(&InvokeBody::Json(value)), expected);
+        assert_eq!(payload_size(&InvokeBody::Raw(vec![0; 7])), 7);
+    }
+}
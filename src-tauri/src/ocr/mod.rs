@@ -0,0 +1,183 @@
+//! Text extraction from images through an external OCR engine.
+//!
+//! The engine is a Tesseract-compatible command line: it is run as
+//! `<command> <image> stdout -l <languages>` and its standard output is taken as the
+//! recognized text. Project language tags are turned into Tesseract language codes so the
+//! engine loads the right trained data.
+
+use std::path::Path;
+use std::process::Stdio;
+use std::time::Duration;
+
+use thiserror::Error;
+use tokio::process::Command;
+
+/// Longest an engine may work on a single image.
+const OCR_TIMEOUT: Duration = Duration::from_secs(120);
+/// Language used when none of the hints has a known Tesseract code.
+const FALLBACK_LANGUAGE: &str = "eng";
+
+/// Errors raised while running the OCR engine.
+#[derive(Debug, Error)]
+pub enum OcrError {
+    #[error("the OCR engine '{0}' could not be started: {1}")]
+    Unavailable(String, String),
+    #[error("the OCR engine did not finish within {} seconds", OCR_TIMEOUT.as_secs())]
+    Timeout,
+    #[error("the OCR engine failed: {0}")]
+    Failed(String),
+}
+
+/// Tesseract codes for BCP 47 language tags, deduplicated in hint order. Script subtags
+/// pick the Chinese and Serbian variants; unknown languages are skipped and English is
+/// used when nothing is left.
+pub fn tesseract_languages<'a>(tags: impl IntoIterator<Item = &'a str>) -> Vec<&'static str> {
+    let mut codes = Vec::new();
+    for tag in tags {
+        let normalized = tag.trim().replace('_', "-").to_ascii_lowercase();
+        let mut parts = normalized.split('-');
+        let primary = parts.next().unwrap_or_default();
+        let rest: Vec<&str> = parts.collect();
+        let code = match primary {
+            "zh" if rest
+                .iter()
+                .any(|part| matches!(*part, "hant" | "tw" | "hk" | "mo")) =>
+            {
+                Some("chi_tra")
+            }
+            "zh" => Some("chi_sim"),
+            "sr" if rest.contains(&"latn") => Some("srp_latn"),
+            other => iso_639_2(other),
+        };
+        if let Some(code) = code
+            && !codes.contains(&code)
+        {
+            codes.push(code);
+        }
+    }
+    if codes.is_empty() {
+        codes.push(FALLBACK_LANGUAGE);
+    }
+    codes
+}
+
+fn iso_639_2(primary: &str) -> Option<&'static str> {
+    let code = match primary {
+        "ar" => "ara",
+        "bg" => "bul",
+        "ca" => "cat",
+        "cs" => "ces",
+        "da" => "dan",
+        "de" => "deu",
+        "el" => "ell",
+        "en" => "eng",
+        "es" => "spa",
+        "et" => "est",
+        "fi" => "fin",
+        "fr" => "fra",
+        "he" => "heb",
+        "hi" => "hin",
+        "hr" => "hrv",
+        "hu" => "hun",
+        "id" => "ind",
+        "it" => "ita",
+        "ja" => "jpn",
+        "ko" => "kor",
+        "lt" => "lit",
+        "lv" => "lav",
+        "nb" | "no" => "nor",
+        "nl" => "nld",
+        "pl" => "pol",
+        "pt" => "por",
+        "ro" => "ron",
+        "ru" => "rus",
+        "sk" => "slk",
+        "sl" => "slv",
+        "sr" => "srp",
+        "sv" => "swe",
+        "th" => "tha",
+        "tr" => "tur",
+        "uk" => "ukr",
+        "vi" => "vie",
+        _ => return None,
+    };
+    Some(code)
+}
+
+/// Runs the engine on `image` and returns the recognized text, tidied by [`tidy_text`].
+pub async fn recognize(
+    command: &str,
+    image: &Path,
+    languages: &[&str],
+) -> Result<String, OcrError> {
+    let child = Command::new(command)
+        .arg(image)
+        .arg("stdout")
+        .arg("-l")
+        .arg(languages.join("+"))
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|error| OcrError::Unavailable(command.to_string(), error.to_string()))?;
+    let output = tokio::time::timeout(OCR_TIMEOUT, child.wait_with_output())
+        .await
+        .map_err(|_| OcrError::Timeout)?
+        .map_err(|error| OcrError::Failed(error.to_string()))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let message = stderr
+            .lines()
+            .map(str::trim)
+            .rfind(|line| !line.is_empty())
+            .map(str::to_string)
+            .unwrap_or_else(|| output.status.to_string());
+        return Err(OcrError::Failed(message));
+    }
+    Ok(tidy_text(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Drops page breaks and trailing spaces, and collapses runs of blank lines so paragraphs
+/// are separated by exactly one.
+pub fn tidy_text(raw: &str) -> String {
+    let mut text = String::with_capacity(raw.len());
+    let mut blank_run = false;
+    for line in raw.replace('\u{c}', "\n").lines() {
+        let line = line.trim_end();
+        if line.is_empty() {
+            blank_run = !text.is_empty();
+            continue;
+        }
+        if blank_run {
+            text.push('\n');
+            blank_run = false;
+        }
+        text.push_str(line);
+        text.push('\n');
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn language_tags_map_to_tesseract_codes() {
+        assert_eq!(
+            tesseract_languages(["de-DE", "en", "de-AT", "zh-Hant-TW", "sr-Latn", "xx"]),
+            vec!["deu", "eng", "chi_tra", "srp_latn"]
+        );
+        assert_eq!(tesseract_languages(["zh_CN"]), vec!["chi_sim"]);
+        assert_eq!(tesseract_languages(["tlh"]), vec!["eng"]);
+    }
+
+    #[test]
+    fn recognized_text_is_tidied() {
+        assert_eq!(
+            tidy_text("\n\nFirst line  \nsecond\n\n\n\nNext paragraph\n\u{c}"),
+            "First line\nsecond\n\nNext paragraph\n"
+        );
+    }
+}
@@ -0,0 +1,135 @@
+//! Publishes the domain events of the transactional outbox.
+//!
+//! State changes write their events to `event_outbox` in the same transaction (see
+//! `db::operations::outbox`). The dispatcher polls for due events, emits each one to the
+//! webview under its topic, POSTs it to the configured webhook, and only then marks it
+//! delivered. A failed publish is retried with exponential backoff, so subscribers may see
+//! an event more than once and should deduplicate by `eventId`. While the app is offline,
+//! events wait for it to come back online rather than using up their retries on the webhook.
+//! Once delivered, an event runs the automation scripts of its hook.
+
+use std::time::Duration;
+
+use anyhow::Context;
+use serde_json::{Value, json};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::db::DbManager;
+use crate::db::types::OutboxEventRecord;
+use crate::http::HttpClient;
+use crate::ipc::OfflineMode;
+use crate::ipc::commands::run_script_hooks;
+use crate::settings::SettingsManager;
+
+/// Pause between polls when the outbox is drained.
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+const BATCH_SIZE: i64 = 100;
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+const FIRST_RETRY_DELAY_SECS: i64 = 5;
+const MAX_RETRY_DELAY_SECS: i64 = 60 * 60;
+
+/// Starts the dispatcher in the background.
+pub fn start(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let client = HttpClient::new(|builder| builder.timeout(WEBHOOK_TIMEOUT));
+        loop {
+            let handled = match dispatch_due(&app, &client.current()).await {
+                Ok(handled) => handled,
+                Err(error) => {
+                    log::warn!(target: "outbox", "failed to dispatch events: {error:#}");
+                    0
+                }
+            };
+            // A full batch means more events are probably waiting.
+            if handled < BATCH_SIZE as usize {
+                tokio::time::sleep(POLL_INTERVAL).await;
+            }
+        }
+    });
+}
+
+/// Publishes the due events once; returns how many were attempted.
+async fn dispatch_due(app: &AppHandle, client: &reqwest::Client) -> anyhow::Result<usize> {
+    let webhook_url = app
+        .state::<SettingsManager>()
+        .current()
+        .await
+        .event_webhook_url;
+    if webhook_url.is_some() && app.state::<OfflineMode>().is_offline() {
+        return Ok(0);
+    }
+    let db = app.state::<DbManager>();
+    let events = db.list_due_outbox_events(BATCH_SIZE).await?;
+    if events.is_empty() {
+        return Ok(0);
+    }
+    for event in &events {
+        match publish(app, client, webhook_url.as_deref(), event).await {
+            Ok(()) => {
+                db.mark_outbox_event_delivered(event.event_id).await?;
+                if let Ok(payload) = serde_json::from_str::<Value>(&event.payload_json) {
+                    run_script_hooks(app, &event.topic, &payload).await;
+                }
+            }
+            Err(error) => {
+                let retry_in = retry_delay_secs(event.attempts);
+                log::warn!(
+                    target: "outbox",
+                    "failed to publish event {} ({}), retrying in {retry_in}s: {error:#}",
+                    event.event_id,
+                    event.topic
+                );
+                db.record_outbox_event_failure(event.event_id, &format!("{error:#}"), retry_in)
+                    .await?;
+            }
+        }
+    }
+    Ok(events.len())
+}
+
+async fn publish(
+    app: &AppHandle,
+    client: &reqwest::Client,
+    webhook_url: Option<&str>,
+    event: &OutboxEventRecord,
+) -> anyhow::Result<()> {
+    let payload: Value =
+        serde_json::from_str(&event.payload_json).context("the stored payload is not JSON")?;
+    app.emit(&event.topic, &payload)
+        .context("emitting to the webview failed")?;
+    if let Some(url) = webhook_url {
+        client
+            .post(url)
+            .json(&json!({
+                "eventId": event.event_id,
+                "topic": event.topic,
+                "createdAt": event.created_at,
+                "payload": payload,
+            }))
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .context("the webhook rejected the event")?;
+    }
+    Ok(())
+}
+
+/// Delay before the next attempt of an event that already failed `attempts` times.
+fn retry_delay_secs(attempts: i64) -> i64 {
+    (FIRST_RETRY_DELAY_SECS << attempts.clamp(0, 16)).min(MAX_RETRY_DELAY_SECS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backs_off_exponentially_up_to_an_hour() {
+        assert_eq!(retry_delay_secs(0), 5);
+        assert_eq!(retry_delay_secs(1), 10);
+        assert_eq!(retry_delay_secs(4), 80);
+        assert_eq!(retry_delay_secs(12), MAX_RETRY_DELAY_SECS);
+        assert_eq!(retry_delay_secs(1_000), MAX_RETRY_DELAY_SECS);
+    }
+}
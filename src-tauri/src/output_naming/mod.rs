@@ -0,0 +1,319 @@
+//! Output file names for converted documents.
+//!
+//! A template mixes literal text with placeholders: `{stem}` (the source file name without
+//! extension), `{pair}` (the language pair folder, e.g. `en-US_de-DE`), `{source}`,
+//! `{target}`, `{version}` (the XLIFF version), `{date}` (`YYYYMMDD`), `{uuid}` (the
+//! conversion identifier) and `{counter}` (or `{counter:N}`, zero-padded to N digits). The
+//! extension is added by the caller. Every template names either `{stem}` or `{uuid}`, so
+//! different files can get different names; when two outputs still collide, the counter
+//! goes up, or `-2`, `-3`, ... is appended when the template has no counter.
+//!
+//! Where the outputs go inside the `Translations` folder is set by a [`TranslationsLayout`].
+
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::{Result, bail};
+use time::OffsetDateTime;
+
+/// XLIFF files are named after their source file.
+pub const DEFAULT_XLIFF_NAME_TEMPLATE: &str = "{stem}";
+/// JLIFF documents are named after their conversion.
+pub const DEFAULT_JLIFF_NAME_TEMPLATE: &str = "{uuid}";
+
+/// Folder the source files and the outputs of every language pair are kept under.
+pub const TRANSLATIONS_DIR: &str = "Translations";
+
+/// Characters that cannot appear in a file name on at least one supported platform.
+const RESERVED_CHARS: [char; 9] = ['/', '\\', ':', '*', '?', '"', '<', '>', '|'];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Part {
+    Text(String),
+    Stem,
+    Pair,
+    Source,
+    Target,
+    Version,
+    Date,
+    Uuid,
+    Counter { width: usize },
+}
+
+/// Values the placeholders of a template are replaced with.
+#[derive(Debug, Clone)]
+pub struct NameValues<'a> {
+    pub stem: &'a str,
+    pub source_lang: &'a str,
+    pub target_lang: &'a str,
+    pub pair: &'a str,
+    pub version: &'a str,
+    pub uuid: &'a str,
+    pub date: OffsetDateTime,
+}
+
+/// A parsed output name template.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputNameTemplate {
+    parts: Vec<Part>,
+}
+
+impl OutputNameTemplate {
+    pub fn parse(template: &str) -> Result<Self> {
+        let mut parts = Vec::new();
+        let mut text = String::new();
+        let mut chars = template.trim().chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '{' => {
+                    let mut closed = false;
+                    let name: String = chars
+                        .by_ref()
+                        .take_while(|c| {
+                            closed = *c == '}';
+                            !closed
+                        })
+                        .collect();
+                    if !closed {
+                        bail!("The output name template has an unclosed '{{'.");
+                    }
+                    if !text.is_empty() {
+                        parts.push(Part::Text(std::mem::take(&mut text)));
+                    }
+                    parts.push(match name.as_str() {
+                        "stem" => Part::Stem,
+                        "pair" => Part::Pair,
+                        "source" => Part::Source,
+                        "target" => Part::Target,
+                        "version" => Part::Version,
+                        "date" => Part::Date,
+                        "uuid" => Part::Uuid,
+                        "counter" => Part::Counter { width: 1 },
+                        _ => match name.strip_prefix("counter:").map(str::parse::<usize>) {
+                            Some(Ok(width @ 1..=6)) => Part::Counter { width },
+                            _ => bail!("'{{{name}}}' is not an output name placeholder."),
+                        },
+                    });
+                }
+                '}' => bail!("The output name template has an unmatched '}}'."),
+                c if RESERVED_CHARS.contains(&c) || c.is_control() => {
+                    bail!("The output name template cannot contain '{c}'.")
+                }
+                _ => text.push(c),
+            }
+        }
+        if !text.is_empty() {
+            parts.push(Part::Text(text));
+        }
+
+        if !parts
+            .iter()
+            .any(|part| matches!(part, Part::Stem | Part::Uuid))
+        {
+            bail!("The output name template must contain {{stem}} or {{uuid}}.");
+        }
+        let counters = parts
+            .iter()
+            .filter(|part| matches!(part, Part::Counter { .. }))
+            .count();
+        if counters > 1 {
+            bail!("The output name template can contain {{counter}} only once.");
+        }
+        if matches!(parts.first(), Some(Part::Text(text)) if text.starts_with('.')) {
+            bail!("The output name template cannot start with '.'.");
+        }
+        Ok(Self { parts })
+    }
+
+    /// The first name, with `extension` added, that `is_taken` accepts.
+    pub fn unique_name(
+        &self,
+        values: &NameValues<'_>,
+        extension: &str,
+        mut is_taken: impl FnMut(&str) -> bool,
+    ) -> String {
+        let has_counter = self
+            .parts
+            .iter()
+            .any(|part| matches!(part, Part::Counter { .. }));
+        let mut counter = 1;
+        loop {
+            let mut name = self.render(values, counter);
+            if !has_counter && counter > 1 {
+                name.push_str(&format!("-{counter}"));
+            }
+            name.push('.');
+            name.push_str(extension);
+            if !is_taken(&name) {
+                return name;
+            }
+            counter += 1;
+        }
+    }
+
+    /// The name without extension, with `{counter}` set to `counter`.
+    pub fn render(&self, values: &NameValues<'_>, counter: usize) -> String {
+        let mut name = String::new();
+        for part in &self.parts {
+            match part {
+                Part::Text(text) => name.push_str(text),
+                Part::Stem => push_value(&mut name, values.stem),
+                Part::Pair => push_value(&mut name, values.pair),
+                Part::Source => push_value(&mut name, values.source_lang),
+                Part::Target => push_value(&mut name, values.target_lang),
+                Part::Version => push_value(&mut name, values.version),
+                Part::Uuid => push_value(&mut name, values.uuid),
+                Part::Date => name.push_str(&format!(
+                    "{:04}{:02}{:02}",
+                    values.date.year(),
+                    u8::from(values.date.month()),
+                    values.date.day()
+                )),
+                Part::Counter { width } => name.push_str(&format!("{counter:0width$}")),
+            }
+        }
+        name
+    }
+}
+
+/// How the outputs of each language pair are organized inside the `Translations` folder,
+/// relative to the folders the source files are stored in there.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum TranslationsLayout {
+    /// `Translations/<pair>/<source folders>/`.
+    #[default]
+    LanguageFirst,
+    /// `Translations/<source folders>/<pair>/`.
+    SourceFirst,
+}
+
+impl TranslationsLayout {
+    pub const ALL: [TranslationsLayout; 2] = [
+        TranslationsLayout::LanguageFirst,
+        TranslationsLayout::SourceFirst,
+    ];
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|layout| layout.as_str().eq_ignore_ascii_case(value.trim()))
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TranslationsLayout::LanguageFirst => "language_first",
+            TranslationsLayout::SourceFirst => "source_first",
+        }
+    }
+
+    /// Folder, relative to the project root, the outputs of the source file stored at
+    /// `stored_at` go to for the language pair folder `pair_dir`.
+    pub fn output_dir(self, stored_at: &Path, pair_dir: &str) -> PathBuf {
+        let mut folders: Vec<_> = stored_at
+            .parent()
+            .into_iter()
+            .flat_map(Path::components)
+            .filter_map(|component| match component {
+                Component::Normal(name) => Some(name),
+                _ => None,
+            })
+            .collect();
+        if folders
+            .first()
+            .is_some_and(|first| *first == TRANSLATIONS_DIR)
+        {
+            folders.remove(0);
+        }
+
+        let mut dir = PathBuf::from(TRANSLATIONS_DIR);
+        if self == TranslationsLayout::LanguageFirst {
+            dir.push(pair_dir);
+        }
+        dir.extend(folders);
+        if self == TranslationsLayout::SourceFirst {
+            dir.push(pair_dir);
+        }
+        dir
+    }
+}
+
+/// Appends a placeholder value with characters that are not allowed in file names replaced.
+fn push_value(name: &mut String, value: &str) {
+    name.extend(value.chars().map(|c| {
+        if RESERVED_CHARS.contains(&c) || c.is_control() {
+            '_'
+        } else {
+            c
+        }
+    }));
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn templates_render_placeholders_and_resolve_collisions() {
+        let values = NameValues {
+            stem: "manual: part 1",
+            source_lang: "en-US",
+            target_lang: "de-DE",
+            pair: "en-US_de-DE",
+            version: "2.1",
+            uuid: "6f1c",
+            date: OffsetDateTime::from_unix_timestamp(1_772_877_600).unwrap(),
+        };
+
+        let default = OutputNameTemplate::parse(DEFAULT_XLIFF_NAME_TEMPLATE).unwrap();
+        let mut taken: HashSet<String> = HashSet::new();
+        for expected in ["manual_ part 1.xlf", "manual_ part 1-2.xlf"] {
+            let name = default.unique_name(&values, "xlf", |name| taken.contains(name));
+            assert_eq!(name, expected);
+            taken.insert(name);
+        }
+
+        let dated =
+            OutputNameTemplate::parse("{date}_{stem}_{target}_v{version}_{counter:2}").unwrap();
+        assert_eq!(
+            dated.unique_name(&values, "xlf", |name| name.ends_with("_01.xlf")),
+            "20260307_manual_ part 1_de-DE_v2.1_02.xlf"
+        );
+
+        for invalid in [
+            "{pair}",
+            "{stem",
+            "{stem}/x",
+            "{stem}{counter}{counter}",
+            "{lang}",
+        ] {
+            assert!(OutputNameTemplate::parse(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn layouts_place_the_language_folder_around_the_source_folders() {
+        let nested = Path::new("Translations/manuals/2026/guide.docx");
+        assert_eq!(
+            TranslationsLayout::LanguageFirst.output_dir(nested, "en-US_de-DE"),
+            Path::new("Translations/en-US_de-DE/manuals/2026")
+        );
+        assert_eq!(
+            TranslationsLayout::SourceFirst.output_dir(nested, "en-US_de-DE"),
+            Path::new("Translations/manuals/2026/en-US_de-DE")
+        );
+
+        for layout in TranslationsLayout::ALL {
+            assert_eq!(
+                layout.output_dir(Path::new("Translations/guide.docx"), "en_fr"),
+                Path::new("Translations/en_fr")
+            );
+            assert_eq!(TranslationsLayout::parse(layout.as_str()), Some(layout));
+        }
+        assert_eq!(
+            TranslationsLayout::SourceFirst.output_dir(Path::new("../guide.docx"), "en_fr"),
+            Path::new("Translations/en_fr")
+        );
+        assert_eq!(TranslationsLayout::parse("by_date"), None);
+    }
+}
@@ -0,0 +1,683 @@
+//! Plugins: external programs that run as custom pipeline stages.
+//!
+//! A plugin is a folder with a `plugin.json` manifest naming its entry point, a WASM module
+//! run by the WASI runtime whose absolute path is set in the app settings, or a native
+//! executable. Nothing stops an executable from reading or sending anything the user can, so
+//! those only run once the user allows native plugins. The contract is JSON over stdio: the
+//! plugin reads one [`PluginRequest`] (a batch of JLIFF segments and its settings) from stdin
+//! and writes one [`PluginResponse`] with the targets it changed to stdout, then exits; a
+//! non-zero exit fails the batch with the last line of stderr.
+//!
+//! WASM plugins only see files through their data folder, `<app_folder>/plugins/<id>/`, which
+//! the runtime preopens as their only directory; they get no network access and no
+//! environment variables, and receive project data only over stdin. The runtime, like an
+//! executable, is started with a scrubbed environment in that folder.
+
+use std::collections::HashSet;
+use std::path::{Component, Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
+
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use thiserror::Error;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::process::Command;
+
+use crate::jliff::model::{JliffDocument, SegmentStatus};
+
+/// Manifest file at the root of a plugin folder.
+pub const PLUGIN_MANIFEST_FILE: &str = "plugin.json";
+/// Version of the stdio contract this build speaks.
+pub const PLUGIN_CONTRACT_VERSION: u32 = 1;
+/// Folder of the app folder holding the data folders of plugins.
+pub const PLUGINS_DIR: &str = "plugins";
+/// Segments sent to a plugin per run.
+pub const PLUGIN_BATCH_SIZE: usize = 200;
+
+const DEFAULT_TIMEOUT_SECS: u64 = 120;
+const MAX_TIMEOUT_SECS: u64 = 60 * 60;
+/// First bytes of every WASM binary module.
+const WASM_MAGIC: [u8; 4] = *b"\0asm";
+/// Largest response read from a plugin.
+const MAX_RESPONSE_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Errors raised while running a plugin.
+#[derive(Debug, Error)]
+pub enum PluginError {
+    #[error("the plugin '{0}' could not be started: {1}")]
+    Unavailable(String, String),
+    #[error("the plugin '{0}' did not answer within {1} seconds")]
+    Timeout(String, u64),
+    #[error("the plugin '{0}' failed: {1}")]
+    Failed(String, String),
+    #[error("the plugin '{0}' returned an invalid response: {1}")]
+    InvalidResponse(String, String),
+    #[error("failed to prepare the plugin's data folder: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// How the entry point is run. Executables are not sandboxed and need the user's opt-in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PluginRuntime {
+    Executable,
+    Wasm,
+}
+
+impl PluginRuntime {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PluginRuntime::Executable => "executable",
+            PluginRuntime::Wasm => "wasm",
+        }
+    }
+}
+
+/// Checks the WASI runtime path from the settings: an absolute path to an existing file, so
+/// which program runs plugins never depends on `PATH` or the environment.
+pub fn check_wasm_runtime(path: &Path) -> Result<()> {
+    if !path.is_absolute() {
+        bail!("The WASM runtime must be an absolute path.");
+    }
+    if !path.is_file() {
+        bail!("The WASM runtime {} does not exist.", path.display());
+    }
+    Ok(())
+}
+
+/// Type of a plugin setting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PluginSettingKind {
+    String,
+    Number,
+    Boolean,
+}
+
+impl PluginSettingKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PluginSettingKind::String => "string",
+            PluginSettingKind::Number => "number",
+            PluginSettingKind::Boolean => "boolean",
+        }
+    }
+
+    fn accepts(self, value: &Value) -> bool {
+        match self {
+            PluginSettingKind::String => value.is_string(),
+            PluginSettingKind::Number => value.is_number(),
+            PluginSettingKind::Boolean => value.is_boolean(),
+        }
+    }
+}
+
+/// A setting the plugin declares; values are stored per plugin and sent with every request.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginSettingSpec {
+    pub key: String,
+    pub label: String,
+    #[serde(rename = "type")]
+    pub kind: PluginSettingKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<Value>,
+    #[serde(default)]
+    pub required: bool,
+}
+
+/// Contents of `plugin.json`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginManifest {
+    /// Lowercase letters, digits, `.`, `-` and `_`, e.g. `acme.terminology-fixer`.
+    pub id: String,
+    pub name: String,
+    pub version: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Contract version the plugin implements.
+    pub contract: u32,
+    pub runtime: PluginRuntime,
+    /// Entry point, relative to the plugin folder.
+    pub entry: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Time one batch may take, [`DEFAULT_TIMEOUT_SECS`] when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
+    #[serde(default)]
+    pub settings: Vec<PluginSettingSpec>,
+}
+
+impl PluginManifest {
+    pub fn parse(json: &str) -> Result<Self> {
+        let manifest: PluginManifest =
+            serde_json::from_str(json).context("The plugin manifest is not valid JSON.")?;
+        let id = &manifest.id;
+        if id.is_empty()
+            || !id
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || "._-".contains(c))
+            || id.starts_with('.')
+        {
+            bail!("'{id}' is not a valid plugin id; use lowercase letters, digits, '.', '-', '_'.");
+        }
+        if manifest.name.trim().is_empty() {
+            bail!("The plugin '{id}' has no name.");
+        }
+        if manifest.contract != PLUGIN_CONTRACT_VERSION {
+            bail!(
+                "The plugin '{id}' implements contract {}; this version of the app runs \
+                 contract {PLUGIN_CONTRACT_VERSION}.",
+                manifest.contract
+            );
+        }
+        let entry = Path::new(&manifest.entry);
+        if manifest.entry.is_empty()
+            || !entry
+                .components()
+                .all(|component| matches!(component, Component::Normal(_)))
+        {
+            bail!("The entry of the plugin '{id}' must be a path inside the plugin folder.");
+        }
+        if manifest
+            .timeout_secs
+            .is_some_and(|secs| secs == 0 || secs > MAX_TIMEOUT_SECS)
+        {
+            bail!("timeoutSecs must be between 1 and {MAX_TIMEOUT_SECS}.");
+        }
+
+        let mut keys = HashSet::new();
+        for spec in &manifest.settings {
+            if spec.key.trim().is_empty() || !keys.insert(spec.key.as_str()) {
+                bail!("Setting keys of the plugin '{id}' must be unique and not empty.");
+            }
+            if let Some(default) = &spec.default
+                && !spec.kind.accepts(default)
+            {
+                bail!(
+                    "The default of the setting '{}' has the wrong type.",
+                    spec.key
+                );
+            }
+        }
+        Ok(manifest)
+    }
+
+    /// Reads the manifest of the plugin folder `dir`.
+    pub fn load(dir: &Path) -> Result<Self> {
+        let path = dir.join(PLUGIN_MANIFEST_FILE);
+        let json = std::fs::read_to_string(&path)
+            .with_context(|| format!("Cannot read {}.", path.display()))?;
+        Self::parse(&json)
+    }
+
+    /// Checks that the entry point in the plugin folder `dir` can be run: a WASM binary
+    /// module, or an executable when `native_allowed`.
+    pub fn check_entry(&self, dir: &Path, native_allowed: bool) -> Result<()> {
+        let id = &self.id;
+        let entry = dir.join(&self.entry);
+        if !entry.is_file() {
+            bail!(
+                "The entry '{}' of the plugin '{id}' does not exist.",
+                self.entry
+            );
+        }
+        match self.runtime {
+            PluginRuntime::Wasm => {
+                let mut magic = [0; 4];
+                let is_wasm = std::fs::File::open(&entry)
+                    .and_then(|mut file| std::io::Read::read_exact(&mut file, &mut magic))
+                    .is_ok_and(|()| magic == WASM_MAGIC);
+                if !is_wasm {
+                    bail!(
+                        "The entry '{}' of the plugin '{id}' is not a WASM module.",
+                        self.entry
+                    );
+                }
+            }
+            PluginRuntime::Executable if !native_allowed => {
+                bail!(
+                    "The plugin '{id}' is a native executable, which runs without a sandbox; \
+                     allow native plugins in the settings to use it."
+                );
+            }
+            PluginRuntime::Executable => {}
+        }
+        Ok(())
+    }
+
+    pub fn timeout(&self) -> Duration {
+        Duration::from_secs(self.timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS))
+    }
+
+    /// Checks stored or submitted setting values against the declared settings and fills
+    /// in defaults. Unknown keys are dropped when `lenient`, rejected otherwise.
+    pub fn resolve_settings(
+        &self,
+        values: &Map<String, Value>,
+        lenient: bool,
+    ) -> Result<Map<String, Value>> {
+        if !lenient && let Some(unknown) = values.keys().find(|key| self.setting(key).is_none()) {
+            bail!("The plugin '{}' has no setting '{unknown}'.", self.id);
+        }
+        let mut resolved = Map::new();
+        for spec in &self.settings {
+            match values.get(&spec.key).filter(|value| !value.is_null()) {
+                Some(value) if spec.kind.accepts(value) => {
+                    resolved.insert(spec.key.clone(), value.clone());
+                }
+                Some(_) if !lenient => {
+                    bail!(
+                        "The setting '{}' must be a {}.",
+                        spec.key,
+                        spec.kind.as_str()
+                    );
+                }
+                _ => match &spec.default {
+                    Some(default) => {
+                        resolved.insert(spec.key.clone(), default.clone());
+                    }
+                    None if spec.required && !lenient => {
+                        bail!("The setting '{}' is required.", spec.key);
+                    }
+                    None => {}
+                },
+            }
+        }
+        Ok(resolved)
+    }
+
+    fn setting(&self, key: &str) -> Option<&PluginSettingSpec> {
+        self.settings.iter().find(|spec| spec.key == key)
+    }
+}
+
+/// A segment as plugins see it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginSegment {
+    pub unit_id: String,
+    pub transunit_id: String,
+    pub source: String,
+    pub target: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+}
+
+/// What a plugin reads from stdin.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginRequest {
+    pub contract: u32,
+    pub plugin_id: String,
+    pub settings: Map<String, Value>,
+    pub source_language: String,
+    pub target_language: String,
+    /// Name of the source document.
+    pub file: String,
+    pub segments: Vec<PluginSegment>,
+}
+
+/// A target a plugin changed; `status` optionally moves the segment to another status.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginSegmentUpdate {
+    pub transunit_id: String,
+    pub target: String,
+    #[serde(default)]
+    pub status: Option<String>,
+}
+
+/// What a plugin writes to stdout. Segments it leaves alone may be omitted.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginResponse {
+    pub segments: Vec<PluginSegmentUpdate>,
+}
+
+/// A target changed by a plugin.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PluginEdit {
+    pub transunit_id: String,
+    pub previous_target: String,
+    pub new_target: String,
+}
+
+/// A registered plugin ready to run.
+#[derive(Debug, Clone)]
+pub struct PluginInstance {
+    pub manifest: PluginManifest,
+    /// Folder holding the manifest and entry point.
+    pub dir: PathBuf,
+    /// The plugin's working directory, the only folder it is meant to use.
+    pub data_dir: PathBuf,
+    pub settings: Map<String, Value>,
+    /// WASI runtime from the settings; WASM plugins cannot run without one.
+    pub wasm_runtime: Option<PathBuf>,
+    /// Whether the user allowed native executables.
+    pub native_allowed: bool,
+}
+
+impl PluginInstance {
+    /// Requests for every segment of `document`, [`PLUGIN_BATCH_SIZE`] segments each.
+    pub fn requests(&self, document: &JliffDocument) -> Vec<PluginRequest> {
+        let segments: Vec<PluginSegment> = document
+            .transunits
+            .iter()
+            .map(|unit| PluginSegment {
+                unit_id: unit.unit_id.clone(),
+                transunit_id: unit.transunit_id.clone(),
+                source: unit.source.clone(),
+                target: unit.effective_target().to_string(),
+                status: unit.status.map(|status| status.as_str().to_string()),
+            })
+            .collect();
+        segments
+            .chunks(PLUGIN_BATCH_SIZE)
+            .map(|batch| PluginRequest {
+                contract: PLUGIN_CONTRACT_VERSION,
+                plugin_id: self.manifest.id.clone(),
+                settings: self.settings.clone(),
+                source_language: document.source_language.clone(),
+                target_language: document.target_language.clone(),
+                file: document.file.clone(),
+                segments: batch.to_vec(),
+            })
+            .collect()
+    }
+
+    /// Runs the plugin on one request.
+    pub async fn run(&self, request: &PluginRequest) -> Result<PluginResponse, PluginError> {
+        let id = &self.manifest.id;
+        self.manifest
+            .check_entry(&self.dir, self.native_allowed)
+            .map_err(|error| PluginError::Unavailable(id.clone(), format!("{error:#}")))?;
+        let mut command = self
+            .command()
+            .map_err(|error| PluginError::Unavailable(id.clone(), format!("{error:#}")))?;
+        tokio::fs::create_dir_all(&self.data_dir).await?;
+        let input = serde_json::to_vec(request)
+            .map_err(|error| PluginError::Failed(id.clone(), error.to_string()))?;
+
+        let mut child = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|error| PluginError::Unavailable(id.clone(), error.to_string()))?;
+        let mut stdin = child.stdin.take().expect("stdin is piped");
+        let mut stdout = child.stdout.take().expect("stdout is piped");
+        let mut stderr = child.stderr.take().expect("stderr is piped");
+
+        let exchange = async {
+            // A plugin may answer before reading everything; its stdout is what counts.
+            let write = async {
+                let _ = stdin.write_all(&input).await;
+                drop(stdin);
+            };
+            let mut output = Vec::new();
+            let mut errors = Vec::new();
+            let read = (&mut stdout)
+                .take(MAX_RESPONSE_BYTES)
+                .read_to_end(&mut output);
+            let read_errors = (&mut stderr).take(1024 * 1024).read_to_end(&mut errors);
+            let ((), read, _) = tokio::join!(write, read, read_errors);
+            read?;
+            let status = child.wait().await?;
+            Ok::<_, std::io::Error>((status, output, errors))
+        };
+        let timeout = self.manifest.timeout();
+        let (status, output, errors) = tokio::time::timeout(timeout, exchange)
+            .await
+            .map_err(|_| PluginError::Timeout(id.clone(), timeout.as_secs()))?
+            .map_err(|error| PluginError::Failed(id.clone(), error.to_string()))?;
+
+        if !status.success() {
+            let errors = String::from_utf8_lossy(&errors);
+            let message = errors
+                .lines()
+                .map(str::trim)
+                .rfind(|line| !line.is_empty())
+                .map(str::to_string)
+                .unwrap_or_else(|| status.to_string());
+            return Err(PluginError::Failed(id.clone(), message));
+        }
+        serde_json::from_slice(&output)
+            .map_err(|error| PluginError::InvalidResponse(id.clone(), error.to_string()))
+    }
+
+    fn command(&self) -> Result<Command> {
+        let entry = self.dir.join(&self.manifest.entry);
+        let mut command = match self.manifest.runtime {
+            PluginRuntime::Executable => Command::new(&entry),
+            PluginRuntime::Wasm => {
+                let Some(runtime) = &self.wasm_runtime else {
+                    bail!("no WASM runtime is set in the settings");
+                };
+                check_wasm_runtime(runtime)?;
+                let mut command = Command::new(runtime);
+                // The data folder is the only preopened directory. WASI runtimes give a
+                // module no network access and no environment variables unless asked to.
+                command
+                    .arg("run")
+                    .arg("--dir")
+                    .arg(format!("{}::/", self.data_dir.display()))
+                    .arg(&entry);
+                command
+            }
+        };
+        command.args(&self.manifest.args);
+
+        command.env_clear().current_dir(&self.data_dir);
+        for name in ["PATH", "SYSTEMROOT", "LANG"] {
+            if let Some(value) = std::env::var_os(name) {
+                command.env(name, value);
+            }
+        }
+        for name in ["HOME", "USERPROFILE", "TMPDIR", "TEMP", "TMP"] {
+            command.env(name, &self.data_dir);
+        }
+        Ok(command)
+    }
+}
+
+/// Applies a plugin's response to `document`. `sent` is the batch the plugin received: a
+/// target is only replaced when it still reads as it was sent, so edits made while the
+/// plugin ran are kept. Unknown segments and unknown statuses fail the batch before any
+/// segment is changed.
+pub fn apply_response(
+    document: &mut JliffDocument,
+    sent: &[PluginSegment],
+    response: PluginResponse,
+) -> Result<Vec<PluginEdit>> {
+    let mut checked = Vec::with_capacity(response.segments.len());
+    for update in response.segments {
+        let Some(original) = sent
+            .iter()
+            .find(|segment| segment.transunit_id == update.transunit_id)
+        else {
+            bail!(
+                "The plugin changed the segment '{}', which was not in its batch.",
+                update.transunit_id
+            );
+        };
+        let status = update
+            .status
+            .as_deref()
+            .map(|value| {
+                SegmentStatus::parse(value)
+                    .with_context(|| format!("'{value}' is not a segment status."))
+            })
+            .transpose()?;
+        checked.push((update, original, status));
+    }
+
+    let mut edits = Vec::new();
+    for (update, original, status) in checked {
+        let Some(unit) = document
+            .transunits
+            .iter_mut()
+            .find(|unit| unit.transunit_id == update.transunit_id)
+        else {
+            continue;
+        };
+        if unit.effective_target() != original.target {
+            continue;
+        }
+        if let Some(status) = status {
+            unit.status = Some(status);
+        }
+        if unit.effective_target() != update.target {
+            edits.push(PluginEdit {
+                transunit_id: unit.transunit_id.clone(),
+                previous_target: std::mem::replace(
+                    unit.effective_target_mut(),
+                    update.target.clone(),
+                ),
+                new_target: update.target,
+            });
+        }
+    }
+    Ok(edits)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn manifests_are_checked_and_settings_resolved_against_them() {
+        let manifest = PluginManifest::parse(
+            r#"{"id": "acme.fixer", "name": "Fixer", "version": "1.0.0", "contract": 1,
+                "runtime": "wasm", "entry": "fixer.wasm", "settings": [
+                    {"key": "style", "label": "Style", "type": "string", "default": "formal"},
+                    {"key": "apiKey", "label": "API key", "type": "string", "required": true}
+                ]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            manifest.timeout(),
+            Duration::from_secs(DEFAULT_TIMEOUT_SECS)
+        );
+
+        let values = json!({"apiKey": "k"}).as_object().unwrap().clone();
+        let resolved = manifest.resolve_settings(&values, false).unwrap();
+        assert_eq!(resolved["style"], "formal");
+        assert!(manifest.resolve_settings(&Map::new(), false).is_err());
+        let unknown = json!({"apiKey": "k", "tone": 1})
+            .as_object()
+            .unwrap()
+            .clone();
+        assert!(manifest.resolve_settings(&unknown, false).is_err());
+        assert_eq!(manifest.resolve_settings(&unknown, true).unwrap().len(), 2);
+
+        for invalid in [
+            r#"{"id": "Acme", "name": "x", "version": "1", "contract": 1,
+                "runtime": "wasm", "entry": "run.wasm"}"#,
+            r#"{"id": "acme", "name": "x", "version": "1", "contract": 2,
+                "runtime": "wasm", "entry": "run.wasm"}"#,
+            r#"{"id": "acme", "name": "x", "version": "1", "contract": 1,
+                "runtime": "wasm", "entry": "../run.wasm"}"#,
+            r#"{"id": "acme", "name": "x", "version": "1", "contract": 1,
+                "runtime": "python", "entry": "run.py"}"#,
+        ] {
+            assert!(PluginManifest::parse(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn native_entries_need_the_opt_in() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("run"), b"#!/bin/sh\n").unwrap();
+        std::fs::write(dir.path().join("run.wasm"), b"\0asm\x01\0\0\0").unwrap();
+        let manifest = |runtime: &str, entry: &str| {
+            PluginManifest::parse(&format!(
+                r#"{{"id": "acme", "name": "x", "version": "1", "contract": 1,
+                    "runtime": "{runtime}", "entry": "{entry}"}}"#
+            ))
+            .unwrap()
+        };
+
+        let native = manifest("executable", "run");
+        assert!(native.check_entry(dir.path(), false).is_err());
+        assert!(native.check_entry(dir.path(), true).is_ok());
+        assert!(
+            manifest("wasm", "run.wasm")
+                .check_entry(dir.path(), false)
+                .is_ok()
+        );
+        assert!(
+            manifest("wasm", "run")
+                .check_entry(dir.path(), true)
+                .is_err()
+        );
+        assert!(check_wasm_runtime(Path::new("wasmtime")).is_err());
+    }
+
+    #[test]
+    fn responses_only_replace_targets_that_did_not_change_meanwhile() {
+        let mut document: JliffDocument = serde_json::from_value(json!({
+            "Project_name": "p", "Project_ID": "1", "File": "a.docx", "User": "u",
+            "Source_language": "en", "Target_language": "de",
+            "Transunits": [
+                {"unit id": "u1", "transunit_id": "t1", "Source": "Hello",
+                 "Target_translation": "Hallo"},
+                {"unit id": "u2", "transunit_id": "t2", "Source": "World",
+                 "Target_translation": "Welt"}
+            ]
+        }))
+        .unwrap();
+        let sent = vec![
+            PluginSegment {
+                unit_id: "u1".into(),
+                transunit_id: "t1".into(),
+                source: "Hello".into(),
+                target: "Hallo".into(),
+                status: None,
+            },
+            PluginSegment {
+                unit_id: "u2".into(),
+                transunit_id: "t2".into(),
+                source: "World".into(),
+                target: "Erde".into(),
+                status: None,
+            },
+        ];
+        let response: PluginResponse = serde_json::from_value(json!({"segments": [
+            {"transunitId": "t1", "target": "Hallo!", "status": "draft"},
+            {"transunitId": "t2", "target": "Welt!"}
+        ]}))
+        .unwrap();
+
+        let edits = apply_response(&mut document, &sent, response).unwrap();
+        assert_eq!(
+            edits,
+            vec![PluginEdit {
+                transunit_id: "t1".into(),
+                previous_target: "Hallo".into(),
+                new_target: "Hallo!".into(),
+            }]
+        );
+        assert_eq!(document.transunits[0].status, Some(SegmentStatus::Draft));
+        assert_eq!(document.transunits[1].effective_target(), "Welt");
+
+        let sent = vec![PluginSegment {
+            target: "Hallo!".into(),
+            ..sent[0].clone()
+        }];
+        let stray: PluginResponse = serde_json::from_value(json!({"segments": [
+            {"transunitId": "t1", "target": "Hallo?"},
+            {"transunitId": "t9", "target": ""}
+        ]}))
+        .unwrap();
+        assert!(apply_response(&mut document, &sent, stray).is_err());
+        assert_eq!(document.transunits[0].effective_target(), "Hallo!");
+    }
+}
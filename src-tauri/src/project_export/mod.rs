@@ -0,0 +1,185 @@
+//! Project bundles: a whole project in one ZIP, for handing it to a colleague on another
+//! machine.
+//!
+//! A bundle holds the project folder (translations, references, instructions and JLIFF
+//! documents) under `project/`, and a `manifest.json` with the project's metadata, language
+//! pairs and files as recorded in the database.
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::delivery::{PackageEntry, PackageSource};
+
+/// Extension of project bundles.
+pub const PROJECT_BUNDLE_EXTENSION: &str = "zip";
+pub const MANIFEST_NAME: &str = "manifest.json";
+/// Folder of the archive holding the project folder.
+pub const PROJECT_FOLDER: &str = "project";
+/// Manifest format written by this version.
+pub const FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectBundleManifest {
+    pub format_version: u32,
+    pub exported_at: String,
+    pub app_version: String,
+    pub project: ExportedProject,
+    pub language_pairs: Vec<ExportedLanguagePair>,
+    pub files: Vec<ExportedFile>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedProject {
+    pub project_uuid: String,
+    pub name: String,
+    pub number: Option<String>,
+    pub project_type: String,
+    pub status: String,
+    pub client_name: Option<String>,
+    pub subjects: Vec<String>,
+    pub notes: Option<String>,
+    pub due_date: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedLanguagePair {
+    pub source_lang: String,
+    pub target_lang: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedFile {
+    pub filename: String,
+    /// Path of the file in the project folder, with `/` separators.
+    pub stored_at: String,
+    /// `processable`, `reference`, `instructions`, `image` or `ocr`.
+    pub role: String,
+    pub language_pairs: Vec<ExportedLanguagePair>,
+}
+
+/// Archive entries of a bundle: the manifest, then every file under `project_root`.
+pub fn bundle_entries(
+    manifest: &ProjectBundleManifest,
+    project_root: &Path,
+) -> Result<Vec<PackageEntry>> {
+    let mut entries = vec![PackageEntry {
+        name: MANIFEST_NAME.into(),
+        source: PackageSource::Contents(
+            serde_json::to_vec_pretty(manifest).context("the manifest could not be written")?,
+        ),
+    }];
+    entries.extend(
+        project_files(project_root)?
+            .into_iter()
+            .map(|rel_path| PackageEntry {
+                name: format!("{PROJECT_FOLDER}/{rel_path}"),
+                source: PackageSource::File(project_root.join(&rel_path)),
+            }),
+    );
+    Ok(entries)
+}
+
+/// Paths of the files under `root`, relative to it with `/` separators, in sorted order.
+/// Symbolic links are skipped so nothing outside the project ends up in the bundle.
+pub fn project_files(root: &Path) -> Result<Vec<String>> {
+    let mut pending = vec![root.to_path_buf()];
+    let mut files = Vec::new();
+    while let Some(dir) = pending.pop() {
+        let entries =
+            fs::read_dir(&dir).with_context(|| format!("{} could not be listed", dir.display()))?;
+        for entry in entries {
+            let entry = entry.with_context(|| format!("{} could not be listed", dir.display()))?;
+            let file_type = entry.file_type()?;
+            let path = entry.path();
+            if file_type.is_dir() {
+                pending.push(path);
+            } else if file_type.is_file()
+                && let Ok(relative) = path.strip_prefix(root)
+            {
+                let components: Vec<String> = relative
+                    .components()
+                    .map(|component| component.as_os_str().to_string_lossy().into_owned())
+                    .collect();
+                files.push(components.join("/"));
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+
+    use zip::ZipArchive;
+
+    use super::*;
+    use crate::delivery::write_package;
+
+    #[test]
+    fn bundles_hold_the_manifest_and_the_whole_project_folder() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("Acme website");
+        fs::create_dir_all(root.join("Translations/en-US_de-DE")).unwrap();
+        fs::create_dir_all(root.join("References")).unwrap();
+        fs::create_dir_all(root.join("Instructions")).unwrap();
+        fs::write(root.join("Translations/home.docx"), b"source").unwrap();
+        fs::write(root.join("Translations/en-US_de-DE/home.jliff.json"), b"{}").unwrap();
+        fs::write(root.join("References/glossary.pdf"), b"reference").unwrap();
+        fs::write(root.join("Instructions/brief.txt"), b"brief").unwrap();
+
+        let manifest = ProjectBundleManifest {
+            format_version: FORMAT_VERSION,
+            exported_at: "2026-10-15T09:00:00Z".into(),
+            app_version: "1.0.0".into(),
+            project: ExportedProject {
+                project_uuid: "7f0c5c5e-8f4e-4f39-9a57-1f1f6f3b2a10".into(),
+                name: "Acme website".into(),
+                number: Some("ACME-0001".into()),
+                project_type: "translation".into(),
+                status: "active".into(),
+                client_name: Some("Acme".into()),
+                subjects: vec!["marketing".into()],
+                notes: None,
+                due_date: None,
+                created_at: "2026-10-01 08:00:00".into(),
+                updated_at: "2026-10-14 17:30:00".into(),
+            },
+            language_pairs: vec![ExportedLanguagePair {
+                source_lang: "en-US".into(),
+                target_lang: "de-DE".into(),
+            }],
+            files: Vec::new(),
+        };
+
+        let bundle = dir.path().join("bundle.zip");
+        write_package(&bundle, &bundle_entries(&manifest, &root).unwrap()).unwrap();
+
+        let mut archive = ZipArchive::new(File::open(&bundle).unwrap()).unwrap();
+        let mut names: Vec<String> = archive.file_names().map(str::to_string).collect();
+        names.sort();
+        assert_eq!(
+            names,
+            [
+                "manifest.json",
+                "project/Instructions/brief.txt",
+                "project/References/glossary.pdf",
+                "project/Translations/en-US_de-DE/home.jliff.json",
+                "project/Translations/home.docx",
+            ]
+        );
+        let read: ProjectBundleManifest =
+            serde_json::from_reader(archive.by_name(MANIFEST_NAME).unwrap()).unwrap();
+        assert_eq!(read, manifest);
+    }
+}
@@ -0,0 +1,203 @@
+//! Streaming client for OpenAI-compatible chat completion endpoints, used for interactive
+//! single-segment translation.
+//!
+//! The endpoint is configured through `LLM_API_BASE_URL` (defaults to the OpenAI API),
+//! `LLM_API_KEY` and `LLM_MODEL`, read from the app environment like the other API keys.
+
+use std::time::Duration;
+
+use log::warn;
+use serde::Serialize;
+use serde_json::{Value, json};
+use thiserror::Error;
+
+use crate::http::HttpClient;
+
+const DEFAULT_BASE_URL: &str = "https://api.openai.com/v1";
+const DEFAULT_MODEL: &str = "gpt-4o-mini";
+/// Provider name under which LLM requests are rate limited.
+pub const LLM_PROVIDER: &str = "llm";
+
+/// Errors raised while streaming a completion.
+#[derive(Debug, Error)]
+pub enum LlmError {
+    #[error("LLM translation is not configured. Set LLM_API_KEY in the app environment.")]
+    NotConfigured,
+    #[error("the LLM request failed: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("the LLM provider answered with HTTP {status}: {body}")]
+    Status { status: u16, body: String },
+    #[error("the LLM provider sent an unreadable stream event: {0}")]
+    Decode(String),
+}
+
+/// One message of a chat completion request.
+#[derive(Debug, Clone, Serialize)]
+pub struct ChatMessage {
+    pub role: &'static str,
+    pub content: String,
+}
+
+/// Content of a decoded server-sent event.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StreamEvent {
+    Delta(String),
+    Done,
+}
+
+/// Splits a `text/event-stream` body into completion deltas. Chunks may end anywhere, even
+/// inside a UTF-8 sequence, so incomplete lines are buffered until the next chunk.
+#[derive(Debug, Default)]
+pub struct SseDecoder {
+    buffer: Vec<u8>,
+}
+
+impl SseDecoder {
+    pub fn push(&mut self, chunk: &[u8]) -> Result<Vec<StreamEvent>, LlmError> {
+        self.buffer.extend_from_slice(chunk);
+        let mut events = Vec::new();
+        while let Some(end) = self.buffer.iter().position(|byte| *byte == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&line);
+            let Some(data) = line.trim_end().strip_prefix("data:") else {
+                continue;
+            };
+            let data = data.trim_start();
+            if data == "[DONE]" {
+                events.push(StreamEvent::Done);
+                continue;
+            }
+            let value: Value =
+                serde_json::from_str(data).map_err(|error| LlmError::Decode(error.to_string()))?;
+            if let Some(delta) = value
+                .pointer("/choices/0/delta/content")
+                .and_then(Value::as_str)
+                .filter(|delta| !delta.is_empty())
+            {
+                events.push(StreamEvent::Delta(delta.to_string()));
+            }
+        }
+        Ok(events)
+    }
+}
+
+/// Chat completion client registered as Tauri state.
+pub struct LlmClient {
+    client: HttpClient,
+    base_url: String,
+    api_key: Option<String>,
+    model: String,
+}
+
+impl LlmClient {
+    pub fn from_env() -> Self {
+        let var = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+        };
+        let api_key = var("LLM_API_KEY");
+        if api_key.is_none() {
+            warn!("LLM_API_KEY is not set. Streaming LLM translation is disabled.");
+        }
+
+        let client = HttpClient::new(|builder| builder.connect_timeout(Duration::from_secs(10)));
+
+        Self {
+            client,
+            base_url: var("LLM_API_BASE_URL")
+                .unwrap_or_else(|| DEFAULT_BASE_URL.to_string())
+                .trim_end_matches('/')
+                .to_string(),
+            api_key,
+            model: var("LLM_MODEL").unwrap_or_else(|| DEFAULT_MODEL.to_string()),
+        }
+    }
+
+    pub fn is_configured(&self) -> bool {
+        self.api_key.is_some()
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    pub fn default_model(&self) -> &str {
+        &self.model
+    }
+
+    /// Streams a completion, calling `on_delta` with each text fragment as it arrives, and
+    /// returns the full text.
+    pub async fn stream_chat(
+        &self,
+        model: &str,
+        messages: &[ChatMessage],
+        mut on_delta: impl FnMut(&str),
+    ) -> Result<String, LlmError> {
+        let api_key = self.api_key.as_deref().ok_or(LlmError::NotConfigured)?;
+        let mut response = self
+            .client
+            .current()
+            .post(format!("{}/chat/completions", self.base_url))
+            .bearer_auth(api_key)
+            .json(&json!({
+                "model": model,
+                "messages": messages,
+                "stream": true,
+            }))
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            let status = response.status().as_u16();
+            let body = response.text().await.unwrap_or_default();
+            return Err(LlmError::Status { status, body });
+        }
+
+        let mut decoder = SseDecoder::default();
+        let mut text = String::new();
+        while let Some(chunk) = response.chunk().await? {
+            for event in decoder.push(&chunk)? {
+                match event {
+                    StreamEvent::Delta(delta) => {
+                        text.push_str(&delta);
+                        on_delta(&delta);
+                    }
+                    StreamEvent::Done => return Ok(text),
+                }
+            }
+        }
+        Ok(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_deltas_split_across_chunks() {
+        let mut decoder = SseDecoder::default();
+        let body = "data: {\"choices\":[{\"delta\":{\"role\":\"assistant\"}}]}\n\n\
+                    data: {\"choices\":[{\"delta\":{\"content\":\"Größe \"}}]}\n\n\
+                    : keep-alive\n\
+                    data: {\"choices\":[{\"delta\":{\"content\":\"{{ph:1}}\"}}]}\n\n\
+                    data: [DONE]\n\n";
+        let bytes = body.as_bytes();
+        // Split inside the two-byte "ö".
+        let split = body.find('ö').unwrap() + 1;
+
+        let mut events = decoder.push(&bytes[..split]).unwrap();
+        assert!(events.is_empty());
+        events.extend(decoder.push(&bytes[split..]).unwrap());
+
+        assert_eq!(
+            events,
+            [
+                StreamEvent::Delta("Größe ".into()),
+                StreamEvent::Delta("{{ph:1}}".into()),
+                StreamEvent::Done,
+            ]
+        );
+    }
+}
@@ -0,0 +1,330 @@
+//! Provider targeting a locally running Ollama or llama.cpp server, so text never leaves the
+//! machine.
+//!
+//! Both servers expose an OpenAI-compatible `/v1/chat/completions` endpoint, which is used for
+//! translation. They differ in how they describe themselves: Ollama lists its models under
+//! `/api/tags` and reports context sizes via `/api/show`, while llama.cpp serves a single model
+//! and reports its context under `/props`. [`LocalModelClient::probe`] tries both.
+
+use std::net::IpAddr;
+use std::time::Duration;
+
+use reqwest::Url;
+use serde_json::{Value, json};
+use thiserror::Error;
+
+use super::llm::ChatMessage;
+use crate::http::HttpClient;
+
+/// Provider name under which local model usage is recorded.
+pub const LOCAL_PROVIDER: &str = "local";
+/// Context assumed when the server does not report one.
+const DEFAULT_CONTEXT_TOKENS: u32 = 2048;
+/// Rough characters per token used to size chunks.
+const CHARS_PER_TOKEN: usize = 3;
+
+/// Errors raised while talking to the local server.
+#[derive(Debug, Error)]
+pub enum LocalModelError {
+    #[error("the local model server at {0} is not reachable: {1}")]
+    Unreachable(String, String),
+    #[error("the local model server answered with HTTP {status}: {body}")]
+    Status { status: u16, body: String },
+    #[error("no local model is configured. Choose one of the models offered by the server.")]
+    NoModel,
+    #[error("the local model server sent an unexpected response: {0}")]
+    Decode(String),
+}
+
+/// Kind of server found at the configured address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LocalServerKind {
+    Ollama,
+    LlamaCpp,
+    /// Answers `/v1/models` but is neither of the above.
+    OpenAiCompatible,
+}
+
+impl LocalServerKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LocalServerKind::Ollama => "ollama",
+            LocalServerKind::LlamaCpp => "llama.cpp",
+            LocalServerKind::OpenAiCompatible => "openai-compatible",
+        }
+    }
+}
+
+/// What the local server offers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocalModelCapabilities {
+    pub server: LocalServerKind,
+    pub models: Vec<String>,
+    /// Context window of the probed model, in tokens, when the server reports it.
+    pub context_tokens: Option<u32>,
+}
+
+/// HTTP client for a local model server.
+pub struct LocalModelClient {
+    client: HttpClient,
+}
+
+impl Default for LocalModelClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LocalModelClient {
+    pub fn new() -> Self {
+        // Local models can take minutes on CPU; only the connection is expected to be fast.
+        let client = HttpClient::new(|builder| {
+            builder
+                .connect_timeout(Duration::from_secs(3))
+                .timeout(Duration::from_secs(600))
+        });
+        Self { client }
+    }
+
+    /// Detects the server at `base_url`, lists its models and, for `model` (or the only
+    /// model served), its context window.
+    pub async fn probe(
+        &self,
+        base_url: &str,
+        model: Option<&str>,
+    ) -> Result<LocalModelCapabilities, LocalModelError> {
+        let base_url = base_url.trim_end_matches('/');
+
+        if let Ok(tags) = self.get_json(base_url, "/api/tags").await {
+            let models: Vec<String> = tags["models"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|entry| entry["name"].as_str().map(str::to_string))
+                .collect();
+            let context_tokens = match model.or(models.first().map(String::as_str)) {
+                Some(model) => self
+                    .post_json(base_url, "/api/show", json!({ "model": model }))
+                    .await
+                    .ok()
+                    .and_then(|show| ollama_context_tokens(&show)),
+                None => None,
+            };
+            return Ok(LocalModelCapabilities {
+                server: LocalServerKind::Ollama,
+                models,
+                context_tokens,
+            });
+        }
+
+        let listed = self.get_json(base_url, "/v1/models").await?;
+        let models = listed["data"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| entry["id"].as_str().map(str::to_string))
+            .collect();
+        match self.get_json(base_url, "/props").await {
+            Ok(props) => Ok(LocalModelCapabilities {
+                server: LocalServerKind::LlamaCpp,
+                models,
+                context_tokens: props["default_generation_settings"]["n_ctx"]
+                    .as_u64()
+                    .and_then(|value| u32::try_from(value).ok()),
+            }),
+            Err(_) => Ok(LocalModelCapabilities {
+                server: LocalServerKind::OpenAiCompatible,
+                models,
+                context_tokens: None,
+            }),
+        }
+    }
+
+    /// Sends one chat completion and returns the answer.
+    pub async fn complete(
+        &self,
+        base_url: &str,
+        model: &str,
+        messages: &[ChatMessage],
+    ) -> Result<String, LocalModelError> {
+        if model.trim().is_empty() {
+            return Err(LocalModelError::NoModel);
+        }
+        let response = self
+            .post_json(
+                base_url.trim_end_matches('/'),
+                "/v1/chat/completions",
+                json!({
+                    "model": model,
+                    "messages": messages,
+                    "stream": false,
+                    "temperature": 0.2,
+                }),
+            )
+            .await?;
+        response
+            .pointer("/choices/0/message/content")
+            .and_then(Value::as_str)
+            .map(|text| text.trim().to_string())
+            .ok_or_else(|| LocalModelError::Decode("missing choices[0].message.content".into()))
+    }
+
+    async fn get_json(&self, base_url: &str, path: &str) -> Result<Value, LocalModelError> {
+        let request = self.client.current().get(format!("{base_url}{path}"));
+        Self::send(base_url, request).await
+    }
+
+    async fn post_json(
+        &self,
+        base_url: &str,
+        path: &str,
+        body: Value,
+    ) -> Result<Value, LocalModelError> {
+        let request = self
+            .client
+            .current()
+            .post(format!("{base_url}{path}"))
+            .json(&body);
+        Self::send(base_url, request).await
+    }
+
+    async fn send(
+        base_url: &str,
+        request: reqwest::RequestBuilder,
+    ) -> Result<Value, LocalModelError> {
+        let response = request.send().await.map_err(|error| {
+            LocalModelError::Unreachable(base_url.to_string(), error.to_string())
+        })?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(LocalModelError::Status {
+                status: status.as_u16(),
+                body,
+            });
+        }
+        response
+            .json()
+            .await
+            .map_err(|error| LocalModelError::Decode(error.to_string()))
+    }
+}
+
+/// Ollama reports the context as `model_info["<architecture>.context_length"]`.
+fn ollama_context_tokens(show: &Value) -> Option<u32> {
+    show["model_info"]
+        .as_object()?
+        .iter()
+        .find(|(key, _)| key.ends_with(".context_length"))
+        .and_then(|(_, value)| value.as_u64())
+        .and_then(|value| u32::try_from(value).ok())
+}
+
+/// Whether `base_url` points at this machine. Only then does text sent to the local provider
+/// stay on it.
+pub fn is_loopback_url(base_url: &str) -> bool {
+    let Some(host) = Url::parse(base_url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_ascii_lowercase))
+    else {
+        return false;
+    };
+    host == "localhost"
+        || host
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .parse::<IpAddr>()
+            .is_ok_and(|address| address.is_loopback())
+}
+
+/// Largest source chunk, in characters, that leaves room in the context window for the
+/// prompt and an answer of similar length.
+pub fn chunk_budget(context_tokens: Option<u32>) -> usize {
+    let tokens = context_tokens.unwrap_or(DEFAULT_CONTEXT_TOKENS) as usize;
+    // A third for the source, a third for the translation, the rest for instructions.
+    (tokens / 3 * CHARS_PER_TOKEN).max(200)
+}
+
+/// Splits `text` into chunks of at most `max_chars` characters, preferring paragraph, then
+/// line, then sentence boundaries. `{{...}}` placeholders are never split; a single
+/// placeholder or word longer than the budget becomes its own oversized chunk.
+pub fn chunk_text(text: &str, max_chars: usize) -> Vec<&str> {
+    let max_chars = max_chars.max(1);
+    let mut chunks = Vec::new();
+    let mut rest = text;
+    while rest.chars().count() > max_chars {
+        let limit = rest
+            .char_indices()
+            .nth(max_chars)
+            .map(|(index, _)| index)
+            .unwrap_or(rest.len());
+        let window = &rest[..limit];
+        let split = ["\n\n", "\n", ". ", "! ", "? ", "; ", ", ", " "]
+            .iter()
+            .find_map(|separator| {
+                window
+                    .rmatch_indices(separator)
+                    .map(|(index, _)| index + separator.len())
+                    .find(|&index| index > 0 && !inside_placeholder(rest, index))
+            })
+            .or_else(|| placeholder_safe_end(rest, limit))
+            .unwrap_or(limit);
+        chunks.push(&rest[..split]);
+        rest = &rest[split..];
+    }
+    if !rest.is_empty() {
+        chunks.push(rest);
+    }
+    chunks
+}
+
+/// Whether byte `index` falls between the braces of a `{{...}}` placeholder.
+fn inside_placeholder(text: &str, index: usize) -> bool {
+    let before = &text[..index];
+    match (before.rfind("{{"), before.rfind("}}")) {
+        (Some(open), Some(close)) => open > close,
+        (Some(_), None) => true,
+        _ => false,
+    }
+}
+
+/// End of the first placeholder spanning `limit`, so it stays in one chunk.
+fn placeholder_safe_end(text: &str, limit: usize) -> Option<usize> {
+    if !inside_placeholder(text, limit) {
+        return None;
+    }
+    text[limit..].find("}}").map(|offset| limit + offset + 2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chunks_at_natural_boundaries_without_splitting_placeholders() {
+        let text =
+            "First sentence here. Second {{ph:1}}sentence{{ph:2}} follows.\n\nNew paragraph.";
+        let chunks = chunk_text(text, 40);
+        assert_eq!(chunks.concat(), text);
+        assert!(
+            chunks
+                .iter()
+                .all(|chunk| !inside_placeholder(chunk, chunk.len()))
+        );
+        assert_eq!(chunks[0], "First sentence here. ");
+
+        assert_eq!(chunk_text("{{ph:12345}}", 5), ["{{ph:12345}}"]);
+        assert_eq!(chunk_text("short", 40), ["short"]);
+        assert_eq!(chunk_budget(Some(4096)), 4095);
+    }
+
+    #[test]
+    fn only_addresses_on_this_machine_are_loopback() {
+        assert!(is_loopback_url("http://127.0.0.1:11434"));
+        assert!(is_loopback_url("http://localhost:8080/v1"));
+        assert!(is_loopback_url("http://[::1]:11434"));
+        assert!(!is_loopback_url("http://192.168.1.20:11434"));
+        assert!(!is_loopback_url("https://models.example.com"));
+        assert!(!is_loopback_url("not a url"));
+    }
+}
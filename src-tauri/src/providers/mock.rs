@@ -0,0 +1,171 @@
+//! Offline provider answering with pseudo translations, for tests and for demoing the app
+//! without network access or API keys.
+//!
+//! The output is derived only from the target language and the source text: ASCII letters
+//! are swapped for accented look-alikes, `{{...}}` placeholders are kept verbatim and the
+//! result is prefixed with the target language, so `Hello {{ph:1}}` becomes
+//! `[de] Ħëľľö {{ph:1}}`. Whether a segment fails is decided by a hash of its source, so a
+//! given failure rate always fails the same segments.
+
+use std::time::Duration;
+
+use thiserror::Error;
+
+/// Provider name under which mock translations are routed and recorded.
+pub const MOCK_PROVIDER: &str = "mock";
+
+/// Behaviour of the mock provider.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MockProviderConfig {
+    /// Time each translation takes, spread over its deltas when streaming.
+    pub latency: Duration,
+    /// Share of segments, in percent, answered with an error.
+    pub failure_rate_percent: u8,
+}
+
+#[derive(Debug, Error)]
+pub enum MockProviderError {
+    #[error("the mock provider was configured to fail this segment")]
+    InjectedFailure,
+}
+
+/// Deterministic stand-in for a translation provider.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MockTranslator {
+    config: MockProviderConfig,
+}
+
+impl MockTranslator {
+    pub fn new(config: MockProviderConfig) -> Self {
+        Self { config }
+    }
+
+    pub async fn translate(
+        &self,
+        target_lang: &str,
+        source: &str,
+    ) -> Result<String, MockProviderError> {
+        self.stream(target_lang, source, |_| {}).await
+    }
+
+    /// Emits the translation word by word, calling `on_delta` with each fragment, and
+    /// returns the full text.
+    pub async fn stream(
+        &self,
+        target_lang: &str,
+        source: &str,
+        mut on_delta: impl FnMut(&str),
+    ) -> Result<String, MockProviderError> {
+        let text = pseudo_translate(target_lang, source);
+        let words: Vec<&str> = text.split_inclusive(' ').collect();
+        let pause = self.config.latency / words.len().max(1) as u32;
+        for word in &words {
+            if !pause.is_zero() {
+                tokio::time::sleep(pause).await;
+            }
+            on_delta(word);
+        }
+        if fails(source, self.config.failure_rate_percent) {
+            return Err(MockProviderError::InjectedFailure);
+        }
+        Ok(text)
+    }
+}
+
+/// Pseudo translation of `source` into `target_lang`.
+pub fn pseudo_translate(target_lang: &str, source: &str) -> String {
+    let lang = target_lang
+        .trim()
+        .split(['-', '_'])
+        .next()
+        .filter(|primary| !primary.is_empty())
+        .unwrap_or("xx")
+        .to_ascii_lowercase();
+    let mut text = format!("[{lang}] ");
+    let mut rest = source;
+    while let Some(start) = rest.find("{{") {
+        let Some(length) = rest[start..].find("}}") else {
+            break;
+        };
+        text.extend(rest[..start].chars().map(accent));
+        text.push_str(&rest[start..start + length + 2]);
+        rest = &rest[start + length + 2..];
+    }
+    text.extend(rest.chars().map(accent));
+    text
+}
+
+fn accent(ch: char) -> char {
+    const LOWER: [char; 26] = [
+        'à', 'ƀ', 'č', 'ď', 'ë', 'ƒ', 'ğ', 'ħ', 'ï', 'ĵ', 'ķ', 'ľ', 'ɱ', 'ñ', 'ö', 'þ', 'ǫ', 'ř',
+        'š', 'ŧ', 'ü', 'ṽ', 'ŵ', 'ẋ', 'ý', 'ž',
+    ];
+    const UPPER: [char; 26] = [
+        'À', 'Ɓ', 'Č', 'Ď', 'Ë', 'Ƒ', 'Ğ', 'Ħ', 'Ï', 'Ĵ', 'Ķ', 'Ľ', 'Ṁ', 'Ñ', 'Ö', 'Þ', 'Ǫ', 'Ř',
+        'Š', 'Ŧ', 'Ü', 'Ṽ', 'Ŵ', 'Ẋ', 'Ý', 'Ž',
+    ];
+    match ch {
+        'a'..='z' => LOWER[(ch as u8 - b'a') as usize],
+        'A'..='Z' => UPPER[(ch as u8 - b'A') as usize],
+        other => other,
+    }
+}
+
+/// FNV-1a, which unlike the std hasher is stable across Rust releases.
+fn fails(source: &str, failure_rate_percent: u8) -> bool {
+    let hash = source
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+        });
+    hash % 100 < u64::from(failure_rate_percent.min(100))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pseudo_translation_keeps_placeholders() {
+        assert_eq!(
+            pseudo_translate("de-DE", "Hello {{ph:1}}World{{/ph:1}}!"),
+            "[de] Ħëľľö {{ph:1}}Ŵöřľď{{/ph:1}}!"
+        );
+        assert_eq!(pseudo_translate("", "ok {{open"), "[xx] öķ {{öþëñ");
+    }
+
+    #[tokio::test]
+    async fn streams_the_same_text_it_returns() {
+        let translator = MockTranslator::default();
+        let mut streamed = String::new();
+        let text = translator
+            .stream("fr", "one two three", |delta| streamed.push_str(delta))
+            .await
+            .unwrap();
+        assert_eq!(streamed, text);
+        assert_eq!(text, "[fr] öñë ŧŵö ŧħřëë");
+    }
+
+    #[tokio::test]
+    async fn failure_rate_is_deterministic() {
+        let always = MockTranslator::new(MockProviderConfig {
+            failure_rate_percent: 100,
+            ..MockProviderConfig::default()
+        });
+        assert!(always.translate("fr", "segment").await.is_err());
+
+        let sometimes = MockTranslator::new(MockProviderConfig {
+            failure_rate_percent: 50,
+            ..MockProviderConfig::default()
+        });
+        let sources: Vec<String> = (0..200).map(|index| format!("segment {index}")).collect();
+        let mut failed = 0;
+        for source in &sources {
+            let first = sometimes.translate("fr", source).await.is_err();
+            let second = sometimes.translate("fr", source).await.is_err();
+            assert_eq!(first, second);
+            failed += usize::from(first);
+        }
+        assert!((50..150).contains(&failed), "{failed} of 200 failed");
+    }
+}
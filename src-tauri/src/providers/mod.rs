@@ -0,0 +1,320 @@
+//! Outbound MT/LLM provider coordination.
+//!
+//! The [`ProviderRateLimiter`] is registered as Tauri state and keeps one token bucket per
+//! limit (requests per minute, tokens per minute) for every provider. Callers acquire a permit
+//! before each provider request; permits are handed out in arrival order, so a large batch
+//! waits in a queue instead of firing requests that the provider answers with HTTP 429.
+//!
+//! [`LlmClient`] streams chat completions for interactive translation, and
+//! [`LocalModelClient`] talks to a local Ollama or llama.cpp server. Both send messages
+//! rendered from a [`PromptTemplateDefinition`]. Which provider handles a segment is decided
+//! by per-project [`RoutingRule`]s. [`MockTranslator`] stands in for both when the mock
+//! provider is enabled in the settings.
+
+mod llm;
+mod local;
+mod mock;
+mod prompt;
+mod routing;
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use tokio::sync::Mutex as AsyncMutex;
+
+pub use llm::{ChatMessage, LLM_PROVIDER, LlmClient};
+pub use local::{LOCAL_PROVIDER, LocalModelClient, chunk_budget, chunk_text, is_loopback_url};
+pub use mock::{MOCK_PROVIDER, MockProviderConfig, MockTranslator};
+pub use prompt::{PromptTemplateDefinition, PromptTerm, PromptVariables};
+pub use routing::{RoutingContext, RoutingRule, route};
+
+/// Per-minute limits of one provider. Unset limits are not enforced.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RateLimit {
+    pub requests_per_minute: Option<u32>,
+    pub tokens_per_minute: Option<u32>,
+}
+
+/// Token bucket refilled continuously up to one minute's allowance. Requests larger than
+/// the bucket are allowed once it is full and leave it in debt, which later requests wait
+/// out.
+#[derive(Debug, Clone)]
+struct TokenBucket {
+    capacity: f64,
+    available: f64,
+    refilled_at: Instant,
+}
+
+impl TokenBucket {
+    fn new(per_minute: u32, now: Instant) -> Self {
+        let capacity = f64::from(per_minute.max(1));
+        Self {
+            capacity,
+            available: capacity,
+            refilled_at: now,
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now
+            .saturating_duration_since(self.refilled_at)
+            .as_secs_f64();
+        self.available = (self.available + elapsed * self.capacity / 60.0).min(self.capacity);
+        self.refilled_at = now;
+    }
+
+    /// Time until `amount` can be taken.
+    fn wait_for(&mut self, amount: f64, now: Instant) -> Duration {
+        self.refill(now);
+        let missing = amount.min(self.capacity) - self.available;
+        if missing <= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(missing * 60.0 / self.capacity)
+        }
+    }
+
+    fn take(&mut self, amount: f64, now: Instant) {
+        self.refill(now);
+        self.available -= amount;
+    }
+}
+
+#[derive(Debug, Default)]
+struct ProviderBuckets {
+    requests: Option<TokenBucket>,
+    tokens: Option<TokenBucket>,
+    /// Set when the provider asked us to back off (e.g. `Retry-After` on a 429).
+    paused_until: Option<Instant>,
+}
+
+impl ProviderBuckets {
+    fn new(limit: RateLimit, now: Instant) -> Self {
+        Self {
+            requests: limit
+                .requests_per_minute
+                .map(|per_minute| TokenBucket::new(per_minute, now)),
+            tokens: limit
+                .tokens_per_minute
+                .map(|per_minute| TokenBucket::new(per_minute, now)),
+            paused_until: None,
+        }
+    }
+
+    /// Time until a request of `tokens` tokens may be sent.
+    fn wait_for(&mut self, tokens: u32, now: Instant) -> Duration {
+        let paused = self
+            .paused_until
+            .map(|until| until.saturating_duration_since(now))
+            .unwrap_or_default();
+        let requests = self
+            .requests
+            .as_mut()
+            .map(|bucket| bucket.wait_for(1.0, now))
+            .unwrap_or_default();
+        let tokens = self
+            .tokens
+            .as_mut()
+            .map(|bucket| bucket.wait_for(f64::from(tokens), now))
+            .unwrap_or_default();
+        paused.max(requests).max(tokens)
+    }
+
+    fn take(&mut self, tokens: u32, now: Instant) {
+        if let Some(bucket) = self.requests.as_mut() {
+            bucket.take(1.0, now);
+        }
+        if let Some(bucket) = self.tokens.as_mut() {
+            bucket.take(f64::from(tokens), now);
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct ProviderQueue {
+    /// Held by the request at the head of the queue; tokio's mutex is fair, so permits are
+    /// handed out in arrival order.
+    turn: AsyncMutex<()>,
+    buckets: Mutex<ProviderBuckets>,
+    waiting: AtomicUsize,
+}
+
+impl ProviderQueue {
+    fn buckets(&self) -> MutexGuard<'_, ProviderBuckets> {
+        self.buckets
+            .lock()
+            .expect("provider rate limit buckets poisoned")
+    }
+}
+
+/// Progress of a permit request, reported while it waits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitWait {
+    pub wait: Duration,
+    /// Requests queued for the same provider, including this one.
+    pub queued: usize,
+}
+
+/// Rate limiter shared by all outbound provider requests.
+#[derive(Debug, Default)]
+pub struct ProviderRateLimiter {
+    limits: Mutex<HashMap<String, RateLimit>>,
+    queues: Mutex<HashMap<String, Arc<ProviderQueue>>>,
+}
+
+impl ProviderRateLimiter {
+    pub fn new(limits: impl IntoIterator<Item = (String, RateLimit)>) -> Self {
+        Self {
+            limits: Mutex::new(limits.into_iter().collect()),
+            queues: Default::default(),
+        }
+    }
+
+    /// Replaces the limits of `provider`; waiting requests keep their place in the queue.
+    pub fn set_limit(&self, provider: &str, limit: RateLimit) {
+        self.limits
+            .lock()
+            .expect("provider rate limits poisoned")
+            .insert(provider.to_string(), limit);
+        let queue = self.queue(provider);
+        let mut buckets = queue.buckets();
+        let paused_until = buckets.paused_until;
+        *buckets = ProviderBuckets::new(limit, Instant::now());
+        buckets.paused_until = paused_until;
+    }
+
+    /// Replaces every limit, e.g. after another database was opened; providers missing from
+    /// `limits` become unlimited.
+    pub fn replace_limits(&self, limits: impl IntoIterator<Item = (String, RateLimit)>) {
+        let limits: HashMap<String, RateLimit> = limits.into_iter().collect();
+        let queued: Vec<String> = self
+            .queues
+            .lock()
+            .expect("provider rate limit queues poisoned")
+            .keys()
+            .filter(|provider| !limits.contains_key(*provider))
+            .cloned()
+            .collect();
+        for provider in &queued {
+            self.set_limit(provider, RateLimit::default());
+        }
+        for (provider, limit) in &limits {
+            self.set_limit(provider, *limit);
+        }
+        self.limits
+            .lock()
+            .expect("provider rate limits poisoned")
+            .retain(|provider, _| limits.contains_key(provider));
+    }
+
+    /// Holds back every request to `provider` for `duration`, e.g. after a 429 response.
+    pub fn pause(&self, provider: &str, duration: Duration) {
+        let until = Instant::now() + duration;
+        let queue = self.queue(provider);
+        let mut buckets = queue.buckets();
+        buckets.paused_until = buckets.paused_until.max(Some(until));
+    }
+
+    /// Waits until a request of `tokens` tokens may be sent to `provider`, calling `on_wait`
+    /// before each sleep. Returns the total time spent waiting.
+    pub async fn acquire(
+        &self,
+        provider: &str,
+        tokens: u32,
+        mut on_wait: impl FnMut(RateLimitWait),
+    ) -> Duration {
+        let queue = self.queue(provider);
+        let started = Instant::now();
+        queue.waiting.fetch_add(1, Ordering::SeqCst);
+        let _turn = queue.turn.lock().await;
+        loop {
+            let wait = {
+                let now = Instant::now();
+                let mut buckets = queue.buckets();
+                let wait = buckets.wait_for(tokens, now);
+                if wait.is_zero() {
+                    buckets.take(tokens, now);
+                }
+                wait
+            };
+            if wait.is_zero() {
+                break;
+            }
+            on_wait(RateLimitWait {
+                wait,
+                queued: queue.waiting.load(Ordering::SeqCst),
+            });
+            tokio::time::sleep(wait).await;
+        }
+        queue.waiting.fetch_sub(1, Ordering::SeqCst);
+        started.elapsed()
+    }
+
+    fn queue(&self, provider: &str) -> Arc<ProviderQueue> {
+        let limit = self
+            .limits
+            .lock()
+            .expect("provider rate limits poisoned")
+            .get(provider)
+            .copied()
+            .unwrap_or_default();
+        self.queues
+            .lock()
+            .expect("provider rate limit queues poisoned")
+            .entry(provider.to_string())
+            .or_insert_with(|| {
+                Arc::new(ProviderQueue {
+                    buckets: Mutex::new(ProviderBuckets::new(limit, Instant::now())),
+                    ..Default::default()
+                })
+            })
+            .clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn buckets_refill_over_the_minute_and_carry_debt() {
+        let start = Instant::now();
+        let mut requests = ProviderBuckets::new(
+            RateLimit {
+                requests_per_minute: Some(2),
+                tokens_per_minute: None,
+            },
+            start,
+        );
+        requests.take(100, start);
+        requests.take(100, start);
+        // Both requests of the minute are spent: the next one waits 30s for a request slot.
+        assert_eq!(requests.wait_for(100, start), Duration::from_secs(30));
+        assert_eq!(
+            requests.wait_for(100, start + Duration::from_secs(30)),
+            Duration::ZERO
+        );
+
+        let mut tokens = ProviderBuckets::new(
+            RateLimit {
+                requests_per_minute: None,
+                tokens_per_minute: Some(600),
+            },
+            start,
+        );
+        tokens.take(200, start);
+        // An oversized request only needs a full bucket, then leaves it in debt.
+        assert_eq!(tokens.wait_for(1_000, start), Duration::from_secs(20));
+        let full = start + Duration::from_secs(20);
+        tokens.take(1_000, full);
+        assert_eq!(
+            tokens.wait_for(1, full),
+            Duration::from_secs_f64(401.0 / 10.0)
+        );
+
+        tokens.paused_until = Some(full + Duration::from_secs(90));
+        assert_eq!(tokens.wait_for(0, full), Duration::from_secs(90));
+    }
+}
@@ -0,0 +1,269 @@
+//! Editable LLM prompt templates.
+//!
+//! A template holds a system prompt, an optional user prompt and few-shot examples. Texts may
+//! reference `${variable}`s (see [`PROMPT_VARIABLES`]); `${...}` is used instead of braces
+//! because segment text already carries `{{...}}` placeholders. A paragraph of the system
+//! prompt whose variables all expand to nothing is dropped, so optional slots such as
+//! terminology or context can come with their own heading.
+
+use anyhow::{Result, bail};
+use serde::{Deserialize, Serialize};
+
+use super::llm::ChatMessage;
+
+/// Variables a template may reference.
+pub const PROMPT_VARIABLES: [&str; 6] = [
+    "source_lang",
+    "target_lang",
+    "source",
+    "context",
+    "terminology",
+    "project_name",
+];
+
+const DEFAULT_SYSTEM_PROMPT: &str = "You are a professional translator. Translate the user's \
+text from ${source_lang} to ${target_lang}. Keep every {{...}} placeholder exactly as written. \
+Reply with the translation only.\n\nUse this terminology:\n${terminology}\n\nContext:\n${context}";
+const DEFAULT_USER_PROMPT: &str = "${source}";
+
+/// Stored template content.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptTemplateDefinition {
+    pub system_prompt: String,
+    /// Message carrying the segment; `${source}` when unset.
+    #[serde(default)]
+    pub user_prompt: Option<String>,
+    #[serde(default)]
+    pub examples: Vec<PromptExample>,
+}
+
+/// A few-shot example, sent as a user/assistant exchange before the segment.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptExample {
+    pub source: String,
+    pub target: String,
+}
+
+/// A terminology entry injected through `${terminology}`. Terms without a target must be kept
+/// as they are.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptTerm {
+    pub source: String,
+    #[serde(default)]
+    pub target: Option<String>,
+}
+
+/// Values substituted into a template.
+#[derive(Debug, Clone, Default)]
+pub struct PromptVariables<'a> {
+    pub source_lang: &'a str,
+    pub target_lang: &'a str,
+    pub source: &'a str,
+    pub context: Option<&'a str>,
+    pub project_name: Option<&'a str>,
+    pub terms: &'a [PromptTerm],
+}
+
+impl PromptVariables<'_> {
+    fn value(&self, name: &str) -> String {
+        match name {
+            "source_lang" => self.source_lang.to_string(),
+            "target_lang" => self.target_lang.to_string(),
+            "source" => self.source.to_string(),
+            "context" => self.context.unwrap_or_default().trim().to_string(),
+            "project_name" => self.project_name.unwrap_or_default().to_string(),
+            "terminology" => self
+                .terms
+                .iter()
+                .map(|term| match &term.target {
+                    Some(target) => format!("- {} → {target}", term.source),
+                    None => format!("- {} (do not translate)", term.source),
+                })
+                .collect::<Vec<_>>()
+                .join("\n"),
+            _ => String::new(),
+        }
+    }
+}
+
+impl Default for PromptTemplateDefinition {
+    /// Prompt used when no template is assigned.
+    fn default() -> Self {
+        Self {
+            system_prompt: DEFAULT_SYSTEM_PROMPT.to_string(),
+            user_prompt: None,
+            examples: Vec::new(),
+        }
+    }
+}
+
+impl PromptTemplateDefinition {
+    /// Rejects empty prompts and examples and unknown variables.
+    pub fn validate(&self) -> Result<()> {
+        if self.system_prompt.trim().is_empty() {
+            bail!("The system prompt must not be empty.");
+        }
+        if self
+            .user_prompt
+            .as_deref()
+            .is_some_and(|prompt| prompt.trim().is_empty())
+        {
+            bail!("The user prompt must not be empty; remove it to send the segment as is.");
+        }
+        for (index, example) in self.examples.iter().enumerate() {
+            if example.source.trim().is_empty() || example.target.trim().is_empty() {
+                bail!("Example {} needs both a source and a target.", index + 1);
+            }
+        }
+        let texts = [self.system_prompt.as_str()]
+            .into_iter()
+            .chain(self.user_prompt.as_deref());
+        for text in texts {
+            for name in variables(text) {
+                if !PROMPT_VARIABLES.contains(&name) {
+                    bail!(
+                        "Unknown variable '${{{name}}}'. Available variables: {}.",
+                        PROMPT_VARIABLES.join(", ")
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Messages sent to the model: system prompt, examples, then the segment.
+    pub fn render(&self, variables: &PromptVariables<'_>) -> Vec<ChatMessage> {
+        let system = self
+            .system_prompt
+            .split("\n\n")
+            .filter_map(|paragraph| {
+                let names = self::variables(paragraph);
+                let expanded = substitute(paragraph, variables);
+                let empty_slot = !names.is_empty()
+                    && names
+                        .iter()
+                        .all(|name| variables.value(name).trim().is_empty());
+                (!empty_slot).then_some(expanded)
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n");
+
+        let mut messages = vec![ChatMessage {
+            role: "system",
+            content: system,
+        }];
+        for example in &self.examples {
+            messages.push(ChatMessage {
+                role: "user",
+                content: example.source.clone(),
+            });
+            messages.push(ChatMessage {
+                role: "assistant",
+                content: example.target.clone(),
+            });
+        }
+        messages.push(ChatMessage {
+            role: "user",
+            content: substitute(
+                self.user_prompt.as_deref().unwrap_or(DEFAULT_USER_PROMPT),
+                variables,
+            ),
+        });
+        messages
+    }
+}
+
+/// Names of the `${...}` variables referenced by `text`.
+fn variables(text: &str) -> Vec<&str> {
+    let mut names = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("${") {
+        let Some(len) = rest[start + 2..].find('}') else {
+            break;
+        };
+        names.push(&rest[start + 2..start + 2 + len]);
+        rest = &rest[start + 2 + len + 1..];
+    }
+    names
+}
+
+fn substitute(text: &str, variables: &PromptVariables<'_>) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("${") {
+        let Some(len) = rest[start + 2..].find('}') else {
+            break;
+        };
+        output.push_str(&rest[..start]);
+        output.push_str(&variables.value(&rest[start + 2..start + 2 + len]));
+        rest = &rest[start + 2 + len + 1..];
+    }
+    output.push_str(rest);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_variables_examples_and_drops_empty_slots() {
+        let template = PromptTemplateDefinition {
+            system_prompt: "Translate ${project_name} strings into ${target_lang}.\n\n\
+                            Glossary:\n${terminology}\n\nNotes: ${context}"
+                .into(),
+            user_prompt: Some("Source (${source_lang}): ${source}".into()),
+            examples: vec![PromptExample {
+                source: "Save".into(),
+                target: "Speichern".into(),
+            }],
+        };
+        template.validate().unwrap();
+
+        let terms = [
+            PromptTerm {
+                source: "file".into(),
+                target: Some("Datei".into()),
+            },
+            PromptTerm {
+                source: "WeGentic".into(),
+                target: None,
+            },
+        ];
+        let messages = template.render(&PromptVariables {
+            source_lang: "en",
+            target_lang: "de",
+            source: "Open {{ph:1}}file",
+            context: None,
+            project_name: Some("Demo"),
+            terms: &terms,
+        });
+
+        let contents: Vec<_> = messages
+            .iter()
+            .map(|message| (message.role, message.content.as_str()))
+            .collect();
+        assert_eq!(
+            contents,
+            [
+                (
+                    "system",
+                    "Translate Demo strings into de.\n\nGlossary:\n- file → Datei\n- WeGentic (do not translate)"
+                ),
+                ("user", "Save"),
+                ("assistant", "Speichern"),
+                ("user", "Source (en): Open {{ph:1}}file"),
+            ]
+        );
+
+        let unknown = PromptTemplateDefinition {
+            system_prompt: "Use ${tone}.".into(),
+            ..Default::default()
+        };
+        assert!(unknown.validate().is_err());
+        PromptTemplateDefinition::default().validate().unwrap();
+    }
+}
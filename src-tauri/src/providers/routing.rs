@@ -0,0 +1,112 @@
+//! Routing rules choosing the MT/LLM provider of a segment.
+//!
+//! Rules are evaluated in order and the first match wins: the project's own rules, then
+//! those of its client. Each condition left unset matches anything, so a rule without
+//! conditions is a catch-all default. A language condition without a region (`ja`) matches
+//! every region of that language (`ja-JP`).
+
+/// One routing rule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoutingRule {
+    pub source_lang: Option<String>,
+    pub target_lang: Option<String>,
+    pub subject: Option<String>,
+    pub provider: String,
+}
+
+/// What a segment is routed on.
+#[derive(Debug, Clone, Copy)]
+pub struct RoutingContext<'a> {
+    pub source_lang: &'a str,
+    pub target_lang: &'a str,
+    pub subjects: &'a [String],
+}
+
+impl RoutingRule {
+    /// Why the rule does not apply to `context`, or `None` when it matches.
+    pub fn mismatch(&self, context: &RoutingContext<'_>) -> Option<String> {
+        if let Some(lang) = &self.source_lang
+            && !language_matches(lang, context.source_lang)
+        {
+            return Some(format!(
+                "source language {} is not {lang}",
+                context.source_lang
+            ));
+        }
+        if let Some(lang) = &self.target_lang
+            && !language_matches(lang, context.target_lang)
+        {
+            return Some(format!(
+                "target language {} is not {lang}",
+                context.target_lang
+            ));
+        }
+        if let Some(subject) = &self.subject
+            && !context
+                .subjects
+                .iter()
+                .any(|candidate| candidate.trim().eq_ignore_ascii_case(subject.trim()))
+        {
+            return Some(format!("the project has no subject '{subject}'"));
+        }
+        None
+    }
+}
+
+/// Index of the first rule matching `context`.
+pub fn route(rules: &[RoutingRule], context: &RoutingContext<'_>) -> Option<usize> {
+    rules
+        .iter()
+        .position(|rule| rule.mismatch(context).is_none())
+}
+
+fn language_matches(rule: &str, lang: &str) -> bool {
+    let rule = rule.trim().replace('_', "-");
+    let lang = lang.trim().replace('_', "-");
+    if rule.eq_ignore_ascii_case(&lang) {
+        return true;
+    }
+    !rule.contains('-')
+        && lang
+            .split('-')
+            .next()
+            .is_some_and(|primary| primary.eq_ignore_ascii_case(&rule))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(target_lang: Option<&str>, subject: Option<&str>, provider: &str) -> RoutingRule {
+        RoutingRule {
+            source_lang: None,
+            target_lang: target_lang.map(str::to_string),
+            subject: subject.map(str::to_string),
+            provider: provider.to_string(),
+        }
+    }
+
+    #[test]
+    fn first_matching_rule_wins() {
+        let rules = [
+            rule(Some("ja"), None, "a"),
+            rule(None, Some("Legal"), "b"),
+            rule(None, None, "default"),
+        ];
+        let legal = ["legal".to_string()];
+        let context = |target_lang, subjects| RoutingContext {
+            source_lang: "en-US",
+            target_lang,
+            subjects,
+        };
+
+        assert_eq!(route(&rules, &context("ja-JP", &legal)), Some(0));
+        assert_eq!(route(&rules, &context("de_DE", &legal)), Some(1));
+        assert_eq!(route(&rules, &context("de-DE", &[])), Some(2));
+        assert_eq!(route(&rules[..2], &context("jav", &[])), None);
+        assert_eq!(
+            rules[0].mismatch(&context("de-DE", &[])).as_deref(),
+            Some("target language de-DE is not ja")
+        );
+    }
+}
@@ -0,0 +1,239 @@
+//! QA profiles: portable definitions of which QA checks run and how their findings are rated.
+//!
+//! A profile lists per-check settings (enabled flag and severity override) and default target
+//! length limits per language. Profiles are stored as JSON in the `qa_profiles` table and
+//! exchanged between installations as [`QaProfileFile`] documents.
+
+use std::collections::HashSet;
+
+use anyhow::{Result, bail};
+use serde::{Deserialize, Serialize};
+
+/// `format` marker of exported profile files.
+pub const QA_PROFILE_FORMAT: &str = "weg-qa-profile";
+/// Current version of the exported profile file layout.
+pub const QA_PROFILE_VERSION: u32 = 1;
+
+const SEVERITIES: [&str; 3] = ["info", "warning", "critical"];
+
+/// Check configuration carried by a profile. Checks that are not listed keep their
+/// built-in behaviour.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QaProfileDefinition {
+    #[serde(default)]
+    pub checks: Vec<QaCheckSetting>,
+    #[serde(default)]
+    pub length_limits: Vec<LanguageLengthLimit>,
+}
+
+/// Settings of one QA check, identified by its `check_type` (e.g. `spelling`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QaCheckSetting {
+    pub check_type: String,
+    pub enabled: bool,
+    /// Severity of the findings; the check's own severity when unset.
+    #[serde(default)]
+    pub severity: Option<String>,
+}
+
+/// Maximum target length applied to segments without their own `slr:sizeRestriction`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LanguageLengthLimit {
+    /// Target language tag; a bare language (`de`) also covers its regional variants.
+    pub language: String,
+    pub max_length: usize,
+}
+
+/// Effective configuration of a check under a profile.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedCheck {
+    pub enabled: bool,
+    pub severity: String,
+}
+
+impl QaProfileDefinition {
+    /// Rejects unknown severities, duplicate entries and empty limits.
+    pub fn validate(&self) -> Result<()> {
+        let mut check_types = HashSet::new();
+        for check in &self.checks {
+            let check_type = check.check_type.trim();
+            if check_type.is_empty() {
+                bail!("Check types must not be empty.");
+            }
+            if !check_types.insert(check_type.to_ascii_lowercase()) {
+                bail!("Check '{check_type}' is configured more than once.");
+            }
+            if let Some(severity) = &check.severity
+                && !SEVERITIES.contains(&severity.as_str())
+            {
+                bail!(
+                    "Unknown severity '{severity}' for check '{check_type}'. Use info, warning or critical."
+                );
+            }
+        }
+
+        let mut languages = HashSet::new();
+        for limit in &self.length_limits {
+            let language = limit.language.trim();
+            if language.is_empty() {
+                bail!("Length limits must name a language.");
+            }
+            if !languages.insert(language.to_ascii_lowercase()) {
+                bail!("A length limit for '{language}' is defined more than once.");
+            }
+            if limit.max_length == 0 {
+                bail!("The length limit for '{language}' must be greater than zero.");
+            }
+        }
+        Ok(())
+    }
+
+    /// Settings of `check_type`, falling back to an enabled check rated `default_severity`.
+    pub fn check(&self, check_type: &str, default_severity: &str) -> ResolvedCheck {
+        let setting = self
+            .checks
+            .iter()
+            .find(|check| check.check_type.trim().eq_ignore_ascii_case(check_type));
+        ResolvedCheck {
+            enabled: setting.is_none_or(|check| check.enabled),
+            severity: setting
+                .and_then(|check| check.severity.clone())
+                .unwrap_or_else(|| default_severity.to_string()),
+        }
+    }
+
+    /// Whether `check_type` is listed and enabled. Opt-in behaviour such as the confirm
+    /// auto-fixes stays off for checks the profile does not mention.
+    pub fn opted_in(&self, check_type: &str) -> bool {
+        self.checks
+            .iter()
+            .any(|check| check.enabled && check.check_type.trim().eq_ignore_ascii_case(check_type))
+    }
+
+    /// Default maximum length for targets in `language`, preferring an exact tag match over
+    /// the bare language.
+    pub fn max_length_for(&self, language: &str) -> Option<usize> {
+        let language = language.trim();
+        let primary = language.split(['-', '_']).next().unwrap_or(language);
+        let find = |tag: &str| {
+            self.length_limits
+                .iter()
+                .find(|limit| limit.language.trim().eq_ignore_ascii_case(tag))
+                .map(|limit| limit.max_length)
+        };
+        find(language).or_else(|| find(primary))
+    }
+}
+
+/// Exported form of a profile.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QaProfileFile {
+    pub format: String,
+    pub version: u32,
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub definition: QaProfileDefinition,
+}
+
+impl QaProfileFile {
+    pub fn new(name: String, description: Option<String>, definition: QaProfileDefinition) -> Self {
+        Self {
+            format: QA_PROFILE_FORMAT.to_string(),
+            version: QA_PROFILE_VERSION,
+            name,
+            description,
+            definition,
+        }
+    }
+
+    /// Parses and validates an exported profile.
+    pub fn parse(bytes: &[u8]) -> Result<Self> {
+        let file: QaProfileFile = serde_json::from_slice(bytes)?;
+        if file.format != QA_PROFILE_FORMAT {
+            bail!("The file is not a QA profile export.");
+        }
+        if file.version > QA_PROFILE_VERSION {
+            bail!(
+                "QA profile version {} is newer than the supported version {QA_PROFILE_VERSION}.",
+                file.version
+            );
+        }
+        if file.name.trim().is_empty() {
+            bail!("The QA profile has no name.");
+        }
+        file.definition.validate()?;
+        Ok(file)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_checks_and_language_limits() {
+        let definition = QaProfileDefinition {
+            checks: vec![
+                QaCheckSetting {
+                    check_type: "spelling".into(),
+                    enabled: false,
+                    severity: None,
+                },
+                QaCheckSetting {
+                    check_type: "length_limit".into(),
+                    enabled: true,
+                    severity: Some("warning".into()),
+                },
+            ],
+            length_limits: vec![
+                LanguageLengthLimit {
+                    language: "de".into(),
+                    max_length: 80,
+                },
+                LanguageLengthLimit {
+                    language: "de-CH".into(),
+                    max_length: 70,
+                },
+            ],
+        };
+
+        assert!(!definition.check("spelling", "warning").enabled);
+        assert!(!definition.opted_in("spelling"));
+        assert!(definition.opted_in("LENGTH_LIMIT"));
+        assert!(!definition.opted_in("autofix_double_spaces"));
+        assert_eq!(
+            definition.check("length_limit", "critical").severity,
+            "warning"
+        );
+        assert_eq!(
+            definition.check("protected_term", "critical"),
+            ResolvedCheck {
+                enabled: true,
+                severity: "critical".into()
+            }
+        );
+        assert_eq!(definition.max_length_for("de-AT"), Some(80));
+        assert_eq!(definition.max_length_for("de-ch"), Some(70));
+        assert_eq!(definition.max_length_for("fr"), None);
+
+        let exported = serde_json::to_vec(&QaProfileFile::new(
+            "Agency".into(),
+            None,
+            definition.clone(),
+        ))
+        .unwrap();
+        assert_eq!(
+            QaProfileFile::parse(&exported).unwrap().definition,
+            definition
+        );
+        assert!(
+            QaProfileFile::parse(br#"{"format":"other","version":1,"name":"x","definition":{}}"#)
+                .is_err()
+        );
+    }
+}
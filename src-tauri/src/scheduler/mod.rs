@@ -0,0 +1,229 @@
+//! Periodic background work (backups, archival, cache GC, sync, deadline checks).
+//!
+//! Each task has a cron-like [`Schedule`] in local time and a jitter that spreads runs so
+//! tasks sharing a schedule do not all start at the same second. The start of every run is
+//! persisted in `scheduled_task_runs`; a run missed while the app was closed happens shortly
+//! after the next start instead of waiting for the following slot. A task never overlaps
+//! itself: a run due while the previous one is still going is skipped.
+
+mod schedule;
+mod tasks;
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{DateTime, Local, Utc};
+use tauri::{AppHandle, Manager};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::db::DbManager;
+
+pub use schedule::{Schedule, ScheduleError};
+pub use tasks::builtin_tasks;
+
+/// Delay before catching up a run missed while the app was closed, so it does not compete
+/// with startup work.
+const CATCH_UP_DELAY: Duration = Duration::from_secs(60);
+
+pub type TaskFuture = Pin<Box<dyn Future<Output = anyhow::Result<()>> + Send>>;
+
+/// A background task and when it runs.
+pub struct TaskDefinition {
+    /// Stable identifier, also the key of the persisted run history.
+    pub id: &'static str,
+    pub description: &'static str,
+    /// Cron-like expression, see [`Schedule`].
+    pub schedule: &'static str,
+    /// Upper bound of the random delay added to every run.
+    pub jitter: Duration,
+    pub run: fn(AppHandle) -> TaskFuture,
+}
+
+#[derive(Debug, Error)]
+pub enum SchedulerError {
+    #[error("unknown task '{0}'")]
+    UnknownTask(String),
+    #[error("task '{0}' is already running")]
+    AlreadyRunning(String),
+}
+
+/// Scheduled tasks and their runtime state, registered as Tauri state.
+pub struct Scheduler {
+    tasks: Vec<Arc<ScheduledTask>>,
+}
+
+/// Runtime view of a task.
+#[derive(Debug, Clone)]
+pub struct ScheduledTaskStatus {
+    pub id: &'static str,
+    pub description: &'static str,
+    pub schedule: &'static str,
+    pub next_run_at: Option<DateTime<Utc>>,
+    pub running: bool,
+}
+
+struct ScheduledTask {
+    definition: TaskDefinition,
+    schedule: Schedule,
+    running: AtomicBool,
+    next_run_at: Mutex<Option<DateTime<Utc>>>,
+}
+
+impl Scheduler {
+    pub fn new(definitions: Vec<TaskDefinition>) -> Result<Self, ScheduleError> {
+        let tasks = definitions
+            .into_iter()
+            .map(|definition| {
+                Ok(Arc::new(ScheduledTask {
+                    schedule: Schedule::parse(definition.schedule)?,
+                    definition,
+                    running: AtomicBool::new(false),
+                    next_run_at: Mutex::new(None),
+                }))
+            })
+            .collect::<Result<_, ScheduleError>>()?;
+        Ok(Self { tasks })
+    }
+
+    /// Spawns one loop per task. Call once, after the state the tasks use is managed.
+    pub fn start(&self, app: &AppHandle) {
+        for task in &self.tasks {
+            tauri::async_runtime::spawn(run_loop(app.clone(), Arc::clone(task)));
+        }
+    }
+
+    pub fn statuses(&self) -> Vec<ScheduledTaskStatus> {
+        self.tasks
+            .iter()
+            .map(|task| ScheduledTaskStatus {
+                id: task.definition.id,
+                description: task.definition.description,
+                schedule: task.definition.schedule,
+                next_run_at: *task.next_run_at.lock().expect("scheduler state poisoned"),
+                running: task.running.load(Ordering::SeqCst),
+            })
+            .collect()
+    }
+
+    /// Runs a task immediately, outside its schedule. The run continues in the background.
+    pub fn run_now(&self, app: &AppHandle, task_id: &str) -> Result<(), SchedulerError> {
+        let task = self
+            .tasks
+            .iter()
+            .find(|task| task.definition.id == task_id)
+            .ok_or_else(|| SchedulerError::UnknownTask(task_id.to_string()))?;
+        if !task.try_begin() {
+            return Err(SchedulerError::AlreadyRunning(task_id.to_string()));
+        }
+        tauri::async_runtime::spawn(execute(app.clone(), Arc::clone(task)));
+        Ok(())
+    }
+}
+
+impl ScheduledTask {
+    fn try_begin(&self) -> bool {
+        self.running
+            .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+            .is_ok()
+    }
+
+    /// When the next run is due: soon when a slot was missed since `last_started_at`, the
+    /// next slot otherwise.
+    fn next_due(
+        &self,
+        last_started_at: Option<DateTime<Utc>>,
+        now: DateTime<Utc>,
+    ) -> Option<DateTime<Utc>> {
+        if let Some(last) = last_started_at
+            && let Some(missed) = self.next_slot(last)
+            && missed <= now
+        {
+            return Some(now + CATCH_UP_DELAY);
+        }
+        self.next_slot(now)
+    }
+
+    fn next_slot(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let mut after = after.with_timezone(&Local).naive_local();
+        // Local times skipped by a DST change have no instant; move on to the next slot.
+        for _ in 0..4 {
+            let slot = self.schedule.next_after(after)?;
+            if let Some(instant) = slot.and_local_timezone(Local).earliest() {
+                return Some(instant.with_timezone(&Utc));
+            }
+            after = slot;
+        }
+        None
+    }
+
+    fn jitter(&self) -> Duration {
+        let max_ms = self.definition.jitter.as_millis();
+        if max_ms == 0 {
+            return Duration::ZERO;
+        }
+        // v4 UUIDs are random, which is all the jitter needs.
+        Duration::from_millis((Uuid::new_v4().as_u128() % max_ms) as u64)
+    }
+}
+
+async fn run_loop(app: AppHandle, task: Arc<ScheduledTask>) {
+    let id = task.definition.id;
+    let mut last_started_at = match app.state::<DbManager>().list_scheduled_task_runs().await {
+        Ok(runs) => runs
+            .into_iter()
+            .find(|run| run.task_id == id)
+            .and_then(|run| DateTime::parse_from_rfc3339(&run.last_started_at).ok())
+            .map(|at| at.with_timezone(&Utc)),
+        Err(error) => {
+            log::warn!(target: "scheduler", "failed to load the run history of {id}: {error}");
+            None
+        }
+    };
+    loop {
+        let now = Utc::now();
+        let Some(due) = task.next_due(last_started_at, now) else {
+            log::warn!(target: "scheduler", "task {id} has no upcoming run");
+            return;
+        };
+        let due = due + task.jitter();
+        *task.next_run_at.lock().expect("scheduler state poisoned") = Some(due);
+        tokio::time::sleep((due - now).to_std().unwrap_or_default()).await;
+
+        last_started_at = Some(Utc::now());
+        if task.try_begin() {
+            execute(app.clone(), Arc::clone(&task)).await;
+        } else {
+            log::info!(target: "scheduler", "skipping {id}: the previous run is still going");
+        }
+    }
+}
+
+/// Runs a task whose `running` flag the caller already set, recording the run.
+async fn execute(app: AppHandle, task: Arc<ScheduledTask>) {
+    let id = task.definition.id;
+    let db = app.state::<DbManager>();
+    if let Err(error) = db
+        .record_scheduled_task_started(id, &Utc::now().to_rfc3339())
+        .await
+    {
+        log::warn!(target: "scheduler", "failed to record the start of {id}: {error}");
+    }
+    log::info!(target: "scheduler", "running {id}");
+    let outcome = (task.definition.run)(app.clone()).await;
+    let error = outcome.err().map(|error| format!("{error:#}"));
+    match &error {
+        Some(error) => log::warn!(target: "scheduler", "task {id} failed: {error}"),
+        None => log::info!(target: "scheduler", "task {id} finished"),
+    }
+    if let Err(error) = db
+        .record_scheduled_task_finished(id, &Utc::now().to_rfc3339(), error.as_deref())
+        .await
+    {
+        log::warn!(target: "scheduler", "failed to record the end of {id}: {error}");
+    }
+    task.running.store(false, Ordering::SeqCst);
+}
@@ -0,0 +1,183 @@
+//! Cron-like schedules: five fields (`minute hour day-of-month month day-of-week`) in local
+//! time, each `*`, a value, a range `a-b`, a step `*/n` or `a-b/n`, or a comma list of those.
+//! `@hourly`, `@daily`, `@weekly` and `@monthly` are accepted as shorthands. As in cron, a
+//! day matches when either day field matches if both are restricted.
+
+use chrono::{Datelike, Duration, NaiveDateTime, NaiveTime, Timelike};
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+#[error("invalid schedule '{expression}': {reason}")]
+pub struct ScheduleError {
+    pub expression: String,
+    pub reason: String,
+}
+
+/// A parsed schedule; each field is a bit set of the values it matches.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Schedule {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    days_restricted: bool,
+    weekdays_restricted: bool,
+}
+
+impl Schedule {
+    pub fn parse(expression: &str) -> Result<Self, ScheduleError> {
+        let error = |reason: String| ScheduleError {
+            expression: expression.to_string(),
+            reason,
+        };
+        let expanded = match expression.trim() {
+            "@hourly" => "0 * * * *",
+            "@daily" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            other => other,
+        };
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        let &[minutes, hours, days, months, weekdays] = fields.as_slice() else {
+            return Err(error(format!("expected 5 fields, found {}", fields.len())));
+        };
+        let mut weekday_bits = parse_field(weekdays, 0, 7).map_err(&error)?;
+        // Both 0 and 7 mean Sunday.
+        if weekday_bits & (1 << 7) != 0 {
+            weekday_bits = (weekday_bits & !(1 << 7)) | 1;
+        }
+        Ok(Self {
+            minutes: parse_field(minutes, 0, 59).map_err(&error)?,
+            hours: parse_field(hours, 0, 23).map_err(&error)?,
+            days: parse_field(days, 1, 31).map_err(&error)?,
+            months: parse_field(months, 1, 12).map_err(&error)?,
+            weekdays: weekday_bits,
+            days_restricted: days != "*",
+            weekdays_restricted: weekdays != "*",
+        })
+    }
+
+    /// First matching minute strictly after `after`, looking at most four years ahead.
+    pub fn next_after(&self, after: NaiveDateTime) -> Option<NaiveDateTime> {
+        let start = after.with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        let mut date = start.date();
+        let mut from_minute = start.hour() * 60 + start.minute();
+        for _ in 0..(4 * 366) {
+            if self.matches_date(date) {
+                for minute_of_day in from_minute..24 * 60 {
+                    let (hour, minute) = (minute_of_day / 60, minute_of_day % 60);
+                    if has(self.hours, hour) && has(self.minutes, minute) {
+                        return Some(date.and_time(NaiveTime::from_hms_opt(hour, minute, 0)?));
+                    }
+                }
+            }
+            date = date.succ_opt()?;
+            from_minute = 0;
+        }
+        None
+    }
+
+    fn matches_date(&self, date: chrono::NaiveDate) -> bool {
+        if !has(self.months, date.month()) {
+            return false;
+        }
+        let day = has(self.days, date.day());
+        let weekday = has(self.weekdays, date.weekday().num_days_from_sunday());
+        match (self.days_restricted, self.weekdays_restricted) {
+            (true, true) => day || weekday,
+            (true, false) => day,
+            (false, true) => weekday,
+            (false, false) => true,
+        }
+    }
+}
+
+fn has(bits: u64, value: u32) -> bool {
+    bits & (1 << value) != 0
+}
+
+fn parse_field(field: &str, min: u32, max: u32) -> Result<u64, String> {
+    let mut bits = 0;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (
+                range,
+                step.parse::<u32>()
+                    .ok()
+                    .filter(|step| *step > 0)
+                    .ok_or_else(|| format!("invalid step in '{part}'"))?,
+            ),
+            None => (part, 1),
+        };
+        let (low, high) = match range {
+            "*" => (min, max),
+            _ => match range.split_once('-') {
+                Some((low, high)) => (parse_value(low, part)?, parse_value(high, part)?),
+                None => {
+                    let value = parse_value(range, part)?;
+                    // `a/n` means from `a` to the end of the field.
+                    (value, if step > 1 { max } else { value })
+                }
+            },
+        };
+        if low < min || high > max || low > high {
+            return Err(format!("'{part}' is outside {min}-{max}"));
+        }
+        for value in (low..=high).step_by(step as usize) {
+            bits |= 1 << value;
+        }
+    }
+    Ok(bits)
+}
+
+fn parse_value(value: &str, part: &str) -> Result<u32, String> {
+    value
+        .parse()
+        .map_err(|_| format!("'{part}' is not a number, range or step"))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+
+    fn at(day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2025, 3, day)
+            .unwrap()
+            .and_hms_opt(hour, minute, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn finds_the_next_matching_minute() {
+        let nightly = Schedule::parse("30 3 * * *").unwrap();
+        assert_eq!(nightly.next_after(at(3, 2, 0)), Some(at(3, 3, 30)));
+        assert_eq!(nightly.next_after(at(3, 3, 30)), Some(at(4, 3, 30)));
+
+        let quarter_hours = Schedule::parse("*/15 9-17 * * 1-5").unwrap();
+        // 2025-03-08 is a Saturday.
+        assert_eq!(quarter_hours.next_after(at(7, 17, 50)), Some(at(10, 9, 0)));
+        assert_eq!(quarter_hours.next_after(at(10, 9, 1)), Some(at(10, 9, 15)));
+
+        // Either day field matches when both are restricted; 7 is Sunday.
+        let either = Schedule::parse("0 0 15 * 7").unwrap();
+        assert_eq!(either.next_after(at(3, 0, 0)), Some(at(9, 0, 0)));
+        assert_eq!(either.next_after(at(9, 0, 0)), Some(at(15, 0, 0)));
+
+        assert_eq!(
+            Schedule::parse("@daily").unwrap(),
+            Schedule::parse("0 0 * * *").unwrap()
+        );
+        assert!(Schedule::parse("60 * * * *").is_err());
+        assert!(Schedule::parse("* * *").is_err());
+        assert!(Schedule::parse("*/0 * * * *").is_err());
+        assert_eq!(
+            Schedule::parse("0 0 30 2 *")
+                .unwrap()
+                .next_after(at(1, 0, 0)),
+            None
+        );
+    }
+}
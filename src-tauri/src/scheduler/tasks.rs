@@ -0,0 +1,142 @@
+//! Tasks the scheduler runs out of the box. Features needing periodic work add theirs here.
+
+use std::time::Duration;
+
+use tauri::{AppHandle, Manager};
+
+use super::{TaskDefinition, TaskFuture};
+use crate::db::DbManager;
+use crate::ipc::commands::{check_for_updates, has_passphrase, lock_workspace, probe_connectivity};
+use crate::ipc::{OfflineMode, WorkspaceLock};
+use crate::secrets::SecretsVault;
+use crate::settings::SettingsManager;
+use crate::updater::Updater;
+
+/// Days delivered domain events stay available to windows catching up.
+const DELIVERED_EVENT_RETENTION_DAYS: i64 = 7;
+
+pub fn builtin_tasks() -> Vec<TaskDefinition> {
+    vec![
+        TaskDefinition {
+            id: "provider_cache_gc",
+            description: "Delete expired provider translations from the cache.",
+            schedule: "30 3 * * *",
+            jitter: Duration::from_secs(15 * 60),
+            run: purge_expired_provider_cache,
+        },
+        TaskDefinition {
+            id: "event_outbox_gc",
+            description: "Delete domain events delivered more than a week ago.",
+            schedule: "45 3 * * *",
+            jitter: Duration::from_secs(15 * 60),
+            run: purge_delivered_events,
+        },
+        TaskDefinition {
+            id: "project_health_check",
+            description: "Re-evaluate project health and report projects that got worse.",
+            schedule: "0 * * * *",
+            jitter: Duration::from_secs(5 * 60),
+            run: refresh_project_health,
+        },
+        TaskDefinition {
+            id: "workspace_auto_lock",
+            description: "Lock the workspace after the configured time without activity.",
+            schedule: "* * * * *",
+            jitter: Duration::ZERO,
+            run: lock_idle_workspace,
+        },
+        TaskDefinition {
+            id: "update_check",
+            description: "Check for a newer release and download it in the background.",
+            schedule: "20 */6 * * *",
+            jitter: Duration::from_secs(30 * 60),
+            run: check_for_app_update,
+        },
+        TaskDefinition {
+            id: "connectivity_probe",
+            description: "Check whether the network is reachable, switching offline mode.",
+            schedule: "* * * * *",
+            jitter: Duration::ZERO,
+            run: probe_network,
+        },
+    ]
+}
+
+fn purge_expired_provider_cache(app: AppHandle) -> TaskFuture {
+    Box::pin(async move {
+        let removed = app
+            .state::<DbManager>()
+            .purge_provider_cache(None, true)
+            .await?;
+        log::info!(target: "scheduler", "removed {removed} expired provider cache entries");
+        Ok(())
+    })
+}
+
+fn purge_delivered_events(app: AppHandle) -> TaskFuture {
+    Box::pin(async move {
+        let removed = app
+            .state::<DbManager>()
+            .purge_delivered_outbox_events(DELIVERED_EVENT_RETENTION_DAYS)
+            .await?;
+        log::info!(target: "scheduler", "removed {removed} delivered outbox events");
+        Ok(())
+    })
+}
+
+fn refresh_project_health(app: AppHandle) -> TaskFuture {
+    Box::pin(async move {
+        let records = app.state::<DbManager>().refresh_project_health().await?;
+        let unhealthy = records
+            .iter()
+            .filter(|record| record.level != "healthy")
+            .count();
+        let total = records.len();
+        log::info!(target: "scheduler", "evaluated {total} projects, {unhealthy} at risk or critical");
+        Ok(())
+    })
+}
+
+fn lock_idle_workspace(app: AppHandle) -> TaskFuture {
+    Box::pin(async move {
+        let Some(minutes) = app
+            .state::<SettingsManager>()
+            .current()
+            .await
+            .auto_lock_minutes
+        else {
+            return Ok(());
+        };
+        let lock = app.state::<WorkspaceLock>();
+        if !lock.is_locked()
+            && lock.idle_for() >= Duration::from_secs(u64::from(minutes) * 60)
+            && has_passphrase(&app.state::<SecretsVault>())?
+        {
+            log::info!(target: "scheduler", "locking the workspace after {minutes} idle minutes");
+            lock_workspace(&app, "inactivity")?;
+        }
+        Ok(())
+    })
+}
+
+fn check_for_app_update(app: AppHandle) -> TaskFuture {
+    Box::pin(async move {
+        if !app.state::<Updater>().is_enabled() || app.state::<OfflineMode>().is_offline() {
+            return Ok(());
+        }
+        let status = check_for_updates(&app).await?;
+        log::info!(target: "scheduler", "update check finished: {}", status.state.as_str());
+        Ok(())
+    })
+}
+
+fn probe_network(app: AppHandle) -> TaskFuture {
+    Box::pin(async move {
+        // A user forcing offline mode expects no traffic at all.
+        if app.state::<OfflineMode>().is_forced() {
+            return Ok(());
+        }
+        probe_connectivity(&app).await;
+        Ok(())
+    })
+}
@@ -0,0 +1,350 @@
+//! Automation scripts: small user scripts run at hook points, for automation that does not
+//! warrant a plugin.
+//!
+//! Scripts are [Rhai](https://rhai.rs). A script belongs to one [`ScriptHook`] and sees what
+//! happened as the map `event`. Its only way to act is the functions listed on
+//! [`ScriptAction`]; each queues an action that the app applies once the script has finished.
+//! The engine has no file, module or network access, so a script never touches files, the
+//! network or the database itself. Runs are bounded by an operation budget, a call depth and
+//! the size of the strings, arrays and maps they build.
+
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+
+use rhai::module_resolvers::DummyModuleResolver;
+use rhai::{AST, Dynamic, Engine, EvalAltResult, ImmutableString, ParseError, Position, Scope};
+
+/// Largest script source, in bytes.
+pub const MAX_SCRIPT_BYTES: usize = 64 * 1024;
+/// Actions one run may queue.
+pub const MAX_SCRIPT_ACTIONS: usize = 20;
+/// Check type of the QA findings raised by `flag()`, followed by the script name.
+pub const SCRIPT_CHECK_PREFIX: &str = "script:";
+
+const MAX_OPERATIONS: u64 = 100_000;
+const MAX_CALL_LEVELS: usize = 32;
+const MAX_STRING_LEN: usize = 100_000;
+const MAX_ARRAY_LEN: usize = 10_000;
+const MAX_LOG_LINES: usize = 100;
+const FLAG_SEVERITIES: [&str; 3] = ["info", "warning", "critical"];
+
+/// A compile or runtime error, with the line it was raised on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScriptError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for ScriptError {}
+
+impl ScriptError {
+    fn at(position: Position, message: impl Into<String>) -> Self {
+        Self {
+            line: position.line().unwrap_or(1),
+            message: message.into(),
+        }
+    }
+}
+
+impl From<ParseError> for ScriptError {
+    fn from(error: ParseError) -> Self {
+        Self::at(error.1, error.0.to_string())
+    }
+}
+
+impl From<Box<EvalAltResult>> for ScriptError {
+    fn from(mut error: Box<EvalAltResult>) -> Self {
+        let position = error.take_position();
+        Self::at(position, error.to_string())
+    }
+}
+
+/// Where a script runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ScriptHook {
+    /// A translator confirmed a segment.
+    SegmentConfirm,
+    /// A project was created.
+    ProjectCreate,
+    /// A QA check raised a finding that was not there before.
+    QaFinding,
+}
+
+impl ScriptHook {
+    pub const ALL: [ScriptHook; 3] = [
+        ScriptHook::SegmentConfirm,
+        ScriptHook::ProjectCreate,
+        ScriptHook::QaFinding,
+    ];
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|hook| hook.as_str().eq_ignore_ascii_case(value.trim()))
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ScriptHook::SegmentConfirm => "on_segment_confirm",
+            ScriptHook::ProjectCreate => "on_project_create",
+            ScriptHook::QaFinding => "on_qa_finding",
+        }
+    }
+}
+
+/// What a script asked for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScriptAction {
+    /// `notify(message)`: shows a message in the app.
+    Notify { message: String },
+    /// `add_note(body)`: adds a note to the event's project.
+    AddNote { body: String },
+    /// `flag(message)` or `flag(message, severity)`, `on_segment_confirm` only: raises a QA
+    /// finding on the confirmed segment.
+    Flag { message: String, severity: String },
+    /// `resolve()`, `on_qa_finding` only: resolves the finding.
+    ResolveFinding,
+}
+
+/// Result of a run: the actions to apply and what the script logged with `log(...)`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScriptOutcome {
+    pub actions: Vec<ScriptAction>,
+    pub logs: Vec<String>,
+}
+
+/// Checks that a script is small enough and parses.
+pub fn check(source: &str) -> Result<(), ScriptError> {
+    let engine = engine(ScriptHook::ProjectCreate, Rc::default());
+    parse(&engine, source).map(|_| ())
+}
+
+/// Runs a script for `hook` with `event` in scope.
+pub fn run(
+    source: &str,
+    hook: ScriptHook,
+    event: &serde_json::Value,
+) -> Result<ScriptOutcome, ScriptError> {
+    let outcome = Rc::new(RefCell::new(ScriptOutcome::default()));
+    let engine = engine(hook, Rc::clone(&outcome));
+    let ast = parse(&engine, source)?;
+    let mut scope = Scope::new();
+    scope.push_dynamic("event", rhai::serde::to_dynamic(event)?);
+    engine.run_ast_with_scope(&mut scope, &ast)?;
+    drop(engine);
+    Ok(Rc::try_unwrap(outcome)
+        .map(RefCell::into_inner)
+        .unwrap_or_else(|outcome| outcome.borrow().clone()))
+}
+
+fn parse(engine: &Engine, source: &str) -> Result<AST, ScriptError> {
+    if source.len() > MAX_SCRIPT_BYTES {
+        return Err(ScriptError {
+            line: 1,
+            message: format!("scripts can have at most {MAX_SCRIPT_BYTES} bytes"),
+        });
+    }
+    Ok(engine.compile(source)?)
+}
+
+/// A sandboxed engine whose host functions queue actions for `hook` into `outcome`.
+fn engine(hook: ScriptHook, outcome: Rc<RefCell<ScriptOutcome>>) -> Engine {
+    let mut engine = Engine::new();
+    engine
+        .set_module_resolver(DummyModuleResolver::new())
+        .disable_symbol("import")
+        .disable_symbol("eval")
+        .set_max_operations(MAX_OPERATIONS)
+        .set_max_call_levels(MAX_CALL_LEVELS)
+        .set_max_string_size(MAX_STRING_LEN)
+        .set_max_array_size(MAX_ARRAY_LEN)
+        .set_max_map_size(MAX_ARRAY_LEN);
+
+    let log = {
+        let outcome = Rc::clone(&outcome);
+        move |parts: &[Dynamic]| {
+            let mut outcome = outcome.borrow_mut();
+            if outcome.logs.len() < MAX_LOG_LINES {
+                let line: Vec<String> = parts.iter().map(Dynamic::to_string).collect();
+                outcome.logs.push(line.join(" "));
+            }
+        }
+    };
+    let print = log.clone();
+    engine.on_print(move |text| print(&[text.into()]));
+    let log_1 = log.clone();
+    engine.register_fn("log", move |a: Dynamic| log_1(&[a]));
+    // Shadows the logarithm of the math package, which would otherwise win for a float.
+    let log_float = log.clone();
+    engine.register_fn("log", move |a: f64| log_float(&[a.into()]));
+    let log_2 = log.clone();
+    engine.register_fn("log", move |a: Dynamic, b: Dynamic| log_2(&[a, b]));
+    engine.register_fn("log", move |a: Dynamic, b: Dynamic, c: Dynamic| {
+        log(&[a, b, c])
+    });
+
+    let host = HookHost { hook, outcome };
+    let notify = host.clone();
+    engine.register_fn("notify", move |message: ImmutableString| {
+        let message = text(&message)?;
+        notify.queue(ScriptAction::Notify { message })
+    });
+    let add_note = host.clone();
+    engine.register_fn("add_note", move |body: ImmutableString| {
+        let body = text(&body)?;
+        add_note.queue(ScriptAction::AddNote { body })
+    });
+    let flag = host.clone();
+    engine.register_fn("flag", move |message: ImmutableString| {
+        flag.flag(&message, "warning")
+    });
+    let flag = host.clone();
+    engine.register_fn(
+        "flag",
+        move |message: ImmutableString, severity: ImmutableString| flag.flag(&message, &severity),
+    );
+    engine.register_fn("resolve", move || {
+        host.only_in(ScriptHook::QaFinding)?;
+        host.queue(ScriptAction::ResolveFinding)
+    });
+    engine
+}
+
+type HostResult = Result<(), Box<EvalAltResult>>;
+
+fn text(value: &str) -> Result<String, Box<EvalAltResult>> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Err("expects a non-empty string".into());
+    }
+    Ok(value.to_string())
+}
+
+#[derive(Clone)]
+struct HookHost {
+    hook: ScriptHook,
+    outcome: Rc<RefCell<ScriptOutcome>>,
+}
+
+impl HookHost {
+    fn queue(&self, action: ScriptAction) -> HostResult {
+        let mut outcome = self.outcome.borrow_mut();
+        if outcome.actions.len() >= MAX_SCRIPT_ACTIONS {
+            return Err(format!("a script can queue at most {MAX_SCRIPT_ACTIONS} actions").into());
+        }
+        outcome.actions.push(action);
+        Ok(())
+    }
+
+    fn only_in(&self, hook: ScriptHook) -> HostResult {
+        if self.hook == hook {
+            Ok(())
+        } else {
+            Err(format!("only available in {} scripts", hook.as_str()).into())
+        }
+    }
+
+    fn flag(&self, message: &str, severity: &str) -> HostResult {
+        self.only_in(ScriptHook::SegmentConfirm)?;
+        let message = text(message)?;
+        let severity = text(severity)?.to_ascii_lowercase();
+        if !FLAG_SEVERITIES.contains(&severity.as_str()) {
+            return Err(format!(
+                "unknown severity '{severity}'; use {}",
+                FLAG_SEVERITIES.join(", ")
+            )
+            .into());
+        }
+        self.queue(ScriptAction::Flag { message, severity })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn scripts_queue_the_actions_of_their_hook() {
+        let event = json!({
+            "target": "Bitte klicken Sie auf OK",
+            "target_language": "de-DE",
+            "word_count": 5
+        });
+        let outcome = run(
+            r#"
+            if event.target.contains("OK") && event.word_count < 10 {
+                flag("Short UI string with a button label", "info");
+                log("flagged", event.target_language);
+            }
+            "#,
+            ScriptHook::SegmentConfirm,
+            &event,
+        )
+        .unwrap();
+        assert_eq!(
+            outcome.actions,
+            [ScriptAction::Flag {
+                message: "Short UI string with a button label".into(),
+                severity: "info".into(),
+            }]
+        );
+        assert_eq!(outcome.logs, ["flagged de-DE"]);
+        let logged = run(
+            "log(2.5); print(\"done\");",
+            ScriptHook::ProjectCreate,
+            &event,
+        )
+        .unwrap();
+        assert_eq!(logged.logs, ["2.5", "done"]);
+
+        // Hook-specific functions fail elsewhere, as do too many actions.
+        assert!(run("resolve();", ScriptHook::SegmentConfirm, &event).is_err());
+        assert!(
+            run(
+                "flag(\"x\", \"fatal\");",
+                ScriptHook::SegmentConfirm,
+                &event
+            )
+            .is_err()
+        );
+        let spam = "for i in [1,2,3,4,5,6,7,8,9,10,11,12,13,14,15,16,17,18,19,20,21] { \
+                    notify(\"n\"); }";
+        assert!(run(spam, ScriptHook::ProjectCreate, &event).is_err());
+        assert_eq!(
+            ScriptHook::parse("ON_QA_FINDING"),
+            Some(ScriptHook::QaFinding)
+        );
+    }
+
+    #[test]
+    fn reports_errors_with_their_line_and_stops_runaway_scripts() {
+        let event = json!({"target": "Hallo"});
+        let compile_error = check("let x = 1;\nlet y = ;").unwrap_err();
+        assert_eq!(compile_error.line, 2);
+
+        let runtime =
+            run("let x = 1;\nunknown(x);", ScriptHook::ProjectCreate, &event).unwrap_err();
+        assert_eq!(runtime.line, 2);
+        assert!(run("let y = 1 / 0;", ScriptHook::ProjectCreate, &event).is_err());
+
+        let looping = "loop { log(1); }";
+        assert!(run(looping, ScriptHook::ProjectCreate, &event).is_err());
+        let recursing = "fn deeper(n) { deeper(n + 1) } deeper(0);";
+        assert!(run(recursing, ScriptHook::ProjectCreate, &event).is_err());
+        let doubling = "let s = \"x\"; loop { s += s; }";
+        assert!(run(doubling, ScriptHook::ProjectCreate, &event).is_err());
+
+        // No way out of the sandbox.
+        assert!(check("import \"secrets\" as s;").is_err());
+        assert!(check("eval(\"1\")").is_err());
+    }
+}
@@ -0,0 +1,121 @@
+//! Secrets vault: credentials the app needs but must not keep in its database or settings
+//! file, such as passwords and access keys of delivery targets.
+//!
+//! Secrets are stored in the operating system's credential store (Keychain on macOS,
+//! Credential Manager on Windows, the Secret Service on Linux) under the app's service
+//! name, one entry per key. The database only records that a secret exists. Keys of a
+//! workspace's database and client data are stored per workspace, see [`workspace_secret`].
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::path::Path;
+use std::sync::Mutex;
+
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+/// Service name of the app's entries in the credential store.
+const SERVICE: &str = "weg-translator";
+
+#[derive(Debug, Error)]
+pub enum VaultError {
+    #[error("the credential store is unavailable: {0}")]
+    Unavailable(String),
+    #[error("the secret could not be stored: {0}")]
+    Store(String),
+}
+
+enum Backend {
+    Keychain,
+    /// Process-local store for tests.
+    Memory(Mutex<HashMap<String, String>>),
+}
+
+/// Vault registered as Tauri state.
+pub struct SecretsVault {
+    backend: Backend,
+}
+
+impl SecretsVault {
+    /// Vault backed by the operating system's credential store.
+    pub fn keychain() -> Self {
+        Self {
+            backend: Backend::Keychain,
+        }
+    }
+
+    /// Vault keeping secrets in memory only.
+    pub fn in_memory() -> Self {
+        Self {
+            backend: Backend::Memory(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Stores `secret` under `key`, replacing an earlier one.
+    pub fn store(&self, key: &str, secret: &str) -> Result<(), VaultError> {
+        match &self.backend {
+            Backend::Keychain => entry(key)?
+                .set_password(secret)
+                .map_err(|error| VaultError::Store(error.to_string())),
+            Backend::Memory(secrets) => {
+                secrets
+                    .lock()
+                    .expect("secrets poisoned")
+                    .insert(key.to_string(), secret.to_string());
+                Ok(())
+            }
+        }
+    }
+
+    /// Reads the secret stored under `key`.
+    pub fn read(&self, key: &str) -> Result<Option<String>, VaultError> {
+        match &self.backend {
+            Backend::Keychain => match entry(key)?.get_password() {
+                Ok(secret) => Ok(Some(secret)),
+                Err(keyring::Error::NoEntry) => Ok(None),
+                Err(error) => Err(VaultError::Unavailable(error.to_string())),
+            },
+            Backend::Memory(secrets) => {
+                Ok(secrets.lock().expect("secrets poisoned").get(key).cloned())
+            }
+        }
+    }
+
+    /// Reads the secret `name` of the workspace in `folder`. Secrets stored before workspaces
+    /// had their own were shared by all of them, and are read when the workspace has none.
+    pub fn read_workspace(&self, name: &str, folder: &Path) -> Result<Option<String>, VaultError> {
+        match self.read(&workspace_secret(name, folder))? {
+            Some(secret) => Ok(Some(secret)),
+            None => self.read(name),
+        }
+    }
+
+    /// Removes the secret stored under `key`; removing a missing secret succeeds.
+    pub fn delete(&self, key: &str) -> Result<(), VaultError> {
+        match &self.backend {
+            Backend::Keychain => match entry(key)?.delete_credential() {
+                Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+                Err(error) => Err(VaultError::Store(error.to_string())),
+            },
+            Backend::Memory(secrets) => {
+                secrets.lock().expect("secrets poisoned").remove(key);
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Key under which the secret `name` of the workspace in `folder` is stored.
+pub fn workspace_secret(name: &str, folder: &Path) -> String {
+    let digest = Sha256::digest(folder.to_string_lossy().as_bytes());
+    digest[..8]
+        .iter()
+        .fold(format!("{name}:"), |mut key, byte| {
+            let _ = write!(key, "{byte:02x}");
+            key
+        })
+}
+
+fn entry(key: &str) -> Result<keyring::Entry, VaultError> {
+    keyring::Entry::new(SERVICE, key).map_err(|error| VaultError::Unavailable(error.to_string()))
+}
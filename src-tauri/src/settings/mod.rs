@@ -3,16 +3,32 @@ use std::fs;
 use std::io::{self, ErrorKind};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
 use thiserror::Error;
 use tokio::sync::RwLock;
 use tokio::task;
 
+use crate::db::numbering::DEFAULT_PROJECT_NUMBER_TEMPLATE;
+use crate::downloads::DOWNLOADS_DIR;
+use crate::import::roles::{RoleRule, default_role_rules};
+use crate::intake::INTAKE_DIR;
+use crate::output_naming::{DEFAULT_JLIFF_NAME_TEMPLATE, DEFAULT_XLIFF_NAME_TEMPLATE};
+use crate::plugins::check_wasm_runtime;
+use crate::providers::{MockProviderConfig, is_loopback_url};
+
 #[cfg(target_family = "unix")]
 use libc::EXDEV;
 
 #[cfg(target_family = "windows")]
 const ERROR_NOT_SAME_DEVICE: i32 = 17;
 
+/// An app folder the user can switch to, with its own database and projects.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkspaceEntry {
+    pub name: String,
+    pub folder: PathBuf,
+}
+
 #[derive(Debug, Clone)]
 pub struct AppSettings {
     pub app_folder: PathBuf,
@@ -25,6 +41,67 @@ pub struct AppSettings {
     pub show_notifications: bool,
     pub enable_sound_notifications: bool,
     pub max_parallel_conversions: u32,
+    pub auto_propagate_repetitions: bool,
+    /// `file` or `project`
+    pub propagation_scope: String,
+    pub propagation_case_sensitive: bool,
+    /// Minutes of editing between JLIFF snapshots; 0 disables the time trigger.
+    pub snapshot_interval_minutes: u32,
+    /// Edits between JLIFF snapshots; 0 disables the edit trigger.
+    pub snapshot_edit_threshold: u32,
+    /// Snapshots kept per document.
+    pub snapshot_retention: u32,
+    /// What happens when a confirmed target exceeds its length limit: `off`, `warn` or `block`.
+    pub length_limit_enforcement: String,
+    /// Hours a cached provider translation stays valid; 0 disables the cache.
+    pub provider_cache_ttl_hours: u32,
+    /// Address of a local Ollama or llama.cpp server.
+    pub local_model_base_url: String,
+    /// Model used on the local server; empty until one is chosen.
+    pub local_model_name: String,
+    /// Answer backend provider requests with the offline mock provider.
+    pub mock_provider_enabled: bool,
+    /// Milliseconds each mock translation takes.
+    pub mock_provider_latency_ms: u32,
+    /// Share of segments, in percent, the mock provider fails.
+    pub mock_provider_failure_rate: u32,
+    /// Normalize extracted source and target text to Unicode NFC.
+    pub normalize_unicode_nfc: bool,
+    /// Endpoint receiving every domain event as a JSON POST; events only reach the webview
+    /// when unset.
+    pub event_webhook_url: Option<String>,
+    /// Whether EU VAT numbers of clients are confirmed against the VIES register when saved.
+    pub vies_lookup: bool,
+    /// ISO 4217 currency reports convert totals into.
+    pub base_currency: String,
+    /// Template new projects are numbered with, e.g. `{client_code}-{yyyy}-{seq:4}`.
+    pub project_number_template: String,
+    /// Rules classifying dropped files into asset roles; the first match wins.
+    pub asset_role_rules: Vec<RoleRule>,
+    /// Tesseract-compatible command images are read with; OCR is unavailable when unset.
+    pub ocr_command: Option<String>,
+    /// Absolute path of the WASI runtime (e.g. wasmtime) WASM plugins run with; they cannot
+    /// run while unset.
+    pub plugin_wasm_runtime: Option<PathBuf>,
+    /// Whether plugins may be native executables, which run without a sandbox.
+    pub allow_native_plugins: bool,
+    /// Whether the database file is encrypted with the key kept in the OS keychain.
+    pub database_encryption: bool,
+    /// Minutes without user activity after which the workspace locks; `None` never locks.
+    pub auto_lock_minutes: Option<u32>,
+    /// Template XLIFF files are named with, e.g. `{stem}`; projects can override it.
+    pub xliff_name_template: String,
+    /// Template JLIFF documents are named with, e.g. `{uuid}`; projects can override it.
+    pub jliff_name_template: String,
+    /// Workspaces the user switches between; the current app folder may be missing from it.
+    pub workspaces: Vec<WorkspaceEntry>,
+    /// HTTP(S) proxy outbound requests go through; its password is kept in the keychain.
+    pub proxy_url: Option<String>,
+    pub proxy_username: Option<String>,
+    /// Hosts reached without the proxy.
+    pub no_proxy: Vec<String>,
+    /// Whether crash reports may be sent to the crash report endpoint.
+    pub crash_report_upload: bool,
     pub database_journal_mode: String,
     pub database_synchronous: String,
 }
@@ -34,9 +111,26 @@ impl AppSettings {
         self.app_folder.join("projects")
     }
 
+    /// Root of the managed download cache.
+    pub fn downloads_dir(&self) -> PathBuf {
+        self.app_folder.join(DOWNLOADS_DIR)
+    }
+
+    /// Root of the staged email intake drafts.
+    pub fn intake_dir(&self) -> PathBuf {
+        self.app_folder.join(INTAKE_DIR)
+    }
+
     pub fn database_path(&self, file_name: &str) -> PathBuf {
         self.app_folder.join(file_name)
     }
+
+    pub fn mock_provider(&self) -> MockProviderConfig {
+        MockProviderConfig {
+            latency: Duration::from_millis(u64::from(self.mock_provider_latency_ms)),
+            failure_rate_percent: self.mock_provider_failure_rate.min(100) as u8,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -61,6 +155,68 @@ struct RawSettings {
     enable_sound_notifications: bool,
     #[serde(default = "default_max_parallel")]
     max_parallel_conversions: u32,
+    #[serde(default = "default_true")]
+    auto_propagate_repetitions: bool,
+    #[serde(default = "default_propagation_scope")]
+    propagation_scope: String,
+    #[serde(default = "default_true")]
+    propagation_case_sensitive: bool,
+    #[serde(default = "default_snapshot_interval_minutes")]
+    snapshot_interval_minutes: u32,
+    #[serde(default = "default_snapshot_edit_threshold")]
+    snapshot_edit_threshold: u32,
+    #[serde(default = "default_snapshot_retention")]
+    snapshot_retention: u32,
+    #[serde(default = "default_length_limit_enforcement")]
+    length_limit_enforcement: String,
+    #[serde(default = "default_provider_cache_ttl_hours")]
+    provider_cache_ttl_hours: u32,
+    #[serde(default = "default_local_model_base_url")]
+    local_model_base_url: String,
+    #[serde(default)]
+    local_model_name: String,
+    #[serde(default = "default_false")]
+    mock_provider_enabled: bool,
+    #[serde(default = "default_mock_provider_latency_ms")]
+    mock_provider_latency_ms: u32,
+    #[serde(default)]
+    mock_provider_failure_rate: u32,
+    #[serde(default = "default_true")]
+    normalize_unicode_nfc: bool,
+    #[serde(default)]
+    event_webhook_url: Option<String>,
+    #[serde(default = "default_false")]
+    vies_lookup: bool,
+    #[serde(default = "default_base_currency")]
+    base_currency: String,
+    #[serde(default = "default_project_number_template")]
+    project_number_template: String,
+    #[serde(default = "default_role_rules")]
+    asset_role_rules: Vec<RoleRule>,
+    #[serde(default)]
+    ocr_command: Option<String>,
+    #[serde(default)]
+    plugin_wasm_runtime: Option<PathBuf>,
+    #[serde(default = "default_false")]
+    allow_native_plugins: bool,
+    #[serde(default = "default_false")]
+    database_encryption: bool,
+    #[serde(default)]
+    auto_lock_minutes: Option<u32>,
+    #[serde(default = "default_xliff_name_template")]
+    xliff_name_template: String,
+    #[serde(default = "default_jliff_name_template")]
+    jliff_name_template: String,
+    #[serde(default)]
+    workspaces: Vec<WorkspaceEntry>,
+    #[serde(default)]
+    proxy_url: Option<String>,
+    #[serde(default)]
+    proxy_username: Option<String>,
+    #[serde(default)]
+    no_proxy: Vec<String>,
+    #[serde(default = "default_false")]
+    crash_report_upload: bool,
     #[serde(default = "default_database_journal_mode")]
     database_journal_mode: String,
     #[serde(default = "default_database_synchronous")]
@@ -80,6 +236,37 @@ impl RawSettings {
             show_notifications: settings.show_notifications,
             enable_sound_notifications: settings.enable_sound_notifications,
             max_parallel_conversions: settings.max_parallel_conversions,
+            auto_propagate_repetitions: settings.auto_propagate_repetitions,
+            propagation_scope: settings.propagation_scope.clone(),
+            propagation_case_sensitive: settings.propagation_case_sensitive,
+            snapshot_interval_minutes: settings.snapshot_interval_minutes,
+            snapshot_edit_threshold: settings.snapshot_edit_threshold,
+            snapshot_retention: settings.snapshot_retention,
+            length_limit_enforcement: settings.length_limit_enforcement.clone(),
+            provider_cache_ttl_hours: settings.provider_cache_ttl_hours,
+            local_model_base_url: settings.local_model_base_url.clone(),
+            local_model_name: settings.local_model_name.clone(),
+            mock_provider_enabled: settings.mock_provider_enabled,
+            mock_provider_latency_ms: settings.mock_provider_latency_ms,
+            mock_provider_failure_rate: settings.mock_provider_failure_rate,
+            normalize_unicode_nfc: settings.normalize_unicode_nfc,
+            event_webhook_url: settings.event_webhook_url.clone(),
+            vies_lookup: settings.vies_lookup,
+            base_currency: settings.base_currency.clone(),
+            project_number_template: settings.project_number_template.clone(),
+            asset_role_rules: settings.asset_role_rules.clone(),
+            ocr_command: settings.ocr_command.clone(),
+            plugin_wasm_runtime: settings.plugin_wasm_runtime.clone(),
+            allow_native_plugins: settings.allow_native_plugins,
+            database_encryption: settings.database_encryption,
+            auto_lock_minutes: settings.auto_lock_minutes,
+            xliff_name_template: settings.xliff_name_template.clone(),
+            jliff_name_template: settings.jliff_name_template.clone(),
+            workspaces: settings.workspaces.clone(),
+            proxy_url: settings.proxy_url.clone(),
+            proxy_username: settings.proxy_username.clone(),
+            no_proxy: settings.no_proxy.clone(),
+            crash_report_upload: settings.crash_report_upload,
             database_journal_mode: settings.database_journal_mode.clone(),
             database_synchronous: settings.database_synchronous.clone(),
         }
@@ -144,6 +331,25 @@ impl SettingsManager {
         Ok(())
     }
 
+    /// Makes `path` the app folder and stores `workspaces` in the same write.
+    pub async fn update_and_save_workspace(
+        &self,
+        path: PathBuf,
+        workspaces: Vec<WorkspaceEntry>,
+    ) -> Result<(), SettingsError> {
+        {
+            let mut guard = self.inner.settings.write().await;
+            let original_folder = std::mem::replace(&mut guard.app_folder, path);
+            let original_workspaces = std::mem::replace(&mut guard.workspaces, workspaces);
+            if let Err(error) = Self::write_to_disk(&self.inner.file_path, &guard) {
+                guard.app_folder = original_folder;
+                guard.workspaces = original_workspaces;
+                return Err(error);
+            }
+        }
+        Ok(())
+    }
+
     pub async fn update_and_save_auto_convert_on_open(
         &self,
         enabled: bool,
@@ -254,6 +460,329 @@ impl SettingsManager {
         }
         Ok(())
     }
+
+    pub async fn update_and_save_length_limit_enforcement(
+        &self,
+        mode: String,
+    ) -> Result<(), SettingsError> {
+        {
+            let mut guard = self.inner.settings.write().await;
+            let original = std::mem::replace(&mut guard.length_limit_enforcement, mode);
+            if let Err(error) = Self::write_to_disk(&self.inner.file_path, &guard) {
+                guard.length_limit_enforcement = original;
+                return Err(error);
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn update_and_save_provider_cache_ttl(
+        &self,
+        hours: u32,
+    ) -> Result<(), SettingsError> {
+        {
+            let mut guard = self.inner.settings.write().await;
+            let original = std::mem::replace(&mut guard.provider_cache_ttl_hours, hours);
+            if let Err(error) = Self::write_to_disk(&self.inner.file_path, &guard) {
+                guard.provider_cache_ttl_hours = original;
+                return Err(error);
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn update_and_save_local_model(
+        &self,
+        base_url: String,
+        model: String,
+    ) -> Result<(), SettingsError> {
+        {
+            let mut guard = self.inner.settings.write().await;
+            let original_base_url = std::mem::replace(&mut guard.local_model_base_url, base_url);
+            let original_model = std::mem::replace(&mut guard.local_model_name, model);
+            if let Err(error) = Self::write_to_disk(&self.inner.file_path, &guard) {
+                guard.local_model_base_url = original_base_url;
+                guard.local_model_name = original_model;
+                return Err(error);
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn update_and_save_mock_provider(
+        &self,
+        enabled: bool,
+        latency_ms: u32,
+        failure_rate: u32,
+    ) -> Result<(), SettingsError> {
+        {
+            let mut guard = self.inner.settings.write().await;
+            let original_enabled = std::mem::replace(&mut guard.mock_provider_enabled, enabled);
+            let original_latency =
+                std::mem::replace(&mut guard.mock_provider_latency_ms, latency_ms);
+            let original_failure_rate =
+                std::mem::replace(&mut guard.mock_provider_failure_rate, failure_rate);
+            if let Err(error) = Self::write_to_disk(&self.inner.file_path, &guard) {
+                guard.mock_provider_enabled = original_enabled;
+                guard.mock_provider_latency_ms = original_latency;
+                guard.mock_provider_failure_rate = original_failure_rate;
+                return Err(error);
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn update_and_save_unicode_normalization(
+        &self,
+        enabled: bool,
+    ) -> Result<(), SettingsError> {
+        {
+            let mut guard = self.inner.settings.write().await;
+            let original = std::mem::replace(&mut guard.normalize_unicode_nfc, enabled);
+            if let Err(error) = Self::write_to_disk(&self.inner.file_path, &guard) {
+                guard.normalize_unicode_nfc = original;
+                return Err(error);
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn update_and_save_event_webhook_url(
+        &self,
+        url: Option<String>,
+    ) -> Result<(), SettingsError> {
+        {
+            let mut guard = self.inner.settings.write().await;
+            let original = std::mem::replace(&mut guard.event_webhook_url, url);
+            if let Err(error) = Self::write_to_disk(&self.inner.file_path, &guard) {
+                guard.event_webhook_url = original;
+                return Err(error);
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn update_and_save_proxy(
+        &self,
+        url: Option<String>,
+        username: Option<String>,
+        no_proxy: Vec<String>,
+    ) -> Result<(), SettingsError> {
+        {
+            let mut guard = self.inner.settings.write().await;
+            let original_url = std::mem::replace(&mut guard.proxy_url, url);
+            let original_username = std::mem::replace(&mut guard.proxy_username, username);
+            let original_no_proxy = std::mem::replace(&mut guard.no_proxy, no_proxy);
+            if let Err(error) = Self::write_to_disk(&self.inner.file_path, &guard) {
+                guard.proxy_url = original_url;
+                guard.proxy_username = original_username;
+                guard.no_proxy = original_no_proxy;
+                return Err(error);
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn update_and_save_crash_report_upload(
+        &self,
+        enabled: bool,
+    ) -> Result<(), SettingsError> {
+        {
+            let mut guard = self.inner.settings.write().await;
+            let original = std::mem::replace(&mut guard.crash_report_upload, enabled);
+            if let Err(error) = Self::write_to_disk(&self.inner.file_path, &guard) {
+                guard.crash_report_upload = original;
+                return Err(error);
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn update_and_save_project_number_template(
+        &self,
+        template: String,
+    ) -> Result<(), SettingsError> {
+        {
+            let mut guard = self.inner.settings.write().await;
+            let original = std::mem::replace(&mut guard.project_number_template, template);
+            if let Err(error) = Self::write_to_disk(&self.inner.file_path, &guard) {
+                guard.project_number_template = original;
+                return Err(error);
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn update_and_save_output_name_templates(
+        &self,
+        xliff_template: String,
+        jliff_template: String,
+    ) -> Result<(), SettingsError> {
+        {
+            let mut guard = self.inner.settings.write().await;
+            let original_xliff = std::mem::replace(&mut guard.xliff_name_template, xliff_template);
+            let original_jliff = std::mem::replace(&mut guard.jliff_name_template, jliff_template);
+            if let Err(error) = Self::write_to_disk(&self.inner.file_path, &guard) {
+                guard.xliff_name_template = original_xliff;
+                guard.jliff_name_template = original_jliff;
+                return Err(error);
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn update_and_save_asset_role_rules(
+        &self,
+        rules: Vec<RoleRule>,
+    ) -> Result<(), SettingsError> {
+        {
+            let mut guard = self.inner.settings.write().await;
+            let original = std::mem::replace(&mut guard.asset_role_rules, rules);
+            if let Err(error) = Self::write_to_disk(&self.inner.file_path, &guard) {
+                guard.asset_role_rules = original;
+                return Err(error);
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn update_and_save_ocr_command(
+        &self,
+        command: Option<String>,
+    ) -> Result<(), SettingsError> {
+        {
+            let mut guard = self.inner.settings.write().await;
+            let original = std::mem::replace(&mut guard.ocr_command, command);
+            if let Err(error) = Self::write_to_disk(&self.inner.file_path, &guard) {
+                guard.ocr_command = original;
+                return Err(error);
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn update_and_save_plugin_runtime(
+        &self,
+        wasm_runtime: Option<PathBuf>,
+        allow_native: bool,
+    ) -> Result<(), SettingsError> {
+        {
+            let mut guard = self.inner.settings.write().await;
+            let original_runtime = std::mem::replace(&mut guard.plugin_wasm_runtime, wasm_runtime);
+            let original_native = std::mem::replace(&mut guard.allow_native_plugins, allow_native);
+            if let Err(error) = Self::write_to_disk(&self.inner.file_path, &guard) {
+                guard.plugin_wasm_runtime = original_runtime;
+                guard.allow_native_plugins = original_native;
+                return Err(error);
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn update_and_save_auto_lock_minutes(
+        &self,
+        minutes: Option<u32>,
+    ) -> Result<(), SettingsError> {
+        {
+            let mut guard = self.inner.settings.write().await;
+            let original = std::mem::replace(&mut guard.auto_lock_minutes, minutes);
+            if let Err(error) = Self::write_to_disk(&self.inner.file_path, &guard) {
+                guard.auto_lock_minutes = original;
+                return Err(error);
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn update_and_save_database_encryption(
+        &self,
+        enabled: bool,
+    ) -> Result<(), SettingsError> {
+        {
+            let mut guard = self.inner.settings.write().await;
+            let original = std::mem::replace(&mut guard.database_encryption, enabled);
+            if let Err(error) = Self::write_to_disk(&self.inner.file_path, &guard) {
+                guard.database_encryption = original;
+                return Err(error);
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn update_and_save_base_currency(
+        &self,
+        currency: String,
+    ) -> Result<(), SettingsError> {
+        {
+            let mut guard = self.inner.settings.write().await;
+            let original = std::mem::replace(&mut guard.base_currency, currency);
+            if let Err(error) = Self::write_to_disk(&self.inner.file_path, &guard) {
+                guard.base_currency = original;
+                return Err(error);
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn update_and_save_vies_lookup(&self, enabled: bool) -> Result<(), SettingsError> {
+        {
+            let mut guard = self.inner.settings.write().await;
+            let original = std::mem::replace(&mut guard.vies_lookup, enabled);
+            if let Err(error) = Self::write_to_disk(&self.inner.file_path, &guard) {
+                guard.vies_lookup = original;
+                return Err(error);
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn update_and_save_snapshots(
+        &self,
+        interval_minutes: u32,
+        edit_threshold: u32,
+        retention: u32,
+    ) -> Result<(), SettingsError> {
+        {
+            let mut guard = self.inner.settings.write().await;
+            let original_interval = guard.snapshot_interval_minutes;
+            let original_threshold = guard.snapshot_edit_threshold;
+            let original_retention = guard.snapshot_retention;
+            guard.snapshot_interval_minutes = interval_minutes;
+            guard.snapshot_edit_threshold = edit_threshold;
+            guard.snapshot_retention = retention;
+            if let Err(error) = Self::write_to_disk(&self.inner.file_path, &guard) {
+                guard.snapshot_interval_minutes = original_interval;
+                guard.snapshot_edit_threshold = original_threshold;
+                guard.snapshot_retention = original_retention;
+                return Err(error);
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn update_and_save_propagation(
+        &self,
+        enabled: bool,
+        scope: String,
+        case_sensitive: bool,
+    ) -> Result<(), SettingsError> {
+        {
+            let mut guard = self.inner.settings.write().await;
+            let original_enabled = guard.auto_propagate_repetitions;
+            let original_scope = guard.propagation_scope.clone();
+            let original_case_sensitive = guard.propagation_case_sensitive;
+            guard.auto_propagate_repetitions = enabled;
+            guard.propagation_scope = scope;
+            guard.propagation_case_sensitive = case_sensitive;
+            if let Err(error) = Self::write_to_disk(&self.inner.file_path, &guard) {
+                guard.auto_propagate_repetitions = original_enabled;
+                guard.propagation_scope = original_scope;
+                guard.propagation_case_sensitive = original_case_sensitive;
+                return Err(error);
+            }
+        }
+        Ok(())
+    }
 }
 
 pub fn load_or_init(
@@ -274,6 +803,43 @@ pub fn load_or_init(
             show_notifications: raw.show_notifications,
             enable_sound_notifications: raw.enable_sound_notifications,
             max_parallel_conversions: raw.max_parallel_conversions,
+            auto_propagate_repetitions: raw.auto_propagate_repetitions,
+            propagation_scope: raw.propagation_scope,
+            propagation_case_sensitive: raw.propagation_case_sensitive,
+            snapshot_interval_minutes: raw.snapshot_interval_minutes,
+            snapshot_edit_threshold: raw.snapshot_edit_threshold,
+            snapshot_retention: raw.snapshot_retention.max(1),
+            length_limit_enforcement: raw.length_limit_enforcement,
+            provider_cache_ttl_hours: raw.provider_cache_ttl_hours,
+            // Projects barred from external MT still use the local model, so it must be local.
+            local_model_base_url: Some(raw.local_model_base_url)
+                .filter(|url| is_loopback_url(url))
+                .unwrap_or_else(default_local_model_base_url),
+            local_model_name: raw.local_model_name,
+            mock_provider_enabled: raw.mock_provider_enabled,
+            mock_provider_latency_ms: raw.mock_provider_latency_ms,
+            mock_provider_failure_rate: raw.mock_provider_failure_rate.min(100),
+            normalize_unicode_nfc: raw.normalize_unicode_nfc,
+            event_webhook_url: raw.event_webhook_url,
+            vies_lookup: raw.vies_lookup,
+            base_currency: raw.base_currency,
+            project_number_template: raw.project_number_template,
+            asset_role_rules: raw.asset_role_rules,
+            ocr_command: raw.ocr_command,
+            // A runtime that moved or was replaced by a relative name is not used.
+            plugin_wasm_runtime: raw
+                .plugin_wasm_runtime
+                .filter(|path| check_wasm_runtime(path).is_ok()),
+            allow_native_plugins: raw.allow_native_plugins,
+            database_encryption: raw.database_encryption,
+            auto_lock_minutes: raw.auto_lock_minutes,
+            xliff_name_template: raw.xliff_name_template,
+            jliff_name_template: raw.jliff_name_template,
+            workspaces: raw.workspaces,
+            proxy_url: raw.proxy_url,
+            proxy_username: raw.proxy_username,
+            no_proxy: raw.no_proxy,
+            crash_report_upload: raw.crash_report_upload,
             database_journal_mode: raw.database_journal_mode,
             database_synchronous: raw.database_synchronous,
         })
@@ -289,6 +855,37 @@ pub fn load_or_init(
             show_notifications: true,
             enable_sound_notifications: false,
             max_parallel_conversions: default_max_parallel(),
+            auto_propagate_repetitions: true,
+            propagation_scope: default_propagation_scope(),
+            propagation_case_sensitive: true,
+            snapshot_interval_minutes: default_snapshot_interval_minutes(),
+            snapshot_edit_threshold: default_snapshot_edit_threshold(),
+            snapshot_retention: default_snapshot_retention(),
+            length_limit_enforcement: default_length_limit_enforcement(),
+            provider_cache_ttl_hours: default_provider_cache_ttl_hours(),
+            local_model_base_url: default_local_model_base_url(),
+            local_model_name: String::new(),
+            mock_provider_enabled: false,
+            mock_provider_latency_ms: default_mock_provider_latency_ms(),
+            mock_provider_failure_rate: 0,
+            normalize_unicode_nfc: true,
+            event_webhook_url: None,
+            vies_lookup: false,
+            base_currency: default_base_currency(),
+            project_number_template: default_project_number_template(),
+            asset_role_rules: default_role_rules(),
+            ocr_command: None,
+            plugin_wasm_runtime: None,
+            allow_native_plugins: false,
+            database_encryption: false,
+            auto_lock_minutes: None,
+            xliff_name_template: default_xliff_name_template(),
+            jliff_name_template: default_jliff_name_template(),
+            workspaces: Vec::new(),
+            proxy_url: None,
+            proxy_username: None,
+            no_proxy: Vec::new(),
+            crash_report_upload: false,
             database_journal_mode: default_database_journal_mode(),
             database_synchronous: default_database_synchronous(),
         })
@@ -323,6 +920,22 @@ fn default_ui_language() -> String {
     "en".to_string()
 }
 
+fn default_base_currency() -> String {
+    "EUR".to_string()
+}
+
+fn default_project_number_template() -> String {
+    DEFAULT_PROJECT_NUMBER_TEMPLATE.to_string()
+}
+
+fn default_xliff_name_template() -> String {
+    DEFAULT_XLIFF_NAME_TEMPLATE.to_string()
+}
+
+fn default_jliff_name_template() -> String {
+    DEFAULT_JLIFF_NAME_TEMPLATE.to_string()
+}
+
 fn default_source_language() -> String {
     "en-US".to_string()
 }
@@ -339,6 +952,38 @@ fn default_max_parallel() -> u32 {
     4
 }
 
+fn default_propagation_scope() -> String {
+    "file".to_string()
+}
+
+fn default_snapshot_interval_minutes() -> u32 {
+    10
+}
+
+fn default_snapshot_edit_threshold() -> u32 {
+    50
+}
+
+fn default_snapshot_retention() -> u32 {
+    20
+}
+
+fn default_length_limit_enforcement() -> String {
+    "warn".into()
+}
+
+fn default_provider_cache_ttl_hours() -> u32 {
+    24 * 30
+}
+
+fn default_local_model_base_url() -> String {
+    "http://127.0.0.1:11434".into()
+}
+
+fn default_mock_provider_latency_ms() -> u32 {
+    400
+}
+
 fn default_database_journal_mode() -> String {
     "WAL".to_string()
 }
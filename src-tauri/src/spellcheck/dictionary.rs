@@ -0,0 +1,131 @@
+//! Dictionary discovery, download, and loading.
+//!
+//! Dictionaries are plain Hunspell `.aff`/`.dic` pairs kept in
+//! `<app_folder>/dictionaries/<tag>/`, user-installed or previously downloaded.
+//! For a language tag such as `it-IT` the full tag is probed first and then the
+//! primary subtag (`it`). Missing dictionaries can be fetched on demand from
+//! the public `wooorm/dictionaries` mirror through the shared [`Downloader`].
+
+use std::path::{Path, PathBuf};
+
+use spellbook::Dictionary;
+use tokio::fs;
+
+use super::SpellcheckError;
+use crate::downloads::Downloader;
+
+/// Directory (relative to the app folder) holding dictionaries.
+pub const DICTIONARIES_DIR: &str = "dictionaries";
+
+/// Base URL used for on-demand downloads. `{lang}` is replaced by the language tag.
+const DOWNLOAD_URL_TEMPLATE: &str =
+    "https://raw.githubusercontent.com/wooorm/dictionaries/main/dictionaries/{lang}/index";
+
+/// Paths of a Hunspell dictionary pair found on disk.
+#[derive(Debug, Clone)]
+pub struct DictionaryFiles {
+    pub language: String,
+    pub aff_path: PathBuf,
+    pub dic_path: PathBuf,
+}
+
+/// Returns the candidate tags probed for a language, most specific first.
+pub fn candidate_tags(language: &str) -> Vec<String> {
+    let normalized = language.trim().replace('_', "-");
+    let mut tags = vec![normalized.clone()];
+    if let Some((primary, _)) = normalized.split_once('-') {
+        tags.push(primary.to_ascii_lowercase());
+    }
+    tags.dedup();
+    tags
+}
+
+/// Locates an installed dictionary for `language`.
+pub async fn locate(user_root: &Path, language: &str) -> Option<DictionaryFiles> {
+    for tag in candidate_tags(language) {
+        if let Some(files) = probe_directory(&user_root.join(&tag), &tag).await {
+            return Some(files);
+        }
+    }
+    None
+}
+
+async fn probe_directory(dir: &Path, tag: &str) -> Option<DictionaryFiles> {
+    for stem in [tag, "index"] {
+        let aff_path = dir.join(format!("{stem}.aff"));
+        let dic_path = dir.join(format!("{stem}.dic"));
+        let aff_exists = fs::try_exists(&aff_path).await.unwrap_or(false);
+        let dic_exists = fs::try_exists(&dic_path).await.unwrap_or(false);
+        if aff_exists && dic_exists {
+            return Some(DictionaryFiles {
+                language: tag.to_string(),
+                aff_path,
+                dic_path,
+            });
+        }
+    }
+    None
+}
+
+/// Downloads the dictionary pair for `language` into the user dictionary folder.
+pub async fn download(
+    downloader: &Downloader,
+    user_root: &Path,
+    language: &str,
+) -> Result<DictionaryFiles, SpellcheckError> {
+    let mut last_error = None;
+
+    for tag in candidate_tags(language) {
+        let base = DOWNLOAD_URL_TEMPLATE.replace("{lang}", &tag);
+        let dir = user_root.join(&tag);
+        let aff_path = dir.join(format!("{tag}.aff"));
+        let dic_path = dir.join(format!("{tag}.dic"));
+
+        if let Err(error) = downloader
+            .download_to(&format!("{base}.aff"), &aff_path, None, |_| {})
+            .await
+        {
+            last_error = Some(SpellcheckError::Download(error.to_string()));
+            continue;
+        }
+        if let Err(error) = downloader
+            .download_to(&format!("{base}.dic"), &dic_path, None, |_| {})
+            .await
+        {
+            // Without its `.dic` the `.aff` is useless; drop it so the next tag is probed cleanly.
+            let _ = fs::remove_file(&aff_path).await;
+            last_error = Some(SpellcheckError::Download(error.to_string()));
+            continue;
+        }
+
+        log::info!(
+            target: "spellcheck::dictionary",
+            "downloaded dictionary '{}' into {}",
+            tag,
+            dir.display()
+        );
+
+        return Ok(DictionaryFiles {
+            language: tag,
+            aff_path,
+            dic_path,
+        });
+    }
+
+    Err(last_error.unwrap_or_else(|| SpellcheckError::DictionaryUnavailable(language.to_string())))
+}
+
+/// Parses a dictionary pair into a ready-to-use checker.
+pub async fn load(files: &DictionaryFiles) -> Result<Dictionary, SpellcheckError> {
+    let aff = fs::read_to_string(&files.aff_path).await?;
+    let dic = fs::read_to_string(&files.dic_path).await?;
+    let language = files.language.clone();
+    tokio::task::spawn_blocking(move || {
+        Dictionary::new(&aff, &dic)
+            .map_err(|error| SpellcheckError::InvalidDictionary(language, error.to_string()))
+    })
+    .await
+    .map_err(|error| {
+        SpellcheckError::InvalidDictionary(files.language.clone(), error.to_string())
+    })?
+}
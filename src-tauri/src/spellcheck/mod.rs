@@ -0,0 +1,121 @@
+//! Spellchecking for target segments backed by Hunspell-compatible dictionaries.
+//!
+//! The [`SpellcheckService`] is registered as Tauri state and keeps parsed
+//! dictionaries in memory, keyed by the resolved language tag, so repeated
+//! checks in the editor do not re-parse multi-megabyte `.dic` files.
+
+mod dictionary;
+mod tokenizer;
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+use serde::Serialize;
+use spellbook::Dictionary;
+use thiserror::Error;
+use tokio::sync::RwLock;
+
+use crate::downloads::Downloader;
+
+pub use dictionary::DICTIONARIES_DIR;
+pub use tokenizer::{WordToken, tokenize};
+
+/// Maximum number of suggestions returned per misspelled word.
+const MAX_SUGGESTIONS: usize = 5;
+
+/// Errors raised while resolving dictionaries or checking text.
+#[derive(Debug, Error)]
+pub enum SpellcheckError {
+    #[error("no dictionary is available for language '{0}'")]
+    DictionaryUnavailable(String),
+    #[error("failed to download dictionary: {0}")]
+    Download(String),
+    #[error("dictionary '{0}' could not be parsed: {1}")]
+    InvalidDictionary(String, String),
+    #[error("failed to access dictionary files: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// A misspelled word and its suggested replacements.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Misspelling {
+    pub word: String,
+    pub start: usize,
+    pub end: usize,
+    pub suggestions: Vec<String>,
+}
+
+/// Shared spellcheck state: dictionary cache plus the downloader fetching missing ones.
+#[derive(Clone)]
+pub struct SpellcheckService {
+    downloader: Arc<Downloader>,
+    loaded: Arc<RwLock<HashMap<String, Arc<Dictionary>>>>,
+}
+
+impl Default for SpellcheckService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SpellcheckService {
+    pub fn new() -> Self {
+        Self {
+            downloader: Arc::new(Downloader::new()),
+            loaded: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// Returns the parsed dictionary for `language`, downloading it when allowed and missing.
+    pub async fn dictionary_for(
+        &self,
+        app_folder: &Path,
+        language: &str,
+        allow_download: bool,
+    ) -> Result<Arc<Dictionary>, SpellcheckError> {
+        let user_root = app_folder.join(DICTIONARIES_DIR);
+
+        for tag in dictionary::candidate_tags(language) {
+            if let Some(existing) = self.loaded.read().await.get(&tag) {
+                return Ok(Arc::clone(existing));
+            }
+        }
+
+        let files = match dictionary::locate(&user_root, language).await {
+            Some(files) => files,
+            None if allow_download => {
+                dictionary::download(&self.downloader, &user_root, language).await?
+            }
+            None => return Err(SpellcheckError::DictionaryUnavailable(language.to_string())),
+        };
+
+        let parsed = Arc::new(dictionary::load(&files).await?);
+        self.loaded
+            .write()
+            .await
+            .insert(files.language.clone(), Arc::clone(&parsed));
+        Ok(parsed)
+    }
+}
+
+/// Checks `text` against `dictionary`, returning misspellings in reading order.
+pub fn check_text(dictionary: &Dictionary, text: &str) -> Vec<Misspelling> {
+    let mut findings = Vec::new();
+    for token in tokenize(text) {
+        if dictionary.check(token.word) {
+            continue;
+        }
+        let mut suggestions = Vec::new();
+        dictionary.suggest(token.word, &mut suggestions);
+        suggestions.truncate(MAX_SUGGESTIONS);
+        findings.push(Misspelling {
+            word: token.word.to_string(),
+            start: token.start,
+            end: token.end,
+            suggestions,
+        });
+    }
+    findings
+}
@@ -0,0 +1,134 @@
+//! Word tokenizer used by the spellchecker.
+//!
+//! Segments contain placeholder tokens (`{{ph:1}}`), URLs, numbers and other
+//! material that must never be reported as misspelled. The tokenizer walks the
+//! text once and yields only plain words together with their character offsets
+//! so the editor can underline them precisely.
+
+/// A candidate word extracted from segment text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WordToken<'a> {
+    pub word: &'a str,
+    /// Offset of the first character, counted in `char`s.
+    pub start: usize,
+    /// Offset one past the last character, counted in `char`s.
+    pub end: usize,
+}
+
+/// Splits `text` into spellcheckable words, skipping placeholders, URLs, e-mail
+/// addresses and tokens that contain digits.
+pub fn tokenize(text: &str) -> Vec<WordToken<'_>> {
+    let mut tokens = Vec::new();
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let mut index = 0;
+
+    while index < chars.len() {
+        let (byte_start, ch) = chars[index];
+
+        // Placeholders are opaque: jump past the closing `}}`.
+        if ch == '{' && text[byte_start..].starts_with("{{") {
+            if let Some(close) = text[byte_start..].find("}}") {
+                let byte_end = byte_start + close + 2;
+                while index < chars.len() && chars[index].0 < byte_end {
+                    index += 1;
+                }
+                continue;
+            }
+        }
+
+        if !ch.is_alphanumeric() {
+            index += 1;
+            continue;
+        }
+
+        // Consume a run of "word-ish" characters; apostrophes and hyphens are kept
+        // when they sit between two letters ("don't", "e-mail").
+        let start_index = index;
+        while index < chars.len() {
+            let current = chars[index].1;
+            if current.is_alphanumeric() {
+                index += 1;
+                continue;
+            }
+            let joins_letters = matches!(current, '\'' | '’' | '-')
+                && index + 1 < chars.len()
+                && chars[index + 1].1.is_alphabetic()
+                && index > start_index;
+            if joins_letters {
+                index += 1;
+                continue;
+            }
+            break;
+        }
+
+        let byte_end = chars.get(index).map(|(pos, _)| *pos).unwrap_or(text.len());
+        let word = &text[byte_start..byte_end];
+
+        // Skip anything that looks like a URL, e-mail or path fragment.
+        let next = chars.get(index).map(|(_, c)| *c);
+        let looks_technical = matches!(next, Some('@') | Some(':') | Some('/'))
+            && text[byte_end..]
+                .chars()
+                .nth(1)
+                .is_some_and(|c| !c.is_whitespace());
+        let previous = start_index
+            .checked_sub(1)
+            .and_then(|i| chars.get(i))
+            .map(|(_, c)| *c);
+        let follows_technical = matches!(previous, Some('@') | Some('/') | Some('.'))
+            && start_index >= 2
+            && !chars[start_index - 2].1.is_whitespace();
+
+        if looks_technical || follows_technical || word.chars().any(|c| c.is_ascii_digit()) {
+            continue;
+        }
+
+        tokens.push(WordToken {
+            word,
+            start: start_index,
+            end: index,
+        });
+    }
+
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(text: &str) -> Vec<&str> {
+        tokenize(text).into_iter().map(|token| token.word).collect()
+    }
+
+    #[test]
+    fn skips_placeholders_and_numbers() {
+        assert_eq!(
+            words("Click {{pc:1:start}}Save{{pc:1:end}} to store 3 files v2"),
+            vec!["Click", "Save", "to", "store", "files"]
+        );
+    }
+
+    #[test]
+    fn keeps_inner_apostrophes_and_hyphens() {
+        assert_eq!(
+            words("don't re-enter 'quoted'"),
+            vec!["don't", "re-enter", "quoted"]
+        );
+    }
+
+    #[test]
+    fn skips_urls_and_emails() {
+        assert_eq!(
+            words("Write to support@example.com or visit https://example.com/docs now"),
+            vec!["Write", "to", "or", "visit", "now"]
+        );
+    }
+
+    #[test]
+    fn reports_character_offsets() {
+        let tokens = tokenize("Ciao mondò");
+        assert_eq!(tokens[1].start, 5);
+        assert_eq!(tokens[1].end, 10);
+    }
+}
@@ -0,0 +1,143 @@
+//! Anonymised diagnostic bundles that users attach to bug reports.
+//!
+//! A bundle is a zip of JSON reports and recent log files. Nothing in it may identify the
+//! user or leak credentials: settings values under secret-looking keys are replaced, and
+//! every text goes through an [`Anonymizer`] that masks the home directory, the app folder
+//! and e-mail addresses. Document content is never added.
+
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::Path;
+
+use regex::Regex;
+use serde_json::Value;
+use zip::CompressionMethod;
+use zip::write::{SimpleFileOptions, ZipWriter};
+
+/// Placeholder written instead of a secret value.
+const REDACTED: &str = "[redacted]";
+/// Key fragments marking a settings value as secret.
+const SECRET_KEY_FRAGMENTS: [&str; 6] =
+    ["key", "token", "secret", "password", "credential", "auth"];
+
+/// One file of the bundle.
+#[derive(Debug, Clone)]
+pub struct BundleEntry {
+    pub name: String,
+    pub contents: Vec<u8>,
+}
+
+/// Masks personal paths and e-mail addresses in free text.
+pub struct Anonymizer {
+    replacements: Vec<(String, &'static str)>,
+    email: Regex,
+}
+
+impl Anonymizer {
+    pub fn new(home_dir: Option<&Path>, app_folder: &Path) -> Self {
+        let mut replacements = vec![(app_folder.to_string_lossy().into_owned(), "<app_folder>")];
+        if let Some(home) = home_dir {
+            replacements.push((home.to_string_lossy().into_owned(), "~"));
+        }
+        // Longest first, so the app folder wins over the home directory containing it.
+        replacements.retain(|(path, _)| path.len() > 1);
+        replacements.sort_by(|a, b| b.0.len().cmp(&a.0.len()));
+        Self {
+            replacements,
+            email: Regex::new(r"[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}")
+                .expect("valid e-mail pattern"),
+        }
+    }
+
+    pub fn apply(&self, text: &str) -> String {
+        let mut output = text.to_string();
+        for (path, replacement) in &self.replacements {
+            output = output.replace(path.as_str(), replacement);
+            // Logs written as JSON escape Windows separators.
+            let escaped = path.replace('\\', "\\\\");
+            if escaped != *path {
+                output = output.replace(&escaped, replacement);
+            }
+        }
+        self.email.replace_all(&output, "<email>").into_owned()
+    }
+
+    /// Anonymises every string of a JSON value and redacts secret-looking keys.
+    pub fn apply_json(&self, value: &mut Value) {
+        match value {
+            Value::String(text) => *text = self.apply(text),
+            Value::Array(items) => items.iter_mut().for_each(|item| self.apply_json(item)),
+            Value::Object(map) => {
+                for (key, item) in map.iter_mut() {
+                    if is_secret_key(key) && !matches!(item, Value::Null | Value::Bool(_)) {
+                        *item = Value::String(REDACTED.into());
+                    } else {
+                        self.apply_json(item);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn is_secret_key(key: &str) -> bool {
+    let key = key.to_ascii_lowercase();
+    SECRET_KEY_FRAGMENTS
+        .iter()
+        .any(|fragment| key.contains(fragment))
+}
+
+/// Writes `entries` into a new zip archive at `path`.
+pub fn write_zip(path: &Path, entries: &[BundleEntry]) -> io::Result<()> {
+    let mut writer = ZipWriter::new(File::create(path)?);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    for entry in entries {
+        writer
+            .start_file(entry.name.as_str(), options)
+            .map_err(io::Error::other)?;
+        writer.write_all(&entry.contents)?;
+    }
+    writer.finish().map_err(io::Error::other)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn masks_paths_emails_and_secrets() {
+        let anonymizer = Anonymizer::new(
+            Some(Path::new("/home/jane")),
+            Path::new("/home/jane/WegTranslator"),
+        );
+        assert_eq!(
+            anonymizer.apply(
+                "opened /home/jane/WegTranslator/projects/a and /home/jane/x for jane@example.com"
+            ),
+            "opened <app_folder>/projects/a and ~/x for <email>"
+        );
+
+        let mut settings = json!({
+            "appFolder": "/home/jane/WegTranslator",
+            "apiKey": "sk-123",
+            "localModelName": "qwen",
+            "authEnabled": true,
+            "nested": [{ "accessToken": "abc" }],
+        });
+        anonymizer.apply_json(&mut settings);
+        assert_eq!(
+            settings,
+            json!({
+                "appFolder": "<app_folder>",
+                "apiKey": REDACTED,
+                "localModelName": "qwen",
+                "authEnabled": true,
+                "nested": [{ "accessToken": REDACTED }],
+            })
+        );
+    }
+}
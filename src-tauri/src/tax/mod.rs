@@ -0,0 +1,413 @@
+//! Validation of client VAT and tax identification numbers.
+//!
+//! Numbers are read with their country prefix, as they appear on invoices ("DE 136 695 976",
+//! "CHE-116.281.710 MWST"). Every EU member state's VAT number is checked against its
+//! national format, and against its check digits where the scheme has public ones; the UK,
+//! Swiss and Norwegian numbers are checked the same way. EU numbers can additionally be
+//! confirmed against the Commission's VIES register through [`vies::ViesClient`]. Numbers
+//! without a known prefix are kept as entered.
+
+pub mod vies;
+
+use anyhow::{Result, bail};
+use regex::Regex;
+
+/// How far a tax number was verified.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaxIdStatus {
+    /// Confirmed as registered by VIES.
+    Vies,
+    /// Format and check digits verified.
+    Checksum,
+    /// Format verified; the scheme has no public check digits.
+    Format,
+    /// No known scheme; kept as entered.
+    Unverified,
+}
+
+impl TaxIdStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TaxIdStatus::Vies => "vies",
+            TaxIdStatus::Checksum => "checksum",
+            TaxIdStatus::Format => "format",
+            TaxIdStatus::Unverified => "unverified",
+        }
+    }
+}
+
+/// Outcome of checking a tax number locally.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaxIdCheck {
+    /// Prefix followed by the national number, without separators: `DE136695976`.
+    pub normalized: String,
+    /// Scheme prefix, when recognised. Greece uses `EL` and Northern Ireland `XI`, as in VIES.
+    pub prefix: Option<&'static str>,
+    pub status: TaxIdStatus,
+    /// Whether VIES can confirm the number.
+    pub eu_vat: bool,
+}
+
+impl TaxIdCheck {
+    /// National part of the number, as VIES expects it.
+    pub fn national_number(&self) -> &str {
+        &self.normalized[self.prefix.map_or(0, str::len)..]
+    }
+}
+
+struct Scheme {
+    prefix: &'static str,
+    /// Format of the national part.
+    pattern: &'static str,
+    check_digits: Option<fn(&str) -> bool>,
+    eu_vat: bool,
+}
+
+const fn eu(prefix: &'static str, pattern: &'static str, check: fn(&str) -> bool) -> Scheme {
+    Scheme {
+        prefix,
+        pattern,
+        check_digits: Some(check),
+        eu_vat: true,
+    }
+}
+
+const fn eu_format(prefix: &'static str, pattern: &'static str) -> Scheme {
+    Scheme {
+        prefix,
+        pattern,
+        check_digits: None,
+        eu_vat: true,
+    }
+}
+
+const SCHEMES: &[Scheme] = &[
+    eu("AT", r"^U\d{8}$", austria),
+    eu("BE", r"^[01]\d{9}$", belgium),
+    eu_format("BG", r"^\d{9,10}$"),
+    eu_format("CY", r"^\d{8}[A-Z]$"),
+    eu_format("CZ", r"^\d{8,10}$"),
+    eu("DE", r"^\d{9}$", iso7064_mod11_10),
+    eu("DK", r"^\d{8}$", denmark),
+    eu("EE", r"^\d{9}$", estonia),
+    eu("EL", r"^\d{9}$", greece),
+    eu_format("ES", r"^[A-Z0-9]\d{7}[A-Z0-9]$"),
+    eu("FI", r"^\d{8}$", finland),
+    eu("FR", r"^[A-Z0-9]{2}\d{9}$", france),
+    eu("HR", r"^\d{11}$", iso7064_mod11_10),
+    eu("HU", r"^\d{8}$", hungary),
+    eu_format("IE", r"^\d[A-Z0-9+*]\d{5}[A-Z]{1,2}$"),
+    eu("IT", r"^\d{11}$", luhn),
+    eu_format("LT", r"^(\d{9}|\d{12})$"),
+    eu("LU", r"^\d{8}$", luxembourg),
+    eu_format("LV", r"^\d{11}$"),
+    eu_format("MT", r"^\d{8}$"),
+    eu("NL", r"^\d{9}B\d{2}$", netherlands),
+    eu("PL", r"^\d{10}$", poland),
+    eu("PT", r"^\d{9}$", portugal),
+    eu_format("RO", r"^\d{2,10}$"),
+    eu("SE", r"^\d{10}01$", |digits| luhn(&digits[..10])),
+    eu("SI", r"^\d{8}$", slovenia),
+    eu("SK", r"^\d{10}$", |digits| {
+        digits.parse::<u64>().is_ok_and(|n| n % 11 == 0)
+    }),
+    eu("XI", r"^(\d{9}|\d{12})$", united_kingdom),
+    Scheme {
+        prefix: "GB",
+        pattern: r"^(\d{9}|\d{12}|GD[0-4]\d{2}|HA[5-9]\d{2})$",
+        check_digits: Some(united_kingdom),
+        eu_vat: false,
+    },
+    Scheme {
+        prefix: "CH",
+        pattern: r"^\d{9}$",
+        check_digits: Some(|digits| mod11(digits, &[5, 4, 3, 2, 7, 6, 5, 4])),
+        eu_vat: false,
+    },
+    Scheme {
+        prefix: "NO",
+        pattern: r"^\d{9}$",
+        check_digits: Some(|digits| mod11(digits, &[3, 2, 7, 6, 5, 4, 3, 2])),
+        eu_vat: false,
+    },
+];
+
+/// Checks a tax number written with its country prefix. Numbers of a known scheme that do
+/// not match its format or check digits are rejected; others are accepted as unverified.
+pub fn check_tax_id(raw: &str) -> Result<TaxIdCheck> {
+    let cleaned: String = raw
+        .chars()
+        .filter(|c| !c.is_whitespace() && !matches!(c, '.' | '-' | '/' | ','))
+        .flat_map(char::to_uppercase)
+        .collect();
+    if cleaned.is_empty() {
+        bail!("The tax number is empty.");
+    }
+    let (prefix, national) = split_prefix(&cleaned);
+    let Some(scheme) = prefix.and_then(|prefix| SCHEMES.iter().find(|s| s.prefix == prefix)) else {
+        return Ok(TaxIdCheck {
+            normalized: cleaned,
+            prefix: None,
+            status: TaxIdStatus::Unverified,
+            eu_vat: false,
+        });
+    };
+
+    let display = raw.trim();
+    let format = Regex::new(scheme.pattern).expect("tax number patterns are valid");
+    if !format.is_match(national) {
+        bail!(
+            "'{display}' does not have the format of a {} tax number.",
+            scheme.prefix
+        );
+    }
+    let status = match scheme.check_digits {
+        Some(check) if !check(national) => bail!(
+            "'{display}' is not a valid {} tax number: the check digits do not match.",
+            scheme.prefix
+        ),
+        Some(_) => TaxIdStatus::Checksum,
+        None => TaxIdStatus::Format,
+    };
+    Ok(TaxIdCheck {
+        normalized: format!("{}{national}", scheme.prefix),
+        prefix: Some(scheme.prefix),
+        status,
+        eu_vat: scheme.eu_vat,
+    })
+}
+
+/// Splits off the scheme prefix, mapping the ISO code of Greece and the Swiss `CHE` prefix
+/// and `MWST`/`TVA`/`IVA` suffix, and the Norwegian `MVA` suffix, to their scheme.
+fn split_prefix(cleaned: &str) -> (Option<&str>, &str) {
+    if let Some(rest) = cleaned.strip_prefix("CHE") {
+        let rest = ["MWST", "TVA", "IVA"]
+            .iter()
+            .find_map(|suffix| rest.strip_suffix(suffix))
+            .unwrap_or(rest);
+        return (Some("CH"), rest);
+    }
+    let (prefix, rest) = match cleaned.get(..2) {
+        Some(prefix) if cleaned.len() > 2 && prefix.bytes().all(|b| b.is_ascii_uppercase()) => {
+            (prefix, &cleaned[2..])
+        }
+        _ => return (None, cleaned),
+    };
+    match prefix {
+        "GR" => (Some("EL"), rest),
+        "NO" => (Some("NO"), rest.strip_suffix("MVA").unwrap_or(rest)),
+        _ => (Some(prefix), rest),
+    }
+}
+
+fn digits(number: &str) -> Vec<u32> {
+    number.chars().filter_map(|c| c.to_digit(10)).collect()
+}
+
+fn weighted_sum(digits: &[u32], weights: &[u32]) -> u32 {
+    digits.iter().zip(weights).map(|(d, w)| d * w).sum()
+}
+
+/// Weighted modulo 11 where the last digit is `11 - sum % 11`, 11 meaning 0 and 10 invalid.
+fn mod11(number: &str, weights: &[u32]) -> bool {
+    let digits = digits(number);
+    let check = 11 - weighted_sum(&digits, weights) % 11;
+    let check = if check == 11 { 0 } else { check };
+    check != 10 && digits.last() == Some(&check)
+}
+
+fn iso7064_mod11_10(number: &str) -> bool {
+    let digits = digits(number);
+    let Some((last, body)) = digits.split_last() else {
+        return false;
+    };
+    let mut product = 10;
+    for digit in body {
+        let sum = (digit + product) % 10;
+        product = (if sum == 0 { 10 } else { sum } * 2) % 11;
+    }
+    (11 - product) % 10 == *last
+}
+
+fn luhn(number: &str) -> bool {
+    let sum: u32 = digits(number)
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(index, digit)| match index % 2 {
+            0 => *digit,
+            _ if *digit > 4 => digit * 2 - 9,
+            _ => digit * 2,
+        })
+        .sum();
+    sum % 10 == 0
+}
+
+fn austria(number: &str) -> bool {
+    let digits = digits(number);
+    let sum: u32 = digits[..7]
+        .iter()
+        .enumerate()
+        .map(|(index, digit)| {
+            let product = digit * if index % 2 == 1 { 2 } else { 1 };
+            product / 10 + product % 10
+        })
+        .sum();
+    (10 - (sum + 4) % 10) % 10 == digits[7]
+}
+
+fn belgium(number: &str) -> bool {
+    let (body, check) = number.split_at(8);
+    match (body.parse::<u64>(), check.parse::<u64>()) {
+        (Ok(body), Ok(check)) => 97 - body % 97 == check,
+        _ => false,
+    }
+}
+
+fn denmark(number: &str) -> bool {
+    weighted_sum(&digits(number), &[2, 7, 6, 5, 4, 3, 2, 1]) % 11 == 0
+}
+
+fn estonia(number: &str) -> bool {
+    let digits = digits(number);
+    let sum = weighted_sum(&digits, &[3, 7, 1, 3, 7, 1, 3, 7]);
+    (10 - sum % 10) % 10 == digits[8]
+}
+
+fn finland(number: &str) -> bool {
+    let digits = digits(number);
+    match weighted_sum(&digits, &[7, 9, 10, 5, 8, 4, 2]) % 11 {
+        0 => digits[7] == 0,
+        1 => false,
+        rest => digits[7] == 11 - rest,
+    }
+}
+
+fn france(number: &str) -> bool {
+    let (key, siren) = number.split_at(2);
+    match (key.parse::<u64>(), siren.parse::<u64>()) {
+        (Ok(key), Ok(siren)) => key == (12 + 3 * (siren % 97)) % 97,
+        // Alphanumeric keys are issued to newer companies and cannot be recomputed.
+        _ => true,
+    }
+}
+
+fn greece(number: &str) -> bool {
+    let digits = digits(number);
+    let sum = weighted_sum(&digits, &[256, 128, 64, 32, 16, 8, 4, 2]);
+    sum % 11 % 10 == digits[8]
+}
+
+fn hungary(number: &str) -> bool {
+    let digits = digits(number);
+    let sum = weighted_sum(&digits, &[9, 7, 3, 1, 9, 7, 3]);
+    (10 - sum % 10) % 10 == digits[7]
+}
+
+fn luxembourg(number: &str) -> bool {
+    let (body, check) = number.split_at(6);
+    match (body.parse::<u64>(), check.parse::<u64>()) {
+        (Ok(body), Ok(check)) => body % 89 == check,
+        _ => false,
+    }
+}
+
+/// Dutch numbers use weighted modulo 11, or modulo 97 over the whole number for sole
+/// traders registered since 2020.
+fn netherlands(number: &str) -> bool {
+    let digits = digits(&number[..9]);
+    let sum = weighted_sum(&digits, &[9, 8, 7, 6, 5, 4, 3, 2]);
+    if sum % 11 == digits[8] {
+        return true;
+    }
+    // Letters count as 10 to 35, so "NL" is "2321" and "B" is "11".
+    let numeric: String = format!("NL{number}")
+        .chars()
+        .map(|c| c.to_digit(36).map_or_else(String::new, |v| v.to_string()))
+        .collect();
+    numeric
+        .bytes()
+        .fold(0u32, |rest, b| (rest * 10 + u32::from(b - b'0')) % 97)
+        == 1
+}
+
+fn poland(number: &str) -> bool {
+    let digits = digits(number);
+    let check = weighted_sum(&digits, &[6, 5, 7, 2, 3, 4, 5, 6, 7]) % 11;
+    check != 10 && check == digits[9]
+}
+
+fn portugal(number: &str) -> bool {
+    let digits = digits(number);
+    let check = 11 - weighted_sum(&digits, &[9, 8, 7, 6, 5, 4, 3, 2]) % 11;
+    (if check >= 10 { 0 } else { check }) == digits[8]
+}
+
+fn slovenia(number: &str) -> bool {
+    let digits = digits(number);
+    match 11 - weighted_sum(&digits, &[8, 7, 6, 5, 4, 3, 2]) % 11 {
+        11 => false,
+        10 => digits[7] == 0,
+        check => digits[7] == check,
+    }
+}
+
+/// Weighted modulo 97 over the first nine digits; numbers issued since 2010 are offset by 55.
+/// Government department (`GD`) and health authority (`HA`) numbers have no check digits.
+fn united_kingdom(number: &str) -> bool {
+    if number.starts_with("GD") || number.starts_with("HA") {
+        return true;
+    }
+    let digits = digits(&number[..9]);
+    let sum = weighted_sum(&digits, &[8, 7, 6, 5, 4, 3, 2]) + digits[7] * 10 + digits[8];
+    matches!(sum % 97, 0 | 55)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verifies_check_digits_of_known_schemes() {
+        for valid in [
+            "DE 136 695 976",
+            "BE 0202.239.951",
+            "FR40303265045",
+            "IT00743110157",
+            "NL004495445B01",
+            "ATU10223006",
+            "DK13585628",
+            "PL526-025-02-74",
+            "FI01120389",
+            "PT501964843",
+            "GB980780684",
+            "CHE-116.281.710 MWST",
+        ] {
+            let check = check_tax_id(valid).unwrap_or_else(|e| panic!("{valid}: {e}"));
+            assert_eq!(check.status, TaxIdStatus::Checksum, "{valid}");
+        }
+
+        let german = check_tax_id("de 136-695-976").unwrap();
+        assert_eq!(german.normalized, "DE136695976");
+        assert_eq!(german.national_number(), "136695976");
+        assert!(german.eu_vat);
+        assert!(!check_tax_id("CHE116281710").unwrap().eu_vat);
+
+        assert!(check_tax_id("DE136695977").is_err());
+        assert!(check_tax_id("DE13669597").is_err());
+        assert!(check_tax_id("NL004495446B01").is_err());
+    }
+
+    #[test]
+    fn keeps_unknown_and_format_only_numbers() {
+        let spanish = check_tax_id("ESB12345678").unwrap();
+        assert_eq!(spanish.status, TaxIdStatus::Format);
+        let greek = check_tax_id("GR094014201").unwrap();
+        assert_eq!(greek.normalized, "EL094014201");
+
+        let ein = check_tax_id("12-3456789").unwrap();
+        assert_eq!(ein.status, TaxIdStatus::Unverified);
+        assert_eq!(ein.normalized, "123456789");
+        assert!(ein.prefix.is_none());
+    }
+}
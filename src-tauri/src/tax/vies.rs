@@ -0,0 +1,121 @@
+//! Lookups of EU VAT numbers in the Commission's VIES register.
+//!
+//! Answers are cached for a day per number, so saving a client again or re-checking it while
+//! editing does not query the member state's service each time. Unavailable member state
+//! services are not cached.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result, bail};
+use serde::Deserialize;
+
+use super::TaxIdCheck;
+use crate::http::HttpClient;
+
+pub const VIES_URL: &str = "https://ec.europa.eu/taxation_customs/vies/rest-api/ms";
+const USER_AGENT: &str = "weg-translator/1.0 (vies)";
+const CACHE_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// What VIES reports for a VAT number.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ViesRegistration {
+    pub valid: bool,
+    /// Registered trader name, where the member state discloses it.
+    pub name: Option<String>,
+    pub address: Option<String>,
+}
+
+pub struct ViesClient {
+    client: HttpClient,
+    cache: Mutex<HashMap<String, (Instant, ViesRegistration)>>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ViesResponse {
+    is_valid: bool,
+    #[serde(default)]
+    user_error: Option<String>,
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    address: Option<String>,
+}
+
+impl ViesClient {
+    pub fn new() -> Self {
+        let client = HttpClient::new(|builder| {
+            builder
+                .user_agent(USER_AGENT)
+                .timeout(Duration::from_secs(10))
+        });
+        Self {
+            client,
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Looks `check` up in VIES. Fails when the number is not an EU VAT number or the
+    /// member state's service cannot answer.
+    pub async fn lookup(&self, check: &TaxIdCheck) -> Result<ViesRegistration> {
+        let (true, Some(prefix)) = (check.eu_vat, check.prefix) else {
+            bail!("{} is not an EU VAT number.", check.normalized);
+        };
+        if let Some(registration) = self.cached(&check.normalized) {
+            return Ok(registration);
+        }
+
+        let response: ViesResponse = self
+            .client
+            .current()
+            .get(format!(
+                "{VIES_URL}/{prefix}/vat/{}",
+                check.national_number()
+            ))
+            .send()
+            .await
+            .context("VIES could not be reached")?
+            .error_for_status()
+            .context("VIES rejected the request")?
+            .json()
+            .await
+            .context("VIES sent an unexpected response")?;
+        if let Some(error) = response
+            .user_error
+            .as_deref()
+            .filter(|error| !matches!(*error, "VALID" | "INVALID"))
+        {
+            bail!("VIES could not check the number ({error}).");
+        }
+
+        // Member states that do not disclose trader details answer "---".
+        let disclosed = |value: Option<String>| {
+            value
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty() && value != "---")
+        };
+        let registration = ViesRegistration {
+            valid: response.is_valid,
+            name: disclosed(response.name),
+            address: disclosed(response.address),
+        };
+        if let Ok(mut cache) = self.cache.lock() {
+            cache.retain(|_, (stored_at, _)| stored_at.elapsed() < CACHE_TTL);
+            cache.insert(
+                check.normalized.clone(),
+                (Instant::now(), registration.clone()),
+            );
+        }
+        Ok(registration)
+    }
+
+    fn cached(&self, normalized: &str) -> Option<ViesRegistration> {
+        let cache = self.cache.lock().ok()?;
+        cache
+            .get(normalized)
+            .filter(|(stored_at, _)| stored_at.elapsed() < CACHE_TTL)
+            .map(|(_, registration)| registration.clone())
+    }
+}
@@ -0,0 +1,345 @@
+//! Term candidate extraction from segment sources.
+//!
+//! Candidates are word n-grams that recur in the sources. An n-gram never crosses
+//! punctuation or an inline code, and may neither start nor end with a stopword of the
+//! language, so "the file" is not a candidate but "save as dialog" is. Shorter n-grams that
+//! only ever occur inside one longer candidate are dropped in its favour. Candidates are
+//! ranked by frequency, weighted towards multi-word terms.
+//!
+//! When confirmed translations are available, each candidate is paired with the target
+//! n-gram that co-occurs with it most consistently (Dice coefficient over segments).
+
+use std::collections::{HashMap, HashSet};
+
+use crate::jliff::restructure::placeholder_spans;
+
+/// Lowest Dice coefficient for a target n-gram to be proposed as an equivalent.
+const MIN_TARGET_DICE: f64 = 0.3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExtractionOptions {
+    /// Longest candidate, in words.
+    pub max_words: usize,
+    /// Fewest occurrences for a candidate to be kept.
+    pub min_frequency: usize,
+    /// Most candidates returned.
+    pub limit: usize,
+}
+
+impl Default for ExtractionOptions {
+    fn default() -> Self {
+        Self {
+            max_words: 3,
+            min_frequency: 2,
+            limit: 200,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TermCandidate {
+    /// Most frequent spelling of the term.
+    pub term: String,
+    /// Case-folded form candidates are deduplicated on.
+    pub key: String,
+    pub frequency: usize,
+    pub score: f64,
+    /// Proposed equivalent from confirmed translations.
+    pub target_term: Option<String>,
+}
+
+/// Extracts term candidates from segment sources in `language`, best first.
+pub fn extract_terms(
+    sources: &[&str],
+    language: &str,
+    options: ExtractionOptions,
+) -> Vec<TermCandidate> {
+    let max_words = options.max_words.max(1);
+    let stopwords = stopwords(language);
+
+    // key -> (frequency, spellings with their counts, words)
+    let mut counts: HashMap<String, (usize, HashMap<String, usize>, usize)> = HashMap::new();
+    for source in sources {
+        for chunk in chunks(source) {
+            for_each_ngram(&chunk, max_words, stopwords, |words| {
+                let surface = words.join(" ");
+                let entry = counts
+                    .entry(surface.to_lowercase())
+                    .or_insert_with(|| (0, HashMap::new(), words.len()));
+                entry.0 += 1;
+                *entry.1.entry(surface).or_default() += 1;
+            });
+        }
+    }
+
+    let frequent: HashMap<&String, usize> = counts
+        .iter()
+        .filter(|(_, (frequency, _, _))| *frequency >= options.min_frequency.max(1))
+        .map(|(key, (frequency, _, _))| (key, *frequency))
+        .collect();
+    let mut candidates: Vec<TermCandidate> = counts
+        .iter()
+        .filter(|(key, (frequency, _, _))| {
+            frequent.contains_key(key) && !nested_in_longer(key, *frequency, &frequent)
+        })
+        .map(|(key, (frequency, spellings, words))| {
+            let term = spellings
+                .iter()
+                .max_by(|left, right| {
+                    // Sentence-initial capitals should not win a tie.
+                    left.1
+                        .cmp(right.1)
+                        .then_with(|| (left.0 == key).cmp(&(right.0 == key)))
+                        .then_with(|| right.0.cmp(left.0))
+                })
+                .map(|(spelling, _)| spelling.clone())
+                .unwrap_or_else(|| key.clone());
+            TermCandidate {
+                term,
+                key: key.clone(),
+                frequency: *frequency,
+                score: *frequency as f64 * (1.0 + 0.5 * (*words as f64 - 1.0)),
+                target_term: None,
+            }
+        })
+        .collect();
+    candidates.sort_by(|left, right| {
+        right
+            .score
+            .total_cmp(&left.score)
+            .then_with(|| left.key.cmp(&right.key))
+    });
+    candidates.truncate(options.limit);
+    candidates
+}
+
+/// Proposes a target equivalent for each candidate from `(source, target)` pairs of
+/// confirmed translations.
+pub fn pair_targets(
+    candidates: &mut [TermCandidate],
+    translations: &[(&str, &str)],
+    target_language: &str,
+    max_words: usize,
+) {
+    let target_stopwords = stopwords(target_language);
+    let segments: Vec<(String, HashSet<String>)> = translations
+        .iter()
+        .map(|(source, target)| {
+            let mut ngrams = HashSet::new();
+            for chunk in chunks(target) {
+                for_each_ngram(&chunk, max_words.max(1) + 1, target_stopwords, |words| {
+                    ngrams.insert(words.join(" "));
+                });
+            }
+            (format!(" {} ", folded_words(source)), ngrams)
+        })
+        .collect();
+    let mut target_segments: HashMap<String, usize> = HashMap::new();
+    for (_, ngrams) in &segments {
+        for ngram in ngrams {
+            *target_segments.entry(ngram.to_lowercase()).or_default() += 1;
+        }
+    }
+
+    for candidate in candidates.iter_mut() {
+        let needle = format!(" {} ", candidate.key);
+        let mut together: HashMap<&String, usize> = HashMap::new();
+        let mut source_segments = 0;
+        for (source, ngrams) in &segments {
+            if !source.contains(&needle) {
+                continue;
+            }
+            source_segments += 1;
+            for ngram in ngrams {
+                *together.entry(ngram).or_default() += 1;
+            }
+        }
+        let best = together
+            .into_iter()
+            .filter(|(_, count)| *count >= source_segments.min(2))
+            .map(|(ngram, count)| {
+                let total = source_segments + target_segments[&ngram.to_lowercase()];
+                (ngram, 2.0 * count as f64 / total as f64)
+            })
+            .filter(|(_, dice)| *dice >= MIN_TARGET_DICE)
+            .max_by(|left, right| {
+                left.1
+                    .total_cmp(&right.1)
+                    .then_with(|| left.0.split(' ').count().cmp(&right.0.split(' ').count()))
+                    .then_with(|| right.0.cmp(left.0))
+            });
+        candidate.target_term = best.map(|(ngram, _)| ngram.clone());
+    }
+}
+
+/// Runs of words not separated by punctuation or inline codes.
+fn chunks(text: &str) -> Vec<Vec<String>> {
+    let mut plain = String::with_capacity(text.len());
+    let mut cursor = 0;
+    for (start, end) in placeholder_spans(text) {
+        plain.push_str(&text[cursor..start]);
+        plain.push('|');
+        cursor = end;
+    }
+    plain.push_str(&text[cursor..]);
+
+    let mut chunks = Vec::new();
+    let mut chunk: Vec<String> = Vec::new();
+    let mut word = String::new();
+    let chars: Vec<char> = plain.chars().collect();
+    for (index, &ch) in chars.iter().enumerate() {
+        let joins = matches!(ch, '-' | '\'' | '’')
+            && !word.is_empty()
+            && chars
+                .get(index + 1)
+                .is_some_and(|next| next.is_alphanumeric());
+        if ch.is_alphanumeric() || joins {
+            word.push(ch);
+            continue;
+        }
+        if !word.is_empty() {
+            chunk.push(std::mem::take(&mut word));
+        }
+        if !ch.is_whitespace() && !chunk.is_empty() {
+            chunks.push(std::mem::take(&mut chunk));
+        }
+    }
+    if !word.is_empty() {
+        chunk.push(word);
+    }
+    if !chunk.is_empty() {
+        chunks.push(chunk);
+    }
+    chunks
+}
+
+fn for_each_ngram(
+    words: &[String],
+    max_words: usize,
+    stopwords: &[&str],
+    mut visit: impl FnMut(&[String]),
+) {
+    let is_stopword = |word: &String| stopwords.contains(&word.to_lowercase().as_str());
+    for start in 0..words.len() {
+        if is_stopword(&words[start]) || !is_term_word(&words[start]) {
+            continue;
+        }
+        for end in start + 1..=(start + max_words).min(words.len()) {
+            let last = &words[end - 1];
+            if is_stopword(last) || !is_term_word(last) {
+                continue;
+            }
+            if end - start == 1 && words[start].chars().count() < 3 {
+                continue;
+            }
+            visit(&words[start..end]);
+        }
+    }
+}
+
+/// Words that can start or end a term: not a bare number.
+fn is_term_word(word: &str) -> bool {
+    word.chars().any(char::is_alphabetic)
+}
+
+fn folded_words(text: &str) -> String {
+    chunks(text)
+        .into_iter()
+        .map(|chunk| chunk.join(" "))
+        .collect::<Vec<_>>()
+        .join(" | ")
+        .to_lowercase()
+}
+
+/// Whether the n-gram occurs only as part of a single longer frequent n-gram.
+fn nested_in_longer(key: &str, frequency: usize, frequent: &HashMap<&String, usize>) -> bool {
+    let needle = format!(" {key} ");
+    frequent.iter().any(|(other, other_frequency)| {
+        *other_frequency == frequency
+            && other.len() > key.len()
+            && format!(" {other} ").contains(&needle)
+    })
+}
+
+fn stopwords(language: &str) -> &'static [&'static str] {
+    let primary = language
+        .split(['-', '_'])
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    match primary.as_str() {
+        "en" => &[
+            "a", "an", "and", "are", "as", "at", "be", "by", "can", "do", "for", "from", "has",
+            "have", "if", "in", "is", "it", "its", "may", "must", "no", "not", "of", "on", "or",
+            "our", "should", "so", "that", "the", "their", "then", "there", "these", "this", "to",
+            "was", "we", "were", "when", "which", "will", "with", "you", "your",
+        ],
+        "de" => &[
+            "als", "am", "an", "auch", "auf", "aus", "bei", "das", "dass", "dem", "den", "der",
+            "des", "die", "ein", "eine", "einem", "einen", "einer", "eines", "es", "für", "hat",
+            "im", "in", "ist", "kann", "mit", "nach", "nicht", "oder", "sich", "sie", "sind",
+            "und", "von", "vor", "werden", "wird", "wie", "zu", "zum", "zur",
+        ],
+        "fr" => &[
+            "à", "au", "aux", "avec", "ce", "ces", "dans", "de", "des", "du", "elle", "en", "est",
+            "et", "il", "la", "le", "les", "leur", "ne", "ou", "par", "pas", "pour", "qui", "que",
+            "sa", "se", "son", "sont", "sur", "un", "une", "vous",
+        ],
+        "es" => &[
+            "a", "al", "como", "con", "de", "del", "el", "en", "es", "esta", "este", "la", "las",
+            "lo", "los", "no", "o", "para", "por", "que", "se", "su", "sus", "un", "una", "y",
+        ],
+        "it" => &[
+            "a", "al", "alla", "che", "con", "da", "del", "della", "di", "e", "è", "gli", "i",
+            "il", "in", "la", "le", "lo", "non", "o", "per", "si", "su", "un", "una", "uno",
+        ],
+        "pt" => &[
+            "a", "ao", "as", "com", "da", "das", "de", "do", "dos", "e", "é", "em", "na", "no",
+            "o", "os", "ou", "para", "por", "que", "se", "um", "uma",
+        ],
+        "nl" => &[
+            "de", "den", "der", "een", "en", "het", "in", "is", "met", "niet", "of", "op", "te",
+            "van", "voor", "wordt", "zijn",
+        ],
+        _ => &[],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extracts_recurring_terms_and_pairs_targets() {
+        let sources = [
+            "Open the {{ph:1}}Print Settings dialog.",
+            "Close the print settings dialog, then restart.",
+            "The print settings dialog lists 3 printers.",
+            "Restart the printer.",
+        ];
+        let candidates = extract_terms(&sources, "en-US", ExtractionOptions::default());
+        let terms: Vec<&str> = candidates.iter().map(|c| c.term.as_str()).collect();
+        assert_eq!(terms[0], "print settings dialog");
+        assert!(!terms.contains(&"settings dialog"));
+        assert!(terms.contains(&"restart"));
+        assert!(!terms.iter().any(|term| term.starts_with("the ")));
+
+        let translations = [
+            (sources[0], "Öffnen Sie den Dialog Druckeinstellungen."),
+            (
+                sources[1],
+                "Schließen Sie den Dialog Druckeinstellungen und starten Sie neu.",
+            ),
+            (
+                sources[2],
+                "Der Dialog Druckeinstellungen listet 3 Drucker auf.",
+            ),
+        ];
+        let mut first = candidates[..1].to_vec();
+        pair_targets(&mut first, &translations, "de-DE", 3);
+        assert_eq!(
+            first[0].target_term.as_deref(),
+            Some("Dialog Druckeinstellungen")
+        );
+    }
+}
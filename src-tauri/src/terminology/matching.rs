@@ -0,0 +1,196 @@
+//! Termbase lookups in segment text.
+//!
+//! Terms and texts are compared word by word on their stems (see [`super::stemming`]), so a
+//! termbase entry "Lieferung" is found in "die Lieferungen" and "print dialog" in "Print
+//! dialogs". Inline codes are skipped and break a term: a term never spans a placeholder.
+//! Where several terms overlap, the earliest, then longest, wins.
+
+use std::collections::HashMap;
+
+use super::stemming::Analyzer;
+use crate::jliff::restructure::placeholder_spans;
+
+/// A term found in a text. `start` and `end` are byte offsets of the matched words.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TermHit {
+    /// Index of the term in the list the matcher was built from.
+    pub term_index: usize,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Finds a fixed list of terms in texts of one language.
+#[derive(Debug, Clone)]
+pub struct TermMatcher {
+    analyzer: Analyzer,
+    /// First stem -> (term index, stems of the term), longest terms first.
+    by_first_stem: HashMap<String, Vec<(usize, Vec<String>)>>,
+}
+
+impl TermMatcher {
+    pub fn new<S: AsRef<str>>(terms: &[S], language: &str) -> Self {
+        let analyzer = Analyzer::for_language(language);
+        let mut by_first_stem: HashMap<String, Vec<(usize, Vec<String>)>> = HashMap::new();
+        for (index, term) in terms.iter().enumerate() {
+            let stems: Vec<String> = words(term.as_ref())
+                .into_iter()
+                .map(|word| analyzer.stem(word.text))
+                .collect();
+            if let Some(first) = stems.first() {
+                by_first_stem
+                    .entry(first.clone())
+                    .or_default()
+                    .push((index, stems));
+            }
+        }
+        for terms in by_first_stem.values_mut() {
+            terms.sort_by_key(|(index, stems)| (std::cmp::Reverse(stems.len()), *index));
+        }
+        Self {
+            analyzer,
+            by_first_stem,
+        }
+    }
+
+    /// Non-overlapping term occurrences in `text`, in text order.
+    pub fn find(&self, text: &str) -> Vec<TermHit> {
+        let words = words(text);
+        let stems: Vec<String> = words
+            .iter()
+            .map(|word| self.analyzer.stem(word.text))
+            .collect();
+
+        let mut hits = Vec::new();
+        let mut position = 0;
+        while position < words.len() {
+            let found = self
+                .by_first_stem
+                .get(&stems[position])
+                .into_iter()
+                .flatten()
+                .find(|(_, term)| {
+                    let end = position + term.len();
+                    end <= words.len()
+                        && stems[position..end] == term[..]
+                        && words[position..end - 1]
+                            .iter()
+                            .all(|word| !word.break_after)
+                });
+            match found {
+                Some((term_index, term)) => {
+                    let last = position + term.len() - 1;
+                    hits.push(TermHit {
+                        term_index: *term_index,
+                        start: words[position].start,
+                        end: words[last].start + words[last].text.len(),
+                    });
+                    position = last + 1;
+                }
+                None => position += 1,
+            }
+        }
+        hits
+    }
+
+    /// Whether `term` occurs in `text`, inflected or not.
+    pub fn contains(text: &str, term: &str, language: &str) -> bool {
+        !Self::new(&[term], language).find(text).is_empty()
+    }
+}
+
+struct Word<'a> {
+    text: &'a str,
+    start: usize,
+    /// A placeholder or punctuation follows before the next word.
+    break_after: bool,
+}
+
+/// Words of `text` with their byte offsets. Hyphens and apostrophes inside a word are kept.
+fn words(text: &str) -> Vec<Word<'_>> {
+    let placeholders = placeholder_spans(text);
+    let mut words: Vec<Word<'_>> = Vec::new();
+    let mut start: Option<usize> = None;
+    let mut chars = text.char_indices().peekable();
+    while let Some((offset, ch)) = chars.next() {
+        if let Some(&(_, end)) = placeholders.iter().find(|(begin, _)| *begin == offset) {
+            if let Some(begin) = start.take() {
+                words.push(word(text, begin, offset));
+            }
+            if let Some(last) = words.last_mut() {
+                last.break_after = true;
+            }
+            while chars.peek().is_some_and(|(next, _)| *next < end) {
+                chars.next();
+            }
+            continue;
+        }
+        let joins = matches!(ch, '-' | '\'' | '’')
+            && start.is_some()
+            && chars.peek().is_some_and(|(_, next)| next.is_alphanumeric());
+        if ch.is_alphanumeric() || joins {
+            start.get_or_insert(offset);
+            continue;
+        }
+        if let Some(begin) = start.take() {
+            words.push(word(text, begin, offset));
+        }
+        if !ch.is_whitespace()
+            && let Some(last) = words.last_mut()
+        {
+            last.break_after = true;
+        }
+    }
+    if let Some(begin) = start {
+        words.push(word(text, begin, text.len()));
+    }
+    words
+}
+
+fn word(text: &str, start: usize, end: usize) -> Word<'_> {
+    Word {
+        text: &text[start..end],
+        start,
+        break_after: false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_inflected_terms_and_prefers_longer_ones() {
+        let terms = [
+            "Lieferung",
+            "Lieferadresse",
+            "Druckdialog",
+            "Druckdialog öffnen",
+        ];
+        let matcher = TermMatcher::new(&terms, "de");
+        let text = "Die Lieferungen gehen an die {{ph:1}}Lieferadressen. Druckdialoge öffnen.";
+        let hits = matcher.find(text);
+        let found: Vec<(&str, &str)> = hits
+            .iter()
+            .map(|hit| (terms[hit.term_index], &text[hit.start..hit.end]))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("Lieferung", "Lieferungen"),
+                ("Lieferadresse", "Lieferadressen"),
+                ("Druckdialog öffnen", "Druckdialoge öffnen"),
+            ]
+        );
+
+        assert!(TermMatcher::contains(
+            "Print dialogs.",
+            "print dialog",
+            "en"
+        ));
+        assert!(!TermMatcher::contains(
+            "Print. Dialog",
+            "print dialog",
+            "en"
+        ));
+    }
+}
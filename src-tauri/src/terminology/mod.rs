@@ -0,0 +1,7 @@
+//! Terminology: term candidates extracted from project content, the termbases they are
+//! accepted into (see `db::operations::terminology`), and lookups of termbase entries in
+//! segments that tolerate inflection.
+
+pub mod extraction;
+pub mod matching;
+pub mod stemming;
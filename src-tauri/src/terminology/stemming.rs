@@ -0,0 +1,208 @@
+//! Light stemmers used to match terms in their inflected forms.
+//!
+//! Each analyzer strips the inflectional endings of its language from a lowercased word, so
+//! "Lieferung" and "Lieferungen" share the stem "lieferung" and "talo", "talossa" and
+//! "taloissa" share "talo". Stems are matching keys, not words: they are never shown. The
+//! analyzers are deliberately light (endings only, no dictionaries) and keep at least three
+//! characters of every word, which errs towards missing a match rather than inventing one.
+//! German follows CISTEM (Weissweiler & Fraser, 2017); the Finnish and Slavic analyzers strip
+//! case endings in the manner of Savoy's light stemmers. Languages without an analyzer are
+//! matched on lowercased words.
+
+/// Shortest stem an ending may be stripped down to, in characters.
+const MIN_STEM: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Analyzer {
+    English,
+    German,
+    Dutch,
+    French,
+    Spanish,
+    Portuguese,
+    Italian,
+    Finnish,
+    Russian,
+    Ukrainian,
+    Polish,
+    Czech,
+    Slovak,
+    /// Lowercasing only.
+    Plain,
+}
+
+impl Analyzer {
+    /// Picks the analyzer for a BCP 47 language tag.
+    pub fn for_language(language: &str) -> Self {
+        let primary = language
+            .split(['-', '_'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        match primary.as_str() {
+            "en" => Self::English,
+            "de" => Self::German,
+            "nl" => Self::Dutch,
+            "fr" => Self::French,
+            "es" => Self::Spanish,
+            "pt" => Self::Portuguese,
+            "it" => Self::Italian,
+            "fi" => Self::Finnish,
+            "ru" => Self::Russian,
+            "uk" => Self::Ukrainian,
+            "pl" => Self::Polish,
+            "cs" => Self::Czech,
+            "sk" => Self::Slovak,
+            _ => Self::Plain,
+        }
+    }
+
+    /// Stem of a single word.
+    pub fn stem(self, word: &str) -> String {
+        let lower = word.to_lowercase();
+        match self {
+            Self::English => stem_english(&lower),
+            Self::German => stem_german(&lower, word.starts_with(char::is_uppercase)),
+            Self::Dutch => strip_longest(&lower, &["heden", "eren", "en", "es", "s", "e"]),
+            Self::French => strip_longest(&lower, &["aux", "eaux", "es", "s", "x", "e"]),
+            Self::Spanish | Self::Portuguese => {
+                strip_longest(&lower, &["ces", "es", "s", "a", "o", "e"])
+            }
+            Self::Italian => strip_longest(&lower, &["a", "e", "i", "o"]),
+            Self::Finnish => stem_finnish(&lower),
+            Self::Russian | Self::Ukrainian => strip_longest(&lower, RUSSIAN_ENDINGS),
+            Self::Polish => strip_longest(&lower, POLISH_ENDINGS),
+            Self::Czech | Self::Slovak => strip_longest(&lower, CZECH_ENDINGS),
+            Self::Plain => lower,
+        }
+    }
+}
+
+const RUSSIAN_ENDINGS: &[&str] = &[
+    "иями", "ями", "ами", "ого", "его", "ому", "ему", "ыми", "ими", "ией", "ой", "ей", "ий", "ый",
+    "ая", "яя", "ое", "ее", "ую", "юю", "ах", "ях", "ов", "ев", "ом", "ем", "ам", "ям", "ию", "ия",
+    "ие", "ы", "и", "а", "я", "о", "е", "у", "ю", "ь", "і", "ї", "є",
+];
+
+const POLISH_ENDINGS: &[&str] = &[
+    "iami", "ami", "ach", "ów", "om", "owi", "owie", "em", "iem", "ie", "ią", "ię", "y", "i", "a",
+    "ą", "ę", "u", "o", "e",
+];
+
+const CZECH_ENDINGS: &[&str] = &[
+    "ech", "ách", "ami", "emi", "ové", "ovi", "ům", "ou", "em", "ám", "y", "u", "a", "e", "i", "o",
+    "ů", "í", "é", "ě", "á",
+];
+
+const FINNISH_CASES: &[&str] = &[
+    "ssa", "ssä", "sta", "stä", "lla", "llä", "lta", "ltä", "lle", "ksi", "tta", "ttä", "ine",
+    "hin", "han", "hän", "seen", "siin", "na", "nä", "ta", "tä", "a", "ä", "n",
+];
+
+const FINNISH_POSSESSIVES: &[&str] = &["nsa", "nsä", "mme", "nne", "ni", "si", "an", "än"];
+
+/// Strips the longest of `endings` that leaves a stem of at least [`MIN_STEM`] characters.
+fn strip_longest(word: &str, endings: &[&str]) -> String {
+    let length = word.chars().count();
+    endings
+        .iter()
+        .filter(|ending| word.ends_with(*ending))
+        .filter(|ending| length >= MIN_STEM + ending.chars().count())
+        .max_by_key(|ending| ending.len())
+        .map(|ending| word[..word.len() - ending.len()].to_string())
+        .unwrap_or_else(|| word.to_string())
+}
+
+fn stem_english(word: &str) -> String {
+    let length = word.chars().count();
+    if length <= MIN_STEM {
+        return word.to_string();
+    }
+    if let Some(stem) = word.strip_suffix("ies")
+        && length > 4
+    {
+        return format!("{stem}y");
+    }
+    for sibilant in ["sses", "shes", "ches", "xes", "zes"] {
+        if word.ends_with(sibilant) {
+            return word[..word.len() - 2].to_string();
+        }
+    }
+    if word.ends_with('s') && !word.ends_with("ss") && !word.ends_with("us") {
+        return word[..word.len() - 1].to_string();
+    }
+    for ending in ["ing", "ed"] {
+        if word.ends_with(ending) && length >= MIN_STEM + ending.len() + 1 {
+            return word[..word.len() - ending.len()].to_string();
+        }
+    }
+    word.to_string()
+}
+
+/// CISTEM without its character substitutions: umlauts are folded, then `em`, `er` and `nd`
+/// are stripped from long words and `e`, `s`, `n` (and `t` from words not written as nouns)
+/// from any word, until none applies.
+fn stem_german(word: &str, capitalized: bool) -> String {
+    let mut stem: Vec<char> = word
+        .replace('ß', "ss")
+        .chars()
+        .map(|ch| match ch {
+            'ä' => 'a',
+            'ö' => 'o',
+            'ü' => 'u',
+            other => other,
+        })
+        .collect();
+    if stem.len() > 6 && stem.starts_with(&['g', 'e']) {
+        stem.drain(..2);
+    }
+    while stem.len() > MIN_STEM {
+        let ending: String = stem[stem.len() - 2..].iter().collect();
+        if stem.len() > 5 && matches!(ending.as_str(), "em" | "er" | "nd") {
+            stem.truncate(stem.len() - 2);
+        } else if matches!(stem.last(), Some('e' | 's' | 'n'))
+            || (!capitalized && stem.last() == Some(&'t'))
+        {
+            stem.pop();
+        } else {
+            break;
+        }
+    }
+    stem.into_iter().collect()
+}
+
+fn stem_finnish(word: &str) -> String {
+    let stem = strip_longest(word, FINNISH_POSSESSIVES);
+    let stem = strip_longest(&stem, FINNISH_CASES);
+    // Plural stems end in `i` or `j` before the case ending: "talo-i-ssa".
+    if stem.chars().count() > MIN_STEM + 1 && (stem.ends_with('i') || stem.ends_with('j')) {
+        stem[..stem.len() - 1].to_string()
+    } else {
+        stem
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inflected_forms_share_a_stem() {
+        let german = Analyzer::for_language("de-DE");
+        assert_eq!(german.stem("Lieferung"), german.stem("Lieferungen"));
+        assert_eq!(german.stem("Drucker"), german.stem("Druckern"));
+        assert_ne!(german.stem("Lieferung"), german.stem("Liefertermin"));
+
+        let finnish = Analyzer::for_language("fi");
+        assert_eq!(finnish.stem("talo"), finnish.stem("talossa"));
+        assert_eq!(finnish.stem("talo"), finnish.stem("taloissa"));
+
+        let russian = Analyzer::for_language("ru");
+        assert_eq!(russian.stem("поставка"), russian.stem("поставки"));
+        assert_eq!(russian.stem("поставка"), russian.stem("поставками"));
+
+        let english = Analyzer::for_language("en-GB");
+        assert_eq!(english.stem("Deliveries"), english.stem("delivery"));
+        assert_eq!(Analyzer::for_language("ja").stem("Tokyo"), "tokyo");
+    }
+}
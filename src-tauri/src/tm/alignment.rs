@@ -0,0 +1,236 @@
+//! Sentence alignment of a document with its translation, to build translation memory
+//! entries from past work.
+//!
+//! Both texts are split into sentences and aligned with the Gale-Church length model: the
+//! lengths of a sentence and its translation are proportional, and the normalised
+//! difference between them is roughly normally distributed. A dynamic programme picks the
+//! cheapest sequence of 1-1, 1-0, 0-1, 2-1, 1-2 and 2-2 groupings ("beads"). The length
+//! ratio is estimated from the two documents, so scripts of different density align too.
+//! Only a band around the diagonal is searched, which keeps long documents tractable.
+
+use anyhow::{Result, bail};
+
+use crate::jliff::segmentation::{CustomRule, split_sentences_with, uses_cjk_rules};
+
+/// Origin of the translation memory entries committed from alignments.
+pub const ALIGNMENT_ORIGIN: &str = "alignment";
+
+/// Variance of the length difference per character (Gale & Church, 1993).
+const VARIANCE: f64 = 6.8;
+/// Width of the searched band on each side of the diagonal, in sentences.
+const MIN_BAND: usize = 40;
+/// Larger documents should be split before aligning.
+pub const MAX_SENTENCES: usize = 20_000;
+
+/// Bead shapes with their prior probabilities.
+const BEADS: [(usize, usize, f64); 6] = [
+    (1, 1, 0.89),
+    (1, 0, 0.0099 / 2.0),
+    (0, 1, 0.0099 / 2.0),
+    (2, 1, 0.089 / 2.0),
+    (1, 2, 0.089 / 2.0),
+    (2, 2, 0.011),
+];
+
+/// Sentences of one side grouped with their translation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlignedPair {
+    /// Empty when the source sentence has no translation.
+    pub source_text: String,
+    /// Empty when the target sentence has no source.
+    pub target_text: String,
+    /// How plausible the two lengths are for a translation, from 0 to 1; 0 for unpaired
+    /// sentences.
+    pub score: f64,
+}
+
+impl AlignedPair {
+    pub fn is_paired(&self) -> bool {
+        !self.source_text.is_empty() && !self.target_text.is_empty()
+    }
+}
+
+/// Splits a plain-text document into sentences. Every non-blank line is a paragraph, so
+/// sentences never span a line break.
+pub fn document_sentences(text: &str, language: &str, rules: &[CustomRule]) -> Result<Vec<String>> {
+    let mut sentences = Vec::new();
+    for paragraph in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        for sentence in split_sentences_with(paragraph, language, rules)? {
+            let sentence = sentence.trim();
+            if !sentence.is_empty() {
+                sentences.push(sentence.to_string());
+            }
+        }
+    }
+    Ok(sentences)
+}
+
+/// Aligns `source` sentences with `target` sentences, in document order.
+pub fn align_sentences(
+    source: &[String],
+    target: &[String],
+    source_lang: &str,
+    target_lang: &str,
+) -> Result<Vec<AlignedPair>> {
+    if source.len() > MAX_SENTENCES || target.len() > MAX_SENTENCES {
+        bail!("documents longer than {MAX_SENTENCES} sentences must be split before aligning");
+    }
+    let source_lengths: Vec<f64> = source.iter().map(|text| char_len(text)).collect();
+    let target_lengths: Vec<f64> = target.iter().map(|text| char_len(text)).collect();
+    let ratio = {
+        let source_total: f64 = source_lengths.iter().sum();
+        let target_total: f64 = target_lengths.iter().sum();
+        if source_total > 0.0 && target_total > 0.0 {
+            target_total / source_total
+        } else {
+            1.0
+        }
+    };
+
+    let (rows, columns) = (source.len(), target.len());
+    let band = MIN_BAND + rows.abs_diff(columns);
+    let window = |row: usize| -> (usize, usize) {
+        let center = if rows == 0 { 0 } else { row * columns / rows };
+        (center.saturating_sub(band), (center + band).min(columns))
+    };
+
+    // cells[row][column - low]: cheapest cost to align the first `row` source and
+    // `column` target sentences, and the bead that reached it.
+    let mut cells: Vec<(usize, Vec<(f64, usize)>)> = Vec::with_capacity(rows + 1);
+    for row in 0..=rows {
+        let (low, high) = window(row);
+        let mut line = vec![(f64::INFINITY, usize::MAX); high - low + 1];
+        for column in low..=high {
+            if row == 0 && column == 0 {
+                line[0] = (0.0, usize::MAX);
+                continue;
+            }
+            for (bead, &(take_source, take_target, prior)) in BEADS.iter().enumerate() {
+                if take_source > row || take_target > column {
+                    continue;
+                }
+                let Some(previous) = cost_at(&cells, row - take_source, column - take_target)
+                else {
+                    continue;
+                };
+                let source_length: f64 = source_lengths[row - take_source..row].iter().sum();
+                let target_length: f64 = target_lengths[column - take_target..column].iter().sum();
+                let cost = previous + length_cost(source_length, target_length, ratio) - prior.ln();
+                if cost < line[column - low].0 {
+                    line[column - low] = (cost, bead);
+                }
+            }
+        }
+        cells.push((low, line));
+    }
+
+    let mut pairs = Vec::new();
+    let (mut row, mut column) = (rows, columns);
+    while row > 0 || column > 0 {
+        let (low, line) = &cells[row];
+        let Some(&(_, bead)) = column.checked_sub(*low).and_then(|index| line.get(index)) else {
+            bail!("the documents are too far apart to align");
+        };
+        let Some(&(take_source, take_target, _)) = BEADS.get(bead) else {
+            bail!("the documents are too far apart to align");
+        };
+        let source_part = &source[row - take_source..row];
+        let target_part = &target[column - take_target..column];
+        let score = if take_source > 0 && take_target > 0 {
+            let source_length: f64 = source_lengths[row - take_source..row].iter().sum();
+            let target_length: f64 = target_lengths[column - take_target..column].iter().sum();
+            (length_probability(source_length, target_length, ratio) * 100.0).round() / 100.0
+        } else {
+            0.0
+        };
+        pairs.push(AlignedPair {
+            source_text: join(source_part, source_lang),
+            target_text: join(target_part, target_lang),
+            score,
+        });
+        row -= take_source;
+        column -= take_target;
+    }
+    pairs.reverse();
+    Ok(pairs)
+}
+
+fn cost_at(cells: &[(usize, Vec<(f64, usize)>)], row: usize, column: usize) -> Option<f64> {
+    let (low, line) = cells.get(row)?;
+    let (cost, _) = *line.get(column.checked_sub(*low)?)?;
+    cost.is_finite().then_some(cost)
+}
+
+fn char_len(text: &str) -> f64 {
+    text.chars().filter(|ch| !ch.is_whitespace()).count() as f64
+}
+
+fn join(sentences: &[String], language: &str) -> String {
+    let separator = if uses_cjk_rules(language) { "" } else { " " };
+    sentences.join(separator)
+}
+
+/// Negative log probability of the length difference.
+fn length_cost(source_length: f64, target_length: f64, ratio: f64) -> f64 {
+    -length_probability(source_length, target_length, ratio)
+        .max(f64::MIN_POSITIVE)
+        .ln()
+}
+
+/// Two-sided probability of a length difference at least this large between a text and its
+/// translation.
+fn length_probability(source_length: f64, target_length: f64, ratio: f64) -> f64 {
+    if source_length == 0.0 && target_length == 0.0 {
+        return 1.0;
+    }
+    let mean = (source_length + target_length / ratio) / 2.0;
+    let z = (ratio * source_length - target_length) / (VARIANCE * mean).sqrt();
+    2.0 * (1.0 - normal_cdf(z.abs()))
+}
+
+/// Standard normal distribution function (Abramowitz & Stegun 26.2.17).
+fn normal_cdf(x: f64) -> f64 {
+    let t = 1.0 / (1.0 + 0.2316419 * x.abs());
+    let density = (-x * x / 2.0).exp() / (2.0 * std::f64::consts::PI).sqrt();
+    let tail = density
+        * t
+        * (0.319381530
+            + t * (-0.356563782 + t * (1.781477937 + t * (-1.821255978 + t * 1.330274429))));
+    if x >= 0.0 { 1.0 - tail } else { tail }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aligns_sentences_merged_in_the_translation() {
+        let source = document_sentences(
+            "Open the settings. Choose a language for the interface.\n\
+             Restart the application to apply the change. Contact support if it fails.",
+            "en",
+            &[],
+        )
+        .unwrap();
+        let target = document_sentences(
+            "Öffnen Sie die Einstellungen. Wählen Sie eine Sprache für die \
+             Benutzeroberfläche.\n\
+             Starten Sie die Anwendung neu, um die Änderung zu übernehmen, und wenden Sie sich \
+             an den Support, falls dies fehlschlägt.",
+            "de",
+            &[],
+        )
+        .unwrap();
+        assert_eq!((source.len(), target.len()), (4, 3));
+
+        let pairs = align_sentences(&source, &target, "en", "de").unwrap();
+        assert_eq!(pairs.len(), 3);
+        assert_eq!(pairs[0].target_text, "Öffnen Sie die Einstellungen.");
+        assert!(pairs[1].score > 0.5);
+        assert_eq!(
+            pairs[2].source_text,
+            "Restart the application to apply the change. Contact support if it fails."
+        );
+        assert!(pairs.iter().all(AlignedPair::is_paired));
+    }
+}
@@ -0,0 +1,283 @@
+//! Translation memory housekeeping.
+//!
+//! The functions here plan changes over a snapshot of a language pair's entries: duplicates
+//! that differ only in whitespace, sources translated in more than one way, filtered
+//! find/replace edits, and the entries of the opposite language direction. Callers preview
+//! a plan or apply it through `DbManager`.
+
+use std::collections::{HashMap, HashSet};
+
+use uuid::Uuid;
+
+use super::normalize;
+use crate::db::types::{NewTranslationMemoryEntryArgs, TranslationMemoryEntryRecord};
+
+/// Origin recorded on entries created by [`reversed_entries`].
+pub const REVERSED_ORIGIN: &str = "reversed";
+
+/// Selects entries; every criterion that is set must match. Text criteria ignore case.
+#[derive(Debug, Clone, Default)]
+pub struct EntryFilter {
+    pub source_contains: Option<String>,
+    pub target_contains: Option<String>,
+    pub origin: Option<String>,
+    pub project_uuid: Option<Uuid>,
+}
+
+impl EntryFilter {
+    pub fn is_empty(&self) -> bool {
+        self.source_contains.is_none()
+            && self.target_contains.is_none()
+            && self.origin.is_none()
+            && self.project_uuid.is_none()
+    }
+
+    pub fn matches(&self, entry: &TranslationMemoryEntryRecord) -> bool {
+        let contains = |text: &str, needle: &Option<String>| {
+            needle
+                .as_deref()
+                .is_none_or(|needle| text.to_lowercase().contains(&needle.to_lowercase()))
+        };
+        contains(&entry.source_text, &self.source_contains)
+            && contains(&entry.target_text, &self.target_contains)
+            && self
+                .origin
+                .as_deref()
+                .is_none_or(|origin| entry.origin == origin)
+            && self
+                .project_uuid
+                .is_none_or(|project_uuid| entry.project_uuid == Some(project_uuid))
+    }
+}
+
+/// Entries holding the same pair once whitespace is collapsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DuplicateGroup {
+    pub kept: Uuid,
+    pub removed: Vec<Uuid>,
+    pub source_text: String,
+    pub target_text: String,
+}
+
+/// Groups duplicate entries, keeping the first of each group. Entries are expected most
+/// recently updated first, as the database lists them, so the freshest copy survives.
+pub fn find_duplicates(entries: &[TranslationMemoryEntryRecord]) -> Vec<DuplicateGroup> {
+    let mut groups: Vec<DuplicateGroup> = Vec::new();
+    let mut index: HashMap<(String, String), usize> = HashMap::new();
+    for entry in entries {
+        let key = (normalize(&entry.source_text), normalize(&entry.target_text));
+        match index.get(&key) {
+            Some(&position) => groups[position].removed.push(entry.entry_uuid),
+            None => {
+                index.insert(key, groups.len());
+                groups.push(DuplicateGroup {
+                    kept: entry.entry_uuid,
+                    removed: Vec::new(),
+                    source_text: entry.source_text.clone(),
+                    target_text: entry.target_text.clone(),
+                });
+            }
+        }
+    }
+    groups.retain(|group| !group.removed.is_empty());
+    groups
+}
+
+/// One of the translations stored for a source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TargetVariant {
+    pub target_text: String,
+    pub entry_uuids: Vec<Uuid>,
+    /// Latest update among the entries holding this translation.
+    pub updated_at: String,
+}
+
+/// A source stored with more than one distinct translation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InconsistentSource {
+    pub source_text: String,
+    pub variants: Vec<TargetVariant>,
+}
+
+/// Sources with several translations, in source order; variants are listed most recently
+/// updated first.
+pub fn find_inconsistencies(entries: &[TranslationMemoryEntryRecord]) -> Vec<InconsistentSource> {
+    let mut sources: HashMap<String, InconsistentSource> = HashMap::new();
+    for entry in entries {
+        let group = sources
+            .entry(normalize(&entry.source_text))
+            .or_insert_with(|| InconsistentSource {
+                source_text: entry.source_text.clone(),
+                variants: Vec::new(),
+            });
+        let target = normalize(&entry.target_text);
+        match group
+            .variants
+            .iter_mut()
+            .find(|variant| normalize(&variant.target_text) == target)
+        {
+            Some(variant) => {
+                variant.entry_uuids.push(entry.entry_uuid);
+                if entry.updated_at > variant.updated_at {
+                    variant.updated_at = entry.updated_at.clone();
+                }
+            }
+            None => group.variants.push(TargetVariant {
+                target_text: entry.target_text.clone(),
+                entry_uuids: vec![entry.entry_uuid],
+                updated_at: entry.updated_at.clone(),
+            }),
+        }
+    }
+    let mut inconsistent: Vec<InconsistentSource> = sources
+        .into_values()
+        .filter(|group| group.variants.len() > 1)
+        .map(|mut group| {
+            group
+                .variants
+                .sort_by(|left, right| right.updated_at.cmp(&left.updated_at));
+            group
+        })
+        .collect();
+    inconsistent.sort_by(|left, right| left.source_text.cmp(&right.source_text));
+    inconsistent
+}
+
+/// Which texts a find/replace edit rewrites.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextField {
+    Source,
+    Target,
+    Both,
+}
+
+impl TextField {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "source" => Some(Self::Source),
+            "target" => Some(Self::Target),
+            "both" => Some(Self::Both),
+            _ => None,
+        }
+    }
+}
+
+/// New texts for an entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntryEdit {
+    pub entry_uuid: Uuid,
+    pub source_text: String,
+    pub target_text: String,
+}
+
+/// Replaces every occurrence of `find` in the selected texts of the matching entries.
+/// Entries the replacement leaves unchanged are not returned.
+pub fn plan_replacements(
+    entries: &[TranslationMemoryEntryRecord],
+    filter: &EntryFilter,
+    find: &str,
+    replace: &str,
+    field: TextField,
+) -> Vec<EntryEdit> {
+    entries
+        .iter()
+        .filter(|entry| filter.matches(entry))
+        .filter_map(|entry| {
+            let rewrite = |text: &str, selected: bool| {
+                if selected {
+                    text.replace(find, replace)
+                } else {
+                    text.to_string()
+                }
+            };
+            let source_text = rewrite(&entry.source_text, field != TextField::Target);
+            let target_text = rewrite(&entry.target_text, field != TextField::Source);
+            (source_text != entry.source_text || target_text != entry.target_text).then(|| {
+                EntryEdit {
+                    entry_uuid: entry.entry_uuid,
+                    source_text,
+                    target_text,
+                }
+            })
+        })
+        .collect()
+}
+
+/// The entries of the opposite language direction, one per distinct pair.
+pub fn reversed_entries(
+    entries: &[TranslationMemoryEntryRecord],
+) -> Vec<NewTranslationMemoryEntryArgs> {
+    let mut seen = HashSet::new();
+    entries
+        .iter()
+        .filter(|entry| seen.insert((&entry.source_text, &entry.target_text)))
+        .map(|entry| NewTranslationMemoryEntryArgs {
+            source_lang: entry.target_lang.clone(),
+            target_lang: entry.source_lang.clone(),
+            source_text: entry.target_text.clone(),
+            target_text: entry.source_text.clone(),
+            project_uuid: entry.project_uuid,
+            origin: REVERSED_ORIGIN.to_string(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(source: &str, target: &str, updated_at: &str) -> TranslationMemoryEntryRecord {
+        TranslationMemoryEntryRecord {
+            entry_uuid: Uuid::new_v4(),
+            source_lang: "en-us".into(),
+            target_lang: "de-de".into(),
+            source_text: source.into(),
+            target_text: target.into(),
+            project_uuid: None,
+            origin: "editor".into(),
+            created_at: updated_at.into(),
+            updated_at: updated_at.into(),
+        }
+    }
+
+    #[test]
+    fn finds_duplicates_and_inconsistent_sources() {
+        let entries = [
+            entry("Save the file.", "Datei speichern.", "2026-03-02"),
+            entry("Save  the file.", "Datei  speichern.", "2026-03-01"),
+            entry("Save the file.", "Speichern Sie die Datei.", "2026-02-01"),
+            entry("Cancel", "Abbrechen", "2026-01-01"),
+        ];
+
+        let duplicates = find_duplicates(&entries);
+        assert_eq!(duplicates.len(), 1);
+        assert_eq!(duplicates[0].kept, entries[0].entry_uuid);
+        assert_eq!(duplicates[0].removed, [entries[1].entry_uuid]);
+
+        let inconsistent = find_inconsistencies(&entries);
+        assert_eq!(inconsistent.len(), 1);
+        assert_eq!(inconsistent[0].source_text, "Save the file.");
+        let variants = &inconsistent[0].variants;
+        assert_eq!(variants.len(), 2);
+        assert_eq!(variants[0].entry_uuids.len(), 2);
+        assert_eq!(variants[1].target_text, "Speichern Sie die Datei.");
+
+        let filter = EntryFilter {
+            target_contains: Some("DATEI".into()),
+            ..EntryFilter::default()
+        };
+        let edits = plan_replacements(&entries, &filter, "Datei", "Akte", TextField::Target);
+        assert_eq!(edits.len(), 3);
+        assert_eq!(edits[2].target_text, "Speichern Sie die Akte.");
+
+        let reversed = reversed_entries(&entries);
+        assert_eq!(reversed.len(), 4);
+        assert_eq!(
+            (
+                reversed[3].source_lang.as_str(),
+                reversed[3].source_text.as_str()
+            ),
+            ("de-de", "Abbrechen")
+        );
+    }
+}
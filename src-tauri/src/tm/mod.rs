@@ -0,0 +1,278 @@
+//! Translation memory lookups.
+//!
+//! Entries are stored in SQLite (see `db::operations::translation_memory`); this module
+//! scores them against segment sources. Scores are percentages: 100 means the source is
+//! identical after collapsing whitespace, lower values come from the character edit
+//! distance between the two sources. A candidate's penalty is taken off its scores, so a
+//! penalised exact match is reported as a fuzzy one. Concordance lookups use
+//! [`find_occurrence`], which locates a query phrase (or, failing that, all of its words) in
+//! a text.
+
+pub mod alignment;
+pub mod maintenance;
+
+use std::collections::HashMap;
+
+/// A source/target pair offered to the matcher.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TmCandidate {
+    pub source: String,
+    pub target: String,
+    /// Percentage points taken off the scores of this candidate.
+    pub penalty: u8,
+}
+
+/// The best candidate found for a source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TmMatch {
+    pub target: String,
+    /// Similarity percentage (0-100).
+    pub score: u8,
+}
+
+impl TmMatch {
+    pub fn is_exact(&self) -> bool {
+        self.score == 100
+    }
+}
+
+/// Scores segment sources against a fixed set of candidates.
+///
+/// Candidates listed first win ties, so callers pass the entries of higher-priority
+/// memories first and, within a memory, the most recent entries first.
+#[derive(Debug, Default)]
+pub struct TmMatcher {
+    candidates: Vec<(Vec<char>, TmCandidate)>,
+    exact: HashMap<String, Vec<usize>>,
+}
+
+impl TmMatcher {
+    pub fn new(candidates: impl IntoIterator<Item = TmCandidate>) -> Self {
+        let mut matcher = TmMatcher::default();
+        for candidate in candidates {
+            let key = normalize(&candidate.source);
+            let index = matcher.candidates.len();
+            matcher.exact.entry(key.clone()).or_default().push(index);
+            matcher.candidates.push((key.chars().collect(), candidate));
+        }
+        matcher
+    }
+
+    /// Returns the highest-scoring candidate at or above `min_score`, penalties applied.
+    pub fn best_match(&self, source: &str, min_score: u8) -> Option<TmMatch> {
+        let key = normalize(source);
+        let mut best: Option<(u8, usize)> = None;
+        for &index in self.exact.get(&key).into_iter().flatten() {
+            let score = 100u8.saturating_sub(self.candidates[index].1.penalty);
+            keep_best(&mut best, score, index, min_score);
+        }
+        // Nothing beats an exact match without penalty.
+        if best.is_none_or(|(score, _)| score < 100) {
+            let chars: Vec<char> = key.chars().collect();
+            for (index, (candidate_chars, candidate)) in self.candidates.iter().enumerate() {
+                // The length difference alone bounds the achievable score.
+                let bound = length_bound(chars.len(), candidate_chars.len());
+                if bound.saturating_sub(candidate.penalty) < min_score {
+                    continue;
+                }
+                let score = fuzzy_score(&chars, candidate_chars).saturating_sub(candidate.penalty);
+                keep_best(&mut best, score, index, min_score);
+            }
+        }
+        best.map(|(score, index)| to_match(&self.candidates[index].1, score))
+    }
+}
+
+fn keep_best(best: &mut Option<(u8, usize)>, score: u8, index: usize, min_score: u8) {
+    if score >= min_score && best.is_none_or(|(best_score, _)| score > best_score) {
+        *best = Some((score, index));
+    }
+}
+
+/// Where a concordance query occurs in a text. Offsets count characters, not bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Occurrence {
+    pub start: usize,
+    pub end: usize,
+    /// Ranking percentage: phrase occurrences score 50-100, scattered words 1-49, higher
+    /// when the query covers more of the text.
+    pub score: u8,
+}
+
+/// Finds `query` in `text`, ignoring case. Returns `None` unless the whole phrase or every
+/// word of the query occurs.
+pub fn find_occurrence(text: &str, query: &str) -> Option<Occurrence> {
+    let query = normalize(query);
+    let query_chars = fold(&query);
+    let text_chars = fold(text);
+    if query_chars.is_empty() || text_chars.is_empty() {
+        return None;
+    }
+
+    if let Some(start) = find_chars(&text_chars, &query_chars) {
+        let coverage = query_chars.len() * 50 / text_chars.len();
+        return Some(Occurrence {
+            start,
+            end: start + query_chars.len(),
+            score: (50 + coverage).min(100) as u8,
+        });
+    }
+
+    let mut start = usize::MAX;
+    let mut end = 0;
+    let mut covered = 0;
+    for word in query.split(' ') {
+        let word_chars = fold(word);
+        let position = find_chars(&text_chars, &word_chars)?;
+        start = start.min(position);
+        end = end.max(position + word_chars.len());
+        covered += word_chars.len();
+    }
+    Some(Occurrence {
+        start,
+        end,
+        score: (covered * 49 / text_chars.len()).clamp(1, 49) as u8,
+    })
+}
+
+/// Lowercases character by character so offsets into the result match the input.
+fn fold(text: &str) -> Vec<char> {
+    text.chars()
+        .map(|c| c.to_lowercase().next().unwrap_or(c))
+        .collect()
+}
+
+fn find_chars(haystack: &[char], needle: &[char]) -> Option<usize> {
+    if needle.len() > haystack.len() {
+        return None;
+    }
+    (0..=haystack.len() - needle.len()).find(|&start| haystack[start..].starts_with(needle))
+}
+
+/// `source` with whitespace runs collapsed to single spaces; the key of exact matches.
+pub fn normalize(source: &str) -> String {
+    source.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Similarity percentage of two texts on the scale of TM match scores.
+pub fn similarity(left: &str, right: &str) -> u8 {
+    let left = normalize(left);
+    let right = normalize(right);
+    if left == right {
+        return 100;
+    }
+    let left: Vec<char> = left.chars().collect();
+    let right: Vec<char> = right.chars().collect();
+    fuzzy_score(&left, &right)
+}
+
+fn to_match(candidate: &TmCandidate, score: u8) -> TmMatch {
+    TmMatch {
+        target: candidate.target.clone(),
+        score,
+    }
+}
+
+fn length_bound(left: usize, right: usize) -> u8 {
+    let longest = left.max(right);
+    if longest == 0 {
+        return 100;
+    }
+    (left.min(right) * 100 / longest) as u8
+}
+
+/// Edit-distance similarity, capped at 99 so only identical sources score 100.
+fn fuzzy_score(left: &[char], right: &[char]) -> u8 {
+    let longest = left.len().max(right.len());
+    if longest == 0 {
+        return 100;
+    }
+    let distance = levenshtein(left, right);
+    let score = (longest - distance) * 100 / longest;
+    score.min(99) as u8
+}
+
+fn levenshtein(left: &[char], right: &[char]) -> usize {
+    let mut previous: Vec<usize> = (0..=right.len()).collect();
+    let mut current = vec![0; right.len() + 1];
+    for (i, left_char) in left.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, right_char) in right.iter().enumerate() {
+            let substitution = previous[j] + usize::from(left_char != right_char);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[right.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(source: &str, target: &str) -> TmCandidate {
+        TmCandidate {
+            source: source.into(),
+            target: target.into(),
+            penalty: 0,
+        }
+    }
+
+    #[test]
+    fn prefers_exact_then_closest_fuzzy_match() {
+        let matcher = TmMatcher::new([
+            candidate("Open the file.", "Öffnen Sie die Datei."),
+            candidate("Close the file.", "Schließen Sie die Datei."),
+            candidate("Save  the file.", "Speichern Sie die Datei."),
+        ]);
+
+        let exact = matcher.best_match("Save the file.", 75).unwrap();
+        assert!(exact.is_exact());
+        assert_eq!(exact.target, "Speichern Sie die Datei.");
+
+        let fuzzy = matcher.best_match("Close the files.", 75).unwrap();
+        assert_eq!(fuzzy.target, "Schließen Sie die Datei.");
+        assert_eq!(fuzzy.score, 93);
+
+        assert!(matcher.best_match("Print the report now.", 75).is_none());
+        assert_eq!(matcher.best_match("save the file.", 75).unwrap().score, 92);
+    }
+
+    #[test]
+    fn penalties_lower_scores_and_break_ties() {
+        let matcher = TmMatcher::new([
+            TmCandidate {
+                penalty: 5,
+                ..candidate("Save the file.", "Datei speichern.")
+            },
+            candidate("Save the files.", "Dateien speichern."),
+        ]);
+
+        let found = matcher.best_match("Save the file.", 75).unwrap();
+        assert!(!found.is_exact());
+        assert_eq!(
+            (found.target.as_str(), found.score),
+            ("Datei speichern.", 95)
+        );
+        assert!(matcher.best_match("Save the file.", 96).is_none());
+
+        let penalised_only = TmMatcher::new([TmCandidate {
+            penalty: 30,
+            ..candidate("Close the file.", "Datei schließen.")
+        }]);
+        assert!(penalised_only.best_match("Close the files.", 75).is_none());
+    }
+
+    #[test]
+    fn finds_phrases_before_scattered_words() {
+        let phrase = find_occurrence("Öffnen Sie die Datei.", "die  DATEI").unwrap();
+        assert_eq!((phrase.start, phrase.end), (11, 20));
+        assert_eq!(phrase.score, 71);
+
+        let words = find_occurrence("Save the file to disk.", "disk file").unwrap();
+        assert_eq!((words.start, words.end), (9, 21));
+        assert!(words.score < 50);
+
+        assert!(find_occurrence("Save the file.", "print file").is_none());
+    }
+}
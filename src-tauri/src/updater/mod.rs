@@ -0,0 +1,606 @@
+//! Self-update: checks the release feed, stages the update for this platform in the
+//! background and verifies it before it is installed.
+//!
+//! The feed URL and the hex Ed25519 public key releases are signed with are compiled into
+//! the binary from the `UPDATE_FEED_URL` and `UPDATE_PUBLIC_KEY` build environment, so
+//! nothing on the user's machine can redirect or re-key updates. Without both, or when the
+//! feed is not `https://`, updates are disabled. The feed is JSON:
+//!
+//! ```json
+//! {"version": "2.4.0", "notes": "...", "pubDate": "2026-10-01T00:00:00Z",
+//!  "platforms": {"windows-x86_64": {"url": "...", "sha256": "...", "signature": "...",
+//!                                   "size": 84213760}}}
+//! ```
+//!
+//! Platform keys are `<os>-<arch>` as Rust names them. Installer URLs must be `https://`
+//! too. The signature (hex) signs the 32-byte SHA-256 digest of the installer. Installers
+//! are downloaded with the shared [`Downloader`] into the `updates` category of the
+//! download cache, so an interrupted download resumes on the next check; the signature is
+//! checked once the download is complete and again right before the installer is launched.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use ed25519_dalek::{Signature, VerifyingKey};
+use serde::Deserialize;
+use thiserror::Error;
+use tokio::sync::Mutex as AsyncMutex;
+
+use crate::downloads::{DownloadError, Downloader, sha256_file};
+use crate::http::HttpClient;
+
+/// URL of the release feed, fixed at build time.
+const FEED_URL: Option<&str> = option_env!("UPDATE_FEED_URL");
+/// Hex Ed25519 public key releases are signed with, fixed at build time.
+const PUBLIC_KEY: Option<&str> = option_env!("UPDATE_PUBLIC_KEY");
+/// Download cache category staged installers are kept in.
+pub const UPDATES_CATEGORY: &str = "updates";
+
+const FEED_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Errors raised while checking for, staging or installing an update.
+#[derive(Debug, Error)]
+pub enum UpdateError {
+    #[error("updates are not configured in this build")]
+    Disabled,
+    #[error("the release feed could not be read: {0}")]
+    Feed(String),
+    #[error("failed to download the update: {0}")]
+    Download(#[from] DownloadError),
+    #[error("the update's signature is not valid")]
+    InvalidSignature,
+    #[error("no update is ready to install")]
+    NotReady,
+    #[error("failed to launch the installer: {0}")]
+    Launch(#[from] std::io::Error),
+}
+
+/// Where the updater is in its cycle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpdateState {
+    /// No feed or key was built in.
+    Disabled,
+    /// No check ran yet in this session.
+    Idle,
+    Checking,
+    UpToDate,
+    Downloading,
+    /// The update is downloaded and verified.
+    Ready,
+    Failed,
+}
+
+impl UpdateState {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            UpdateState::Disabled => "disabled",
+            UpdateState::Idle => "idle",
+            UpdateState::Checking => "checking",
+            UpdateState::UpToDate => "up_to_date",
+            UpdateState::Downloading => "downloading",
+            UpdateState::Ready => "ready",
+            UpdateState::Failed => "failed",
+        }
+    }
+}
+
+/// The release feed.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReleaseFeed {
+    pub version: String,
+    #[serde(default)]
+    pub notes: Option<String>,
+    #[serde(default)]
+    pub pub_date: Option<String>,
+    pub platforms: HashMap<String, PlatformRelease>,
+}
+
+/// The installer of a release for one platform.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlatformRelease {
+    pub url: String,
+    pub sha256: String,
+    pub signature: String,
+    #[serde(default)]
+    pub size: Option<u64>,
+}
+
+/// A release newer than the running version, with the installer for this platform.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AvailableRelease {
+    pub version: String,
+    pub notes: Option<String>,
+    pub pub_date: Option<String>,
+    pub installer: PlatformRelease,
+}
+
+impl AvailableRelease {
+    /// Name the installer is staged under, prefixed with the version.
+    pub fn file_name(&self) -> String {
+        let name = self
+            .installer
+            .url
+            .split(['?', '#'])
+            .next()
+            .and_then(|url| url.rsplit('/').next())
+            .filter(|name| !name.is_empty() && *name != "..")
+            .unwrap_or("installer");
+        format!("{}-{name}", self.version)
+    }
+}
+
+/// What the updater knows right now.
+#[derive(Debug, Clone)]
+pub struct UpdateStatus {
+    pub state: UpdateState,
+    pub release: Option<AvailableRelease>,
+    pub downloaded: u64,
+    pub total: Option<u64>,
+    pub staged_path: Option<PathBuf>,
+    pub error: Option<String>,
+    pub checked_at: Option<DateTime<Utc>>,
+}
+
+impl UpdateStatus {
+    fn new(state: UpdateState) -> Self {
+        Self {
+            state,
+            release: None,
+            downloaded: 0,
+            total: None,
+            staged_path: None,
+            error: None,
+            checked_at: None,
+        }
+    }
+}
+
+struct UpdaterConfig {
+    feed_url: String,
+    public_key: [u8; 32],
+}
+
+/// Updater registered as Tauri state. One check runs at a time.
+pub struct Updater {
+    client: HttpClient,
+    config: Option<UpdaterConfig>,
+    status: Mutex<UpdateStatus>,
+    running: AsyncMutex<()>,
+}
+
+impl Updater {
+    /// Uses the feed URL and public key built into the binary.
+    pub fn built_in() -> Self {
+        Self::new(updater_config(FEED_URL, PUBLIC_KEY))
+    }
+
+    fn new(config: Option<UpdaterConfig>) -> Self {
+        let client = HttpClient::new(|builder| builder.timeout(FEED_TIMEOUT));
+        let state = if config.is_some() {
+            UpdateState::Idle
+        } else {
+            UpdateState::Disabled
+        };
+        Self {
+            client,
+            config,
+            status: Mutex::new(UpdateStatus::new(state)),
+            running: AsyncMutex::new(()),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.config.is_some()
+    }
+
+    /// Address of the release feed, when updates are enabled.
+    pub fn feed_url(&self) -> Option<&str> {
+        self.config.as_ref().map(|config| config.feed_url.as_str())
+    }
+
+    pub fn status(&self) -> UpdateStatus {
+        self.status.lock().expect("update status poisoned").clone()
+    }
+
+    /// Checks the feed and, when a newer release exists, downloads and verifies its
+    /// installer into `cache_root`. `on_change` sees every status change, download progress
+    /// included. A check started while another runs returns the current status.
+    pub async fn check(
+        &self,
+        downloader: &Downloader,
+        cache_root: &Path,
+        mut on_change: impl FnMut(&UpdateStatus),
+    ) -> Result<UpdateStatus, UpdateError> {
+        let config = self.config.as_ref().ok_or(UpdateError::Disabled)?;
+        let Ok(_running) = self.running.try_lock() else {
+            return Ok(self.status());
+        };
+
+        let previous = self.status();
+        on_change(&self.update(|status| {
+            status.state = UpdateState::Checking;
+            status.error = None;
+        }));
+        match self
+            .stage(config, downloader, cache_root, &previous, &mut on_change)
+            .await
+        {
+            Ok(status) => Ok(status),
+            Err(error) => {
+                log::warn!(target: "updater", "update check failed: {error}");
+                let status = self.update(|status| {
+                    status.state = UpdateState::Failed;
+                    status.error = Some(error.to_string());
+                    status.checked_at = Some(Utc::now());
+                });
+                on_change(&status);
+                Err(error)
+            }
+        }
+    }
+
+    async fn stage(
+        &self,
+        config: &UpdaterConfig,
+        downloader: &Downloader,
+        cache_root: &Path,
+        previous: &UpdateStatus,
+        on_change: &mut impl FnMut(&UpdateStatus),
+    ) -> Result<UpdateStatus, UpdateError> {
+        let feed = self.fetch_feed(&config.feed_url).await?;
+        let Some(release) = available_release(&feed, env!("CARGO_PKG_VERSION"), &platform())?
+        else {
+            let status = self.update(|status| {
+                *status = UpdateStatus::new(UpdateState::UpToDate);
+                status.checked_at = Some(Utc::now());
+            });
+            on_change(&status);
+            return Ok(status);
+        };
+
+        if previous.state == UpdateState::Ready
+            && previous.release.as_ref() == Some(&release)
+            && previous.staged_path.as_deref().is_some_and(Path::exists)
+        {
+            let status = self.update(|status| {
+                status.state = UpdateState::Ready;
+                status.checked_at = Some(Utc::now());
+            });
+            on_change(&status);
+            return Ok(status);
+        }
+
+        on_change(&self.update(|status| {
+            *status = UpdateStatus::new(UpdateState::Downloading);
+            status.total = release.installer.size;
+            status.release = Some(release.clone());
+        }));
+        let path = downloader
+            .fetch(
+                cache_root,
+                UPDATES_CATEGORY,
+                &release.file_name(),
+                &release.installer.url,
+                Some(&release.installer.sha256),
+                |progress| {
+                    on_change(&self.update(|status| {
+                        status.downloaded = progress.downloaded;
+                        status.total = progress.total.or(status.total);
+                    }));
+                },
+            )
+            .await?;
+        if !verify_installer(&config.public_key, &release.installer, &path).await? {
+            let _ = tokio::fs::remove_file(&path).await;
+            return Err(UpdateError::InvalidSignature);
+        }
+
+        log::info!(target: "updater", "update {} is ready to install", release.version);
+        let status = self.update(|status| {
+            status.state = UpdateState::Ready;
+            status.staged_path = Some(path);
+            status.checked_at = Some(Utc::now());
+        });
+        on_change(&status);
+        Ok(status)
+    }
+
+    /// The staged installer, verified again so a file swapped after the download is never
+    /// launched.
+    pub async fn verified_installer(&self) -> Result<PathBuf, UpdateError> {
+        let config = self.config.as_ref().ok_or(UpdateError::Disabled)?;
+        let status = self.status();
+        let (Some(release), Some(path)) = (status.release, status.staged_path) else {
+            return Err(UpdateError::NotReady);
+        };
+        if status.state != UpdateState::Ready || !path.exists() {
+            return Err(UpdateError::NotReady);
+        }
+        if !verify_installer(&config.public_key, &release.installer, &path).await? {
+            return Err(UpdateError::InvalidSignature);
+        }
+        Ok(path)
+    }
+
+    async fn fetch_feed(&self, url: &str) -> Result<ReleaseFeed, UpdateError> {
+        let response = self
+            .client
+            .current()
+            .get(url)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|error| UpdateError::Feed(error.to_string()))?;
+        response
+            .json()
+            .await
+            .map_err(|error| UpdateError::Feed(error.to_string()))
+    }
+
+    fn update(&self, change: impl FnOnce(&mut UpdateStatus)) -> UpdateStatus {
+        let mut status = self.status.lock().expect("update status poisoned");
+        change(&mut status);
+        status.clone()
+    }
+}
+
+/// The updater configuration for a feed URL and hex public key; `None` unless both are set,
+/// the feed is `https://` and the key is a valid Ed25519 point.
+fn updater_config(feed_url: Option<&str>, public_key: Option<&str>) -> Option<UpdaterConfig> {
+    let feed_url = feed_url.map(str::trim).filter(|url| !url.is_empty())?;
+    let public_key = public_key.map(str::trim).filter(|key| !key.is_empty())?;
+    if !feed_url.starts_with("https://") {
+        log::warn!(target: "updater", "the release feed must be https://; updates are disabled");
+        return None;
+    }
+    let Some(public_key) =
+        decode_hex::<32>(public_key).filter(|key| VerifyingKey::from_bytes(key).is_ok())
+    else {
+        log::warn!(target: "updater", "the update key is not an Ed25519 key; updates are disabled");
+        return None;
+    };
+    Some(UpdaterConfig {
+        feed_url: feed_url.to_string(),
+        public_key,
+    })
+}
+
+/// Key of the running platform in the release feed, e.g. `windows-x86_64`.
+pub fn platform() -> String {
+    format!("{}-{}", std::env::consts::OS, std::env::consts::ARCH)
+}
+
+/// The release of `feed` for `platform` when it is newer than `current_version`.
+pub fn available_release(
+    feed: &ReleaseFeed,
+    current_version: &str,
+    platform: &str,
+) -> Result<Option<AvailableRelease>, UpdateError> {
+    let newer = is_newer(&feed.version, current_version)
+        .ok_or_else(|| UpdateError::Feed(format!("'{}' is not a release version", feed.version)))?;
+    if !newer {
+        return Ok(None);
+    }
+    let Some(installer) = feed.platforms.get(platform) else {
+        log::info!(target: "updater", "release {} has no installer for {platform}", feed.version);
+        return Ok(None);
+    };
+    let installer = PlatformRelease {
+        url: installer.url.trim().to_string(),
+        sha256: installer.sha256.trim().to_ascii_lowercase(),
+        signature: installer.signature.trim().to_ascii_lowercase(),
+        size: installer.size,
+    };
+    if !installer.url.starts_with("https://") {
+        return Err(UpdateError::Feed(
+            "the installer URL must be https://".into(),
+        ));
+    }
+    if decode_hex::<32>(&installer.sha256).is_none()
+        || decode_hex::<64>(&installer.signature).is_none()
+    {
+        return Err(UpdateError::Feed(
+            "the installer needs a hex sha256 digest and signature".into(),
+        ));
+    }
+    Ok(Some(AvailableRelease {
+        version: feed.version.trim().to_string(),
+        notes: feed.notes.clone(),
+        pub_date: feed.pub_date.clone(),
+        installer,
+    }))
+}
+
+/// Whether `candidate` is a later version than `current`, or `None` when either is not a
+/// `major.minor.patch[-pre]` version. Pre-releases sort before their release and among each
+/// other by their text.
+pub fn is_newer(candidate: &str, current: &str) -> Option<bool> {
+    let parse = |version: &str| {
+        let version = version.trim().trim_start_matches('v');
+        let (core, pre) = match version.split_once('-') {
+            Some((core, pre)) => (core, Some(pre.to_string())),
+            None => (version, None),
+        };
+        let numbers: Vec<u64> = core
+            .split('.')
+            .map(str::parse)
+            .collect::<Result<_, _>>()
+            .ok()?;
+        let [major, minor, patch] = numbers[..] else {
+            return None;
+        };
+        // A release (no pre-release part) sorts after its pre-releases.
+        Some(((major, minor, patch), pre.is_none(), pre))
+    };
+    Some(parse(candidate)? > parse(current)?)
+}
+
+/// Checks the installer at `path` against the release's digest and signature.
+async fn verify_installer(
+    public_key: &[u8; 32],
+    installer: &PlatformRelease,
+    path: &Path,
+) -> Result<bool, UpdateError> {
+    if sha256_file(path).await? != installer.sha256 {
+        return Ok(false);
+    }
+    let (Some(digest), Some(signature)) = (
+        decode_hex::<32>(&installer.sha256),
+        decode_hex::<64>(&installer.signature),
+    ) else {
+        return Ok(false);
+    };
+    Ok(verify_signature(public_key, &digest, &signature))
+}
+
+/// Whether `signature` is a valid Ed25519 signature of `message` by `public_key`.
+fn verify_signature(public_key: &[u8; 32], message: &[u8], signature: &[u8; 64]) -> bool {
+    VerifyingKey::from_bytes(public_key)
+        .and_then(|key| key.verify_strict(message, &Signature::from_bytes(signature)))
+        .is_ok()
+}
+
+/// Starts the installer at `path` detached from the app, which is expected to exit next.
+pub fn launch_installer(path: &Path) -> Result<(), UpdateError> {
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    let mut command = if cfg!(target_os = "windows") && extension == "msi" {
+        let mut command = Command::new("msiexec");
+        command.arg("/i").arg(path);
+        command
+    } else if cfg!(target_os = "macos") {
+        let mut command = Command::new("open");
+        command.arg(path);
+        command
+    } else if cfg!(target_os = "linux") && extension != "appimage" {
+        let mut command = Command::new("xdg-open");
+        command.arg(path);
+        command
+    } else {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755))?;
+        }
+        Command::new(path)
+    };
+    command.spawn()?;
+    Ok(())
+}
+
+fn decode_hex<const N: usize>(text: &str) -> Option<[u8; N]> {
+    let text = text.trim();
+    if text.len() != N * 2 || !text.is_ascii() {
+        return None;
+    }
+    let mut bytes = [0; N];
+    for (byte, pair) in bytes.iter_mut().zip(text.as_bytes().chunks(2)) {
+        *byte = u8::from_str_radix(std::str::from_utf8(pair).ok()?, 16).ok()?;
+    }
+    Some(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn picks_newer_releases_for_this_platform_only() {
+        assert_eq!(is_newer("2.4.0", "2.3.9"), Some(true));
+        assert_eq!(is_newer("v2.10.0", "2.9.0"), Some(true));
+        assert_eq!(is_newer("2.4.0", "2.4.0"), Some(false));
+        assert_eq!(is_newer("2.4.0-beta.1", "2.4.0"), Some(false));
+        assert_eq!(is_newer("2.4.0", "2.4.0-beta.1"), Some(true));
+        assert_eq!(is_newer("2.4", "2.3.0"), None);
+
+        let feed: ReleaseFeed = serde_json::from_str(&format!(
+            r#"{{"version": "9.0.0", "notes": "Faster imports", "platforms": {{
+                "linux-x86_64": {{"url": "https://example.com/dl/weg_9.0.0.AppImage?x=1",
+                    "sha256": "{}", "signature": "{}", "size": 1024}}}}}}"#,
+            "AB".repeat(32),
+            "cd".repeat(64)
+        ))
+        .unwrap();
+        let release = available_release(&feed, "2.4.0", "linux-x86_64")
+            .unwrap()
+            .unwrap();
+        assert_eq!(release.installer.sha256, "ab".repeat(32));
+        assert_eq!(release.file_name(), "9.0.0-weg_9.0.0.AppImage");
+        assert_eq!(release.notes.as_deref(), Some("Faster imports"));
+        assert!(
+            available_release(&feed, "2.4.0", "windows-x86_64")
+                .unwrap()
+                .is_none()
+        );
+        assert!(
+            available_release(&feed, "9.0.0", "linux-x86_64")
+                .unwrap()
+                .is_none()
+        );
+
+        let mut unsigned = feed.clone();
+        unsigned
+            .platforms
+            .get_mut("linux-x86_64")
+            .unwrap()
+            .signature
+            .clear();
+        assert!(available_release(&unsigned, "2.4.0", "linux-x86_64").is_err());
+
+        let mut plain_http = feed.clone();
+        let installer = plain_http.platforms.get_mut("linux-x86_64").unwrap();
+        installer.url = installer.url.replacen("https://", "http://", 1);
+        assert!(available_release(&plain_http, "2.4.0", "linux-x86_64").is_err());
+    }
+
+    fn hex<const N: usize>(text: &str) -> [u8; N] {
+        std::array::from_fn(|i| u8::from_str_radix(&text[i * 2..i * 2 + 2], 16).unwrap())
+    }
+
+    #[test]
+    fn verifies_the_rfc_8032_test_vectors() {
+        let key_1 = hex("d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a");
+        let signature_1 = hex(
+            "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e065224901555fb8821590a33bac\
+             c61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b",
+        );
+        assert!(verify_signature(&key_1, b"", &signature_1));
+        assert!(!verify_signature(&key_1, b"x", &signature_1));
+
+        let key_2 = hex("3d4017c3e843895a92b70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4660c");
+        let signature_2 = hex(
+            "92a009a9f0d4cab8720e820b5f642540a2b27b5416503f8fb3762223ebdb69da085ac1e43e15996e\
+             458f3613d0f11d8c387b2eaeb4302aeeb00d291612bb0c00",
+        );
+        assert!(verify_signature(&key_2, &[0x72], &signature_2));
+        assert!(!verify_signature(&key_1, &[0x72], &signature_2));
+
+        let mut tampered = signature_2;
+        tampered[40] ^= 1;
+        assert!(!verify_signature(&key_2, &[0x72], &tampered));
+        // S must be below the group order.
+        let mut unreduced = signature_2;
+        unreduced[63] |= 0xf0;
+        assert!(!verify_signature(&key_2, &[0x72], &unreduced));
+    }
+
+    #[test]
+    fn only_https_feeds_with_a_valid_key_enable_updates() {
+        let key = "3d4017c3e843895a92b70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4660c";
+        let feed = "https://updates.example.com/feed.json";
+        assert!(updater_config(Some(feed), Some(key)).is_some());
+        assert!(updater_config(Some("http://updates.example.com/feed.json"), Some(key)).is_none());
+        assert!(updater_config(Some(feed), Some("not a key")).is_none());
+        assert!(updater_config(None, Some(key)).is_none());
+        assert!(updater_config(Some(feed), None).is_none());
+    }
+}
@@ -0,0 +1,122 @@
+//! Golden-file regression corpus for the XLIFF → JLIFF converter.
+//!
+//! Every directory under `tests/fixtures/converter/` holds an `input.xlf` and the committed
+//! `expected.jliff.json` / `expected.tags.json` it must convert to. After an intended output
+//! change, rerun with `UPDATE_GOLDEN=1` to rewrite the expected files and review the diff
+//! before committing.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+use tempfile::tempdir;
+
+use weg_translator_lib::{ConversionOptions, convert_xliff};
+
+const CORPUS_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/converter");
+
+#[test]
+fn converter_outputs_match_golden_files() {
+    let update = std::env::var_os("UPDATE_GOLDEN").is_some();
+    let mut cases: Vec<PathBuf> = fs::read_dir(CORPUS_DIR)
+        .expect("expected golden corpus directory")
+        .map(|entry| entry.expect("expected readable corpus entry").path())
+        .filter(|path| path.join("input.xlf").is_file())
+        .collect();
+    cases.sort();
+    assert!(!cases.is_empty(), "golden corpus is empty");
+
+    let mut failures = Vec::new();
+    for case in &cases {
+        let name = case.file_name().unwrap().to_string_lossy().into_owned();
+        let (jliff, tags) = convert_case(case);
+        for (file, actual) in [("expected.jliff.json", jliff), ("expected.tags.json", tags)] {
+            let path = case.join(file);
+            if update {
+                let pretty = serde_json::to_string_pretty(&actual).unwrap();
+                fs::write(&path, format!("{pretty}\n")).expect("expected golden file write");
+                continue;
+            }
+            let expected = read_json(&path);
+            let mut differences = Vec::new();
+            collect_differences("", &expected, &actual, &mut differences);
+            if !differences.is_empty() {
+                failures.push(format!("{name}/{file}:\n  {}", differences.join("\n  ")));
+            }
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "converter output drifted from the golden corpus (rerun with UPDATE_GOLDEN=1 if the \
+         change is intended):\n\n{}",
+        failures.join("\n\n")
+    );
+}
+
+fn convert_case(case: &Path) -> (Value, Value) {
+    let out_dir = tempdir().expect("expected temp dir");
+    let mut opts = ConversionOptions::new(
+        case.join("input.xlf"),
+        out_dir.path().to_path_buf(),
+        "Golden Corpus".to_string(),
+        "golden".to_string(),
+        "golden@example.com".to_string(),
+    );
+    opts.file_prefix = Some("output".to_string());
+
+    let artifacts = convert_xliff(&opts)
+        .unwrap_or_else(|error| panic!("failed to convert {}: {error:#}", case.display()));
+    assert_eq!(
+        artifacts.len(),
+        1,
+        "{} produced several artifacts",
+        case.display()
+    );
+    (
+        read_json(&artifacts[0].jliff_path),
+        read_json(&artifacts[0].tag_map_path),
+    )
+}
+
+fn read_json(path: &Path) -> Value {
+    let raw = fs::read_to_string(path)
+        .unwrap_or_else(|error| panic!("failed to read {}: {error}", path.display()));
+    serde_json::from_str(&raw)
+        .unwrap_or_else(|error| panic!("{} is not valid JSON: {error}", path.display()))
+}
+
+/// Lists every JSON pointer where `actual` differs from `expected`.
+fn collect_differences(pointer: &str, expected: &Value, actual: &Value, out: &mut Vec<String>) {
+    match (expected, actual) {
+        (Value::Object(expected), Value::Object(actual)) => {
+            let mut keys: Vec<&String> = expected.keys().chain(actual.keys()).collect();
+            keys.sort();
+            keys.dedup();
+            for key in keys {
+                let child = format!("{pointer}/{key}");
+                match (expected.get(key), actual.get(key)) {
+                    (Some(expected), Some(actual)) => {
+                        collect_differences(&child, expected, actual, out)
+                    }
+                    (Some(expected), None) => {
+                        out.push(format!("{child}: missing, expected {expected}"))
+                    }
+                    (None, Some(actual)) => out.push(format!("{child}: unexpected {actual}")),
+                    (None, None) => {}
+                }
+            }
+        }
+        (Value::Array(expected_items), Value::Array(actual_items))
+            if expected_items.len() == actual_items.len() =>
+        {
+            for (index, (expected, actual)) in expected_items.iter().zip(actual_items).enumerate() {
+                collect_differences(&format!("{pointer}/{index}"), expected, actual, out);
+            }
+        }
+        _ if expected != actual => {
+            out.push(format!("{pointer}: expected {expected}, got {actual}"));
+        }
+        _ => {}
+    }
+}
@@ -1,11 +1,20 @@
+use sqlx::SqlitePool;
 use sqlx::sqlite::SqlitePoolOptions;
 use tempfile::tempdir;
 use uuid::Uuid;
 
 use weg_translator_lib::{
-    DatabasePerformanceConfig, DbError, DbManager, FileLanguagePairInput, NewClientArgs,
-    NewFileInfoArgs, NewProjectArgs, NewProjectFileArgs, NewUserArgs, PermissionOverrideInput,
-    ProjectLanguagePairInput, ProjectSubjectInput, UpdateProjectArgs, initialise_schema,
+    ArtifactKind, ArtifactStatus, DatabasePerformanceConfig, DbError, DbManager,
+    ExchangeRateRecord, FieldCipher, FileLanguagePairInput, NewAlignmentArgs, NewAlignmentPairArgs,
+    NewArtifactArgs, NewAutomationScriptArgs, NewClientArgs, NewConversionOutputNameArgs,
+    NewDeliveryPackageArgs, NewDeliveryReceiptArgs, NewDeliveryTargetArgs, NewExchangeRateArgs,
+    NewFileInfoArgs, NewNoteArgs, NewOfflineOperationArgs, NewOutboundRequestArgs, NewProjectArgs,
+    NewProjectFileArgs, NewProtectedTermArgs, NewSegmentReferenceArgs,
+    NewSegmentationExceptionArgs, NewTermCandidateArgs, NewTermbaseEntryArgs,
+    NewTranslationMemoryEntryArgs, NewUserArgs, PermissionOverrideInput, PostalAddress,
+    ProjectLanguagePairInput, ProjectSubjectInput, ProjectTmAssignmentArgs,
+    TermCandidateDecisionArgs, TermCandidateRecord, UpdateAlignmentPairArgs, UpdateNoteArgs,
+    UpdateProjectArgs, UpdateTranslationMemoryEntryArgs, VatValidation, initialise_schema,
 };
 
 async fn memory_manager() -> DbManager {
@@ -44,6 +53,9 @@ fn sample_client_args(client_uuid: Uuid) -> NewClientArgs {
         address: None,
         vat_number: None,
         note: Some("Enterprise account".into()),
+        currency: None,
+        postal_address: PostalAddress::default(),
+        vat_validation: VatValidation::default(),
     }
 }
 
@@ -56,6 +68,7 @@ fn sample_project_args(project_uuid: Uuid, user_uuid: Uuid, client_uuid: Uuid) -
         client_uuid: Some(client_uuid),
         r#type: "translation".into(),
         notes: Some("Priority launch".into()),
+        due_date: None,
         subjects: vec![ProjectSubjectInput {
             subject: "marketing".into(),
         }],
@@ -162,6 +175,7 @@ async fn updating_language_pairs_requires_non_empty_list() {
             client_uuid: None,
             r#type: "translation".into(),
             notes: None,
+            due_date: None,
             subjects: vec![],
             language_pairs: vec![ProjectLanguagePairInput {
                 source_lang: "en-US".into(),
@@ -180,6 +194,8 @@ async fn updating_language_pairs_requires_non_empty_list() {
             client_uuid: None,
             r#type: None,
             notes: None,
+            due_date: None,
+            no_external_mt: None,
             subjects: None,
             language_pairs: Some(vec![]),
         })
@@ -196,6 +212,86 @@ async fn updating_language_pairs_requires_non_empty_list() {
     }
 }
 
+#[tokio::test]
+async fn swapping_a_language_pair_reverses_its_file_pairs() {
+    let manager = memory_manager().await;
+
+    let user_uuid = Uuid::new_v4();
+    manager
+        .create_user_profile(sample_user_args(user_uuid))
+        .await
+        .expect("user creation should succeed");
+    let client_uuid = Uuid::new_v4();
+    manager
+        .create_client_record(sample_client_args(client_uuid))
+        .await
+        .expect("client creation should succeed");
+
+    let project_uuid = Uuid::new_v4();
+    manager
+        .create_project_bundle(sample_project_args(project_uuid, user_uuid, client_uuid))
+        .await
+        .expect("project creation should succeed");
+    let file_uuid = Uuid::new_v4();
+    manager
+        .attach_project_file(
+            NewFileInfoArgs {
+                file_uuid,
+                ext: "docx".into(),
+                r#type: "processable".into(),
+                size_bytes: None,
+                segment_count: None,
+                token_count: None,
+                notes: None,
+            },
+            NewProjectFileArgs {
+                project_uuid,
+                file_uuid,
+                filename: "brochure.docx".into(),
+                stored_at: "Processable/brochure.docx".into(),
+                r#type: "processable".into(),
+                language_pairs: vec![FileLanguagePairInput {
+                    source_lang: "en-US".into(),
+                    target_lang: "it-IT".into(),
+                }],
+            },
+        )
+        .await
+        .expect("file attachment should succeed");
+
+    let bundle = manager
+        .swap_project_language_pair(project_uuid, "en-us", "it-it")
+        .await
+        .expect("swap should succeed")
+        .expect("bundle should exist");
+    let pairs: Vec<(&str, &str)> = bundle
+        .language_pairs
+        .iter()
+        .map(|pair| (pair.source_lang.as_str(), pair.target_lang.as_str()))
+        .collect();
+    assert_eq!(pairs, vec![("it-IT", "en-US")]);
+    let file_pair = &bundle.files[0].language_pairs[0];
+    assert_eq!(
+        (
+            file_pair.source_lang.as_str(),
+            file_pair.target_lang.as_str()
+        ),
+        ("it-IT", "en-US")
+    );
+
+    let missing = manager
+        .swap_project_language_pair(project_uuid, "en-US", "it-IT")
+        .await;
+    assert!(matches!(missing, Err(DbError::ConstraintViolation(_))));
+    assert!(
+        manager
+            .swap_project_language_pair(Uuid::new_v4(), "en-US", "it-IT")
+            .await
+            .expect("swap of an unknown project should not fail")
+            .is_none()
+    );
+}
+
 #[tokio::test]
 async fn clients_persist_across_manager_reopen() {
     let temp_dir = tempdir().expect("temporary directory should be created");
@@ -231,3 +327,1766 @@ async fn clients_persist_across_manager_reopen() {
         .close()
         .expect("temporary directory should be removed after manager drop");
 }
+
+#[tokio::test]
+async fn protected_terms_are_replaced_and_listed_longest_first() {
+    let manager = memory_manager().await;
+
+    let user_uuid = Uuid::new_v4();
+    manager
+        .create_user_profile(sample_user_args(user_uuid))
+        .await
+        .expect("user creation should succeed");
+    let client_uuid = Uuid::new_v4();
+    manager
+        .create_client_record(sample_client_args(client_uuid))
+        .await
+        .expect("client creation should succeed");
+    let project_uuid = Uuid::new_v4();
+    manager
+        .create_project_bundle(sample_project_args(project_uuid, user_uuid, client_uuid))
+        .await
+        .expect("project creation should succeed");
+
+    let term = |value: &str, case_sensitive: bool| NewProtectedTermArgs {
+        term: value.into(),
+        case_sensitive,
+        note: None,
+    };
+
+    manager
+        .replace_protected_terms(project_uuid, &[term("Acme", true)])
+        .await
+        .expect("initial terms should persist");
+    manager
+        .replace_protected_terms(
+            project_uuid,
+            &[term("Acme", true), term("Acme Cloud", false)],
+        )
+        .await
+        .expect("replacement should persist");
+
+    let terms = manager
+        .list_protected_terms(project_uuid)
+        .await
+        .expect("terms should list");
+    let names: Vec<&str> = terms.iter().map(|record| record.term.as_str()).collect();
+    assert_eq!(names, vec!["Acme Cloud", "Acme"]);
+    assert!(!terms[0].case_sensitive);
+}
+
+#[tokio::test]
+async fn project_tm_assignments_are_replaced_and_listed_by_priority() {
+    let manager = memory_manager().await;
+
+    let user_uuid = Uuid::new_v4();
+    manager
+        .create_user_profile(sample_user_args(user_uuid))
+        .await
+        .expect("user creation should succeed");
+    let client_uuid = Uuid::new_v4();
+    manager
+        .create_client_record(sample_client_args(client_uuid))
+        .await
+        .expect("client creation should succeed");
+    let project_uuid = Uuid::new_v4();
+    manager
+        .create_project_bundle(sample_project_args(project_uuid, user_uuid, client_uuid))
+        .await
+        .expect("project creation should succeed");
+
+    let assignment = |tm_origin: &str, priority: i64, penalty: i64| ProjectTmAssignmentArgs {
+        tm_origin: tm_origin.into(),
+        priority,
+        penalty,
+    };
+    let records = manager
+        .replace_project_tm_assignments(
+            project_uuid,
+            &[assignment("alignment", 2, 5), assignment("editor", 1, 0)],
+        )
+        .await
+        .expect("assignments should persist");
+    let origins: Vec<&str> = records
+        .iter()
+        .map(|record| record.tm_origin.as_str())
+        .collect();
+    assert_eq!(origins, vec!["editor", "alignment"]);
+    assert_eq!(records[1].penalty, 5);
+
+    manager
+        .replace_project_tm_assignments(project_uuid, &[])
+        .await
+        .expect("clearing assignments should succeed");
+    let records = manager
+        .list_project_tm_assignments(project_uuid)
+        .await
+        .expect("assignments should list");
+    assert!(records.is_empty());
+}
+
+#[tokio::test]
+async fn term_candidates_keep_decisions_across_extractions() {
+    let manager = memory_manager().await;
+
+    let user_uuid = Uuid::new_v4();
+    manager
+        .create_user_profile(sample_user_args(user_uuid))
+        .await
+        .expect("user creation should succeed");
+    let client_uuid = Uuid::new_v4();
+    manager
+        .create_client_record(sample_client_args(client_uuid))
+        .await
+        .expect("client creation should succeed");
+    let project_uuid = Uuid::new_v4();
+    manager
+        .create_project_bundle(sample_project_args(project_uuid, user_uuid, client_uuid))
+        .await
+        .expect("project creation should succeed");
+
+    let candidate = |term: &str, target: Option<&str>, frequency: i64| NewTermCandidateArgs {
+        term: term.into(),
+        term_key: term.to_lowercase(),
+        target_term: target.map(str::to_string),
+        frequency,
+        score: frequency as f64,
+    };
+    manager
+        .replace_pending_term_candidates(
+            project_uuid,
+            "en-US",
+            "de-DE",
+            &[
+                candidate("print dialog", Some("Druckdialog"), 4),
+                candidate("toolbar", None, 2),
+            ],
+        )
+        .await
+        .expect("candidates should be stored");
+    let pending = manager
+        .list_term_candidates(project_uuid, Some("pending"))
+        .await
+        .expect("candidates should list");
+    assert_eq!(pending.len(), 2);
+    assert_eq!(pending[0].term, "print dialog");
+
+    let decision = |record: &TermCandidateRecord, status: &str| TermCandidateDecisionArgs {
+        candidate_uuid: record.candidate_uuid,
+        status: status.into(),
+        target_term: None,
+    };
+    let added = manager
+        .decide_term_candidates(
+            project_uuid,
+            "project",
+            &[
+                decision(&pending[0], "accepted"),
+                decision(&pending[1], "rejected"),
+            ],
+        )
+        .await
+        .expect("decisions should be recorded");
+    assert_eq!(added.len(), 1);
+    assert_eq!(added[0].target_term, "Druckdialog");
+    assert_eq!(added[0].source_lang, "en-us");
+
+    manager
+        .replace_pending_term_candidates(
+            project_uuid,
+            "en-us",
+            "de-de",
+            &[
+                candidate("toolbar", None, 3),
+                candidate("status bar", None, 2),
+            ],
+        )
+        .await
+        .expect("a new extraction should replace pending candidates");
+    let candidates = manager
+        .list_term_candidates(project_uuid, None)
+        .await
+        .expect("candidates should list");
+    let statuses: Vec<(&str, &str)> = candidates
+        .iter()
+        .map(|record| (record.term.as_str(), record.status.as_str()))
+        .collect();
+    assert_eq!(
+        statuses,
+        vec![
+            ("print dialog", "accepted"),
+            ("status bar", "pending"),
+            ("toolbar", "rejected"),
+        ]
+    );
+
+    let entries = manager
+        .list_termbase_entries("EN-US", "DE-DE")
+        .await
+        .expect("termbase entries should list");
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].project_uuid, Some(project_uuid));
+}
+
+#[tokio::test]
+async fn alignment_pairs_are_stored_in_order_and_reviewed() {
+    let manager = memory_manager().await;
+    let alignment_uuid = Uuid::new_v4();
+    let pair = |source: &str, target: &str, score: f64| NewAlignmentPairArgs {
+        source_text: source.into(),
+        target_text: target.into(),
+        score,
+    };
+    let record = manager
+        .create_alignment(&NewAlignmentArgs {
+            alignment_uuid,
+            source_lang: "en-US".into(),
+            target_lang: "de-DE".into(),
+            source_name: "manual.txt".into(),
+            target_name: "handbuch.txt".into(),
+            pairs: vec![
+                pair("Open the file.", "Öffnen Sie die Datei.", 0.9),
+                pair("See also page 4.", "", 0.0),
+            ],
+        })
+        .await
+        .expect("alignment should be stored");
+    assert_eq!(record.source_lang, "en-us");
+    assert_eq!(record.committed_at, None);
+
+    let updated = manager
+        .update_alignment_pairs(
+            alignment_uuid,
+            &[
+                UpdateAlignmentPairArgs {
+                    position: 0,
+                    status: Some("accepted".into()),
+                    source_text: None,
+                    target_text: None,
+                },
+                UpdateAlignmentPairArgs {
+                    position: 1,
+                    status: None,
+                    source_text: None,
+                    target_text: Some("Siehe auch Seite 4.".into()),
+                },
+            ],
+        )
+        .await
+        .expect("pairs should be updated");
+    assert_eq!(updated, 2);
+
+    let pairs = manager
+        .list_alignment_pairs(alignment_uuid)
+        .await
+        .expect("pairs should list");
+    assert_eq!(pairs[0].status, "accepted");
+    assert_eq!(pairs[1].status, "pending");
+    assert_eq!(pairs[1].target_text, "Siehe auch Seite 4.");
+
+    let committed = manager
+        .mark_alignment_committed(alignment_uuid)
+        .await
+        .expect("alignment should be stamped")
+        .expect("alignment should exist");
+    assert!(committed.committed_at.is_some());
+
+    assert!(
+        manager
+            .delete_alignment(alignment_uuid)
+            .await
+            .expect("alignment should be deleted")
+    );
+    assert!(
+        manager
+            .list_alignment_pairs(alignment_uuid)
+            .await
+            .expect("pairs should list")
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn exchange_rates_in_effect_are_the_latest_on_or_before_the_day() {
+    let manager = memory_manager().await;
+    let rate = |quote: &str, date: &str, rate: f64| NewExchangeRateArgs {
+        base_currency: "EUR".into(),
+        quote_currency: quote.into(),
+        rate_date: date.into(),
+        rate,
+        source: "manual".into(),
+    };
+    manager
+        .record_exchange_rates(&[
+            rate("USD", "2026-03-01", 1.08),
+            rate("USD", "2026-03-05", 1.10),
+            rate("CHF", "2026-02-20", 0.94),
+        ])
+        .await
+        .expect("rates should persist");
+    manager
+        .record_exchange_rates(&[rate("USD", "2026-03-05", 1.12)])
+        .await
+        .expect("same-day rate should replace the earlier one");
+
+    let on = |records: Vec<ExchangeRateRecord>| {
+        records
+            .into_iter()
+            .map(|record| (record.quote_currency, record.rate))
+            .collect::<Vec<_>>()
+    };
+    let march_3 = manager.exchange_rates_on("2026-03-03").await.unwrap();
+    assert_eq!(
+        on(march_3),
+        [("CHF".to_string(), 0.94), ("USD".to_string(), 1.08)]
+    );
+    let march_9 = manager.exchange_rates_on("2026-03-09").await.unwrap();
+    assert_eq!(
+        on(march_9),
+        [("CHF".to_string(), 0.94), ("USD".to_string(), 1.12)]
+    );
+    assert!(
+        manager
+            .exchange_rates_on("2026-01-01")
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn projects_are_numbered_per_client_and_found_by_number() {
+    let manager = memory_manager().await;
+    let user_uuid = Uuid::new_v4();
+    manager
+        .create_user_profile(sample_user_args(user_uuid))
+        .await
+        .expect("user creation should succeed");
+    let client_uuid = Uuid::new_v4();
+    manager
+        .create_client_record(sample_client_args(client_uuid))
+        .await
+        .expect("client creation should succeed");
+
+    let mut numbers = Vec::new();
+    for client in [Some(client_uuid), Some(client_uuid), None] {
+        let mut args = sample_project_args(Uuid::new_v4(), user_uuid, client_uuid);
+        args.client_uuid = client;
+        let bundle = manager
+            .create_project_bundle(args)
+            .await
+            .expect("project creation should succeed");
+        numbers.push(
+            bundle
+                .project
+                .project_number
+                .expect("projects get a number"),
+        );
+    }
+    let year = time::OffsetDateTime::now_utc().year();
+    assert_eq!(
+        numbers,
+        vec![
+            format!("ACME-{year}-0001"),
+            format!("ACME-{year}-0002"),
+            format!("INT-{year}-0001"),
+        ]
+    );
+
+    let found = manager
+        .list_project_records(Some("acme-"))
+        .await
+        .expect("projects should list");
+    let mut found: Vec<String> = found
+        .into_iter()
+        .filter_map(|record| record.project_number)
+        .collect();
+    found.sort();
+    assert_eq!(found, numbers[..2]);
+}
+
+#[tokio::test]
+async fn client_search_folds_accents_and_matches_wildcards_literally() {
+    let manager = memory_manager().await;
+    for name in [
+        "Müller & Söhne",
+        "100% Pure",
+        "Under_score Ltd",
+        "Underscore Ltd",
+    ] {
+        let mut args = sample_client_args(Uuid::new_v4());
+        args.name = name.into();
+        manager
+            .create_client_record(args)
+            .await
+            .expect("client creation should succeed");
+    }
+
+    let search = |query: &'static str| {
+        let manager = &manager;
+        async move {
+            let mut names: Vec<String> = manager
+                .list_client_records(Some(query))
+                .await
+                .expect("clients should list")
+                .into_iter()
+                .map(|record| record.name)
+                .collect();
+            names.sort();
+            names
+        }
+    };
+    assert_eq!(search("muller").await, ["Müller & Söhne"]);
+    assert_eq!(search("MÜLLER").await, ["Müller & Söhne"]);
+    assert_eq!(search("sohne").await, ["Müller & Söhne"]);
+    assert_eq!(search("%").await, ["100% Pure"]);
+    assert_eq!(search("0% p").await, ["100% Pure"]);
+    assert_eq!(search("_").await, ["Under_score Ltd"]);
+    assert_eq!(search("under_s").await, ["Under_score Ltd"]);
+    assert_eq!(search("underscore").await, ["Underscore Ltd"]);
+    assert!(search("\\").await.is_empty());
+}
+
+#[tokio::test]
+async fn delivery_packages_are_listed_newest_first_and_marked_delivered_once() {
+    let manager = memory_manager().await;
+    let user_uuid = Uuid::new_v4();
+    manager
+        .create_user_profile(sample_user_args(user_uuid))
+        .await
+        .expect("user creation should succeed");
+    let client_uuid = Uuid::new_v4();
+    manager
+        .create_client_record(sample_client_args(client_uuid))
+        .await
+        .expect("client creation should succeed");
+    let project_uuid = Uuid::new_v4();
+    manager
+        .create_project_bundle(sample_project_args(project_uuid, user_uuid, client_uuid))
+        .await
+        .expect("project creation should succeed");
+
+    let mut packages = Vec::new();
+    for name in ["first.zip", "second.zip"] {
+        let file_uuid = Uuid::new_v4();
+        manager
+            .attach_project_file(
+                NewFileInfoArgs {
+                    file_uuid,
+                    ext: "zip".into(),
+                    r#type: "delivery".into(),
+                    size_bytes: Some(512),
+                    segment_count: None,
+                    token_count: None,
+                    notes: None,
+                },
+                NewProjectFileArgs {
+                    project_uuid,
+                    file_uuid,
+                    filename: name.into(),
+                    stored_at: format!("Deliveries/{name}"),
+                    r#type: "delivery".into(),
+                    language_pairs: Vec::new(),
+                },
+            )
+            .await
+            .expect("package file should attach");
+        let artifact_uuid = Uuid::new_v4();
+        manager
+            .upsert_artifact_record(NewArtifactArgs {
+                artifact_uuid,
+                project_uuid,
+                file_uuid,
+                artifact_type: ArtifactKind::DeliveryPackage.as_str().into(),
+                size_bytes: Some(512),
+                segment_count: None,
+                token_count: None,
+                status: ArtifactStatus::Generated.as_str().into(),
+            })
+            .await
+            .expect("package artifact should be stored");
+        manager
+            .record_delivery_package(NewDeliveryPackageArgs {
+                artifact_uuid,
+                project_uuid,
+                source_lang: "en-US".into(),
+                target_lang: "it-IT".into(),
+                rel_path: format!("Deliveries/{name}"),
+                document_count: 1,
+                bilingual_count: 1,
+                open_findings: 0,
+            })
+            .await
+            .expect("package should be recorded");
+        packages.push(artifact_uuid);
+    }
+
+    let listed = manager
+        .list_delivery_packages(project_uuid)
+        .await
+        .expect("packages should list");
+    let listed: Vec<Uuid> = listed.iter().map(|record| record.artifact_uuid).collect();
+    assert_eq!(listed, vec![packages[1], packages[0]]);
+
+    let delivered = manager
+        .mark_delivery_package_delivered(packages[1])
+        .await
+        .expect("package should be marked")
+        .expect("package exists");
+    let delivered_at = delivered.delivered_at.expect("delivery date is set");
+    let again = manager
+        .mark_delivery_package_delivered(packages[1])
+        .await
+        .expect("package should be marked")
+        .expect("package exists");
+    assert_eq!(again.delivered_at, Some(delivered_at));
+    assert!(
+        manager
+            .mark_delivery_package_delivered(Uuid::new_v4())
+            .await
+            .expect("marking an unknown package succeeds")
+            .is_none()
+    );
+}
+
+#[tokio::test]
+async fn delivery_receipts_outlive_their_target() {
+    let manager = memory_manager().await;
+    let user_uuid = Uuid::new_v4();
+    manager
+        .create_user_profile(sample_user_args(user_uuid))
+        .await
+        .expect("user creation should succeed");
+    let client_uuid = Uuid::new_v4();
+    manager
+        .create_client_record(sample_client_args(client_uuid))
+        .await
+        .expect("client creation should succeed");
+    let project_uuid = Uuid::new_v4();
+    manager
+        .create_project_bundle(sample_project_args(project_uuid, user_uuid, client_uuid))
+        .await
+        .expect("project creation should succeed");
+
+    let file_uuid = Uuid::new_v4();
+    manager
+        .attach_project_file(
+            NewFileInfoArgs {
+                file_uuid,
+                ext: "zip".into(),
+                r#type: "delivery".into(),
+                size_bytes: Some(512),
+                segment_count: None,
+                token_count: None,
+                notes: None,
+            },
+            NewProjectFileArgs {
+                project_uuid,
+                file_uuid,
+                filename: "package.zip".into(),
+                stored_at: "Deliveries/package.zip".into(),
+                r#type: "delivery".into(),
+                language_pairs: Vec::new(),
+            },
+        )
+        .await
+        .expect("package file should attach");
+    let artifact_uuid = Uuid::new_v4();
+    manager
+        .upsert_artifact_record(NewArtifactArgs {
+            artifact_uuid,
+            project_uuid,
+            file_uuid,
+            artifact_type: ArtifactKind::DeliveryPackage.as_str().into(),
+            size_bytes: Some(512),
+            segment_count: None,
+            token_count: None,
+            status: ArtifactStatus::Generated.as_str().into(),
+        })
+        .await
+        .expect("package artifact should be stored");
+    manager
+        .record_delivery_package(NewDeliveryPackageArgs {
+            artifact_uuid,
+            project_uuid,
+            source_lang: "en-US".into(),
+            target_lang: "it-IT".into(),
+            rel_path: "Deliveries/package.zip".into(),
+            document_count: 1,
+            bilingual_count: 0,
+            open_findings: 0,
+        })
+        .await
+        .expect("package should be recorded");
+
+    let target_uuid = Uuid::new_v4();
+    let target = NewDeliveryTargetArgs {
+        target_uuid,
+        client_uuid,
+        name: "Client SFTP".into(),
+        transport: "sftp".into(),
+        settings_json: r#"{"transport":"sftp","host":"files.example","username":"acme"}"#.into(),
+    };
+    manager
+        .upsert_delivery_target(target.clone())
+        .await
+        .expect("target should be stored");
+    let renamed = manager
+        .upsert_delivery_target(NewDeliveryTargetArgs {
+            name: "Client SFTP (incoming)".into(),
+            ..target
+        })
+        .await
+        .expect("target should be updated");
+    assert_eq!(renamed.name, "Client SFTP (incoming)");
+    assert_eq!(
+        manager
+            .list_delivery_targets(client_uuid)
+            .await
+            .expect("targets should list")
+            .len(),
+        1
+    );
+
+    let receipt = manager
+        .record_delivery_receipt(NewDeliveryReceiptArgs {
+            receipt_uuid: Uuid::new_v4(),
+            artifact_uuid,
+            project_uuid,
+            target_uuid: Some(target_uuid),
+            transport: "sftp".into(),
+            status: "uploaded".into(),
+            remote_location: Some("sftp://acme@files.example:22/package.zip".into()),
+            size_bytes: Some(512),
+            sha256: Some("ab".repeat(32)),
+            error: None,
+            started_at: "2026-03-09 10:00:00".into(),
+        })
+        .await
+        .expect("receipt should be recorded");
+
+    assert!(
+        manager
+            .delete_delivery_target(target_uuid)
+            .await
+            .expect("target should delete")
+    );
+    let receipts = manager
+        .list_delivery_receipts(project_uuid)
+        .await
+        .expect("receipts should list");
+    assert_eq!(receipts.len(), 1);
+    assert_eq!(receipts[0].receipt_uuid, receipt.receipt_uuid);
+    assert_eq!(receipts[0].target_uuid, None);
+}
+
+#[tokio::test]
+async fn segment_references_are_counted_and_removed_with_their_file() {
+    let manager = memory_manager().await;
+    let user_uuid = Uuid::new_v4();
+    manager
+        .create_user_profile(sample_user_args(user_uuid))
+        .await
+        .expect("user creation should succeed");
+    let client_uuid = Uuid::new_v4();
+    manager
+        .create_client_record(sample_client_args(client_uuid))
+        .await
+        .expect("client creation should succeed");
+    let project_uuid = Uuid::new_v4();
+    manager
+        .create_project_bundle(sample_project_args(project_uuid, user_uuid, client_uuid))
+        .await
+        .expect("project creation should succeed");
+
+    let file_uuid = Uuid::new_v4();
+    manager
+        .attach_project_file(
+            NewFileInfoArgs {
+                file_uuid,
+                ext: "png".into(),
+                r#type: "image".into(),
+                size_bytes: Some(2048),
+                segment_count: None,
+                token_count: None,
+                notes: None,
+            },
+            NewProjectFileArgs {
+                project_uuid,
+                file_uuid,
+                filename: "login-screen.png".into(),
+                stored_at: "Images/login-screen.png".into(),
+                r#type: "image".into(),
+                language_pairs: Vec::new(),
+            },
+        )
+        .await
+        .expect("screenshot should attach");
+
+    let link = |transunit_id: &str, anchor: Option<&str>| NewSegmentReferenceArgs {
+        reference_uuid: Uuid::new_v4(),
+        project_uuid,
+        jliff_rel_path: "Translations/en-US_it-IT/ui.jliff".into(),
+        transunit_id: transunit_id.into(),
+        file_uuid,
+        anchor: anchor.map(str::to_string),
+        note: None,
+    };
+    let first = manager
+        .create_segment_reference(link("u1", None))
+        .await
+        .expect("reference should be created");
+    manager
+        .create_segment_reference(link("u1", Some("top right")))
+        .await
+        .expect("second reference should be created");
+    manager
+        .create_segment_reference(link("u2", None))
+        .await
+        .expect("third reference should be created");
+
+    let counts = manager
+        .count_segment_references(project_uuid, "Translations/en-US_it-IT/ui.jliff")
+        .await
+        .expect("references should be counted");
+    assert_eq!(counts.get("u1"), Some(&2));
+    assert_eq!(counts.get("u2"), Some(&1));
+    assert_eq!(counts.get("u3"), None);
+
+    let updated = manager
+        .update_segment_reference(
+            first.reference_uuid,
+            Some("login button"),
+            Some("Keep short"),
+        )
+        .await
+        .expect("reference should update")
+        .expect("reference should exist");
+    assert_eq!(updated.anchor.as_deref(), Some("login button"));
+    let for_u1 = manager
+        .list_segment_references(
+            project_uuid,
+            "Translations/en-US_it-IT/ui.jliff",
+            Some("u1"),
+        )
+        .await
+        .expect("references should list");
+    assert_eq!(for_u1.len(), 2);
+    assert_eq!(for_u1[0].reference_uuid, first.reference_uuid);
+
+    manager
+        .detach_project_file(project_uuid, file_uuid)
+        .await
+        .expect("screenshot should detach");
+    assert!(
+        manager
+            .list_segment_references(project_uuid, "Translations/en-US_it-IT/ui.jliff", None)
+            .await
+            .expect("references should list")
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn segmentation_exceptions_combine_project_and_global_lists() {
+    let manager = memory_manager().await;
+    let user_uuid = Uuid::new_v4();
+    manager
+        .create_user_profile(sample_user_args(user_uuid))
+        .await
+        .expect("user creation should succeed");
+    let client_uuid = Uuid::new_v4();
+    manager
+        .create_client_record(sample_client_args(client_uuid))
+        .await
+        .expect("client creation should succeed");
+    let project_uuid = Uuid::new_v4();
+    manager
+        .create_project_bundle(sample_project_args(project_uuid, user_uuid, client_uuid))
+        .await
+        .expect("project creation should succeed");
+
+    let exception = |project_uuid: Option<Uuid>, language: &str, abbreviation: &str| {
+        NewSegmentationExceptionArgs {
+            exception_uuid: Uuid::new_v4(),
+            project_uuid,
+            language: language.into(),
+            kind: "abbreviation".into(),
+            before_text: abbreviation.into(),
+            after_text: String::new(),
+        }
+    };
+    let global = manager
+        .create_segmentation_exception(exception(None, "de", "z.B."))
+        .await
+        .expect("global exception should be created");
+    manager
+        .create_segmentation_exception(exception(Some(project_uuid), "de-AT", "bzw."))
+        .await
+        .expect("project exception should be created");
+    manager
+        .create_segmentation_exception(exception(Some(project_uuid), "fr", "cf."))
+        .await
+        .expect("project exception should be created");
+
+    let applying = manager
+        .segmentation_exceptions_for_language(Some(project_uuid), "de-AT")
+        .await
+        .expect("exceptions should list");
+    let texts: Vec<&str> = applying
+        .iter()
+        .map(|record| record.before_text.as_str())
+        .collect();
+    assert_eq!(texts, ["bzw.", "z.B."]);
+    assert!(
+        manager
+            .segmentation_exceptions_for_language(Some(project_uuid), "den")
+            .await
+            .expect("exceptions should list")
+            .is_empty()
+    );
+    assert_eq!(
+        manager
+            .list_segmentation_exceptions(None)
+            .await
+            .expect("global exceptions should list"),
+        [global.clone()]
+    );
+
+    assert!(
+        manager
+            .delete_segmentation_exception(global.exception_uuid)
+            .await
+            .expect("exception should delete")
+    );
+    assert_eq!(
+        manager
+            .segmentation_exceptions_for_language(None, "de")
+            .await
+            .expect("exceptions should list")
+            .len(),
+        0
+    );
+}
+
+#[tokio::test]
+async fn overdue_projects_turn_critical_and_report_the_degradation_once() {
+    let manager = memory_manager().await;
+    let user_uuid = Uuid::new_v4();
+    manager
+        .create_user_profile(sample_user_args(user_uuid))
+        .await
+        .expect("user creation should succeed");
+    let client_uuid = Uuid::new_v4();
+    manager
+        .create_client_record(sample_client_args(client_uuid))
+        .await
+        .expect("client creation should succeed");
+    let project_uuid = Uuid::new_v4();
+    manager
+        .create_project_bundle(sample_project_args(project_uuid, user_uuid, client_uuid))
+        .await
+        .expect("project creation should succeed");
+
+    let health = manager
+        .refresh_project_health()
+        .await
+        .expect("health should be evaluated");
+    assert_eq!(health.len(), 1);
+    assert_eq!(health[0].level, "healthy");
+    assert_eq!(health[0].score, 100);
+
+    manager
+        .update_project_bundle(UpdateProjectArgs {
+            project_uuid,
+            project_name: None,
+            project_status: None,
+            user_uuid: None,
+            client_uuid: None,
+            r#type: None,
+            notes: None,
+            due_date: Some(Some("2020-01-31".into())),
+            no_external_mt: None,
+            subjects: None,
+            language_pairs: None,
+        })
+        .await
+        .expect("due date should be set");
+    let health = manager
+        .refresh_project_health()
+        .await
+        .expect("health should be re-evaluated");
+    assert_eq!(health[0].level, "critical");
+    assert_eq!(health[0].flags.0, vec!["overdue".to_string()]);
+    manager
+        .refresh_project_health()
+        .await
+        .expect("unchanged health should be re-evaluated");
+
+    let degraded: Vec<_> = manager
+        .list_outbox_events_after(0, 100)
+        .await
+        .expect("events should be listed")
+        .into_iter()
+        .filter(|event| event.topic == "projects://health_degraded")
+        .collect();
+    assert_eq!(degraded.len(), 1);
+    let payload: serde_json::Value =
+        serde_json::from_str(&degraded[0].payload_json).expect("payload should be JSON");
+    assert_eq!(payload["projectUuid"], project_uuid.to_string());
+    assert_eq!(payload["previousLevel"], "healthy");
+    assert_eq!(payload["level"], "critical");
+}
+
+#[tokio::test]
+async fn language_pair_activity_merges_projects_and_translation_memory() {
+    let manager = memory_manager().await;
+    let user_uuid = Uuid::new_v4();
+    manager
+        .create_user_profile(sample_user_args(user_uuid))
+        .await
+        .expect("user creation should succeed");
+    let client_uuid = Uuid::new_v4();
+    manager
+        .create_client_record(sample_client_args(client_uuid))
+        .await
+        .expect("client creation should succeed");
+    for _ in 0..2 {
+        manager
+            .create_project_bundle(sample_project_args(Uuid::new_v4(), user_uuid, client_uuid))
+            .await
+            .expect("project creation should succeed");
+    }
+    let entry = |source_lang: &str, target_lang: &str, text: &str| NewTranslationMemoryEntryArgs {
+        source_lang: source_lang.into(),
+        target_lang: target_lang.into(),
+        source_text: text.into(),
+        target_text: format!("{text} (tradotto)"),
+        project_uuid: None,
+        origin: "editor".into(),
+    };
+    manager
+        .upsert_translation_memory_entries(&[
+            entry("en-us", "it-it", "Save"),
+            entry("en-us", "it-it", "Cancel"),
+            entry("en-us", "de-de", "Save"),
+        ])
+        .await
+        .expect("TM entries should be stored");
+
+    let activity = manager
+        .list_language_pair_activity()
+        .await
+        .expect("activity should be aggregated");
+    assert_eq!(activity.len(), 2);
+    assert_eq!(
+        (
+            activity[0].target_lang.as_str(),
+            activity[0].project_count,
+            activity[0].tm_entries
+        ),
+        ("de-de", 0, 1)
+    );
+    assert_eq!(
+        (
+            activity[1].target_lang.as_str(),
+            activity[1].project_count,
+            activity[1].tm_entries
+        ),
+        ("it-it", 2, 2)
+    );
+    assert_eq!(activity[1].delivered_packages, 0);
+    assert_eq!(activity[1].average_turnaround_hours, None);
+}
+
+#[tokio::test]
+async fn translation_memory_edits_merge_entries_that_become_identical() {
+    let manager = memory_manager().await;
+    let entry = |target: &str| NewTranslationMemoryEntryArgs {
+        source_lang: "en-us".into(),
+        target_lang: "de-de".into(),
+        source_text: "Save the file.".into(),
+        target_text: target.into(),
+        project_uuid: None,
+        origin: "editor".into(),
+    };
+    let stored = manager
+        .upsert_translation_memory_entries(&[
+            entry("Datei speichern."),
+            entry("Akte speichern."),
+            entry("Speichern Sie die Datei."),
+        ])
+        .await
+        .expect("TM entries should be stored");
+
+    let merged = manager
+        .update_translation_memory_entries(&[
+            UpdateTranslationMemoryEntryArgs {
+                entry_uuid: stored[1].entry_uuid,
+                source_text: "Save the file.".into(),
+                target_text: "Datei speichern.".into(),
+            },
+            UpdateTranslationMemoryEntryArgs {
+                entry_uuid: stored[2].entry_uuid,
+                source_text: "Save the file.".into(),
+                target_text: "Speichern Sie die Akte.".into(),
+            },
+        ])
+        .await
+        .expect("TM entries should be updated");
+    assert_eq!(merged, 1);
+
+    let deleted = manager
+        .delete_translation_memory_entries(&[stored[0].entry_uuid, Uuid::new_v4()])
+        .await
+        .expect("TM entries should be deleted");
+    assert_eq!(deleted, 1);
+
+    let remaining = manager
+        .list_translation_memory_entries("en-US", "de-DE")
+        .await
+        .expect("TM entries should be listed");
+    assert_eq!(remaining.len(), 1);
+    assert_eq!(remaining[0].target_text, "Speichern Sie die Akte.");
+}
+
+async fn stored_email(pool: &SqlitePool) -> String {
+    sqlx::query_scalar("SELECT email FROM clients")
+        .fetch_one(pool)
+        .await
+        .expect("client row should exist")
+}
+
+#[tokio::test]
+async fn client_details_are_sealed_at_rest_and_survive_key_rotation() {
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect(":memory:")
+        .await
+        .expect("failed to open in-memory SQLite");
+    initialise_schema(&pool)
+        .await
+        .expect("schema bootstrap should succeed");
+    let manager = DbManager::from_pool(pool.clone());
+    let client_uuid = Uuid::new_v4();
+    manager
+        .create_client_record(sample_client_args(client_uuid))
+        .await
+        .expect("client creation should succeed");
+    assert_eq!(stored_email(&pool).await, "contact@acme.example");
+
+    let first = FieldCipher::from_hex(&"1f".repeat(32)).expect("valid key");
+    let rewritten = manager
+        .rotate_client_pii_key(first)
+        .await
+        .expect("rotation should seal existing clients");
+    assert_eq!(rewritten, 1);
+    assert!(stored_email(&pool).await.starts_with("pii1:"));
+
+    let second = FieldCipher::from_hex(&"a0".repeat(32)).expect("valid key");
+    manager
+        .rotate_client_pii_key(second)
+        .await
+        .expect("rotation should re-seal clients");
+    let record = manager
+        .get_client_record(client_uuid)
+        .await
+        .expect("client fetch should succeed")
+        .expect("client should exist");
+    assert_eq!(record.email.as_deref(), Some("contact@acme.example"));
+    assert_eq!(record.note.as_deref(), Some("Enterprise account"));
+
+    manager.set_pii_cipher(first);
+    assert!(matches!(
+        manager.get_client_record(client_uuid).await,
+        Err(DbError::PiiDecryption(_))
+    ));
+}
+
+#[tokio::test]
+async fn project_notes_are_paged_pinned_first_and_track_mentions() {
+    let manager = memory_manager().await;
+
+    let user_uuid = Uuid::new_v4();
+    manager
+        .create_user_profile(sample_user_args(user_uuid))
+        .await
+        .expect("user creation should succeed");
+    let client_uuid = Uuid::new_v4();
+    manager
+        .create_client_record(sample_client_args(client_uuid))
+        .await
+        .expect("client creation should succeed");
+    let project_uuid = Uuid::new_v4();
+    manager
+        .create_project_bundle(sample_project_args(project_uuid, user_uuid, client_uuid))
+        .await
+        .expect("project creation should succeed");
+
+    let mut note_ids = Vec::new();
+    for body in [
+        "Glossary sent to the client.",
+        "@Demo-User please check `@legal` wording with @reviewer.",
+        "Deadline moved to Friday.",
+    ] {
+        let note = manager
+            .insert_note(NewNoteArgs {
+                project_id: project_uuid,
+                author_user_id: user_uuid.to_string(),
+                body: body.into(),
+                is_markdown: true,
+                pinned: false,
+            })
+            .await
+            .expect("note creation should succeed");
+        note_ids.push(note.note_id);
+    }
+
+    let mentioned = manager
+        .get_note(note_ids[1])
+        .await
+        .expect("note fetch should succeed")
+        .expect("note should exist");
+    let mentions: Vec<(&str, Option<Uuid>)> = mentioned
+        .mentions
+        .iter()
+        .map(|mention| (mention.mention.as_str(), mention.user_uuid))
+        .collect();
+    assert_eq!(
+        mentions,
+        vec![("demo-user", Some(user_uuid)), ("reviewer", None)]
+    );
+
+    let pinned = manager
+        .update_note(UpdateNoteArgs {
+            note_id: note_ids[0],
+            pinned: Some(true),
+            ..UpdateNoteArgs::default()
+        })
+        .await
+        .expect("note update should succeed")
+        .expect("note should exist");
+    assert!(pinned.pinned);
+    assert!(pinned.updated_at.is_some());
+
+    let page = manager
+        .list_notes_for_project(project_uuid, 2, 0)
+        .await
+        .expect("listing notes should succeed");
+    assert_eq!(page.total, 3);
+    assert_eq!(page.notes.len(), 2);
+    assert_eq!(page.notes[0].note_id, note_ids[0]);
+
+    let mentioning = manager
+        .list_notes_mentioning(user_uuid, 10)
+        .await
+        .expect("listing mentions should succeed");
+    assert_eq!(mentioning.len(), 1);
+
+    manager
+        .update_note(UpdateNoteArgs {
+            note_id: note_ids[1],
+            body: Some("No mentions any more.".into()),
+            ..UpdateNoteArgs::default()
+        })
+        .await
+        .expect("note update should succeed");
+    assert!(
+        manager
+            .list_notes_mentioning(user_uuid, 10)
+            .await
+            .expect("listing mentions should succeed")
+            .is_empty()
+    );
+
+    manager
+        .delete_note(note_ids[2])
+        .await
+        .expect("note deletion should succeed");
+    let page = manager
+        .list_notes_for_project(project_uuid, 10, 0)
+        .await
+        .expect("listing notes should succeed");
+    assert_eq!(page.total, 2);
+}
+
+#[tokio::test]
+async fn domains_link_to_projects_and_termbases_and_reject_duplicate_names() {
+    let manager = memory_manager().await;
+
+    let user_uuid = Uuid::new_v4();
+    manager
+        .create_user_profile(sample_user_args(user_uuid))
+        .await
+        .expect("user creation should succeed");
+    let client_uuid = Uuid::new_v4();
+    manager
+        .create_client_record(sample_client_args(client_uuid))
+        .await
+        .expect("client creation should succeed");
+    let project_uuid = Uuid::new_v4();
+    manager
+        .create_project_bundle(sample_project_args(project_uuid, user_uuid, client_uuid))
+        .await
+        .expect("project creation should succeed");
+
+    let legal = manager
+        .create_domain("Legal", Some("Contracts and court filings"))
+        .await
+        .expect("domain creation should succeed");
+    let medical = manager
+        .create_domain("Medical", None)
+        .await
+        .expect("domain creation should succeed");
+    let duplicate = manager.create_domain("legal", None).await;
+    assert!(matches!(duplicate, Err(DbError::ConstraintViolation(_))));
+
+    let linked = manager
+        .set_project_domains(
+            project_uuid,
+            &[medical.domain_id.clone(), legal.domain_id.clone()],
+        )
+        .await
+        .expect("linking domains should succeed");
+    let names: Vec<&str> = linked.iter().map(|domain| domain.name.as_str()).collect();
+    assert_eq!(names, vec!["Legal", "Medical"]);
+
+    manager
+        .set_termbase_domains("contracts", &[legal.domain_id.clone()])
+        .await
+        .expect("linking a termbase should succeed");
+    let renamed = manager
+        .update_domain(&legal.domain_id, "Law", None)
+        .await
+        .expect("domain update should succeed")
+        .expect("domain should exist");
+    assert_eq!(renamed.description, None);
+    let links = manager
+        .list_termbase_domains()
+        .await
+        .expect("termbase links should load");
+    assert_eq!(links, vec![("contracts".to_string(), renamed)]);
+    assert_eq!(
+        manager
+            .list_termbase_names()
+            .await
+            .expect("termbase names should load"),
+        vec!["contracts".to_string()]
+    );
+
+    assert!(
+        manager
+            .delete_domain(&legal.domain_id)
+            .await
+            .expect("domain deletion should succeed")
+    );
+    assert!(
+        !manager
+            .delete_domain(&legal.domain_id)
+            .await
+            .expect("repeated deletion should succeed")
+    );
+    let remaining = manager
+        .list_project_domains(project_uuid)
+        .await
+        .expect("project domains should load");
+    assert_eq!(remaining, vec![medical]);
+    assert!(
+        manager
+            .list_termbase_domains()
+            .await
+            .expect("termbase links should load")
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn managed_subjects_normalize_project_subjects_and_aliases() {
+    let manager = memory_manager().await;
+
+    let user_uuid = Uuid::new_v4();
+    manager
+        .create_user_profile(sample_user_args(user_uuid))
+        .await
+        .expect("user creation should succeed");
+    let client_uuid = Uuid::new_v4();
+    manager
+        .create_client_record(sample_client_args(client_uuid))
+        .await
+        .expect("client creation should succeed");
+    let first_project = Uuid::new_v4();
+    manager
+        .create_project_bundle(sample_project_args(first_project, user_uuid, client_uuid))
+        .await
+        .expect("project creation should succeed");
+
+    let managed = manager
+        .upsert_subject(
+            " Marketing ",
+            &["Mktg".into(), "Advertising".into(), "MKTG".into()],
+        )
+        .await
+        .expect("subject creation should succeed");
+    assert_eq!(managed.subject, "Marketing");
+    assert_eq!(managed.aliases, vec!["Advertising", "Mktg"]);
+
+    let second_project = Uuid::new_v4();
+    let mut args = sample_project_args(second_project, user_uuid, client_uuid);
+    args.subjects = vec![ProjectSubjectInput {
+        subject: "mktg.".into(),
+    }];
+    manager
+        .create_project_bundle(args)
+        .await
+        .expect("project creation should succeed");
+    for project_uuid in [first_project, second_project] {
+        let bundle = manager
+            .get_project_bundle(project_uuid)
+            .await
+            .expect("project fetch should succeed")
+            .expect("project should exist");
+        let subjects: Vec<&str> = bundle
+            .subjects
+            .iter()
+            .map(|record| record.subject.as_str())
+            .collect();
+        assert_eq!(subjects, vec!["Marketing"]);
+    }
+
+    let conflict = manager.upsert_subject("advertising", &[]).await;
+    assert!(matches!(conflict, Err(DbError::ConstraintViolation(_))));
+
+    let suggestions = manager
+        .suggest_subjects("adv", 10)
+        .await
+        .expect("suggestions should load");
+    assert_eq!(suggestions.len(), 1);
+    assert_eq!(suggestions[0].subject, "Marketing");
+    assert_eq!(suggestions[0].matched_alias.as_deref(), Some("Advertising"));
+    assert!(suggestions[0].managed);
+    assert_eq!(suggestions[0].project_count, 2);
+
+    assert!(
+        manager
+            .delete_subject("Marketing")
+            .await
+            .expect("subject deletion should succeed")
+    );
+    assert!(
+        manager
+            .list_subject_taxonomy()
+            .await
+            .expect("taxonomy should load")
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn output_naming_overrides_and_recorded_names_are_per_project() {
+    let manager = memory_manager().await;
+
+    let user_uuid = Uuid::new_v4();
+    manager
+        .create_user_profile(sample_user_args(user_uuid))
+        .await
+        .expect("user creation should succeed");
+    let client_uuid = Uuid::new_v4();
+    manager
+        .create_client_record(sample_client_args(client_uuid))
+        .await
+        .expect("client creation should succeed");
+    let project_uuid = Uuid::new_v4();
+    manager
+        .create_project_bundle(sample_project_args(project_uuid, user_uuid, client_uuid))
+        .await
+        .expect("project creation should succeed");
+
+    let naming = manager
+        .set_project_output_naming(project_uuid, Some("{date}_{stem}"), None, None)
+        .await
+        .expect("naming override should save")
+        .expect("override should be stored");
+    assert_eq!(naming.xliff_template.as_deref(), Some("{date}_{stem}"));
+    assert_eq!(naming.jliff_template, None);
+    assert_eq!(naming.translations_layout, None);
+
+    let file_uuid = Uuid::new_v4();
+    let mut args = NewConversionOutputNameArgs {
+        project_uuid,
+        file_uuid,
+        source_lang: "en-US".into(),
+        target_lang: "de-DE".into(),
+        xliff_template: "{stem}".into(),
+        xliff_rel_path: "Translations/en-US_de-DE/manual.xlf".into(),
+        jliff_template: "{uuid}".into(),
+        jliff_prefix: "6f1c".into(),
+    };
+    manager
+        .record_conversion_output_name(args.clone())
+        .await
+        .expect("output name should record");
+    args.xliff_template = "{date}_{stem}".into();
+    args.xliff_rel_path = "Translations/en-US_de-DE/20261015_manual.xlf".into();
+    manager
+        .record_conversion_output_name(args)
+        .await
+        .expect("output name should be replaced");
+    let names = manager
+        .list_conversion_output_names(project_uuid)
+        .await
+        .expect("output names should load");
+    assert_eq!(names.len(), 1);
+    assert_eq!(
+        names[0].xliff_rel_path,
+        "Translations/en-US_de-DE/20261015_manual.xlf"
+    );
+
+    let layout_only = manager
+        .set_project_output_naming(project_uuid, None, None, Some("source_first"))
+        .await
+        .expect("layout override should save")
+        .expect("a layout alone should be stored");
+    assert_eq!(layout_only.xliff_template, None);
+    assert_eq!(
+        layout_only.translations_layout.as_deref(),
+        Some("source_first")
+    );
+
+    assert!(
+        manager
+            .set_project_output_naming(project_uuid, None, None, None)
+            .await
+            .expect("clearing the override should succeed")
+            .is_none()
+    );
+    assert!(
+        manager
+            .get_project_output_naming(project_uuid)
+            .await
+            .expect("naming should load")
+            .is_none()
+    );
+}
+
+#[tokio::test]
+async fn plugin_settings_are_replaced_and_dropped_with_their_plugin() {
+    let manager = memory_manager().await;
+
+    let record = manager
+        .register_plugin("acme.fixer", "/plugins/fixer", r#"{"id":"acme.fixer"}"#)
+        .await
+        .expect("plugin should register");
+    assert_eq!(record.plugin_dir, "/plugins/fixer");
+    let moved = manager
+        .register_plugin("acme.fixer", "/plugins/fixer-2", r#"{"id":"acme.fixer"}"#)
+        .await
+        .expect("registering again should update the plugin");
+    assert_eq!(moved.plugin_dir, "/plugins/fixer-2");
+    assert_eq!(moved.registered_at, record.registered_at);
+    assert_eq!(manager.list_plugins().await.unwrap().len(), 1);
+
+    manager
+        .replace_plugin_settings(
+            "acme.fixer",
+            &[
+                ("style".into(), r#""formal""#.into()),
+                ("strict".into(), "true".into()),
+            ],
+        )
+        .await
+        .expect("settings should save");
+    let settings = manager
+        .replace_plugin_settings("acme.fixer", &[("style".into(), r#""casual""#.into())])
+        .await
+        .expect("settings should be replaced");
+    assert_eq!(settings.len(), 1);
+    assert_eq!(settings[0].value_json, r#""casual""#);
+
+    assert!(manager.unregister_plugin("acme.fixer").await.unwrap());
+    assert!(manager.get_plugin("acme.fixer").await.unwrap().is_none());
+    assert!(
+        manager
+            .list_plugin_settings("acme.fixer")
+            .await
+            .unwrap()
+            .is_empty()
+    );
+    assert!(!manager.unregister_plugin("acme.fixer").await.unwrap());
+}
+
+#[tokio::test]
+async fn automation_scripts_are_listed_per_hook_and_record_their_runs() {
+    let manager = memory_manager().await;
+    let script = |name: &str, hook: &str, enabled: bool| NewAutomationScriptArgs {
+        name: name.into(),
+        hook: hook.into(),
+        source: "notify(\"hi\");".into(),
+        enabled,
+    };
+
+    let first = manager
+        .upsert_automation_script(script("welcome", "on_project_create", true))
+        .await
+        .expect("script should save");
+    manager
+        .upsert_automation_script(script("short-ui", "on_segment_confirm", false))
+        .await
+        .expect("script should save");
+    assert!(
+        manager
+            .upsert_automation_script(script("bad", "on_delivery", true))
+            .await
+            .is_err()
+    );
+
+    manager
+        .record_automation_script_run(first.script_uuid, Some("line 1: boom"))
+        .await
+        .expect("run should be recorded");
+    let saved_again = manager
+        .upsert_automation_script(script("welcome", "on_project_create", true))
+        .await
+        .expect("saving by name should replace the script");
+    assert_eq!(saved_again.script_uuid, first.script_uuid);
+    assert!(saved_again.last_run_at.is_some());
+    assert!(saved_again.last_error.is_none());
+
+    assert!(
+        manager
+            .list_enabled_automation_scripts("on_segment_confirm")
+            .await
+            .unwrap()
+            .is_empty()
+    );
+    let all = manager.list_automation_scripts().await.unwrap();
+    let short_ui = all.iter().find(|record| record.name == "short-ui").unwrap();
+    manager
+        .set_automation_script_enabled(short_ui.script_uuid, true)
+        .await
+        .expect("script should be enabled")
+        .expect("script should exist");
+    let enabled = manager
+        .list_enabled_automation_scripts("on_segment_confirm")
+        .await
+        .unwrap();
+    assert_eq!(enabled.len(), 1);
+    assert_eq!(enabled[0].name, "short-ui");
+
+    assert!(
+        manager
+            .delete_automation_script(first.script_uuid)
+            .await
+            .unwrap()
+    );
+    assert!(
+        !manager
+            .delete_automation_script(first.script_uuid)
+            .await
+            .unwrap()
+    );
+    assert_eq!(manager.list_automation_scripts().await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn offline_operations_are_listed_oldest_first_until_discarded() {
+    let manager = memory_manager().await;
+    let operation = |artifact: &str| NewOfflineOperationArgs {
+        operation: "upload_delivery_package_v2".into(),
+        payload_json: format!(r#"{{"artifactUuid":"{artifact}"}}"#),
+        project_uuid: None,
+    };
+
+    let first = manager
+        .queue_offline_operation(operation("first"))
+        .await
+        .expect("operation should be queued");
+    manager
+        .queue_offline_operation(operation("second"))
+        .await
+        .expect("operation should be queued");
+    let queued = manager.list_offline_operations().await.unwrap();
+    assert_eq!(queued.len(), 2);
+    assert_eq!(queued[0].operation_uuid, first.operation_uuid);
+    assert!(queued[1].payload_json.contains("second"));
+
+    assert!(
+        manager
+            .delete_offline_operation(first.operation_uuid)
+            .await
+            .unwrap()
+    );
+    assert!(
+        !manager
+            .delete_offline_operation(first.operation_uuid)
+            .await
+            .unwrap()
+    );
+    assert_eq!(manager.list_offline_operations().await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn imported_terms_and_segmentation_rules_skip_existing_ones() {
+    let manager = memory_manager().await;
+    let term = |source_term: &str, target_term: &str| NewTermbaseEntryArgs {
+        termbase: "Product".into(),
+        source_lang: "EN".into(),
+        target_lang: "de".into(),
+        source_term: source_term.into(),
+        target_term: target_term.into(),
+        project_uuid: None,
+    };
+    let added = manager
+        .add_termbase_entries(&[term("widget", "Widget"), term("gadget", "")])
+        .await
+        .expect("terms should be added");
+    assert_eq!(added.len(), 2);
+    assert_eq!(added[0].source_lang, "en");
+    let added = manager
+        .add_termbase_entries(&[term("widget", "Widget"), term("gizmo", "Dings")])
+        .await
+        .expect("terms should be added");
+    assert_eq!(added.len(), 1);
+    assert_eq!(added[0].source_term, "gizmo");
+    assert_eq!(manager.list_all_termbase_entries().await.unwrap().len(), 3);
+
+    let rule = |before_text: &str| NewSegmentationExceptionArgs {
+        exception_uuid: Uuid::new_v4(),
+        project_uuid: None,
+        language: "de".into(),
+        kind: "abbreviation".into(),
+        before_text: before_text.into(),
+        after_text: String::new(),
+    };
+    manager
+        .create_segmentation_exception(rule("z.B."))
+        .await
+        .expect("exception should be created");
+    let added = manager
+        .add_missing_segmentation_exceptions(&[rule("z.B."), rule("usw.")])
+        .await
+        .expect("exceptions should be added");
+    assert_eq!(added.len(), 1);
+    assert_eq!(added[0].before_text, "usw.");
+    assert_eq!(
+        manager
+            .list_segmentation_exceptions(None)
+            .await
+            .unwrap()
+            .len(),
+        2
+    );
+
+    manager
+        .upsert_translation_memory_entries(&[
+            NewTranslationMemoryEntryArgs {
+                source_lang: "en".into(),
+                target_lang: "fr".into(),
+                source_text: "Save".into(),
+                target_text: "Enregistrer".into(),
+                project_uuid: None,
+                origin: "editor".into(),
+            },
+            NewTranslationMemoryEntryArgs {
+                source_lang: "en".into(),
+                target_lang: "de".into(),
+                source_text: "Save".into(),
+                target_text: "Speichern".into(),
+                project_uuid: None,
+                origin: "editor".into(),
+            },
+        ])
+        .await
+        .expect("entries should be stored");
+    let all = manager.list_all_translation_memory_entries().await.unwrap();
+    let pairs: Vec<_> = all.iter().map(|entry| entry.target_lang.as_str()).collect();
+    assert_eq!(pairs, ["de", "fr"]);
+}
+
+#[tokio::test]
+async fn outbound_requests_outlive_projects_that_forbid_external_mt() {
+    let manager = memory_manager().await;
+    let user_uuid = Uuid::new_v4();
+    manager
+        .create_user_profile(sample_user_args(user_uuid))
+        .await
+        .expect("user creation should succeed");
+    let client_uuid = Uuid::new_v4();
+    manager
+        .create_client_record(sample_client_args(client_uuid))
+        .await
+        .expect("client creation should succeed");
+    let project_uuid = Uuid::new_v4();
+    manager
+        .create_project_bundle(sample_project_args(project_uuid, user_uuid, client_uuid))
+        .await
+        .expect("project creation should succeed");
+    assert!(
+        !manager
+            .project_forbids_external_mt(project_uuid)
+            .await
+            .unwrap()
+    );
+
+    let bundle = manager
+        .update_project_bundle(UpdateProjectArgs {
+            project_uuid,
+            project_name: None,
+            project_status: None,
+            user_uuid: None,
+            client_uuid: None,
+            r#type: None,
+            notes: None,
+            due_date: None,
+            no_external_mt: Some(true),
+            subjects: None,
+            language_pairs: None,
+        })
+        .await
+        .expect("project should be updated")
+        .expect("project should exist");
+    assert!(bundle.project.no_external_mt);
+    assert!(
+        manager
+            .project_forbids_external_mt(project_uuid)
+            .await
+            .unwrap()
+    );
+
+    let request = |provider: &str, project_uuid: Option<Uuid>| NewOutboundRequestArgs {
+        provider: provider.into(),
+        project_uuid,
+        segment_count: 1,
+        char_count: 42,
+        destination_host: Some("api.example.com".into()),
+    };
+    manager
+        .record_outbound_request(request("llm", Some(project_uuid)))
+        .await
+        .expect("request should be recorded");
+    let newest = manager
+        .record_outbound_request(request("local", None))
+        .await
+        .expect("request should be recorded");
+    let all = manager.list_outbound_requests(None, 10).await.unwrap();
+    assert_eq!(all.len(), 2);
+    assert_eq!(all[0].request_uuid, newest.request_uuid);
+    assert_eq!(
+        manager
+            .list_outbound_requests(Some(project_uuid), 10)
+            .await
+            .unwrap()
+            .len(),
+        1
+    );
+
+    manager
+        .delete_project_bundle(project_uuid)
+        .await
+        .expect("project should be deleted");
+    let kept = manager
+        .list_outbound_requests(Some(project_uuid), 10)
+        .await
+        .unwrap();
+    assert_eq!(kept.len(), 1);
+    assert_eq!(kept[0].char_count, 42);
+}
@@ -5,8 +5,8 @@ use uuid::Uuid;
 
 use weg_translator_lib::ipc_test::{get_project_bundle_v2, get_project_statistics_v2};
 use weg_translator_lib::{
-    DbManager, NewClientArgs, NewProjectArgs, NewUserArgs, ProjectLanguagePairInput,
-    ProjectSubjectInput, initialise_schema,
+    DbManager, NewClientArgs, NewProjectArgs, NewUserArgs, PostalAddress, ProjectLanguagePairInput,
+    ProjectSubjectInput, VatValidation, initialise_schema,
 };
 
 async fn memory_manager() -> DbManager {
@@ -42,6 +42,9 @@ fn sample_client_args(client_uuid: Uuid) -> NewClientArgs {
         address: None,
         vat_number: None,
         note: Some("Client used for IPC command coverage.".into()),
+        currency: None,
+        postal_address: PostalAddress::default(),
+        vat_validation: VatValidation::default(),
     }
 }
 
@@ -54,6 +57,7 @@ fn sample_project_args(project_uuid: Uuid, user_uuid: Uuid, client_uuid: Uuid) -
         client_uuid: Some(client_uuid),
         r#type: "translation".into(),
         notes: Some("Created for IPC command coverage.".into()),
+        due_date: None,
         subjects: vec![ProjectSubjectInput {
             subject: "demo".into(),
         }],
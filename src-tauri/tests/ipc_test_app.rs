@@ -0,0 +1,346 @@
+use serde_json::json;
+use tauri::Emitter;
+
+use uuid::Uuid;
+
+use weg_translator_lib::NewUserArgs;
+use weg_translator_lib::ipc_test::{
+    CreateClientPayload, OfflineMode, RequestSensitiveActionPayload, SensitiveActionTokens,
+    SetWorkspacePassphrasePayload, TestAppBuilder, WorkspaceLock, create_client_record_v2,
+    error_message, get_client_record_v2, list_client_records_v2, lock_workspace,
+    refresh_exchange_rates_v2, request_sensitive_action_v2, set_active_profile_v2,
+    set_workspace_passphrase_v2, unlock_workspace,
+};
+
+fn client_payload(name: &str) -> CreateClientPayload {
+    CreateClientPayload {
+        client_uuid: None,
+        name: name.into(),
+        email: Some("billing@example.com".into()),
+        phone: None,
+        address: None,
+        vat_number: None,
+        note: None,
+        currency: None,
+        postal_address: None,
+    }
+}
+
+#[tokio::test]
+async fn commands_run_against_in_memory_state() {
+    let app = TestAppBuilder::new().build().await;
+
+    let created = create_client_record_v2(
+        app.state(),
+        app.state(),
+        app.state(),
+        app.state(),
+        client_payload("Acme"),
+    )
+    .await
+    .expect("client creation should succeed");
+    let listed = list_client_records_v2(app.state(), None)
+        .await
+        .expect("listing clients should succeed");
+
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0].client_uuid, created.client_uuid);
+    assert_eq!(listed[0].name, "Acme");
+
+    create_client_record_v2(
+        app.state(),
+        app.state(),
+        app.state(),
+        app.state(),
+        client_payload("Müller GmbH"),
+    )
+    .await
+    .expect("client creation should succeed");
+    let matched = list_client_records_v2(app.state(), Some("MULLER".into()))
+        .await
+        .expect("searching clients should succeed");
+    assert_eq!(matched.len(), 1);
+    assert_eq!(matched[0].name, "Müller GmbH");
+}
+
+#[tokio::test]
+async fn vat_numbers_are_checked_and_normalized() {
+    let app = TestAppBuilder::new().build().await;
+
+    let created = create_client_record_v2(
+        app.state(),
+        app.state(),
+        app.state(),
+        app.state(),
+        CreateClientPayload {
+            vat_number: Some("de 136 695 976".into()),
+            ..client_payload("Acme")
+        },
+    )
+    .await
+    .expect("client creation should succeed");
+    assert_eq!(created.vat_number.as_deref(), Some("DE136695976"));
+    assert_eq!(created.vat_status.as_deref(), Some("checksum"));
+    assert!(created.vat_checked_at.is_some());
+
+    let error = create_client_record_v2(
+        app.state(),
+        app.state(),
+        app.state(),
+        app.state(),
+        CreateClientPayload {
+            vat_number: Some("DE136695977".into()),
+            ..client_payload("Typo GmbH")
+        },
+    )
+    .await
+    .expect_err("wrong check digits should be rejected");
+    assert!(error_message(&error).contains("check digits"));
+}
+
+#[tokio::test]
+async fn outbound_commands_fail_fast_while_offline() {
+    let app = TestAppBuilder::new().build().await;
+    assert!(app.state::<OfflineMode>().set_forced(true));
+
+    let error = refresh_exchange_rates_v2(app.state(), app.state())
+        .await
+        .expect_err("refreshing exchange rates should need the network");
+    assert_eq!(error.0["code"], "OFFLINE");
+    assert!(error_message(&error).contains("offline"));
+
+    // VIES is skipped, so the checksum alone decides.
+    let created = create_client_record_v2(
+        app.state(),
+        app.state(),
+        app.state(),
+        app.state(),
+        CreateClientPayload {
+            vat_number: Some("DE136695976".into()),
+            ..client_payload("Acme")
+        },
+    )
+    .await
+    .expect("client creation should succeed offline");
+    assert_eq!(created.vat_status.as_deref(), Some("checksum"));
+}
+
+#[tokio::test]
+async fn validation_errors_surface_their_message() {
+    let app = TestAppBuilder::new().build().await;
+
+    let error = get_client_record_v2(app.state(), "not-a-uuid".into())
+        .await
+        .expect_err("malformed uuid should be rejected");
+
+    assert!(
+        error_message(&error).contains("invalid clientUuid"),
+        "unexpected message: {}",
+        error_message(&error)
+    );
+}
+
+#[tokio::test]
+async fn apps_are_isolated_and_clean_up_their_folder() {
+    let first = TestAppBuilder::new().build().await;
+    let second = TestAppBuilder::new().build().await;
+    create_client_record_v2(
+        first.state(),
+        first.state(),
+        first.state(),
+        first.state(),
+        client_payload("Only in first"),
+    )
+    .await
+    .expect("client creation should succeed");
+
+    let listed = list_client_records_v2(second.state(), None)
+        .await
+        .expect("listing clients should succeed");
+    assert!(listed.is_empty());
+
+    let folder = first.app_folder().to_path_buf();
+    assert!(folder.is_dir());
+    drop(first);
+    assert!(!folder.exists());
+}
+
+#[tokio::test]
+async fn settings_overrides_and_event_capture() {
+    let app = TestAppBuilder::new()
+        .with_settings(|settings| settings.auto_convert_on_open = false)
+        .capture_event("test:ping")
+        .build()
+        .await;
+
+    assert!(!app.settings().current().await.auto_convert_on_open);
+
+    app.handle()
+        .emit("test:ping", json!({ "step": 1 }))
+        .expect("emit should succeed");
+    app.handle()
+        .emit("test:ignored", json!({}))
+        .expect("emit should succeed");
+
+    assert_eq!(app.event_payloads("test:ping"), vec![json!({ "step": 1 })]);
+    assert_eq!(app.events().len(), 1);
+}
+
+#[tokio::test]
+async fn sensitive_actions_need_an_admin_and_a_fresh_token() {
+    let app = TestAppBuilder::new().build().await;
+    let user = |username: &str, role: &str| NewUserArgs {
+        user_uuid: Uuid::new_v4(),
+        username: username.into(),
+        email: format!("{username}@example.com"),
+        phone: None,
+        address: None,
+        roles: vec![role.into()],
+        permission_overrides: Vec::new(),
+    };
+    let admin = user("admin", "admin");
+    let translator = user("translator", "translator");
+    for args in [admin.clone(), translator.clone()] {
+        app.db()
+            .create_user_profile(args)
+            .await
+            .expect("user creation should succeed");
+    }
+    let request = |action: &str| RequestSensitiveActionPayload {
+        action: action.into(),
+    };
+    let sign_in = |user_uuid: Uuid| {
+        set_active_profile_v2(
+            app.state(),
+            app.state(),
+            app.state(),
+            Some(user_uuid.to_string()),
+        )
+    };
+
+    let error = request_sensitive_action_v2(
+        app.state(),
+        app.state(),
+        app.state(),
+        request("update_app_folder"),
+    )
+    .await
+    .expect_err("tokens need a signed-in profile");
+    assert!(error_message(&error).contains("Sign in"));
+
+    sign_in(translator.user_uuid)
+        .await
+        .expect("translators can sign in");
+    let error = request_sensitive_action_v2(
+        app.state(),
+        app.state(),
+        app.state(),
+        request("update_app_folder"),
+    )
+    .await
+    .expect_err("translators cannot change the app folder");
+    assert!(error_message(&error).contains("not allowed"));
+
+    sign_in(admin.user_uuid).await.expect("admins can sign in");
+    let error = request_sensitive_action_v2(
+        app.state(),
+        app.state(),
+        app.state(),
+        request("update_theme"),
+    )
+    .await
+    .expect_err("only sensitive actions are confirmed");
+    assert!(error_message(&error).contains("not a sensitive action"));
+
+    let granted = request_sensitive_action_v2(
+        app.state(),
+        app.state(),
+        app.state(),
+        request("update_app_folder"),
+    )
+    .await
+    .expect("admins get a token");
+    let tokens = app.state::<SensitiveActionTokens>();
+    assert_eq!(tokens.redeem(&granted.token, "update_ocr_command"), None);
+
+    let granted = request_sensitive_action_v2(
+        app.state(),
+        app.state(),
+        app.state(),
+        request("update_app_folder"),
+    )
+    .await
+    .expect("admins get a token");
+    assert_eq!(
+        tokens.redeem(&granted.token, "update_app_folder"),
+        Some(admin.user_uuid)
+    );
+    assert_eq!(tokens.redeem(&granted.token, "update_app_folder"), None);
+
+    let granted = request_sensitive_action_v2(
+        app.state(),
+        app.state(),
+        app.state(),
+        request("update_app_folder"),
+    )
+    .await
+    .expect("admins get a token");
+    sign_in(translator.user_uuid)
+        .await
+        .expect("profiles can change");
+    assert_eq!(
+        tokens.redeem(&granted.token, "update_app_folder"),
+        None,
+        "tokens of the previous profile are dropped"
+    );
+    assert!(sign_in(Uuid::new_v4()).await.is_err());
+}
+
+#[tokio::test]
+async fn a_locked_workspace_unlocks_only_with_the_passphrase() {
+    let app = TestAppBuilder::new()
+        .capture_event("workspace://lock_state")
+        .build()
+        .await;
+    let error = lock_workspace(app.handle(), "manual")
+        .expect_err("without a passphrase the workspace cannot lock");
+    assert!(error.to_string().contains("Set a passphrase"));
+    assert!(!app.state::<WorkspaceLock>().is_locked());
+
+    let state = set_workspace_passphrase_v2(
+        app.state(),
+        app.state(),
+        SetWorkspacePassphrasePayload {
+            current_passphrase: None,
+            passphrase: Some("correct horse".into()),
+        },
+    )
+    .await
+    .expect("the first passphrase needs no current one");
+    assert!(state.has_passphrase);
+
+    lock_workspace(app.handle(), "inactivity").expect("a passphrase is set");
+    lock_workspace(app.handle(), "manual").expect("locking twice is harmless");
+    assert!(app.state::<WorkspaceLock>().is_locked());
+    let error = unlock_workspace(app.handle(), None)
+        .await
+        .expect_err("unlocking needs the passphrase");
+    assert!(error.to_string().contains("passphrase is wrong"));
+    let error = unlock_workspace(app.handle(), Some("wrong horse"))
+        .await
+        .expect_err("a wrong passphrase keeps the workspace locked");
+    assert!(error.to_string().contains("passphrase is wrong"));
+    assert!(app.state::<WorkspaceLock>().is_locked());
+
+    unlock_workspace(app.handle(), Some("correct horse"))
+        .await
+        .expect("the right passphrase unlocks");
+    assert!(!app.state::<WorkspaceLock>().is_locked());
+    assert_eq!(
+        app.event_payloads("workspace://lock_state"),
+        vec![
+            json!({ "locked": true, "reason": "inactivity" }),
+            json!({ "locked": false, "reason": "unlocked" }),
+        ]
+    );
+}
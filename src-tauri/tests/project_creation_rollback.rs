@@ -83,6 +83,7 @@ async fn command_rollback_removes_db_entries_on_copy_failure() {
         client_uuid: None,
         r#type: "translation".into(),
         notes: None,
+        due_date: None,
         subjects: Vec::new(),
         language_pairs: vec![ProjectLanguagePairDto {
             source_lang: "en-US".into(),
@@ -95,6 +96,7 @@ async fn command_rollback_removes_db_entries_on_copy_failure() {
             role: ProjectAssetRoleDto::Processable,
             path: missing_asset_path(&app_folder),
         }],
+        pipeline_preset_uuid: None,
     };
 
     let result =
@@ -111,7 +113,7 @@ async fn command_rollback_removes_db_entries_on_copy_failure() {
     );
 
     let project_records = db_manager
-        .list_project_records()
+        .list_project_records(None)
         .await
         .expect("listing project records should succeed");
     assert!(
@@ -258,6 +258,7 @@ function mapConversionPlanToEnsurePlan(plan: ConversionPlan): EnsureConversionsP
       version: task.version ?? defaultVersion,
       paragraph: task.paragraph ?? true,
       embed: task.embed ?? true,
+      srx: task.srx ?? undefined,
     })),
     integrityAlerts: plan.integrityAlerts.map((alert) => ({
       fileId: alert.fileUuid,
@@ -92,6 +92,7 @@ interface ConversionTaskDto {
   version?: string | null;
   paragraph?: boolean | null;
   embed?: boolean | null;
+  srx?: string | null;
 }
 
 interface ConversionPlanDto {
@@ -652,6 +653,7 @@ function mapConversionTaskDto(task: ConversionTaskDto): ConversionTask {
     version: task.version ?? null,
     paragraph: task.paragraph ?? null,
     embed: task.embed ?? null,
+    srx: task.srx ?? null,
   };
 }
 
@@ -237,6 +237,7 @@ export interface EnsureConversionsTask {
   version: string;
   paragraph: boolean;
   embed: boolean;
+  srx?: string;
 }
 
 export interface EnsureConversionsPlan {
@@ -212,6 +212,7 @@ export function ProjectViewRoute(initialProps: ProjectViewRouteProps) {
                 version: castXliffVersion(task.version),
                 paragraph: task.paragraph,
                 embed: task.embed,
+                srx: task.srx,
               },
               {
                 onStdout: (line) => console.debug("[openxliff]", line.trim()),
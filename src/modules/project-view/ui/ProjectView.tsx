@@ -186,6 +186,7 @@ export function ProjectView({ projectSummary }: Props) {
         version: /^2\./.test(task.version) ? (task.version as "2.0" | "2.1" | "2.2") : undefined,
         paragraph: task.paragraph,
         embed: task.embed,
+        srx: task.srx,
       }, { onStdout, onStderr });
 
       if (!res.ok) {
@@ -116,6 +116,7 @@ export function mapConversionPlanFromResponse(
       sourceAbsPath: task.sourcePath,
       outputRelPath: task.xliffRelPath,
       outputAbsPath: joinPathSegments(projectDir, task.xliffRelPath),
+      srx: task.srx ?? null,
     })),
   };
 }
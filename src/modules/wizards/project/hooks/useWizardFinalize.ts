@@ -213,6 +213,7 @@ export function useWizardFinalize({
             version: XLIFF_TARGET_VERSION,
             embed: true,
             paragraph: true,
+            srx: task.srx ?? undefined,
           },
           {
             onStdout: (line) => {
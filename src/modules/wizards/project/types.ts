@@ -144,6 +144,7 @@ export interface WizardConversionTask {
   sourceAbsPath: string;
   outputAbsPath: string;
   outputRelPath: string;
+  srx?: string | null;
 }
 
 export interface WizardConversionPlan {
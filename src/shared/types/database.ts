@@ -121,6 +121,7 @@ export interface ConversionTask {
   version?: Nullable<string>;
   paragraph?: Nullable<boolean>;
   embed?: Nullable<boolean>;
+  srx?: Nullable<string>;
 }
 
 export interface ConversionPlan {